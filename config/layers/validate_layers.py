@@ -9,10 +9,14 @@ Checks:
 - Referenced style files exist
 - Layer IDs follow naming convention ({model}_{parameter})
 - Each layer has at least one level with a default
+- Temporal composite layers have a source, reducer and valid windows
+- Ensemble layers have a source and a statistic (with a threshold for probabilities)
+- Column layers have a source, reducer and a low/high default range
 """
 
 import sys
 import os
+import re
 from pathlib import Path
 from typing import Any
 
@@ -41,6 +45,115 @@ def load_yaml_file(filepath: Path) -> dict | None:
         return None
 
 
+TEMPORAL_REDUCERS = {"max", "sum", "mean"}
+ISO8601_DURATION = re.compile(r"^P(\d+D)?(T(\d+H)?(\d+M)?(\d+S)?)?$", re.IGNORECASE)
+ENSEMBLE_STATISTICS = {"mean", "spread", "probability"}
+THRESHOLD_COMPARISONS = {"above", "below"}
+VERTICAL_REDUCERS = {"max", "min", "mean"}
+
+
+def validate_temporal(layer_id: str, temporal: Any, errors: list[str]) -> None:
+    """Validate a temporal composite block (source, reducer, windows)."""
+    if not isinstance(temporal, dict):
+        errors.append(f"Layer '{layer_id}': 'temporal' must be an object")
+        return
+
+    if not temporal.get("source"):
+        errors.append(f"Layer '{layer_id}': temporal layer must have 'source'")
+
+    reducer = temporal.get("reducer")
+    if reducer not in TEMPORAL_REDUCERS:
+        errors.append(
+            f"Layer '{layer_id}': temporal reducer must be one of "
+            f"{sorted(TEMPORAL_REDUCERS)}, got {reducer!r}"
+        )
+
+    windows = temporal.get("windows")
+    if not isinstance(windows, list) or not windows:
+        errors.append(f"Layer '{layer_id}': temporal layer must list 'windows'")
+        return
+
+    for window in windows:
+        match = ISO8601_DURATION.match(str(window))
+        if not match or str(window).upper() in ("P", "PT") or str(window).endswith("T"):
+            errors.append(
+                f"Layer '{layer_id}': window {window!r} is not an ISO 8601 "
+                "duration (e.g., PT1H, P1D)"
+            )
+
+    default_window = temporal.get("default_window")
+    if default_window is not None and default_window not in windows:
+        errors.append(
+            f"Layer '{layer_id}': default_window {default_window!r} is not in windows"
+        )
+
+
+def validate_ensemble(layer_id: str, ensemble: Any, errors: list[str]) -> None:
+    """Validate an ensemble block (source, statistic, threshold)."""
+    if not isinstance(ensemble, dict):
+        errors.append(f"Layer '{layer_id}': 'ensemble' must be an object")
+        return
+
+    if not ensemble.get("source"):
+        errors.append(f"Layer '{layer_id}': ensemble layer must have 'source'")
+
+    statistic = ensemble.get("statistic")
+    if statistic not in ENSEMBLE_STATISTICS:
+        errors.append(
+            f"Layer '{layer_id}': ensemble statistic must be one of "
+            f"{sorted(ENSEMBLE_STATISTICS)}, got {statistic!r}"
+        )
+
+    threshold = ensemble.get("threshold")
+    if statistic == "probability":
+        if isinstance(threshold, bool) or not isinstance(threshold, (int, float)):
+            errors.append(
+                f"Layer '{layer_id}': probability ensemble layer must have a numeric 'threshold'"
+            )
+        comparison = ensemble.get("comparison", "above")
+        if comparison not in THRESHOLD_COMPARISONS:
+            errors.append(
+                f"Layer '{layer_id}': ensemble comparison must be one of "
+                f"{sorted(THRESHOLD_COMPARISONS)}, got {comparison!r}"
+            )
+    elif threshold is not None:
+        errors.append(
+            f"Layer '{layer_id}': 'threshold' only applies to the probability statistic"
+        )
+
+    max_members = ensemble.get("max_members")
+    if max_members is not None and (not isinstance(max_members, int) or max_members < 1):
+        errors.append(f"Layer '{layer_id}': max_members must be a positive integer")
+
+
+def validate_vertical(layer_id: str, vertical: Any, errors: list[str]) -> None:
+    """Validate a column composite block (source, reducer, default_range)."""
+    if not isinstance(vertical, dict):
+        errors.append(f"Layer '{layer_id}': 'vertical' must be an object")
+        return
+
+    if not vertical.get("source"):
+        errors.append(f"Layer '{layer_id}': vertical layer must have 'source'")
+
+    reducer = vertical.get("reducer")
+    if reducer not in VERTICAL_REDUCERS:
+        errors.append(
+            f"Layer '{layer_id}': vertical reducer must be one of "
+            f"{sorted(VERTICAL_REDUCERS)}, got {reducer!r}"
+        )
+
+    default_range = vertical.get("default_range")
+    if not isinstance(default_range, str) or default_range.count("/") != 1:
+        errors.append(
+            f"Layer '{layer_id}': vertical layer must have a 'default_range' "
+            f"like '1000 mb/500 mb', got {default_range!r}"
+        )
+
+    max_levels = vertical.get("max_levels")
+    if max_levels is not None and (not isinstance(max_levels, int) or max_levels < 1):
+        errors.append(f"Layer '{layer_id}': max_levels must be a positive integer")
+
+
 def validate_layer(
     layer: dict[str, Any],
     model: str,
@@ -97,6 +210,21 @@ def validate_layer(
     if layer.get("composite") and not layer.get("requires"):
         errors.append(f"Layer '{layer_id}': composite layer must have 'requires' field")
 
+    # Check temporal composite settings
+    temporal = layer.get("temporal")
+    if temporal is not None:
+        validate_temporal(layer_id, temporal, errors)
+
+    # Check ensemble settings
+    ensemble = layer.get("ensemble")
+    if ensemble is not None:
+        validate_ensemble(layer_id, ensemble, errors)
+
+    # Check column composite settings
+    vertical = layer.get("vertical")
+    if vertical is not None:
+        validate_vertical(layer_id, vertical, errors)
+
     return layer_id if "id" in layer else None
 
 
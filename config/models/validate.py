@@ -37,7 +37,7 @@ except ImportError:
 
 # Valid values for enumerated fields
 VALID_DIMENSION_TYPES = {"forecast", "observation"}
-VALID_SOURCE_TYPES = {"aws_s3", "aws_s3_goes", "aws_s3_grib2", "local", "http"}
+VALID_SOURCE_TYPES = {"aws_s3", "aws_s3_goes", "aws_s3_grib2", "local", "http", "synthetic"}
 VALID_PROJECTION_TYPES = {
     "geographic",
     "latlon",
@@ -95,6 +95,7 @@ VALID_CONVERSIONS = {
     "ms_to_kt",
     "ms_to_mph",
 }
+VALID_DOWNSAMPLE_METHODS = {"mean", "max", "nearest"}
 
 
 class ValidationError:
@@ -150,6 +151,7 @@ class ModelValidator:
         self._validate_schedule_section()
         self._validate_retention_section()
         self._validate_precaching_section()
+        self._validate_zarr_section()
         self._validate_parameters_section()
         self._validate_composites_section()
 
@@ -403,6 +405,36 @@ class ModelValidator:
                     "precaching.parameters", "Must be a list of parameter names"
                 )
 
+    def _validate_zarr_section(self):
+        """Validate the 'zarr' section (optional)."""
+        if "zarr" not in self.data:
+            return  # Optional section
+
+        zarr = self.data["zarr"]
+        if not isinstance(zarr, dict):
+            self.add_error("zarr", "Section must be a mapping")
+            return
+
+        codec = str(zarr.get("compression", "blosc_zstd"))
+        codec = codec.lower().replace("-", "_")
+        if codec not in ("none", "lz4", "zstd", "blosc_lz4", "blosc_zstd"):
+            self.add_error(
+                "zarr.compression",
+                f"Unknown codec '{codec}' "
+                "(expected none, zstd, blosc_lz4 or blosc_zstd)",
+            )
+            return
+
+        if "compression_level" in zarr:
+            level = zarr["compression_level"]
+            max_level = 22 if codec == "zstd" else 9
+            valid = isinstance(level, int) and not isinstance(level, bool)
+            if not valid or not 1 <= level <= max_level:
+                self.add_error(
+                    "zarr.compression_level",
+                    f"Must be an integer 1-{max_level} for {codec}",
+                )
+
     def _validate_parameters_section(self):
         """Validate the 'parameters' section (required)."""
         if "parameters" not in self.data:
@@ -469,6 +501,22 @@ class ModelValidator:
                         f"Unknown conversion '{conv}'. Known: {', '.join(sorted(VALID_CONVERSIONS))}",
                     )
 
+            # Optional: pyramid overrides
+            if "downsample" in param:
+                method = param["downsample"]
+                if method not in VALID_DOWNSAMPLE_METHODS:
+                    self.add_error(
+                        f"{path}.downsample",
+                        f"Unknown method '{method}'. Valid: {', '.join(sorted(VALID_DOWNSAMPLE_METHODS))}",
+                    )
+            if "pyramid_levels" in param:
+                levels = param["pyramid_levels"]
+                if not isinstance(levels, int) or isinstance(levels, bool) or levels < 1:
+                    self.add_error(
+                        f"{path}.pyramid_levels",
+                        "Must be an integer >= 1 (1 = native resolution only)",
+                    )
+
     def _validate_levels(self, levels: Any, path: str):
         """Validate a levels array."""
         if not isinstance(levels, list):
@@ -507,6 +555,39 @@ class ModelValidator:
                         f"{level_path}.values", "Must have at least one value"
                     )
 
+            # Range of values (either order): range: [1000, 100]
+            if "range" in level:
+                rng = level["range"]
+                if (
+                    not isinstance(rng, list)
+                    or len(rng) != 2
+                    or not all(isinstance(v, int) and v >= 0 for v in rng)
+                ):
+                    self.add_error(
+                        f"{level_path}.range",
+                        "Must be [min, max] non-negative integers",
+                    )
+
+            # Regex on the level description: pattern: "^\\d+ mb$"
+            if "pattern" in level:
+                patterns = level["pattern"]
+                if isinstance(patterns, str):
+                    patterns = [patterns]
+                if not isinstance(patterns, list) or not patterns:
+                    self.add_error(
+                        f"{level_path}.pattern",
+                        "Must be a regex string or list of strings",
+                    )
+                else:
+                    for pattern in patterns:
+                        try:
+                            re.compile(str(pattern))
+                        except re.error as e:
+                            self.add_error(
+                                f"{level_path}.pattern",
+                                f"Invalid regex '{pattern}': {e}",
+                            )
+
     def _validate_composites_section(self):
         """Validate the 'composites' section (optional)."""
         if "composites" not in self.data:
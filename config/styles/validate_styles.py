@@ -26,8 +26,12 @@ VALID_STYLE_TYPES = {
     "filled_contour",
     "wind_barbs",
     "wind_arrows",
+    "rgb_composite",
 }
 
+# Valid composite recipe kinds
+VALID_COMPOSITE_KINDS = {"rgb", "sandwich", "packed_rgb"}
+
 # Valid transform types
 VALID_TRANSFORM_TYPES = {
     "none",
@@ -36,6 +40,8 @@ VALID_TRANSFORM_TYPES = {
     "mps_to_knots",
     "k_to_c",
     "m_to_km",
+    "expression",
+    "piecewise",
 }
 
 # Valid interpolation types
@@ -125,7 +131,12 @@ def validate_stop(stop: Any, index: int, path: str, errors: list, file: str):
 
 
 def validate_transform(transform: Any, path: str, errors: list, file: str):
-    """Validate a transform object."""
+    """Validate a transform object (or expression shorthand string)."""
+    if isinstance(transform, str):
+        if not transform.strip():
+            errors.append(ValidationError(file, path, "Transform expression is empty"))
+        return
+
     if not isinstance(transform, dict):
         errors.append(
             ValidationError(
@@ -156,6 +167,48 @@ def validate_transform(transform: Any, path: str, errors: list, file: str):
                 ValidationError(file, f"{path}.offset", "Offset must be a number")
             )
 
+    # 'expression' needs an expression string; 'inverse' is optional
+    if transform.get("type") == "expression":
+        if not isinstance(transform.get("expression"), str):
+            errors.append(
+                ValidationError(
+                    file,
+                    f"{path}.expression",
+                    "Expression transform requires 'expression' string",
+                )
+            )
+        if "inverse" in transform and not isinstance(transform["inverse"], str):
+            errors.append(
+                ValidationError(file, f"{path}.inverse", "Inverse must be a string")
+            )
+
+    # 'piecewise' needs [input, output] points ascending by input
+    if transform.get("type") == "piecewise":
+        points = transform.get("points")
+        if (
+            not isinstance(points, list)
+            or len(points) < 2
+            or not all(
+                isinstance(p, list)
+                and len(p) == 2
+                and all(isinstance(v, (int, float)) for v in p)
+                for p in points
+            )
+        ):
+            errors.append(
+                ValidationError(
+                    file,
+                    f"{path}.points",
+                    "Piecewise transform requires at least 2 [input, output] points",
+                )
+            )
+        elif any(b[0] <= a[0] for a, b in zip(points, points[1:])):
+            errors.append(
+                ValidationError(
+                    file, f"{path}.points", "Points must be strictly ascending by input"
+                )
+            )
+
 
 def validate_range(range_obj: Any, path: str, errors: list, file: str):
     """Validate a range object."""
@@ -313,6 +366,105 @@ def validate_color_by_speed(cbs: Any, path: str, errors: list, file: str):
         )
 
 
+def validate_composite_channel(channel: Any, path: str, errors: list, file: str):
+    """Validate a composite channel (weighted bands, range, gamma)."""
+    if not isinstance(channel, dict):
+        errors.append(ValidationError(file, path, "Channel must be object"))
+        return
+
+    bands = channel.get("bands")
+    if not isinstance(bands, list) or not bands:
+        errors.append(
+            ValidationError(file, f"{path}.bands", "bands must be non-empty array")
+        )
+    else:
+        for i, term in enumerate(bands):
+            if not isinstance(term, dict) or not isinstance(term.get("band"), str):
+                errors.append(
+                    ValidationError(
+                        file, f"{path}.bands[{i}]", "Band term must have string 'band'"
+                    )
+                )
+            elif "weight" in term and not isinstance(term["weight"], (int, float)):
+                errors.append(
+                    ValidationError(
+                        file, f"{path}.bands[{i}].weight", "weight must be number"
+                    )
+                )
+
+    rng = channel.get("range")
+    if (
+            not isinstance(rng, list)
+            or len(rng) != 2
+            or not all(isinstance(v, (int, float)) for v in rng)
+    ):
+        errors.append(
+            ValidationError(file, f"{path}.range", "range must be [min, max] numbers")
+        )
+    elif rng[0] == rng[1]:
+        errors.append(ValidationError(file, f"{path}.range", "range min == max"))
+
+    if "gamma" in channel and (
+            not isinstance(channel["gamma"], (int, float)) or channel["gamma"] <= 0
+    ):
+        errors.append(
+            ValidationError(file, f"{path}.gamma", "gamma must be positive number")
+        )
+
+
+def validate_composite(composite: Any, path: str, errors: list, file: str):
+    """Validate a multi-band composite recipe."""
+    if not isinstance(composite, dict):
+        errors.append(ValidationError(file, path, "composite must be object"))
+        return
+
+    kind = composite.get("kind")
+    if kind not in VALID_COMPOSITE_KINDS:
+        errors.append(
+            ValidationError(
+                file,
+                f"{path}.kind",
+                f"Invalid kind '{kind}'. Valid: {VALID_COMPOSITE_KINDS}",
+            )
+        )
+        return
+
+    if kind == "packed_rgb":
+        # Single pre-composited band, no channel recipes
+        if not isinstance(composite.get("band"), str) or not composite["band"]:
+            errors.append(
+                ValidationError(
+                    file, f"{path}.band", "Composite 'packed_rgb' requires 'band'"
+                )
+            )
+        return
+
+    channels = ("red", "green", "blue") if kind == "rgb" else ("base", "overlay")
+    for name in channels:
+        if name not in composite:
+            errors.append(
+                ValidationError(file, path, f"Composite '{kind}' requires '{name}'")
+            )
+        else:
+            validate_composite_channel(
+                composite[name], f"{path}.{name}", errors, file
+            )
+
+    if kind == "sandwich":
+        stops = composite.get("overlay_stops")
+        if not isinstance(stops, list) or len(stops) < 2:
+            errors.append(
+                ValidationError(
+                    file,
+                    f"{path}.overlay_stops",
+                    "overlay_stops must have at least 2 entries",
+                )
+            )
+        else:
+            for i, stop in enumerate(stops):
+                validate_stop(stop, i, f"{path}.overlay_stops", errors, file)
+
+
 def validate_style(style_id: str, style: Any, path: str, errors: list, file: str):
     """Validate a single style definition."""
     if not isinstance(style, dict):
@@ -423,6 +575,16 @@ def validate_style(style_id: str, style: Any, path: str, errors: list, file: str
                 style["color_by_speed"], f"{path}.color_by_speed", errors, file
             )
 
+    elif style_type == "rgb_composite":
+        if "composite" not in style:
+            errors.append(
+                ValidationError(
+                    file, path, "Style type 'rgb_composite' requires 'composite'"
+                )
+            )
+        else:
+            validate_composite(style["composite"], f"{path}.composite", errors, file)
+
 
 def validate_file(filepath: Path, verbose: bool = False) -> list:
     """Validate a single style JSON file."""
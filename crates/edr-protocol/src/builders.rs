@@ -0,0 +1,573 @@
+//! Query URL builders for EDR clients.
+//!
+//! The types in [`queries`](crate::queries) parse query parameters on the
+//! server side; the builders here go the other way and produce data query
+//! URLs for load testers, validation scripts and other clients. Every URL is
+//! checked with the same parsers the server uses before it is returned, so a
+//! built URL is one the EDR API accepts.
+//!
+//! ```rust
+//! use edr_protocol::builders::{PositionQueryBuilder, QueryFormat};
+//!
+//! let url = PositionQueryBuilder::new("hrrr-surface")
+//!     .point(-100.0, 40.0)
+//!     .parameters(["TMP", "RH"])
+//!     .datetime("2024-12-29T12:00:00Z")
+//!     .format(QueryFormat::GeoJson)
+//!     .build("http://localhost:8083/edr")
+//!     .unwrap();
+//!
+//! assert_eq!(
+//!     url,
+//!     "http://localhost:8083/edr/collections/hrrr-surface/position\
+//!      ?coords=POINT(-100%2040)&datetime=2024-12-29T12:00:00Z\
+//!      &parameter-name=TMP,RH&f=GeoJSON"
+//! );
+//! ```
+
+use thiserror::Error;
+
+use crate::queries::{AreaQuery, BboxQuery, CoordinateParseError, DateTimeQuery, PositionQuery};
+
+/// Errors that can occur when building a query URL.
+#[derive(Debug, Error, PartialEq)]
+pub enum QueryBuildError {
+    /// A required query parameter was not set.
+    #[error("Missing required parameter: {0}")]
+    Missing(&'static str),
+
+    /// A query parameter the server would reject.
+    #[error("Invalid {name}: {source}")]
+    Invalid {
+        name: &'static str,
+        #[source]
+        source: CoordinateParseError,
+    },
+
+    /// A collection or instance id that can't be used as a path segment.
+    #[error("Invalid identifier '{0}'")]
+    InvalidId(String),
+
+    /// A parameter name that is empty or contains a comma.
+    #[error("Invalid parameter name '{0}'")]
+    InvalidParameterName(String),
+}
+
+/// Output format requested with the `f` query parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueryFormat {
+    /// CoverageJSON (the server default)
+    #[default]
+    CoverageJson,
+    /// GeoJSON
+    GeoJson,
+}
+
+impl QueryFormat {
+    /// Value of the `f` query parameter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueryFormat::CoverageJson => "CoverageJSON",
+            QueryFormat::GeoJson => "GeoJSON",
+        }
+    }
+}
+
+/// Parameters shared by all data queries.
+#[derive(Debug, Clone, Default)]
+struct QueryOptions {
+    instance: Option<String>,
+    z: Option<String>,
+    datetime: Option<String>,
+    parameters: Vec<String>,
+    crs: Option<String>,
+    format: Option<QueryFormat>,
+}
+
+impl QueryOptions {
+    /// Validate the shared parameters and append them to `params`.
+    fn push_params(&self, params: &mut Vec<(&'static str, String)>) -> Result<(), QueryBuildError> {
+        if let Some(z) = &self.z {
+            PositionQuery::parse_z(z)
+                .map_err(|source| QueryBuildError::Invalid { name: "z", source })?;
+            params.push(("z", z.clone()));
+        }
+        if let Some(datetime) = &self.datetime {
+            DateTimeQuery::parse(datetime).map_err(|source| QueryBuildError::Invalid {
+                name: "datetime",
+                source,
+            })?;
+            params.push(("datetime", datetime.clone()));
+        }
+        if !self.parameters.is_empty() {
+            if let Some(name) = self
+                .parameters
+                .iter()
+                .find(|p| p.trim().is_empty() || p.contains(','))
+            {
+                return Err(QueryBuildError::InvalidParameterName(name.clone()));
+            }
+            params.push(("parameter-name", self.parameters.join(",")));
+        }
+        if let Some(crs) = &self.crs {
+            params.push(("crs", crs.clone()));
+        }
+        if let Some(format) = self.format {
+            params.push(("f", format.as_str().to_string()));
+        }
+        Ok(())
+    }
+
+    /// Assemble the URL of a `query_type` query on `collection`.
+    fn url(
+        &self,
+        base_url: &str,
+        collection: &str,
+        query_type: &str,
+        params: &[(&'static str, String)],
+    ) -> Result<String, QueryBuildError> {
+        let mut url = format!(
+            "{}/collections/{}",
+            base_url.trim_end_matches('/'),
+            path_segment(collection)?
+        );
+        if let Some(instance) = &self.instance {
+            url.push_str("/instances/");
+            url.push_str(path_segment(instance)?);
+        }
+        url.push('/');
+        url.push_str(query_type);
+
+        for (i, (name, value)) in params.iter().enumerate() {
+            url.push(if i == 0 { '?' } else { '&' });
+            url.push_str(name);
+            url.push('=');
+            url.push_str(&encode(value));
+        }
+        Ok(url)
+    }
+}
+
+/// Setters for the parameters every query builder shares.
+macro_rules! query_option_setters {
+    () => {
+        /// Query a specific model run instead of the latest one.
+        pub fn instance(mut self, instance_id: impl Into<String>) -> Self {
+            self.options.instance = Some(instance_id.into());
+            self
+        }
+
+        /// Vertical levels to query.
+        pub fn z(mut self, levels: &[f64]) -> Self {
+            let levels: Vec<String> = levels.iter().map(f64::to_string).collect();
+            self.options.z = Some(levels.join(","));
+            self
+        }
+
+        /// Vertical range to query (`from/to`).
+        pub fn z_range(mut self, from: f64, to: f64) -> Self {
+            self.options.z = Some(format!("{}/{}", from, to));
+            self
+        }
+
+        /// Datetime instant, list or interval (e.g. `2024-12-29T12:00:00Z`,
+        /// `2024-12-29T00:00:00Z/..`, or `../..` for every time).
+        pub fn datetime(mut self, datetime: impl Into<String>) -> Self {
+            self.options.datetime = Some(datetime.into());
+            self
+        }
+
+        /// Add a parameter to retrieve.
+        pub fn parameter(mut self, name: impl Into<String>) -> Self {
+            self.options.parameters.push(name.into());
+            self
+        }
+
+        /// Add several parameters to retrieve.
+        pub fn parameters<I, S>(mut self, names: I) -> Self
+        where
+            I: IntoIterator<Item = S>,
+            S: Into<String>,
+        {
+            self.options
+                .parameters
+                .extend(names.into_iter().map(Into::into));
+            self
+        }
+
+        /// Coordinate reference system of the response.
+        pub fn crs(mut self, crs: impl Into<String>) -> Self {
+            self.options.crs = Some(crs.into());
+            self
+        }
+
+        /// Output format of the response.
+        pub fn format(mut self, format: QueryFormat) -> Self {
+            self.options.format = Some(format);
+            self
+        }
+    };
+}
+
+/// Builder for position queries at one or more points.
+#[derive(Debug, Clone)]
+pub struct PositionQueryBuilder {
+    collection: String,
+    points: Vec<(f64, f64)>,
+    options: QueryOptions,
+}
+
+impl PositionQueryBuilder {
+    /// Start a position query on a collection.
+    pub fn new(collection: impl Into<String>) -> Self {
+        Self {
+            collection: collection.into(),
+            points: Vec::new(),
+            options: QueryOptions::default(),
+        }
+    }
+
+    /// Add a point to query. Several points make a `MULTIPOINT` query.
+    pub fn point(mut self, lon: f64, lat: f64) -> Self {
+        self.points.push((lon, lat));
+        self
+    }
+
+    query_option_setters!();
+
+    /// Validate the query and return its URL under `base_url` (the EDR API
+    /// root, e.g. `http://localhost:8083/edr`).
+    pub fn build(&self, base_url: &str) -> Result<String, QueryBuildError> {
+        let coords = match self.points.as_slice() {
+            [] => return Err(QueryBuildError::Missing("coords")),
+            [(lon, lat)] => format!("POINT({} {})", lon, lat),
+            points => {
+                let points: Vec<String> = points
+                    .iter()
+                    .map(|(lon, lat)| format!("({} {})", lon, lat))
+                    .collect();
+                format!("MULTIPOINT({})", points.join(","))
+            }
+        };
+        PositionQuery::parse_coords_multi(&coords).map_err(|source| QueryBuildError::Invalid {
+            name: "coords",
+            source,
+        })?;
+
+        let mut params = vec![("coords", coords)];
+        self.options.push_params(&mut params)?;
+        self.options
+            .url(base_url, &self.collection, "position", &params)
+    }
+}
+
+/// Builder for area queries over a polygon.
+#[derive(Debug, Clone)]
+pub struct AreaQueryBuilder {
+    collection: String,
+    ring: Vec<(f64, f64)>,
+    options: QueryOptions,
+}
+
+impl AreaQueryBuilder {
+    /// Start an area query on a collection.
+    pub fn new(collection: impl Into<String>) -> Self {
+        Self {
+            collection: collection.into(),
+            ring: Vec::new(),
+            options: QueryOptions::default(),
+        }
+    }
+
+    /// Polygon to query, as its exterior ring. The ring is closed if its
+    /// last point isn't its first.
+    pub fn polygon(mut self, ring: impl IntoIterator<Item = (f64, f64)>) -> Self {
+        self.ring = ring.into_iter().collect();
+        if let (Some(&first), Some(&last)) = (self.ring.first(), self.ring.last()) {
+            if first != last {
+                self.ring.push(first);
+            }
+        }
+        self
+    }
+
+    /// Rectangular polygon covering a bounding box.
+    pub fn bbox(self, west: f64, south: f64, east: f64, north: f64) -> Self {
+        self.polygon([(west, south), (east, south), (east, north), (west, north)])
+    }
+
+    query_option_setters!();
+
+    /// Validate the query and return its URL under `base_url`.
+    pub fn build(&self, base_url: &str) -> Result<String, QueryBuildError> {
+        if self.ring.is_empty() {
+            return Err(QueryBuildError::Missing("coords"));
+        }
+        let ring: Vec<String> = self
+            .ring
+            .iter()
+            .map(|(lon, lat)| format!("{} {}", lon, lat))
+            .collect();
+        let coords = format!("POLYGON(({}))", ring.join(","));
+        AreaQuery::parse_polygon_multi(&coords).map_err(|source| QueryBuildError::Invalid {
+            name: "coords",
+            source,
+        })?;
+
+        let mut params = vec![("coords", coords)];
+        self.options.push_params(&mut params)?;
+        self.options
+            .url(base_url, &self.collection, "area", &params)
+    }
+}
+
+/// Builder for cube queries over a bounding box and vertical levels.
+#[derive(Debug, Clone)]
+pub struct CubeQueryBuilder {
+    collection: String,
+    bbox: Option<[f64; 4]>,
+    resolution: Option<(u32, u32)>,
+    options: QueryOptions,
+}
+
+impl CubeQueryBuilder {
+    /// Start a cube query on a collection.
+    pub fn new(collection: impl Into<String>) -> Self {
+        Self {
+            collection: collection.into(),
+            bbox: None,
+            resolution: None,
+            options: QueryOptions::default(),
+        }
+    }
+
+    /// Bounding box to query.
+    pub fn bbox(mut self, west: f64, south: f64, east: f64, north: f64) -> Self {
+        self.bbox = Some([west, south, east, north]);
+        self
+    }
+
+    /// Number of output grid points along x and y (0 = native resolution).
+    pub fn resolution(mut self, x: u32, y: u32) -> Self {
+        self.resolution = Some((x, y));
+        self
+    }
+
+    query_option_setters!();
+
+    /// Validate the query and return its URL under `base_url`. Cube queries
+    /// require both a bounding box and vertical levels.
+    pub fn build(&self, base_url: &str) -> Result<String, QueryBuildError> {
+        let [west, south, east, north] = self.bbox.ok_or(QueryBuildError::Missing("bbox"))?;
+        if self.options.z.is_none() {
+            return Err(QueryBuildError::Missing("z"));
+        }
+        let bbox = format!("{},{},{},{}", west, south, east, north);
+        BboxQuery::parse(&bbox).map_err(|source| QueryBuildError::Invalid {
+            name: "bbox",
+            source,
+        })?;
+
+        let mut params = vec![("bbox", bbox)];
+        self.options.push_params(&mut params)?;
+        if let Some((x, y)) = self.resolution {
+            params.push(("resolution-x", x.to_string()));
+            params.push(("resolution-y", y.to_string()));
+        }
+        self.options
+            .url(base_url, &self.collection, "cube", &params)
+    }
+}
+
+/// Check that an id can be used as a URL path segment as is.
+fn path_segment(id: &str) -> Result<&str, QueryBuildError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~' | ':'));
+    if valid {
+        Ok(id)
+    } else {
+        Err(QueryBuildError::InvalidId(id.to_string()))
+    }
+}
+
+/// Percent-encode a query parameter value. Separators EDR values are built
+/// from (`,` `/` `:` and parentheses) are kept readable.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b','
+            | b'/'
+            | b':'
+            | b'('
+            | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::ParsedCoords;
+
+    const BASE: &str = "http://localhost:8083/edr/";
+
+    /// Decode the value of query parameter `name`.
+    fn param(url: &str, name: &str) -> Option<String> {
+        let query = url.split_once('?')?.1;
+        query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == name).then(|| {
+                let mut decoded = Vec::new();
+                let bytes = value.as_bytes();
+                let mut i = 0;
+                while i < bytes.len() {
+                    if bytes[i] == b'%' {
+                        let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+                        decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                        i += 3;
+                    } else {
+                        decoded.push(bytes[i]);
+                        i += 1;
+                    }
+                }
+                String::from_utf8(decoded).unwrap()
+            })
+        })
+    }
+
+    #[test]
+    fn test_position_query() {
+        let url = PositionQueryBuilder::new("hrrr-isobaric")
+            .point(-100.0, 40.5)
+            .z(&[850.0, 700.0])
+            .parameter("TMP")
+            .build(BASE)
+            .unwrap();
+        assert!(url.starts_with("http://localhost:8083/edr/collections/hrrr-isobaric/position?"));
+        assert_eq!(param(&url, "z").unwrap(), "850,700");
+
+        // The server's parser reads the coordinates back
+        let coords = param(&url, "coords").unwrap();
+        assert_eq!(
+            PositionQuery::parse_coords(&coords).unwrap(),
+            (-100.0, 40.5)
+        );
+    }
+
+    #[test]
+    fn test_multipoint_position_query() {
+        let url = PositionQueryBuilder::new("gfs")
+            .point(-97.5, 35.2)
+            .point(-100.0, 40.0)
+            .datetime("tomorrow")
+            .build(BASE);
+        // The datetime isn't ISO 8601
+        assert!(matches!(
+            url,
+            Err(QueryBuildError::Invalid {
+                name: "datetime",
+                ..
+            })
+        ));
+
+        let url = PositionQueryBuilder::new("gfs")
+            .point(-97.5, 35.2)
+            .point(-100.0, 40.0)
+            .instance("2024-12-29T12:00:00Z")
+            .datetime("2024-12-29T12:00:00+00:00/..")
+            .build(BASE)
+            .unwrap();
+        assert!(url.contains("/collections/gfs/instances/2024-12-29T12:00:00Z/position?"));
+        assert!(url.contains("&datetime=2024-12-29T12:00:00%2B00:00/..")); // '+' is encoded
+        let coords = param(&url, "coords").unwrap();
+        assert_eq!(
+            PositionQuery::parse_coords_multi(&coords).unwrap(),
+            ParsedCoords::Multi(vec![(-97.5, 35.2), (-100.0, 40.0)])
+        );
+    }
+
+    #[test]
+    fn test_area_query() {
+        let url = AreaQueryBuilder::new("hrrr-surface")
+            .bbox(-101.0, 39.0, -100.0, 40.0)
+            .parameters(["TMP", "RH"])
+            .datetime("../..")
+            .format(QueryFormat::CoverageJson)
+            .build(BASE)
+            .unwrap();
+        assert_eq!(param(&url, "parameter-name").unwrap(), "TMP,RH");
+        assert_eq!(param(&url, "f").unwrap(), "CoverageJSON");
+
+        // The ring was closed
+        let coords = param(&url, "coords").unwrap();
+        assert_eq!(coords, "POLYGON((-101 39,-100 39,-100 40,-101 40,-101 39))");
+        assert_eq!(AreaQuery::parse_polygon(&coords).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_cube_query() {
+        let url = CubeQueryBuilder::new("hrrr-isobaric")
+            .bbox(-102.0, 38.0, -100.0, 40.0)
+            .z(&[850.0])
+            .resolution(10, 10)
+            .build(BASE)
+            .unwrap();
+        assert_eq!(
+            url,
+            "http://localhost:8083/edr/collections/hrrr-isobaric/cube\
+             ?bbox=-102,38,-100,40&z=850&resolution-x=10&resolution-y=10"
+        );
+
+        let no_levels = CubeQueryBuilder::new("hrrr-isobaric").bbox(-102.0, 38.0, -100.0, 40.0);
+        assert_eq!(no_levels.build(BASE), Err(QueryBuildError::Missing("z")));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_eq!(
+            PositionQueryBuilder::new("gfs").build(BASE),
+            Err(QueryBuildError::Missing("coords"))
+        );
+        assert!(matches!(
+            PositionQueryBuilder::new("gfs")
+                .point(-200.0, 40.0)
+                .build(BASE),
+            Err(QueryBuildError::Invalid { name: "coords", .. })
+        ));
+        // Too few vertices for a polygon
+        assert!(AreaQueryBuilder::new("gfs")
+            .polygon([(0.0, 0.0), (1.0, 1.0)])
+            .build(BASE)
+            .is_err());
+        assert!(CubeQueryBuilder::new("gfs")
+            .bbox(0.0, 10.0, 1.0, 5.0)
+            .z(&[850.0])
+            .build(BASE)
+            .is_err());
+        assert_eq!(
+            PositionQueryBuilder::new("gfs")
+                .point(0.0, 0.0)
+                .parameter("TMP,RH")
+                .build(BASE),
+            Err(QueryBuildError::InvalidParameterName("TMP,RH".to_string()))
+        );
+        assert_eq!(
+            PositionQueryBuilder::new("../admin")
+                .point(0.0, 0.0)
+                .build(BASE),
+            Err(QueryBuildError::InvalidId("../admin".to_string()))
+        );
+    }
+}
@@ -1,4 +1,8 @@
 //! EDR API error types.
+//!
+//! Errors are reported to clients as RFC 7807 problem details
+//! (`application/problem+json`). Each [`EdrError`] maps to an
+//! [`EdrErrorKind`], which fixes the problem `type` URI, title and HTTP status.
 
 use thiserror::Error;
 
@@ -20,10 +24,18 @@ pub enum EdrError {
     #[error("Parameter not found: {0}")]
     ParameterNotFound(String),
 
-    /// Invalid query parameter.
-    #[error("Invalid parameter: {0}")]
+    /// Named location not found.
+    #[error("Location not found: {0}")]
+    LocationNotFound(String),
+
+    /// Invalid query parameter (the message describes the problem).
+    #[error("{0}")]
     InvalidParameter(String),
 
+    /// Query location lies outside the collection's extent.
+    #[error("Outside collection extent: {0}")]
+    OutOfExtent(String),
+
     /// Coordinate parsing error.
     #[error("Coordinate error: {0}")]
     CoordinateError(#[from] CoordinateParseError),
@@ -32,8 +44,8 @@ pub enum EdrError {
     #[error("No data available: {0}")]
     NoDataAvailable(String),
 
-    /// Response would be too large.
-    #[error("Response too large: {0}")]
+    /// Response would be too large (the message describes the limit).
+    #[error("{0}")]
     ResponseTooLarge(String),
 
     /// Unsupported query type.
@@ -48,51 +60,134 @@ pub enum EdrError {
     #[error("Unsupported CRS: {0}")]
     UnsupportedCrs(String),
 
+    /// None of the media types in the Accept header can be produced.
+    #[error("Not acceptable: {0}")]
+    NotAcceptable(String),
+
     /// Internal server error.
     #[error("Internal error: {0}")]
     InternalError(String),
 
-    /// Data access error.
+    /// Reading the data failed for a transient reason (storage or catalog
+    /// unavailable); the same request may succeed later.
     #[error("Data access error: {0}")]
     DataAccessError(String),
 }
 
-impl EdrError {
-    /// Get the HTTP status code for this error.
+/// Media type of problem detail responses.
+pub const PROBLEM_JSON_MEDIA_TYPE: &str = "application/problem+json";
+
+/// Base URI of the problem `type` identifiers.
+const EXCEPTION_BASE_URI: &str = "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0";
+
+/// Category of an error, as reported in the problem `type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdrErrorKind {
+    /// A query parameter is missing, malformed or unsupported.
+    InvalidParameter,
+    /// The requested collection does not exist.
+    UnknownCollection,
+    /// Another resource (instance, parameter, location, data) does not exist.
+    NotFound,
+    /// The query location lies outside the collection's extent.
+    OutOfExtent,
+    /// The Accept header asks for a media type that cannot be produced.
+    NotAcceptable,
+    /// The response would exceed a size or cost limit.
+    TooLarge,
+    /// The caller's query budget is exhausted.
+    TooManyRequests,
+    /// The server failed to handle a valid request.
+    ServerError,
+    /// A backend the server depends on (storage, catalog) failed.
+    Upstream,
+}
+
+impl EdrErrorKind {
+    /// Problem `type` URI.
+    pub fn type_uri(&self) -> String {
+        let name = match self {
+            EdrErrorKind::InvalidParameter => "invalid-parameter-value",
+            EdrErrorKind::UnknownCollection => "unknown-collection",
+            EdrErrorKind::NotFound => "not-found",
+            EdrErrorKind::OutOfExtent => "out-of-extent",
+            EdrErrorKind::NotAcceptable => "not-acceptable",
+            EdrErrorKind::TooLarge => "response-too-large",
+            EdrErrorKind::TooManyRequests => "too-many-requests",
+            EdrErrorKind::ServerError => "server-error",
+            EdrErrorKind::Upstream => "upstream-error",
+        };
+        format!("{}/{}", EXCEPTION_BASE_URI, name)
+    }
+
+    /// Problem `title`.
+    pub fn title(&self) -> &'static str {
+        match self {
+            EdrErrorKind::InvalidParameter | EdrErrorKind::OutOfExtent => "Bad Request",
+            EdrErrorKind::UnknownCollection | EdrErrorKind::NotFound => "Not Found",
+            EdrErrorKind::NotAcceptable => "Not Acceptable",
+            EdrErrorKind::TooLarge => "Payload Too Large",
+            EdrErrorKind::TooManyRequests => "Too Many Requests",
+            EdrErrorKind::ServerError => "Internal Server Error",
+            EdrErrorKind::Upstream => "Bad Gateway",
+        }
+    }
+
+    /// HTTP status code.
     pub fn status_code(&self) -> u16 {
         match self {
-            EdrError::CollectionNotFound(_) => 404,
-            EdrError::InstanceNotFound(_) => 404,
-            EdrError::ParameterNotFound(_) => 404,
-            EdrError::InvalidParameter(_) => 400,
-            EdrError::CoordinateError(_) => 400,
-            EdrError::NoDataAvailable(_) => 404,
-            EdrError::ResponseTooLarge(_) => 413,
-            EdrError::UnsupportedQuery(_) => 400,
-            EdrError::UnsupportedFormat(_) => 400,
-            EdrError::UnsupportedCrs(_) => 400,
-            EdrError::InternalError(_) => 500,
-            EdrError::DataAccessError(_) => 500,
+            EdrErrorKind::InvalidParameter | EdrErrorKind::OutOfExtent => 400,
+            EdrErrorKind::UnknownCollection | EdrErrorKind::NotFound => 404,
+            EdrErrorKind::NotAcceptable => 406,
+            EdrErrorKind::TooLarge => 413,
+            EdrErrorKind::TooManyRequests => 429,
+            EdrErrorKind::ServerError => 500,
+            EdrErrorKind::Upstream => 502,
         }
     }
 
-    /// Convert to an ExceptionResponse.
-    pub fn to_exception(&self) -> ExceptionResponse {
+    /// Build a problem detail of this kind.
+    pub fn exception(&self, detail: impl Into<String>) -> ExceptionResponse {
+        ExceptionResponse::new(self.type_uri(), self.status_code(), detail).with_title(self.title())
+    }
+}
+
+impl EdrError {
+    /// Get the category of this error.
+    pub fn kind(&self) -> EdrErrorKind {
         match self {
-            EdrError::CollectionNotFound(msg) => ExceptionResponse::not_found(msg),
-            EdrError::InstanceNotFound(msg) => ExceptionResponse::not_found(msg),
-            EdrError::ParameterNotFound(msg) => ExceptionResponse::not_found(msg),
-            EdrError::NoDataAvailable(msg) => ExceptionResponse::not_found(msg),
-            EdrError::InvalidParameter(msg) => ExceptionResponse::bad_request(msg),
-            EdrError::CoordinateError(e) => ExceptionResponse::bad_request(e.to_string()),
-            EdrError::UnsupportedQuery(msg) => ExceptionResponse::bad_request(msg),
-            EdrError::UnsupportedFormat(msg) => ExceptionResponse::bad_request(msg),
-            EdrError::UnsupportedCrs(msg) => ExceptionResponse::bad_request(msg),
-            EdrError::ResponseTooLarge(msg) => ExceptionResponse::payload_too_large(msg),
-            EdrError::InternalError(msg) => ExceptionResponse::internal_error(msg),
-            EdrError::DataAccessError(msg) => ExceptionResponse::internal_error(msg),
+            EdrError::CollectionNotFound(_) => EdrErrorKind::UnknownCollection,
+            EdrError::InstanceNotFound(_)
+            | EdrError::ParameterNotFound(_)
+            | EdrError::LocationNotFound(_)
+            | EdrError::NoDataAvailable(_) => EdrErrorKind::NotFound,
+            EdrError::InvalidParameter(_)
+            | EdrError::CoordinateError(_)
+            | EdrError::UnsupportedQuery(_)
+            | EdrError::UnsupportedFormat(_)
+            | EdrError::UnsupportedCrs(_) => EdrErrorKind::InvalidParameter,
+            EdrError::OutOfExtent(_) => EdrErrorKind::OutOfExtent,
+            EdrError::NotAcceptable(_) => EdrErrorKind::NotAcceptable,
+            EdrError::ResponseTooLarge(_) => EdrErrorKind::TooLarge,
+            EdrError::InternalError(_) => EdrErrorKind::ServerError,
+            EdrError::DataAccessError(_) => EdrErrorKind::Upstream,
         }
     }
+
+    /// Get the HTTP status code for this error.
+    pub fn status_code(&self) -> u16 {
+        self.kind().status_code()
+    }
+
+    /// Convert to an ExceptionResponse (problem detail without an instance).
+    pub fn to_exception(&self) -> ExceptionResponse {
+        self.kind().exception(self.to_string())
+    }
+
+    /// Convert to a problem detail for the request at `instance`.
+    pub fn to_problem(&self, instance: impl Into<String>) -> ExceptionResponse {
+        self.to_exception().with_instance(instance)
+    }
 }
 
 #[cfg(test)]
@@ -151,6 +246,53 @@ mod tests {
         assert!(display.contains("test-collection"));
     }
 
+    #[test]
+    fn test_error_kinds() {
+        let err = EdrError::CollectionNotFound("gfs".to_string());
+        assert_eq!(err.kind(), EdrErrorKind::UnknownCollection);
+        assert!(err.to_exception().type_.ends_with("/unknown-collection"));
+
+        let err = EdrError::OutOfExtent("POINT(10 50) not in -125,20,-60,55".to_string());
+        assert_eq!(err.status_code(), 400);
+        assert!(err.to_exception().type_.ends_with("/out-of-extent"));
+
+        assert_eq!(
+            EdrError::NotAcceptable("text/xml".to_string()).status_code(),
+            406
+        );
+        assert_eq!(
+            EdrError::UnsupportedCrs("EPSG:3857".to_string()).kind(),
+            EdrErrorKind::InvalidParameter
+        );
+    }
+
+    #[test]
+    fn test_error_to_problem() {
+        let err = EdrError::InvalidParameter("Missing required parameter: coords".to_string());
+        let problem = err.to_problem("/edr/collections/hrrr/position");
+
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(
+            json["type"],
+            "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0/invalid-parameter-value"
+        );
+        assert_eq!(json["title"], "Bad Request");
+        assert_eq!(json["status"], 400);
+        assert_eq!(json["detail"], "Missing required parameter: coords");
+        assert_eq!(json["instance"], "/edr/collections/hrrr/position");
+    }
+
+    #[test]
+    fn test_data_access_error_is_bad_gateway() {
+        let err = EdrError::DataAccessError("storage timed out".to_string());
+        assert_eq!(err.kind(), EdrErrorKind::Upstream);
+        assert_eq!(err.status_code(), 502);
+
+        let exc = err.to_exception();
+        assert_eq!(exc.title.as_deref(), Some("Bad Gateway"));
+        assert!(exc.type_.ends_with("/upstream-error"));
+    }
+
     #[test]
     fn test_response_too_large_exception() {
         let err =
@@ -25,6 +25,7 @@
 //! );
 //! ```
 
+pub mod builders;
 pub mod collections;
 pub mod coverage_json;
 pub mod errors;
@@ -32,23 +33,28 @@ pub mod geojson;
 pub mod locations;
 pub mod parameters;
 pub mod queries;
+pub mod raster;
 pub mod responses;
 pub mod types;
 
 // Re-export commonly used types
+pub use builders::{
+    AreaQueryBuilder, CubeQueryBuilder, PositionQueryBuilder, QueryBuildError, QueryFormat,
+};
 pub use collections::{Collection, CollectionList, DataQueries, Instance, InstanceList};
 pub use coverage_json::{
     Axis, CoverageCollection, CoverageJson, Domain, DomainType, NdArray, ReferenceSystem,
 };
-pub use errors::EdrError;
+pub use errors::{EdrError, EdrErrorKind, PROBLEM_JSON_MEDIA_TYPE};
 pub use geojson::{EdrFeature, EdrFeatureCollection, EdrGeometry, EdrProperties, ParameterValue};
 pub use locations::{Location, LocationFeature, LocationFeatureCollection, LocationsConfig};
 pub use parameters::{ObservedProperty, Parameter, Unit};
 pub use queries::{
     AreaQuery, BboxQuery, CoordinateParseError, CorridorQuery, DateTimeQuery, DistanceUnit,
-    LineStringType, ParsedCoords, ParsedPolygons, ParsedTrajectory, PositionQuery, RadiusQuery,
-    TrajectoryQuery, TrajectoryWaypoint, VerticalUnit,
+    LineStringType, ParsedCoords, ParsedPolygons, ParsedTrajectory, PolygonRings, PositionQuery,
+    RadiusQuery, TrajectoryQuery, TrajectoryWaypoint, VerticalUnit,
 };
+pub use raster::{RasterExportError, RasterGrid};
 pub use responses::{ConformanceClasses, LandingPage};
 pub use types::{Crs, Extent, Link, LinkVariables, SpatialExtent, TemporalExtent, VerticalExtent};
 
@@ -639,21 +639,81 @@ pub struct AreaQuery {
     pub crs: Option<String>,
 }
 
+/// A polygon as an exterior ring and zero or more interior rings (holes).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolygonRings {
+    /// Exterior ring of (lon, lat) points.
+    pub exterior: Vec<(f64, f64)>,
+    /// Interior rings; points inside a hole are outside the polygon.
+    pub holes: Vec<Vec<(f64, f64)>>,
+}
+
+impl PolygonRings {
+    /// Check if a point is inside the exterior ring and outside every hole.
+    pub fn contains_point(&self, lon: f64, lat: f64) -> bool {
+        ring_contains_point(&self.exterior, lon, lat)
+            && !self
+                .holes
+                .iter()
+                .any(|hole| ring_contains_point(hole, lon, lat))
+    }
+}
+
 /// Result of parsing polygon coordinates - can be single polygon or multiple polygons.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParsedPolygons {
-    /// Single polygon (ring of points).
-    Single(Vec<(f64, f64)>),
+    /// Single polygon.
+    Single(PolygonRings),
     /// Multiple polygons.
-    Multi(Vec<Vec<(f64, f64)>>),
+    Multi(Vec<PolygonRings>),
+}
+
+impl ParsedPolygons {
+    /// All polygons, in the order given.
+    pub fn polygons(&self) -> &[PolygonRings] {
+        match self {
+            ParsedPolygons::Single(polygon) => std::slice::from_ref(polygon),
+            ParsedPolygons::Multi(polygons) => polygons,
+        }
+    }
+
+    /// Check if a point is inside any of the polygons (holes excluded).
+    pub fn contains_point(&self, lon: f64, lat: f64) -> bool {
+        self.polygons()
+            .iter()
+            .any(|polygon| polygon.contains_point(lon, lat))
+    }
+
+    /// Bounding box enclosing every polygon's exterior ring.
+    pub fn bbox(&self) -> BboxQuery {
+        let mut west = f64::MAX;
+        let mut south = f64::MAX;
+        let mut east = f64::MIN;
+        let mut north = f64::MIN;
+
+        for (lon, lat) in self.polygons().iter().flat_map(|p| &p.exterior) {
+            west = west.min(*lon);
+            east = east.max(*lon);
+            south = south.min(*lat);
+            north = north.max(*lat);
+        }
+
+        BboxQuery {
+            west,
+            south,
+            east,
+            north,
+        }
+    }
 }
 
 impl AreaQuery {
     /// Parse a WKT POLYGON or MULTIPOLYGON string.
     ///
-    /// Accepts formats:
+    /// Accepts formats, each polygon optionally followed by interior rings:
     /// - `POLYGON((lon1 lat1, lon2 lat2, lon3 lat3, lon1 lat1))`
-    /// - `MULTIPOLYGON(((ring1)),((ring2)))`
+    /// - `POLYGON((exterior),(hole1),(hole2))`
+    /// - `MULTIPOLYGON(((ring1)),((ring2),(hole)))`
     ///
     /// Returns ParsedPolygons to handle both cases.
     pub fn parse_polygon_multi(coords: &str) -> Result<ParsedPolygons, CoordinateParseError> {
@@ -666,8 +726,8 @@ impl AreaQuery {
         }
 
         if upper.starts_with("POLYGON") {
-            let polygon = Self::parse_polygon(coords)?;
-            return Ok(ParsedPolygons::Single(polygon));
+            let body = Self::wkt_body(coords.get("POLYGON".len()..).unwrap_or_default())?;
+            return Ok(ParsedPolygons::Single(Self::parse_polygon_rings(body)?));
         }
 
         Err(CoordinateParseError::InvalidWkt(
@@ -750,64 +810,14 @@ impl AreaQuery {
 
     /// Parse a WKT MULTIPOLYGON string.
     ///
-    /// Accepts format: `MULTIPOLYGON(((ring1)),((ring2)))`
-    fn parse_wkt_multipolygon(coords: &str) -> Result<Vec<Vec<(f64, f64)>>, CoordinateParseError> {
-        // Find content after MULTIPOLYGON
-        let start = coords.find('(').ok_or_else(|| {
-            CoordinateParseError::InvalidWkt("Missing opening parenthesis".to_string())
-        })?;
-        let end = coords.rfind(')').ok_or_else(|| {
-            CoordinateParseError::InvalidWkt("Missing closing parenthesis".to_string())
-        })?;
+    /// Accepts format: `MULTIPOLYGON(((ring1)),((ring2),(hole)))`
+    fn parse_wkt_multipolygon(coords: &str) -> Result<Vec<PolygonRings>, CoordinateParseError> {
+        let body = Self::wkt_body(coords.get("MULTIPOLYGON".len()..).unwrap_or_default())?;
 
-        if end <= start {
-            return Err(CoordinateParseError::InvalidWkt(
-                "Invalid parenthesis order".to_string(),
-            ));
-        }
-
-        let inner = &coords[start + 1..end];
-
-        // Parse each polygon - they are enclosed in double parentheses
-        // Format: ((ring1)),((ring2))
-        let mut polygons = Vec::new();
-        let mut depth = 0;
-        let mut current_polygon = String::new();
-
-        for ch in inner.chars() {
-            match ch {
-                '(' => {
-                    depth += 1;
-                    if depth > 1 {
-                        current_polygon.push(ch);
-                    }
-                }
-                ')' => {
-                    depth -= 1;
-                    if depth == 1 {
-                        // End of a polygon's ring
-                        let ring_str = current_polygon.trim();
-                        if !ring_str.is_empty() {
-                            // Remove extra parentheses if present
-                            let ring_str = ring_str.trim_start_matches('(').trim_end_matches(')');
-                            let ring = Self::parse_ring(ring_str)?;
-                            polygons.push(ring);
-                        }
-                        current_polygon.clear();
-                    } else if depth > 1 {
-                        current_polygon.push(ch);
-                    }
-                }
-                ',' if depth <= 1 => {
-                    // Skip comma between polygons
-                }
-                _ => {
-                    if depth > 1 {
-                        current_polygon.push(ch);
-                    }
-                }
-            }
-        }
+        let polygons = split_wkt_groups(body)?
+            .into_iter()
+            .map(Self::parse_polygon_rings)
+            .collect::<Result<Vec<_>, _>>()?;
 
         if polygons.is_empty() {
             return Err(CoordinateParseError::InvalidWkt(
@@ -818,6 +828,34 @@ impl AreaQuery {
         Ok(polygons)
     }
 
+    /// Contents of the single parenthesised group following a WKT keyword.
+    fn wkt_body(after_keyword: &str) -> Result<&str, CoordinateParseError> {
+        match split_wkt_groups(after_keyword)?.as_slice() {
+            [body] => Ok(body),
+            _ => Err(CoordinateParseError::InvalidWkt(
+                "Expected a single parenthesised coordinate list".to_string(),
+            )),
+        }
+    }
+
+    /// Parse a polygon body `(exterior),(hole1),...` into its rings.
+    fn parse_polygon_rings(body: &str) -> Result<PolygonRings, CoordinateParseError> {
+        let mut rings = split_wkt_groups(body)?
+            .into_iter()
+            .map(Self::parse_ring)
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+
+        let exterior = rings.next().ok_or_else(|| {
+            CoordinateParseError::InvalidWkt("Polygon must have an exterior ring".to_string())
+        })?;
+
+        Ok(PolygonRings {
+            exterior,
+            holes: rings.collect(),
+        })
+    }
+
     /// Calculate the bounding box of the polygon.
     pub fn bbox(&self) -> BboxQuery {
         let mut west = f64::MAX;
@@ -847,26 +885,76 @@ impl AreaQuery {
 
     /// Check if a point is inside the polygon using ray casting algorithm.
     pub fn contains_point(&self, lon: f64, lat: f64) -> bool {
-        let n = self.polygon.len();
-        if n < 3 {
-            return false;
-        }
+        ring_contains_point(&self.polygon, lon, lat)
+    }
+}
 
-        let mut inside = false;
-        let mut j = n - 1;
+/// Check if a point is inside a ring using ray casting algorithm.
+fn ring_contains_point(ring: &[(f64, f64)], lon: f64, lat: f64) -> bool {
+    let n = ring.len();
+    if n < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut j = n - 1;
 
-        for i in 0..n {
-            let (xi, yi) = self.polygon[i];
-            let (xj, yj) = self.polygon[j];
+    for i in 0..n {
+        let (xi, yi) = ring[i];
+        let (xj, yj) = ring[j];
 
-            if ((yi > lat) != (yj > lat)) && (lon < (xj - xi) * (lat - yi) / (yj - yi) + xi) {
-                inside = !inside;
+        if ((yi > lat) != (yj > lat)) && (lon < (xj - xi) * (lat - yi) / (yj - yi) + xi) {
+            inside = !inside;
+        }
+        j = i;
+    }
+
+    inside
+}
+
+/// Split WKT text into the contents of its top-level parenthesised groups.
+///
+/// `(a),(b (c))` yields `["a", "b (c)"]`. Anything other than commas and
+/// whitespace between groups is an error, as are unbalanced parentheses.
+fn split_wkt_groups(text: &str) -> Result<Vec<&str>, CoordinateParseError> {
+    let mut groups = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '(' => {
+                if depth == 0 {
+                    start = i + 1;
+                }
+                depth += 1;
+            }
+            ')' => {
+                depth = depth.checked_sub(1).ok_or_else(|| {
+                    CoordinateParseError::InvalidWkt("Unbalanced parentheses".to_string())
+                })?;
+                if depth == 0 {
+                    groups.push(text[start..i].trim());
+                }
             }
-            j = i;
+            ',' => {}
+            c if depth == 0 && !c.is_whitespace() => {
+                return Err(CoordinateParseError::InvalidWkt(format!(
+                    "Unexpected '{}' outside parentheses",
+                    c
+                )));
+            }
+            _ => {}
         }
+    }
 
-        inside
+    if depth != 0 {
+        return Err(CoordinateParseError::InvalidWkt(
+            "Unbalanced parentheses".to_string(),
+        ));
     }
+
+    Ok(groups)
 }
 
 /// Distance units supported for radius queries.
@@ -971,12 +1059,12 @@ impl RadiusQuery {
                 ))
             })
             .and_then(|v| {
-                if v <= 0.0 {
+                if v > 0.0 && v.is_finite() {
+                    Ok(v)
+                } else {
                     Err(CoordinateParseError::OutOfRange(
                         "Radius must be a positive number".to_string(),
                     ))
-                } else {
-                    Ok(v)
                 }
             })
     }
@@ -2316,6 +2404,88 @@ mod tests {
         assert!((area - 4.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_parse_polygon_multi_with_hole() {
+        let parsed = AreaQuery::parse_polygon_multi(
+            "POLYGON((-100 30, -90 30, -90 40, -100 40, -100 30),(-97 33, -93 33, -93 37, -97 37, -97 33))",
+        )
+        .unwrap();
+        let ParsedPolygons::Single(polygon) = &parsed else {
+            panic!("Expected a single polygon, got {:?}", parsed);
+        };
+        assert_eq!(polygon.exterior.len(), 5);
+        assert_eq!(polygon.holes.len(), 1);
+        assert_eq!(polygon.holes[0][0], (-97.0, 33.0));
+
+        // Inside the exterior ring but not the hole
+        assert!(parsed.contains_point(-99.0, 31.0));
+        // Inside the hole
+        assert!(!parsed.contains_point(-95.0, 35.0));
+        // Outside entirely
+        assert!(!parsed.contains_point(-101.0, 35.0));
+    }
+
+    #[test]
+    fn test_parse_multipolygon_with_holes() {
+        let parsed = AreaQuery::parse_polygon_multi(
+            "MULTIPOLYGON(((-100 30, -90 30, -90 40, -100 40, -100 30),(-97 33, -93 33, -93 37, -97 37, -97 33)),((-80 30, -70 30, -70 40, -80 40, -80 30)))",
+        )
+        .unwrap();
+        let polygons = parsed.polygons();
+        assert_eq!(polygons.len(), 2, "A hole is not a separate polygon");
+        assert_eq!(polygons[0].holes.len(), 1);
+        assert!(polygons[1].holes.is_empty());
+
+        assert!(parsed.contains_point(-99.0, 31.0));
+        assert!(!parsed.contains_point(-95.0, 35.0));
+        assert!(parsed.contains_point(-75.0, 35.0));
+        assert!(!parsed.contains_point(-85.0, 35.0));
+
+        // The bbox covers every polygon, not just the first
+        let bbox = parsed.bbox();
+        assert_eq!((bbox.west, bbox.east), (-100.0, -70.0));
+        assert_eq!((bbox.south, bbox.north), (30.0, 40.0));
+    }
+
+    #[test]
+    fn test_parse_polygon_multi_invalid() {
+        for coords in [
+            "POLYGON((-100 35, -98 35, -98 37, -100 37, -100 35)",
+            "POLYGON((-100 35, -98 35, -98 37, -100 37, -100 35)))",
+            "POLYGON((-100 35, -98 35, -98 37, -100 37, -100 35)) junk",
+            "POLYGON()",
+            "MULTIPOLYGON()",
+            "MULTIPOLYGON(((-100 35, -98 35, -100 35)))",
+        ] {
+            assert!(
+                AreaQuery::parse_polygon_multi(coords).is_err(),
+                "{} should be rejected",
+                coords
+            );
+        }
+    }
+
+    /// `coords` examples from the OGC API - EDR area query specification.
+    #[test]
+    fn test_parse_polygon_multi_edr_spec_examples() {
+        let parsed = AreaQuery::parse_polygon_multi(
+            "POLYGON((-6.1 50.3,-4.35 51.4,-2.6 51.6,-2.8 50.6,-5.3 49.9,-6.1 50.3))",
+        )
+        .unwrap();
+        assert_eq!(parsed.polygons().len(), 1);
+        assert_eq!(parsed.polygons()[0].exterior.len(), 6);
+        assert!(parsed.contains_point(-4.0, 50.8));
+
+        let parsed = AreaQuery::parse_polygon_multi(
+            "MULTIPOLYGON(((-6.1 50.3,-4.35 51.4,-2.6 51.6,-2.8 50.6,-5.3 49.9,-6.1 50.3)),((-2.1 52.3,-1.35 53.4,-0.6 53.6,-0.8 52.6,-2.1 52.3)))",
+        )
+        .unwrap();
+        assert_eq!(parsed.polygons().len(), 2);
+        assert!(parsed.contains_point(-4.0, 50.8));
+        assert!(parsed.contains_point(-1.2, 53.0));
+        assert!(!parsed.contains_point(-2.0, 52.0));
+    }
+
     // =========== RadiusQuery tests ===========
 
     #[test]
@@ -2370,6 +2540,8 @@ mod tests {
         assert!(RadiusQuery::parse_within("abc").is_err());
         assert!(RadiusQuery::parse_within("-10").is_err());
         assert!(RadiusQuery::parse_within("0").is_err());
+        assert!(RadiusQuery::parse_within("NaN").is_err());
+        assert!(RadiusQuery::parse_within("inf").is_err());
     }
 
     #[test]
@@ -0,0 +1,639 @@
+//! Raster exports of grid coverages for legacy GIS tools.
+//!
+//! Area and cube responses can be returned as an Arc/Info ASCII Grid (`.asc`)
+//! or as a float32 band-interleaved-by-line raster (`.bil`) with its ESRI
+//! `.hdr` and `.prj` sidecars bundled in a ZIP archive. Both formats need a
+//! regular, axis-aligned WGS84 grid, so only coverages with evenly spaced
+//! `x` and `y` axes can be exported.
+//!
+//! Rows are written north-up. Missing values become [`NODATA_VALUE`].
+
+use thiserror::Error;
+
+use crate::coverage_json::{Axis, AxisValue, CoverageCollection, CoverageJson, DomainType};
+
+/// Value written for missing cells.
+pub const NODATA_VALUE: f32 = -9999.0;
+
+/// ESRI WKT for WGS84 geographic coordinates, written to `.prj` sidecars.
+pub const WGS84_PRJ: &str = "GEOGCS[\"GCS_WGS_1984\",DATUM[\"D_WGS_1984\",\
+SPHEROID[\"WGS_1984\",6378137.0,298.257223563]],PRIMEM[\"Greenwich\",0.0],\
+UNIT[\"Degree\",0.0174532925199433]]";
+
+/// Relative tolerance when checking that axis values are evenly spaced.
+const SPACING_TOLERANCE: f64 = 1e-4;
+
+/// Errors that can occur when exporting a coverage as a raster.
+#[derive(Debug, Error, PartialEq)]
+pub enum RasterExportError {
+    /// The coverage domain is not a grid.
+    #[error("Raster export requires a Grid domain")]
+    NotAGrid,
+
+    /// A required axis is missing from the domain.
+    #[error("Raster export requires a '{0}' axis")]
+    MissingAxis(String),
+
+    /// An axis cannot be described by an origin and a cell size.
+    #[error("Raster export requires an evenly spaced '{0}' axis")]
+    IrregularAxis(String),
+
+    /// A range doesn't match the grid described by the domain.
+    #[error("Range '{0}' does not match the coverage grid")]
+    ShapeMismatch(String),
+
+    /// The format holds a single band but the coverage has several.
+    #[error("{0} bands requested; this format holds a single parameter at a single level")]
+    TooManyBands(usize),
+
+    /// The coverage has no ranges to export.
+    #[error("Coverage has no parameter values to export")]
+    Empty,
+
+    /// The archive would exceed the 4 GiB ZIP limit.
+    #[error("Raster export is too large")]
+    TooLarge,
+}
+
+/// One band of a raster export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterBand {
+    /// Band name (the parameter, with the level for multi-level cubes).
+    pub name: String,
+
+    /// Cell values, row-major and north-up. NaN marks missing cells.
+    pub values: Vec<f32>,
+}
+
+/// A regular north-up WGS84 grid with one or more bands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterGrid {
+    /// Number of columns.
+    pub ncols: usize,
+
+    /// Number of rows.
+    pub nrows: usize,
+
+    /// Longitude of the western edge of the grid.
+    pub west: f64,
+
+    /// Latitude of the northern edge of the grid.
+    pub north: f64,
+
+    /// Cell width in degrees.
+    pub dx: f64,
+
+    /// Cell height in degrees.
+    pub dy: f64,
+
+    /// Bands in export order.
+    pub bands: Vec<RasterBand>,
+}
+
+/// A regular axis: first cell centre, signed step and length.
+#[derive(Debug, Clone, Copy)]
+struct RegularAxis {
+    start: f64,
+    step: f64,
+    len: usize,
+}
+
+impl RasterGrid {
+    /// Build a raster from a grid coverage, one band per parameter.
+    ///
+    /// Bands are ordered by parameter name.
+    pub fn from_coverage(coverage: &CoverageJson) -> Result<Self, RasterExportError> {
+        let mut grid = Self::from_domain(coverage)?;
+        grid.bands = coverage_bands(coverage, &grid, None)?;
+        if grid.bands.is_empty() {
+            return Err(RasterExportError::Empty);
+        }
+        Ok(grid)
+    }
+
+    /// Build a raster from a collection of grid coverages sharing one grid,
+    /// such as the per-level coverages of a cube query.
+    ///
+    /// Bands are ordered by coverage, then by parameter name. When there is
+    /// more than one coverage, band names carry the coverage's `z` value.
+    pub fn from_collection(collection: &CoverageCollection) -> Result<Self, RasterExportError> {
+        let first = collection
+            .coverages
+            .first()
+            .ok_or(RasterExportError::Empty)?;
+        let mut grid = Self::from_domain(first)?;
+
+        for coverage in &collection.coverages {
+            let other = Self::from_domain(coverage)?;
+            if !grid.same_grid(&other) {
+                return Err(RasterExportError::ShapeMismatch(
+                    "coverage collection".to_string(),
+                ));
+            }
+            let level = (collection.coverages.len() > 1)
+                .then(|| coverage_level(coverage))
+                .flatten();
+            let bands = coverage_bands(coverage, &grid, level)?;
+            grid.bands.extend(bands);
+        }
+
+        if grid.bands.is_empty() {
+            return Err(RasterExportError::Empty);
+        }
+        Ok(grid)
+    }
+
+    /// Read the grid geometry from a coverage domain, without bands.
+    fn from_domain(coverage: &CoverageJson) -> Result<Self, RasterExportError> {
+        let domain = &coverage.domain;
+        if domain.domain_type != DomainType::Grid {
+            return Err(RasterExportError::NotAGrid);
+        }
+
+        let x = regular_axis(domain.axes.get("x"), "x")?;
+        let y = regular_axis(domain.axes.get("y"), "y")?;
+        if x.step <= 0.0 {
+            return Err(RasterExportError::IrregularAxis("x".to_string()));
+        }
+
+        let dx = x.step;
+        let dy = y.step.abs();
+        let y_max = if y.step > 0.0 {
+            y.start + y.step * (y.len - 1) as f64
+        } else {
+            y.start
+        };
+
+        Ok(Self {
+            ncols: x.len,
+            nrows: y.len,
+            west: x.start - dx / 2.0,
+            north: y_max + dy / 2.0,
+            dx,
+            dy,
+            bands: Vec::new(),
+        })
+    }
+
+    fn same_grid(&self, other: &Self) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= self.dx.min(self.dy) * SPACING_TOLERANCE;
+        self.ncols == other.ncols
+            && self.nrows == other.nrows
+            && close(self.west, other.west)
+            && close(self.north, other.north)
+            && close(self.dx, other.dx)
+            && close(self.dy, other.dy)
+    }
+
+    /// Latitude of the southern edge.
+    pub fn south(&self) -> f64 {
+        self.north - self.dy * self.nrows as f64
+    }
+
+    /// Encode the raster as an Arc/Info ASCII Grid.
+    ///
+    /// The format holds one band, so the raster must have exactly one.
+    pub fn encode_ascii_grid(&self) -> Result<String, RasterExportError> {
+        let band = match self.bands.as_slice() {
+            [band] => band,
+            [] => return Err(RasterExportError::Empty),
+            bands => return Err(RasterExportError::TooManyBands(bands.len())),
+        };
+
+        let mut out = String::with_capacity(self.ncols * self.nrows * 8 + 128);
+        out.push_str(&format!("ncols {}\n", self.ncols));
+        out.push_str(&format!("nrows {}\n", self.nrows));
+        out.push_str(&format!("xllcorner {}\n", self.west));
+        out.push_str(&format!("yllcorner {}\n", self.south()));
+        if self.is_square() {
+            out.push_str(&format!("cellsize {}\n", self.dx));
+        } else {
+            // Non-square cells use the dx/dy extension understood by GDAL
+            out.push_str(&format!("dx {}\n", self.dx));
+            out.push_str(&format!("dy {}\n", self.dy));
+        }
+        out.push_str(&format!("NODATA_value {}\n", NODATA_VALUE));
+
+        for row in band.values.chunks(self.ncols) {
+            let line: Vec<String> = row
+                .iter()
+                .map(|&v| if v.is_nan() { NODATA_VALUE } else { v }.to_string())
+                .collect();
+            out.push_str(&line.join(" "));
+            out.push('\n');
+        }
+
+        Ok(out)
+    }
+
+    /// Encode the pixels as little-endian float32, band interleaved by line.
+    pub fn encode_bil(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.ncols * self.nrows * self.bands.len() * 4);
+        for row in 0..self.nrows {
+            let cells = row * self.ncols..(row + 1) * self.ncols;
+            for band in &self.bands {
+                for &v in &band.values[cells.clone()] {
+                    let v = if v.is_nan() { NODATA_VALUE } else { v };
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    /// ESRI `.hdr` sidecar describing the `.bil` layout and georeferencing.
+    pub fn bil_header(&self) -> String {
+        let band_row_bytes = self.ncols * 4;
+        [
+            "BYTEORDER I".to_string(),
+            "LAYOUT BIL".to_string(),
+            format!("NROWS {}", self.nrows),
+            format!("NCOLS {}", self.ncols),
+            format!("NBANDS {}", self.bands.len()),
+            "NBITS 32".to_string(),
+            "PIXELTYPE FLOAT".to_string(),
+            format!("BANDROWBYTES {}", band_row_bytes),
+            format!("TOTALROWBYTES {}", band_row_bytes * self.bands.len()),
+            // ULXMAP/ULYMAP are the centre of the upper-left cell
+            format!("ULXMAP {}", self.west + self.dx / 2.0),
+            format!("ULYMAP {}", self.north - self.dy / 2.0),
+            format!("XDIM {}", self.dx),
+            format!("YDIM {}", self.dy),
+            format!("NODATA {}", NODATA_VALUE),
+            String::new(),
+        ]
+        .join("\n")
+    }
+
+    /// Bundle `<basename>.bil`, `.hdr` and `.prj` into a ZIP archive.
+    pub fn encode_bil_zip(&self, basename: &str) -> Result<Vec<u8>, RasterExportError> {
+        if self.bands.is_empty() {
+            return Err(RasterExportError::Empty);
+        }
+        let bil = self.encode_bil();
+        let hdr = self.bil_header();
+        zip_stored(&[
+            (&format!("{}.bil", basename), &bil),
+            (&format!("{}.hdr", basename), hdr.as_bytes()),
+            (&format!("{}.prj", basename), WGS84_PRJ.as_bytes()),
+        ])
+    }
+
+    fn is_square(&self) -> bool {
+        (self.dx - self.dy).abs() <= self.dx * SPACING_TOLERANCE
+    }
+}
+
+/// Describe an axis by its first value and a constant step.
+fn regular_axis(axis: Option<&Axis>, name: &str) -> Result<RegularAxis, RasterExportError> {
+    let irregular = || RasterExportError::IrregularAxis(name.to_string());
+    let axis = match axis {
+        Some(axis) => axis,
+        None => return Err(RasterExportError::MissingAxis(name.to_string())),
+    };
+
+    let regular = match axis {
+        Axis::Regular { start, stop, num } if *num >= 2 => RegularAxis {
+            start: *start,
+            step: (stop - start) / (*num - 1) as f64,
+            len: *num,
+        },
+        Axis::Values { values } if values.len() >= 2 => {
+            let values: Vec<f64> = values
+                .iter()
+                .map(|v| match v {
+                    AxisValue::Float(f) => Some(*f),
+                    AxisValue::String(_) => None,
+                })
+                .collect::<Option<_>>()
+                .ok_or_else(irregular)?;
+            let step = (values[values.len() - 1] - values[0]) / (values.len() - 1) as f64;
+            let evenly_spaced = values
+                .windows(2)
+                .all(|pair| ((pair[1] - pair[0]) - step).abs() <= step.abs() * SPACING_TOLERANCE);
+            if !evenly_spaced {
+                return Err(irregular());
+            }
+            RegularAxis {
+                start: values[0],
+                step,
+                len: values.len(),
+            }
+        }
+        _ => return Err(irregular()),
+    };
+
+    if regular.step == 0.0 || !regular.step.is_finite() {
+        return Err(irregular());
+    }
+    Ok(regular)
+}
+
+/// The single `z` value of a coverage, if it has one.
+fn coverage_level(coverage: &CoverageJson) -> Option<f64> {
+    match coverage.domain.axes.get("z")? {
+        Axis::Values { values } => match values.as_slice() {
+            [AxisValue::Float(z)] => Some(*z),
+            _ => None,
+        },
+        Axis::Regular { start, num: 1, .. } => Some(*start),
+        _ => None,
+    }
+}
+
+/// Extract one north-up band per range of a coverage.
+fn coverage_bands(
+    coverage: &CoverageJson,
+    grid: &RasterGrid,
+    level: Option<f64>,
+) -> Result<Vec<RasterBand>, RasterExportError> {
+    let Some(ranges) = &coverage.ranges else {
+        return Ok(Vec::new());
+    };
+    let y = regular_axis(coverage.domain.axes.get("y"), "y")?;
+    let north_first = y.step < 0.0;
+
+    let mut names: Vec<&String> = ranges.keys().collect();
+    names.sort();
+
+    let mut bands = Vec::with_capacity(names.len());
+    for name in names {
+        let array = &ranges[name];
+        let mismatch = || RasterExportError::ShapeMismatch(name.clone());
+
+        // Default layout is [y, x]
+        let default_axes = ["y".to_string(), "x".to_string()];
+        let axis_names = array.axis_names.as_deref().unwrap_or(&default_axes);
+        let default_shape = [grid.nrows, grid.ncols];
+        let shape = array.shape.as_deref().unwrap_or(&default_shape);
+        if axis_names.len() != shape.len() {
+            return Err(mismatch());
+        }
+
+        // Row-major strides; every axis other than x and y must be a single slice
+        let mut y_stride = None;
+        let mut x_stride = None;
+        let mut stride = 1;
+        for (axis, &len) in axis_names.iter().zip(shape).rev() {
+            match axis.as_str() {
+                "y" if len == grid.nrows => y_stride = Some(stride),
+                "x" if len == grid.ncols => x_stride = Some(stride),
+                _ if len == 1 => {}
+                _ => return Err(mismatch()),
+            }
+            stride *= len;
+        }
+        let (Some(y_stride), Some(x_stride)) = (y_stride, x_stride) else {
+            return Err(mismatch());
+        };
+        if array.values.len() != stride {
+            return Err(mismatch());
+        }
+
+        let mut values = Vec::with_capacity(grid.nrows * grid.ncols);
+        for row in 0..grid.nrows {
+            let src_row = if north_first {
+                row
+            } else {
+                grid.nrows - 1 - row
+            };
+            for col in 0..grid.ncols {
+                let v = array.values[src_row * y_stride + col * x_stride];
+                values.push(v.unwrap_or(f32::NAN));
+            }
+        }
+
+        let name = match level {
+            Some(z) => format!("{}_{}", name, z),
+            None => name.clone(),
+        };
+        bands.push(RasterBand { name, values });
+    }
+
+    Ok(bands)
+}
+
+/// Write an uncompressed ZIP archive.
+fn zip_stored(files: &[(&str, &[u8])]) -> Result<Vec<u8>, RasterExportError> {
+    // DOS date for 1980-01-01, the earliest ZIP timestamp
+    const DOS_DATE: u16 = (1 << 5) | 1;
+    const VERSION: u16 = 20;
+
+    let total: usize = files
+        .iter()
+        .map(|(name, data)| name.len() + data.len())
+        .sum();
+    if total + files.len() * 128 > u32::MAX as usize {
+        return Err(RasterExportError::TooLarge);
+    }
+
+    let mut out = Vec::with_capacity(total + files.len() * 128);
+    let mut central = Vec::new();
+
+    for (name, data) in files {
+        let offset = out.len() as u32;
+        let crc = crc32fast::hash(data);
+        let size = data.len() as u32;
+
+        // Local file header
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // flags
+        out.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+        out.extend_from_slice(&0u16.to_le_bytes()); // time
+        out.extend_from_slice(&DOS_DATE.to_le_bytes());
+        out.extend_from_slice(&crc.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(data);
+
+        // Central directory entry
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&VERSION.to_le_bytes()); // made by
+        central.extend_from_slice(&VERSION.to_le_bytes()); // needed
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes());
+        central.extend_from_slice(&DOS_DATE.to_le_bytes());
+        central.extend_from_slice(&crc.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&size.to_le_bytes());
+        central.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        central.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = out.len() as u32;
+    let central_size = central.len() as u32;
+    out.extend_from_slice(&central);
+
+    // End of central directory
+    let entries = files.len() as u16;
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.extend_from_slice(&entries.to_le_bytes());
+    out.extend_from_slice(&entries.to_le_bytes());
+    out.extend_from_slice(&central_size.to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coverage_json::{CovJsonParameter, CoverageType, Domain};
+    use std::collections::HashMap;
+
+    fn grid_coverage(domain: Domain) -> CoverageJson {
+        CoverageJson {
+            type_: CoverageType::Coverage,
+            domain,
+            parameters: Some(HashMap::new()),
+            ranges: Some(HashMap::new()),
+        }
+    }
+
+    /// 3×2 grid at 0.5° spacing, y south to north as area queries build it.
+    fn area_coverage() -> CoverageJson {
+        let domain = Domain::grid(vec![-100.0, -99.5, -99.0], vec![30.0, 30.5], None, None);
+        grid_coverage(domain).with_parameter_array_nullable(
+            "TMP",
+            CovJsonParameter::new("TMP"),
+            vec![Some(1.0), Some(2.0), Some(3.0), Some(4.0), None, Some(6.0)],
+            vec![2, 3],
+            vec!["y".to_string(), "x".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_from_coverage_flips_to_north_up() {
+        let grid = RasterGrid::from_coverage(&area_coverage()).unwrap();
+        assert_eq!((grid.ncols, grid.nrows), (3, 2));
+        assert_eq!(grid.west, -100.25);
+        assert_eq!(grid.north, 30.75);
+        assert_eq!(grid.south(), 29.75);
+        assert_eq!(grid.bands.len(), 1);
+
+        // Northern row first
+        let values = &grid.bands[0].values;
+        assert_eq!(values[0], 4.0);
+        assert!(values[1].is_nan());
+        assert_eq!(&values[2..], &[6.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_encode_ascii_grid() {
+        let grid = RasterGrid::from_coverage(&area_coverage()).unwrap();
+        let asc = grid.encode_ascii_grid().unwrap();
+        assert_eq!(
+            asc,
+            "ncols 3\nnrows 2\nxllcorner -100.25\nyllcorner 29.75\ncellsize 0.5\n\
+             NODATA_value -9999\n4 -9999 6\n1 2 3\n"
+        );
+
+        // Two parameters don't fit a single-band format
+        let coverage = area_coverage().with_parameter_array_nullable(
+            "UGRD",
+            CovJsonParameter::new("UGRD"),
+            vec![Some(0.0); 6],
+            vec![2, 3],
+            vec!["y".to_string(), "x".to_string()],
+        );
+        let grid = RasterGrid::from_coverage(&coverage).unwrap();
+        assert_eq!(
+            grid.encode_ascii_grid(),
+            Err(RasterExportError::TooManyBands(2))
+        );
+    }
+
+    #[test]
+    fn test_encode_bil() {
+        let coverage = area_coverage().with_parameter_array_nullable(
+            "UGRD",
+            CovJsonParameter::new("UGRD"),
+            vec![Some(10.0); 6],
+            vec![2, 3],
+            vec!["y".to_string(), "x".to_string()],
+        );
+        let grid = RasterGrid::from_coverage(&coverage).unwrap();
+
+        let bil = grid.encode_bil();
+        assert_eq!(bil.len(), 3 * 2 * 2 * 4);
+        let pixels: Vec<f32> = bil
+            .chunks(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        // Row 0 of TMP, row 0 of UGRD, row 1 of TMP, row 1 of UGRD
+        assert_eq!(
+            pixels,
+            vec![4.0, -9999.0, 6.0, 10.0, 10.0, 10.0, 1.0, 2.0, 3.0, 10.0, 10.0, 10.0]
+        );
+
+        let hdr = grid.bil_header();
+        assert!(hdr.contains("NBANDS 2\n"));
+        assert!(hdr.contains("TOTALROWBYTES 24\n"));
+        assert!(hdr.contains("ULXMAP -100\n"));
+        assert!(hdr.contains("ULYMAP 30.5\n"));
+    }
+
+    #[test]
+    fn test_from_collection_cube_levels() {
+        let coverage = |z: f64, value: f32| {
+            let domain = Domain::cube_grid(-100.0, -99.0, 3, 31.0, 30.0, 3, None, z);
+            grid_coverage(domain).with_parameter_array_nullable(
+                "TMP",
+                CovJsonParameter::new("TMP"),
+                vec![Some(value); 9],
+                vec![1, 3, 3, 1],
+                ["t", "y", "x", "z"].map(String::from).to_vec(),
+            )
+        };
+
+        let collection = CoverageCollection::new()
+            .with_coverage(coverage(850.0, 1.0))
+            .with_coverage(coverage(500.0, 2.0));
+        let grid = RasterGrid::from_collection(&collection).unwrap();
+        assert_eq!(grid.bands.len(), 2);
+        assert_eq!(grid.bands[0].name, "TMP_850");
+        assert_eq!(grid.bands[1].values, vec![2.0; 9]);
+        assert_eq!(grid.north, 31.25);
+    }
+
+    #[test]
+    fn test_irregular_axis_rejected() {
+        let domain = Domain::grid(vec![0.0, 1.0, 3.0], vec![0.0, 1.0], None, None);
+        let coverage = grid_coverage(domain);
+        assert_eq!(
+            RasterGrid::from_coverage(&coverage),
+            Err(RasterExportError::IrregularAxis("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_zip_archive_layout() {
+        let grid = RasterGrid::from_coverage(&area_coverage()).unwrap();
+        let zip = grid.encode_bil_zip("gfs_area").unwrap();
+
+        assert_eq!(&zip[..4], &[0x50, 0x4b, 0x03, 0x04]);
+        let eocd = &zip[zip.len() - 22..];
+        assert_eq!(&eocd[..4], &[0x50, 0x4b, 0x05, 0x06]);
+        assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 3);
+
+        // First entry is the .bil, stored uncompressed
+        let name_len = u16::from_le_bytes([zip[26], zip[27]]) as usize;
+        assert_eq!(&zip[30..30 + name_len], b"gfs_area.bil");
+        let size = u32::from_le_bytes(zip[22..26].try_into().unwrap());
+        assert_eq!(size as usize, grid.encode_bil().len());
+    }
+}
@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::conformance;
+use crate::errors::EdrErrorKind;
 use crate::types::Link;
 
 /// Landing page response for the EDR API root.
@@ -107,7 +108,7 @@ impl ConformanceClasses {
     }
 }
 
-/// Exception response for errors.
+/// Exception response for errors (an RFC 7807 problem detail).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExceptionResponse {
     /// Exception type identifier.
@@ -157,42 +158,27 @@ impl ExceptionResponse {
 
     /// Create a 404 Not Found exception.
     pub fn not_found(detail: impl Into<String>) -> Self {
-        Self::new(
-            "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0/not-found",
-            404,
-            detail,
-        )
-        .with_title("Not Found")
+        EdrErrorKind::NotFound.exception(detail)
     }
 
     /// Create a 400 Bad Request exception.
     pub fn bad_request(detail: impl Into<String>) -> Self {
-        Self::new(
-            "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0/invalid-parameter-value",
-            400,
-            detail,
-        )
-        .with_title("Bad Request")
+        EdrErrorKind::InvalidParameter.exception(detail)
     }
 
     /// Create a 413 Payload Too Large exception.
     pub fn payload_too_large(detail: impl Into<String>) -> Self {
-        Self::new(
-            "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0/response-too-large",
-            413,
-            detail,
-        )
-        .with_title("Payload Too Large")
+        EdrErrorKind::TooLarge.exception(detail)
+    }
+
+    /// Create a 429 Too Many Requests exception.
+    pub fn too_many_requests(detail: impl Into<String>) -> Self {
+        EdrErrorKind::TooManyRequests.exception(detail)
     }
 
     /// Create a 500 Internal Server Error exception.
     pub fn internal_error(detail: impl Into<String>) -> Self {
-        Self::new(
-            "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0/server-error",
-            500,
-            detail,
-        )
-        .with_title("Internal Server Error")
+        EdrErrorKind::ServerError.exception(detail)
     }
 }
 
@@ -302,6 +288,14 @@ mod tests {
         assert!(exc.type_.contains("response-too-large"));
     }
 
+    #[test]
+    fn test_exception_too_many_requests() {
+        let exc = ExceptionResponse::too_many_requests("Hourly budget exhausted");
+
+        assert_eq!(exc.status, Some(429));
+        assert_eq!(exc.title, Some("Too Many Requests".to_string()));
+    }
+
     #[test]
     fn test_exception_internal_error() {
         let exc = ExceptionResponse::internal_error("Database connection failed");
@@ -0,0 +1,177 @@
+//! Property tests for the EDR query parameter parsers.
+//!
+//! `coords`, `bbox`, `z`, `datetime` and `within` arrive as free text, and a
+//! WKT typo must come back as a 400, not a panic. The generators splice WKT
+//! keywords, parentheses, separators and numbers together so that most
+//! inputs get deep into the parsers before they fail. Every coordinate a
+//! parser accepts must be a finite, in-range number.
+//!
+//! The `edr_coords` target in the repository's `fuzz/` crate covers the same
+//! parsers with cargo-fuzz.
+
+use edr_protocol::{
+    AreaQuery, BboxQuery, DateTimeQuery, DistanceUnit, ParsedCoords, PositionQuery, RadiusQuery,
+    TrajectoryQuery, VerticalUnit,
+};
+use proptest::prelude::*;
+
+// ============================================================================
+// Generators
+// ============================================================================
+
+/// Numbers as they appear in queries, including the awkward ones.
+fn number_text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (-180.0f64..180.0).prop_map(|v| v.to_string()),
+        any::<f64>().prop_map(|v| v.to_string()),
+        Just("NaN".to_string()),
+        Just("-inf".to_string()),
+        Just("1e400".to_string()),
+        "[0-9eE+.-]{1,10}",
+    ]
+}
+
+/// One token of a WKT-like string.
+fn wkt_token() -> impl Strategy<Value = String> {
+    prop_oneof![
+        4 => number_text(),
+        2 => prop::sample::select(vec!["(", ")", "((", "))", ",", " ", "),("]).prop_map(str::to_string),
+        1 => prop::sample::select(vec![
+            "POINT", "MULTIPOINT", "POLYGON", "MULTIPOLYGON", "LINESTRING", "LINESTRINGZ",
+            "LINESTRINGM", "LINESTRINGZM", "MULTILINESTRING", "MULTILINESTRINGZM", "point",
+        ])
+        .prop_map(str::to_string),
+        1 => ".{0,3}",
+    ]
+}
+
+/// Strings that look like `coords` values.
+fn coords_text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        prop::collection::vec(wkt_token(), 0..24).prop_map(|tokens| tokens.concat()),
+        ".{0,40}",
+    ]
+}
+
+/// Strings that look like `z`, `datetime`, `bbox` or `within` values.
+fn list_text() -> impl Strategy<Value = String> {
+    let token = prop_oneof![
+        3 => number_text(),
+        2 => prop::sample::select(vec![",", "/", "..", "R", "r", " "]).prop_map(str::to_string),
+        1 => Just("2024-12-29T12:00:00Z".to_string()),
+        1 => ".{0,3}",
+    ];
+    prop_oneof![
+        prop::collection::vec(token, 0..12).prop_map(|tokens| tokens.concat()),
+        ".{0,40}",
+    ]
+}
+
+fn in_range(lon: f64, lat: f64) -> bool {
+    (-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat)
+}
+
+// ============================================================================
+// Position (POINT, MULTIPOINT)
+// ============================================================================
+
+proptest! {
+    #[test]
+    fn point_coords_never_panic(coords in coords_text()) {
+        match PositionQuery::parse_coords_multi(&coords) {
+            Ok(ParsedCoords::Single(lon, lat)) => prop_assert!(in_range(lon, lat)),
+            Ok(ParsedCoords::Multi(points)) => {
+                prop_assert!(points.iter().all(|&(lon, lat)| in_range(lon, lat)))
+            }
+            Err(_) => {}
+        }
+        if let Ok((lon, lat)) = PositionQuery::parse_coords(&coords) {
+            prop_assert!(in_range(lon, lat));
+        }
+    }
+
+    #[test]
+    fn point_round_trips(lon in -180.0f64..=180.0, lat in -90.0f64..=90.0) {
+        prop_assert_eq!(
+            PositionQuery::parse_coords(&format!("POINT({} {})", lon, lat)).unwrap(),
+            (lon, lat)
+        );
+        prop_assert_eq!(PositionQuery::parse_coords(&format!("{},{}", lon, lat)).unwrap(), (lon, lat));
+    }
+
+    #[test]
+    fn multipoint_round_trips(points in prop::collection::vec((-180.0f64..=180.0, -90.0f64..=90.0), 2..6)) {
+        let wkt = format!(
+            "MULTIPOINT({})",
+            points
+                .iter()
+                .map(|(lon, lat)| format!("({} {})", lon, lat))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        prop_assert_eq!(
+            PositionQuery::parse_coords_multi(&wkt).unwrap(),
+            ParsedCoords::Multi(points)
+        );
+    }
+}
+
+// ============================================================================
+// Area and trajectory (POLYGON, LINESTRING)
+// ============================================================================
+
+proptest! {
+    #[test]
+    fn polygon_coords_never_panic(coords in coords_text()) {
+        if let Ok(polygons) = AreaQuery::parse_polygon_multi(&coords) {
+            for polygon in polygons.polygons() {
+                for ring in std::iter::once(&polygon.exterior).chain(&polygon.holes) {
+                    prop_assert!(ring.iter().all(|&(lon, lat)| lon.is_finite() && lat.is_finite()));
+                }
+            }
+        }
+        let _ = AreaQuery::parse_polygon(&coords);
+    }
+
+    #[test]
+    fn linestring_coords_never_panic(coords in coords_text()) {
+        if let Ok(trajectory) = TrajectoryQuery::parse_coords(&coords) {
+            prop_assert!(!trajectory.waypoints.is_empty());
+        }
+    }
+}
+
+// ============================================================================
+// bbox, z, datetime, within
+// ============================================================================
+
+proptest! {
+    #[test]
+    fn list_params_never_panic(value in list_text()) {
+        if let Ok(bbox) = BboxQuery::parse(&value) {
+            prop_assert!(in_range(bbox.west, bbox.south) && in_range(bbox.east, bbox.north));
+            prop_assert!(bbox.south <= bbox.north);
+        }
+        if let Ok(levels) = PositionQuery::parse_z(&value) {
+            prop_assert!(!levels.is_empty());
+        }
+        if let Ok(radius) = RadiusQuery::parse_within(&value) {
+            prop_assert!(radius > 0.0);
+        }
+        let _ = DateTimeQuery::parse(&value);
+        let _ = DistanceUnit::parse(&value);
+        let _ = VerticalUnit::parse(&value);
+        let _ = PositionQuery::parse_parameter_names(&value);
+    }
+
+    #[test]
+    fn bbox_round_trips(
+        west in -180.0f64..=180.0,
+        east in -180.0f64..=180.0,
+        south in -90.0f64..=0.0,
+        north in 0.0f64..=90.0,
+    ) {
+        let bbox = BboxQuery::parse(&format!("{},{},{},{}", west, south, east, north)).unwrap();
+        prop_assert_eq!((bbox.west, bbox.south, bbox.east, bbox.north), (west, south, east, north));
+    }
+}
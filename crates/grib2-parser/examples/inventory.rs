@@ -0,0 +1,83 @@
+//! Print a `wgrib2 -s` style inventory of a GRIB2 file.
+//!
+//! ```text
+//! cargo run -p grib2-parser --example inventory -- gfs.t00z.pgrb2.0p25.f006 [table.csv ...]
+//! ```
+//!
+//! Parameter names come from the WMO/NCEP parameter tables given after the
+//! file (CSV or wgrib2 `gribtab`, e.g. `config/grib2_tables/*.csv`), on top
+//! of a few common ones; codes found in neither print as `P{d}_{c}_{n}`.
+
+use bytes::Bytes;
+use grib2_parser::{Grib2Reader, Grib2Tables, LevelDescription};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+fn tables(table_paths: &[String]) -> Result<Grib2Tables, String> {
+    let mut tables = Grib2Tables::new();
+    for (discipline, category, number, name) in [
+        (0, 0, 0, "TMP"),
+        (0, 1, 1, "RH"),
+        (0, 1, 8, "APCP"),
+        (0, 2, 2, "UGRD"),
+        (0, 2, 3, "VGRD"),
+        (0, 3, 1, "PRMSL"),
+        (0, 3, 5, "HGT"),
+        (0, 16, 196, "REFC"),
+    ] {
+        tables.add_parameter(discipline, category, number, name.to_string());
+    }
+
+    // Level wording as wgrib2 prints it
+    for (level_type, description) in [
+        (1, "surface"),
+        (101, "mean sea level"),
+        (200, "entire atmosphere"),
+    ] {
+        tables.add_level(
+            level_type,
+            LevelDescription::Static(description.to_string()),
+        );
+    }
+    for (level_type, template) in [
+        (100, "{value_mb} mb"),
+        (102, "{value} m above mean sea level"),
+        (103, "{value} m above ground"),
+    ] {
+        tables.add_level(level_type, LevelDescription::Template(template.to_string()));
+    }
+
+    for path in table_paths {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        tables
+            .load_parameter_table(&text)
+            .map_err(|e| format!("{}: {}", path, e))?;
+    }
+    Ok(tables)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((path, table_paths)) = args.split_first() else {
+        eprintln!("usage: inventory <file.grib2> [parameter table ...]");
+        return ExitCode::FAILURE;
+    };
+
+    let result = tables(table_paths).and_then(|tables| {
+        let data = std::fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        Grib2Reader::new(Bytes::from(data), Arc::new(tables))
+            .inventory()
+            .map_err(|e| format!("{}: {}", path, e))
+    });
+
+    match result {
+        Ok(index) => {
+            print!("{}", index);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
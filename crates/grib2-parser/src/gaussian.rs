@@ -0,0 +1,201 @@
+//! Latitudes of Gaussian grids (template 3.40).
+//!
+//! A Gaussian grid with N parallels between a pole and the equator has 2N
+//! latitudes: the arcsines of the roots of the Legendre polynomial P_2N.
+//! They are symmetric about the equator but not evenly spaced, so rows of a
+//! Gaussian grid must not be read as a regular lat/lon grid. Regional grids
+//! cover a contiguous run of the global latitudes, from La1 to La2.
+//!
+//! [`regrid_to_regular_latitudes`] resamples the rows onto evenly spaced
+//! latitudes between the same first and last parallel, which is how the rest
+//! of the pipeline reads a grid.
+
+use crate::sections::GridDefinition;
+
+/// Newton iterations stop once a root moves less than this.
+const ROOT_TOLERANCE: f64 = 1e-15;
+const MAX_ITERATIONS: usize = 100;
+
+/// The 2N Gaussian latitudes of a grid with `n` parallels between a pole and
+/// the equator, in degrees from north to south.
+pub fn gaussian_latitudes(n: usize) -> Vec<f64> {
+    let order = 2 * n;
+    let mut north = Vec::with_capacity(n);
+
+    for i in 1..=n {
+        // Initial guess close to the i-th root (Tricomi)
+        let mut x = (std::f64::consts::PI * (i as f64 - 0.25) / (order as f64 + 0.5)).cos();
+        for _ in 0..MAX_ITERATIONS {
+            let (p, p_prev) = legendre(order, x);
+            let derivative = order as f64 * (x * p - p_prev) / (x * x - 1.0);
+            let step = p / derivative;
+            x -= step;
+            if step.abs() < ROOT_TOLERANCE {
+                break;
+            }
+        }
+        north.push(x.asin().to_degrees());
+    }
+
+    let south: Vec<f64> = north.iter().rev().map(|lat| -lat).collect();
+    north.extend(south);
+    north
+}
+
+/// P_order(x) and P_(order-1)(x), by the three-term recurrence.
+fn legendre(order: usize, x: f64) -> (f64, f64) {
+    let (mut p, mut p_prev) = (1.0, 0.0);
+    for k in 1..=order {
+        let k = k as f64;
+        let next = ((2.0 * k - 1.0) * x * p - (k - 1.0) * p_prev) / k;
+        p_prev = p;
+        p = next;
+    }
+    (p, p_prev)
+}
+
+/// Latitudes of the rows of a template 3.40 grid, in scanning order.
+///
+/// None if the grid isn't Gaussian or its first and last latitudes don't
+/// bound `num_points_latitude` Gaussian latitudes.
+pub fn grid_latitudes(grid: &GridDefinition) -> Option<Vec<f64>> {
+    let n = grid.gaussian_parallels? as usize;
+    if n == 0 {
+        return None;
+    }
+    let all = gaussian_latitudes(n);
+
+    // La1 and La2 are rounded to the nearest latitude in the template
+    let nearest = |millidegrees: i32| {
+        let lat = millidegrees as f64 / 1_000.0;
+        (0..all.len()).min_by(|&a, &b| (all[a] - lat).abs().total_cmp(&(all[b] - lat).abs()))
+    };
+    let first = nearest(grid.first_latitude_millidegrees)?;
+    let last = nearest(grid.last_latitude_millidegrees)?;
+
+    let mut rows: Vec<f64> = all[first.min(last)..=first.max(last)].to_vec();
+    if first > last {
+        rows.reverse();
+    }
+    (rows.len() == grid.num_points_latitude as usize).then_some(rows)
+}
+
+/// Resample grid rows at `latitudes` (monotonic, one per row) onto as many
+/// evenly spaced latitudes between the first and last, by linear
+/// interpolation between the neighbouring rows.
+pub fn regrid_to_regular_latitudes(values: &[f32], width: usize, latitudes: &[f64]) -> Vec<f32> {
+    let rows = latitudes.len();
+    if rows < 2 || values.len() != width * rows {
+        return values.to_vec();
+    }
+
+    let (first, last) = (latitudes[0], latitudes[rows - 1]);
+    let mut out = Vec::with_capacity(values.len());
+    let mut upper = 0;
+    for row in 0..rows {
+        let target = first + (last - first) * row as f64 / (rows - 1) as f64;
+        // Latitudes and targets advance together, so the bracket only moves on
+        while upper + 2 < rows && (latitudes[upper + 1] - target) * (last - first) <= 0.0 {
+            upper += 1;
+        }
+
+        let (lat0, lat1) = (latitudes[upper], latitudes[upper + 1]);
+        let t = ((target - lat0) / (lat1 - lat0)).clamp(0.0, 1.0) as f32;
+        let row0 = &values[upper * width..(upper + 1) * width];
+        let row1 = &values[(upper + 1) * width..(upper + 2) * width];
+        out.extend(row0.iter().zip(row1).map(|(&a, &b)| {
+            if t == 0.0 {
+                a
+            } else if t == 1.0 {
+                b
+            } else {
+                a + (b - a) * t
+            }
+        }));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    fn gaussian_grid(n: u32, first: f64, last: f64, rows: u32) -> GridDefinition {
+        GridDefinition {
+            grid_shape: 6,
+            num_points_latitude: rows,
+            num_points_longitude: 4,
+            first_latitude_millidegrees: (first * 1_000.0) as i32,
+            first_longitude_millidegrees: 0,
+            last_latitude_millidegrees: (last * 1_000.0) as i32,
+            last_longitude_millidegrees: 270_000,
+            latitude_increment_millidegrees: 0,
+            longitude_increment_millidegrees: 90_000,
+            scanning_mode: 0,
+            template_number: 40,
+            template_data: Bytes::new(),
+            lambert_conformal: None,
+            gaussian_parallels: Some(n),
+        }
+    }
+
+    #[test]
+    fn test_gaussian_latitudes_small_n() {
+        // Roots of P2 are +-1/sqrt(3)
+        let lats = gaussian_latitudes(1);
+        let expected = (1.0f64 / 3.0f64.sqrt()).asin().to_degrees();
+        assert_eq!(lats.len(), 2);
+        assert!((lats[0] - expected).abs() < 1e-12);
+        assert!((lats[1] + expected).abs() < 1e-12);
+
+        // Roots of P4: 0.861136..., 0.339981...
+        let lats = gaussian_latitudes(2);
+        assert!((lats[0].to_radians().sin() - 0.861_136_311_594_053).abs() < 1e-12);
+        assert!((lats[1].to_radians().sin() - 0.339_981_043_584_856).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gaussian_latitudes_n80() {
+        // First latitudes of the ECMWF N80 grid
+        let lats = gaussian_latitudes(80);
+        assert_eq!(lats.len(), 160);
+        assert!((lats[0] - 89.141_519).abs() < 1e-5);
+        assert!((lats[1] - 88.029_429).abs() < 1e-5);
+        assert!((lats[79] - 0.560_745).abs() < 1e-5);
+        assert!(lats.windows(2).all(|w| w[0] > w[1]));
+        assert!((lats[0] + lats[159]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_grid_latitudes() {
+        let lats = gaussian_latitudes(80);
+        let global = gaussian_grid(80, lats[0], lats[159], 160);
+        assert_eq!(grid_latitudes(&global).unwrap(), lats);
+
+        // A regional grid scanning south to north
+        let regional = gaussian_grid(80, lats[100], lats[90], 11);
+        let rows = grid_latitudes(&regional).unwrap();
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[0], lats[100]);
+        assert_eq!(rows[10], lats[90]);
+
+        // Row count disagreeing with the bounds
+        assert!(grid_latitudes(&gaussian_grid(80, lats[0], lats[159], 100)).is_none());
+    }
+
+    #[test]
+    fn test_regrid_to_regular_latitudes() {
+        // Values equal to the latitude regrid to the regular latitudes
+        let latitudes = [60.0, 50.0, 20.0, 0.0];
+        let values: Vec<f32> = latitudes
+            .iter()
+            .flat_map(|&lat| [lat as f32, lat as f32])
+            .collect();
+        let regular = regrid_to_regular_latitudes(&values, 2, &latitudes);
+        assert_eq!(regular, vec![60.0, 60.0, 40.0, 40.0, 20.0, 20.0, 0.0, 0.0]);
+
+        // Mismatched sizes are left alone
+        assert_eq!(regrid_to_regular_latitudes(&values, 3, &latitudes), values);
+    }
+}
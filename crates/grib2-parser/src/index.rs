@@ -0,0 +1,350 @@
+//! NOAA `.idx` sidecar index files.
+//!
+//! NOMADS and the NOAA open data buckets publish a `.idx` file next to every
+//! GRIB2 file, with one line per message:
+//!
+//! ```text
+//! 1:0:d=2024011500:PRMSL:mean sea level:anl:
+//! 2:990253:d=2024011500:CLMR:1 hybrid level:anl:
+//! 595:412233840:d=2024011500:TMP:2 m above ground:6 hour fcst:
+//! ```
+//!
+//! The fields are the message number, its byte offset in the file, the
+//! reference time, the parameter, the level and the forecast description.
+//! A message's length is the distance to the next message's offset, so
+//! single parameters can be fetched with HTTP range requests instead of
+//! downloading the whole file.
+//!
+//! The same lines, as `wgrib2 -s` prints them, can be produced for any GRIB2
+//! file with [`Grib2Reader::inventory`](crate::Grib2Reader::inventory); the
+//! `Display` impls of [`Grib2Index`] and [`Grib2IndexEntry`] write them.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::fmt;
+
+use crate::sections::{Identification, ProductDefinition};
+use crate::{Grib2Error, Grib2Result};
+
+/// One message listed in a `.idx` file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grib2IndexEntry {
+    /// Message number (1-based)
+    pub message_number: u32,
+    /// Submessage number for messages holding several fields ("12.2")
+    pub submessage: Option<u32>,
+    /// Byte offset of the message in the GRIB2 file
+    pub offset: u64,
+    /// Length of the message in bytes; `None` for the last message
+    pub length: Option<u64>,
+    /// Reference time, from `d=YYYYMMDDHH`
+    pub reference_time: Option<DateTime<Utc>>,
+    /// Parameter short name (e.g. "TMP")
+    pub parameter: String,
+    /// Level description (e.g. "2 m above ground")
+    pub level: String,
+    /// Forecast description (e.g. "anl", "6 hour fcst", "0-6 hour acc fcst")
+    pub forecast: String,
+    /// Ensemble member, from `ENS=...` (e.g. "+1", "-1", "low-res ctl")
+    pub ensemble: Option<String>,
+}
+
+impl Grib2IndexEntry {
+    /// Value of an HTTP `Range` header fetching this message.
+    pub fn range_header(&self) -> String {
+        match self.length {
+            Some(length) => format!("bytes={}-{}", self.offset, self.offset + length - 1),
+            None => format!("bytes={}-", self.offset),
+        }
+    }
+
+    /// Entry for a message of a GRIB2 file, described the way `wgrib2 -s`
+    /// describes it.
+    pub(crate) fn from_header(
+        message_number: u32,
+        offset: usize,
+        length: usize,
+        identification: &Identification,
+        product: &ProductDefinition,
+    ) -> Self {
+        Self {
+            message_number,
+            submessage: None,
+            offset: offset as u64,
+            length: Some(length as u64),
+            reference_time: Some(identification.reference_time),
+            parameter: product.parameter_short_name.clone(),
+            level: product.level_description.clone(),
+            forecast: forecast_description(product),
+            ensemble: ensemble_description(product),
+        }
+    }
+
+    fn parse(line: &str) -> Grib2Result<Self> {
+        let invalid = |reason: &str| {
+            Grib2Error::InvalidFormat(format!("{} in index line '{}'", reason, line))
+        };
+
+        let fields: Vec<&str> = line.split(':').collect();
+        if fields.len() < 6 {
+            return Err(invalid("Expected at least 6 fields"));
+        }
+
+        let (message_number, submessage) = match fields[0].split_once('.') {
+            Some((number, sub)) => (
+                number,
+                Some(sub.parse().map_err(|_| invalid("Invalid submessage"))?),
+            ),
+            None => (fields[0], None),
+        };
+        let message_number = message_number
+            .parse()
+            .map_err(|_| invalid("Invalid message number"))?;
+        let offset = fields[1].parse().map_err(|_| invalid("Invalid offset"))?;
+
+        let reference_time = fields[2]
+            .strip_prefix("d=")
+            .and_then(|d| NaiveDateTime::parse_from_str(&format!("{}00", d), "%Y%m%d%H%M").ok())
+            .map(|t| DateTime::<Utc>::from_naive_utc_and_offset(t, Utc));
+
+        Ok(Self {
+            message_number,
+            submessage,
+            offset,
+            length: None,
+            reference_time,
+            parameter: fields[3].to_string(),
+            level: fields[4].to_string(),
+            forecast: fields[5].to_string(),
+            ensemble: fields
+                .get(6)
+                .and_then(|field| field.strip_prefix("ENS="))
+                .map(str::to_string),
+        })
+    }
+}
+
+impl fmt::Display for Grib2IndexEntry {
+    /// The entry's `.idx` line, without a line break.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message_number)?;
+        if let Some(submessage) = self.submessage {
+            write!(f, ".{}", submessage)?;
+        }
+        write!(f, ":{}:d=", self.offset)?;
+        if let Some(time) = self.reference_time {
+            write!(f, "{}", time.format("%Y%m%d%H"))?;
+        }
+        write!(f, ":{}:{}:{}:", self.parameter, self.level, self.forecast)?;
+        if let Some(ensemble) = &self.ensemble {
+            write!(f, "ENS={}", ensemble)?;
+        }
+        Ok(())
+    }
+}
+
+/// Forecast time of a product as `wgrib2 -s` writes it: "anl" for forecast
+/// time 0, "6 hour fcst" (or "15 min fcst", "2 day fcst", in the product's
+/// own time unit), and "0-6 hour acc fcst" for statistically processed
+/// products.
+pub fn forecast_description(product: &ProductDefinition) -> String {
+    if let Some(stat) = &product.statistical_processing {
+        let process = match stat.process {
+            0 => "ave",
+            1 => "acc",
+            2 => "max",
+            3 => "min",
+            4 => "last-first",
+            5 => "RMS",
+            6 => "StdDev",
+            7 => "covar",
+            8 => "first-last",
+            9 => "ratio",
+            _ => "stat",
+        };
+        return format!(
+            "{}-{} hour {} fcst",
+            stat.start_hour, stat.end_hour, process
+        );
+    }
+
+    let duration = product.forecast_duration;
+    if duration.is_zero() {
+        return "anl".to_string();
+    }
+    match product.forecast_time_unit {
+        0 => format!("{} min fcst", duration.num_minutes()),
+        2 => format!("{} day fcst", duration.num_days()),
+        13 => format!("{} sec fcst", duration.num_seconds()),
+        _ => format!("{} hour fcst", duration.num_hours()),
+    }
+}
+
+/// `ENS=` value of an ensemble member (templates 4.1 and 4.11), from the
+/// type of ensemble forecast (Table 4.6) and the perturbation number.
+fn ensemble_description(product: &ProductDefinition) -> Option<String> {
+    if !matches!(product.template_number, 1 | 11) {
+        return None;
+    }
+    let number = product.perturbation_number?;
+    match product.template_data.get(25)? {
+        0 => Some("hi-res ctl".to_string()),
+        1 => Some("low-res ctl".to_string()),
+        2 => Some(format!("-{}", number)),
+        3 => Some(format!("+{}", number)),
+        _ => None,
+    }
+}
+
+/// Parsed `.idx` index of a GRIB2 file.
+#[derive(Debug, Clone, Default)]
+pub struct Grib2Index {
+    entries: Vec<Grib2IndexEntry>,
+}
+
+impl Grib2Index {
+    /// Parse the text of a `.idx` file. Blank lines are ignored.
+    pub fn parse(text: &str) -> Grib2Result<Self> {
+        let mut entries = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(Grib2IndexEntry::parse)
+            .collect::<Grib2Result<Vec<_>>>()?;
+
+        // Submessages share their message's offset, so the length runs to
+        // the next entry with a different offset
+        for i in 0..entries.len() {
+            let offset = entries[i].offset;
+            entries[i].length = entries[i + 1..]
+                .iter()
+                .find(|next| next.offset != offset)
+                .map(|next| next.offset.saturating_sub(offset));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Index of entries whose lengths are already known, e.g. from a scan of
+    /// the file itself.
+    pub fn from_entries(entries: Vec<Grib2IndexEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// All entries, in file order.
+    pub fn entries(&self) -> &[Grib2IndexEntry] {
+        &self.entries
+    }
+
+    /// First entry for a parameter at a level (e.g. "TMP", "2 m above ground").
+    pub fn find(&self, parameter: &str, level: &str) -> Option<&Grib2IndexEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.parameter == parameter && e.level == level)
+    }
+
+    /// Entries of a parameter, at any level.
+    pub fn find_parameter<'a>(
+        &'a self,
+        parameter: &'a str,
+    ) -> impl Iterator<Item = &'a Grib2IndexEntry> + 'a {
+        self.entries
+            .iter()
+            .filter(move |e| e.parameter == parameter)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl fmt::Display for Grib2Index {
+    /// The `.idx` text of the index, one line per entry.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const GFS_IDX: &str = "\
+1:0:d=2024011500:PRMSL:mean sea level:anl:
+2:990253:d=2024011500:CLMR:1 hybrid level:anl:
+3:1190731:d=2024011500:UGRD:10 m above ground:6 hour fcst:
+3.2:1190731:d=2024011500:VGRD:10 m above ground:6 hour fcst:
+4:2413087:d=2024011500:TMP:2 m above ground:6 hour fcst:
+";
+
+    #[test]
+    fn test_parse_index() {
+        let index = Grib2Index::parse(GFS_IDX).unwrap();
+        assert_eq!(index.len(), 5);
+
+        let first = &index.entries()[0];
+        assert_eq!(first.message_number, 1);
+        assert_eq!(first.offset, 0);
+        assert_eq!(first.length, Some(990253));
+        assert_eq!(first.parameter, "PRMSL");
+        assert_eq!(first.level, "mean sea level");
+        assert_eq!(first.forecast, "anl");
+        assert_eq!(
+            first.reference_time,
+            Some(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap())
+        );
+        assert_eq!(first.range_header(), "bytes=0-990252");
+    }
+
+    #[test]
+    fn test_submessages_share_offset() {
+        let index = Grib2Index::parse(GFS_IDX).unwrap();
+
+        let u = index.find("UGRD", "10 m above ground").unwrap();
+        let v = index.find("VGRD", "10 m above ground").unwrap();
+        assert_eq!((u.message_number, u.submessage), (3, None));
+        assert_eq!((v.message_number, v.submessage), (3, Some(2)));
+        assert_eq!(u.offset, v.offset);
+        assert_eq!(u.length, Some(2413087 - 1190731));
+        assert_eq!(v.length, u.length);
+    }
+
+    #[test]
+    fn test_last_entry_runs_to_end_of_file() {
+        let index = Grib2Index::parse(GFS_IDX).unwrap();
+        let tmp = index.find("TMP", "2 m above ground").unwrap();
+
+        assert_eq!(tmp.length, None);
+        assert_eq!(tmp.range_header(), "bytes=2413087-");
+        assert_eq!(index.find_parameter("TMP").count(), 1);
+        assert!(index.find("TMP", "surface").is_none());
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let text = format!(
+            "{}5:3605000:d=2024011500:APCP:surface:0-6 hour acc fcst:ENS=+3\n",
+            GFS_IDX
+        );
+        let index = Grib2Index::parse(&text).unwrap();
+
+        let member = index.find("APCP", "surface").unwrap();
+        assert_eq!(member.ensemble.as_deref(), Some("+3"));
+        assert_eq!(index.entries()[0].ensemble, None);
+        assert_eq!(index.to_string(), text);
+    }
+
+    #[test]
+    fn test_invalid_index_line() {
+        assert!(Grib2Index::parse("1:0:d=2024011500:PRMSL").is_err());
+        assert!(Grib2Index::parse("x:0:d=2024011500:PRMSL:mean sea level:anl:").is_err());
+        assert!(Grib2Index::parse("\n\n").unwrap().is_empty());
+    }
+}
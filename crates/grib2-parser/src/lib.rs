@@ -42,12 +42,20 @@
 //! }
 //! ```
 
+pub mod gaussian;
+pub mod index;
+pub mod mosaic;
 pub mod sections;
 pub mod tables;
 pub mod unpacking;
+pub mod writer;
 
-pub use tables::{Grib2Tables, LevelDescription};
-pub use unpacking::unpack_simple;
+pub use gaussian::{gaussian_latitudes, regrid_to_regular_latitudes};
+pub use index::{Grib2Index, Grib2IndexEntry};
+pub use mosaic::{LatLonGrid, MosaicAssembler, MosaicGrid, MosaicKey, OverlapPolicy};
+pub use tables::{Grib2Tables, LevelDescription, ParameterInfo, TableLoadStats};
+pub use unpacking::{unpack_complex, unpack_simple};
+pub use writer::{encode_message, encode_values, pack_simple, SimplePacked};
 
 use bytes::Bytes;
 use std::sync::Arc;
@@ -127,10 +135,9 @@ pub struct Grib2Message {
 }
 
 impl Grib2Message {
-    /// Get the valid time (reference time + forecast hour)
+    /// Get the valid time (reference time + forecast time)
     pub fn valid_time(&self) -> chrono::DateTime<chrono::Utc> {
-        let offset = chrono::Duration::hours(self.product_definition.forecast_hour as i64);
-        self.identification.reference_time + offset
+        self.identification.reference_time + self.product_definition.forecast_duration
     }
 
     /// Get parameter short name
@@ -151,15 +158,73 @@ impl Grib2Message {
         )
     }
 
-    /// Unpack the grid data values using the external `grib` crate.
+    /// Template numbers of the grid, product and data representation
+    /// sections, e.g. `3.30/4.0/5.3`, for logging messages that are skipped.
+    pub fn templates(&self) -> String {
+        format!(
+            "3.{}/4.{}/5.{}",
+            self.grid_definition.template_number,
+            self.product_definition.template_number,
+            self.data_representation.template_number
+        )
+    }
+
+    /// Unpack the grid data values.
     ///
-    /// This method now uses the mature `grib` crate which supports:
+    /// Complex packing (Templates 5.2 and 5.3, used by HRRR) is decoded
+    /// natively from the parsed sections. Other templates go through the
+    /// external `grib` crate, which re-parses the message and supports:
     /// - Template 5.0: Simple packing
-    /// - Template 5.2: Complex packing
-    /// - Template 5.3: Complex packing with spatial differencing
     /// - Template 5.40/5.41: JPEG 2000 compression (if enabled)
     /// - Template 5.15: PNG compression (enabled by default)
+    ///
+    /// Missing values are NaN.
     pub fn unpack_data(&self) -> Grib2Result<Vec<f32>> {
+        match self.data_representation.template_number {
+            2 | 3 => self.unpack_complex(),
+            _ => self.unpack_with_grib(),
+        }
+    }
+
+    /// Unpack the grid data values, with the points the Section 6 bitmap
+    /// marks as missing set to `fill` (`f32::NAN` to keep them NaN).
+    ///
+    /// When a bitmap is present the result always has one value per grid
+    /// point, even if a decoder only returned the values of the present
+    /// points. Missing values flagged by the packing itself stay NaN.
+    pub fn unpack_data_with_missing(&self, fill: f32) -> Grib2Result<Vec<f32>> {
+        let values = self.unpack_data()?;
+        match &self.bitmap {
+            Some(bitmap) if bitmap.indicator == 0 => {
+                let (nj, ni) = self.grid_dims();
+                unpacking::mask_missing(values, &bitmap.data, ni as usize * nj as usize, fill)
+            }
+            _ => Ok(values),
+        }
+    }
+
+    fn unpack_complex(&self) -> Grib2Result<Vec<f32>> {
+        let bitmap = match &self.bitmap {
+            Some(bitmap) if bitmap.indicator == 0 => Some(bitmap.data.as_ref()),
+            Some(bitmap) => {
+                return Err(Grib2Error::UnpackingError(format!(
+                    "Bitmap indicator {} not supported",
+                    bitmap.indicator
+                )))
+            }
+            None => None,
+        };
+        let (nj, ni) = self.grid_dims();
+
+        unpacking::unpack_complex(
+            &self.data_representation,
+            &self.data_section.data,
+            bitmap,
+            ni as usize * nj as usize,
+        )
+    }
+
+    fn unpack_with_grib(&self) -> Grib2Result<Vec<f32>> {
         use std::io::Cursor;
 
         // Use the grib crate to parse and decode the message
@@ -189,6 +254,26 @@ impl Grib2Message {
     }
 }
 
+/// One message of a file, as listed by [`Grib2Reader::scan_inventory`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventoryEntry {
+    /// Byte offset of the message in the file
+    pub offset: usize,
+    /// Length of the message in bytes
+    pub length: usize,
+    /// Parameter short name (e.g. "TMP")
+    pub parameter: String,
+    /// Level description (e.g. "2 m above ground")
+    pub level: String,
+    /// Type of first fixed surface (Table 4.5)
+    pub level_type: u8,
+    pub level_value: u32,
+    pub forecast_hour: u32,
+    pub reference_time: chrono::DateTime<chrono::Utc>,
+    /// Grid of the message, to tell regional tiles of one field apart
+    pub grid_definition: sections::GridDefinition,
+}
+
 /// GRIB2 file reader that iterates over messages.
 pub struct Grib2Reader {
     data: Bytes,
@@ -225,6 +310,127 @@ impl Grib2Reader {
         self.current_offset < self.data.len()
     }
 
+    /// Move to the message listed in a `.idx` entry, so the next
+    /// [`next_message`](Self::next_message) reads it without scanning the
+    /// messages before it.
+    ///
+    /// The reader must hold the whole file; for data fetched with
+    /// [`Grib2IndexEntry::range_header`] the message starts at offset 0.
+    pub fn seek_to_entry(&mut self, entry: &Grib2IndexEntry) -> Grib2Result<()> {
+        let offset = usize::try_from(entry.offset).map_err(|_| Grib2Error::UnexpectedEnd)?;
+        if offset >= self.data.len() {
+            return Err(Grib2Error::UnexpectedEnd);
+        }
+        if !self.data[offset..].starts_with(b"GRIB") {
+            return Err(Grib2Error::ParseError {
+                offset,
+                reason: format!(
+                    "Index entry {} ({} {}) does not point at a GRIB2 message",
+                    entry.message_number, entry.parameter, entry.level
+                ),
+            });
+        }
+
+        self.current_offset = offset;
+        Ok(())
+    }
+
+    /// Move to the message starting at `offset`, e.g. one listed by
+    /// [`scan_inventory`](Self::scan_inventory).
+    pub fn seek_to_offset(&mut self, offset: usize) -> Grib2Result<()> {
+        if offset >= self.data.len() {
+            return Err(Grib2Error::UnexpectedEnd);
+        }
+        if !self.data[offset..].starts_with(b"GRIB") {
+            return Err(Grib2Error::ParseError {
+                offset,
+                reason: "No GRIB2 message starts here".to_string(),
+            });
+        }
+
+        self.current_offset = offset;
+        Ok(())
+    }
+
+    /// List every message of the file without decoding or copying its data.
+    ///
+    /// Only Sections 0, 1, 3 and 4 are parsed; each message is skipped using
+    /// its length from Section 0. Read the messages worth keeping with
+    /// [`seek_to_offset`](Self::seek_to_offset) and
+    /// [`next_message`](Self::next_message). The reading position is left
+    /// unchanged. Fails on the first malformed message, since the messages
+    /// after it can't be located.
+    pub fn scan_inventory(&self) -> Grib2Result<Vec<InventoryEntry>> {
+        self.scan(
+            |offset, length, identification, grid_definition, product_definition| InventoryEntry {
+                offset,
+                length,
+                parameter: product_definition.parameter_short_name,
+                level: product_definition.level_description,
+                level_type: product_definition.level_type,
+                level_value: product_definition.level_value,
+                forecast_hour: product_definition.forecast_hour,
+                reference_time: identification.reference_time,
+                grid_definition,
+            },
+        )
+    }
+
+    /// Inventory of the file as `wgrib2 -s` lists it, from the same scan
+    /// as [`scan_inventory`](Self::scan_inventory).
+    ///
+    /// The index prints as `.idx` text (`1:0:d=2024011500:TMP:2 m above
+    /// ground:anl:`), so it can stand in for a missing `.idx` file or be
+    /// diffed against one. Parameter and level names come from the reader's
+    /// tables, so they match wgrib2's only as far as the tables do.
+    pub fn inventory(&self) -> Grib2Result<Grib2Index> {
+        let mut message_number = 0;
+        let entries = self.scan(|offset, length, identification, _, product_definition| {
+            message_number += 1;
+            Grib2IndexEntry::from_header(
+                message_number,
+                offset,
+                length,
+                &identification,
+                &product_definition,
+            )
+        })?;
+        Ok(Grib2Index::from_entries(entries))
+    }
+
+    /// Parse the headers of every message, skipping from one to the next by
+    /// their lengths, and map each to an entry.
+    fn scan<T>(
+        &self,
+        mut entry: impl FnMut(
+            usize,
+            usize,
+            sections::Identification,
+            sections::GridDefinition,
+            sections::ProductDefinition,
+        ) -> T,
+    ) -> Grib2Result<Vec<T>> {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+
+        while offset < self.data.len() {
+            let (indicator, message_data) = self.message_at(offset)?;
+            let (identification, grid_definition, product_definition) =
+                self.parse_header(offset, &indicator, message_data)?;
+
+            entries.push(entry(
+                offset,
+                message_data.len(),
+                identification,
+                grid_definition,
+                product_definition,
+            ));
+            offset += message_data.len();
+        }
+
+        Ok(entries)
+    }
+
     /// Read and parse the next GRIB2 message.
     pub fn next_message(&mut self) -> Grib2Result<Option<Grib2Message>> {
         // Check if we're at the end
@@ -233,7 +439,48 @@ impl Grib2Reader {
         }
 
         let message_offset = self.current_offset;
-        let remaining = &self.data[self.current_offset..];
+        let (indicator, message_data) = self.message_at(message_offset)?;
+        let message_length = message_data.len();
+
+        let (identification, grid_definition, product_definition) =
+            self.parse_header(message_offset, &indicator, message_data)?;
+
+        let data_representation =
+            sections::parse_data_representation(message_data).map_err(|e| {
+                Grib2Error::ParseError {
+                    offset: message_offset + 16,
+                    reason: format!("Failed to parse data representation: {}", e),
+                }
+            })?;
+
+        let bitmap = sections::parse_bitmap(message_data).ok();
+
+        let data_section =
+            sections::parse_data_section(message_data).map_err(|e| Grib2Error::ParseError {
+                offset: message_offset + 16,
+                reason: format!("Failed to parse data section: {}", e),
+            })?;
+
+        let message = Grib2Message {
+            offset: message_offset,
+            indicator,
+            identification,
+            grid_definition,
+            product_definition,
+            data_representation,
+            bitmap,
+            data_section,
+            raw_data: Bytes::copy_from_slice(message_data),
+        };
+
+        self.current_offset += message_length;
+        Ok(Some(message))
+    }
+
+    /// Section 0 and the bytes of the message starting at `offset`, checked
+    /// to end with "7777".
+    fn message_at(&self, offset: usize) -> Grib2Result<(sections::Indicator, &[u8])> {
+        let remaining = &self.data[offset..];
 
         // Parse Section 0 (Indicator)
         if remaining.len() < 16 {
@@ -244,7 +491,7 @@ impl Grib2Reader {
 
         let indicator =
             sections::parse_indicator(remaining).map_err(|e| Grib2Error::ParseError {
-                offset: message_offset,
+                offset,
                 reason: format!("Failed to parse indicator: {}", e),
             })?;
 
@@ -255,69 +502,64 @@ impl Grib2Reader {
             ));
         }
 
-        if message_offset + message_length > self.data.len() {
+        if offset + message_length > self.data.len() {
             return Err(Grib2Error::UnexpectedEnd);
         }
 
-        let message_data = &self.data[message_offset..message_offset + message_length];
+        let message_data = &self.data[offset..offset + message_length];
 
+        // Verify end section
+        if &message_data[message_data.len() - 4..] != b"7777" {
+            return Err(Grib2Error::InvalidFormat(
+                "Message does not end with '7777'".to_string(),
+            ));
+        }
+
+        Ok((indicator, message_data))
+    }
+
+    /// Parse Sections 1, 3 and 4 of a message, naming its parameter.
+    fn parse_header(
+        &self,
+        offset: usize,
+        indicator: &sections::Indicator,
+        message_data: &[u8],
+    ) -> Grib2Result<(
+        sections::Identification,
+        sections::GridDefinition,
+        sections::ProductDefinition,
+    )> {
         // Parse all sections - use the full message data for the parsers
         let identification =
             sections::parse_identification(message_data).map_err(|e| Grib2Error::ParseError {
-                offset: message_offset + 16,
+                offset: offset + 16,
                 reason: format!("Failed to parse identification: {}", e),
             })?;
 
         let grid_definition =
             sections::parse_grid_definition(message_data).map_err(|e| Grib2Error::ParseError {
-                offset: message_offset + 16,
+                offset: offset + 16,
                 reason: format!("Failed to parse grid definition: {}", e),
             })?;
 
-        let product_definition =
+        let mut product_definition =
             sections::parse_product_definition(message_data, indicator.discipline, &self.tables)
                 .map_err(|e| Grib2Error::ParseError {
-                    offset: message_offset + 16,
+                    offset: offset + 16,
                     reason: format!("Failed to parse product definition: {}", e),
                 })?;
 
-        let data_representation =
-            sections::parse_data_representation(message_data).map_err(|e| {
-                Grib2Error::ParseError {
-                    offset: message_offset + 16,
-                    reason: format!("Failed to parse data representation: {}", e),
-                }
-            })?;
-
-        let bitmap = sections::parse_bitmap(message_data).ok();
-
-        let data_section =
-            sections::parse_data_section(message_data).map_err(|e| Grib2Error::ParseError {
-                offset: message_offset + 16,
-                reason: format!("Failed to parse data section: {}", e),
-            })?;
-
-        // Verify end section
-        if message_data.len() < 4 || &message_data[message_data.len() - 4..] != b"7777" {
-            return Err(Grib2Error::InvalidFormat(
-                "Message does not end with '7777'".to_string(),
-            ));
+        // Local parameters of the originating center take precedence
+        if let Some(name) = self.tables.get_local_parameter_name(
+            identification.center,
+            indicator.discipline,
+            product_definition.parameter_category,
+            product_definition.parameter_number,
+        ) {
+            product_definition.parameter_short_name = name.to_string();
         }
 
-        let message = Grib2Message {
-            offset: message_offset,
-            indicator,
-            identification,
-            grid_definition,
-            product_definition,
-            data_representation,
-            bitmap,
-            data_section,
-            raw_data: Bytes::copy_from_slice(message_data),
-        };
-
-        self.current_offset += message_length;
-        Ok(Some(message))
+        Ok((identification, grid_definition, product_definition))
     }
 
     /// Create an iterator over all messages in the file.
@@ -370,4 +612,27 @@ mod tests {
         assert_eq!(reader.position(), 0);
         assert!(reader.has_more());
     }
+
+    #[test]
+    fn test_seek_to_entry() {
+        let tables = create_test_tables();
+        let mut data = vec![0u8; 32];
+        data.extend_from_slice(b"GRIB\x00\x00\x00\x02");
+        let mut reader = Grib2Reader::new(Bytes::from(data), tables);
+
+        let index = Grib2Index::parse(
+            "1:0:d=2024011500:PRMSL:mean sea level:anl:\n\
+             2:32:d=2024011500:TMP:2 m above ground:anl:\n\
+             3:64:d=2024011500:UGRD:10 m above ground:anl:",
+        )
+        .unwrap();
+
+        reader.seek_to_entry(&index.entries()[1]).unwrap();
+        assert_eq!(reader.position(), 32);
+
+        // Offset 0 holds no message, offset 64 is past the end
+        assert!(reader.seek_to_entry(&index.entries()[0]).is_err());
+        assert!(reader.seek_to_entry(&index.entries()[2]).is_err());
+        assert_eq!(reader.position(), 32);
+    }
 }
@@ -0,0 +1,638 @@
+//! Mosaicking of tiled/regional GRIB2 messages into a single grid.
+//!
+//! Some MRMS feeds split one product into several regional messages (tiles)
+//! with the same parameter, level and valid time. [`MosaicAssembler`] collects
+//! those tiles and stitches them onto one regular lat/lon grid covering the
+//! union of their extents, at the finest resolution among them. Tiles may
+//! overlap and may use different sub-grids; each target cell takes the nearest
+//! source point of every tile covering it, and overlapping values are combined
+//! with an [`OverlapPolicy`].
+//!
+//! Only regular lat/lon grids (template 3.0) are supported.
+
+use std::collections::BTreeMap;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+
+use crate::sections::GridDefinition;
+use crate::{Grib2Error, Grib2Message, Grib2Result};
+
+/// Scanning mode flag: points scan east to west.
+const SCAN_EAST_TO_WEST: u8 = 0x80;
+/// Scanning mode flag: points scan south to north.
+const SCAN_SOUTH_TO_NORTH: u8 = 0x40;
+/// Scanning mode flag: adjacent points are consecutive in the j direction.
+const SCAN_J_CONSECUTIVE: u8 = 0x20;
+/// Scanning mode flag: adjacent rows scan in opposite directions.
+const SCAN_BOUSTROPHEDON: u8 = 0x10;
+
+/// Tolerance (degrees) when matching cells against tile extents.
+const EPSILON: f64 = 1e-6;
+
+/// Identifies the product a tile belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MosaicKey {
+    pub parameter: String,
+    pub level: String,
+    pub valid_time: DateTime<Utc>,
+}
+
+impl MosaicKey {
+    pub fn new(parameter: &str, level: &str, valid_time: DateTime<Utc>) -> Self {
+        Self {
+            parameter: parameter.to_string(),
+            level: level.to_string(),
+            valid_time,
+        }
+    }
+
+    /// Key for the product carried by a message.
+    pub fn from_message(message: &Grib2Message) -> Self {
+        Self::new(message.parameter(), message.level(), message.valid_time())
+    }
+}
+
+/// How values are combined where tiles overlap.
+///
+/// A valid value always wins over a missing (NaN) one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Largest value (the usual convention for radar composites)
+    #[default]
+    Max,
+    /// Value from the tile added first
+    First,
+    /// Value from the tile added last
+    Last,
+    /// Mean of the overlapping values
+    Mean,
+}
+
+/// Geometry of a regular lat/lon grid, normalized to north-to-south rows and
+/// west-to-east columns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatLonGrid {
+    pub width: usize,
+    pub height: usize,
+    /// Latitude of the first (northernmost) row, degrees
+    pub north: f64,
+    /// Longitude of the first (westernmost) column, degrees in [0, 360)
+    pub west: f64,
+    /// Row spacing, degrees
+    pub lat_step: f64,
+    /// Column spacing, degrees
+    pub lon_step: f64,
+}
+
+impl LatLonGrid {
+    /// Geometry of a template 3.0 grid definition.
+    ///
+    /// Steps are derived from the corner points rather than the increment
+    /// fields so they are exact at the section's millidegree precision.
+    pub fn from_definition(grid: &GridDefinition) -> Grib2Result<Self> {
+        if grid.template_number != 0 {
+            return Err(Grib2Error::InvalidGrid(format!(
+                "Grid template 3.{} is not supported for mosaic",
+                grid.template_number
+            )));
+        }
+        if grid.scanning_mode & (SCAN_J_CONSECUTIVE | SCAN_BOUSTROPHEDON) != 0 {
+            return Err(Grib2Error::InvalidGrid(format!(
+                "Unsupported scanning mode {:#04x} for mosaic",
+                grid.scanning_mode
+            )));
+        }
+
+        let width = grid.num_points_longitude as usize;
+        let height = grid.num_points_latitude as usize;
+        if width < 2 || height < 2 {
+            return Err(Grib2Error::InvalidGrid(format!(
+                "Mosaic tiles need at least 2x2 points, got {}x{}",
+                width, height
+            )));
+        }
+
+        let first_lat = grid.first_latitude_millidegrees as f64 / 1_000.0;
+        let last_lat = grid.last_latitude_millidegrees as f64 / 1_000.0;
+        let first_lon = grid.first_longitude_millidegrees as f64 / 1_000.0;
+        let last_lon = grid.last_longitude_millidegrees as f64 / 1_000.0;
+
+        let (west, east) = if grid.scanning_mode & SCAN_EAST_TO_WEST != 0 {
+            (last_lon, first_lon)
+        } else {
+            (first_lon, last_lon)
+        };
+        let lat_step = (first_lat - last_lat).abs() / (height - 1) as f64;
+        let lon_step = (east - west).rem_euclid(360.0) / (width - 1) as f64;
+
+        if lat_step <= 0.0 || lon_step <= 0.0 {
+            return Err(Grib2Error::InvalidGrid(
+                "Mosaic tiles must be regular lat/lon grids".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            north: first_lat.max(last_lat),
+            west: west.rem_euclid(360.0),
+            lat_step,
+            lon_step,
+        })
+    }
+
+    /// Latitude of the last (southernmost) row, degrees.
+    pub fn south(&self) -> f64 {
+        self.north - (self.height - 1) as f64 * self.lat_step
+    }
+
+    /// Longitude of the last (easternmost) column, degrees (may exceed 360).
+    pub fn east(&self) -> f64 {
+        self.west + (self.width - 1) as f64 * self.lon_step
+    }
+
+    /// Grid definition (scanning mode 0) describing this grid.
+    pub fn to_grid_definition(&self) -> GridDefinition {
+        let millidegrees = |degrees: f64| (degrees * 1_000.0).round() as i32;
+        GridDefinition {
+            grid_shape: 0,
+            num_points_latitude: self.height as u32,
+            num_points_longitude: self.width as u32,
+            first_latitude_millidegrees: millidegrees(self.north),
+            first_longitude_millidegrees: millidegrees(self.west),
+            last_latitude_millidegrees: millidegrees(self.south()),
+            last_longitude_millidegrees: millidegrees(self.east()),
+            latitude_increment_millidegrees: millidegrees(self.lat_step) as u32,
+            longitude_increment_millidegrees: millidegrees(self.lon_step) as u32,
+            scanning_mode: 0,
+            template_number: 0,
+            template_data: Bytes::new(),
+            lambert_conformal: None,
+            gaussian_parallels: None,
+        }
+    }
+
+    /// Nearest point index for a coordinate, if it lies within the grid.
+    fn nearest_index(&self, lat: f64, lon: f64) -> Option<usize> {
+        let tolerance = 0.5 + EPSILON;
+        let row = (self.north - lat) / self.lat_step;
+        let mut col = (lon - self.west).rem_euclid(360.0) / self.lon_step;
+        // Just west of the first column
+        if col > 360.0 / self.lon_step - tolerance {
+            col -= 360.0 / self.lon_step;
+        }
+        if row < -tolerance
+            || row > (self.height - 1) as f64 + tolerance
+            || col < -tolerance
+            || col > (self.width - 1) as f64 + tolerance
+        {
+            return None;
+        }
+        let row = (row.round().max(0.0) as usize).min(self.height - 1);
+        let col = (col.round().max(0.0) as usize).min(self.width - 1);
+        Some(row * self.width + col)
+    }
+}
+
+/// A stitched grid, rows north to south and columns west to east.
+#[derive(Debug, Clone)]
+pub struct MosaicGrid {
+    pub grid: LatLonGrid,
+    /// Row-major values, NaN where no tile has data
+    pub values: Vec<f32>,
+    /// Number of tiles stitched into this grid
+    pub tile_count: usize,
+}
+
+impl MosaicGrid {
+    pub fn width(&self) -> usize {
+        self.grid.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height
+    }
+
+    /// Grid definition of the mosaic (see [`LatLonGrid::to_grid_definition`]).
+    pub fn grid_definition(&self) -> GridDefinition {
+        self.grid.to_grid_definition()
+    }
+}
+
+/// One tile, with values reordered north-to-south/west-to-east.
+#[derive(Debug, Clone)]
+struct Tile {
+    grid: LatLonGrid,
+    values: Vec<f32>,
+}
+
+/// Collects tiles per product and stitches them into mosaics.
+///
+/// # Example
+///
+/// ```no_run
+/// # use grib2_parser::{Grib2Message, MosaicAssembler, OverlapPolicy};
+/// # fn example(messages: Vec<Grib2Message>) -> grib2_parser::Grib2Result<()> {
+/// let mut assembler = MosaicAssembler::new(OverlapPolicy::Max);
+/// for message in &messages {
+///     assembler.add_message(message)?;
+/// }
+/// for (key, mosaic) in assembler.finish() {
+///     println!("{} {}: {}x{}", key.parameter, key.level, mosaic.width(), mosaic.height());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MosaicAssembler {
+    policy: OverlapPolicy,
+    tiles: BTreeMap<MosaicKey, Vec<Tile>>,
+}
+
+impl MosaicAssembler {
+    pub fn new(policy: OverlapPolicy) -> Self {
+        Self {
+            policy,
+            tiles: BTreeMap::new(),
+        }
+    }
+
+    /// Add a tile of already unpacked values in the grid's scanning order.
+    ///
+    /// Returns `false` if the product already has a tile on an identical grid;
+    /// such duplicate messages are ignored and the first one is kept.
+    pub fn add(
+        &mut self,
+        key: MosaicKey,
+        grid_definition: &GridDefinition,
+        values: Vec<f32>,
+    ) -> Grib2Result<bool> {
+        let grid = LatLonGrid::from_definition(grid_definition)?;
+        if values.len() != grid.width * grid.height {
+            return Err(Grib2Error::InvalidGrid(format!(
+                "Tile has {} values for a {}x{} grid",
+                values.len(),
+                grid.width,
+                grid.height
+            )));
+        }
+
+        let tiles = self.tiles.entry(key).or_default();
+        if tiles.iter().any(|tile| tile.grid == grid) {
+            return Ok(false);
+        }
+
+        let values = normalize_scan_order(values, &grid, grid_definition.scanning_mode);
+        tiles.push(Tile { grid, values });
+        Ok(true)
+    }
+
+    /// Unpack a message and add it as a tile of its product.
+    pub fn add_message(&mut self, message: &Grib2Message) -> Grib2Result<bool> {
+        // Points outside a tile's coverage must not mask another tile's data
+        let values = message.unpack_data_with_missing(f32::NAN)?;
+        self.add(
+            MosaicKey::from_message(message),
+            &message.grid_definition,
+            values,
+        )
+    }
+
+    /// Number of products collected.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Stitch the tiles of every product.
+    pub fn finish(self) -> Vec<(MosaicKey, MosaicGrid)> {
+        let policy = self.policy;
+        self.tiles
+            .into_iter()
+            .map(|(key, tiles)| (key, stitch(&tiles, policy)))
+            .collect()
+    }
+}
+
+/// Reorder values from the grid's scanning order to north-to-south rows and
+/// west-to-east columns.
+fn normalize_scan_order(values: Vec<f32>, grid: &LatLonGrid, scanning_mode: u8) -> Vec<f32> {
+    let flip_rows = scanning_mode & SCAN_SOUTH_TO_NORTH != 0;
+    let flip_cols = scanning_mode & SCAN_EAST_TO_WEST != 0;
+    if !flip_rows && !flip_cols {
+        return values;
+    }
+
+    let (width, height) = (grid.width, grid.height);
+    let mut normalized = Vec::with_capacity(values.len());
+    for row in 0..height {
+        let src_row = if flip_rows { height - 1 - row } else { row };
+        for col in 0..width {
+            let src_col = if flip_cols { width - 1 - col } else { col };
+            normalized.push(values[src_row * width + src_col]);
+        }
+    }
+    normalized
+}
+
+/// Target grid covering every tile at the finest spacing among them.
+fn union_grid(tiles: &[Tile]) -> LatLonGrid {
+    let first = &tiles[0].grid;
+    let mut north = first.north;
+    let mut south = first.south();
+    let mut west = first.west;
+    let mut east = first.east();
+    let mut lat_step = first.lat_step;
+    let mut lon_step = first.lon_step;
+
+    for tile in &tiles[1..] {
+        let grid = &tile.grid;
+        north = north.max(grid.north);
+        south = south.min(grid.south());
+        west = west.min(grid.west);
+        east = east.max(grid.east());
+        lat_step = lat_step.min(grid.lat_step);
+        lon_step = lon_step.min(grid.lon_step);
+    }
+
+    LatLonGrid {
+        width: ((east - west) / lon_step + EPSILON).round() as usize + 1,
+        height: ((north - south) / lat_step + EPSILON).round() as usize + 1,
+        north,
+        west,
+        lat_step,
+        lon_step,
+    }
+}
+
+fn stitch(tiles: &[Tile], policy: OverlapPolicy) -> MosaicGrid {
+    let grid = union_grid(tiles);
+    let mut values = vec![f32::NAN; grid.width * grid.height];
+    let mut counts = vec![0u32; values.len()];
+
+    for tile in tiles {
+        // Only visit target rows/columns within the tile's extent
+        let first_row = ((grid.north - tile.grid.north) / grid.lat_step - 0.5 - EPSILON)
+            .ceil()
+            .max(0.0) as usize;
+        let last_row = (((grid.north - tile.grid.south()) / grid.lat_step + 0.5 + EPSILON).floor()
+            as usize)
+            .min(grid.height - 1);
+        let first_col = ((tile.grid.west - grid.west) / grid.lon_step - 0.5 - EPSILON)
+            .ceil()
+            .max(0.0) as usize;
+        let last_col = (((tile.grid.east() - grid.west) / grid.lon_step + 0.5 + EPSILON).floor()
+            as usize)
+            .min(grid.width - 1);
+
+        for row in first_row..=last_row {
+            let lat = grid.north - row as f64 * grid.lat_step;
+            for col in first_col..=last_col {
+                let lon = grid.west + col as f64 * grid.lon_step;
+                let Some(src) = tile.grid.nearest_index(lat, lon) else {
+                    continue;
+                };
+                let value = tile.values[src];
+                if value.is_nan() {
+                    continue;
+                }
+
+                let idx = row * grid.width + col;
+                let current = values[idx];
+                values[idx] = if current.is_nan() {
+                    value
+                } else {
+                    match policy {
+                        OverlapPolicy::Max => current.max(value),
+                        OverlapPolicy::First => current,
+                        OverlapPolicy::Last => value,
+                        OverlapPolicy::Mean => current + value,
+                    }
+                };
+                counts[idx] += 1;
+            }
+        }
+    }
+
+    if policy == OverlapPolicy::Mean {
+        for (value, count) in values.iter_mut().zip(&counts) {
+            if *count > 1 {
+                *value /= *count as f32;
+            }
+        }
+    }
+
+    MosaicGrid {
+        grid,
+        values,
+        tile_count: tiles.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Grid definition with corners in degrees, scanning north to south.
+    fn definition(
+        north: f64,
+        west: f64,
+        width: u32,
+        height: u32,
+        step: f64,
+        scanning_mode: u8,
+    ) -> GridDefinition {
+        let south = north - (height - 1) as f64 * step;
+        let east = west + (width - 1) as f64 * step;
+        let (first_lat, last_lat) = if scanning_mode & SCAN_SOUTH_TO_NORTH != 0 {
+            (south, north)
+        } else {
+            (north, south)
+        };
+        let (first_lon, last_lon) = if scanning_mode & SCAN_EAST_TO_WEST != 0 {
+            (east, west)
+        } else {
+            (west, east)
+        };
+        let md = |degrees: f64| (degrees * 1_000.0).round() as i32;
+        GridDefinition {
+            grid_shape: 6,
+            num_points_latitude: height,
+            num_points_longitude: width,
+            first_latitude_millidegrees: md(first_lat),
+            first_longitude_millidegrees: md(first_lon),
+            last_latitude_millidegrees: md(last_lat),
+            last_longitude_millidegrees: md(last_lon),
+            latitude_increment_millidegrees: md(step) as u32,
+            longitude_increment_millidegrees: md(step) as u32,
+            scanning_mode,
+            template_number: 0,
+            template_data: Bytes::new(),
+            lambert_conformal: None,
+            gaussian_parallels: None,
+        }
+    }
+
+    fn key() -> MosaicKey {
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        MosaicKey::new("REFL", "0.5 km above MSL", time)
+    }
+
+    fn assemble(policy: OverlapPolicy, tiles: Vec<(GridDefinition, Vec<f32>)>) -> MosaicGrid {
+        let mut assembler = MosaicAssembler::new(policy);
+        for (definition, values) in tiles {
+            assert!(assembler.add(key(), &definition, values).unwrap());
+        }
+        let mut mosaics = assembler.finish();
+        assert_eq!(mosaics.len(), 1);
+        mosaics.remove(0).1
+    }
+
+    #[test]
+    fn test_lat_lon_grid_from_mrms_definition() {
+        let definition = definition(54.995, 230.005, 7000, 3500, 0.01, 0);
+        let grid = LatLonGrid::from_definition(&definition).unwrap();
+        assert_eq!((grid.width, grid.height), (7000, 3500));
+        assert!((grid.lat_step - 0.01).abs() < 1e-9);
+        assert!((grid.lon_step - 0.01).abs() < 1e-9);
+        assert!((grid.south() - 20.005).abs() < 1e-6);
+        assert!((grid.east() - 299.995).abs() < 1e-6);
+
+        let roundtrip = grid.to_grid_definition();
+        assert_eq!(roundtrip.first_latitude_millidegrees, 54_995);
+        assert_eq!(roundtrip.last_longitude_millidegrees, 299_995);
+    }
+
+    #[test]
+    fn test_stitches_overlapping_tiles() {
+        // Two 3x2 tiles sharing the middle column
+        let west_tile = definition(40.0, 260.0, 3, 2, 1.0, 0);
+        let east_tile = definition(40.0, 262.0, 3, 2, 1.0, 0);
+        let mosaic = assemble(
+            OverlapPolicy::Max,
+            vec![
+                (west_tile, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]),
+                (east_tile, vec![10.0, 20.0, 30.0, 1.0, 1.0, 1.0]),
+            ],
+        );
+
+        assert_eq!((mosaic.width(), mosaic.height()), (5, 2));
+        assert_eq!(mosaic.tile_count, 2);
+        assert_eq!(
+            mosaic.values,
+            vec![1.0, 2.0, 10.0, 20.0, 30.0, 4.0, 5.0, 6.0, 1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_overlap_policies() {
+        let tiles = || {
+            vec![
+                (definition(40.0, 260.0, 2, 2, 1.0, 0), vec![4.0; 4]),
+                (definition(40.0, 261.0, 2, 2, 1.0, 0), vec![2.0; 4]),
+            ]
+        };
+        // Column 1 is covered by both tiles
+        let overlap = |policy| assemble(policy, tiles()).values[1];
+        assert_eq!(overlap(OverlapPolicy::Max), 4.0);
+        assert_eq!(overlap(OverlapPolicy::First), 4.0);
+        assert_eq!(overlap(OverlapPolicy::Last), 2.0);
+        assert_eq!(overlap(OverlapPolicy::Mean), 3.0);
+    }
+
+    #[test]
+    fn test_valid_value_beats_missing() {
+        let mosaic = assemble(
+            OverlapPolicy::First,
+            vec![
+                (definition(40.0, 260.0, 2, 2, 1.0, 0), vec![f32::NAN; 4]),
+                (definition(40.0, 261.0, 2, 2, 1.0, 0), vec![7.0; 4]),
+            ],
+        );
+        assert!(mosaic.values[0].is_nan());
+        assert_eq!(mosaic.values[1], 7.0);
+        assert_eq!(mosaic.values[2], 7.0);
+    }
+
+    #[test]
+    fn test_differing_resolution_and_gaps() {
+        // Coarse 2-degree tile to the west, fine 1-degree tile to the east
+        // with a gap between them
+        let mosaic = assemble(
+            OverlapPolicy::Max,
+            vec![
+                (
+                    definition(40.0, 260.0, 2, 2, 2.0, 0),
+                    vec![1.0, 2.0, 3.0, 4.0],
+                ),
+                (definition(40.0, 264.0, 2, 3, 1.0, 0), vec![5.0; 6]),
+            ],
+        );
+
+        assert!((mosaic.grid.lat_step - 1.0).abs() < 1e-9);
+        assert_eq!((mosaic.width(), mosaic.height()), (6, 3));
+        // Coarse tile cells are nearest-neighbour sampled
+        assert_eq!(mosaic.values[0], 1.0);
+        assert_eq!(mosaic.values[2], 2.0);
+        // 263E lies outside both tiles
+        assert!(mosaic.values[3].is_nan());
+        assert_eq!(mosaic.values[4], 5.0);
+        assert_eq!(mosaic.values[2 * 6 + 5], 5.0);
+    }
+
+    #[test]
+    fn test_normalizes_scanning_mode() {
+        // South-to-north, east-to-west tile
+        let scanning_mode = SCAN_SOUTH_TO_NORTH | SCAN_EAST_TO_WEST;
+        let mosaic = assemble(
+            OverlapPolicy::Max,
+            vec![(
+                definition(40.0, 260.0, 2, 2, 1.0, scanning_mode),
+                vec![4.0, 3.0, 2.0, 1.0],
+            )],
+        );
+        assert_eq!(mosaic.values, vec![1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_duplicate_grid_is_ignored() {
+        let mut assembler = MosaicAssembler::default();
+        let definition = definition(40.0, 260.0, 2, 2, 1.0, 0);
+        assert!(assembler.add(key(), &definition, vec![1.0; 4]).unwrap());
+        assert!(!assembler.add(key(), &definition, vec![9.0; 4]).unwrap());
+
+        let (_, mosaic) = assembler.finish().remove(0);
+        assert_eq!(mosaic.tile_count, 1);
+        assert_eq!(mosaic.values, vec![1.0; 4]);
+    }
+
+    #[test]
+    fn test_rejects_invalid_tiles() {
+        let mut assembler = MosaicAssembler::default();
+        let definition = definition(40.0, 260.0, 2, 2, 1.0, 0);
+        assert!(assembler.add(key(), &definition, vec![1.0; 3]).is_err());
+
+        let column_major = GridDefinition {
+            scanning_mode: SCAN_J_CONSECUTIVE,
+            ..definition.clone()
+        };
+        assert!(assembler.add(key(), &column_major, vec![1.0; 4]).is_err());
+
+        // Non-template-0 grids have no coordinates
+        let unknown = GridDefinition {
+            template_number: 30,
+            first_latitude_millidegrees: 0,
+            first_longitude_millidegrees: 0,
+            last_latitude_millidegrees: 0,
+            last_longitude_millidegrees: 0,
+            ..definition
+        };
+        assert!(assembler.add(key(), &unknown, vec![1.0; 4]).is_err());
+        assert!(assembler.is_empty());
+    }
+}
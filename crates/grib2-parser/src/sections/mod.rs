@@ -33,6 +33,16 @@ pub fn decode_grib2_signed(bytes: &[u8]) -> i32 {
     }
 }
 
+/// Decode a 2-octet GRIB2 sign-magnitude integer, such as a scale factor.
+fn decode_grib2_signed_i16(high: u8, low: u8) -> i16 {
+    let magnitude = i16::from_be_bytes([high & 0x7F, low]);
+    if high & 0x80 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
 /// Section 0: Indicator Section (16 bytes)
 #[derive(Debug, Clone)]
 pub struct Indicator {
@@ -57,7 +67,7 @@ pub struct Identification {
 }
 
 /// Section 3: Grid Definition Section
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GridDefinition {
     pub grid_shape: u8,
     pub num_points_latitude: u32,
@@ -69,6 +79,61 @@ pub struct GridDefinition {
     pub latitude_increment_millidegrees: u32,
     pub longitude_increment_millidegrees: u32,
     pub scanning_mode: u8,
+    /// Grid definition template number (Table 3.1)
+    pub template_number: u16,
+    /// Raw template octets (from octet 15 to the end of the section), kept so
+    /// grids in unsupported templates can still be identified
+    pub template_data: Bytes,
+    /// Projection parameters of a template 3.30 (Lambert conformal) grid
+    pub lambert_conformal: Option<LambertConformalParams>,
+    /// N - number of parallels between a pole and the equator of a
+    /// template 3.40 (Gaussian) grid
+    pub gaussian_parallels: Option<u32>,
+}
+
+impl GridDefinition {
+    /// Whether the template is decoded into the fields above.
+    ///
+    /// Templates 3.0 (regular lat/lon), 3.30 (Lambert conformal) and 3.40
+    /// (Gaussian) are; other templates carry the point counts alone. Lambert
+    /// conformal grids only have a first point, their projection is in
+    /// `lambert_conformal`. Gaussian grids have no latitude increment, their
+    /// latitudes follow from `gaussian_parallels` (see [`crate::gaussian`]).
+    pub fn is_template_supported(&self) -> bool {
+        matches!(self.template_number, 0 | 30 | 40)
+    }
+}
+
+/// Lambert conformal projection of a template 3.30 grid.
+///
+/// Angles are in degrees and distances in meters, converted from the
+/// template's microdegrees and millimeters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LambertConformalParams {
+    /// La1 - latitude of the first grid point
+    pub first_latitude: f64,
+    /// Lo1 - longitude of the first grid point (0 to 360)
+    pub first_longitude: f64,
+    /// LaD - latitude where Dx and Dy are specified
+    pub lad: f64,
+    /// LoV - longitude of the meridian parallel to the y-axis (0 to 360)
+    pub lov: f64,
+    /// Dx - x-direction grid length
+    pub dx: f64,
+    /// Dy - y-direction grid length
+    pub dy: f64,
+    /// Projection centre flag (Table 3.5, bit 1 set for the south pole)
+    pub projection_centre: u8,
+    /// Latin1 - first latitude from the pole at which the secant cone cuts
+    /// the sphere
+    pub latin1: f64,
+    /// Latin2 - second latitude from the pole at which the secant cone cuts
+    /// the sphere
+    pub latin2: f64,
+    /// Latitude of the southern pole of projection
+    pub south_pole_latitude: f64,
+    /// Longitude of the southern pole of projection
+    pub south_pole_longitude: f64,
 }
 
 /// Section 4: Product Definition Section
@@ -80,7 +145,153 @@ pub struct ProductDefinition {
     pub level_type: u8,
     pub level_value: u32,
     pub level_description: String,
+    /// Forecast time in whole hours
     pub forecast_hour: u32,
+    /// Indicator of unit of time range of the forecast time (Table 4.4):
+    /// 0 minute, 1 hour, 2 day, 13 second, ...
+    pub forecast_time_unit: u8,
+    /// Forecast time in its own unit, so sub-hourly products (15-minute
+    /// HRRR, MRMS) keep their minutes
+    pub forecast_duration: chrono::Duration,
+    /// Product definition template number (Table 4.0)
+    pub template_number: u16,
+    /// Raw template octets (from octet 10 to the end of the section)
+    pub template_data: Bytes,
+    /// Ensemble member, "c00" for a control forecast or "p01", "n01", ...
+    /// for perturbed ones (templates 4.1 and 4.11)
+    pub ensemble_member: Option<String>,
+    /// Perturbation number (templates 4.1 and 4.11)
+    pub perturbation_number: Option<u8>,
+    /// Number of forecasts in the ensemble (templates 4.1 and 4.11)
+    pub total_members: Option<u8>,
+    /// Time interval the values are accumulated, averaged, ... over
+    /// (templates 4.8 and 4.11)
+    pub statistical_processing: Option<StatisticalProcessing>,
+}
+
+impl ProductDefinition {
+    /// Whether the template is decoded into the fields above.
+    ///
+    /// Templates 4.0 to 4.15 share the layout read here: parameter,
+    /// forecast time and first fixed surface.
+    pub fn is_template_supported(&self) -> bool {
+        self.template_number <= 15
+    }
+
+    /// Whether the product is one member of an ensemble forecast.
+    pub fn is_ensemble(&self) -> bool {
+        self.ensemble_member.is_some()
+    }
+}
+
+/// Statistical processing of a product over a time interval (templates 4.8
+/// and 4.11), e.g. a 0-6 hour precipitation accumulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatisticalProcessing {
+    /// Statistical process (Table 4.10): 0 average, 1 accumulation,
+    /// 2 maximum, 3 minimum, ...
+    pub process: u8,
+    /// Forecast hour the interval starts at
+    pub start_hour: u32,
+    /// Forecast hour the interval ends at
+    pub end_hour: u32,
+    /// End of the overall time interval
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl StatisticalProcessing {
+    /// Length of the interval in hours.
+    pub fn period_hours(&self) -> u32 {
+        self.end_hour.saturating_sub(self.start_hour)
+    }
+
+    /// Name of the statistical process (Table 4.10).
+    pub fn process_name(&self) -> &'static str {
+        match self.process {
+            0 => "average",
+            1 => "accumulation",
+            2 => "maximum",
+            3 => "minimum",
+            4 => "difference",
+            5 => "root mean square",
+            6 => "standard deviation",
+            7 => "covariance",
+            8 => "reverse difference",
+            9 => "ratio",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Convert a time value in a unit of Table 4.4 to a duration, if the unit
+/// is one of the fixed-length ones (not months, years, ...).
+fn time_to_duration(unit: u8, value: u32) -> Option<chrono::Duration> {
+    let value = value as i64;
+    match unit {
+        0 => Some(chrono::Duration::minutes(value)),
+        1 => Some(chrono::Duration::hours(value)),
+        2 => Some(chrono::Duration::days(value)),
+        10 => Some(chrono::Duration::hours(value * 3)),
+        11 => Some(chrono::Duration::hours(value * 6)),
+        12 => Some(chrono::Duration::hours(value * 12)),
+        13 => Some(chrono::Duration::seconds(value)),
+        _ => None,
+    }
+}
+
+/// Convert a time value in a unit of Table 4.4 to whole hours.
+fn time_to_hours(unit: u8, value: u32) -> Option<u32> {
+    u32::try_from(time_to_duration(unit, value)?.num_hours()).ok()
+}
+
+/// Parse the statistical processing block of template 4.8, or of 4.11
+/// after its ensemble octets. `block` starts at the end of the overall
+/// time interval:
+/// Bytes 0-6: End of overall time interval (year, month, day, hour, minute, second)
+/// Byte 7: Number of time range specifications
+/// Bytes 8-11: Number of missing data values
+/// Byte 12: Statistical process (Table 4.10)
+/// Byte 13: Type of time increment (Table 4.11)
+/// Byte 14: Unit of time range (Table 4.4)
+/// Bytes 15-18: Length of the time range
+///
+/// Only the first (outermost) time range is read.
+fn parse_statistical_processing(
+    block: &[u8],
+    forecast_unit: u8,
+    forecast_time: u32,
+) -> Option<StatisticalProcessing> {
+    if block.len() < 19 || block[7] == 0 {
+        return None;
+    }
+
+    let length = u32::from_be_bytes([block[15], block[16], block[17], block[18]]);
+    let start_hour = time_to_hours(forecast_unit, forecast_time)?;
+    let end_hour = start_hour.checked_add(time_to_hours(block[14], length)?)?;
+
+    let year = u16::from_be_bytes([block[0], block[1]]);
+    let end_time = NaiveDate::from_ymd_opt(year as i32, block[2] as u32, block[3] as u32)
+        .and_then(|date| date.and_hms_opt(block[4] as u32, block[5] as u32, block[6] as u32))
+        .map(|time| DateTime::<Utc>::from_naive_utc_and_offset(time, Utc));
+
+    Some(StatisticalProcessing {
+        process: block[12],
+        start_hour,
+        end_hour,
+        end_time,
+    })
+}
+
+/// Member identifier from the type of ensemble forecast (Table 4.6) and the
+/// perturbation number, following NCEP file naming (gec00, gep01).
+fn ensemble_member_id(ensemble_type: u8, perturbation_number: u8) -> String {
+    let prefix = match ensemble_type {
+        // Unperturbed high/low resolution control forecast
+        0 | 1 => 'c',
+        2 => 'n',
+        _ => 'p',
+    };
+    format!("{}{:02}", prefix, perturbation_number)
 }
 
 /// Section 5: Data Representation Section
@@ -93,6 +304,10 @@ pub struct DataRepresentation {
     pub binary_scale_factor: i16,
     pub decimal_scale_factor: i16,
     pub bits_per_value: u8,
+    /// Data representation template number (Table 5.0)
+    pub template_number: u16,
+    /// Raw template octets (from octet 12 to the end of the section)
+    pub template_data: Bytes,
 }
 
 /// Section 6: Bitmap Section
@@ -237,10 +452,13 @@ pub fn parse_grid_definition(data: &[u8]) -> Result<GridDefinition, Grib2Error>
 
     // Template data starts at byte 14
     let gd = &section_data[14..];
+    let template_data = template_bytes(section_data, 14);
 
-    if grid_template == 0 {
+    if grid_template == 0 || grid_template == 40 {
         // Template 0: Latitude/longitude (or equidistant cylindrical or Plate Carree)
         // GRIB2 Code Table 3.1 - Template 3.0
+        // Template 40 (Gaussian latitude/longitude) shares the layout, with
+        // N in place of Dj
         //
         // Byte 0: Shape of the Earth (Table 3.2)
         // Byte 1: Scale factor of radius of spherical Earth
@@ -260,12 +478,17 @@ pub fn parse_grid_definition(data: &[u8]) -> Result<GridDefinition, Grib2Error>
         // Bytes 45-48: Lo2 - longitude of last grid point (i32, microdegrees)
         // Bytes 49-52: Di - i direction increment (u32, microdegrees)
         // Bytes 53-56: Dj - j direction increment (u32, microdegrees)
+        //              (template 40: N - parallels between a pole and the equator)
         // Byte 57: Scanning mode (flags)
 
         if gd.len() < 58 {
             return Err(Grib2Error::InvalidSection {
                 section: 3,
-                reason: format!("Template 0 needs at least 58 bytes, got {}", gd.len()),
+                reason: format!(
+                    "Template {} needs at least 58 bytes, got {}",
+                    grid_template,
+                    gd.len()
+                ),
             });
         }
 
@@ -284,6 +507,27 @@ pub fn parse_grid_definition(data: &[u8]) -> Result<GridDefinition, Grib2Error>
         let dj = u32::from_be_bytes([gd[53], gd[54], gd[55], gd[56]]);
         let scanning_mode = gd[57];
 
+        if grid_template == 40 {
+            // Gaussian latitudes aren't evenly spaced, so there is no
+            // latitude increment
+            return Ok(GridDefinition {
+                grid_shape,
+                num_points_longitude: ni,
+                num_points_latitude: nj,
+                first_latitude_millidegrees: la1 / 1000,
+                first_longitude_millidegrees: lo1 / 1000,
+                last_latitude_millidegrees: la2 / 1000,
+                last_longitude_millidegrees: lo2 / 1000,
+                latitude_increment_millidegrees: 0,
+                longitude_increment_millidegrees: di / 1000,
+                scanning_mode,
+                template_number: grid_template,
+                template_data,
+                lambert_conformal: None,
+                gaussian_parallels: Some(dj),
+            });
+        }
+
         // Convert from microdegrees to millidegrees (divide by 1000)
         // Note: Our struct uses millidegrees for historical reasons
         Ok(GridDefinition {
@@ -297,6 +541,83 @@ pub fn parse_grid_definition(data: &[u8]) -> Result<GridDefinition, Grib2Error>
             latitude_increment_millidegrees: di / 1000,
             longitude_increment_millidegrees: dj / 1000,
             scanning_mode,
+            template_number: grid_template,
+            template_data,
+            lambert_conformal: None,
+            gaussian_parallels: None,
+        })
+    } else if grid_template == 30 {
+        // Template 30: Lambert conformal
+        // GRIB2 Code Table 3.1 - Template 3.30
+        //
+        // Bytes 0-15: Shape of the Earth, as in template 0
+        // Bytes 16-19: Nx - number of points along the x-axis (u32)
+        // Bytes 20-23: Ny - number of points along the y-axis (u32)
+        // Bytes 24-27: La1 - latitude of first grid point (i32, microdegrees)
+        // Bytes 28-31: Lo1 - longitude of first grid point (i32, microdegrees)
+        // Byte 32: Resolution and component flags
+        // Bytes 33-36: LaD - latitude where Dx and Dy are specified
+        // Bytes 37-40: LoV - orientation of the grid
+        // Bytes 41-44: Dx - x-direction grid length (u32, millimeters)
+        // Bytes 45-48: Dy - y-direction grid length (u32, millimeters)
+        // Byte 49: Projection centre flag
+        // Byte 50: Scanning mode (flags)
+        // Bytes 51-54: Latin1 - first secant latitude
+        // Bytes 55-58: Latin2 - second secant latitude
+        // Bytes 59-62: Latitude of the southern pole of projection
+        // Bytes 63-66: Longitude of the southern pole of projection
+
+        // Read within the section, a truncated template must not run into
+        // the next one
+        let gd = &template_data[..];
+        if gd.len() < 67 {
+            return Err(Grib2Error::InvalidSection {
+                section: 3,
+                reason: format!("Template 30 needs at least 67 bytes, got {}", gd.len()),
+            });
+        }
+
+        let grid_shape = gd[0];
+
+        let nx = u32::from_be_bytes([gd[16], gd[17], gd[18], gd[19]]);
+        let ny = u32::from_be_bytes([gd[20], gd[21], gd[22], gd[23]]);
+        let la1 = decode_grib2_signed(&gd[24..28]);
+        let lo1 = decode_grib2_signed(&gd[28..32]);
+        let dx = u32::from_be_bytes([gd[41], gd[42], gd[43], gd[44]]);
+        let dy = u32::from_be_bytes([gd[45], gd[46], gd[47], gd[48]]);
+        let scanning_mode = gd[50];
+
+        let degrees = |bytes: &[u8]| decode_grib2_signed(bytes) as f64 / 1_000_000.0;
+        let lambert_conformal = LambertConformalParams {
+            first_latitude: la1 as f64 / 1_000_000.0,
+            first_longitude: lo1 as f64 / 1_000_000.0,
+            lad: degrees(&gd[33..37]),
+            lov: degrees(&gd[37..41]),
+            dx: dx as f64 / 1_000.0,
+            dy: dy as f64 / 1_000.0,
+            projection_centre: gd[49],
+            latin1: degrees(&gd[51..55]),
+            latin2: degrees(&gd[55..59]),
+            south_pole_latitude: degrees(&gd[59..63]),
+            south_pole_longitude: degrees(&gd[63..67]),
+        };
+
+        // Projected grids have no last point or angular increments
+        Ok(GridDefinition {
+            grid_shape,
+            num_points_longitude: nx,
+            num_points_latitude: ny,
+            first_latitude_millidegrees: la1 / 1000,
+            first_longitude_millidegrees: lo1 / 1000,
+            last_latitude_millidegrees: 0,
+            last_longitude_millidegrees: 0,
+            latitude_increment_millidegrees: 0,
+            longitude_increment_millidegrees: 0,
+            scanning_mode,
+            template_number: grid_template,
+            template_data,
+            lambert_conformal: Some(lambert_conformal),
+            gaussian_parallels: None,
         })
     } else {
         // Fallback for other templates - just get dimensions
@@ -323,6 +644,10 @@ pub fn parse_grid_definition(data: &[u8]) -> Result<GridDefinition, Grib2Error>
             latitude_increment_millidegrees: 0,
             longitude_increment_millidegrees: 0,
             scanning_mode: 0,
+            template_number: grid_template,
+            template_data,
+            lambert_conformal: None,
+            gaussian_parallels: None,
         })
     }
 }
@@ -352,24 +677,27 @@ pub fn parse_product_definition(
     // Byte 10: Parameter number
     // ... (rest depends on template)
 
+    let template_number = u16::from_be_bytes([section_data[7], section_data[8]]);
     let parameter_category = section_data[9];
     let parameter_number = section_data[10];
 
     // For template 0 (analysis/forecast at horizontal level):
-    // Byte 18-21: Forecast time (4 bytes)
+    // Byte 17: Indicator of unit of time range (Table 4.4)
+    // Byte 18-21: Forecast time in that unit (4 bytes)
     // Byte 22: Type of first fixed surface
     // Byte 23: Scale factor of first fixed surface
     // Byte 24-27: Scaled value of first fixed surface (4 bytes)
-    let forecast_hour = if section_data.len() >= 22 {
-        u32::from_be_bytes([
-            section_data[18],
-            section_data[19],
-            section_data[20],
-            section_data[21],
-        ])
-    } else {
-        0
-    };
+    let forecast_time_unit = section_data[17];
+    let forecast_time = u32::from_be_bytes([
+        section_data[18],
+        section_data[19],
+        section_data[20],
+        section_data[21],
+    ]);
+    // Units without a fixed length are read as hours, as before
+    let forecast_duration = time_to_duration(forecast_time_unit, forecast_time)
+        .unwrap_or_else(|| chrono::Duration::hours(forecast_time as i64));
+    let forecast_hour = u32::try_from(forecast_duration.num_hours()).unwrap_or(forecast_time);
 
     let level_type = section_data.get(22).copied().unwrap_or(1);
     let scale_factor = section_data.get(23).copied().unwrap_or(0) as i8;
@@ -398,6 +726,27 @@ pub fn parse_product_definition(
         tables.get_parameter_name(discipline, parameter_category, parameter_number);
     let level_description = tables.get_level_description(level_type, level_value);
 
+    // Templates 4.1 and 4.11 (individual ensemble forecast) follow the 4.0
+    // layout with, at template octets 25-27 (section octets 35-37):
+    // Byte 25: Type of ensemble forecast (Table 4.6)
+    // Byte 26: Perturbation number
+    // Byte 27: Number of forecasts in ensemble
+    let template_data = template_bytes(section_data, 9);
+    let ensemble = match template_number {
+        1 | 11 => template_data.get(25..28),
+        _ => None,
+    };
+
+    // Template 4.8 (statistically processed values over a time interval)
+    // continues at template octet 25 with the interval; 4.11 has it after
+    // its ensemble octets. The forecast time is the start of the interval.
+    let statistical_processing = match template_number {
+        8 => template_data.get(25..),
+        11 => template_data.get(28..),
+        _ => None,
+    }
+    .and_then(|block| parse_statistical_processing(block, forecast_time_unit, forecast_time));
+
     Ok(ProductDefinition {
         parameter_category,
         parameter_number,
@@ -406,6 +755,14 @@ pub fn parse_product_definition(
         level_value,
         level_description,
         forecast_hour,
+        forecast_time_unit,
+        forecast_duration,
+        template_number,
+        ensemble_member: ensemble.map(|e| ensemble_member_id(e[0], e[1])),
+        perturbation_number: ensemble.map(|e| e[1]),
+        total_members: ensemble.map(|e| e[2]),
+        statistical_processing,
+        template_data,
     })
 }
 
@@ -459,13 +816,14 @@ pub fn parse_data_representation(data: &[u8]) -> Result<DataRepresentation, Grib
     } else {
         0.0
     };
+    // Scale factors are sign-magnitude like all GRIB2 signed integers
     let binary_scale_factor = if template_data.len() >= 6 {
-        i16::from_be_bytes([template_data[4], template_data[5]])
+        decode_grib2_signed_i16(template_data[4], template_data[5])
     } else {
         0
     };
     let decimal_scale_factor = if template_data.len() >= 8 {
-        i16::from_be_bytes([template_data[6], template_data[7]])
+        decode_grib2_signed_i16(template_data[6], template_data[7])
     } else {
         0
     };
@@ -480,6 +838,8 @@ pub fn parse_data_representation(data: &[u8]) -> Result<DataRepresentation, Grib
         binary_scale_factor,
         decimal_scale_factor,
         bits_per_value,
+        template_number,
+        template_data: template_bytes(section_data, 11),
     })
 }
 
@@ -559,6 +919,21 @@ pub fn parse_data_section(data: &[u8]) -> Result<DataSection, Grib2Error> {
 
 // ===== Helper Functions =====
 
+/// Copy a section's template octets, from `start` to the end of the section
+fn template_bytes(section_data: &[u8], start: usize) -> Bytes {
+    let section_length = u32::from_be_bytes([
+        section_data[0],
+        section_data[1],
+        section_data[2],
+        section_data[3],
+    ]) as usize;
+    let end = section_length.min(section_data.len());
+    if start >= end {
+        return Bytes::new();
+    }
+    Bytes::copy_from_slice(&section_data[start..end])
+}
+
 /// Find a section by number within a message
 fn find_section(data: &[u8], section_num: u8) -> Result<usize, Grib2Error> {
     let mut offset = 16; // After Section 0
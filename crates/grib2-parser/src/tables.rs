@@ -4,13 +4,47 @@
 //! GRIB2 numeric codes into human-readable parameter names and level descriptions.
 //!
 //! Tables are built from model configuration YAML files, allowing the mapping
-//! to be configured without code changes.
+//! to be configured without code changes. Parameter names can also be loaded
+//! at runtime from WMO/NCEP parameter tables, either CSV files with a header
+//! or wgrib2 `gribtab` flat files (see [`Grib2Tables::load_parameter_table`]),
+//! which also give each parameter a description and units.
 
 use std::collections::HashMap;
 
+use crate::{Grib2Error, Grib2Result};
+
 /// Lookup key for parameter: (discipline, category, number)
 pub type ParamKey = (u8, u8, u8);
 
+/// Lookup key for a center's local parameter: (center, discipline, category, number)
+pub type LocalParamKey = (u16, u8, u8, u8);
+
+/// Outcome of loading a parameter table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableLoadStats {
+    /// Entries added to the tables
+    pub loaded: usize,
+    /// Rows that couldn't be read and were skipped
+    pub skipped: usize,
+}
+
+/// Description and units of a parameter, from a parameter table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParameterInfo {
+    /// Long name (e.g., "Temperature")
+    pub description: String,
+    /// Units as written in the table (e.g., "K", "kg m-2")
+    pub units: String,
+}
+
+/// One readable row of a parameter table.
+struct TableRow {
+    center: u16,
+    key: ParamKey,
+    name: String,
+    info: ParameterInfo,
+}
+
 /// Level description - either static text or a template with {value} placeholder
 #[derive(Debug, Clone)]
 pub enum LevelDescription {
@@ -46,8 +80,13 @@ impl LevelDescription {
 pub struct Grib2Tables {
     /// (discipline, category, number) -> parameter short name (e.g., "TMP", "UGRD")
     parameters: HashMap<ParamKey, String>,
+    /// Local parameters of an originating center (e.g. NCEP's MXUPHL), only
+    /// used for messages from that center
+    local_parameters: HashMap<LocalParamKey, String>,
     /// level_type -> description pattern
     levels: HashMap<u8, LevelDescription>,
+    /// Parameter short name -> description and units from parameter tables
+    info: HashMap<String, ParameterInfo>,
 }
 
 impl Grib2Tables {
@@ -67,6 +106,71 @@ impl Grib2Tables {
         self.parameters.insert((discipline, category, number), name);
     }
 
+    /// Add a parameter mapping local to an originating center (Table C-11,
+    /// e.g. 7 for NCEP)
+    pub fn add_local_parameter(
+        &mut self,
+        center: u16,
+        discipline: u8,
+        category: u8,
+        number: u8,
+        name: String,
+    ) {
+        self.local_parameters
+            .insert((center, discipline, category, number), name);
+    }
+
+    /// Load parameter names from the text of a WMO/NCEP parameter table.
+    ///
+    /// Two formats are read:
+    /// - CSV with a header naming the `discipline`, `category` and `number`
+    ///   columns and a short name column (`shortName`, `abbrev` or
+    ///   `abbreviation`). An optional `center` column marks local entries;
+    ///   0, 255 or empty is a WMO entry.
+    /// - wgrib2 `gribtab` lines: `{disc, mtab_set, mtab_low, mtab_high,
+    ///   center, local_table, category, number, "NAME", "description",
+    ///   "units"},` with center 0 for WMO entries.
+    ///
+    /// Blank lines and `#` or `//` comments are ignored, unreadable rows are
+    /// skipped. Entries replace earlier ones with the same codes, so tables
+    /// loaded first are overridden by later ones.
+    pub fn load_parameter_table(&mut self, text: &str) -> Grib2Result<TableLoadStats> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"));
+        let Some(first) = lines.next() else {
+            return Ok(TableLoadStats::default());
+        };
+
+        let mut stats = TableLoadStats::default();
+        let mut add = |row: Option<TableRow>| match row {
+            Some(row) => {
+                let (discipline, category, number) = row.key;
+                if row.info != ParameterInfo::default() {
+                    self.info.insert(row.name.clone(), row.info);
+                }
+                if row.center == 0 || row.center == 255 {
+                    self.add_parameter(discipline, category, number, row.name);
+                } else {
+                    self.add_local_parameter(row.center, discipline, category, number, row.name);
+                }
+                stats.loaded += 1;
+            }
+            None => stats.skipped += 1,
+        };
+
+        if first.starts_with('{') {
+            add(parse_gribtab_line(first));
+            lines.map(parse_gribtab_line).for_each(add);
+        } else {
+            let columns = CsvColumns::from_header(first)?;
+            lines.map(|line| columns.parse(line)).for_each(add);
+        }
+
+        Ok(stats)
+    }
+
     /// Add a level description mapping
     ///
     /// # Arguments
@@ -76,6 +180,19 @@ impl Grib2Tables {
         self.levels.insert(level_type, description);
     }
 
+    /// Look up the short name of a center's local parameter.
+    pub fn get_local_parameter_name(
+        &self,
+        center: u16,
+        discipline: u8,
+        category: u8,
+        number: u8,
+    ) -> Option<&str> {
+        self.local_parameters
+            .get(&(center, discipline, category, number))
+            .map(String::as_str)
+    }
+
     /// Look up parameter short name by GRIB2 codes.
     ///
     /// Returns "P{discipline}_{category}_{number}" if not found.
@@ -86,6 +203,12 @@ impl Grib2Tables {
             .unwrap_or_else(|| format!("P{}_{}_{}", discipline, category, number))
     }
 
+    /// Look up the description and units of a parameter by short name, if
+    /// a loaded parameter table gave them.
+    pub fn get_parameter_info(&self, name: &str) -> Option<&ParameterInfo> {
+        self.info.get(name)
+    }
+
     /// Look up level description by type code and value.
     ///
     /// Returns "Level type {type} value {value}" if not found.
@@ -96,9 +219,9 @@ impl Grib2Tables {
         }
     }
 
-    /// Get the number of parameters in the table
+    /// Get the number of parameters in the table, local ones included
     pub fn parameter_count(&self) -> usize {
-        self.parameters.len()
+        self.parameters.len() + self.local_parameters.len()
     }
 
     /// Get the number of level types in the table
@@ -108,8 +231,128 @@ impl Grib2Tables {
 
     /// Check if the tables are empty
     pub fn is_empty(&self) -> bool {
-        self.parameters.is_empty() && self.levels.is_empty()
+        self.parameter_count() == 0 && self.levels.is_empty()
+    }
+}
+
+/// Positions of the columns of a CSV parameter table.
+struct CsvColumns {
+    discipline: usize,
+    category: usize,
+    number: usize,
+    short_name: usize,
+    description: Option<usize>,
+    units: Option<usize>,
+    center: Option<usize>,
+}
+
+impl CsvColumns {
+    fn from_header(header: &str) -> Grib2Result<Self> {
+        let names: Vec<String> = split_csv(header)
+            .iter()
+            .map(|name| name.to_lowercase().replace([' ', '_'], ""))
+            .collect();
+        let find = |aliases: &[&str]| names.iter().position(|n| aliases.contains(&n.as_str()));
+        let require = |aliases: &[&str]| {
+            find(aliases).ok_or_else(|| {
+                Grib2Error::InvalidFormat(format!(
+                    "Parameter table header has no '{}' column",
+                    aliases[0]
+                ))
+            })
+        };
+
+        Ok(Self {
+            discipline: require(&["discipline"])?,
+            category: require(&["category", "parametercategory"])?,
+            number: require(&["number", "parameternumber"])?,
+            short_name: require(&["shortname", "abbrev", "abbreviation"])?,
+            description: find(&["description", "longname"]),
+            units: find(&["units", "unit"]),
+            center: find(&["center", "centre"]),
+        })
+    }
+
+    fn parse(&self, line: &str) -> Option<TableRow> {
+        let fields = split_csv(line);
+        let field = |i: usize| fields.get(i).map(|f| f.trim());
+        let name = field(self.short_name).filter(|n| !n.is_empty())?;
+        let center = match self.center.and_then(field) {
+            None | Some("") => 0,
+            Some(center) => center.parse().ok()?,
+        };
+
+        let text = |column: Option<usize>| column.and_then(field).unwrap_or("").to_string();
+
+        Some(TableRow {
+            center,
+            key: (
+                field(self.discipline)?.parse().ok()?,
+                field(self.category)?.parse().ok()?,
+                field(self.number)?.parse().ok()?,
+            ),
+            name: name.to_string(),
+            info: ParameterInfo {
+                description: text(self.description),
+                units: text(self.units),
+            },
+        })
+    }
+}
+
+/// Split a CSV line, honoring double-quoted fields.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Parse a wgrib2 `gribtab` line, e.g.
+/// `{ 0, 1, 0, 255, 7, 1, 7, 199, "MXUPHL", "Hourly Maximum of Updraft Helicity", "m^2/s^2"},`
+fn parse_gribtab_line(line: &str) -> Option<TableRow> {
+    let body = line
+        .trim_end_matches(',')
+        .strip_prefix('{')?
+        .strip_suffix('}')?;
+    let fields = split_csv(body);
+    if fields.len() < 9 {
+        return None;
+    }
+    let number = |i: usize| fields[i].trim().parse::<u16>().ok();
+    let name = fields[8].trim();
+    if name.is_empty() {
+        return None;
     }
+
+    let text = |i: usize| fields.get(i).map_or("", |f| f.trim()).to_string();
+
+    Some(TableRow {
+        center: number(4)?,
+        key: (
+            u8::try_from(number(0)?).ok()?,
+            u8::try_from(number(6)?).ok()?,
+            u8::try_from(number(7)?).ok()?,
+        ),
+        name: name.to_string(),
+        info: ParameterInfo {
+            description: text(9),
+            units: text(10),
+        },
+    })
 }
 
 #[cfg(test)]
@@ -212,4 +455,85 @@ mod tests {
         assert_eq!(tables.get_parameter_name(0, 0, 0), "P0_0_0");
         assert_eq!(tables.get_level_description(1, 0), "Level type 1 value 0");
     }
+
+    #[test]
+    fn test_load_csv_parameter_table() {
+        let csv = "\
+# NCEP GRIB2 Table 4.2
+Discipline,Category,Number,shortName,\"Description\",Units,Center
+0,0,0,TMP,Temperature,K,
+0,1,8,APCP,\"Total Precipitation, accumulated\",kg m-2,0
+0,7,199,MXUPHL,Hourly Maximum of Updraft Helicity,m2 s-2,7
+0,x,1,BAD,Unreadable row,,
+";
+        let mut tables = Grib2Tables::new();
+        let stats = tables.load_parameter_table(csv).unwrap();
+
+        assert_eq!(
+            stats,
+            TableLoadStats {
+                loaded: 3,
+                skipped: 1
+            }
+        );
+        assert_eq!(tables.get_parameter_name(0, 0, 0), "TMP");
+        assert_eq!(tables.get_parameter_name(0, 1, 8), "APCP");
+        // Local entries only apply to their center
+        assert_eq!(tables.get_parameter_name(0, 7, 199), "P0_7_199");
+        assert_eq!(
+            tables.get_local_parameter_name(7, 0, 7, 199),
+            Some("MXUPHL")
+        );
+        assert_eq!(tables.get_local_parameter_name(98, 0, 7, 199), None);
+
+        let info = tables.get_parameter_info("APCP").unwrap();
+        assert_eq!(info.description, "Total Precipitation, accumulated");
+        assert_eq!(info.units, "kg m-2");
+        assert_eq!(tables.get_parameter_info("UGRD"), None);
+    }
+
+    #[test]
+    fn test_load_gribtab_parameter_table() {
+        let gribtab = r#"
+{ 0, 1, 0, 255, 0, 0, 2, 2, "UGRD", "U-Component of Wind", "m/s"},
+{ 0, 1, 0, 255, 7, 1, 7, 199, "MXUPHL", "Hourly Maximum of Updraft Helicity", "m^2/s^2"},
+{ 0, 1, 0, 255, 7, 1, 7},
+"#;
+        let mut tables = Grib2Tables::new();
+        let stats = tables.load_parameter_table(gribtab).unwrap();
+
+        assert_eq!(
+            stats,
+            TableLoadStats {
+                loaded: 2,
+                skipped: 1
+            }
+        );
+        assert_eq!(tables.get_parameter_name(0, 2, 2), "UGRD");
+        assert_eq!(
+            tables.get_local_parameter_name(7, 0, 7, 199),
+            Some("MXUPHL")
+        );
+        assert_eq!(tables.parameter_count(), 2);
+        assert_eq!(
+            tables.get_parameter_info("UGRD"),
+            Some(&ParameterInfo {
+                description: "U-Component of Wind".to_string(),
+                units: "m/s".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_parameter_table_errors() {
+        let mut tables = Grib2Tables::new();
+        assert!(tables
+            .load_parameter_table("Discipline,Category,Number\n0,0,0\n")
+            .is_err());
+        assert_eq!(
+            tables.load_parameter_table("\n# empty\n").unwrap(),
+            TableLoadStats::default()
+        );
+        assert!(tables.is_empty());
+    }
 }
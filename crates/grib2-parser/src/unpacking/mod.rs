@@ -2,9 +2,10 @@
 //!
 //! Implements various packing methods:
 //! - Simple packing (most common, used by GFS)
-//! - Complex packing
+//! - Complex packing, with or without spatial differencing (HRRR, NAM)
 //! - JPEG2000 compression
 
+use crate::sections::DataRepresentation;
 use crate::Grib2Error;
 
 /// Unpack simple packed GRIB2 data
@@ -66,6 +67,404 @@ pub fn unpack_simple(
     Ok(values)
 }
 
+/// Unpack complex packed GRIB2 data (Template 5.2), or complex packing with
+/// spatial differencing (Template 5.3).
+///
+/// Values are split into groups, each with its own reference and bit width;
+/// Template 5.3 packs first- or second-order differences of the values.
+/// Missing values, whether flagged by missing value management or cleared in
+/// the bitmap, are returned as NaN.
+///
+/// `num_grid_points` is the number of points covered by the bitmap and is
+/// only used when one is present.
+pub fn unpack_complex(
+    data_representation: &DataRepresentation,
+    packed_data: &[u8],
+    bitmap: Option<&[u8]>,
+    num_grid_points: usize,
+) -> Result<Vec<f32>, Grib2Error> {
+    let params = ComplexPacking::parse(data_representation)?;
+    let num_values = data_representation.num_data_points as usize;
+    let mut reader = BitReader::new(packed_data);
+
+    // Template 5.3 prefixes the groups with the first values of the field
+    // and the overall minimum of the differences
+    let order = params.spatial_differencing_order as usize;
+    let mut first_values = [0i64; 2];
+    let mut minimum = 0i64;
+    if order > 0 {
+        let octets = params.extra_descriptor_octets as usize;
+        for value in first_values.iter_mut().take(order) {
+            *value = reader.read_signed_octets(octets)?;
+        }
+        minimum = reader.read_signed_octets(octets)?;
+    }
+
+    let num_groups = params.num_groups as usize;
+    let references = reader.read_group_values(num_groups, params.bits_per_value)?;
+    let widths = reader.read_group_values(num_groups, params.group_width_bits)?;
+    let mut lengths = reader.read_group_values(num_groups, params.group_length_bits)?;
+    for length in lengths.iter_mut() {
+        *length = *length * params.group_length_increment as u32 + params.group_length_reference;
+    }
+    if let Some(last) = lengths.last_mut() {
+        *last = params.last_group_length;
+    }
+
+    let total: usize = lengths.iter().map(|&l| l as usize).sum();
+    if total < num_values {
+        return Err(Grib2Error::UnpackingError(format!(
+            "Complex packing groups hold {} values, expected {}",
+            total, num_values
+        )));
+    }
+
+    let mut decoder = ValueDecoder::new(&params, first_values);
+    let mut values = Vec::with_capacity(num_values);
+
+    for ((&reference, &width), &length) in references.iter().zip(&widths).zip(&lengths) {
+        let width = (width + params.group_width_reference as u32) as usize;
+        let length = (length as usize).min(num_values - values.len());
+
+        if width == 0 {
+            // Constant group: no values are stored, all equal the reference
+            let value = if params.missing(reference, params.bits_per_value as usize) {
+                None
+            } else {
+                Some(reference as i64 + minimum)
+            };
+            for _ in 0..length {
+                values.push(decoder.decode(value));
+            }
+        } else {
+            for _ in 0..length {
+                let packed = reader.read(width).map_err(|e| {
+                    Grib2Error::UnpackingError(format!("Failed to extract group value: {}", e))
+                })?;
+                let value = if params.missing(packed, width) {
+                    None
+                } else {
+                    Some(packed as i64 + reference as i64 + minimum)
+                };
+                values.push(decoder.decode(value));
+            }
+        }
+
+        if values.len() == num_values {
+            break;
+        }
+    }
+
+    match bitmap {
+        Some(bitmap) => apply_bitmap(values, bitmap, num_grid_points, f32::NAN),
+        None => Ok(values),
+    }
+}
+
+/// Set the grid points a Section 6 bitmap marks as missing to `fill`.
+///
+/// `values` may hold one value per grid point, or only the values of the
+/// points present in the bitmap (as stored in Section 7), which are then
+/// spread over the grid.
+pub fn mask_missing(
+    mut values: Vec<f32>,
+    bitmap: &[u8],
+    num_grid_points: usize,
+    fill: f32,
+) -> Result<Vec<f32>, Grib2Error> {
+    if bitmap.len() * 8 < num_grid_points {
+        return Err(Grib2Error::UnpackingError(format!(
+            "Bitmap covers {} points, grid has {}",
+            bitmap.len() * 8,
+            num_grid_points
+        )));
+    }
+    let present = |i: usize| (bitmap[i / 8] >> (7 - i % 8)) & 1 == 1;
+
+    if values.len() == num_grid_points {
+        for (i, value) in values.iter_mut().enumerate() {
+            if !present(i) {
+                *value = fill;
+            }
+        }
+        return Ok(values);
+    }
+
+    let num_present = (0..num_grid_points).filter(|&i| present(i)).count();
+    if values.len() != num_present {
+        return Err(Grib2Error::UnpackingError(format!(
+            "{} data values, but the bitmap marks {} of {} points present",
+            values.len(),
+            num_present,
+            num_grid_points
+        )));
+    }
+    apply_bitmap(values, bitmap, num_grid_points, fill)
+}
+
+/// Template 5.2/5.3 parameters.
+#[derive(Debug, Clone)]
+struct ComplexPacking {
+    reference_value: f32,
+    binary_scale_factor: i16,
+    decimal_scale_factor: i16,
+    bits_per_value: u8,
+    /// Code table 5.5: 0 = none, 1 = primary, 2 = primary and secondary
+    missing_value_management: u8,
+    num_groups: u32,
+    group_width_reference: u8,
+    group_width_bits: u8,
+    group_length_reference: u32,
+    group_length_increment: u8,
+    last_group_length: u32,
+    group_length_bits: u8,
+    /// Code table 5.6: 0 for Template 5.2, else 1 or 2
+    spatial_differencing_order: u8,
+    extra_descriptor_octets: u8,
+}
+
+impl ComplexPacking {
+    fn parse(drs: &DataRepresentation) -> Result<Self, Grib2Error> {
+        // Template octets are numbered from octet 12 of Section 5:
+        // [10] group splitting method, [11] missing value management,
+        // [12-19] missing value substitutes, [20-23] number of groups,
+        // [24] group width reference, [25] bits for group widths,
+        // [26-29] group length reference, [30] group length increment,
+        // [31-34] true length of last group, [35] bits for group lengths,
+        // and for Template 5.3 [36] order of spatial differencing,
+        // [37] octets per extra descriptor in Section 7.
+        let t = &drs.template_data;
+        let needed = match drs.template_number {
+            2 => 36,
+            3 => 38,
+            n => {
+                return Err(Grib2Error::UnsupportedTemplate {
+                    template_number: n,
+                    reason: "not a complex packing template".to_string(),
+                })
+            }
+        };
+        if t.len() < needed {
+            return Err(Grib2Error::InvalidSection {
+                section: 5,
+                reason: format!(
+                    "Template 5.{} needs {} octets, got {}",
+                    drs.template_number,
+                    needed,
+                    t.len()
+                ),
+            });
+        }
+
+        let unsupported = |reason: String| Grib2Error::UnsupportedTemplate {
+            template_number: drs.template_number,
+            reason,
+        };
+        if t[10] != 1 {
+            return Err(unsupported(format!("group splitting method {}", t[10])));
+        }
+        if t[11] > 2 {
+            return Err(unsupported(format!("missing value management {}", t[11])));
+        }
+
+        let (spatial_differencing_order, extra_descriptor_octets) = if drs.template_number == 3 {
+            (t[36], t[37])
+        } else {
+            (0, 0)
+        };
+        if drs.template_number == 3 {
+            if !(1..=2).contains(&spatial_differencing_order) {
+                return Err(unsupported(format!(
+                    "spatial differencing order {}",
+                    spatial_differencing_order
+                )));
+            }
+            if !(1..=4).contains(&extra_descriptor_octets) {
+                return Err(unsupported(format!(
+                    "{} octets per extra descriptor",
+                    extra_descriptor_octets
+                )));
+            }
+        }
+
+        let u32_at = |i: usize| u32::from_be_bytes([t[i], t[i + 1], t[i + 2], t[i + 3]]);
+
+        Ok(Self {
+            reference_value: drs.reference_value,
+            binary_scale_factor: drs.binary_scale_factor,
+            decimal_scale_factor: drs.decimal_scale_factor,
+            bits_per_value: drs.bits_per_value,
+            missing_value_management: t[11],
+            num_groups: u32_at(20),
+            group_width_reference: t[24],
+            group_width_bits: t[25],
+            group_length_reference: u32_at(26),
+            group_length_increment: t[30],
+            last_group_length: u32_at(31),
+            group_length_bits: t[35],
+            spatial_differencing_order,
+            extra_descriptor_octets,
+        })
+    }
+
+    /// Whether a packed value of `width` bits is a missing value: all ones
+    /// (primary) or all ones but the last bit (secondary).
+    fn missing(&self, packed: u32, width: usize) -> bool {
+        let primary = ((1u64 << width) - 1) as u32;
+        match self.missing_value_management {
+            1 => packed == primary,
+            2 => packed == primary || packed == primary.wrapping_sub(1),
+            _ => false,
+        }
+    }
+}
+
+/// Undoes spatial differencing and applies the scale factors.
+struct ValueDecoder {
+    order: usize,
+    first_values: [i64; 2],
+    /// Number of non-missing values decoded so far
+    count: usize,
+    previous: [i64; 2],
+    reference_value: f32,
+    binary_scale: f32,
+    decimal_scale: f32,
+}
+
+impl ValueDecoder {
+    fn new(params: &ComplexPacking, first_values: [i64; 2]) -> Self {
+        Self {
+            order: params.spatial_differencing_order as usize,
+            first_values,
+            count: 0,
+            previous: [0; 2],
+            reference_value: params.reference_value,
+            binary_scale: 2.0_f32.powi(params.binary_scale_factor as i32),
+            decimal_scale: 10.0_f32.powi(-(params.decimal_scale_factor as i32)),
+        }
+    }
+
+    /// Decode the next value. Missing values are skipped by the spatial
+    /// differencing.
+    fn decode(&mut self, value: Option<i64>) -> f32 {
+        let Some(value) = value else {
+            return f32::NAN;
+        };
+
+        let value = if self.count < self.order {
+            self.first_values[self.count]
+        } else {
+            match self.order {
+                1 => value + self.previous[1],
+                2 => value + 2 * self.previous[1] - self.previous[0],
+                _ => value,
+            }
+        };
+        self.previous = [self.previous[1], value];
+        self.count += 1;
+
+        (self.reference_value + (value as f32) * self.binary_scale) * self.decimal_scale
+    }
+}
+
+/// Spread the decoded values over the grid, NaN where the bitmap is clear.
+fn apply_bitmap(
+    values: Vec<f32>,
+    bitmap: &[u8],
+    num_grid_points: usize,
+    fill: f32,
+) -> Result<Vec<f32>, Grib2Error> {
+    if bitmap.len() * 8 < num_grid_points {
+        return Err(Grib2Error::UnpackingError(format!(
+            "Bitmap covers {} points, grid has {}",
+            bitmap.len() * 8,
+            num_grid_points
+        )));
+    }
+
+    let mut values = values.into_iter();
+    let mut grid = Vec::with_capacity(num_grid_points);
+    for i in 0..num_grid_points {
+        if (bitmap[i / 8] >> (7 - i % 8)) & 1 == 1 {
+            let value = values.next().ok_or_else(|| {
+                Grib2Error::UnpackingError("Bitmap has more points than data values".to_string())
+            })?;
+            grid.push(value);
+        } else {
+            grid.push(fill);
+        }
+    }
+
+    Ok(grid)
+}
+
+/// Sequential MSB-first reader over packed data.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// Read an unsigned value of up to 32 bits; zero bits read as 0.
+    fn read(&mut self, num_bits: usize) -> Result<u32, String> {
+        if num_bits == 0 {
+            return Ok(0);
+        }
+        if num_bits > 32 {
+            return Err(format!("Invalid number of bits: {}", num_bits));
+        }
+
+        let end = self.position + num_bits;
+        if end > self.data.len() * 8 {
+            return Err("Not enough data to extract bits".to_string());
+        }
+
+        let first = self.position / 8;
+        let last = (end - 1) / 8;
+        let mut value = 0u64;
+        for &byte in &self.data[first..=last] {
+            value = (value << 8) | byte as u64;
+        }
+        value >>= (last + 1) * 8 - end;
+        self.position = end;
+
+        Ok((value & ((1u64 << num_bits) - 1)) as u32)
+    }
+
+    /// Read a sign-magnitude integer of `octets` bytes from a byte boundary.
+    fn read_signed_octets(&mut self, octets: usize) -> Result<i64, Grib2Error> {
+        let raw = self.read(octets * 8).map_err(|e| {
+            Grib2Error::UnpackingError(format!("Failed to read extra descriptor: {}", e))
+        })? as i64;
+        let sign_bit = 1i64 << (octets * 8 - 1);
+        Ok(if raw & sign_bit != 0 {
+            -(raw & (sign_bit - 1))
+        } else {
+            raw
+        })
+    }
+
+    /// Read one `num_bits` value per group, then skip to the next octet.
+    fn read_group_values(
+        &mut self,
+        num_groups: usize,
+        num_bits: u8,
+    ) -> Result<Vec<u32>, Grib2Error> {
+        let values = (0..num_groups)
+            .map(|_| self.read(num_bits as usize))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                Grib2Error::UnpackingError(format!("Failed to read group descriptors: {}", e))
+            })?;
+        self.position = self.position.div_ceil(8) * 8;
+        Ok(values)
+    }
+}
+
 /// Extract bits from a byte array
 /// Returns the bits as a 32-bit unsigned integer
 fn extract_bits(data: &[u8], start_bit: usize, num_bits: usize) -> Result<u32, String> {
@@ -94,6 +493,72 @@ fn extract_bits(data: &[u8], start_bit: usize, num_bits: usize) -> Result<u32, S
 #[cfg(test)]
 mod tests {
     use super::*;
+    use bytes::Bytes;
+
+    /// MSB-first bit packer for building Section 7 payloads.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        bits: usize,
+    }
+
+    impl BitWriter {
+        fn write(&mut self, value: u32, num_bits: usize) -> &mut Self {
+            for i in (0..num_bits).rev() {
+                if self.bits.is_multiple_of(8) {
+                    self.bytes.push(0);
+                }
+                let bit = ((value >> i) & 1) as u8;
+                *self.bytes.last_mut().unwrap() |= bit << (7 - self.bits % 8);
+                self.bits += 1;
+            }
+            self
+        }
+
+        fn align(&mut self) -> &mut Self {
+            self.bits = self.bytes.len() * 8;
+            self
+        }
+    }
+
+    /// Template 5.2 (order 0) or 5.3 representation with 8-bit group
+    /// widths and lengths, no width/length references and 2-octet extra
+    /// descriptors.
+    fn complex_representation(
+        num_points: u32,
+        bits_per_value: u8,
+        missing_value_management: u8,
+        num_groups: u32,
+        last_group_length: u32,
+        order: u8,
+    ) -> DataRepresentation {
+        let mut t = vec![0u8; 10];
+        t[8] = bits_per_value;
+        t.push(1); // group splitting method
+        t.push(missing_value_management);
+        t.extend([0xFF; 8]); // missing value substitutes
+        t.extend(num_groups.to_be_bytes());
+        t.extend([0, 8]); // group width reference, bits
+        t.extend(0u32.to_be_bytes()); // group length reference
+        t.push(1); // group length increment
+        t.extend(last_group_length.to_be_bytes());
+        t.push(8); // bits for group lengths
+        if order > 0 {
+            t.extend([order, 2]);
+        }
+
+        DataRepresentation {
+            num_data_points: num_points,
+            packing_method: if order > 0 { 3 } else { 2 },
+            original_data_type: 0,
+            reference_value: 0.0,
+            binary_scale_factor: 0,
+            decimal_scale_factor: 0,
+            bits_per_value,
+            template_number: if order > 0 { 3 } else { 2 },
+            template_data: Bytes::from(t),
+        }
+    }
 
     #[test]
     fn test_extract_bits() {
@@ -134,4 +599,137 @@ mod tests {
         // Second value should be close to 200.0
         assert!((vals[1].unwrap() - 200.0).abs() < 0.1);
     }
+
+    #[test]
+    fn test_bit_reader() {
+        let data = [0b1011_0101, 0b1100_0011, 0x80, 0x05];
+        let mut reader = BitReader::new(&data);
+
+        assert_eq!(reader.read(0).unwrap(), 0);
+        assert_eq!(reader.read(3).unwrap(), 0b101);
+        // Spans the first byte boundary
+        assert_eq!(reader.read(7).unwrap(), 0b101_0111);
+        assert!(reader.read(33).is_err());
+
+        // Sign-magnitude extra descriptors start on an octet
+        let mut reader = BitReader::new(&data[2..]);
+        assert_eq!(reader.read_signed_octets(2).unwrap(), -5);
+        assert!(reader.read_signed_octets(1).is_err());
+    }
+
+    #[test]
+    fn test_complex_unpacking() {
+        // Group 1: reference 5, 3-bit values 0, 2, 7
+        // Group 2: constant 9 (width 0), 2 values
+        let mut drs = complex_representation(5, 8, 0, 2, 2, 0);
+        drs.reference_value = 10.0;
+        drs.decimal_scale_factor = 1;
+
+        let mut data = BitWriter::default();
+        data.write(5, 8).write(9, 8).align();
+        data.write(3, 8).write(0, 8).align();
+        data.write(3, 8).write(0, 8).align();
+        data.write(0, 3).write(2, 3).write(7, 3);
+
+        let values = unpack_complex(&drs, &data.bytes, None, 0).unwrap();
+        let expected = [1.5, 1.7, 2.2, 1.9, 1.9];
+        assert_eq!(values.len(), expected.len());
+        for (value, expected) in values.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-6, "{:?}", values);
+        }
+    }
+
+    #[test]
+    fn test_second_order_spatial_differencing_skips_missing() {
+        // Field 100, 103, 108, missing, 112, 121: second-order differences
+        // 2, -1, 5 are stored less their minimum (-1), 7 flags missing
+        let drs = complex_representation(6, 8, 1, 1, 6, 2);
+
+        let mut data = BitWriter::default();
+        data.write(100, 16).write(103, 16).write(0x8001, 16);
+        data.write(0, 8).align();
+        data.write(3, 8).align();
+        data.write(6, 8).align();
+        for packed in [0, 0, 3, 7, 0, 6] {
+            data.write(packed, 3);
+        }
+
+        let values = unpack_complex(&drs, &data.bytes, None, 0).unwrap();
+        assert_eq!(&values[..3], &[100.0, 103.0, 108.0]);
+        assert!(values[3].is_nan());
+        assert_eq!(&values[4..], &[112.0, 121.0]);
+    }
+
+    #[test]
+    fn test_first_order_spatial_differencing_with_bitmap() {
+        // Group 1: first value -5, then differences -1, 1 stored less the
+        // minimum (-2). Group 2: constant secondary missing value (14).
+        let mut drs = complex_representation(4, 4, 2, 2, 1, 1);
+        drs.binary_scale_factor = 1;
+
+        let mut data = BitWriter::default();
+        data.write(0x8005, 16).write(0x8002, 16);
+        data.write(0, 4).write(14, 4).align();
+        data.write(3, 8).write(0, 8).align();
+        data.write(3, 8).write(0, 8).align();
+        data.write(0, 3).write(1, 3).write(3, 3);
+
+        // Points 0, 2, 3 and 5 of 6 have data
+        let bitmap = [0b1011_0100];
+        let values = unpack_complex(&drs, &data.bytes, Some(&bitmap), 6).unwrap();
+
+        assert_eq!(values.len(), 6);
+        assert_eq!(values[0], -10.0);
+        assert_eq!(values[2], -12.0);
+        assert_eq!(values[3], -10.0);
+        for i in [1, 4, 5] {
+            assert!(values[i].is_nan(), "point {}: {:?}", i, values);
+        }
+    }
+
+    #[test]
+    fn test_complex_unpacking_errors() {
+        let mut drs = complex_representation(5, 8, 0, 1, 4, 0);
+        let mut data = BitWriter::default();
+        data.write(0, 8).align();
+        data.write(0, 8).align();
+        data.write(0, 8).align();
+
+        // Groups hold fewer values than the section declares
+        assert!(matches!(
+            unpack_complex(&drs, &data.bytes, None, 0),
+            Err(Grib2Error::UnpackingError(_))
+        ));
+
+        // Only general group splitting is defined by WMO
+        let mut template = drs.template_data.to_vec();
+        template[10] = 0;
+        drs.template_data = Bytes::from(template);
+        assert!(matches!(
+            unpack_complex(&drs, &data.bytes, None, 0),
+            Err(Grib2Error::UnsupportedTemplate {
+                template_number: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_mask_missing() {
+        let bitmap = [0b1011_0000];
+
+        // Values for the present points only are spread over the grid
+        let grid = mask_missing(vec![1.0, 2.0, 3.0], &bitmap, 5, -999.0).unwrap();
+        assert_eq!(grid, vec![1.0, -999.0, 2.0, 3.0, -999.0]);
+
+        // Full grids have the missing points overwritten
+        let grid = mask_missing(vec![1.0, 0.0, 2.0, 3.0, 0.0], &bitmap, 5, f32::NAN).unwrap();
+        assert_eq!(grid[..1], [1.0]);
+        assert!(grid[1].is_nan() && grid[4].is_nan());
+        assert_eq!(grid[2..4], [2.0, 3.0]);
+
+        // Neither one value per point nor per present point
+        assert!(mask_missing(vec![1.0, 2.0], &bitmap, 5, f32::NAN).is_err());
+        assert!(mask_missing(vec![1.0; 9], &bitmap, 9, f32::NAN).is_err());
+    }
 }
@@ -0,0 +1,576 @@
+//! GRIB2 message encoding.
+//!
+//! Writes a [`Grib2Message`] back out as a standalone GRIB2 message, so a
+//! multi-message file (e.g. an MRMS product bundle) can be split into one
+//! file per parameter:
+//!
+//! ```no_run
+//! use grib2_parser::{writer, Grib2Reader, Grib2Tables};
+//! use bytes::Bytes;
+//! use std::sync::Arc;
+//!
+//! let data = std::fs::read("mrms.grib2").unwrap();
+//! let mut reader = Grib2Reader::new(Bytes::from(data), Arc::new(Grib2Tables::new()));
+//! for (i, message) in reader.iter_messages().enumerate() {
+//!     let bytes = writer::encode_message(&message.unwrap()).unwrap();
+//!     std::fs::write(format!("message_{}.grib2", i), bytes).unwrap();
+//! }
+//! ```
+//!
+//! The grid (section 3) and product (section 4) templates are written from
+//! the raw octets kept by the parser; coordinate values of hybrid levels and
+//! the local use section are not kept and are dropped. Data is always written
+//! with simple packing (Template 5.0): simple packed messages are copied as
+//! is, others are unpacked and repacked at their decimal scale factor.
+
+use crate::{Grib2Error, Grib2Message, Grib2Result};
+
+/// Most bits per packed value; wider ranges are coarsened with the binary
+/// scale factor instead. 24 bits is the precision of an f32.
+const MAX_BITS_PER_VALUE: u32 = 24;
+
+/// Field values packed with simple packing (Template 5.0).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimplePacked {
+    /// Reference value R, the smallest value scaled by 10^D
+    pub reference_value: f32,
+    /// Binary scale factor E
+    pub binary_scale_factor: i16,
+    /// Decimal scale factor D
+    pub decimal_scale_factor: i16,
+    /// Bits per packed value, 0 for a constant field
+    pub bits_per_value: u8,
+    /// Number of packed (non-missing) values
+    pub num_values: u32,
+    /// Packed values
+    pub data: Vec<u8>,
+    /// One bit per grid point, cleared for missing values; `None` if no
+    /// value is missing
+    pub bitmap: Option<Vec<u8>>,
+}
+
+/// Pack values with simple packing: `Y = (R + X * 2^E) / 10^D`.
+///
+/// Values are kept to `decimal_scale_factor` decimal places, with as many
+/// bits as their range needs up to 24. NaN values are missing and are
+/// flagged in a bitmap.
+pub fn pack_simple(values: &[f32], decimal_scale_factor: i16) -> SimplePacked {
+    let decimal_scale = 10f64.powi(decimal_scale_factor as i32);
+    let scaled: Vec<f64> = values
+        .iter()
+        .filter(|v| !v.is_nan())
+        .map(|&v| v as f64 * decimal_scale)
+        .collect();
+
+    let bitmap = values.iter().any(|v| v.is_nan()).then(|| {
+        let mut bitmap = vec![0u8; values.len().div_ceil(8)];
+        for (i, value) in values.iter().enumerate() {
+            if !value.is_nan() {
+                bitmap[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        bitmap
+    });
+
+    let min = scaled.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = scaled.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mut packed = SimplePacked {
+        reference_value: if scaled.is_empty() { 0.0 } else { min as f32 },
+        binary_scale_factor: 0,
+        decimal_scale_factor,
+        bits_per_value: 0,
+        num_values: scaled.len() as u32,
+        data: Vec::new(),
+        bitmap,
+    };
+
+    // R is stored as an f32, pack relative to the stored value
+    let reference = packed.reference_value as f64;
+    let range = (max - reference).round();
+    if scaled.is_empty() || range <= 0.0 {
+        return packed;
+    }
+
+    // Bits needed for the range at E = 0, coarsened to fit the widest
+    // packing
+    let mut bits = (range + 1.0).log2().ceil() as u32;
+    let mut binary_scale = 0;
+    if bits > MAX_BITS_PER_VALUE {
+        binary_scale = (bits - MAX_BITS_PER_VALUE) as i16;
+        bits = MAX_BITS_PER_VALUE;
+    }
+    let step = 2f64.powi(binary_scale as i32);
+    let max_packed = (1u64 << bits) - 1;
+
+    let mut writer = BitWriter::default();
+    for value in scaled {
+        let x = ((value - reference) / step)
+            .round()
+            .clamp(0.0, max_packed as f64);
+        writer.write(x as u32, bits);
+    }
+
+    packed.binary_scale_factor = binary_scale;
+    packed.bits_per_value = bits as u8;
+    packed.data = writer.finish();
+    packed
+}
+
+/// Encode a message as a standalone GRIB2 message.
+///
+/// See the [module documentation](self) for what is kept.
+pub fn encode_message(message: &Grib2Message) -> Grib2Result<Vec<u8>> {
+    let drs = &message.data_representation;
+    let copyable_bitmap = match &message.bitmap {
+        None => true,
+        Some(bitmap) => bitmap.indicator == 0,
+    };
+
+    if drs.template_number == 0 && copyable_bitmap {
+        let packed = SimplePacked {
+            reference_value: drs.reference_value,
+            binary_scale_factor: drs.binary_scale_factor,
+            decimal_scale_factor: drs.decimal_scale_factor,
+            bits_per_value: drs.bits_per_value,
+            num_values: drs.num_data_points,
+            data: message.data_section.data.to_vec(),
+            bitmap: message.bitmap.as_ref().map(|b| b.data.to_vec()),
+        };
+        return write_message(message, &packed);
+    }
+
+    let values = message.unpack_data()?;
+    encode_values(message, &values, drs.decimal_scale_factor)
+}
+
+/// Encode a message with its data replaced by `values`, packed with simple
+/// packing at `decimal_scale_factor`. NaN values are missing.
+///
+/// `values` must cover every grid point, in the message's scanning order.
+pub fn encode_values(
+    message: &Grib2Message,
+    values: &[f32],
+    decimal_scale_factor: i16,
+) -> Grib2Result<Vec<u8>> {
+    let num_points = grid_point_count(message);
+    if values.len() != num_points {
+        return Err(Grib2Error::InvalidGrid(format!(
+            "Expected {} values for a {}x{} grid, got {}",
+            num_points,
+            message.grid_definition.num_points_longitude,
+            message.grid_definition.num_points_latitude,
+            values.len()
+        )));
+    }
+
+    write_message(message, &pack_simple(values, decimal_scale_factor))
+}
+
+fn grid_point_count(message: &Grib2Message) -> usize {
+    let (nj, ni) = message.grid_dims();
+    ni as usize * nj as usize
+}
+
+fn write_message(message: &Grib2Message, packed: &SimplePacked) -> Grib2Result<Vec<u8>> {
+    let grid = &message.grid_definition;
+    let product = &message.product_definition;
+    for (section, template_number, template_data) in [
+        (3, grid.template_number, &grid.template_data),
+        (4, product.template_number, &product.template_data),
+    ] {
+        if template_data.is_empty() {
+            return Err(Grib2Error::UnsupportedTemplate {
+                template_number,
+                reason: format!("Section {} template octets were not kept", section),
+            });
+        }
+    }
+
+    let mut sections = Vec::new();
+    write_identification(&mut sections, message);
+
+    // Section 3: source of grid definition, number of data points, no
+    // optional list of points
+    let mut body = vec![0];
+    body.extend_from_slice(&(grid_point_count(message) as u32).to_be_bytes());
+    body.extend_from_slice(&[0, 0]);
+    body.extend_from_slice(&grid.template_number.to_be_bytes());
+    body.extend_from_slice(&grid.template_data);
+    write_section(&mut sections, 3, &body);
+
+    // Section 4: no coordinate values after the template
+    let mut body = vec![0, 0];
+    body.extend_from_slice(&product.template_number.to_be_bytes());
+    body.extend_from_slice(&product.template_data);
+    write_section(&mut sections, 4, &body);
+
+    write_section(&mut sections, 5, &data_representation(packed));
+
+    match &packed.bitmap {
+        Some(bitmap) => {
+            let mut body = vec![0];
+            body.extend_from_slice(bitmap);
+            write_section(&mut sections, 6, &body);
+        }
+        None => write_section(&mut sections, 6, &[255]),
+    }
+
+    write_section(&mut sections, 7, &packed.data);
+
+    // Section 0, then the sections and the "7777" end section
+    let length = 16 + sections.len() + 4;
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(b"GRIB");
+    out.extend_from_slice(&[0, 0]);
+    out.push(message.indicator.discipline);
+    out.push(2);
+    out.extend_from_slice(&(length as u64).to_be_bytes());
+    out.extend_from_slice(&sections);
+    out.extend_from_slice(b"7777");
+    Ok(out)
+}
+
+fn write_identification(out: &mut Vec<u8>, message: &Grib2Message) {
+    use chrono::{Datelike, Timelike};
+
+    let id = &message.identification;
+    let time = id.reference_time;
+    let mut body = Vec::with_capacity(16);
+    body.extend_from_slice(&id.center.to_be_bytes());
+    body.extend_from_slice(&id.sub_center.to_be_bytes());
+    body.push(id.table_version);
+    body.push(id.local_table_version);
+    body.push(id.significance_of_reference_time);
+    body.extend_from_slice(&(time.year() as u16).to_be_bytes());
+    body.push(time.month() as u8);
+    body.push(time.day() as u8);
+    body.push(time.hour() as u8);
+    body.push(time.minute() as u8);
+    body.push(time.second() as u8);
+    body.push(id.production_status);
+    body.push(id.data_type);
+    write_section(out, 1, &body);
+}
+
+/// Template 5.0 section body for packed values.
+fn data_representation(packed: &SimplePacked) -> Vec<u8> {
+    let mut body = Vec::with_capacity(16);
+    body.extend_from_slice(&packed.num_values.to_be_bytes());
+    body.extend_from_slice(&0u16.to_be_bytes());
+    body.extend_from_slice(&packed.reference_value.to_be_bytes());
+    body.extend_from_slice(&encode_grib2_signed_i16(packed.binary_scale_factor));
+    body.extend_from_slice(&encode_grib2_signed_i16(packed.decimal_scale_factor));
+    body.push(packed.bits_per_value);
+    // Original field values are floating point
+    body.push(0);
+    body
+}
+
+/// Append a section: its length, its number, then `body`.
+fn write_section(out: &mut Vec<u8>, number: u8, body: &[u8]) {
+    out.extend_from_slice(&(body.len() as u32 + 5).to_be_bytes());
+    out.push(number);
+    out.extend_from_slice(body);
+}
+
+/// Encode a 2-octet GRIB2 sign-magnitude integer.
+fn encode_grib2_signed_i16(value: i16) -> [u8; 2] {
+    let magnitude = value.unsigned_abs() & 0x7FFF;
+    let sign = if value < 0 { 0x8000 } else { 0 };
+    (sign | magnitude).to_be_bytes()
+}
+
+/// Packs values of a fixed bit width, most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// Bits used in the last byte, 0 when it is full
+    used: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, num_bits: u32) {
+        for bit in (0..num_bits).rev() {
+            if self.used == 0 {
+                self.bytes.push(0);
+            }
+            if (value >> bit) & 1 == 1 {
+                *self.bytes.last_mut().unwrap() |= 0x80 >> self.used;
+            }
+            self.used = (self.used + 1) % 8;
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grib2Reader, Grib2Tables};
+    use bytes::Bytes;
+    use std::sync::Arc;
+
+    /// Template 3.0 octets of a 0.01 degree grid starting at 54.995N
+    /// 230.005E, scanning north to south.
+    fn latlon_template(ni: u32, nj: u32) -> Vec<u8> {
+        let mut t = vec![6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        t.extend_from_slice(&ni.to_be_bytes());
+        t.extend_from_slice(&nj.to_be_bytes());
+        t.extend_from_slice(&[0; 8]);
+        t.extend_from_slice(&54_995_000u32.to_be_bytes());
+        t.extend_from_slice(&230_005_000u32.to_be_bytes());
+        t.push(48);
+        t.extend_from_slice(&(54_995_000 - (nj - 1) * 10_000).to_be_bytes());
+        t.extend_from_slice(&(230_005_000 + (ni - 1) * 10_000).to_be_bytes());
+        t.extend_from_slice(&10_000u32.to_be_bytes());
+        t.extend_from_slice(&10_000u32.to_be_bytes());
+        t.push(0);
+        t
+    }
+
+    /// Template 4.0 octets of parameter 209/0/16 at 500 m above MSL.
+    fn product_template() -> Vec<u8> {
+        let mut t = vec![0, 16, 2, 0, 0, 0, 0, 0, 1];
+        t.extend_from_slice(&0u32.to_be_bytes());
+        t.extend_from_slice(&[102, 0]);
+        t.extend_from_slice(&500u32.to_be_bytes());
+        t.extend_from_slice(&[255, 0, 0, 0, 0, 0]);
+        t
+    }
+
+    fn tables() -> Arc<Grib2Tables> {
+        let mut tables = Grib2Tables::new();
+        tables.add_parameter(209, 0, 16, "MergedReflectivityQC".to_string());
+        Arc::new(tables)
+    }
+
+    /// A parsed message with a constant field, to re-encode with new values.
+    fn template_message(ni: u32, nj: u32) -> Grib2Message {
+        let mut data = b"GRIB\0\0\xd1\x02".to_vec();
+        data.extend_from_slice(&[0; 8]);
+        write_section(
+            &mut data,
+            1,
+            &[0, 161, 0, 0, 2, 1, 0, 0x07, 0xE9, 1, 15, 12, 30, 0, 0, 1],
+        );
+        let mut grid = vec![0];
+        grid.extend_from_slice(&(ni * nj).to_be_bytes());
+        grid.extend_from_slice(&[0, 0, 0, 0]);
+        grid.extend_from_slice(&latlon_template(ni, nj));
+        write_section(&mut data, 3, &grid);
+        let mut product = vec![0, 0, 0, 0];
+        product.extend_from_slice(&product_template());
+        write_section(&mut data, 4, &product);
+        let packed = pack_simple(&vec![0.0; (ni * nj) as usize], 1);
+        write_section(&mut data, 5, &data_representation(&packed));
+        write_section(&mut data, 6, &[255]);
+        write_section(&mut data, 7, &[]);
+        data.extend_from_slice(b"7777");
+        let length = data.len() as u64;
+        data[8..16].copy_from_slice(&length.to_be_bytes());
+
+        parse(data)
+    }
+
+    fn parse(data: Vec<u8>) -> Grib2Message {
+        Grib2Reader::new(Bytes::from(data), tables())
+            .next_message()
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_encode_signed_i16() {
+        // Sign-magnitude, not two's complement
+        assert_eq!(encode_grib2_signed_i16(3), [0x00, 0x03]);
+        assert_eq!(encode_grib2_signed_i16(-3), [0x80, 0x03]);
+        assert_eq!(encode_grib2_signed_i16(-300), [0x81, 0x2C]);
+    }
+
+    #[test]
+    fn test_pack_simple() {
+        let values = [-999.0, 12.5, 40.0, 72.25];
+        let packed = pack_simple(&values, 2);
+        assert_eq!(packed.reference_value, -99900.0);
+        assert_eq!(packed.binary_scale_factor, 0);
+        assert_eq!(packed.num_values, 4);
+        assert!(packed.bitmap.is_none());
+
+        let unpacked = crate::unpack_simple(
+            &packed.data,
+            packed.num_values,
+            packed.bits_per_value,
+            packed.reference_value,
+            packed.binary_scale_factor,
+            packed.decimal_scale_factor,
+            None,
+        )
+        .unwrap();
+        for (value, unpacked) in values.iter().zip(unpacked) {
+            assert!((value - unpacked.unwrap()).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_pack_simple_wide_range_uses_binary_scale() {
+        let packed = pack_simple(&[0.0, 1.0e9], 0);
+        assert_eq!(packed.bits_per_value, 24);
+        assert!(packed.binary_scale_factor > 0);
+    }
+
+    #[test]
+    fn test_pack_constant_field() {
+        let packed = pack_simple(&[5.0, 5.0, f32::NAN], 0);
+        assert_eq!(packed.bits_per_value, 0);
+        assert_eq!(packed.reference_value, 5.0);
+        assert_eq!(packed.num_values, 2);
+        assert!(packed.data.is_empty());
+        assert_eq!(packed.bitmap, Some(vec![0b1100_0000]));
+    }
+
+    #[test]
+    fn test_encode_values_round_trip() {
+        let template = template_message(4, 3);
+        let values: Vec<f32> = (0..12).map(|i| i as f32 * 2.5 - 10.0).collect();
+
+        let bytes = encode_values(&template, &values, 1).unwrap();
+        let message = parse(bytes);
+
+        assert_eq!(message.indicator.discipline, 209);
+        assert_eq!(message.identification.center, 161);
+        assert_eq!(
+            message.identification.reference_time,
+            template.identification.reference_time
+        );
+        assert_eq!(message.grid_definition, template.grid_definition);
+        assert_eq!(message.parameter(), "MergedReflectivityQC");
+        assert_eq!(message.product_definition.level_value, 500);
+        assert_eq!(message.templates(), "3.0/4.0/5.0");
+
+        let unpacked = message.unpack_data().unwrap();
+        assert_eq!(unpacked.len(), values.len());
+        for (value, unpacked) in values.iter().zip(&unpacked) {
+            assert!((value - unpacked).abs() < 1e-3, "{} != {}", value, unpacked);
+        }
+    }
+
+    #[test]
+    fn test_encode_values_with_missing() {
+        let template = template_message(4, 2);
+        let mut values = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        values[2] = f32::NAN;
+        values[7] = f32::NAN;
+
+        let message = parse(encode_values(&template, &values, 0).unwrap());
+        let bitmap = message.bitmap.as_ref().unwrap();
+        assert_eq!(bitmap.indicator, 0);
+        assert_eq!(bitmap.data.as_ref(), &[0b1101_1110]);
+        assert_eq!(message.data_representation.num_data_points, 6);
+
+        let unpacked = message.unpack_data().unwrap();
+        for (value, unpacked) in values.iter().zip(&unpacked) {
+            if value.is_nan() {
+                assert!(unpacked.is_nan());
+            } else {
+                assert_eq!(value, unpacked);
+            }
+        }
+
+        let filled = message.unpack_data_with_missing(-999.0).unwrap();
+        assert_eq!(filled, vec![1.0, 2.0, -999.0, 4.0, 5.0, 6.0, 7.0, -999.0]);
+    }
+
+    #[test]
+    fn test_encode_message_copies_simple_packing() {
+        let template = template_message(3, 3);
+        let values: Vec<f32> = (0..9).map(|i| i as f32).collect();
+        let bytes = encode_values(&template, &values, 0).unwrap();
+
+        let message = parse(bytes.clone());
+        assert_eq!(encode_message(&message).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_encode_values_checks_grid_size() {
+        let template = template_message(3, 3);
+        assert!(matches!(
+            encode_values(&template, &[1.0; 4], 0),
+            Err(Grib2Error::InvalidGrid(_))
+        ));
+    }
+
+    #[test]
+    fn test_scan_inventory_of_written_messages() {
+        let template = template_message(3, 2);
+        let first = encode_values(&template, &[1.0; 6], 0).unwrap();
+
+        // Same field at forecast hour 6 (template octets 10-13)
+        let mut later = template.clone();
+        let mut product = later.product_definition.template_data.to_vec();
+        product[9..13].copy_from_slice(&6u32.to_be_bytes());
+        later.product_definition.template_data = Bytes::from(product);
+        let second = encode_values(&later, &[2.0; 6], 0).unwrap();
+
+        let mut file = first.clone();
+        file.extend_from_slice(&second);
+        let mut reader = Grib2Reader::new(Bytes::from(file), tables());
+
+        let inventory = reader.scan_inventory().unwrap();
+        assert_eq!(inventory.len(), 2);
+        assert_eq!((inventory[0].offset, inventory[0].length), (0, first.len()));
+        assert_eq!(
+            (inventory[1].offset, inventory[1].length),
+            (first.len(), second.len())
+        );
+        assert_eq!(inventory[1].parameter, "MergedReflectivityQC");
+        assert_eq!(inventory[1].level_value, 500);
+        assert_eq!(inventory[1].forecast_hour, 6);
+        assert_eq!(inventory[1].grid_definition, template.grid_definition);
+        assert_eq!(reader.position(), 0);
+
+        reader.seek_to_offset(inventory[1].offset).unwrap();
+        let message = reader.next_message().unwrap().unwrap();
+        assert_eq!(message.product_definition.forecast_hour, 6);
+        assert_eq!(message.unpack_data().unwrap(), vec![2.0; 6]);
+        assert!(reader.seek_to_offset(1).is_err());
+
+        // A truncated message can't be skipped
+        let mut truncated = first;
+        truncated.extend_from_slice(&second[..second.len() - 10]);
+        let reader = Grib2Reader::new(Bytes::from(truncated), tables());
+        assert!(reader.scan_inventory().is_err());
+    }
+
+    #[test]
+    fn test_inventory_lines() {
+        let template = template_message(3, 2);
+        let first = encode_values(&template, &[1.0; 6], 0).unwrap();
+
+        // 90 minutes ahead (unit octet 9, forecast time octets 10-13)
+        let mut later = template.clone();
+        let mut product = later.product_definition.template_data.to_vec();
+        product[8] = 0;
+        product[9..13].copy_from_slice(&90u32.to_be_bytes());
+        later.product_definition.template_data = Bytes::from(product);
+        let second = encode_values(&later, &[2.0; 6], 0).unwrap();
+
+        let mut file = first.clone();
+        file.extend_from_slice(&second);
+        let reader = Grib2Reader::new(Bytes::from(file), tables());
+
+        let index = reader.inventory().unwrap();
+        assert_eq!(
+            index.to_string(),
+            format!(
+                "1:0:d=2025011512:MergedReflectivityQC:Level type 102 value 500:anl:\n\
+                 2:{}:d=2025011512:MergedReflectivityQC:Level type 102 value 500:90 min fcst:\n",
+                first.len()
+            )
+        );
+        // Unlike a parsed .idx, the scan knows the length of the last message
+        assert_eq!(index.entries()[1].length, Some(second.len() as u64));
+    }
+}
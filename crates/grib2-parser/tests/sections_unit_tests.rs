@@ -2,7 +2,11 @@
 //!
 //! These tests don't require test data files and focus on individual functions.
 
-use grib2_parser::sections::decode_grib2_signed;
+use chrono::{Duration, TimeZone, Utc};
+use grib2_parser::sections::{
+    decode_grib2_signed, parse_data_representation, parse_grid_definition, parse_product_definition,
+};
+use grib2_parser::Grib2Tables;
 
 // ============================================================================
 // decode_grib2_signed tests
@@ -194,3 +198,396 @@ fn test_hrrr_typical_coordinates() {
     let lon = (134_095_000_u32 | 0x80000000).to_be_bytes();
     assert_eq!(decode_grib2_signed(&lon), -134_095_000);
 }
+
+// ============================================================================
+// Template number and raw template passthrough tests
+// ============================================================================
+
+/// Build a section: 4-byte length, section number, then the body
+fn section(number: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = ((body.len() + 5) as u32).to_be_bytes().to_vec();
+    out.push(number);
+    out.extend_from_slice(body);
+    out
+}
+
+/// A message with sections 3, 4 and 5 using the given templates.
+///
+/// Template bodies are filled with a recognisable byte pattern.
+fn message_with_templates(grid: u16, product: u16, packing: u16) -> Vec<u8> {
+    let mut data = b"GRIB\0\0\0\x02".to_vec();
+    data.extend_from_slice(&[0; 8]);
+
+    // Section 3: source, point count, optional list, template, 60 template octets
+    let mut sec3 = vec![0, 0, 0, 0, 4, 0, 0];
+    sec3.extend_from_slice(&grid.to_be_bytes());
+    sec3.extend((0..60).map(|i| i as u8));
+    data.extend(section(3, &sec3));
+
+    // Section 4: coordinate count, template, 30 template octets
+    let mut sec4 = vec![0, 0];
+    sec4.extend_from_slice(&product.to_be_bytes());
+    sec4.extend([0xAB; 30]);
+    data.extend(section(4, &sec4));
+
+    // Section 5: point count, template, 16 template octets
+    let mut sec5 = vec![0, 0, 0, 4];
+    sec5.extend_from_slice(&packing.to_be_bytes());
+    sec5.extend([0xCD; 16]);
+    data.extend(section(5, &sec5));
+
+    data.extend(section(7, &[]));
+    data.extend_from_slice(b"7777");
+    data
+}
+
+#[test]
+fn test_unsupported_grid_template_keeps_raw_bytes() {
+    // Template 3.20: polar stereographic
+    let data = message_with_templates(20, 0, 0);
+    let grid = parse_grid_definition(&data).unwrap();
+
+    assert_eq!(grid.template_number, 20);
+    assert!(!grid.is_template_supported());
+    assert!(grid.lambert_conformal.is_none());
+    assert_eq!(grid.template_data.len(), 60);
+    assert_eq!(grid.template_data[59], 59);
+}
+
+/// Sign-magnitude encoding of a value in microdegrees
+fn microdegrees(degrees: f64) -> [u8; 4] {
+    let magnitude = (degrees.abs() * 1_000_000.0).round() as u32;
+    let sign = if degrees < 0.0 { 0x8000_0000 } else { 0 };
+    (magnitude | sign).to_be_bytes()
+}
+
+#[test]
+fn test_lambert_conformal_grid_template() {
+    let mut data = b"GRIB\0\0\0\x02".to_vec();
+    data.extend_from_slice(&[0; 8]);
+
+    // Section 3, template 3.30 with the HRRR CONUS grid
+    let mut sec3 = vec![0];
+    sec3.extend_from_slice(&(1799u32 * 1059).to_be_bytes());
+    sec3.extend_from_slice(&[0, 0, 0, 30]);
+    sec3.push(6); // Spherical Earth, radius 6371229 m
+    sec3.extend_from_slice(&[0; 15]);
+    sec3.extend_from_slice(&1799u32.to_be_bytes());
+    sec3.extend_from_slice(&1059u32.to_be_bytes());
+    sec3.extend_from_slice(&microdegrees(21.138123));
+    sec3.extend_from_slice(&microdegrees(237.280472));
+    sec3.push(0x08); // Resolution and component flags
+    sec3.extend_from_slice(&microdegrees(38.5));
+    sec3.extend_from_slice(&microdegrees(262.5));
+    sec3.extend_from_slice(&3_000_000u32.to_be_bytes());
+    sec3.extend_from_slice(&3_000_000u32.to_be_bytes());
+    sec3.push(0); // Projection centre: north pole
+    sec3.push(0x40); // Scanning mode: south to north
+    sec3.extend_from_slice(&microdegrees(38.5));
+    sec3.extend_from_slice(&microdegrees(38.5));
+    sec3.extend_from_slice(&microdegrees(-90.0));
+    sec3.extend_from_slice(&microdegrees(0.0));
+    data.extend(section(3, &sec3));
+    data.extend_from_slice(b"7777");
+
+    let grid = parse_grid_definition(&data).unwrap();
+    assert_eq!(grid.template_number, 30);
+    assert!(grid.is_template_supported());
+    assert_eq!(grid.grid_shape, 6);
+    assert_eq!(grid.num_points_longitude, 1799);
+    assert_eq!(grid.num_points_latitude, 1059);
+    assert_eq!(grid.first_latitude_millidegrees, 21_138);
+    assert_eq!(grid.first_longitude_millidegrees, 237_280);
+    assert_eq!(grid.scanning_mode, 0x40);
+
+    let lambert = grid.lambert_conformal.unwrap();
+    assert!((lambert.first_latitude - 21.138123).abs() < 1e-9);
+    assert!((lambert.first_longitude - 237.280472).abs() < 1e-9);
+    assert_eq!(lambert.lad, 38.5);
+    assert_eq!(lambert.lov, 262.5);
+    assert_eq!(lambert.dx, 3000.0);
+    assert_eq!(lambert.dy, 3000.0);
+    assert_eq!(lambert.projection_centre, 0);
+    assert_eq!(lambert.latin1, 38.5);
+    assert_eq!(lambert.latin2, 38.5);
+    assert_eq!(lambert.south_pole_latitude, -90.0);
+    assert_eq!(lambert.south_pole_longitude, 0.0);
+}
+
+#[test]
+fn test_gaussian_grid_template() {
+    let mut data = b"GRIB\0\0\0\x02".to_vec();
+    data.extend_from_slice(&[0; 8]);
+
+    // Section 3, template 3.40 with a global N80 Gaussian grid
+    let mut sec3 = vec![0];
+    sec3.extend_from_slice(&(320u32 * 160).to_be_bytes());
+    sec3.extend_from_slice(&[0, 0, 0, 40]);
+    sec3.push(6); // Spherical Earth, radius 6371229 m
+    sec3.extend_from_slice(&[0; 15]);
+    sec3.extend_from_slice(&320u32.to_be_bytes());
+    sec3.extend_from_slice(&160u32.to_be_bytes());
+    sec3.extend_from_slice(&[0; 8]); // Basic angle and subdivisions
+    sec3.extend_from_slice(&microdegrees(89.141519));
+    sec3.extend_from_slice(&microdegrees(0.0));
+    sec3.push(0x30); // Resolution and component flags
+    sec3.extend_from_slice(&microdegrees(-89.141519));
+    sec3.extend_from_slice(&microdegrees(358.875));
+    sec3.extend_from_slice(&1_125_000u32.to_be_bytes()); // Di
+    sec3.extend_from_slice(&80u32.to_be_bytes()); // N
+    sec3.push(0); // Scanning mode: north to south
+    data.extend(section(3, &sec3));
+    data.extend_from_slice(b"7777");
+
+    let grid = parse_grid_definition(&data).unwrap();
+    assert_eq!(grid.template_number, 40);
+    assert!(grid.is_template_supported());
+    assert_eq!(grid.num_points_longitude, 320);
+    assert_eq!(grid.num_points_latitude, 160);
+    assert_eq!(grid.first_latitude_millidegrees, 89_141);
+    assert_eq!(grid.last_latitude_millidegrees, -89_141);
+    assert_eq!(grid.last_longitude_millidegrees, 358_875);
+    assert_eq!(grid.longitude_increment_millidegrees, 1_125);
+    assert_eq!(grid.latitude_increment_millidegrees, 0);
+    assert_eq!(grid.gaussian_parallels, Some(80));
+
+    let latitudes = grib2_parser::gaussian::grid_latitudes(&grid).unwrap();
+    assert_eq!(latitudes.len(), 160);
+    assert!((latitudes[0] - 89.141519).abs() < 1e-5);
+}
+
+#[test]
+fn test_truncated_lambert_conformal_template() {
+    // 60 template octets, template 3.30 needs 67
+    let data = message_with_templates(30, 0, 0);
+    assert!(parse_grid_definition(&data).is_err());
+}
+
+#[test]
+fn test_supported_grid_template_number() {
+    let data = message_with_templates(0, 0, 0);
+    let grid = parse_grid_definition(&data).unwrap();
+
+    assert_eq!(grid.template_number, 0);
+    assert!(grid.is_template_supported());
+    assert_eq!(grid.template_data.len(), 60);
+}
+
+#[test]
+fn test_product_and_packing_template_numbers() {
+    let data = message_with_templates(0, 44, 3);
+    let tables = Grib2Tables::new();
+
+    // Template 4.44: aerosol, a different layout from 4.0
+    let product = parse_product_definition(&data, 0, &tables).unwrap();
+    assert_eq!(product.template_number, 44);
+    assert!(!product.is_template_supported());
+    // Template octets end at the section boundary, not the message end
+    assert_eq!(product.template_data.len(), 30);
+    assert!(product.template_data.iter().all(|&b| b == 0xAB));
+
+    let packing = parse_data_representation(&data).unwrap();
+    assert_eq!(packing.template_number, 3);
+    assert_eq!(packing.packing_method, 3);
+    assert_eq!(&packing.template_data[..], &[0xCD; 16]);
+}
+
+/// A message with a template 4.1/4.11 section 4: 2 m temperature at f006
+fn ensemble_message(template: u16, ensemble_type: u8, perturbation: u8) -> Vec<u8> {
+    let mut data = b"GRIB\0\0\0\x02".to_vec();
+    data.extend_from_slice(&[0; 8]);
+
+    let mut sec4 = vec![0, 0];
+    sec4.extend_from_slice(&template.to_be_bytes());
+    // Category, number, process, background, forecast process, cutoff, unit
+    sec4.extend_from_slice(&[0, 0, 4, 0, 107, 0, 0, 0, 1]);
+    sec4.extend_from_slice(&6u32.to_be_bytes());
+    // First fixed surface: 2 m above ground; no second surface
+    sec4.extend_from_slice(&[103, 0, 0, 0, 0, 2, 255, 0, 0, 0, 0, 0]);
+    sec4.extend_from_slice(&[ensemble_type, perturbation, 31]);
+    if template == 11 {
+        // Statistical processing (4.11 only), left empty
+        sec4.extend_from_slice(&[0; 34]);
+    }
+    data.extend(section(4, &sec4));
+    data.extend_from_slice(b"7777");
+    data
+}
+
+#[test]
+fn test_ensemble_product_templates() {
+    let tables = Grib2Tables::new();
+
+    let control = parse_product_definition(&ensemble_message(1, 1, 0), 0, &tables).unwrap();
+    assert_eq!(control.template_number, 1);
+    assert!(control.is_template_supported());
+    assert!(control.is_ensemble());
+    assert_eq!(control.ensemble_member.as_deref(), Some("c00"));
+    assert_eq!(control.perturbation_number, Some(0));
+    assert_eq!(control.total_members, Some(31));
+    assert_eq!(control.forecast_hour, 6);
+    assert_eq!(control.level_type, 103);
+    assert_eq!(control.level_value, 2);
+
+    let perturbed = parse_product_definition(&ensemble_message(11, 3, 7), 0, &tables).unwrap();
+    assert_eq!(perturbed.template_number, 11);
+    assert_eq!(perturbed.ensemble_member.as_deref(), Some("p07"));
+    assert_eq!(perturbed.perturbation_number, Some(7));
+    assert_eq!(perturbed.total_members, Some(31));
+
+    let negative = parse_product_definition(&ensemble_message(1, 2, 12), 0, &tables).unwrap();
+    assert_eq!(negative.ensemble_member.as_deref(), Some("n12"));
+}
+
+#[test]
+fn test_deterministic_product_has_no_member() {
+    let data = message_with_templates(0, 0, 0);
+    let product = parse_product_definition(&data, 0, &Grib2Tables::new()).unwrap();
+
+    assert!(!product.is_ensemble());
+    assert_eq!(product.perturbation_number, None);
+    assert_eq!(product.total_members, None);
+}
+
+/// A message with a template 4.8/4.11 section 4: surface APCP accumulated
+/// over `length` hours from `start` hours, valid 2024-01-15 at `start + length` Z
+fn accumulation_message(template: u16, start: u32, length: u32) -> Vec<u8> {
+    let mut data = b"GRIB\0\0\0\x02".to_vec();
+    data.extend_from_slice(&[0; 8]);
+
+    let mut sec4 = vec![0, 0];
+    sec4.extend_from_slice(&template.to_be_bytes());
+    // Category, number, process, background, forecast process, cutoff, unit
+    sec4.extend_from_slice(&[1, 8, 2, 0, 96, 0, 0, 0, 1]);
+    sec4.extend_from_slice(&start.to_be_bytes());
+    // First fixed surface: ground; no second surface
+    sec4.extend_from_slice(&[1, 0, 0, 0, 0, 0, 255, 0, 0, 0, 0, 0]);
+    if template == 11 {
+        sec4.extend_from_slice(&[3, 7, 31]);
+    }
+    // End of overall time interval
+    sec4.extend_from_slice(&2024u16.to_be_bytes());
+    sec4.extend_from_slice(&[1, 15, (start + length) as u8, 0, 0]);
+    // One time range, no missing values: accumulation, hourly, `length` hours
+    sec4.extend_from_slice(&[1, 0, 0, 0, 0, 1, 2, 1]);
+    sec4.extend_from_slice(&length.to_be_bytes());
+    // Continuous processing: no increment
+    sec4.extend_from_slice(&[255, 0, 0, 0, 0]);
+    data.extend(section(4, &sec4));
+    data.extend_from_slice(b"7777");
+    data
+}
+
+#[test]
+fn test_statistical_product_template() {
+    let mut tables = Grib2Tables::new();
+    tables.add_parameter(0, 1, 8, "APCP".to_string());
+
+    let product = parse_product_definition(&accumulation_message(8, 0, 6), 0, &tables).unwrap();
+    assert_eq!(product.template_number, 8);
+    assert!(product.is_template_supported());
+    assert_eq!(product.parameter_short_name, "APCP");
+    assert_eq!(product.level_type, 1);
+
+    let stats = product
+        .statistical_processing
+        .expect("accumulation interval");
+    assert_eq!(stats.process, 1);
+    assert_eq!(stats.process_name(), "accumulation");
+    assert_eq!(stats.start_hour, 0);
+    assert_eq!(stats.end_hour, 6);
+    assert_eq!(stats.period_hours(), 6);
+    assert_eq!(
+        stats.end_time,
+        Some(Utc.with_ymd_and_hms(2024, 1, 15, 6, 0, 0).unwrap())
+    );
+
+    // The 6-12 hour accumulation in the f012 file has forecast time 6
+    let product = parse_product_definition(&accumulation_message(8, 6, 6), 0, &tables).unwrap();
+    let stats = product.statistical_processing.unwrap();
+    assert_eq!((stats.start_hour, stats.end_hour), (6, 12));
+}
+
+#[test]
+fn test_ensemble_statistical_product_template() {
+    let product =
+        parse_product_definition(&accumulation_message(11, 12, 6), 0, &Grib2Tables::new()).unwrap();
+
+    assert_eq!(product.ensemble_member.as_deref(), Some("p07"));
+    let stats = product.statistical_processing.unwrap();
+    assert_eq!((stats.start_hour, stats.end_hour), (12, 18));
+
+    // No time range given
+    let product =
+        parse_product_definition(&ensemble_message(11, 3, 7), 0, &Grib2Tables::new()).unwrap();
+    assert!(product.statistical_processing.is_none());
+
+    let product =
+        parse_product_definition(&message_with_templates(0, 0, 0), 0, &Grib2Tables::new()).unwrap();
+    assert!(product.statistical_processing.is_none());
+}
+
+/// A message with a template 4.0 section 4: 2 m temperature with the
+/// forecast time given in `unit` (Table 4.4)
+fn forecast_message(unit: u8, forecast_time: u32) -> Vec<u8> {
+    let mut data = b"GRIB\0\0\0\x02".to_vec();
+    data.extend_from_slice(&[0; 8]);
+
+    let mut sec4 = vec![0, 0, 0, 0];
+    // Category, number, process, background, forecast process, cutoff, unit
+    sec4.extend_from_slice(&[0, 0, 2, 0, 83, 0, 0, 0, unit]);
+    sec4.extend_from_slice(&forecast_time.to_be_bytes());
+    sec4.extend_from_slice(&[103, 0, 0, 0, 0, 2, 255, 0, 0, 0, 0, 0]);
+    data.extend(section(4, &sec4));
+    data.extend_from_slice(b"7777");
+    data
+}
+
+#[test]
+fn test_forecast_time_units() {
+    let tables = Grib2Tables::new();
+    let parse = |unit, time| parse_product_definition(&forecast_message(unit, time), 0, &tables);
+
+    // 15-minute HRRR sub-hourly output
+    let product = parse(0, 15).unwrap();
+    assert_eq!(product.forecast_time_unit, 0);
+    assert_eq!(product.forecast_duration, Duration::minutes(15));
+    assert_eq!(product.forecast_hour, 0);
+
+    let product = parse(0, 105).unwrap();
+    assert_eq!(product.forecast_duration, Duration::minutes(105));
+    assert_eq!(product.forecast_hour, 1);
+
+    let product = parse(1, 6).unwrap();
+    assert_eq!(product.forecast_duration, Duration::hours(6));
+    assert_eq!(product.forecast_hour, 6);
+
+    assert_eq!(parse(2, 2).unwrap().forecast_hour, 48);
+    assert_eq!(parse(11, 2).unwrap().forecast_hour, 12);
+    assert_eq!(
+        parse(13, 90).unwrap().forecast_duration,
+        Duration::seconds(90)
+    );
+
+    // Months have no fixed length; the value is read as hours
+    assert_eq!(parse(3, 1).unwrap().forecast_duration, Duration::hours(1));
+}
+
+#[test]
+fn test_scale_factors_are_sign_magnitude() {
+    let mut data = b"GRIB\0\0\0\x02".to_vec();
+    data.extend_from_slice(&[0; 8]);
+
+    // Section 5: 4 points, template 5.3, R = 1.0, E = -3, D = 2, 12 bits
+    let mut sec5 = vec![0, 0, 0, 4, 0, 3];
+    sec5.extend_from_slice(&1.0f32.to_be_bytes());
+    sec5.extend_from_slice(&[0x80, 0x03, 0x00, 0x02, 12, 0]);
+    data.extend(section(5, &sec5));
+    data.extend_from_slice(b"7777");
+
+    let packing = parse_data_representation(&data).unwrap();
+    assert_eq!(packing.reference_value, 1.0);
+    assert_eq!(packing.binary_scale_factor, -3);
+    assert_eq!(packing.decimal_scale_factor, 2);
+    assert_eq!(packing.bits_per_value, 12);
+}
@@ -328,7 +328,9 @@ impl Grib2Builder {
         section.extend_from_slice(&0u16.to_be_bytes()); // Template 5.0
 
         section.extend_from_slice(&reference_value.to_be_bytes()); // Reference value
-        section.extend_from_slice(&binary_scale_factor.to_be_bytes()); // Binary scale factor
+        let sign = if binary_scale_factor < 0 { 0x8000 } else { 0 };
+        let magnitude = binary_scale_factor.unsigned_abs();
+        section.extend_from_slice(&(sign | magnitude).to_be_bytes()); // Binary scale factor (sign-magnitude)
         section.extend_from_slice(&0i16.to_be_bytes()); // Decimal scale factor
         section.push(bits_per_value);
         section.push(0); // Original field type (floating point)
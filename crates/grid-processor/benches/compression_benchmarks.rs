@@ -0,0 +1,194 @@
+//! Benchmarks for Zarr chunk compression codecs.
+//!
+//! Writes an MRMS-like reflectivity grid with each codec, prints the stored
+//! size, and measures compressing a grid and decompressing one chunk (the
+//! work a tile request does on a cache miss).
+//!
+//! Run with: cargo bench --package grid-processor --bench compression_benchmarks
+
+use std::path::Path;
+use std::sync::Arc;
+
+use chrono::Utc;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use grid_processor::{BoundingBox, GridProcessorConfig, ZarrCompression, ZarrWriter};
+use zarrs::array::Array;
+use zarrs_filesystem::FilesystemStore;
+
+const GRID_SIZE: usize = 2048;
+const CHUNK_SIZE: usize = 512;
+
+/// Codecs and levels compared, covering the fast and small ends of each.
+const CODECS: &[(ZarrCompression, u8)] = &[
+    (ZarrCompression::None, 1),
+    (ZarrCompression::BloscLz4, 5),
+    (ZarrCompression::BloscZstd, 1),
+    (ZarrCompression::BloscZstd, 9),
+    (ZarrCompression::Zstd, 3),
+    (ZarrCompression::Zstd, 19),
+];
+
+/// Generate a reflectivity-like field: storm cells of 5-65 dBZ over a
+/// background of missing values (NaN), as in MRMS after sentinel handling.
+fn generate_reflectivity(width: usize, height: usize) -> Vec<f32> {
+    let cells: Vec<(f32, f32, f32, f32)> = (0..40u32)
+        .map(|i| {
+            let h = hash(i);
+            let x = (h & 0xffff) as f32 / 65535.0 * width as f32;
+            let y = (h >> 16) as f32 / 65535.0 * height as f32;
+            let radius = 20.0 + (hash(i + 1000) % 120) as f32;
+            let peak = 35.0 + (hash(i + 2000) % 30) as f32;
+            (x, y, radius, peak)
+        })
+        .collect();
+
+    let mut data = vec![f32::NAN; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            let dbz = cells
+                .iter()
+                .map(|&(cx, cy, radius, peak)| {
+                    let d2 = (x as f32 - cx).powi(2) + (y as f32 - cy).powi(2);
+                    peak * (-d2 / (radius * radius)).exp()
+                })
+                .fold(0.0f32, f32::max);
+            if dbz >= 5.0 {
+                // Radar noise, quantized to 0.5 dBZ like the source data
+                let noise = (hash((y * width + x) as u32) % 7) as f32 - 3.0;
+                data[y * width + x] = ((dbz + noise) * 2.0).round() / 2.0;
+            }
+        }
+    }
+    data
+}
+
+/// Cheap deterministic integer hash, to avoid a random number dependency.
+fn hash(mut x: u32) -> u32 {
+    x = (x ^ 61) ^ (x >> 16);
+    x = x.wrapping_mul(9);
+    x ^= x >> 4;
+    x = x.wrapping_mul(0x27d4_eb2d);
+    x ^ (x >> 15)
+}
+
+fn label(compression: ZarrCompression, level: u8) -> String {
+    match compression {
+        ZarrCompression::None => compression.to_string(),
+        _ => format!("{}_{}", compression, level),
+    }
+}
+
+fn config(compression: ZarrCompression, level: u8) -> GridProcessorConfig {
+    GridProcessorConfig {
+        zarr_compression: compression,
+        zarr_compression_level: level,
+        zarr_chunk_size: CHUNK_SIZE,
+        ..Default::default()
+    }
+}
+
+fn write_grid(dir: &Path, config: GridProcessorConfig, data: &[f32]) {
+    let store = FilesystemStore::new(dir).expect("Failed to create store");
+    ZarrWriter::new(config)
+        .write(
+            store,
+            "/",
+            data,
+            GRID_SIZE,
+            GRID_SIZE,
+            &BoundingBox::new(-100.0, 30.0, -79.52, 50.48),
+            "mrms",
+            "REFL",
+            "0 m above MSL",
+            "dBZ",
+            Utc::now(),
+            0,
+        )
+        .expect("Failed to write grid");
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    std::fs::read_dir(dir)
+        .expect("Failed to read dir")
+        .map(|entry| {
+            let entry = entry.expect("Failed to read entry");
+            let meta = entry.metadata().expect("Failed to read metadata");
+            if meta.is_dir() {
+                dir_size(&entry.path())
+            } else {
+                meta.len()
+            }
+        })
+        .sum()
+}
+
+fn bench_compress(c: &mut Criterion) {
+    let data = generate_reflectivity(GRID_SIZE, GRID_SIZE);
+    let raw_bytes = (data.len() * std::mem::size_of::<f32>()) as u64;
+
+    let mut group = c.benchmark_group("compress_grid");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(raw_bytes));
+
+    for &(compression, level) in CODECS {
+        // Stored size is what the storage bill sees; criterion can't report it
+        let dir = tempfile::tempdir().unwrap();
+        write_grid(dir.path(), config(compression, level), &data);
+        let stored = dir_size(dir.path());
+        eprintln!(
+            "{:>14}: {:>10} bytes stored ({:.1}x)",
+            label(compression, level),
+            stored,
+            raw_bytes as f64 / stored as f64
+        );
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(label(compression, level)),
+            &(compression, level),
+            |b, &(compression, level)| {
+                b.iter(|| {
+                    let dir = tempfile::tempdir().unwrap();
+                    write_grid(dir.path(), config(compression, level), black_box(&data));
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_decompress_chunk(c: &mut Criterion) {
+    let data = generate_reflectivity(GRID_SIZE, GRID_SIZE);
+
+    let mut group = c.benchmark_group("decompress_chunk");
+    group.throughput(Throughput::Bytes(
+        (CHUNK_SIZE * CHUNK_SIZE * std::mem::size_of::<f32>()) as u64,
+    ));
+
+    for &(compression, level) in CODECS {
+        let dir = tempfile::tempdir().unwrap();
+        write_grid(dir.path(), config(compression, level), &data);
+        let store = FilesystemStore::new(dir.path()).expect("Failed to create store");
+        let array = Array::open(Arc::new(store), "/").expect("Failed to open array");
+
+        // A chunk near the middle of the grid, where the storms are
+        let chunk = [1u64, 1u64];
+        group.bench_with_input(
+            BenchmarkId::from_parameter(label(compression, level)),
+            &chunk,
+            |b, chunk| {
+                b.iter(|| {
+                    let values: Vec<f32> = array
+                        .retrieve_chunk_elements(black_box(chunk))
+                        .expect("Failed to read chunk");
+                    black_box(values)
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_compress, bench_decompress_chunk);
+criterion_main!(benches);
@@ -2,21 +2,57 @@
 
 use lru::LruCache;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use super::disk_tier::DiskTier;
+use super::freshness::ObjectVersions;
 use crate::types::CacheStats;
 
 /// Cache key for chunks: (zarr_path_hash, chunk_x, chunk_y).
 pub type ChunkKey = (u64, usize, usize);
 
+/// A cached chunk and the storage version it was read from.
+pub(super) struct CachedChunk {
+    pub(super) data: Vec<f32>,
+    /// Zarr array path the chunk belongs to (for prefix invalidation).
+    pub(super) path: Arc<str>,
+    /// ETag of the stored object when the chunk was read, if known.
+    pub(super) etag: Option<String>,
+    /// When the chunk was fetched or last confirmed fresh.
+    pub(super) validated_at: Instant,
+    /// Fetched by prefetching and not read since.
+    pub(super) prefetched: bool,
+}
+
+/// ETag revalidation policy for cached chunks.
+#[derive(Clone)]
+pub struct Revalidation {
+    /// Source of current object ETags.
+    pub versions: Arc<dyn ObjectVersions>,
+    /// How long a chunk is trusted before it is revalidated.
+    pub after: Duration,
+}
+
 /// LRU cache for decompressed chunks with memory-bounded eviction.
+///
+/// With a disk tier, chunks evicted from memory are written to local disk
+/// and read back on a later miss instead of being refetched from storage.
 pub struct ChunkCache {
-    cache: LruCache<ChunkKey, Vec<f32>>,
+    cache: LruCache<ChunkKey, CachedChunk>,
     memory_limit: usize,
     current_memory: usize,
+    revalidation: Option<Revalidation>,
+    disk: Option<DiskTier>,
     hits: AtomicU64,
     misses: AtomicU64,
     evictions: AtomicU64,
+    disk_hits: AtomicU64,
+    prefetched: AtomicU64,
+    prefetch_hits: AtomicU64,
+    prefetch_misses: AtomicU64,
 }
 
 impl ChunkCache {
@@ -30,28 +66,73 @@ impl ChunkCache {
             cache: LruCache::new(NonZeroUsize::new(max_entries).unwrap()),
             memory_limit,
             current_memory: 0,
+            revalidation: None,
+            disk: None,
             hits: AtomicU64::new(0),
             misses: AtomicU64::new(0),
             evictions: AtomicU64::new(0),
+            disk_hits: AtomicU64::new(0),
+            prefetched: AtomicU64::new(0),
+            prefetch_hits: AtomicU64::new(0),
+            prefetch_misses: AtomicU64::new(0),
         }
     }
 
+    /// Enable ETag revalidation of cached chunks.
+    pub fn with_revalidation(mut self, versions: Arc<dyn ObjectVersions>, after: Duration) -> Self {
+        self.revalidation = Some(Revalidation { versions, after });
+        self
+    }
+
+    /// Spill evicted chunks to `dir`, keeping at most `max_bytes` there.
+    ///
+    /// The directory is created if needed, and chunk files left in it by a
+    /// previous process are deleted, so it must not be shared between
+    /// processes.
+    pub fn enable_disk_tier(
+        &mut self,
+        dir: impl AsRef<Path>,
+        max_bytes: u64,
+    ) -> std::io::Result<()> {
+        self.disk = Some(DiskTier::open(dir, max_bytes)?);
+        Ok(())
+    }
+
+    /// Get the revalidation policy, if enabled.
+    pub fn revalidation(&self) -> Option<&Revalidation> {
+        self.revalidation.as_ref()
+    }
+
     /// Try to get a chunk from the cache.
     ///
     /// Returns `Some(data)` if found (cache hit), `None` if not found (cache miss).
+    /// Chunks found in the disk tier are moved back into memory.
     pub fn get(&mut self, key: &ChunkKey) -> Option<&Vec<f32>> {
-        if let Some(data) = self.cache.get(key) {
-            self.hits.fetch_add(1, Ordering::Relaxed);
-            Some(data)
-        } else {
-            self.misses.fetch_add(1, Ordering::Relaxed);
-            None
+        if !self.cache.contains(key) {
+            match self.disk.as_mut().and_then(|disk| disk.take(key)) {
+                Some(entry) => {
+                    self.disk_hits.fetch_add(1, Ordering::Relaxed);
+                    self.put_entry(*key, entry);
+                }
+                None => {
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    return None;
+                }
+            }
+        }
+
+        let entry = self.cache.get_mut(key)?;
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        if entry.prefetched {
+            entry.prefetched = false;
+            self.prefetch_hits.fetch_add(1, Ordering::Relaxed);
         }
+        Some(&entry.data)
     }
 
-    /// Check if a key exists in the cache without updating LRU order.
+    /// Check if a key exists in memory or on disk without updating LRU order.
     pub fn contains(&self, key: &ChunkKey) -> bool {
-        self.cache.contains(key)
+        self.cache.contains(key) || self.disk.as_ref().is_some_and(|disk| disk.contains(key))
     }
 
     /// Insert a chunk into the cache.
@@ -59,22 +140,158 @@ impl ChunkCache {
     /// If the cache is at capacity, the least recently used entries
     /// will be evicted to make room.
     pub fn insert(&mut self, key: ChunkKey, data: Vec<f32>) {
-        let data_size = data.len() * std::mem::size_of::<f32>();
+        self.insert_versioned(key, "", data, None);
+    }
+
+    /// Insert a chunk along with its array path and storage ETag.
+    ///
+    /// The path enables `invalidate_prefix`; the ETag enables revalidation.
+    pub fn insert_versioned(
+        &mut self,
+        key: ChunkKey,
+        path: &str,
+        data: Vec<f32>,
+        etag: Option<String>,
+    ) {
+        // Replacing an entry must not double-count its memory
+        self.remove(&key);
+
+        let entry = CachedChunk {
+            data,
+            path: Arc::from(normalize_path(path)),
+            etag,
+            validated_at: Instant::now(),
+            prefetched: false,
+        };
+        self.put_entry(key, entry);
+    }
+
+    /// Insert a chunk fetched ahead of any read, unless it is already
+    /// cached (a read may have fetched it meanwhile).
+    ///
+    /// The chunk counts as a prefetch hit when a read first finds it, and
+    /// as a prefetch miss if it is evicted from memory before that.
+    /// Returns true if the chunk was inserted.
+    pub fn insert_prefetched(
+        &mut self,
+        key: ChunkKey,
+        path: &str,
+        data: Vec<f32>,
+        etag: Option<String>,
+    ) -> bool {
+        if self.contains(&key) {
+            return false;
+        }
+        let entry = CachedChunk {
+            data,
+            path: Arc::from(normalize_path(path)),
+            etag,
+            validated_at: Instant::now(),
+            prefetched: true,
+        };
+        self.put_entry(key, entry);
+        self.prefetched.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// Insert an entry that is not cached, evicting to make room.
+    fn put_entry(&mut self, key: ChunkKey, entry: CachedChunk) {
+        let data_size = entry.data.len() * std::mem::size_of::<f32>();
 
         // Evict if necessary to make room
         while self.current_memory + data_size > self.memory_limit && !self.cache.is_empty() {
-            if let Some((_, evicted)) = self.cache.pop_lru() {
-                let evicted_size = evicted.len() * std::mem::size_of::<f32>();
-                self.current_memory = self.current_memory.saturating_sub(evicted_size);
-                self.evictions.fetch_add(1, Ordering::Relaxed);
-            }
+            self.evict_lru();
         }
 
         // Only insert if the data fits (or cache was empty)
         if data_size <= self.memory_limit {
-            self.cache.put(key, data);
+            self.cache.put(key, entry);
             self.current_memory += data_size;
+        } else if let Some(disk) = &mut self.disk {
+            disk.spill(key, entry);
+        }
+    }
+
+    /// Evict the least recently used chunk, spilling it to disk if enabled.
+    fn evict_lru(&mut self) {
+        if let Some((key, evicted)) = self.cache.pop_lru() {
+            let evicted_size = evicted.data.len() * std::mem::size_of::<f32>();
+            self.current_memory = self.current_memory.saturating_sub(evicted_size);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+            if evicted.prefetched {
+                self.prefetch_misses.fetch_add(1, Ordering::Relaxed);
+            }
+            if let Some(disk) = &mut self.disk {
+                disk.spill(key, evicted);
+            }
+        }
+    }
+
+    /// ETag of a cached chunk that is due for revalidation.
+    ///
+    /// Returns None if revalidation is disabled, the chunk is not cached,
+    /// has no known ETag, or was validated within the staleness window.
+    /// Does not update LRU order or hit statistics.
+    pub fn revalidation_due(&self, key: &ChunkKey) -> Option<String> {
+        let after = self.revalidation.as_ref()?.after;
+        let (etag, validated_at) = match self.cache.peek(key) {
+            Some(entry) => (&entry.etag, entry.validated_at),
+            None => {
+                let spilled = self.disk.as_ref()?.peek(key)?;
+                (&spilled.etag, spilled.validated_at)
+            }
+        };
+        if validated_at.elapsed() < after {
+            return None;
+        }
+        etag.clone()
+    }
+
+    /// Mark a cached chunk as confirmed fresh, restarting its staleness window.
+    pub fn mark_validated(&mut self, key: &ChunkKey) {
+        if let Some(entry) = self.cache.peek_mut(key) {
+            entry.validated_at = Instant::now();
+        }
+    }
+
+    /// Remove a single chunk from memory and disk.
+    ///
+    /// Returns true if the chunk was cached.
+    pub fn remove(&mut self, key: &ChunkKey) -> bool {
+        let on_disk = self.disk.as_mut().is_some_and(|disk| disk.remove(key));
+        match self.cache.pop(key) {
+            Some(entry) => {
+                let size = entry.data.len() * std::mem::size_of::<f32>();
+                self.current_memory = self.current_memory.saturating_sub(size);
+                true
+            }
+            None => on_disk,
+        }
+    }
+
+    /// Remove all chunks whose array path starts with `prefix`.
+    ///
+    /// Used when ingestion overwrites an array (or a whole run directory).
+    /// Leading slashes are ignored on both sides. Returns the number of
+    /// chunks removed.
+    pub fn invalidate_prefix(&mut self, prefix: &str) -> usize {
+        let prefix = normalize_path(prefix);
+        if prefix.is_empty() {
+            return 0;
         }
+
+        let keys: Vec<ChunkKey> = self
+            .cache
+            .iter()
+            .filter(|(_, entry)| entry.path.starts_with(prefix))
+            .map(|(key, _)| *key)
+            .collect();
+
+        let spilled = match &mut self.disk {
+            Some(disk) => disk.invalidate_prefix(prefix),
+            None => 0,
+        };
+        keys.iter().filter(|key| self.remove(key)).count() + spilled
     }
 
     /// Get cache statistics.
@@ -85,27 +302,33 @@ impl ChunkCache {
             entries: self.cache.len(),
             memory_bytes: self.current_memory as u64,
             evictions: self.evictions.load(Ordering::Relaxed),
+            disk_hits: self.disk_hits.load(Ordering::Relaxed),
+            disk_entries: self.disk.as_ref().map_or(0, DiskTier::len),
+            disk_bytes: self.disk.as_ref().map_or(0, DiskTier::bytes),
+            prefetched: self.prefetched.load(Ordering::Relaxed),
+            prefetch_hits: self.prefetch_hits.load(Ordering::Relaxed),
+            prefetch_misses: self.prefetch_misses.load(Ordering::Relaxed),
         }
     }
 
-    /// Clear all entries from the cache.
+    /// Clear all entries from memory and disk.
     pub fn clear(&mut self) {
         self.cache.clear();
         self.current_memory = 0;
+        if let Some(disk) = &mut self.disk {
+            disk.clear();
+        }
     }
 
-    /// Evict entries to reach target memory usage.
+    /// Evict entries to reach target memory usage, spilling them to disk
+    /// if the disk tier is enabled.
     ///
     /// Returns the number of entries evicted.
     pub fn evict_to_target(&mut self, target_bytes: usize) -> usize {
         let mut evicted = 0;
         while self.current_memory > target_bytes && !self.cache.is_empty() {
-            if let Some((_, data)) = self.cache.pop_lru() {
-                let data_size = data.len() * std::mem::size_of::<f32>();
-                self.current_memory = self.current_memory.saturating_sub(data_size);
-                self.evictions.fetch_add(1, Ordering::Relaxed);
-                evicted += 1;
-            }
+            self.evict_lru();
+            evicted += 1;
         }
         evicted
     }
@@ -131,6 +354,11 @@ impl ChunkCache {
     }
 }
 
+/// Strip leading slashes so "/grids/..." and "grids/..." compare equal.
+fn normalize_path(path: &str) -> &str {
+    path.trim_start_matches('/')
+}
+
 /// Helper function to compute a hash for the zarr path.
 /// Used as part of the cache key to distinguish chunks from different grids.
 pub fn hash_path(path: &str) -> u64 {
@@ -203,6 +431,31 @@ mod tests {
         assert_eq!(stats.entries, 1);
     }
 
+    #[test]
+    fn test_prefetch_stats() {
+        // Room for two 16-byte chunks
+        let mut cache = ChunkCache::new(32);
+        let data: Vec<f32> = vec![1.0; 4];
+
+        assert!(cache.insert_prefetched((0, 0, 0), "a.zarr", data.clone(), None));
+        assert!(cache.insert_prefetched((0, 1, 0), "a.zarr", data.clone(), None));
+        // Already cached: not replaced or counted again
+        assert!(!cache.insert_prefetched((0, 1, 0), "a.zarr", data.clone(), None));
+
+        // Only the first read of a prefetched chunk is a prefetch hit
+        cache.get(&(0, 0, 0));
+        cache.get(&(0, 0, 0));
+
+        // (0, 1, 0) is least recently used and goes unread
+        cache.insert((0, 2, 0), data);
+
+        let stats = cache.stats();
+        assert_eq!(stats.prefetched, 2);
+        assert_eq!((stats.prefetch_hits, stats.prefetch_misses), (1, 1));
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.prefetch_hit_rate(), 0.5);
+    }
+
     #[test]
     fn test_cache_clear() {
         let mut cache = ChunkCache::new(1024 * 1024);
@@ -234,6 +487,106 @@ mod tests {
         assert!(cache.memory_usage() <= before / 2);
     }
 
+    #[test]
+    fn test_invalidate_prefix() {
+        let mut cache = ChunkCache::new(1024 * 1024);
+        let data: Vec<f32> = vec![1.0; 4];
+
+        let tmp = "/grids/gfs/20241212_00z/tmp_2m_f006.zarr/0";
+        let ugrd = "/grids/gfs/20241212_00z/ugrd_10m_f006.zarr/0";
+        cache.insert_versioned((hash_path(tmp), 0, 0), tmp, data.clone(), None);
+        cache.insert_versioned((hash_path(tmp), 1, 0), tmp, data.clone(), None);
+        cache.insert_versioned((hash_path(ugrd), 0, 0), ugrd, data.clone(), None);
+
+        let removed = cache.invalidate_prefix("grids/gfs/20241212_00z/tmp_2m_f006.zarr");
+        assert_eq!(removed, 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.memory_usage(), 16);
+
+        assert_eq!(cache.invalidate_prefix("/grids/gfs/20241212_00z/"), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_revalidation_due() {
+        use crate::cache::freshness::{Freshness, ObjectVersions};
+        use crate::error::Result;
+
+        struct NoVersions;
+
+        #[async_trait::async_trait]
+        impl ObjectVersions for NoVersions {
+            async fn etag(&self, _key: &str) -> Result<Option<String>> {
+                Ok(None)
+            }
+            async fn check(&self, _key: &str, _etag: &str) -> Result<Freshness> {
+                Ok(Freshness::NotModified)
+            }
+        }
+
+        let key = (0, 0, 0);
+        let data: Vec<f32> = vec![1.0; 4];
+
+        // Disabled: never due
+        let mut cache = ChunkCache::new(1024 * 1024);
+        cache.insert_versioned(key, "a.zarr", data.clone(), Some("\"v1\"".to_string()));
+        assert_eq!(cache.revalidation_due(&key), None);
+
+        // Zero window: due immediately, but only for chunks with an ETag
+        let mut cache =
+            ChunkCache::new(1024 * 1024).with_revalidation(Arc::new(NoVersions), Duration::ZERO);
+        cache.insert_versioned(key, "a.zarr", data.clone(), Some("\"v1\"".to_string()));
+        cache.insert_versioned((0, 1, 0), "a.zarr", data.clone(), None);
+        assert_eq!(cache.revalidation_due(&key), Some("\"v1\"".to_string()));
+        assert_eq!(cache.revalidation_due(&(0, 1, 0)), None);
+
+        // Long window: fresh after insert
+        let mut cache = ChunkCache::new(1024 * 1024)
+            .with_revalidation(Arc::new(NoVersions), Duration::from_secs(3600));
+        cache.insert_versioned(key, "a.zarr", data, Some("\"v1\"".to_string()));
+        assert_eq!(cache.revalidation_due(&key), None);
+    }
+
+    #[test]
+    fn test_disk_tier() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("stale.chunk"), b"old").unwrap();
+
+        // Memory holds one 16-byte chunk, disk two
+        let mut cache = ChunkCache::new(16);
+        cache.enable_disk_tier(dir.path(), 32).unwrap();
+        assert!(!dir.path().join("stale.chunk").exists());
+
+        let path = "grids/gfs/tmp.zarr";
+        for i in 0..4 {
+            let data = vec![i as f32; 4];
+            cache.insert_versioned((1, i, 0), path, data, Some(format!("v{}", i)));
+        }
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.disk_entries), (1, 2));
+        assert_eq!(stats.disk_bytes, 32);
+        assert!(!cache.contains(&(1, 0, 0)));
+        assert!(cache.contains(&(1, 1, 0)));
+
+        // A disk hit moves the chunk back to memory, spilling the resident one
+        assert_eq!(cache.get(&(1, 1, 0)), Some(&vec![1.0; 4]));
+        assert_eq!(cache.get(&(1, 3, 0)), Some(&vec![3.0; 4]));
+        assert!(cache.get(&(1, 0, 0)).is_none());
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.disk_hits, stats.misses), (2, 2, 1));
+        assert_eq!(stats.disk_entries, 2);
+
+        // Invalidation and clearing reach the disk tier
+        assert_eq!(cache.invalidate_prefix("/grids/gfs/"), 3);
+        assert_eq!(cache.stats().disk_bytes, 0);
+        cache.insert_versioned((1, 0, 0), path, vec![0.0; 4], None);
+        cache.evict_to_target(0);
+        assert_eq!(cache.stats().disk_entries, 1);
+        cache.clear();
+        assert!(!cache.contains(&(1, 0, 0)));
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
     #[test]
     fn test_hash_path() {
         let hash1 = hash_path("grids/gfs/20241212/TMP.zarr");
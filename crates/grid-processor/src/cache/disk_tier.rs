@@ -0,0 +1,203 @@
+//! Local disk tier for chunks evicted from the memory cache.
+//!
+//! Chunks are stored decompressed (raw little-endian f32), so a disk hit
+//! costs one local read instead of an object storage fetch plus
+//! decompression. The index lives in memory: files left by a previous
+//! process are deleted on open, since their ETags and array paths are
+//! unknown.
+
+use lru::LruCache;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::warn;
+
+use super::chunk_cache::{CachedChunk, ChunkKey};
+
+/// File extension of spilled chunks.
+const CHUNK_EXTENSION: &str = "chunk";
+
+/// A chunk on disk; everything but the data stays in memory.
+pub(super) struct SpilledChunk {
+    /// Number of f32 values in the file.
+    len: usize,
+    pub(super) path: Arc<str>,
+    pub(super) etag: Option<String>,
+    pub(super) validated_at: Instant,
+}
+
+impl SpilledChunk {
+    fn size(&self) -> u64 {
+        (self.len * std::mem::size_of::<f32>()) as u64
+    }
+}
+
+/// Size-capped LRU of chunk files in a local directory.
+pub(super) struct DiskTier {
+    dir: PathBuf,
+    max_bytes: u64,
+    current_bytes: u64,
+    index: LruCache<ChunkKey, SpilledChunk>,
+}
+
+impl DiskTier {
+    /// Open (creating if needed) a disk tier in `dir`, holding at most
+    /// `max_bytes` of chunk data.
+    pub(super) fn open(dir: impl AsRef<Path>, max_bytes: u64) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == CHUNK_EXTENSION) {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(Self {
+            dir,
+            max_bytes,
+            current_bytes: 0,
+            index: LruCache::unbounded(),
+        })
+    }
+
+    /// Write an evicted chunk to disk, dropping the least recently spilled
+    /// chunks to stay under the size cap. Write failures drop the chunk.
+    pub(super) fn spill(&mut self, key: ChunkKey, chunk: CachedChunk) {
+        let spilled = SpilledChunk {
+            len: chunk.data.len(),
+            path: chunk.path,
+            etag: chunk.etag,
+            validated_at: chunk.validated_at,
+        };
+        let size = spilled.size();
+        if size > self.max_bytes {
+            return;
+        }
+
+        self.remove(&key);
+        while self.current_bytes + size > self.max_bytes {
+            let Some((lru_key, lru)) = self.index.pop_lru() else {
+                break;
+            };
+            self.delete_file(&lru_key, &lru);
+        }
+
+        let bytes: Vec<u8> = chunk.data.iter().flat_map(|v| v.to_le_bytes()).collect();
+        if let Err(e) = fs::write(self.file_path(&key), bytes) {
+            warn!(dir = %self.dir.display(), error = %e, "Failed to spill chunk to disk");
+            return;
+        }
+        self.current_bytes += size;
+        self.index.put(key, spilled);
+    }
+
+    /// Read a chunk back and remove it from disk (it returns to memory).
+    ///
+    /// Unreadable or truncated files count as a miss.
+    pub(super) fn take(&mut self, key: &ChunkKey) -> Option<CachedChunk> {
+        let spilled = self.index.pop(key)?;
+        let file = self.file_path(key);
+        let bytes = fs::read(&file);
+        self.delete_file(key, &spilled);
+
+        let bytes = match bytes {
+            Ok(bytes) if bytes.len() as u64 == spilled.size() => bytes,
+            Ok(bytes) => {
+                warn!(
+                    file = %file.display(),
+                    expected = spilled.size(),
+                    actual = bytes.len(),
+                    "Spilled chunk has the wrong size, discarding"
+                );
+                return None;
+            }
+            Err(e) => {
+                warn!(file = %file.display(), error = %e, "Failed to read spilled chunk");
+                return None;
+            }
+        };
+
+        let data = bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        Some(CachedChunk {
+            data,
+            path: spilled.path,
+            etag: spilled.etag,
+            validated_at: spilled.validated_at,
+            prefetched: false,
+        })
+    }
+
+    /// Look up a spilled chunk without reading it or updating LRU order.
+    pub(super) fn peek(&self, key: &ChunkKey) -> Option<&SpilledChunk> {
+        self.index.peek(key)
+    }
+
+    /// Check if a chunk is on disk without updating LRU order.
+    pub(super) fn contains(&self, key: &ChunkKey) -> bool {
+        self.index.contains(key)
+    }
+
+    /// Remove a single chunk. Returns true if it was on disk.
+    pub(super) fn remove(&mut self, key: &ChunkKey) -> bool {
+        match self.index.pop(key) {
+            Some(spilled) => {
+                self.delete_file(key, &spilled);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove all chunks whose array path starts with `prefix` (already
+    /// normalized). Returns the number removed.
+    pub(super) fn invalidate_prefix(&mut self, prefix: &str) -> usize {
+        let keys: Vec<ChunkKey> = self
+            .index
+            .iter()
+            .filter(|(_, spilled)| spilled.path.starts_with(prefix))
+            .map(|(key, _)| *key)
+            .collect();
+
+        keys.iter().filter(|key| self.remove(key)).count()
+    }
+
+    /// Remove every chunk.
+    pub(super) fn clear(&mut self) {
+        while let Some((key, spilled)) = self.index.pop_lru() {
+            self.delete_file(&key, &spilled);
+        }
+    }
+
+    /// Number of chunks on disk.
+    pub(super) fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Bytes of chunk data on disk.
+    pub(super) fn bytes(&self) -> u64 {
+        self.current_bytes
+    }
+
+    fn file_path(&self, key: &ChunkKey) -> PathBuf {
+        let (hash, x, y) = key;
+        self.dir
+            .join(format!("{:016x}_{}_{}.{}", hash, x, y, CHUNK_EXTENSION))
+    }
+
+    fn delete_file(&mut self, key: &ChunkKey, spilled: &SpilledChunk) {
+        self.current_bytes = self.current_bytes.saturating_sub(spilled.size());
+        let file = self.file_path(key);
+        if let Err(e) = fs::remove_file(&file) {
+            if e.kind() != io::ErrorKind::NotFound {
+                warn!(file = %file.display(), error = %e, "Failed to delete spilled chunk");
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Chunk freshness checks against object storage using ETags.
+//!
+//! When a Zarr array is rewritten in place (e.g. a model run re-ingested),
+//! cached chunks for that path become stale. The chunk cache records the
+//! ETag of the stored object a chunk was read from; after a configurable
+//! staleness window the processor revalidates it with a conditional
+//! `If-None-Match` request, which costs a single round trip and no body.
+
+use async_trait::async_trait;
+use object_store::path::Path;
+use object_store::{GetOptions, ObjectStore};
+use std::sync::Arc;
+
+use crate::error::{GridProcessorError, Result};
+
+/// Result of revalidating a cached object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Freshness {
+    /// The stored object still has the cached ETag.
+    NotModified,
+    /// The object changed; carries the new ETag if the backend reports one.
+    Modified(Option<String>),
+    /// The object no longer exists.
+    Missing,
+}
+
+/// Source of object versions (ETags) for chunk revalidation.
+#[async_trait]
+pub trait ObjectVersions: Send + Sync {
+    /// Current ETag of the object at `key`, if the backend reports one.
+    async fn etag(&self, key: &str) -> Result<Option<String>>;
+
+    /// Check whether the object at `key` still has `etag`.
+    async fn check(&self, key: &str, etag: &str) -> Result<Freshness>;
+}
+
+/// [`ObjectVersions`] backed by an `object_store` client (MinIO/S3).
+pub struct ObjectStoreVersions {
+    store: Arc<dyn ObjectStore>,
+}
+
+impl ObjectStoreVersions {
+    /// Create a version source from an object store client.
+    pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+        Self { store }
+    }
+
+    fn path(key: &str) -> Path {
+        Path::from(key.trim_start_matches('/'))
+    }
+}
+
+#[async_trait]
+impl ObjectVersions for ObjectStoreVersions {
+    async fn etag(&self, key: &str) -> Result<Option<String>> {
+        match self.store.head(&Self::path(key)).await {
+            Ok(meta) => Ok(meta.e_tag),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(GridProcessorError::Storage(e.to_string())),
+        }
+    }
+
+    async fn check(&self, key: &str, etag: &str) -> Result<Freshness> {
+        let options = GetOptions {
+            if_none_match: Some(etag.to_string()),
+            head: true,
+            ..Default::default()
+        };
+
+        match self.store.get_opts(&Self::path(key), options).await {
+            Ok(result) => Ok(Freshness::Modified(result.meta.e_tag)),
+            Err(object_store::Error::NotModified { .. }) => Ok(Freshness::NotModified),
+            Err(object_store::Error::NotFound { .. }) => Ok(Freshness::Missing),
+            Err(e) => Err(GridProcessorError::Storage(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn test_object_store_versions_detects_overwrite() {
+        let store = Arc::new(InMemory::new());
+        let key = "grids/gfs/run/tmp.zarr/0/c/0/0";
+        store
+            .put(&Path::from(key), Bytes::from_static(b"v1"))
+            .await
+            .unwrap();
+
+        let versions = ObjectStoreVersions::new(store.clone());
+        let etag = versions.etag(key).await.unwrap().expect("etag");
+        assert_eq!(
+            versions.check(key, &etag).await.unwrap(),
+            Freshness::NotModified
+        );
+
+        store
+            .put(&Path::from(key), Bytes::from_static(b"v2"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            versions.check(key, &etag).await.unwrap(),
+            Freshness::Modified(Some(_))
+        ));
+
+        store.delete(&Path::from(key)).await.unwrap();
+        assert_eq!(
+            versions.check(key, &etag).await.unwrap(),
+            Freshness::Missing
+        );
+    }
+}
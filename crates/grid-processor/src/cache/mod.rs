@@ -1,5 +1,8 @@
 //! Cache implementations for grid processing.
 
 mod chunk_cache;
+mod disk_tier;
+pub mod freshness;
 
-pub use chunk_cache::{hash_path, ChunkCache, ChunkKey};
+pub use chunk_cache::{hash_path, ChunkCache, ChunkKey, Revalidation};
+pub use freshness::{Freshness, ObjectStoreVersions, ObjectVersions};
@@ -16,7 +16,7 @@ pub struct GridProcessorConfig {
     /// Compression codec for Zarr files.
     pub zarr_compression: ZarrCompression,
 
-    /// Compression level (1-9).
+    /// Compression level (1-9 for Blosc, 1-22 for zstd).
     pub zarr_compression_level: u8,
 
     /// Enable byte shuffle filter for better compression.
@@ -24,6 +24,66 @@ pub struct GridProcessorConfig {
 
     /// Interpolation method for grid resampling.
     pub interpolation: InterpolationMethod,
+
+    /// Revalidate cached chunks against object storage ETags once they are
+    /// older than this many seconds. None disables revalidation.
+    #[serde(default)]
+    pub chunk_revalidate_secs: Option<u64>,
+
+    /// Directory for chunks evicted from the memory cache. None disables
+    /// the disk tier.
+    #[serde(default)]
+    pub chunk_disk_cache_dir: Option<String>,
+
+    /// Size cap of the chunk disk tier in megabytes.
+    #[serde(default = "default_chunk_disk_cache_size_mb")]
+    pub chunk_disk_cache_size_mb: usize,
+
+    /// Append a CRC32C checksum to every chunk written.
+    #[serde(default = "default_true")]
+    pub zarr_checksums: bool,
+
+    /// What to do when a chunk read from storage fails its checksum.
+    #[serde(default)]
+    pub checksum_policy: ChecksumPolicy,
+
+    /// How grids are divided into chunks (per parameter in model configs).
+    #[serde(default)]
+    pub chunk_layout: ChunkLayout,
+
+    /// Chunks one region read fetches from storage at the same time.
+    #[serde(default = "default_max_concurrent_chunk_fetches")]
+    pub max_concurrent_chunk_fetches: usize,
+
+    /// Rings of neighbouring tiles whose chunks are prefetched after a tile
+    /// is read. 0 disables chunk prefetching.
+    #[serde(default = "default_chunk_prefetch_rings")]
+    pub chunk_prefetch_rings: usize,
+
+    /// Most chunks one prefetch fetches, so tiles at low zoom don't warm
+    /// most of the grid.
+    #[serde(default = "default_chunk_prefetch_max_chunks")]
+    pub chunk_prefetch_max_chunks: usize,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_chunk_disk_cache_size_mb() -> usize {
+    4096
+}
+
+fn default_max_concurrent_chunk_fetches() -> usize {
+    8
+}
+
+fn default_chunk_prefetch_rings() -> usize {
+    1
+}
+
+fn default_chunk_prefetch_max_chunks() -> usize {
+    16
 }
 
 impl Default for GridProcessorConfig {
@@ -35,6 +95,15 @@ impl Default for GridProcessorConfig {
             zarr_compression_level: 1,
             zarr_shuffle: true,
             interpolation: InterpolationMethod::Bilinear,
+            chunk_revalidate_secs: None,
+            chunk_disk_cache_dir: None,
+            chunk_disk_cache_size_mb: default_chunk_disk_cache_size_mb(),
+            zarr_checksums: true,
+            checksum_policy: ChecksumPolicy::Reject,
+            chunk_layout: ChunkLayout::Square,
+            max_concurrent_chunk_fetches: default_max_concurrent_chunk_fetches(),
+            chunk_prefetch_rings: default_chunk_prefetch_rings(),
+            chunk_prefetch_max_chunks: default_chunk_prefetch_max_chunks(),
         }
     }
 }
@@ -74,6 +143,50 @@ impl GridProcessorConfig {
             config.interpolation = InterpolationMethod::from_str(&val);
         }
 
+        if let Ok(val) = std::env::var("CHUNK_REVALIDATE_SECS") {
+            if let Ok(secs) = val.parse() {
+                config.chunk_revalidate_secs = Some(secs);
+            }
+        }
+
+        if let Ok(val) = std::env::var("CHUNK_DISK_CACHE_DIR") {
+            if !val.is_empty() {
+                config.chunk_disk_cache_dir = Some(val);
+            }
+        }
+
+        if let Ok(val) = std::env::var("CHUNK_DISK_CACHE_SIZE_MB") {
+            if let Ok(size) = val.parse() {
+                config.chunk_disk_cache_size_mb = size;
+            }
+        }
+
+        if let Ok(val) = std::env::var("ZARR_CHECKSUMS") {
+            config.zarr_checksums = val.to_lowercase() == "true" || val == "1";
+        }
+
+        if let Ok(val) = std::env::var("CHUNK_CHECKSUM_POLICY") {
+            config.checksum_policy = ChecksumPolicy::parse(&val);
+        }
+
+        if let Ok(val) = std::env::var("MAX_CONCURRENT_CHUNK_FETCHES") {
+            if let Ok(count) = val.parse() {
+                config.max_concurrent_chunk_fetches = count;
+            }
+        }
+
+        if let Ok(val) = std::env::var("CHUNK_PREFETCH_RINGS") {
+            if let Ok(rings) = val.parse() {
+                config.chunk_prefetch_rings = rings;
+            }
+        }
+
+        if let Ok(val) = std::env::var("CHUNK_PREFETCH_MAX_CHUNKS") {
+            if let Ok(count) = val.parse() {
+                config.chunk_prefetch_max_chunks = count;
+            }
+        }
+
         config
     }
 
@@ -87,8 +200,20 @@ impl GridProcessorConfig {
             return Err("zarr_chunk_size must be > 0".to_string());
         }
 
-        if self.zarr_compression_level == 0 || self.zarr_compression_level > 9 {
-            return Err("zarr_compression_level must be 1-9".to_string());
+        if self.max_concurrent_chunk_fetches == 0 {
+            return Err("max_concurrent_chunk_fetches must be > 0".to_string());
+        }
+
+        self.chunk_layout.validate()?;
+
+        let levels = self.zarr_compression.levels();
+        if !levels.contains(&self.zarr_compression_level) {
+            return Err(format!(
+                "zarr_compression_level must be {}-{} for {}",
+                levels.start(),
+                levels.end(),
+                self.zarr_compression
+            ));
         }
 
         Ok(())
@@ -98,6 +223,17 @@ impl GridProcessorConfig {
     pub fn chunk_cache_size_bytes(&self) -> usize {
         self.chunk_cache_size_mb * 1024 * 1024
     }
+
+    /// Get the chunk disk tier size cap in bytes.
+    pub fn chunk_disk_cache_size_bytes(&self) -> u64 {
+        self.chunk_disk_cache_size_mb as u64 * 1024 * 1024
+    }
+
+    /// Get the chunk revalidation window, if enabled.
+    pub fn chunk_revalidate_after(&self) -> Option<std::time::Duration> {
+        self.chunk_revalidate_secs
+            .map(std::time::Duration::from_secs)
+    }
 }
 
 /// Compression codec for Zarr files.
@@ -105,11 +241,13 @@ impl GridProcessorConfig {
 pub enum ZarrCompression {
     /// No compression.
     None,
-    /// LZ4 compression.
+    /// LZ4 compression (written as Blosc with LZ4, as Zarr V3 has no
+    /// standalone LZ4 codec).
     Lz4,
-    /// Zstd compression.
+    /// Plain zstd, without Blosc's shuffle. Levels above 9 trade slower
+    /// writes for smaller chunks; decompression speed barely changes.
     Zstd,
-    /// Blosc with LZ4.
+    /// Blosc with LZ4: fastest to decompress.
     BloscLz4,
     /// Blosc with Zstd (recommended).
     BloscZstd,
@@ -122,18 +260,23 @@ impl Default for ZarrCompression {
 }
 
 impl ZarrCompression {
-    /// Parse from string (case-insensitive).
-    pub fn from_str(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "none" => Self::None,
-            "lz4" => Self::Lz4,
-            "zstd" => Self::Zstd,
-            "blosc_lz4" => Self::BloscLz4,
-            "blosc_zstd" => Self::BloscZstd,
-            _ => Self::BloscZstd,
+    /// Parse from string (case-insensitive, `-` or `_` separated).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "none" => Some(Self::None),
+            "lz4" => Some(Self::Lz4),
+            "zstd" => Some(Self::Zstd),
+            "blosc_lz4" => Some(Self::BloscLz4),
+            "blosc_zstd" => Some(Self::BloscZstd),
+            _ => None,
         }
     }
 
+    /// Parse from string (case-insensitive), defaulting to `BloscZstd`.
+    pub fn from_str(s: &str) -> Self {
+        Self::parse(s).unwrap_or_default()
+    }
+
     /// Get the codec name as a string.
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -144,6 +287,14 @@ impl ZarrCompression {
             Self::BloscZstd => "blosc_zstd",
         }
     }
+
+    /// Valid compression levels for this codec.
+    pub fn levels(&self) -> std::ops::RangeInclusive<u8> {
+        match self {
+            Self::Zstd => 1..=22,
+            _ => 1..=9,
+        }
+    }
 }
 
 impl std::fmt::Display for ZarrCompression {
@@ -152,6 +303,144 @@ impl std::fmt::Display for ZarrCompression {
     }
 }
 
+/// Handling of chunks whose checksum doesn't match their contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecksumPolicy {
+    /// Fail the read, so corrupt data is never rendered.
+    #[default]
+    Reject,
+    /// Log a warning and decode the chunk anyway.
+    Warn,
+}
+
+impl ChecksumPolicy {
+    /// Parse from string (case-insensitive), defaulting to `Reject`.
+    pub fn parse(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "warn" => Self::Warn,
+            _ => Self::Reject,
+        }
+    }
+
+    /// Get the policy name as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Warn => "warn",
+        }
+    }
+}
+
+// ============================================================================
+// Chunk Layout
+// ============================================================================
+
+/// Chunk edge [`ChunkLayout::Auto`] uses for time-series access.
+pub const TIME_SERIES_CHUNK_SIZE: usize = 128;
+
+/// Most inner chunks a shard may hold. The shard index has an entry per
+/// chunk and is fetched before any data, so it has to stay small.
+pub const MAX_CHUNKS_PER_SHARD: usize = 65_536;
+
+/// How a parameter is expected to be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessPattern {
+    /// Map tiles: rectangular windows of a single time step.
+    #[default]
+    Tiles,
+    /// EDR position and time-series queries: a few cells from every
+    /// forecast hour, each of which is a separate array.
+    TimeSeries,
+}
+
+impl AccessPattern {
+    /// Parse from string (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "tiles" => Some(Self::Tiles),
+            "time_series" => Some(Self::TimeSeries),
+            _ => None,
+        }
+    }
+}
+
+/// How grids are divided into chunks within their shard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkLayout {
+    /// Square chunks of `zarr_chunk_size` at every level.
+    #[default]
+    Square,
+    /// The same explicit chunk shape at every level.
+    Fixed { width: usize, height: usize },
+    /// Picked for each level from its size and the expected access.
+    ///
+    /// Tiles keep `zarr_chunk_size` squares; time series use
+    /// [`TIME_SERIES_CHUNK_SIZE`] squares so a point read decodes as
+    /// little of each forecast hour as possible. Chunks never extend past
+    /// a grid smaller than them.
+    Auto(AccessPattern),
+}
+
+impl ChunkLayout {
+    /// Chunk shape (width, height) for a grid of the given size.
+    pub fn chunk_shape(&self, chunk_size: usize, width: usize, height: usize) -> (usize, usize) {
+        match *self {
+            Self::Square => (chunk_size, chunk_size),
+            Self::Fixed { width, height } => (width, height),
+            Self::Auto(access) => {
+                let edge = match access {
+                    AccessPattern::Tiles => chunk_size,
+                    AccessPattern::TimeSeries => TIME_SERIES_CHUNK_SIZE.min(chunk_size),
+                };
+                (edge.min(width.max(1)), edge.min(height.max(1)))
+            }
+        }
+    }
+
+    /// Check that the layout describes a usable chunk shape.
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Self::Fixed { width, height } if width == 0 || height == 0 => {
+                Err("chunk_layout width and height must be > 0".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a chunk shape against the shard holding a `width` x `height`
+    /// grid, which is padded out to a whole number of chunks.
+    pub fn validate_shard(
+        chunk_shape: (usize, usize),
+        width: usize,
+        height: usize,
+    ) -> Result<(), String> {
+        let (chunk_w, chunk_h) = chunk_shape;
+        if chunk_w == 0 || chunk_h == 0 {
+            return Err("chunk width and height must be > 0".to_string());
+        }
+        let chunks = width.div_ceil(chunk_w) * height.div_ceil(chunk_h);
+        if chunks > MAX_CHUNKS_PER_SHARD {
+            return Err(format!(
+                "{}x{} chunks split a {}x{} shard into {} chunks (at most {})",
+                chunk_w, chunk_h, width, height, chunks, MAX_CHUNKS_PER_SHARD
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the layout name as a string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Square => "square",
+            Self::Fixed { .. } => "fixed",
+            Self::Auto(_) => "auto",
+        }
+    }
+}
+
 // ============================================================================
 // Pyramid Configuration
 // ============================================================================
@@ -171,6 +460,9 @@ pub struct PyramidConfig {
 
     /// Default downsampling method (can be overridden per parameter).
     pub default_method: DownsampleMethod,
+
+    /// Maximum number of levels including native (None = until min_dimension).
+    pub max_levels: Option<usize>,
 }
 
 impl Default for PyramidConfig {
@@ -180,6 +472,7 @@ impl Default for PyramidConfig {
             min_dimension: 256,
             downscale_factor: 2,
             default_method: DownsampleMethod::Mean,
+            max_levels: None,
         }
     }
 }
@@ -205,6 +498,12 @@ impl PyramidConfig {
             }
         }
 
+        if let Ok(val) = std::env::var("PYRAMID_MAX_LEVELS") {
+            if let Ok(levels) = val.parse() {
+                config.max_levels = Some(levels);
+            }
+        }
+
         if let Ok(val) = std::env::var("PYRAMID_DOWNSAMPLE_METHOD") {
             config.default_method = match val.to_lowercase().as_str() {
                 "max" => DownsampleMethod::Max,
@@ -226,6 +525,10 @@ impl PyramidConfig {
             return Err("pyramid downscale_factor must be >= 2".to_string());
         }
 
+        if self.max_levels == Some(0) {
+            return Err("pyramid max_levels must be >= 1".to_string());
+        }
+
         Ok(())
     }
 
@@ -241,7 +544,8 @@ impl PyramidConfig {
         let mut w = width;
         let mut h = height;
 
-        while w.min(h) >= self.min_dimension {
+        let max_levels = self.max_levels.unwrap_or(usize::MAX);
+        while levels < max_levels && w.min(h) >= self.min_dimension {
             w /= self.downscale_factor;
             h /= self.downscale_factor;
             if w > 0 && h > 0 {
@@ -266,6 +570,13 @@ mod tests {
         assert_eq!(config.zarr_compression_level, 1);
         assert!(config.zarr_shuffle);
         assert_eq!(config.interpolation, InterpolationMethod::Bilinear);
+        assert!(config.zarr_checksums);
+        assert_eq!(config.checksum_policy, ChecksumPolicy::Reject);
+        assert_eq!(config.chunk_disk_cache_dir, None);
+        assert_eq!(config.chunk_disk_cache_size_bytes(), 4096 * 1024 * 1024);
+        assert_eq!(config.max_concurrent_chunk_fetches, 8);
+        assert_eq!(config.chunk_prefetch_rings, 1);
+        assert_eq!(config.chunk_prefetch_max_chunks, 16);
     }
 
     #[test]
@@ -280,12 +591,102 @@ mod tests {
         config.zarr_chunk_size = 0;
         assert!(config.validate().is_err());
 
+        config = GridProcessorConfig::default();
+        config.max_concurrent_chunk_fetches = 0;
+        assert!(config.validate().is_err());
+
         config = GridProcessorConfig::default();
         config.zarr_compression_level = 0;
         assert!(config.validate().is_err());
 
         config.zarr_compression_level = 10;
         assert!(config.validate().is_err());
+
+        // zstd accepts its full level range
+        config.zarr_compression = ZarrCompression::Zstd;
+        assert!(config.validate().is_ok());
+        config.zarr_compression_level = 23;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_chunk_layout_shapes() {
+        assert_eq!(ChunkLayout::Square.chunk_shape(512, 100, 50), (512, 512));
+        assert_eq!(
+            ChunkLayout::Fixed {
+                width: 1024,
+                height: 64
+            }
+            .chunk_shape(512, 1799, 1059),
+            (1024, 64)
+        );
+
+        let tiles = ChunkLayout::Auto(AccessPattern::Tiles);
+        assert_eq!(tiles.chunk_shape(512, 1799, 1059), (512, 512));
+        assert_eq!(tiles.chunk_shape(512, 225, 132), (225, 132));
+
+        let series = ChunkLayout::Auto(AccessPattern::TimeSeries);
+        assert_eq!(series.chunk_shape(512, 1799, 1059), (128, 128));
+        assert_eq!(series.chunk_shape(512, 100, 1059), (100, 128));
+        assert_eq!(series.chunk_shape(64, 1799, 1059), (64, 64));
+    }
+
+    #[test]
+    fn test_chunk_layout_validation() {
+        let mut config = GridProcessorConfig {
+            chunk_layout: ChunkLayout::Fixed {
+                width: 0,
+                height: 64,
+            },
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        config.chunk_layout = ChunkLayout::Auto(AccessPattern::TimeSeries);
+        assert!(config.validate().is_ok());
+
+        assert!(ChunkLayout::validate_shard((512, 512), 1799, 1059).is_ok());
+        assert!(ChunkLayout::validate_shard((1024, 1024), 100, 100).is_ok());
+        assert!(ChunkLayout::validate_shard((0, 512), 1799, 1059).is_err());
+        // 1799 * 1059 single-cell chunks
+        assert!(ChunkLayout::validate_shard((1, 1), 1799, 1059).is_err());
+    }
+
+    #[test]
+    fn test_chunk_layout_serialization() {
+        let layouts = [
+            (ChunkLayout::Square, serde_json::json!("square")),
+            (
+                ChunkLayout::Fixed {
+                    width: 256,
+                    height: 128,
+                },
+                serde_json::json!({"fixed": {"width": 256, "height": 128}}),
+            ),
+            (
+                ChunkLayout::Auto(AccessPattern::TimeSeries),
+                serde_json::json!({"auto": "time_series"}),
+            ),
+        ];
+        for (layout, json) in layouts {
+            assert_eq!(serde_json::to_value(layout).unwrap(), json);
+            assert_eq!(serde_json::from_value::<ChunkLayout>(json).unwrap(), layout);
+        }
+    }
+
+    #[test]
+    fn test_pyramid_max_levels() {
+        let mut config = PyramidConfig {
+            min_dimension: 64,
+            ..Default::default()
+        };
+        assert_eq!(config.calculate_num_levels(1024, 512), 5);
+
+        config.max_levels = Some(2);
+        assert_eq!(config.calculate_num_levels(1024, 512), 2);
+
+        config.max_levels = Some(0);
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -301,9 +702,36 @@ mod tests {
             ZarrCompression::from_str("BLOSC_ZSTD"),
             ZarrCompression::BloscZstd
         );
+        assert_eq!(
+            ZarrCompression::from_str("blosc-lz4"),
+            ZarrCompression::BloscLz4
+        );
         assert_eq!(
             ZarrCompression::from_str("invalid"),
             ZarrCompression::BloscZstd
         );
+        assert_eq!(ZarrCompression::parse("invalid"), None);
+    }
+
+    #[test]
+    fn test_checksum_policy_parse() {
+        assert_eq!(ChecksumPolicy::parse("WARN"), ChecksumPolicy::Warn);
+        assert_eq!(ChecksumPolicy::parse("reject"), ChecksumPolicy::Reject);
+        assert_eq!(ChecksumPolicy::parse("invalid"), ChecksumPolicy::Reject);
+    }
+
+    #[test]
+    fn test_checksum_settings_default_when_missing() {
+        // Configs serialized before checksums existed
+        let mut value = serde_json::to_value(GridProcessorConfig::default()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("zarr_checksums");
+        object.remove("checksum_policy");
+        object.remove("chunk_layout");
+
+        let config: GridProcessorConfig = serde_json::from_value(value).unwrap();
+        assert!(config.zarr_checksums);
+        assert_eq!(config.checksum_policy, ChecksumPolicy::Reject);
+        assert_eq!(config.chunk_layout, ChunkLayout::Square);
     }
 }
@@ -0,0 +1,209 @@
+//! Parameters computed on the fly from stored ones.
+//!
+//! A [`DerivedParameter`] names its input parameters and combines their
+//! values cell by cell. [`GridDataService`](crate::GridDataService) falls back
+//! to one when the catalog has no dataset for the queried parameter, reading
+//! the inputs at the query's level and time through the shared chunk cache,
+//! so e.g. `WIND_SPEED` tiles reuse chunks already fetched for `UGRD` and
+//! `VGRD` layers.
+//!
+//! A cell is NaN if any input is NaN there.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{GridProcessorError, Result};
+
+/// Parameter computed from other parameters at the same level and time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DerivedParameter {
+    /// Wind speed sqrt(U² + V²) in m/s, from UGRD and VGRD
+    WindSpeed,
+    /// Temperature minus dew point in K, from TMP and DPT
+    DewpointDepression,
+    /// NWS heat index in K, from TMP (K) and RH (%)
+    HeatIndex,
+}
+
+impl DerivedParameter {
+    /// All derived parameters.
+    pub const ALL: [Self; 3] = [Self::WindSpeed, Self::DewpointDepression, Self::HeatIndex];
+
+    /// Look up a derived parameter by name (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Parameter name used in queries and layer configs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::WindSpeed => "WIND_SPEED",
+            Self::DewpointDepression => "DEWPOINT_DEPRESSION",
+            Self::HeatIndex => "HEAT_INDEX",
+        }
+    }
+
+    /// Stored parameters read to compute this one, in the order
+    /// [`compute`](Self::compute) takes them.
+    pub fn inputs(&self) -> &'static [&'static str] {
+        match self {
+            Self::WindSpeed => &["UGRD", "VGRD"],
+            Self::DewpointDepression => &["TMP", "DPT"],
+            Self::HeatIndex => &["TMP", "RH"],
+        }
+    }
+
+    /// Units of the computed values.
+    pub fn units(&self) -> &'static str {
+        match self {
+            Self::WindSpeed => "m/s",
+            Self::DewpointDepression | Self::HeatIndex => "K",
+        }
+    }
+
+    /// Compute one cell from its input values (one per [`inputs`](Self::inputs)).
+    pub fn compute(&self, values: &[f32]) -> f32 {
+        if values.len() != self.inputs().len() || values.iter().any(|v| v.is_nan()) {
+            return f32::NAN;
+        }
+        match self {
+            Self::WindSpeed => values[0].hypot(values[1]),
+            Self::DewpointDepression => values[0] - values[1],
+            Self::HeatIndex => heat_index(values[0], values[1]),
+        }
+    }
+
+    /// Combine equally sized input grids cell by cell.
+    pub fn combine(&self, grids: &[&[f32]]) -> Result<Vec<f32>> {
+        if grids.len() != self.inputs().len() {
+            return Err(GridProcessorError::ConfigError(format!(
+                "{} needs {} input grids, got {}",
+                self.name(),
+                self.inputs().len(),
+                grids.len()
+            )));
+        }
+        let len = grids[0].len();
+        if grids.iter().any(|g| g.len() != len) {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "{} input grids differ in size",
+                self.name()
+            )));
+        }
+
+        let mut values = vec![0.0; grids.len()];
+        Ok((0..len)
+            .map(|i| {
+                for (value, grid) in values.iter_mut().zip(grids) {
+                    *value = grid[i];
+                }
+                self.compute(&values)
+            })
+            .collect())
+    }
+}
+
+impl fmt::Display for DerivedParameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DerivedParameter {
+    type Err = GridProcessorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s).ok_or_else(|| {
+            GridProcessorError::ConfigError(format!("unknown derived parameter '{}'", s))
+        })
+    }
+}
+
+/// NWS heat index (Rothfusz regression with its low and high humidity
+/// adjustments) from temperature in K and relative humidity in %.
+///
+/// Below about 80 °F the simple Steadman formula is used, as the NWS does.
+fn heat_index(temperature_k: f32, rh: f32) -> f32 {
+    let t = (temperature_k as f64 - 273.15) * 9.0 / 5.0 + 32.0;
+    let rh = (rh as f64).clamp(0.0, 100.0);
+
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    let hi = if (simple + t) / 2.0 < 80.0 {
+        simple
+    } else {
+        let mut hi = -42.379 + 2.049_015_23 * t + 10.143_331_27 * rh
+            - 0.224_755_41 * t * rh
+            - 0.006_837_83 * t * t
+            - 0.054_817_17 * rh * rh
+            + 0.001_228_74 * t * t * rh
+            + 0.000_852_82 * t * rh * rh
+            - 0.000_001_99 * t * t * rh * rh;
+        if rh < 13.0 && (80.0..=112.0).contains(&t) {
+            hi -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+        } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+            hi += (rh - 85.0) / 10.0 * (87.0 - t) / 5.0;
+        }
+        hi
+    };
+
+    ((hi - 32.0) * 5.0 / 9.0 + 273.15) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fahrenheit_to_k(f: f32) -> f32 {
+        (f - 32.0) * 5.0 / 9.0 + 273.15
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            DerivedParameter::parse("wind_speed"),
+            Some(DerivedParameter::WindSpeed)
+        );
+        assert_eq!(
+            "HEAT_INDEX".parse::<DerivedParameter>().unwrap(),
+            DerivedParameter::HeatIndex
+        );
+        assert_eq!(DerivedParameter::parse("TMP"), None);
+        for parameter in DerivedParameter::ALL {
+            assert_eq!(DerivedParameter::parse(parameter.name()), Some(parameter));
+        }
+    }
+
+    #[test]
+    fn test_compute() {
+        assert_eq!(DerivedParameter::WindSpeed.compute(&[3.0, -4.0]), 5.0);
+        assert_eq!(
+            DerivedParameter::DewpointDepression.compute(&[293.15, 288.15]),
+            5.0
+        );
+        assert!(DerivedParameter::WindSpeed
+            .compute(&[3.0, f32::NAN])
+            .is_nan());
+        assert!(DerivedParameter::WindSpeed.compute(&[3.0]).is_nan());
+
+        // NWS heat index table: 96 °F at 65% RH feels like 121 °F
+        let hi = DerivedParameter::HeatIndex.compute(&[fahrenheit_to_k(96.0), 65.0]);
+        assert!((hi - fahrenheit_to_k(121.0)).abs() < 0.6, "{}", hi);
+        // Mild conditions stay close to the air temperature
+        let hi = DerivedParameter::HeatIndex.compute(&[fahrenheit_to_k(70.0), 50.0]);
+        assert!((hi - fahrenheit_to_k(69.6)).abs() < 0.6, "{}", hi);
+    }
+
+    #[test]
+    fn test_combine() {
+        let u = [3.0, 0.0, f32::NAN];
+        let v = [4.0, -2.0, 1.0];
+        let speed = DerivedParameter::WindSpeed.combine(&[&u, &v]).unwrap();
+        assert_eq!(&speed[..2], &[5.0, 2.0]);
+        assert!(speed[2].is_nan());
+
+        assert!(DerivedParameter::WindSpeed.combine(&[&u]).is_err());
+        assert!(DerivedParameter::WindSpeed.combine(&[&u, &v[..2]]).is_err());
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! This module provides functions to reduce grid resolution by a factor of 2,
 //! supporting different methods appropriate for various weather data types.
+//! It also provides a box filter used to anti-alias reads that cover many
+//! source cells per output pixel (see [`MinifyOptions`]).
 
 use serde::{Deserialize, Serialize};
 
@@ -185,6 +187,7 @@ pub struct PyramidLevelData {
 /// * `width` - Width of input grid
 /// * `height` - Height of input grid
 /// * `min_dimension` - Stop when min(width, height) < this value
+/// * `max_levels` - Maximum number of levels including native (None = no limit)
 /// * `method` - Downsampling method to use
 ///
 /// # Returns
@@ -194,6 +197,7 @@ pub fn generate_pyramid(
     width: usize,
     height: usize,
     min_dimension: usize,
+    max_levels: Option<usize>,
     method: DownsampleMethod,
 ) -> Vec<PyramidLevelData> {
     let mut levels = Vec::new();
@@ -214,7 +218,8 @@ pub fn generate_pyramid(
     let mut current_level = 0u32;
     let mut current_scale = 1u32;
 
-    loop {
+    let max_levels = max_levels.unwrap_or(usize::MAX);
+    while levels.len() < max_levels {
         // Calculate what the next level dimensions would be
         let next_width = current_width / 2;
         let next_height = current_height / 2;
@@ -251,6 +256,136 @@ pub fn generate_pyramid(
     levels
 }
 
+/// Filter applied when a read is minified (many source cells per output pixel).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MinifyFilter {
+    /// Sample the grid directly; fast, but aliases noisy fields
+    #[default]
+    Bilinear,
+    /// Average the source cells under each output pixel before sampling
+    Box,
+}
+
+/// When and how to anti-alias minified reads.
+///
+/// With [`MinifyFilter::Box`], a read whose source-to-output resolution
+/// ratio exceeds `threshold` is box filtered with a window of about one
+/// output pixel before it is sampled. Below the threshold bilinear sampling
+/// doesn't skip enough cells to alias, so the data is left alone.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MinifyOptions {
+    #[serde(default)]
+    pub filter: MinifyFilter,
+    /// Source cells per output pixel above which the filter applies
+    #[serde(default = "default_minify_threshold")]
+    pub threshold: f64,
+}
+
+fn default_minify_threshold() -> f64 {
+    2.0
+}
+
+impl Default for MinifyOptions {
+    fn default() -> Self {
+        Self {
+            filter: MinifyFilter::default(),
+            threshold: default_minify_threshold(),
+        }
+    }
+}
+
+impl MinifyOptions {
+    /// Box filter with the default threshold.
+    pub fn box_filter() -> Self {
+        Self {
+            filter: MinifyFilter::Box,
+            ..Self::default()
+        }
+    }
+
+    /// Box filter window (columns, rows) for a source-to-output ratio.
+    ///
+    /// `ratio` is source cells per output pixel along x and y. Returns `None`
+    /// when no filtering is needed.
+    pub fn window(&self, ratio: (f64, f64)) -> Option<(usize, usize)> {
+        let max_ratio = ratio.0.max(ratio.1);
+        if self.filter != MinifyFilter::Box || max_ratio.is_nan() || max_ratio <= self.threshold {
+            return None;
+        }
+        let size = |r: f64| {
+            if r.is_finite() {
+                r.round().max(1.0) as usize
+            } else {
+                1
+            }
+        };
+        Some((size(ratio.0), size(ratio.1)))
+    }
+}
+
+/// Box filter a grid, keeping its size.
+///
+/// Each valid cell becomes the mean of the valid (non-NaN) cells in the
+/// `window` (columns, rows) centred on it, clipped at the grid edges. NaN
+/// cells stay NaN so data coverage doesn't grow.
+///
+/// Uses summed-area tables, so the cost doesn't depend on the window size.
+pub fn box_filter(data: &[f32], width: usize, height: usize, window: (usize, usize)) -> Vec<f32> {
+    let (win_x, win_y) = (window.0.max(1), window.1.max(1));
+    if (win_x == 1 && win_y == 1) || width == 0 || height == 0 {
+        return data.to_vec();
+    }
+
+    // Summed-area tables of values and valid counts, with a zero first row/column
+    let stride = width + 1;
+    let mut sums = vec![0.0f64; stride * (height + 1)];
+    let mut counts = vec![0u32; stride * (height + 1)];
+    for y in 0..height {
+        let mut row_sum = 0.0f64;
+        let mut row_count = 0u32;
+        for x in 0..width {
+            let v = data.get(y * width + x).copied().unwrap_or(f32::NAN);
+            if !v.is_nan() {
+                row_sum += v as f64;
+                row_count += 1;
+            }
+            let i = (y + 1) * stride + x + 1;
+            sums[i] = sums[i - stride] + row_sum;
+            counts[i] = counts[i - stride] + row_count;
+        }
+    }
+
+    let (before_x, after_x) = ((win_x - 1) / 2, win_x / 2);
+    let (before_y, after_y) = ((win_y - 1) / 2, win_y / 2);
+    let mut output = vec![f32::NAN; width * height];
+
+    for y in 0..height {
+        let y0 = y.saturating_sub(before_y);
+        let y1 = (y + after_y + 1).min(height);
+        for x in 0..width {
+            let v = data.get(y * width + x).copied().unwrap_or(f32::NAN);
+            if v.is_nan() {
+                continue;
+            }
+            let x0 = x.saturating_sub(before_x);
+            let x1 = (x + after_x + 1).min(width);
+
+            let (a, b, c, d) = (
+                y0 * stride + x0,
+                y0 * stride + x1,
+                y1 * stride + x0,
+                y1 * stride + x1,
+            );
+            let sum = sums[d] - sums[b] - sums[c] + sums[a];
+            let count = counts[d] + counts[a] - counts[b] - counts[c];
+            output[y * width + x] = (sum / count as f64) as f32;
+        }
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,7 +445,7 @@ mod tests {
     fn test_generate_pyramid() {
         // 16x16 grid
         let data: Vec<f32> = (0..256).map(|x| x as f32).collect();
-        let levels = generate_pyramid(&data, 16, 16, 4, DownsampleMethod::Mean);
+        let levels = generate_pyramid(&data, 16, 16, 4, None, DownsampleMethod::Mean);
 
         // Should have: 16x16, 8x8, 4x4 = 3 levels
         // Level 4 (2x2) would be below min_dimension=4, so not included
@@ -332,6 +467,18 @@ mod tests {
         assert_eq!(levels[2].scale, 4);
     }
 
+    #[test]
+    fn test_generate_pyramid_max_levels() {
+        let data: Vec<f32> = (0..256).map(|x| x as f32).collect();
+        let levels = generate_pyramid(&data, 16, 16, 1, Some(2), DownsampleMethod::Mean);
+
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[1].width, 8);
+
+        let native_only = generate_pyramid(&data, 16, 16, 1, Some(1), DownsampleMethod::Mean);
+        assert_eq!(native_only.len(), 1);
+    }
+
     #[test]
     fn test_downsample_method_for_parameter() {
         assert_eq!(
@@ -351,4 +498,53 @@ mod tests {
             DownsampleMethod::Max
         );
     }
+
+    #[test]
+    fn test_minify_window() {
+        let bilinear = MinifyOptions::default();
+        assert_eq!(bilinear.window((8.0, 8.0)), None);
+
+        let boxed = MinifyOptions::box_filter();
+        // At or below the threshold, plain sampling is fine
+        assert_eq!(boxed.window((2.0, 1.5)), None);
+        assert_eq!(boxed.window((4.4, 2.6)), Some((4, 3)));
+        assert_eq!(boxed.window((6.0, 0.2)), Some((6, 1)));
+
+        let opts: MinifyOptions = serde_json::from_str(r#"{"filter": "box"}"#).unwrap();
+        assert_eq!(opts, MinifyOptions::box_filter());
+    }
+
+    #[test]
+    fn test_box_filter() {
+        // Checkerboard speckle averages out
+        let data: Vec<f32> = (0..16)
+            .map(|i| if (i % 4 + i / 4) % 2 == 0 { 0.0 } else { 10.0 })
+            .collect();
+        let result = box_filter(&data, 4, 4, (2, 2));
+        assert_eq!(result.len(), 16);
+        for y in 0..3 {
+            for x in 0..3 {
+                assert!((result[y * 4 + x] - 5.0).abs() < 0.001);
+            }
+        }
+        // The corner window is clipped to the corner cell
+        assert_eq!(result[15], data[15]);
+
+        // A 1x1 window leaves the data unchanged
+        assert_eq!(box_filter(&data, 4, 4, (1, 1)), data);
+    }
+
+    #[test]
+    fn test_box_filter_handles_nan() {
+        let data = vec![1.0, 3.0, f32::NAN, 5.0, 7.0, 9.0];
+        let result = box_filter(&data, 3, 2, (3, 1));
+
+        // Clipped at the edge: mean of 1, 3
+        assert!((result[0] - 2.0).abs() < 0.001);
+        // NaN neighbours are skipped: mean of 1, 3
+        assert!((result[1] - 2.0).abs() < 0.001);
+        // NaN cells stay NaN
+        assert!(result[2].is_nan());
+        assert!((result[4] - 7.0).abs() < 0.001);
+    }
 }
@@ -0,0 +1,275 @@
+//! Ensemble post-processing of the members of one forecast.
+//!
+//! Ensemble layers ("ensemble mean 500 mb height", "probability 2 m
+//! temperature < 0 °C") combine every member of a run at one forecast hour
+//! cell by cell. [`EnsembleAccumulator`] folds the members in one at a time,
+//! like [`TemporalAccumulator`](crate::TemporalAccumulator) does for time
+//! windows, so only the running statistics are held in memory.
+//!
+//! All member grids must have the same shape. NaN cells are skipped, so a
+//! cell's statistic is over the members with data there; a cell that is NaN
+//! in every member stays NaN.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{GridProcessorError, Result};
+
+/// Which side of the threshold counts as exceeding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThresholdComparison {
+    /// Values strictly above the threshold (e.g., precipitation > 10 mm)
+    Above,
+    /// Values strictly below the threshold (e.g., temperature < 273.15 K)
+    Below,
+}
+
+impl ThresholdComparison {
+    /// Name used in configuration and cache keys.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Above => "above",
+            Self::Below => "below",
+        }
+    }
+
+    /// Whether `value` is on the exceeding side of `threshold`.
+    pub fn exceeds(&self, value: f32, threshold: f32) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::Below => value < threshold,
+        }
+    }
+}
+
+impl FromStr for ThresholdComparison {
+    type Err = GridProcessorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "above" | "gt" | ">" => Ok(Self::Above),
+            "below" | "lt" | "<" => Ok(Self::Below),
+            _ => Err(GridProcessorError::ConfigError(format!(
+                "unknown threshold comparison '{}' (expected above or below)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Statistic computed over the members of an ensemble.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnsembleStatistic {
+    /// Mean of the members
+    Mean,
+    /// Spread: population standard deviation of the members
+    Spread,
+    /// Percentage (0-100) of members beyond a threshold, in the parameter's
+    /// native units
+    Probability {
+        threshold: f32,
+        comparison: ThresholdComparison,
+    },
+}
+
+impl EnsembleStatistic {
+    /// Name of the statistic, without its threshold.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mean => "mean",
+            Self::Spread => "spread",
+            Self::Probability { .. } => "probability",
+        }
+    }
+}
+
+impl fmt::Display for EnsembleStatistic {
+    /// `mean`, `spread`, or `probability_below_273.15`, for logs and cache keys.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Probability {
+                threshold,
+                comparison,
+            } => write!(f, "{}_{}_{}", self.name(), comparison.as_str(), threshold),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+/// Running cell-by-cell statistic over equally sized member grids.
+pub struct EnsembleAccumulator {
+    statistic: EnsembleStatistic,
+    /// Running mean per cell (Welford), or the number of exceeding members
+    /// for probabilities
+    means: Vec<f64>,
+    /// Sum of squared differences from the mean per cell, for the spread
+    squares: Vec<f64>,
+    /// Number of valid (non-NaN) members seen per cell
+    counts: Vec<u32>,
+    members: usize,
+}
+
+impl EnsembleAccumulator {
+    /// Create an accumulator for member grids of `len` cells.
+    pub fn new(statistic: EnsembleStatistic, len: usize) -> Self {
+        let squares = match statistic {
+            EnsembleStatistic::Spread => vec![0.0; len],
+            _ => Vec::new(),
+        };
+        Self {
+            statistic,
+            means: vec![0.0; len],
+            squares,
+            counts: vec![0; len],
+            members: 0,
+        }
+    }
+
+    /// Fold one member's grid into the statistic.
+    pub fn add(&mut self, data: &[f32]) -> Result<()> {
+        if data.len() != self.means.len() {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "ensemble member grid has {} cells, expected {}",
+                data.len(),
+                self.means.len()
+            )));
+        }
+
+        for (i, &value) in data.iter().enumerate() {
+            if value.is_nan() {
+                continue;
+            }
+            self.counts[i] += 1;
+            match self.statistic {
+                EnsembleStatistic::Probability {
+                    threshold,
+                    comparison,
+                } => {
+                    if comparison.exceeds(value, threshold) {
+                        self.means[i] += 1.0;
+                    }
+                }
+                EnsembleStatistic::Mean | EnsembleStatistic::Spread => {
+                    let value = value as f64;
+                    let delta = value - self.means[i];
+                    self.means[i] += delta / self.counts[i] as f64;
+                    if let Some(square) = self.squares.get_mut(i) {
+                        *square += delta * (value - self.means[i]);
+                    }
+                }
+            }
+        }
+
+        self.members += 1;
+        Ok(())
+    }
+
+    /// Number of members folded in so far.
+    pub fn member_count(&self) -> usize {
+        self.members
+    }
+
+    /// Finish the reduction and return the statistic's grid.
+    pub fn finish(self) -> Vec<f32> {
+        self.counts
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| {
+                if count == 0 {
+                    return f32::NAN;
+                }
+                let value = match self.statistic {
+                    EnsembleStatistic::Mean => self.means[i],
+                    EnsembleStatistic::Spread => (self.squares[i] / count as f64).sqrt(),
+                    EnsembleStatistic::Probability { .. } => 100.0 * self.means[i] / count as f64,
+                };
+                value as f32
+            })
+            .collect()
+    }
+}
+
+/// Reduce a set of equally sized member grids in one call.
+pub fn reduce_members(statistic: EnsembleStatistic, members: &[&[f32]]) -> Result<Vec<f32>> {
+    let len = members.first().map(|m| m.len()).unwrap_or(0);
+    let mut accumulator = EnsembleAccumulator::new(statistic, len);
+    for member in members {
+        accumulator.add(member)?;
+    }
+    Ok(accumulator.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAN: f32 = f32::NAN;
+
+    fn members() -> [[f32; 3]; 4] {
+        [
+            [270.0, 280.0, NAN],
+            [272.0, 280.0, NAN],
+            [274.0, 290.0, NAN],
+            [276.0, NAN, NAN],
+        ]
+    }
+
+    fn reduce(statistic: EnsembleStatistic) -> Vec<f32> {
+        let members = members();
+        let grids: Vec<&[f32]> = members.iter().map(|m| m.as_slice()).collect();
+        reduce_members(statistic, &grids).unwrap()
+    }
+
+    #[test]
+    fn test_mean_and_spread() {
+        let mean = reduce(EnsembleStatistic::Mean);
+        assert_eq!(mean[0], 273.0);
+        // Only the members with data count
+        assert!((mean[1] - 283.333).abs() < 1e-3);
+        assert!(mean[2].is_nan());
+
+        let spread = reduce(EnsembleStatistic::Spread);
+        assert!((spread[0] - 5.0f32.sqrt()).abs() < 1e-5);
+        assert!((spread[1] - 4.714).abs() < 1e-3);
+        assert!(spread[2].is_nan());
+    }
+
+    #[test]
+    fn test_probability() {
+        let below_freezing = reduce(EnsembleStatistic::Probability {
+            threshold: 273.15,
+            comparison: ThresholdComparison::Below,
+        });
+        assert_eq!(below_freezing[0], 50.0);
+        assert_eq!(below_freezing[1], 0.0);
+        assert!(below_freezing[2].is_nan());
+
+        // The threshold itself does not exceed
+        let above = reduce(EnsembleStatistic::Probability {
+            threshold: 280.0,
+            comparison: ThresholdComparison::Above,
+        });
+        assert!((above[1] - 100.0 / 3.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_accumulator_rejects_mismatched_grid() {
+        let mut acc = EnsembleAccumulator::new(EnsembleStatistic::Mean, 4);
+        acc.add(&[0.0; 4]).unwrap();
+        assert!(acc.add(&[0.0; 3]).is_err());
+        assert_eq!(acc.member_count(), 1);
+    }
+
+    #[test]
+    fn test_statistic_names() {
+        assert_eq!(EnsembleStatistic::Spread.to_string(), "spread");
+        let probability = EnsembleStatistic::Probability {
+            threshold: 273.15,
+            comparison: "lt".parse().unwrap(),
+        };
+        assert_eq!(probability.to_string(), "probability_below_273.15");
+        assert!("equal".parse::<ThresholdComparison>().is_err());
+    }
+}
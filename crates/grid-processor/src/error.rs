@@ -1,7 +1,46 @@
 //! Error types for grid processing.
+//!
+//! Every [`GridProcessorError`] falls into an [`ErrorKind`], which tells
+//! callers whether a read is worth retrying and which HTTP status to answer
+//! with.
 
 use thiserror::Error;
 
+/// Broad class of a [`GridProcessorError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The dataset or array doesn't exist.
+    NotFound,
+    /// The request can't be served from this grid (e.g. out of bounds).
+    InvalidRequest,
+    /// Object storage or the catalog failed or timed out.
+    Upstream,
+    /// Stored data or metadata is unreadable (bad checksum, codec or attributes).
+    CorruptData,
+    /// Misconfiguration or a bug in this service.
+    Internal,
+}
+
+impl ErrorKind {
+    /// Whether the same read may succeed if tried again.
+    ///
+    /// Only upstream failures are transient; corrupt data stays corrupt and a
+    /// missing dataset stays missing until the next ingest.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Upstream)
+    }
+
+    /// HTTP status code to answer with.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::NotFound => 404,
+            Self::InvalidRequest => 400,
+            Self::Upstream => 502,
+            Self::CorruptData | Self::Internal => 500,
+        }
+    }
+}
+
 /// Errors that can occur during grid processing.
 #[derive(Error, Debug)]
 pub enum GridProcessorError {
@@ -29,6 +68,10 @@ pub enum GridProcessorError {
     #[error("storage error: {0}")]
     StorageError(String),
 
+    /// A chunk read from storage doesn't match its checksum.
+    #[error("checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+
     /// Decompression error.
     #[error("decompression error: {0}")]
     DecompressionError(String),
@@ -67,6 +110,31 @@ pub enum GridProcessorError {
 }
 
 impl GridProcessorError {
+    /// Classify this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NotFound(_) => ErrorKind::NotFound,
+            Self::OutOfBounds { .. } => ErrorKind::InvalidRequest,
+            Self::StorageError(_) | Self::Storage(_) | Self::Catalog(_) => ErrorKind::Upstream,
+            Self::InvalidMetadata(_)
+            | Self::ZarrError(_)
+            | Self::ChecksumMismatch(_)
+            | Self::DecompressionError(_)
+            | Self::Metadata(_) => ErrorKind::CorruptData,
+            Self::OpenFailed(_)
+            | Self::ReadFailed(_)
+            | Self::ConfigError(_)
+            | Self::CacheError(_)
+            | Self::ProjectionError(_)
+            | Self::InterpolationError(_) => ErrorKind::Internal,
+        }
+    }
+
+    /// Whether the failed operation may succeed if tried again.
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
+
     /// Create an OpenFailed error.
     pub fn open_failed(msg: impl Into<String>) -> Self {
         Self::OpenFailed(msg.into())
@@ -115,3 +183,40 @@ impl From<serde_json::Error> for GridProcessorError {
 
 /// Result type for grid processor operations.
 pub type Result<T> = std::result::Result<T, GridProcessorError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind() {
+        let err = GridProcessorError::NotFound("gfs/TMP".into());
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(err.kind().http_status(), 404);
+        assert!(!err.is_retryable());
+
+        let err = GridProcessorError::storage_error("connection reset");
+        assert_eq!(err.kind(), ErrorKind::Upstream);
+        assert_eq!(err.kind().http_status(), 502);
+        assert!(err.is_retryable());
+        assert!(GridProcessorError::Catalog("pool timed out".into()).is_retryable());
+
+        let err = GridProcessorError::ChecksumMismatch("chunk (0, 0)".into());
+        assert_eq!(err.kind(), ErrorKind::CorruptData);
+        assert_eq!(err.kind().http_status(), 500);
+        assert!(!err.is_retryable());
+
+        let err = GridProcessorError::out_of_bounds("[0, 10]", "[0, 5]");
+        assert_eq!(err.kind().http_status(), 400);
+        assert_eq!(
+            GridProcessorError::ConfigError("bad".into()).kind(),
+            ErrorKind::Internal
+        );
+    }
+
+    #[test]
+    fn test_io_errors_are_upstream() {
+        let io = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        assert!(GridProcessorError::from(io).is_retryable());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! The `GridProcessorFactory` manages:
 //! - Shared `ChunkCache` across all processors
-//! - Shared storage connection (MinIO/S3)
+//! - Shared storage connection (MinIO/S3, GCS or Azure Blob)
 //! - Common configuration settings
 //!
 //! # Example
@@ -20,10 +20,11 @@
 
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tracing::{info, warn};
 
 use crate::cache::ChunkCache;
 use crate::config::GridProcessorConfig;
-use crate::minio_storage::MinioConfig;
+use crate::object_backends::{create_minio_object_versions, MinioConfig};
 use crate::types::{CacheStats, GridMetadata};
 use crate::writer::ZarrMetadata;
 
@@ -59,12 +60,35 @@ impl GridProcessorFactory {
     /// * `minio_config` - MinIO/S3 connection configuration
     /// * `chunk_cache_size_mb` - Memory budget for the chunk cache in MB
     pub fn new(minio_config: MinioConfig, chunk_cache_size_mb: usize) -> Self {
-        let chunk_cache = Arc::new(RwLock::new(ChunkCache::new(
-            chunk_cache_size_mb * 1024 * 1024,
-        )));
-
         let config = GridProcessorConfig::from_env();
 
+        let mut chunk_cache = ChunkCache::new(chunk_cache_size_mb * 1024 * 1024);
+        if let Some(after) = config.chunk_revalidate_after() {
+            match create_minio_object_versions(&minio_config) {
+                Ok(versions) => {
+                    info!(
+                        revalidate_secs = after.as_secs(),
+                        "Chunk cache ETag revalidation enabled"
+                    );
+                    chunk_cache = chunk_cache.with_revalidation(versions, after);
+                }
+                Err(e) => {
+                    warn!(error = %e, "Chunk cache ETag revalidation disabled");
+                }
+            }
+        }
+        if let Some(dir) = &config.chunk_disk_cache_dir {
+            match chunk_cache.enable_disk_tier(dir, config.chunk_disk_cache_size_bytes()) {
+                Ok(()) => info!(
+                    dir = %dir,
+                    size_mb = config.chunk_disk_cache_size_mb,
+                    "Chunk cache disk tier enabled"
+                ),
+                Err(e) => warn!(dir = %dir, error = %e, "Chunk cache disk tier disabled"),
+            }
+        }
+        let chunk_cache = Arc::new(RwLock::new(chunk_cache));
+
         Self {
             config,
             chunk_cache,
@@ -99,6 +123,25 @@ impl GridProcessorFactory {
         &self.minio_config
     }
 
+    /// Remove cached chunks for all arrays under a storage path prefix.
+    ///
+    /// Call this when ingestion overwrites an array in place.
+    /// Returns the number of chunks removed.
+    pub async fn invalidate_chunks(&self, prefix: &str) -> usize {
+        self.chunk_cache.write().await.invalidate_prefix(prefix)
+    }
+
+    /// Evict every chunk from memory, spilling to the disk tier if it is
+    /// enabled (for memory pressure relief).
+    ///
+    /// # Returns
+    /// Tuple of (entries evicted, bytes freed)
+    pub async fn evict_chunk_cache(&self) -> (usize, u64) {
+        let mut cache = self.chunk_cache.write().await;
+        let bytes = cache.memory_usage() as u64;
+        (cache.evict_to_target(0), bytes)
+    }
+
     /// Clear the chunk cache (for hot reload / cache invalidation).
     ///
     /// # Returns
@@ -128,6 +171,9 @@ impl From<&ZarrMetadata> for GridMetadata {
             chunk_shape: zarr.chunk_shape,
             num_chunks: zarr.num_chunks,
             fill_value: zarr.fill_value,
+            coordinates: zarr.coordinates.clone(),
+            time: zarr.time.clone(),
+            vertical: zarr.vertical.clone(),
         }
     }
 }
@@ -141,6 +187,7 @@ impl From<ZarrMetadata> for GridMetadata {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ChunkLayout;
     use crate::types::BoundingBox;
     use chrono::{TimeZone, Utc};
 
@@ -155,10 +202,15 @@ mod tests {
             bbox: BoundingBox::new(0.0, -90.0, 360.0, 90.0),
             shape: (1440, 721),
             chunk_shape: (512, 512),
+            chunk_layout: ChunkLayout::Square,
             num_chunks: (3, 2),
             fill_value: f32::NAN,
             dtype: "float32".to_string(),
             compression: "blosc".to_string(),
+            coordinates: None,
+            checksum: None,
+            time: None,
+            vertical: None,
         }
     }
 
@@ -118,6 +118,10 @@
 //! | EDR Area | `read_region()` | Bbox query for CoverageJSON |
 //! | EDR Trajectory | Multiple `read_point()` | Iterate over path |
 //! | WCS GetCoverage | `read_region()` | Raw grid data export |
+//! | Temporal composites | [`TemporalAccumulator`] | Max/sum/mean over a time window |
+//! | Ensemble products | [`EnsembleAccumulator`] | Mean/spread/probability over members |
+//! | Column composites | [`VerticalAccumulator`] | Max/min/mean over a level range |
+//! | Derived parameters | [`DerivedParameter`] | Wind speed from UGRD/VGRD |
 //!
 //! ## Feature Flags
 //!
@@ -125,41 +129,62 @@
 
 pub mod cache;
 pub mod config;
+pub mod derived;
 pub mod downsample;
+pub mod ensemble;
 pub mod error;
 pub mod factory;
-pub mod minio_storage;
+pub mod object_backends;
 pub mod processor;
 pub mod projection;
 pub mod query;
 pub mod service;
+pub mod temporal;
 #[cfg(test)]
 pub mod testdata;
 pub mod types;
+pub mod vertical;
 pub mod writer;
 
 // Re-export commonly used types at crate root
-pub use cache::{ChunkCache, ChunkKey};
-pub use config::{GridProcessorConfig, PyramidConfig, ZarrCompression};
-pub use downsample::{generate_pyramid, DownsampleMethod, PyramidLevelData};
-pub use error::{GridProcessorError, Result};
+pub use cache::{ChunkCache, ChunkKey, Freshness, ObjectStoreVersions, ObjectVersions};
+pub use config::{
+    AccessPattern, ChecksumPolicy, ChunkLayout, GridProcessorConfig, PyramidConfig, ZarrCompression,
+};
+pub use derived::DerivedParameter;
+pub use downsample::{
+    box_filter, generate_pyramid, DownsampleMethod, MinifyFilter, MinifyOptions, PyramidLevelData,
+};
+pub use ensemble::{reduce_members, EnsembleAccumulator, EnsembleStatistic, ThresholdComparison};
+pub use error::{ErrorKind, GridProcessorError, Result};
 pub use factory::GridProcessorFactory;
-pub use minio_storage::{create_minio_storage, MinioConfig};
+pub use object_backends::{
+    create_minio_object_versions, create_minio_storage, MinioConfig, ObjectBackend,
+};
 pub use processor::{
-    parse_multiscale_metadata, GridProcessor, MultiscaleGridProcessorFactory, ZarrGridProcessor,
+    parse_multiscale_metadata, ChunkVerification, GridProcessor, MultiscaleGridProcessorFactory,
+    ZarrGridProcessor,
 };
 pub use projection::interpolation::resample_grid;
 pub use projection::{
-    bilinear_interpolate, cubic_interpolate, nearest_interpolate,
-    reproject_geostationary_to_geographic, tile_to_bbox,
+    bilinear_interpolate, cubic_interpolate, nearest_interpolate, normalize_longitude,
+    reproject_geostationary_to_geographic, tile_to_bbox, wrap_longitude, LongitudeAxis,
+};
+pub use query::{
+    canonicalize_level, DatasetQuery, PointValue, PyramidSelection, TimeSpecification,
 };
-pub use query::{DatasetQuery, PointValue, TimeSpecification};
 pub use service::GridDataService;
+pub use temporal::{reduce_grids, TemporalAccumulator, TemporalCompositeCache, TemporalReducer};
 pub use types::{
-    AxisInfo, BoundingBox, CacheStats, GridMetadata, GridRegion, InterpolationMethod,
-    MultiscaleMetadata, PyramidLevel,
+    AxisCoordinates, AxisInfo, BoundingBox, CacheStats, GridCoordinates, GridMetadata, GridRegion,
+    InterpolationMethod, LevelSelection, MultiscaleMetadata, Provenance, PyramidLevel, TimeAxis,
+    TimeSelection, VerticalAxis, NATIVE_LEVEL,
+};
+pub use vertical::{reduce_levels, LevelRange, VerticalAccumulator, VerticalReducer};
+pub use writer::{
+    encode_geotiff, CfAttributes, ExportFormat, GeoTiffOptions, GridSeries, MultiscaleWriteResult,
+    NetCdfCoverage, ZarrMetadata, ZarrWriteResult, ZarrWriter,
 };
-pub use writer::{MultiscaleWriteResult, ZarrMetadata, ZarrWriteResult, ZarrWriter};
 
 // Re-export storage traits for use by consumers
 pub use zarrs::storage::ReadableStorageTraits;
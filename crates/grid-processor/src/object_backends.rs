@@ -0,0 +1,403 @@
+//! Object storage backends for Zarr access.
+//!
+//! This module provides helper functions for creating storage backends that
+//! work with the zarrs crate. Grids are read from MinIO/S3 by default, or from
+//! Google Cloud Storage or Azure Blob Storage when [`MinioConfig::backend`]
+//! selects them. For offline use and tests, the same paths can be served from
+//! a local directory instead.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// Use the direct object_store crate (version must match what zarrs_object_store uses)
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::azure::{MicrosoftAzure, MicrosoftAzureBuilder};
+use object_store::gcp::{GoogleCloudStorage, GoogleCloudStorageBuilder};
+use object_store::local::LocalFileSystem;
+use object_store::ObjectStore;
+use tracing::warn;
+use zarrs_object_store::AsyncObjectStore;
+use zarrs_storage::storage_adapter::async_to_sync::{
+    AsyncToSyncBlockOn, AsyncToSyncStorageAdapter,
+};
+
+use crate::cache::ObjectStoreVersions;
+use crate::error::{GridProcessorError, Result};
+
+/// Blocking executor that works from within a tokio runtime.
+///
+/// Uses `tokio::task::block_in_place` to move the current task to a blocking
+/// thread, then uses the runtime handle to drive the future. This avoids the
+/// "cannot start a runtime from within a runtime" error.
+#[derive(Clone, Copy)]
+pub struct TokioBlockOn;
+
+impl AsyncToSyncBlockOn for TokioBlockOn {
+    fn block_on<F: core::future::Future>(&self, future: F) -> F::Output {
+        // block_in_place moves the current task off the async worker thread
+        // so we can safely call block_on without nesting runtimes
+        tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+    }
+}
+
+/// Object storage service that holds the Zarr grids.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ObjectBackend {
+    /// MinIO or AWS S3, using the endpoint, keys and region of [`MinioConfig`]
+    #[default]
+    S3,
+    /// Google Cloud Storage
+    Gcs {
+        /// Service account JSON file; application default credentials or
+        /// the instance metadata server are used when unset
+        service_account_path: Option<String>,
+    },
+    /// Azure Blob Storage; [`MinioConfig::bucket`] names the container
+    Azure {
+        /// Storage account name
+        account: String,
+        /// Storage account key; managed identity is used when unset
+        access_key: Option<String>,
+        /// Blob endpoint (e.g., an Azurite emulator URL); defaults to
+        /// `https://{account}.blob.core.windows.net`
+        endpoint: Option<String>,
+    },
+}
+
+impl ObjectBackend {
+    /// Backend selected by `OBJECT_STORE_BACKEND` (`s3`, `gcs` or `azure`),
+    /// with its credentials from the environment. Defaults to S3.
+    pub fn from_env() -> Self {
+        let name = std::env::var("OBJECT_STORE_BACKEND").unwrap_or_else(|_| "s3".to_string());
+        let backend = name.parse().unwrap_or_else(|e| {
+            warn!(error = %e, "Falling back to the S3 object storage backend");
+            Self::S3
+        });
+
+        match backend {
+            Self::S3 => Self::S3,
+            Self::Gcs { .. } => Self::Gcs {
+                service_account_path: std::env::var("GOOGLE_SERVICE_ACCOUNT").ok(),
+            },
+            Self::Azure { .. } => Self::Azure {
+                account: std::env::var("AZURE_STORAGE_ACCOUNT_NAME").unwrap_or_default(),
+                access_key: std::env::var("AZURE_STORAGE_ACCOUNT_KEY").ok(),
+                endpoint: std::env::var("AZURE_STORAGE_ENDPOINT").ok(),
+            },
+        }
+    }
+
+    /// Short name used in configuration and logs.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::S3 => "s3",
+            Self::Gcs { .. } => "gcs",
+            Self::Azure { .. } => "azure",
+        }
+    }
+
+    /// Environment variable holding the bucket (or container) name.
+    fn bucket_env_var(&self) -> &'static str {
+        match self {
+            Self::S3 => "S3_BUCKET",
+            Self::Gcs { .. } => "GCS_BUCKET",
+            Self::Azure { .. } => "AZURE_CONTAINER_NAME",
+        }
+    }
+}
+
+impl FromStr for ObjectBackend {
+    type Err = GridProcessorError;
+
+    /// Parse a backend name, without credentials.
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "s3" | "minio" => Ok(Self::S3),
+            "gcs" | "gcp" => Ok(Self::Gcs {
+                service_account_path: None,
+            }),
+            "azure" => Ok(Self::Azure {
+                account: String::new(),
+                access_key: None,
+                endpoint: None,
+            }),
+            _ => Err(GridProcessorError::ConfigError(format!(
+                "unknown object storage backend '{}' (expected s3, gcs or azure)",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ObjectBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Configuration for connecting to object storage.
+///
+/// Named for the default MinIO/S3 backend; `backend` selects GCS or Azure
+/// Blob Storage instead, in which case only `bucket` and `allow_http` of the
+/// S3 fields apply.
+#[derive(Debug, Clone)]
+pub struct MinioConfig {
+    /// Object storage service
+    pub backend: ObjectBackend,
+    /// S3/MinIO endpoint URL (e.g., "http://minio:9000")
+    pub endpoint: String,
+    /// Bucket name (container name for Azure)
+    pub bucket: String,
+    /// Access key ID
+    pub access_key_id: String,
+    /// Secret access key
+    pub secret_access_key: String,
+    /// AWS region (use "us-east-1" for MinIO)
+    pub region: String,
+    /// Allow HTTP (required for local MinIO)
+    pub allow_http: bool,
+    /// Read objects from this directory instead of S3 (offline mode)
+    pub local_dir: Option<PathBuf>,
+}
+
+impl Default for MinioConfig {
+    fn default() -> Self {
+        Self {
+            backend: ObjectBackend::S3,
+            endpoint: "http://minio:9000".to_string(),
+            bucket: "weather-data".to_string(),
+            access_key_id: "minioadmin".to_string(),
+            secret_access_key: "minioadmin".to_string(),
+            region: "us-east-1".to_string(),
+            allow_http: true,
+            local_dir: None,
+        }
+    }
+}
+
+impl MinioConfig {
+    /// Create config from environment variables.
+    ///
+    /// The bucket comes from `S3_BUCKET`, `GCS_BUCKET` or
+    /// `AZURE_CONTAINER_NAME`, depending on the backend.
+    pub fn from_env() -> Self {
+        let backend = ObjectBackend::from_env();
+        Self {
+            endpoint: std::env::var("S3_ENDPOINT")
+                .unwrap_or_else(|_| "http://minio:9000".to_string()),
+            bucket: std::env::var(backend.bucket_env_var())
+                .unwrap_or_else(|_| "weather-data".to_string()),
+            access_key_id: std::env::var("S3_ACCESS_KEY")
+                .unwrap_or_else(|_| "minioadmin".to_string()),
+            secret_access_key: std::env::var("S3_SECRET_KEY")
+                .unwrap_or_else(|_| "minioadmin".to_string()),
+            region: std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+            allow_http: std::env::var("S3_ALLOW_HTTP")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(true),
+            local_dir: None,
+            backend,
+        }
+    }
+
+    /// Config that serves objects from a local directory, where object
+    /// paths map to files below `dir`.
+    pub fn local(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            local_dir: Some(dir.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Storage type alias for MinIO-backed Zarr access (async).
+pub type AsyncMinioStorage = AsyncObjectStore<Arc<dyn ObjectStore>>;
+
+/// Storage type alias for MinIO-backed Zarr access (sync adapter).
+/// This type implements ReadableStorageTraits and can be used with ZarrGridProcessor.
+pub type MinioStorage = AsyncToSyncStorageAdapter<AsyncMinioStorage, TokioBlockOn>;
+
+/// Create a MinIO-compatible storage backend for Zarr access.
+///
+/// This function creates an object_store client configured for MinIO,
+/// wraps it in AsyncObjectStore, and then wraps that in an async-to-sync
+/// adapter for use with the synchronous zarrs API.
+///
+/// # Arguments
+/// * `config` - MinIO connection configuration
+///
+/// # Returns
+/// An Arc-wrapped storage adapter that implements ReadableStorageTraits
+pub fn create_minio_storage(config: &MinioConfig) -> Result<Arc<MinioStorage>> {
+    let store = build_object_store(config)?;
+
+    let async_store = Arc::new(AsyncObjectStore::new(store));
+
+    // Use TokioBlockOn which uses block_in_place + Handle::current().block_on()
+    // This is safe to call from within tokio async contexts
+    let block_on = TokioBlockOn;
+
+    // Wrap in async-to-sync adapter
+    let sync_store = AsyncToSyncStorageAdapter::new(async_store, block_on);
+
+    Ok(Arc::new(sync_store))
+}
+
+/// Create an ETag source for revalidating cached chunks against object
+/// storage.
+///
+/// Keys passed to the returned source are Zarr store keys, which map
+/// directly to object paths in the bucket.
+pub fn create_minio_object_versions(config: &MinioConfig) -> Result<Arc<ObjectStoreVersions>> {
+    let store = build_object_store(config)?;
+    Ok(Arc::new(ObjectStoreVersions::new(store)))
+}
+
+/// Build the object store client for a config: the local directory if one
+/// is set, the configured backend otherwise.
+fn build_object_store(config: &MinioConfig) -> Result<Arc<dyn ObjectStore>> {
+    match &config.local_dir {
+        Some(dir) => {
+            let store = LocalFileSystem::new_with_prefix(dir).map_err(|e| {
+                GridProcessorError::open_failed(format!(
+                    "Failed to open local store {}: {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+            Ok(Arc::new(store))
+        }
+        None => match &config.backend {
+            ObjectBackend::S3 => Ok(Arc::new(build_s3_client(config)?)),
+            ObjectBackend::Gcs {
+                service_account_path,
+            } => Ok(Arc::new(build_gcs_client(
+                config,
+                service_account_path.as_deref(),
+            )?)),
+            ObjectBackend::Azure {
+                account,
+                access_key,
+                endpoint,
+            } => Ok(Arc::new(build_azure_client(
+                config,
+                account,
+                access_key.as_deref(),
+                endpoint.as_deref(),
+            )?)),
+        },
+    }
+}
+
+/// Build an S3 client configured for MinIO.
+fn build_s3_client(config: &MinioConfig) -> Result<AmazonS3> {
+    AmazonS3Builder::new()
+        .with_endpoint(&config.endpoint)
+        .with_bucket_name(&config.bucket)
+        .with_access_key_id(&config.access_key_id)
+        .with_secret_access_key(&config.secret_access_key)
+        .with_region(&config.region)
+        .with_allow_http(config.allow_http)
+        .build()
+        .map_err(|e| GridProcessorError::open_failed(format!("Failed to create S3 client: {}", e)))
+}
+
+/// Build a Google Cloud Storage client.
+fn build_gcs_client(
+    config: &MinioConfig,
+    service_account_path: Option<&str>,
+) -> Result<GoogleCloudStorage> {
+    let mut builder = GoogleCloudStorageBuilder::new().with_bucket_name(&config.bucket);
+    if let Some(path) = service_account_path {
+        builder = builder.with_service_account_path(path);
+    }
+    builder
+        .build()
+        .map_err(|e| GridProcessorError::open_failed(format!("Failed to create GCS client: {}", e)))
+}
+
+/// Build an Azure Blob Storage client for the container named by the bucket.
+fn build_azure_client(
+    config: &MinioConfig,
+    account: &str,
+    access_key: Option<&str>,
+    endpoint: Option<&str>,
+) -> Result<MicrosoftAzure> {
+    if account.is_empty() {
+        return Err(GridProcessorError::ConfigError(
+            "Azure Blob backend needs a storage account (AZURE_STORAGE_ACCOUNT_NAME)".to_string(),
+        ));
+    }
+    let mut builder = MicrosoftAzureBuilder::new()
+        .with_account(account)
+        .with_container_name(&config.bucket)
+        .with_allow_http(config.allow_http);
+    if let Some(key) = access_key {
+        builder = builder.with_access_key(key);
+    }
+    if let Some(endpoint) = endpoint {
+        builder = builder.with_endpoint(endpoint.to_string());
+    }
+    builder.build().map_err(|e| {
+        GridProcessorError::open_failed(format!("Failed to create Azure Blob client: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = MinioConfig::default();
+        assert_eq!(config.endpoint, "http://minio:9000");
+        assert_eq!(config.bucket, "weather-data");
+        assert!(config.allow_http);
+        assert!(config.local_dir.is_none());
+        assert_eq!(config.backend, ObjectBackend::S3);
+    }
+
+    #[test]
+    fn test_backend_names() {
+        assert_eq!("MinIO".parse::<ObjectBackend>().unwrap(), ObjectBackend::S3);
+        assert_eq!("gcp".parse::<ObjectBackend>().unwrap().to_string(), "gcs");
+        assert_eq!("azure".parse::<ObjectBackend>().unwrap().name(), "azure");
+        assert!("ftp".parse::<ObjectBackend>().is_err());
+    }
+
+    #[test]
+    fn test_cloud_backends() {
+        let gcs = MinioConfig {
+            backend: ObjectBackend::Gcs {
+                service_account_path: None,
+            },
+            ..MinioConfig::default()
+        };
+        assert!(build_object_store(&gcs).is_ok());
+
+        let azure = |account: &str| MinioConfig {
+            backend: ObjectBackend::Azure {
+                account: account.to_string(),
+                access_key: Some("c2VjcmV0".to_string()),
+                endpoint: Some("http://azurite:10000/devstoreaccount1".to_string()),
+            },
+            ..MinioConfig::default()
+        };
+        assert!(build_object_store(&azure("devstoreaccount1")).is_ok());
+        // Azure needs a storage account
+        assert!(build_object_store(&azure("")).is_err());
+    }
+
+    #[test]
+    fn test_local_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = MinioConfig::local(dir.path());
+        assert_eq!(config.local_dir.as_deref(), Some(dir.path()));
+        assert!(build_object_store(&config).is_ok());
+
+        let missing = MinioConfig::local(dir.path().join("missing"));
+        assert!(build_object_store(&missing).is_err());
+    }
+}
@@ -2,17 +2,25 @@
 
 mod zarr;
 
-pub use zarr::{parse_multiscale_metadata, MultiscaleGridProcessorFactory, ZarrGridProcessor};
+pub use zarr::{
+    parse_multiscale_metadata, ChunkVerification, MultiscaleGridProcessorFactory, ZarrGridProcessor,
+};
 
 use async_trait::async_trait;
 
 use crate::error::Result;
-use crate::types::{BoundingBox, CacheStats, GridMetadata, GridRegion};
+use crate::types::{BoundingBox, CacheStats, GridMetadata, GridRegion, TimeSelection};
 
 /// Trait for accessing grid data with efficient partial reads.
 ///
 /// This trait abstracts over different grid data formats (Zarr, etc.)
 /// and provides efficient region-based access to gridded data.
+///
+/// Grids may hold several forecast hours of a run on a time axis
+/// ([`GridMetadata::time`]); the `_at` reads pick a time step, the others
+/// read the first one. Likewise, level stacks ([`GridMetadata::vertical`])
+/// are read at their first level except by
+/// [`read_profile`](Self::read_profile).
 #[async_trait]
 pub trait GridProcessor: Send + Sync {
     /// Read grid data for a geographic region.
@@ -27,6 +35,17 @@ pub trait GridProcessor: Send + Sync {
     /// * `GridRegion` containing the data and metadata for the region
     async fn read_region(&self, bbox: &BoundingBox) -> Result<GridRegion>;
 
+    /// Read grid data for a geographic region at one time step.
+    ///
+    /// # Arguments
+    /// * `bbox` - Geographic bounding box to read
+    /// * `time` - Time step, by index, forecast hour or valid time
+    ///
+    /// # Returns
+    /// * `GridRegion` for the region at that time step
+    /// * `NotFound` error if the grid has no such time step
+    async fn read_region_at(&self, bbox: &BoundingBox, time: &TimeSelection) -> Result<GridRegion>;
+
     /// Read a single point value with bilinear interpolation (for GetFeatureInfo).
     ///
     /// # Arguments
@@ -38,6 +57,25 @@ pub trait GridProcessor: Send + Sync {
     /// * `None` if the point is outside the grid or is a fill value
     async fn read_point(&self, lon: f64, lat: f64) -> Result<Option<f32>>;
 
+    /// Read a single point value at one time step, like
+    /// [`read_point`](Self::read_point).
+    ///
+    /// Returns a `NotFound` error if the grid has no such time step.
+    async fn read_point_at(&self, lon: f64, lat: f64, time: &TimeSelection) -> Result<Option<f32>>;
+
+    /// Read a point through every level of a level stack, with the same
+    /// interpolation as [`read_point`](Self::read_point).
+    ///
+    /// # Arguments
+    /// * `lon` - Longitude in degrees
+    /// * `lat` - Latitude in degrees
+    ///
+    /// # Returns
+    /// * `(level value, value)` pairs in the order of the vertical axis, the
+    ///   value `None` where there is no data (at every level outside the grid)
+    /// * `NotFound` error if the grid has no vertical axis
+    async fn read_profile(&self, lon: f64, lat: f64) -> Result<Vec<(f64, Option<f32>)>>;
+
     /// Read the raw value at a specific grid cell index (for numbers style).
     /// No interpolation is performed - returns the exact stored value.
     ///
@@ -55,9 +93,9 @@ pub trait GridProcessor: Send + Sync {
 
     /// Prefetch chunks for anticipated requests.
     ///
-    /// This is a hint to the processor that these regions will likely
-    /// be requested soon. The implementation may choose to fetch
-    /// and cache the relevant chunks proactively.
+    /// `bboxes` are map tiles that were just read. Implementations may
+    /// predict the tiles a panning client will ask for next and fetch
+    /// and cache their chunks proactively.
     async fn prefetch(&self, bboxes: &[BoundingBox]);
 
     /// Get cache statistics for monitoring.
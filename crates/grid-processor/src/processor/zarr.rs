@@ -1,18 +1,26 @@
 //! Zarr V3 grid processor implementation.
 
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::RwLock;
 
 use async_trait::async_trait;
-use tracing::{debug, error, info};
-use zarrs::array::Array;
+use futures::stream::{self, StreamExt};
+use metrics::{counter, histogram};
+use tracing::{debug, error, info, warn};
+use zarrs::array::codec::{CodecError, CodecOptionsBuilder};
+use zarrs::array::{Array, ArrayCreateError, ArrayError};
 use zarrs::array_subset::ArraySubset;
 use zarrs::storage::ReadableStorageTraits;
 
-use crate::cache::{hash_path, ChunkCache};
-use crate::config::GridProcessorConfig;
+use crate::cache::{hash_path, ChunkCache, ChunkKey, Freshness, ObjectVersions};
+use crate::config::{ChecksumPolicy, GridProcessorConfig};
 use crate::error::{GridProcessorError, Result};
-use crate::types::{BoundingBox, CacheStats, GridMetadata, GridRegion, MultiscaleMetadata};
+use crate::projection::normalize_longitude;
+use crate::types::{
+    BoundingBox, CacheStats, GridCoordinates, GridMetadata, GridRegion, LevelSelection,
+    MultiscaleMetadata, Provenance, TimeAxis, TimeSelection, VerticalAxis,
+};
 
 use super::GridProcessor;
 
@@ -22,9 +30,17 @@ use super::GridProcessor;
 /// 1. Calculating which chunks intersect the requested region
 /// 2. Fetching only those chunks via byte-range requests
 /// 3. Caching decompressed chunks for reuse across requests
+///
+/// Storage reads block, so cache misses are fetched on tokio's blocking
+/// pool, up to [`GridProcessorConfig::max_concurrent_chunk_fetches`] at a
+/// time per region read.
+///
+/// Arrays are either 2D (`[rows, cols]`), time series (`[time, rows,
+/// cols]`, one time step per chunk, see [`TimeAxis`]) or level stacks
+/// (`[level, rows, cols]`, one level per chunk, see [`VerticalAxis`]).
 pub struct ZarrGridProcessor<S: ReadableStorageTraits> {
-    /// The Zarr array.
-    array: Array<S>,
+    /// The Zarr array (shared with blocking chunk fetches).
+    array: Arc<Array<S>>,
     /// Storage path (for cache key generation).
     path: String,
     /// Hash of the path for efficient cache keys.
@@ -34,10 +50,69 @@ pub struct ZarrGridProcessor<S: ReadableStorageTraits> {
     /// Shared chunk cache for decompressed data.
     chunk_cache: Arc<RwLock<ChunkCache>>,
     /// Configuration.
-    #[allow(dead_code)]
     config: GridProcessorConfig,
 }
 
+/// 2D grid within an array: a time step and vertical level, both 0 for
+/// arrays without those axes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Slice {
+    time: usize,
+    level: usize,
+}
+
+/// Result of verifying every chunk of an array against its checksum.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkVerification {
+    /// Number of chunks read.
+    pub chunks_checked: usize,
+    /// Chunks (x, y) whose checksum didn't match, at any time step or level.
+    pub corrupt_chunks: Vec<(usize, usize)>,
+}
+
+impl ChunkVerification {
+    /// Whether every chunk matched its checksum.
+    pub fn is_intact(&self) -> bool {
+        self.corrupt_chunks.is_empty()
+    }
+}
+
+/// Whether a Zarr read failed because a chunk didn't match its checksum.
+fn is_checksum_error(error: &ArrayError) -> bool {
+    matches!(error, ArrayError::CodecError(CodecError::InvalidChecksum))
+}
+
+/// Map a failed chunk read to an error of the right kind: storage failures
+/// are upstream and worth retrying, codec failures mean the chunk is corrupt.
+fn read_error(error: ArrayError) -> GridProcessorError {
+    match error {
+        ArrayError::StorageError(e) | ArrayError::CodecError(CodecError::StorageError(e)) => {
+            GridProcessorError::storage_error(e.to_string())
+        }
+        ArrayError::CodecError(e) => GridProcessorError::DecompressionError(e.to_string()),
+        e => GridProcessorError::read_failed(e.to_string()),
+    }
+}
+
+/// Map a failure to open an array as-is (`error`) and as level 0 of a
+/// pyramid (`level0_error`): missing in both places is not found, a storage
+/// failure in either is upstream, anything else is unreadable metadata.
+fn open_error(error: ArrayCreateError, level0_error: ArrayCreateError) -> GridProcessorError {
+    let message = format!(
+        "Failed to open as array ({}) or level 0 ({})",
+        error, level0_error
+    );
+    match (&error, &level0_error) {
+        (ArrayCreateError::MissingMetadata, ArrayCreateError::MissingMetadata) => {
+            GridProcessorError::NotFound(message)
+        }
+        (ArrayCreateError::StorageError(_), _) | (_, ArrayCreateError::StorageError(_)) => {
+            GridProcessorError::storage_error(message)
+        }
+        _ => GridProcessorError::invalid_metadata(message),
+    }
+}
+
 impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
     /// Open a Zarr array from storage.
     ///
@@ -59,12 +134,7 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
             Err(e) => {
                 // Might be a group (pyramid store), try level 0
                 let level0_path = format!("{}/0", path.trim_end_matches('/'));
-                Array::open(store, &level0_path).map_err(|e2| {
-                    GridProcessorError::open_failed(format!(
-                        "Failed to open as array ({}) or level 0 ({})",
-                        e, e2
-                    ))
-                })?
+                Array::open(store, &level0_path).map_err(|e2| open_error(e, e2))?
             }
         };
 
@@ -79,7 +149,7 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
         let path_hash = hash_path(path);
 
         Ok(Self {
-            array,
+            array: Arc::new(array),
             path: path.to_string(),
             path_hash,
             metadata,
@@ -133,10 +203,7 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
                         original_error = %e,
                         "Failed to open Zarr array (tried both root and level 0)"
                     );
-                    GridProcessorError::open_failed(format!(
-                        "Failed to open as array ({}) or level 0 ({})",
-                        e, e2
-                    ))
+                    open_error(e, e2)
                 })?
             }
         };
@@ -151,7 +218,7 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
         );
 
         Ok(Self {
-            array,
+            array: Arc::new(array),
             path: path.to_string(),
             path_hash,
             metadata,
@@ -165,23 +232,28 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
         let attrs = array.attributes();
         let shape = array.shape();
 
-        // Ensure we have at least 2 dimensions
-        if shape.len() < 2 {
-            return Err(GridProcessorError::invalid_metadata(
-                "Array must have at least 2 dimensions",
-            ));
+        // 2D grids, or time series / level stacks with a leading dimension
+        if shape.len() != 2 && shape.len() != 3 {
+            return Err(GridProcessorError::invalid_metadata(format!(
+                "Array must have 2 or 3 dimensions, has {}",
+                shape.len()
+            )));
         }
+        let (row_dim, col_dim) = (shape.len() - 2, shape.len() - 1);
 
         // Get chunk shape from array configuration
         let chunk_grid = array.chunk_grid();
-        // Use origin [0, 0] for getting chunk shape
+        // Use the origin for getting chunk shape
         let origin = vec![0u64; shape.len()];
         let chunk_shape = chunk_grid
             .chunk_shape(&origin, array.shape())
             .map_err(|e| GridProcessorError::invalid_metadata(e.to_string()))?
             .ok_or_else(|| GridProcessorError::invalid_metadata("missing chunk shape"))?;
 
-        let chunk_shape = (chunk_shape[1].get() as usize, chunk_shape[0].get() as usize);
+        let chunk_shape = (
+            chunk_shape[col_dim].get() as usize,
+            chunk_shape[row_dim].get() as usize,
+        );
 
         // Parse required attributes
         let model = attrs
@@ -248,8 +320,54 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
             .map(f32::from_ne_bytes)
             .unwrap_or(f32::NAN);
 
+        // Explicit coordinates, only present for non-uniformly spaced grids
+        let coordinates = attrs
+            .get("coordinates")
+            .and_then(|v| serde_json::from_value::<GridCoordinates>(v.clone()).ok());
+
+        // Levels of a level stack, or forecast hours of a time series
+        let vertical = attrs
+            .get("vertical_levels")
+            .and_then(|v| serde_json::from_value::<VerticalAxis>(v.clone()).ok());
+        let time = match (&vertical, shape.len()) {
+            (Some(vertical), 3) => {
+                if vertical.len() as u64 != shape[0] {
+                    return Err(GridProcessorError::invalid_metadata(format!(
+                        "{} vertical levels for {} levels",
+                        vertical.len(),
+                        shape[0]
+                    )));
+                }
+                None
+            }
+            (None, 3) => {
+                let forecast_hours: Vec<u32> = attrs
+                    .get("forecast_hours")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .ok_or_else(|| {
+                        GridProcessorError::invalid_metadata(
+                            "3D array is missing its forecast_hours or vertical_levels attribute",
+                        )
+                    })?;
+                if forecast_hours.len() as u64 != shape[0] {
+                    return Err(GridProcessorError::invalid_metadata(format!(
+                        "{} forecast hours for {} time steps",
+                        forecast_hours.len(),
+                        shape[0]
+                    )));
+                }
+                Some(TimeAxis::new(forecast_hours))
+            }
+            (Some(_), _) => {
+                return Err(GridProcessorError::invalid_metadata(
+                    "2D array has a vertical_levels attribute",
+                ))
+            }
+            (None, _) => None,
+        };
+
         // Grid shape: Zarr is [rows, cols] but we store as (width, height)
-        let grid_shape = (shape[1] as usize, shape[0] as usize);
+        let grid_shape = (shape[col_dim] as usize, shape[row_dim] as usize);
 
         // Calculate number of chunks
         let num_chunks = (
@@ -269,9 +387,45 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
             chunk_shape,
             num_chunks,
             fill_value,
+            coordinates,
+            time,
+            vertical,
         })
     }
 
+    /// Zarr indices of a grid row and column in a slice: the time step or
+    /// level first, for 3D arrays.
+    fn array_indices(&self, slice: Slice, row: usize, col: usize) -> Vec<u64> {
+        let mut indices = Vec::with_capacity(3);
+        if self.metadata.time.is_some() {
+            indices.push(slice.time as u64);
+        }
+        if self.metadata.vertical.is_some() {
+            indices.push(slice.level as u64);
+        }
+        indices.extend([row as u64, col as u64]);
+        indices
+    }
+
+    /// Position on the time axis of a requested time step.
+    fn time_step(&self, time: &TimeSelection) -> Result<usize> {
+        self.metadata
+            .time_index(time)
+            .ok_or_else(|| GridProcessorError::NotFound(format!("{} in {}", time, self.path)))
+    }
+
+    /// Chunk cache key of a chunk in a slice.
+    ///
+    /// The first slice uses the path hash, so 2D arrays keep their keys.
+    fn cache_key(&self, slice: Slice, chunk_x: usize, chunk_y: usize) -> ChunkKey {
+        let path_hash = if slice == Slice::default() {
+            self.path_hash
+        } else {
+            hash_path(&format!("{}#t{}z{}", self.path, slice.time, slice.level))
+        };
+        (path_hash, chunk_x, chunk_y)
+    }
+
     /// Calculate which chunks intersect a bounding box.
     ///
     /// This is O(1) - pure arithmetic, no iteration or lookup needed.
@@ -282,8 +436,7 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
         let (chunk_w, chunk_h) = self.metadata.chunk_shape;
 
         // Calculate grid resolution
-        let lon_per_cell = grid_bbox.width() / grid_width as f64;
-        let lat_per_cell = grid_bbox.height() / grid_height as f64;
+        let (lon_per_cell, lat_per_cell) = self.metadata.resolution();
 
         // Normalize bbox to grid's coordinate system (handles 0-360 vs -180/180)
         let norm_bbox = bbox.normalize_to_grid(grid_bbox);
@@ -314,9 +467,9 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
             .collect()
     }
 
-    /// Read and decompress a single chunk (synchronous).
-    fn read_chunk_sync(&self, chunk_x: usize, chunk_y: usize) -> Result<Vec<f32>> {
-        // Cache miss - read from Zarr
+    /// Array subset covered by a chunk in a slice (partial at the grid
+    /// edges).
+    fn chunk_subset(&self, slice: Slice, chunk_x: usize, chunk_y: usize) -> Result<ArraySubset> {
         let (chunk_w, chunk_h) = self.metadata.chunk_shape;
         let (grid_w, grid_h) = self.metadata.shape;
 
@@ -330,6 +483,8 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
 
         debug!(
             path = %self.path,
+            time = slice.time,
+            level = slice.level,
             chunk_x = chunk_x,
             chunk_y = chunk_y,
             start_row = start_row,
@@ -339,12 +494,11 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
             "Reading Zarr chunk"
         );
 
-        // Zarr uses [row, col] indexing
-        let subset = ArraySubset::new_with_start_shape(
-            vec![start_row as u64, start_col as u64],
-            vec![actual_h as u64, actual_w as u64],
-        )
-        .map_err(|e| {
+        // Zarr uses [row, col] indexing, after the time step or level if any
+        let start = self.array_indices(slice, start_row, start_col);
+        let mut subset_shape = vec![1; start.len() - 2];
+        subset_shape.extend([actual_h as u64, actual_w as u64]);
+        ArraySubset::new_with_start_shape(start, subset_shape).map_err(|e| {
             error!(
                 path = %self.path,
                 chunk_x = chunk_x,
@@ -353,25 +507,56 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
                 "Failed to create array subset"
             );
             GridProcessorError::read_failed(e.to_string())
-        })?;
+        })
+    }
 
-        let data: Vec<f32> = self
-            .array
-            .retrieve_array_subset_elements(&subset)
-            .map_err(|e| {
+    /// Read and decompress a single chunk from storage on the blocking pool.
+    async fn fetch_chunk(&self, slice: Slice, chunk_x: usize, chunk_y: usize) -> Result<Vec<f32>> {
+        let subset = self.chunk_subset(slice, chunk_x, chunk_y)?;
+        let array = self.array.clone();
+        let path = self.path.clone();
+        let policy = self.config.checksum_policy;
+
+        let started = Instant::now();
+        let data = tokio::task::spawn_blocking(move || {
+            Self::read_chunk_sync(&array, &path, policy, &subset, chunk_x, chunk_y)
+        })
+        .await
+        .map_err(|e| GridProcessorError::read_failed(format!("Chunk fetch task failed: {}", e)))?;
+        histogram!("zarr_chunk_fetch_duration_ms").record(started.elapsed().as_secs_f64() * 1000.0);
+
+        data
+    }
+
+    /// Read and decompress a single chunk (synchronous).
+    fn read_chunk_sync(
+        array: &Array<S>,
+        path: &str,
+        policy: ChecksumPolicy,
+        subset: &ArraySubset,
+        chunk_x: usize,
+        chunk_y: usize,
+    ) -> Result<Vec<f32>> {
+        let data: Vec<f32> = match array.retrieve_array_subset_elements(subset) {
+            Ok(data) => data,
+            Err(e) if is_checksum_error(&e) => {
+                Self::read_corrupt_chunk(array, path, policy, chunk_x, chunk_y, subset)?
+            }
+            Err(e) => {
                 error!(
-                    path = %self.path,
+                    path = %path,
                     chunk_x = chunk_x,
                     chunk_y = chunk_y,
                     subset = ?subset,
                     error = %e,
                     "Failed to retrieve chunk data from Zarr"
                 );
-                GridProcessorError::read_failed(e.to_string())
-            })?;
+                return Err(read_error(e));
+            }
+        };
 
         debug!(
-            path = %self.path,
+            path = %path,
             chunk_x = chunk_x,
             chunk_y = chunk_y,
             data_len = data.len(),
@@ -381,21 +566,141 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
         Ok(data)
     }
 
-    /// Read and decompress a single chunk with caching.
-    async fn read_chunk(&self, chunk_x: usize, chunk_y: usize) -> Result<Vec<f32>> {
-        let cache_key = (self.path_hash, chunk_x, chunk_y);
+    /// Handle a chunk that failed its checksum according to the configured
+    /// [`ChecksumPolicy`]: fail the read, or decode it without validation.
+    fn read_corrupt_chunk(
+        array: &Array<S>,
+        path: &str,
+        policy: ChecksumPolicy,
+        chunk_x: usize,
+        chunk_y: usize,
+        subset: &ArraySubset,
+    ) -> Result<Vec<f32>> {
+        counter!("zarr_chunk_checksum_failures_total", "policy" => policy.as_str()).increment(1);
+
+        match policy {
+            ChecksumPolicy::Reject => {
+                error!(
+                    path = %path,
+                    chunk_x = chunk_x,
+                    chunk_y = chunk_y,
+                    "Zarr chunk failed checksum verification, rejecting read"
+                );
+                Err(GridProcessorError::ChecksumMismatch(format!(
+                    "chunk ({}, {}) of {}",
+                    chunk_x, chunk_y, path
+                )))
+            }
+            ChecksumPolicy::Warn => {
+                warn!(
+                    path = %path,
+                    chunk_x = chunk_x,
+                    chunk_y = chunk_y,
+                    "Zarr chunk failed checksum verification, serving it unverified"
+                );
+                let options = CodecOptionsBuilder::new().validate_checksums(false).build();
+                array
+                    .retrieve_array_subset_elements_opt(subset, &options)
+                    .map_err(read_error)
+            }
+        }
+    }
+
+    /// Read every chunk from storage, bypassing the cache, and report the
+    /// ones that don't match their checksum.
+    ///
+    /// Used by verification sweeps; the read policy doesn't apply. Arrays
+    /// written without checksums always verify as intact. Time series and
+    /// level stacks are checked at every time step or level.
+    pub fn verify_chunks(&self) -> Result<ChunkVerification> {
+        let (chunks_x, chunks_y) = self.metadata.num_chunks;
+        let mut verification = ChunkVerification::default();
+
+        let slices = (0..self.metadata.num_time_steps()).flat_map(|time| {
+            (0..self.metadata.num_vertical_levels()).map(move |level| Slice { time, level })
+        });
+        for slice in slices {
+            for chunk_y in 0..chunks_y {
+                for chunk_x in 0..chunks_x {
+                    let subset = self.chunk_subset(slice, chunk_x, chunk_y)?;
+                    match self.array.retrieve_array_subset_elements::<f32>(&subset) {
+                        Ok(_) => {}
+                        Err(e) if is_checksum_error(&e) => {
+                            counter!("zarr_chunk_checksum_failures_total", "policy" => "sweep")
+                                .increment(1);
+                            if !verification.corrupt_chunks.contains(&(chunk_x, chunk_y)) {
+                                verification.corrupt_chunks.push((chunk_x, chunk_y));
+                            }
+                        }
+                        Err(e) => return Err(read_error(e)),
+                    }
+                    verification.chunks_checked += 1;
+                }
+            }
+        }
+
+        Ok(verification)
+    }
+
+    /// Read and decompress a single chunk of a slice with caching.
+    async fn read_chunk(&self, slice: Slice, chunk_x: usize, chunk_y: usize) -> Result<Vec<f32>> {
+        let cache_key = self.cache_key(slice, chunk_x, chunk_y);
 
         // Check cache first
-        {
+        let (cached, versions) = {
             let mut cache = self.chunk_cache.write().await;
-            if let Some(data) = cache.get(&cache_key) {
+            let versions = cache.revalidation().map(|r| r.versions.clone());
+            let stale_etag = cache.revalidation_due(&cache_key);
+            let cached = cache.get(&cache_key).map(|data| (data.clone(), stale_etag));
+            (cached, versions)
+        };
+
+        if let Some((data, stale_etag)) = cached {
+            let (Some(etag), Some(versions)) = (stale_etag, versions.as_ref()) else {
                 debug!(
                     path = %self.path,
                     chunk_x = chunk_x,
                     chunk_y = chunk_y,
                     "Chunk cache HIT"
                 );
-                return Ok(data.clone());
+                return Ok(data);
+            };
+
+            // Past the staleness window: conditional request against storage
+            let key = self
+                .storage_key(slice, chunk_x, chunk_y)
+                .unwrap_or_default();
+            match versions.check(&key, &etag).await {
+                Ok(Freshness::NotModified) => {
+                    debug!(
+                        path = %self.path,
+                        chunk_x = chunk_x,
+                        chunk_y = chunk_y,
+                        "Chunk cache HIT (revalidated)"
+                    );
+                    self.chunk_cache.write().await.mark_validated(&cache_key);
+                    return Ok(data);
+                }
+                Ok(freshness) => {
+                    info!(
+                        path = %self.path,
+                        chunk_x = chunk_x,
+                        chunk_y = chunk_y,
+                        freshness = ?freshness,
+                        "Cached chunk changed in storage, refetching"
+                    );
+                    self.chunk_cache.write().await.remove(&cache_key);
+                }
+                Err(e) => {
+                    // Serve the cached chunk rather than failing the request
+                    warn!(
+                        path = %self.path,
+                        key = %key,
+                        error = %e,
+                        "Chunk revalidation failed, serving cached data"
+                    );
+                    return Ok(data);
+                }
             }
         }
 
@@ -406,18 +711,215 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
             "Chunk cache MISS - fetching from storage"
         );
 
+        // Record the object version before reading, so a concurrent overwrite
+        // leaves us with an older ETag and is caught on the next revalidation
+        let etag = self
+            .chunk_etag(versions.as_deref(), slice, chunk_x, chunk_y)
+            .await;
+
         // Cache miss - read from Zarr (blocking in spawn_blocking)
-        let data = self.read_chunk_sync(chunk_x, chunk_y)?;
+        let data = self.fetch_chunk(slice, chunk_x, chunk_y).await?;
 
         // Cache the result
         {
             let mut cache = self.chunk_cache.write().await;
-            cache.insert(cache_key, data.clone());
+            cache.insert_versioned(cache_key, &self.path, data.clone(), etag);
         }
 
         Ok(data)
     }
 
+    /// Current ETag of the object holding a chunk, when revalidation is on.
+    async fn chunk_etag(
+        &self,
+        versions: Option<&dyn ObjectVersions>,
+        slice: Slice,
+        chunk_x: usize,
+        chunk_y: usize,
+    ) -> Option<String> {
+        let versions = versions?;
+        let key = self.storage_key(slice, chunk_x, chunk_y)?;
+        versions.etag(&key).await.unwrap_or_else(|e| {
+            warn!(path = %self.path, key = %key, error = %e, "Failed to fetch chunk ETag");
+            None
+        })
+    }
+
+    /// Fetch a chunk of the first slice into the cache ahead of any read.
+    ///
+    /// Returns true if the chunk was fetched and cached.
+    async fn prefetch_chunk(&self, chunk_x: usize, chunk_y: usize) -> bool {
+        let slice = Slice::default();
+        let versions = {
+            let cache = self.chunk_cache.read().await;
+            cache.revalidation().map(|r| r.versions.clone())
+        };
+        let etag = self
+            .chunk_etag(versions.as_deref(), slice, chunk_x, chunk_y)
+            .await;
+
+        match self.fetch_chunk(slice, chunk_x, chunk_y).await {
+            Ok(data) => {
+                let key = self.cache_key(slice, chunk_x, chunk_y);
+                self.chunk_cache
+                    .write()
+                    .await
+                    .insert_prefetched(key, &self.path, data, etag)
+            }
+            Err(e) => {
+                warn!(
+                    path = %self.path,
+                    chunk_x = chunk_x,
+                    chunk_y = chunk_y,
+                    error = %e,
+                    "Failed to prefetch chunk"
+                );
+                false
+            }
+        }
+    }
+
+    /// Chunks of the first slice around the given tiles that a client
+    /// panning the map will need next and that aren't cached yet, nearest
+    /// tiles first and at most `chunk_prefetch_max_chunks` of them.
+    async fn predicted_chunks(&self, tiles: &[BoundingBox]) -> Vec<(usize, usize)> {
+        let mut chunks: Vec<(usize, usize)> = Vec::new();
+        for tile in tiles {
+            for neighbour in tile.adjacent_tiles(self.config.chunk_prefetch_rings) {
+                // A neighbour across the grid's seam would need two reads; the
+                // next request for it will load it the usual way
+                if neighbour.wraps_on_grid(&self.metadata.bbox) {
+                    continue;
+                }
+                for chunk in self.chunks_for_bbox(&neighbour) {
+                    if !chunks.contains(&chunk) {
+                        chunks.push(chunk);
+                    }
+                }
+            }
+        }
+
+        // The tiles' own chunks were cached when they were read
+        let cache = self.chunk_cache.read().await;
+        chunks
+            .into_iter()
+            .filter(|&(cx, cy)| !cache.contains(&self.cache_key(Slice::default(), cx, cy)))
+            .take(self.config.chunk_prefetch_max_chunks)
+            .collect()
+    }
+
+    /// Prefetch around `tile` on a background task, so the read that just
+    /// served it can return. Does nothing when chunk prefetching is
+    /// disabled (`chunk_prefetch_rings` of 0).
+    pub fn spawn_prefetch(self, tile: BoundingBox) {
+        if self.config.chunk_prefetch_rings == 0 {
+            return;
+        }
+        tokio::spawn(async move {
+            self.prefetch(&[tile]).await;
+        });
+    }
+
+    /// Store key of the object holding a chunk (for ETag revalidation).
+    ///
+    /// For sharded arrays this is the shard containing the chunk.
+    fn storage_key(&self, slice: Slice, chunk_x: usize, chunk_y: usize) -> Option<String> {
+        let (chunk_w, chunk_h) = self.metadata.chunk_shape;
+        let start = self.array_indices(slice, chunk_y * chunk_h, chunk_x * chunk_w);
+        let indices = self
+            .array
+            .chunk_grid()
+            .chunk_indices(&start, self.array.shape())
+            .ok()??;
+        Some(self.array.chunk_key(&indices).as_str().to_string())
+    }
+
+    /// Store keys of `chunks` in a slice, without duplicates (sharded
+    /// arrays hold several chunks per key).
+    fn chunk_keys(&self, slice: Slice, chunks: &[(usize, usize)]) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::with_capacity(chunks.len());
+        for key in chunks
+            .iter()
+            .filter_map(|&(cx, cy)| self.storage_key(slice, cx, cy))
+        {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    /// Provenance of a [`read_point`](GridProcessor::read_point) at
+    /// (lon, lat): the chunks holding the cells it may interpolate between
+    /// (at the first time step or level of 3D arrays).
+    ///
+    /// Points outside the grid read nothing and have no chunk keys.
+    pub fn point_provenance(&self, lon: f64, lat: f64) -> Provenance {
+        let Some((grid_x, grid_y)) = self.point_to_grid(lon, lat) else {
+            return Provenance::new(&self.path, Vec::new());
+        };
+
+        let grid_h = self.metadata.shape.1;
+        let (chunk_w, chunk_h) = self.metadata.chunk_shape;
+        let row = (grid_y.floor() as usize).min(grid_h - 1);
+
+        // Same neighbours as read_point, including the wrap of global grids
+        let (col, next_col, _) = self.metadata.longitude_axis().neighbours(grid_x);
+        let next_row = (row + 1).min(grid_h - 1);
+
+        let chunks: Vec<_> = [row, next_row]
+            .iter()
+            .flat_map(|r| [col, next_col].map(|c| (c / chunk_w, r / chunk_h)))
+            .collect();
+        Provenance::new(&self.path, self.chunk_keys(Slice::default(), &chunks))
+    }
+
+    /// Continuous grid position (column, row) of a point, if it lies on
+    /// the grid. Longitudes in either convention are accepted, and every
+    /// longitude lies on a global grid.
+    fn point_to_grid(&self, lon: f64, lat: f64) -> Option<(f64, f64)> {
+        let grid_bbox = &self.metadata.bbox;
+        if lat < grid_bbox.min_lat || lat > grid_bbox.max_lat {
+            return None;
+        }
+        let lon = normalize_longitude(lon, grid_bbox.uses_0_360_longitude());
+        let grid_x = self.metadata.longitude_axis().column(lon)?;
+        let grid_y = (grid_bbox.max_lat - lat) / self.metadata.resolution().1;
+        Some((grid_x, grid_y))
+    }
+
+    /// Column nearest to continuous column `grid_x`; past the last column
+    /// of a global grid that is column 0.
+    fn nearest_col(&self, grid_x: f64) -> usize {
+        let col = grid_x.round() as usize;
+        if col == self.metadata.shape.0 && self.metadata.longitude_axis().is_global() {
+            0
+        } else {
+            col
+        }
+    }
+
+    /// Check that the array opened from storage has the shape given in
+    /// this processor's metadata.
+    ///
+    /// Catalog metadata describes the native grid; a processor that ended up
+    /// on a downsampled pyramid level would otherwise index it with native
+    /// coordinates and answer with the wrong cells.
+    pub fn verify_shape(&self) -> Result<()> {
+        let shape = self.array.shape();
+        let opened = (
+            shape[shape.len() - 1] as usize,
+            shape[shape.len() - 2] as usize,
+        );
+        if opened != self.metadata.shape {
+            return Err(GridProcessorError::invalid_metadata(format!(
+                "{} has shape {:?}, expected {:?}",
+                self.path, opened, self.metadata.shape
+            )));
+        }
+        Ok(())
+    }
+
     /// Assemble chunks into a contiguous grid region.
     fn assemble_region(
         &self,
@@ -504,17 +1006,21 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
             grid_bbox.max_lat - min_row as f64 * res_y,
         );
 
-        Ok(GridRegion::new(
-            output,
-            out_width,
-            out_height,
-            actual_bbox,
-            (res_x, res_y),
-        ))
+        // Chunk selection above assumes uniform spacing; the buffer added in
+        // read_region absorbs the deviation of non-uniform grids, and the
+        // region carries the exact coordinates of the rows/columns returned.
+        let grid_coords = self.metadata.grid_coordinates();
+        let coordinates = grid_coords.slice(min_col, min_row, (out_width, out_height));
+
+        Ok(
+            GridRegion::new(output, out_width, out_height, actual_bbox, (res_x, res_y))
+                .with_coordinates(coordinates),
+        )
     }
 
-    /// Read a single value at grid coordinates (used for bilinear interpolation)
-    async fn read_single_value(&self, col: usize, row: usize) -> Result<f32> {
+    /// Read a single value at grid coordinates in a slice (used for
+    /// bilinear interpolation)
+    async fn read_single_value(&self, slice: Slice, col: usize, row: usize) -> Result<f32> {
         let (grid_w, grid_h) = self.metadata.shape;
         if col >= grid_w || row >= grid_h {
             return Ok(f32::NAN);
@@ -524,7 +1030,7 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
         let chunk_x = col / chunk_w;
         let chunk_y = row / chunk_h;
 
-        let chunk_data = self.read_chunk(chunk_x, chunk_y).await?;
+        let chunk_data = self.read_chunk(slice, chunk_x, chunk_y).await?;
 
         let chunk_start_col = chunk_x * chunk_w;
         let chunk_start_row = chunk_y * chunk_h;
@@ -536,19 +1042,117 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> ZarrGridProcessor<S> {
 
         Ok(chunk_data.get(idx).copied().unwrap_or(f32::NAN))
     }
+
+    /// Value at a point in a slice, interpolated bilinearly between the four
+    /// surrounding cells (see [`GridProcessor::read_point`]).
+    async fn interpolate_point(&self, lon: f64, lat: f64, slice: Slice) -> Result<Option<f32>> {
+        // Calculate grid indices (floating point for interpolation), if the
+        // point is within grid bounds
+        let Some((grid_x, grid_y)) = self.point_to_grid(lon, lat) else {
+            return Ok(None);
+        };
+        let (grid_w, grid_h) = self.metadata.shape;
+
+        // Check if we're very close to an exact grid point (within 1% of cell size)
+        // If so, return the exact grid cell value without interpolation
+        let dx_frac = grid_x - grid_x.floor();
+        let dy_frac = grid_y - grid_y.floor();
+        let snap_threshold = 0.01; // 1% of cell size
+
+        let near_grid_point = (dx_frac < snap_threshold || dx_frac > (1.0 - snap_threshold))
+            && (dy_frac < snap_threshold || dy_frac > (1.0 - snap_threshold));
+
+        if near_grid_point {
+            // Snap to nearest grid point and return exact value
+            let col = self.nearest_col(grid_x);
+            let row = grid_y.round() as usize;
+            if col >= grid_w || row >= grid_h {
+                return Ok(None);
+            }
+            let value = self.read_single_value(slice, col, row).await?;
+            let fill = self.metadata.fill_value;
+            if value.is_nan() || value == fill {
+                return Ok(None);
+            }
+            return Ok(Some(value));
+        }
+
+        // Calculate the four corners for bilinear interpolation. For global
+        // grids (like GFS 0-360), the last column wraps around to column 0
+        let (x1, x2, dx) = self.metadata.longitude_axis().neighbours(grid_x);
+        let y1 = grid_y.floor() as usize;
+        let y2 = (y1 + 1).min(grid_h - 1);
+
+        if y1 >= grid_h {
+            return Ok(None);
+        }
+
+        // Calculate interpolation weights
+        let dx = dx as f32;
+        let dy = (grid_y - y1 as f64) as f32;
+
+        // Read values at the four corners
+        // We may need to read up to 4 chunks if the point is near chunk boundaries
+        let v11 = self.read_single_value(slice, x1, y1).await?;
+        let v21 = self.read_single_value(slice, x2, y1).await?;
+        let v12 = self.read_single_value(slice, x1, y2).await?;
+        let v22 = self.read_single_value(slice, x2, y2).await?;
+
+        // Check for fill/NaN values
+        let fill = self.metadata.fill_value;
+        if v11.is_nan()
+            || v21.is_nan()
+            || v12.is_nan()
+            || v22.is_nan()
+            || v11 == fill
+            || v21 == fill
+            || v12 == fill
+            || v22 == fill
+        {
+            // If any corner is missing, fall back to nearest neighbor
+            let col = self.nearest_col(grid_x);
+            let row = grid_y.round() as usize;
+            if col >= grid_w || row >= grid_h {
+                return Ok(None);
+            }
+            let value = self.read_single_value(slice, col, row).await?;
+            if value.is_nan() || value == fill {
+                return Ok(None);
+            }
+            return Ok(Some(value));
+        }
+
+        // Bilinear interpolation
+        let v1 = v11 * (1.0 - dx) + v21 * dx;
+        let v2 = v12 * (1.0 - dx) + v22 * dx;
+        let value = v1 * (1.0 - dy) + v2 * dy;
+
+        Ok(Some(value))
+    }
 }
 
 #[async_trait]
 impl<S: ReadableStorageTraits + Send + Sync + 'static> GridProcessor for ZarrGridProcessor<S> {
     async fn read_region(&self, bbox: &BoundingBox) -> Result<GridRegion> {
-        // Check if this request crosses the dateline on a 0-360 grid
-        // If so, we need to read the FULL grid and let the caller handle resampling
-        let effective_bbox = if bbox.crosses_dateline_on_360_grid(&self.metadata.bbox) {
+        self.read_region_at(bbox, &TimeSelection::Index(0)).await
+    }
+
+    async fn read_region_at(&self, bbox: &BoundingBox, time: &TimeSelection) -> Result<GridRegion> {
+        let slice = Slice {
+            time: self.time_step(time)?,
+            level: 0,
+        };
+
+        // Check if this request wraps past the edge of the grid's longitude
+        // convention (the prime meridian of a 0-360 grid, the antimeridian of
+        // a -180/180 one). If so, we need to read the FULL grid and let the
+        // caller handle resampling
+        let effective_bbox = if bbox.wraps_on_grid(&self.metadata.bbox) {
             tracing::debug!(
                 path = %self.path,
                 request_bbox = ?bbox,
                 grid_bbox = ?self.metadata.bbox,
-                "Request crosses dateline on 0-360 grid, reading full grid"
+                "Request wraps around the grid's longitude seam, reading full grid"
             );
             // Use the full grid bbox instead of the request bbox
             self.metadata.bbox.clone()
@@ -603,122 +1207,55 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> GridProcessor for ZarrGri
             ));
         }
 
-        // 2. Read all needed chunks in parallel
+        // 2. Read all needed chunks in parallel, at most
+        // max_concurrent_chunk_fetches at a time
         // This significantly reduces latency when multiple chunks are needed
         // (e.g., 4 chunks @ 50ms each: sequential=200ms, parallel=50ms)
-        let chunk_futures: Vec<_> = chunks
-            .iter()
-            .map(|(cx, cy)| self.read_chunk(*cx, *cy))
-            .collect();
-
-        let chunk_results = futures::future::join_all(chunk_futures).await;
+        let chunk_results: Vec<_> = stream::iter(chunks.iter())
+            .map(|(cx, cy)| self.read_chunk(slice, *cx, *cy))
+            .buffered(self.config.max_concurrent_chunk_fetches.max(1))
+            .collect()
+            .await;
 
         // Collect results, propagating any errors.
-        // Note: We use join_all (not try_join_all) to let all fetches complete even if one fails,
-        // avoiding wasted work. However, we still fail if ANY chunk is missing since we need
+        // Note: We let all fetches complete even if one fails, avoiding wasted work
+        // (the chunks are cached). However, we still fail if ANY chunk is missing since we need
         // all chunks to render a complete tile - partial data would produce incorrect output.
         let chunk_data: Vec<_> = chunk_results.into_iter().collect::<Result<Vec<_>>>()?;
 
         // 3. Assemble chunks into contiguous region
-        self.assemble_region(&effective_bbox, &chunks, &chunk_data)
+        let region = self.assemble_region(&effective_bbox, &chunks, &chunk_data)?;
+        let mut provenance = Provenance::new(&self.path, self.chunk_keys(slice, &chunks));
+        if let Some(axis) = &self.metadata.time {
+            provenance.reference_time = Some(self.metadata.reference_time);
+            provenance.forecast_hour = axis.forecast_hours.get(slice.time).copied();
+        }
+        Ok(region.with_provenance(provenance))
     }
 
     async fn read_point(&self, lon: f64, lat: f64) -> Result<Option<f32>> {
-        // Check if point is within grid bounds
-        if !self.metadata.bbox.contains(lon, lat) {
-            return Ok(None);
-        }
-
-        // Calculate grid indices (floating point for interpolation)
-        let (res_x, res_y) = self.metadata.resolution();
-        let grid_bbox = &self.metadata.bbox;
-        let (grid_w, grid_h) = self.metadata.shape;
-
-        let grid_x = (lon - grid_bbox.min_lon) / res_x;
-        let grid_y = (grid_bbox.max_lat - lat) / res_y;
-
-        // Check if we're very close to an exact grid point (within 1% of cell size)
-        // If so, return the exact grid cell value without interpolation
-        let dx_frac = grid_x - grid_x.floor();
-        let dy_frac = grid_y - grid_y.floor();
-        let snap_threshold = 0.01; // 1% of cell size
-
-        let near_grid_point = (dx_frac < snap_threshold || dx_frac > (1.0 - snap_threshold))
-            && (dy_frac < snap_threshold || dy_frac > (1.0 - snap_threshold));
-
-        if near_grid_point {
-            // Snap to nearest grid point and return exact value
-            let col = grid_x.round() as usize;
-            let row = grid_y.round() as usize;
-            if col >= grid_w || row >= grid_h {
-                return Ok(None);
-            }
-            let value = self.read_single_value(col, row).await?;
-            let fill = self.metadata.fill_value;
-            if value.is_nan() || value == fill {
-                return Ok(None);
-            }
-            return Ok(Some(value));
-        }
-
-        // Calculate the four corners for bilinear interpolation
-        let x1 = grid_x.floor() as usize;
-        let y1 = grid_y.floor() as usize;
+        self.read_point_at(lon, lat, &TimeSelection::Index(0)).await
+    }
 
-        // For global grids (like GFS 0-360), wrap x2 around
-        let is_global = grid_bbox.max_lon - grid_bbox.min_lon > 359.0;
-        let x2 = if is_global && x1 + 1 >= grid_w {
-            0 // Wrap to column 0
-        } else {
-            (x1 + 1).min(grid_w - 1)
+    async fn read_point_at(&self, lon: f64, lat: f64, time: &TimeSelection) -> Result<Option<f32>> {
+        let slice = Slice {
+            time: self.time_step(time)?,
+            level: 0,
         };
-        let y2 = (y1 + 1).min(grid_h - 1);
-
-        if x1 >= grid_w || y1 >= grid_h {
-            return Ok(None);
-        }
-
-        // Calculate interpolation weights
-        let dx = (grid_x - x1 as f64) as f32;
-        let dy = (grid_y - y1 as f64) as f32;
-
-        // Read values at the four corners
-        // We may need to read up to 4 chunks if the point is near chunk boundaries
-        let v11 = self.read_single_value(x1, y1).await?;
-        let v21 = self.read_single_value(x2, y1).await?;
-        let v12 = self.read_single_value(x1, y2).await?;
-        let v22 = self.read_single_value(x2, y2).await?;
+        self.interpolate_point(lon, lat, slice).await
+    }
 
-        // Check for fill/NaN values
-        let fill = self.metadata.fill_value;
-        if v11.is_nan()
-            || v21.is_nan()
-            || v12.is_nan()
-            || v22.is_nan()
-            || v11 == fill
-            || v21 == fill
-            || v12 == fill
-            || v22 == fill
-        {
-            // If any corner is missing, fall back to nearest neighbor
-            let col = grid_x.round() as usize;
-            let row = grid_y.round() as usize;
-            if col >= grid_w || row >= grid_h {
-                return Ok(None);
-            }
-            let value = self.read_single_value(col, row).await?;
-            if value.is_nan() || value == fill {
-                return Ok(None);
-            }
-            return Ok(Some(value));
-        }
+    async fn read_profile(&self, lon: f64, lat: f64) -> Result<Vec<(f64, Option<f32>)>> {
+        let vertical = self.metadata.vertical.as_ref().ok_or_else(|| {
+            GridProcessorError::NotFound(format!("vertical levels of {}", self.path))
+        })?;
 
-        // Bilinear interpolation
-        let v1 = v11 * (1.0 - dx) + v21 * dx;
-        let v2 = v12 * (1.0 - dx) + v22 * dx;
-        let value = v1 * (1.0 - dy) + v2 * dy;
+        // Each level is a separate chunk; read them concurrently
+        let reads = (0..vertical.len())
+            .map(|level| self.interpolate_point(lon, lat, Slice { time: 0, level }));
+        let values = futures::future::try_join_all(reads).await?;
 
-        Ok(Some(value))
+        Ok(vertical.values.iter().copied().zip(values).collect())
     }
 
     fn metadata(&self) -> &GridMetadata {
@@ -726,28 +1263,38 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> GridProcessor for ZarrGri
     }
 
     async fn prefetch(&self, bboxes: &[BoundingBox]) {
-        for bbox in bboxes {
-            let chunks = self.chunks_for_bbox(bbox);
-            for (cx, cy) in chunks {
-                // Fire and forget - errors are logged but not propagated
-                if let Err(e) = self.read_chunk(cx, cy).await {
-                    tracing::warn!(
-                        path = %self.path,
-                        chunk_x = cx,
-                        chunk_y = cy,
-                        error = %e,
-                        "Failed to prefetch chunk"
-                    );
-                }
-            }
+        if self.config.chunk_prefetch_rings == 0 {
+            return;
         }
+        let chunks = self.predicted_chunks(bboxes).await;
+        if chunks.is_empty() {
+            return;
+        }
+
+        debug!(
+            path = %self.path,
+            chunks = ?chunks,
+            "Prefetching {} chunks around {} tiles",
+            chunks.len(),
+            bboxes.len()
+        );
+
+        // Failures are logged and otherwise ignored: a read will retry
+        let warmed = stream::iter(chunks)
+            .map(|(cx, cy)| self.prefetch_chunk(cx, cy))
+            .buffer_unordered(self.config.max_concurrent_chunk_fetches.max(1))
+            .filter(|&cached| std::future::ready(cached))
+            .count()
+            .await;
+        counter!("zarr_chunk_prefetch_total").increment(warmed as u64);
     }
 
     fn cache_stats(&self) -> CacheStats {
-        // Note: We can't easily get stats without blocking here.
-        // For now, return default stats. A more sophisticated implementation
-        // would use a separate stats tracker.
-        CacheStats::default()
+        // Stats are only a snapshot; skip them rather than wait for a writer
+        self.chunk_cache
+            .try_read()
+            .map(|cache| cache.stats())
+            .unwrap_or_default()
     }
 
     async fn read_grid_cell(&self, col: usize, row: usize) -> Result<Option<f32>> {
@@ -756,7 +1303,7 @@ impl<S: ReadableStorageTraits + Send + Sync + 'static> GridProcessor for ZarrGri
             return Ok(None);
         }
 
-        let value = self.read_single_value(col, row).await?;
+        let value = self.read_single_value(Slice::default(), col, row).await?;
 
         // Check for fill value
         let fill = self.metadata.fill_value;
@@ -831,13 +1378,52 @@ impl<S: ReadableStorageTraits + Clone + Send + Sync + 'static> MultiscaleGridPro
         bbox: &BoundingBox,
         output_size: (usize, usize),
     ) -> Result<(GridRegion, u32)> {
-        let level = self.optimal_level_for(bbox, output_size);
+        self.read_region_selected(
+            &LevelSelection::ForOutput {
+                bbox: *bbox,
+                output_size,
+            },
+            bbox,
+        )
+        .await
+    }
+
+    /// Read a region from the level picked by `selection`, returning the
+    /// region and the level it was read from.
+    pub async fn read_region_selected(
+        &self,
+        selection: &LevelSelection,
+        bbox: &BoundingBox,
+    ) -> Result<(GridRegion, u32)> {
+        let level = self.multiscale.select_level(selection);
         let region = self.read_region_at_level(level, bbox).await?;
         Ok((region, level))
     }
 
     /// Read a region from a specific pyramid level.
     pub async fn read_region_at_level(&self, level: u32, bbox: &BoundingBox) -> Result<GridRegion> {
+        let processor = self.level_processor(level)?;
+        let mut region = processor.read_region(bbox).await?;
+        if let Some(provenance) = region.provenance.as_mut() {
+            provenance.pyramid_level = level;
+        }
+        Ok(region)
+    }
+
+    /// Prefetch chunks of the tiles around `tile` at a pyramid level on a
+    /// background task. See [`ZarrGridProcessor::spawn_prefetch`].
+    pub fn spawn_prefetch(&self, level: u32, tile: BoundingBox) {
+        if self.config.chunk_prefetch_rings == 0 {
+            return;
+        }
+        match self.level_processor(level) {
+            Ok(processor) => processor.spawn_prefetch(tile),
+            Err(e) => debug!(level = level, error = %e, "Skipping prefetch for pyramid level"),
+        }
+    }
+
+    /// Open the array of a pyramid level, sharing this factory's chunk cache.
+    fn level_processor(&self, level: u32) -> Result<ZarrGridProcessor<S>> {
         let level_info = self.multiscale.get_level(level).ok_or_else(|| {
             GridProcessorError::invalid_metadata(format!("Pyramid level {} not found", level))
         })?;
@@ -862,15 +1448,13 @@ impl<S: ReadableStorageTraits + Clone + Send + Sync + 'static> MultiscaleGridPro
         );
 
         // Create processor for this level
-        let processor = ZarrGridProcessor::with_metadata(
+        ZarrGridProcessor::with_metadata(
             self.storage.clone(),
             &level_path,
             level_metadata,
             self.chunk_cache.clone(),
             self.config.clone(),
-        )?;
-
-        processor.read_region(bbox).await
+        )
     }
 
     /// Create GridMetadata for a specific pyramid level.
@@ -902,6 +1486,9 @@ impl<S: ReadableStorageTraits + Clone + Send + Sync + 'static> MultiscaleGridPro
             chunk_shape: level.chunk_shape,
             num_chunks: level.num_chunks(),
             fill_value: f32::NAN,
+            coordinates: self.multiscale.coordinates_for_level(level),
+            time: None,
+            vertical: None,
         }
     }
 
@@ -942,6 +1529,9 @@ mod tests {
             chunk_shape: (512, 512),
             num_chunks: (3, 2),
             fill_value: f32::NAN,
+            coordinates: None,
+            time: None,
+            vertical: None,
         };
 
         // Calculate chunks for a small bbox
@@ -0,0 +1,239 @@
+//! Longitude normalization and antimeridian wraparound for resampling.
+//!
+//! Grids store longitudes in one of two conventions: 0 to 360 (GFS) or
+//! -180 to 180 (HRRR, MRMS, GOES), while requests may use either and even
+//! run past ±180 when a client wraps the map. Everything that turns a
+//! longitude into a grid column goes through this module, so tiles on either
+//! side of a seam sample the same cells.
+//!
+//! A *global* grid repeats every 360°: the column after the last one is
+//! column 0 again. The cell between them (359.75° to 360° on a 0.25° GFS
+//! grid) is interpolated across the wrap instead of being left empty.
+
+/// Wrap a longitude into `[start, start + 360)`.
+pub fn wrap_longitude(lon: f64, start: f64) -> f64 {
+    start + (lon - start).rem_euclid(360.0)
+}
+
+/// Normalize a longitude to a grid's convention: `[0, 360]` for 0-360 grids,
+/// `[-180, 180]` otherwise.
+///
+/// Longitudes already in range are returned unchanged, so the eastern edge
+/// of a grid (360° or 180°) stays addressable.
+pub fn normalize_longitude(lon: f64, uses_0_360: bool) -> f64 {
+    let start = if uses_0_360 { 0.0 } else { -180.0 };
+    if lon < start || lon > start + 360.0 {
+        wrap_longitude(lon, start)
+    } else {
+        lon
+    }
+}
+
+/// Regularly spaced longitude axis of a grid or of a region read from one.
+///
+/// Column `i` sits at `min_lon + i * step`, the positions the bilinear
+/// resamplers interpolate between.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LongitudeAxis {
+    min_lon: f64,
+    step: f64,
+    width: usize,
+    global: bool,
+}
+
+impl LongitudeAxis {
+    /// Axis of `width` columns covering `min_lon..max_lon`.
+    ///
+    /// Bounds are treated the way grid metadata records them: each column is
+    /// `(max_lon - min_lon) / width` wide. A span within one and a half
+    /// columns of 360° is a global grid, whether its bounds stop at the last
+    /// column (0 to 359.75) or at 360°; its columns are then exactly
+    /// `360 / width` apart so the wrap lands back on column 0.
+    pub fn new(min_lon: f64, max_lon: f64, width: usize) -> Self {
+        let span = max_lon - min_lon;
+        let step = span / width.max(1) as f64;
+        let global = width > 1 && span > 0.0 && span >= 360.0 - 1.5 * step;
+        Self {
+            min_lon,
+            step: if global { 360.0 / width as f64 } else { step },
+            width,
+            global,
+        }
+    }
+
+    /// Axis of `count` points at `start + i * step`, e.g. from
+    /// [`AxisCoordinates::Regular`](crate::AxisCoordinates::Regular).
+    pub fn regular(start: f64, step: f64, count: usize) -> Self {
+        Self::new(start, start + step * count as f64, count)
+    }
+
+    /// Whether the axis wraps around the globe.
+    pub fn is_global(&self) -> bool {
+        self.global
+    }
+
+    /// Degrees of longitude between adjacent columns.
+    pub fn step(&self) -> f64 {
+        self.step
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Continuous column index of `lon`.
+    ///
+    /// On a global axis every longitude has a column in `[0, width)`. Other
+    /// axes return None outside `min_lon..=min_lon + width * step`; the last
+    /// column is held out to the eastern bound.
+    pub fn column(&self, lon: f64) -> Option<f64> {
+        if self.width == 0 || self.step <= 0.0 {
+            return None;
+        }
+        if self.global {
+            let x = (wrap_longitude(lon, self.min_lon) - self.min_lon) / self.step;
+            // Rounding can land just past the last column; that is column 0
+            return Some(if x >= self.width as f64 { 0.0 } else { x });
+        }
+        let x = (lon - self.min_lon) / self.step;
+        (x >= 0.0 && x <= self.width as f64).then_some(x)
+    }
+
+    /// Columns to interpolate between at continuous column `x`, and the
+    /// weight of the second one.
+    ///
+    /// The last column of a global axis pairs with column 0; elsewhere the
+    /// last column is repeated.
+    pub fn neighbours(&self, x: f64) -> (usize, usize, f64) {
+        let last = self.width.saturating_sub(1);
+        let x1 = (x.max(0.0).floor() as usize).min(last);
+        let x2 = if x1 < last {
+            x1 + 1
+        } else if self.global {
+            0
+        } else {
+            x1
+        };
+        let dx = if x2 == x1 {
+            0.0
+        } else {
+            (x - x1 as f64).clamp(0.0, 1.0)
+        };
+        (x1, x2, dx)
+    }
+
+    /// Bilinearly interpolate row-major `data` (`width` columns, `height`
+    /// rows) at continuous column `x` and row `y`.
+    ///
+    /// Rows are clamped at the top and bottom of the grid. Returns NaN if
+    /// any of the four cells is missing or NaN.
+    pub fn bilinear(&self, data: &[f32], height: usize, x: f64, y: f64) -> f32 {
+        if height == 0 || y < 0.0 {
+            return f32::NAN;
+        }
+        let (x1, x2, dx) = self.neighbours(x);
+        let y1 = (y.floor() as usize).min(height - 1);
+        let y2 = (y1 + 1).min(height - 1);
+        let dy = if y2 == y1 {
+            0.0
+        } else {
+            (y - y1 as f64) as f32
+        };
+        let dx = dx as f32;
+
+        let cell = |col: usize, row: usize| {
+            data.get(row * self.width + col)
+                .copied()
+                .unwrap_or(f32::NAN)
+        };
+        let (v11, v21) = (cell(x1, y1), cell(x2, y1));
+        let (v12, v22) = (cell(x1, y2), cell(x2, y2));
+        if v11.is_nan() || v21.is_nan() || v12.is_nan() || v22.is_nan() {
+            return f32::NAN;
+        }
+
+        let v1 = v11 * (1.0 - dx) + v21 * dx;
+        let v2 = v12 * (1.0 - dx) + v22 * dx;
+        v1 * (1.0 - dy) + v2 * dy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_longitude() {
+        assert_eq!(normalize_longitude(-90.0, true), 270.0);
+        assert_eq!(normalize_longitude(360.0, true), 360.0);
+        assert_eq!(normalize_longitude(370.0, true), 10.0);
+        assert_eq!(normalize_longitude(190.0, false), -170.0);
+        assert_eq!(normalize_longitude(180.0, false), 180.0);
+        assert_eq!(normalize_longitude(-190.0, false), 170.0);
+        assert_eq!(normalize_longitude(270.0, false), -90.0);
+    }
+
+    #[test]
+    fn test_global_axis_detection() {
+        // GFS bounds as decoded from GRIB2 (last column at 359.75)
+        let gfs = LongitudeAxis::new(0.0, 359.75, 1440);
+        assert!(gfs.is_global());
+        assert_eq!(gfs.step(), 0.25);
+        // Cell bounds
+        assert!(LongitudeAxis::new(0.0, 360.0, 1440).is_global());
+        assert!(LongitudeAxis::new(-180.0, 179.0, 360).is_global());
+        // Regional grids and regions read from a global grid
+        assert!(!LongitudeAxis::new(-130.0, -60.0, 7000).is_global());
+        assert!(!LongitudeAxis::new(250.0, 275.0, 100).is_global());
+    }
+
+    #[test]
+    fn test_global_column_wraps() {
+        let axis = LongitudeAxis::new(0.0, 359.75, 1440);
+        assert_eq!(axis.column(0.0), Some(0.0));
+        assert_eq!(axis.column(359.75), Some(1439.0));
+        assert_eq!(axis.column(-0.25), Some(1439.0));
+        assert_eq!(axis.column(180.0), Some(720.0));
+        assert_eq!(axis.column(-180.0), Some(720.0));
+        assert_eq!(axis.column(540.0), Some(720.0));
+
+        let x = axis.column(359.875).unwrap();
+        assert_eq!(axis.neighbours(x), (1439, 0, 0.5));
+        // Just short of 360° rounds onto column 0 rather than past the end
+        assert_eq!(axis.column(-1e-15), Some(0.0));
+    }
+
+    #[test]
+    fn test_regional_column() {
+        let axis = LongitudeAxis::new(250.0, 275.0, 100);
+        assert_eq!(axis.column(250.0), Some(0.0));
+        assert_eq!(axis.column(275.0), Some(100.0));
+        assert_eq!(axis.column(249.9), None);
+        assert_eq!(axis.column(275.1), None);
+        // The last column is held to the eastern bound, not wrapped
+        assert_eq!(axis.neighbours(99.5), (99, 99, 0.0));
+        assert_eq!(axis.neighbours(100.0), (99, 99, 0.0));
+    }
+
+    #[test]
+    fn test_bilinear_across_wrap() {
+        // 4 columns at 0, 90, 180 and 270 degrees
+        let axis = LongitudeAxis::new(0.0, 270.0, 4);
+        assert!(axis.is_global());
+        let data = [0.0f32, 1.0, 2.0, 3.0, 10.0, 11.0, 12.0, 13.0];
+
+        let x = axis.column(315.0).unwrap();
+        assert!((axis.bilinear(&data, 2, x, 0.0) - 1.5).abs() < 1e-6);
+        assert!((axis.bilinear(&data, 2, x, 0.5) - 6.5).abs() < 1e-6);
+        // The same point from the other side of the seam
+        let x = axis.column(-45.0).unwrap();
+        assert!((axis.bilinear(&data, 2, x, 0.0) - 1.5).abs() < 1e-6);
+        // Bottom row is clamped
+        assert!((axis.bilinear(&data, 2, 1.0, 1.5) - 11.0).abs() < 1e-6);
+
+        let mut holes = data;
+        holes[0] = f32::NAN;
+        assert!(axis.bilinear(&holes, 2, 3.5, 0.0).is_nan());
+    }
+}
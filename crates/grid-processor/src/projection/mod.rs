@@ -4,9 +4,11 @@
 //! for re-projecting grids to geographic coordinates.
 
 pub mod interpolation;
+pub mod longitude;
 pub mod reproject;
 
 pub use interpolation::{bilinear_interpolate, cubic_interpolate, nearest_interpolate};
+pub use longitude::{normalize_longitude, wrap_longitude, LongitudeAxis};
 pub use reproject::reproject_geostationary_to_geographic;
 
 use crate::types::BoundingBox;
@@ -6,7 +6,7 @@
 //! # Examples
 //!
 //! ```rust
-//! use grid_processor::{DatasetQuery, TimeSpecification};
+//! use grid_processor::{DatasetQuery, PyramidSelection, TimeSpecification};
 //! use chrono::Utc;
 //!
 //! // Query for a specific forecast
@@ -22,11 +22,19 @@
 //! // Query for observation data (GOES, MRMS)
 //! let query = DatasetQuery::observation("goes18", "CMI_C13")
 //!     .at_time(Utc::now());
+//!
+//! // Read regions at native resolution even when pyramids exist
+//! let query = DatasetQuery::forecast("hrrr", "REFC")
+//!     .with_pyramid(PyramidSelection::NATIVE);
 //! ```
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::types::{BoundingBox, LevelSelection, Provenance, NATIVE_LEVEL};
+
+pub use storage::{canonicalize_level, levels_match, CanonicalLevel, LevelKind};
+
 /// Query parameters for finding a dataset.
 ///
 /// Use the builder methods to construct a query:
@@ -45,6 +53,60 @@ pub struct DatasetQuery {
 
     /// Time specification for finding the dataset
     pub time_spec: TimeSpecification,
+
+    /// How region reads pick a pyramid level
+    #[serde(default)]
+    pub pyramid: PyramidSelection,
+}
+
+/// How a region read picks its pyramid level, for datasets that have them.
+///
+/// Point reads always use native resolution, whatever the selection.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PyramidSelection {
+    /// Coarsest level with at least as many pixels as the output size, or
+    /// native resolution when the read has no output size.
+    #[default]
+    Auto,
+    /// This level, or the coarsest one if the pyramid has fewer levels.
+    Level(u32),
+    /// Coarsest level whose cells are at most this many degrees across.
+    MinResolution(f64),
+    /// Like [`Auto`](Self::Auto) for an output this many times the size:
+    /// above 1 reads finer levels, below 1 coarser ones.
+    Quality(f64),
+}
+
+impl PyramidSelection {
+    /// Native resolution.
+    pub const NATIVE: Self = Self::Level(NATIVE_LEVEL);
+
+    /// How to pick the level for a read of `bbox`, rendered at
+    /// `output_size` if given.
+    pub fn level_selection(
+        &self,
+        bbox: &BoundingBox,
+        output_size: Option<(usize, usize)>,
+    ) -> LevelSelection {
+        let for_output = |(width, height): (usize, usize), scale: f64| {
+            let scaled = |n: usize| ((n as f64 * scale).ceil() as usize).max(1);
+            LevelSelection::ForOutput {
+                bbox: *bbox,
+                output_size: (scaled(width), scaled(height)),
+            }
+        };
+        match *self {
+            Self::Auto => output_size.map_or(LevelSelection::Native, |size| for_output(size, 1.0)),
+            Self::Level(level) => LevelSelection::Level(level),
+            Self::MinResolution(degrees) => LevelSelection::MinResolution(degrees),
+            Self::Quality(quality) => {
+                // Nonsensical factors fall back to automatic selection
+                let quality = if quality > 0.0 { quality } else { 1.0 };
+                output_size.map_or(LevelSelection::Native, |size| for_output(size, quality))
+            }
+        }
+    }
 }
 
 /// Time specification for finding a dataset.
@@ -102,6 +164,7 @@ impl DatasetQuery {
             parameter: parameter.into(),
             level: None,
             time_spec: TimeSpecification::Latest,
+            pyramid: PyramidSelection::Auto,
         }
     }
 
@@ -125,15 +188,20 @@ impl DatasetQuery {
             parameter: parameter.into(),
             level: None,
             time_spec: TimeSpecification::Latest,
+            pyramid: PyramidSelection::Auto,
         }
     }
 
     /// Specify the level for this query.
     ///
+    /// The level is canonicalized, so producer spellings such as
+    /// "2 m above gnd" or "500 hPa" find the datasets cataloged as
+    /// "2 m above ground" and "500 mb".
+    ///
     /// # Arguments
     /// * `level` - Level description (e.g., "2 m above ground", "500 mb", "surface")
     pub fn at_level(mut self, level: impl Into<String>) -> Self {
-        self.level = Some(level.into());
+        self.level = Some(canonicalize_level(&level.into()));
         self
     }
 
@@ -205,6 +273,16 @@ impl DatasetQuery {
         self
     }
 
+    /// Choose how region reads pick a pyramid level (automatic by default).
+    ///
+    /// # Arguments
+    /// * `selection` - e.g. [`PyramidSelection::NATIVE`] for analysis
+    ///   queries that must not see downsampled values
+    pub fn with_pyramid(mut self, selection: PyramidSelection) -> Self {
+        self.pyramid = selection;
+        self
+    }
+
     /// Check if this query is for observation data.
     pub fn is_observation(&self) -> bool {
         matches!(self.time_spec, TimeSpecification::Observation { .. })
@@ -263,6 +341,12 @@ pub struct PointValue {
     pub time: DateTime<Utc>,
     /// Forecast hour (for forecast data)
     pub forecast_hour: Option<u32>,
+    /// Pyramid level that answered the query (always
+    /// [`NATIVE_LEVEL`](crate::types::NATIVE_LEVEL) for
+    /// [`GridDataService::read_point`](crate::GridDataService::read_point))
+    pub pyramid_level: u32,
+    /// Dataset, file and chunks the value was read from
+    pub provenance: Option<Provenance>,
 }
 
 #[cfg(test)]
@@ -321,4 +405,58 @@ mod tests {
         assert_eq!(query.reference_time(), Some(run_time));
         assert_eq!(query.forecast_hour(), Some(12));
     }
+
+    #[test]
+    fn test_at_level_canonicalizes() {
+        let query = DatasetQuery::forecast("hrrr", "TMP").at_level("2 m above gnd");
+        assert_eq!(query.level, Some("2 m above ground".to_string()));
+
+        let query = DatasetQuery::forecast("gfs", "HGT").at_level("500 hPa");
+        assert_eq!(query.level, Some("500 mb".to_string()));
+
+        let query = DatasetQuery::observation("mrms", "REFL").at_level("500m AMSL");
+        assert_eq!(query.level, Some("500 m above MSL".to_string()));
+    }
+
+    #[test]
+    fn test_pyramid_selection() {
+        let bbox = BoundingBox::new(0.0, 0.0, 10.0, 10.0);
+        let query = DatasetQuery::forecast("gfs", "TMP");
+        assert_eq!(query.pyramid, PyramidSelection::Auto);
+        assert_eq!(
+            query.pyramid.level_selection(&bbox, None),
+            LevelSelection::Native
+        );
+        assert_eq!(
+            query.pyramid.level_selection(&bbox, Some((256, 256))),
+            LevelSelection::ForOutput {
+                bbox,
+                output_size: (256, 256)
+            }
+        );
+
+        let query = query.with_pyramid(PyramidSelection::NATIVE);
+        assert_eq!(
+            query.pyramid.level_selection(&bbox, Some((256, 256))),
+            LevelSelection::Level(NATIVE_LEVEL)
+        );
+
+        // Half quality renders as if the tile were half the size
+        assert_eq!(
+            PyramidSelection::Quality(0.5).level_selection(&bbox, Some((256, 255))),
+            LevelSelection::ForOutput {
+                bbox,
+                output_size: (128, 128)
+            }
+        );
+        assert_eq!(
+            PyramidSelection::Quality(-1.0).level_selection(&bbox, Some((256, 256))),
+            PyramidSelection::Auto.level_selection(&bbox, Some((256, 256)))
+        );
+
+        // Older serialized queries without a selection read automatically
+        let json = r#"{"model":"gfs","parameter":"TMP","level":null,"time_spec":"Latest"}"#;
+        let query: DatasetQuery = serde_json::from_str(json).unwrap();
+        assert_eq!(query.pyramid, PyramidSelection::Auto);
+    }
 }
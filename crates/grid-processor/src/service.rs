@@ -25,16 +25,20 @@
 
 use std::sync::Arc;
 
-use storage::Catalog;
+use futures::future::try_join_all;
+use storage::{Catalog, CatalogEntry};
+use tracing::debug;
 
+use crate::derived::DerivedParameter;
+use crate::downsample::MinifyOptions;
 use crate::error::{GridProcessorError, Result};
 use crate::factory::GridProcessorFactory;
-use crate::minio_storage::{create_minio_storage, MinioConfig};
+use crate::object_backends::{create_minio_storage, MinioConfig};
 use crate::processor::{
     parse_multiscale_metadata, GridProcessor, MultiscaleGridProcessorFactory, ZarrGridProcessor,
 };
-use crate::query::{DatasetQuery, PointValue, TimeSpecification};
-use crate::types::{BoundingBox, CacheStats, GridMetadata, GridRegion};
+use crate::query::{DatasetQuery, PointValue, PyramidSelection, TimeSpecification};
+use crate::types::{BoundingBox, CacheStats, GridMetadata, GridRegion, NATIVE_LEVEL};
 use crate::writer::ZarrMetadata;
 
 /// High-level service for accessing grid data.
@@ -45,6 +49,7 @@ use crate::writer::ZarrMetadata;
 /// - Storage access (fetching from MinIO/S3)
 /// - Chunk caching (shared across requests)
 /// - Model-specific handling (0-360 longitude, projection quirks)
+/// - Derived parameters ([`DerivedParameter`]) computed from stored inputs
 ///
 /// # Example
 ///
@@ -96,6 +101,7 @@ impl GridDataService {
     /// * `query` - Dataset query specifying model, parameter, time, level
     /// * `bbox` - Geographic bounding box to read
     /// * `output_size` - Optional output dimensions for pyramid level selection
+    ///   (see [`DatasetQuery::pyramid`] to override it)
     ///
     /// # Returns
     /// `GridRegion` containing the data and metadata
@@ -116,14 +122,30 @@ impl GridDataService {
         bbox: &BoundingBox,
         output_size: Option<(usize, usize)>,
     ) -> Result<GridRegion> {
-        // Find the dataset in the catalog
-        let entry = self.find_dataset(query).await?.ok_or_else(|| {
-            GridProcessorError::NotFound(format!(
-                "No dataset found for {}/{} with specified time/level",
-                query.model, query.parameter
-            ))
-        })?;
+        // Find the dataset in the catalog, falling back to a derived parameter
+        match self.find_dataset(query).await? {
+            Some(entry) => {
+                self.read_entry_region(&entry, bbox, output_size, query.pyramid)
+                    .await
+            }
+            None => match DerivedParameter::parse(&query.parameter) {
+                Some(derived) => {
+                    self.read_derived_region(derived, query, bbox, output_size)
+                        .await
+                }
+                None => Err(not_found(query)),
+            },
+        }
+    }
 
+    /// Read a region of a cataloged dataset.
+    async fn read_entry_region(
+        &self,
+        entry: &CatalogEntry,
+        bbox: &BoundingBox,
+        output_size: Option<(usize, usize)>,
+        pyramid: PyramidSelection,
+    ) -> Result<GridRegion> {
         // Parse Zarr metadata
         let zarr_json = entry.zarr_metadata.as_ref().ok_or_else(|| {
             GridProcessorError::Metadata("Catalog entry missing zarr_metadata".to_string())
@@ -143,8 +165,9 @@ impl GridDataService {
         let multiscale_meta = parse_multiscale_metadata(zarr_json);
 
         // Read the region
-        if let (Some(ms_meta), Some(out_size)) = (multiscale_meta, output_size) {
-            if ms_meta.num_levels() > 1 {
+        if let Some(ms_meta) = multiscale_meta.filter(|m| m.num_levels() > 1) {
+            let selection = pyramid.level_selection(bbox, output_size);
+            if ms_meta.select_level(&selection) != NATIVE_LEVEL {
                 // Use pyramid-aware loading
                 let ms_factory = MultiscaleGridProcessorFactory::new(
                     store,
@@ -154,8 +177,8 @@ impl GridDataService {
                     self.factory.config().clone(),
                 );
 
-                let (region, _level) = ms_factory.read_region_for_output(bbox, out_size).await?;
-                return Ok(region);
+                let (region, _level) = ms_factory.read_region_selected(&selection, bbox).await?;
+                return Ok(with_dataset_provenance(region, entry));
             }
         }
 
@@ -169,7 +192,64 @@ impl GridDataService {
             self.factory.chunk_cache(),
             self.factory.config().clone(),
         )?;
-        processor.read_region(bbox).await
+        let region = processor.read_region(bbox).await?;
+        Ok(with_dataset_provenance(region, entry))
+    }
+
+    /// Read the inputs of a derived parameter over a region and combine
+    /// them. The region's provenance is that of the first input.
+    async fn read_derived_region(
+        &self,
+        derived: DerivedParameter,
+        query: &DatasetQuery,
+        bbox: &BoundingBox,
+        output_size: Option<(usize, usize)>,
+    ) -> Result<GridRegion> {
+        let entries = self.find_derived_inputs(derived, query).await?;
+        let mut regions = try_join_all(
+            entries
+                .iter()
+                .map(|entry| self.read_entry_region(entry, bbox, output_size, query.pyramid)),
+        )
+        .await?;
+
+        // Inputs of one model share a grid, so the same bbox and output
+        // size select the same pyramid level and window
+        if regions
+            .iter()
+            .any(|r| (r.width, r.height) != (regions[0].width, regions[0].height))
+        {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "{} inputs {:?} are on different grids",
+                derived,
+                derived.inputs()
+            )));
+        }
+
+        let grids: Vec<&[f32]> = regions.iter().map(|r| r.data.as_slice()).collect();
+        let data = derived.combine(&grids)?;
+        let mut region = regions.swap_remove(0);
+        region.data = data;
+        debug!(parameter = %derived, model = %query.model, "Computed derived region");
+        Ok(region)
+    }
+
+    /// Read a region and anti-alias it for rendering at `output_size`.
+    ///
+    /// Reads like [`read_region`](Self::read_region), then box filters the
+    /// data when `minify` asks for it (see [`GridRegion::minify`]).
+    pub async fn read_region_minified(
+        &self,
+        query: &DatasetQuery,
+        bbox: &BoundingBox,
+        output_size: (usize, usize),
+        minify: &MinifyOptions,
+    ) -> Result<GridRegion> {
+        let mut region = self.read_region(query, bbox, Some(output_size)).await?;
+        if let Some(window) = region.minify(bbox, output_size, minify) {
+            debug!(window = ?window, "Box filtered minified region");
+        }
+        Ok(region)
     }
 
     /// Query a single point value.
@@ -181,17 +261,29 @@ impl GridDataService {
     /// * `lon` - Longitude in degrees (-180 to 180 or 0 to 360)
     /// * `lat` - Latitude in degrees (-90 to 90)
     ///
+    /// Always reads native resolution, even when the dataset has pyramid
+    /// levels; fails if the array opened isn't the native grid.
+    ///
     /// # Returns
     /// `PointValue` containing the value and metadata
     pub async fn read_point(&self, query: &DatasetQuery, lon: f64, lat: f64) -> Result<PointValue> {
-        // Find the dataset
-        let entry = self.find_dataset(query).await?.ok_or_else(|| {
-            GridProcessorError::NotFound(format!(
-                "No dataset found for {}/{} with specified time/level",
-                query.model, query.parameter
-            ))
-        })?;
+        // Find the dataset, falling back to a derived parameter
+        match self.find_dataset(query).await? {
+            Some(entry) => self.read_entry_point(&entry, lon, lat).await,
+            None => match DerivedParameter::parse(&query.parameter) {
+                Some(derived) => self.read_derived_point(derived, query, lon, lat).await,
+                None => Err(not_found(query)),
+            },
+        }
+    }
 
+    /// Query a single point of a cataloged dataset.
+    async fn read_entry_point(
+        &self,
+        entry: &CatalogEntry,
+        lon: f64,
+        lat: f64,
+    ) -> Result<PointValue> {
         // Parse metadata
         let zarr_json = entry.zarr_metadata.as_ref().ok_or_else(|| {
             GridProcessorError::Metadata("Catalog entry missing zarr_metadata".to_string())
@@ -200,9 +292,11 @@ impl GridDataService {
         let zarr_meta = ZarrMetadata::from_json(zarr_json)
             .map_err(|e| GridProcessorError::Metadata(e.to_string()))?;
 
-        // Build path and create processor
+        // Points always come from native resolution, whatever the pyramid
+        // holds: a downsampled level would blend the value with its neighbours
         let zarr_path = normalize_path(&entry.storage_path);
-        let level_path = append_level_path(&zarr_path, 0);
+        let level = NATIVE_LEVEL;
+        let level_path = append_level_path(&zarr_path, level);
 
         // Create storage
         let store = create_minio_storage(self.factory.minio_config())
@@ -216,9 +310,12 @@ impl GridDataService {
             self.factory.chunk_cache(),
             self.factory.config().clone(),
         )?;
+        processor.verify_shape()?;
 
         // Query the point
         let value = processor.read_point(lon, lat).await?;
+        let mut provenance = processor.point_provenance(lon, lat).with_dataset(entry);
+        provenance.pyramid_level = level;
 
         Ok(PointValue {
             value,
@@ -228,28 +325,56 @@ impl GridDataService {
             level: zarr_meta.level.clone(),
             time: zarr_meta.reference_time,
             forecast_hour: Some(zarr_meta.forecast_hour),
+            pyramid_level: level,
+            provenance: Some(provenance),
         })
     }
 
+    /// Query the inputs of a derived parameter at a point and combine them.
+    async fn read_derived_point(
+        &self,
+        derived: DerivedParameter,
+        query: &DatasetQuery,
+        lon: f64,
+        lat: f64,
+    ) -> Result<PointValue> {
+        let entries = self.find_derived_inputs(derived, query).await?;
+        let mut points = try_join_all(
+            entries
+                .iter()
+                .map(|entry| self.read_entry_point(entry, lon, lat)),
+        )
+        .await?;
+
+        let values: Vec<f32> = points.iter().map(|p| p.value.unwrap_or(f32::NAN)).collect();
+        let value = derived.compute(&values);
+
+        let mut point = points.swap_remove(0);
+        point.value = (!value.is_nan()).then_some(value);
+        point.parameter = derived.name().to_string();
+        point.units = derived.units().to_string();
+        Ok(point)
+    }
+
     /// Get metadata for a dataset without loading data.
     ///
     /// Useful for checking dataset availability or getting bounds.
     pub async fn get_metadata(&self, query: &DatasetQuery) -> Result<GridMetadata> {
-        let entry = self.find_dataset(query).await?.ok_or_else(|| {
-            GridProcessorError::NotFound(format!(
-                "No dataset found for {}/{} with specified time/level",
-                query.model, query.parameter
-            ))
-        })?;
-
-        let zarr_json = entry.zarr_metadata.as_ref().ok_or_else(|| {
-            GridProcessorError::Metadata("Catalog entry missing zarr_metadata".to_string())
-        })?;
-
-        let zarr_meta = ZarrMetadata::from_json(zarr_json)
-            .map_err(|e| GridProcessorError::Metadata(e.to_string()))?;
-
-        Ok(GridMetadata::from(&zarr_meta))
+        let entry = match self.find_dataset(query).await? {
+            Some(entry) => entry,
+            None => match DerivedParameter::parse(&query.parameter) {
+                Some(derived) => {
+                    // Inputs share the grid; the first one describes it
+                    let entries = self.find_derived_inputs(derived, query).await?;
+                    let mut metadata = entry_metadata(&entries[0])?;
+                    metadata.parameter = derived.name().to_string();
+                    metadata.units = derived.units().to_string();
+                    return Ok(metadata);
+                }
+                None => return Err(not_found(query)),
+            },
+        };
+        entry_metadata(&entry)
     }
 
     /// Get cache statistics for monitoring.
@@ -275,6 +400,51 @@ impl GridDataService {
     // Private helpers
     // ========================================================================
 
+    /// Find the datasets of a derived parameter's inputs, in input order.
+    ///
+    /// The first input is found with the query's own time and level; the
+    /// others are pinned to its run, forecast hour (or observation time) and
+    /// level, so every input describes the same moment.
+    async fn find_derived_inputs(
+        &self,
+        derived: DerivedParameter,
+        query: &DatasetQuery,
+    ) -> Result<Vec<CatalogEntry>> {
+        let mut entries: Vec<CatalogEntry> = Vec::with_capacity(derived.inputs().len());
+        for input in derived.inputs() {
+            let input_query = match entries.first() {
+                None => DatasetQuery {
+                    parameter: input.to_string(),
+                    ..query.clone()
+                },
+                Some(first) => DatasetQuery {
+                    model: query.model.clone(),
+                    parameter: input.to_string(),
+                    level: Some(first.level.clone()),
+                    time_spec: match query.time_spec {
+                        TimeSpecification::Observation { .. } => TimeSpecification::Observation {
+                            time: first.reference_time,
+                        },
+                        _ => TimeSpecification::Forecast {
+                            reference_time: Some(first.reference_time),
+                            forecast_hour: Some(first.forecast_hour),
+                        },
+                    },
+                    pyramid: query.pyramid,
+                },
+            };
+
+            let entry = self.find_dataset(&input_query).await?.ok_or_else(|| {
+                GridProcessorError::NotFound(format!(
+                    "No {}/{} dataset to derive {} from",
+                    query.model, input, derived
+                ))
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
     /// Find a dataset in the catalog based on the query.
     async fn find_dataset(&self, query: &DatasetQuery) -> Result<Option<storage::CatalogEntry>> {
         let level = query.level.as_deref();
@@ -288,12 +458,21 @@ impl GridDataService {
                     .map_err(|e| GridProcessorError::Catalog(e.to_string()))
             }
 
+            TimeSpecification::Forecast {
+                reference_time: Some(run),
+                forecast_hour: Some(hour),
+            } => self
+                .catalog
+                .find_by_run_and_forecast_hour(&query.model, &query.parameter, *run, *hour, level)
+                .await
+                .map_err(|e| GridProcessorError::Catalog(e.to_string())),
+
             TimeSpecification::Forecast {
                 reference_time: _,
                 forecast_hour,
             } => {
-                // Note: Current catalog doesn't support filtering by reference_time directly,
-                // so we use the best matching query based on what's available.
+                // Without both a run and a forecast hour, use the best matching
+                // query based on what's available.
                 match (forecast_hour, level) {
                     (Some(hour), Some(lev)) => self
                         .catalog
@@ -361,6 +540,32 @@ impl GridDataService {
     }
 }
 
+/// Error for a query that matches no dataset.
+fn not_found(query: &DatasetQuery) -> GridProcessorError {
+    GridProcessorError::NotFound(format!(
+        "No dataset found for {}/{} with specified time/level",
+        query.model, query.parameter
+    ))
+}
+
+/// Grid metadata of a cataloged dataset.
+fn entry_metadata(entry: &CatalogEntry) -> Result<GridMetadata> {
+    let zarr_json = entry.zarr_metadata.as_ref().ok_or_else(|| {
+        GridProcessorError::Metadata("Catalog entry missing zarr_metadata".to_string())
+    })?;
+
+    let zarr_meta = ZarrMetadata::from_json(zarr_json)
+        .map_err(|e| GridProcessorError::Metadata(e.to_string()))?;
+
+    Ok(GridMetadata::from(&zarr_meta))
+}
+
+/// Add the catalog entry's dataset and run to a region's provenance.
+fn with_dataset_provenance(mut region: GridRegion, entry: &CatalogEntry) -> GridRegion {
+    region.provenance = region.provenance.map(|p| p.with_dataset(entry));
+    region
+}
+
 /// Normalize a storage path to have a leading slash.
 fn normalize_path(path: &str) -> String {
     if path.starts_with('/') {
@@ -0,0 +1,306 @@
+//! Temporal reduction of several grids into one composite.
+//!
+//! Temporal-composite layers ("max reflectivity over the last hour", "24 h
+//! precipitation total") combine every dataset in a time window cell by cell.
+//! [`TemporalAccumulator`] folds the grids in one at a time, so only the
+//! running result is held in memory, and [`TemporalCompositeCache`] keeps
+//! recent composites so requests for the same window don't re-read every
+//! dataset.
+//!
+//! All grids in a composite must have the same shape (callers resample them
+//! onto a common output grid first). NaN cells are skipped; a cell that is NaN
+//! in every grid stays NaN.
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::error::{GridProcessorError, Result};
+use crate::types::CacheStats;
+
+/// How grids in a time window are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemporalReducer {
+    /// Largest value (e.g., max reflectivity over the last hour)
+    Max,
+    /// Total (e.g., 24 h precipitation from hourly accumulations)
+    Sum,
+    /// Average of the valid values
+    Mean,
+}
+
+impl TemporalReducer {
+    /// Name used in configuration and cache keys.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Max => "max",
+            Self::Sum => "sum",
+            Self::Mean => "mean",
+        }
+    }
+}
+
+impl fmt::Display for TemporalReducer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TemporalReducer {
+    type Err = GridProcessorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "max" => Ok(Self::Max),
+            "sum" => Ok(Self::Sum),
+            "mean" => Ok(Self::Mean),
+            _ => Err(GridProcessorError::ConfigError(format!(
+                "unknown temporal reducer '{}' (expected max, sum or mean)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Running cell-by-cell reduction of equally sized grids.
+pub struct TemporalAccumulator {
+    reducer: TemporalReducer,
+    values: Vec<f32>,
+    /// Number of valid (non-NaN) values seen per cell
+    counts: Vec<u32>,
+    grids: usize,
+}
+
+impl TemporalAccumulator {
+    /// Create an accumulator for grids of `len` cells.
+    pub fn new(reducer: TemporalReducer, len: usize) -> Self {
+        Self {
+            reducer,
+            values: vec![f32::NAN; len],
+            counts: vec![0; len],
+            grids: 0,
+        }
+    }
+
+    /// Fold one grid into the composite.
+    pub fn add(&mut self, data: &[f32]) -> Result<()> {
+        if data.len() != self.values.len() {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "temporal composite grid has {} cells, expected {}",
+                data.len(),
+                self.values.len()
+            )));
+        }
+
+        for ((acc, count), &value) in self.values.iter_mut().zip(&mut self.counts).zip(data) {
+            if value.is_nan() {
+                continue;
+            }
+            *acc = if *count == 0 {
+                value
+            } else {
+                match self.reducer {
+                    TemporalReducer::Max => acc.max(value),
+                    TemporalReducer::Sum | TemporalReducer::Mean => *acc + value,
+                }
+            };
+            *count += 1;
+        }
+
+        self.grids += 1;
+        Ok(())
+    }
+
+    /// Number of grids folded in so far.
+    pub fn grid_count(&self) -> usize {
+        self.grids
+    }
+
+    /// Finish the reduction and return the composite grid.
+    pub fn finish(mut self) -> Vec<f32> {
+        if self.reducer == TemporalReducer::Mean {
+            for (value, &count) in self.values.iter_mut().zip(&self.counts) {
+                if count > 0 {
+                    *value /= count as f32;
+                }
+            }
+        }
+        self.values
+    }
+}
+
+/// Reduce a set of equally sized grids in one call.
+pub fn reduce_grids(reducer: TemporalReducer, grids: &[&[f32]]) -> Result<Vec<f32>> {
+    let len = grids.first().map(|g| g.len()).unwrap_or(0);
+    let mut accumulator = TemporalAccumulator::new(reducer, len);
+    for grid in grids {
+        accumulator.add(grid)?;
+    }
+    Ok(accumulator.finish())
+}
+
+/// Memory-bounded LRU cache of reduced composites.
+///
+/// Keys are chosen by the caller and should identify everything the composite
+/// depends on (source datasets, reducer, output grid).
+pub struct TemporalCompositeCache {
+    cache: LruCache<String, Arc<Vec<f32>>>,
+    memory_limit: usize,
+    current_memory: usize,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl TemporalCompositeCache {
+    /// Create a cache holding up to `memory_limit` bytes of composites.
+    pub fn new(memory_limit: usize) -> Self {
+        // Estimate max entries assuming 256×256 tiles
+        let composite_size_estimate = 256 * 256 * std::mem::size_of::<f32>();
+        let max_entries = (memory_limit / composite_size_estimate).max(16);
+
+        Self {
+            cache: LruCache::new(NonZeroUsize::new(max_entries).unwrap()),
+            memory_limit,
+            current_memory: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Get a cached composite.
+    pub fn get(&mut self, key: &str) -> Option<Arc<Vec<f32>>> {
+        match self.cache.get(key) {
+            Some(data) => {
+                self.hits += 1;
+                Some(data.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Insert a composite, evicting least recently used entries to make room.
+    pub fn insert(&mut self, key: String, data: Arc<Vec<f32>>) {
+        let data_size = data.len() * std::mem::size_of::<f32>();
+        if data_size > self.memory_limit {
+            return;
+        }
+
+        if let Some(previous) = self.cache.pop(&key) {
+            self.current_memory -= previous.len() * std::mem::size_of::<f32>();
+        }
+
+        while self.current_memory + data_size > self.memory_limit
+            || self.cache.len() == self.cache.cap().get()
+        {
+            let Some((_, evicted)) = self.cache.pop_lru() else {
+                break;
+            };
+            self.current_memory -= evicted.len() * std::mem::size_of::<f32>();
+            self.evictions += 1;
+        }
+
+        self.cache.put(key, data);
+        self.current_memory += data_size;
+    }
+
+    /// Get cache statistics.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+            memory_bytes: self.current_memory as u64,
+            evictions: self.evictions,
+            ..Default::default()
+        }
+    }
+
+    /// Clear all entries from the cache.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.current_memory = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAN: f32 = f32::NAN;
+
+    #[test]
+    fn test_reducers() {
+        let a = [1.0, 5.0, NAN, NAN];
+        let b = [3.0, 2.0, 4.0, NAN];
+        let grids: [&[f32]; 2] = [&a, &b];
+
+        let max = reduce_grids(TemporalReducer::Max, &grids).unwrap();
+        assert_eq!(&max[..3], &[3.0, 5.0, 4.0]);
+        assert!(max[3].is_nan());
+
+        let sum = reduce_grids(TemporalReducer::Sum, &grids).unwrap();
+        assert_eq!(&sum[..3], &[4.0, 7.0, 4.0]);
+        assert!(sum[3].is_nan());
+
+        // Mean only counts valid values per cell
+        let mean = reduce_grids(TemporalReducer::Mean, &grids).unwrap();
+        assert_eq!(&mean[..3], &[2.0, 3.5, 4.0]);
+        assert!(mean[3].is_nan());
+    }
+
+    #[test]
+    fn test_accumulator_rejects_mismatched_grid() {
+        let mut acc = TemporalAccumulator::new(TemporalReducer::Max, 4);
+        acc.add(&[0.0; 4]).unwrap();
+        assert!(acc.add(&[0.0; 3]).is_err());
+        assert_eq!(acc.grid_count(), 1);
+    }
+
+    #[test]
+    fn test_reducer_parse() {
+        assert_eq!(
+            "MAX".parse::<TemporalReducer>().unwrap(),
+            TemporalReducer::Max
+        );
+        assert_eq!(
+            "sum".parse::<TemporalReducer>().unwrap(),
+            TemporalReducer::Sum
+        );
+        assert!("median".parse::<TemporalReducer>().is_err());
+        assert_eq!(TemporalReducer::Mean.to_string(), "mean");
+    }
+
+    #[test]
+    fn test_composite_cache_eviction() {
+        // Room for two 4-cell composites
+        let mut cache = TemporalCompositeCache::new(32);
+        cache.insert("a".into(), Arc::new(vec![1.0; 4]));
+        cache.insert("b".into(), Arc::new(vec![2.0; 4]));
+        assert!(cache.get("a").is_some());
+
+        // "b" is least recently used
+        cache.insert("c".into(), Arc::new(vec![3.0; 4]));
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("c").unwrap()[0], 3.0);
+
+        // Replacing a key doesn't double count
+        cache.insert("c".into(), Arc::new(vec![4.0; 4]));
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.memory_bytes, 32);
+        assert_eq!(stats.evictions, 1);
+
+        // Too large to cache at all
+        cache.insert("big".into(), Arc::new(vec![0.0; 16]));
+        assert!(cache.get("big").is_none());
+    }
+}
@@ -145,6 +145,7 @@ pub fn write_multiscale_zarr(
         min_dimension,
         downscale_factor: 2,
         default_method: DownsampleMethod::Mean,
+        max_levels: None,
     };
 
     let store = Arc::new(FilesystemStore::new(path)?);
@@ -2,6 +2,10 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use storage::{CatalogEntry, SourceLineage};
+
+use crate::downsample::{box_filter, MinifyOptions};
+use crate::projection::{normalize_longitude, LongitudeAxis};
 
 /// A geographic bounding box in WGS84 coordinates.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -64,6 +68,54 @@ impl BoundingBox {
         }
     }
 
+    /// Predict the tiles a slippy-map client showing this tile will ask for
+    /// next: the `rings` rings of same-size tiles around it, nearest first,
+    /// edge neighbours before corners.
+    ///
+    /// Tiles step by the tile's width in longitude and by its height in Web
+    /// Mercator in latitude, so WebMercatorQuad neighbours come out exact.
+    /// Neighbours past the Mercator latitude limit are dropped; longitudes
+    /// are not wrapped.
+    pub fn adjacent_tiles(&self, rings: usize) -> Vec<BoundingBox> {
+        let max_y = std::f64::consts::PI;
+        let to_y = |lat: f64| lat.to_radians().tan().asinh().clamp(-max_y, max_y);
+        let to_lat = |y: f64| y.sinh().atan().to_degrees();
+
+        let width = self.width();
+        let south = to_y(self.min_lat);
+        let height = to_y(self.max_lat) - south;
+        if !(width > 0.0 && height > 0.0 && width.is_finite()) {
+            return Vec::new();
+        }
+
+        let rings = rings as i64;
+        let mut offsets: Vec<(i64, i64)> = (-rings..=rings)
+            .flat_map(|dy| (-rings..=rings).map(move |dx| (dx, dy)))
+            .filter(|&offset| offset != (0, 0))
+            .collect();
+        offsets.sort_by_key(|&(dx, dy)| (dx.abs().max(dy.abs()), dx.abs() + dy.abs()));
+
+        // Allow for rounding in the latitude round trip at the poles
+        let limit = max_y - height * 1e-6;
+        offsets
+            .into_iter()
+            .filter_map(|(dx, dy)| {
+                let min_y = south + dy as f64 * height;
+                let max_y_tile = min_y + height;
+                if max_y_tile <= -limit || min_y >= limit {
+                    return None;
+                }
+                let west = self.min_lon + dx as f64 * width;
+                Some(BoundingBox::new(
+                    west,
+                    to_lat(min_y.max(-max_y)),
+                    west + width,
+                    to_lat(max_y_tile.min(max_y)),
+                ))
+            })
+            .collect()
+    }
+
     /// Clamp this bounding box to valid geographic coordinates.
     pub fn clamp_to_valid(&self) -> Self {
         Self {
@@ -92,33 +144,36 @@ impl BoundingBox {
         self.min_lon < 0.0 && self.max_lon >= 0.0
     }
 
+    /// Check if this request bbox wraps past the edge of a grid's longitude
+    /// convention, so no single column range of the grid covers it.
+    ///
+    /// Generalizes [`crosses_dateline_on_360_grid`](Self::crosses_dateline_on_360_grid)
+    /// to -180/180 grids, where a request running past 180° (e.g. 170 to 190)
+    /// continues at the grid's western edge.
+    pub fn wraps_on_grid(&self, grid_bbox: &BoundingBox) -> bool {
+        if self.width() >= 360.0 {
+            return true;
+        }
+        let uses_0_360 = grid_bbox.uses_0_360_longitude();
+        normalize_longitude(self.min_lon, uses_0_360)
+            > normalize_longitude(self.max_lon, uses_0_360)
+    }
+
     /// Normalize a request bbox to match a grid's coordinate system.
-    /// If the grid uses 0-360 longitude and the request uses -180/180,
-    /// convert the request to 0-360.
+    /// Longitudes outside the grid's convention are wrapped into it, e.g.
+    /// -100 becomes 260 for a 0-360 grid and 190 becomes -170 for a
+    /// -180/180 grid.
     ///
-    /// NOTE: This does NOT handle cross-dateline requests properly.
-    /// Use `crosses_dateline_on_360_grid()` to check first, and if true,
+    /// NOTE: This does NOT handle requests that wrap around the grid's seam.
+    /// Use `wraps_on_grid()` to check first, and if true,
     /// either load the full grid or split into two requests.
     pub fn normalize_to_grid(&self, grid_bbox: &BoundingBox) -> Self {
-        if grid_bbox.uses_0_360_longitude() && self.min_lon < 0.0 {
-            // Grid uses 0-360, request uses -180/180
-            // Convert request to 0-360 by adding 360 to negative longitudes
-            Self {
-                min_lon: if self.min_lon < 0.0 {
-                    self.min_lon + 360.0
-                } else {
-                    self.min_lon
-                },
-                min_lat: self.min_lat,
-                max_lon: if self.max_lon < 0.0 {
-                    self.max_lon + 360.0
-                } else {
-                    self.max_lon
-                },
-                max_lat: self.max_lat,
-            }
-        } else {
-            *self
+        let uses_0_360 = grid_bbox.uses_0_360_longitude();
+        Self {
+            min_lon: normalize_longitude(self.min_lon, uses_0_360),
+            min_lat: self.min_lat,
+            max_lon: normalize_longitude(self.max_lon, uses_0_360),
+            max_lat: self.max_lat,
         }
     }
 }
@@ -130,6 +185,210 @@ impl Default for BoundingBox {
     }
 }
 
+/// Coordinate values along one grid axis.
+///
+/// Most grids are regular and are described generatively by a start value and
+/// a step. Grids with non-uniform spacing (e.g. Gaussian latitudes) carry the
+/// explicit coordinate of every grid point instead. Values follow the grid's
+/// index order, so a latitude axis is usually descending (north to south).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AxisCoordinates {
+    /// Uniformly spaced points: `start + i * step` for `i` in `0..count`.
+    Regular { start: f64, step: f64, count: usize },
+    /// Explicit, strictly monotonic coordinate of every point.
+    Explicit { values: Vec<f64> },
+}
+
+impl AxisCoordinates {
+    /// Create a regular axis.
+    pub fn regular(start: f64, step: f64, count: usize) -> Self {
+        Self::Regular { start, step, count }
+    }
+
+    /// Create an axis from explicit coordinate values.
+    pub fn explicit(values: Vec<f64>) -> Self {
+        Self::Explicit { values }
+    }
+
+    /// Number of points along the axis.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Regular { count, .. } => *count,
+            Self::Explicit { values } => values.len(),
+        }
+    }
+
+    /// Check if the axis has no points.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Check if the axis is uniformly spaced.
+    pub fn is_regular(&self) -> bool {
+        matches!(self, Self::Regular { .. })
+    }
+
+    /// Coordinate of the point at `index`.
+    pub fn value(&self, index: usize) -> Option<f64> {
+        match self {
+            Self::Regular { start, step, count } => {
+                (index < *count).then_some(start + index as f64 * step)
+            }
+            Self::Explicit { values } => values.get(index).copied(),
+        }
+    }
+
+    /// All coordinate values, in index order.
+    pub fn values(&self) -> Vec<f64> {
+        match self {
+            Self::Regular { start, step, count } => {
+                (0..*count).map(|i| start + i as f64 * step).collect()
+            }
+            Self::Explicit { values } => values.clone(),
+        }
+    }
+
+    /// Continuous index of `coord` along the axis.
+    ///
+    /// Returns None if the coordinate lies outside the first and last points.
+    /// Between two explicit points the index is linearly interpolated, which
+    /// is what bilinear resampling needs.
+    pub fn fractional_index(&self, coord: f64) -> Option<f64> {
+        match self {
+            Self::Regular { start, step, count } => {
+                if *count == 0 || *step == 0.0 {
+                    return None;
+                }
+                let idx = (coord - start) / step;
+                (idx >= 0.0 && idx <= (*count - 1) as f64).then_some(idx)
+            }
+            Self::Explicit { values } => {
+                let (first, last) = (*values.first()?, *values.last()?);
+                let ascending = last >= first;
+                let (lo, hi) = if ascending {
+                    (first, last)
+                } else {
+                    (last, first)
+                };
+                if coord < lo || coord > hi {
+                    return None;
+                }
+                if values.len() == 1 {
+                    return Some(0.0);
+                }
+
+                // First index whose value is past `coord` in axis order
+                let upper = if ascending {
+                    values.partition_point(|&v| v <= coord)
+                } else {
+                    values.partition_point(|&v| v >= coord)
+                };
+                let i = upper.clamp(1, values.len() - 1) - 1;
+                let (a, b) = (values[i], values[i + 1]);
+                let t = if b == a { 0.0 } else { (coord - a) / (b - a) };
+                Some(i as f64 + t.clamp(0.0, 1.0))
+            }
+        }
+    }
+
+    /// Index of the point nearest to `coord`, if it lies within the axis.
+    pub fn nearest_index(&self, coord: f64) -> Option<usize> {
+        self.fractional_index(coord).map(|idx| idx.round() as usize)
+    }
+
+    /// Sub-axis of `len` points starting at index `start`.
+    pub fn slice(&self, start: usize, len: usize) -> Self {
+        match self {
+            Self::Regular {
+                start: origin,
+                step,
+                count,
+            } => Self::Regular {
+                start: origin + start as f64 * step,
+                step: *step,
+                count: len.min(count.saturating_sub(start)),
+            },
+            Self::Explicit { values } => {
+                let end = (start + len).min(values.len());
+                Self::Explicit {
+                    values: values[start.min(end)..end].to_vec(),
+                }
+            }
+        }
+    }
+
+    /// Axis of a 2x downsampled grid, matching `downsample::downsample_2x`.
+    ///
+    /// Each output point sits midway between the two input points it covers.
+    pub fn downsample_2x(&self) -> Self {
+        match self {
+            Self::Regular { start, step, count } => Self::Regular {
+                start: start + step / 2.0,
+                step: step * 2.0,
+                count: count / 2,
+            },
+            Self::Explicit { values } => Self::Explicit {
+                values: values
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0] + pair[1]) / 2.0)
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Longitude and latitude coordinates of a rectilinear grid.
+///
+/// Curvilinear grids (2D coordinate fields, e.g. Lambert Conformal or
+/// geostationary) are not described here; they are handled by the
+/// projection-based resamplers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridCoordinates {
+    /// Longitude of each column, west to east.
+    pub lon: AxisCoordinates,
+    /// Latitude of each row, in row order (usually north to south).
+    pub lat: AxisCoordinates,
+}
+
+impl GridCoordinates {
+    /// Regular coordinates for a grid with points at `min_lon + i * res_x`
+    /// and `max_lat - j * res_y`.
+    pub fn regular(bbox: &BoundingBox, shape: (usize, usize), resolution: (f64, f64)) -> Self {
+        Self {
+            lon: AxisCoordinates::regular(bbox.min_lon, resolution.0, shape.0),
+            lat: AxisCoordinates::regular(bbox.max_lat, -resolution.1, shape.1),
+        }
+    }
+
+    /// Check if both axes are uniformly spaced.
+    pub fn is_regular(&self) -> bool {
+        self.lon.is_regular() && self.lat.is_regular()
+    }
+
+    /// Coordinates of the sub-grid starting at (`col`, `row`) with `shape`.
+    pub fn slice(&self, col: usize, row: usize, shape: (usize, usize)) -> Self {
+        Self {
+            lon: self.lon.slice(col, shape.0),
+            lat: self.lat.slice(row, shape.1),
+        }
+    }
+
+    /// Coordinates of a pyramid level downsampled by `scale` (a power of 2).
+    pub fn for_pyramid_scale(&self, scale: f64) -> Self {
+        let mut coords = self.clone();
+        let mut current = 1.0;
+        while current < scale {
+            coords = Self {
+                lon: coords.lon.downsample_2x(),
+                lat: coords.lat.downsample_2x(),
+            };
+            current *= 2.0;
+        }
+        coords
+    }
+}
+
 /// Grid data for a specific region.
 #[derive(Debug, Clone)]
 pub struct GridRegion {
@@ -142,11 +401,21 @@ pub struct GridRegion {
     /// Geographic bounds of this region.
     pub bbox: BoundingBox,
     /// Resolution in degrees per grid point (lon, lat).
+    ///
+    /// Only exact for regular grids; see `coordinates` for the actual
+    /// position of each point.
     pub resolution: (f64, f64),
+    /// Longitude/latitude of every column and row.
+    pub coordinates: GridCoordinates,
+    /// Where the data was read from, when known.
+    pub provenance: Option<Provenance>,
 }
 
 impl GridRegion {
-    /// Create a new grid region.
+    /// Create a new grid region on a regular grid.
+    ///
+    /// Coordinates are derived from the bbox and resolution; use
+    /// `with_coordinates` for grids with non-uniform spacing.
     pub fn new(
         data: Vec<f32>,
         width: usize,
@@ -154,15 +423,40 @@ impl GridRegion {
         bbox: BoundingBox,
         resolution: (f64, f64),
     ) -> Self {
+        let coordinates = GridCoordinates::regular(&bbox, (width, height), resolution);
         Self {
             data,
             width,
             height,
             bbox,
             resolution,
+            coordinates,
+            provenance: None,
         }
     }
 
+    /// Replace the region's coordinates.
+    pub fn with_coordinates(mut self, coordinates: GridCoordinates) -> Self {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Attach the region's provenance.
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Longitude of each column, west to east.
+    pub fn lon_values(&self) -> Vec<f64> {
+        self.coordinates.lon.values()
+    }
+
+    /// Latitude of each row, in row order.
+    pub fn lat_values(&self) -> Vec<f64> {
+        self.coordinates.lat.values()
+    }
+
     /// Get the value at a specific grid coordinate.
     pub fn get(&self, col: usize, row: usize) -> Option<f32> {
         if col >= self.width || row >= self.height {
@@ -177,6 +471,12 @@ impl GridRegion {
             return None;
         }
 
+        if !self.coordinates.is_regular() {
+            let col = self.coordinates.lon.nearest_index(lon)?;
+            let row = self.coordinates.lat.nearest_index(lat)?;
+            return self.get(col, row);
+        }
+
         let col = ((lon - self.bbox.min_lon) / self.resolution.0).floor() as usize;
         let row = ((self.bbox.max_lat - lat) / self.resolution.1).floor() as usize;
 
@@ -192,6 +492,79 @@ impl GridRegion {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
+
+    /// Source cells per output pixel (x, y) when `bbox` is rendered at
+    /// `output_size`.
+    pub fn oversampling(&self, bbox: &BoundingBox, output_size: (usize, usize)) -> (f64, f64) {
+        let (out_w, out_h) = (output_size.0.max(1) as f64, output_size.1.max(1) as f64);
+        (
+            bbox.width() / out_w / self.resolution.0.abs(),
+            bbox.height() / out_h / self.resolution.1.abs(),
+        )
+    }
+
+    /// Anti-alias the region for rendering `bbox` at `output_size`.
+    ///
+    /// Box filters the data in place when `options` call for it (see
+    /// [`MinifyOptions::window`]). Returns the window used, if any.
+    pub fn minify(
+        &mut self,
+        bbox: &BoundingBox,
+        output_size: (usize, usize),
+        options: &MinifyOptions,
+    ) -> Option<(usize, usize)> {
+        let window = options.window(self.oversampling(bbox, output_size))?;
+        self.data = box_filter(&self.data, self.width, self.height, window);
+        Some(window)
+    }
+}
+
+/// Where a read's values came from: which dataset, run and file, and which
+/// parts of the file were touched.
+///
+/// Processors fill in the array path, pyramid level and chunk keys; the
+/// [`GridDataService`](crate::GridDataService) adds the catalog fields.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Catalog id of the dataset (see [`CatalogEntry::dataset_id`])
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dataset_id: Option<String>,
+    /// Storage path of the Zarr array that was read
+    pub source: String,
+    /// Model run the data belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference_time: Option<DateTime<Utc>>,
+    /// Forecast hour within the run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forecast_hour: Option<u32>,
+    /// Pyramid level read (0 = native resolution)
+    pub pyramid_level: u32,
+    /// Store keys of the chunks read (shards, for sharded arrays)
+    pub chunk_keys: Vec<String>,
+    /// Source file the dataset was ingested from, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<SourceLineage>,
+}
+
+impl Provenance {
+    /// Provenance of a read of `chunk_keys` from the array at `source`.
+    pub fn new(source: impl Into<String>, chunk_keys: Vec<String>) -> Self {
+        Self {
+            source: source.into(),
+            chunk_keys,
+            ..Self::default()
+        }
+    }
+
+    /// Fill in the dataset, run, forecast hour and source lineage from a
+    /// catalog entry.
+    pub fn with_dataset(mut self, entry: &CatalogEntry) -> Self {
+        self.dataset_id = Some(entry.dataset_id());
+        self.reference_time = Some(entry.reference_time);
+        self.forecast_hour = Some(entry.forecast_hour);
+        self.lineage = entry.lineage.clone();
+        self
+    }
 }
 
 /// Metadata about a grid dataset.
@@ -219,17 +592,64 @@ pub struct GridMetadata {
     pub num_chunks: (usize, usize),
     /// Fill/missing value.
     pub fill_value: f32,
+    /// Explicit grid coordinates, for grids that are not uniformly spaced.
+    /// None means regular spacing derived from `bbox` and `shape`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<GridCoordinates>,
+    /// Forecast hours held by a time series array (see [`TimeAxis`]).
+    /// None means a single 2D grid at `forecast_hour`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeAxis>,
+    /// Vertical levels held by a level stack (see [`VerticalAxis`]).
+    /// None means a single 2D grid at `level`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertical: Option<VerticalAxis>,
 }
 
 impl GridMetadata {
+    /// Position on the time axis of a time step, if the grid has it.
+    ///
+    /// Grids without a time axis hold one step: index 0, at `forecast_hour`.
+    pub fn time_index(&self, time: &TimeSelection) -> Option<usize> {
+        match &self.time {
+            Some(axis) => axis.index_of(time, self.reference_time),
+            None => TimeAxis::new(vec![self.forecast_hour]).index_of(time, self.reference_time),
+        }
+    }
+
+    /// Number of time steps held.
+    pub fn num_time_steps(&self) -> usize {
+        self.time.as_ref().map_or(1, TimeAxis::len)
+    }
+
+    /// Number of vertical levels held.
+    pub fn num_vertical_levels(&self) -> usize {
+        self.vertical.as_ref().map_or(1, VerticalAxis::len)
+    }
+
     /// Calculate the grid resolution in degrees per point.
+    ///
+    /// Global grids have exactly `360 / width` degrees between columns, even
+    /// when their bbox stops at the last column (GFS: 0 to 359.75).
     pub fn resolution(&self) -> (f64, f64) {
         (
-            self.bbox.width() / self.shape.0 as f64,
+            self.longitude_axis().step(),
             self.bbox.height() / self.shape.1 as f64,
         )
     }
 
+    /// Longitude axis of the full grid.
+    pub fn longitude_axis(&self) -> LongitudeAxis {
+        LongitudeAxis::new(self.bbox.min_lon, self.bbox.max_lon, self.shape.0)
+    }
+
+    /// Coordinates of every column and row of the full grid.
+    pub fn grid_coordinates(&self) -> GridCoordinates {
+        self.coordinates
+            .clone()
+            .unwrap_or_else(|| GridCoordinates::regular(&self.bbox, self.shape, self.resolution()))
+    }
+
     /// Calculate how many chunks exist along each dimension.
     pub fn calculate_num_chunks(&self) -> (usize, usize) {
         let chunks_x = (self.shape.0 + self.chunk_shape.0 - 1) / self.chunk_shape.0;
@@ -263,6 +683,134 @@ impl GridMetadata {
     }
 }
 
+/// Time axis of an array holding several forecast hours of one run.
+///
+/// Time series arrays are 3D (`[time, rows, cols]`) with one time step per
+/// chunk, so a read only fetches the chunks of the step it asks for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeAxis {
+    /// Forecast hour of each time step, ascending.
+    pub forecast_hours: Vec<u32>,
+}
+
+impl TimeAxis {
+    /// Create a time axis; the hours are sorted and deduplicated.
+    pub fn new(mut forecast_hours: Vec<u32>) -> Self {
+        forecast_hours.sort_unstable();
+        forecast_hours.dedup();
+        Self { forecast_hours }
+    }
+
+    /// Number of time steps.
+    pub fn len(&self) -> usize {
+        self.forecast_hours.len()
+    }
+
+    /// Whether the axis has no time steps.
+    pub fn is_empty(&self) -> bool {
+        self.forecast_hours.is_empty()
+    }
+
+    /// Position of a forecast hour on the axis.
+    pub fn index_of_forecast_hour(&self, forecast_hour: u32) -> Option<usize> {
+        self.forecast_hours.binary_search(&forecast_hour).ok()
+    }
+
+    /// Valid time of the step at `index`.
+    pub fn valid_time(&self, reference_time: DateTime<Utc>, index: usize) -> Option<DateTime<Utc>> {
+        let hour = *self.forecast_hours.get(index)?;
+        Some(reference_time + chrono::Duration::hours(hour as i64))
+    }
+
+    /// Position of a time step on the axis of a run starting at
+    /// `reference_time`. Valid times must match a step exactly.
+    pub fn index_of(&self, time: &TimeSelection, reference_time: DateTime<Utc>) -> Option<usize> {
+        match *time {
+            TimeSelection::Index(index) => (index < self.len()).then_some(index),
+            TimeSelection::ForecastHour(hour) => self.index_of_forecast_hour(hour),
+            TimeSelection::ValidTime(valid_time) => {
+                let hours = (valid_time - reference_time).num_seconds();
+                if hours < 0 || hours % 3600 != 0 {
+                    return None;
+                }
+                self.index_of_forecast_hour(u32::try_from(hours / 3600).ok()?)
+            }
+        }
+    }
+}
+
+/// Vertical axis of an array holding several levels of one parameter
+/// (e.g., temperature on every isobaric level).
+///
+/// Level stacks are 3D (`[level, rows, cols]`) with one level per chunk. Reading a point through every level
+/// ([`read_profile`](crate::GridProcessor::read_profile)) then opens a single
+/// array instead of one per level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerticalAxis {
+    /// Units of the level values (e.g., "hPa", "m").
+    pub units: String,
+    /// Value of each level, in storage order (e.g., 1000, 925, 850 hPa).
+    pub values: Vec<f64>,
+}
+
+impl VerticalAxis {
+    /// Create a vertical axis; duplicate levels are dropped, the order kept.
+    pub fn new(units: impl Into<String>, values: Vec<f64>) -> Self {
+        let mut unique: Vec<f64> = Vec::with_capacity(values.len());
+        for value in values {
+            if unique.iter().all(|&v| !same_level(v, value)) {
+                unique.push(value);
+            }
+        }
+        Self {
+            units: units.into(),
+            values: unique,
+        }
+    }
+
+    /// Number of levels.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the axis has no levels.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Position of a level value on the axis.
+    pub fn index_of(&self, value: f64) -> Option<usize> {
+        self.values.iter().position(|&v| same_level(v, value))
+    }
+}
+
+/// Level values are decoded from scaled GRIB2 integers, so compare them with
+/// a small tolerance.
+fn same_level(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Time step to read from a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSelection {
+    /// Position on the time axis.
+    Index(usize),
+    /// Forecast hour of the run.
+    ForecastHour(u32),
+    /// Valid time (reference time + forecast hour).
+    ValidTime(DateTime<Utc>),
+}
+
+impl std::fmt::Display for TimeSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeSelection::Index(index) => write!(f, "time index {}", index),
+            TimeSelection::ForecastHour(hour) => write!(f, "forecast hour {}", hour),
+            TimeSelection::ValidTime(time) => write!(f, "valid time {}", time.to_rfc3339()),
+        }
+    }
+}
+
 /// Interpolation method for grid resampling.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum InterpolationMethod {
@@ -304,6 +852,16 @@ pub struct CacheStats {
     pub entries: usize,
     pub memory_bytes: u64,
     pub evictions: u64,
+    /// Hits served from the disk tier (included in `hits`).
+    pub disk_hits: u64,
+    pub disk_entries: usize,
+    pub disk_bytes: u64,
+    /// Chunks fetched by prefetching rather than by a read.
+    pub prefetched: u64,
+    /// Prefetched chunks a read later found in memory.
+    pub prefetch_hits: u64,
+    /// Prefetched chunks evicted from memory before any read used them.
+    pub prefetch_misses: u64,
 }
 
 impl CacheStats {
@@ -316,11 +874,23 @@ impl CacheStats {
             self.hits as f64 / total as f64
         }
     }
+
+    /// Fraction of prefetched chunks that were used, of those either used
+    /// or evicted so far (0.0 - 1.0).
+    pub fn prefetch_hit_rate(&self) -> f64 {
+        let total = self.prefetch_hits + self.prefetch_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.prefetch_hits as f64 / total as f64
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
 
     #[test]
     fn test_bbox_intersects() {
@@ -349,6 +919,166 @@ mod tests {
         assert!((bbox.height() - 10.0).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn test_bbox_wraps_on_grid() {
+        let gfs = BoundingBox::new(0.0, -90.0, 359.75, 90.0);
+        let global_180 = BoundingBox::new(-180.0, -90.0, 179.75, 90.0);
+
+        // Across the prime meridian only wraps on a 0-360 grid
+        let prime = BoundingBox::new(-10.0, 0.0, 10.0, 10.0);
+        assert!(prime.wraps_on_grid(&gfs));
+        assert!(!prime.wraps_on_grid(&global_180));
+
+        // Across the antimeridian only wraps on a -180/180 grid
+        for dateline in [
+            BoundingBox::new(170.0, 0.0, 190.0, 10.0),
+            BoundingBox::new(-190.0, 0.0, -170.0, 10.0),
+        ] {
+            assert!(!dateline.wraps_on_grid(&gfs));
+            assert!(dateline.wraps_on_grid(&global_180));
+        }
+
+        assert!(BoundingBox::default().wraps_on_grid(&gfs));
+        assert!(!BoundingBox::new(-100.0, 30.0, -90.0, 40.0).wraps_on_grid(&gfs));
+        assert!(!BoundingBox::new(-10.0, 0.0, 0.0, 10.0).wraps_on_grid(&global_180));
+    }
+
+    #[test]
+    fn test_adjacent_tiles() {
+        // WebMercatorQuad z2 tile (1, 1): x 1, y 1 of 4
+        let tile = BoundingBox::new(-90.0, 0.0, 0.0, 66.51326044311186);
+        let tiles = tile.adjacent_tiles(1);
+        assert_eq!(tiles.len(), 8);
+
+        // Edge neighbours first, then corners
+        let close = |a: &BoundingBox, b: &BoundingBox| {
+            (a.min_lon - b.min_lon).abs() < 1e-9
+                && (a.min_lat - b.min_lat).abs() < 1e-9
+                && (a.max_lon - b.max_lon).abs() < 1e-9
+                && (a.max_lat - b.max_lat).abs() < 1e-9
+        };
+        let south = BoundingBox::new(-90.0, -66.51326044311186, 0.0, 0.0);
+        let north = BoundingBox::new(-90.0, 66.51326044311186, 0.0, 85.0511287798066);
+        let east = BoundingBox::new(0.0, 0.0, 90.0, 66.51326044311186);
+        assert!(tiles[..4].iter().any(|t| close(t, &south)));
+        assert!(tiles[..4].iter().any(|t| close(t, &north)));
+        assert!(tiles[..4].iter().any(|t| close(t, &east)));
+        assert!(tiles[4..].iter().all(|t| t.min_lon != -90.0));
+
+        // Nothing past the Mercator limit: the row north of the top row
+        // is dropped
+        let top = BoundingBox::new(-90.0, 66.51326044311186, 0.0, 85.0511287798066);
+        assert_eq!(top.adjacent_tiles(1).len(), 5);
+        assert_eq!(tile.adjacent_tiles(2).len(), 19);
+        assert!(tile.adjacent_tiles(0).is_empty());
+        assert!(BoundingBox::new(0.0, 10.0, 0.0, 20.0)
+            .adjacent_tiles(1)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_global_grid_resolution() {
+        let metadata = |bbox: BoundingBox, shape: (usize, usize)| GridMetadata {
+            model: "gfs".to_string(),
+            parameter: "TMP".to_string(),
+            level: "2 m above ground".to_string(),
+            units: "K".to_string(),
+            reference_time: Utc::now(),
+            forecast_hour: 0,
+            bbox,
+            shape,
+            chunk_shape: (512, 512),
+            num_chunks: (3, 2),
+            fill_value: f32::NAN,
+            coordinates: None,
+            time: None,
+            vertical: None,
+        };
+
+        // Bounds decoded from GRIB2 stop at the last column
+        let gfs = metadata(BoundingBox::new(0.0, -90.0, 359.75, 90.0), (1440, 721));
+        assert_eq!(gfs.resolution().0, 0.25);
+        assert_eq!(gfs.grid_coordinates().lon.value(1439), Some(359.75));
+        let cells = metadata(BoundingBox::new(0.0, -90.0, 360.0, 90.0), (1440, 721));
+        assert_eq!(cells.resolution().0, 0.25);
+
+        let regional = metadata(BoundingBox::new(-130.0, 20.0, -60.0, 55.0), (700, 350));
+        assert!((regional.resolution().0 - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_time_index() {
+        let run = Utc.with_ymd_and_hms(2024, 12, 22, 0, 0, 0).unwrap();
+        let hours = |h: i64| run + chrono::Duration::hours(h);
+        let mut metadata = GridMetadata {
+            model: "gfs".to_string(),
+            parameter: "TMP".to_string(),
+            level: "2 m above ground".to_string(),
+            units: "K".to_string(),
+            reference_time: run,
+            forecast_hour: 6,
+            bbox: BoundingBox::new(0.0, -90.0, 360.0, 90.0),
+            shape: (1440, 721),
+            chunk_shape: (512, 512),
+            num_chunks: (3, 2),
+            fill_value: f32::NAN,
+            coordinates: None,
+            time: None,
+            vertical: None,
+        };
+
+        // A 2D grid holds only its own forecast hour
+        assert_eq!(metadata.num_time_steps(), 1);
+        assert_eq!(metadata.time_index(&TimeSelection::Index(0)), Some(0));
+        assert_eq!(
+            metadata.time_index(&TimeSelection::ForecastHour(6)),
+            Some(0)
+        );
+        assert_eq!(metadata.time_index(&TimeSelection::ForecastHour(0)), None);
+        assert_eq!(
+            metadata.time_index(&TimeSelection::ValidTime(hours(6))),
+            Some(0)
+        );
+
+        metadata.time = Some(TimeAxis::new(vec![12, 0, 6, 6, 3]));
+        let axis = metadata.time.as_ref().unwrap();
+        assert_eq!(axis.forecast_hours, vec![0, 3, 6, 12]);
+        assert_eq!(axis.valid_time(run, 3), Some(hours(12)));
+        assert_eq!(axis.valid_time(run, 4), None);
+
+        assert_eq!(metadata.num_time_steps(), 4);
+        assert_eq!(metadata.time_index(&TimeSelection::Index(3)), Some(3));
+        assert_eq!(metadata.time_index(&TimeSelection::Index(4)), None);
+        assert_eq!(
+            metadata.time_index(&TimeSelection::ForecastHour(3)),
+            Some(1)
+        );
+        assert_eq!(
+            metadata.time_index(&TimeSelection::ValidTime(hours(12))),
+            Some(3)
+        );
+        // No step in between hours or before the run
+        let half_past = hours(3) + chrono::Duration::minutes(30);
+        assert_eq!(
+            metadata.time_index(&TimeSelection::ValidTime(half_past)),
+            None
+        );
+        assert_eq!(
+            metadata.time_index(&TimeSelection::ValidTime(hours(-6))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_vertical_axis() {
+        let axis = VerticalAxis::new("hPa", vec![1000.0, 850.0, 500.0, 850.0, 250.0]);
+        assert_eq!(axis.values, vec![1000.0, 850.0, 500.0, 250.0]);
+        assert_eq!(axis.len(), 4);
+        assert_eq!(axis.index_of(500.0), Some(2));
+        assert_eq!(axis.index_of(500.000_000_1), Some(2));
+        assert_eq!(axis.index_of(700.0), None);
+    }
+
     #[test]
     fn test_grid_region_get() {
         let data: Vec<f32> = (0..9).map(|i| i as f32).collect();
@@ -360,6 +1090,158 @@ mod tests {
         assert_eq!(region.get(3, 0), None);
     }
 
+    #[test]
+    fn test_grid_region_regular_coordinates() {
+        let region = GridRegion::new(
+            vec![0.0; 6],
+            3,
+            2,
+            BoundingBox::new(10.0, 0.0, 13.0, 2.0),
+            (1.0, 1.0),
+        );
+
+        assert!(region.coordinates.is_regular());
+        assert_eq!(region.lon_values(), vec![10.0, 11.0, 12.0]);
+        assert_eq!(region.lat_values(), vec![2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_grid_region_minify() {
+        let data: Vec<f32> = (0..64).map(|i| (i % 2) as f32 * 10.0).collect();
+        let bbox = BoundingBox::new(0.0, 0.0, 8.0, 8.0);
+        let mut region = GridRegion::new(data.clone(), 8, 8, bbox, (1.0, 1.0));
+
+        // 8 cells into 2 pixels: 4 source cells per output pixel
+        assert_eq!(region.oversampling(&bbox, (2, 2)), (4.0, 4.0));
+
+        assert_eq!(
+            region.minify(&bbox, (2, 2), &MinifyOptions::default()),
+            None
+        );
+        assert_eq!(region.data, data);
+
+        let window = region.minify(&bbox, (2, 2), &MinifyOptions::box_filter());
+        assert_eq!(window, Some((4, 4)));
+        assert!((region.get(2, 2).unwrap() - 5.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_explicit_axis_fractional_index() {
+        // Descending, non-uniform latitudes (Gaussian-like)
+        let lat = AxisCoordinates::explicit(vec![60.0, 50.0, 30.0, 0.0]);
+
+        assert_eq!(lat.fractional_index(60.0), Some(0.0));
+        assert_eq!(lat.fractional_index(40.0), Some(1.5));
+        assert_eq!(lat.fractional_index(15.0), Some(2.5));
+        assert_eq!(lat.fractional_index(0.0), Some(3.0));
+        assert_eq!(lat.fractional_index(70.0), None);
+        assert_eq!(lat.nearest_index(34.0), Some(2));
+
+        let lon = AxisCoordinates::explicit(vec![0.0, 1.0, 3.0]);
+        assert_eq!(lon.fractional_index(2.0), Some(1.5));
+    }
+
+    #[test]
+    fn test_axis_slice_and_downsample() {
+        let regular = AxisCoordinates::regular(0.0, 0.5, 8);
+        assert_eq!(regular.slice(2, 3).values(), vec![1.0, 1.5, 2.0]);
+        assert_eq!(regular.slice(6, 5).len(), 2);
+        assert_eq!(
+            regular.downsample_2x().values(),
+            vec![0.25, 1.25, 2.25, 3.25]
+        );
+
+        let explicit = AxisCoordinates::explicit(vec![0.0, 1.0, 3.0, 7.0, 8.0]);
+        assert_eq!(explicit.slice(1, 2).values(), vec![1.0, 3.0]);
+        assert_eq!(explicit.downsample_2x().values(), vec![0.5, 5.0]);
+    }
+
+    #[test]
+    fn test_grid_region_explicit_coordinates_lookup() {
+        let data: Vec<f32> = (0..6).map(|i| i as f32).collect();
+        let coords = GridCoordinates {
+            lon: AxisCoordinates::regular(0.0, 1.0, 3),
+            lat: AxisCoordinates::explicit(vec![10.0, 8.0]),
+        };
+        let region = GridRegion::new(
+            data,
+            3,
+            2,
+            BoundingBox::new(0.0, 8.0, 2.0, 10.0),
+            (1.0, 1.0),
+        )
+        .with_coordinates(coords);
+
+        assert_eq!(region.get_at_coords(2.0, 8.2), Some(5.0));
+        assert_eq!(region.get_at_coords(0.0, 9.8), Some(0.0));
+    }
+
+    #[test]
+    fn test_multiscale_coordinates_for_level() {
+        let lat = AxisCoordinates::explicit(vec![40.0, 30.0, 25.0, 5.0]);
+        let multiscale = MultiscaleMetadata {
+            name: "test_TMP".to_string(),
+            axes: vec![
+                AxisInfo::spatial_degrees("y").with_coordinates(lat),
+                AxisInfo::spatial_degrees("x"),
+            ],
+            levels: vec![
+                PyramidLevel::new(0, "0", (4, 4), 1.0, (4, 4)),
+                PyramidLevel::new(1, "1", (2, 2), 2.0, (2, 2)),
+            ],
+            downsample_method: "mean".to_string(),
+            native_resolution: (1.0, 10.0),
+            bbox: BoundingBox::new(0.0, 5.0, 4.0, 40.0),
+        };
+
+        let level1 = multiscale.get_level(1).unwrap();
+        let coords = multiscale.coordinates_for_level(level1).unwrap();
+        assert_eq!(coords.lat.values(), vec![35.0, 15.0]);
+        assert_eq!(coords.lon.values(), vec![0.5, 2.5]);
+    }
+
+    #[test]
+    fn test_select_level() {
+        let multiscale = MultiscaleMetadata {
+            name: "test_TMP".to_string(),
+            axes: vec![
+                AxisInfo::spatial_degrees("y"),
+                AxisInfo::spatial_degrees("x"),
+            ],
+            levels: vec![
+                PyramidLevel::new(0, "0", (1024, 1024), 1.0, (256, 256)),
+                PyramidLevel::new(1, "1", (512, 512), 2.0, (256, 256)),
+                PyramidLevel::new(2, "2", (256, 256), 4.0, (256, 256)),
+            ],
+            downsample_method: "mean".to_string(),
+            native_resolution: (0.1, 0.1),
+            bbox: BoundingBox::new(0.0, 0.0, 102.4, 102.4),
+        };
+
+        // A zoomed-out tile is served from a coarse level...
+        let tile = LevelSelection::ForOutput {
+            bbox: multiscale.bbox,
+            output_size: (256, 256),
+        };
+        assert_eq!(multiscale.select_level(&tile), 2);
+
+        // ...but a point never is
+        assert_eq!(
+            multiscale.select_level(&LevelSelection::Native),
+            NATIVE_LEVEL
+        );
+
+        // Explicit levels are clamped to the pyramid
+        assert_eq!(multiscale.select_level(&LevelSelection::Level(1)), 1);
+        assert_eq!(multiscale.select_level(&LevelSelection::Level(7)), 2);
+
+        // Native cells are 0.1°, level 1 0.2° and level 2 0.4°
+        let select = |degrees| multiscale.select_level(&LevelSelection::MinResolution(degrees));
+        assert_eq!(select(0.25), 1);
+        assert_eq!(select(1.0), 2);
+        assert_eq!(select(0.05), NATIVE_LEVEL);
+    }
+
     #[test]
     fn test_interpolation_method_from_str() {
         assert_eq!(
@@ -393,6 +1275,54 @@ mod tests {
         stats.misses = 20;
         assert!((stats.hit_rate() - 0.8).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_provenance_with_dataset() {
+        let reference_time = Utc.with_ymd_and_hms(2024, 12, 22, 6, 0, 0).unwrap();
+        let entry = CatalogEntry {
+            model: "gfs".to_string(),
+            parameter: "TMP".to_string(),
+            level: "2 m above ground".to_string(),
+            reference_time,
+            forecast_hour: 3,
+            bbox: wms_common::BoundingBox::new(0.0, -90.0, 360.0, 90.0),
+            storage_path: "grids/gfs/20241222_06z/tmp_f003.zarr".to_string(),
+            file_size: 0,
+            zarr_metadata: None,
+            ensemble_member: None,
+            lineage: Some(SourceLineage {
+                url: None,
+                file: "/data/gfs.t06z.pgrb2.0p25.f003".to_string(),
+                sha256: "00".repeat(32),
+                message_offset: Some(4096),
+                message_length: Some(1024),
+                ingestion_version: "0.1.0".to_string(),
+            }),
+        };
+
+        let region = GridRegion::new(
+            vec![0.0; 4],
+            2,
+            2,
+            BoundingBox::new(0.0, 0.0, 2.0, 2.0),
+            (1.0, 1.0),
+        )
+        .with_provenance(Provenance::new(
+            "/grids/gfs/20241222_06z/tmp_f003.zarr/0",
+            vec!["c/0/0".to_string()],
+        ));
+        let provenance = region.provenance.unwrap().with_dataset(&entry);
+
+        assert_eq!(
+            provenance.dataset_id.as_deref(),
+            Some("gfs/TMP/2 m above ground/20241222T0600Z/f003")
+        );
+        assert_eq!(provenance.reference_time, Some(reference_time));
+        assert_eq!(provenance.forecast_hour, Some(3));
+        assert_eq!(provenance.pyramid_level, 0);
+        assert_eq!(provenance.chunk_keys, vec!["c/0/0"]);
+        assert_eq!(provenance.lineage, entry.lineage);
+    }
 }
 
 // ============================================================================
@@ -463,6 +1393,9 @@ pub struct AxisInfo {
     /// Physical unit (e.g., "degree", "meter")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub unit: Option<String>,
+    /// Native-resolution coordinates along this axis, if not uniformly spaced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<AxisCoordinates>,
 }
 
 impl AxisInfo {
@@ -472,8 +1405,37 @@ impl AxisInfo {
             name: name.to_string(),
             axis_type: "space".to_string(),
             unit: Some("degree".to_string()),
+            coordinates: None,
         }
     }
+
+    /// Attach explicit coordinates to this axis.
+    pub fn with_coordinates(mut self, coordinates: AxisCoordinates) -> Self {
+        self.coordinates = Some(coordinates);
+        self
+    }
+}
+
+/// Pyramid level holding the data at native resolution.
+pub const NATIVE_LEVEL: u32 = 0;
+
+/// How a read picks its pyramid level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LevelSelection {
+    /// Native resolution only. Point queries use this so a value is never
+    /// averaged with its neighbours by downsampling.
+    Native,
+    /// Coarsest level that still has enough pixels to render `bbox` at
+    /// `output_size` (see [`MultiscaleMetadata::optimal_level_for`]).
+    ForOutput {
+        bbox: BoundingBox,
+        output_size: (usize, usize),
+    },
+    /// This level, or the coarsest one if the pyramid has fewer levels.
+    Level(u32),
+    /// Coarsest level whose cells are at most this many degrees across
+    /// (native resolution if none is that fine).
+    MinResolution(f64),
 }
 
 /// Metadata for a multi-resolution (pyramid) dataset.
@@ -504,7 +1466,33 @@ impl MultiscaleMetadata {
 
     /// Get the native (level 0) metadata.
     pub fn native_level(&self) -> Option<&PyramidLevel> {
-        self.get_level(0)
+        self.get_level(NATIVE_LEVEL)
+    }
+
+    /// Pyramid level a read should use.
+    pub fn select_level(&self, selection: &LevelSelection) -> u32 {
+        match selection {
+            LevelSelection::Native => NATIVE_LEVEL,
+            LevelSelection::ForOutput { bbox, output_size } => {
+                self.optimal_level_for(bbox, *output_size)
+            }
+            LevelSelection::Level(level) => self
+                .levels
+                .iter()
+                .map(|l| l.level)
+                .filter(|l| l <= level)
+                .max()
+                .unwrap_or(NATIVE_LEVEL),
+            LevelSelection::MinResolution(degrees) => self
+                .levels
+                .iter()
+                .rev()
+                .find(|l| {
+                    let (x, y) = l.resolution(self.native_resolution);
+                    x.max(y) <= *degrees
+                })
+                .map_or(NATIVE_LEVEL, |l| l.level),
+        }
     }
 
     /// Get the coarsest (highest level number) pyramid level.
@@ -541,11 +1529,39 @@ impl MultiscaleMetadata {
         }
 
         // Fall back to native resolution
-        0
+        NATIVE_LEVEL
     }
 
     /// Get the number of pyramid levels.
     pub fn num_levels(&self) -> usize {
         self.levels.len()
     }
+
+    /// Explicit coordinates at a pyramid level, if the native axes carry them.
+    ///
+    /// Returns None for regular grids, whose coordinates follow from the
+    /// bbox and level shape.
+    pub fn coordinates_for_level(&self, level: &PyramidLevel) -> Option<GridCoordinates> {
+        let axis = |name: &str| {
+            self.axes
+                .iter()
+                .find(|a| a.name == name)
+                .and_then(|a| a.coordinates.clone())
+        };
+        let (lon, lat) = (axis("x"), axis("y"));
+        if lon.is_none() && lat.is_none() {
+            return None;
+        }
+
+        let (width, height) = self.native_level().map(|l| l.shape)?;
+        let native = GridCoordinates {
+            lon: lon.unwrap_or_else(|| {
+                AxisCoordinates::regular(self.bbox.min_lon, self.native_resolution.0, width)
+            }),
+            lat: lat.unwrap_or_else(|| {
+                AxisCoordinates::regular(self.bbox.max_lat, -self.native_resolution.1, height)
+            }),
+        };
+        Some(native.for_pyramid_scale(level.scale))
+    }
 }
@@ -0,0 +1,311 @@
+//! Vertical reduction of the levels of one dataset into a column composite.
+//!
+//! Column layers ("max temperature between 1000 and 500 mb", "mean wind
+//! speed in the lowest kilometre") combine every level of a parameter in a
+//! [`LevelRange`] cell by cell. [`VerticalAccumulator`] folds the levels in
+//! one at a time, like [`TemporalAccumulator`](crate::TemporalAccumulator)
+//! does for time windows, so only the running result is held in memory.
+//!
+//! All level grids must have the same shape. NaN cells are skipped (a level
+//! below ground is usually NaN); a cell that is NaN at every level stays NaN.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{GridProcessorError, Result};
+use crate::query::{CanonicalLevel, LevelKind};
+
+/// How the levels in a range are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VerticalReducer {
+    /// Largest value in the column (e.g., max temperature aloft)
+    Max,
+    /// Smallest value in the column (e.g., coldest level)
+    Min,
+    /// Average of the valid levels (unweighted by layer thickness)
+    Mean,
+}
+
+impl VerticalReducer {
+    /// Name used in configuration and cache keys.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Max => "max",
+            Self::Min => "min",
+            Self::Mean => "mean",
+        }
+    }
+}
+
+impl fmt::Display for VerticalReducer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VerticalReducer {
+    type Err = GridProcessorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "max" => Ok(Self::Max),
+            "min" => Ok(Self::Min),
+            "mean" => Ok(Self::Mean),
+            _ => Err(GridProcessorError::ConfigError(format!(
+                "unknown vertical reducer '{}' (expected max, min or mean)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Running cell-by-cell reduction of the levels of a column.
+pub struct VerticalAccumulator {
+    reducer: VerticalReducer,
+    values: Vec<f32>,
+    /// Number of valid (non-NaN) levels seen per cell
+    counts: Vec<u32>,
+    levels: usize,
+}
+
+impl VerticalAccumulator {
+    /// Create an accumulator for level grids of `len` cells.
+    pub fn new(reducer: VerticalReducer, len: usize) -> Self {
+        Self {
+            reducer,
+            values: vec![f32::NAN; len],
+            counts: vec![0; len],
+            levels: 0,
+        }
+    }
+
+    /// Fold one level into the composite.
+    pub fn add(&mut self, data: &[f32]) -> Result<()> {
+        if data.len() != self.values.len() {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "vertical composite level has {} cells, expected {}",
+                data.len(),
+                self.values.len()
+            )));
+        }
+
+        for ((acc, count), &value) in self.values.iter_mut().zip(&mut self.counts).zip(data) {
+            if value.is_nan() {
+                continue;
+            }
+            *acc = if *count == 0 {
+                value
+            } else {
+                match self.reducer {
+                    VerticalReducer::Max => acc.max(value),
+                    VerticalReducer::Min => acc.min(value),
+                    VerticalReducer::Mean => *acc + value,
+                }
+            };
+            *count += 1;
+        }
+
+        self.levels += 1;
+        Ok(())
+    }
+
+    /// Number of levels folded in so far.
+    pub fn level_count(&self) -> usize {
+        self.levels
+    }
+
+    /// Finish the reduction and return the composite grid.
+    pub fn finish(mut self) -> Vec<f32> {
+        if self.reducer == VerticalReducer::Mean {
+            for (value, &count) in self.values.iter_mut().zip(&self.counts) {
+                if count > 0 {
+                    *value /= count as f32;
+                }
+            }
+        }
+        self.values
+    }
+}
+
+/// Reduce the grids of several levels in one call.
+pub fn reduce_levels(reducer: VerticalReducer, grids: &[&[f32]]) -> Result<Vec<f32>> {
+    let len = grids.first().map(|g| g.len()).unwrap_or(0);
+    let mut accumulator = VerticalAccumulator::new(reducer, len);
+    for grid in grids {
+        accumulator.add(grid)?;
+    }
+    Ok(accumulator.finish())
+}
+
+/// An inclusive range of levels of one kind, e.g. "1000 mb/500 mb".
+///
+/// This is the WMS `ELEVATION=low/high` form. Either end may be written in
+/// any spelling [`CanonicalLevel`] understands, and a bare number takes its
+/// units from the other end ("1000/500 mb"). The ends may be in either
+/// order, so "500 mb/1000 mb" is the same range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelRange {
+    pub kind: LevelKind,
+    /// Smaller bound, in meters or millibars
+    pub min: f64,
+    /// Larger bound, in meters or millibars
+    pub max: f64,
+}
+
+impl LevelRange {
+    /// Whether an ELEVATION value is a range rather than a single level.
+    pub fn is_range(elevation: &str) -> bool {
+        elevation.contains('/')
+    }
+
+    /// Whether a level name is inside the range.
+    pub fn contains(&self, level: &str) -> bool {
+        let level = CanonicalLevel::parse(level);
+        level.kind == self.kind
+            && level
+                .value
+                .is_some_and(|v| v >= self.min - 1e-6 && v <= self.max + 1e-6)
+    }
+
+    /// The level names inside the range, in the order given.
+    pub fn select<'a>(&self, levels: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+        levels.into_iter().filter(|l| self.contains(l)).collect()
+    }
+}
+
+impl FromStr for LevelRange {
+    type Err = GridProcessorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            GridProcessorError::ConfigError(format!("invalid level range '{}': {}", s, reason))
+        };
+
+        let (low, high) = s
+            .split_once('/')
+            .ok_or_else(|| invalid("expected low/high"))?;
+        if high.contains('/') {
+            return Err(invalid("level ranges have no resolution"));
+        }
+
+        let low_level = CanonicalLevel::parse(low);
+        let high_level = CanonicalLevel::parse(high);
+        let bare = |text: &str| text.trim().parse::<f64>().ok();
+        let (kind, a, b) = match (low_level.value, high_level.value) {
+            (Some(a), Some(b)) if low_level.kind == high_level.kind => (low_level.kind, a, b),
+            (Some(_), Some(_)) => return Err(invalid("ends are different kinds of level")),
+            (Some(a), None) => (
+                low_level.kind,
+                a,
+                bare(high).ok_or_else(|| invalid("unrecognized upper level"))?,
+            ),
+            (None, Some(b)) => (
+                high_level.kind,
+                bare(low).ok_or_else(|| invalid("unrecognized lower level"))?,
+                b,
+            ),
+            (None, None) => return Err(invalid("expected heights or pressures")),
+        };
+
+        Ok(Self {
+            kind,
+            min: a.min(b),
+            max: a.max(b),
+        })
+    }
+}
+
+impl fmt::Display for LevelRange {
+    /// Canonical form, e.g. "500 mb/1000 mb", smaller bound first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let end = |value| CanonicalLevel {
+            kind: self.kind,
+            value: Some(value),
+            name: None,
+        };
+        write!(f, "{}/{}", end(self.min), end(self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAN: f32 = f32::NAN;
+
+    #[test]
+    fn test_reducers() {
+        let a = [1.0, 5.0, NAN, NAN];
+        let b = [3.0, 2.0, 4.0, NAN];
+        let grids: [&[f32]; 2] = [&a, &b];
+
+        let max = reduce_levels(VerticalReducer::Max, &grids).unwrap();
+        assert_eq!(&max[..3], &[3.0, 5.0, 4.0]);
+        assert!(max[3].is_nan());
+
+        let min = reduce_levels(VerticalReducer::Min, &grids).unwrap();
+        assert_eq!(&min[..3], &[1.0, 2.0, 4.0]);
+        assert!(min[3].is_nan());
+
+        // Mean only counts valid levels per cell
+        let mean = reduce_levels(VerticalReducer::Mean, &grids).unwrap();
+        assert_eq!(&mean[..3], &[2.0, 3.5, 4.0]);
+        assert!(mean[3].is_nan());
+    }
+
+    #[test]
+    fn test_accumulator_rejects_mismatched_grid() {
+        let mut acc = VerticalAccumulator::new(VerticalReducer::Min, 4);
+        acc.add(&[0.0; 4]).unwrap();
+        assert!(acc.add(&[0.0; 3]).is_err());
+        assert_eq!(acc.level_count(), 1);
+    }
+
+    #[test]
+    fn test_reducer_parse() {
+        assert_eq!(
+            "MIN".parse::<VerticalReducer>().unwrap(),
+            VerticalReducer::Min
+        );
+        assert!("sum".parse::<VerticalReducer>().is_err());
+        assert_eq!(VerticalReducer::Mean.to_string(), "mean");
+    }
+
+    #[test]
+    fn test_level_range_parse() {
+        let range: LevelRange = "1000 mb/500 hPa".parse().unwrap();
+        assert_eq!(range.kind, LevelKind::Isobaric);
+        assert_eq!((range.min, range.max), (500.0, 1000.0));
+        assert_eq!(range.to_string(), "500 mb/1000 mb");
+
+        // A bare number takes the other end's units
+        assert_eq!("1000/500 mb".parse::<LevelRange>().unwrap(), range);
+
+        let heights: LevelRange = "0 m above ground/1 km AGL".parse().unwrap();
+        assert_eq!(heights.kind, LevelKind::HeightAboveGround);
+        assert_eq!(heights.max, 1000.0);
+
+        assert!("500 mb".parse::<LevelRange>().is_err());
+        assert!("500 mb/2 m above ground".parse::<LevelRange>().is_err());
+        assert!("surface/500 mb".parse::<LevelRange>().is_err());
+        assert!("1000/500/50 mb".parse::<LevelRange>().is_err());
+    }
+
+    #[test]
+    fn test_level_range_select() {
+        let range: LevelRange = "850 mb/500 mb".parse().unwrap();
+        let levels = [
+            "1000 mb",
+            "850 mb",
+            "700 mb",
+            "500 mb",
+            "250 mb",
+            "2 m above ground",
+        ];
+        assert_eq!(range.select(levels), vec!["850 mb", "700 mb", "500 mb"]);
+        assert!(range.contains("70000 Pa"));
+        assert!(!range.contains("surface"));
+    }
+}
@@ -0,0 +1,381 @@
+//! CF (Climate and Forecast) metadata for Zarr output.
+//!
+//! Grids are written with the CF attributes third-party tools such as xarray
+//! look for: `standard_name`, `long_name`, `units`, `_FillValue` and a
+//! `grid_mapping` pointing at a `crs` variable that describes the WGS84
+//! latitude/longitude grid. Multiscale groups also get `lat`/`lon`
+//! coordinate variables for the native level.
+//!
+//! Standard names come from the GRIB2 short parameter names used in the model
+//! configs; parameters without a CF equivalent only get a `long_name`.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+
+/// CF conventions version declared on multiscale groups.
+pub const CF_CONVENTIONS: &str = "CF-1.8";
+
+/// Name of the grid mapping variable written next to the data arrays.
+pub const GRID_MAPPING_VARIABLE: &str = "crs";
+
+/// Dimension names of the native-resolution arrays.
+pub const NATIVE_DIMENSIONS: [&str; 2] = ["lat", "lon"];
+
+/// Leading dimension of time series arrays.
+pub const TIME_DIMENSION: &str = "time";
+
+/// Leading dimension of level stacks.
+pub const VERTICAL_DIMENSION: &str = "level";
+
+/// OGC WKT for WGS84 geographic coordinates.
+pub const WGS84_WKT: &str = "GEOGCRS[\"WGS 84\",DATUM[\"World Geodetic System 1984\",\
+ELLIPSOID[\"WGS 84\",6378137,298.257223563,LENGTHUNIT[\"metre\",1]]],\
+PRIMEM[\"Greenwich\",0,ANGLEUNIT[\"degree\",0.0174532925199433]],\
+CS[ellipsoidal,2],AXIS[\"geodetic latitude (Lat)\",north,ORDER[1],\
+ANGLEUNIT[\"degree\",0.0174532925199433]],AXIS[\"geodetic longitude (Lon)\",east,ORDER[2],\
+ANGLEUNIT[\"degree\",0.0174532925199433]],ID[\"EPSG\",4326]]";
+
+/// CF attributes describing one variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CfAttributes {
+    /// CF standard name, if the parameter has one.
+    pub standard_name: Option<String>,
+    /// Human-readable description.
+    pub long_name: String,
+}
+
+impl CfAttributes {
+    /// Attributes derived from a GRIB2 parameter name and level.
+    ///
+    /// The long name defaults to "`parameter` at `level`"; use
+    /// [`Self::with_long_name`] to supply the model config's description.
+    pub fn for_parameter(parameter: &str, level: &str) -> Self {
+        Self {
+            standard_name: standard_name(parameter, level).map(str::to_string),
+            long_name: format!("{} at {}", parameter, level),
+        }
+    }
+
+    /// Replace the long name.
+    pub fn with_long_name(mut self, long_name: impl Into<String>) -> Self {
+        self.long_name = long_name.into();
+        self
+    }
+
+    /// Add the CF variable attributes.
+    ///
+    /// `grid_mapping` is added separately, only where a `crs` variable is
+    /// written alongside the array.
+    pub(crate) fn insert_into(&self, attrs: &mut Map<String, Value>) {
+        if let Some(standard_name) = &self.standard_name {
+            attrs.insert("standard_name".to_string(), json!(standard_name));
+        }
+        attrs.insert("long_name".to_string(), json!(self.long_name));
+        // JSON has no NaN literal; Zarr V3 spells it as a string
+        attrs.insert("_FillValue".to_string(), json!("NaN"));
+    }
+}
+
+/// CF standard name for a GRIB2 parameter at a level.
+pub fn standard_name(parameter: &str, level: &str) -> Option<&'static str> {
+    let at_surface = level.eq_ignore_ascii_case("surface");
+    let name = match parameter {
+        "TMP" if at_surface => "surface_temperature",
+        "TMP" => "air_temperature",
+        "DPT" => "dew_point_temperature",
+        "RH" => "relative_humidity",
+        "SPFH" => "specific_humidity",
+        "UGRD" => "eastward_wind",
+        "VGRD" => "northward_wind",
+        "WIND" => "wind_speed",
+        "GUST" => "wind_speed_of_gust",
+        "PRMSL" | "MSLMA" => "air_pressure_at_mean_sea_level",
+        "PRES" if at_surface => "surface_air_pressure",
+        "PRES" => "air_pressure",
+        "HGT" if at_surface => "surface_altitude",
+        "HGT" => "geopotential_height",
+        "APCP" => "precipitation_amount",
+        "TCDC" => "cloud_area_fraction",
+        "LCDC" => "low_type_cloud_area_fraction",
+        "MCDC" => "medium_type_cloud_area_fraction",
+        "HCDC" => "high_type_cloud_area_fraction",
+        "CAPE" => "atmosphere_convective_available_potential_energy",
+        "CIN" => "atmosphere_convective_inhibition",
+        "VIS" => "visibility_in_air",
+        "PWAT" => "atmosphere_mass_content_of_water_vapor",
+        "SNOD" => "surface_snow_thickness",
+        "WEASD" => "surface_snow_amount",
+        "REFC" | "REFD" | "REFL" => "equivalent_reflectivity_factor",
+        "PRECIP_RATE" => "lwe_precipitation_rate",
+        "QPE_01H" | "QPE_24H" => "lwe_thickness_of_precipitation_amount",
+        _ => return goes_standard_name(parameter),
+    };
+    Some(name)
+}
+
+/// GOES ABI bands 1-6 are reflectances, 7-16 brightness temperatures.
+fn goes_standard_name(parameter: &str) -> Option<&'static str> {
+    let band: u8 = parameter.strip_prefix("CMI_C")?.parse().ok()?;
+    match band {
+        1..=6 => Some("toa_bidirectional_reflectance"),
+        7..=16 => Some("toa_brightness_temperature"),
+        _ => None,
+    }
+}
+
+/// Attributes of the `crs` grid mapping variable.
+pub fn crs_attributes() -> Map<String, Value> {
+    let mut attrs = Map::new();
+    attrs.insert("grid_mapping_name".to_string(), json!("latitude_longitude"));
+    attrs.insert("semi_major_axis".to_string(), json!(6378137.0));
+    attrs.insert("inverse_flattening".to_string(), json!(298.257223563));
+    attrs.insert("longitude_of_prime_meridian".to_string(), json!(0.0));
+    attrs.insert("crs_wkt".to_string(), json!(WGS84_WKT));
+    // GDAL and rioxarray read the WKT from `spatial_ref`
+    attrs.insert("spatial_ref".to_string(), json!(WGS84_WKT));
+    attrs
+}
+
+/// Attributes of a latitude or longitude coordinate variable.
+pub fn coordinate_attributes(axis: &str) -> Map<String, Value> {
+    let (standard_name, long_name, units) = match axis {
+        "lat" => ("latitude", "latitude", "degrees_north"),
+        _ => ("longitude", "longitude", "degrees_east"),
+    };
+    let mut attrs = Map::new();
+    attrs.insert("standard_name".to_string(), json!(standard_name));
+    attrs.insert("long_name".to_string(), json!(long_name));
+    attrs.insert("units".to_string(), json!(units));
+    attrs.insert(
+        "axis".to_string(),
+        json!(if axis == "lat" { "Y" } else { "X" }),
+    );
+    attrs
+}
+
+/// Attributes of a time coordinate counted in hours from `origin`.
+pub fn time_attributes(origin: DateTime<Utc>) -> Map<String, Value> {
+    let mut attrs = Map::new();
+    attrs.insert("standard_name".to_string(), json!("time"));
+    attrs.insert("long_name".to_string(), json!("forecast valid time"));
+    attrs.insert(
+        "units".to_string(),
+        json!(format!(
+            "hours since {}",
+            origin.format("%Y-%m-%d %H:%M:%S")
+        )),
+    );
+    attrs.insert("calendar".to_string(), json!("proleptic_gregorian"));
+    attrs.insert("axis".to_string(), json!("T"));
+    attrs
+}
+
+/// Attributes of a vertical coordinate in `units` ("hPa" or "m").
+///
+/// Pressure levels increase downwards; anything else is taken as a height
+/// above ground.
+pub fn vertical_attributes(units: &str) -> Map<String, Value> {
+    let (standard_name, long_name, positive) = if units.eq_ignore_ascii_case("hPa") {
+        ("air_pressure", "pressure level", "down")
+    } else {
+        ("height", "height above ground", "up")
+    };
+    let mut attrs = Map::new();
+    attrs.insert("standard_name".to_string(), json!(standard_name));
+    attrs.insert("long_name".to_string(), json!(long_name));
+    attrs.insert("units".to_string(), json!(units));
+    attrs.insert("positive".to_string(), json!(positive));
+    attrs.insert("axis".to_string(), json!("Z"));
+    attrs
+}
+
+/// Dimension names for a pyramid level.
+///
+/// Coarser levels have their own dimensions so all levels can be opened as
+/// one dataset; only the native level has coordinate variables.
+pub fn level_dimensions(pyramid_level: u32) -> [String; 2] {
+    if pyramid_level == 0 {
+        NATIVE_DIMENSIONS.map(str::to_string)
+    } else {
+        NATIVE_DIMENSIONS.map(|d| format!("{}_{}", d, pyramid_level))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod checker {
+    //! A small CF checker for the attributes this crate writes.
+    //!
+    //! Covers the CF 1.8 rules that apply to our output: known standard
+    //! names, required units, grid mapping references and coordinate
+    //! variables.
+
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    /// Entries of the CF standard name table that our parameters map to,
+    /// plus the coordinate names.
+    const STANDARD_NAMES: &[&str] = &[
+        "air_temperature",
+        "surface_temperature",
+        "dew_point_temperature",
+        "relative_humidity",
+        "specific_humidity",
+        "eastward_wind",
+        "northward_wind",
+        "wind_speed",
+        "wind_speed_of_gust",
+        "air_pressure_at_mean_sea_level",
+        "surface_air_pressure",
+        "air_pressure",
+        "surface_altitude",
+        "geopotential_height",
+        "precipitation_amount",
+        "cloud_area_fraction",
+        "low_type_cloud_area_fraction",
+        "medium_type_cloud_area_fraction",
+        "high_type_cloud_area_fraction",
+        "atmosphere_convective_available_potential_energy",
+        "atmosphere_convective_inhibition",
+        "visibility_in_air",
+        "atmosphere_mass_content_of_water_vapor",
+        "surface_snow_thickness",
+        "surface_snow_amount",
+        "equivalent_reflectivity_factor",
+        "lwe_precipitation_rate",
+        "lwe_thickness_of_precipitation_amount",
+        "toa_bidirectional_reflectance",
+        "toa_brightness_temperature",
+        "latitude",
+        "longitude",
+        "time",
+    ];
+
+    const GRID_MAPPING_NAMES: &[&str] = &["latitude_longitude"];
+
+    /// Check the variables of a group (name → Zarr array metadata).
+    ///
+    /// Returns a list of violations, empty when the group is compliant.
+    pub fn check_group(group_attrs: &Value, arrays: &HashMap<String, Value>) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        match group_attrs["Conventions"].as_str() {
+            Some(c) if c.starts_with("CF-") => {}
+            _ => errors.push("group: missing Conventions = CF-x.y".to_string()),
+        }
+
+        for (name, meta) in arrays {
+            let attrs = &meta["attributes"];
+            let dims: Vec<&str> = meta["dimension_names"]
+                .as_array()
+                .map(|d| d.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let shape: Vec<u64> = meta["shape"]
+                .as_array()
+                .map(|s| s.iter().filter_map(Value::as_u64).collect())
+                .unwrap_or_default();
+
+            if dims.len() != shape.len() {
+                errors.push(format!("{}: dimension_names don't match shape", name));
+            }
+
+            // Grid mapping variables only need a valid grid_mapping_name
+            if let Some(mapping) = attrs.get("grid_mapping_name") {
+                if !mapping
+                    .as_str()
+                    .is_some_and(|m| GRID_MAPPING_NAMES.contains(&m))
+                {
+                    errors.push(format!("{}: unknown grid_mapping_name {}", name, mapping));
+                }
+                continue;
+            }
+
+            if let Some(standard_name) = attrs.get("standard_name") {
+                if !standard_name
+                    .as_str()
+                    .is_some_and(|s| STANDARD_NAMES.contains(&s))
+                {
+                    errors.push(format!(
+                        "{}: {} is not a CF standard name",
+                        name, standard_name
+                    ));
+                }
+            } else if attrs.get("long_name").and_then(Value::as_str).is_none() {
+                errors.push(format!("{}: needs a standard_name or long_name", name));
+            }
+
+            if attrs
+                .get("units")
+                .and_then(Value::as_str)
+                .is_none_or(str::is_empty)
+            {
+                errors.push(format!("{}: missing units", name));
+            }
+
+            // Coordinate variables share their name with their one dimension
+            if dims == [name.as_str()] {
+                continue;
+            }
+
+            match attrs.get("grid_mapping").and_then(Value::as_str) {
+                Some(mapping) if arrays.contains_key(mapping) => {}
+                Some(mapping) => errors.push(format!(
+                    "{}: grid_mapping {} is not a variable",
+                    name, mapping
+                )),
+                None => errors.push(format!("{}: missing grid_mapping", name)),
+            }
+            if attrs.get("_FillValue").is_none() {
+                errors.push(format!("{}: missing _FillValue", name));
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_names() {
+        assert_eq!(
+            standard_name("TMP", "2 m above ground"),
+            Some("air_temperature")
+        );
+        assert_eq!(standard_name("TMP", "surface"), Some("surface_temperature"));
+        assert_eq!(standard_name("HGT", "500 mb"), Some("geopotential_height"));
+        assert_eq!(
+            standard_name("CMI_C02", "surface"),
+            Some("toa_bidirectional_reflectance")
+        );
+        assert_eq!(
+            standard_name("CMI_C13", "surface"),
+            Some("toa_brightness_temperature")
+        );
+        assert_eq!(standard_name("WIND_BARBS", "10 m above ground"), None);
+    }
+
+    #[test]
+    fn test_cf_attributes() {
+        let mut attrs = Map::new();
+        CfAttributes::for_parameter("UGRD", "10 m above ground")
+            .with_long_name("U-Component of Wind")
+            .insert_into(&mut attrs);
+
+        assert_eq!(attrs["standard_name"], "eastward_wind");
+        assert_eq!(attrs["long_name"], "U-Component of Wind");
+        assert_eq!(attrs["_FillValue"], "NaN");
+
+        // No standard name for parameters without a CF equivalent
+        let cf = CfAttributes::for_parameter("WIND_BARBS", "surface");
+        assert_eq!(cf.standard_name, None);
+        assert_eq!(cf.long_name, "WIND_BARBS at surface");
+    }
+
+    #[test]
+    fn test_level_dimensions() {
+        assert_eq!(level_dimensions(0), ["lat", "lon"]);
+        assert_eq!(level_dimensions(2), ["lat_2", "lon_2"]);
+    }
+}
@@ -0,0 +1,399 @@
+//! Coverages of one valid time, written as CF-compliant NetCDF.
+//!
+//! A [`NetCdfCoverage`] holds one or more parameters on a shared
+//! `lat`/`lon` grid, optionally stacked over a vertical axis: a region read
+//! ([`GridRegion`]), a level stack of regions (an EDR cube), or the profile
+//! of a single point. Variables are written as `(time, [level,] lat, lon)`
+//! with a length-one time dimension, so the file carries its valid time and
+//! model run like the [`GridSeries`](super::GridSeries) exports.
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+
+use super::cf::{self, CfAttributes};
+use super::netcdf::{attributes, float_variable_attributes, NcValues, NetCdfFile};
+use crate::error::{GridProcessorError, Result};
+use crate::types::{GridRegion, VerticalAxis};
+
+/// Name of the scalar run time variable.
+const REFERENCE_TIME_VARIABLE: &str = "forecast_reference_time";
+
+/// One parameter of a coverage.
+#[derive(Debug, Clone)]
+struct CoverageVariable {
+    name: String,
+    units: String,
+    cf: CfAttributes,
+    values: Vec<f32>,
+}
+
+/// Parameters at one valid time on a shared grid.
+///
+/// Values are row-major with rows in `lat` order; with a vertical axis the
+/// levels are outermost, in axis order.
+#[derive(Debug, Clone)]
+pub struct NetCdfCoverage {
+    pub lon: Vec<f64>,
+    pub lat: Vec<f64>,
+    pub valid_time: DateTime<Utc>,
+    pub reference_time: Option<DateTime<Utc>>,
+    pub vertical: Option<VerticalAxis>,
+    title: Option<String>,
+    variables: Vec<CoverageVariable>,
+}
+
+impl NetCdfCoverage {
+    /// Create an empty coverage on the given grid.
+    pub fn new(lon: Vec<f64>, lat: Vec<f64>, valid_time: DateTime<Utc>) -> Self {
+        Self {
+            lon,
+            lat,
+            valid_time,
+            reference_time: None,
+            vertical: None,
+            title: None,
+            variables: Vec::new(),
+        }
+    }
+
+    /// Create an empty coverage on the grid of a region read.
+    pub fn for_region(region: &GridRegion, valid_time: DateTime<Utc>) -> Self {
+        Self::new(region.lon_values(), region.lat_values(), valid_time)
+    }
+
+    /// Create an empty coverage of a single point, e.g. for profiles.
+    pub fn for_point(lon: f64, lat: f64, valid_time: DateTime<Utc>) -> Self {
+        Self::new(vec![lon], vec![lat], valid_time)
+    }
+
+    /// Set the model run the coverage was forecast from.
+    pub fn with_reference_time(mut self, reference_time: DateTime<Utc>) -> Self {
+        self.reference_time = Some(reference_time);
+        self
+    }
+
+    /// Stack the variables over a vertical axis.
+    pub fn with_vertical(mut self, vertical: VerticalAxis) -> Self {
+        self.vertical = Some(vertical);
+        self
+    }
+
+    /// Set the `title` global attribute (defaults to the parameter names).
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Number of levels per variable (1 without a vertical axis).
+    pub fn level_count(&self) -> usize {
+        self.vertical.as_ref().map_or(1, VerticalAxis::len)
+    }
+
+    /// Number of variables.
+    pub fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// Check if the coverage has no variables.
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    /// Add a parameter covering every level and grid point.
+    pub fn add_variable(
+        &mut self,
+        name: &str,
+        units: &str,
+        cf: CfAttributes,
+        values: &[f32],
+    ) -> Result<()> {
+        let expected = self.level_count() * self.lat.len() * self.lon.len();
+        if values.len() != expected {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "variable {} has {} values, the coverage has {}",
+                name,
+                values.len(),
+                expected
+            )));
+        }
+        if self.variables.iter().any(|v| v.name == name) {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "variable {} added twice",
+                name
+            )));
+        }
+        self.variables.push(CoverageVariable {
+            name: name.to_string(),
+            units: units.to_string(),
+            cf,
+            values: values.to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Add a parameter from a point profile, as returned by
+    /// [`read_profile`](crate::GridProcessor::read_profile).
+    ///
+    /// Levels are matched to the vertical axis by value; axis levels missing
+    /// from the profile are NaN, profile levels not on the axis are dropped.
+    pub fn add_profile(
+        &mut self,
+        name: &str,
+        units: &str,
+        cf: CfAttributes,
+        profile: &[(f64, Option<f32>)],
+    ) -> Result<()> {
+        let vertical = self.vertical.as_ref().ok_or_else(|| {
+            GridProcessorError::InvalidMetadata(format!(
+                "profile {} needs a coverage with a vertical axis",
+                name
+            ))
+        })?;
+        if self.lon.len() * self.lat.len() != 1 {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "profile {} needs a single-point coverage",
+                name
+            )));
+        }
+
+        let mut values = vec![f32::NAN; vertical.len()];
+        for &(level, value) in profile {
+            if let (Some(index), Some(value)) = (vertical.index_of(level), value) {
+                values[index] = value;
+            }
+        }
+        self.add_variable(name, units, cf, &values)
+    }
+
+    /// Encode as a NetCDF file.
+    pub fn to_netcdf(&self) -> Result<Vec<u8>> {
+        if self.is_empty() {
+            return Err(GridProcessorError::InvalidMetadata(
+                "coverage has no variables".to_string(),
+            ));
+        }
+
+        let [lat_dim, lon_dim] = cf::NATIVE_DIMENSIONS;
+        let mut nc = NetCdfFile::new();
+        nc.add_dimension(cf::TIME_DIMENSION, 1)?;
+        if let Some(vertical) = &self.vertical {
+            nc.add_dimension(cf::VERTICAL_DIMENSION, vertical.len())?;
+        }
+        nc.add_dimension(lat_dim, self.lat.len())?;
+        nc.add_dimension(lon_dim, self.lon.len())?;
+
+        for (name, value) in attributes(self.global_attributes()) {
+            nc.add_attribute(&name, value);
+        }
+
+        nc.add_variable(
+            cf::GRID_MAPPING_VARIABLE,
+            &[],
+            NcValues::Int(vec![0]),
+            attributes(cf::crs_attributes()),
+        )?;
+
+        // Time counts from the run when it is known, so the value is the
+        // forecast lead time
+        let origin = self.reference_time.unwrap_or(self.valid_time);
+        let lead_hours = (self.valid_time - origin).num_seconds() as f64 / 3600.0;
+        nc.add_variable(
+            cf::TIME_DIMENSION,
+            &[cf::TIME_DIMENSION],
+            NcValues::Double(vec![lead_hours]),
+            attributes(cf::time_attributes(origin)),
+        )?;
+        if self.reference_time.is_some() {
+            let mut attrs = cf::time_attributes(origin);
+            attrs.insert("standard_name".to_string(), json!(REFERENCE_TIME_VARIABLE));
+            attrs.insert("long_name".to_string(), json!("forecast reference time"));
+            attrs.remove("axis");
+            nc.add_variable(
+                REFERENCE_TIME_VARIABLE,
+                &[],
+                NcValues::Double(vec![0.0]),
+                attributes(attrs),
+            )?;
+        }
+
+        if let Some(vertical) = &self.vertical {
+            nc.add_variable(
+                cf::VERTICAL_DIMENSION,
+                &[cf::VERTICAL_DIMENSION],
+                NcValues::Double(vertical.values.clone()),
+                attributes(cf::vertical_attributes(&vertical.units)),
+            )?;
+        }
+        nc.add_variable(
+            lat_dim,
+            &[lat_dim],
+            NcValues::Double(self.lat.clone()),
+            attributes(cf::coordinate_attributes(lat_dim)),
+        )?;
+        nc.add_variable(
+            lon_dim,
+            &[lon_dim],
+            NcValues::Double(self.lon.clone()),
+            attributes(cf::coordinate_attributes(lon_dim)),
+        )?;
+
+        let dims: Vec<&str> = if self.vertical.is_some() {
+            vec![cf::TIME_DIMENSION, cf::VERTICAL_DIMENSION, lat_dim, lon_dim]
+        } else {
+            vec![cf::TIME_DIMENSION, lat_dim, lon_dim]
+        };
+        for variable in &self.variables {
+            nc.add_variable(
+                &variable.name,
+                &dims,
+                NcValues::Float(variable.values.clone()),
+                float_variable_attributes(self.data_attributes(variable)),
+            )?;
+        }
+
+        Ok(nc.to_bytes())
+    }
+
+    fn data_attributes(&self, variable: &CoverageVariable) -> Map<String, Value> {
+        let mut attrs = Map::new();
+        variable.cf.insert_into(&mut attrs);
+        attrs.insert("units".to_string(), json!(variable.units));
+        attrs.insert("grid_mapping".to_string(), json!(cf::GRID_MAPPING_VARIABLE));
+        if self.reference_time.is_some() {
+            attrs.insert("coordinates".to_string(), json!(REFERENCE_TIME_VARIABLE));
+        }
+        attrs
+    }
+
+    fn global_attributes(&self) -> Map<String, Value> {
+        let title = self.title.clone().unwrap_or_else(|| {
+            let names: Vec<&str> = self.variables.iter().map(|v| v.name.as_str()).collect();
+            names.join(", ")
+        });
+        let mut attrs = Map::new();
+        attrs.insert("Conventions".to_string(), json!(cf::CF_CONVENTIONS));
+        attrs.insert("title".to_string(), json!(title));
+        attrs.insert(
+            "valid_time".to_string(),
+            json!(self.valid_time.to_rfc3339()),
+        );
+        if let Some(reference_time) = self.reference_time {
+            attrs.insert(
+                "reference_time".to_string(),
+                json!(reference_time.to_rfc3339()),
+            );
+        }
+        attrs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn run() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap()
+    }
+
+    fn cube() -> NetCdfCoverage {
+        let mut coverage = NetCdfCoverage::new(
+            vec![-100.0, -99.0, -98.0],
+            vec![40.0, 39.0],
+            run() + chrono::Duration::hours(6),
+        )
+        .with_reference_time(run())
+        .with_vertical(VerticalAxis::new("hPa", vec![850.0, 500.0]));
+        let mut values = vec![270.0; 6];
+        values.extend([250.0, 251.0, 252.0, 253.0, 254.0, f32::NAN]);
+        coverage
+            .add_variable(
+                "TMP",
+                "K",
+                CfAttributes::for_parameter("TMP", "850 mb"),
+                &values,
+            )
+            .unwrap();
+        coverage
+    }
+
+    #[test]
+    fn test_add_variable_checks_size() {
+        let mut coverage = cube();
+        let cf = CfAttributes::for_parameter("RH", "850 mb");
+        assert!(coverage
+            .add_variable("RH", "%", cf.clone(), &[0.0; 6])
+            .is_err());
+        assert!(coverage.add_variable("TMP", "K", cf, &[0.0; 12]).is_err());
+        assert_eq!(coverage.len(), 1);
+    }
+
+    #[test]
+    fn test_netcdf_cube() {
+        let bytes = cube().to_netcdf().unwrap();
+        assert_eq!(&bytes[..4], b"CDF\x02");
+
+        // The data variable comes last: 1 x 2 x 2 x 3 float32 values
+        let data = &bytes[bytes.len() - 48..];
+        assert_eq!(f32::from_be_bytes(data[..4].try_into().unwrap()), 270.0);
+        assert_eq!(f32::from_be_bytes(data[24..28].try_into().unwrap()), 250.0);
+        assert!(f32::from_be_bytes(data[44..].try_into().unwrap()).is_nan());
+
+        let header = String::from_utf8_lossy(&bytes[..bytes.len() - 48]);
+        for text in [
+            "air_temperature",
+            "air_pressure",
+            "forecast_reference_time",
+            "hours since 2024-12-17 12:00:00",
+            "grid_mapping",
+            "CF-1.8",
+        ] {
+            assert!(header.contains(text), "missing {}", text);
+        }
+        // Six hours after the run
+        assert!(bytes
+            .windows(8)
+            .any(|w| w == 6.0f64.to_be_bytes().as_slice()));
+    }
+
+    #[test]
+    fn test_profile() {
+        let mut coverage = NetCdfCoverage::for_point(-97.5, 35.2, run())
+            .with_vertical(VerticalAxis::new("hPa", vec![1000.0, 850.0, 500.0]));
+        let profile = [(500.0, Some(250.0)), (1000.0, None), (300.0, Some(230.0))];
+        coverage
+            .add_profile(
+                "TMP",
+                "K",
+                CfAttributes::for_parameter("TMP", "500 mb"),
+                &profile,
+            )
+            .unwrap();
+
+        let bytes = coverage.to_netcdf().unwrap();
+        let data = &bytes[bytes.len() - 12..];
+        assert!(f32::from_be_bytes(data[..4].try_into().unwrap()).is_nan());
+        assert!(f32::from_be_bytes(data[4..8].try_into().unwrap()).is_nan());
+        assert_eq!(f32::from_be_bytes(data[8..].try_into().unwrap()), 250.0);
+
+        // Without a run, time counts from the valid time
+        let header = String::from_utf8_lossy(&bytes[..bytes.len() - 12]);
+        assert!(header.contains("hours since 2024-12-17 12:00:00"));
+        assert!(!header.contains("forecast_reference_time"));
+    }
+
+    #[test]
+    fn test_profile_needs_vertical_point() {
+        let cf = CfAttributes::for_parameter("TMP", "500 mb");
+        let mut flat = NetCdfCoverage::for_point(0.0, 0.0, run());
+        assert!(flat.add_profile("TMP", "K", cf.clone(), &[]).is_err());
+
+        let mut grid = NetCdfCoverage::new(vec![0.0, 1.0], vec![0.0], run())
+            .with_vertical(VerticalAxis::new("hPa", vec![500.0]));
+        assert!(grid.add_profile("TMP", "K", cf, &[]).is_err());
+
+        assert!(NetCdfCoverage::for_point(0.0, 0.0, run())
+            .to_netcdf()
+            .is_err());
+    }
+}
@@ -0,0 +1,562 @@
+//! Cloud-Optimized GeoTIFF writer for coverage exports.
+//!
+//! Writes a single-band 32-bit float GeoTIFF in EPSG:4326 from a
+//! [`GridRegion`]. The file follows the COG layout: every IFD (full
+//! resolution first, then 2x mean overviews) sits at the start of the file,
+//! followed by the tiles, smallest overview first, so a reader can fetch the
+//! header and then only the tiles it needs with range requests.
+//!
+//! Tiles are uncompressed, and offsets are 32-bit (classic TIFF), which
+//! limits exports to 4 GiB.
+
+use crate::downsample::{downsample_2x, DownsampleMethod};
+use crate::error::{GridProcessorError, Result};
+use crate::types::{AxisCoordinates, GridRegion};
+
+const NEW_SUBFILE_TYPE: u16 = 254;
+const IMAGE_WIDTH: u16 = 256;
+const IMAGE_LENGTH: u16 = 257;
+const BITS_PER_SAMPLE: u16 = 258;
+const COMPRESSION: u16 = 259;
+const PHOTOMETRIC: u16 = 262;
+const SAMPLES_PER_PIXEL: u16 = 277;
+const PLANAR_CONFIGURATION: u16 = 284;
+const TILE_WIDTH: u16 = 322;
+const TILE_LENGTH: u16 = 323;
+const TILE_OFFSETS: u16 = 324;
+const TILE_BYTE_COUNTS: u16 = 325;
+const SAMPLE_FORMAT: u16 = 339;
+const MODEL_PIXEL_SCALE: u16 = 33550;
+const MODEL_TIEPOINT: u16 = 33922;
+const GEO_KEY_DIRECTORY: u16 = 34735;
+const GDAL_NODATA: u16 = 42113;
+
+/// Sample format code of IEEE floating point samples.
+const SAMPLE_FORMAT_FLOAT: u16 = 3;
+
+/// Options of a GeoTIFF export.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoTiffOptions {
+    /// Value written for missing (NaN) cells and declared as nodata.
+    pub nodata: f32,
+    /// Width and height of the square tiles, a multiple of 16.
+    pub tile_size: usize,
+}
+
+impl Default for GeoTiffOptions {
+    fn default() -> Self {
+        Self {
+            nodata: f32::NAN,
+            tile_size: 256,
+        }
+    }
+}
+
+/// Values of a TIFF tag.
+enum TagValue {
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Double(Vec<f64>),
+    Ascii(String),
+}
+
+impl TagValue {
+    fn type_code(&self) -> u16 {
+        match self {
+            Self::Ascii(_) => 2,
+            Self::Short(_) => 3,
+            Self::Long(_) => 4,
+            Self::Double(_) => 12,
+        }
+    }
+
+    fn count(&self) -> u32 {
+        match self {
+            // NUL terminated
+            Self::Ascii(s) => s.len() as u32 + 1,
+            Self::Short(v) => v.len() as u32,
+            Self::Long(v) => v.len() as u32,
+            Self::Double(v) => v.len() as u32,
+        }
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Self::Ascii(s) => {
+                out.extend_from_slice(s.as_bytes());
+                out.push(0);
+            }
+            Self::Short(v) => v.iter().for_each(|x| out.extend(x.to_le_bytes())),
+            Self::Long(v) => v.iter().for_each(|x| out.extend(x.to_le_bytes())),
+            Self::Double(v) => v.iter().for_each(|x| out.extend(x.to_le_bytes())),
+        }
+        out
+    }
+}
+
+/// One image of the file: the full resolution grid or an overview.
+struct Image {
+    width: usize,
+    height: usize,
+    /// Row-major values, north to south
+    data: Vec<f32>,
+}
+
+impl Image {
+    fn tiles_across(&self, tile_size: usize) -> usize {
+        self.width.div_ceil(tile_size)
+    }
+
+    fn tile_count(&self, tile_size: usize) -> usize {
+        self.tiles_across(tile_size) * self.height.div_ceil(tile_size)
+    }
+
+    /// Tiles in row-major order, padded with `nodata` past the image edges.
+    fn write_tiles(&self, tile_size: usize, nodata: f32, out: &mut Vec<u8>) {
+        for tile_y in 0..self.height.div_ceil(tile_size) {
+            for tile_x in 0..self.tiles_across(tile_size) {
+                for row in tile_y * tile_size..(tile_y + 1) * tile_size {
+                    for col in tile_x * tile_size..(tile_x + 1) * tile_size {
+                        let value = if row < self.height && col < self.width {
+                            self.data[row * self.width + col]
+                        } else {
+                            nodata
+                        };
+                        let value = if value.is_nan() { nodata } else { value };
+                        out.extend(value.to_le_bytes());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Encode a region as a Cloud-Optimized GeoTIFF.
+///
+/// The region must be on a regular grid. Its grid points become pixel
+/// centres, so the raster extends half a cell past the region's bbox.
+/// Overviews are added until the smallest fits in one tile.
+pub fn encode_geotiff(region: &GridRegion, options: &GeoTiffOptions) -> Result<Vec<u8>> {
+    let tile_size = options.tile_size;
+    if tile_size == 0 || !tile_size.is_multiple_of(16) {
+        return Err(GridProcessorError::InvalidMetadata(format!(
+            "GeoTIFF tile size {} is not a multiple of 16",
+            tile_size
+        )));
+    }
+    if region.width == 0 || region.height == 0 || region.data.len() != region.width * region.height
+    {
+        return Err(GridProcessorError::InvalidMetadata(format!(
+            "cannot export a {}x{} region with {} values as GeoTIFF",
+            region.width,
+            region.height,
+            region.data.len()
+        )));
+    }
+
+    let (full, pixel_scale, tiepoint) = north_up(region)?;
+
+    let mut images = vec![full];
+    loop {
+        let last = &images[images.len() - 1];
+        if last.width <= tile_size && last.height <= tile_size {
+            break;
+        }
+        let (data, width, height) =
+            downsample_2x(&last.data, last.width, last.height, DownsampleMethod::Mean);
+        if width == 0 || height == 0 {
+            break;
+        }
+        images.push(Image {
+            width,
+            height,
+            data,
+        });
+    }
+
+    let nodata = if options.nodata.is_nan() {
+        "nan".to_string()
+    } else {
+        options.nodata.to_string()
+    };
+    let tile_bytes = tile_size * tile_size * 4;
+
+    // IFDs come first, so their size (which doesn't depend on the offsets
+    // they hold) fixes where the tiles start
+    let placeholder: Vec<Vec<u32>> = images
+        .iter()
+        .map(|image| vec![0; image.tile_count(tile_size)])
+        .collect();
+    let mut ifd_offsets = Vec::with_capacity(images.len());
+    let mut offset = 8;
+    for (index, image) in images.iter().enumerate() {
+        ifd_offsets.push(offset);
+        let tags = image_tags(
+            image,
+            index,
+            tile_size,
+            &placeholder[index],
+            &nodata,
+            pixel_scale,
+            tiepoint,
+        );
+        offset += encode_ifd(tags, offset, 0).len();
+    }
+
+    // Tile data: smallest overview first, full resolution last
+    let mut tile_offsets = placeholder;
+    for offsets in tile_offsets.iter_mut().rev() {
+        for tile_offset in offsets.iter_mut() {
+            *tile_offset = u32::try_from(offset).map_err(|_| too_large())?;
+            offset += tile_bytes;
+        }
+    }
+
+    let mut out = Vec::with_capacity(offset);
+    out.extend_from_slice(b"II");
+    out.extend(42u16.to_le_bytes());
+    out.extend(8u32.to_le_bytes());
+    for (index, image) in images.iter().enumerate() {
+        let next = ifd_offsets.get(index + 1).copied().unwrap_or(0);
+        let tags = image_tags(
+            image,
+            index,
+            tile_size,
+            &tile_offsets[index],
+            &nodata,
+            pixel_scale,
+            tiepoint,
+        );
+        out.extend(encode_ifd(tags, ifd_offsets[index], next));
+    }
+    for image in images.iter().rev() {
+        image.write_tiles(tile_size, options.nodata, &mut out);
+    }
+    Ok(out)
+}
+
+fn too_large() -> GridProcessorError {
+    GridProcessorError::InvalidMetadata("GeoTIFF export exceeds 4 GiB".to_string())
+}
+
+/// Values of the region with rows north to south, with the pixel scale and
+/// the model coordinates of the raster's upper left corner.
+fn north_up(region: &GridRegion) -> Result<(Image, [f64; 2], [f64; 2])> {
+    let (
+        AxisCoordinates::Regular {
+            start: lon0,
+            step: dx,
+            ..
+        },
+        AxisCoordinates::Regular {
+            start: lat0,
+            step: dy,
+            ..
+        },
+    ) = (&region.coordinates.lon, &region.coordinates.lat)
+    else {
+        return Err(GridProcessorError::InvalidMetadata(
+            "GeoTIFF export needs a regularly spaced grid".to_string(),
+        ));
+    };
+    let (width, height) = (region.width, region.height);
+    // Single row or column grids have no step; fall back to the resolution
+    let dx = if width > 1 { *dx } else { region.resolution.0 };
+    let dy = if height > 1 {
+        *dy
+    } else {
+        -region.resolution.1
+    };
+    if dx <= 0.0 || dy == 0.0 || !dx.is_finite() || !dy.is_finite() {
+        return Err(GridProcessorError::InvalidMetadata(format!(
+            "GeoTIFF export needs west to east columns, got steps ({}, {})",
+            dx, dy
+        )));
+    }
+
+    let (data, north) = if dy < 0.0 {
+        (region.data.clone(), *lat0)
+    } else {
+        // South to north rows: flip them
+        let data = region.data.chunks(width).rev().flatten().copied().collect();
+        (data, lat0 + (height - 1) as f64 * dy)
+    };
+    let dy = dy.abs();
+
+    Ok((
+        Image {
+            width,
+            height,
+            data,
+        },
+        [dx, dy],
+        [lon0 - dx / 2.0, north + dy / 2.0],
+    ))
+}
+
+/// Tags of an image. Only the full resolution image (index 0) carries the
+/// georeferencing; overviews share it.
+fn image_tags(
+    image: &Image,
+    index: usize,
+    tile_size: usize,
+    tile_offsets: &[u32],
+    nodata: &str,
+    pixel_scale: [f64; 2],
+    tiepoint: [f64; 2],
+) -> Vec<(u16, TagValue)> {
+    let tile_bytes = (tile_size * tile_size * 4) as u32;
+    let mut tags = vec![
+        (
+            NEW_SUBFILE_TYPE,
+            // Reduced resolution version of the full image
+            TagValue::Long(vec![if index == 0 { 0 } else { 1 }]),
+        ),
+        (IMAGE_WIDTH, TagValue::Long(vec![image.width as u32])),
+        (IMAGE_LENGTH, TagValue::Long(vec![image.height as u32])),
+        (BITS_PER_SAMPLE, TagValue::Short(vec![32])),
+        // No compression
+        (COMPRESSION, TagValue::Short(vec![1])),
+        // BlackIsZero
+        (PHOTOMETRIC, TagValue::Short(vec![1])),
+        (SAMPLES_PER_PIXEL, TagValue::Short(vec![1])),
+        // Chunky
+        (PLANAR_CONFIGURATION, TagValue::Short(vec![1])),
+        (TILE_WIDTH, TagValue::Long(vec![tile_size as u32])),
+        (TILE_LENGTH, TagValue::Long(vec![tile_size as u32])),
+        (TILE_OFFSETS, TagValue::Long(tile_offsets.to_vec())),
+        (
+            TILE_BYTE_COUNTS,
+            TagValue::Long(vec![tile_bytes; tile_offsets.len()]),
+        ),
+        (SAMPLE_FORMAT, TagValue::Short(vec![SAMPLE_FORMAT_FLOAT])),
+    ];
+    if index == 0 {
+        tags.extend([
+            (
+                MODEL_PIXEL_SCALE,
+                TagValue::Double(vec![pixel_scale[0], pixel_scale[1], 0.0]),
+            ),
+            (
+                MODEL_TIEPOINT,
+                TagValue::Double(vec![0.0, 0.0, 0.0, tiepoint[0], tiepoint[1], 0.0]),
+            ),
+            (
+                GEO_KEY_DIRECTORY,
+                TagValue::Short(vec![
+                    // Version 1.1.0, 3 keys
+                    1, 1, 0, 3, //
+                    // GTModelTypeGeoKey: geographic
+                    1024, 0, 1, 2, //
+                    // GTRasterTypeGeoKey: pixel is area
+                    1025, 0, 1, 1, //
+                    // GeographicTypeGeoKey: WGS 84
+                    2048, 0, 1, 4326,
+                ]),
+            ),
+        ]);
+    }
+    tags.push((GDAL_NODATA, TagValue::Ascii(nodata.to_string())));
+    tags
+}
+
+/// Encode an IFD written at `offset`, followed by the values that don't fit
+/// in their entries.
+fn encode_ifd(mut tags: Vec<(u16, TagValue)>, offset: usize, next: usize) -> Vec<u8> {
+    tags.sort_by_key(|(tag, _)| *tag);
+
+    let mut entries = Vec::new();
+    let mut extra = Vec::new();
+    let extra_start = offset + 2 + tags.len() * 12 + 4;
+    for (tag, value) in &tags {
+        entries.extend(tag.to_le_bytes());
+        entries.extend(value.type_code().to_le_bytes());
+        entries.extend(value.count().to_le_bytes());
+        let mut bytes = value.bytes();
+        if bytes.len() <= 4 {
+            bytes.resize(4, 0);
+            entries.extend(bytes);
+        } else {
+            entries.extend(((extra_start + extra.len()) as u32).to_le_bytes());
+            extra.extend(bytes);
+            // Values start on a word boundary
+            extra.resize(extra.len().div_ceil(2) * 2, 0);
+        }
+    }
+
+    let mut out = Vec::with_capacity(extra_start - offset + extra.len());
+    out.extend((tags.len() as u16).to_le_bytes());
+    out.extend(entries);
+    out.extend((next as u32).to_le_bytes());
+    out.extend(extra);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BoundingBox, GridCoordinates};
+    use std::collections::HashMap;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes(bytes[at..at + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    fn f32_at(bytes: &[u8], at: usize) -> f32 {
+        f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    /// IFDs of a file, as tag → raw value bytes.
+    fn read_ifds(bytes: &[u8]) -> Vec<HashMap<u16, Vec<u8>>> {
+        let mut ifds = Vec::new();
+        let mut offset = u32_at(bytes, 4) as usize;
+        while offset != 0 {
+            let count = u16_at(bytes, offset) as usize;
+            let mut tags = HashMap::new();
+            for i in 0..count {
+                let entry = offset + 2 + i * 12;
+                let size = match u16_at(bytes, entry + 2) {
+                    2 => 1,
+                    3 => 2,
+                    4 => 4,
+                    12 => 8,
+                    other => panic!("unexpected type {}", other),
+                } * u32_at(bytes, entry + 4) as usize;
+                let at = if size <= 4 {
+                    entry + 8
+                } else {
+                    u32_at(bytes, entry + 8) as usize
+                };
+                tags.insert(u16_at(bytes, entry), bytes[at..at + size].to_vec());
+            }
+            ifds.push(tags);
+            offset = u32_at(bytes, offset + 2 + count * 12) as usize;
+        }
+        ifds
+    }
+
+    fn doubles(bytes: &[u8]) -> Vec<f64> {
+        bytes
+            .chunks(8)
+            .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+            .collect()
+    }
+
+    fn longs(bytes: &[u8]) -> Vec<u32> {
+        bytes
+            .chunks(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+            .collect()
+    }
+
+    fn region(width: usize, height: usize) -> GridRegion {
+        let data = (0..width * height).map(|v| v as f32).collect();
+        GridRegion::new(
+            data,
+            width,
+            height,
+            BoundingBox::new(-100.0, 30.0, -100.0 + (width - 1) as f64 * 0.5, 40.0),
+            (0.5, 0.25),
+        )
+    }
+
+    #[test]
+    fn test_georeferencing_and_tiles() {
+        let mut region = region(20, 3);
+        region.data[21] = f32::NAN;
+        let options = GeoTiffOptions {
+            nodata: -9999.0,
+            tile_size: 16,
+        };
+        let bytes = encode_geotiff(&region, &options).unwrap();
+
+        assert_eq!(&bytes[..4], b"II*\0");
+        let ifds = read_ifds(&bytes);
+        // 20x3 needs an overview to fit in a 16x16 tile
+        assert_eq!(ifds.len(), 2);
+
+        let full = &ifds[0];
+        assert_eq!(longs(&full[&IMAGE_WIDTH]), vec![20]);
+        assert_eq!(longs(&full[&IMAGE_LENGTH]), vec![3]);
+        assert_eq!(u16_at(&full[&SAMPLE_FORMAT], 0), SAMPLE_FORMAT_FLOAT);
+        assert_eq!(doubles(&full[&MODEL_PIXEL_SCALE]), vec![0.5, 0.25, 0.0]);
+        // Grid points are pixel centres
+        assert_eq!(
+            doubles(&full[&MODEL_TIEPOINT]),
+            vec![0.0, 0.0, 0.0, -100.25, 40.125, 0.0]
+        );
+        assert_eq!(full[&GDAL_NODATA], b"-9999\0");
+
+        let offsets = longs(&full[&TILE_OFFSETS]);
+        assert_eq!(offsets.len(), 2);
+        assert_eq!(longs(&full[&TILE_BYTE_COUNTS]), vec![16 * 16 * 4; 2]);
+        // Full resolution tiles come last
+        assert_eq!(offsets[1] as usize + 16 * 16 * 4, bytes.len());
+
+        let first = offsets[0] as usize;
+        assert_eq!(f32_at(&bytes, first), 0.0);
+        // Row 1 starts one tile row in; the NaN cell became nodata
+        assert_eq!(f32_at(&bytes, first + 16 * 4), 20.0);
+        assert_eq!(f32_at(&bytes, first + 17 * 4), -9999.0);
+        // Past the last row the tile is padded with nodata
+        assert_eq!(f32_at(&bytes, first + 3 * 16 * 4), -9999.0);
+        let second = offsets[1] as usize;
+        assert_eq!(f32_at(&bytes, second), 16.0);
+
+        let overview = &ifds[1];
+        assert_eq!(longs(&overview[&NEW_SUBFILE_TYPE]), vec![1]);
+        assert_eq!(longs(&overview[&IMAGE_WIDTH]), vec![10]);
+        assert_eq!(longs(&overview[&IMAGE_LENGTH]), vec![1]);
+        assert!(!overview.contains_key(&MODEL_TIEPOINT));
+        assert!(longs(&overview[&TILE_OFFSETS])[0] < offsets[0]);
+    }
+
+    #[test]
+    fn test_south_to_north_rows_are_flipped() {
+        let mut region = GridRegion::new(
+            vec![1.0, 2.0, 3.0, 4.0],
+            2,
+            2,
+            BoundingBox::new(0.0, 10.0, 1.0, 11.0),
+            (1.0, 1.0),
+        );
+        region.coordinates.lat = AxisCoordinates::regular(10.0, 1.0, 2);
+        let bytes = encode_geotiff(&region, &GeoTiffOptions::default()).unwrap();
+
+        let ifds = read_ifds(&bytes);
+        assert_eq!(ifds.len(), 1);
+        assert_eq!(doubles(&ifds[0][&MODEL_TIEPOINT])[3..5], [-0.5, 11.5]);
+        assert_eq!(ifds[0][&GDAL_NODATA], b"nan\0");
+        let first = longs(&ifds[0][&TILE_OFFSETS])[0] as usize;
+        assert_eq!(f32_at(&bytes, first), 3.0);
+        assert_eq!(f32_at(&bytes, first + 256 * 4), 1.0);
+        assert!(f32_at(&bytes, first + 2 * 4).is_nan());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_regions() {
+        let options = GeoTiffOptions::default();
+
+        let mut irregular = region(2, 2);
+        irregular.coordinates = GridCoordinates {
+            lon: AxisCoordinates::regular(0.0, 1.0, 2),
+            lat: AxisCoordinates::explicit(vec![10.0, 9.5]),
+        };
+        assert!(encode_geotiff(&irregular, &options).is_err());
+
+        let mut short = region(2, 2);
+        short.data.pop();
+        assert!(encode_geotiff(&short, &options).is_err());
+
+        let odd_tiles = GeoTiffOptions {
+            tile_size: 100,
+            ..options
+        };
+        assert!(encode_geotiff(&region(2, 2), &odd_tiles).is_err());
+    }
+}
@@ -2,7 +2,21 @@
 //!
 //! This module is used during ingestion to write grid data
 //! in Zarr V3 format with sharding and optional multi-resolution pyramids.
+//! Exports of a parameter across forecast hours are written by [`series`]
+//! as NetCDF or zipped Zarr, single regions by [`geotiff`] as
+//! Cloud-Optimized GeoTIFF, and regions, level stacks and profiles at one
+//! valid time by [`coverage`] as NetCDF.
 
+pub mod cf;
+mod coverage;
+pub mod geotiff;
+pub mod netcdf;
+mod series;
 mod zarr_writer;
+pub mod zip;
 
+pub use cf::CfAttributes;
+pub use coverage::NetCdfCoverage;
+pub use geotiff::{encode_geotiff, GeoTiffOptions};
+pub use series::{ExportFormat, GridSeries};
 pub use zarr_writer::{MultiscaleWriteResult, ZarrMetadata, ZarrWriteResult, ZarrWriter};
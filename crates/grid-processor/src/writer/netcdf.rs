@@ -0,0 +1,388 @@
+//! Minimal NetCDF writer for data exports.
+//!
+//! Writes the 64-bit offset variant of the classic format (CDF-2), which
+//! every NetCDF library reads and which needs no HDF5. Only fixed-size
+//! variables are supported: exports know their time axis up front, so no
+//! record (unlimited) dimension is needed.
+
+use serde_json::{Map, Value};
+
+use crate::error::{GridProcessorError, Result};
+
+/// Magic number of the 64-bit offset format.
+const MAGIC: &[u8; 4] = b"CDF\x02";
+
+const NC_DIMENSION: u32 = 0x0A;
+const NC_VARIABLE: u32 = 0x0B;
+const NC_ATTRIBUTE: u32 = 0x0C;
+
+/// Values of a variable or attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NcValues {
+    Text(String),
+    Int(Vec<i32>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+}
+
+impl NcValues {
+    /// Attribute value for a JSON attribute as written to Zarr metadata.
+    ///
+    /// Strings become text, integers that fit become ints, other numbers
+    /// doubles. Returns None for values NetCDF attributes can't hold.
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(s) => Some(Self::Text(s.clone())),
+            Value::Number(n) => match n.as_i64().and_then(|i| i32::try_from(i).ok()) {
+                Some(i) => Some(Self::Int(vec![i])),
+                None => Some(Self::Double(vec![n.as_f64()?])),
+            },
+            Value::Array(items) if !items.is_empty() => items
+                .iter()
+                .map(Value::as_f64)
+                .collect::<Option<Vec<_>>>()
+                .map(Self::Double),
+            _ => None,
+        }
+    }
+
+    fn type_code(&self) -> u32 {
+        match self {
+            Self::Text(_) => 2,
+            Self::Int(_) => 4,
+            Self::Float(_) => 5,
+            Self::Double(_) => 6,
+        }
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Text(s) => s.len(),
+            Self::Int(v) => v.len(),
+            Self::Float(v) => v.len(),
+            Self::Double(v) => v.len(),
+        }
+    }
+
+    /// Check if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn byte_len(&self) -> usize {
+        match self {
+            Self::Text(s) => s.len(),
+            Self::Int(v) => v.len() * 4,
+            Self::Float(v) => v.len() * 4,
+            Self::Double(v) => v.len() * 8,
+        }
+    }
+
+    /// Write the values big-endian, padded to a 4-byte boundary.
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Text(s) => out.extend_from_slice(s.as_bytes()),
+            Self::Int(v) => v.iter().for_each(|x| out.extend(x.to_be_bytes())),
+            Self::Float(v) => v.iter().for_each(|x| out.extend(x.to_be_bytes())),
+            Self::Double(v) => v.iter().for_each(|x| out.extend(x.to_be_bytes())),
+        }
+        pad(out);
+    }
+}
+
+/// NetCDF attributes for JSON attributes as written to Zarr metadata.
+///
+/// Attributes without a NetCDF equivalent are dropped.
+pub fn attributes(attrs: Map<String, Value>) -> Vec<(String, NcValues)> {
+    attrs
+        .iter()
+        .filter_map(|(name, value)| Some((name.clone(), NcValues::from_json(value)?)))
+        .collect()
+}
+
+/// Attributes of a float32 data variable.
+///
+/// The fill value must have the variable's type; JSON spells NaN as a
+/// string, so it is replaced with a float NaN.
+pub fn float_variable_attributes(mut attrs: Map<String, Value>) -> Vec<(String, NcValues)> {
+    attrs.remove("_FillValue");
+    let mut attrs = attributes(attrs);
+    attrs.push(("_FillValue".to_string(), NcValues::Float(vec![f32::NAN])));
+    attrs
+}
+
+struct Variable {
+    name: String,
+    dims: Vec<usize>,
+    attributes: Vec<(String, NcValues)>,
+    data: NcValues,
+}
+
+/// An in-memory NetCDF file.
+///
+/// ```ignore
+/// let mut nc = NetCdfFile::new();
+/// nc.add_dimension("lon", 3)?;
+/// nc.add_variable("lon", &["lon"], NcValues::Double(vec![0.0, 1.0, 2.0]), vec![])?;
+/// let bytes = nc.to_bytes();
+/// ```
+#[derive(Default)]
+pub struct NetCdfFile {
+    dims: Vec<(String, usize)>,
+    attributes: Vec<(String, NcValues)>,
+    vars: Vec<Variable>,
+}
+
+impl NetCdfFile {
+    /// Create an empty file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a fixed-size dimension.
+    pub fn add_dimension(&mut self, name: &str, len: usize) -> Result<()> {
+        // Length 0 marks the record dimension in the file format
+        if len == 0 || len > u32::MAX as usize {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "dimension {} has unsupported length {}",
+                name, len
+            )));
+        }
+        self.dims.push((name.to_string(), len));
+        Ok(())
+    }
+
+    /// Add a global attribute.
+    pub fn add_attribute(&mut self, name: &str, value: NcValues) {
+        self.attributes.push((name.to_string(), value));
+    }
+
+    /// Add a variable over previously added dimensions (outermost first).
+    ///
+    /// A variable without dimensions is a scalar holding one value.
+    pub fn add_variable(
+        &mut self,
+        name: &str,
+        dims: &[&str],
+        data: NcValues,
+        attributes: Vec<(String, NcValues)>,
+    ) -> Result<()> {
+        let dims = dims
+            .iter()
+            .map(|dim| {
+                self.dims.iter().position(|(d, _)| d == dim).ok_or_else(|| {
+                    GridProcessorError::InvalidMetadata(format!(
+                        "variable {} uses unknown dimension {}",
+                        name, dim
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let expected: usize = dims.iter().map(|&d| self.dims[d].1).product();
+        if data.len() != expected {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "variable {} has {} values, its dimensions need {}",
+                name,
+                data.len(),
+                expected
+            )));
+        }
+
+        self.vars.push(Variable {
+            name: name.to_string(),
+            dims,
+            attributes,
+            data,
+        });
+        Ok(())
+    }
+
+    /// Encode the file.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Offsets are fixed width, so the header length doesn't depend on them
+        let header_len = self.header(&vec![0; self.vars.len()]).len() as u64;
+        let mut begins = Vec::with_capacity(self.vars.len());
+        let mut offset = header_len;
+        for var in &self.vars {
+            begins.push(offset);
+            offset += padded(var.data.byte_len()) as u64;
+        }
+
+        let mut out = self.header(&begins);
+        out.reserve((offset - header_len) as usize);
+        for var in &self.vars {
+            var.data.write(&mut out);
+        }
+        out
+    }
+
+    fn header(&self, begins: &[u64]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        // numrecs: no record dimension
+        put_u32(&mut out, 0);
+
+        if self.dims.is_empty() {
+            put_absent(&mut out);
+        } else {
+            put_u32(&mut out, NC_DIMENSION);
+            put_u32(&mut out, self.dims.len() as u32);
+            for (name, len) in &self.dims {
+                put_name(&mut out, name);
+                put_u32(&mut out, *len as u32);
+            }
+        }
+
+        put_attributes(&mut out, &self.attributes);
+
+        if self.vars.is_empty() {
+            put_absent(&mut out);
+        } else {
+            put_u32(&mut out, NC_VARIABLE);
+            put_u32(&mut out, self.vars.len() as u32);
+            for (var, begin) in self.vars.iter().zip(begins) {
+                put_name(&mut out, &var.name);
+                put_u32(&mut out, var.dims.len() as u32);
+                for &dim in &var.dims {
+                    put_u32(&mut out, dim as u32);
+                }
+                put_attributes(&mut out, &var.attributes);
+                put_u32(&mut out, var.data.type_code());
+                // Sizes past 4 GiB are clamped, as the format allows for the
+                // last variable
+                let vsize = padded(var.data.byte_len()).min(u32::MAX as usize);
+                put_u32(&mut out, vsize as u32);
+                out.extend_from_slice(&begin.to_be_bytes());
+            }
+        }
+        out
+    }
+}
+
+fn padded(len: usize) -> usize {
+    len.div_ceil(4) * 4
+}
+
+fn pad(out: &mut Vec<u8>) {
+    out.resize(padded(out.len()), 0);
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_absent(out: &mut Vec<u8>) {
+    put_u32(out, 0);
+    put_u32(out, 0);
+}
+
+fn put_name(out: &mut Vec<u8>, name: &str) {
+    put_u32(out, name.len() as u32);
+    out.extend_from_slice(name.as_bytes());
+    pad(out);
+}
+
+fn put_attributes(out: &mut Vec<u8>, attributes: &[(String, NcValues)]) {
+    if attributes.is_empty() {
+        put_absent(out);
+        return;
+    }
+    put_u32(out, NC_ATTRIBUTE);
+    put_u32(out, attributes.len() as u32);
+    for (name, value) in attributes {
+        put_name(out, name);
+        put_u32(out, value.type_code());
+        put_u32(out, value.len() as u32);
+        value.write(out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_header_layout() {
+        let mut nc = NetCdfFile::new();
+        nc.add_dimension("x", 3).unwrap();
+        nc.add_attribute("title", NcValues::Text("ab".to_string()));
+        nc.add_variable(
+            "v",
+            &["x"],
+            NcValues::Float(vec![1.0, 2.0, 3.0]),
+            vec![("units".to_string(), NcValues::Text("K".to_string()))],
+        )
+        .unwrap();
+        let bytes = nc.to_bytes();
+
+        assert_eq!(&bytes[..4], b"CDF\x02");
+        assert_eq!(read_u32(&bytes, 4), 0);
+        // Dimension list: tag, count, name "x" padded to 4, length
+        assert_eq!(read_u32(&bytes, 8), NC_DIMENSION);
+        assert_eq!(read_u32(&bytes, 12), 1);
+        assert_eq!(read_u32(&bytes, 16), 1);
+        assert_eq!(&bytes[20..24], b"x\0\0\0");
+        assert_eq!(read_u32(&bytes, 24), 3);
+        // Global attribute: tag, count, name, NC_CHAR, 2 chars padded
+        assert_eq!(read_u32(&bytes, 28), NC_ATTRIBUTE);
+        assert_eq!(&bytes[40..45], b"title");
+        assert_eq!(read_u32(&bytes, 48), 2);
+        assert_eq!(read_u32(&bytes, 52), 2);
+        assert_eq!(&bytes[56..60], b"ab\0\0");
+
+        // The data follows the header at the variable's begin offset
+        let begin = u64::from_be_bytes(
+            bytes[bytes.len() - 20..bytes.len() - 12]
+                .try_into()
+                .unwrap(),
+        );
+        assert_eq!(begin as usize, bytes.len() - 12);
+        assert_eq!(read_u32(&bytes, bytes.len() - 24), 12);
+        assert_eq!(
+            &bytes[begin as usize..begin as usize + 4],
+            &1.0f32.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_variable_checks() {
+        let mut nc = NetCdfFile::new();
+        nc.add_dimension("x", 2).unwrap();
+        assert!(nc.add_dimension("t", 0).is_err());
+        assert!(nc
+            .add_variable("v", &["y"], NcValues::Int(vec![1, 2]), vec![])
+            .is_err());
+        assert!(nc
+            .add_variable("v", &["x"], NcValues::Int(vec![1]), vec![])
+            .is_err());
+        // Scalars hold a single value
+        nc.add_variable("crs", &[], NcValues::Int(vec![0]), vec![])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_values_from_json() {
+        use serde_json::json;
+
+        assert_eq!(
+            NcValues::from_json(&json!("K")),
+            Some(NcValues::Text("K".to_string()))
+        );
+        assert_eq!(NcValues::from_json(&json!(3)), Some(NcValues::Int(vec![3])));
+        assert_eq!(
+            NcValues::from_json(&json!(298.257223563)),
+            Some(NcValues::Double(vec![298.257223563]))
+        );
+        assert_eq!(
+            NcValues::from_json(&json!([1, 2.5])),
+            Some(NcValues::Double(vec![1.0, 2.5]))
+        );
+        assert_eq!(NcValues::from_json(&json!({"a": 1})), None);
+    }
+}
@@ -0,0 +1,401 @@
+//! Time series of one parameter, written as a single NetCDF file or
+//! zipped Zarr store.
+//!
+//! Exports stack the forecast hours of a run into a `(time, lat, lon)`
+//! variable with the same CF attributes the ingestion Zarr output carries,
+//! so the bundle opens directly in xarray, Panoply or GDAL.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::cf::{self, CfAttributes};
+use super::netcdf::{attributes, float_variable_attributes, NcValues, NetCdfFile};
+use super::zip::ZipArchive;
+use crate::error::{GridProcessorError, Result};
+
+/// Name of the time dimension and coordinate variable.
+const TIME_DIMENSION: &str = "time";
+
+/// File format of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// NetCDF classic (64-bit offset)
+    #[default]
+    NetCdf,
+    /// Zarr V3 store in an uncompressed ZIP archive
+    Zarr,
+}
+
+impl ExportFormat {
+    /// File name extension.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::NetCdf => "nc",
+            ExportFormat::Zarr => "zarr.zip",
+        }
+    }
+
+    /// MIME type of the encoded file.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::NetCdf => "application/x-netcdf",
+            ExportFormat::Zarr => "application/zip",
+        }
+    }
+}
+
+/// Grids of one parameter at successive forecast hours of a run.
+///
+/// All grids share the `lon`/`lat` coordinates; values are row-major with
+/// rows in `lat` order.
+#[derive(Debug, Clone)]
+pub struct GridSeries {
+    pub parameter: String,
+    pub level: String,
+    pub units: String,
+    pub reference_time: DateTime<Utc>,
+    pub lon: Vec<f64>,
+    pub lat: Vec<f64>,
+    cf: CfAttributes,
+    forecast_hours: Vec<u32>,
+    values: Vec<f32>,
+}
+
+impl GridSeries {
+    /// Create an empty series on the given grid.
+    pub fn new(
+        parameter: &str,
+        level: &str,
+        units: &str,
+        reference_time: DateTime<Utc>,
+        lon: Vec<f64>,
+        lat: Vec<f64>,
+    ) -> Self {
+        Self {
+            parameter: parameter.to_string(),
+            level: level.to_string(),
+            units: units.to_string(),
+            reference_time,
+            lon,
+            lat,
+            cf: CfAttributes::for_parameter(parameter, level),
+            forecast_hours: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Replace the CF attributes derived from the parameter name.
+    pub fn with_cf_attributes(mut self, cf: CfAttributes) -> Self {
+        self.cf = cf;
+        self
+    }
+
+    /// Append the grid of the next forecast hour.
+    pub fn push(&mut self, forecast_hour: u32, values: &[f32]) -> Result<()> {
+        let expected = self.lon.len() * self.lat.len();
+        if values.len() != expected {
+            return Err(GridProcessorError::InvalidMetadata(format!(
+                "forecast hour {} has {} values, the grid has {}",
+                forecast_hour,
+                values.len(),
+                expected
+            )));
+        }
+        self.forecast_hours.push(forecast_hour);
+        self.values.extend_from_slice(values);
+        Ok(())
+    }
+
+    /// Forecast hours in the series.
+    pub fn forecast_hours(&self) -> &[u32] {
+        &self.forecast_hours
+    }
+
+    /// Number of time steps.
+    pub fn len(&self) -> usize {
+        self.forecast_hours.len()
+    }
+
+    /// Check if the series has no time steps.
+    pub fn is_empty(&self) -> bool {
+        self.forecast_hours.is_empty()
+    }
+
+    /// Encode the series in the given format.
+    pub fn encode(&self, format: ExportFormat) -> Result<Vec<u8>> {
+        if self.is_empty() {
+            return Err(GridProcessorError::InvalidMetadata(
+                "export has no time steps".to_string(),
+            ));
+        }
+        match format {
+            ExportFormat::NetCdf => self.to_netcdf(),
+            ExportFormat::Zarr => self.to_zarr_zip(),
+        }
+    }
+
+    /// Encode as a NetCDF file.
+    pub fn to_netcdf(&self) -> Result<Vec<u8>> {
+        let [lat_dim, lon_dim] = cf::NATIVE_DIMENSIONS;
+        let mut nc = NetCdfFile::new();
+        nc.add_dimension(TIME_DIMENSION, self.len())?;
+        nc.add_dimension(lat_dim, self.lat.len())?;
+        nc.add_dimension(lon_dim, self.lon.len())?;
+
+        for (name, value) in self.global_attributes() {
+            if let Some(value) = NcValues::from_json(&value) {
+                nc.add_attribute(&name, value);
+            }
+        }
+
+        nc.add_variable(
+            cf::GRID_MAPPING_VARIABLE,
+            &[],
+            NcValues::Int(vec![0]),
+            attributes(cf::crs_attributes()),
+        )?;
+        nc.add_variable(
+            TIME_DIMENSION,
+            &[TIME_DIMENSION],
+            NcValues::Double(self.time_values()),
+            attributes(self.time_attributes()),
+        )?;
+        nc.add_variable(
+            lat_dim,
+            &[lat_dim],
+            NcValues::Double(self.lat.clone()),
+            attributes(cf::coordinate_attributes(lat_dim)),
+        )?;
+        nc.add_variable(
+            lon_dim,
+            &[lon_dim],
+            NcValues::Double(self.lon.clone()),
+            attributes(cf::coordinate_attributes(lon_dim)),
+        )?;
+
+        nc.add_variable(
+            &self.parameter,
+            &[TIME_DIMENSION, lat_dim, lon_dim],
+            NcValues::Float(self.values.clone()),
+            float_variable_attributes(self.data_attributes()),
+        )?;
+
+        Ok(nc.to_bytes())
+    }
+
+    /// Encode as a Zarr V3 store in a ZIP archive.
+    ///
+    /// The data array has one uncompressed chunk per time step.
+    pub fn to_zarr_zip(&self) -> Result<Vec<u8>> {
+        let [lat_dim, lon_dim] = cf::NATIVE_DIMENSIONS;
+        let (nt, ny, nx) = (self.len(), self.lat.len(), self.lon.len());
+        let mut zip = ZipArchive::new();
+
+        let group = json!({
+            "zarr_format": 3,
+            "node_type": "group",
+            "attributes": self.global_attributes(),
+        });
+        zip.add("zarr.json", &to_json(&group)?)?;
+
+        // Scalar grid mapping variable, as in the ingestion output
+        let crs = array_metadata(&[], "int32", json!(0), cf::crs_attributes(), &[]);
+        zip.add(
+            &format!("{}/zarr.json", cf::GRID_MAPPING_VARIABLE),
+            &to_json(&crs)?,
+        )?;
+
+        for (name, values, attrs) in [
+            (TIME_DIMENSION, self.time_values(), self.time_attributes()),
+            (
+                lat_dim,
+                self.lat.clone(),
+                cf::coordinate_attributes(lat_dim),
+            ),
+            (
+                lon_dim,
+                self.lon.clone(),
+                cf::coordinate_attributes(lon_dim),
+            ),
+        ] {
+            let meta = array_metadata(&[values.len()], "float64", json!("NaN"), attrs, &[name]);
+            zip.add(&format!("{}/zarr.json", name), &to_json(&meta)?)?;
+            let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+            zip.add(&format!("{}/c/0", name), &bytes)?;
+        }
+
+        let mut meta = array_metadata(
+            &[nt, ny, nx],
+            "float32",
+            json!("NaN"),
+            self.data_attributes(),
+            &[TIME_DIMENSION, lat_dim, lon_dim],
+        );
+        meta["chunk_grid"]["configuration"]["chunk_shape"] = json!([1, ny, nx]);
+        zip.add(&format!("{}/zarr.json", self.parameter), &to_json(&meta)?)?;
+        for (t, step) in self.values.chunks_exact(ny * nx).enumerate() {
+            let bytes: Vec<u8> = step.iter().flat_map(|v| v.to_le_bytes()).collect();
+            zip.add(&format!("{}/c/{}/0/0", self.parameter, t), &bytes)?;
+        }
+
+        zip.finish()
+    }
+
+    /// Hours since the reference time of each step.
+    fn time_values(&self) -> Vec<f64> {
+        self.forecast_hours.iter().map(|&h| h as f64).collect()
+    }
+
+    fn time_attributes(&self) -> Map<String, Value> {
+        cf::time_attributes(self.reference_time)
+    }
+
+    fn data_attributes(&self) -> Map<String, Value> {
+        let mut attrs = Map::new();
+        self.cf.insert_into(&mut attrs);
+        attrs.insert("units".to_string(), json!(self.units));
+        attrs.insert("grid_mapping".to_string(), json!(cf::GRID_MAPPING_VARIABLE));
+        attrs
+    }
+
+    fn global_attributes(&self) -> Map<String, Value> {
+        let mut attrs = Map::new();
+        attrs.insert("Conventions".to_string(), json!(cf::CF_CONVENTIONS));
+        attrs.insert(
+            "title".to_string(),
+            json!(format!("{} at {}", self.parameter, self.level)),
+        );
+        attrs.insert("parameter".to_string(), json!(self.parameter));
+        attrs.insert("level".to_string(), json!(self.level));
+        attrs.insert(
+            "reference_time".to_string(),
+            json!(self.reference_time.to_rfc3339()),
+        );
+        attrs
+    }
+}
+
+/// Zarr V3 array metadata with a single chunk and no compression.
+fn array_metadata(
+    shape: &[usize],
+    data_type: &str,
+    fill_value: Value,
+    attributes: Map<String, Value>,
+    dimension_names: &[&str],
+) -> Value {
+    json!({
+        "zarr_format": 3,
+        "node_type": "array",
+        "shape": shape,
+        "data_type": data_type,
+        "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": shape}},
+        "chunk_key_encoding": {"name": "default", "configuration": {"separator": "/"}},
+        "fill_value": fill_value,
+        "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}],
+        "attributes": attributes,
+        "dimension_names": dimension_names,
+    })
+}
+
+fn to_json(value: &Value) -> Result<Vec<u8>> {
+    serde_json::to_vec_pretty(value).map_err(|e| GridProcessorError::StorageError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::cf::checker;
+    use crate::writer::zip::read_entries;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn series() -> GridSeries {
+        let run = Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap();
+        let mut series = GridSeries::new(
+            "TMP",
+            "2 m above ground",
+            "K",
+            run,
+            vec![-100.0, -99.0, -98.0],
+            vec![40.0, 39.0],
+        );
+        series.push(0, &[280.0; 6]).unwrap();
+        series
+            .push(3, &[281.0, 282.0, 283.0, 284.0, 285.0, f32::NAN])
+            .unwrap();
+        series
+    }
+
+    #[test]
+    fn test_push_checks_grid_size() {
+        let mut series = series();
+        assert!(series.push(6, &[1.0; 5]).is_err());
+        assert_eq!(series.forecast_hours(), &[0, 3]);
+    }
+
+    #[test]
+    fn test_zarr_bundle_is_cf_compliant() {
+        let entries: HashMap<String, Vec<u8>> = read_entries(&series().to_zarr_zip().unwrap())
+            .into_iter()
+            .collect();
+
+        let json = |key: &str| -> Value { serde_json::from_slice(&entries[key]).unwrap() };
+        let group = json("zarr.json");
+        let arrays: HashMap<String, Value> = ["TMP", "time", "lat", "lon", "crs"]
+            .iter()
+            .map(|name| (name.to_string(), json(&format!("{}/zarr.json", name))))
+            .collect();
+        let errors = checker::check_group(&group["attributes"], &arrays);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        assert_eq!(arrays["TMP"]["shape"], json!([2, 2, 3]));
+        assert_eq!(
+            arrays["TMP"]["chunk_grid"]["configuration"]["chunk_shape"],
+            json!([1, 2, 3])
+        );
+        assert_eq!(
+            arrays["time"]["attributes"]["units"],
+            "hours since 2024-12-17 12:00:00"
+        );
+
+        // Second time step, little-endian float32
+        let chunk = &entries["TMP/c/1/0/0"];
+        assert_eq!(chunk.len(), 6 * 4);
+        assert_eq!(f32::from_le_bytes(chunk[..4].try_into().unwrap()), 281.0);
+        assert!(f32::from_le_bytes(chunk[20..].try_into().unwrap()).is_nan());
+
+        let time = &entries["time/c/0"];
+        assert_eq!(f64::from_le_bytes(time[8..].try_into().unwrap()), 3.0);
+    }
+
+    #[test]
+    fn test_netcdf_bundle() {
+        let bytes = series().encode(ExportFormat::NetCdf).unwrap();
+        assert_eq!(&bytes[..4], b"CDF\x02");
+
+        // The data variable comes last: 2 x 2 x 3 float32 values
+        let data = &bytes[bytes.len() - 48..];
+        assert_eq!(f32::from_be_bytes(data[..4].try_into().unwrap()), 280.0);
+        assert_eq!(f32::from_be_bytes(data[24..28].try_into().unwrap()), 281.0);
+        assert!(f32::from_be_bytes(data[44..].try_into().unwrap()).is_nan());
+
+        let header = String::from_utf8_lossy(&bytes[..bytes.len() - 48]);
+        for text in [
+            "air_temperature",
+            "hours since 2024-12-17 12:00:00",
+            "CF-1.8",
+        ] {
+            assert!(header.contains(text), "missing {}", text);
+        }
+    }
+
+    #[test]
+    fn test_empty_series_is_an_error() {
+        let run = Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap();
+        let series = GridSeries::new("TMP", "surface", "K", run, vec![0.0], vec![0.0]);
+        assert!(series.encode(ExportFormat::Zarr).is_err());
+        assert_eq!(ExportFormat::Zarr.extension(), "zarr.zip");
+    }
+}
@@ -10,14 +10,21 @@ use zarrs::array::codec::array_to_bytes::sharding::ShardingCodecBuilder;
 use zarrs::array::codec::bytes_to_bytes::blosc::{
     BloscCodec, BloscCompressionLevel, BloscCompressor, BloscShuffleMode,
 };
-use zarrs::array::{ArrayBuilder, DataType, FillValue};
+use zarrs::array::codec::bytes_to_bytes::crc32c::Crc32cCodec;
+use zarrs::array::codec::bytes_to_bytes::zstd::ZstdCodec;
+use zarrs::array::codec::BytesToBytesCodecTraits;
+use zarrs::array::{Array, ArrayBuilder, DataType, FillValue};
 use zarrs::array_subset::ArraySubset;
 use zarrs::storage::{ReadableStorageTraits, StoreKey, WritableStorageTraits};
 
-use crate::config::{GridProcessorConfig, PyramidConfig, ZarrCompression};
+use super::cf::{self, CfAttributes};
+use crate::config::{ChunkLayout, GridProcessorConfig, PyramidConfig, ZarrCompression};
 use crate::downsample::{generate_pyramid, DownsampleMethod};
 use crate::error::{GridProcessorError, Result};
-use crate::types::{AxisInfo, BoundingBox, MultiscaleMetadata, PyramidLevel};
+use crate::types::{
+    AxisInfo, BoundingBox, GridCoordinates, MultiscaleMetadata, PyramidLevel, TimeAxis,
+    VerticalAxis,
+};
 
 /// Helper for serde to skip NaN values.
 fn is_nan_f32(v: &f32) -> bool {
@@ -36,6 +43,9 @@ pub struct ZarrMetadata {
     pub shape: (usize, usize),
     /// Chunk dimensions.
     pub chunk_shape: (usize, usize),
+    /// Layout the chunk shape was chosen by.
+    #[serde(default)]
+    pub chunk_layout: ChunkLayout,
     /// Number of chunks (x, y).
     pub num_chunks: (usize, usize),
     /// Data type.
@@ -59,6 +69,26 @@ pub struct ZarrMetadata {
     pub reference_time: DateTime<Utc>,
     /// Forecast hour.
     pub forecast_hour: u32,
+    /// Explicit grid coordinates, for grids that are not uniformly spaced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<GridCoordinates>,
+    /// Checksum appended to each chunk ("crc32c"), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// Forecast hours of a time series array, see
+    /// [`ZarrWriter::create_time_series`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeAxis>,
+    /// Levels of a level stack, see [`ZarrWriter::create_vertical_stack`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vertical: Option<VerticalAxis>,
+}
+
+/// Leading dimension of a 3D array.
+#[derive(Clone, Copy)]
+enum LeadingAxis<'a> {
+    Time(&'a TimeAxis),
+    Vertical(&'a VerticalAxis),
 }
 
 impl ZarrMetadata {
@@ -84,14 +114,44 @@ pub struct ZarrWriteResult {
 }
 
 /// Writer for creating Zarr V3 arrays from grid data.
+///
+/// Arrays carry CF attributes (see [`cf`]) so they can be read by xarray and
+/// other CF-aware tools.
 pub struct ZarrWriter {
     config: GridProcessorConfig,
+    cf: Option<CfAttributes>,
 }
 
 impl ZarrWriter {
     /// Create a new ZarrWriter with the given configuration.
     pub fn new(config: GridProcessorConfig) -> Self {
-        Self { config }
+        Self { config, cf: None }
+    }
+
+    /// Use these CF attributes instead of deriving them from the parameter
+    /// and level (e.g. to set `long_name` from the model config).
+    pub fn with_cf_attributes(mut self, cf: CfAttributes) -> Self {
+        self.cf = Some(cf);
+        self
+    }
+
+    /// CF attributes for a parameter/level.
+    fn cf_attributes(&self, parameter: &str, level: &str) -> CfAttributes {
+        self.cf
+            .clone()
+            .unwrap_or_else(|| CfAttributes::for_parameter(parameter, level))
+    }
+
+    /// Chunk shape (width, height) for a grid or pyramid level, checked
+    /// against the single shard that holds it.
+    fn chunk_shape(&self, width: usize, height: usize) -> Result<(usize, usize)> {
+        let chunk_shape =
+            self.config
+                .chunk_layout
+                .chunk_shape(self.config.zarr_chunk_size, width, height);
+        ChunkLayout::validate_shard(chunk_shape, width, height)
+            .map_err(GridProcessorError::ConfigError)?;
+        Ok(chunk_shape)
     }
 
     /// Write grid data to a Zarr array.
@@ -127,11 +187,11 @@ impl ZarrWriter {
         reference_time: DateTime<Utc>,
         forecast_hour: u32,
     ) -> Result<ZarrWriteResult> {
-        let chunk_size = self.config.zarr_chunk_size;
+        let (chunk_w, chunk_h) = self.chunk_shape(width, height)?;
 
         // Calculate number of chunks
-        let chunks_x = (width + chunk_size - 1) / chunk_size;
-        let chunks_y = (height + chunk_size - 1) / chunk_size;
+        let chunks_x = (width + chunk_w - 1) / chunk_w;
+        let chunks_y = (height + chunk_h - 1) / chunk_h;
 
         // Create storage
         let store = Arc::new(storage);
@@ -142,7 +202,7 @@ impl ZarrWriter {
             path,
             width,
             height,
-            chunk_size,
+            (chunk_w, chunk_h),
             bbox,
             model,
             parameter,
@@ -150,6 +210,7 @@ impl ZarrWriter {
             units,
             reference_time,
             forecast_hour,
+            None,
         )?;
 
         // Store metadata
@@ -172,7 +233,8 @@ impl ZarrWriter {
         // Create metadata for catalog
         let metadata = ZarrMetadata {
             shape: (width, height),
-            chunk_shape: (chunk_size, chunk_size),
+            chunk_shape: (chunk_w, chunk_h),
+            chunk_layout: self.config.chunk_layout,
             num_chunks: (chunks_x, chunks_y),
             dtype: "float32".to_string(),
             fill_value: f32::NAN,
@@ -184,6 +246,10 @@ impl ZarrWriter {
             units: units.to_string(),
             reference_time,
             forecast_hour,
+            coordinates: None,
+            checksum: self.checksum(),
+            time: None,
+            vertical: None,
         };
 
         Ok(ZarrWriteResult {
@@ -192,14 +258,247 @@ impl ZarrWriter {
         })
     }
 
+    /// Create an empty time series array for the forecast hours of a run.
+    ///
+    /// The array is `[time, rows, cols]` with one time step per chunk, so a
+    /// single store holds the whole run. Fill it with
+    /// [`write_time_step`](Self::write_time_step) as forecast hours arrive;
+    /// steps never written read as NaN. The chunks of each step are separate
+    /// objects, so steps can be written concurrently. Time series have no
+    /// pyramid levels.
+    ///
+    /// Returns the catalog metadata, whose `forecast_hour` is the first hour.
+    pub fn create_time_series<S: ReadableStorageTraits + WritableStorageTraits + 'static>(
+        &self,
+        storage: S,
+        path: &str,
+        width: usize,
+        height: usize,
+        bbox: &BoundingBox,
+        model: &str,
+        parameter: &str,
+        level: &str,
+        units: &str,
+        reference_time: DateTime<Utc>,
+        forecast_hours: &[u32],
+    ) -> Result<ZarrMetadata> {
+        let time = TimeAxis::new(forecast_hours.to_vec());
+        let first_hour = *time.forecast_hours.first().ok_or_else(|| {
+            GridProcessorError::ConfigError("time series needs a forecast hour".to_string())
+        })?;
+        let (chunk_w, chunk_h) = self.chunk_shape(width, height)?;
+
+        let array = self.build_array(
+            Arc::new(storage),
+            path,
+            width,
+            height,
+            (chunk_w, chunk_h),
+            bbox,
+            model,
+            parameter,
+            level,
+            units,
+            reference_time,
+            first_hour,
+            Some(LeadingAxis::Time(&time)),
+        )?;
+        array
+            .store_metadata()
+            .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+
+        Ok(ZarrMetadata {
+            shape: (width, height),
+            chunk_shape: (chunk_w, chunk_h),
+            chunk_layout: self.config.chunk_layout,
+            num_chunks: (width.div_ceil(chunk_w), height.div_ceil(chunk_h)),
+            dtype: "float32".to_string(),
+            fill_value: f32::NAN,
+            bbox: *bbox,
+            compression: self.config.zarr_compression.as_str().to_string(),
+            model: model.to_string(),
+            parameter: parameter.to_string(),
+            level: level.to_string(),
+            units: units.to_string(),
+            reference_time,
+            forecast_hour: first_hour,
+            coordinates: None,
+            checksum: self.checksum(),
+            time: Some(time),
+            vertical: None,
+        })
+    }
+
+    /// Write the grid of one forecast hour into a time series array created
+    /// by [`create_time_series`](Self::create_time_series).
+    ///
+    /// # Returns
+    /// Bytes written (approximate)
+    pub fn write_time_step<S: ReadableStorageTraits + WritableStorageTraits + 'static>(
+        &self,
+        storage: S,
+        path: &str,
+        metadata: &ZarrMetadata,
+        forecast_hour: u32,
+        data: &[f32],
+    ) -> Result<u64> {
+        let time = metadata.time.as_ref().ok_or_else(|| {
+            GridProcessorError::ConfigError(format!("{} is not a time series", path))
+        })?;
+        let index = time.index_of_forecast_hour(forecast_hour).ok_or_else(|| {
+            GridProcessorError::ConfigError(format!(
+                "forecast hour {} is not on the time axis of {}",
+                forecast_hour, path
+            ))
+        })?;
+        self.write_leading_slice(storage, path, metadata, index, data)
+    }
+
+    /// Create an empty level stack holding a parameter on several vertical
+    /// levels (e.g., every isobaric level of a forecast hour).
+    ///
+    /// The array is `[level, rows, cols]` with one level per chunk, so
+    /// region reads still fetch a single level while
+    /// [`read_profile`](crate::GridProcessor::read_profile) reads a point
+    /// through all of them from one array. Fill it with
+    /// [`write_vertical_level`](Self::write_vertical_level); levels never
+    /// written read as NaN. Level stacks have no pyramid levels.
+    ///
+    /// `level` describes the stack as a whole (e.g., "isobaric"); the level
+    /// values are in `vertical`.
+    pub fn create_vertical_stack<S: ReadableStorageTraits + WritableStorageTraits + 'static>(
+        &self,
+        storage: S,
+        path: &str,
+        width: usize,
+        height: usize,
+        bbox: &BoundingBox,
+        model: &str,
+        parameter: &str,
+        level: &str,
+        units: &str,
+        reference_time: DateTime<Utc>,
+        forecast_hour: u32,
+        vertical: &VerticalAxis,
+    ) -> Result<ZarrMetadata> {
+        let vertical = VerticalAxis::new(vertical.units.clone(), vertical.values.clone());
+        if vertical.is_empty() {
+            return Err(GridProcessorError::ConfigError(
+                "level stack needs a vertical level".to_string(),
+            ));
+        }
+        let (chunk_w, chunk_h) = self.chunk_shape(width, height)?;
+
+        let array = self.build_array(
+            Arc::new(storage),
+            path,
+            width,
+            height,
+            (chunk_w, chunk_h),
+            bbox,
+            model,
+            parameter,
+            level,
+            units,
+            reference_time,
+            forecast_hour,
+            Some(LeadingAxis::Vertical(&vertical)),
+        )?;
+        array
+            .store_metadata()
+            .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+
+        Ok(ZarrMetadata {
+            shape: (width, height),
+            chunk_shape: (chunk_w, chunk_h),
+            chunk_layout: self.config.chunk_layout,
+            num_chunks: (width.div_ceil(chunk_w), height.div_ceil(chunk_h)),
+            dtype: "float32".to_string(),
+            fill_value: f32::NAN,
+            bbox: *bbox,
+            compression: self.config.zarr_compression.as_str().to_string(),
+            model: model.to_string(),
+            parameter: parameter.to_string(),
+            level: level.to_string(),
+            units: units.to_string(),
+            reference_time,
+            forecast_hour,
+            coordinates: None,
+            checksum: self.checksum(),
+            time: None,
+            vertical: Some(vertical),
+        })
+    }
+
+    /// Write the grid of one vertical level into a level stack created by
+    /// [`create_vertical_stack`](Self::create_vertical_stack).
+    ///
+    /// # Returns
+    /// Bytes written (approximate)
+    pub fn write_vertical_level<S: ReadableStorageTraits + WritableStorageTraits + 'static>(
+        &self,
+        storage: S,
+        path: &str,
+        metadata: &ZarrMetadata,
+        level_value: f64,
+        data: &[f32],
+    ) -> Result<u64> {
+        let vertical = metadata.vertical.as_ref().ok_or_else(|| {
+            GridProcessorError::ConfigError(format!("{} is not a level stack", path))
+        })?;
+        let index = vertical.index_of(level_value).ok_or_else(|| {
+            GridProcessorError::ConfigError(format!(
+                "level {} {} is not on the vertical axis of {}",
+                level_value, vertical.units, path
+            ))
+        })?;
+        self.write_leading_slice(storage, path, metadata, index, data)
+    }
+
+    /// Write one grid at `index` of the leading dimension of a 3D array.
+    fn write_leading_slice<S: ReadableStorageTraits + WritableStorageTraits + 'static>(
+        &self,
+        storage: S,
+        path: &str,
+        metadata: &ZarrMetadata,
+        index: usize,
+        data: &[f32],
+    ) -> Result<u64> {
+        let (width, height) = metadata.shape;
+        if data.len() != width * height {
+            return Err(GridProcessorError::ConfigError(format!(
+                "grid has {} values, expected {}x{}",
+                data.len(),
+                width,
+                height
+            )));
+        }
+
+        let array = Array::open(Arc::new(storage), path)
+            .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+        let subset = ArraySubset::new_with_start_shape(
+            vec![index as u64, 0, 0],
+            vec![1, height as u64, width as u64],
+        )
+        .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+        array
+            .store_array_subset_elements(&subset, data)
+            .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+
+        Ok((data.len() * std::mem::size_of::<f32>()) as u64)
+    }
+
     /// Build a Zarr array with the configured settings.
+    ///
+    /// With a time or vertical axis the array gets a leading dimension of one
+    /// step or level per chunk.
     fn build_array<S: ReadableStorageTraits + WritableStorageTraits + 'static>(
         &self,
         storage: Arc<S>,
         path: &str,
         width: usize,
         height: usize,
-        chunk_size: usize,
+        chunk_shape: (usize, usize),
         bbox: &BoundingBox,
         model: &str,
         parameter: &str,
@@ -207,6 +506,7 @@ impl ZarrWriter {
         units: &str,
         reference_time: DateTime<Utc>,
         forecast_hour: u32,
+        leading: Option<LeadingAxis<'_>>,
     ) -> Result<zarrs::array::Array<S>> {
         // Build attributes
         let mut attrs = serde_json::Map::new();
@@ -226,37 +526,98 @@ impl ZarrWriter {
             "bbox".to_string(),
             serde_json::json!([bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat]),
         );
+        self.cf_attributes(parameter, level).insert_into(&mut attrs);
+
+        // Shape [rows, cols], or [time, rows, cols] for time series and
+        // [level, rows, cols] for level stacks
+        let mut shape = vec![height as u64, width as u64];
+        let mut chunk_dims = vec![chunk_shape.1 as u64, chunk_shape.0 as u64];
+        let mut dimension_names = cf::level_dimensions(0).to_vec();
+        let leading = match leading {
+            Some(LeadingAxis::Time(time)) => {
+                attrs.insert(
+                    "forecast_hours".to_string(),
+                    serde_json::json!(time.forecast_hours),
+                );
+                Some((time.len(), cf::TIME_DIMENSION))
+            }
+            Some(LeadingAxis::Vertical(vertical)) => {
+                attrs.insert("vertical_levels".to_string(), serde_json::json!(vertical));
+                Some((vertical.len(), cf::VERTICAL_DIMENSION))
+            }
+            None => None,
+        };
+        if let Some((len, dimension)) = leading {
+            shape.insert(0, len as u64);
+            chunk_dims.insert(0, 1);
+            dimension_names.insert(0, dimension.to_string());
+        }
 
         // Create chunk grid
-        let chunk_grid: zarrs::array::ChunkGrid = vec![chunk_size as u64, chunk_size as u64]
+        let chunk_grid: zarrs::array::ChunkGrid = chunk_dims
             .try_into()
             .map_err(|e| GridProcessorError::ConfigError(format!("{:?}", e)))?;
 
         // Create array builder
         let mut binding = ArrayBuilder::new(
-            vec![height as u64, width as u64], // shape [rows, cols]
+            shape,
             DataType::Float32,
             chunk_grid,
             FillValue::from(f32::NAN),
         );
-        let mut builder = binding.attributes(attrs);
+        binding
+            .attributes(attrs)
+            .dimension_names(Some(dimension_names))
+            .bytes_to_bytes_codecs(self.bytes_to_bytes_codecs()?)
+            .build(storage, path)
+            .map_err(|e| GridProcessorError::StorageError(e.to_string()))
+    }
 
-        // Add compression if configured
+    /// Codecs applied to each encoded chunk: compression, then a CRC32C
+    /// checksum of the compressed bytes.
+    fn bytes_to_bytes_codecs(&self) -> Result<Vec<Arc<dyn BytesToBytesCodecTraits>>> {
+        let mut codecs = Vec::new();
         if self.config.zarr_compression != ZarrCompression::None {
-            let codec = self.create_compression_codec()?;
-            builder = builder.bytes_to_bytes_codecs(vec![codec]);
+            codecs.push(self.create_compression_codec()?);
+        }
+        if self.config.zarr_checksums {
+            codecs.push(Arc::new(Crc32cCodec::new()) as Arc<dyn BytesToBytesCodecTraits>);
         }
+        Ok(codecs)
+    }
 
-        // Build the array
-        builder
-            .build(storage, path)
-            .map_err(|e| GridProcessorError::StorageError(e.to_string()))
+    /// Name of the chunk checksum recorded in [`ZarrMetadata`].
+    fn checksum(&self) -> Option<String> {
+        self.config.zarr_checksums.then(|| "crc32c".to_string())
     }
 
     /// Create the compression codec based on configuration.
-    fn create_compression_codec(
-        &self,
-    ) -> Result<Arc<dyn zarrs::array::codec::BytesToBytesCodecTraits>> {
+    fn create_compression_codec(&self) -> Result<Arc<dyn BytesToBytesCodecTraits>> {
+        let compression = self.config.zarr_compression;
+        if !compression
+            .levels()
+            .contains(&self.config.zarr_compression_level)
+        {
+            return Err(GridProcessorError::ConfigError(format!(
+                "Invalid compression level {} for {}",
+                self.config.zarr_compression_level, compression
+            )));
+        }
+
+        let compressor = match compression {
+            ZarrCompression::None => {
+                return Err(GridProcessorError::ConfigError(
+                    "No compression configured".to_string(),
+                ))
+            }
+            ZarrCompression::Zstd => {
+                let level = i32::from(self.config.zarr_compression_level);
+                return Ok(Arc::new(ZstdCodec::new(level, false)));
+            }
+            ZarrCompression::Lz4 | ZarrCompression::BloscLz4 => BloscCompressor::LZ4,
+            ZarrCompression::BloscZstd => BloscCompressor::Zstd,
+        };
+
         let level =
             BloscCompressionLevel::try_from(self.config.zarr_compression_level).map_err(|_| {
                 GridProcessorError::ConfigError("Invalid compression level".to_string())
@@ -275,16 +636,6 @@ impl ZarrWriter {
             None
         };
 
-        let compressor = match self.config.zarr_compression {
-            ZarrCompression::None => {
-                return Err(GridProcessorError::ConfigError(
-                    "No compression configured".to_string(),
-                ))
-            }
-            ZarrCompression::Lz4 | ZarrCompression::BloscLz4 => BloscCompressor::LZ4,
-            ZarrCompression::Zstd | ZarrCompression::BloscZstd => BloscCompressor::Zstd,
-        };
-
         // BloscCodec::new(cname, clevel, blocksize, shuffle_mode, typesize)
         let codec = BloscCodec::new(compressor, level, None, shuffle, typesize)
             .map_err(|e| GridProcessorError::ConfigError(e.to_string()))?;
@@ -310,23 +661,20 @@ impl ZarrWriter {
         reference_time: DateTime<Utc>,
         forecast_hour: u32,
     ) -> Result<ZarrWriteResult> {
-        let chunk_size = self.config.zarr_chunk_size;
+        let (chunk_w, chunk_h) = self.chunk_shape(width, height)?;
 
         // Calculate number of chunks
-        let chunks_x = (width + chunk_size - 1) / chunk_size;
-        let chunks_y = (height + chunk_size - 1) / chunk_size;
+        let chunks_x = (width + chunk_w - 1) / chunk_w;
+        let chunks_y = (height + chunk_h - 1) / chunk_h;
 
         // Shard shape covers entire grid (single shard)
-        let shard_shape = vec![
-            (chunks_y * chunk_size) as u64,
-            (chunks_x * chunk_size) as u64,
-        ];
+        let shard_shape = vec![(chunks_y * chunk_h) as u64, (chunks_x * chunk_w) as u64];
 
         // Create storage
         let store = Arc::new(storage);
 
         // Build sharding codec
-        let sharding_codec = self.build_sharding_codec(chunk_size)?;
+        let sharding_codec = self.build_sharding_codec((chunk_w, chunk_h))?;
 
         // Build attributes
         let mut attrs = serde_json::Map::new();
@@ -346,6 +694,7 @@ impl ZarrWriter {
             "bbox".to_string(),
             serde_json::json!([bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat]),
         );
+        self.cf_attributes(parameter, level).insert_into(&mut attrs);
 
         // Create chunk grid for shard
         let chunk_grid: zarrs::array::ChunkGrid = shard_shape
@@ -363,6 +712,7 @@ impl ZarrWriter {
         let array = binding
             .array_to_bytes_codec(Arc::new(sharding_codec))
             .attributes(attrs)
+            .dimension_names(Some(cf::level_dimensions(0)))
             .build(store, path)
             .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
 
@@ -386,7 +736,8 @@ impl ZarrWriter {
         // Create metadata for catalog
         let metadata = ZarrMetadata {
             shape: (width, height),
-            chunk_shape: (chunk_size, chunk_size),
+            chunk_shape: (chunk_w, chunk_h),
+            chunk_layout: self.config.chunk_layout,
             num_chunks: (chunks_x, chunks_y),
             dtype: "float32".to_string(),
             fill_value: f32::NAN,
@@ -398,6 +749,10 @@ impl ZarrWriter {
             units: units.to_string(),
             reference_time,
             forecast_hour,
+            coordinates: None,
+            checksum: self.checksum(),
+            time: None,
+            vertical: None,
         };
 
         Ok(ZarrWriteResult {
@@ -409,24 +764,21 @@ impl ZarrWriter {
     /// Build a sharding codec with compression.
     fn build_sharding_codec(
         &self,
-        chunk_size: usize,
+        chunk_shape: (usize, usize),
     ) -> Result<zarrs::array::codec::array_to_bytes::sharding::ShardingCodec> {
-        // Create inner chunk shape
-        let inner_chunk_shape: Vec<std::num::NonZeroU64> = vec![
-            std::num::NonZeroU64::new(chunk_size as u64).unwrap(),
-            std::num::NonZeroU64::new(chunk_size as u64).unwrap(),
-        ];
-
-        let builder = if self.config.zarr_compression != ZarrCompression::None {
-            let codec = self.create_compression_codec()?;
-            ShardingCodecBuilder::new(inner_chunk_shape.into())
-                .bytes_to_bytes_codecs(vec![codec])
-                .build()
-        } else {
-            ShardingCodecBuilder::new(inner_chunk_shape.into()).build()
-        };
+        // Create inner chunk shape [rows, cols]
+        let inner_chunk_shape: Vec<std::num::NonZeroU64> = [chunk_shape.1, chunk_shape.0]
+            .iter()
+            .map(|&n| {
+                std::num::NonZeroU64::new(n as u64).ok_or_else(|| {
+                    GridProcessorError::ConfigError("chunk dimensions must be > 0".to_string())
+                })
+            })
+            .collect::<Result<_>>()?;
 
-        Ok(builder)
+        Ok(ShardingCodecBuilder::new(inner_chunk_shape.into())
+            .bytes_to_bytes_codecs(self.bytes_to_bytes_codecs()?)
+            .build())
     }
 
     /// Write grid data as a multi-resolution pyramid.
@@ -471,7 +823,7 @@ impl ZarrWriter {
         downsample_method: DownsampleMethod,
     ) -> Result<MultiscaleWriteResult> {
         let store = Arc::new(storage);
-        let chunk_size = self.config.zarr_chunk_size;
+        let (chunk_w, chunk_h) = self.chunk_shape(width, height)?;
 
         // Calculate native resolution
         let native_resolution = (bbox.width() / width as f64, bbox.height() / height as f64);
@@ -483,6 +835,7 @@ impl ZarrWriter {
                 width,
                 height,
                 pyramid_config.min_dimension,
+                pyramid_config.max_levels,
                 downsample_method,
             )
         } else {
@@ -552,7 +905,7 @@ impl ZarrWriter {
                 idx.to_string(),
                 (level_width, level_height),
                 level_data.scale as f64,
-                (chunk_size, chunk_size),
+                result.metadata.chunk_shape,
             ));
         }
 
@@ -572,6 +925,9 @@ impl ZarrWriter {
             downsample_method,
         )?;
 
+        // CF grid mapping and coordinate variables for the native level
+        self.write_cf_variables(store.clone(), group_path, width, height, bbox)?;
+
         // Create multiscale metadata for catalog
         let multiscale_metadata = MultiscaleMetadata {
             name: format!("{}_{}", model, parameter),
@@ -588,10 +944,11 @@ impl ZarrWriter {
         // Create ZarrMetadata for backward compatibility with existing catalog code
         let zarr_metadata = ZarrMetadata {
             shape: (width, height),
-            chunk_shape: (chunk_size, chunk_size),
+            chunk_shape: (chunk_w, chunk_h),
+            chunk_layout: self.config.chunk_layout,
             num_chunks: (
-                (width + chunk_size - 1) / chunk_size,
-                (height + chunk_size - 1) / chunk_size,
+                (width + chunk_w - 1) / chunk_w,
+                (height + chunk_h - 1) / chunk_h,
             ),
             dtype: "float32".to_string(),
             fill_value: f32::NAN,
@@ -606,6 +963,10 @@ impl ZarrWriter {
             units: units.to_string(),
             reference_time,
             forecast_hour,
+            coordinates: None,
+            checksum: self.checksum(),
+            time: None,
+            vertical: None,
         };
 
         Ok(MultiscaleWriteResult {
@@ -634,20 +995,17 @@ impl ZarrWriter {
         pyramid_level: u32,
         scale: f64,
     ) -> Result<ZarrWriteResult> {
-        let chunk_size = self.config.zarr_chunk_size;
+        let (chunk_w, chunk_h) = self.chunk_shape(width, height)?;
 
         // Calculate number of chunks
-        let chunks_x = (width + chunk_size - 1) / chunk_size;
-        let chunks_y = (height + chunk_size - 1) / chunk_size;
+        let chunks_x = (width + chunk_w - 1) / chunk_w;
+        let chunks_y = (height + chunk_h - 1) / chunk_h;
 
         // Shard shape covers entire grid (single shard per level)
-        let shard_shape = vec![
-            (chunks_y * chunk_size) as u64,
-            (chunks_x * chunk_size) as u64,
-        ];
+        let shard_shape = vec![(chunks_y * chunk_h) as u64, (chunks_x * chunk_w) as u64];
 
         // Build sharding codec
-        let sharding_codec = self.build_sharding_codec(chunk_size)?;
+        let sharding_codec = self.build_sharding_codec((chunk_w, chunk_h))?;
 
         // Build attributes for this level
         let mut attrs = serde_json::Map::new();
@@ -672,6 +1030,11 @@ impl ZarrWriter {
             serde_json::json!(pyramid_level),
         );
         attrs.insert("scale".to_string(), serde_json::json!(scale));
+        self.cf_attributes(parameter, level).insert_into(&mut attrs);
+        attrs.insert(
+            "grid_mapping".to_string(),
+            serde_json::json!(cf::GRID_MAPPING_VARIABLE),
+        );
 
         // Create chunk grid for shard
         let chunk_grid: zarrs::array::ChunkGrid = shard_shape
@@ -689,6 +1052,7 @@ impl ZarrWriter {
         let array = binding
             .array_to_bytes_codec(Arc::new(sharding_codec))
             .attributes(attrs)
+            .dimension_names(Some(cf::level_dimensions(pyramid_level)))
             .build(storage, path)
             .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
 
@@ -712,7 +1076,8 @@ impl ZarrWriter {
         // Create metadata for this level
         let metadata = ZarrMetadata {
             shape: (width, height),
-            chunk_shape: (chunk_size, chunk_size),
+            chunk_shape: (chunk_w, chunk_h),
+            chunk_layout: self.config.chunk_layout,
             num_chunks: (chunks_x, chunks_y),
             dtype: "float32".to_string(),
             fill_value: f32::NAN,
@@ -724,6 +1089,10 @@ impl ZarrWriter {
             units: units.to_string(),
             reference_time,
             forecast_hour,
+            coordinates: None,
+            checksum: self.checksum(),
+            time: None,
+            vertical: None,
         };
 
         Ok(ZarrWriteResult {
@@ -784,6 +1153,7 @@ impl ZarrWriter {
 
         // Build full group attributes
         let group_attrs = serde_json::json!({
+            "Conventions": cf::CF_CONVENTIONS,
             "multiscales": multiscales,
             "model": model,
             "parameter": parameter,
@@ -823,6 +1193,87 @@ impl ZarrWriter {
 
         Ok(())
     }
+
+    /// Write the CF `crs` grid mapping variable and the `lat`/`lon`
+    /// coordinate variables of the native level into the group.
+    fn write_cf_variables<S: ReadableStorageTraits + WritableStorageTraits + 'static>(
+        &self,
+        storage: Arc<S>,
+        group_path: &str,
+        width: usize,
+        height: usize,
+        bbox: &BoundingBox,
+    ) -> Result<()> {
+        let node_path = |name: &str| {
+            if group_path == "/" || group_path.is_empty() {
+                format!("/{}", name)
+            } else {
+                format!("{}/{}", group_path.trim_end_matches('/'), name)
+            }
+        };
+
+        // Scalar grid mapping variable: only its attributes matter, so no
+        // chunk is stored and readers see the fill value
+        let crs_metadata = serde_json::json!({
+            "zarr_format": 3,
+            "node_type": "array",
+            "shape": [],
+            "data_type": "int32",
+            "chunk_grid": {"name": "regular", "configuration": {"chunk_shape": []}},
+            "chunk_key_encoding": {"name": "default", "configuration": {"separator": "/"}},
+            "fill_value": 0,
+            "codecs": [{"name": "bytes", "configuration": {"endian": "little"}}],
+            "attributes": cf::crs_attributes(),
+            "dimension_names": []
+        });
+        let crs_key = format!(
+            "{}/zarr.json",
+            node_path(cf::GRID_MAPPING_VARIABLE).trim_start_matches('/')
+        );
+        let store_key =
+            StoreKey::new(&crs_key).map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+        let metadata_bytes = serde_json::to_vec_pretty(&crs_metadata)
+            .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+        storage
+            .set(&store_key, metadata_bytes.into())
+            .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+
+        // Cell coordinates, matching how readers place the grid
+        let resolution = (bbox.width() / width as f64, bbox.height() / height as f64);
+        let coords = GridCoordinates::regular(bbox, (width, height), resolution);
+        let [lat_dim, lon_dim] = cf::NATIVE_DIMENSIONS;
+        for (name, values) in [
+            (lat_dim, coords.lat.values()),
+            (lon_dim, coords.lon.values()),
+        ] {
+            let len = values.len() as u64;
+            let chunk_grid: zarrs::array::ChunkGrid = vec![len]
+                .try_into()
+                .map_err(|e| GridProcessorError::ConfigError(format!("{:?}", e)))?;
+            let mut binding = ArrayBuilder::new(
+                vec![len],
+                DataType::Float64,
+                chunk_grid,
+                FillValue::from(f64::NAN),
+            );
+            let array = binding
+                .attributes(cf::coordinate_attributes(name))
+                .dimension_names(Some([name]))
+                .build(storage.clone(), &node_path(name))
+                .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+
+            array
+                .store_metadata()
+                .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+            let subset = ArraySubset::new_with_start_shape(vec![0], vec![len])
+                .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+            array
+                .store_array_subset_elements(&subset, &values)
+                .map_err(|e| GridProcessorError::StorageError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Result of writing a multi-resolution pyramid.
@@ -841,6 +1292,7 @@ pub struct MultiscaleWriteResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::AccessPattern;
     use zarrs_filesystem::FilesystemStore;
 
     fn create_test_data(width: usize, height: usize) -> Vec<f32> {
@@ -926,11 +1378,226 @@ mod tests {
         assert_eq!(result.metadata.compression, "blosc_zstd");
     }
 
+    #[test]
+    fn test_zarr_writer_codecs_roundtrip() {
+        let data = create_test_data(100, 80);
+        let bbox = BoundingBox::new(0.0, 0.0, 100.0, 80.0);
+
+        for (compression, level) in [
+            (ZarrCompression::None, 1),
+            (ZarrCompression::Zstd, 19),
+            (ZarrCompression::BloscLz4, 5),
+        ] {
+            let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+            let store = FilesystemStore::new(temp_dir.path()).expect("Failed to create store");
+
+            let config = GridProcessorConfig {
+                zarr_compression: compression,
+                zarr_compression_level: level,
+                zarr_chunk_size: 32,
+                ..Default::default()
+            };
+            let result = ZarrWriter::new(config)
+                .write(
+                    store,
+                    "/",
+                    &data,
+                    100,
+                    80,
+                    &bbox,
+                    "test",
+                    "TEST_VAR",
+                    "surface",
+                    "K",
+                    Utc::now(),
+                    0,
+                )
+                .expect("Failed to write");
+            assert_eq!(result.metadata.compression, compression.as_str());
+
+            let store = FilesystemStore::new(temp_dir.path()).expect("Failed to create store");
+            let array = Array::open(Arc::new(store), "/").expect("Failed to open array");
+            let read: Vec<f32> = array
+                .retrieve_array_subset_elements(&array.subset_all())
+                .expect("Failed to read");
+            assert_eq!(read, data, "{} roundtrip", compression);
+        }
+
+        // Blosc levels stop at 9
+        let config = GridProcessorConfig {
+            zarr_compression: ZarrCompression::BloscZstd,
+            zarr_compression_level: 19,
+            ..Default::default()
+        };
+        assert!(ZarrWriter::new(config).create_compression_codec().is_err());
+    }
+
+    #[test]
+    fn test_multiscale_cf_compliance() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let zarr_path = temp_dir.path().join("cf.zarr");
+        std::fs::create_dir_all(&zarr_path).expect("Failed to create dir");
+        let store = FilesystemStore::new(&zarr_path).expect("Failed to create store");
+
+        let config = GridProcessorConfig {
+            zarr_chunk_size: 32,
+            ..Default::default()
+        };
+        let pyramid_config = PyramidConfig {
+            enabled: true,
+            min_dimension: 16,
+            downscale_factor: 2,
+            default_method: DownsampleMethod::Mean,
+            max_levels: None,
+        };
+        let writer = ZarrWriter::new(config).with_cf_attributes(
+            CfAttributes::for_parameter("TMP", "2 m above ground").with_long_name("Temperature"),
+        );
+
+        let data = create_test_data(64, 32);
+        let bbox = BoundingBox::new(-100.0, 30.0, -90.0, 35.0);
+        let result = writer
+            .write_multiscale(
+                store,
+                "/",
+                &data,
+                64,
+                32,
+                &bbox,
+                "test",
+                "TMP",
+                "2 m above ground",
+                "K",
+                Utc::now(),
+                0,
+                &pyramid_config,
+                DownsampleMethod::Mean,
+            )
+            .expect("Failed to write");
+        assert!(result.num_levels > 1);
+
+        let read_json = |path: std::path::PathBuf| -> serde_json::Value {
+            serde_json::from_slice(&std::fs::read(path).expect("Missing zarr.json"))
+                .expect("Invalid zarr.json")
+        };
+        let group = read_json(zarr_path.join("zarr.json"));
+        let mut arrays = std::collections::HashMap::new();
+        for entry in std::fs::read_dir(&zarr_path).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                arrays.insert(name, read_json(path.join("zarr.json")));
+            }
+        }
+
+        let errors = cf::checker::check_group(&group["attributes"], &arrays);
+        assert!(errors.is_empty(), "CF violations: {:?}", errors);
+
+        let native = &arrays["0"]["attributes"];
+        assert_eq!(native["standard_name"], "air_temperature");
+        assert_eq!(native["long_name"], "Temperature");
+        assert_eq!(native["grid_mapping"], "crs");
+        assert_eq!(
+            arrays["0"]["dimension_names"],
+            serde_json::json!(["lat", "lon"])
+        );
+        assert_eq!(arrays["lat"]["shape"], serde_json::json!([32]));
+        assert!(arrays["crs"]["attributes"]["crs_wkt"]
+            .as_str()
+            .unwrap()
+            .contains("EPSG\",4326"));
+    }
+
+    #[test]
+    fn test_multiscale_chunk_layout() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let zarr_path = temp_dir.path().join("layout.zarr");
+        std::fs::create_dir_all(&zarr_path).expect("Failed to create dir");
+        let store = FilesystemStore::new(&zarr_path).expect("Failed to create store");
+
+        let config = GridProcessorConfig {
+            zarr_chunk_size: 32,
+            chunk_layout: ChunkLayout::Auto(AccessPattern::Tiles),
+            ..Default::default()
+        };
+        let pyramid_config = PyramidConfig {
+            min_dimension: 16,
+            max_levels: Some(2),
+            ..Default::default()
+        };
+        let result = ZarrWriter::new(config)
+            .write_multiscale(
+                store,
+                "/",
+                &create_test_data(64, 32),
+                64,
+                32,
+                &BoundingBox::new(-100.0, 30.0, -90.0, 35.0),
+                "test",
+                "TMP",
+                "2 m above ground",
+                "K",
+                Utc::now(),
+                0,
+                &pyramid_config,
+                DownsampleMethod::Mean,
+            )
+            .expect("Failed to write");
+
+        // The 32x16 overview gets chunks that fit it rather than 32x32
+        let levels = &result.multiscale_metadata.levels;
+        assert_eq!(levels[0].chunk_shape, (32, 32));
+        assert_eq!(levels[1].chunk_shape, (32, 16));
+        assert_eq!(
+            result.zarr_metadata.chunk_layout,
+            ChunkLayout::Auto(AccessPattern::Tiles)
+        );
+
+        let array: serde_json::Value = serde_json::from_slice(
+            &std::fs::read(zarr_path.join("1").join("zarr.json")).expect("Missing zarr.json"),
+        )
+        .expect("Invalid zarr.json");
+        assert_eq!(
+            array["codecs"][0]["configuration"]["chunk_shape"],
+            serde_json::json!([16, 32])
+        );
+    }
+
+    #[test]
+    fn test_chunk_layout_rejects_oversized_shard_index() {
+        let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let store = FilesystemStore::new(temp_dir.path()).expect("Failed to create store");
+
+        let config = GridProcessorConfig {
+            chunk_layout: ChunkLayout::Fixed {
+                width: 1,
+                height: 1,
+            },
+            ..Default::default()
+        };
+        let result = ZarrWriter::new(config).write_sharded(
+            store,
+            "/",
+            &create_test_data(300, 300),
+            300,
+            300,
+            &BoundingBox::new(0.0, 0.0, 300.0, 300.0),
+            "test",
+            "TMP",
+            "surface",
+            "K",
+            Utc::now(),
+            0,
+        );
+        assert!(matches!(result, Err(GridProcessorError::ConfigError(_))));
+    }
+
     #[test]
     fn test_zarr_metadata_serialization() {
         let metadata = ZarrMetadata {
             shape: (1440, 721),
             chunk_shape: (512, 512),
+            chunk_layout: ChunkLayout::Auto(AccessPattern::TimeSeries),
             num_chunks: (3, 2),
             dtype: "float32".to_string(),
             fill_value: f32::NAN,
@@ -942,6 +1609,10 @@ mod tests {
             units: "K".to_string(),
             reference_time: Utc::now(),
             forecast_hour: 6,
+            coordinates: None,
+            checksum: Some("crc32c".to_string()),
+            time: None,
+            vertical: None,
         };
 
         let json = metadata.to_json();
@@ -950,5 +1621,15 @@ mod tests {
         assert_eq!(restored.shape, metadata.shape);
         assert_eq!(restored.model, metadata.model);
         assert_eq!(restored.parameter, metadata.parameter);
+        assert_eq!(restored.checksum.as_deref(), Some("crc32c"));
+        assert_eq!(restored.chunk_layout, metadata.chunk_layout);
+
+        // Metadata written before chunk checksums and layouts
+        let mut legacy = json;
+        legacy.as_object_mut().unwrap().remove("checksum");
+        legacy.as_object_mut().unwrap().remove("chunk_layout");
+        let restored = ZarrMetadata::from_json(&legacy).expect("Failed to deserialize");
+        assert!(restored.checksum.is_none());
+        assert_eq!(restored.chunk_layout, ChunkLayout::Square);
     }
 }
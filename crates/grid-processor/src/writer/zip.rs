@@ -0,0 +1,185 @@
+//! Uncompressed ZIP archives for bundling Zarr stores.
+//!
+//! Entries are stored rather than deflated so readers such as zarr-python's
+//! `ZipStore` and GDAL's `/vsizip/` can read chunks in place; chunk codecs
+//! do any compression. ZIP64 is not supported, so archives are limited to
+//! 4 GiB and 65535 entries.
+
+use crate::error::{GridProcessorError, Result};
+
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const CENTRAL_HEADER: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+
+/// Version 2.0: stored entries and directories.
+const VERSION: u16 = 20;
+
+/// General purpose flag bit 11: names are UTF-8.
+const UTF8_NAMES: u16 = 1 << 11;
+
+/// MS-DOS date of 1980-01-01, the earliest representable.
+const DOS_DATE: u16 = (1 << 5) | 1;
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// A ZIP archive built in memory.
+#[derive(Default)]
+pub struct ZipArchive {
+    buf: Vec<u8>,
+    entries: Vec<Entry>,
+}
+
+impl ZipArchive {
+    /// Create an empty archive.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a file.
+    pub fn add(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let too_large =
+            || GridProcessorError::StorageError("ZIP archive exceeds 4 GiB".to_string());
+        let offset = u32::try_from(self.buf.len()).map_err(|_| too_large())?;
+        let size = u32::try_from(data.len()).map_err(|_| too_large())?;
+        if self.entries.len() == u16::MAX as usize {
+            return Err(GridProcessorError::StorageError(
+                "ZIP archive exceeds 65535 entries".to_string(),
+            ));
+        }
+        let crc = crc32fast::hash(data);
+
+        let buf = &mut self.buf;
+        put_u32(buf, LOCAL_HEADER);
+        put_u16(buf, VERSION);
+        put_u16(buf, UTF8_NAMES);
+        put_u16(buf, 0); // stored
+        put_u16(buf, 0); // time
+        put_u16(buf, DOS_DATE);
+        put_u32(buf, crc);
+        put_u32(buf, size);
+        put_u32(buf, size);
+        put_u16(buf, name.len() as u16);
+        put_u16(buf, 0); // extra field length
+        buf.extend_from_slice(name.as_bytes());
+        buf.extend_from_slice(data);
+
+        self.entries.push(Entry {
+            name: name.to_string(),
+            crc,
+            size,
+            offset,
+        });
+        Ok(())
+    }
+
+    /// Write the central directory and return the archive bytes.
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        let too_large =
+            || GridProcessorError::StorageError("ZIP archive exceeds 4 GiB".to_string());
+        let directory_offset = u32::try_from(self.buf.len()).map_err(|_| too_large())?;
+
+        let buf = &mut self.buf;
+        for entry in &self.entries {
+            put_u32(buf, CENTRAL_HEADER);
+            put_u16(buf, VERSION); // made by
+            put_u16(buf, VERSION); // needed to extract
+            put_u16(buf, UTF8_NAMES);
+            put_u16(buf, 0); // stored
+            put_u16(buf, 0); // time
+            put_u16(buf, DOS_DATE);
+            put_u32(buf, entry.crc);
+            put_u32(buf, entry.size);
+            put_u32(buf, entry.size);
+            put_u16(buf, entry.name.len() as u16);
+            put_u16(buf, 0); // extra field length
+            put_u16(buf, 0); // comment length
+            put_u16(buf, 0); // disk number
+            put_u16(buf, 0); // internal attributes
+            put_u32(buf, 0); // external attributes
+            put_u32(buf, entry.offset);
+            buf.extend_from_slice(entry.name.as_bytes());
+        }
+        let directory_size = u32::try_from(buf.len()).map_err(|_| too_large())? - directory_offset;
+
+        put_u32(buf, END_OF_CENTRAL_DIRECTORY);
+        put_u16(buf, 0); // this disk
+        put_u16(buf, 0); // disk with the central directory
+        put_u16(buf, self.entries.len() as u16);
+        put_u16(buf, self.entries.len() as u16);
+        put_u32(buf, directory_size);
+        put_u32(buf, directory_offset);
+        put_u16(buf, 0); // comment length
+
+        Ok(self.buf)
+    }
+}
+
+fn put_u16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Read the files of an archive written by [`ZipArchive`].
+#[cfg(test)]
+pub(crate) fn read_entries(bytes: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let u16_at = |at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+
+    let eocd = bytes.len() - 22;
+    assert_eq!(u32_at(eocd), END_OF_CENTRAL_DIRECTORY as usize);
+    let mut at = u32_at(eocd + 16);
+    (0..u16_at(eocd + 10))
+        .map(|_| {
+            assert_eq!(u32_at(at), CENTRAL_HEADER as usize);
+            let size = u32_at(at + 24);
+            let name_len = u16_at(at + 28);
+            let offset = u32_at(at + 42);
+            let name = String::from_utf8(bytes[at + 46..at + 46 + name_len].to_vec()).unwrap();
+            at += 46 + name_len;
+
+            assert_eq!(u32_at(offset), LOCAL_HEADER as usize);
+            let start = offset + 30 + u16_at(offset + 26);
+            let data = bytes[start..start + size].to_vec();
+            assert_eq!(crc32fast::hash(&data) as usize, u32_at(offset + 14));
+            (name, data)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut zip = ZipArchive::new();
+        zip.add("zarr.json", b"{}").unwrap();
+        zip.add("TMP/c/0/0/0", &[1, 2, 3, 4]).unwrap();
+        zip.add("empty", &[]).unwrap();
+        let bytes = zip.finish().unwrap();
+
+        assert_eq!(
+            read_entries(&bytes),
+            vec![
+                ("zarr.json".to_string(), b"{}".to_vec()),
+                ("TMP/c/0/0/0".to_string(), vec![1, 2, 3, 4]),
+                ("empty".to_string(), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_archive() {
+        let bytes = ZipArchive::new().finish().unwrap();
+        assert_eq!(bytes.len(), 22);
+        assert!(read_entries(&bytes).is_empty());
+    }
+}
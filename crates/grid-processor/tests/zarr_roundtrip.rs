@@ -8,7 +8,10 @@
 
 use std::sync::Arc;
 
-use grid_processor::{BoundingBox, GridProcessor, GridProcessorConfig, ZarrGridProcessor};
+use grid_processor::{
+    BoundingBox, ChunkCache, GridProcessor, GridProcessorConfig, ZarrGridProcessor,
+};
+use tokio::sync::RwLock;
 use zarrs::array::{ArrayBuilder, DataType, FillValue};
 use zarrs::array_subset::ArraySubset;
 use zarrs_filesystem::FilesystemStore;
@@ -301,6 +304,105 @@ async fn test_zarr_read_point() {
     println!("  Tested {} points", test_points.len());
 }
 
+#[tokio::test]
+async fn test_zarr_read_provenance() {
+    // 50x40 grid at 1 deg resolution in 16x16 chunks (4 x 3 chunks)
+    let (width, height, chunk_size) = (50, 40, 16);
+    let bbox = BoundingBox::new(0.0, 0.0, 50.0, 40.0);
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let zarr_path = temp_dir.path().join("test_provenance.zarr");
+    write_zarr_array_simple(
+        &zarr_path,
+        &create_test_data(width, height),
+        width,
+        height,
+        chunk_size,
+        &bbox,
+    )
+    .expect("Failed to write Zarr");
+
+    let store = FilesystemStore::new(&zarr_path).expect("Failed to open store");
+    let processor = ZarrGridProcessor::open(store, "/", GridProcessorConfig::default())
+        .expect("Failed to open ZarrGridProcessor");
+
+    // Columns 0..12 and rows 28..40 (with the interpolation buffer) lie in
+    // the first chunk column of the bottom two chunk rows
+    let region = processor
+        .read_region(&BoundingBox::new(1.0, 1.0, 10.0, 10.0))
+        .await
+        .expect("Failed to read region");
+    let provenance = region.provenance.expect("Region should carry provenance");
+    assert_eq!(provenance.source, "/");
+    assert_eq!(provenance.pyramid_level, 0);
+    assert_eq!(provenance.chunk_keys, vec!["c/1/0", "c/2/0"]);
+    assert!(provenance.dataset_id.is_none(), "No catalog entry was used");
+
+    // A point inside one chunk touches only that chunk
+    let provenance = processor.point_provenance(20.5, 20.5);
+    assert_eq!(provenance.chunk_keys, vec!["c/1/1"]);
+
+    // A point at a chunk corner interpolates across four chunks
+    let provenance = processor.point_provenance(15.5, 24.5);
+    assert_eq!(
+        provenance.chunk_keys,
+        vec!["c/0/0", "c/0/1", "c/1/0", "c/1/1"]
+    );
+
+    assert!(processor
+        .point_provenance(-10.0, 50.0)
+        .chunk_keys
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_verify_shape() {
+    let (width, height, chunk_size) = (50, 40, 16);
+    let bbox = BoundingBox::new(0.0, 0.0, 50.0, 40.0);
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let native_path = temp_dir.path().join("test_shape.zarr");
+    let coarse_path = temp_dir.path().join("test_shape_coarse.zarr");
+    write_zarr_array_simple(
+        &native_path,
+        &create_test_data(width, height),
+        width,
+        height,
+        chunk_size,
+        &bbox,
+    )
+    .expect("Failed to write Zarr");
+    write_zarr_array_simple(
+        &coarse_path,
+        &create_test_data(width / 2, height / 2),
+        width / 2,
+        height / 2,
+        chunk_size,
+        &bbox,
+    )
+    .expect("Failed to write Zarr");
+
+    let store = FilesystemStore::new(&native_path).expect("Failed to open store");
+    let native = ZarrGridProcessor::open(store, "/", GridProcessorConfig::default())
+        .expect("Failed to open ZarrGridProcessor");
+    native
+        .verify_shape()
+        .expect("Native array matches its metadata");
+
+    // Native metadata on a 2x downsampled array is caught before any read
+    let cache = Arc::new(RwLock::new(ChunkCache::new(1024 * 1024)));
+    let store = FilesystemStore::new(&coarse_path).expect("Failed to open store");
+    let coarse = ZarrGridProcessor::with_metadata(
+        store,
+        "/",
+        native.metadata().clone(),
+        cache,
+        GridProcessorConfig::default(),
+    )
+    .expect("Failed to open ZarrGridProcessor");
+    assert!(coarse.verify_shape().is_err());
+}
+
 #[tokio::test]
 async fn test_chunk_cache_efficiency() {
     // Test that the chunk cache works - reading the same region twice should hit cache
@@ -349,3 +451,372 @@ async fn test_chunk_cache_efficiency() {
 
     println!("Cache efficiency test passed!");
 }
+
+#[tokio::test]
+async fn test_prefetch_adjacent_tiles() {
+    // 1° cells in 32-cell chunks: a 4x3 grid of chunks
+    let (width, height) = (100, 80);
+    let bbox = BoundingBox::new(0.0, 0.0, 100.0, 80.0);
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let zarr_path = temp_dir.path().join("test_prefetch.zarr");
+    write_zarr_array_simple(
+        &zarr_path,
+        &create_test_data(width, height),
+        width,
+        height,
+        32,
+        &bbox,
+    )
+    .expect("Failed to write Zarr");
+
+    let open = |chunk_prefetch_rings| {
+        let config = GridProcessorConfig {
+            chunk_prefetch_rings,
+            ..Default::default()
+        };
+        let store = FilesystemStore::new(&zarr_path).expect("Failed to open store");
+        ZarrGridProcessor::open(store, "/", config).expect("Failed to open ZarrGridProcessor")
+    };
+
+    // Zoom 4 tile x=9, y=6, covering chunks (0, 1) and (1, 1)
+    let tile = BoundingBox::new(22.5, 21.943045533438177, 45.0, 40.97989806962013);
+    let east = tile
+        .adjacent_tiles(1)
+        .into_iter()
+        .find(|t| t.min_lon == 45.0 && (t.min_lat - tile.min_lat).abs() < 1e-9)
+        .expect("Missing east neighbour");
+
+    let processor = open(1);
+    processor.read_region(&tile).await.expect("Failed to read");
+    processor.prefetch(&[tile]).await;
+
+    // The ring of tiles around it spans all nine chunks of columns 0-2
+    let stats = processor.cache_stats();
+    assert_eq!(stats.prefetched, 7);
+    assert_eq!(stats.entries, 9);
+    assert_eq!(stats.prefetch_hits, 0);
+
+    // Prefetching again finds everything cached
+    processor.prefetch(&[tile]).await;
+    assert_eq!(processor.cache_stats().prefetched, 7);
+
+    // Panning east reads chunk (2, 1) from the prefetched set
+    let region = processor.read_region(&east).await.expect("Failed to read");
+    assert!(!region.data.is_empty());
+    let stats = processor.cache_stats();
+    assert_eq!(stats.prefetch_hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert!(stats.prefetch_hit_rate() > 0.0);
+
+    // Zero rings turns prefetching off
+    let processor = open(0);
+    processor.read_region(&tile).await.expect("Failed to read");
+    processor.prefetch(&[tile]).await;
+    let stats = processor.cache_stats();
+    assert_eq!(stats.prefetched, 0);
+    assert_eq!(stats.entries, 2);
+}
+
+#[tokio::test]
+async fn test_corrupt_chunk_fails_checksum() {
+    use grid_processor::{ChecksumPolicy, GridProcessorError, ZarrCompression, ZarrWriter};
+
+    let (width, height) = (64, 32);
+    let bbox = BoundingBox::new(0.0, -16.0, 64.0, 16.0);
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let zarr_path = temp_dir.path().join("test.zarr");
+    std::fs::create_dir_all(&zarr_path).expect("Failed to create dir");
+
+    let config = GridProcessorConfig {
+        zarr_chunk_size: 32,
+        zarr_compression: ZarrCompression::None,
+        ..Default::default()
+    };
+    let result = ZarrWriter::new(config.clone())
+        .write(
+            FilesystemStore::new(&zarr_path).expect("Failed to create store"),
+            "/",
+            &create_test_data(width, height),
+            width,
+            height,
+            &bbox,
+            "test",
+            "TEST_VAR",
+            "surface",
+            "units",
+            chrono::Utc::now(),
+            0,
+        )
+        .expect("Failed to write Zarr");
+    assert_eq!(result.metadata.checksum.as_deref(), Some("crc32c"));
+
+    // Flip one bit of the second chunk's data, as bit rot in storage would
+    let chunk_file = zarr_path.join("c/0/1");
+    let mut bytes = std::fs::read(&chunk_file).expect("Failed to read chunk");
+    bytes[0] ^= 0x01;
+    std::fs::write(&chunk_file, bytes).expect("Failed to write chunk");
+
+    let open = |policy| {
+        let config = GridProcessorConfig {
+            checksum_policy: policy,
+            ..config.clone()
+        };
+        let store = FilesystemStore::new(&zarr_path).expect("Failed to open store");
+        ZarrGridProcessor::open(store, "/", config).expect("Failed to open ZarrGridProcessor")
+    };
+
+    let processor = open(ChecksumPolicy::Reject);
+    let verification = processor.verify_chunks().expect("Failed to verify chunks");
+    assert_eq!(verification.chunks_checked, 2);
+    assert_eq!(verification.corrupt_chunks, vec![(1, 0)]);
+
+    // The intact chunk still reads; the corrupt one is rejected
+    let west = BoundingBox::new(0.0, -16.0, 32.0, 16.0);
+    assert!(processor.read_region(&west).await.is_ok());
+    match processor.read_region(&bbox).await {
+        Err(GridProcessorError::ChecksumMismatch(_)) => {}
+        other => panic!(
+            "Expected checksum mismatch, got {:?}",
+            other.map(|r| r.width)
+        ),
+    }
+
+    // Warn serves the chunk as stored
+    let region = open(ChecksumPolicy::Warn)
+        .read_region(&bbox)
+        .await
+        .expect("Failed to read region");
+    assert_eq!(region.data.len(), width * height);
+}
+
+#[tokio::test]
+async fn test_time_series_roundtrip() {
+    use chrono::{Duration, TimeZone, Utc};
+    use grid_processor::{GridProcessorError, TimeSelection, ZarrCompression, ZarrWriter};
+
+    let (width, height) = (40, 20);
+    let bbox = BoundingBox::new(0.0, 0.0, 40.0, 20.0);
+    let run = Utc.with_ymd_and_hms(2024, 12, 12, 0, 0, 0).unwrap();
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let zarr_path = temp_dir.path().join("run.zarr");
+    std::fs::create_dir_all(&zarr_path).expect("Failed to create dir");
+    let store = || FilesystemStore::new(&zarr_path).expect("Failed to create store");
+
+    let writer = ZarrWriter::new(GridProcessorConfig {
+        zarr_chunk_size: 16,
+        zarr_compression: ZarrCompression::None,
+        ..Default::default()
+    });
+    let metadata = writer
+        .create_time_series(
+            store(),
+            "/",
+            width,
+            height,
+            &bbox,
+            "test",
+            "TEST_VAR",
+            "surface",
+            "K",
+            run,
+            &[0, 3, 6],
+        )
+        .expect("Failed to create time series");
+
+    // Each hour's grid is offset by 100000 * hour; hour 3 is never written
+    let step = |hour: u32| -> Vec<f32> {
+        create_test_data(width, height)
+            .into_iter()
+            .map(|v| v + 100_000.0 * hour as f32)
+            .collect()
+    };
+    for hour in [6, 0] {
+        writer
+            .write_time_step(store(), "/", &metadata, hour, &step(hour))
+            .expect("Failed to write time step");
+    }
+    assert!(writer
+        .write_time_step(store(), "/", &metadata, 9, &step(9))
+        .is_err());
+
+    let processor = ZarrGridProcessor::open(store(), "/", GridProcessorConfig::default())
+        .expect("Failed to open ZarrGridProcessor");
+    let grid = processor.metadata();
+    assert_eq!(grid.shape, (width, height));
+    assert_eq!(grid.chunk_shape, (16, 16));
+    assert_eq!(grid.num_time_steps(), 3);
+
+    // Time steps by index, forecast hour and valid time
+    let at = |hour: i64| TimeSelection::ValidTime(run + Duration::hours(hour));
+    for (time, expected) in [
+        (TimeSelection::Index(0), 0.0),
+        (TimeSelection::ForecastHour(6), 600_000.0),
+        (at(6), 600_000.0),
+    ] {
+        let value = processor
+            .read_point_at(0.0, 20.0, &time)
+            .await
+            .expect("Failed to read point")
+            .expect("Point should have value");
+        assert_eq!(value, expected, "{}", time);
+    }
+    // Without a time, the first step is read
+    assert_eq!(processor.read_point(0.0, 20.0).await.unwrap(), Some(0.0));
+    // A step never written is missing data
+    assert_eq!(
+        processor
+            .read_point_at(0.0, 20.0, &TimeSelection::ForecastHour(3))
+            .await
+            .unwrap(),
+        None
+    );
+
+    let region = processor
+        .read_region_at(&bbox, &TimeSelection::ForecastHour(6))
+        .await
+        .expect("Failed to read region");
+    assert_eq!(region.data, step(6));
+    let provenance = region.provenance.expect("Region should have provenance");
+    assert_eq!(provenance.forecast_hour, Some(6));
+
+    match processor.read_region_at(&bbox, &at(12)).await {
+        Err(GridProcessorError::NotFound(_)) => {}
+        other => panic!(
+            "Expected missing time step, got {:?}",
+            other.map(|r| r.width)
+        ),
+    }
+}
+
+#[tokio::test]
+async fn test_vertical_stack_profile() {
+    use chrono::{TimeZone, Utc};
+    use grid_processor::{GridProcessorError, VerticalAxis, ZarrCompression, ZarrWriter};
+
+    let (width, height) = (40, 20);
+    let bbox = BoundingBox::new(0.0, 0.0, 40.0, 20.0);
+    let run = Utc.with_ymd_and_hms(2024, 12, 12, 0, 0, 0).unwrap();
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let zarr_path = temp_dir.path().join("isobaric.zarr");
+    std::fs::create_dir_all(&zarr_path).expect("Failed to create dir");
+    let store = || FilesystemStore::new(&zarr_path).expect("Failed to create store");
+
+    let writer = ZarrWriter::new(GridProcessorConfig {
+        zarr_chunk_size: 16,
+        zarr_compression: ZarrCompression::None,
+        ..Default::default()
+    });
+    let levels = VerticalAxis::new("hPa", vec![1000.0, 850.0, 500.0]);
+    let metadata = writer
+        .create_vertical_stack(
+            store(),
+            "/",
+            width,
+            height,
+            &bbox,
+            "test",
+            "TMP",
+            "isobaric",
+            "K",
+            run,
+            6,
+            &levels,
+        )
+        .expect("Failed to create level stack");
+
+    // Each level's grid is offset by 1000 * level; 850 hPa is never written
+    let level = |hpa: f64| -> Vec<f32> {
+        create_test_data(width, height)
+            .into_iter()
+            .map(|v| v + 1000.0 * hpa as f32)
+            .collect()
+    };
+    for hpa in [500.0, 1000.0] {
+        writer
+            .write_vertical_level(store(), "/", &metadata, hpa, &level(hpa))
+            .expect("Failed to write level");
+    }
+    assert!(writer
+        .write_vertical_level(store(), "/", &metadata, 700.0, &level(700.0))
+        .is_err());
+
+    let processor = ZarrGridProcessor::open(store(), "/", GridProcessorConfig::default())
+        .expect("Failed to open ZarrGridProcessor");
+    let grid = processor.metadata();
+    assert_eq!(grid.shape, (width, height));
+    assert_eq!(grid.vertical.as_ref(), Some(&levels));
+    assert!(grid.time.is_none());
+
+    // Every level at one point, from one array
+    let profile = processor
+        .read_profile(0.0, 20.0)
+        .await
+        .expect("Failed to read profile");
+    assert_eq!(
+        profile,
+        vec![
+            (1000.0, Some(1_000_000.0)),
+            (850.0, None),
+            (500.0, Some(500_000.0))
+        ]
+    );
+
+    // Interpolated between cells like read_point
+    let profile = processor.read_profile(0.5, 19.5).await.unwrap();
+    let point = processor.read_point(0.5, 19.5).await.unwrap();
+    assert_eq!(profile[0].1, point);
+
+    // Outside the grid every level is missing
+    let outside = processor.read_profile(100.0, 50.0).await.unwrap();
+    assert!(outside.iter().all(|(_, value)| value.is_none()));
+
+    // Region reads see the first level
+    let region = processor.read_region(&bbox).await.unwrap();
+    assert_eq!(region.data, level(1000.0));
+
+    // 2D arrays have no profile
+    let flat_path = temp_dir.path().join("flat.zarr");
+    std::fs::create_dir_all(&flat_path).expect("Failed to create dir");
+    writer
+        .write(
+            FilesystemStore::new(&flat_path).unwrap(),
+            "/",
+            &create_test_data(width, height),
+            width,
+            height,
+            &bbox,
+            "test",
+            "TMP",
+            "500 mb",
+            "K",
+            run,
+            6,
+        )
+        .expect("Failed to write 2D array");
+    let flat = ZarrGridProcessor::open(
+        FilesystemStore::new(&flat_path).unwrap(),
+        "/",
+        GridProcessorConfig::default(),
+    )
+    .unwrap();
+    assert!(matches!(
+        flat.read_profile(0.0, 20.0).await,
+        Err(GridProcessorError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_open_missing_array_is_not_found() {
+    use grid_processor::ErrorKind;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let store = FilesystemStore::new(temp_dir.path()).expect("Failed to create store");
+
+    let err = match ZarrGridProcessor::open(store, "/missing", GridProcessorConfig::default()) {
+        Ok(_) => panic!("Opened an array that doesn't exist"),
+        Err(e) => e,
+    };
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert!(!err.is_retryable());
+}
@@ -2,21 +2,27 @@
 
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::Read;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 use zarrs_filesystem::FilesystemStore;
 
+use grib2_parser::sections::LambertConformalParams;
+use grib2_parser::{gaussian, InventoryEntry, MosaicAssembler, MosaicKey, OverlapPolicy};
 use grid_processor::{
-    BoundingBox as GpBoundingBox, DownsampleMethod, GridProcessorConfig, PyramidConfig, ZarrWriter,
+    BoundingBox as GpBoundingBox, CfAttributes, DownsampleMethod, GridProcessorConfig,
+    PyramidConfig, ZarrWriter,
 };
 use projection::LambertConformal;
-use storage::{Catalog, CatalogEntry, ObjectStorage};
+use storage::{Catalog, CatalogEntry, ObjectStorage, SourceLineage};
 
 use crate::error::{IngestionError, Result};
-use crate::metadata::{get_bbox_from_grid, get_model_bbox};
-use crate::tables::{build_filter_for_model, build_tables_for_model};
+use crate::metadata::{get_bbox_from_grid, get_model_bbox, source_lineage};
+use crate::tables::{
+    build_filter_for_model, build_tables_for_model, expected_forecast_hours, IngestionFilter,
+    PyramidSettings,
+};
 use crate::upload::upload_zarr_directory;
 use crate::{IngestOptions, IngestionResult};
 
@@ -24,6 +30,9 @@ use crate::{IngestOptions, IngestionResult};
 ///
 /// Parses the GRIB2 file, extracts target parameters, writes Zarr pyramids,
 /// uploads to object storage, and registers in the catalog.
+///
+/// Parameters delivered as several regional messages on different grids
+/// (tiled MRMS feeds) are mosaicked into a single grid before writing.
 pub async fn ingest_grib2(
     storage: &Arc<ObjectStorage>,
     catalog: &Catalog,
@@ -55,64 +64,105 @@ pub async fn ingest_grib2(
     // Build ingestion filter from model config (fail-fast if config is missing/invalid)
     let filter = build_filter_for_model(&model)?;
 
-    // Parse GRIB2 data
+    let lineage = source_lineage(&data, file_path, options.source_url.as_deref());
+
+    // Locate the messages without decoding them, so only the kept ones are
+    // read and copied
     let mut reader = grib2_parser::Grib2Reader::new(data, tables);
+    let inventory = reader
+        .scan_inventory()
+        .map_err(|e| IngestionError::Grib2Parse(e.to_string()))?;
+    let kept: Vec<&InventoryEntry> = inventory
+        .iter()
+        .filter(|entry| {
+            filter.should_ingest_level(
+                &entry.parameter,
+                entry.level_type,
+                entry.level_value,
+                &entry.level,
+            )
+        })
+        .collect();
+    debug!(
+        messages = inventory.len(),
+        kept = kept.len(),
+        "Scanned GRIB2 inventory"
+    );
+
+    // Tiled parameters are collected and written once all messages are read
+    let tiled_keys = tiled_param_levels(&kept);
+    let mut mosaics = MosaicAssembler::new(OverlapPolicy::Max);
 
     // Track registered parameters
     let mut registered_params: HashSet<String> = HashSet::new();
     let mut datasets_registered = 0usize;
     let mut bytes_written = 0u64;
-    let mut grib_reference_time: Option<DateTime<Utc>> = None;
+    let mut storage_paths: Vec<String> = Vec::new();
     let mut registered_param_names: HashSet<String> = HashSet::new();
 
-    while let Some(message) = reader.next_message().ok().flatten() {
-        // Extract reference time from first message
-        if grib_reference_time.is_none() {
-            grib_reference_time = Some(message.identification.reference_time);
-            info!(
-                reference_time = %message.identification.reference_time,
-                "Extracted reference time"
-            );
-        }
-
-        let param = &message.product_definition.parameter_short_name;
-        let level = &message.product_definition.level_description;
-        let level_type = message.product_definition.level_type;
-        let level_value = message.product_definition.level_value;
-
-        let param_level_key = format!("{}:{}", param, level);
+    // Reference time from the first message
+    let grib_reference_time: Option<DateTime<Utc>> =
+        inventory.first().map(|entry| entry.reference_time);
+    if let Some(reference_time) = grib_reference_time {
+        info!(reference_time = %reference_time, "Extracted reference time");
+    }
 
-        // Check if we should register this parameter (config-driven filtering)
-        let should_register = !registered_params.contains(&param_level_key)
-            && filter.should_ingest(param, level_type, level_value);
+    for entry in kept {
+        let param_level_key = format!("{}:{}", entry.parameter, entry.level);
 
-        if !should_register {
+        // Repeated messages of a field on one grid: the first one is ingested
+        if registered_params.contains(&param_level_key) {
             continue;
         }
 
-        let reference_time = grib_reference_time.unwrap_or_else(Utc::now);
+        let message = match reader
+            .seek_to_offset(entry.offset)
+            .and_then(|_| reader.next_message())
+        {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(e) => {
+                warn!(
+                    error = %e,
+                    param = %entry.parameter,
+                    offset = entry.offset,
+                    "Failed to read GRIB2 message, skipping"
+                );
+                continue;
+            }
+        };
 
-        // Sanitize level for path
-        let level_sanitized = level.replace([' ', '/'], "_").to_lowercase();
+        let param = &message.product_definition.parameter_short_name;
+        let level = &message.product_definition.level_description;
 
-        // Storage path format
-        let zarr_storage_path = build_storage_path(
-            &model,
-            &reference_time,
-            param,
-            &level_sanitized,
-            forecast_hour,
-        );
+        let reference_time = grib_reference_time.unwrap_or_else(Utc::now);
+
+        if !message.grid_definition.is_template_supported()
+            || !message.product_definition.is_template_supported()
+        {
+            debug!(
+                param = %param,
+                level = %level,
+                templates = %message.templates(),
+                "GRIB2 message uses templates the parser only partially decodes"
+            );
+        }
 
         // Extract grid dimensions
         let width = message.grid_definition.num_points_longitude as usize;
         let height = message.grid_definition.num_points_latitude as usize;
 
-        // Unpack the grid data
-        let grid_data = match message.unpack_data() {
+        // Unpack the grid data; points outside the bitmap (e.g. MRMS
+        // coverage gaps) are NaN so they render as no data rather than zero
+        let grid_data = match message.unpack_data_with_missing(f32::NAN) {
             Ok(data) => data,
             Err(e) => {
-                warn!(error = %e, param = %param, "Failed to unpack GRIB2 data, skipping");
+                warn!(
+                    error = %e,
+                    param = %param,
+                    templates = %message.templates(),
+                    "Failed to unpack GRIB2 data, skipping"
+                );
                 continue;
             }
         };
@@ -130,12 +180,13 @@ pub async fn ingest_grib2(
             }
         };
 
-        // Convert values outside valid_range to NaN (sentinel value handling)
+        // Convert values outside valid_range to NaN (sentinel value handling).
+        // Points already missing don't count towards the warning below.
         let mut out_of_range_count = 0usize;
         let grid_data: Vec<f32> = grid_data
             .into_iter()
             .map(|v| {
-                if valid_range.is_valid(v) {
+                if v.is_nan() || valid_range.is_valid(v) {
                     v
                 } else {
                     out_of_range_count += 1;
@@ -165,14 +216,44 @@ pub async fn ingest_grib2(
                 expected = width * height,
                 actual = grid_data.len(),
                 param = %param,
+                templates = %message.templates(),
                 "Grid data size mismatch, skipping"
             );
             continue;
         }
 
+        // Gaussian rows aren't evenly spaced in latitude; resample them onto
+        // the regular latitudes a stored grid is read with
+        let grid_data = if message.grid_definition.gaussian_parallels.is_some() {
+            match gaussian::grid_latitudes(&message.grid_definition) {
+                Some(latitudes) => {
+                    gaussian::regrid_to_regular_latitudes(&grid_data, width, &latitudes)
+                }
+                None => {
+                    warn!(
+                        param = %param,
+                        level = %level,
+                        rows = height,
+                        "Gaussian grid latitudes don't match its row count, skipping"
+                    );
+                    continue;
+                }
+            }
+        } else {
+            grid_data
+        };
+
+        if tiled_keys.contains(&param_level_key) {
+            let key = MosaicKey::from_message(&message);
+            if let Err(e) = mosaics.add(key, &message.grid_definition, grid_data) {
+                warn!(error = %e, param = %param, level = %level, "Cannot mosaic tile, skipping");
+            }
+            continue;
+        }
+
         // Calculate bounding box
-        let gp_bbox = if model == "hrrr" {
-            let proj = LambertConformal::hrrr();
+        let gp_bbox = if let Some(lambert) = &message.grid_definition.lambert_conformal {
+            let proj = lambert_projection(lambert, width, height);
             let (min_lon, min_lat, max_lon, max_lat) = proj.geographic_bounds();
             GpBoundingBox::new(min_lon, min_lat, max_lon, max_lat)
         } else {
@@ -185,65 +266,126 @@ pub async fn ingest_grib2(
             )
         };
 
-        // Get units from config (defaults to "unknown" if not specified)
-        let units = filter.get_units(param);
-
-        // Write Zarr and upload
-        match write_and_upload_zarr(
+        let stored = store_grid(
             storage,
+            catalog,
+            &filter,
+            &model,
+            param,
+            level,
+            reference_time,
+            forecast_hour,
+            message.product_definition.ensemble_member.as_deref(),
+            Some(
+                lineage
+                    .clone()
+                    .with_message(entry.offset as u64, entry.length as u64),
+            ),
             &grid_data,
             width,
             height,
             &gp_bbox,
+        )
+        .await;
+
+        if let Some(stored) = stored {
+            // Uploaded even if registration failed (re-ingest)
+            storage_paths.push(stored.storage_path);
+            if let Some(size) = stored.registered_size {
+                registered_params.insert(param_level_key);
+                registered_param_names.insert(param.to_string());
+                datasets_registered += 1;
+                bytes_written += size;
+            }
+        }
+    }
+
+    let reference_time = grib_reference_time.unwrap_or_else(Utc::now);
+    for (key, mosaic) in mosaics.finish() {
+        info!(
+            param = %key.parameter,
+            level = %key.level,
+            tiles = mosaic.tile_count,
+            width = mosaic.width(),
+            height = mosaic.height(),
+            "Mosaicked tiled GRIB2 messages"
+        );
+
+        let grib_bbox = get_bbox_from_grid(&mosaic.grid_definition());
+        let gp_bbox = GpBoundingBox::new(
+            grib_bbox.min_x,
+            grib_bbox.min_y,
+            grib_bbox.max_x,
+            grib_bbox.max_y,
+        );
+
+        let stored = store_grid(
+            storage,
+            catalog,
+            &filter,
             &model,
-            param,
-            level,
-            units,
+            &key.parameter,
+            &key.level,
             reference_time,
             forecast_hour,
-            &zarr_storage_path,
+            None,
+            None,
+            &mosaic.values,
+            mosaic.width(),
+            mosaic.height(),
+            &gp_bbox,
         )
-        .await
-        {
-            Ok((zarr_file_size, zarr_metadata)) => {
-                // Register in catalog
-                let bbox = get_model_bbox(&model);
-                let entry = CatalogEntry {
-                    model: model.clone(),
-                    parameter: param.to_string(),
-                    level: level.clone(),
-                    reference_time,
-                    forecast_hour,
-                    bbox,
-                    storage_path: zarr_storage_path,
-                    file_size: zarr_file_size,
-                    zarr_metadata: Some(zarr_metadata),
-                };
-
-                match catalog.register_dataset(&entry).await {
-                    Ok(id) => {
-                        debug!(id = %id, param = %param, level = %level, "Registered Zarr dataset");
-                        registered_params.insert(param_level_key);
-                        registered_param_names.insert(param.to_string());
-                        datasets_registered += 1;
-                        bytes_written += zarr_file_size;
-                    }
-                    Err(e) => {
-                        debug!(
-                            param = %param,
-                            level = %level,
-                            error = %e,
-                            "Could not register (may already exist)"
-                        );
-                    }
-                }
+        .await;
+
+        if let Some(stored) = stored {
+            storage_paths.push(stored.storage_path);
+            if let Some(size) = stored.registered_size {
+                registered_param_names.insert(key.parameter);
+                datasets_registered += 1;
+                bytes_written += size;
             }
-            Err(e) => {
-                warn!(error = %e, param = %param, "Failed to write/upload Zarr, skipping");
+        }
+    }
+
+    // Latest-run queries switch to this run once all its hours are in
+    if let (Some(reference_time), Some(expected)) =
+        (grib_reference_time, expected_forecast_hours(&model))
+    {
+        if datasets_registered > 0 {
+            match catalog
+                .update_run_publication(&model, reference_time, expected)
+                .await
+            {
+                Ok(published) => debug!(
+                    model = %model,
+                    reference_time = %reference_time,
+                    expected_forecast_hours = expected,
+                    published,
+                    "Updated run publication"
+                ),
+                Err(e) => warn!(
+                    model = %model,
+                    reference_time = %reference_time,
+                    error = %e,
+                    "Failed to update run publication"
+                ),
             }
         }
     }
 
+    // Refresh display metadata (long name, units, style) for the catalog
+    for param in &registered_param_names {
+        let metadata = filter.parameter_metadata(&model, param, &tables);
+        if let Err(e) = catalog.upsert_parameter_metadata(&metadata).await {
+            warn!(
+                model = %model,
+                parameter = %param,
+                error = %e,
+                "Failed to store parameter metadata"
+            );
+        }
+    }
+
     let parameters: Vec<String> = registered_param_names.into_iter().collect();
 
     info!(
@@ -259,16 +401,178 @@ pub async fn ingest_grib2(
         reference_time: grib_reference_time.unwrap_or_else(Utc::now),
         parameters,
         bytes_written,
+        storage_paths,
+    })
+}
+
+/// Parameter/level keys whose ingested messages come on more than one grid.
+///
+/// These are regional tiles of one product and are mosaicked. Repeated
+/// messages on the same grid (e.g. GFS accumulation windows) are not; the
+/// first one is ingested.
+fn tiled_param_levels(kept: &[&InventoryEntry]) -> HashSet<String> {
+    let mut first_grids = HashMap::new();
+    let mut tiled = HashSet::new();
+
+    for entry in kept {
+        let key = format!("{}:{}", entry.parameter, entry.level);
+        match first_grids.get(&key) {
+            None => {
+                first_grids.insert(key, &entry.grid_definition);
+            }
+            Some(grid) if **grid != entry.grid_definition => {
+                tiled.insert(key);
+            }
+            Some(_) => {}
+        }
+    }
+
+    tiled
+}
+
+/// Projection of a Lambert conformal (template 3.30) grid.
+///
+/// Longitudes are given to the projection in [-180, 180).
+fn lambert_projection(
+    lambert: &LambertConformalParams,
+    width: usize,
+    height: usize,
+) -> LambertConformal {
+    let signed = |lon: f64| if lon >= 180.0 { lon - 360.0 } else { lon };
+    LambertConformal::from_grib2(
+        lambert.first_latitude,
+        signed(lambert.first_longitude),
+        signed(lambert.lov),
+        lambert.latin1,
+        lambert.latin2,
+        lambert.dx,
+        lambert.dy,
+        width,
+        height,
+    )
+}
+
+/// Zarr dataset written by [`store_grid`].
+pub(crate) struct StoredGrid {
+    pub(crate) storage_path: String,
+    /// Zarr size in bytes, if the dataset was registered in the catalog
+    pub(crate) registered_size: Option<u64>,
+}
+
+/// Write one parameter/level grid to Zarr, upload it and register it in the
+/// catalog with its source `lineage` (`None` for grids built from several
+/// messages or files). Returns `None` if the write or upload failed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn store_grid(
+    storage: &Arc<ObjectStorage>,
+    catalog: &Catalog,
+    filter: &IngestionFilter,
+    model: &str,
+    param: &str,
+    level: &str,
+    reference_time: DateTime<Utc>,
+    forecast_hour: u32,
+    ensemble_member: Option<&str>,
+    lineage: Option<SourceLineage>,
+    grid_data: &[f32],
+    width: usize,
+    height: usize,
+    bbox: &GpBoundingBox,
+) -> Option<StoredGrid> {
+    // Sanitize level for path
+    let level_sanitized = level.replace([' ', '/'], "_").to_lowercase();
+
+    // Storage path format
+    let zarr_storage_path = build_storage_path(
+        model,
+        &reference_time,
+        param,
+        &level_sanitized,
+        forecast_hour,
+        ensemble_member,
+    );
+
+    // Get units, CF attributes and pyramid overrides from config
+    let units = filter.get_units(param);
+    let mut cf = CfAttributes::for_parameter(param, level);
+    if let Some(description) = filter.get_description(param) {
+        cf = cf.with_long_name(format!("{} at {}", description, level));
+    }
+    let pyramid_settings = filter.get_pyramid_settings(param);
+
+    // Write Zarr and upload
+    let (zarr_file_size, zarr_metadata) = match write_and_upload_zarr(
+        storage,
+        grid_data,
+        width,
+        height,
+        bbox,
+        model,
+        param,
+        level,
+        units,
+        cf,
+        reference_time,
+        forecast_hour,
+        &zarr_storage_path,
+        &pyramid_settings,
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(e) => {
+            warn!(error = %e, param = %param, "Failed to write/upload Zarr, skipping");
+            return None;
+        }
+    };
+
+    // Register in catalog
+    let entry = CatalogEntry {
+        model: model.to_string(),
+        parameter: param.to_string(),
+        level: level.to_string(),
+        reference_time,
+        forecast_hour,
+        bbox: get_model_bbox(model),
+        storage_path: zarr_storage_path.clone(),
+        file_size: zarr_file_size,
+        zarr_metadata: Some(zarr_metadata),
+        ensemble_member: ensemble_member.map(str::to_string),
+        lineage,
+    };
+
+    let registered_size = match catalog.register_dataset(&entry).await {
+        Ok(id) => {
+            debug!(id = %id, param = %param, level = %level, "Registered Zarr dataset");
+            Some(zarr_file_size)
+        }
+        Err(e) => {
+            debug!(
+                param = %param,
+                level = %level,
+                error = %e,
+                "Could not register (may already exist)"
+            );
+            None
+        }
+    };
+
+    Some(StoredGrid {
+        storage_path: zarr_storage_path,
+        registered_size,
     })
 }
 
 /// Build storage path for a parameter.
+///
+/// Ensemble members are stored in a directory per member within the run.
 fn build_storage_path(
     model: &str,
     reference_time: &DateTime<Utc>,
     param: &str,
     level_sanitized: &str,
     forecast_hour: u32,
+    ensemble_member: Option<&str>,
 ) -> String {
     // For observation data like MRMS, use minute-level paths
     // For forecast models, use hourly paths
@@ -277,11 +581,15 @@ fn build_storage_path(
     } else {
         reference_time.format("%Y%m%d_%Hz").to_string()
     };
+    let run_dir = match ensemble_member {
+        Some(member) => format!("{}/{}", run_date, member),
+        None => run_date,
+    };
 
     format!(
         "grids/{}/{}/{}_{}_f{:03}.zarr",
         model,
-        run_date,
+        run_dir,
         param.to_lowercase(),
         level_sanitized,
         forecast_hour
@@ -299,27 +607,32 @@ async fn write_and_upload_zarr(
     param: &str,
     level: &str,
     units: &str,
+    cf: CfAttributes,
     reference_time: DateTime<Utc>,
     forecast_hour: u32,
     storage_path: &str,
+    pyramid_settings: &PyramidSettings,
 ) -> Result<(u64, serde_json::Value)> {
     // Create temporary directory for Zarr output
     let temp_dir = tempfile::tempdir()?;
     let zarr_path = temp_dir.path().join("grid.zarr");
     std::fs::create_dir_all(&zarr_path)?;
 
-    // Create Zarr writer
-    let config = GridProcessorConfig::default();
-    let writer = ZarrWriter::new(config);
+    // Create Zarr writer (model compression and per-parameter chunk layout
+    // override the defaults)
+    let config = pyramid_settings.apply_writer(&GridProcessorConfig::default());
+    let writer = ZarrWriter::new(config).with_cf_attributes(cf);
 
     // Create filesystem store
     let store = FilesystemStore::new(&zarr_path).map_err(|e| {
         IngestionError::ZarrWrite(format!("Failed to create filesystem store: {}", e))
     })?;
 
-    // Configure pyramid generation
-    let pyramid_config = PyramidConfig::from_env();
-    let downsample_method = DownsampleMethod::for_parameter(param);
+    // Configure pyramid generation (per-parameter config overrides env defaults)
+    let pyramid_config = pyramid_settings.apply(&PyramidConfig::from_env());
+    let downsample_method = pyramid_settings
+        .downsample
+        .unwrap_or_else(|| DownsampleMethod::for_parameter(param));
 
     // Write Zarr with pyramid levels
     let write_result = writer
@@ -383,6 +696,35 @@ mod tests {
     use flate2::Compression;
     use std::io::Write;
 
+    #[test]
+    fn test_lambert_projection_from_grid() {
+        // HRRR CONUS grid as read from section 3
+        let lambert = LambertConformalParams {
+            first_latitude: 21.138123,
+            first_longitude: 237.280472,
+            lad: 38.5,
+            lov: 262.5,
+            dx: 3000.0,
+            dy: 3000.0,
+            projection_centre: 0,
+            latin1: 38.5,
+            latin2: 38.5,
+            south_pole_latitude: -90.0,
+            south_pole_longitude: 0.0,
+        };
+
+        let proj = lambert_projection(&lambert, 1799, 1059);
+        let expected = LambertConformal::hrrr();
+        assert_eq!(proj.dimensions(), expected.dimensions());
+
+        let bounds = proj.geographic_bounds();
+        let expected = expected.geographic_bounds();
+        assert!((bounds.0 - expected.0).abs() < 1e-9);
+        assert!((bounds.1 - expected.1).abs() < 1e-9);
+        assert!((bounds.2 - expected.2).abs() < 1e-9);
+        assert!((bounds.3 - expected.3).abs() < 1e-9);
+    }
+
     #[test]
     fn test_decompress_gzip_valid() {
         // Create gzip-compressed test data
@@ -430,15 +772,40 @@ mod tests {
     #[test]
     fn test_build_storage_path_gfs() {
         let reference_time = Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap();
-        let path = build_storage_path("gfs", &reference_time, "TMP", "2m_above_ground", 6);
+        let path = build_storage_path("gfs", &reference_time, "TMP", "2m_above_ground", 6, None);
 
         assert_eq!(path, "grids/gfs/20241217_12z/tmp_2m_above_ground_f006.zarr");
     }
 
+    #[test]
+    fn test_build_storage_path_ensemble_member() {
+        let reference_time = Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap();
+        let path = build_storage_path(
+            "gefs",
+            &reference_time,
+            "TMP",
+            "2m_above_ground",
+            6,
+            Some("p01"),
+        );
+
+        assert_eq!(
+            path,
+            "grids/gefs/20241217_12z/p01/tmp_2m_above_ground_f006.zarr"
+        );
+    }
+
     #[test]
     fn test_build_storage_path_hrrr() {
         let reference_time = Utc.with_ymd_and_hms(2024, 12, 17, 0, 0, 0).unwrap();
-        let path = build_storage_path("hrrr", &reference_time, "UGRD", "10m_above_ground", 12);
+        let path = build_storage_path(
+            "hrrr",
+            &reference_time,
+            "UGRD",
+            "10m_above_ground",
+            12,
+            None,
+        );
 
         // Note: %Hz format produces "0z" for hour 0 (no leading zero)
         assert_eq!(
@@ -451,7 +818,7 @@ mod tests {
     fn test_build_storage_path_mrms() {
         // MRMS uses minute-level timestamps
         let reference_time = Utc.with_ymd_and_hms(2024, 12, 17, 14, 32, 0).unwrap();
-        let path = build_storage_path("mrms", &reference_time, "REFL", "surface", 0);
+        let path = build_storage_path("mrms", &reference_time, "REFL", "surface", 0, None);
 
         assert_eq!(path, "grids/mrms/20241217_1432z/refl_surface_f000.zarr");
     }
@@ -459,7 +826,7 @@ mod tests {
     #[test]
     fn test_build_storage_path_parameter_lowercase() {
         let reference_time = Utc.with_ymd_and_hms(2024, 12, 17, 0, 0, 0).unwrap();
-        let path = build_storage_path("gfs", &reference_time, "CAPE", "surface", 0);
+        let path = build_storage_path("gfs", &reference_time, "CAPE", "surface", 0, None);
 
         // Parameter should be lowercase in path
         assert!(path.contains("/cape_"));
@@ -470,15 +837,15 @@ mod tests {
         let reference_time = Utc.with_ymd_and_hms(2024, 12, 17, 0, 0, 0).unwrap();
 
         // Single digit should be zero-padded to 3 digits
-        let path = build_storage_path("gfs", &reference_time, "TMP", "surface", 3);
+        let path = build_storage_path("gfs", &reference_time, "TMP", "surface", 3, None);
         assert!(path.ends_with("_f003.zarr"));
 
         // Double digit
-        let path = build_storage_path("gfs", &reference_time, "TMP", "surface", 48);
+        let path = build_storage_path("gfs", &reference_time, "TMP", "surface", 48, None);
         assert!(path.ends_with("_f048.zarr"));
 
         // Triple digit
-        let path = build_storage_path("gfs", &reference_time, "TMP", "surface", 120);
+        let path = build_storage_path("gfs", &reference_time, "TMP", "surface", 120, None);
         assert!(path.ends_with("_f120.zarr"));
     }
 
@@ -491,6 +858,7 @@ mod tests {
             reference_time: Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap(),
             parameters: vec!["TMP".to_string(), "UGRD".to_string()],
             bytes_written: 1024 * 1024 * 50, // 50 MB
+            storage_paths: vec![],
         };
 
         assert_eq!(result.datasets_registered, 5);
@@ -508,6 +876,7 @@ mod tests {
             reference_time: Utc::now(),
             parameters: vec![],
             bytes_written: 0,
+            storage_paths: vec![],
         };
 
         assert_eq!(result.datasets_registered, 0);
@@ -11,6 +11,8 @@ use crate::error::Result;
 use crate::grib2;
 use crate::metadata::{detect_file_type, FileType};
 use crate::netcdf;
+use crate::synthetic;
+use crate::true_color::PendingTrueColor;
 
 /// Options for ingestion operations.
 #[derive(Debug, Clone, Default)]
@@ -19,6 +21,8 @@ pub struct IngestOptions {
     pub model: Option<String>,
     /// Override forecast hour detection from filename
     pub forecast_hour: Option<u32>,
+    /// URL the file was downloaded from, recorded in the datasets' lineage
+    pub source_url: Option<String>,
 }
 
 /// Result of an ingestion operation.
@@ -34,6 +38,8 @@ pub struct IngestionResult {
     pub parameters: Vec<String>,
     /// Total bytes written to storage
     pub bytes_written: u64,
+    /// Zarr storage paths written (or overwritten) by this ingestion
+    pub storage_paths: Vec<String>,
 }
 
 /// Core ingester for weather data.
@@ -45,12 +51,18 @@ pub struct Ingester {
     storage: Arc<ObjectStorage>,
     /// Catalog for dataset registration
     catalog: Catalog,
+    /// GOES visible bands waiting for the rest of their scan's true color
+    true_color: PendingTrueColor,
 }
 
 impl Ingester {
     /// Create a new Ingester.
     pub fn new(storage: Arc<ObjectStorage>, catalog: Catalog) -> Self {
-        Self { storage, catalog }
+        Self {
+            storage,
+            catalog,
+            true_color: PendingTrueColor::new(),
+        }
     }
 
     /// Ingest a file from the filesystem.
@@ -96,7 +108,15 @@ impl Ingester {
                 .await
             }
             FileType::NetCdf => {
-                netcdf::ingest_netcdf(&self.storage, &self.catalog, data, file_path, &options).await
+                netcdf::ingest_netcdf(
+                    &self.storage,
+                    &self.catalog,
+                    &self.true_color,
+                    data,
+                    file_path,
+                    &options,
+                )
+                .await
             }
             FileType::Unknown => {
                 // Try to guess based on content or model
@@ -105,6 +125,7 @@ impl Ingester {
                         return netcdf::ingest_netcdf(
                             &self.storage,
                             &self.catalog,
+                            &self.true_color,
                             data,
                             file_path,
                             &options,
@@ -158,7 +179,24 @@ impl Ingester {
         file_path: &str,
         options: IngestOptions,
     ) -> Result<IngestionResult> {
-        netcdf::ingest_netcdf(&self.storage, &self.catalog, data, file_path, &options).await
+        netcdf::ingest_netcdf(
+            &self.storage,
+            &self.catalog,
+            &self.true_color,
+            data,
+            file_path,
+            &options,
+        )
+        .await
+    }
+
+    /// Generate and store a run of a synthetic model (see [`synthetic`]).
+    pub async fn ingest_synthetic(
+        &self,
+        model: &str,
+        reference_time: DateTime<Utc>,
+    ) -> Result<IngestionResult> {
+        synthetic::ingest_synthetic(&self.storage, &self.catalog, model, reference_time).await
     }
 
     /// Get a reference to the storage client.
@@ -18,13 +18,31 @@
 //!
 //! Which parameters are ingested is controlled by each model's YAML config file
 //! in `config/models/`. See [`tables::IngestionFilter`] for details.
+//!
+//! # Satellites
+//!
+//! Geostationary satellites (positions, ellipsoid, sweep axis) are also
+//! defined by model configs. See [`satellites`] for details.
+//!
+//! # Synthetic Model
+//!
+//! Models with `source.type: synthetic` are generated rather than
+//! downloaded, for demos and CI. See [`synthetic`] for details.
+//!
+//! # True Color
+//!
+//! GOES models with a `TRUE_COLOR` parameter get a true color composite of
+//! each scan once its bands C01-C03 are in. See [`true_color`] for details.
 
 pub mod error;
 mod grib2;
 mod ingester;
 pub mod metadata;
 mod netcdf;
+pub mod satellites;
+pub mod synthetic;
 pub mod tables;
+pub mod true_color;
 mod upload;
 
 // Re-exports
@@ -35,7 +53,13 @@ pub use metadata::{
     get_bbox_from_grid, get_model_bbox, goes_band_to_parameter, parse_goes_filename, FileType,
     GoesFileInfo,
 };
+pub use satellites::build_satellite_registry;
+pub use synthetic::{SyntheticModel, SyntheticWeather, SYNTHETIC_MODEL};
 pub use tables::{
-    build_filter_for_model, build_tables_for_model, build_tables_from_configs, IngestionFilter,
-    LevelFilter, ValidRange,
+    build_filter_for_model, build_tables_for_model, build_tables_from_configs,
+    expected_forecast_hours, IngestionFilter, LevelFilter, PyramidSettings, ValidRange,
+};
+pub use true_color::{PendingTrueColor, TRUE_COLOR_PARAMETER};
+pub use upload::{
+    upload_file, upload_metrics, upload_zarr_directory_with_config, UploadConfig, UploadMetrics,
 };
@@ -4,7 +4,9 @@
 //! metadata from weather data filenames.
 
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
 use std::path::Path;
+use storage::SourceLineage;
 use wms_common::BoundingBox;
 
 /// Detected file type based on extension and content.
@@ -23,7 +25,7 @@ pub enum FileType {
 /// Information extracted from a GOES filename.
 #[derive(Debug, Clone)]
 pub struct GoesFileInfo {
-    /// Satellite identifier (goes16, goes18)
+    /// Satellite identifier (e.g. goes18, goes19)
     pub satellite: String,
     /// Band number (1-16)
     pub band: u8,
@@ -52,16 +54,14 @@ pub fn detect_file_type(path: &str) -> FileType {
 
 /// Extract model name from filename.
 ///
-/// Supports: GFS, HRRR, MRMS, GOES-16, GOES-18
+/// Supports: GFS, HRRR, MRMS, GOES (any satellite number)
 pub fn extract_model_from_filename(file_path: &str) -> Option<String> {
     let filename = Path::new(file_path).file_name().and_then(|s| s.to_str())?;
 
     let lower = filename.to_lowercase();
 
-    if lower.contains("_g16_") || lower.contains("goes16") {
-        Some("goes16".to_string())
-    } else if lower.contains("_g18_") || lower.contains("goes18") {
-        Some("goes18".to_string())
+    if let Some(satellite) = goes_satellite_from_filename(filename) {
+        Some(satellite)
     } else if lower.starts_with("hrrr") || lower.contains("hrrr") {
         Some("hrrr".to_string())
     } else if lower.starts_with("gfs") || lower.contains("gfs") {
@@ -138,18 +138,32 @@ pub fn extract_mrms_param(file_path: &str) -> Option<String> {
     }
 }
 
+/// Extract the GOES satellite id from a filename.
+///
+/// Recognizes the `_G19_` platform tag of NOAA file names and `goes19`
+/// anywhere in the name (case-insensitive); both map to `goes19`.
+pub fn goes_satellite_from_filename(filename: &str) -> Option<String> {
+    let lower = filename.to_lowercase();
+    let number_after = |tag: &str, suffix: &str| {
+        lower.match_indices(tag).find_map(|(pos, _)| {
+            let start = pos + tag.len();
+            let digits = lower.get(start..start + 2)?;
+            (digits.bytes().all(|b| b.is_ascii_digit()) && lower[start + 2..].starts_with(suffix))
+                .then(|| digits.to_string())
+        })
+    };
+
+    number_after("_g", "_")
+        .or_else(|| number_after("goes", ""))
+        .map(|number| format!("goes{}", number))
+}
+
 /// Parse GOES filename to extract metadata.
 ///
 /// Example: `OR_ABI-L2-MCMIPC-M6C02_G18_s20241217180021_...`
 pub fn parse_goes_filename(filename: &str) -> Option<GoesFileInfo> {
-    // Extract satellite from _G16_ or _G18_
-    let satellite = if filename.contains("_G16_") {
-        "goes16"
-    } else if filename.contains("_G18_") {
-        "goes18"
-    } else {
-        return None;
-    };
+    // Extract satellite from the _G##_ platform tag
+    let satellite = goes_satellite_from_filename(filename)?;
 
     // Extract band number from M6C## or M3C##
     let band = filename
@@ -185,7 +199,7 @@ pub fn parse_goes_filename(filename: &str) -> Option<GoesFileInfo> {
         .unwrap_or_else(|| "MCMIPC".to_string());
 
     Some(GoesFileInfo {
-        satellite: satellite.to_string(),
+        satellite,
         band,
         scan_mode,
         observation_time,
@@ -221,7 +235,7 @@ pub fn get_model_bbox(model: &str) -> BoundingBox {
         "hrrr" => BoundingBox::new(-122.719528, 21.138123, -60.917193, 47.842195),
         "mrms" => BoundingBox::new(-130.0, 20.0, -60.0, 55.0),
         "gfs" => BoundingBox::new(0.0, -90.0, 360.0, 90.0),
-        "goes16" => BoundingBox::new(-143.0, 14.5, -53.0, 55.5),
+        "goes16" | "goes19" => BoundingBox::new(-143.0, 14.5, -53.0, 55.5),
         "goes18" => BoundingBox::new(-165.0, 14.5, -90.0, 55.5),
         _ => BoundingBox::new(0.0, -90.0, 360.0, 90.0),
     }
@@ -256,6 +270,25 @@ pub fn goes_band_to_parameter(band: u8) -> String {
     format!("CMI_C{:02}", band)
 }
 
+/// Lineage of a source file, recorded with every dataset ingested from it.
+///
+/// `data` is the file contents as parsed (decompressed for `.gz` files), so
+/// GRIB2 message offsets refer to the same bytes as the hash.
+pub fn source_lineage(data: &[u8], file_path: &str, url: Option<&str>) -> SourceLineage {
+    let sha256 = Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    SourceLineage {
+        url: url.map(str::to_string),
+        file: file_path.to_string(),
+        sha256,
+        message_offset: None,
+        message_length: None,
+        ingestion_version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -513,6 +546,34 @@ mod tests {
         assert_eq!(info.scan_mode, "M6");
     }
 
+    #[test]
+    fn test_parse_goes_filename_goes19() {
+        let filename =
+            "OR_ABI-L2-CMIPC-M6C13_G19_s20251001800210_e20251001800210_c20251001800210.nc";
+        let info = parse_goes_filename(filename).expect("Should parse");
+
+        assert_eq!(info.satellite, "goes19");
+        assert_eq!(info.band, 13);
+        assert_eq!(
+            extract_model_from_filename(filename),
+            Some("goes19".to_string())
+        );
+    }
+
+    #[test]
+    fn test_goes_satellite_from_filename() {
+        assert_eq!(
+            goes_satellite_from_filename("goes19_conus.nc"),
+            Some("goes19".to_string())
+        );
+        assert_eq!(
+            goes_satellite_from_filename("OR_ABI_G18_test.nc"),
+            Some("goes18".to_string())
+        );
+        assert_eq!(goes_satellite_from_filename("goes_conus.nc"), None);
+        assert_eq!(goes_satellite_from_filename("hrrr_g123_f001.grib2"), None);
+    }
+
     #[test]
     fn test_parse_goes_filename_m3_scan_mode() {
         let filename =
@@ -626,4 +687,22 @@ mod tests {
         let debug_str = format!("{:?}", ft);
         assert!(debug_str.contains("Grib2"));
     }
+
+    // ==================== Source Lineage ====================
+
+    #[test]
+    fn test_source_lineage() {
+        let lineage = source_lineage(b"abc", "/data/gfs.grib2", Some("https://example.com/gfs"));
+        assert_eq!(
+            lineage.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(lineage.file, "/data/gfs.grib2");
+        assert_eq!(lineage.url.as_deref(), Some("https://example.com/gfs"));
+        assert_eq!(lineage.message_offset, None);
+
+        let message = lineage.with_message(1024, 512);
+        assert_eq!(message.message_offset, Some(1024));
+        assert_eq!(message.message_length, Some(512));
+    }
 }
@@ -8,16 +8,20 @@ use tracing::{debug, info, warn};
 use zarrs_filesystem::FilesystemStore;
 
 use grid_processor::{
-    reproject_geostationary_to_geographic, BoundingBox as GpBoundingBox, DownsampleMethod,
-    GridProcessorConfig, PyramidConfig, ZarrWriter,
+    reproject_geostationary_to_geographic, BoundingBox as GpBoundingBox, CfAttributes,
+    DownsampleMethod, GridProcessorConfig, PyramidConfig, ZarrWriter,
 };
+use netcdf_parser::composite::TRUE_COLOR_BANDS;
+use netcdf_parser::{GoesBand, GoesProjection};
 use projection::Geostationary;
-use storage::{Catalog, CatalogEntry, ObjectStorage};
+use storage::{Catalog, CatalogEntry, ObjectStorage, SourceLineage};
 use wms_common::BoundingBox;
 
 use crate::error::{IngestionError, Result};
-use crate::metadata::parse_goes_filename;
-use crate::tables::build_filter_for_model;
+use crate::metadata::{goes_satellite_from_filename, parse_goes_filename, source_lineage};
+use crate::satellites::build_satellite_registry;
+use crate::tables::{build_filter_for_model, PyramidSettings};
+use crate::true_color::{ingest_true_color, PendingTrueColor, TRUE_COLOR_PARAMETER};
 use crate::upload::upload_zarr_directory;
 use crate::{IngestOptions, IngestionResult};
 
@@ -48,9 +52,14 @@ fn band_to_parameter(band: u8) -> (&'static str, &'static str) {
 ///
 /// Parses the NetCDF file, reprojects from geostationary to geographic coordinates,
 /// writes Zarr pyramids, uploads to object storage, and registers in the catalog.
+///
+/// Bands C01-C03 of models that configure a `TRUE_COLOR` parameter are also
+/// held in `true_color` until their scan is complete, then composited (see
+/// [`crate::true_color`]).
 pub async fn ingest_netcdf(
     storage: &Arc<ObjectStorage>,
     catalog: &Catalog,
+    true_color: &PendingTrueColor,
     data: Bytes,
     file_path: &str,
     options: &IngestOptions,
@@ -63,14 +72,6 @@ pub async fn ingest_netcdf(
     // Parse filename to extract metadata
     let file_info = parse_goes_filename(filename);
 
-    // Determine model (satellite)
-    let model = options.model.clone().unwrap_or_else(|| {
-        file_info
-            .as_ref()
-            .map(|i| i.satellite.clone())
-            .unwrap_or_else(|| extract_satellite_from_filename(filename))
-    });
-
     // Extract band number
     let band = file_info
         .as_ref()
@@ -85,13 +86,56 @@ pub async fn ingest_netcdf(
 
     let (parameter, level) = band_to_parameter(band);
 
+    // Parse the NetCDF file
+    let (raw_data, width, height, attributes, x_offset, y_offset, x_scale, y_scale) =
+        netcdf_parser::load_goes_netcdf_from_bytes(&data)
+            .map_err(|e| IngestionError::NetcdfParse(e.to_string()))?;
+
+    // Identify the satellite from the file's platform and position
+    let registry = build_satellite_registry();
+    let identified = registry.identify(&attributes);
+
+    // Determine model (satellite)
+    let model = options
+        .model
+        .clone()
+        .or_else(|| file_info.as_ref().map(|i| i.satellite.clone()))
+        .or_else(|| identified.map(|s| s.id.clone()))
+        .or_else(|| goes_satellite_from_filename(filename))
+        .ok_or_else(|| {
+            IngestionError::MissingMetadata(format!(
+                "Cannot determine the satellite of {} (platform {:?}, longitude {:?})",
+                filename, attributes.platform_id, attributes.longitude_origin
+            ))
+        })?;
+
+    // The file's own attributes win; the registry fills in what they omit
+    let satellite = identified.or_else(|| registry.get(&model));
+    let projection = attributes.projection(satellite).ok_or_else(|| {
+        IngestionError::Projection(format!(
+            "No longitude_of_projection_origin in {} and no satellite registered for model {}",
+            filename, model
+        ))
+    })?;
+    if !matches!(projection.sweep_angle_axis.as_str(), "x" | "y") {
+        return Err(IngestionError::Projection(format!(
+            "Sweep angle axis {:?} is not supported (only \"x\" or \"y\")",
+            projection.sweep_angle_axis
+        )));
+    }
+
     info!(
         model = %model,
+        satellite = ?satellite.map(|s| &s.id),
         band = band,
         parameter = parameter,
         observation_time = %observation_time,
         file_size = data.len(),
-        "Ingesting GOES NetCDF file"
+        width = width,
+        height = height,
+        longitude_origin = projection.longitude_origin,
+        sweep_angle_axis = %projection.sweep_angle_axis,
+        "Parsed GOES NetCDF file"
     );
 
     // Build ingestion filter from model config (for valid_range)
@@ -105,28 +149,10 @@ pub async fn ingest_netcdf(
         ))
     })?;
 
-    // Parse the NetCDF file
-    let (raw_data, width, height, projection, x_offset, y_offset, x_scale, y_scale) =
-        netcdf_parser::load_goes_netcdf_from_bytes(&data)
-            .map_err(|e| IngestionError::NetcdfParse(e.to_string()))?;
-
-    info!(
-        width = width,
-        height = height,
-        longitude_origin = projection.longitude_origin,
-        "Parsed GOES NetCDF data"
-    );
-
     // Create Geostationary projection for reprojection
-    let proj = Geostationary::from_goes(
-        projection.perspective_point_height,
-        projection.semi_major_axis,
-        projection.semi_minor_axis,
-        projection.longitude_origin,
-        x_offset as f64,
-        y_offset as f64,
-        x_scale as f64,
-        y_scale as f64,
+    let proj = geostationary(
+        &projection,
+        [x_offset, y_offset, x_scale, y_scale],
         width,
         height,
     );
@@ -172,7 +198,144 @@ pub async fn ingest_netcdf(
         "Reprojection complete"
     );
 
-    // Create Zarr storage path: grids/{model}/{date}/{HH}/{param}_{MM}.zarr
+    let units = if band <= 6 {
+        "reflectance" // Visible/near-IR bands
+    } else {
+        "K" // IR bands (brightness temperature)
+    };
+    let (zarr_file_size, zarr_storage_path) = store_grid(
+        storage,
+        catalog,
+        &filtered_data,
+        out_width,
+        out_height,
+        &gp_bbox,
+        &model,
+        parameter,
+        level,
+        units,
+        filter.get_description(parameter),
+        observation_time,
+        &filter.get_pyramid_settings(parameter),
+        Some(source_lineage(
+            &data,
+            file_path,
+            options.source_url.as_deref(),
+        )),
+    )
+    .await?;
+
+    info!(
+        model = %model,
+        parameter = parameter,
+        band = band,
+        "GOES NetCDF ingestion complete"
+    );
+
+    let mut result = IngestionResult {
+        datasets_registered: 1,
+        model,
+        reference_time: observation_time,
+        parameters: vec![parameter.to_string()],
+        bytes_written: zarr_file_size,
+        storage_paths: vec![zarr_storage_path],
+    };
+
+    // Hold visible bands for the true color composite of their scan
+    let wants_true_color = filter.get_valid_range(TRUE_COLOR_PARAMETER).is_some();
+    if wants_true_color && TRUE_COLOR_BANDS.contains(&band) {
+        let goes_band = GoesBand {
+            band,
+            data: raw_data,
+            width,
+            height,
+            scale_factor: 1.0,
+            add_offset: 0.0,
+            attributes,
+            x_offset,
+            y_offset,
+            x_scale,
+            y_scale,
+        };
+        if let Some(bands) = true_color.add(&result.model, observation_time, goes_band) {
+            match ingest_true_color(
+                storage,
+                catalog,
+                &result.model,
+                observation_time,
+                bands,
+                &projection,
+                &filter,
+            )
+            .await
+            {
+                Ok((size, path)) => {
+                    result.datasets_registered += 1;
+                    result.parameters.push(TRUE_COLOR_PARAMETER.to_string());
+                    result.bytes_written += size;
+                    result.storage_paths.push(path);
+                }
+                Err(e) => {
+                    warn!(
+                        model = %result.model,
+                        observation_time = %observation_time,
+                        error = %e,
+                        "Failed to build true color composite"
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Geostationary projection of a scan with the given
+/// `[x_offset, y_offset, x_scale, y_scale]` scan angle geometry.
+pub(crate) fn geostationary(
+    projection: &GoesProjection,
+    [x_offset, y_offset, x_scale, y_scale]: [f32; 4],
+    width: usize,
+    height: usize,
+) -> Geostationary {
+    Geostationary::from_goes(
+        projection.perspective_point_height,
+        projection.semi_major_axis,
+        projection.semi_minor_axis,
+        projection.longitude_origin,
+        x_offset as f64,
+        y_offset as f64,
+        x_scale as f64,
+        y_scale as f64,
+        width,
+        height,
+    )
+    .with_sweep_x(projection.sweeps_x())
+}
+
+/// Write a reprojected grid as Zarr, upload it and register it in the
+/// catalog as an observation.
+///
+/// Stored at `grids/{model}/{date}/{HH}/{param}_{MM}.zarr`, with the source
+/// file's `lineage` if it came from a single file. Returns the stored size
+/// and path.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn store_grid(
+    storage: &Arc<ObjectStorage>,
+    catalog: &Catalog,
+    grid_data: &[f32],
+    width: usize,
+    height: usize,
+    bbox: &GpBoundingBox,
+    model: &str,
+    parameter: &str,
+    level: &str,
+    units: &str,
+    description: Option<&str>,
+    observation_time: DateTime<Utc>,
+    pyramid_settings: &PyramidSettings,
+    lineage: Option<SourceLineage>,
+) -> Result<(u64, String)> {
     let date = observation_time.format("%Y-%m-%d").to_string();
     let hour = observation_time.format("%H").to_string();
     let minute = observation_time.format("%M").to_string();
@@ -184,29 +347,26 @@ pub async fn ingest_netcdf(
     // Write Zarr and upload
     let (zarr_file_size, zarr_metadata) = write_and_upload_zarr(
         storage,
-        &filtered_data,
-        out_width,
-        out_height,
-        &gp_bbox,
-        &model,
+        grid_data,
+        width,
+        height,
+        bbox,
+        model,
         parameter,
         level,
-        band,
+        units,
+        description,
         observation_time,
         &zarr_storage_path,
+        pyramid_settings,
     )
     .await?;
 
     // Register in catalog
-    let catalog_bbox = BoundingBox::new(
-        gp_bbox.min_lon,
-        gp_bbox.min_lat,
-        gp_bbox.max_lon,
-        gp_bbox.max_lat,
-    );
+    let catalog_bbox = BoundingBox::new(bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat);
 
     let entry = CatalogEntry {
-        model: model.clone(),
+        model: model.to_string(),
         parameter: parameter.to_string(),
         level: level.to_string(),
         reference_time: observation_time,
@@ -215,6 +375,8 @@ pub async fn ingest_netcdf(
         storage_path: zarr_storage_path.clone(),
         file_size: zarr_file_size,
         zarr_metadata: Some(zarr_metadata),
+        ensemble_member: None,
+        lineage,
     };
 
     match catalog.register_dataset(&entry).await {
@@ -223,7 +385,6 @@ pub async fn ingest_netcdf(
                 id = %id,
                 parameter = parameter,
                 model = %model,
-                band = band,
                 "Registered GOES Zarr dataset"
             );
         }
@@ -232,20 +393,7 @@ pub async fn ingest_netcdf(
         }
     }
 
-    info!(
-        model = %model,
-        parameter = parameter,
-        band = band,
-        "GOES NetCDF ingestion complete"
-    );
-
-    Ok(IngestionResult {
-        datasets_registered: 1,
-        model,
-        reference_time: observation_time,
-        parameters: vec![parameter.to_string()],
-        bytes_written: zarr_file_size,
-    })
+    Ok((zarr_file_size, zarr_storage_path))
 }
 
 /// Write Zarr pyramid and upload to storage.
@@ -258,34 +406,36 @@ async fn write_and_upload_zarr(
     model: &str,
     param: &str,
     level: &str,
-    band: u8,
+    units: &str,
+    description: Option<&str>,
     observation_time: DateTime<Utc>,
     storage_path: &str,
+    pyramid_settings: &PyramidSettings,
 ) -> Result<(u64, serde_json::Value)> {
     // Create temporary directory
     let temp_dir = tempfile::tempdir()?;
     let zarr_path = temp_dir.path().join("grid.zarr");
     std::fs::create_dir_all(&zarr_path)?;
 
-    // Create Zarr writer
-    let config = GridProcessorConfig::default();
-    let writer = ZarrWriter::new(config);
+    // Create Zarr writer (model compression and per-parameter chunk layout
+    // override the defaults)
+    let config = pyramid_settings.apply_writer(&GridProcessorConfig::default());
+    let mut cf = CfAttributes::for_parameter(param, level);
+    if let Some(description) = description {
+        cf = cf.with_long_name(description);
+    }
+    let writer = ZarrWriter::new(config).with_cf_attributes(cf);
 
     // Create filesystem store
     let store = FilesystemStore::new(&zarr_path).map_err(|e| {
         IngestionError::ZarrWrite(format!("Failed to create filesystem store: {}", e))
     })?;
 
-    // Determine units based on band type
-    let units = if band <= 6 {
-        "reflectance" // Visible/near-IR bands
-    } else {
-        "K" // IR bands (brightness temperature)
-    };
-
     // Configure pyramid generation
-    let pyramid_config = PyramidConfig::from_env();
-    let downsample_method = DownsampleMethod::Mean; // Mean for satellite data
+    let pyramid_config = pyramid_settings.apply(&PyramidConfig::from_env());
+    let downsample_method = pyramid_settings
+        .downsample
+        .unwrap_or(DownsampleMethod::Mean); // Mean for satellite data
 
     // Write Zarr with pyramid levels
     let write_result = writer
@@ -338,17 +488,6 @@ async fn write_and_upload_zarr(
     Ok((zarr_file_size, zarr_json))
 }
 
-/// Extract satellite model from filename.
-fn extract_satellite_from_filename(filename: &str) -> String {
-    if filename.contains("_G16_") || filename.to_lowercase().contains("goes16") {
-        "goes16".to_string()
-    } else if filename.contains("_G18_") || filename.to_lowercase().contains("goes18") {
-        "goes18".to_string()
-    } else {
-        "goes16".to_string() // Default
-    }
-}
-
 /// Extract band number from filename.
 fn extract_band_from_filename(filename: &str) -> Option<u8> {
     filename
@@ -0,0 +1,202 @@
+//! Geostationary satellite registry from model configuration files.
+//!
+//! Every model config whose grid uses the geostationary projection registers
+//! a satellite from its `grid.projection_params`:
+//!
+//! ```yaml
+//! model:
+//!   id: goes19
+//! grid:
+//!   projection: geostationary
+//!   projection_params:
+//!     platform_id: G19                  # `platform_ID` attribute of the files
+//!     satellite_longitude: -75.0        # required
+//!     satellite_height: 35786023.0
+//!     semi_major_axis: 6378137.0
+//!     semi_minor_axis: 6356752.31414
+//!     sweep_angle_axis: x
+//! ```
+//!
+//! Only `satellite_longitude` is required; the rest default to GOES-R values
+//! (Himawari needs `sweep_angle_axis: y`).
+//! Adding a satellite therefore only needs a model config.
+
+use netcdf_parser::{Satellite, SatelliteRegistry};
+use std::fs;
+use std::path::Path;
+use tracing::{debug, warn};
+
+use crate::error::IngestionError;
+use crate::tables::get_models_dir;
+
+/// Build the satellite registry from all model configs in config/models/.
+///
+/// Configs are registered in file name order, so of two satellites sharing
+/// an orbital slot the later id (e.g. goes19 over goes16) is matched by
+/// longitude. Invalid configs are skipped with a warning.
+///
+/// The config directory can be overridden via the CONFIG_DIR environment variable.
+pub fn build_satellite_registry() -> SatelliteRegistry {
+    load_satellites(&get_models_dir())
+}
+
+fn load_satellites(models_dir: &Path) -> SatelliteRegistry {
+    let mut registry = SatelliteRegistry::new();
+
+    let mut paths: Vec<_> = match fs::read_dir(models_dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("yaml"))
+            .collect(),
+        Err(e) => {
+            warn!(path = ?models_dir, error = %e, "Models config directory not readable, no satellites registered");
+            return registry;
+        }
+    };
+    paths.sort();
+
+    for path in paths {
+        match load_satellite(&path) {
+            Ok(Some(satellite)) => registry.register(satellite),
+            Ok(None) => {}
+            Err(e) => warn!(path = ?path, error = %e, "Skipping satellite config"),
+        }
+    }
+
+    debug!(
+        satellites = ?registry.satellites().iter().map(|s| &s.id).collect::<Vec<_>>(),
+        "Built satellite registry from model configs"
+    );
+    registry
+}
+
+/// Satellite defined by a model config, or `None` if the model isn't
+/// geostationary.
+fn load_satellite(path: &Path) -> Result<Option<Satellite>, IngestionError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| IngestionError::InvalidConfig(format!("Cannot read {:?}: {}", path, e)))?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(&contents)
+        .map_err(|e| IngestionError::InvalidConfig(format!("Invalid YAML in {:?}: {}", path, e)))?;
+
+    let grid = &yaml["grid"];
+    if grid["projection"].as_str() != Some("geostationary") {
+        return Ok(None);
+    }
+
+    let id = yaml["model"]["id"]
+        .as_str()
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()))
+        .ok_or_else(|| IngestionError::InvalidConfig(format!("No model id in {:?}", path)))?;
+
+    let params = &grid["projection_params"];
+    let longitude = params["satellite_longitude"].as_f64().ok_or_else(|| {
+        IngestionError::InvalidConfig(format!(
+            "grid.projection_params.satellite_longitude is required in {:?}",
+            path
+        ))
+    })?;
+
+    let mut satellite = Satellite::new(id, longitude);
+    satellite.platform_id = params["platform_id"].as_str().map(str::to_string);
+    if let Some(height) = params["satellite_height"].as_f64() {
+        satellite.perspective_point_height = height;
+    }
+    if let Some(axis) = params["semi_major_axis"].as_f64() {
+        satellite.semi_major_axis = axis;
+    }
+    if let Some(axis) = params["semi_minor_axis"].as_f64() {
+        satellite.semi_minor_axis = axis;
+    }
+    if let Some(sweep) = params["sweep_angle_axis"].as_str() {
+        if sweep != "x" && sweep != "y" {
+            return Err(IngestionError::InvalidConfig(format!(
+                "grid.projection_params.sweep_angle_axis must be \"x\" or \"y\" in {:?}, got {:?}",
+                path, sweep
+            )));
+        }
+        satellite.sweep_angle_axis = sweep.to_string();
+    }
+
+    Ok(Some(satellite))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_config(dir: &Path, name: &str, content: &str) {
+        fs::write(dir.join(format!("{}.yaml", name)), content).unwrap();
+    }
+
+    #[test]
+    fn test_load_satellites() {
+        let dir = tempdir().unwrap();
+        write_config(
+            dir.path(),
+            "himawari9",
+            r#"
+model:
+  id: himawari9
+grid:
+  projection: geostationary
+  projection_params:
+    platform_id: H09
+    satellite_longitude: 140.7
+    sweep_angle_axis: y
+"#,
+        );
+        write_config(
+            dir.path(),
+            "gfs",
+            r#"
+model:
+  id: gfs
+grid:
+  projection: latlon
+"#,
+        );
+        write_config(
+            dir.path(),
+            "broken",
+            r#"
+grid:
+  projection: geostationary
+  projection_params:
+    satellite_height: 35786023.0
+"#,
+        );
+
+        let registry = load_satellites(dir.path());
+        assert_eq!(registry.satellites().len(), 1);
+
+        let satellite = registry.by_platform_id("H09").unwrap();
+        assert_eq!(satellite.id, "himawari9");
+        assert_eq!(satellite.longitude, 140.7);
+        assert_eq!(satellite.sweep_angle_axis, "y");
+        assert_eq!(satellite.perspective_point_height, 35786023.0);
+    }
+
+    #[test]
+    fn test_missing_models_dir() {
+        let dir = tempdir().unwrap();
+        assert!(load_satellites(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_goes_satellites_from_real_config() {
+        let registry = load_satellites(Path::new("../../config/models"));
+
+        let goes18 = registry.get("goes18").expect("goes18 config");
+        assert_eq!(goes18.platform_id.as_deref(), Some("G18"));
+        assert_eq!(goes18.longitude, -137.0);
+
+        // GOES-19 took over the GOES-East slot from GOES-16
+        let goes19 = registry.get("goes19").expect("goes19 config");
+        let goes16 = registry.get("goes16").expect("goes16 config");
+        assert_eq!(goes19.longitude, goes16.longitude);
+        assert_eq!(registry.by_longitude(-75.0).unwrap().id, "goes19");
+        assert_eq!(registry.by_platform_id("G16").unwrap().id, "goes16");
+    }
+}
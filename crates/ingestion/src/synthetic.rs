@@ -0,0 +1,558 @@
+//! Synthetic "analytic weather" model for demos and CI.
+//!
+//! Instead of decoding downloaded files, the ingester can generate a model
+//! run itself: smooth global fields that evolve over the forecast hours and
+//! are written, registered and published exactly like a GFS run. A stack
+//! started from an empty environment then has data to render.
+//!
+//! The model is an ordinary model config with `source.type: synthetic`
+//! (see `config/models/synthetic.yaml`):
+//!
+//! ```yaml
+//! model:
+//!   id: synthetic
+//! source:
+//!   type: synthetic
+//! grid:
+//!   resolution: "1deg"          # global 0-360 grid
+//! schedule:
+//!   cycles: [0, 6, 12, 18]
+//!   forecast_hours: { start: 0, end: 12, step: 1 }
+//! synthetic:                    # optional, defaults shown
+//!   wave_number: 4             # whole number
+//!   wave_speed: 0.5             # degrees longitude per hour
+//!   pressure_amplitude: 1500    # Pa
+//!   wind_scale: 5               # m/s
+//! parameters: [...]             # TMP, UGRD, VGRD and/or PRMSL
+//! ```
+//!
+//! # Fields
+//!
+//! A train of highs and lows drifts east around each latitude circle:
+//!
+//! - **PRMSL**: 1013.25 hPa plus the pressure waves
+//! - **UGRD/VGRD**: mid-latitude westerlies plus flow along the isobars,
+//!   circulating around the lows as in the northern hemisphere
+//! - **TMP**: warm equator, cold poles, a diurnal cycle following the sun and
+//!   warm sectors ahead of the lows
+//!
+//! Fields depend only on the valid time, so overlapping runs agree and a
+//! regenerated run is identical.
+
+use chrono::{DateTime, Duration, DurationRound, Timelike, Utc};
+use serde_yaml::Value;
+use std::f64::consts::PI;
+use std::fs;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use grid_processor::BoundingBox as GpBoundingBox;
+use storage::{Catalog, ObjectStorage};
+
+use crate::error::{IngestionError, Result};
+use crate::grib2::store_grid;
+use crate::tables::{build_filter_for_model, expected_forecast_hours, get_models_dir};
+use crate::IngestionResult;
+
+/// Model id of the bundled synthetic model config.
+pub const SYNTHETIC_MODEL: &str = "synthetic";
+
+/// Parameters the generator can produce.
+pub const SYNTHETIC_PARAMETERS: [&str; 4] = ["TMP", "UGRD", "VGRD", "PRMSL"];
+
+/// Standard sea level pressure (Pa).
+const BASE_PRESSURE: f64 = 101_325.0;
+
+/// Shape of the generated weather, from the `synthetic` config section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticWeather {
+    /// Number of highs (and lows) around each latitude circle
+    pub wave_number: f64,
+    /// Eastward drift of the pressure systems (degrees longitude per hour)
+    pub wave_speed: f64,
+    /// Pressure anomaly of the highs and lows (Pa)
+    pub pressure_amplitude: f64,
+    /// Wind of the flow around the pressure systems (m/s per wave)
+    pub wind_scale: f64,
+}
+
+impl Default for SyntheticWeather {
+    fn default() -> Self {
+        Self {
+            wave_number: 4.0,
+            wave_speed: 0.5,
+            pressure_amplitude: 1500.0,
+            wind_scale: 5.0,
+        }
+    }
+}
+
+impl SyntheticWeather {
+    /// Value of `param` at a point and time, or `None` if the generator
+    /// doesn't produce the parameter.
+    ///
+    /// Longitudes may be -180..180 or 0-360.
+    pub fn value(&self, param: &str, lon: f64, lat: f64, valid_time: DateTime<Utc>) -> Option<f32> {
+        let phi = lat.to_radians();
+        let k = self.wave_number;
+        // Phase of the pressure waves, drifting east with time
+        let hours = valid_time.timestamp() as f64 / 3600.0;
+        let drift = (self.wave_speed * hours).rem_euclid(360.0);
+        let theta = (lon - drift).to_radians();
+
+        // Pressure anomaly p = A sin(2φ) cos(kθ); the wind is proportional
+        // to (-∂p/∂φ, ∂p/∂λ / cos φ), i.e. along the isobars
+        let value = match param {
+            "PRMSL" => {
+                BASE_PRESSURE + self.pressure_amplitude * (2.0 * phi).sin() * (k * theta).cos()
+            }
+            "UGRD" => {
+                let westerlies = 10.0 * (2.0 * phi).sin().powi(2);
+                westerlies - self.wind_scale * (2.0 * phi).cos() * (k * theta).cos()
+            }
+            "VGRD" => -self.wind_scale * k * phi.sin() * (k * theta).sin(),
+            "TMP" => {
+                let solar_hour =
+                    valid_time.hour() as f64 + valid_time.minute() as f64 / 60.0 + lon / 15.0;
+                // Warmest at 15:00 local solar time
+                let diurnal = 5.0 * phi.cos() * (2.0 * PI * (solar_hour - 15.0) / 24.0).cos();
+                let warm_sectors = 3.0 * (2.0 * phi).sin() * (k * theta).sin();
+                288.0 - 40.0 * phi.sin().powi(2) + diurnal + warm_sectors
+            }
+            _ => return None,
+        };
+        Some(value as f32)
+    }
+}
+
+/// Global regular lat/lon grid, laid out like GFS: 0-360 longitudes, rows
+/// from 90°N to 90°S.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyntheticGrid {
+    /// Grid spacing (degrees)
+    pub resolution: f64,
+}
+
+impl SyntheticGrid {
+    /// Grid with `resolution` degree spacing, which must divide 180.
+    pub fn new(resolution: f64) -> Result<Self> {
+        let cells = 180.0 / resolution;
+        if resolution.is_nan() || resolution <= 0.0 || (cells - cells.round()).abs() > 1e-9 {
+            return Err(IngestionError::InvalidConfig(format!(
+                "Synthetic grid resolution must divide 180 degrees, got {}",
+                resolution
+            )));
+        }
+        Ok(Self { resolution })
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        (360.0 / self.resolution).round() as usize
+    }
+
+    /// Number of rows (both poles included).
+    pub fn height(&self) -> usize {
+        (180.0 / self.resolution).round() as usize + 1
+    }
+
+    /// Extent of the grid's points.
+    pub fn bbox(&self) -> GpBoundingBox {
+        GpBoundingBox::new(0.0, -90.0, 360.0 - self.resolution, 90.0)
+    }
+
+    /// Generate `param` at `valid_time`, row-major from the northernmost row.
+    pub fn generate(
+        &self,
+        weather: &SyntheticWeather,
+        param: &str,
+        valid_time: DateTime<Utc>,
+    ) -> Option<Vec<f32>> {
+        let (width, height) = (self.width(), self.height());
+        let mut data = Vec::with_capacity(width * height);
+        for row in 0..height {
+            let lat = 90.0 - row as f64 * self.resolution;
+            for col in 0..width {
+                let lon = col as f64 * self.resolution;
+                data.push(weather.value(param, lon, lat, valid_time)?);
+            }
+        }
+        Some(data)
+    }
+}
+
+/// One parameter/level to generate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticField {
+    pub parameter: String,
+    /// Level description, e.g. "2 m above ground"
+    pub level: String,
+}
+
+/// A synthetic model config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticModel {
+    pub model: String,
+    pub grid: SyntheticGrid,
+    pub weather: SyntheticWeather,
+    /// Cycle hours (UTC)
+    pub cycles: Vec<u32>,
+    pub forecast_hours: Vec<u32>,
+    pub fields: Vec<SyntheticField>,
+}
+
+impl SyntheticModel {
+    /// Load the config of `model` from config/models/.
+    pub fn load(model: &str) -> Result<Self> {
+        let path = get_models_dir().join(format!("{}.yaml", model));
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| IngestionError::InvalidConfig(format!("Cannot read {:?}: {}", path, e)))?;
+        let yaml: Value = serde_yaml::from_str(&contents).map_err(|e| {
+            IngestionError::InvalidConfig(format!("Invalid YAML in {:?}: {}", path, e))
+        })?;
+        Self::from_yaml(model, &yaml)
+    }
+
+    /// Build from a parsed model config.
+    pub fn from_yaml(model: &str, yaml: &Value) -> Result<Self> {
+        if yaml["source"]["type"].as_str() != Some("synthetic") {
+            return Err(IngestionError::InvalidConfig(format!(
+                "Model {} is not synthetic (source.type must be \"synthetic\")",
+                model
+            )));
+        }
+
+        let resolution = yaml["grid"]["resolution"]
+            .as_str()
+            .map(|r| r.trim().trim_end_matches("deg").parse::<f64>())
+            .unwrap_or(Ok(1.0))
+            .map_err(|_| {
+                IngestionError::InvalidConfig(format!(
+                    "grid.resolution of {} must look like \"1deg\"",
+                    model
+                ))
+            })?;
+
+        let defaults = SyntheticWeather::default();
+        let section = &yaml["synthetic"];
+        let number = |key: &str, default: f64| section[key].as_f64().unwrap_or(default);
+        let weather = SyntheticWeather {
+            wave_number: number("wave_number", defaults.wave_number),
+            wave_speed: number("wave_speed", defaults.wave_speed),
+            pressure_amplitude: number("pressure_amplitude", defaults.pressure_amplitude),
+            wind_scale: number("wind_scale", defaults.wind_scale),
+        };
+        // Whole waves keep the fields continuous across 0°/360°
+        if weather.wave_number < 1.0 || weather.wave_number.fract() != 0.0 {
+            return Err(IngestionError::InvalidConfig(format!(
+                "synthetic.wave_number of {} must be a whole number of at least 1, got {}",
+                model, weather.wave_number
+            )));
+        }
+
+        let schedule = &yaml["schedule"];
+        let cycles: Vec<u32> = match schedule["cycles"].as_sequence() {
+            Some(cycles) => cycles
+                .iter()
+                .filter_map(|c| c.as_u64())
+                .map(|c| c as u32)
+                .collect(),
+            None => vec![0],
+        };
+        let range = &schedule["forecast_hours"];
+        let start = range["start"].as_u64().unwrap_or(0) as u32;
+        let end = range["end"].as_u64().unwrap_or(start as u64) as u32;
+        let step = range["step"].as_u64().unwrap_or(1).max(1) as usize;
+        let forecast_hours: Vec<u32> = (start..=end).step_by(step).collect();
+
+        let mut fields = Vec::new();
+        for param in yaml["parameters"].as_sequence().into_iter().flatten() {
+            let Some(name) = param["name"].as_str() else {
+                continue;
+            };
+            if !SYNTHETIC_PARAMETERS.contains(&name) {
+                warn!(model = %model, parameter = %name, "Synthetic model can't generate parameter, skipping");
+                continue;
+            }
+            for level in param["levels"].as_sequence().into_iter().flatten() {
+                if let Some(display) = level["display"].as_str() {
+                    fields.push(SyntheticField {
+                        parameter: name.to_string(),
+                        level: display.to_string(),
+                    });
+                }
+            }
+        }
+        if fields.is_empty() {
+            return Err(IngestionError::InvalidConfig(format!(
+                "Synthetic model {} has no parameters to generate (supported: {:?})",
+                model, SYNTHETIC_PARAMETERS
+            )));
+        }
+
+        Ok(Self {
+            model: model.to_string(),
+            grid: SyntheticGrid::new(resolution)?,
+            weather,
+            cycles,
+            forecast_hours,
+            fields,
+        })
+    }
+
+    /// Most recent cycle at or before `now`.
+    pub fn latest_cycle(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let hour = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+        (0..=24)
+            .map(|back| hour - Duration::hours(back))
+            .find(|t| self.cycles.is_empty() || self.cycles.contains(&t.hour()))
+            .unwrap_or(hour)
+    }
+}
+
+/// Generate a run of a synthetic model and store it like a downloaded one:
+/// every field and forecast hour is written to Zarr, registered, and the
+/// run published once complete.
+pub(crate) async fn ingest_synthetic(
+    storage: &Arc<ObjectStorage>,
+    catalog: &Catalog,
+    model: &str,
+    reference_time: DateTime<Utc>,
+) -> Result<IngestionResult> {
+    let config = SyntheticModel::load(model)?;
+    let filter = build_filter_for_model(model)?;
+    let grid = config.grid;
+    let bbox = grid.bbox();
+
+    info!(
+        model = %model,
+        reference_time = %reference_time,
+        fields = config.fields.len(),
+        forecast_hours = config.forecast_hours.len(),
+        width = grid.width(),
+        height = grid.height(),
+        "Generating synthetic model run"
+    );
+
+    let mut datasets_registered = 0usize;
+    let mut bytes_written = 0u64;
+    let mut storage_paths = Vec::new();
+    let mut parameters: Vec<String> = Vec::new();
+
+    for &forecast_hour in &config.forecast_hours {
+        let valid_time = reference_time + Duration::hours(forecast_hour as i64);
+        for field in &config.fields {
+            let Some(data) = grid.generate(&config.weather, &field.parameter, valid_time) else {
+                continue;
+            };
+
+            let stored = store_grid(
+                storage,
+                catalog,
+                &filter,
+                model,
+                &field.parameter,
+                &field.level,
+                reference_time,
+                forecast_hour,
+                None,
+                None,
+                &data,
+                grid.width(),
+                grid.height(),
+                &bbox,
+            )
+            .await;
+
+            if let Some(stored) = stored {
+                storage_paths.push(stored.storage_path);
+                if let Some(size) = stored.registered_size {
+                    datasets_registered += 1;
+                    bytes_written += size;
+                    if !parameters.contains(&field.parameter) {
+                        parameters.push(field.parameter.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(expected) = expected_forecast_hours(model) {
+        if datasets_registered > 0 {
+            if let Err(e) = catalog
+                .update_run_publication(model, reference_time, expected)
+                .await
+            {
+                warn!(model = %model, error = %e, "Failed to update run publication");
+            }
+        }
+    }
+
+    info!(
+        model = %model,
+        datasets = datasets_registered,
+        parameters = ?parameters,
+        "Synthetic model run complete"
+    );
+
+    Ok(IngestionResult {
+        datasets_registered,
+        model: model.to_string(),
+        reference_time,
+        parameters,
+        bytes_written,
+        storage_paths,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config() -> Value {
+        serde_yaml::from_str(
+            r#"
+model:
+  id: synthetic
+source:
+  type: synthetic
+grid:
+  resolution: "2deg"
+schedule:
+  cycles: [0, 12]
+  forecast_hours: { start: 0, end: 6, step: 3 }
+synthetic:
+  wave_speed: 1.0
+parameters:
+  - name: TMP
+    levels:
+      - display: "2 m above ground"
+  - name: PRMSL
+    levels:
+      - display: "mean sea level"
+  - name: REFC
+    levels:
+      - display: "entire atmosphere"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_from_yaml() {
+        let model = SyntheticModel::from_yaml("synthetic", &config()).unwrap();
+
+        assert_eq!(model.grid.resolution, 2.0);
+        assert_eq!(model.weather.wave_speed, 1.0);
+        assert_eq!(model.weather.wave_number, 4.0);
+        assert_eq!(model.forecast_hours, vec![0, 3, 6]);
+        // REFC can't be generated
+        let params: Vec<&str> = model.fields.iter().map(|f| f.parameter.as_str()).collect();
+        assert_eq!(params, vec!["TMP", "PRMSL"]);
+        assert_eq!(model.fields[0].level, "2 m above ground");
+
+        let mut gfs = config();
+        gfs["source"]["type"] = Value::from("aws_s3");
+        assert!(SyntheticModel::from_yaml("gfs", &gfs).is_err());
+    }
+
+    #[test]
+    fn test_latest_cycle() {
+        let model = SyntheticModel::from_yaml("synthetic", &config()).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 11, 42, 0).unwrap();
+        assert_eq!(
+            model.latest_cycle(now),
+            Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()
+        );
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        assert_eq!(model.latest_cycle(now), now);
+    }
+
+    #[test]
+    fn test_grid_layout() {
+        let grid = SyntheticGrid::new(1.0).unwrap();
+        assert_eq!((grid.width(), grid.height()), (360, 181));
+        assert_eq!(grid.bbox(), GpBoundingBox::new(0.0, -90.0, 359.0, 90.0));
+        assert!(SyntheticGrid::new(0.7).is_err());
+        assert!(SyntheticGrid::new(0.0).is_err());
+
+        // First row is the north pole: coldest, and the same all around
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let data = grid
+            .generate(&SyntheticWeather::default(), "TMP", time)
+            .unwrap();
+        assert_eq!(data.len(), 360 * 181);
+        let equator = data[90 * 360];
+        assert!(data[0] < equator - 30.0);
+        assert!((data[0] - data[180]).abs() < 1e-3);
+        assert!(grid
+            .generate(&SyntheticWeather::default(), "REFC", time)
+            .is_none());
+    }
+
+    #[test]
+    fn test_fields_plausible_and_smooth() {
+        let weather = SyntheticWeather::default();
+        let grid = SyntheticGrid::new(2.0).unwrap();
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 6, 0, 0).unwrap();
+        let ranges = [
+            ("TMP", 230.0, 310.0, 2.0),
+            ("PRMSL", 99_000.0, 104_000.0, 300.0),
+            ("UGRD", -30.0, 30.0, 3.0),
+            ("VGRD", -30.0, 30.0, 3.0),
+        ];
+
+        for (param, min, max, max_step) in ranges {
+            let data = grid.generate(&weather, param, time).unwrap();
+            assert!(data.iter().all(|v| (min..=max).contains(v)), "{}", param);
+
+            // Neighbours differ by little, including across 0°/360°
+            let width = grid.width();
+            for row in data.chunks(width) {
+                for col in 0..width {
+                    let next = row[(col + 1) % width];
+                    assert!((row[col] - next).abs() <= max_step, "{}", param);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fields_evolve() {
+        let weather = SyntheticWeather::default();
+        let t0 = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let t6 = t0 + Duration::hours(6);
+
+        // Pressure systems move east by wave_speed degrees an hour
+        let p0 = weather.value("PRMSL", 100.0, 45.0, t0).unwrap();
+        let p6 = weather.value("PRMSL", 103.0, 45.0, t6).unwrap();
+        assert!((p0 - p6).abs() < 1e-3);
+        assert!((p0 - weather.value("PRMSL", 100.0, 45.0, t6).unwrap()).abs() > 1.0);
+
+        // Independent of which run produced it, and of the longitude convention
+        assert_eq!(
+            weather.value("TMP", -80.0, 30.0, t6),
+            weather.value("TMP", 280.0, 30.0, t6)
+        );
+    }
+
+    #[test]
+    fn test_wind_follows_isobars() {
+        let weather = SyntheticWeather::default();
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let (lon, lat) = (37.0, 40.0);
+        let d = 0.1;
+
+        let p = |lon: f64, lat: f64| weather.value("PRMSL", lon, lat, time).unwrap() as f64;
+        let dp_east = (p(lon + d, lat) - p(lon - d, lat)) / (2.0 * d * lat.to_radians().cos());
+        let dp_north = (p(lon, lat + d) - p(lon, lat - d)) / (2.0 * d);
+
+        // Perturbation wind (without the westerlies) is perpendicular to ∇p
+        let westerlies = 10.0 * (2.0 * lat.to_radians()).sin().powi(2);
+        let u = weather.value("UGRD", lon, lat, time).unwrap() as f64 - westerlies;
+        let v = weather.value("VGRD", lon, lat, time).unwrap() as f64;
+        let cos = (u * dp_east + v * dp_north) / (u.hypot(v) * dp_east.hypot(dp_north));
+        assert!(cos.abs() < 1e-2, "cos = {}", cos);
+    }
+}
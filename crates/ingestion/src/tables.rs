@@ -5,15 +5,23 @@
 //!
 //! Also builds `IngestionFilter` to determine which parameter/level
 //! combinations should be ingested for each model, and provides valid_range
-//! for converting sentinel values to NaN during ingestion.
+//! for converting sentinel values to NaN during ingestion, plus per-parameter
+//! pyramid and chunk layout settings and the model's Zarr compression.
 
 use crate::error::IngestionError;
 use grib2_parser::{Grib2Tables, LevelDescription};
+use grid_processor::{
+    AccessPattern, ChunkLayout, DownsampleMethod, GridProcessorConfig, PyramidConfig,
+    ZarrCompression,
+};
+use regex::Regex;
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use storage::ParameterMetadata;
 use tracing::{debug, error, warn};
 
 // ============================================================================
@@ -43,11 +51,95 @@ impl ValidRange {
 }
 
 /// Filter criteria for a single parameter at a specific level type.
-#[derive(Debug, Clone)]
+///
+/// A level is accepted if it matches any of `allowed_values`, `ranges` or
+/// `patterns`. If none of them are set, all values for this level type are
+/// accepted.
+#[derive(Debug, Clone, Default)]
 pub struct LevelFilter {
     /// Specific values allowed (e.g., `[2]` for "2m only", `[1000, 850, 500]` for pressure levels).
-    /// If None, accept all values for this level type.
     pub allowed_values: Option<HashSet<u32>>,
+    /// Inclusive value ranges (e.g., `100..=1000` for all mb levels from 1000 to 100).
+    pub ranges: Vec<RangeInclusive<u32>>,
+    /// Regexes matched against the level description (e.g., `^\d+ mb$`).
+    pub patterns: Vec<Regex>,
+}
+
+impl LevelFilter {
+    /// Returns true if no criteria are set, i.e. every value is accepted.
+    pub fn accepts_all(&self) -> bool {
+        self.allowed_values.is_none() && self.ranges.is_empty() && self.patterns.is_empty()
+    }
+
+    /// Check a level value and its description against the filter.
+    pub fn matches(&self, level_value: u32, level_description: &str) -> bool {
+        self.accepts_all()
+            || self
+                .allowed_values
+                .as_ref()
+                .is_some_and(|values| values.contains(&level_value))
+            || self.ranges.iter().any(|range| range.contains(&level_value))
+            || self
+                .patterns
+                .iter()
+                .any(|pattern| pattern.is_match(level_description))
+    }
+
+    /// Merge another filter for the same parameter/level type.
+    ///
+    /// The result accepts any level either filter accepts.
+    fn merge(&mut self, other: LevelFilter) {
+        if self.accepts_all() || other.accepts_all() {
+            *self = LevelFilter::default();
+            return;
+        }
+
+        if let Some(values) = other.allowed_values {
+            self.allowed_values
+                .get_or_insert_with(HashSet::new)
+                .extend(values);
+        }
+        self.ranges.extend(other.ranges);
+        self.patterns.extend(other.patterns);
+    }
+}
+
+/// Per-parameter pyramid overrides from model configuration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PyramidSettings {
+    /// Maximum number of pyramid levels including native (`pyramid_levels`).
+    pub max_levels: Option<usize>,
+    /// Downsampling method (`downsample`).
+    pub downsample: Option<DownsampleMethod>,
+    /// Chunk layout of every level (`chunks` and `access`).
+    pub chunk_layout: Option<ChunkLayout>,
+    /// Compression codec (model-wide `zarr.compression`).
+    pub compression: Option<ZarrCompression>,
+    /// Compression level (model-wide `zarr.compression_level`).
+    pub compression_level: Option<u8>,
+}
+
+impl PyramidSettings {
+    /// Apply these overrides on top of a base pyramid configuration.
+    pub fn apply(&self, base: &PyramidConfig) -> PyramidConfig {
+        PyramidConfig {
+            max_levels: self.max_levels.or(base.max_levels),
+            ..base.clone()
+        }
+    }
+
+    /// Apply the chunk layout and compression overrides on top of a base
+    /// writer configuration.
+    pub fn apply_writer(&self, base: &GridProcessorConfig) -> GridProcessorConfig {
+        GridProcessorConfig {
+            chunk_layout: self.chunk_layout.unwrap_or(base.chunk_layout),
+            zarr_compression: self.compression.unwrap_or(base.zarr_compression),
+            zarr_compression_level: self
+                .compression_level
+                .unwrap_or(base.zarr_compression_level),
+            ..base.clone()
+        }
+    }
 }
 
 /// Ingestion filter built from model configuration.
@@ -64,6 +156,14 @@ pub struct IngestionFilter {
     valid_ranges: HashMap<String, ValidRange>,
     /// Map: parameter_name → units string (e.g., "K", "%", "m/s").
     units: HashMap<String, String>,
+    /// Map: parameter_name → description (e.g., "Temperature").
+    descriptions: HashMap<String, String>,
+    /// Map: parameter_name → default style (e.g., "temperature").
+    styles: HashMap<String, String>,
+    /// Map: parameter_name → pyramid overrides.
+    pyramids: HashMap<String, PyramidSettings>,
+    /// Model-wide compression overrides (only the compression fields are set).
+    compression: PyramidSettings,
 }
 
 impl IngestionFilter {
@@ -76,15 +176,26 @@ impl IngestionFilter {
     ///
     /// Returns `true` if:
     /// - The parameter name and level type are in the filter, AND
-    /// - Either no specific values are required, OR the level_value is in the allowed set
+    /// - Either no criteria are set, OR the level_value is in the allowed set or a range
     ///
     /// Returns `false` if the parameter/level combination is not defined in the config.
+    /// Level patterns never match here; use [`Self::should_ingest_level`] when the
+    /// level description is available.
     pub fn should_ingest(&self, param: &str, level_type: u8, level_value: u32) -> bool {
+        self.should_ingest_level(param, level_type, level_value, "")
+    }
+
+    /// Check if a parameter/level combination should be ingested, matching
+    /// level patterns against `level_description` (e.g., "500 mb").
+    pub fn should_ingest_level(
+        &self,
+        param: &str,
+        level_type: u8,
+        level_value: u32,
+        level_description: &str,
+    ) -> bool {
         match self.filters.get(&(param.to_string(), level_type)) {
-            Some(filter) => match &filter.allowed_values {
-                Some(values) => values.contains(&level_value),
-                None => true, // No specific values = accept all
-            },
+            Some(filter) => filter.matches(level_value, level_description),
             None => false, // Not in filter = don't ingest
         }
     }
@@ -111,6 +222,74 @@ impl IngestionFilter {
             .unwrap_or("unknown")
     }
 
+    /// Get the description of a parameter, if configured.
+    ///
+    /// Written to Zarr arrays as the CF `long_name`.
+    pub fn get_description(&self, param: &str) -> Option<&str> {
+        self.descriptions.get(param).map(|s| s.as_str())
+    }
+
+    /// Get the default style of a parameter, if configured.
+    pub fn get_style(&self, param: &str) -> Option<&str> {
+        self.styles.get(param).map(|s| s.as_str())
+    }
+
+    /// Build the catalog metadata record for a parameter.
+    ///
+    /// Description and units from the model config take precedence; the
+    /// GRIB2 parameter tables fill in whatever the config leaves out.
+    pub fn parameter_metadata(
+        &self,
+        model: &str,
+        param: &str,
+        tables: &Grib2Tables,
+    ) -> ParameterMetadata {
+        let info = tables.get_parameter_info(param);
+        let long_name = self
+            .get_description(param)
+            .or_else(|| info.map(|i| i.description.as_str()))
+            .filter(|d| !d.is_empty())
+            .unwrap_or(param);
+        let units = self
+            .units
+            .get(param)
+            .map(|u| u.as_str())
+            .or_else(|| info.map(|i| i.units.as_str()))
+            .filter(|u| !u.is_empty())
+            .unwrap_or("unknown");
+        let range = self.get_valid_range(param);
+
+        ParameterMetadata {
+            model: model.to_string(),
+            parameter: param.to_string(),
+            long_name: long_name.to_string(),
+            units: units.to_string(),
+            default_style: self.get_style(param).map(str::to_string),
+            valid_min: range.map(|r| r.min as f64),
+            valid_max: range.map(|r| r.max as f64),
+        }
+    }
+
+    /// Get the pyramid overrides for a parameter (empty if none configured),
+    /// including the model's compression settings.
+    pub fn get_pyramid_settings(&self, param: &str) -> PyramidSettings {
+        PyramidSettings {
+            compression: self.compression.compression,
+            compression_level: self.compression.compression_level,
+            ..self.pyramids.get(param).copied().unwrap_or_default()
+        }
+    }
+
+    /// Get the downsampling method for a parameter.
+    ///
+    /// Uses the configured `downsample` method, falling back to
+    /// [`DownsampleMethod::for_parameter`].
+    pub fn get_downsample_method(&self, param: &str) -> DownsampleMethod {
+        self.get_pyramid_settings(param)
+            .downsample
+            .unwrap_or_else(|| DownsampleMethod::for_parameter(param))
+    }
+
     /// Returns true if this filter has any parameters defined.
     pub fn is_empty(&self) -> bool {
         self.filters.is_empty()
@@ -123,31 +302,13 @@ impl IngestionFilter {
 
     /// Insert or merge a filter entry for a parameter/level combination.
     ///
-    /// If an entry already exists for this (param, level_code) pair, the allowed_values
+    /// If an entry already exists for this (param, level_code) pair, the criteria
     /// are merged together. This allows multiple level definitions with the same
     /// level_code but different values (e.g., multiple isobaric levels).
     fn insert(&mut self, param: String, level_code: u8, filter: LevelFilter) {
         let key = (param, level_code);
         if let Some(existing) = self.filters.get_mut(&key) {
-            // Merge allowed_values
-            match (&mut existing.allowed_values, filter.allowed_values) {
-                (Some(existing_values), Some(new_values)) => {
-                    // Merge the new values into existing set
-                    existing_values.extend(new_values);
-                }
-                (None, Some(new_values)) => {
-                    // Existing accepts all, new has specific values - stay permissive
-                    // Actually, this shouldn't happen in practice
-                    existing.allowed_values = Some(new_values);
-                }
-                (Some(_), None) => {
-                    // Existing has specific values, new accepts all - stay permissive
-                    existing.allowed_values = None;
-                }
-                (None, None) => {
-                    // Both accept all - no change needed
-                }
-            }
+            existing.merge(filter);
         } else {
             // No existing entry, just insert
             self.filters.insert(key, filter);
@@ -163,6 +324,21 @@ impl IngestionFilter {
     fn set_units(&mut self, param: String, units: String) {
         self.units.insert(param, units);
     }
+
+    /// Set the description for a parameter.
+    fn set_description(&mut self, param: String, description: String) {
+        self.descriptions.insert(param, description);
+    }
+
+    /// Set the default style for a parameter.
+    fn set_style(&mut self, param: String, style: String) {
+        self.styles.insert(param, style);
+    }
+
+    /// Set the pyramid overrides for a parameter.
+    fn set_pyramid_settings(&mut self, param: String, settings: PyramidSettings) {
+        self.pyramids.insert(param, settings);
+    }
 }
 
 // ============================================================================
@@ -241,6 +417,10 @@ pub fn build_filter_for_model(model: &str) -> Result<Arc<IngestionFilter>, Inges
 }
 
 /// Load ingestion filter criteria from a model config file.
+///
+/// Validates the parameter entries while loading. All problems are reported
+/// together, each prefixed with its location in the file (e.g.
+/// `parameters[3] (TMP).levels[0].range`).
 fn load_filter_from_config(
     path: &Path,
     filter: &mut IngestionFilter,
@@ -258,29 +438,35 @@ fn load_filter_from_config(
             IngestionError::InvalidConfig(format!("Missing 'parameters' section in {:?}", path))
         })?;
 
-    // Track parameters missing valid_range for error reporting
-    let mut missing_valid_range: Vec<String> = Vec::new();
+    let mut errors: Vec<String> = Vec::new();
+
+    if let Some(zarr) = yaml.get("zarr") {
+        filter.compression = parse_compression(zarr, &mut errors);
+    }
 
-    for param in parameters {
+    for (idx, param) in parameters.iter().enumerate() {
         let name = match param.get("name").and_then(|n| n.as_str()) {
             Some(n) => n.to_string(),
             None => continue, // Skip entries without a name
         };
+        let at = format!("parameters[{}] ({})", idx, name);
 
         // Parse valid_range: [min, max] - required for sentinel value conversion
-        if let Some(valid_range) = param.get("valid_range").and_then(|v| v.as_sequence()) {
-            if valid_range.len() >= 2 {
-                let min = valid_range[0].as_f64().map(|v| v as f32);
-                let max = valid_range[1].as_f64().map(|v| v as f32);
-                if let (Some(min), Some(max)) = (min, max) {
-                    filter.set_valid_range(name.clone(), ValidRange::new(min, max));
+        match param.get("valid_range") {
+            Some(value) => match parse_pair(value, |v| v.as_f64()) {
+                Some((min, max)) if min <= max => {
+                    filter.set_valid_range(name.clone(), ValidRange::new(min as f32, max as f32));
                 }
-            }
-        } else {
-            // Track missing valid_range (only add once per param name)
-            if !missing_valid_range.contains(&name) {
-                missing_valid_range.push(name.clone());
-            }
+                _ => errors.push(format!(
+                    "{}.valid_range: expected [min, max] numbers with min <= max",
+                    at
+                )),
+            },
+            None => errors.push(format!(
+                "{}.valid_range: missing. Add valid_range: [min, max] to each parameter \
+                 for sentinel value handling.",
+                at
+            )),
         }
 
         // Parse units string (e.g., "K", "%", "m/s")
@@ -288,54 +474,287 @@ fn load_filter_from_config(
             filter.set_units(name.clone(), units.to_string());
         }
 
+        if let Some(description) = param.get("description").and_then(|d| d.as_str()) {
+            filter.set_description(name.clone(), description.to_string());
+        }
+
+        if let Some(style) = param.get("style").and_then(|s| s.as_str()) {
+            filter.set_style(name.clone(), style.to_string());
+        }
+
+        if let Some(settings) = parse_pyramid_settings(param, &at, &mut errors) {
+            let existing = filter.pyramids.get(&name).copied().unwrap_or_default();
+            if existing != PyramidSettings::default() && existing != settings {
+                errors.push(format!(
+                    "{}: pyramid settings {:?} conflict with an earlier {} entry ({:?})",
+                    at, settings, name, existing
+                ));
+            } else {
+                filter.set_pyramid_settings(name.clone(), settings);
+            }
+        }
+
         let levels = match param.get("levels").and_then(|l| l.as_sequence()) {
             Some(l) => l,
             None => continue, // Skip parameters without levels defined
         };
 
-        for level in levels {
-            let level_code = match level.get("level_code").and_then(|v| v.as_u64()) {
-                Some(code) => code as u8,
+        for (level_idx, level) in levels.iter().enumerate() {
+            let level_at = format!("{}.levels[{}]", at, level_idx);
+            let level_code = match level.get("level_code") {
+                Some(code) => match code.as_u64().and_then(|c| u8::try_from(c).ok()) {
+                    Some(code) => code,
+                    None => {
+                        errors.push(format!("{}.level_code: expected 0-255", level_at));
+                        continue;
+                    }
+                },
                 None => continue, // Skip levels without level_code
             };
 
-            // Check for single value or array of values
-            let allowed_values = if let Some(value) = level.get("value").and_then(|v| v.as_u64()) {
-                // Single value: value: 2
-                Some(HashSet::from([value as u32]))
-            } else if let Some(values) = level.get("values").and_then(|v| v.as_sequence()) {
-                // Array of values: values: [1000, 850, 500]
-                let set: HashSet<u32> = values
-                    .iter()
-                    .filter_map(|v| v.as_u64().map(|n| n as u32))
-                    .collect();
-                if set.is_empty() {
-                    None
-                } else {
-                    Some(set)
-                }
-            } else {
-                // No value specified = accept all values for this level type
-                None
-            };
-
-            filter.insert(name.clone(), level_code, LevelFilter { allowed_values });
+            if let Some(level_filter) = parse_level_filter(level, &level_at, &mut errors) {
+                filter.insert(name.clone(), level_code, level_filter);
+            }
         }
     }
 
-    // Fail if any parameters are missing valid_range
-    if !missing_valid_range.is_empty() {
+    if !errors.is_empty() {
         return Err(IngestionError::InvalidConfig(format!(
-            "Parameters missing required 'valid_range' in {:?}: {}. \
-             Add valid_range: [min, max] to each parameter for sentinel value handling.",
+            "Invalid model config {:?}:\n  {}",
             path,
-            missing_valid_range.join(", ")
+            errors.join("\n  ")
         )));
     }
 
     Ok(())
 }
 
+/// Parse a two-element `[a, b]` sequence.
+fn parse_pair<T>(
+    value: &serde_yaml::Value,
+    get: impl Fn(&serde_yaml::Value) -> Option<T>,
+) -> Option<(T, T)> {
+    match value.as_sequence().map(|s| s.as_slice()) {
+        Some([a, b]) => Some((get(a)?, get(b)?)),
+        _ => None,
+    }
+}
+
+/// Parse the `value`, `values`, `range` and `pattern` criteria of a level entry.
+///
+/// Returns None (after recording errors) if any criterion is malformed.
+fn parse_level_filter(
+    level: &serde_yaml::Value,
+    at: &str,
+    errors: &mut Vec<String>,
+) -> Option<LevelFilter> {
+    let errors_before = errors.len();
+    let mut filter = LevelFilter::default();
+
+    // Single value: value: 2
+    if let Some(value) = level.get("value") {
+        match value.as_u64().and_then(|v| u32::try_from(v).ok()) {
+            Some(v) => filter.allowed_values = Some(HashSet::from([v])),
+            None => errors.push(format!("{}.value: expected a non-negative integer", at)),
+        }
+    }
+
+    // Array of values: values: [1000, 850, 500]
+    if let Some(values) = level.get("values") {
+        let parsed: Option<HashSet<u32>> = values.as_sequence().and_then(|seq| {
+            seq.iter()
+                .map(|v| v.as_u64().and_then(|n| u32::try_from(n).ok()))
+                .collect()
+        });
+        match parsed {
+            // An empty list accepts all values, like omitting it
+            Some(set) if !set.is_empty() => {
+                filter
+                    .allowed_values
+                    .get_or_insert_with(HashSet::new)
+                    .extend(set);
+            }
+            Some(_) => {}
+            None => errors.push(format!(
+                "{}.values: expected a list of non-negative integers",
+                at
+            )),
+        }
+    }
+
+    // Inclusive range in either order: range: [1000, 100]
+    if let Some(range) = level.get("range") {
+        match parse_pair(range, |v| v.as_u64().and_then(|n| u32::try_from(n).ok())) {
+            Some((a, b)) => filter.ranges.push(a.min(b)..=a.max(b)),
+            None => errors.push(format!(
+                "{}.range: expected [min, max] non-negative integers",
+                at
+            )),
+        }
+    }
+
+    // Regex(es) on the level description: pattern: "^\\d+ mb$"
+    if let Some(pattern) = level.get("pattern") {
+        let patterns: Vec<&str> = match pattern {
+            serde_yaml::Value::String(s) => vec![s.as_str()],
+            serde_yaml::Value::Sequence(seq) => seq.iter().filter_map(|p| p.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if patterns.is_empty() {
+            errors.push(format!(
+                "{}.pattern: expected a regex string or list of strings",
+                at
+            ));
+        }
+        for pattern in patterns {
+            match Regex::new(pattern) {
+                Ok(re) => filter.patterns.push(re),
+                Err(e) => errors.push(format!(
+                    "{}.pattern: invalid regex '{}': {}",
+                    at, pattern, e
+                )),
+            }
+        }
+    }
+
+    (errors.len() == errors_before).then_some(filter)
+}
+
+/// Parse the per-parameter `downsample`, `pyramid_levels`, `chunks` and
+/// `access` settings.
+///
+/// Returns None if none is set or any is malformed.
+fn parse_pyramid_settings(
+    param: &serde_yaml::Value,
+    at: &str,
+    errors: &mut Vec<String>,
+) -> Option<PyramidSettings> {
+    let errors_before = errors.len();
+    let mut settings = PyramidSettings::default();
+
+    if let Some(method) = param.get("downsample") {
+        match serde_yaml::from_value::<DownsampleMethod>(method.clone()) {
+            Ok(method) => settings.downsample = Some(method),
+            Err(_) => errors.push(format!(
+                "{}.downsample: unknown method {:?} (expected mean, max or nearest)",
+                at,
+                method.as_str().unwrap_or_default()
+            )),
+        }
+    }
+
+    if let Some(levels) = param.get("pyramid_levels") {
+        match levels.as_u64().filter(|&n| n >= 1) {
+            Some(n) => settings.max_levels = Some(n as usize),
+            None => errors.push(format!(
+                "{}.pyramid_levels: expected an integer >= 1 (1 = native resolution only)",
+                at
+            )),
+        }
+    }
+
+    settings.chunk_layout = parse_chunk_layout(param, at, errors);
+
+    (errors.len() == errors_before && settings != PyramidSettings::default()).then_some(settings)
+}
+
+/// Parse `chunks` (`square`, `auto` or `[width, height]`) and `access`
+/// (`tiles` or `time_series`, which implies `chunks: auto`).
+fn parse_chunk_layout(
+    param: &serde_yaml::Value,
+    at: &str,
+    errors: &mut Vec<String>,
+) -> Option<ChunkLayout> {
+    let access = match param.get("access") {
+        Some(value) => match value.as_str().and_then(AccessPattern::parse) {
+            Some(access) => Some(access),
+            None => {
+                errors.push(format!(
+                    "{}.access: unknown access pattern {:?} (expected tiles or time_series)",
+                    at,
+                    value.as_str().unwrap_or_default()
+                ));
+                return None;
+            }
+        },
+        None => None,
+    };
+
+    let layout = match param.get("chunks") {
+        None => return access.map(ChunkLayout::Auto),
+        Some(value) => match value.as_str() {
+            Some("auto") => ChunkLayout::Auto(access.unwrap_or_default()),
+            Some("square") => ChunkLayout::Square,
+            _ => match parse_pair(value, |v| v.as_u64().filter(|&n| n >= 1)) {
+                Some((width, height)) => ChunkLayout::Fixed {
+                    width: width as usize,
+                    height: height as usize,
+                },
+                None => {
+                    errors.push(format!(
+                        "{}.chunks: expected square, auto or [width, height] integers >= 1",
+                        at
+                    ));
+                    return None;
+                }
+            },
+        },
+    };
+
+    if access.is_some() && !matches!(layout, ChunkLayout::Auto(_)) {
+        errors.push(format!(
+            "{}.access: only applies to chunks: auto (chunks is {})",
+            at,
+            layout.as_str()
+        ));
+        return None;
+    }
+    Some(layout)
+}
+
+/// Parse the model-wide `zarr` section (`compression` and
+/// `compression_level`), checking the level against the codec's range.
+fn parse_compression(zarr: &serde_yaml::Value, errors: &mut Vec<String>) -> PyramidSettings {
+    let mut settings = PyramidSettings::default();
+
+    if let Some(value) = zarr.get("compression") {
+        match value.as_str().and_then(ZarrCompression::parse) {
+            Some(compression) => settings.compression = Some(compression),
+            None => {
+                errors.push(format!(
+                    "zarr.compression: unknown codec {:?} \
+                     (expected none, zstd, blosc_lz4 or blosc_zstd)",
+                    value.as_str().unwrap_or_default()
+                ));
+                return PyramidSettings::default();
+            }
+        }
+    }
+
+    if let Some(value) = zarr.get("compression_level") {
+        let compression = settings.compression.unwrap_or_default();
+        let levels = compression.levels();
+        match value
+            .as_u64()
+            .and_then(|n| u8::try_from(n).ok())
+            .filter(|n| levels.contains(n))
+        {
+            Some(level) => settings.compression_level = Some(level),
+            None => {
+                errors.push(format!(
+                    "zarr.compression_level: expected an integer {}-{} for {}",
+                    levels.start(),
+                    levels.end(),
+                    compression
+                ));
+                return PyramidSettings::default();
+            }
+        }
+    }
+
+    settings
+}
+
 // ============================================================================
 // GRIB2 Tables Builder
 // ============================================================================
@@ -343,7 +762,7 @@ fn load_filter_from_config(
 /// Get the models config directory path.
 ///
 /// Checks CONFIG_DIR environment variable first, falls back to "config/models".
-fn get_models_dir() -> PathBuf {
+pub(crate) fn get_models_dir() -> PathBuf {
     if let Ok(config_dir) = env::var("CONFIG_DIR") {
         PathBuf::from(config_dir).join("models")
     } else {
@@ -351,17 +770,75 @@ fn get_models_dir() -> PathBuf {
     }
 }
 
+/// Get the directory of external GRIB2 parameter tables.
+///
+/// GRIB2_TABLES_DIR if set, else "grib2_tables" under CONFIG_DIR, else
+/// "config/grib2_tables".
+pub(crate) fn get_parameter_tables_dir() -> PathBuf {
+    if let Ok(dir) = env::var("GRIB2_TABLES_DIR") {
+        PathBuf::from(dir)
+    } else if let Ok(config_dir) = env::var("CONFIG_DIR") {
+        PathBuf::from(config_dir).join("grib2_tables")
+    } else {
+        PathBuf::from("config/grib2_tables")
+    }
+}
+
+/// Load the WMO/NCEP parameter tables (`.csv`, `.dat` or `.txt`) in `dir`
+/// into `tables`, in file name order so later files override earlier ones.
+///
+/// A missing directory or an unreadable file is logged and skipped; lookups
+/// then fall back to the model configs and to `P{d}_{c}_{n}` names.
+fn load_parameter_tables(tables: &mut Grib2Tables, dir: &Path) {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|s| s.to_str()),
+                    Some("csv" | "dat" | "txt")
+                )
+            })
+            .collect(),
+        Err(_) => {
+            debug!(path = ?dir, "No GRIB2 parameter tables directory");
+            return;
+        }
+    };
+    paths.sort();
+
+    for path in paths {
+        let result = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(tables.load_parameter_table(&text)?));
+        match result {
+            Ok(stats) => {
+                if stats.skipped > 0 {
+                    warn!(path = ?path, skipped = stats.skipped, "Skipped unreadable parameter table rows");
+                }
+                debug!(path = ?path, loaded = stats.loaded, "Loaded GRIB2 parameter table");
+            }
+            Err(e) => warn!(path = ?path, error = %e, "Failed to load GRIB2 parameter table"),
+        }
+    }
+}
+
 /// Build Grib2Tables from all model configuration files in config/models/.
 ///
 /// This reads all .yaml files in the models directory and extracts:
 /// - Parameter mappings from `grib2: {discipline, category, number}` to parameter names
 /// - Level mappings from `level_code` to display text (with {value} templates)
 ///
+/// Parameters missing from the configs are named from the external parameter
+/// tables (see [`get_parameter_tables_dir`]); config names take precedence.
+///
 /// The config directory can be overridden via the CONFIG_DIR environment variable.
 ///
 /// Returns an Arc-wrapped tables instance suitable for sharing across readers.
 pub fn build_tables_from_configs() -> Arc<Grib2Tables> {
     let mut tables = Grib2Tables::new();
+    load_parameter_tables(&mut tables, &get_parameter_tables_dir());
     let models_dir = get_models_dir();
 
     if !models_dir.exists() {
@@ -478,6 +955,7 @@ fn load_model_config(
 /// The config directory can be overridden via the CONFIG_DIR environment variable.
 pub fn build_tables_for_model(model: &str) -> Arc<Grib2Tables> {
     let mut tables = Grib2Tables::new();
+    load_parameter_tables(&mut tables, &get_parameter_tables_dir());
     let config_path = get_models_dir().join(format!("{}.yaml", model));
 
     if !config_path.exists() {
@@ -510,6 +988,29 @@ pub fn build_tables_for_model(model: &str) -> Arc<Grib2Tables> {
     Arc::new(tables)
 }
 
+/// Number of forecast hours a run of `model` is complete with, from the
+/// `schedule.forecast_hours` range of its config.
+///
+/// `None` for models without a forecast hour schedule (observations), whose
+/// data is served as soon as it is ingested.
+pub fn expected_forecast_hours(model: &str) -> Option<u32> {
+    let config_path = get_models_dir().join(format!("{}.yaml", model));
+    let contents = fs::read_to_string(config_path).ok()?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(&contents).ok()?;
+    forecast_hour_count(&yaml["schedule"]["forecast_hours"])
+}
+
+/// Number of hours in a `{start, end, step}` forecast hour range.
+fn forecast_hour_count(range: &serde_yaml::Value) -> Option<u32> {
+    let start = range["start"].as_u64().unwrap_or(0);
+    let end = range["end"].as_u64()?;
+    let step = range["step"].as_u64().unwrap_or(1);
+    if step == 0 || end < start {
+        return None;
+    }
+    u32::try_from((end - start) / step + 1).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -568,6 +1069,18 @@ parameters:
         assert!(levels.contains_key(&103));
     }
 
+    #[test]
+    fn test_forecast_hour_count() {
+        let count = |yaml: &str| forecast_hour_count(&serde_yaml::from_str(yaml).unwrap());
+
+        assert_eq!(count("{start: 0, end: 24, step: 1}"), Some(25));
+        assert_eq!(count("{start: 0, end: 48, step: 3}"), Some(17));
+        assert_eq!(count("{end: 18}"), Some(19));
+        assert_eq!(count("{start: 6, end: 0}"), None);
+        assert_eq!(count("{start: 0, end: 24, step: 0}"), None);
+        assert_eq!(count("~"), None);
+    }
+
     #[test]
     fn test_level_description_formats() {
         let dir = tempdir().unwrap();
@@ -611,6 +1124,51 @@ parameters:
         }
     }
 
+    #[test]
+    fn test_load_parameter_tables() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("a_wmo.csv"),
+            "discipline,category,number,abbrev\n0,0,0,TMP\n0,2,2,UGRD\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b_ncep.dat"),
+            "{ 0, 1, 0, 255, 7, 1, 7, 199, \"MXUPHL\", \"Updraft Helicity\", \"m^2/s^2\"},\n\
+             { 0, 1, 0, 255, 0, 0, 0, 0, \"T\", \"Temperature\", \"K\"},\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.md"), "not a table").unwrap();
+
+        let mut tables = Grib2Tables::new();
+        load_parameter_tables(&mut tables, dir.path());
+
+        // Later files override earlier ones
+        assert_eq!(tables.get_parameter_name(0, 0, 0), "T");
+        assert_eq!(tables.get_parameter_name(0, 2, 2), "UGRD");
+        assert_eq!(
+            tables.get_local_parameter_name(7, 0, 7, 199),
+            Some("MXUPHL")
+        );
+
+        // A missing directory leaves the tables as they are
+        load_parameter_tables(&mut tables, &dir.path().join("missing"));
+        assert_eq!(tables.parameter_count(), 3);
+    }
+
+    #[test]
+    fn test_shipped_ncep_local_table() {
+        let mut tables = Grib2Tables::new();
+        load_parameter_tables(&mut tables, Path::new("../../config/grib2_tables"));
+
+        assert_eq!(
+            tables.get_local_parameter_name(7, 0, 7, 199),
+            Some("MXUPHL")
+        );
+        assert_eq!(tables.get_local_parameter_name(7, 0, 16, 196), Some("REFC"));
+        assert_eq!(tables.get_local_parameter_name(161, 0, 16, 196), None);
+    }
+
     #[test]
     fn test_missing_grib2_section() {
         let dir = tempdir().unwrap();
@@ -675,6 +1233,7 @@ parameters:
             103,
             LevelFilter {
                 allowed_values: Some(HashSet::from([2])),
+                ..Default::default()
             },
         );
 
@@ -696,6 +1255,7 @@ parameters:
             100,
             LevelFilter {
                 allowed_values: Some(HashSet::from([1000, 850, 500, 300])),
+                ..Default::default()
             },
         );
 
@@ -718,6 +1278,7 @@ parameters:
             200,
             LevelFilter {
                 allowed_values: None, // Accept all values
+                ..Default::default()
             },
         );
 
@@ -738,6 +1299,7 @@ parameters:
             103,
             LevelFilter {
                 allowed_values: Some(HashSet::from([2])),
+                ..Default::default()
             },
         );
 
@@ -758,6 +1320,7 @@ parameters:
             103,
             LevelFilter {
                 allowed_values: None,
+                ..Default::default()
             },
         );
         assert!(!filter2.is_empty());
@@ -998,6 +1561,7 @@ parameters:
         let config = r#"
 parameters:
   - name: TMP
+    description: "Temperature"
     valid_range: [150, 350]
     units: "K"
     levels:
@@ -1027,6 +1591,351 @@ parameters:
         assert_eq!(filter.get_units("WIND"), "unknown");
         // Unknown parameter should also return "unknown"
         assert_eq!(filter.get_units("UNKNOWN"), "unknown");
+
+        assert_eq!(filter.get_description("TMP"), Some("Temperature"));
+        assert_eq!(filter.get_description("RH"), None);
+    }
+
+    #[test]
+    fn test_parameter_metadata() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+parameters:
+  - name: TMP
+    description: "Temperature"
+    valid_range: [150, 350]
+    units: "K"
+    style: temperature
+    levels:
+      - level_code: 103
+        value: 2
+  - name: DPT
+    valid_range: [150, 350]
+    levels:
+      - level_code: 103
+        value: 2
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        load_filter_from_config(&dir.path().join("test.yaml"), &mut filter).unwrap();
+
+        let mut tables = Grib2Tables::new();
+        tables
+            .load_parameter_table(
+                "Discipline,Category,Number,shortName,Description,Units\n\
+                 0,0,0,TMP,Temperature (table),K\n\
+                 0,0,6,DPT,Dew Point Temperature,K\n",
+            )
+            .unwrap();
+
+        let tmp = filter.parameter_metadata("gfs", "TMP", &tables);
+        assert_eq!(tmp.long_name, "Temperature");
+        assert_eq!(tmp.units, "K");
+        assert_eq!(tmp.default_style.as_deref(), Some("temperature"));
+        assert_eq!((tmp.valid_min, tmp.valid_max), (Some(150.0), Some(350.0)));
+
+        // Falls back to the GRIB2 tables for what the config leaves out
+        let dpt = filter.parameter_metadata("gfs", "DPT", &tables);
+        assert_eq!(dpt.long_name, "Dew Point Temperature");
+        assert_eq!(dpt.units, "K");
+        assert_eq!(dpt.default_style, None);
+
+        let unknown = filter.parameter_metadata("gfs", "XYZ", &tables);
+        assert_eq!(unknown.long_name, "XYZ");
+        assert_eq!(unknown.units, "unknown");
+        assert_eq!(unknown.valid_min, None);
+    }
+
+    #[test]
+    fn test_load_filter_range_and_pattern() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+parameters:
+  - name: TMP
+    valid_range: [150, 350]
+    levels:
+      - level_code: 100
+        range: [1000, 100]
+      - level_code: 103
+        pattern: "^(2|80) m above ground$"
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        load_filter_from_config(&dir.path().join("test.yaml"), &mut filter).unwrap();
+
+        // Range accepts either bound order, inclusive
+        assert!(filter.should_ingest("TMP", 100, 1000));
+        assert!(filter.should_ingest("TMP", 100, 500));
+        assert!(filter.should_ingest("TMP", 100, 100));
+        assert!(!filter.should_ingest("TMP", 100, 70));
+
+        // Patterns match the level description
+        assert!(filter.should_ingest_level("TMP", 103, 2, "2 m above ground"));
+        assert!(filter.should_ingest_level("TMP", 103, 80, "80 m above ground"));
+        assert!(!filter.should_ingest_level("TMP", 103, 10, "10 m above ground"));
+        assert!(!filter.should_ingest("TMP", 103, 2));
+    }
+
+    #[test]
+    fn test_level_filter_merge_with_accept_all_stays_permissive() {
+        let mut filter = IngestionFilter::new();
+        filter.insert(
+            "TMP".to_string(),
+            100,
+            LevelFilter {
+                ranges: vec![100..=1000],
+                ..Default::default()
+            },
+        );
+        filter.insert(
+            "TMP".to_string(),
+            100,
+            LevelFilter {
+                allowed_values: Some(HashSet::from([10])),
+                ..Default::default()
+            },
+        );
+        assert!(filter.should_ingest("TMP", 100, 500));
+        assert!(filter.should_ingest("TMP", 100, 10));
+        assert!(!filter.should_ingest("TMP", 100, 20));
+
+        filter.insert("TMP".to_string(), 100, LevelFilter::default());
+        assert!(filter.should_ingest("TMP", 100, 20));
+    }
+
+    #[test]
+    fn test_load_filter_pyramid_settings() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+parameters:
+  - name: REFL
+    valid_range: [-30, 80]
+    downsample: max
+    pyramid_levels: 3
+    levels:
+      - level_code: 1
+  - name: TMP
+    valid_range: [150, 350]
+    levels:
+      - level_code: 1
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        load_filter_from_config(&dir.path().join("test.yaml"), &mut filter).unwrap();
+
+        let settings = filter.get_pyramid_settings("REFL");
+        assert_eq!(settings.max_levels, Some(3));
+        assert_eq!(settings.downsample, Some(DownsampleMethod::Max));
+        assert_eq!(filter.get_downsample_method("REFL"), DownsampleMethod::Max);
+
+        let base = PyramidConfig::default();
+        assert_eq!(settings.apply(&base).max_levels, Some(3));
+        assert_eq!(
+            filter.get_pyramid_settings("TMP").apply(&base).max_levels,
+            None
+        );
+        // Unconfigured parameters fall back to the built-in heuristic
+        assert_eq!(filter.get_downsample_method("TMP"), DownsampleMethod::Mean);
+    }
+
+    #[test]
+    fn test_load_filter_chunk_layout() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+parameters:
+  - name: REFL
+    valid_range: [-30, 80]
+    chunks: square
+  - name: TMP
+    valid_range: [150, 350]
+    access: time_series
+  - name: UGRD
+    valid_range: [-150, 150]
+    chunks: auto
+  - name: VGRD
+    valid_range: [-150, 150]
+    chunks: [1024, 256]
+  - name: RH
+    valid_range: [0, 100]
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        load_filter_from_config(&dir.path().join("test.yaml"), &mut filter).unwrap();
+
+        let layout = |name: &str| filter.get_pyramid_settings(name).chunk_layout;
+        assert_eq!(layout("REFL"), Some(ChunkLayout::Square));
+        assert_eq!(
+            layout("TMP"),
+            Some(ChunkLayout::Auto(AccessPattern::TimeSeries))
+        );
+        assert_eq!(
+            layout("UGRD"),
+            Some(ChunkLayout::Auto(AccessPattern::Tiles))
+        );
+        assert_eq!(
+            layout("VGRD"),
+            Some(ChunkLayout::Fixed {
+                width: 1024,
+                height: 256
+            })
+        );
+        assert_eq!(layout("RH"), None);
+
+        let base = GridProcessorConfig::default();
+        assert_eq!(
+            filter
+                .get_pyramid_settings("TMP")
+                .apply_writer(&base)
+                .chunk_layout,
+            ChunkLayout::Auto(AccessPattern::TimeSeries)
+        );
+        assert_eq!(
+            filter
+                .get_pyramid_settings("RH")
+                .apply_writer(&base)
+                .chunk_layout,
+            ChunkLayout::Square
+        );
+    }
+
+    #[test]
+    fn test_load_filter_rejects_bad_chunk_layout() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+parameters:
+  - name: TMP
+    valid_range: [150, 350]
+    chunks: [512, 0]
+  - name: RH
+    valid_range: [0, 100]
+    access: sideways
+  - name: UGRD
+    valid_range: [-150, 150]
+    chunks: square
+    access: time_series
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        let err = load_filter_from_config(&dir.path().join("test.yaml"), &mut filter)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("parameters[0] (TMP).chunks"));
+        assert!(err.contains("parameters[1] (RH).access"));
+        assert!(err.contains("parameters[2] (UGRD).access"));
+    }
+
+    #[test]
+    fn test_load_filter_compression() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+zarr:
+  compression: zstd
+  compression_level: 19
+parameters:
+  - name: REFL
+    valid_range: [-30, 80]
+    chunks: [1024, 256]
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        load_filter_from_config(&dir.path().join("test.yaml"), &mut filter).unwrap();
+
+        let settings = filter.get_pyramid_settings("REFL");
+        assert_eq!(settings.compression, Some(ZarrCompression::Zstd));
+        assert_eq!(settings.compression_level, Some(19));
+        let config = settings.apply_writer(&GridProcessorConfig::default());
+        assert_eq!(config.zarr_compression, ZarrCompression::Zstd);
+        assert_eq!(config.zarr_compression_level, 19);
+        assert!(config.validate().is_ok());
+
+        // Parameters not listed still get the model's compression
+        assert_eq!(
+            filter.get_pyramid_settings("TMP").compression,
+            Some(ZarrCompression::Zstd)
+        );
+
+        // Blosc levels stop at 9
+        let config = r#"
+zarr:
+  compression: blosc-lz4
+  compression_level: 19
+parameters:
+  - name: REFL
+    valid_range: [-30, 80]
+"#;
+        create_test_config(dir.path(), "bad", config);
+        let mut filter = IngestionFilter::new();
+        let err = load_filter_from_config(&dir.path().join("bad.yaml"), &mut filter)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("zarr.compression_level: expected an integer 1-9 for blosc_lz4"));
+    }
+
+    #[test]
+    fn test_load_filter_reports_error_paths() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+parameters:
+  - name: TMP
+    valid_range: [350, 150]
+    downsample: median
+    levels:
+      - level_code: 100
+        range: [1000]
+  - name: RH
+    valid_range: [0, 100]
+    pyramid_levels: 0
+    levels:
+      - level_code: 103
+        pattern: "(unclosed"
+      - level_code: 300
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        let err = load_filter_from_config(&dir.path().join("test.yaml"), &mut filter)
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("parameters[0] (TMP).valid_range"));
+        assert!(err.contains("parameters[0] (TMP).downsample"));
+        assert!(err.contains("parameters[0] (TMP).levels[0].range"));
+        assert!(err.contains("parameters[1] (RH).pyramid_levels"));
+        assert!(err.contains("parameters[1] (RH).levels[0].pattern"));
+        assert!(err.contains("parameters[1] (RH).levels[1].level_code"));
+    }
+
+    #[test]
+    fn test_load_filter_conflicting_pyramid_settings_fails() {
+        let dir = tempdir().unwrap();
+        let config = r#"
+parameters:
+  - name: TMP
+    valid_range: [150, 350]
+    downsample: mean
+    levels:
+      - level_code: 103
+        value: 2
+  - name: TMP
+    valid_range: [150, 350]
+    downsample: max
+    levels:
+      - level_code: 100
+"#;
+        create_test_config(dir.path(), "test", config);
+
+        let mut filter = IngestionFilter::new();
+        let err = load_filter_from_config(&dir.path().join("test.yaml"), &mut filter)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("parameters[1] (TMP)"));
+        assert!(err.contains("conflict"));
     }
 }
 
@@ -1105,6 +2014,17 @@ mod integration_tests {
         println!("All HRRR filter tests passed!");
     }
 
+    #[test]
+    fn test_expected_forecast_hours_from_real_config() {
+        std::env::set_var("CONFIG_DIR", "../../config");
+
+        assert_eq!(expected_forecast_hours("gfs"), Some(25));
+        assert_eq!(expected_forecast_hours("hrrr"), Some(19));
+        // Observations are not gated on completeness
+        assert_eq!(expected_forecast_hours("mrms"), None);
+        assert_eq!(expected_forecast_hours("no-such-model"), None);
+    }
+
     #[test]
     fn test_units_from_real_config() {
         // This test uses the actual HRRR config file
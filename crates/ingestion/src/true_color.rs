@@ -0,0 +1,239 @@
+//! True color composites of GOES scans, built at ingestion time.
+//!
+//! The blue (C01), red (C02) and veggie (C03) bands of a scan arrive as
+//! separate files. Models that list a `TRUE_COLOR` parameter in their config
+//! have those bands held in [`PendingTrueColor`] until all three of a scan
+//! are in; the composite is then built with [`netcdf_parser::composite`],
+//! reprojected channel by channel and stored as one grid of packed RGB
+//! values ([`netcdf_parser::pack_rgb`]), so WMS can serve it like any other
+//! parameter.
+//!
+//! Packed values must not be averaged, so the parameter's pyramid should use
+//! `downsample: nearest`.
+
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+use netcdf_parser::composite::{pack_rgb, true_color, TrueColorParams, TRUE_COLOR_BANDS};
+use netcdf_parser::{GoesBand, GoesProjection};
+use storage::{Catalog, ObjectStorage};
+
+use grid_processor::reproject_geostationary_to_geographic;
+
+use crate::error::{IngestionError, Result};
+use crate::netcdf::{geostationary, store_grid};
+use crate::tables::IngestionFilter;
+
+/// Parameter name of the true color composite.
+pub const TRUE_COLOR_PARAMETER: &str = "TRUE_COLOR";
+
+/// Level name of the true color composite.
+pub const TRUE_COLOR_LEVEL: &str = "true_color";
+
+/// Scans held at once. The three bands of a scan arrive within seconds of
+/// each other, and a full resolution CONUS C02 band alone is ~240 MB.
+const MAX_PENDING_SCANS: usize = 2;
+
+/// Visible bands waiting for the rest of their scan.
+#[derive(Default)]
+pub struct PendingTrueColor {
+    scans: Mutex<Vec<PendingScan>>,
+}
+
+struct PendingScan {
+    model: String,
+    observation_time: DateTime<Utc>,
+    /// Indexed like [`TRUE_COLOR_BANDS`]
+    bands: [Option<GoesBand>; 3],
+}
+
+impl PendingTrueColor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold a band of a scan.
+    ///
+    /// Returns the scan's blue, red and veggie bands once all three are in,
+    /// and forgets the scan. Bands other than C01-C03 are ignored. When more
+    /// than [`MAX_PENDING_SCANS`] scans are incomplete, the oldest is dropped.
+    pub fn add(
+        &self,
+        model: &str,
+        observation_time: DateTime<Utc>,
+        band: GoesBand,
+    ) -> Option<[GoesBand; 3]> {
+        let slot = TRUE_COLOR_BANDS.iter().position(|&b| b == band.band)?;
+        let mut scans = self.scans.lock().unwrap_or_else(|e| e.into_inner());
+
+        let index = match scans
+            .iter()
+            .position(|s| s.model == model && s.observation_time == observation_time)
+        {
+            Some(index) => index,
+            None => {
+                scans.push(PendingScan {
+                    model: model.to_string(),
+                    observation_time,
+                    bands: [None, None, None],
+                });
+                scans.len() - 1
+            }
+        };
+        scans[index].bands[slot] = Some(band);
+
+        if scans[index].bands.iter().all(Option::is_some) {
+            let [blue, red, veggie] = scans.remove(index).bands;
+            return Some([blue?, red?, veggie?]);
+        }
+
+        while scans.len() > MAX_PENDING_SCANS {
+            let oldest = scans
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, s)| s.observation_time)
+                .map(|(i, _)| i)?;
+            scans.remove(oldest);
+        }
+        None
+    }
+
+    /// Number of incomplete scans held.
+    pub fn len(&self) -> usize {
+        self.scans.lock().map(|s| s.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Build, reproject and store the true color composite of a complete scan.
+///
+/// Returns the stored size and path.
+pub(crate) async fn ingest_true_color(
+    storage: &Arc<ObjectStorage>,
+    catalog: &Catalog,
+    model: &str,
+    observation_time: DateTime<Utc>,
+    [blue, red, veggie]: [GoesBand; 3],
+    projection: &GoesProjection,
+    filter: &IngestionFilter,
+) -> Result<(u64, String)> {
+    let rgb = true_color(&blue, &red, &veggie, &TrueColorParams::default())
+        .map_err(|e| IngestionError::NetcdfParse(e.to_string()))?;
+    // The full resolution bands are no longer needed
+    drop((blue, red, veggie));
+
+    let geometry = &rgb.red;
+    let proj = geostationary(
+        projection,
+        [
+            geometry.x_offset,
+            geometry.y_offset,
+            geometry.x_scale,
+            geometry.y_scale,
+        ],
+        geometry.width,
+        geometry.height,
+    );
+
+    // Reproject each channel on its own: interpolating packed values would
+    // mix the channels
+    let [red, green, blue] = [&rgb.red, &rgb.green, &rgb.blue].map(|channel| {
+        reproject_geostationary_to_geographic(&channel.data, channel.width, channel.height, &proj)
+    });
+    let (out_width, out_height, bbox) = (red.1, red.2, red.3);
+    let packed: Vec<f32> = red
+        .0
+        .iter()
+        .zip(&green.0)
+        .zip(&blue.0)
+        .map(|((&r, &g), &b)| pack_rgb(r, g, b))
+        .collect();
+
+    info!(
+        model = %model,
+        observation_time = %observation_time,
+        width = out_width,
+        height = out_height,
+        "Built true color composite"
+    );
+
+    store_grid(
+        storage,
+        catalog,
+        &packed,
+        out_width,
+        out_height,
+        &bbox,
+        model,
+        TRUE_COLOR_PARAMETER,
+        TRUE_COLOR_LEVEL,
+        filter.get_units(TRUE_COLOR_PARAMETER),
+        filter.get_description(TRUE_COLOR_PARAMETER),
+        observation_time,
+        &filter.get_pyramid_settings(TRUE_COLOR_PARAMETER),
+        None,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use netcdf_parser::GoesAttributes;
+
+    fn band(band: u8) -> GoesBand {
+        GoesBand {
+            band,
+            data: vec![0.5; 4],
+            width: 2,
+            height: 2,
+            scale_factor: 1.0,
+            add_offset: 0.0,
+            attributes: GoesAttributes::default(),
+            x_offset: 0.0,
+            y_offset: 0.0,
+            x_scale: 5.6e-5,
+            y_scale: -5.6e-5,
+        }
+    }
+
+    #[test]
+    fn test_completes_scan_in_any_order() {
+        let pending = PendingTrueColor::new();
+        let time = Utc.with_ymd_and_hms(2024, 6, 1, 18, 1, 0).unwrap();
+
+        assert!(pending.add("goes19", time, band(2)).is_none());
+        assert!(pending.add("goes19", time, band(13)).is_none());
+        assert!(pending.add("goes19", time, band(3)).is_none());
+        // Same scan time on another satellite is another scan
+        assert!(pending.add("goes18", time, band(1)).is_none());
+        assert_eq!(pending.len(), 2);
+
+        let bands = pending.add("goes19", time, band(1)).unwrap();
+        assert_eq!(bands.map(|b| b.band), [1, 2, 3]);
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn test_drops_oldest_incomplete_scan() {
+        let pending = PendingTrueColor::new();
+        let time = |minute| Utc.with_ymd_and_hms(2024, 6, 1, 18, minute, 0).unwrap();
+
+        pending.add("goes19", time(1), band(1));
+        pending.add("goes19", time(6), band(1));
+        pending.add("goes19", time(11), band(1));
+        assert_eq!(pending.len(), MAX_PENDING_SCANS);
+
+        // The 18:01 scan was dropped; its late bands are the oldest held, so
+        // they are dropped again rather than completing it
+        assert!(pending.add("goes19", time(1), band(2)).is_none());
+        assert!(pending.add("goes19", time(1), band(3)).is_none());
+        assert!(pending.add("goes19", time(11), band(2)).is_none());
+        assert!(pending.add("goes19", time(11), band(3)).is_some());
+    }
+}
@@ -1,16 +1,170 @@
 //! Zarr directory upload utilities.
+//!
+//! Small files are written with a single PUT. Files at or above the multipart
+//! threshold (large sharded Zarr chunks, typically 50-200 MB) are split into
+//! parts that are uploaded concurrently. Every PUT, single or part, is retried
+//! with exponential backoff; a multipart upload that still fails is aborted so
+//! no orphaned parts are left in the bucket.
 
 use bytes::Bytes;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::future::Future;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 use storage::ObjectStorage;
-use tracing::debug;
+use tracing::{debug, warn};
+use wms_common::WmsResult;
 
 use crate::error::{IngestionError, Result};
 
+/// Smallest part size accepted by S3 (for all but the last part).
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// Settings for uploading Zarr files to object storage.
+#[derive(Debug, Clone)]
+pub struct UploadConfig {
+    /// Files at least this large (bytes) are uploaded in parts
+    pub multipart_threshold: usize,
+    /// Size of each part in bytes (at least 5 MiB)
+    pub part_size: usize,
+    /// Parts uploaded concurrently per file
+    pub max_concurrent_parts: usize,
+    /// Retries per PUT after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on each further retry
+    pub retry_backoff: Duration,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self {
+            multipart_threshold: 32 * 1024 * 1024,
+            part_size: 8 * 1024 * 1024,
+            max_concurrent_parts: 4,
+            max_retries: 3,
+            retry_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl UploadConfig {
+    /// Load configuration from environment variables.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("UPLOAD_MULTIPART_THRESHOLD_MB") {
+            if let Ok(mb) = val.parse::<usize>() {
+                config.multipart_threshold = mb * 1024 * 1024;
+            }
+        }
+
+        if let Ok(val) = std::env::var("UPLOAD_PART_SIZE_MB") {
+            if let Ok(mb) = val.parse::<usize>() {
+                config.part_size = (mb * 1024 * 1024).max(MIN_PART_SIZE);
+            }
+        }
+
+        if let Ok(val) = std::env::var("UPLOAD_CONCURRENCY") {
+            if let Ok(n) = val.parse::<usize>() {
+                config.max_concurrent_parts = n.max(1);
+            }
+        }
+
+        if let Ok(val) = std::env::var("UPLOAD_MAX_RETRIES") {
+            if let Ok(n) = val.parse() {
+                config.max_retries = n;
+            }
+        }
+
+        if let Ok(val) = std::env::var("UPLOAD_RETRY_BACKOFF_MS") {
+            if let Ok(ms) = val.parse() {
+                config.retry_backoff = Duration::from_millis(ms);
+            }
+        }
+
+        config
+    }
+
+    /// Whether a file of `size` bytes is uploaded in parts.
+    pub fn use_multipart(&self, size: usize) -> bool {
+        size >= self.multipart_threshold && size > self.part_size
+    }
+}
+
+/// Upload counters since process start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UploadMetrics {
+    /// Files uploaded with a single PUT
+    pub single_uploads: u64,
+    /// Files uploaded in parts
+    pub multipart_uploads: u64,
+    /// Parts uploaded
+    pub parts_uploaded: u64,
+    /// PUTs retried after a failure (single or part)
+    pub retries: u64,
+    /// Multipart uploads aborted after a part failed
+    pub aborted_uploads: u64,
+}
+
+impl UploadMetrics {
+    /// Render as Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        format!(
+            "# HELP ingester_upload_files_total Files uploaded to object storage\n\
+             # TYPE ingester_upload_files_total counter\n\
+             ingester_upload_files_total{{method=\"single\"}} {}\n\
+             ingester_upload_files_total{{method=\"multipart\"}} {}\n\
+             # HELP ingester_upload_parts_total Multipart upload parts uploaded\n\
+             # TYPE ingester_upload_parts_total counter\n\
+             ingester_upload_parts_total {}\n\
+             # HELP ingester_upload_retries_total Upload requests retried after a failure\n\
+             # TYPE ingester_upload_retries_total counter\n\
+             ingester_upload_retries_total {}\n\
+             # HELP ingester_upload_aborted_total Multipart uploads aborted after a failure\n\
+             # TYPE ingester_upload_aborted_total counter\n\
+             ingester_upload_aborted_total {}\n",
+            self.single_uploads,
+            self.multipart_uploads,
+            self.parts_uploaded,
+            self.retries,
+            self.aborted_uploads
+        )
+    }
+}
+
+struct UploadCounters {
+    single_uploads: AtomicU64,
+    multipart_uploads: AtomicU64,
+    parts_uploaded: AtomicU64,
+    retries: AtomicU64,
+    aborted_uploads: AtomicU64,
+}
+
+static COUNTERS: UploadCounters = UploadCounters {
+    single_uploads: AtomicU64::new(0),
+    multipart_uploads: AtomicU64::new(0),
+    parts_uploaded: AtomicU64::new(0),
+    retries: AtomicU64::new(0),
+    aborted_uploads: AtomicU64::new(0),
+};
+
+/// Snapshot of the upload counters.
+pub fn upload_metrics() -> UploadMetrics {
+    UploadMetrics {
+        single_uploads: COUNTERS.single_uploads.load(Ordering::Relaxed),
+        multipart_uploads: COUNTERS.multipart_uploads.load(Ordering::Relaxed),
+        parts_uploaded: COUNTERS.parts_uploaded.load(Ordering::Relaxed),
+        retries: COUNTERS.retries.load(Ordering::Relaxed),
+        aborted_uploads: COUNTERS.aborted_uploads.load(Ordering::Relaxed),
+    }
+}
+
 /// Upload a Zarr directory to object storage.
 ///
 /// Recursively walks the local Zarr directory and uploads all files
-/// to the specified storage prefix.
+/// to the specified storage prefix, using [`UploadConfig::from_env`].
 ///
 /// # Arguments
 /// * `storage` - Object storage client (MinIO/S3)
@@ -23,6 +177,22 @@ pub async fn upload_zarr_directory(
     storage: &ObjectStorage,
     local_path: &Path,
     storage_prefix: &str,
+) -> Result<u64> {
+    upload_zarr_directory_with_config(
+        storage,
+        local_path,
+        storage_prefix,
+        &UploadConfig::from_env(),
+    )
+    .await
+}
+
+/// Upload a Zarr directory to object storage with explicit upload settings.
+pub async fn upload_zarr_directory_with_config(
+    storage: &ObjectStorage,
+    local_path: &Path,
+    storage_prefix: &str,
+    config: &UploadConfig,
 ) -> Result<u64> {
     let mut total_size = 0u64;
 
@@ -37,12 +207,11 @@ pub async fn upload_zarr_directory(
 
             let storage_path = format!("{}/{}", storage_prefix, relative_path.display());
 
-            let file_data = tokio::fs::read(entry.path()).await?;
+            let file_data = Bytes::from(tokio::fs::read(entry.path()).await?);
             let file_size = file_data.len() as u64;
             total_size += file_size;
 
-            storage
-                .put(&storage_path, Bytes::from(file_data))
+            upload_file(storage, &storage_path, file_data, config)
                 .await
                 .map_err(|e| IngestionError::StorageUpload(e.to_string()))?;
 
@@ -52,3 +221,192 @@ pub async fn upload_zarr_directory(
 
     Ok(total_size)
 }
+
+/// Upload one object, in parts if it is over the multipart threshold.
+pub async fn upload_file(
+    storage: &ObjectStorage,
+    path: &str,
+    data: Bytes,
+    config: &UploadConfig,
+) -> WmsResult<()> {
+    if !config.use_multipart(data.len()) {
+        with_retry(config, || storage.put(path, data.clone())).await?;
+        COUNTERS.single_uploads.fetch_add(1, Ordering::Relaxed);
+        return Ok(());
+    }
+
+    let upload_id = with_retry(config, || storage.create_multipart(path)).await?;
+    let ranges = part_ranges(data.len(), config.part_size);
+    debug!(path = %path, parts = ranges.len(), "Starting multipart upload");
+
+    // Parts finish in any order; completion needs them in part order
+    let uploaded: WmsResult<Vec<_>> = stream::iter(ranges.into_iter().enumerate())
+        .map(|(idx, range)| {
+            let part = data.slice(range);
+            let upload_id = &upload_id;
+            async move {
+                let part_id = with_retry(config, || {
+                    storage.put_part(path, upload_id, idx, part.clone())
+                })
+                .await?;
+                COUNTERS.parts_uploaded.fetch_add(1, Ordering::Relaxed);
+                Ok((idx, part_id))
+            }
+        })
+        .buffer_unordered(config.max_concurrent_parts.max(1))
+        .try_collect()
+        .await;
+
+    let result = match uploaded {
+        Ok(mut parts) => {
+            parts.sort_by_key(|(idx, _)| *idx);
+            let parts = parts.into_iter().map(|(_, part_id)| part_id).collect();
+            storage.complete_multipart(path, &upload_id, parts).await
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => {
+            COUNTERS.multipart_uploads.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+        Err(e) => {
+            COUNTERS.aborted_uploads.fetch_add(1, Ordering::Relaxed);
+            if let Err(abort_err) = storage.abort_multipart(path, &upload_id).await {
+                warn!(path = %path, error = %abort_err, "Failed to abort multipart upload");
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Byte ranges of the parts of an object of `len` bytes.
+///
+/// All parts are `part_size` bytes except the last, which holds the remainder.
+fn part_ranges(len: usize, part_size: usize) -> Vec<Range<usize>> {
+    let part_size = part_size.max(1);
+    (0..len)
+        .step_by(part_size)
+        .map(|start| start..(start + part_size).min(len))
+        .collect()
+}
+
+/// Run a storage request, retrying failures with exponential backoff.
+async fn with_retry<T, F, Fut>(config: &UploadConfig, mut request: F) -> WmsResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = WmsResult<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < config.max_retries => {
+                let delay = config.retry_backoff * 2u32.saturating_pow(attempt);
+                attempt += 1;
+                COUNTERS.retries.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    attempt = attempt,
+                    max_retries = config.max_retries,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %e,
+                    "Upload request failed, retrying"
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wms_common::WmsError;
+
+    fn test_config(max_retries: u32) -> UploadConfig {
+        UploadConfig {
+            max_retries,
+            retry_backoff: Duration::ZERO,
+            ..UploadConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_part_ranges() {
+        assert_eq!(part_ranges(10, 4), vec![0..4, 4..8, 8..10]);
+        assert_eq!(part_ranges(8, 4), vec![0..4, 4..8]);
+        assert_eq!(part_ranges(3, 4), vec![0..3]);
+        assert!(part_ranges(0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_use_multipart() {
+        let config = UploadConfig::default();
+        assert!(!config.use_multipart(1024));
+        assert!(!config.use_multipart(config.multipart_threshold - 1));
+        assert!(config.use_multipart(config.multipart_threshold));
+        assert!(config.use_multipart(200 * 1024 * 1024));
+
+        // A file that fits in one part is never split
+        let config = UploadConfig {
+            multipart_threshold: 0,
+            ..UploadConfig::default()
+        };
+        assert!(!config.use_multipart(config.part_size));
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() {
+        let before = upload_metrics().retries;
+        let mut calls = 0;
+
+        let result = with_retry(&test_config(3), || {
+            calls += 1;
+            let call = calls;
+            async move {
+                if call < 3 {
+                    Err(WmsError::StorageError("connection reset".to_string()))
+                } else {
+                    Ok(call)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert!(upload_metrics().retries >= before + 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up() {
+        let mut calls = 0;
+
+        let result: WmsResult<()> = with_retry(&test_config(2), || {
+            calls += 1;
+            async { Err(WmsError::StorageError("timeout".to_string())) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_metrics_prometheus_format() {
+        let metrics = UploadMetrics {
+            single_uploads: 5,
+            multipart_uploads: 2,
+            parts_uploaded: 12,
+            retries: 3,
+            aborted_uploads: 1,
+        };
+        let text = metrics.to_prometheus();
+
+        assert!(text.contains("ingester_upload_files_total{method=\"multipart\"} 2\n"));
+        assert!(text.contains("ingester_upload_parts_total 12\n"));
+        assert!(text.contains("ingester_upload_retries_total 3\n"));
+        assert!(text.contains("ingester_upload_aborted_total 1\n"));
+    }
+}
@@ -0,0 +1,491 @@
+//! Generic reader for CF-1.x gridded variables.
+//!
+//! Reads any gridded variable that follows the
+//! [CF conventions](https://cfconventions.org/), not just GOES ABI imagery:
+//!
+//! - the x and y dimensions are found from their coordinate variables
+//!   (`axis`, `standard_name` or `units`), falling back to the CF
+//!   recommended `(..., y, x)` order
+//! - packed values are unpacked with `scale_factor` and `add_offset`
+//! - `_FillValue` and `missing_value` become NaN
+//! - the CRS is read from the variable named by `grid_mapping`
+//!
+//! NDFD and RTMA NetCDF files are read this way:
+//!
+//! ```rust,ignore
+//! use netcdf_parser::cf::CfFile;
+//!
+//! let file = CfFile::from_bytes(&bytes)?;
+//! for name in file.grid_variables() {
+//!     let grid = file.read_variable(&name)?;
+//!     println!("{}: {}x{} {:?}", name, grid.width, grid.height, grid.units);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use netcdf::{AttributeValue, Extent};
+
+use crate::error::{NetCdfError, NetCdfResult};
+use crate::native::{get_f64_attr, get_global_str_attr, get_str_attr, has_attr, open_from_bytes};
+
+/// Kind of a coordinate axis, per CF section 4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxisKind {
+    /// Longitude or projection x coordinate
+    X,
+    /// Latitude or projection y coordinate
+    Y,
+    /// Vertical coordinate
+    Z,
+    /// Time
+    T,
+}
+
+impl AxisKind {
+    /// Classify a coordinate variable from its CF attributes.
+    ///
+    /// An explicit `axis` attribute wins; otherwise `standard_name`, then
+    /// `units` (degrees_east/north, "<unit> since <date>"), then `positive`
+    /// (which only vertical coordinates carry).
+    pub fn from_attributes(
+        axis: Option<&str>,
+        standard_name: Option<&str>,
+        units: Option<&str>,
+        positive: Option<&str>,
+    ) -> Option<Self> {
+        match axis.map(str::trim) {
+            Some("X") => return Some(Self::X),
+            Some("Y") => return Some(Self::Y),
+            Some("Z") => return Some(Self::Z),
+            Some("T") => return Some(Self::T),
+            _ => {}
+        }
+
+        match standard_name {
+            Some("longitude" | "grid_longitude" | "projection_x_coordinate") => {
+                return Some(Self::X)
+            }
+            Some("latitude" | "grid_latitude" | "projection_y_coordinate") => return Some(Self::Y),
+            Some("time") => return Some(Self::T),
+            _ => {}
+        }
+
+        if let Some(units) = units.map(str::trim) {
+            match units {
+                "degrees_east" | "degree_east" | "degree_E" | "degrees_E" | "degreeE"
+                | "degreesE" => return Some(Self::X),
+                "degrees_north" | "degree_north" | "degree_N" | "degrees_N" | "degreeN"
+                | "degreesN" => return Some(Self::Y),
+                _ if units.contains(" since ") => return Some(Self::T),
+                _ => {}
+            }
+        }
+
+        positive.map(|_| Self::Z)
+    }
+}
+
+/// A dimension of a variable with its coordinate values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CfAxis {
+    /// Dimension name
+    pub name: String,
+    /// Axis kind, if the coordinate variable says
+    pub kind: Option<AxisKind>,
+    /// Coordinate values, empty if the dimension has no coordinate variable
+    pub values: Vec<f64>,
+    /// Units of the coordinate values
+    pub units: Option<String>,
+}
+
+/// How a variable's stored values map to physical values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Packing {
+    /// `scale_factor` attribute (1 if absent)
+    pub scale_factor: f64,
+    /// `add_offset` attribute (0 if absent)
+    pub add_offset: f64,
+    /// `_FillValue` attribute, in stored (packed) units
+    pub fill_value: Option<f64>,
+    /// `missing_value` attribute, in stored (packed) units
+    pub missing_value: Option<f64>,
+}
+
+impl Default for Packing {
+    fn default() -> Self {
+        Self {
+            scale_factor: 1.0,
+            add_offset: 0.0,
+            fill_value: None,
+            missing_value: None,
+        }
+    }
+}
+
+impl Packing {
+    /// Unpack a stored value; fill and missing values (and NaN) become NaN.
+    pub fn unpack(&self, raw: f64) -> f32 {
+        if raw.is_nan() || Some(raw) == self.fill_value || Some(raw) == self.missing_value {
+            return f32::NAN;
+        }
+        (raw * self.scale_factor + self.add_offset) as f32
+    }
+}
+
+/// Coordinate reference system from a CF grid mapping variable.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridMapping {
+    /// Name of the grid mapping variable
+    pub variable: String,
+    /// `grid_mapping_name`, e.g. "lambert_conformal_conic"
+    pub name: String,
+    /// Numeric attributes, e.g. `standard_parallel` or
+    /// `longitude_of_central_meridian`
+    pub parameters: BTreeMap<String, Vec<f64>>,
+    /// `crs_wkt` (or GDAL's `spatial_ref`), if present
+    pub crs_wkt: Option<String>,
+}
+
+impl GridMapping {
+    /// Check if the grid is on plain latitude/longitude.
+    pub fn is_geographic(&self) -> bool {
+        self.name == "latitude_longitude"
+    }
+
+    /// First value of a numeric parameter.
+    pub fn parameter(&self, name: &str) -> Option<f64> {
+        self.parameters.get(name)?.first().copied()
+    }
+}
+
+/// A 2D slice of a CF gridded variable.
+#[derive(Debug, Clone)]
+pub struct CfGrid {
+    /// Variable name
+    pub variable: String,
+    /// Unpacked values, row-major in stored order (y rows of x columns)
+    pub data: Vec<f32>,
+    /// Length of the x dimension
+    pub width: usize,
+    /// Length of the y dimension
+    pub height: usize,
+    /// The x (column) axis
+    pub x: CfAxis,
+    /// The y (row) axis
+    pub y: CfAxis,
+    /// Index taken along each other dimension (e.g. time, height)
+    pub fixed: Vec<(CfAxis, usize)>,
+    /// `units` attribute
+    pub units: Option<String>,
+    /// `standard_name` attribute
+    pub standard_name: Option<String>,
+    /// `long_name` attribute
+    pub long_name: Option<String>,
+    /// Packing that was applied
+    pub packing: Packing,
+    /// CRS from the `grid_mapping` attribute
+    pub grid_mapping: Option<GridMapping>,
+}
+
+/// A CF NetCDF file opened from bytes.
+pub struct CfFile {
+    file: netcdf::File,
+    temp_file: PathBuf,
+}
+
+impl CfFile {
+    /// Open a NetCDF file from bytes.
+    pub fn from_bytes(data: &[u8]) -> NetCdfResult<Self> {
+        let (file, temp_file) = open_from_bytes(data)?;
+        Ok(Self { file, temp_file })
+    }
+
+    /// The global `Conventions` attribute, e.g. "CF-1.6".
+    pub fn conventions(&self) -> Option<String> {
+        get_global_str_attr(&self.file, "Conventions")
+    }
+
+    /// Names of the variables that have both an x and a y dimension,
+    /// excluding coordinate variables.
+    pub fn grid_variables(&self) -> Vec<String> {
+        self.file
+            .variables()
+            .filter(|var| {
+                let dims = var.dimensions();
+                let is_coordinate = dims.len() == 1 && dims[0].name() == var.name();
+                !is_coordinate && self.spatial_dims(var).is_ok()
+            })
+            .map(|var| var.name())
+            .collect()
+    }
+
+    /// Read a variable, taking the first index of any dimension other than
+    /// x and y.
+    pub fn read_variable(&self, name: &str) -> NetCdfResult<CfGrid> {
+        self.read_variable_at(name, &[])
+    }
+
+    /// Read a variable, taking the given index along the named non-spatial
+    /// dimensions (e.g. `[("time", 3)]`) and the first index along the rest.
+    pub fn read_variable_at(&self, name: &str, indices: &[(&str, usize)]) -> NetCdfResult<CfGrid> {
+        let var = self
+            .file
+            .variable(name)
+            .ok_or_else(|| NetCdfError::MissingData(format!("{} variable", name)))?;
+        let (y_dim, x_dim) = self.spatial_dims(&var)?;
+
+        let dims = var.dimensions();
+        let mut extents = Vec::with_capacity(dims.len());
+        let mut fixed = Vec::new();
+        for (i, dim) in dims.iter().enumerate() {
+            if i == y_dim || i == x_dim {
+                extents.push(Extent::from(..));
+                continue;
+            }
+            let dim_name = dim.name();
+            let index = indices
+                .iter()
+                .find(|(n, _)| *n == dim_name)
+                .map_or(0, |(_, index)| *index);
+            if index >= dim.len() {
+                return Err(NetCdfError::InvalidFormat(format!(
+                    "Index {} out of range for dimension {} of {} (length {})",
+                    index,
+                    dim_name,
+                    name,
+                    dim.len()
+                )));
+            }
+            extents.push(Extent::from(index));
+            fixed.push((self.axis(&dim_name), index));
+        }
+
+        let raw: Vec<f64> = var
+            .get_values(extents)
+            .map_err(|e| NetCdfError::InvalidFormat(format!("Failed to read {}: {}", name, e)))?;
+
+        let packing = Packing {
+            scale_factor: get_f64_attr(&var, "scale_factor").unwrap_or(1.0),
+            add_offset: get_f64_attr(&var, "add_offset").unwrap_or(0.0),
+            fill_value: get_f64_attr(&var, "_FillValue"),
+            missing_value: get_f64_attr(&var, "missing_value"),
+        };
+        let data = raw.iter().map(|&v| packing.unpack(v)).collect();
+
+        let grid_mapping = get_str_attr(&var, "grid_mapping")
+            .map(|mapping| self.grid_mapping(&mapping))
+            .transpose()?;
+
+        Ok(CfGrid {
+            variable: name.to_string(),
+            data,
+            width: dims[x_dim].len(),
+            height: dims[y_dim].len(),
+            x: self.axis(&dims[x_dim].name()),
+            y: self.axis(&dims[y_dim].name()),
+            fixed,
+            units: get_str_attr(&var, "units"),
+            standard_name: get_str_attr(&var, "standard_name"),
+            long_name: get_str_attr(&var, "long_name"),
+            packing,
+            grid_mapping,
+        })
+    }
+
+    /// Positions of the y and x dimensions among a variable's dimensions.
+    ///
+    /// Uses the coordinate variables' axis kinds, or the last two dimensions
+    /// if neither x nor y is marked and those two have no kind. Only `(..., y, x)` layouts are supported.
+    fn spatial_dims(&self, var: &netcdf::Variable) -> NetCdfResult<(usize, usize)> {
+        let dims = var.dimensions();
+        let kinds: Vec<_> = dims.iter().map(|d| self.axis_kind(&d.name())).collect();
+        let find = |kind| kinds.iter().position(|k| *k == Some(kind));
+
+        let (y, x) = match (find(AxisKind::Y), find(AxisKind::X)) {
+            (Some(y), Some(x)) => (y, x),
+            // Unmarked trailing dimensions (not e.g. time bounds)
+            (None, None)
+                if dims.len() >= 2 && kinds[dims.len() - 2..].iter().all(Option::is_none) =>
+            {
+                (dims.len() - 2, dims.len() - 1)
+            }
+            _ => {
+                return Err(NetCdfError::MissingData(format!(
+                    "x and y dimensions of {}",
+                    var.name()
+                )))
+            }
+        };
+
+        if y > x {
+            return Err(NetCdfError::InvalidFormat(format!(
+                "{} has its x dimension before y; only (..., y, x) layouts are supported",
+                var.name()
+            )));
+        }
+        Ok((y, x))
+    }
+
+    /// Axis kind of a dimension, from its coordinate variable.
+    fn axis_kind(&self, dim_name: &str) -> Option<AxisKind> {
+        let coord = self.file.variable(dim_name)?;
+        AxisKind::from_attributes(
+            get_str_attr(&coord, "axis").as_deref(),
+            get_str_attr(&coord, "standard_name").as_deref(),
+            get_str_attr(&coord, "units").as_deref(),
+            get_str_attr(&coord, "positive").as_deref(),
+        )
+    }
+
+    /// A dimension with the values of its coordinate variable, if any.
+    fn axis(&self, dim_name: &str) -> CfAxis {
+        let coord = self.file.variable(dim_name);
+        CfAxis {
+            name: dim_name.to_string(),
+            kind: self.axis_kind(dim_name),
+            values: coord
+                .as_ref()
+                .and_then(|c| c.get_values::<f64, _>(..).ok())
+                .unwrap_or_default(),
+            units: coord.as_ref().and_then(|c| get_str_attr(c, "units")),
+        }
+    }
+
+    /// Read the grid mapping named by a `grid_mapping` attribute.
+    ///
+    /// The extended form (`"crs: x y"`) names the mapping before the colon.
+    fn grid_mapping(&self, attribute: &str) -> NetCdfResult<GridMapping> {
+        let variable = attribute
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let var = self.file.variable(&variable).ok_or_else(|| {
+            NetCdfError::MissingData(format!("grid mapping variable {}", variable))
+        })?;
+        let name = get_str_attr(&var, "grid_mapping_name").ok_or_else(|| {
+            NetCdfError::MissingData(format!("grid_mapping_name of {}", variable))
+        })?;
+
+        let parameters = var
+            .attributes()
+            .filter_map(|attr| {
+                let values = attr.value().ok().and_then(attribute_f64s)?;
+                Some((attr.name().to_string(), values))
+            })
+            .collect();
+
+        let crs_wkt = ["crs_wkt", "spatial_ref"]
+            .iter()
+            .find(|n| has_attr(&var, n))
+            .and_then(|n| get_str_attr(&var, n));
+
+        Ok(GridMapping {
+            variable,
+            name,
+            parameters,
+            crs_wkt,
+        })
+    }
+}
+
+impl Drop for CfFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.temp_file);
+    }
+}
+
+/// Numeric attribute values as f64, scalar or array; None for strings.
+fn attribute_f64s(value: AttributeValue) -> Option<Vec<f64>> {
+    fn all<T: Into<f64>>(values: Vec<T>) -> Option<Vec<f64>> {
+        Some(values.into_iter().map(Into::into).collect())
+    }
+
+    match value {
+        AttributeValue::Doubles(v) => all(v),
+        AttributeValue::Floats(v) => all(v),
+        AttributeValue::Ints(v) => all(v),
+        AttributeValue::Uints(v) => all(v),
+        AttributeValue::Shorts(v) => all(v),
+        AttributeValue::Ushorts(v) => all(v),
+        AttributeValue::Schars(v) => all(v),
+        AttributeValue::Uchars(v) => all(v),
+        AttributeValue::Str(_) | AttributeValue::Strs(_) => None,
+        scalar => f64::try_from(scalar).ok().map(|v| vec![v]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis_kind_from_attributes() {
+        let kind = AxisKind::from_attributes;
+        assert_eq!(kind(Some("X"), None, None, None), Some(AxisKind::X));
+        // axis wins over conflicting hints
+        assert_eq!(
+            kind(Some("Y"), Some("longitude"), None, None),
+            Some(AxisKind::Y)
+        );
+        assert_eq!(
+            kind(None, Some("projection_x_coordinate"), Some("m"), None),
+            Some(AxisKind::X)
+        );
+        assert_eq!(
+            kind(None, None, Some("degrees_north"), None),
+            Some(AxisKind::Y)
+        );
+        assert_eq!(
+            kind(None, None, Some("hours since 2024-01-01 00:00:00"), None),
+            Some(AxisKind::T)
+        );
+        assert_eq!(kind(None, None, Some("m"), Some("up")), Some(AxisKind::Z));
+        assert_eq!(kind(None, None, Some("m"), None), None);
+    }
+
+    #[test]
+    fn test_packing_unpack() {
+        let packing = Packing {
+            scale_factor: 0.01,
+            add_offset: 273.15,
+            fill_value: Some(-32767.0),
+            missing_value: Some(-32768.0),
+        };
+        assert!((packing.unpack(1000.0) - 283.15).abs() < 1e-4);
+        assert!(packing.unpack(-32767.0).is_nan());
+        assert!(packing.unpack(-32768.0).is_nan());
+        assert!(packing.unpack(f64::NAN).is_nan());
+
+        assert_eq!(Packing::default().unpack(42.5), 42.5);
+    }
+
+    #[test]
+    fn test_attribute_f64s() {
+        assert_eq!(
+            attribute_f64s(AttributeValue::Doubles(vec![25.0, 25.0])),
+            Some(vec![25.0, 25.0])
+        );
+        assert_eq!(
+            attribute_f64s(AttributeValue::Float(-95.0)),
+            Some(vec![-95.0])
+        );
+        assert_eq!(attribute_f64s(AttributeValue::Str("x".into())), None);
+    }
+
+    #[test]
+    fn test_grid_mapping_parameter() {
+        let mapping = GridMapping {
+            variable: "LambertConformal_Projection".to_string(),
+            name: "lambert_conformal_conic".to_string(),
+            parameters: BTreeMap::from([("standard_parallel".to_string(), vec![25.0, 25.0])]),
+            crs_wkt: None,
+        };
+        assert_eq!(mapping.parameter("standard_parallel"), Some(25.0));
+        assert_eq!(mapping.parameter("missing"), None);
+        assert!(!mapping.is_geographic());
+    }
+}
@@ -0,0 +1,320 @@
+//! Multi-band composites of GOES ABI bands.
+//!
+//! ABI has no green band, so a natural color ("true color") image is built
+//! from the blue (C01, 0.47µm) and red (C02, 0.64µm) bands and a green
+//! synthesized from them plus the "veggie" near-IR band (C03, 0.86µm), which
+//! stands in for the green reflectance of vegetation:
+//!
+//! ```text
+//! green = 0.45 * red + 0.10 * veggie + 0.45 * blue
+//! ```
+//!
+//! Before mixing, an estimate of the Rayleigh scattering path reflectance is
+//! removed from each band; left in, it washes the whole image out in blue
+//! haze. The estimate is a fixed offset per band rather than a radiative
+//! transfer correction using the sun and view angles, so the limb stays
+//! hazier than the disk center. The channels are then gamma corrected for
+//! display and packed into one grid with [`pack_rgb`]:
+//!
+//! ```rust,ignore
+//! use netcdf_parser::composite::{pack_rgb, true_color, TrueColorParams};
+//!
+//! let rgb = true_color(&c01, &c02, &c03, &TrueColorParams::default())?;
+//! let packed: Vec<f32> = (0..rgb.red.data.len())
+//!     .map(|i| pack_rgb(rgb.red.data[i], rgb.green.data[i], rgb.blue.data[i]))
+//!     .collect();
+//! ```
+
+use crate::error::{NetCdfError, NetCdfResult};
+use crate::native::GoesBand;
+
+/// Bands read by [`true_color`]: blue, red and veggie.
+pub const TRUE_COLOR_BANDS: [u8; 3] = [1, 2, 3];
+
+/// Tuning of the true color composite.
+///
+/// Both arrays are indexed by band: C01 (blue), C02 (red), C03 (veggie).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrueColorParams {
+    /// Weight of each band in the synthesized green
+    pub green_weights: [f32; 3],
+    /// Rayleigh path reflectance removed from each band
+    pub rayleigh: [f32; 3],
+    /// Display gamma of the output channels (1.0 for linear reflectance)
+    pub gamma: f32,
+}
+
+impl Default for TrueColorParams {
+    /// CIMSS green weights, with path reflectances typical of a mid-latitude
+    /// view at moderate sun angles.
+    fn default() -> Self {
+        Self {
+            green_weights: [0.45, 0.45, 0.10],
+            rayleigh: [0.06, 0.02, 0.0],
+            gamma: 2.2,
+        }
+    }
+}
+
+/// Red, green and blue channels of a composite, each in `[0, 1]` (NaN where
+/// a band has no data) on the scan geometry of the coarsest input band.
+#[derive(Debug, Clone)]
+pub struct RgbBands {
+    pub red: GoesBand,
+    pub green: GoesBand,
+    pub blue: GoesBand,
+}
+
+/// Build a true color composite from bands C01, C02 and C03 of one scan.
+///
+/// Bands finer than the coarsest one (C02 has twice the resolution of C01
+/// and C03) are block averaged down to its grid, so their dimensions must be
+/// whole multiples of it.
+pub fn true_color(
+    blue: &GoesBand,
+    red: &GoesBand,
+    veggie: &GoesBand,
+    params: &TrueColorParams,
+) -> NetCdfResult<RgbBands> {
+    let bands = [blue, red, veggie];
+    for (band, expected) in bands.iter().zip(TRUE_COLOR_BANDS) {
+        if band.band != expected {
+            return Err(NetCdfError::InvalidFormat(format!(
+                "true color expects bands {:?}, got band {} in place of {}",
+                TRUE_COLOR_BANDS, band.band, expected
+            )));
+        }
+    }
+
+    let target = bands
+        .iter()
+        .min_by_key(|b| b.width)
+        .copied()
+        .unwrap_or(blue);
+    let [b, r, v] = [
+        block_mean(blue, target.width, target.height)?,
+        block_mean(red, target.width, target.height)?,
+        block_mean(veggie, target.width, target.height)?,
+    ];
+
+    let gamma = if params.gamma > 0.0 {
+        params.gamma
+    } else {
+        1.0
+    };
+    let display = |value: f32| value.clamp(0.0, 1.0).powf(1.0 / gamma);
+    let [w_blue, w_red, w_veggie] = params.green_weights;
+
+    let len = target.width * target.height;
+    let mut channels = [
+        Vec::with_capacity(len),
+        Vec::with_capacity(len),
+        Vec::with_capacity(len),
+    ];
+    for i in 0..len {
+        let blue = remove_path_reflectance(b[i], params.rayleigh[0]);
+        let red = remove_path_reflectance(r[i], params.rayleigh[1]);
+        let veggie = remove_path_reflectance(v[i], params.rayleigh[2]);
+        let green = w_red * red + w_veggie * veggie + w_blue * blue;
+
+        channels[0].push(display(red));
+        channels[1].push(display(green));
+        channels[2].push(display(blue));
+    }
+
+    let [red, green, blue] = channels.map(|data| GoesBand {
+        band: 0,
+        data,
+        width: target.width,
+        height: target.height,
+        scale_factor: 1.0,
+        add_offset: 0.0,
+        attributes: target.attributes.clone(),
+        x_offset: target.x_offset,
+        y_offset: target.y_offset,
+        x_scale: target.x_scale,
+        y_scale: target.y_scale,
+    });
+    Ok(RgbBands { red, green, blue })
+}
+
+/// Pack red, green and blue values in `[0, 1]` into one grid value.
+///
+/// Each channel is quantized to 8 bits and the value is the integer
+/// `0xRRGGBB`, which an f32 holds exactly. Any NaN channel gives NaN.
+/// Packed grids must only be resampled with nearest neighbour (or unpacked
+/// first with [`unpack_rgb`]), since interpolating mixes the channels.
+pub fn pack_rgb(red: f32, green: f32, blue: f32) -> f32 {
+    if red.is_nan() || green.is_nan() || blue.is_nan() {
+        return f32::NAN;
+    }
+    let quantize = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u32;
+    ((quantize(red) << 16) | (quantize(green) << 8) | quantize(blue)) as f32
+}
+
+/// Unpack a value written by [`pack_rgb`] into red, green and blue in
+/// `[0, 1]`. Returns None for NaN or values that are not packed colors.
+pub fn unpack_rgb(packed: f32) -> Option<[f32; 3]> {
+    if !(0.0..=16_777_215.0).contains(&packed) || packed.fract() != 0.0 {
+        return None;
+    }
+    let packed = packed as u32;
+    Some([16, 8, 0].map(|shift| ((packed >> shift) & 0xff) as f32 / 255.0))
+}
+
+/// Remove a path reflectance and rescale so a reflectance of 1 stays 1.
+/// NaN (no data) is kept.
+fn remove_path_reflectance(reflectance: f32, path: f32) -> f32 {
+    let corrected = (reflectance - path) / (1.0 - path);
+    if corrected < 0.0 {
+        0.0
+    } else {
+        corrected
+    }
+}
+
+/// Average a band down to `width` x `height`, ignoring NaN cells.
+fn block_mean(band: &GoesBand, width: usize, height: usize) -> NetCdfResult<Vec<f32>> {
+    if width == 0
+        || height == 0
+        || !band.width.is_multiple_of(width)
+        || !band.height.is_multiple_of(height)
+    {
+        return Err(NetCdfError::InvalidFormat(format!(
+            "band {} is {}x{}, not a multiple of the {}x{} composite grid",
+            band.band, band.width, band.height, width, height
+        )));
+    }
+    let (fx, fy) = (band.width / width, band.height / height);
+    if fx == 1 && fy == 1 {
+        return Ok(band.data.clone());
+    }
+
+    let mut out = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            let (mut sum, mut count) = (0.0f32, 0u32);
+            for row in y * fy..(y + 1) * fy {
+                let start = row * band.width + x * fx;
+                for &v in &band.data[start..start + fx] {
+                    if !v.is_nan() {
+                        sum += v;
+                        count += 1;
+                    }
+                }
+            }
+            out.push(if count > 0 {
+                sum / count as f32
+            } else {
+                f32::NAN
+            });
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::satellite::GoesAttributes;
+
+    fn band(band: u8, width: usize, height: usize, data: Vec<f32>) -> GoesBand {
+        GoesBand {
+            band,
+            data,
+            width,
+            height,
+            scale_factor: 1.0,
+            add_offset: 0.0,
+            attributes: GoesAttributes::default(),
+            x_offset: -0.1,
+            y_offset: 0.1,
+            x_scale: 2.8e-5,
+            y_scale: -2.8e-5,
+        }
+    }
+
+    #[test]
+    fn test_pack_rgb_roundtrip() {
+        assert_eq!(pack_rgb(1.0, 0.0, 0.0), 16_711_680.0);
+        assert_eq!(pack_rgb(0.0, 0.0, 1.0), 255.0);
+        assert!(pack_rgb(0.5, f32::NAN, 0.5).is_nan());
+
+        let [r, g, b] = unpack_rgb(pack_rgb(0.2, 0.4, 0.6)).unwrap();
+        assert!((r - 0.2).abs() < 0.5 / 255.0);
+        assert!((g - 0.4).abs() < 0.5 / 255.0);
+        assert!((b - 0.6).abs() < 0.5 / 255.0);
+
+        assert_eq!(unpack_rgb(f32::NAN), None);
+        assert_eq!(unpack_rgb(-1.0), None);
+        assert_eq!(unpack_rgb(0.5), None);
+    }
+
+    #[test]
+    fn test_true_color_synthesizes_green() {
+        let params = TrueColorParams {
+            rayleigh: [0.0; 3],
+            gamma: 1.0,
+            ..Default::default()
+        };
+        let rgb = true_color(
+            &band(1, 1, 1, vec![0.2]),
+            &band(2, 1, 1, vec![0.4]),
+            &band(3, 1, 1, vec![0.8]),
+            &params,
+        )
+        .unwrap();
+
+        assert!((rgb.red.data[0] - 0.4).abs() < 1e-6);
+        assert!((rgb.blue.data[0] - 0.2).abs() < 1e-6);
+        let green = 0.45 * 0.4 + 0.10 * 0.8 + 0.45 * 0.2;
+        assert!((rgb.green.data[0] - green).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_true_color_rayleigh_and_gamma() {
+        let rgb = true_color(
+            &band(1, 1, 1, vec![0.03]),
+            &band(2, 1, 1, vec![1.0]),
+            &band(3, 1, 1, vec![0.0]),
+            &TrueColorParams::default(),
+        )
+        .unwrap();
+        // Blue below its path reflectance is black, bright red stays bright
+        assert_eq!(rgb.blue.data[0], 0.0);
+        assert!((rgb.red.data[0] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_true_color_averages_red_to_coarse_grid() {
+        // 2x1 blue/veggie grid, 4x2 red grid
+        let red = vec![0.2, 0.4, 0.6, f32::NAN, 0.2, 0.4, 0.6, 0.6];
+        let rgb = true_color(
+            &band(1, 2, 1, vec![0.1, f32::NAN]),
+            &band(2, 4, 2, red),
+            &band(3, 2, 1, vec![0.5, 0.5]),
+            &TrueColorParams {
+                rayleigh: [0.0; 3],
+                gamma: 1.0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!((rgb.red.width, rgb.red.height), (2, 1));
+        assert_eq!(rgb.red.x_scale, 2.8e-5);
+        assert!((rgb.red.data[0] - 0.3).abs() < 1e-6);
+        // NaN cells are left out of the average
+        assert!((rgb.red.data[1] - 0.6).abs() < 1e-6);
+        // A missing band leaves a gap
+        assert!(rgb.green.data[1].is_nan());
+    }
+
+    #[test]
+    fn test_true_color_rejects_mismatched_bands() {
+        let params = TrueColorParams::default();
+        let one = |b: u8| band(b, 3, 1, vec![0.1; 3]);
+        assert!(true_color(&one(2), &one(1), &one(3), &params).is_err());
+        assert!(true_color(&one(1), &band(2, 4, 1, vec![0.1; 4]), &one(3), &params).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+//! GOES GLM (Geostationary Lightning Mapper) L2 LCFA point data.
+//!
+//! A GLM L2 "Lightning Cluster-Filter Algorithm" file covers 20 seconds and
+//! lists lightning at three levels of detail:
+//!
+//! - **events**: single pixel detections in one 2 ms frame
+//! - **groups**: adjacent events in the same frame
+//! - **flashes**: groups close in space and time
+//!
+//! Each level has `<feature>_lat`, `<feature>_lon`, `<feature>_energy` and a
+//! time offset variable, most of them stored as packed unsigned shorts.
+//! [`GlmFile::points`] unpacks them into [`LightningPoint`]s:
+//!
+//! ```rust,ignore
+//! use netcdf_parser::glm::{GlmFeature, GlmFile};
+//!
+//! let file = GlmFile::from_bytes(&bytes)?;
+//! for flash in file.points(GlmFeature::Flash)? {
+//!     println!("{} {:.3},{:.3} {:e} J", flash.time, flash.lat, flash.lon, flash.energy);
+//! }
+//! ```
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+
+use crate::cf::Packing;
+use crate::error::{NetCdfError, NetCdfResult};
+use crate::native::{get_f64_attr, get_global_str_attr, get_str_attr, open_from_bytes};
+
+/// Level of detail of GLM lightning features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlmFeature {
+    /// Flashes (clusters of groups)
+    Flash,
+    /// Groups (clusters of events in one frame)
+    Group,
+    /// Events (single pixel detections)
+    Event,
+}
+
+impl GlmFeature {
+    /// Prefix of the feature's variable names.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            GlmFeature::Flash => "flash",
+            GlmFeature::Group => "group",
+            GlmFeature::Event => "event",
+        }
+    }
+
+    /// Variable holding each feature's time, as an offset from the time in
+    /// its `units`.
+    fn time_variable(&self) -> &'static str {
+        match self {
+            GlmFeature::Flash => "flash_time_offset_of_first_event",
+            GlmFeature::Group => "group_time_offset",
+            GlmFeature::Event => "event_time_offset",
+        }
+    }
+}
+
+/// One lightning flash, group or event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightningPoint {
+    /// Latitude (degrees north)
+    pub lat: f64,
+    /// Longitude (degrees east)
+    pub lon: f64,
+    /// Radiant energy (J), NaN if not reported
+    pub energy: f64,
+    /// Time of the feature (the first event, for flashes)
+    pub time: DateTime<Utc>,
+}
+
+/// A GOES GLM L2 LCFA NetCDF file opened from bytes.
+pub struct GlmFile {
+    file: netcdf::File,
+    temp_file: PathBuf,
+}
+
+impl GlmFile {
+    /// Open a GLM NetCDF file from bytes.
+    pub fn from_bytes(data: &[u8]) -> NetCdfResult<Self> {
+        let (file, temp_file) = open_from_bytes(data)?;
+        Ok(Self { file, temp_file })
+    }
+
+    /// The global `platform_ID` attribute, e.g. "G18".
+    pub fn platform_id(&self) -> Option<String> {
+        get_global_str_attr(&self.file, "platform_ID")
+    }
+
+    /// Start and end of the period the file covers.
+    pub fn time_coverage(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let parse = |name: &str| {
+            DateTime::parse_from_rfc3339(&get_global_str_attr(&self.file, name)?)
+                .ok()
+                .map(|t| t.with_timezone(&Utc))
+        };
+        Some((parse("time_coverage_start")?, parse("time_coverage_end")?))
+    }
+
+    /// Read every flash, group or event with a valid position and time.
+    pub fn points(&self, feature: GlmFeature) -> NetCdfResult<Vec<LightningPoint>> {
+        let prefix = feature.prefix();
+        let lat = self.read_packed(&format!("{}_lat", prefix))?;
+        let lon = self.read_packed(&format!("{}_lon", prefix))?;
+        let energy = self.read_packed(&format!("{}_energy", prefix))?;
+        let offsets = self.read_packed(feature.time_variable())?;
+
+        let time_var = self.variable(feature.time_variable())?;
+        let base_time = get_str_attr(&time_var, "units")
+            .as_deref()
+            .and_then(parse_seconds_since)
+            .ok_or_else(|| {
+                NetCdfError::InvalidFormat(format!(
+                    "{} units must be \"seconds since <time>\"",
+                    feature.time_variable()
+                ))
+            })?;
+
+        if [lon.len(), energy.len(), offsets.len()] != [lat.len(); 3] {
+            return Err(NetCdfError::InvalidFormat(format!(
+                "{} variables have different lengths",
+                prefix
+            )));
+        }
+
+        Ok((0..lat.len())
+            .filter(|&i| lat[i].is_finite() && lon[i].is_finite() && offsets[i].is_finite())
+            .map(|i| LightningPoint {
+                lat: lat[i],
+                lon: lon[i],
+                energy: energy[i],
+                time: base_time + Duration::microseconds((offsets[i] * 1e6).round() as i64),
+            })
+            .collect())
+    }
+
+    fn variable(&self, name: &str) -> NetCdfResult<netcdf::Variable<'_>> {
+        self.file
+            .variable(name)
+            .ok_or_else(|| NetCdfError::MissingData(format!("{} variable", name)))
+    }
+
+    /// Read a 1-D variable, unpacking it with its `scale_factor`,
+    /// `add_offset` and `_Unsigned` attributes.
+    fn read_packed(&self, name: &str) -> NetCdfResult<Vec<f64>> {
+        let var = self.variable(name)?;
+        let raw: Vec<f64> = var
+            .get_values(..)
+            .map_err(|e| NetCdfError::InvalidFormat(format!("Failed to read {}: {}", name, e)))?;
+
+        let packing = Packing {
+            scale_factor: get_f64_attr(&var, "scale_factor").unwrap_or(1.0),
+            add_offset: get_f64_attr(&var, "add_offset").unwrap_or(0.0),
+            fill_value: get_f64_attr(&var, "_FillValue"),
+            missing_value: get_f64_attr(&var, "missing_value"),
+        };
+        let unsigned = get_str_attr(&var, "_Unsigned").as_deref() == Some("true");
+        Ok(raw
+            .iter()
+            .map(|&v| unpack_short(&packing, unsigned, v))
+            .collect())
+    }
+}
+
+impl Drop for GlmFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.temp_file);
+    }
+}
+
+/// Read all flashes, groups or events from a GLM L2 LCFA file.
+pub fn load_glm_points(data: &[u8], feature: GlmFeature) -> NetCdfResult<Vec<LightningPoint>> {
+    GlmFile::from_bytes(data)?.points(feature)
+}
+
+/// Unpack a stored short. Fill values are compared before the `_Unsigned`
+/// reinterpretation, since GLM stores them as signed (usually -1).
+fn unpack_short(packing: &Packing, unsigned: bool, raw: f64) -> f64 {
+    if raw.is_nan() || Some(raw) == packing.fill_value || Some(raw) == packing.missing_value {
+        return f64::NAN;
+    }
+    let stored = if unsigned && raw < 0.0 {
+        raw + 65536.0
+    } else {
+        raw
+    };
+    stored * packing.scale_factor + packing.add_offset
+}
+
+/// Parse "seconds since 2024-06-01 12:00:00.000" time units.
+fn parse_seconds_since(units: &str) -> Option<DateTime<Utc>> {
+    let since = units.trim().strip_prefix("seconds since ")?.trim();
+    let since = since.trim_end_matches('Z').replace('T', " ");
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&since, format).ok())
+        .map(|t| t.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_feature_variables() {
+        assert_eq!(GlmFeature::Flash.prefix(), "flash");
+        assert_eq!(
+            GlmFeature::Flash.time_variable(),
+            "flash_time_offset_of_first_event"
+        );
+        assert_eq!(GlmFeature::Group.time_variable(), "group_time_offset");
+        assert_eq!(GlmFeature::Event.time_variable(), "event_time_offset");
+    }
+
+    #[test]
+    fn test_unpack_unsigned_short() {
+        // event_lat as written by the GLM ground system
+        let packing = Packing {
+            scale_factor: 0.00203128,
+            add_offset: -66.56,
+            fill_value: Some(-1.0),
+            missing_value: None,
+        };
+        assert!(unpack_short(&packing, true, -1.0).is_nan());
+        // 0x8000 stored as a signed short
+        let lat = unpack_short(&packing, true, -32768.0);
+        assert!((lat - (32768.0 * 0.00203128 - 66.56)).abs() < 1e-9);
+        assert!((unpack_short(&packing, false, 100.0) - (100.0 * 0.00203128 - 66.56)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parse_seconds_since() {
+        let expected = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 20).unwrap();
+        assert_eq!(
+            parse_seconds_since("seconds since 2024-06-01 12:00:20.000"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_seconds_since("seconds since 2024-06-01T12:00:20Z"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_seconds_since("seconds since 2024-06-01 12:00:20.500"),
+            Some(expected + Duration::milliseconds(500))
+        );
+        assert_eq!(parse_seconds_since("hours since 2024-06-01 12:00:00"), None);
+        assert_eq!(parse_seconds_since("1"), None);
+    }
+}
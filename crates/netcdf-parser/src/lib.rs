@@ -7,27 +7,49 @@
 //!
 //! - **Native parsing**: High-performance reading using the `netcdf` library
 //! - **Geostationary projection**: Convert between scan angles and lat/lon
+//! - **Satellite registry**: Config-driven satellite positions, matched
+//!   against file attributes
+//! - **CF grids**: Any CF-1.x gridded variable (e.g. NDFD, RTMA), not just
+//!   GOES ABI
+//! - **GLM lightning**: Flash, group and event points from GOES GLM L2 files
+//! - **Composites**: True color from ABI bands 1-3, with synthesized green
 //!
 //! # GOES-R ABI Data Structure
 //!
 //! GOES-R ABI files use the geostationary projection with coordinates in radians.
 //! The main data variable is `CMI` (Cloud and Moisture Imagery) which contains
 //! either reflectance factors (bands 1-6) or brightness temperatures (bands 7-16).
+//! Multi-band MCMIP files carry one `CMI_Cnn` variable per band instead; read
+//! them with [`load_goes_band`] or [`GoesFile`].
 //!
 //! # Module Structure
 //!
+//! - [`cf`] - Generic reader for CF-1.x gridded variables
+//! - [`composite`] - Multi-band composites (true color)
 //! - [`error`] - Error types and result alias
+//! - [`glm`] - GOES GLM lightning point data
 //! - [`projection`] - Geostationary coordinate transformations
+//! - [`satellite`] - Satellite registry and file projection attributes
 //! - [`native`] - High-performance netcdf library parsing
 
+pub mod cf;
+pub mod composite;
 pub mod error;
+pub mod glm;
 pub mod native;
 pub mod projection;
+pub mod satellite;
 
 // Re-export commonly used items at crate root
+pub use cf::{CfFile, CfGrid};
+pub use composite::{pack_rgb, true_color, unpack_rgb, RgbBands, TrueColorParams};
 pub use error::{NetCdfError, NetCdfResult};
-pub use native::{load_goes_netcdf_from_bytes, silence_hdf5_errors};
+pub use glm::{load_glm_points, GlmFeature, GlmFile, LightningPoint};
+pub use native::{
+    load_goes_band, load_goes_netcdf_from_bytes, silence_hdf5_errors, GoesBand, GoesFile,
+};
 pub use projection::GoesProjection;
+pub use satellite::{GoesAttributes, Satellite, SatelliteRegistry};
 
 #[cfg(test)]
 mod tests {
@@ -35,7 +57,7 @@ mod tests {
 
     #[test]
     fn test_goes_projection_roundtrip() {
-        let proj = GoesProjection::goes16();
+        let proj = GoesProjection::default();
 
         // Test a point near the center of CONUS
         let (lon, lat) = (-95.0, 35.0);
@@ -65,7 +87,7 @@ mod tests {
 
     #[test]
     fn test_goes_projection_off_earth() {
-        let proj = GoesProjection::goes16();
+        let proj = GoesProjection::default();
 
         // A point that should be off Earth (large scan angle)
         let result = proj.to_geographic(0.5, 0.5); // ~28 degrees
@@ -20,7 +20,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Once;
 
 use crate::error::{NetCdfError, NetCdfResult};
-use crate::projection::GoesProjection;
+use crate::satellite::GoesAttributes;
 
 /// Silence HDF5's automatic error printing to stderr.
 ///
@@ -55,21 +55,238 @@ pub fn silence_hdf5_errors() {
     });
 }
 
+/// One band of a GOES ABI file: scaled values plus the scan geometry needed
+/// to reproject them.
+#[derive(Debug, Clone)]
+pub struct GoesBand {
+    /// ABI band number (1-16), or 0 if the file doesn't say
+    pub band: u8,
+    /// Scaled values (reflectance or brightness temperature), NaN for fill
+    pub data: Vec<f32>,
+    /// Grid width (x dimension)
+    pub width: usize,
+    /// Grid height (y dimension)
+    pub height: usize,
+    /// Scale factor applied to this band's packed values
+    pub scale_factor: f32,
+    /// Offset applied to this band's packed values
+    pub add_offset: f32,
+    /// Projection attributes, to be resolved against the
+    /// [`SatelliteRegistry`](crate::SatelliteRegistry)
+    pub attributes: GoesAttributes,
+    /// Scan angle offset of the x axis (radians)
+    pub x_offset: f32,
+    /// Scan angle offset of the y axis (radians)
+    pub y_offset: f32,
+    /// Scan angle scale factor of the x axis (radians/pixel)
+    pub x_scale: f32,
+    /// Scan angle scale factor of the y axis (radians/pixel)
+    pub y_scale: f32,
+}
+
+/// A GOES ABI NetCDF file opened from bytes.
+///
+/// Reads both single-band CMIP files, whose data is in `CMI`, and multi-band
+/// MCMIP files, which hold one `CMI_Cnn` variable per band. The bytes are
+/// written to a temp file once, so several bands can be read from one open.
+pub struct GoesFile {
+    file: netcdf::File,
+    temp_file: PathBuf,
+}
+
+impl GoesFile {
+    /// Open a GOES NetCDF file from bytes.
+    pub fn from_bytes(data: &[u8]) -> NetCdfResult<Self> {
+        let (file, temp_file) = open_from_bytes(data)?;
+        Ok(Self { file, temp_file })
+    }
+
+    /// Bands present in the file, in ascending order.
+    ///
+    /// For a single-band file this is the band in its `band_id` variable.
+    pub fn bands(&self) -> Vec<u8> {
+        let bands: Vec<u8> = (1..=16)
+            .filter(|&band| self.file.variable(&band_variable_name(band)).is_some())
+            .collect();
+        if bands.is_empty() {
+            return self.single_band_id().into_iter().collect();
+        }
+        bands
+    }
+
+    /// Read one band, scaled with that band's own scale factor and offset.
+    pub fn band(&self, band: u8) -> NetCdfResult<GoesBand> {
+        let name = band_variable_name(band);
+        if self.file.variable(&name).is_some() {
+            return self.read_band(&name, band);
+        }
+        if self.file.variable("CMI").is_some() && self.single_band_id() == Some(band) {
+            return self.read_band("CMI", band);
+        }
+        Err(NetCdfError::MissingData(format!(
+            "band {} (no {} variable, file has bands {:?})",
+            band,
+            name,
+            self.bands()
+        )))
+    }
+
+    /// Band number of a single-band file, from its `band_id` variable.
+    fn single_band_id(&self) -> Option<u8> {
+        let ids: Vec<i8> = self.file.variable("band_id")?.get_values(..).ok()?;
+        u8::try_from(*ids.first()?).ok()
+    }
+
+    /// Read and scale the data variable `name` along with the file's geometry.
+    fn read_band(&self, name: &str, band: u8) -> NetCdfResult<GoesBand> {
+        let nc_file = &self.file;
+
+        // Get dimensions
+        let width = nc_file
+            .dimension("x")
+            .ok_or_else(|| NetCdfError::MissingData("x dimension".to_string()))?
+            .len();
+        let height = nc_file
+            .dimension("y")
+            .ok_or_else(|| NetCdfError::MissingData("y dimension".to_string()))?
+            .len();
+
+        // Get the data variable and its data
+        let cmi_var = nc_file
+            .variable(name)
+            .ok_or_else(|| NetCdfError::MissingData(format!("{} variable", name)))?;
+
+        // Read raw data as i16 using (..) to read all extents
+        let raw_data: Vec<i16> = cmi_var
+            .get_values(..)
+            .map_err(|e| NetCdfError::InvalidFormat(format!("Failed to read {}: {}", name, e)))?;
+
+        // Get attributes using get_value helper
+        let scale_factor = get_f32_attr(&cmi_var, "scale_factor").unwrap_or(1.0);
+        let add_offset = get_f32_attr(&cmi_var, "add_offset").unwrap_or(0.0);
+        let fill_value = get_i16_attr(&cmi_var, "_FillValue").unwrap_or(-1);
+
+        // Apply scale and offset
+        let data: Vec<f32> = raw_data
+            .iter()
+            .map(|&val| {
+                if val == fill_value {
+                    f32::NAN
+                } else {
+                    val as f32 * scale_factor + add_offset
+                }
+            })
+            .collect();
+
+        // Get coordinate attributes
+        let x_var = nc_file
+            .variable("x")
+            .ok_or_else(|| NetCdfError::MissingData("x variable".to_string()))?;
+        let x_scale = get_f32_attr(&x_var, "scale_factor").unwrap_or(1.4e-05);
+        let x_offset = get_f32_attr(&x_var, "add_offset").unwrap_or(-0.101353);
+
+        let y_var = nc_file
+            .variable("y")
+            .ok_or_else(|| NetCdfError::MissingData("y variable".to_string()))?;
+        let y_scale = get_f32_attr(&y_var, "scale_factor").unwrap_or(-1.4e-05);
+        let y_offset = get_f32_attr(&y_var, "add_offset").unwrap_or(0.128233);
+
+        // Get projection attributes. GOES files call the projection variable
+        // goes_imager_projection; others (e.g. Himawari) only name it in the
+        // data variable's CF grid_mapping attribute.
+        let proj_name = get_str_attr(&cmi_var, "grid_mapping")
+            .unwrap_or_else(|| "goes_imager_projection".to_string());
+        let proj_var = nc_file
+            .variable(&proj_name)
+            .ok_or_else(|| NetCdfError::MissingData(format!("{} variable", proj_name)))?;
+
+        let attributes = GoesAttributes {
+            platform_id: get_global_str_attr(nc_file, "platform_ID"),
+            perspective_point_height: get_f64_attr(&proj_var, "perspective_point_height"),
+            semi_major_axis: get_f64_attr(&proj_var, "semi_major_axis"),
+            semi_minor_axis: get_f64_attr(&proj_var, "semi_minor_axis"),
+            longitude_origin: get_f64_attr(&proj_var, "longitude_of_projection_origin"),
+            sweep_angle_axis: get_str_attr(&proj_var, "sweep_angle_axis"),
+        };
+
+        Ok(GoesBand {
+            band,
+            data,
+            width,
+            height,
+            scale_factor,
+            add_offset,
+            attributes,
+            x_offset,
+            y_offset,
+            x_scale,
+            y_scale,
+        })
+    }
+}
+
+impl Drop for GoesFile {
+    fn drop(&mut self) {
+        // Clean up
+        let _ = std::fs::remove_file(&self.temp_file);
+    }
+}
+
+/// Load one band of a GOES NetCDF file, single-band (CMIP) or multi-band
+/// (MCMIP), from bytes.
+///
+/// To read several bands of the same file, open it once with
+/// [`GoesFile::from_bytes`] instead.
+pub fn load_goes_band(data: &[u8], band: u8) -> NetCdfResult<GoesBand> {
+    GoesFile::from_bytes(data)?.band(band)
+}
+
 /// Load GOES NetCDF data directly from bytes using native netcdf library.
 ///
-/// This is much faster than using ncdump subprocess.
+/// This is much faster than using ncdump subprocess. Reads the `CMI` variable
+/// of a single-band file; use [`load_goes_band`] for multi-band files.
 ///
 /// # Returns
 ///
-/// A tuple of `(data, width, height, projection, x_offset, y_offset, x_scale, y_scale)`:
+/// A tuple of `(data, width, height, attributes, x_offset, y_offset, x_scale, y_scale)`:
 /// - `data`: Scaled CMI values (reflectance or brightness temperature)
 /// - `width`, `height`: Grid dimensions
-/// - `projection`: GOES projection parameters
+/// - `attributes`: Projection attributes, to be resolved against the
+///   [`SatelliteRegistry`](crate::SatelliteRegistry)
 /// - `x_offset`, `y_offset`: Scan angle offsets (radians)
 /// - `x_scale`, `y_scale`: Scan angle scale factors (radians/pixel)
 pub fn load_goes_netcdf_from_bytes(
     data: &[u8],
-) -> NetCdfResult<(Vec<f32>, usize, usize, GoesProjection, f32, f32, f32, f32)> {
+) -> NetCdfResult<(Vec<f32>, usize, usize, GoesAttributes, f32, f32, f32, f32)> {
+    let file = GoesFile::from_bytes(data)?;
+    let band = file.read_band("CMI", file.single_band_id().unwrap_or(0))?;
+
+    Ok((
+        band.data,
+        band.width,
+        band.height,
+        band.attributes,
+        band.x_offset,
+        band.y_offset,
+        band.x_scale,
+        band.y_scale,
+    ))
+}
+
+/// Name of a band's data variable in a multi-band (MCMIP) file.
+fn band_variable_name(band: u8) -> String {
+    format!("CMI_C{:02}", band)
+}
+
+// =============================================================================
+// Internal helpers
+// =============================================================================
+
+/// Write `data` to a temp file and open it with the netcdf library.
+///
+/// Returns the open file and the temp file path, which the caller removes
+/// once it is done with the file.
+pub(crate) fn open_from_bytes(data: &[u8]) -> NetCdfResult<(netcdf::File, PathBuf)> {
     // Silence HDF5's verbose stderr output for missing attributes
     silence_hdf5_errors();
 
@@ -83,87 +300,14 @@ pub fn load_goes_netcdf_from_bytes(
     drop(file);
 
     // Open with netcdf library
-    let nc_file = netcdf::open(&temp_file)
-        .map_err(|e| NetCdfError::InvalidFormat(format!("Failed to open NetCDF: {}", e)))?;
-
-    // Get dimensions
-    let width = nc_file
-        .dimension("x")
-        .ok_or_else(|| NetCdfError::MissingData("x dimension".to_string()))?
-        .len();
-    let height = nc_file
-        .dimension("y")
-        .ok_or_else(|| NetCdfError::MissingData("y dimension".to_string()))?
-        .len();
-
-    // Get CMI variable and its data
-    let cmi_var = nc_file
-        .variable("CMI")
-        .ok_or_else(|| NetCdfError::MissingData("CMI variable".to_string()))?;
-
-    // Read raw data as i16 using (..) to read all extents
-    let raw_data: Vec<i16> = cmi_var
-        .get_values(..)
-        .map_err(|e| NetCdfError::InvalidFormat(format!("Failed to read CMI: {}", e)))?;
-
-    // Get attributes using get_value helper
-    let scale_factor = get_f32_attr(&cmi_var, "scale_factor").unwrap_or(1.0);
-    let add_offset = get_f32_attr(&cmi_var, "add_offset").unwrap_or(0.0);
-    let fill_value = get_i16_attr(&cmi_var, "_FillValue").unwrap_or(-1);
-
-    // Apply scale and offset
-    let data: Vec<f32> = raw_data
-        .iter()
-        .map(|&val| {
-            if val == fill_value {
-                f32::NAN
-            } else {
-                val as f32 * scale_factor + add_offset
-            }
-        })
-        .collect();
-
-    // Get coordinate attributes
-    let x_var = nc_file
-        .variable("x")
-        .ok_or_else(|| NetCdfError::MissingData("x variable".to_string()))?;
-    let x_scale = get_f32_attr(&x_var, "scale_factor").unwrap_or(1.4e-05);
-    let x_offset = get_f32_attr(&x_var, "add_offset").unwrap_or(-0.101353);
-
-    let y_var = nc_file
-        .variable("y")
-        .ok_or_else(|| NetCdfError::MissingData("y variable".to_string()))?;
-    let y_scale = get_f32_attr(&y_var, "scale_factor").unwrap_or(-1.4e-05);
-    let y_offset = get_f32_attr(&y_var, "add_offset").unwrap_or(0.128233);
-
-    // Get projection
-    let proj_var = nc_file
-        .variable("goes_imager_projection")
-        .ok_or_else(|| NetCdfError::MissingData("goes_imager_projection variable".to_string()))?;
-
-    let projection = GoesProjection {
-        perspective_point_height: get_f64_attr(&proj_var, "perspective_point_height")
-            .unwrap_or(35786023.0),
-        semi_major_axis: get_f64_attr(&proj_var, "semi_major_axis").unwrap_or(6378137.0),
-        semi_minor_axis: get_f64_attr(&proj_var, "semi_minor_axis").unwrap_or(6356752.31414),
-        longitude_origin: get_f64_attr(&proj_var, "longitude_of_projection_origin")
-            .unwrap_or(-75.0),
-        latitude_origin: 0.0,
-        sweep_angle_axis: "x".to_string(),
-    };
-
-    // Clean up
-    let _ = std::fs::remove_file(&temp_file);
+    let file = netcdf::open(&temp_file).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_file);
+        NetCdfError::InvalidFormat(format!("Failed to open NetCDF: {}", e))
+    })?;
 
-    Ok((
-        data, width, height, projection, x_offset, y_offset, x_scale, y_scale,
-    ))
+    Ok((file, temp_file))
 }
 
-// =============================================================================
-// Internal helpers
-// =============================================================================
-
 /// Get the optimal temp directory for NetCDF file operations.
 ///
 /// On Linux, uses /dev/shm (memory-backed tmpfs) if available for faster I/O.
@@ -200,7 +344,7 @@ fn generate_temp_filename() -> String {
 
 /// Check if a variable has an attribute with the given name.
 /// This avoids HDF5 error spam when checking for optional attributes.
-fn has_attr(var: &netcdf::Variable, name: &str) -> bool {
+pub(crate) fn has_attr(var: &netcdf::Variable, name: &str) -> bool {
     var.attributes().any(|attr| attr.name() == name)
 }
 
@@ -214,7 +358,7 @@ fn get_f32_attr(var: &netcdf::Variable, name: &str) -> Option<f32> {
 }
 
 /// Helper to get f64 attribute.
-fn get_f64_attr(var: &netcdf::Variable, name: &str) -> Option<f64> {
+pub(crate) fn get_f64_attr(var: &netcdf::Variable, name: &str) -> Option<f64> {
     if !has_attr(var, name) {
         return None;
     }
@@ -231,6 +375,26 @@ fn get_i16_attr(var: &netcdf::Variable, name: &str) -> Option<i16> {
     i16::try_from(attr_value).ok()
 }
 
+/// Helper to get string attribute.
+pub(crate) fn get_str_attr(var: &netcdf::Variable, name: &str) -> Option<String> {
+    if !has_attr(var, name) {
+        return None;
+    }
+    match var.attribute_value(name)?.ok()? {
+        netcdf::AttributeValue::Str(s) => Some(s),
+        _ => None,
+    }
+}
+
+/// Helper to get string global attribute.
+pub(crate) fn get_global_str_attr(file: &netcdf::File, name: &str) -> Option<String> {
+    let attr = file.attributes().find(|attr| attr.name() == name)?;
+    match attr.value().ok()? {
+        netcdf::AttributeValue::Str(s) => Some(s),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -241,6 +405,12 @@ mod tests {
         assert!(dir.exists(), "Temp dir should exist");
     }
 
+    #[test]
+    fn test_band_variable_name() {
+        assert_eq!(band_variable_name(2), "CMI_C02");
+        assert_eq!(band_variable_name(13), "CMI_C13");
+    }
+
     #[test]
     fn test_temp_filename_uniqueness() {
         let name1 = generate_temp_filename();
@@ -1,4 +1,4 @@
-//! GOES ABI geostationary projection.
+//! GOES ABI and Himawari AHI geostationary projection.
 //!
 //! This module provides coordinate conversion between geostationary satellite
 //! scan angles (radians) and geographic coordinates (lat/lon degrees).
@@ -10,6 +10,13 @@
 //! - x: East-West scan angle (positive = east of nadir)
 //! - y: North-South elevation angle (positive = north of equator)
 //!
+//! # Sweep Angle Axis
+//!
+//! GOES-R ABI sweeps along x; Himawari-8/9 AHI (like Meteosat) sweeps along
+//! y. The axis decides which angle is the outer rotation, so it changes the
+//! mapping everywhere off the axes through nadir. It is given by the
+//! `sweep_angle_axis` attribute of the file's projection variable.
+//!
 //! # Reference
 //!
 //! GOES-R Product Definition and Users' Guide (PUG) Volume 4, Section 4.2.8
@@ -30,18 +37,21 @@ pub struct GoesProjection {
     pub longitude_origin: f64,
     /// Latitude of projection origin (always 0 for geostationary)
     pub latitude_origin: f64,
-    /// Sweep angle axis ("x" for GOES-R)
+    /// Sweep angle axis ("x" for GOES-R, "y" for Himawari)
     pub sweep_angle_axis: String,
 }
 
 impl Default for GoesProjection {
+    /// GOES-R fixed grid at the GOES-East slot.
+    ///
+    /// Projections of real files come from their attributes and the
+    /// [`SatelliteRegistry`](crate::SatelliteRegistry).
     fn default() -> Self {
-        // Default values for GOES-16 (GOES-East)
         Self {
             perspective_point_height: 35786023.0,
             semi_major_axis: 6378137.0,
             semi_minor_axis: 6356752.31414,
-            longitude_origin: -75.0, // GOES-16/East
+            longitude_origin: -75.0,
             latitude_origin: 0.0,
             sweep_angle_axis: "x".to_string(),
         }
@@ -49,26 +59,21 @@ impl Default for GoesProjection {
 }
 
 impl GoesProjection {
-    /// Create projection for GOES-16 (GOES-East at 75.2°W).
-    pub fn goes16() -> Self {
-        Self {
-            longitude_origin: -75.2,
-            ..Default::default()
-        }
-    }
-
-    /// Create projection for GOES-18 (GOES-West at 137.2°W).
-    pub fn goes18() -> Self {
-        Self {
-            longitude_origin: -137.2,
-            ..Default::default()
-        }
+    /// Whether the sweep angle axis is x, as for GOES-R. Anything other
+    /// than "y" is treated as x.
+    pub fn sweeps_x(&self) -> bool {
+        !self.sweep_angle_axis.trim().eq_ignore_ascii_case("y")
     }
 
     /// Convert geostationary coordinates (radians) to geographic (lat/lon degrees).
     ///
-    /// Based on GOES-R Product Definition and Users' Guide (PUG) formulas.
-    /// The x,y coordinates are scan angles in radians from the satellite nadir.
+    /// Based on GOES-R Product Definition and Users' Guide (PUG) formulas,
+    /// generalized to either sweep angle axis. The x,y coordinates are scan
+    /// angles in radians from the satellite nadir.
+    ///
+    /// Longitudes are continuous around the sub-satellite point, not wrapped
+    /// to -180..180: east of the dateline, a satellite at 140.7°E reports
+    /// longitudes above 180.
     ///
     /// Returns `None` if the scan angle points to space (off Earth).
     ///
@@ -92,10 +97,16 @@ impl GoesProjection {
         let sin_y = y_rad.sin();
         let cos_y = y_rad.cos();
 
+        // Direction of the line of sight: towards Earth's center, east, north
+        let (dx, dy, dz) = if self.sweeps_x() {
+            (cos_x * cos_y, sin_x, cos_x * sin_y)
+        } else {
+            (cos_x * cos_y, sin_x * cos_y, sin_y)
+        };
+
         // Quadratic coefficients for finding distance to Earth surface
-        let a =
-            sin_x.powi(2) + cos_x.powi(2) * (cos_y.powi(2) + (req / rpol).powi(2) * sin_y.powi(2));
-        let b = -2.0 * h_total * cos_x * cos_y;
+        let a = dx.powi(2) + dy.powi(2) + (req / rpol).powi(2) * dz.powi(2);
+        let b = -2.0 * h_total * dx;
         let c = h_total.powi(2) - req.powi(2);
 
         let discriminant = b * b - 4.0 * a * c;
@@ -106,10 +117,10 @@ impl GoesProjection {
         let rs = (-b - discriminant.sqrt()) / (2.0 * a);
 
         // 3D coordinates (satellite-centered, Earth-fixed)
-        // Note: sy uses negative sin(x) to match forward transform where x = atan2(-sy, sx)
-        let sx = rs * cos_x * cos_y;
-        let sy = -rs * sin_x; // Negative because x = atan2(-sy, sx), so sy = -rs*sin(x)
-        let sz = rs * cos_x * sin_y;
+        // sy is positive to the west, matching from_geographic
+        let sx = rs * dx;
+        let sy = -rs * dy;
+        let sz = rs * dz;
 
         // Convert to geodetic coordinates
         let lat = ((req / rpol).powi(2) * sz / (h_total - sx).hypot(sy)).atan();
@@ -124,7 +135,7 @@ impl GoesProjection {
     ///
     /// # Arguments
     ///
-    /// * `lon` - Longitude in degrees (negative for west)
+    /// * `lon` - Longitude in degrees (negative for west, or 0-360)
     /// * `lat` - Latitude in degrees
     ///
     /// # Returns
@@ -152,17 +163,19 @@ impl GoesProjection {
         let sy = -rc * phi_c.cos() * (lon_rad - lambda_0).sin();
         let sz = rc * phi_c.sin();
 
-        // Check visibility (point must be on satellite-facing side of Earth)
-        if sx <= 0.0 {
+        // The point is visible if the satellite is above its tangent plane
+        if sx * (h_total - sx) < sy.powi(2) + (req / rpol).powi(2) * sz.powi(2) {
             return None; // Behind Earth from satellite's perspective
         }
 
-        // Calculate scan angles using atan2 for correct quadrant
-        let s_xy = sx.hypot(sy);
-        let y_rad = sz.atan2(s_xy);
-        let x_rad = (-sy).atan2(sx); // Negative sy to match inverse formula sign convention
-
-        Some((x_rad, y_rad))
+        // The angle about the sweep axis is measured first; the other one
+        // then uses the full slant range
+        let slant = (sx * sx + sy * sy + sz * sz).sqrt();
+        if self.sweeps_x() {
+            Some(((-sy / slant).asin(), sz.atan2(sx)))
+        } else {
+            Some(((-sy).atan2(sx), (sz / slant).asin()))
+        }
     }
 }
 
@@ -172,22 +185,21 @@ mod tests {
 
     #[test]
     fn test_goes_projection_roundtrip() {
-        let proj = GoesProjection::goes16();
+        let proj = GoesProjection::default();
 
         // Test a point near the center of CONUS
         let (lon, lat) = (-95.0, 35.0);
 
         if let Some((x, y)) = proj.from_geographic(lon, lat) {
             if let Some((lon2, lat2)) = proj.to_geographic(x, y) {
-                // Allow ~0.1 degree tolerance due to float precision and projection approximations
                 assert!(
-                    (lon - lon2).abs() < 0.15,
+                    (lon - lon2).abs() < 1e-6,
                     "Longitude mismatch: {} vs {}",
                     lon,
                     lon2
                 );
                 assert!(
-                    (lat - lat2).abs() < 0.15,
+                    (lat - lat2).abs() < 1e-6,
                     "Latitude mismatch: {} vs {}",
                     lat,
                     lat2
@@ -200,9 +212,67 @@ mod tests {
         }
     }
 
+    /// Himawari-9 full disk projection, as in its NetCDF files.
+    fn himawari() -> GoesProjection {
+        GoesProjection {
+            perspective_point_height: 35785863.0,
+            semi_major_axis: 6378137.0,
+            semi_minor_axis: 6356752.3,
+            longitude_origin: 140.7,
+            latitude_origin: 0.0,
+            sweep_angle_axis: "y".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_himawari_projection_roundtrip() {
+        let proj = himawari();
+        assert!(!proj.sweeps_x());
+
+        // Tokyo, Perth and Hawaii (east of the dateline)
+        for (lon, lat) in [(139.7, 35.7), (115.9, -32.0), (-157.9, 21.3)] {
+            let (x, y) = proj.from_geographic(lon, lat).unwrap();
+            let (lon2, lat2) = proj.to_geographic(x, y).unwrap();
+            assert!(
+                ((lon2 - lon + 180.0).rem_euclid(360.0) - 180.0).abs() < 1e-6,
+                "{} vs {}",
+                lon,
+                lon2
+            );
+            assert!((lat2 - lat).abs() < 1e-6, "{} vs {}", lat, lat2);
+        }
+
+        // Longitudes stay continuous across the dateline
+        let (x, y) = proj.from_geographic(-157.9, 21.3).unwrap();
+        let (lon, _) = proj.to_geographic(x, y).unwrap();
+        assert!((lon - 202.1).abs() < 1e-6);
+
+        // GOES-East is far below Himawari's horizon
+        assert!(proj.from_geographic(-75.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_sweep_axis_changes_scan_angles() {
+        let sweep_x = GoesProjection {
+            sweep_angle_axis: "x".to_string(),
+            ..himawari()
+        };
+        let sweep_y = himawari();
+
+        // Same point on the axes through nadir...
+        let (x1, y1) = sweep_x.from_geographic(140.7, 30.0).unwrap();
+        let (x2, y2) = sweep_y.from_geographic(140.7, 30.0).unwrap();
+        assert!((x1 - x2).abs() < 1e-12 && (y1 - y2).abs() < 1e-12);
+
+        // ...different scan angles off them
+        let (x1, y1) = sweep_x.from_geographic(170.0, 40.0).unwrap();
+        let (x2, y2) = sweep_y.from_geographic(170.0, 40.0).unwrap();
+        assert!((x1 - x2).abs() > 1e-4 || (y1 - y2).abs() > 1e-4);
+    }
+
     #[test]
     fn test_goes_projection_off_earth() {
-        let proj = GoesProjection::goes16();
+        let proj = GoesProjection::default();
 
         // A point that should be off Earth (large scan angle)
         let result = proj.to_geographic(0.5, 0.5); // ~28 degrees
@@ -210,19 +280,4 @@ mod tests {
                                                    // The important thing is it doesn't panic
         println!("Off-earth test result: {:?}", result);
     }
-
-    #[test]
-    fn test_goes16_vs_goes18_longitude() {
-        let goes16 = GoesProjection::goes16();
-        let goes18 = GoesProjection::goes18();
-
-        assert!(
-            (goes16.longitude_origin - (-75.2)).abs() < 0.1,
-            "GOES-16 should be at ~-75°W"
-        );
-        assert!(
-            (goes18.longitude_origin - (-137.2)).abs() < 0.1,
-            "GOES-18 should be at ~-137°W"
-        );
-    }
 }
@@ -0,0 +1,274 @@
+//! Registry of geostationary satellites.
+//!
+//! Satellite positions change over a mission's lifetime (GOES-19 replaced
+//! GOES-16 as GOES-East at 75.2°W), so they are not hard-coded. The ingestion
+//! crate builds a [`SatelliteRegistry`] from the `grid.projection_params` of
+//! each geostationary model config, and the projection of a NetCDF file is
+//! resolved from its own attributes (see [`GoesAttributes`]) with the
+//! registry filling in anything the file leaves out.
+
+use crate::projection::GoesProjection;
+
+/// Sub-satellite longitudes closer than this (degrees) identify a satellite.
+const LONGITUDE_TOLERANCE: f64 = 0.5;
+
+/// A geostationary satellite and its projection parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Satellite {
+    /// Model id the satellite's data is ingested as (e.g. "goes19")
+    pub id: String,
+    /// Value of the `platform_ID` global attribute (e.g. "G19")
+    pub platform_id: Option<String>,
+    /// Sub-satellite longitude (degrees, negative for west)
+    pub longitude: f64,
+    /// Satellite height above the Earth's surface (meters)
+    pub perspective_point_height: f64,
+    /// Semi-major axis of Earth ellipsoid (meters)
+    pub semi_major_axis: f64,
+    /// Semi-minor axis of Earth ellipsoid (meters)
+    pub semi_minor_axis: f64,
+    /// Sweep angle axis ("x" for GOES-R, "y" for Himawari)
+    pub sweep_angle_axis: String,
+}
+
+impl Satellite {
+    /// Create a satellite at `longitude` with GOES-R defaults for the
+    /// height, ellipsoid and sweep axis.
+    pub fn new(id: impl Into<String>, longitude: f64) -> Self {
+        let defaults = GoesProjection::default();
+        Self {
+            id: id.into(),
+            platform_id: None,
+            longitude,
+            perspective_point_height: defaults.perspective_point_height,
+            semi_major_axis: defaults.semi_major_axis,
+            semi_minor_axis: defaults.semi_minor_axis,
+            sweep_angle_axis: defaults.sweep_angle_axis,
+        }
+    }
+
+    /// Set the `platform_ID` the satellite is recognized by.
+    pub fn with_platform_id(mut self, platform_id: impl Into<String>) -> Self {
+        self.platform_id = Some(platform_id.into());
+        self
+    }
+
+    /// Projection of this satellite's fixed grid.
+    pub fn projection(&self) -> GoesProjection {
+        GoesProjection {
+            perspective_point_height: self.perspective_point_height,
+            semi_major_axis: self.semi_major_axis,
+            semi_minor_axis: self.semi_minor_axis,
+            longitude_origin: self.longitude,
+            latitude_origin: 0.0,
+            sweep_angle_axis: self.sweep_angle_axis.clone(),
+        }
+    }
+}
+
+/// Projection attributes read from a GOES NetCDF file.
+///
+/// Every field is optional: older or reprocessed files may omit attributes,
+/// which are then taken from the registered satellite.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GoesAttributes {
+    /// `platform_ID` global attribute (e.g. "G18")
+    pub platform_id: Option<String>,
+    /// `goes_imager_projection:perspective_point_height`
+    pub perspective_point_height: Option<f64>,
+    /// `goes_imager_projection:semi_major_axis`
+    pub semi_major_axis: Option<f64>,
+    /// `goes_imager_projection:semi_minor_axis`
+    pub semi_minor_axis: Option<f64>,
+    /// `goes_imager_projection:longitude_of_projection_origin`
+    pub longitude_origin: Option<f64>,
+    /// `goes_imager_projection:sweep_angle_axis`
+    pub sweep_angle_axis: Option<String>,
+}
+
+impl GoesAttributes {
+    /// Projection described by the attributes, with missing values taken
+    /// from `satellite`.
+    ///
+    /// Returns `None` if neither the file nor the satellite gives the
+    /// sub-satellite longitude.
+    pub fn projection(&self, satellite: Option<&Satellite>) -> Option<GoesProjection> {
+        let base = match satellite {
+            Some(satellite) => satellite.projection(),
+            None => GoesProjection {
+                longitude_origin: self.longitude_origin?,
+                ..Default::default()
+            },
+        };
+
+        Some(GoesProjection {
+            perspective_point_height: self
+                .perspective_point_height
+                .unwrap_or(base.perspective_point_height),
+            semi_major_axis: self.semi_major_axis.unwrap_or(base.semi_major_axis),
+            semi_minor_axis: self.semi_minor_axis.unwrap_or(base.semi_minor_axis),
+            longitude_origin: self.longitude_origin.unwrap_or(base.longitude_origin),
+            latitude_origin: 0.0,
+            sweep_angle_axis: self
+                .sweep_angle_axis
+                .clone()
+                .unwrap_or(base.sweep_angle_axis),
+        })
+    }
+}
+
+/// Known geostationary satellites.
+#[derive(Debug, Clone, Default)]
+pub struct SatelliteRegistry {
+    satellites: Vec<Satellite>,
+}
+
+impl SatelliteRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a satellite, replacing any registered under the same id.
+    pub fn register(&mut self, satellite: Satellite) {
+        self.satellites.retain(|s| s.id != satellite.id);
+        self.satellites.push(satellite);
+    }
+
+    /// Look up a satellite by model id.
+    pub fn get(&self, id: &str) -> Option<&Satellite> {
+        self.satellites.iter().find(|s| s.id == id)
+    }
+
+    /// Look up a satellite by `platform_ID` (case-insensitive).
+    pub fn by_platform_id(&self, platform_id: &str) -> Option<&Satellite> {
+        self.satellites.iter().find(|s| {
+            s.platform_id
+                .as_deref()
+                .is_some_and(|p| p.eq_ignore_ascii_case(platform_id.trim()))
+        })
+    }
+
+    /// Satellite whose sub-satellite longitude is closest to `longitude`,
+    /// if within half a degree.
+    ///
+    /// Satellites sharing a slot (e.g. GOES-16 and GOES-19 at 75.2°W) can't
+    /// be told apart this way; the one registered last wins.
+    pub fn by_longitude(&self, longitude: f64) -> Option<&Satellite> {
+        self.satellites
+            .iter()
+            .rev()
+            .map(|s| (s, (s.longitude - longitude).abs()))
+            .filter(|(_, distance)| *distance <= LONGITUDE_TOLERANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(s, _)| s)
+    }
+
+    /// Identify the satellite a file came from: by `platform_ID`, then by
+    /// the projection's sub-satellite longitude.
+    pub fn identify(&self, attributes: &GoesAttributes) -> Option<&Satellite> {
+        attributes
+            .platform_id
+            .as_deref()
+            .and_then(|p| self.by_platform_id(p))
+            .or_else(|| {
+                attributes
+                    .longitude_origin
+                    .and_then(|lon| self.by_longitude(lon))
+            })
+    }
+
+    /// Registered satellites, in registration order.
+    pub fn satellites(&self) -> &[Satellite] {
+        &self.satellites
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.satellites.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> SatelliteRegistry {
+        let mut registry = SatelliteRegistry::new();
+        registry.register(Satellite::new("goes16", -75.2).with_platform_id("G16"));
+        registry.register(Satellite::new("goes18", -137.2).with_platform_id("G18"));
+        registry.register(Satellite::new("goes19", -75.2).with_platform_id("G19"));
+        registry
+    }
+
+    #[test]
+    fn test_identify_by_platform_id() {
+        let registry = registry();
+        let attributes = GoesAttributes {
+            platform_id: Some("G16".to_string()),
+            longitude_origin: Some(-75.2),
+            ..Default::default()
+        };
+
+        assert_eq!(registry.identify(&attributes).unwrap().id, "goes16");
+        assert_eq!(registry.by_platform_id("g18").unwrap().id, "goes18");
+        assert!(registry.by_platform_id("G17").is_none());
+    }
+
+    #[test]
+    fn test_identify_by_longitude() {
+        let registry = registry();
+
+        // Without a platform the newest satellite in the slot is chosen
+        let east = GoesAttributes {
+            longitude_origin: Some(-75.0),
+            ..Default::default()
+        };
+        assert_eq!(registry.identify(&east).unwrap().id, "goes19");
+
+        let west = GoesAttributes {
+            longitude_origin: Some(-137.0),
+            ..Default::default()
+        };
+        assert_eq!(registry.identify(&west).unwrap().id, "goes18");
+
+        assert!(registry.by_longitude(-105.0).is_none());
+    }
+
+    #[test]
+    fn test_attributes_override_satellite() {
+        let registry = registry();
+        let satellite = registry.get("goes19").unwrap();
+        let attributes = GoesAttributes {
+            longitude_origin: Some(-75.0),
+            sweep_angle_axis: Some("y".to_string()),
+            ..Default::default()
+        };
+
+        let proj = attributes.projection(Some(satellite)).unwrap();
+        assert_eq!(proj.longitude_origin, -75.0);
+        assert_eq!(proj.sweep_angle_axis, "y");
+        assert_eq!(
+            proj.perspective_point_height,
+            satellite.perspective_point_height
+        );
+
+        // Unknown satellite: the file must at least give the longitude
+        assert!(GoesAttributes::default().projection(None).is_none());
+        let proj = GoesAttributes {
+            longitude_origin: Some(140.7),
+            ..Default::default()
+        }
+        .projection(None)
+        .unwrap();
+        assert_eq!(proj.longitude_origin, 140.7);
+    }
+
+    #[test]
+    fn test_register_replaces_id() {
+        let mut registry = registry();
+        registry.register(Satellite::new("goes18", -136.9).with_platform_id("G18"));
+
+        assert_eq!(registry.satellites().len(), 3);
+        assert_eq!(registry.get("goes18").unwrap().longitude, -136.9);
+    }
+}
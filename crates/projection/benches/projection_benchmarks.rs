@@ -0,0 +1,146 @@
+//! Projection transform benchmarks.
+//!
+//! Measures forward and inverse transforms for each projection over a
+//! 256x256 tile's worth of points inside its domain, the unit of work when
+//! resampling a grid onto a map tile.
+//!
+//! Run with: cargo bench --package projection --bench projection_benchmarks
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use projection::{Geostationary, LambertConformal, Mercator, PolarStereographic};
+
+const TILE_POINTS: usize = 256 * 256;
+
+/// Evenly spaced (lat, lon) points covering a lat/lon box.
+fn lat_lon_points(min_lat: f64, max_lat: f64, min_lon: f64, max_lon: f64) -> Vec<(f64, f64)> {
+    let side = (TILE_POINTS as f64).sqrt() as usize;
+    let step = |min: f64, max: f64, k: usize| min + (max - min) * k as f64 / (side - 1) as f64;
+    (0..side)
+        .flat_map(|r| {
+            (0..side).map(move |c| (step(min_lat, max_lat, r), step(min_lon, max_lon, c)))
+        })
+        .collect()
+}
+
+/// Evenly spaced (x, y) points covering a grid.
+fn grid_points(nx: usize, ny: usize) -> Vec<(f64, f64)> {
+    let side = (TILE_POINTS as f64).sqrt() as usize;
+    (0..side)
+        .flat_map(|r| {
+            (0..side).map(move |c| {
+                (
+                    c as f64 * (nx - 1) as f64 / (side - 1) as f64,
+                    r as f64 * (ny - 1) as f64 / (side - 1) as f64,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Benchmark a transform over `points`, summing outputs so nothing is optimized away.
+fn bench_transform(
+    group: &mut criterion::BenchmarkGroup<'_, criterion::measurement::WallTime>,
+    name: &str,
+    points: &[(f64, f64)],
+    transform: impl Fn(f64, f64) -> Option<(f64, f64)>,
+) {
+    group.bench_with_input(BenchmarkId::new(name, points.len()), points, |b, points| {
+        b.iter(|| {
+            let mut sum = 0.0f64;
+            for &(a, c) in points {
+                if let Some((x, y)) = transform(black_box(a), black_box(c)) {
+                    sum += x + y;
+                }
+            }
+            black_box(sum)
+        });
+    });
+}
+
+fn bench_lambert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lambert_hrrr");
+    group.throughput(Throughput::Elements(TILE_POINTS as u64));
+
+    let proj = LambertConformal::hrrr();
+    let geo = lat_lon_points(25.0, 50.0, -125.0, -70.0);
+    let grid = grid_points(proj.nx, proj.ny);
+
+    bench_transform(&mut group, "geo_to_grid", &geo, |lat, lon| {
+        Some(proj.geo_to_grid(lat, lon))
+    });
+    bench_transform(&mut group, "grid_to_geo", &grid, |i, j| {
+        Some(proj.grid_to_geo(i, j))
+    });
+
+    group.finish();
+}
+
+fn bench_geostationary(c: &mut Criterion) {
+    let mut group = c.benchmark_group("geostationary_goes16");
+    group.throughput(Throughput::Elements(TILE_POINTS as u64));
+
+    let proj = Geostationary::goes16_conus();
+    let geo = lat_lon_points(25.0, 50.0, -125.0, -65.0);
+    let grid = grid_points(proj.nx, proj.ny);
+
+    bench_transform(&mut group, "geo_to_grid", &geo, |lat, lon| {
+        proj.geo_to_grid(lat, lon)
+    });
+    bench_transform(&mut group, "grid_to_geo", &grid, |i, j| {
+        proj.grid_to_geo(i, j)
+    });
+
+    group.finish();
+}
+
+fn bench_mercator(c: &mut Criterion) {
+    let mut group = c.benchmark_group("web_mercator");
+    group.throughput(Throughput::Elements(TILE_POINTS as u64));
+
+    let proj = Mercator::web_mercator();
+    let geo = lat_lon_points(-80.0, 80.0, -180.0, 180.0);
+    let extent = proj.half_extent();
+    let xy: Vec<(f64, f64)> = grid_points(2, 2)
+        .into_iter()
+        .map(|(u, v)| ((u * 2.0 - 1.0) * extent, (v * 2.0 - 1.0) * extent))
+        .collect();
+
+    bench_transform(&mut group, "geo_to_xy", &geo, |lat, lon| {
+        Some(proj.geo_to_xy(lat, lon))
+    });
+    bench_transform(&mut group, "xy_to_geo", &xy, |x, y| {
+        Some(proj.xy_to_geo(x, y))
+    });
+
+    group.finish();
+}
+
+fn bench_polar(c: &mut Criterion) {
+    let mut group = c.benchmark_group("polar_stereographic_alaska");
+    group.throughput(Throughput::Elements(TILE_POINTS as u64));
+
+    // NCEP grid 242 (Alaska, 11.25 km)
+    let proj = PolarStereographic::from_grib2(
+        30.0, -173.0, -135.0, 60.0, false, 11250.0, 11250.0, 553, 425,
+    );
+    let geo = lat_lon_points(45.0, 75.0, -180.0, -120.0);
+    let grid = grid_points(proj.nx, proj.ny);
+
+    bench_transform(&mut group, "geo_to_grid", &geo, |lat, lon| {
+        Some(proj.geo_to_grid(lat, lon))
+    });
+    bench_transform(&mut group, "grid_to_geo", &grid, |i, j| {
+        Some(proj.grid_to_geo(i, j))
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_lambert,
+    bench_geostationary,
+    bench_mercator,
+    bench_polar,
+);
+criterion_main!(benches);
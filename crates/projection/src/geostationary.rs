@@ -1,9 +1,20 @@
 //! Geostationary satellite projection.
 //!
-//! This projection is used for GOES-R series satellite imagery.
+//! This projection is used for GOES-R series and Himawari-8/9 imagery.
 //! The satellite views Earth from a fixed position above the equator,
 //! and coordinates are expressed as scan angles in radians from nadir.
 //!
+//! The two instrument families differ in which scan axis sweeps: GOES-R
+//! ABI sweeps along x (`sweep_angle_axis = "x"`), while Himawari AHI, like
+//! Meteosat, sweeps along y. The sweep axis is the outer rotation, so the
+//! same scan angles land on slightly different points away from the axes.
+//!
+//! Longitudes returned by [`Geostationary::scan_to_geo`] are continuous
+//! around the sub-satellite point rather than wrapped to -180..180. A
+//! Pacific satellite such as Himawari at 140.7°E therefore reports its disk
+//! as roughly 60°E to 222°E, the 0-360 convention the grid bounding boxes
+//! already use for GFS.
+//!
 //! Reference: GOES-R Product Definition and Users' Guide (PUG) Volume 4
 
 /// Geostationary projection parameters.
@@ -23,7 +34,8 @@ pub struct Geostationary {
     pub rpol: f64,
     /// Longitude of satellite nadir point (radians)
     pub lambda_0: f64,
-    /// Sweep angle axis ("x" for GOES-R, "y" for Meteosat/Himawari)
+    /// Whether the sweep angle axis is x (GOES-R) rather than y
+    /// (Meteosat/Himawari)
     pub sweep_x: bool,
     /// X coordinate of first grid point (radians)
     pub x_origin: f64,
@@ -82,42 +94,44 @@ impl Geostationary {
         }
     }
 
-    /// Create projection for GOES-16 (GOES-East at 75°W) CONUS sector.
+    /// Use the given sweep angle axis: `true` for x (GOES-R, the default of
+    /// [`from_goes`](Self::from_goes)), `false` for y (Himawari, Meteosat).
+    pub fn with_sweep_x(mut self, sweep_x: bool) -> Self {
+        self.sweep_x = sweep_x;
+        self
+    }
+
+    /// Create projection for the GOES-R CONUS sector of a satellite at
+    /// `longitude_origin_deg`.
     ///
     /// Uses actual CONUS parameters from AWS GOES-16 data:
     /// - X: from -0.10136 to 0.03864 radians (west to east)
     /// - Y: from 0.12824 to 0.04424 radians (north to south)
     /// - Resolution: 0.000028 rad per pixel (1km at nadir)
     /// - Grid: 5000 x 3000 pixels
-    pub fn goes16_conus() -> Self {
+    pub fn goes_conus(longitude_origin_deg: f64) -> Self {
         Self::from_goes(
-            35786023.0,    // perspective_point_height
-            6378137.0,     // semi_major_axis (GRS80)
-            6356752.31414, // semi_minor_axis
-            -75.0,         // longitude_origin (GOES-16 position)
-            -0.101360,     // x_origin (west edge, radians) - x[0] value
-            0.128226,      // y_origin (north edge, radians) - y[0] value
-            0.000028,      // dx (radians per pixel)
-            -0.000028,     // dy (radians per pixel, negative = south)
-            5000,          // nx
-            3000,          // ny
+            35786023.0,           // perspective_point_height
+            6378137.0,            // semi_major_axis (GRS80)
+            6356752.31414,        // semi_minor_axis
+            longitude_origin_deg, // satellite position
+            -0.101360,            // x_origin (west edge, radians) - x[0] value
+            0.128226,             // y_origin (north edge, radians) - y[0] value
+            0.000028,             // dx (radians per pixel)
+            -0.000028,            // dy (radians per pixel, negative = south)
+            5000,                 // nx
+            3000,                 // ny
         )
     }
 
+    /// Create projection for GOES-16 (GOES-East at 75°W) CONUS sector.
+    pub fn goes16_conus() -> Self {
+        Self::goes_conus(-75.0)
+    }
+
     /// Create projection for GOES-18 (GOES-West at 137.2°W) CONUS sector.
     pub fn goes18_conus() -> Self {
-        Self::from_goes(
-            35786023.0,
-            6378137.0,
-            6356752.31414,
-            -137.2, // GOES-18 position
-            -0.101360,
-            0.128226,
-            0.000028,
-            -0.000028,
-            5000,
-            3000,
-        )
+        Self::goes_conus(-137.2)
     }
 
     /// Convert grid indices (i, j) to scan angles (x, y) in radians.
@@ -146,10 +160,17 @@ impl Geostationary {
         let sin_y = y_rad.sin();
         let cos_y = y_rad.cos();
 
+        // Unit vector from the satellite towards the scanned point: towards
+        // Earth's center, east and north
+        let (dx, dy, dz) = if self.sweep_x {
+            (cos_x * cos_y, sin_x, cos_x * sin_y)
+        } else {
+            (cos_x * cos_y, sin_x * cos_y, sin_y)
+        };
+
         // Quadratic coefficients for finding distance to Earth surface
-        let a = sin_x.powi(2)
-            + cos_x.powi(2) * (cos_y.powi(2) + (self.req / self.rpol).powi(2) * sin_y.powi(2));
-        let b = -2.0 * self.h * cos_x * cos_y;
+        let a = dx.powi(2) + dy.powi(2) + (self.req / self.rpol).powi(2) * dz.powi(2);
+        let b = -2.0 * self.h * dx;
         let c = self.h.powi(2) - self.req.powi(2);
 
         let discriminant = b * b - 4.0 * a * c;
@@ -160,9 +181,9 @@ impl Geostationary {
         let rs = (-b - discriminant.sqrt()) / (2.0 * a);
 
         // 3D coordinates (satellite-centered, Earth-fixed)
-        let sx = rs * cos_x * cos_y;
-        let sy = -rs * sin_x;
-        let sz = rs * cos_x * sin_y;
+        let sx = rs * dx;
+        let sy = -rs * dy;
+        let sz = rs * dz;
 
         // Convert to geodetic coordinates
         let lat = ((self.req / self.rpol).powi(2) * sz / (self.h - sx).hypot(sy)).atan();
@@ -207,12 +228,14 @@ impl Geostationary {
             return None; // Behind Earth from satellite's perspective
         }
 
-        // Calculate scan angles
-        let s_xy = sx.hypot(sy);
-        let y_rad = sz.atan2(s_xy);
-        let x_rad = (-sy).atan2(sx);
-
-        Some((x_rad, y_rad))
+        // Calculate scan angles. The angle about the sweep axis is measured
+        // first; the other one then uses the full slant range.
+        let slant = (sx * sx + sy * sy + sz * sz).sqrt();
+        if self.sweep_x {
+            Some(((-sy / slant).asin(), sz.atan2(sx)))
+        } else {
+            Some(((-sy).atan2(sx), (sz / slant).asin()))
+        }
     }
 
     /// Convert geographic coordinates (lat/lon degrees) to grid indices (i, j).
@@ -234,34 +257,37 @@ impl Geostationary {
     /// Get the geographic bounding box of the grid.
     ///
     /// Returns (min_lon, min_lat, max_lon, max_lat) in degrees.
-    /// Samples grid edges to find approximate bounds since the
-    /// geostationary projection creates curved edges.
+    /// Samples full rows and columns spread over the grid to find
+    /// approximate bounds: the geostationary projection creates curved
+    /// edges, and the edges of a full-disk scan are entirely in space.
     pub fn geographic_bounds(&self) -> (f64, f64, f64, f64) {
         let mut min_lat = f64::MAX;
         let mut max_lat = f64::MIN;
         let mut min_lon = f64::MAX;
         let mut max_lon = f64::MIN;
 
-        // Sample along all edges
-        let samples = 50;
-        for t in 0..=samples {
-            let frac = t as f64 / samples as f64;
-
-            // Sample each edge
-            let edges = [
-                (frac * (self.nx as f64 - 1.0), 0.0),                  // Top
-                (frac * (self.nx as f64 - 1.0), self.ny as f64 - 1.0), // Bottom
-                (0.0, frac * (self.ny as f64 - 1.0)),                  // Left
-                (self.nx as f64 - 1.0, frac * (self.ny as f64 - 1.0)), // Right
-            ];
-
-            for (i, j) in edges {
-                if let Some((lat, lon)) = self.grid_to_geo(i, j) {
-                    min_lat = min_lat.min(lat);
-                    max_lat = max_lat.max(lat);
-                    min_lon = min_lon.min(lon);
-                    max_lon = max_lon.max(lon);
-                }
+        let mut include = |i: f64, j: f64| {
+            if let Some((lat, lon)) = self.grid_to_geo(i, j) {
+                min_lat = min_lat.min(lat);
+                max_lat = max_lat.max(lat);
+                min_lon = min_lon.min(lon);
+                max_lon = max_lon.max(lon);
+            }
+        };
+
+        // Sample rows and columns, including the edges
+        let lines = 50;
+        for t in 0..=lines {
+            let frac = t as f64 / lines as f64;
+
+            let j = frac * (self.ny as f64 - 1.0);
+            for i in 0..self.nx {
+                include(i as f64, j);
+            }
+
+            let i = frac * (self.nx as f64 - 1.0);
+            for j in 0..self.ny {
+                include(i, j as f64);
             }
         }
 
@@ -309,17 +335,16 @@ mod tests {
                 j
             );
 
-            // Test roundtrip - allow 0.15 degree tolerance due to projection approximations
-            // and grid discretization effects
+            // Test roundtrip
             if let Some((lat2, lon2)) = proj.grid_to_geo(i, j) {
                 assert!(
-                    (lat - lat2).abs() < 0.15,
+                    (lat - lat2).abs() < 1e-6,
                     "Latitude roundtrip failed: {} vs {}",
                     lat,
                     lat2
                 );
                 assert!(
-                    (lon - lon2).abs() < 0.15,
+                    (lon - lon2).abs() < 1e-6,
                     "Longitude roundtrip failed: {} vs {}",
                     lon,
                     lon2
@@ -410,6 +435,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sweep_axis() {
+        let goes = Geostationary::goes16_conus();
+        let sweep_y = Geostationary::goes16_conus().with_sweep_x(false);
+
+        // Both sweeps agree on the axes through nadir...
+        let on_axis = (0.0, 0.1);
+        let (lon_x, lat_x) = goes.scan_to_geo(on_axis.0, on_axis.1).unwrap();
+        let (lon_y, lat_y) = sweep_y.scan_to_geo(on_axis.0, on_axis.1).unwrap();
+        assert!((lon_x - lon_y).abs() < 1e-9 && (lat_x - lat_y).abs() < 1e-9);
+
+        // ...but not off them
+        let (x, y) = (0.1, 0.1);
+        let (lon_x, lat_x) = goes.scan_to_geo(x, y).unwrap();
+        let (lon_y, lat_y) = sweep_y.scan_to_geo(x, y).unwrap();
+        assert!((lon_x - lon_y).abs() > 0.1 || (lat_x - lat_y).abs() > 0.1);
+
+        // Each roundtrips through its own forward transform
+        let (x2, y2) = sweep_y.geo_to_scan(lon_y, lat_y).unwrap();
+        assert!((x - x2).abs() < 1e-9 && (y - y2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_longitudes_continuous_across_dateline() {
+        // Himawari at 140.7°E sees past the dateline
+        let proj = Geostationary::from_goes(
+            35785863.0, 6378137.0, 6356752.3, 140.7, -0.15368, 0.15368, 0.0000559, -0.0000559,
+            5500, 5500,
+        )
+        .with_sweep_x(false);
+
+        let (x, y) = proj.geo_to_scan(-170.0, 20.0).unwrap();
+        let (lon, lat) = proj.scan_to_geo(x, y).unwrap();
+        assert!((lon - 190.0).abs() < 1e-6, "expected 190°E, got {}", lon);
+        assert!((lat - 20.0).abs() < 1e-6);
+
+        let (min_lon, _, max_lon, _) = proj.geographic_bounds();
+        assert!(min_lon > 0.0 && max_lon > 180.0);
+    }
+
     #[test]
     fn test_off_earth() {
         let proj = Geostationary::goes16_conus();
@@ -1 +1,106 @@
-//! mercator projection/transform
+//! Spherical Mercator projection.
+//!
+//! Web Mercator (EPSG:3857) projects WGS84 coordinates as if the Earth were
+//! a sphere with the WGS84 semi-major axis as its radius. This is the CRS of
+//! XYZ/WMTS tiles and of EPSG:3857 GetMap requests.
+
+use std::f64::consts::PI;
+
+/// Largest latitude representable in Web Mercator (the square world extent).
+pub const WEB_MERCATOR_MAX_LAT: f64 = 85.051_128_779_806_59;
+
+/// Spherical Mercator projection parameters.
+#[derive(Debug, Clone)]
+pub struct Mercator {
+    /// Sphere radius (meters)
+    pub radius: f64,
+    /// Central meridian in radians
+    pub lon0: f64,
+}
+
+impl Mercator {
+    /// Create a spherical Mercator projection.
+    ///
+    /// # Arguments
+    /// * `radius` - Sphere radius (meters)
+    /// * `lon0_deg` - Central meridian (degrees)
+    pub fn new(radius: f64, lon0_deg: f64) -> Self {
+        Self {
+            radius,
+            lon0: lon0_deg.to_radians(),
+        }
+    }
+
+    /// Web Mercator (EPSG:3857).
+    pub fn web_mercator() -> Self {
+        Self::new(6378137.0, 0.0)
+    }
+
+    /// Half the width of the projected world (meters); x ranges over
+    /// `[-half_extent, half_extent]`.
+    pub fn half_extent(&self) -> f64 {
+        PI * self.radius
+    }
+
+    /// Convert geographic coordinates (lat/lon in degrees) to projected
+    /// coordinates (x, y) in meters.
+    ///
+    /// Latitudes are clamped to ±[`WEB_MERCATOR_MAX_LAT`], where y reaches
+    /// the world extent; the poles themselves are at infinity.
+    pub fn geo_to_xy(&self, lat_deg: f64, lon_deg: f64) -> (f64, f64) {
+        let lat = lat_deg
+            .clamp(-WEB_MERCATOR_MAX_LAT, WEB_MERCATOR_MAX_LAT)
+            .to_radians();
+
+        // Normalize longitude difference to [-π, π]
+        let mut dlon = lon_deg.to_radians() - self.lon0;
+        while dlon > PI {
+            dlon -= 2.0 * PI;
+        }
+        while dlon < -PI {
+            dlon += 2.0 * PI;
+        }
+
+        let x = self.radius * dlon;
+        let y = self.radius * (PI / 4.0 + lat / 2.0).tan().ln();
+        (x, y)
+    }
+
+    /// Convert projected coordinates (x, y in meters) to geographic
+    /// coordinates (lat/lon in degrees).
+    pub fn xy_to_geo(&self, x: f64, y: f64) -> (f64, f64) {
+        let lat = 2.0 * (y / self.radius).exp().atan() - PI / 2.0;
+        let lon = self.lon0 + x / self.radius;
+        (lat.to_degrees(), lon.to_degrees())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_web_mercator_origin_and_extent() {
+        let proj = Mercator::web_mercator();
+
+        let (x, y) = proj.geo_to_xy(0.0, 0.0);
+        assert!(x.abs() < 1e-9 && y.abs() < 1e-9);
+
+        // The max latitude maps to the top of the square world
+        let (x, y) = proj.geo_to_xy(WEB_MERCATOR_MAX_LAT, 180.0);
+        assert!((x - proj.half_extent()).abs() < 1e-6, "x = {}", x);
+        assert!((y - proj.half_extent()).abs() < 1e-6, "y = {}", y);
+    }
+
+    #[test]
+    fn test_web_mercator_roundtrip() {
+        let proj = Mercator::web_mercator();
+
+        for (lat, lon) in [(45.0, -93.0), (-33.9, 151.2), (70.0, 20.0)] {
+            let (x, y) = proj.geo_to_xy(lat, lon);
+            let (lat2, lon2) = proj.xy_to_geo(x, y);
+            assert!((lat - lat2).abs() < 1e-9, "lat {} vs {}", lat, lat2);
+            assert!((lon - lon2).abs() < 1e-9, "lon {} vs {}", lon, lon2);
+        }
+    }
+}
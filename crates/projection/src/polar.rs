@@ -1 +1,218 @@
-//! polar projection/transform
+//! Polar Stereographic projection.
+//!
+//! Used for high-latitude model grids (GRIB2 template 3.20), e.g. NCEP's
+//! Alaska and polar domains. Points are projected from the opposite pole onto
+//! a plane through the standard parallel, where the grid spacing is true.
+//!
+//! The projection parameters include:
+//! - Orientation longitude (LoV): The meridian parallel to the grid's y axis
+//! - Standard parallel (LaD): The latitude where dx and dy are true
+//! - Projection center: North or south pole
+//! - Grid spacing: dx, dy in meters
+//! - First grid point: lat1, lon1
+
+use std::f64::consts::PI;
+
+/// Polar Stereographic projection parameters.
+///
+/// These parameters define the projection from geographic (lat/lon) to
+/// grid (i, j) coordinates and vice versa.
+#[derive(Debug, Clone)]
+pub struct PolarStereographic {
+    /// Orientation longitude (LoV) in radians
+    pub lov: f64,
+    /// Standard parallel (LaD) in radians
+    pub lad: f64,
+    /// True for a south polar projection
+    pub south_pole: bool,
+    /// Latitude of first grid point in radians
+    pub lat1: f64,
+    /// Longitude of first grid point in radians
+    pub lon1: f64,
+    /// Grid spacing in X direction (meters)
+    pub dx: f64,
+    /// Grid spacing in Y direction (meters)
+    pub dy: f64,
+    /// Number of grid points in X (i) direction
+    pub nx: usize,
+    /// Number of grid points in Y (j) direction
+    pub ny: usize,
+    /// Earth radius (meters)
+    pub earth_radius: f64,
+    /// Projected coordinates of the first grid point (meters)
+    x1: f64,
+    y1: f64,
+}
+
+impl PolarStereographic {
+    /// Create a new Polar Stereographic projection from GRIB2 parameters.
+    ///
+    /// # Arguments
+    /// * `lat1_deg` - Latitude of first grid point (degrees)
+    /// * `lon1_deg` - Longitude of first grid point (degrees)
+    /// * `lov_deg` - Orientation longitude (degrees)
+    /// * `lad_deg` - Latitude where dx and dy are specified (degrees)
+    /// * `south_pole` - Projection center is the south pole
+    /// * `dx` - Grid spacing X (meters)
+    /// * `dy` - Grid spacing Y (meters)
+    /// * `nx` - Number of X grid points
+    /// * `ny` - Number of Y grid points
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_grib2(
+        lat1_deg: f64,
+        lon1_deg: f64,
+        lov_deg: f64,
+        lad_deg: f64,
+        south_pole: bool,
+        dx: f64,
+        dy: f64,
+        nx: usize,
+        ny: usize,
+    ) -> Self {
+        let mut proj = Self {
+            lov: lov_deg.to_radians(),
+            lad: lad_deg.to_radians(),
+            south_pole,
+            lat1: lat1_deg.to_radians(),
+            lon1: lon1_deg.to_radians(),
+            dx,
+            dy,
+            nx,
+            ny,
+            // Earth radius (GRIB2 shape of the Earth 6)
+            earth_radius: 6371229.0,
+            x1: 0.0,
+            y1: 0.0,
+        };
+        (proj.x1, proj.y1) = proj.geo_to_xy(lat1_deg, lon1_deg);
+        proj
+    }
+
+    /// +1 for a north polar projection, -1 for a south polar one.
+    fn hemisphere(&self) -> f64 {
+        if self.south_pole {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Scaled radius: distance from the pole per unit of tan(π/4 - φ/2).
+    fn k(&self) -> f64 {
+        self.earth_radius * (1.0 + self.hemisphere() * self.lad.sin())
+    }
+
+    /// Convert geographic coordinates (lat/lon in degrees) to projected
+    /// coordinates (x, y) in meters from the pole.
+    pub fn geo_to_xy(&self, lat_deg: f64, lon_deg: f64) -> (f64, f64) {
+        let s = self.hemisphere();
+        let lat = lat_deg.to_radians();
+        let dlon = lon_deg.to_radians() - self.lov;
+
+        let rho = self.k() * (PI / 4.0 - s * lat / 2.0).tan();
+        (rho * dlon.sin(), -s * rho * dlon.cos())
+    }
+
+    /// Convert projected coordinates (x, y in meters from the pole) to
+    /// geographic coordinates (lat/lon in degrees).
+    pub fn xy_to_geo(&self, x: f64, y: f64) -> (f64, f64) {
+        let s = self.hemisphere();
+        let rho = x.hypot(y);
+
+        let lat = s * (PI / 2.0 - 2.0 * (rho / self.k()).atan());
+        let lon = if rho == 0.0 {
+            self.lov
+        } else {
+            self.lov + x.atan2(-s * y)
+        };
+
+        // Normalize longitude to [-180, 180)
+        let lon_deg = (lon.to_degrees() + 180.0).rem_euclid(360.0) - 180.0;
+        (lat.to_degrees(), lon_deg)
+    }
+
+    /// Convert geographic coordinates (lat/lon in degrees) to grid indices (i, j).
+    ///
+    /// Returns (i, j) where i is the column (x) and j is the row (y).
+    /// The indices may be fractional for interpolation purposes.
+    pub fn geo_to_grid(&self, lat_deg: f64, lon_deg: f64) -> (f64, f64) {
+        let (x, y) = self.geo_to_xy(lat_deg, lon_deg);
+        ((x - self.x1) / self.dx, (y - self.y1) / self.dy)
+    }
+
+    /// Convert grid indices (i, j) to geographic coordinates (lat/lon in degrees).
+    ///
+    /// Returns (lat, lon) in degrees.
+    pub fn grid_to_geo(&self, i: f64, j: f64) -> (f64, f64) {
+        self.xy_to_geo(self.x1 + i * self.dx, self.y1 + j * self.dy)
+    }
+
+    /// Check if a geographic point is within the grid.
+    pub fn contains(&self, lat_deg: f64, lon_deg: f64) -> bool {
+        let (i, j) = self.geo_to_grid(lat_deg, lon_deg);
+        i >= 0.0 && i < self.nx as f64 && j >= 0.0 && j < self.ny as f64
+    }
+
+    /// Get grid dimensions.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.nx, self.ny)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// NCEP grid 242 (Alaska, 11.25 km).
+    fn alaska() -> PolarStereographic {
+        PolarStereographic::from_grib2(
+            30.0, -173.0, -135.0, 60.0, false, 11250.0, 11250.0, 553, 425,
+        )
+    }
+
+    #[test]
+    fn test_first_grid_point() {
+        let proj = alaska();
+        let (i, j) = proj.geo_to_grid(30.0, -173.0);
+        assert!(i.abs() < 1e-9 && j.abs() < 1e-9, "({}, {})", i, j);
+    }
+
+    #[test]
+    fn test_pole_and_orientation() {
+        let proj = alaska();
+
+        let (x, y) = proj.geo_to_xy(90.0, 10.0);
+        assert!(x.abs() < 1e-6 && y.abs() < 1e-6);
+
+        // Along LoV, southward is -y in the north polar projection
+        let (x, y) = proj.geo_to_xy(60.0, -135.0);
+        assert!(x.abs() < 1e-6 && y < 0.0);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let proj = alaska();
+        let (lat, lon) = proj.grid_to_geo(276.0, 212.0);
+        let (i, j) = proj.geo_to_grid(lat, lon);
+        assert!((i - 276.0).abs() < 1e-6, "i roundtrip: {}", i);
+        assert!((j - 212.0).abs() < 1e-6, "j roundtrip: {}", j);
+
+        // Anchorage is inside the Alaska grid
+        assert!(proj.contains(61.2, -149.9));
+        assert!(!proj.contains(40.0, -100.0));
+    }
+
+    #[test]
+    fn test_south_pole_roundtrip() {
+        let proj = PolarStereographic::from_grib2(
+            -50.0, 0.0, 0.0, -71.0, true, 25000.0, 25000.0, 400, 400,
+        );
+
+        let (x, y) = proj.geo_to_xy(-90.0, 45.0);
+        assert!(x.abs() < 1e-6 && y.abs() < 1e-6);
+
+        let (lat, lon) = proj.xy_to_geo(1.0e6, -2.0e6);
+        let (x, y) = proj.geo_to_xy(lat, lon);
+        assert!((x - 1.0e6).abs() < 1e-6 && (y + 2.0e6).abs() < 1e-6);
+    }
+}
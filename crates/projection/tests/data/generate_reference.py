@@ -0,0 +1,196 @@
+#!/usr/bin/env python3
+"""Generate proj_reference.csv, the ground truth for tests/proj_reference.rs.
+
+Each projection below mirrors a case in the Rust harness. Points are projected
+with PROJ (through pyproj) and converted to the units our API returns: grid
+indices for model grids, scan angles in radians for geostationary, meters for
+Web Mercator.
+
+    pip install pyproj
+    python3 generate_reference.py > proj_reference.csv
+
+Without pyproj, `--engine formulas` evaluates the spherical/ellipsoidal
+formulas PROJ uses for these projections (lcc, geos, merc, stere) directly.
+The engine used is recorded in the CSV header.
+"""
+
+import argparse
+import math
+import sys
+
+# name -> PROJ definition, output kind and, for grids, first point and spacing
+PROJECTIONS = {
+    "hrrr": {
+        "proj": "+proj=lcc +lat_0=38.5 +lat_1=38.5 +lat_2=38.5 +lon_0=-97.5 +R=6371229",
+        "grid": ((21.138123, -122.719528), 3000.0, 3000.0),
+    },
+    "goes16_conus": {
+        "proj": "+proj=geos +h=35786023 +lon_0=-75 +sweep=x +a=6378137 +b=6356752.31414",
+        "scan_height": 35786023.0,
+    },
+    "goes18_conus": {
+        "proj": "+proj=geos +h=35786023 +lon_0=-137.2 +sweep=x +a=6378137 +b=6356752.31414",
+        "scan_height": 35786023.0,
+    },
+    "himawari_fldk": {
+        "proj": "+proj=geos +h=35785863 +lon_0=140.7 +sweep=y +a=6378137 +b=6356752.3",
+        "scan_height": 35785863.0,
+    },
+    "web_mercator": {
+        "proj": "+proj=merc +a=6378137 +b=6378137 +lon_0=0 +k=1",
+    },
+    "alaska_polar": {
+        "proj": "+proj=stere +lat_0=90 +lat_ts=60 +lon_0=-135 +R=6371229",
+        "grid": ((30.0, -173.0), 11250.0, 11250.0),
+    },
+    "antarctic_polar": {
+        "proj": "+proj=stere +lat_0=-90 +lat_ts=-71 +lon_0=0 +R=6371229",
+        "grid": ((-50.0, -135.0), 25000.0, 25000.0),
+    },
+}
+
+
+def sample_points(name):
+    """Deterministic (lat, lon) samples covering each projection's domain."""
+    if name == "hrrr":
+        points = [(lat, lon) for lat in range(22, 53, 5) for lon in range(-125, -64, 8)]
+        return [(21.138123, -122.719528)] + points
+    if name.startswith("goes") or name == "himawari_fldk":
+        center = {"goes16_conus": -75.0, "goes18_conus": -137.2, "himawari_fldk": 140.7}[name]
+        # Full disk covers both hemispheres, CONUS only the northern one
+        lats = range(-55, 56, 10) if name == "himawari_fldk" else range(15, 56, 10)
+        wrap = lambda lon: round((lon + 180.0) % 360.0 - 180.0, 6)
+        return [(0.0, center)] + [
+            (lat, wrap(center + dlon)) for lat in lats for dlon in range(-60, 61, 15)
+        ]
+    if name == "web_mercator":
+        return [(lat, lon) for lat in (-85, -60, -30, 0, 15, 45, 75, 85) for lon in (-180, -97.5, 0, 33.3, 179.9)]
+    if name == "alaska_polar":
+        return [(30.0, -173.0)] + [(lat, lon) for lat in range(35, 86, 10) for lon in range(-180, -99, 20)]
+    if name == "antarctic_polar":
+        return [(-50.0, -135.0)] + [(lat, lon) for lat in range(-85, -49, 10) for lon in range(-180, 180, 45)]
+    raise ValueError(name)
+
+
+def formulas_transformer(definition):
+    """Forward transform for the PROJ definitions above, using PROJ's formulas."""
+    params = {}
+    for token in definition.split():
+        key, _, value = token.lstrip("+").partition("=")
+        params[key] = value
+    proj = params["proj"]
+    lon0 = math.radians(float(params.get("lon_0", 0)))
+
+    if proj == "lcc":
+        r = float(params["R"])
+        phi1 = math.radians(float(params["lat_1"]))
+        phi0 = math.radians(float(params["lat_0"]))
+        n = math.sin(phi1)
+        c = math.cos(phi1) * math.tan(math.pi / 4 + phi1 / 2) ** n / n
+        rho0 = c * math.tan(math.pi / 4 + phi0 / 2) ** -n
+
+        def forward(lon, lat):
+            lam = math.radians(lon) - lon0
+            rho = c * math.tan(math.pi / 4 + math.radians(lat) / 2) ** -n
+            return r * rho * math.sin(n * lam), r * (rho0 - rho * math.cos(n * lam))
+
+        return forward
+
+    if proj == "geos":
+        a, b, h = float(params["a"]), float(params["b"]), float(params["h"])
+        radius_p, radius_p2 = b / a, (b / a) ** 2
+        radius_g_1 = h / a
+        radius_g = 1 + radius_g_1
+
+        def forward(lon, lat):
+            lam = math.radians(lon) - lon0
+            phi = math.atan(radius_p2 * math.tan(math.radians(lat)))
+            r = radius_p / math.hypot(radius_p * math.cos(phi), math.sin(phi))
+            vx = r * math.cos(lam) * math.cos(phi)
+            vy = r * math.sin(lam) * math.cos(phi)
+            vz = r * math.sin(phi)
+            tmp = radius_g - vx
+            if params["sweep"] == "x":
+                x = radius_g_1 * math.atan(vy / math.hypot(vz, tmp))
+                y = radius_g_1 * math.atan(vz / tmp)
+            else:
+                x = radius_g_1 * math.atan(vy / tmp)
+                y = radius_g_1 * math.atan(vz / math.hypot(vy, tmp))
+            return a * x, a * y
+
+        return forward
+
+    if proj == "merc":
+        a = float(params["a"])
+
+        def forward(lon, lat):
+            lam = math.radians(lon) - lon0
+            return a * lam, a * math.log(math.tan(math.pi / 4 + math.radians(lat) / 2))
+
+        return forward
+
+    if proj == "stere":
+        r = float(params["R"])
+        phits = math.radians(float(params["lat_ts"]))
+        north = float(params["lat_0"]) > 0
+        sign = 1.0 if north else -1.0
+        akm1 = math.cos(phits) / math.tan(math.pi / 4 - 0.5 * sign * phits)
+
+        def forward(lon, lat):
+            lam = math.radians(lon) - lon0
+            phi = math.radians(lat)
+            coslam = math.cos(lam)
+            if north:
+                coslam, phi = -coslam, -phi
+            y = akm1 * math.tan(math.pi / 4 + 0.5 * phi)
+            return r * math.sin(lam) * y, r * coslam * y
+
+        return forward
+
+    raise ValueError(proj)
+
+
+def pyproj_transformer(definition):
+    from pyproj import Proj
+
+    return Proj(definition)
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--engine", choices=["pyproj", "formulas"], default="pyproj")
+    args = parser.parse_args()
+
+    if args.engine == "pyproj":
+        import pyproj
+
+        engine = "pyproj {} (PROJ {})".format(pyproj.__version__, pyproj.proj_version_str)
+        make = pyproj_transformer
+    else:
+        engine = "formulas (PROJ lcc/geos/merc/stere algorithms)"
+        make = formulas_transformer
+
+    out = sys.stdout
+    out.write("# Reference points for tests/proj_reference.rs (see generate_reference.py)\n")
+    out.write("# engine: {}\n".format(engine))
+    out.write("# x,y: grid i,j for model grids; scan angle radians for geos; meters for web_mercator\n")
+    out.write("projection,lat,lon,x,y\n")
+
+    for name, spec in PROJECTIONS.items():
+        forward = make(spec["proj"])
+        origin = None
+        if "grid" in spec:
+            (lat1, lon1), dx, dy = spec["grid"]
+            origin = forward(lon1, lat1)
+
+        for lat, lon in sample_points(name):
+            x, y = forward(lon, lat)
+            if origin is not None:
+                x, y = (x - origin[0]) / dx, (y - origin[1]) / dy
+            elif "scan_height" in spec:
+                x, y = x / spec["scan_height"], y / spec["scan_height"]
+            out.write("{},{!r},{!r},{!r},{!r}\n".format(name, float(lat), float(lon), x, y))
+
+
+if __name__ == "__main__":
+    main()
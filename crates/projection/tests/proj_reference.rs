@@ -0,0 +1,297 @@
+//! Accuracy tests against PROJ reference points.
+//!
+//! `data/proj_reference.csv` holds PROJ reference points for each case below
+//! (regenerate with `data/generate_reference.py`; its header records whether
+//! pyproj or the script's port of the PROJ formulas produced them). Each case has a
+//! tolerance budget for the forward transform, in the units of its output,
+//! and for the inverse transform, in degrees. Failures list the worst points
+//! so a regression shows where in the domain it happens.
+
+use projection::{Geostationary, LambertConformal, Mercator, PolarStereographic};
+
+const REFERENCE_CSV: &str = include_str!("data/proj_reference.csv");
+
+/// Projection under test and the units it is compared in.
+enum Projection {
+    /// Grid indices (i, j)
+    Lambert(LambertConformal),
+    /// Scan angles (radians)
+    Geostationary(Geostationary),
+    /// Projected meters
+    Mercator(Mercator),
+    /// Grid indices (i, j)
+    Polar(PolarStereographic),
+}
+
+impl Projection {
+    fn forward(&self, lat: f64, lon: f64) -> Option<(f64, f64)> {
+        match self {
+            Projection::Lambert(p) => Some(p.geo_to_grid(lat, lon)),
+            Projection::Geostationary(p) => p.geo_to_scan(lon, lat),
+            Projection::Mercator(p) => Some(p.geo_to_xy(lat, lon)),
+            Projection::Polar(p) => Some(p.geo_to_grid(lat, lon)),
+        }
+    }
+
+    /// Returns (lat, lon).
+    fn inverse(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        match self {
+            Projection::Lambert(p) => Some(p.grid_to_geo(x, y)),
+            Projection::Geostationary(p) => p.scan_to_geo(x, y).map(|(lon, lat)| (lat, lon)),
+            Projection::Mercator(p) => Some(p.xy_to_geo(x, y)),
+            Projection::Polar(p) => Some(p.grid_to_geo(x, y)),
+        }
+    }
+}
+
+/// Largest acceptable errors for one case.
+struct Budget {
+    /// Forward transform error, in output units
+    forward: f64,
+    /// Inverse transform error, in degrees
+    inverse_deg: f64,
+}
+
+/// A projection matching one set of rows in the reference CSV.
+struct Case {
+    name: String,
+    projection: Projection,
+    budget: Budget,
+}
+
+fn case(name: &str) -> Case {
+    let (projection, budget) = match name {
+        // 1e-4 grid cells is 0.3 m on the 3 km grid
+        "hrrr" => (
+            Projection::Lambert(LambertConformal::hrrr()),
+            Budget {
+                forward: 1e-4,
+                inverse_deg: 1e-7,
+            },
+        ),
+        // 1e-9 rad is ~3.6 cm at nadir (1/28000 of a 1 km pixel)
+        "goes16_conus" => (
+            Projection::Geostationary(Geostationary::goes16_conus()),
+            Budget {
+                forward: 1e-9,
+                inverse_deg: 1e-7,
+            },
+        ),
+        "goes18_conus" => (
+            Projection::Geostationary(Geostationary::goes18_conus()),
+            Budget {
+                forward: 1e-9,
+                inverse_deg: 1e-7,
+            },
+        ),
+        // Himawari AHI full disk, 2 km (5500 x 5500), sweeping along y
+        "himawari_fldk" => (
+            Projection::Geostationary(
+                Geostationary::from_goes(
+                    35785863.0, 6378137.0, 6356752.3, 140.7, -0.15368, 0.15368, 0.0000559,
+                    -0.0000559, 5500, 5500,
+                )
+                .with_sweep_x(false),
+            ),
+            Budget {
+                forward: 1e-9,
+                inverse_deg: 1e-7,
+            },
+        ),
+        "web_mercator" => (
+            Projection::Mercator(Mercator::web_mercator()),
+            Budget {
+                forward: 1e-3,
+                inverse_deg: 1e-9,
+            },
+        ),
+        // NCEP grid 242 (Alaska, 11.25 km)
+        "alaska_polar" => (
+            Projection::Polar(PolarStereographic::from_grib2(
+                30.0, -173.0, -135.0, 60.0, false, 11250.0, 11250.0, 553, 425,
+            )),
+            Budget {
+                forward: 1e-4,
+                inverse_deg: 1e-7,
+            },
+        ),
+        "antarctic_polar" => (
+            Projection::Polar(PolarStereographic::from_grib2(
+                -50.0, -135.0, 0.0, -71.0, true, 25000.0, 25000.0, 400, 400,
+            )),
+            Budget {
+                forward: 1e-4,
+                inverse_deg: 1e-7,
+            },
+        ),
+        other => panic!("No test case for reference projection '{}'", other),
+    };
+    Case {
+        name: name.to_string(),
+        projection,
+        budget,
+    }
+}
+
+/// One row of the reference CSV.
+struct ReferencePoint {
+    projection: String,
+    lat: f64,
+    lon: f64,
+    x: f64,
+    y: f64,
+}
+
+fn reference_points() -> Vec<ReferencePoint> {
+    REFERENCE_CSV
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .skip(1) // Header
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            assert_eq!(fields.len(), 5, "Malformed reference row: {}", line);
+            let number = |i: usize| -> f64 {
+                fields[i]
+                    .parse()
+                    .unwrap_or_else(|_| panic!("Bad number in reference row: {}", line))
+            };
+            ReferencePoint {
+                projection: fields[0].to_string(),
+                lat: number(1),
+                lon: number(2),
+                x: number(3),
+                y: number(4),
+            }
+        })
+        .collect()
+}
+
+/// Difference between two longitudes, in degrees, accounting for wrap-around.
+fn lon_diff(a: f64, b: f64) -> f64 {
+    ((a - b + 180.0).rem_euclid(360.0) - 180.0).abs()
+}
+
+/// Check every reference point of a case against its budgets.
+fn check(name: &str) {
+    let case = case(name);
+    let points: Vec<ReferencePoint> = reference_points()
+        .into_iter()
+        .filter(|p| p.projection == case.name)
+        .collect();
+    assert!(
+        points.len() >= 10,
+        "{}: only {} reference points",
+        case.name,
+        points.len()
+    );
+
+    // (error, description) for every point over budget
+    let mut failures: Vec<(f64, String)> = Vec::new();
+    let (mut max_forward, mut max_inverse) = (0.0f64, 0.0f64);
+
+    for p in &points {
+        match case.projection.forward(p.lat, p.lon) {
+            Some((x, y)) => {
+                let error = (x - p.x).abs().max((y - p.y).abs());
+                max_forward = max_forward.max(error);
+                if error > case.budget.forward {
+                    failures.push((
+                        error / case.budget.forward,
+                        format!(
+                            "forward ({}, {}): got ({}, {}), expected ({}, {}), error {:e}",
+                            p.lat, p.lon, x, y, p.x, p.y, error
+                        ),
+                    ));
+                }
+            }
+            None => failures.push((
+                f64::INFINITY,
+                format!("forward ({}, {}): no result", p.lat, p.lon),
+            )),
+        }
+
+        match case.projection.inverse(p.x, p.y) {
+            Some((lat, lon)) => {
+                let error = (lat - p.lat).abs().max(lon_diff(lon, p.lon));
+                max_inverse = max_inverse.max(error);
+                if error > case.budget.inverse_deg {
+                    failures.push((
+                        error / case.budget.inverse_deg,
+                        format!(
+                            "inverse ({}, {}): got ({}, {}), expected ({}, {}), error {:e} deg",
+                            p.x, p.y, lat, lon, p.lat, p.lon, error
+                        ),
+                    ));
+                }
+            }
+            None => failures.push((
+                f64::INFINITY,
+                format!("inverse ({}, {}): no result", p.x, p.y),
+            )),
+        }
+    }
+
+    println!(
+        "{}: {} points, max forward error {:e} (budget {:e}), max inverse error {:e} deg (budget {:e})",
+        case.name,
+        points.len(),
+        max_forward,
+        case.budget.forward,
+        max_inverse,
+        case.budget.inverse_deg
+    );
+
+    if !failures.is_empty() {
+        failures.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let worst: Vec<&str> = failures.iter().take(5).map(|(_, d)| d.as_str()).collect();
+        panic!(
+            "{}: {} of {} checks over budget; worst:\n  {}",
+            case.name,
+            failures.len(),
+            points.len() * 2,
+            worst.join("\n  ")
+        );
+    }
+}
+
+#[test]
+fn test_every_reference_projection_has_a_case() {
+    for point in reference_points() {
+        case(&point.projection);
+    }
+}
+
+#[test]
+fn test_lambert_hrrr() {
+    check("hrrr");
+}
+
+#[test]
+fn test_geostationary_goes16() {
+    check("goes16_conus");
+}
+
+#[test]
+fn test_geostationary_goes18() {
+    check("goes18_conus");
+}
+
+#[test]
+fn test_geostationary_himawari() {
+    check("himawari_fldk");
+}
+
+#[test]
+fn test_web_mercator() {
+    check("web_mercator");
+}
+
+#[test]
+fn test_polar_stereographic_north() {
+    check("alaska_polar");
+}
+
+#[test]
+fn test_polar_stereographic_south() {
+    check("antarctic_polar");
+}
@@ -5,7 +5,7 @@
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use rand::Rng;
-use renderer::{gradient, png, style};
+use renderer::{dither::DitherMode, gradient, png, style};
 
 /// Generate a test temperature grid with realistic patterns.
 /// Values are in Kelvin (typical surface temps: 220K to 320K).
@@ -96,6 +96,10 @@ fn create_temperature_style() -> style::StyleDefinition {
         out_of_range: Some("clamp".to_string()),
         legend: None,
         wind: None,
+        composite: None,
+        mask: None,
+        minify: None,
+        dither: None,
     }
 }
 
@@ -143,6 +147,10 @@ fn create_wind_speed_style() -> style::StyleDefinition {
         out_of_range: Some("clamp".to_string()),
         legend: None,
         wind: None,
+        composite: None,
+        mask: None,
+        minify: None,
+        dither: None,
     }
 }
 
@@ -190,6 +198,10 @@ fn create_pressure_style() -> style::StyleDefinition {
         out_of_range: Some("clamp".to_string()),
         legend: None,
         wind: None,
+        composite: None,
+        mask: None,
+        minify: None,
+        dither: None,
     }
 }
 
@@ -439,6 +451,78 @@ fn bench_png_encoding(c: &mut Criterion) {
     group.finish();
 }
 
+/// Compare encoder options: profiles, filters and deflate backends.
+///
+/// Run with: cargo bench --package renderer --bench render_benchmarks -- png_options
+/// Add `--features renderer/fdeflate,renderer/zopfli` to include those backends.
+fn bench_png_options(c: &mut Criterion) {
+    let mut group = c.benchmark_group("png_options");
+    group.sample_size(20);
+
+    let mut variants: Vec<(&str, png::PngOptions)> = vec![
+        ("interactive", png::PngOptions::interactive()),
+        ("seeding", png::PngOptions::seeding()),
+        (
+            "level6_up",
+            png::PngOptions {
+                level: 6,
+                filter: png::FilterStrategy::Up,
+                ..png::PngOptions::interactive()
+            },
+        ),
+        (
+            "level1_adaptive",
+            png::PngOptions {
+                filter: png::FilterStrategy::Adaptive,
+                ..png::PngOptions::interactive()
+            },
+        ),
+    ];
+    if png::DeflateBackend::Fdeflate.is_available() {
+        variants.push((
+            "fdeflate",
+            png::PngOptions {
+                backend: png::DeflateBackend::Fdeflate,
+                ..png::PngOptions::interactive()
+            },
+        ));
+    }
+    if png::DeflateBackend::Zopfli.is_available() {
+        variants.push((
+            "zopfli",
+            png::PngOptions {
+                backend: png::DeflateBackend::Zopfli,
+                ..png::PngOptions::seeding()
+            },
+        ));
+    }
+
+    // 256x256 tiles and a large GetMap image (parallel filtering)
+    for (width, height) in [(256, 256), (2048, 1024)] {
+        let weather_data = generate_weather_rgba_data(width, height);
+        group.throughput(Throughput::Bytes((width * height * 4) as u64));
+
+        for (name, options) in &variants {
+            let size = png::create_png_auto_with_options(&weather_data, width, height, options)
+                .map(|p| p.len())
+                .unwrap_or(0);
+            println!("{} {}x{}: {} bytes", name, width, height, size);
+
+            group.bench_with_input(
+                BenchmarkId::new(*name, format!("{}x{}", width, height)),
+                &weather_data,
+                |b, data| {
+                    b.iter(|| {
+                        png::create_png_auto_with_options(black_box(data), width, height, options)
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 // =============================================================================
 // PRE-COMPUTED PALETTE BENCHMARKS
 // =============================================================================
@@ -551,6 +635,78 @@ fn bench_precomputed_png_encoding(c: &mut Criterion) {
     group.finish();
 }
 
+// =============================================================================
+// DITHERING BENCHMARKS
+// =============================================================================
+
+/// Cost of dithered indexed rendering, alone and with PNG encoding (dithered
+/// tiles compress less well, so encoding is part of the cost).
+fn bench_dithering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dithering");
+
+    let sizes = [(256, 256), (512, 512)];
+    let modes = [
+        ("none", None),
+        ("ordered", Some(DitherMode::Ordered)),
+        ("blue_noise", Some(DitherMode::BlueNoise)),
+    ];
+
+    for (width, height) in sizes {
+        let data: Vec<f32> = generate_linear_grid(width, height)
+            .iter()
+            .map(|v| 233.15 + v * 0.8)
+            .collect();
+
+        group.throughput(Throughput::Elements((width * height) as u64));
+
+        for (name, mode) in modes {
+            let mut temp_style = create_temperature_style();
+            temp_style.dither = mode;
+            let palette = temp_style
+                .compute_palette()
+                .expect("Failed to compute palette");
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("indexed_{}", name), format!("{}x{}", width, height)),
+                &data,
+                |b, data| {
+                    b.iter(|| {
+                        style::apply_style_gradient_indexed(
+                            black_box(data),
+                            width,
+                            height,
+                            &palette,
+                            &temp_style,
+                        )
+                    });
+                },
+            );
+
+            group.bench_with_input(
+                BenchmarkId::new(
+                    format!("with_png_{}", name),
+                    format!("{}x{}", width, height),
+                ),
+                &data,
+                |b, data| {
+                    b.iter(|| {
+                        let indices = style::apply_style_gradient_indexed(
+                            black_box(data),
+                            width,
+                            height,
+                            &palette,
+                            &temp_style,
+                        );
+                        png::create_png_from_precomputed(&indices, width, height, &palette)
+                    });
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 // =============================================================================
 // FULL PIPELINE BENCHMARKS
 // =============================================================================
@@ -721,8 +877,10 @@ criterion_group!(
     bench_style_rendering,
     bench_render_other_types,
     bench_png_encoding,
+    bench_png_options,
     bench_precomputed_palette,
     bench_precomputed_png_encoding,
+    bench_dithering,
     bench_full_pipeline,
     bench_buffer_pooling,
 );
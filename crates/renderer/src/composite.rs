@@ -0,0 +1,269 @@
+//! Multi-band RGB composites for satellite imagery.
+//!
+//! A composite recipe describes how to turn several co-located bands (e.g.
+//! GOES ABI channels) into a single RGBA image. Recipes live in style files
+//! under the `composite` key of a style with `type: "rgb_composite"`.
+//!
+//! Three recipe kinds are supported:
+//!
+//! - **RGB**: each output channel is a weighted sum of bands, stretched over a
+//!   value range and gamma-corrected (true color, air mass, ...)
+//! - **Sandwich**: a grayscale base channel (usually visible reflectance)
+//!   multiplied with a color-enhanced overlay (usually cold IR cloud tops)
+//! - **Packed RGB**: colors composited at ingestion and stored in a single
+//!   band as `0xRRGGBB` values (GOES true color)
+//!
+//! ## Channel stretch
+//!
+//! Each channel maps its combined value `v` to `[0, 1]` with
+//! `t = (v - min) / (max - min)`, clamped, then applies `t^(1/gamma)`.
+//! Setting `min > max` inverts the channel (useful for IR brightness
+//! temperatures, where colder should be brighter).
+
+use crate::style::{hex_to_rgba, interpolate_color_at_value, ColorStop};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Style type for styles that carry a composite recipe.
+pub const COMPOSITE_STYLE_TYPE: &str = "rgb_composite";
+
+/// Channels of a packed RGB band, in [`packed_channel`] order.
+pub const PACKED_CHANNELS: [&str; 3] = ["red", "green", "blue"];
+
+/// Name under which channel `index` (0 = red, 1 = green, 2 = blue) of the
+/// packed RGB band `band` is passed to [`CompositeRecipe::render`].
+pub fn packed_channel(band: &str, index: usize) -> String {
+    format!("{}.{}", band, PACKED_CHANNELS[index])
+}
+
+/// One band's contribution to a channel.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BandTerm {
+    /// Parameter name of the band (e.g., "CMI_C02")
+    pub band: String,
+    /// Weight applied to the band value (default: 1.0)
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+/// A single composite channel: weighted band sum, stretch range and gamma.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CompositeChannel {
+    pub bands: Vec<BandTerm>,
+    /// Value range mapped to [0, 1] as `[min, max]`; `min > max` inverts
+    pub range: [f32; 2],
+    /// Gamma correction (default: 1.0, no correction)
+    #[serde(default = "default_gamma")]
+    pub gamma: f32,
+}
+
+/// Composite recipe.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CompositeRecipe {
+    /// Independent red, green and blue channels
+    Rgb {
+        red: CompositeChannel,
+        green: CompositeChannel,
+        blue: CompositeChannel,
+    },
+    /// Grayscale base multiplied with a color-mapped overlay
+    Sandwich {
+        base: CompositeChannel,
+        overlay: CompositeChannel,
+        /// Colors for the stretched overlay value (0.0 to 1.0). Transparent
+        /// stops leave the base showing through.
+        overlay_stops: Vec<ColorStop>,
+    },
+    /// Colors stored in one band as packed `0xRRGGBB` values. Interpolating
+    /// packed values would mix the channels, so callers unpack the band into
+    /// its channels (each in [0, 1]) before resampling and pass them under
+    /// the names from [`packed_channel`].
+    PackedRgb { band: String },
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_gamma() -> f32 {
+    1.0
+}
+
+impl CompositeChannel {
+    /// Combine and stretch the band values at `idx` to [0, 1].
+    ///
+    /// Returns None if any contributing band is missing (NaN) at that pixel.
+    fn value_at(&self, bands: &[(&[f32], f32)], idx: usize) -> Option<f32> {
+        let mut sum = 0.0;
+        for (data, weight) in bands {
+            let v = data[idx];
+            if v.is_nan() {
+                return None;
+            }
+            sum += v * weight;
+        }
+
+        let [min, max] = self.range;
+        let span = max - min;
+        if span == 0.0 {
+            return None;
+        }
+
+        let t = ((sum - min) / span).clamp(0.0, 1.0);
+        if self.gamma > 0.0 && self.gamma != 1.0 {
+            Some(t.powf(1.0 / self.gamma))
+        } else {
+            Some(t)
+        }
+    }
+
+    /// Resolve band names to data slices, checking dimensions.
+    fn resolve<'a>(
+        &self,
+        data: &'a HashMap<String, Vec<f32>>,
+        len: usize,
+    ) -> Result<Vec<(&'a [f32], f32)>, String> {
+        self.bands
+            .iter()
+            .map(|term| {
+                let values = data
+                    .get(&term.band)
+                    .ok_or_else(|| format!("Missing band data for {}", term.band))?;
+                if values.len() != len {
+                    return Err(format!(
+                        "Band {} has {} values, expected {}",
+                        term.band,
+                        values.len(),
+                        len
+                    ));
+                }
+                Ok((values.as_slice(), term.weight))
+            })
+            .collect()
+    }
+}
+
+impl CompositeRecipe {
+    /// Parameter names of all bands the recipe reads, in first-use order.
+    pub fn required_bands(&self) -> Vec<String> {
+        let channels: Vec<&CompositeChannel> = match self {
+            CompositeRecipe::Rgb { red, green, blue } => vec![red, green, blue],
+            CompositeRecipe::Sandwich { base, overlay, .. } => vec![base, overlay],
+            CompositeRecipe::PackedRgb { band } => return vec![band.clone()],
+        };
+
+        let mut bands: Vec<String> = Vec::new();
+        for term in channels.iter().flat_map(|c| &c.bands) {
+            if !bands.contains(&term.band) {
+                bands.push(term.band.clone());
+            }
+        }
+        bands
+    }
+
+    /// The packed RGB band of a [`CompositeRecipe::PackedRgb`] recipe.
+    pub fn packed_band(&self) -> Option<&str> {
+        match self {
+            CompositeRecipe::PackedRgb { band } => Some(band),
+            _ => None,
+        }
+    }
+
+    /// Render the composite to RGBA pixels.
+    ///
+    /// `data` maps band names to grids of `width * height` values that are
+    /// already resampled to the output image. Pixels where any required band
+    /// is missing are transparent.
+    pub fn render(
+        &self,
+        data: &HashMap<String, Vec<f32>>,
+        width: usize,
+        height: usize,
+    ) -> Result<Vec<u8>, String> {
+        let len = width * height;
+        let mut pixels = vec![0u8; len * 4];
+
+        match self {
+            CompositeRecipe::Rgb { red, green, blue } => {
+                let (r, g, b) = (
+                    red.resolve(data, len)?,
+                    green.resolve(data, len)?,
+                    blue.resolve(data, len)?,
+                );
+
+                pixels.par_chunks_mut(4).enumerate().for_each(|(idx, px)| {
+                    if let (Some(rv), Some(gv), Some(bv)) = (
+                        red.value_at(&r, idx),
+                        green.value_at(&g, idx),
+                        blue.value_at(&b, idx),
+                    ) {
+                        px.copy_from_slice(&[to_u8(rv), to_u8(gv), to_u8(bv), 255]);
+                    }
+                });
+            }
+            CompositeRecipe::Sandwich {
+                base,
+                overlay,
+                overlay_stops,
+            } => {
+                let (base_bands, overlay_bands) =
+                    (base.resolve(data, len)?, overlay.resolve(data, len)?);
+
+                let mut stops: Vec<(f32, (u8, u8, u8, u8))> = overlay_stops
+                    .iter()
+                    .filter_map(|s| hex_to_rgba(&s.color).map(|c| (s.value, c)))
+                    .collect();
+                stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                pixels.par_chunks_mut(4).enumerate().for_each(|(idx, px)| {
+                    let Some(gray) = base.value_at(&base_bands, idx) else {
+                        return;
+                    };
+                    let (r, g, b, a) = overlay
+                        .value_at(&overlay_bands, idx)
+                        .map(|t| interpolate_color_at_value(t, &stops))
+                        .unwrap_or((0, 0, 0, 0));
+
+                    // Multiply blend, weighted by the overlay alpha
+                    let alpha = a as f32 / 255.0;
+                    let blend = |c: u8| gray * (1.0 - alpha + alpha * c as f32 / 255.0);
+                    px.copy_from_slice(&[to_u8(blend(r)), to_u8(blend(g)), to_u8(blend(b)), 255]);
+                });
+            }
+            CompositeRecipe::PackedRgb { band } => {
+                let mut channels = Vec::with_capacity(PACKED_CHANNELS.len());
+                for index in 0..PACKED_CHANNELS.len() {
+                    let name = packed_channel(band, index);
+                    let values = data
+                        .get(&name)
+                        .ok_or_else(|| format!("Missing band data for {}", name))?;
+                    if values.len() != len {
+                        return Err(format!(
+                            "Band {} has {} values, expected {}",
+                            name,
+                            values.len(),
+                            len
+                        ));
+                    }
+                    channels.push(values.as_slice());
+                }
+
+                pixels.par_chunks_mut(4).enumerate().for_each(|(idx, px)| {
+                    let [r, g, b] = [channels[0][idx], channels[1][idx], channels[2][idx]];
+                    if !(r.is_nan() || g.is_nan() || b.is_nan()) {
+                        px.copy_from_slice(&[to_u8(r), to_u8(g), to_u8(b), 255]);
+                    }
+                });
+            }
+        }
+
+        Ok(pixels)
+    }
+}
+
+#[inline]
+fn to_u8(t: f32) -> u8 {
+    (t * 255.0).round().clamp(0.0, 255.0) as u8
+}
@@ -893,6 +893,71 @@ pub fn render_contours(
     render_contours_to_canvas(&contours, width, height, config)
 }
 
+/// Map contour points from source grid indices to output pixels.
+///
+/// `to_pixel` returns None for points with no place in the output (e.g.
+/// outside the output projection's domain); lines are split there. Split
+/// contours are no longer closed.
+pub fn transform_contours(
+    contours: &[Contour],
+    to_pixel: impl Fn(Point) -> Option<Point>,
+) -> Vec<Contour> {
+    let mut transformed = Vec::new();
+
+    for contour in contours {
+        let mut pieces: Vec<Vec<Point>> = vec![Vec::new()];
+        for &point in &contour.points {
+            match to_pixel(point) {
+                Some(pixel) => pieces.last_mut().unwrap().push(pixel),
+                None if !pieces.last().unwrap().is_empty() => pieces.push(Vec::new()),
+                None => {}
+            }
+        }
+
+        let whole = pieces.len() == 1;
+        transformed.extend(
+            pieces
+                .into_iter()
+                .filter(|points| points.len() >= 2)
+                .map(|points| Contour {
+                    level: contour.level,
+                    closed: contour.closed && whole && points.len() == contour.points.len(),
+                    points,
+                }),
+        );
+    }
+
+    transformed
+}
+
+/// Render contours traced on a source grid onto an output canvas.
+///
+/// Unlike [`render_contours`], the data is not resampled to the output
+/// first: contours are traced at the source grid's own resolution (e.g. a
+/// model's native projection) and their points mapped to output pixels
+/// with `to_pixel` (see [`transform_contours`]). This keeps features
+/// smaller than an output pixel from being smoothed away when zoomed in.
+pub fn render_contours_reprojected(
+    data: &[f32],
+    data_width: usize,
+    data_height: usize,
+    width: usize,
+    height: usize,
+    config: &ContourConfig,
+    to_pixel: impl Fn(Point) -> Option<Point>,
+) -> Vec<u8> {
+    let contours = generate_all_contours(data, data_width, data_height, config);
+    let contours = transform_contours(&contours, to_pixel);
+
+    tracing::debug!(
+        num_contours = contours.len(),
+        total_points = contours.iter().map(|c| c.points.len()).sum::<usize>(),
+        "Generated reprojected contours"
+    );
+
+    render_contours_to_canvas(&contours, width, height, config)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -920,6 +985,33 @@ mod tests {
         assert_eq!(segments.len(), 0); // No contour for flat field
     }
 
+    #[test]
+    fn test_transform_contours_splits_unmapped_points() {
+        let square = Contour {
+            level: 1.0,
+            points: vec![
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 0.0),
+                Point::new(1.0, 1.0),
+                Point::new(0.0, 1.0),
+            ],
+            closed: true,
+        };
+
+        let scaled = transform_contours(std::slice::from_ref(&square), |p| {
+            Some(Point::new(p.x * 10.0, p.y * 10.0))
+        });
+        assert_eq!(scaled.len(), 1);
+        assert!(scaled[0].closed);
+        assert_eq!(scaled[0].points[2], Point::new(10.0, 10.0));
+
+        // Dropping (1, 1) leaves two open pieces, one too short to draw
+        let split = transform_contours(&[square], |p| (p != Point::new(1.0, 1.0)).then_some(p));
+        assert_eq!(split.len(), 1);
+        assert!(!split[0].closed);
+        assert_eq!(split[0].points.len(), 2);
+    }
+
     #[test]
     fn test_march_squares_simple() {
         // Simple 3x3 grid with peak in center
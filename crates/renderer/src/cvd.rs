@@ -0,0 +1,311 @@
+//! Color vision deficiency (CVD) palette transforms.
+//!
+//! Generates colorblind-safe variants of gradient styles and validates how
+//! much of a palette's contrast survives under simulated red-green color
+//! blindness (protanopia and deuteranopia).
+//!
+//! ## How variants are generated
+//!
+//! For each style, [`cvd_safe_stops`] tries progressively stronger transforms
+//! and keeps the first one that passes validation:
+//!
+//! 1. The original stops, if they already retain enough contrast
+//! 2. Daltonized stops (red-green error shifted into the blue channel)
+//! 3. Stops remapped onto the cividis ramp, which is designed to look the
+//!    same with and without red-green CVD
+//!
+//! Validation simulates each CVD type with the Machado et al. (2009) matrices
+//! and compares CIE76 ΔE between adjacent stops against normal vision.
+
+use crate::style::{hex_to_rgba, ColorStop, StyleConfig, StyleDefinition};
+
+/// Suffix appended to a style name for its colorblind-safe variant.
+pub const CVD_STYLE_SUFFIX: &str = "_cvd";
+
+/// Minimum fraction of adjacent-stop contrast that must survive simulation
+/// for a palette to count as CVD-safe.
+pub const MIN_CONTRAST_RETENTION: f64 = 0.5;
+
+/// Adjacent stops closer than this ΔE under normal vision are ignored when
+/// computing contrast retention (they are indistinguishable anyway).
+const MIN_NORMAL_DELTA_E: f64 = 1.0;
+
+/// Color vision deficiency types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CvdType {
+    /// Missing L (red) cones
+    Protanopia,
+    /// Missing M (green) cones
+    Deuteranopia,
+    /// Missing S (blue) cones
+    Tritanopia,
+}
+
+impl CvdType {
+    /// Machado et al. (2009) simulation matrix (severity 1.0, linear RGB).
+    fn matrix(&self) -> [[f64; 3]; 3] {
+        match self {
+            CvdType::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            CvdType::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            CvdType::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+/// Contrast retention of a palette under red-green CVD.
+///
+/// Each value is the smallest ratio of simulated to normal ΔE over all
+/// adjacent, visible stop pairs (1.0 means no contrast is lost).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CvdReport {
+    pub protanopia: f64,
+    pub deuteranopia: f64,
+}
+
+impl CvdReport {
+    /// Check if both red-green CVD types retain enough contrast.
+    pub fn is_safe(&self) -> bool {
+        self.protanopia >= MIN_CONTRAST_RETENTION && self.deuteranopia >= MIN_CONTRAST_RETENTION
+    }
+}
+
+/// Check if a style type renders through a color ramp and can get a CVD variant.
+pub fn supports_cvd_variant(style_type: &str) -> bool {
+    style_type == "gradient" || style_type == "filled_contour"
+}
+
+/// Name of the colorblind-safe variant of a style.
+pub fn cvd_style_name(style_name: &str) -> String {
+    format!("{}{}", style_name, CVD_STYLE_SUFFIX)
+}
+
+/// Simulate how an sRGB color appears with the given CVD.
+pub fn simulate(rgb: (u8, u8, u8), cvd: CvdType) -> (u8, u8, u8) {
+    let lin = [
+        srgb_to_linear(rgb.0),
+        srgb_to_linear(rgb.1),
+        srgb_to_linear(rgb.2),
+    ];
+    let m = cvd.matrix();
+    let out: Vec<u8> = m
+        .iter()
+        .map(|row| linear_to_srgb(row[0] * lin[0] + row[1] * lin[1] + row[2] * lin[2]))
+        .collect();
+    (out[0], out[1], out[2])
+}
+
+/// Daltonize a color: shift the information lost to the given CVD into
+/// channels that remain visible (Fidaner et al. error redistribution).
+pub fn daltonize(rgb: (u8, u8, u8), cvd: CvdType) -> (u8, u8, u8) {
+    let sim = simulate(rgb, cvd);
+    let err_r = rgb.0 as f64 - sim.0 as f64;
+    let err_g = rgb.1 as f64 - sim.1 as f64;
+    let err_b = rgb.2 as f64 - sim.2 as f64;
+
+    let shift_g = 0.7 * err_r + err_g;
+    let shift_b = 0.7 * err_r + err_b;
+
+    (
+        rgb.0,
+        (rgb.1 as f64 + shift_g).round().clamp(0.0, 255.0) as u8,
+        (rgb.2 as f64 + shift_b).round().clamp(0.0, 255.0) as u8,
+    )
+}
+
+/// CIE76 color difference between two sRGB colors.
+pub fn delta_e(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let la = srgb_to_lab(a);
+    let lb = srgb_to_lab(b);
+    ((la.0 - lb.0).powi(2) + (la.1 - lb.1).powi(2) + (la.2 - lb.2).powi(2)).sqrt()
+}
+
+/// Measure how much adjacent-stop contrast a palette retains under red-green CVD.
+pub fn validate_stops(stops: &[ColorStop]) -> CvdReport {
+    let colors = visible_colors(stops);
+    CvdReport {
+        protanopia: contrast_retention(&colors, CvdType::Protanopia),
+        deuteranopia: contrast_retention(&colors, CvdType::Deuteranopia),
+    }
+}
+
+/// Generate colorblind-safe color stops for a palette.
+///
+/// Stop values, labels, and alpha are preserved; only colors change. Returns
+/// the original stops unchanged if they already pass [`validate_stops`].
+pub fn cvd_safe_stops(stops: &[ColorStop]) -> Vec<ColorStop> {
+    if validate_stops(stops).is_safe() {
+        return stops.to_vec();
+    }
+
+    let daltonized = map_stop_colors(stops, |_, rgb| daltonize(rgb, CvdType::Deuteranopia));
+    if validate_stops(&daltonized).is_safe() {
+        return daltonized;
+    }
+
+    let last = stops.len().saturating_sub(1).max(1) as f64;
+    map_stop_colors(stops, |i, _| cividis(i as f64 / last))
+}
+
+/// Build the colorblind-safe variant of a style definition.
+///
+/// Returns None for style types that don't render through a color ramp.
+pub fn cvd_variant(style: &StyleDefinition) -> Option<StyleDefinition> {
+    if !supports_cvd_variant(&style.style_type) || style.stops.is_empty() {
+        return None;
+    }
+
+    let mut variant = style.clone();
+    variant.name = format!("{} (colorblind-safe)", style.name);
+    variant.default = false;
+    variant.stops = cvd_safe_stops(&style.stops);
+    Some(variant)
+}
+
+impl StyleConfig {
+    /// Add a `<style>_cvd` colorblind-safe variant for every gradient style.
+    ///
+    /// Styles that already define a `_cvd` variant (or are one) are left alone,
+    /// so hand-tuned variants in the style file take precedence.
+    pub fn with_cvd_variants(mut self) -> Self {
+        let variants: Vec<(String, StyleDefinition)> = self
+            .styles
+            .iter()
+            .filter(|(name, _)| !name.ends_with(CVD_STYLE_SUFFIX))
+            .filter(|(name, _)| !self.styles.contains_key(&cvd_style_name(name)))
+            .filter_map(|(name, style)| cvd_variant(style).map(|v| (cvd_style_name(name), v)))
+            .collect();
+
+        self.styles.extend(variants);
+        self
+    }
+}
+
+// ============================================================================
+// Internal helpers
+// ============================================================================
+
+/// Cividis control points (Nuñez et al. 2018), evenly spaced from 0 to 1.
+const CIVIDIS: [(u8, u8, u8); 5] = [
+    (0, 34, 78),
+    (65, 77, 108),
+    (124, 123, 120),
+    (188, 175, 111),
+    (254, 232, 56),
+];
+
+/// Sample the cividis ramp at `t` in [0, 1].
+fn cividis(t: f64) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0) * (CIVIDIS.len() - 1) as f64;
+    let i = (t.floor() as usize).min(CIVIDIS.len() - 2);
+    let f = t - i as f64;
+    let (a, b) = (CIVIDIS[i], CIVIDIS[i + 1]);
+    let lerp = |x: u8, y: u8| (x as f64 + (y as f64 - x as f64) * f).round() as u8;
+    (lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+}
+
+/// Rewrite stop colors, keeping alpha and leaving fully transparent stops untouched.
+fn map_stop_colors<F>(stops: &[ColorStop], f: F) -> Vec<ColorStop>
+where
+    F: Fn(usize, (u8, u8, u8)) -> (u8, u8, u8),
+{
+    stops
+        .iter()
+        .enumerate()
+        .map(|(i, stop)| {
+            let mut stop = stop.clone();
+            if let Some((r, g, b, a)) = hex_to_rgba(&stop.color) {
+                if a > 0 {
+                    stop.color = rgba_to_hex(f(i, (r, g, b)), a);
+                }
+            }
+            stop
+        })
+        .collect()
+}
+
+/// Colors of the stops that are visible (parseable and not fully transparent).
+fn visible_colors(stops: &[ColorStop]) -> Vec<(u8, u8, u8)> {
+    stops
+        .iter()
+        .filter_map(|s| hex_to_rgba(&s.color))
+        .filter(|&(_, _, _, a)| a > 0)
+        .map(|(r, g, b, _)| (r, g, b))
+        .collect()
+}
+
+fn contrast_retention(colors: &[(u8, u8, u8)], cvd: CvdType) -> f64 {
+    colors
+        .windows(2)
+        .filter_map(|pair| {
+            let normal = delta_e(pair[0], pair[1]);
+            if normal < MIN_NORMAL_DELTA_E {
+                return None;
+            }
+            let simulated = delta_e(simulate(pair[0], cvd), simulate(pair[1], cvd));
+            Some(simulated / normal)
+        })
+        .fold(1.0, f64::min)
+}
+
+fn rgba_to_hex((r, g, b): (u8, u8, u8), a: u8) -> String {
+    if a == 255 {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+    }
+}
+
+fn srgb_to_linear(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let s = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0).round() as u8
+}
+
+fn srgb_to_lab(rgb: (u8, u8, u8)) -> (f64, f64, f64) {
+    let (r, g, b) = (
+        srgb_to_linear(rgb.0),
+        srgb_to_linear(rgb.1),
+        srgb_to_linear(rgb.2),
+    );
+
+    // Linear sRGB to XYZ (D65), normalized by the reference white
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883;
+
+    let f = |t: f64| {
+        if t > 0.008856 {
+            t.cbrt()
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
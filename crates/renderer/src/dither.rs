@@ -0,0 +1,230 @@
+//! Dithering for indexed palette rendering.
+//!
+//! Indexed PNGs quantize a smooth field to 255 colors, which shows up as
+//! visible bands on gentle gradients (e.g. temperature). Dithering adds a
+//! per-pixel threshold before quantization so that a value between two
+//! palette colors is rendered as a mix of both, in proportion to where it
+//! falls between them.
+//!
+//! Thresholds depend only on the pixel position, so output stays
+//! deterministic (and cacheable), and both patterns tile seamlessly across
+//! 256-pixel map tiles:
+//!
+//! - `ordered`: 8x8 Bayer matrix. Cheapest; leaves a faint cross-hatch.
+//! - `blue_noise`: 64x64 void-and-cluster texture. No visible structure;
+//!   roughly twice the mapping cost of `ordered`.
+//!
+//! Enabled per style with `"dither": "ordered"` or `"dither": "blue_noise"`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Dithering pattern applied when mapping values to palette indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DitherMode {
+    /// 8x8 Bayer ordered dithering
+    Ordered,
+    /// 64x64 blue-noise threshold texture
+    BlueNoise,
+}
+
+impl DitherMode {
+    /// Threshold in `[0, 1)` for the pixel at (`x`, `y`).
+    #[inline]
+    pub fn threshold(&self, x: usize, y: usize) -> f32 {
+        match self {
+            DitherMode::Ordered => BAYER_8X8[y % 8][x % 8] as f32 / 64.0 + 0.5 / 64.0,
+            DitherMode::BlueNoise => {
+                let texture = blue_noise_texture();
+                texture[(y % BLUE_NOISE_SIZE) * BLUE_NOISE_SIZE + x % BLUE_NOISE_SIZE]
+            }
+        }
+    }
+
+    /// Thresholds for row `y`; the pattern repeats every `row.len()` pixels.
+    pub(crate) fn row(&self, y: usize) -> &'static [f32] {
+        match self {
+            DitherMode::Ordered => &bayer_rows()[y % 8],
+            DitherMode::BlueNoise => {
+                let start = (y % BLUE_NOISE_SIZE) * BLUE_NOISE_SIZE;
+                &blue_noise_texture()[start..start + BLUE_NOISE_SIZE]
+            }
+        }
+    }
+}
+
+/// Recursive Bayer matrix: each value's rank in the ordered pattern.
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+fn bayer_rows() -> &'static [Vec<f32>; 8] {
+    static ROWS: OnceLock<[Vec<f32>; 8]> = OnceLock::new();
+    ROWS.get_or_init(|| {
+        std::array::from_fn(|y| {
+            (0..8)
+                .map(|x| DitherMode::Ordered.threshold(x, y))
+                .collect()
+        })
+    })
+}
+
+/// Side of the blue-noise texture. Divides 256, so tiles stay seamless.
+const BLUE_NOISE_SIZE: usize = 64;
+
+/// Blue-noise thresholds, generated once on first use.
+fn blue_noise_texture() -> &'static [f32] {
+    static TEXTURE: OnceLock<Vec<f32>> = OnceLock::new();
+    TEXTURE.get_or_init(|| {
+        let n = BLUE_NOISE_SIZE * BLUE_NOISE_SIZE;
+        void_and_cluster(BLUE_NOISE_SIZE, 1.5)
+            .into_iter()
+            .map(|rank| (rank as f32 + 0.5) / n as f32)
+            .collect()
+    })
+}
+
+/// Rank every pixel of a `size` x `size` toroidal grid with Ulichney's
+/// void-and-cluster method, using a Gaussian of `sigma` pixels as the
+/// energy filter. Returns ranks `0..size²` in row-major order.
+fn void_and_cluster(size: usize, sigma: f32) -> Vec<u32> {
+    let n = size * size;
+
+    // Filter weight by toroidal offset
+    let kernel: Vec<f32> = (0..n)
+        .map(|i| {
+            let wrap = |d: usize| d.min(size - d) as f32;
+            let (dx, dy) = (wrap(i % size), wrap(i / size));
+            (-(dx * dx + dy * dy) / (2.0 * sigma * sigma)).exp()
+        })
+        .collect();
+
+    let mut pattern = vec![false; n];
+    let mut energy = vec![0.0f32; n];
+    let toggle = |pattern: &mut [bool], energy: &mut [f32], p: usize| {
+        pattern[p] = !pattern[p];
+        let sign = if pattern[p] { 1.0 } else { -1.0 };
+        let (px, py) = (p % size, p / size);
+        for (q, e) in energy.iter_mut().enumerate() {
+            let dx = (q % size + size - px) % size;
+            let dy = (q / size + size - py) % size;
+            *e += sign * kernel[dy * size + dx];
+        }
+    };
+    // Tightest cluster: the set pixel with the most energy. Largest void:
+    // the unset pixel with the least.
+    let extreme = |pattern: &[bool], energy: &[f32], set: bool| -> usize {
+        let candidates = (0..n).filter(|&p| pattern[p] == set);
+        if set {
+            candidates
+                .max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+                .unwrap()
+        } else {
+            candidates
+                .min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+                .unwrap()
+        }
+    };
+
+    // Initial pattern: a tenth of the pixels, chosen by a fixed LCG so the
+    // texture is identical on every run
+    let initial = n / 10;
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut placed = 0;
+    while placed < initial {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        let p = (state >> 33) as usize % n;
+        if !pattern[p] {
+            toggle(&mut pattern, &mut energy, p);
+            placed += 1;
+        }
+    }
+
+    // Spread it out: move the tightest cluster into the largest void until
+    // that no longer changes anything
+    loop {
+        let cluster = extreme(&pattern, &energy, true);
+        toggle(&mut pattern, &mut energy, cluster);
+        let void = extreme(&pattern, &energy, false);
+        toggle(&mut pattern, &mut energy, void);
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0u32; n];
+
+    // Ranks below the initial count: remove clusters from a copy
+    let (mut working, mut working_energy) = (pattern.clone(), energy.clone());
+    for rank in (0..initial).rev() {
+        let cluster = extreme(&working, &working_energy, true);
+        toggle(&mut working, &mut working_energy, cluster);
+        ranks[cluster] = rank as u32;
+    }
+
+    // Ranks from the initial count up: fill voids
+    for rank in initial..n {
+        let void = extreme(&pattern, &energy, false);
+        toggle(&mut pattern, &mut energy, void);
+        ranks[void] = rank as u32;
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thresholds_cover_unit_interval_evenly() {
+        for mode in [DitherMode::Ordered, DitherMode::BlueNoise] {
+            let size = if mode == DitherMode::Ordered { 8 } else { 64 };
+            let mut thresholds: Vec<f32> = (0..size * size)
+                .map(|i| mode.threshold(i % size, i / size))
+                .collect();
+            thresholds.sort_by(f32::total_cmp);
+
+            // Each rank appears exactly once
+            for (rank, t) in thresholds.iter().enumerate() {
+                let expected = (rank as f32 + 0.5) / (size * size) as f32;
+                assert!((t - expected).abs() < 1e-6, "{:?} rank {}", mode, rank);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rows_match_thresholds() {
+        for mode in [DitherMode::Ordered, DitherMode::BlueNoise] {
+            let row = mode.row(13);
+            for x in 0..130 {
+                assert_eq!(row[x % row.len()], mode.threshold(x, 13));
+            }
+        }
+    }
+
+    #[test]
+    fn test_blue_noise_has_no_low_frequency_clumps() {
+        // Any 8x8 window of a well-spread texture holds a near-even share of
+        // low thresholds; a white-noise texture routinely misses by far more
+        let size = BLUE_NOISE_SIZE;
+        for wy in (0..size).step_by(8) {
+            for wx in (0..size).step_by(8) {
+                let low = (0..64)
+                    .filter(|i| DitherMode::BlueNoise.threshold(wx + i % 8, wy + i / 8) < 0.25)
+                    .count();
+                assert!((10..=22).contains(&low), "window ({}, {}): {}", wx, wy, low);
+            }
+        }
+    }
+}
@@ -0,0 +1,502 @@
+//! Arithmetic expressions in a single variable, used by value transforms.
+//!
+//! Styles can transform data before color mapping with an expression in `x`,
+//! the raw data value:
+//!
+//! ```json
+//! "transform": "log10(x + 1)"
+//! "transform": { "type": "expression", "expression": "10 * log10(x)", "inverse": "10 ^ (x / 10)" }
+//! ```
+//!
+//! Supported syntax:
+//! - numbers (`1`, `0.5`, `1e-3`), the variable `x`, and the constants `pi` and `e`
+//! - operators `+ - * / ^` with the usual precedence (`^` is right-associative)
+//! - functions `log10`, `log2`, `ln`, `exp`, `sqrt`, `abs`, `floor`, `ceil`,
+//!   `min(a, b)`, `max(a, b)` and `pow(a, b)`
+//!
+//! Expressions are parsed once when the style is loaded and evaluated per pixel.
+//! Results outside a function's domain (e.g. `log10` of a negative value) are NaN.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed expression in `x`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+/// Error parsing an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpressionError {
+    /// Byte offset in the source where the error was found
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+impl Expression {
+    /// Parse an expression.
+    pub fn parse(source: &str) -> Result<Self, ExpressionError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            end: source.len(),
+        };
+        let root = parser.expr()?;
+        if let Some((token, position)) = parser.tokens.get(parser.pos) {
+            return Err(ExpressionError {
+                position: *position,
+                message: format!("unexpected {}", token),
+            });
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    /// Evaluate with `x` set to `value`.
+    pub fn eval(&self, value: f32) -> f32 {
+        self.root.eval(value as f64) as f32
+    }
+
+    /// The expression as written in the style.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ExpressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Expression {
+    type Error = ExpressionError;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::parse(&source)
+    }
+}
+
+impl From<Expression> for String {
+    fn from(expression: Expression) -> Self {
+        expression.source
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Log10,
+    Log2,
+    Ln,
+    Exp,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+    Pow,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "log10" => Self::Log10,
+            "log2" => Self::Log2,
+            "ln" => Self::Ln,
+            "exp" => Self::Exp,
+            "sqrt" => Self::Sqrt,
+            "abs" => Self::Abs,
+            "floor" => Self::Floor,
+            "ceil" => Self::Ceil,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "pow" => Self::Pow,
+            _ => return None,
+        })
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Self::Min | Self::Max | Self::Pow => 2,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Var,
+    Neg(Box<Node>),
+    Add(Box<Node>, Box<Node>),
+    Sub(Box<Node>, Box<Node>),
+    Mul(Box<Node>, Box<Node>),
+    Div(Box<Node>, Box<Node>),
+    Pow(Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+impl Node {
+    fn eval(&self, x: f64) -> f64 {
+        match self {
+            Node::Number(n) => *n,
+            Node::Var => x,
+            Node::Neg(a) => -a.eval(x),
+            Node::Add(a, b) => a.eval(x) + b.eval(x),
+            Node::Sub(a, b) => a.eval(x) - b.eval(x),
+            Node::Mul(a, b) => a.eval(x) * b.eval(x),
+            Node::Div(a, b) => a.eval(x) / b.eval(x),
+            Node::Pow(a, b) => a.eval(x).powf(b.eval(x)),
+            Node::Call(func, args) => {
+                let a = args[0].eval(x);
+                match func {
+                    Function::Log10 => a.log10(),
+                    Function::Log2 => a.log2(),
+                    Function::Ln => a.ln(),
+                    Function::Exp => a.exp(),
+                    Function::Sqrt => a.sqrt(),
+                    Function::Abs => a.abs(),
+                    Function::Floor => a.floor(),
+                    Function::Ceil => a.ceil(),
+                    Function::Min => a.min(args[1].eval(x)),
+                    Function::Max => a.max(args[1].eval(x)),
+                    Function::Pow => a.powf(args[1].eval(x)),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number {}", n),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::LParen => f.write_str("'('"),
+            Token::RParen => f.write_str("')'"),
+            Token::Comma => f.write_str("','"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '+' | '-' | '*' | '/' | '^' => {
+                chars.next();
+                Token::Op(c)
+            }
+            '(' => {
+                chars.next();
+                Token::LParen
+            }
+            ')' => {
+                chars.next();
+                Token::RParen
+            }
+            ',' => {
+                chars.next();
+                Token::Comma
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let mut end = start;
+                let mut prev = c;
+                while let Some(&(i, c)) = chars.peek() {
+                    // Allow an exponent sign directly after 'e' (1e-3)
+                    let exponent_sign = (c == '-' || c == '+') && (prev == 'e' || prev == 'E');
+                    if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                        end = i + c.len_utf8();
+                        prev = c;
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let text = &source[start..end];
+                let number = text.parse().map_err(|_| ExpressionError {
+                    position: start,
+                    message: format!("invalid number '{}'", text),
+                })?;
+                Token::Number(number)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start;
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        end = i + c.len_utf8();
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                Token::Ident(source[start..end].to_string())
+            }
+            c => {
+                return Err(ExpressionError {
+                    position: start,
+                    message: format!("unexpected character '{}'", c),
+                })
+            }
+        };
+        tokens.push((token, start));
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive-descent parser:
+///
+/// ```text
+/// expr    := term (('+' | '-') term)*
+/// term    := unary (('*' | '/') unary)*
+/// unary   := '-' unary | power
+/// power   := primary ('^' unary)?
+/// primary := number | 'x' | constant | function '(' args ')' | '(' expr ')'
+/// ```
+struct Parser<'a> {
+    tokens: &'a [(Token, usize)],
+    pos: usize,
+    end: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(_, p)| *p)
+            .unwrap_or(self.end)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ExpressionError {
+        ExpressionError {
+            position: self.position(),
+            message: message.into(),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ExpressionError> {
+        match self.peek() {
+            Some(t) if *t == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(t) => Err(self.error(format!("expected {}, found {}", expected, t))),
+            None => Err(self.error(format!("expected {}, found end of expression", expected))),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let rhs = self.term()?;
+            node = if op == '+' {
+                Node::Add(Box::new(node), Box::new(rhs))
+            } else {
+                Node::Sub(Box::new(node), Box::new(rhs))
+            };
+        }
+        Ok(node)
+    }
+
+    fn term(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek() {
+            let op = *op;
+            self.pos += 1;
+            let rhs = self.unary()?;
+            node = if op == '*' {
+                Node::Mul(Box::new(node), Box::new(rhs))
+            } else {
+                Node::Div(Box::new(node), Box::new(rhs))
+            };
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, ExpressionError> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.pos += 1;
+                Ok(Node::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Op('+')) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Node, ExpressionError> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            let exponent = self.unary()?;
+            return Ok(Node::Pow(Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node, ExpressionError> {
+        let token = match self.peek() {
+            Some(t) => t.clone(),
+            None => return Err(self.error("unexpected end of expression")),
+        };
+
+        match token {
+            Token::Number(n) => {
+                self.pos += 1;
+                Ok(Node::Number(n))
+            }
+            Token::LParen => {
+                self.pos += 1;
+                let node = self.expr()?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Token::Ident(name) => {
+                let start = self.position();
+                self.pos += 1;
+                match name.as_str() {
+                    "x" => return Ok(Node::Var),
+                    "pi" => return Ok(Node::Number(std::f64::consts::PI)),
+                    "e" => return Ok(Node::Number(std::f64::consts::E)),
+                    _ => {}
+                }
+
+                let func = Function::from_name(&name).ok_or_else(|| ExpressionError {
+                    position: start,
+                    message: if name == "log" {
+                        "ambiguous function 'log' (use 'ln' or 'log10')".to_string()
+                    } else {
+                        format!("unknown identifier '{}'", name)
+                    },
+                })?;
+
+                self.expect(Token::LParen)?;
+                let mut args = vec![self.expr()?];
+                while let Some(Token::Comma) = self.peek() {
+                    self.pos += 1;
+                    args.push(self.expr()?);
+                }
+                self.expect(Token::RParen)?;
+
+                if args.len() != func.arity() {
+                    return Err(ExpressionError {
+                        position: start,
+                        message: format!(
+                            "'{}' takes {} argument(s), got {}",
+                            name,
+                            func.arity(),
+                            args.len()
+                        ),
+                    });
+                }
+                Ok(Node::Call(func, args))
+            }
+            t => Err(self.error(format!("unexpected {}", t))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, x: f32) -> f32 {
+        Expression::parse(source).unwrap().eval(x)
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(eval("1 + 2 * 3", 0.0), 7.0);
+        assert_eq!(eval("(1 + 2) * 3", 0.0), 9.0);
+        assert_eq!(eval("2 ^ 3 ^ 2", 0.0), 512.0);
+        assert_eq!(eval("-x ^ 2", 3.0), -9.0);
+        assert_eq!(eval("10 - 4 - 3", 0.0), 3.0);
+        assert_eq!(eval("x / 2 / 2", 8.0), 2.0);
+    }
+
+    #[test]
+    fn test_functions_and_constants() {
+        assert!((eval("log10(x + 1)", 99.0) - 2.0).abs() < 1e-6);
+        assert!((eval("ln(e)", 0.0) - 1.0).abs() < 1e-6);
+        assert_eq!(eval("max(x, 0)", -5.0), 0.0);
+        assert_eq!(eval("pow(x, 2) + sqrt(16)", 3.0), 13.0);
+        assert!((eval("2 * pi", 0.0) - std::f32::consts::TAU).abs() < 1e-6);
+        assert_eq!(eval("1e-3 * x", 2000.0), 2.0);
+        assert!(eval("log10(x)", -1.0).is_nan());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for source in [
+            "", "x +", "(x", "foo(x)", "log(x)", "min(x)", "x $ 2", "2 x",
+        ] {
+            assert!(
+                Expression::parse(source).is_err(),
+                "{:?} should fail",
+                source
+            );
+        }
+
+        let err = Expression::parse("x + y").unwrap_err();
+        assert_eq!(err.position, 4);
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let expr: Expression = serde_json::from_str("\"10 * log10(x)\"").unwrap();
+        assert_eq!(expr.eval(100.0), 20.0);
+        assert_eq!(serde_json::to_string(&expr).unwrap(), "\"10 * log10(x)\"");
+        assert!(serde_json::from_str::<Expression>("\"10 *\"").is_err());
+    }
+}
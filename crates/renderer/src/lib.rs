@@ -6,6 +6,11 @@
 //! - Wind barbs
 //! - Wind arrows
 //! - Style-based color mapping
+//! - Colorblind-safe (CVD) palette variants
+//! - Multi-band RGB composites (satellite true color, sandwich)
+//! - Masking by a secondary field (e.g. precip type by precip rate)
+//! - Value transform expressions (e.g. `log10(x + 1)`) applied before color mapping
+//! - Ordered and blue-noise dithering of indexed palettes to remove banding
 //!
 //! ## Performance Optimizations
 //!
@@ -21,8 +26,13 @@
 
 pub mod barbs;
 pub mod buffer_pool;
+pub mod composite;
 pub mod contour;
+pub mod cvd;
+pub mod dither;
+pub mod expression;
 pub mod gradient;
+pub mod mask;
 pub mod png;
 pub mod style;
 
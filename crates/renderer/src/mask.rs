@@ -0,0 +1,88 @@
+//! Per-pixel masking of a rendered field by a secondary field.
+//!
+//! Compound visualizations hide one parameter where another fails a
+//! threshold, e.g. precipitation type only where the precipitation rate is
+//! above zero. A style opts in with a `mask` entry:
+//!
+//! ```json
+//! "mask": { "parameter": "PRATE", "comparison": "gt", "threshold": 0.00001 }
+//! ```
+//!
+//! The caller resamples the mask field onto the same output grid as the
+//! rendered data and passes it to the style gradient functions as a
+//! [`MaskInput`]. The mask is applied before palette mapping: masked pixels,
+//! and pixels where the mask value is missing, are transparent.
+
+use crate::style::{apply_transform, Transform};
+use serde::{Deserialize, Serialize};
+
+/// Comparison between a mask value and the threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskComparison {
+    /// Show where mask value > threshold
+    #[default]
+    Gt,
+    /// Show where mask value >= threshold
+    Gte,
+    /// Show where mask value < threshold
+    Lt,
+    /// Show where mask value <= threshold
+    Lte,
+}
+
+/// Mask configuration from a style definition.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DataMask {
+    /// Parameter of the same model providing the mask values (e.g., "PRATE")
+    pub parameter: String,
+    /// Level of the mask parameter (default: its first available level)
+    pub level: Option<String>,
+    /// Comparison applied to the mask value (default: "gt")
+    #[serde(default)]
+    pub comparison: MaskComparison,
+    /// Threshold, in mask units after `transform`
+    pub threshold: f32,
+    /// Unit transform applied to mask values before comparing
+    pub transform: Option<Transform>,
+}
+
+impl DataMask {
+    /// Whether a pixel with this mask value is shown.
+    ///
+    /// Missing (NaN) mask values hide the pixel.
+    pub fn shows(&self, mask_value: f32) -> bool {
+        if mask_value.is_nan() {
+            return false;
+        }
+
+        let value = apply_transform(mask_value, self.transform.as_ref());
+        match self.comparison {
+            MaskComparison::Gt => value > self.threshold,
+            MaskComparison::Gte => value >= self.threshold,
+            MaskComparison::Lt => value < self.threshold,
+            MaskComparison::Lte => value <= self.threshold,
+        }
+    }
+}
+
+/// A mask field resampled onto the output grid, with the rule to apply.
+#[derive(Debug, Clone, Copy)]
+pub struct MaskInput<'a> {
+    /// Mask values, same dimensions as the rendered data
+    pub values: &'a [f32],
+    pub mask: &'a DataMask,
+}
+
+impl<'a> MaskInput<'a> {
+    pub fn new(values: &'a [f32], mask: &'a DataMask) -> Self {
+        Self { values, mask }
+    }
+
+    /// Whether the pixel at `idx` is shown (pixels without a mask value are not).
+    pub fn shows(&self, idx: usize) -> bool {
+        self.values
+            .get(idx)
+            .is_some_and(|&value| self.mask.shows(value))
+    }
+}
@@ -7,10 +7,29 @@
 //!
 //! Use `create_png_auto` for automatic mode selection, or `create_png` for
 //! explicit RGBA encoding.
+//!
+//! ## Compression tuning
+//!
+//! Every encoder has a `_with_options` variant taking [`PngOptions`], which
+//! selects the zlib level, scanline filter and deflate backend. Two profiles
+//! cover the common cases:
+//!
+//! - **Interactive** (default): fast zlib, no filtering. Used for tiles and
+//!   GetMap images rendered on request, where encode time is on the critical
+//!   path.
+//! - **Seeding**: maximum zlib level with adaptive filtering. Used for tiles
+//!   rendered ahead of time (cache warming), where smaller output matters more
+//!   than encode time. [`recompress_png`] re-encodes an existing PNG with these
+//!   options.
+//!
+//! Filtering runs in parallel for large images. The `fdeflate` and `zopfli`
+//! cargo features add the corresponding deflate backends.
 
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::io::Write;
+use std::io::{Read, Write};
+use std::str::FromStr;
+use tracing::warn;
 
 /// Maximum colors for indexed PNG (PNG8)
 const MAX_PALETTE_SIZE: usize = 256;
@@ -18,6 +37,186 @@ const MAX_PALETTE_SIZE: usize = 256;
 /// Minimum pixels to benefit from parallel palette extraction
 const PARALLEL_THRESHOLD: usize = 4096; // 64x64 or larger
 
+/// Minimum image data size (bytes) to filter scanlines in parallel
+const PARALLEL_FILTER_THRESHOLD: usize = 512 * 1024; // ~362x362 RGBA or larger
+
+/// PNG file signature
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+// ============================================================================
+// Encoder Options
+// ============================================================================
+
+/// Scanline filter applied before compression.
+///
+/// Filters make smooth gradients compress better at the cost of encode time.
+/// Palette images usually compress best unfiltered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterStrategy {
+    /// No filtering (fastest)
+    #[default]
+    None,
+    Sub,
+    Up,
+    Average,
+    Paeth,
+    /// Pick the filter with the smallest sum of absolute residuals per row
+    Adaptive,
+}
+
+impl FromStr for FilterStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "sub" => Ok(Self::Sub),
+            "up" => Ok(Self::Up),
+            "average" => Ok(Self::Average),
+            "paeth" => Ok(Self::Paeth),
+            "adaptive" => Ok(Self::Adaptive),
+            other => Err(format!("Unknown PNG filter: {}", other)),
+        }
+    }
+}
+
+/// Deflate implementation used to compress image data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeflateBackend {
+    /// flate2 (miniz_oxide), honours the compression level
+    #[default]
+    Flate2,
+    /// fdeflate: faster than flate2 at its fastest level, with better ratio
+    /// on image data (requires the `fdeflate` feature)
+    Fdeflate,
+    /// zopfli: much slower, ~5% smaller output (requires the `zopfli` feature)
+    Zopfli,
+}
+
+impl DeflateBackend {
+    /// Whether the backend was compiled in.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Self::Flate2 => true,
+            Self::Fdeflate => cfg!(feature = "fdeflate"),
+            Self::Zopfli => cfg!(feature = "zopfli"),
+        }
+    }
+}
+
+impl FromStr for DeflateBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let backend = match s.to_lowercase().as_str() {
+            "flate2" | "zlib" => Self::Flate2,
+            "fdeflate" => Self::Fdeflate,
+            "zopfli" => Self::Zopfli,
+            other => return Err(format!("Unknown PNG deflate backend: {}", other)),
+        };
+        if !backend.is_available() {
+            return Err(format!("PNG deflate backend '{}' is not compiled in", s));
+        }
+        Ok(backend)
+    }
+}
+
+/// Rendering context an encoder profile is tuned for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngProfile {
+    /// Images rendered on request (tiles, GetMap)
+    Interactive,
+    /// Tiles rendered ahead of time (cache warming)
+    Seeding,
+}
+
+impl PngProfile {
+    /// Prefix of the environment variables overriding this profile.
+    fn env_prefix(&self) -> &'static str {
+        match self {
+            Self::Interactive => "PNG",
+            Self::Seeding => "PNG_SEED",
+        }
+    }
+}
+
+/// PNG encoder settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PngOptions {
+    /// zlib compression level (0-9). Only used by the flate2 backend.
+    pub level: u32,
+    /// Scanline filter
+    pub filter: FilterStrategy,
+    /// Deflate implementation
+    pub backend: DeflateBackend,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self::interactive()
+    }
+}
+
+impl PngOptions {
+    /// Fast settings for images rendered on request.
+    pub fn interactive() -> Self {
+        Self {
+            level: 1,
+            filter: FilterStrategy::None,
+            backend: DeflateBackend::Flate2,
+        }
+    }
+
+    /// Compact settings for tiles rendered ahead of time.
+    pub fn seeding() -> Self {
+        Self {
+            level: 9,
+            filter: FilterStrategy::Adaptive,
+            backend: DeflateBackend::Flate2,
+        }
+    }
+
+    /// Preset options for a profile.
+    pub fn for_profile(profile: PngProfile) -> Self {
+        match profile {
+            PngProfile::Interactive => Self::interactive(),
+            PngProfile::Seeding => Self::seeding(),
+        }
+    }
+
+    /// Load options for a profile, applying environment overrides.
+    ///
+    /// Interactive reads `PNG_COMPRESSION_LEVEL`, `PNG_FILTER` and
+    /// `PNG_BACKEND`; seeding reads the same names prefixed `PNG_SEED_`.
+    /// Invalid values are logged and ignored.
+    pub fn from_env(profile: PngProfile) -> Self {
+        let mut options = Self::for_profile(profile);
+        let prefix = profile.env_prefix();
+
+        let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok();
+        if let Some(value) = var("COMPRESSION_LEVEL") {
+            match value.parse::<u32>() {
+                Ok(level) if level <= 9 => options.level = level,
+                _ => warn!(value = %value, "Invalid {}_COMPRESSION_LEVEL (expected 0-9)", prefix),
+            }
+        }
+        if let Some(value) = var("FILTER") {
+            match value.parse() {
+                Ok(filter) => options.filter = filter,
+                Err(e) => warn!(error = %e, "Invalid {}_FILTER", prefix),
+            }
+        }
+        if let Some(value) = var("BACKEND") {
+            match value.parse() {
+                Ok(backend) => options.backend = backend,
+                Err(e) => warn!(error = %e, "Invalid {}_BACKEND", prefix),
+            }
+        }
+
+        options
+    }
+}
+
 /// Create a PNG image with automatic format selection.
 ///
 /// Analyzes the pixel data and chooses the most efficient encoding:
@@ -29,6 +228,16 @@ const PARALLEL_THRESHOLD: usize = 4096; // 64x64 or larger
 /// - `width`: Image width in pixels
 /// - `height`: Image height in pixels
 pub fn create_png_auto(pixels: &[u8], width: usize, height: usize) -> Result<Vec<u8>, String> {
+    create_png_auto_with_options(pixels, width, height, &PngOptions::default())
+}
+
+/// [`create_png_auto`] with explicit encoder options.
+pub fn create_png_auto_with_options(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    options: &PngOptions,
+) -> Result<Vec<u8>, String> {
     let num_pixels = pixels.len() / 4;
 
     // Try to extract a palette (use parallel version for larger images)
@@ -41,11 +250,11 @@ pub fn create_png_auto(pixels: &[u8], width: usize, height: usize) -> Result<Vec
     match palette_result {
         Some((palette, indices)) => {
             // Can use indexed PNG
-            create_png_indexed(width, height, &palette, &indices)
+            create_png_indexed_with_options(width, height, &palette, &indices, options)
         }
         None => {
             // Too many colors, fall back to RGBA
-            create_png(pixels, width, height)
+            create_png_with_options(pixels, width, height, options)
         }
     }
 }
@@ -184,7 +393,24 @@ pub fn create_png_from_precomputed(
     height: usize,
     palette: &PrecomputedPalette,
 ) -> Result<Vec<u8>, String> {
-    create_png_indexed(width, height, &palette.colors, indices)
+    create_png_from_precomputed_with_options(
+        indices,
+        width,
+        height,
+        palette,
+        &PngOptions::default(),
+    )
+}
+
+/// [`create_png_from_precomputed`] with explicit encoder options.
+pub fn create_png_from_precomputed_with_options(
+    indices: &[u8],
+    width: usize,
+    height: usize,
+    palette: &PrecomputedPalette,
+    options: &PngOptions,
+) -> Result<Vec<u8>, String> {
+    create_png_indexed_with_options(width, height, &palette.colors, indices, options)
 }
 
 /// Create an indexed PNG (color type 3) from palette and indices.
@@ -198,11 +424,22 @@ pub fn create_png_indexed(
     height: usize,
     palette: &[(u8, u8, u8, u8)],
     indices: &[u8],
+) -> Result<Vec<u8>, String> {
+    create_png_indexed_with_options(width, height, palette, indices, &PngOptions::default())
+}
+
+/// [`create_png_indexed`] with explicit encoder options.
+pub fn create_png_indexed_with_options(
+    width: usize,
+    height: usize,
+    palette: &[(u8, u8, u8, u8)],
+    indices: &[u8],
+    options: &PngOptions,
 ) -> Result<Vec<u8>, String> {
     let mut png = Vec::new();
 
     // PNG signature
-    png.extend_from_slice(&[137, 80, 78, 71, 13, 10, 26, 10]);
+    png.extend_from_slice(&PNG_SIGNATURE);
 
     // IHDR chunk
     let mut ihdr_data = Vec::with_capacity(13);
@@ -232,8 +469,8 @@ pub fn create_png_indexed(
         write_chunk(&mut png, b"tRNS", &trns_data);
     }
 
-    // IDAT chunk (image data)
-    let idat_data = deflate_idat_indexed(indices, width, height)
+    // IDAT chunk (image data, 1 byte per pixel)
+    let idat_data = encode_idat(&indices[..width * height], width, height, 1, options)
         .map_err(|e| format!("IDAT compression failed: {}", e))?;
     write_chunk(&mut png, b"IDAT", &idat_data);
 
@@ -243,31 +480,6 @@ pub fn create_png_indexed(
     Ok(png)
 }
 
-/// Deflate indexed image data for IDAT chunk.
-fn deflate_idat_indexed(
-    indices: &[u8],
-    width: usize,
-    height: usize,
-) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    // Add filter byte (0 = no filter) to each scanline
-    // For indexed, each row is: filter_byte + width index bytes
-    let mut uncompressed = Vec::with_capacity(height * (1 + width));
-
-    for y in 0..height {
-        uncompressed.push(0); // filter type: none
-        let row_start = y * width;
-        let row_end = row_start + width;
-        uncompressed.extend_from_slice(&indices[row_start..row_end]);
-    }
-
-    // Compress with flate2
-    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
-    encoder.write_all(&uncompressed)?;
-    let compressed = encoder.finish()?;
-
-    Ok(compressed)
-}
-
 /// Create a PNG image from RGBA pixel data (color type 6).
 ///
 /// This is the fallback for images with >256 unique colors.
@@ -277,10 +489,20 @@ fn deflate_idat_indexed(
 /// - `width`: Image width in pixels
 /// - `height`: Image height in pixels
 pub fn create_png(pixels: &[u8], width: usize, height: usize) -> Result<Vec<u8>, String> {
+    create_png_with_options(pixels, width, height, &PngOptions::default())
+}
+
+/// [`create_png`] with explicit encoder options.
+pub fn create_png_with_options(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    options: &PngOptions,
+) -> Result<Vec<u8>, String> {
     let mut png = Vec::new();
 
     // PNG signature
-    png.extend_from_slice(&[137, 80, 78, 71, 13, 10, 26, 10]);
+    png.extend_from_slice(&PNG_SIGNATURE);
 
     // IHDR chunk
     let mut ihdr_data = Vec::new();
@@ -294,7 +516,7 @@ pub fn create_png(pixels: &[u8], width: usize, height: usize) -> Result<Vec<u8>,
     write_chunk(&mut png, b"IHDR", &ihdr_data);
 
     // IDAT chunk (image data)
-    let idat_data = deflate_idat_rgba(pixels, width, height)
+    let idat_data = encode_idat(&pixels[..width * height * 4], width * 4, height, 4, options)
         .map_err(|e| format!("IDAT compression failed: {}", e))?;
     write_chunk(&mut png, b"IDAT", &idat_data);
 
@@ -321,27 +543,280 @@ fn write_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
     png.extend_from_slice(&crc.to_be_bytes());
 }
 
-/// Deflate RGBA image data for IDAT chunk.
-fn deflate_idat_rgba(
-    pixels: &[u8],
-    width: usize,
+/// Filter and compress raw image rows for the IDAT chunk.
+///
+/// `data` holds `height` rows of `stride` bytes; `bpp` is bytes per pixel.
+fn encode_idat(
+    data: &[u8],
+    stride: usize,
     height: usize,
+    bpp: usize,
+    options: &PngOptions,
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
-    // Add filter byte (0 = no filter) to each scanline
-    let mut uncompressed = Vec::with_capacity(height * (1 + width * 4));
+    let filtered = filter_scanlines(data, stride, height, bpp, options.filter);
+    compress_zlib(&filtered, options)
+}
+
+/// Compress data to a zlib stream with the configured backend.
+fn compress_zlib(data: &[u8], options: &PngOptions) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    match options.backend {
+        #[cfg(feature = "fdeflate")]
+        DeflateBackend::Fdeflate => Ok(fdeflate::compress_to_vec(data)),
+        #[cfg(feature = "zopfli")]
+        DeflateBackend::Zopfli => {
+            let mut compressed = Vec::new();
+            zopfli::compress(
+                zopfli::Options::default(),
+                zopfli::Format::Zlib,
+                data,
+                &mut compressed,
+            )?;
+            Ok(compressed)
+        }
+        // flate2, and backends that were not compiled in
+        _ => {
+            let level = flate2::Compression::new(options.level.min(9));
+            let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), level);
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+// ============================================================================
+// Scanline Filtering
+// ============================================================================
+
+/// PNG filter type bytes (PNG spec section 9.2).
+const FILTER_NONE: u8 = 0;
+const FILTER_SUB: u8 = 1;
+const FILTER_UP: u8 = 2;
+const FILTER_AVERAGE: u8 = 3;
+const FILTER_PAETH: u8 = 4;
+
+/// Filter `height` rows of `stride` bytes, prefixing each with its filter type.
+///
+/// Each row depends only on the unfiltered input, so rows of large images
+/// are filtered in parallel.
+fn filter_scanlines(
+    data: &[u8],
+    stride: usize,
+    height: usize,
+    bpp: usize,
+    strategy: FilterStrategy,
+) -> Vec<u8> {
+    let mut out = vec![0u8; height * (stride + 1)];
+    if stride == 0 {
+        return out;
+    }
+
+    let filter_row = |(y, out_row): (usize, &mut [u8])| {
+        let row = &data[y * stride..(y + 1) * stride];
+        let prev = (y > 0).then(|| &data[(y - 1) * stride..y * stride]);
+        let (filter_byte, residuals) = out_row.split_first_mut().expect("row has filter byte");
+        *filter_byte = match strategy {
+            FilterStrategy::None => apply_filter(FILTER_NONE, row, prev, bpp, residuals),
+            FilterStrategy::Sub => apply_filter(FILTER_SUB, row, prev, bpp, residuals),
+            FilterStrategy::Up => apply_filter(FILTER_UP, row, prev, bpp, residuals),
+            FilterStrategy::Average => apply_filter(FILTER_AVERAGE, row, prev, bpp, residuals),
+            FilterStrategy::Paeth => apply_filter(FILTER_PAETH, row, prev, bpp, residuals),
+            FilterStrategy::Adaptive => apply_adaptive_filter(row, prev, bpp, residuals),
+        };
+    };
+
+    if data.len() >= PARALLEL_FILTER_THRESHOLD {
+        out.par_chunks_mut(stride + 1)
+            .enumerate()
+            .for_each(filter_row);
+    } else {
+        out.chunks_mut(stride + 1).enumerate().for_each(filter_row);
+    }
+    out
+}
+
+/// Apply a single filter type to a row, returning the filter type byte.
+fn apply_filter(filter: u8, row: &[u8], prev: Option<&[u8]>, bpp: usize, out: &mut [u8]) -> u8 {
+    for i in 0..row.len() {
+        let a = if i >= bpp { row[i - bpp] } else { 0 };
+        let b = prev.map_or(0, |p| p[i]);
+        let c = if i >= bpp {
+            prev.map_or(0, |p| p[i - bpp])
+        } else {
+            0
+        };
+        out[i] = row[i].wrapping_sub(predict(filter, a, b, c));
+    }
+    filter
+}
+
+/// Try every filter type and keep the one with the smallest residuals.
+fn apply_adaptive_filter(row: &[u8], prev: Option<&[u8]>, bpp: usize, out: &mut [u8]) -> u8 {
+    let mut candidate = vec![0u8; row.len()];
+    let mut best = (u64::MAX, FILTER_NONE);
+
+    for filter in [
+        FILTER_NONE,
+        FILTER_SUB,
+        FILTER_UP,
+        FILTER_AVERAGE,
+        FILTER_PAETH,
+    ] {
+        apply_filter(filter, row, prev, bpp, &mut candidate);
+        // Residuals are signed; small magnitudes compress best
+        let cost: u64 = candidate
+            .iter()
+            .map(|&v| (v as i8).unsigned_abs() as u64)
+            .sum();
+        if cost < best.0 {
+            best = (cost, filter);
+            out.copy_from_slice(&candidate);
+        }
+    }
+    best.1
+}
+
+/// Predictor for a filter type from the left (a), up (b) and up-left (c) bytes.
+#[inline(always)]
+fn predict(filter: u8, a: u8, b: u8, c: u8) -> u8 {
+    match filter {
+        FILTER_SUB => a,
+        FILTER_UP => b,
+        FILTER_AVERAGE => ((a as u16 + b as u16) / 2) as u8,
+        FILTER_PAETH => paeth(a, b, c),
+        _ => 0,
+    }
+}
+
+#[inline(always)]
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Reverse scanline filtering in place, returning the raw rows.
+fn unfilter_scanlines(
+    filtered: &[u8],
+    stride: usize,
+    height: usize,
+    bpp: usize,
+) -> Result<Vec<u8>, String> {
+    if filtered.len() < height * (stride + 1) {
+        return Err("Truncated image data".to_string());
+    }
+
+    let mut raw = vec![0u8; height * stride];
     for y in 0..height {
-        uncompressed.push(0); // filter type: none
-        let row_start = y * width * 4;
-        let row_end = row_start + width * 4;
-        uncompressed.extend_from_slice(&pixels[row_start..row_end]);
+        let src = &filtered[y * (stride + 1)..(y + 1) * (stride + 1)];
+        let filter = src[0];
+        if filter > FILTER_PAETH {
+            return Err(format!("Invalid filter type {} in row {}", filter, y));
+        }
+
+        let (done, rest) = raw.split_at_mut(y * stride);
+        let prev = (y > 0).then(|| &done[(y - 1) * stride..]);
+        let row = &mut rest[..stride];
+        for i in 0..stride {
+            let a = if i >= bpp { row[i - bpp] } else { 0 };
+            let b = prev.map_or(0, |p| p[i]);
+            let c = if i >= bpp {
+                prev.map_or(0, |p| p[i - bpp])
+            } else {
+                0
+            };
+            row[i] = src[i + 1].wrapping_add(predict(filter, a, b, c));
+        }
     }
+    Ok(raw)
+}
 
-    // Compress with flate2
-    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
-    encoder.write_all(&uncompressed)?;
-    let compressed = encoder.finish()?;
+// ============================================================================
+// Re-encoding
+// ============================================================================
 
-    Ok(compressed)
+/// Re-encode an 8-bit, non-interlaced PNG with different encoder options.
+///
+/// Image data is decompressed, unfiltered, then filtered and compressed
+/// again; all other chunks (palette, transparency, ...) are kept as-is.
+/// Used to shrink tiles rendered with the interactive profile before they
+/// are stored for seeding.
+pub fn recompress_png(png: &[u8], options: &PngOptions) -> Result<Vec<u8>, String> {
+    if png.len() < PNG_SIGNATURE.len() || png[..8] != PNG_SIGNATURE {
+        return Err("Not a PNG image".to_string());
+    }
+
+    // Split into chunks, concatenating IDAT data
+    let mut chunks: Vec<(&[u8], &[u8])> = Vec::new();
+    let mut idat = Vec::new();
+    let mut idat_index = None;
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 8 <= png.len() {
+        let len = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+        let chunk_type = &png[pos + 4..pos + 8];
+        let data = png
+            .get(pos + 8..pos + 8 + len)
+            .ok_or_else(|| "Truncated PNG chunk".to_string())?;
+        if chunk_type == b"IDAT" {
+            idat_index.get_or_insert(chunks.len());
+            idat.extend_from_slice(data);
+        } else {
+            chunks.push((chunk_type, data));
+        }
+        pos += 12 + len;
+    }
+
+    let ihdr = chunks
+        .iter()
+        .find(|(t, _)| *t == b"IHDR")
+        .map(|(_, d)| *d)
+        .filter(|d| d.len() == 13)
+        .ok_or_else(|| "Missing IHDR chunk".to_string())?;
+    let idat_index = idat_index.ok_or_else(|| "Missing IDAT chunk".to_string())?;
+
+    let width = u32::from_be_bytes(ihdr[0..4].try_into().unwrap()) as usize;
+    let height = u32::from_be_bytes(ihdr[4..8].try_into().unwrap()) as usize;
+    let (bit_depth, color_type, interlace) = (ihdr[8], ihdr[9], ihdr[12]);
+    if bit_depth != 8 || interlace != 0 {
+        return Err("Only 8-bit non-interlaced PNGs can be recompressed".to_string());
+    }
+    let bpp = match color_type {
+        0 | 3 => 1, // grayscale, indexed
+        4 => 2,     // grayscale + alpha
+        2 => 3,     // RGB
+        6 => 4,     // RGBA
+        other => return Err(format!("Invalid PNG color type {}", other)),
+    };
+    let stride = width * bpp;
+
+    let mut filtered = Vec::with_capacity(height * (stride + 1));
+    flate2::read::ZlibDecoder::new(idat.as_slice())
+        .read_to_end(&mut filtered)
+        .map_err(|e| format!("IDAT decompression failed: {}", e))?;
+    let raw = unfilter_scanlines(&filtered, stride, height, bpp)?;
+    let idat_data = encode_idat(&raw, stride, height, bpp, options)
+        .map_err(|e| format!("IDAT compression failed: {}", e))?;
+
+    let mut out = Vec::with_capacity(png.len());
+    out.extend_from_slice(&PNG_SIGNATURE);
+    for (i, (chunk_type, data)) in chunks.iter().enumerate() {
+        if i == idat_index {
+            write_chunk(&mut out, b"IDAT", &idat_data);
+        }
+        let chunk_type: &[u8; 4] = (*chunk_type).try_into().unwrap();
+        write_chunk(&mut out, chunk_type, data);
+    }
+    if idat_index == chunks.len() {
+        write_chunk(&mut out, b"IDAT", &idat_data);
+    }
+    Ok(out)
 }
 
 /// Simple CRC32 checksum (PNG-style)
@@ -1,5 +1,9 @@
 //! Style configuration for weather data rendering.
 
+use crate::composite::CompositeRecipe;
+use crate::dither::DitherMode;
+use crate::expression::Expression;
+use crate::mask::{DataMask, MaskInput};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -59,10 +63,26 @@ pub struct StyleDefinition {
     pub legend: Option<Legend>,
     /// Wind barb rendering configuration (for type: "wind_barbs")
     pub wind: Option<WindBarbStyle>,
+    /// Multi-band composite recipe (for type: "rgb_composite")
+    pub composite: Option<CompositeRecipe>,
+    /// Hide pixels where a secondary field fails a threshold (see [`crate::mask`])
+    pub mask: Option<DataMask>,
+    /// Anti-aliasing for requests that cover many grid cells per pixel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minify: Option<MinifyStyle>,
+    /// Dithering for indexed rendering, to break up banding on smooth fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dither: Option<DitherMode>,
 }
 
-/// Color transformation
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Value transformation applied before color mapping.
+///
+/// Stops and ranges are in transformed units. Besides the named unit
+/// conversions and `linear`, a transform can be an `expression` in `x` (see
+/// [`crate::expression`]) or `piecewise` linear through `points`. A bare string
+/// is shorthand for an expression: `"transform": "log10(x + 1)"`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(try_from = "TransformSpec")]
 pub struct Transform {
     #[serde(rename = "type")]
     pub transform_type: String,
@@ -72,6 +92,159 @@ pub struct Transform {
     /// Offset for linear transform (value * scale + offset)
     #[serde(default)]
     pub offset: Option<f32>,
+    /// Expression in `x` for type "expression"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<Expression>,
+    /// Inverse of `expression`, used to label legends in original units
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inverse: Option<Expression>,
+    /// `[input, output]` breakpoints for type "piecewise", ascending by input.
+    /// Values beyond the ends extrapolate along the first/last segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<Vec<[f32; 2]>>,
+}
+
+/// Accepted JSON forms of [`Transform`].
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TransformSpec {
+    Expression(String),
+    Full {
+        #[serde(rename = "type")]
+        transform_type: String,
+        #[serde(default)]
+        scale: Option<f32>,
+        #[serde(default)]
+        offset: Option<f32>,
+        #[serde(default)]
+        expression: Option<String>,
+        #[serde(default)]
+        inverse: Option<String>,
+        #[serde(default)]
+        points: Option<Vec<[f32; 2]>>,
+    },
+}
+
+impl TryFrom<TransformSpec> for Transform {
+    type Error = String;
+
+    fn try_from(spec: TransformSpec) -> Result<Self, Self::Error> {
+        let parse = |field: &str, source: Option<String>| {
+            source
+                .map(|s| {
+                    Expression::parse(&s)
+                        .map_err(|e| format!("invalid transform {} '{}': {}", field, s, e))
+                })
+                .transpose()
+        };
+
+        let transform = match spec {
+            TransformSpec::Expression(source) => Transform {
+                transform_type: "expression".to_string(),
+                expression: parse("expression", Some(source))?,
+                ..Default::default()
+            },
+            TransformSpec::Full {
+                transform_type,
+                scale,
+                offset,
+                expression,
+                inverse,
+                points,
+            } => Transform {
+                transform_type,
+                scale,
+                offset,
+                expression: parse("expression", expression)?,
+                inverse: parse("inverse", inverse)?,
+                points,
+            },
+        };
+
+        match transform.transform_type.to_lowercase().as_str() {
+            "expression" if transform.expression.is_none() => {
+                Err("transform type 'expression' requires an 'expression'".to_string())
+            }
+            "piecewise" => {
+                let points = transform.points.as_deref().unwrap_or_default();
+                if points.len() < 2 {
+                    Err("transform type 'piecewise' requires at least 2 points".to_string())
+                } else if points.windows(2).any(|w| w[1][0] <= w[0][0]) {
+                    Err(
+                        "piecewise transform points must be strictly ascending by input"
+                            .to_string(),
+                    )
+                } else {
+                    Ok(transform)
+                }
+            }
+            _ => Ok(transform),
+        }
+    }
+}
+
+impl Transform {
+    /// Apply the transform to a data value (same as [`apply_transform`]).
+    pub fn apply(&self, value: f32) -> f32 {
+        apply_transform(value, Some(self))
+    }
+
+    /// Whether legends should label stops in original data units.
+    ///
+    /// True for `expression` and `piecewise`, whose output isn't a physical
+    /// unit; unit conversions are labeled in the converted units.
+    pub fn labels_in_data_units(&self) -> bool {
+        matches!(
+            self.transform_type.to_lowercase().as_str(),
+            "expression" | "piecewise"
+        )
+    }
+
+    /// Map a transformed value back to original data units.
+    ///
+    /// Returns None when the transform can't be inverted: an expression
+    /// without an `inverse`, a piecewise transform that isn't monotonic, or
+    /// a zero linear scale.
+    pub fn invert(&self, value: f32) -> Option<f32> {
+        match self.transform_type.to_lowercase().as_str() {
+            "pa_to_hpa" => Some(value * 100.0),
+            "k_to_c" | "kelvin_to_celsius" => Some(value + 273.15),
+            "m_to_km" => Some(value * 1000.0),
+            "mps_to_knots" => Some(value / 1.94384),
+            "linear" => {
+                let scale = self.scale.unwrap_or(1.0);
+                let offset = self.offset.unwrap_or(0.0);
+                (scale != 0.0).then(|| (value - offset) / scale)
+            }
+            "expression" => self.inverse.as_ref().map(|inv| inv.eval(value)),
+            "piecewise" => {
+                // Swap input and output; only valid when outputs are monotonic
+                let points = self.points.as_deref()?;
+                let mut swapped: Vec<[f32; 2]> = points.iter().map(|p| [p[1], p[0]]).collect();
+                if swapped.windows(2).all(|w| w[1][0] < w[0][0]) {
+                    swapped.reverse();
+                }
+                swapped
+                    .windows(2)
+                    .all(|w| w[1][0] > w[0][0])
+                    .then(|| interpolate_points(&swapped, value))
+            }
+            _ => Some(value),
+        }
+    }
+}
+
+/// Linear interpolation through ascending `[input, output]` points,
+/// extrapolating along the end segments.
+fn interpolate_points(points: &[[f32; 2]], value: f32) -> f32 {
+    let last = points.len() - 1;
+    let segment = points[1..last]
+        .iter()
+        .position(|p| value < p[0])
+        .unwrap_or(last - 1);
+    let [x0, y0] = points[segment];
+    let [x1, y1] = points[segment + 1];
+    y0 + (value - x0) * (y1 - y0) / (x1 - x0)
 }
 
 /// Color stop for gradient
@@ -163,6 +336,37 @@ impl WindBarbStyle {
     }
 }
 
+/// Filtering of oversampled requests (many grid cells per output pixel).
+///
+/// Noisy fields such as reflectivity alias when a large area is sampled
+/// at small pixel sizes. With `filter: "box"`, the grid is averaged over
+/// about one output pixel before sampling once more than `threshold` grid
+/// cells fall in a pixel.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MinifyStyle {
+    /// "bilinear" (default) or "box"
+    #[serde(default = "default_minify_filter")]
+    pub filter: String,
+    /// Grid cells per output pixel above which the filter applies (default: 2)
+    #[serde(default = "default_minify_threshold")]
+    pub threshold: f64,
+}
+
+fn default_minify_filter() -> String {
+    "bilinear".to_string()
+}
+
+fn default_minify_threshold() -> f64 {
+    2.0
+}
+
+impl MinifyStyle {
+    /// Whether the style asks for box filtering.
+    pub fn is_box(&self) -> bool {
+        self.filter.eq_ignore_ascii_case("box")
+    }
+}
+
 impl StyleConfig {
     /// Load style configuration from JSON string
     pub fn from_json(json_str: &str) -> Result<Self, serde_json::Error> {
@@ -205,6 +409,9 @@ impl StyleConfig {
 /// 4096 (12 bits) provides good precision while keeping memory small (4KB).
 const PALETTE_LUT_SIZE: usize = 4096;
 
+/// Steps between the first and last palette colors (indices 1..=255).
+const PALETTE_STEPS: f32 = 254.0;
+
 impl StyleDefinition {
     /// Pre-compute the palette from style color stops.
     ///
@@ -301,6 +508,68 @@ impl StyleDefinition {
     pub fn get_wind_style(&self) -> WindBarbStyle {
         self.wind.clone().unwrap_or_default()
     }
+
+    /// Legend entries for the color stops, in ascending order.
+    ///
+    /// Stops of `expression` and `piecewise` transforms are in transformed
+    /// space (e.g. log-scaled precipitation), so unlabeled stops are labeled
+    /// with the original data value when the transform can be inverted.
+    /// Unit conversions (e.g. `k_to_c`) are labeled in the converted units.
+    pub fn legend_entries(&self) -> Vec<LegendEntry> {
+        let mut stops = self.stops.clone();
+        stops.sort_by(|a, b| {
+            a.value
+                .partial_cmp(&b.value)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let transform = self.transform.as_ref().filter(|t| t.labels_in_data_units());
+
+        stops
+            .into_iter()
+            .map(|stop| {
+                let data_value = transform.and_then(|t| t.invert(stop.value));
+                let label = stop
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| format_legend_value(data_value.unwrap_or(stop.value)));
+                LegendEntry {
+                    value: stop.value,
+                    data_value,
+                    color: stop.color,
+                    label,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A labeled color stop for legend rendering.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LegendEntry {
+    /// Stop value in transformed (color-mapping) units
+    pub value: f32,
+    /// Stop value in original data units, for expression and piecewise transforms
+    pub data_value: Option<f32>,
+    pub color: String,
+    pub label: String,
+}
+
+/// Format a legend value with precision suited to its magnitude.
+fn format_legend_value(value: f32) -> String {
+    let magnitude = value.abs();
+    let text = if magnitude >= 100.0 || magnitude == 0.0 {
+        format!("{:.0}", value)
+    } else if magnitude >= 1.0 {
+        format!("{:.1}", value)
+    } else {
+        format!("{:.3}", value)
+    };
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
 }
 
 /// Pack RGBA into u32 for hashing
@@ -311,7 +580,10 @@ fn pack_color_u32(r: u8, g: u8, b: u8, a: u8) -> u32 {
 }
 
 /// Interpolate color at a specific value given sorted color stops
-fn interpolate_color_at_value(value: f32, stops: &[(f32, (u8, u8, u8, u8))]) -> (u8, u8, u8, u8) {
+pub(crate) fn interpolate_color_at_value(
+    value: f32,
+    stops: &[(f32, (u8, u8, u8, u8))],
+) -> (u8, u8, u8, u8) {
     if stops.is_empty() {
         return (0, 0, 0, 0);
     }
@@ -467,6 +739,23 @@ pub struct ContourOptions {
     pub label_spacing: Option<f32>,
     /// Special levels with custom styling (e.g., freezing level)
     pub special_levels: Option<Vec<SpecialLevel>>,
+    /// Whether contours are traced on the data's native grid or after
+    /// resampling to the output
+    #[serde(default)]
+    pub native_grid: NativeGridMode,
+}
+
+/// Where contours of projected grids are traced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NativeGridMode {
+    /// On the native grid when the output is at least as fine as the grid
+    #[default]
+    Auto,
+    /// Always on the native grid
+    Always,
+    /// Always on the resampled output grid
+    Never,
 }
 
 /// Special level with custom styling
@@ -756,6 +1045,13 @@ pub fn apply_transform(value: f32, transform: Option<&Transform>) -> f32 {
                 let scale = t.scale.unwrap_or(1.0);
                 let offset = t.offset.unwrap_or(0.0);
                 value * scale + offset
+            } else if transform_type == "expression" {
+                t.expression.as_ref().map_or(value, |e| e.eval(value))
+            } else if transform_type == "piecewise" {
+                match t.points.as_deref() {
+                    Some(points) if points.len() >= 2 => interpolate_points(points, value),
+                    _ => value,
+                }
             } else {
                 value
             }
@@ -774,10 +1070,23 @@ pub fn apply_style_gradient(
     width: usize,
     height: usize,
     style: &StyleDefinition,
+) -> Vec<u8> {
+    apply_style_gradient_masked(data, width, height, style, None)
+}
+
+/// Apply style-based color mapping to data, hiding pixels rejected by `mask`.
+///
+/// `mask` values must have the same dimensions as `data`.
+pub fn apply_style_gradient_masked(
+    data: &[f32],
+    width: usize,
+    height: usize,
+    style: &StyleDefinition,
+    mask: Option<MaskInput>,
 ) -> Vec<u8> {
     // Use buffer pool for the pixel buffer
     crate::buffer_pool::take_pixel_buffer(width, height, |pixels| {
-        apply_style_gradient_into(data, width, height, style, pixels);
+        apply_style_gradient_into(data, width, height, style, mask, pixels);
     })
 }
 
@@ -790,6 +1099,7 @@ fn apply_style_gradient_into(
     width: usize,
     height: usize,
     style: &StyleDefinition,
+    mask: Option<MaskInput>,
     pixels: &mut [u8],
 ) {
     // Validate buffer size matches expected dimensions
@@ -858,7 +1168,8 @@ fn apply_style_gradient_into(
                 let raw_value = data[data_idx];
 
                 // Handle NaN as transparent (sentinel values are converted to NaN during ingestion)
-                if raw_value.is_nan() {
+                // Masked pixels are transparent too
+                if raw_value.is_nan() || mask.is_some_and(|m| !m.shows(data_idx)) {
                     row[pixel_idx] = 0;
                     row[pixel_idx + 1] = 0;
                     row[pixel_idx + 2] = 0;
@@ -869,6 +1180,15 @@ fn apply_style_gradient_into(
                 // Apply transform to convert to display units
                 let value = apply_transform(raw_value, transform.as_ref());
 
+                // Values outside an expression's domain (e.g. log of a negative) are NaN
+                if value.is_nan() {
+                    row[pixel_idx] = 0;
+                    row[pixel_idx + 1] = 0;
+                    row[pixel_idx + 2] = 0;
+                    row[pixel_idx + 3] = 0;
+                    continue;
+                }
+
                 // Handle out-of-range values
                 if value < min_range {
                     if out_of_range_transparent {
@@ -974,10 +1294,25 @@ pub fn apply_style_gradient_indexed(
     height: usize,
     palette: &PrecomputedPalette,
     style: &StyleDefinition,
+) -> Vec<u8> {
+    apply_style_gradient_indexed_masked(data, width, height, palette, style, None)
+}
+
+/// Apply style-based color mapping to palette indices, hiding pixels rejected
+/// by `mask` (index 0, transparent).
+///
+/// `mask` values must have the same dimensions as `data`.
+pub fn apply_style_gradient_indexed_masked(
+    data: &[f32],
+    width: usize,
+    height: usize,
+    palette: &PrecomputedPalette,
+    style: &StyleDefinition,
+    mask: Option<MaskInput>,
 ) -> Vec<u8> {
     // Use buffer pool for the index buffer
     crate::buffer_pool::take_index_buffer(width, height, |indices| {
-        apply_style_gradient_indexed_into(data, width, height, palette, style, indices);
+        apply_style_gradient_indexed_into(data, width, height, palette, style, mask, indices);
     })
 }
 
@@ -989,6 +1324,7 @@ fn apply_style_gradient_indexed_into(
     height: usize,
     palette: &PrecomputedPalette,
     style: &StyleDefinition,
+    mask: Option<MaskInput>,
     indices: &mut [u8],
 ) {
     // Validate buffer size matches expected dimensions
@@ -1030,6 +1366,7 @@ fn apply_style_gradient_indexed_into(
         .enumerate()
         .for_each(|(y, row)| {
             let data_row_start = y * width;
+            let dither_row = style.dither.map(|mode| mode.row(y));
 
             for x in 0..width {
                 let data_idx = data_row_start + x;
@@ -1040,9 +1377,9 @@ fn apply_style_gradient_indexed_into(
 
                 let raw_value = data[data_idx];
 
-                // Handle NaN -> transparent (index 0)
+                // Handle NaN and masked pixels -> transparent (index 0)
                 // Sentinel values are converted to NaN during ingestion
-                if raw_value.is_nan() {
+                if raw_value.is_nan() || mask.is_some_and(|m| !m.shows(data_idx)) {
                     row[x] = 0;
                     continue;
                 }
@@ -1050,6 +1387,12 @@ fn apply_style_gradient_indexed_into(
                 // Apply transform
                 let value = apply_transform(raw_value, transform);
 
+                // Values outside an expression's domain (e.g. log of a negative) are NaN
+                if value.is_nan() {
+                    row[x] = 0;
+                    continue;
+                }
+
                 // Handle out-of-range
                 if value < min_value {
                     row[x] = below_range_idx;
@@ -1063,6 +1406,13 @@ fn apply_style_gradient_indexed_into(
 
                 // Normalize to LUT index and lookup
                 let t = (value - min_value) / range;
+                if let Some(thresholds) = dither_row {
+                    // Palette color i sits at t = i / 254; the threshold picks
+                    // between the two nearest colors in proportion to distance
+                    let offset = t * PALETTE_STEPS + thresholds[x % thresholds.len()];
+                    row[x] = 1 + (offset as usize).min(PALETTE_STEPS as usize) as u8;
+                    continue;
+                }
                 let lut_idx = (t * lut_max) as usize;
                 row[x] = palette.value_to_index[lut_idx.min(PALETTE_LUT_SIZE - 1)];
             }
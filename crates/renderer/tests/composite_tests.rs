@@ -0,0 +1,169 @@
+//! Tests for multi-band RGB composites.
+
+use renderer::composite::{packed_channel, CompositeRecipe, COMPOSITE_STYLE_TYPE};
+use renderer::style::StyleConfig;
+use std::collections::HashMap;
+
+fn recipe(json: &str) -> CompositeRecipe {
+    serde_json::from_str(json).expect("valid recipe")
+}
+
+fn bands(entries: &[(&str, Vec<f32>)]) -> HashMap<String, Vec<f32>> {
+    entries
+        .iter()
+        .map(|(name, values)| (name.to_string(), values.clone()))
+        .collect()
+}
+
+#[test]
+fn test_rgb_weighted_sum_and_stretch() {
+    let recipe = recipe(
+        r#"{
+            "kind": "rgb",
+            "red": { "bands": [{ "band": "A" }], "range": [0.0, 1.0] },
+            "green": {
+                "bands": [{ "band": "A", "weight": 0.5 }, { "band": "B", "weight": 0.5 }],
+                "range": [0.0, 1.0]
+            },
+            "blue": { "bands": [{ "band": "B" }], "range": [0.0, 1.0] }
+        }"#,
+    );
+    assert_eq!(recipe.required_bands(), vec!["A", "B"]);
+
+    let data = bands(&[("A", vec![1.0, 2.0]), ("B", vec![0.0, -1.0])]);
+    let pixels = recipe.render(&data, 2, 1).unwrap();
+
+    // Pixel 0: A=1, B=0 -> (255, 128, 0)
+    assert_eq!(&pixels[0..4], &[255, 128, 0, 255]);
+    // Pixel 1: values are clamped to the range
+    assert_eq!(&pixels[4..8], &[255, 128, 0, 255]);
+}
+
+#[test]
+fn test_inverted_range_and_gamma() {
+    let recipe = recipe(
+        r#"{
+            "kind": "rgb",
+            "red": { "bands": [{ "band": "IR" }], "range": [300.0, 200.0] },
+            "green": { "bands": [{ "band": "IR" }], "range": [300.0, 200.0] },
+            "blue": { "bands": [{ "band": "IR" }], "range": [200.0, 300.0], "gamma": 2.0 }
+        }"#,
+    );
+
+    let data = bands(&[("IR", vec![200.0, 275.0])]);
+    let pixels = recipe.render(&data, 2, 1).unwrap();
+
+    // Cold is bright on inverted channels
+    assert_eq!(pixels[0], 255);
+    assert_eq!(pixels[2], 0);
+    // 0.75^(1/2) ~= 0.866
+    assert_eq!(pixels[6], 221);
+}
+
+#[test]
+fn test_missing_values_are_transparent() {
+    let recipe = recipe(
+        r#"{
+            "kind": "rgb",
+            "red": { "bands": [{ "band": "A" }], "range": [0.0, 1.0] },
+            "green": { "bands": [{ "band": "A" }], "range": [0.0, 1.0] },
+            "blue": { "bands": [{ "band": "B" }], "range": [0.0, 1.0] }
+        }"#,
+    );
+
+    let data = bands(&[("A", vec![0.5, 0.5]), ("B", vec![f32::NAN, 0.5])]);
+    let pixels = recipe.render(&data, 2, 1).unwrap();
+
+    assert_eq!(&pixels[0..4], &[0, 0, 0, 0]);
+    assert_eq!(pixels[7], 255);
+}
+
+#[test]
+fn test_missing_band_is_error() {
+    let recipe = recipe(
+        r#"{
+            "kind": "rgb",
+            "red": { "bands": [{ "band": "A" }], "range": [0.0, 1.0] },
+            "green": { "bands": [{ "band": "A" }], "range": [0.0, 1.0] },
+            "blue": { "bands": [{ "band": "B" }], "range": [0.0, 1.0] }
+        }"#,
+    );
+
+    let data = bands(&[("A", vec![0.5])]);
+    assert!(recipe.render(&data, 1, 1).is_err());
+}
+
+#[test]
+fn test_sandwich_multiplies_overlay() {
+    let recipe = recipe(
+        r##"{
+            "kind": "sandwich",
+            "base": { "bands": [{ "band": "VIS" }], "range": [0.0, 1.0] },
+            "overlay": { "bands": [{ "band": "IR" }], "range": [240.0, 200.0] },
+            "overlay_stops": [
+                { "value": 0.0, "color": "#00000000" },
+                { "value": 0.5, "color": "#FF0000FF" },
+                { "value": 1.0, "color": "#FF0000FF" }
+            ]
+        }"##,
+    );
+
+    // Warm IR: overlay transparent, plain grayscale base
+    // Cold IR: base tinted red
+    let data = bands(&[("VIS", vec![1.0, 1.0]), ("IR", vec![250.0, 200.0])]);
+    let pixels = recipe.render(&data, 2, 1).unwrap();
+
+    assert_eq!(&pixels[0..4], &[255, 255, 255, 255]);
+    assert_eq!(&pixels[4..8], &[255, 0, 0, 255]);
+}
+
+#[test]
+fn test_composite_style_parses() {
+    let json = r#"{
+        "version": "1.0",
+        "styles": {
+            "true_color": {
+                "name": "True Color",
+                "type": "rgb_composite",
+                "default": true,
+                "composite": {
+                    "kind": "rgb",
+                    "red": { "bands": [{ "band": "CMI_C02" }], "range": [0.0, 1.0], "gamma": 2.2 },
+                    "green": { "bands": [{ "band": "CMI_C03" }], "range": [0.0, 1.0], "gamma": 2.2 },
+                    "blue": { "bands": [{ "band": "CMI_C01" }], "range": [0.0, 1.0], "gamma": 2.2 }
+                }
+            }
+        }
+    }"#;
+
+    let config = StyleConfig::from_json(json).unwrap();
+    let (_, style) = config.get_default_style().unwrap();
+    assert_eq!(style.style_type, COMPOSITE_STYLE_TYPE);
+    assert_eq!(
+        style.composite.as_ref().unwrap().required_bands(),
+        vec!["CMI_C02", "CMI_C03", "CMI_C01"]
+    );
+}
+
+#[test]
+fn test_packed_rgb_composite() {
+    let recipe = recipe(r#"{ "kind": "packed_rgb", "band": "TRUE_COLOR" }"#);
+    assert_eq!(recipe.required_bands(), vec!["TRUE_COLOR"]);
+    assert_eq!(recipe.packed_band(), Some("TRUE_COLOR"));
+
+    let data = bands(&[
+        ("TRUE_COLOR.red", vec![1.0, f32::NAN]),
+        ("TRUE_COLOR.green", vec![0.5, 0.5]),
+        ("TRUE_COLOR.blue", vec![0.0, 0.5]),
+    ]);
+    assert_eq!(packed_channel("TRUE_COLOR", 1), "TRUE_COLOR.green");
+
+    let pixels = recipe.render(&data, 2, 1).unwrap();
+    assert_eq!(&pixels[0..4], &[255, 128, 0, 255]);
+    // Missing data is transparent
+    assert_eq!(&pixels[4..8], &[0, 0, 0, 0]);
+
+    // The packed band itself can't be rendered without unpacking
+    let packed = bands(&[("TRUE_COLOR", vec![0.0, 0.0])]);
+    assert!(recipe.render(&packed, 2, 1).is_err());
+}
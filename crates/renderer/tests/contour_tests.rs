@@ -2,7 +2,8 @@
 
 use renderer::contour::{
     connect_segments, generate_all_contours, generate_contour_levels, march_squares,
-    render_contours, smooth_contour, Contour, ContourConfig, Point, Segment, SpecialLevelConfig,
+    render_contours, render_contours_reprojected, smooth_contour, Contour, ContourConfig, Point,
+    Segment, SpecialLevelConfig,
 };
 
 // ============================================================================
@@ -631,3 +632,27 @@ fn test_render_contours_no_levels() {
     let pixels = render_contours(&data, 2, 2, &config);
     assert_eq!(pixels.len(), 16);
 }
+
+#[test]
+fn test_render_contours_reprojected() {
+    // A 3x3 peak drawn 10x larger, offset by 5 pixels
+    let data = vec![0.0, 0.0, 0.0, 0.0, 10.0, 0.0, 0.0, 0.0, 0.0];
+    let mut config = ContourConfig::default();
+    config.levels = vec![5.0];
+    config.smoothing_passes = 0;
+
+    let pixels = render_contours_reprojected(&data, 3, 3, 32, 32, &config, |p| {
+        Some(Point::new(p.x * 10.0 + 5.0, p.y * 10.0 + 5.0))
+    });
+    assert_eq!(pixels.len(), 32 * 32 * 4);
+
+    // The ring passes through (15, 10) (grid (1, 0.5)) and not the corners
+    let alpha = |x: usize, y: usize| pixels[(y * 32 + x) * 4 + 3];
+    assert!(alpha(15, 10) > 0);
+    assert_eq!(alpha(0, 0), 0);
+    assert_eq!(alpha(15, 15), 0);
+
+    // Nothing maps into the output: empty canvas
+    let pixels = render_contours_reprojected(&data, 3, 3, 32, 32, &config, |_| None);
+    assert!(pixels.iter().all(|&b| b == 0));
+}
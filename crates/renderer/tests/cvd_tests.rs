@@ -0,0 +1,102 @@
+//! Tests for color vision deficiency (CVD) palette transforms.
+
+use renderer::cvd::{cvd_safe_stops, cvd_style_name, delta_e, simulate, validate_stops, CvdType};
+use renderer::style::{hex_to_rgba, ColorStop, StyleConfig};
+
+fn stops(colors: &[&str]) -> Vec<ColorStop> {
+    colors
+        .iter()
+        .enumerate()
+        .map(|(i, c)| ColorStop {
+            value: i as f32 * 10.0,
+            color: c.to_string(),
+            label: None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_simulate_grays_unchanged() {
+    // Achromatic colors look the same to everyone
+    for v in [0u8, 128, 255] {
+        let sim = simulate((v, v, v), CvdType::Deuteranopia);
+        assert!((sim.0 as i32 - v as i32).abs() <= 1);
+        assert!((sim.1 as i32 - v as i32).abs() <= 1);
+        assert!((sim.2 as i32 - v as i32).abs() <= 1);
+    }
+}
+
+#[test]
+fn test_red_green_confusion_detected() {
+    // Pure red and green are very different normally but much closer under deuteranopia
+    let red = (255, 0, 0);
+    let green = (0, 160, 0);
+    let normal = delta_e(red, green);
+    let simulated = delta_e(
+        simulate(red, CvdType::Deuteranopia),
+        simulate(green, CvdType::Deuteranopia),
+    );
+    assert!(simulated < normal * 0.5);
+
+    let report = validate_stops(&stops(&["#FF0000", "#00A000"]));
+    assert!(!report.is_safe());
+}
+
+#[test]
+fn test_safe_palette_passes_unchanged() {
+    // Blue to yellow survives red-green CVD
+    let original = stops(&["#00224E", "#7C7B78", "#FEE838"]);
+    assert!(validate_stops(&original).is_safe());
+
+    let safe = cvd_safe_stops(&original);
+    let colors: Vec<&str> = safe.iter().map(|s| s.color.as_str()).collect();
+    assert_eq!(colors, vec!["#00224E", "#7C7B78", "#FEE838"]);
+}
+
+#[test]
+fn test_cvd_safe_stops_fix_red_green_palette() {
+    let original = stops(&["#00000000", "#00A000", "#FFFF00", "#FF0000"]);
+    let safe = cvd_safe_stops(&original);
+
+    assert_eq!(safe.len(), original.len());
+    assert!(validate_stops(&safe).is_safe());
+
+    // Values and transparent stops are preserved
+    for (a, b) in original.iter().zip(&safe) {
+        assert_eq!(a.value, b.value);
+    }
+    assert_eq!(safe[0].color, "#00000000");
+    assert!(hex_to_rgba(&safe[1].color).is_some());
+}
+
+#[test]
+fn test_with_cvd_variants() {
+    let json = r##"{
+        "version": "1.0",
+        "styles": {
+            "default": {
+                "default": true,
+                "name": "Temperature",
+                "type": "gradient",
+                "stops": [
+                    {"value": 0, "color": "#00A000"},
+                    {"value": 100, "color": "#FF0000"}
+                ]
+            },
+            "barbs": {
+                "name": "Barbs",
+                "type": "wind_barbs"
+            }
+        }
+    }"##;
+    let config = StyleConfig::from_json(json).unwrap().with_cvd_variants();
+
+    let variant = config.get_style(&cvd_style_name("default")).unwrap();
+    assert_eq!(variant.name, "Temperature (colorblind-safe)");
+    assert!(!variant.default);
+    assert!(validate_stops(&variant.stops).is_safe());
+
+    // Non-gradient styles don't get variants, and the default is unchanged
+    assert!(config.get_style("barbs_cvd").is_none());
+    assert_eq!(config.default_style_name(), Some("default"));
+}
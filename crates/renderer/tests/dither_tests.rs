@@ -0,0 +1,173 @@
+//! Tests for dithered indexed rendering.
+//!
+//! Golden images live in `tests/golden/`. After an intentional change to the
+//! dither patterns, regenerate them with:
+//!
+//! ```sh
+//! UPDATE_GOLDEN=1 cargo test --package renderer --test dither_tests
+//! ```
+
+use renderer::dither::DitherMode;
+use renderer::png::create_png_from_precomputed;
+use renderer::style::{apply_style_gradient_indexed, StyleConfig, StyleDefinition};
+use std::path::PathBuf;
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 64;
+
+fn temperature_style(dither: Option<&str>) -> StyleDefinition {
+    let dither = dither
+        .map(|mode| format!(r#", "dither": "{}""#, mode))
+        .unwrap_or_default();
+    let json = format!(
+        r##"{{
+        "version": "1.0",
+        "styles": {{
+            "temperature": {{
+                "default": true,
+                "name": "Temperature",
+                "type": "gradient",
+                "stops": [
+                    {{"value": -40, "color": "#8B00FF"}},
+                    {{"value": 0, "color": "#0000FF"}},
+                    {{"value": 20, "color": "#00FF00"}},
+                    {{"value": 40, "color": "#FF0000"}}
+                ]{}
+            }}
+        }}
+    }}"##,
+        dither
+    );
+    StyleConfig::from_json(&json)
+        .unwrap()
+        .get_style("temperature")
+        .unwrap()
+        .clone()
+}
+
+/// A shallow ramp from 10 to 12 degrees: only ~6 palette steps across the
+/// image, so undithered output shows wide bands.
+fn smooth_field() -> Vec<f32> {
+    (0..WIDTH * HEIGHT)
+        .map(|i| 10.0 + 2.0 * (i % WIDTH) as f32 / (WIDTH - 1) as f32)
+        .collect()
+}
+
+/// Width of the blocks compared in [`max_block_error`]: one Bayer tile.
+const BLOCK: usize = 8;
+
+/// Largest difference, in palette steps, between the true mean value of an
+/// 8-column block and the mean palette position rendered for it; i.e. how
+/// far off the tone looks from a normal viewing distance.
+fn max_block_error(style: &StyleDefinition) -> f32 {
+    let palette = style.compute_palette().unwrap();
+    let data = smooth_field();
+    let indices = apply_style_gradient_indexed(&data, WIDTH, HEIGHT, &palette, style);
+
+    let steps = 254.0 / (palette.max_value - palette.min_value);
+    let pixels = (BLOCK * HEIGHT) as f32;
+    (0..WIDTH / BLOCK)
+        .map(|block| {
+            let columns = block * BLOCK..(block + 1) * BLOCK;
+            let expected = columns
+                .clone()
+                .map(|x| (data[x] - palette.min_value) * steps * HEIGHT as f32)
+                .sum::<f32>()
+                / pixels;
+            let rendered = columns
+                .flat_map(|x| (0..HEIGHT).map(move |y| y * WIDTH + x))
+                .map(|i| (indices[i] - 1) as f32)
+                .sum::<f32>()
+                / pixels;
+            (rendered - expected).abs()
+        })
+        .fold(0.0, f32::max)
+}
+
+#[test]
+fn test_parse_dither_config() {
+    assert_eq!(temperature_style(None).dither, None);
+    assert_eq!(
+        temperature_style(Some("ordered")).dither,
+        Some(DitherMode::Ordered)
+    );
+    assert_eq!(
+        temperature_style(Some("blue_noise")).dither,
+        Some(DitherMode::BlueNoise)
+    );
+}
+
+#[test]
+fn test_dithering_removes_banding() {
+    let undithered = max_block_error(&temperature_style(None));
+    assert!(undithered > 0.5, "undithered error {}", undithered);
+
+    for mode in ["ordered", "blue_noise"] {
+        let error = max_block_error(&temperature_style(Some(mode)));
+        assert!(error < 0.2, "{} error {}", mode, error);
+    }
+}
+
+#[test]
+fn test_dithering_keeps_transparent_and_out_of_range_pixels() {
+    let style = temperature_style(Some("blue_noise"));
+    let palette = style.compute_palette().unwrap();
+    let data = vec![f32::NAN, -100.0, -40.0, 40.0, 100.0, f32::NAN, 0.0, 0.0];
+
+    let indices = apply_style_gradient_indexed(&data, 4, 2, &palette, &style);
+
+    assert_eq!(indices[0], 0);
+    assert_eq!(indices[5], 0);
+    // Clamped to the end colors, including at the exact range bounds
+    assert_eq!(&indices[1..5], &[1, 1, 255, 255]);
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name)
+}
+
+/// Render the smooth field and compare it with a golden PNG.
+///
+/// Images are compared decoded, so encoder changes don't invalidate them.
+fn assert_matches_golden(dither: Option<&str>, name: &str) {
+    let style = temperature_style(dither);
+    let palette = style.compute_palette().unwrap();
+    let indices = apply_style_gradient_indexed(&smooth_field(), WIDTH, HEIGHT, &palette, &style);
+    let png = create_png_from_precomputed(&indices, WIDTH, HEIGHT, &palette).unwrap();
+
+    let path = golden_path(name);
+    if std::env::var("UPDATE_GOLDEN").is_ok() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &png).unwrap();
+        return;
+    }
+
+    let golden = std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("{}: {} (run with UPDATE_GOLDEN=1)", path.display(), e));
+    let expected = image::load_from_memory(&golden).unwrap().to_rgba8();
+    let actual = image::load_from_memory(&png).unwrap().to_rgba8();
+    assert_eq!(actual.dimensions(), expected.dimensions());
+    assert!(
+        actual.as_raw() == expected.as_raw(),
+        "{} differs from {}",
+        name,
+        path.display()
+    );
+}
+
+#[test]
+fn test_golden_undithered() {
+    assert_matches_golden(None, "dither_none.png");
+}
+
+#[test]
+fn test_golden_ordered() {
+    assert_matches_golden(Some("ordered"), "dither_ordered.png");
+}
+
+#[test]
+fn test_golden_blue_noise() {
+    assert_matches_golden(Some("blue_noise"), "dither_blue_noise.png");
+}
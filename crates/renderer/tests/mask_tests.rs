@@ -0,0 +1,126 @@
+//! Tests for masking rendered data by a secondary field.
+
+use renderer::mask::{DataMask, MaskComparison, MaskInput};
+use renderer::style::{
+    apply_style_gradient, apply_style_gradient_indexed, apply_style_gradient_indexed_masked,
+    apply_style_gradient_masked, StyleConfig, StyleDefinition,
+};
+
+fn precip_type_style() -> StyleDefinition {
+    let json = r##"{
+        "version": "1.0",
+        "styles": {
+            "ptype": {
+                "default": true,
+                "name": "Precipitation Type",
+                "type": "gradient",
+                "stops": [
+                    {"value": 0, "color": "#00FF00"},
+                    {"value": 3, "color": "#0000FF"}
+                ],
+                "mask": {
+                    "parameter": "PRATE",
+                    "comparison": "gte",
+                    "threshold": 0.1,
+                    "transform": {"type": "linear", "scale": 3600}
+                }
+            }
+        }
+    }"##;
+    StyleConfig::from_json(json)
+        .unwrap()
+        .get_style("ptype")
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn test_parse_mask_config() {
+    let style = precip_type_style();
+    let mask = style.mask.as_ref().unwrap();
+    assert_eq!(mask.parameter, "PRATE");
+    assert_eq!(mask.comparison, MaskComparison::Gte);
+    assert_eq!(mask.threshold, 0.1);
+    assert!(mask.level.is_none());
+
+    // Comparison defaults to "gt"
+    let mask: DataMask = serde_json::from_str(r#"{"parameter": "REFL", "threshold": 5}"#).unwrap();
+    assert_eq!(mask.comparison, MaskComparison::Gt);
+}
+
+#[test]
+fn test_mask_comparisons() {
+    let mask = |comparison| DataMask {
+        parameter: "PRATE".to_string(),
+        level: None,
+        comparison,
+        threshold: 1.0,
+        transform: None,
+    };
+
+    assert!(mask(MaskComparison::Gt).shows(1.5));
+    assert!(!mask(MaskComparison::Gt).shows(1.0));
+    assert!(mask(MaskComparison::Gte).shows(1.0));
+    assert!(mask(MaskComparison::Lt).shows(0.5));
+    assert!(!mask(MaskComparison::Lt).shows(1.0));
+    assert!(mask(MaskComparison::Lte).shows(1.0));
+
+    // Missing mask values always hide the pixel
+    assert!(!mask(MaskComparison::Lt).shows(f32::NAN));
+}
+
+#[test]
+fn test_mask_applies_transform() {
+    let style = precip_type_style();
+    let mask = style.mask.as_ref().unwrap();
+
+    // 0.0001 kg/m²/s * 3600 = 0.36 mm/h
+    assert!(mask.shows(0.0001));
+    assert!(!mask.shows(0.00001));
+}
+
+#[test]
+fn test_masked_rgba_rendering() {
+    let style = precip_type_style();
+    let data = vec![1.0, 2.0, 1.0, 2.0];
+    let rates = vec![0.001, 0.0, f32::NAN, 0.001];
+    let mask = MaskInput::new(&rates, style.mask.as_ref().unwrap());
+
+    let unmasked = apply_style_gradient(&data, 2, 2, &style);
+    let masked = apply_style_gradient_masked(&data, 2, 2, &style, Some(mask));
+
+    // Shown pixels are unchanged
+    assert_eq!(masked[0..4], unmasked[0..4]);
+    assert_eq!(masked[12..16], unmasked[12..16]);
+    // Zero rate and missing rate are transparent
+    assert_eq!(masked[4..8], [0, 0, 0, 0]);
+    assert_eq!(masked[8..12], [0, 0, 0, 0]);
+}
+
+#[test]
+fn test_masked_indexed_rendering() {
+    let style = precip_type_style();
+    let palette = style.compute_palette().unwrap();
+    let data = vec![1.0, 2.0, 1.0, 2.0];
+    let rates = vec![0.001, 0.0, f32::NAN, 0.001];
+    let mask = MaskInput::new(&rates, style.mask.as_ref().unwrap());
+
+    let unmasked = apply_style_gradient_indexed(&data, 2, 2, &palette, &style);
+    let masked = apply_style_gradient_indexed_masked(&data, 2, 2, &palette, &style, Some(mask));
+
+    assert_ne!(unmasked[1], 0);
+    assert_eq!(masked, vec![unmasked[0], 0, 0, unmasked[3]]);
+}
+
+#[test]
+fn test_short_mask_hides_remaining_pixels() {
+    let style = precip_type_style();
+    let palette = style.compute_palette().unwrap();
+    let data = vec![1.0; 4];
+    let rates = vec![0.001; 2];
+    let mask = MaskInput::new(&rates, style.mask.as_ref().unwrap());
+
+    let masked = apply_style_gradient_indexed_masked(&data, 2, 2, &palette, &style, Some(mask));
+    assert_ne!(masked[1], 0);
+    assert_eq!(masked[2..], [0, 0]);
+}
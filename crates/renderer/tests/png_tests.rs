@@ -4,8 +4,12 @@
 //! - Palette extraction (sequential and parallel)
 //! - PNG format selection (auto mode)
 //! - File size comparisons for weather-like data
+//! - Encoder options (filters, compression levels, backends) and re-encoding
 
-use renderer::png::{create_png, create_png_auto};
+use renderer::png::{
+    create_png, create_png_auto, create_png_auto_with_options, create_png_with_options,
+    recompress_png, DeflateBackend, FilterStrategy, PngOptions,
+};
 use std::collections::HashSet;
 
 // ============================================================================
@@ -60,6 +64,28 @@ fn generate_weather_pixels(width: usize, height: usize) -> Vec<u8> {
     pixels
 }
 
+/// Generate a smooth RGBA gradient (many colors, benefits from filtering)
+fn generate_gradient_pixels(width: usize, height: usize) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let r = (x * 255 / width.max(1)) as u8;
+            let g = (y * 255 / height.max(1)) as u8;
+            let b = ((x + y) * 127 / (width + height).max(1)) as u8;
+            pixels.extend_from_slice(&[r, g, b, 255]);
+        }
+    }
+    pixels
+}
+
+/// Decode a PNG to RGBA pixels
+fn decode_rgba(png: &[u8]) -> Vec<u8> {
+    image::load_from_memory(png)
+        .expect("valid PNG")
+        .to_rgba8()
+        .into_raw()
+}
+
 /// Count unique colors in pixel data
 fn count_unique_colors(pixels: &[u8]) -> usize {
     let mut unique: HashSet<u32> = HashSet::new();
@@ -337,3 +363,147 @@ fn test_grayscale_gradient_plus_one() {
     let result = create_png_auto(&pixels, 257, 1);
     assert!(result.is_ok());
 }
+
+// ============================================================================
+// Encoder option tests
+// ============================================================================
+
+const ALL_FILTERS: [FilterStrategy; 6] = [
+    FilterStrategy::None,
+    FilterStrategy::Sub,
+    FilterStrategy::Up,
+    FilterStrategy::Average,
+    FilterStrategy::Paeth,
+    FilterStrategy::Adaptive,
+];
+
+#[test]
+fn test_filters_round_trip_rgba() {
+    let pixels = generate_gradient_pixels(37, 23);
+    for filter in ALL_FILTERS {
+        let options = PngOptions {
+            filter,
+            ..PngOptions::interactive()
+        };
+        let png = create_png_with_options(&pixels, 37, 23, &options).unwrap();
+        assert_eq!(decode_rgba(&png), pixels, "filter {:?}", filter);
+    }
+}
+
+#[test]
+fn test_filters_round_trip_indexed() {
+    let pixels = generate_weather_pixels(64, 48);
+    for filter in ALL_FILTERS {
+        let options = PngOptions {
+            filter,
+            ..PngOptions::seeding()
+        };
+        let png = create_png_auto_with_options(&pixels, 64, 48, &options).unwrap();
+        assert_eq!(decode_rgba(&png), pixels, "filter {:?}", filter);
+    }
+}
+
+#[test]
+fn test_parallel_filtering_large_image() {
+    // 1024x1024 RGBA is above the parallel filtering threshold
+    let pixels = generate_gradient_pixels(1024, 1024);
+    let options = PngOptions {
+        filter: FilterStrategy::Adaptive,
+        ..PngOptions::interactive()
+    };
+    let png = create_png_with_options(&pixels, 1024, 1024, &options).unwrap();
+    assert_eq!(decode_rgba(&png), pixels);
+}
+
+#[test]
+fn test_seeding_profile_is_smaller() {
+    let pixels = generate_gradient_pixels(256, 256);
+    let interactive =
+        create_png_with_options(&pixels, 256, 256, &PngOptions::interactive()).unwrap();
+    let seeding = create_png_with_options(&pixels, 256, 256, &PngOptions::seeding()).unwrap();
+
+    println!(
+        "\n256x256 gradient: interactive {} bytes, seeding {} bytes",
+        interactive.len(),
+        seeding.len()
+    );
+    assert!(seeding.len() < interactive.len());
+}
+
+#[test]
+fn test_parse_options() {
+    assert_eq!("paeth".parse(), Ok(FilterStrategy::Paeth));
+    assert_eq!("Adaptive".parse(), Ok(FilterStrategy::Adaptive));
+    assert!("best".parse::<FilterStrategy>().is_err());
+
+    assert_eq!("flate2".parse(), Ok(DeflateBackend::Flate2));
+    assert_eq!("zlib".parse(), Ok(DeflateBackend::Flate2));
+    assert!("brotli".parse::<DeflateBackend>().is_err());
+    // Backends that were not compiled in are rejected
+    assert_eq!(
+        "zopfli".parse::<DeflateBackend>().is_ok(),
+        cfg!(feature = "zopfli")
+    );
+}
+
+#[cfg(feature = "fdeflate")]
+#[test]
+fn test_fdeflate_backend_round_trip() {
+    let pixels = generate_gradient_pixels(128, 64);
+    let options = PngOptions {
+        backend: DeflateBackend::Fdeflate,
+        ..PngOptions::interactive()
+    };
+    let png = create_png_with_options(&pixels, 128, 64, &options).unwrap();
+    assert_eq!(decode_rgba(&png), pixels);
+}
+
+#[cfg(feature = "zopfli")]
+#[test]
+fn test_zopfli_backend_round_trip() {
+    let pixels = generate_weather_pixels(64, 64);
+    let options = PngOptions {
+        backend: DeflateBackend::Zopfli,
+        ..PngOptions::seeding()
+    };
+    let png = create_png_auto_with_options(&pixels, 64, 64, &options).unwrap();
+    assert_eq!(decode_rgba(&png), pixels);
+}
+
+// ============================================================================
+// Re-encoding tests
+// ============================================================================
+
+#[test]
+fn test_recompress_preserves_pixels() {
+    // Indexed (with tRNS) and RGBA inputs
+    let mut weather = generate_weather_pixels(96, 64);
+    weather[3] = 0;
+    let gradient = generate_gradient_pixels(96, 64);
+
+    for pixels in [weather, gradient] {
+        let original = create_png_auto(&pixels, 96, 64).unwrap();
+        let recompressed = recompress_png(&original, &PngOptions::seeding()).unwrap();
+        assert_eq!(decode_rgba(&recompressed), pixels);
+        assert!(recompressed.len() <= original.len());
+    }
+}
+
+#[test]
+fn test_recompress_filtered_input() {
+    let pixels = generate_gradient_pixels(40, 30);
+    let options = PngOptions {
+        filter: FilterStrategy::Paeth,
+        ..PngOptions::interactive()
+    };
+    let filtered = create_png_with_options(&pixels, 40, 30, &options).unwrap();
+    let recompressed = recompress_png(&filtered, &PngOptions::interactive()).unwrap();
+    assert_eq!(decode_rgba(&recompressed), pixels);
+}
+
+#[test]
+fn test_recompress_rejects_invalid_input() {
+    assert!(recompress_png(b"not a png", &PngOptions::seeding()).is_err());
+    let png = create_png(&[255, 0, 0, 255], 1, 1).unwrap();
+    assert!(recompress_png(&png[..20], &PngOptions::seeding()).is_err());
+}
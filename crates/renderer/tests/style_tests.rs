@@ -441,6 +441,7 @@ fn test_apply_transform_scale_only() {
         transform_type: "linear".to_string(),
         scale: Some(2.0),
         offset: None,
+        ..Default::default()
     };
 
     let result = apply_transform(10.0, Some(&transform));
@@ -455,6 +456,7 @@ fn test_apply_transform_offset_only() {
         transform_type: "linear".to_string(),
         scale: None,
         offset: Some(-273.15),
+        ..Default::default()
     };
 
     // With no scale, should default to scale=1.0
@@ -471,6 +473,7 @@ fn test_apply_transform_kelvin_to_celsius() {
         transform_type: "linear".to_string(),
         scale: Some(1.0),
         offset: Some(-273.15),
+        ..Default::default()
     };
 
     // Freezing point: 273.15 K = 0 C
@@ -489,12 +492,138 @@ fn test_apply_transform_pascals_to_hectopascals() {
         transform_type: "linear".to_string(),
         scale: Some(0.01),
         offset: None,
+        ..Default::default()
     };
 
     let result = apply_transform(101325.0, Some(&transform));
     assert!((result - 1013.25).abs() < 0.01);
 }
 
+#[test]
+fn test_expression_transform_shorthand() {
+    let json = r##"{
+        "version": "1.0",
+        "styles": {
+            "log_precip": {
+                "default": true,
+                "name": "Log Precip",
+                "type": "gradient",
+                "transform": "log10(x + 1)",
+                "stops": [
+                    {"value": 0, "color": "#000000"},
+                    {"value": 2, "color": "#FFFFFF"}
+                ]
+            }
+        }
+    }"##;
+
+    let config = StyleConfig::from_json(json).unwrap();
+    let style = config.get_style("log_precip").unwrap();
+    let transform = style.transform.as_ref().unwrap();
+    assert_eq!(transform.transform_type, "expression");
+
+    // 9 -> log10(10) = 1, halfway between the stops
+    assert!((apply_transform(9.0, Some(transform)) - 1.0).abs() < 1e-6);
+    let rgba = apply_style_gradient(&[9.0, 99.0, -5.0], 3, 1, style);
+    assert!(rgba[0] >= 120 && rgba[0] <= 135, "got {}", rgba[0]);
+    assert_eq!(&rgba[4..8], &[255, 255, 255, 255]);
+    // log10 of a negative number is NaN -> transparent
+    assert_eq!(rgba[7 + 4], 0);
+}
+
+#[test]
+fn test_piecewise_transform() {
+    let json = r##"{
+        "version": "1.0",
+        "styles": {
+            "stretch": {
+                "default": true,
+                "name": "Stretch",
+                "type": "gradient",
+                "transform": {"type": "piecewise", "points": [[0, 0], [1, 50], [10, 100]]},
+                "stops": [
+                    {"value": 0, "color": "#000000"},
+                    {"value": 100, "color": "#FFFFFF"}
+                ]
+            }
+        }
+    }"##;
+
+    let config = StyleConfig::from_json(json).unwrap();
+    let transform = config
+        .get_style("stretch")
+        .unwrap()
+        .transform
+        .clone()
+        .unwrap();
+
+    assert!((transform.apply(0.5) - 25.0).abs() < 1e-4);
+    assert!((transform.apply(5.5) - 75.0).abs() < 1e-4);
+    // Extrapolates along the end segments
+    assert!((transform.apply(19.0) - 150.0).abs() < 1e-4);
+    assert!((transform.apply(-1.0) + 50.0).abs() < 1e-4);
+    assert!((transform.invert(75.0).unwrap() - 5.5).abs() < 1e-4);
+}
+
+#[test]
+fn test_invalid_transforms_rejected_at_load() {
+    let style_with = |transform: &str| {
+        format!(
+            r##"{{"version": "1.0", "styles": {{"s": {{"name": "S", "type": "gradient",
+                "transform": {}, "stops": [{{"value": 0, "color": "#000000"}}]}}}}}}"##,
+            transform
+        )
+    };
+
+    assert!(StyleConfig::from_json(&style_with(r#""log10(x""#)).is_err());
+    assert!(StyleConfig::from_json(&style_with(r#"{"type": "expression"}"#)).is_err());
+    assert!(StyleConfig::from_json(&style_with(
+        r#"{"type": "piecewise", "points": [[1, 0], [0, 1]]}"#
+    ))
+    .is_err());
+    assert!(StyleConfig::from_json(&style_with(r#"{"type": "k_to_c"}"#)).is_ok());
+}
+
+#[test]
+fn test_legend_entries_in_original_units() {
+    let json = r##"{
+        "version": "1.0",
+        "styles": {
+            "reflectivity": {
+                "default": true,
+                "name": "Reflectivity",
+                "type": "gradient",
+                "units": "dBZ",
+                "transform": {"type": "expression", "expression": "10 * log10(x)", "inverse": "10 ^ (x / 10)"},
+                "stops": [
+                    {"value": 20, "color": "#00FF00"},
+                    {"value": 0, "color": "#000000"},
+                    {"value": 40, "color": "#FF0000", "label": "Heavy"}
+                ]
+            },
+            "temperature": {
+                "name": "Temperature",
+                "type": "gradient",
+                "transform": {"type": "k_to_c"},
+                "stops": [{"value": -10, "color": "#0000FF"}]
+            }
+        }
+    }"##;
+
+    let config = StyleConfig::from_json(json).unwrap();
+    let entries = config.get_style("reflectivity").unwrap().legend_entries();
+
+    let labels: Vec<&str> = entries.iter().map(|e| e.label.as_str()).collect();
+    assert_eq!(labels, ["1", "100", "Heavy"]);
+    assert!((entries[1].data_value.unwrap() - 100.0).abs() < 1e-3);
+    assert_eq!(entries[1].value, 20.0);
+
+    // Unit conversions keep labels in converted units
+    let entries = config.get_style("temperature").unwrap().legend_entries();
+    assert_eq!(entries[0].label, "-10");
+    assert_eq!(entries[0].data_value, None);
+}
+
 // ============================================================================
 // Palette size and efficiency tests
 // ============================================================================
@@ -736,3 +865,36 @@ fn test_style_version_parsing() {
     let config = StyleConfig::from_json(json).unwrap();
     assert_eq!(config.version, "2.0");
 }
+
+#[test]
+fn test_style_minify_parsing() {
+    let json = r##"{
+        "version": "1.0",
+        "styles": {
+            "speckle": {
+                "default": true,
+                "name": "Reflectivity",
+                "type": "gradient",
+                "stops": [{"value": 0, "color": "#000"}, {"value": 70, "color": "#FFF"}],
+                "minify": {"filter": "box"}
+            },
+            "plain": {
+                "name": "Plain",
+                "type": "gradient",
+                "stops": [{"value": 0, "color": "#000"}, {"value": 70, "color": "#FFF"}]
+            }
+        }
+    }"##;
+
+    let config = StyleConfig::from_json(json).unwrap();
+    let minify = config
+        .get_style("speckle")
+        .unwrap()
+        .minify
+        .as_ref()
+        .unwrap();
+    assert!(minify.is_box());
+    assert_eq!(minify.threshold, 2.0);
+
+    assert!(config.get_style("plain").unwrap().minify.is_none());
+}
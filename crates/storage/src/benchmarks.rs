@@ -0,0 +1,674 @@
+//! Load test and Criterion benchmark history in PostgreSQL.
+//!
+//! Load test runs used to exist only as JSONL files on the API pod, which
+//! disappeared with it. Here each run is a row holding its summary figures
+//! and the full results document, optionally followed by its per-request
+//! log. Criterion summaries get one row per benchmark per run. Both carry
+//! [`RunTags`] (git SHA, branch and a hash of the service configuration),
+//! so dashboards can chart trends and tell code changes from config changes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::catalog::Catalog;
+use wms_common::{WmsError, WmsResult};
+
+/// Request log rows inserted per statement (three bind parameters each).
+const REQUEST_BATCH_SIZE: usize = 1000;
+
+/// Identifies the code and configuration a run was measured against.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct RunTags {
+    /// Commit the service was built from
+    pub git_sha: Option<String>,
+    /// Branch of that commit
+    pub git_branch: Option<String>,
+    /// [`config_hash`] of the service configuration under test
+    pub config_hash: Option<String>,
+}
+
+impl RunTags {
+    /// Fill tags missing here from `other`.
+    pub fn or(self, other: RunTags) -> Self {
+        Self {
+            git_sha: self.git_sha.or(other.git_sha),
+            git_branch: self.git_branch.or(other.git_branch),
+            config_hash: self.config_hash.or(other.config_hash),
+        }
+    }
+}
+
+/// SHA-256 of a configuration document, independent of key order.
+///
+/// Runs against the same settings share a hash, so a trend chart can split
+/// at the points where the configuration changed.
+pub fn config_hash(config: &serde_json::Value) -> String {
+    let mut canonical = String::new();
+    write_canonical(config, &mut canonical);
+    Sha256::digest(canonical.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Serialize JSON with object keys sorted at every level.
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+// ============================================================================
+// Load test runs
+// ============================================================================
+
+/// A load test run as produced by the `load-test` tool, ready to store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewLoadTestRun {
+    pub scenario: String,
+    pub started_at: DateTime<Utc>,
+    pub tags: RunTags,
+    pub requests_per_second: f64,
+    pub latency_p50: f64,
+    pub latency_p90: f64,
+    pub latency_p99: f64,
+    pub cache_hit_rate: f64,
+    pub total_requests: i64,
+    pub failed_requests: i64,
+    /// The complete results document
+    pub results: serde_json::Value,
+}
+
+/// Fields of the `load-test` JSON output stored as columns.
+#[derive(Deserialize)]
+struct ResultsSummary {
+    timestamp: DateTime<Utc>,
+    scenario_name: String,
+    requests_per_second: f64,
+    latency_p50: f64,
+    latency_p90: f64,
+    latency_p99: f64,
+    cache_hit_rate: f64,
+    total_requests: i64,
+    failed_requests: i64,
+    #[serde(default)]
+    git_info: Option<ResultsGitInfo>,
+    #[serde(default)]
+    system_config: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct ResultsGitInfo {
+    commit_hash: String,
+    branch: String,
+}
+
+impl NewLoadTestRun {
+    /// Parse the JSON results of a `load-test run`.
+    ///
+    /// Tags not given in `tags` are taken from the results: the git SHA and
+    /// branch from `git_info` and the config hash from `system_config`.
+    pub fn from_results(results: serde_json::Value, tags: RunTags) -> WmsResult<Self> {
+        let summary =
+            ResultsSummary::deserialize(&results).map_err(|e| WmsError::InvalidParameter {
+                param: "results".to_string(),
+                message: e.to_string(),
+            })?;
+
+        let recorded = RunTags {
+            git_sha: summary.git_info.as_ref().map(|g| g.commit_hash.clone()),
+            git_branch: summary.git_info.as_ref().map(|g| g.branch.clone()),
+            config_hash: summary.system_config.as_ref().map(config_hash),
+        };
+
+        Ok(Self {
+            scenario: summary.scenario_name,
+            started_at: summary.timestamp,
+            tags: tags.or(recorded),
+            requests_per_second: summary.requests_per_second,
+            latency_p50: summary.latency_p50,
+            latency_p90: summary.latency_p90,
+            latency_p99: summary.latency_p99,
+            cache_hit_rate: summary.cache_hit_rate,
+            total_requests: summary.total_requests,
+            failed_requests: summary.failed_requests,
+            results,
+        })
+    }
+}
+
+/// A stored load test run.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LoadTestRun {
+    pub id: Uuid,
+    pub scenario: String,
+    pub started_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub tags: RunTags,
+    pub requests_per_second: f64,
+    pub latency_p50: f64,
+    pub latency_p90: f64,
+    pub latency_p99: f64,
+    pub cache_hit_rate: f64,
+    pub total_requests: i64,
+    pub failed_requests: i64,
+    /// Requests in the stored request log (0 if none was uploaded)
+    pub logged_requests: i64,
+    pub recorded_at: DateTime<Utc>,
+    pub results: serde_json::Value,
+}
+
+/// Filters for listing load test runs. Empty fields match every run.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LoadTestQuery {
+    pub scenario: Option<String>,
+    /// Full SHA or a prefix of it
+    pub git_sha: Option<String>,
+    pub config_hash: Option<String>,
+    /// Most recent runs to return (default 100)
+    pub limit: Option<i64>,
+}
+
+// ============================================================================
+// Criterion results
+// ============================================================================
+
+/// One Criterion benchmark's estimates, ready to store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewCriterionResult {
+    /// Benchmark ID as Criterion names its directory, e.g. `render/png_256`
+    pub benchmark: String,
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    pub std_dev_ns: f64,
+    /// Contents of the benchmark's `estimates.json`
+    pub estimates: serde_json::Value,
+}
+
+impl NewCriterionResult {
+    /// Read the point estimates out of a Criterion `estimates.json`.
+    ///
+    /// Returns `None` if the mean, median or standard deviation is missing.
+    pub fn from_estimates(benchmark: &str, estimates: serde_json::Value) -> Option<Self> {
+        let point = |name: &str| estimates.get(name)?.get("point_estimate")?.as_f64();
+        Some(Self {
+            benchmark: benchmark.to_string(),
+            mean_ns: point("mean")?,
+            median_ns: point("median")?,
+            std_dev_ns: point("std_dev")?,
+            estimates,
+        })
+    }
+}
+
+/// A stored Criterion result.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CriterionResult {
+    pub benchmark: String,
+    pub recorded_at: DateTime<Utc>,
+    #[sqlx(flatten)]
+    #[serde(flatten)]
+    pub tags: RunTags,
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    pub std_dev_ns: f64,
+    /// Mean of the benchmark's previous result, for latest-result listings
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_mean_ns: Option<f64>,
+    pub estimates: serde_json::Value,
+}
+
+impl CriterionResult {
+    /// Change of the mean against the previous result, in percent.
+    pub fn change_pct(&self) -> Option<f64> {
+        self.previous_mean_ns
+            .filter(|previous| *previous > 0.0)
+            .map(|previous| (self.mean_ns - previous) / previous * 100.0)
+    }
+}
+
+// ============================================================================
+// Store
+// ============================================================================
+
+/// Benchmark history tables, sharing the catalog's connection pool.
+#[derive(Clone)]
+pub struct BenchmarkStore {
+    pool: PgPool,
+}
+
+impl BenchmarkStore {
+    /// Use the catalog's database. `None` for an in-memory catalog.
+    pub fn from_catalog(catalog: &Catalog) -> Option<Self> {
+        catalog.pool().ok().map(|pool| Self { pool: pool.clone() })
+    }
+
+    /// Create the benchmark tables if they don't exist.
+    pub async fn migrate(&self) -> WmsResult<()> {
+        for statement in SCHEMA_SQL.split(';') {
+            let trimmed = statement.trim();
+            if !trimmed.is_empty() {
+                sqlx::query(trimmed)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| WmsError::DatabaseError(format!("Migration failed: {}", e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Store a load test run and return its ID.
+    pub async fn record_load_test(&self, run: &NewLoadTestRun) -> WmsResult<Uuid> {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO loadtest_runs (id, scenario, started_at, git_sha, git_branch, \
+             config_hash, requests_per_second, latency_p50, latency_p90, latency_p99, \
+             cache_hit_rate, total_requests, failed_requests, results) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+        )
+        .bind(id)
+        .bind(&run.scenario)
+        .bind(run.started_at)
+        .bind(&run.tags.git_sha)
+        .bind(&run.tags.git_branch)
+        .bind(&run.tags.config_hash)
+        .bind(run.requests_per_second)
+        .bind(run.latency_p50)
+        .bind(run.latency_p90)
+        .bind(run.latency_p99)
+        .bind(run.cache_hit_rate)
+        .bind(run.total_requests)
+        .bind(run.failed_requests)
+        .bind(&run.results)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Insert failed: {}", e)))?;
+        Ok(id)
+    }
+
+    /// Append entries to a run's request log.
+    ///
+    /// Large logs can be uploaded in several calls; entries keep their
+    /// order across calls. Returns the number of entries stored.
+    pub async fn append_load_test_requests(
+        &self,
+        run_id: Uuid,
+        entries: &[serde_json::Value],
+    ) -> WmsResult<u64> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Transaction failed: {}", e)))?;
+
+        // Lock the run so concurrent uploads can't interleave sequence numbers
+        let next_seq = sqlx::query_scalar::<_, i64>(
+            "SELECT COALESCE((SELECT MAX(seq) + 1 FROM loadtest_requests WHERE run_id = $1), 0) \
+             FROM loadtest_runs WHERE id = $1 FOR UPDATE",
+        )
+        .bind(run_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?
+        .ok_or_else(|| WmsError::DataNotAvailable(format!("Load test run {}", run_id)))?;
+
+        let mut stored = 0;
+        for (batch_index, batch) in entries.chunks(REQUEST_BATCH_SIZE).enumerate() {
+            let first_seq = next_seq + (batch_index * REQUEST_BATCH_SIZE) as i64;
+            let mut insert: QueryBuilder<Postgres> =
+                QueryBuilder::new("INSERT INTO loadtest_requests (run_id, seq, entry) ");
+            insert.push_values(batch.iter().enumerate(), |mut row, (i, entry)| {
+                row.push_bind(run_id)
+                    .push_bind(first_seq + i as i64)
+                    .push_bind(entry);
+            });
+            stored += insert
+                .build()
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| WmsError::DatabaseError(format!("Insert failed: {}", e)))?
+                .rows_affected();
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Commit failed: {}", e)))?;
+        Ok(stored)
+    }
+
+    /// Load test runs matching `query`, newest first.
+    pub async fn list_load_tests(&self, query: &LoadTestQuery) -> WmsResult<Vec<LoadTestRun>> {
+        sqlx::query_as::<_, LoadTestRun>(&format!(
+            "{} WHERE ($1::TEXT IS NULL OR scenario = $1) \
+             AND ($2::TEXT IS NULL OR git_sha LIKE $2 || '%') \
+             AND ($3::TEXT IS NULL OR config_hash = $3) \
+             ORDER BY started_at DESC LIMIT $4",
+            LOAD_TEST_SELECT
+        ))
+        .bind(&query.scenario)
+        .bind(&query.git_sha)
+        .bind(&query.config_hash)
+        .bind(query.limit.unwrap_or(100).clamp(1, 1000))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    /// A single load test run.
+    pub async fn get_load_test(&self, id: Uuid) -> WmsResult<Option<LoadTestRun>> {
+        sqlx::query_as::<_, LoadTestRun>(&format!("{} WHERE id = $1", LOAD_TEST_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    /// A run's request log, in the order it was recorded.
+    pub async fn load_test_requests(&self, run_id: Uuid) -> WmsResult<Vec<serde_json::Value>> {
+        sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT entry FROM loadtest_requests WHERE run_id = $1 ORDER BY seq",
+        )
+        .bind(run_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    /// Store the Criterion results of one benchmark run.
+    pub async fn record_criterion(
+        &self,
+        results: &[NewCriterionResult],
+        tags: &RunTags,
+    ) -> WmsResult<u64> {
+        if results.is_empty() {
+            return Ok(0);
+        }
+
+        // One timestamp for the whole run keeps its results together
+        let recorded_at = Utc::now();
+        let mut insert: QueryBuilder<Postgres> = QueryBuilder::new(
+            "INSERT INTO criterion_results (id, benchmark, recorded_at, git_sha, git_branch, \
+             config_hash, mean_ns, median_ns, std_dev_ns, estimates) ",
+        );
+        insert.push_values(results, |mut row, result| {
+            row.push_bind(Uuid::new_v4())
+                .push_bind(&result.benchmark)
+                .push_bind(recorded_at)
+                .push_bind(&tags.git_sha)
+                .push_bind(&tags.git_branch)
+                .push_bind(&tags.config_hash)
+                .push_bind(result.mean_ns)
+                .push_bind(result.median_ns)
+                .push_bind(result.std_dev_ns)
+                .push_bind(&result.estimates);
+        });
+        insert
+            .build()
+            .execute(&self.pool)
+            .await
+            .map(|done| done.rows_affected())
+            .map_err(|e| WmsError::DatabaseError(format!("Insert failed: {}", e)))
+    }
+
+    /// The latest result of every benchmark, with the mean of the one before.
+    pub async fn latest_criterion(&self) -> WmsResult<Vec<CriterionResult>> {
+        sqlx::query_as::<_, CriterionResult>(
+            "SELECT benchmark, recorded_at, git_sha, git_branch, config_hash, mean_ns, \
+             median_ns, std_dev_ns, previous_mean_ns, estimates FROM ( \
+                 SELECT *, \
+                     LAG(mean_ns) OVER (PARTITION BY benchmark ORDER BY recorded_at) \
+                         AS previous_mean_ns, \
+                     ROW_NUMBER() OVER (PARTITION BY benchmark ORDER BY recorded_at DESC) \
+                         AS newest \
+                 FROM criterion_results \
+             ) ranked WHERE newest = 1 ORDER BY benchmark",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    /// The most recent results of one benchmark, oldest first for charting.
+    pub async fn criterion_trend(
+        &self,
+        benchmark: &str,
+        limit: i64,
+    ) -> WmsResult<Vec<CriterionResult>> {
+        sqlx::query_as::<_, CriterionResult>(
+            "SELECT * FROM ( \
+                 SELECT benchmark, recorded_at, git_sha, git_branch, config_hash, mean_ns, \
+                 median_ns, std_dev_ns, estimates FROM criterion_results \
+                 WHERE benchmark = $1 ORDER BY recorded_at DESC LIMIT $2 \
+             ) recent ORDER BY recorded_at",
+        )
+        .bind(benchmark)
+        .bind(limit.clamp(1, 1000))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+}
+
+/// Columns of [`LoadTestRun`], counting each run's logged requests.
+const LOAD_TEST_SELECT: &str = "SELECT id, scenario, started_at, git_sha, git_branch, \
+     config_hash, requests_per_second, latency_p50, latency_p90, latency_p99, cache_hit_rate, \
+     total_requests, failed_requests, recorded_at, results, \
+     (SELECT COUNT(*) FROM loadtest_requests r WHERE r.run_id = loadtest_runs.id) \
+         AS logged_requests \
+     FROM loadtest_runs";
+
+const SCHEMA_SQL: &str = r#"
+CREATE TABLE IF NOT EXISTS loadtest_runs (
+    id UUID PRIMARY KEY,
+    scenario VARCHAR(200) NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    git_sha VARCHAR(64),
+    git_branch VARCHAR(200),
+    config_hash VARCHAR(64),
+    requests_per_second DOUBLE PRECISION NOT NULL,
+    latency_p50 DOUBLE PRECISION NOT NULL,
+    latency_p90 DOUBLE PRECISION NOT NULL,
+    latency_p99 DOUBLE PRECISION NOT NULL,
+    cache_hit_rate DOUBLE PRECISION NOT NULL,
+    total_requests BIGINT NOT NULL,
+    failed_requests BIGINT NOT NULL,
+    results JSONB NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_loadtest_runs_scenario ON loadtest_runs(scenario, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_loadtest_runs_git_sha ON loadtest_runs(git_sha);
+
+-- Per-request log of a run, in request order
+CREATE TABLE IF NOT EXISTS loadtest_requests (
+    run_id UUID NOT NULL REFERENCES loadtest_runs(id) ON DELETE CASCADE,
+    seq BIGINT NOT NULL,
+    entry JSONB NOT NULL,
+
+    PRIMARY KEY(run_id, seq)
+);
+
+-- Criterion estimates, one row per benchmark per run
+CREATE TABLE IF NOT EXISTS criterion_results (
+    id UUID PRIMARY KEY,
+    benchmark VARCHAR(300) NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    git_sha VARCHAR(64),
+    git_branch VARCHAR(200),
+    config_hash VARCHAR(64),
+    mean_ns DOUBLE PRECISION NOT NULL,
+    median_ns DOUBLE PRECISION NOT NULL,
+    std_dev_ns DOUBLE PRECISION NOT NULL,
+    estimates JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_criterion_results_benchmark
+    ON criterion_results(benchmark, recorded_at DESC)
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn results() -> serde_json::Value {
+        json!({
+            "timestamp": "2025-12-02T19:42:58Z",
+            "scenario_name": "goes_random_temporal",
+            "config_name": "goes_random_temporal",
+            "duration_secs": 90.0,
+            "total_requests": 23299,
+            "successful_requests": 23299,
+            "failed_requests": 0,
+            "requests_per_second": 258.8,
+            "latency_p50": 0.8,
+            "latency_p75": 38.0,
+            "latency_p90": 123.5,
+            "latency_p95": 130.4,
+            "latency_p99": 140.9,
+            "cache_hit_rate": 51.3,
+            "layers": ["goes18_CMI_C13"],
+            "concurrency": 10,
+            "system_config": {"l1_cache_enabled": true, "prefetch_rings": 2},
+            "git_info": {
+                "commit_hash": "0a9d2a3f00000000000000000000000000000000",
+                "commit_short": "0a9d2a3",
+                "branch": "main",
+                "commit_message": "Add NetCDF export",
+                "commit_author": "dev",
+                "commit_date": "2025-12-02",
+                "is_dirty": false
+            }
+        })
+    }
+
+    #[test]
+    fn test_config_hash_ignores_key_order() {
+        let a = json!({"b": 1, "a": {"y": [1, 2], "x": "s"}});
+        let b = json!({"a": {"x": "s", "y": [1, 2]}, "b": 1});
+        assert_eq!(config_hash(&a), config_hash(&b));
+        assert_eq!(config_hash(&a).len(), 64);
+
+        assert_ne!(
+            config_hash(&a),
+            config_hash(&json!({"b": 2, "a": {"y": [1, 2], "x": "s"}}))
+        );
+        assert_ne!(
+            config_hash(&a),
+            config_hash(&json!({"b": 1, "a": {"y": [2, 1], "x": "s"}}))
+        );
+    }
+
+    #[test]
+    fn test_load_test_run_from_results() {
+        let run = NewLoadTestRun::from_results(results(), RunTags::default()).unwrap();
+        assert_eq!(run.scenario, "goes_random_temporal");
+        assert_eq!(run.started_at.to_rfc3339(), "2025-12-02T19:42:58+00:00");
+        assert_eq!(run.total_requests, 23299);
+        assert_eq!(run.latency_p99, 140.9);
+        assert_eq!(run.results, results());
+
+        // Tags come from the results unless given
+        assert_eq!(
+            run.tags.git_sha.as_deref(),
+            Some("0a9d2a3f00000000000000000000000000000000")
+        );
+        assert_eq!(run.tags.git_branch.as_deref(), Some("main"));
+        assert_eq!(
+            run.tags.config_hash,
+            Some(config_hash(
+                &json!({"prefetch_rings": 2, "l1_cache_enabled": true})
+            ))
+        );
+
+        let tags = RunTags {
+            git_sha: Some("d706353".to_string()),
+            git_branch: None,
+            config_hash: Some("tuned".to_string()),
+        };
+        let run = NewLoadTestRun::from_results(results(), tags).unwrap();
+        assert_eq!(run.tags.git_sha.as_deref(), Some("d706353"));
+        assert_eq!(run.tags.git_branch.as_deref(), Some("main"));
+        assert_eq!(run.tags.config_hash.as_deref(), Some("tuned"));
+    }
+
+    #[test]
+    fn test_load_test_run_without_tags_or_fields() {
+        let mut untagged = results();
+        let object = untagged.as_object_mut().unwrap();
+        object.remove("git_info");
+        object.remove("system_config");
+        let run = NewLoadTestRun::from_results(untagged, RunTags::default()).unwrap();
+        assert_eq!(run.tags, RunTags::default());
+
+        assert!(NewLoadTestRun::from_results(json!({"files": []}), RunTags::default()).is_err());
+    }
+
+    #[test]
+    fn test_criterion_from_estimates() {
+        let estimate = |v: f64| json!({"point_estimate": v, "standard_error": 1.0});
+        let estimates = json!({
+            "mean": estimate(1500.0),
+            "median": estimate(1450.0),
+            "std_dev": estimate(42.0),
+            "slope": null
+        });
+        let result = NewCriterionResult::from_estimates("render/png_256", estimates.clone())
+            .expect("Missing estimates");
+        assert_eq!(result.benchmark, "render/png_256");
+        assert_eq!(
+            (result.mean_ns, result.median_ns, result.std_dev_ns),
+            (1500.0, 1450.0, 42.0)
+        );
+        assert_eq!(result.estimates, estimates);
+
+        assert!(NewCriterionResult::from_estimates("x", json!({"mean": estimate(1.0)})).is_none());
+    }
+
+    #[test]
+    fn test_criterion_change_pct() {
+        let mut result = CriterionResult {
+            benchmark: "render/png_256".to_string(),
+            recorded_at: Utc::now(),
+            tags: RunTags::default(),
+            mean_ns: 1100.0,
+            median_ns: 1100.0,
+            std_dev_ns: 10.0,
+            previous_mean_ns: Some(1000.0),
+            estimates: json!({}),
+        };
+        assert!((result.change_pct().unwrap() - 10.0).abs() < 1e-9);
+
+        result.previous_mean_ns = None;
+        assert_eq!(result.change_pct(), None);
+    }
+}
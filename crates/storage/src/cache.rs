@@ -5,11 +5,20 @@ use redis::{aio::MultiplexedConnection, AsyncCommands, Client};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
-use wms_common::{BoundingBox, CrsCode, WmsError, WmsResult};
+use wms_common::tile::web_mercator_tile_matrix_set;
+use wms_common::{BoundingBox, CrsCode, TileCoord, WmsError, WmsResult};
+
+/// Version of the normalized cache key format, written as a `v{N}` prefix.
+///
+/// Bump it whenever normalization changes. Keys written under another
+/// version (or before normalization, with no prefix) then never collide
+/// with new ones, so no flush is needed: old entries expire with their TTL.
+pub const CACHE_KEY_VERSION: u32 = 1;
 
 /// Redis tile cache client.
 pub struct TileCache {
-    conn: MultiplexedConnection,
+    /// None for a disabled cache (see [`TileCache::disabled`])
+    conn: Option<MultiplexedConnection>,
     default_ttl: Duration,
 }
 
@@ -29,17 +38,31 @@ impl TileCache {
             .map_err(|e| WmsError::CacheError(format!("Redis connection failed: {}", e)))?;
 
         Ok(Self {
-            conn,
+            conn: Some(conn),
             default_ttl: Duration::from_secs(ttl_secs),
         })
     }
 
+    /// A cache that stores nothing: reads miss, writes and deletes succeed.
+    ///
+    /// Used by offline mode, where there is no Redis.
+    #[cfg(feature = "offline")]
+    pub fn disabled() -> Self {
+        Self {
+            conn: None,
+            default_ttl: Duration::ZERO,
+        }
+    }
+
     /// Get a cached tile.
     pub async fn get(&mut self, key: &CacheKey) -> WmsResult<Option<Bytes>> {
         let key_str = key.to_string();
 
-        let result: Option<Vec<u8>> = self
-            .conn
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(None);
+        };
+
+        let result: Option<Vec<u8>> = conn
             .get(&key_str)
             .await
             .map_err(|e| WmsError::CacheError(format!("Cache get failed: {}", e)))?;
@@ -56,9 +79,11 @@ impl TileCache {
     ) -> WmsResult<()> {
         let key_str = key.to_string();
         let ttl = ttl.unwrap_or(self.default_ttl);
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(());
+        };
 
-        self.conn
-            .set_ex::<_, _, ()>(&key_str, data, ttl.as_secs())
+        conn.set_ex::<_, _, ()>(&key_str, data, ttl.as_secs())
             .await
             .map_err(|e| WmsError::CacheError(format!("Cache set failed: {}", e)))?;
 
@@ -69,8 +94,11 @@ impl TileCache {
     pub async fn exists(&mut self, key: &CacheKey) -> WmsResult<bool> {
         let key_str = key.to_string();
 
-        let exists: bool = self
-            .conn
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(false);
+        };
+
+        let exists: bool = conn
             .exists(&key_str)
             .await
             .map_err(|e| WmsError::CacheError(format!("Cache exists check failed: {}", e)))?;
@@ -81,32 +109,49 @@ impl TileCache {
     /// Delete a specific key.
     pub async fn delete(&mut self, key: &CacheKey) -> WmsResult<()> {
         let key_str = key.to_string();
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(());
+        };
 
-        self.conn
-            .del::<_, ()>(&key_str)
+        conn.del::<_, ()>(&key_str)
             .await
             .map_err(|e| WmsError::CacheError(format!("Cache delete failed: {}", e)))?;
 
         Ok(())
     }
 
-    /// Invalidate all tiles for a layer.
+    /// Invalidate all tiles for a layer, in both key formats.
     pub async fn invalidate_layer(&mut self, layer: &str) -> WmsResult<u64> {
-        let pattern = format!("wms:{}:*", layer);
-        self.delete_by_pattern(&pattern).await
+        let legacy = self.delete_by_pattern(&format!("wms:{}:*", layer)).await?;
+        let current = self
+            .delete_by_pattern(&format!("wms:v{}:{}:*", CACHE_KEY_VERSION, layer))
+            .await?;
+        Ok(legacy + current)
     }
 
-    /// Invalidate all tiles for a layer/time combination.
+    /// Invalidate all tiles for a layer/time combination, in both key formats.
     pub async fn invalidate_layer_time(&mut self, layer: &str, time: &str) -> WmsResult<u64> {
-        let pattern = format!("wms:{}:*:*:*:*:{}:*", layer, time);
-        self.delete_by_pattern(&pattern).await
+        let legacy = self
+            .delete_by_pattern(&format!("wms:{}:*:*:*:*:{}:*", layer, time))
+            .await?;
+        let current = self
+            .delete_by_pattern(&format!(
+                "wms:v{}:{}:*:*:*:*:{}:*",
+                CACHE_KEY_VERSION, layer, time
+            ))
+            .await?;
+        Ok(legacy + current)
     }
 
     /// Get keys matching a pattern.
     pub async fn keys(&mut self, pattern: &str) -> WmsResult<Vec<String>> {
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(Vec::new());
+        };
+
         let keys: Vec<String> = redis::cmd("KEYS")
             .arg(pattern)
-            .query_async(&mut self.conn)
+            .query_async(conn)
             .await
             .map_err(|e| WmsError::CacheError(format!("Pattern search failed: {}", e)))?;
 
@@ -122,10 +167,12 @@ impl TileCache {
         }
 
         let count = keys.len() as u64;
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(0);
+        };
 
         for key in keys {
-            let _: () = self
-                .conn
+            let _: () = conn
                 .del(&key)
                 .await
                 .map_err(|e| WmsError::CacheError(format!("Delete failed: {}", e)))?;
@@ -136,9 +183,16 @@ impl TileCache {
 
     /// Get cache statistics.
     pub async fn stats(&mut self) -> WmsResult<CacheStats> {
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(CacheStats {
+                key_count: 0,
+                memory_used: 0,
+            });
+        };
+
         let info: String = redis::cmd("INFO")
             .arg("memory")
-            .query_async(&mut self.conn)
+            .query_async(conn)
             .await
             .map_err(|e| WmsError::CacheError(format!("Info failed: {}", e)))?;
 
@@ -153,7 +207,7 @@ impl TileCache {
         }
 
         let db_size: u64 = redis::cmd("DBSIZE")
-            .query_async(&mut self.conn)
+            .query_async(conn)
             .await
             .map_err(|e| WmsError::CacheError(format!("DBSIZE failed: {}", e)))?;
 
@@ -175,6 +229,12 @@ pub struct CacheKey {
     pub height: u32,
     pub time: Option<String>,
     pub format: String,
+    /// Tile in the CRS's tile grid, for tile requests (replaces `bbox`)
+    #[serde(default)]
+    pub tile: Option<TileCoord>,
+    /// Key format version; 0 for unnormalized keys (see [`CACHE_KEY_VERSION`])
+    #[serde(default)]
+    pub version: u32,
 }
 
 impl CacheKey {
@@ -197,22 +257,151 @@ impl CacheKey {
             height,
             time,
             format: format.into(),
+            tile: None,
+            version: 0,
+        }
+    }
+
+    /// Key for a 256x256 tile of the CRS's tile grid.
+    pub fn for_tile(
+        layer: impl Into<String>,
+        style: impl Into<String>,
+        crs: CrsCode,
+        coord: TileCoord,
+        time: Option<String>,
+        format: impl Into<String>,
+    ) -> Self {
+        // Unnormalized tile keys have always carried z/x/y in the bbox
+        let bbox = BoundingBox::new(coord.x as f64, coord.y as f64, coord.z as f64, 0.0);
+        Self {
+            tile: Some(coord),
+            ..Self::new(layer, style, crs, bbox, 256, 256, time, format)
+        }
+    }
+
+    /// Canonical form of this key.
+    ///
+    /// Requests that render the same image get the same key:
+    /// - EPSG:3857 bboxes that line up with a WebMercatorQuad tile become
+    ///   that tile, so GetMap and GetTile requests share entries
+    /// - other bboxes are rounded to a fraction of a pixel
+    /// - formats drop the `image/` prefix and are lowercased (`jpg` is `jpeg`)
+    /// - an empty style is `default`, an empty or `current` time is none
+    ///
+    /// Style aliases are resolved by the caller, which knows the layer's
+    /// style policy. Returns the key unchanged when normalization is off.
+    pub fn normalized(&self, config: &KeyNormalization) -> Self {
+        if !config.enabled {
+            return self.clone();
+        }
+
+        let mut key = self.clone();
+        key.version = CACHE_KEY_VERSION;
+        key.layer = key.layer.trim().to_string();
+
+        key.style = match key.style.trim() {
+            "" => "default".to_string(),
+            style => style.to_string(),
+        };
+
+        let format = key.format.trim().to_lowercase();
+        key.format = match format.strip_prefix("image/").unwrap_or(&format) {
+            "jpg" => "jpeg".to_string(),
+            other => other.to_string(),
+        };
+
+        key.time = key
+            .time
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty() && !t.eq_ignore_ascii_case("current"))
+            .map(str::to_string);
+
+        if key.tile.is_none() {
+            if key.crs == CrsCode::Epsg3857 {
+                key.tile = web_mercator_tile_matrix_set().snap_bbox(
+                    &key.bbox,
+                    key.width,
+                    key.height,
+                    config.bbox_tolerance,
+                );
+            }
+            if key.tile.is_none() {
+                key.bbox = round_bbox(&key.bbox, key.width, config.bbox_tolerance);
+            }
+        }
+
+        key
+    }
+}
+
+/// Round bbox coordinates to a power of ten no larger than `tolerance` pixels.
+fn round_bbox(bbox: &BoundingBox, width: u32, tolerance: f64) -> BoundingBox {
+    let step = tolerance * bbox.width().abs() / width.max(1) as f64;
+    if !step.is_finite() || step <= 0.0 {
+        return *bbox;
+    }
+    let step = 10f64.powf(step.log10().floor());
+    let round = |v: f64| (v / step).round() * step;
+    BoundingBox::new(
+        round(bbox.min_x),
+        round(bbox.min_y),
+        round(bbox.max_x),
+        round(bbox.max_y),
+    )
+}
+
+/// How [`CacheKey::normalized`] canonicalizes keys.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyNormalization {
+    /// Normalize keys; when off, keys are used exactly as built
+    pub enabled: bool,
+    /// How far (in pixels) a bbox edge may be off to count as the same request
+    pub bbox_tolerance: f64,
+}
+
+impl Default for KeyNormalization {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bbox_tolerance: 0.01,
         }
     }
 }
 
 impl std::fmt::Display for CacheKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = self.time.as_deref().unwrap_or("current");
+        if self.version == 0 {
+            return write!(
+                f,
+                "wms:{}:{}:{}:{}:{}x{}:{}:{}",
+                self.layer,
+                self.style,
+                self.crs,
+                self.bbox.cache_key(),
+                self.width,
+                self.height,
+                time,
+                self.format
+            );
+        }
+
+        let extent = match &self.tile {
+            Some(tile) => format!("z{}_{}_{}", tile.z, tile.x, tile.y),
+            None => self.bbox.cache_key(),
+        };
         write!(
             f,
-            "wms:{}:{}:{}:{}:{}x{}:{}:{}",
+            "wms:v{}:{}:{}:{}:{}:{}x{}:{}:{}",
+            self.version,
             self.layer,
             self.style,
             self.crs,
-            self.bbox.cache_key(),
+            extent,
             self.width,
             self.height,
-            self.time.as_deref().unwrap_or("current"),
+            time,
             self.format
         )
     }
@@ -246,4 +435,83 @@ mod tests {
         assert!(key_str.starts_with("wms:gfs:temperature_2m:gradient:EPSG:3857"));
         assert!(key_str.contains("512x512"));
     }
+
+    #[test]
+    fn test_tile_key_keeps_legacy_format() {
+        let coord = TileCoord::new(4, 3, 5);
+        let key = CacheKey::for_tile("gfs_TMP", "default", CrsCode::Epsg3857, coord, None, "png");
+        assert_eq!(
+            key.to_string(),
+            "wms:gfs_TMP:default:EPSG:3857:3.000000_5.000000_4.000000_0.000000:256x256:current:png"
+        );
+
+        let key = key.normalized(&KeyNormalization::default());
+        assert_eq!(
+            key.to_string(),
+            "wms:v1:gfs_TMP:default:EPSG:3857:z4_3_5:256x256:current:png"
+        );
+    }
+
+    #[test]
+    fn test_normalized_keys_match() {
+        let config = KeyNormalization::default();
+        let tile = web_mercator_tile_matrix_set()
+            .tile_bbox(&TileCoord::new(6, 14, 24))
+            .unwrap();
+
+        let getmap = CacheKey::new(
+            " gfs_TMP",
+            "",
+            CrsCode::Epsg3857,
+            BoundingBox::new(
+                (tile.min_x * 1000.0).round() / 1000.0,
+                tile.min_y,
+                tile.max_x,
+                (tile.max_y * 1000.0).round() / 1000.0,
+            ),
+            256,
+            256,
+            Some("current".to_string()),
+            "image/PNG",
+        );
+        let gettile = CacheKey::for_tile(
+            "gfs_TMP",
+            "default",
+            CrsCode::Epsg3857,
+            TileCoord::new(6, 14, 24),
+            None,
+            "png",
+        );
+        assert_eq!(
+            getmap.normalized(&config).to_string(),
+            gettile.normalized(&config).to_string()
+        );
+
+        // Other bboxes are rounded to a fraction of a pixel (0.0001 degrees here)
+        let a = CacheKey::new(
+            "gfs_TMP",
+            "default",
+            CrsCode::Epsg4326,
+            BoundingBox::new(-100.0, 30.0, -90.0, 40.0),
+            1000,
+            1000,
+            None,
+            "image/jpg",
+        );
+        let mut b = a.clone();
+        b.bbox = BoundingBox::new(-100.0000001, 30.0000002, -89.9999999, 40.0);
+        b.format = "jpeg".to_string();
+        assert_eq!(
+            a.normalized(&config).to_string(),
+            b.normalized(&config).to_string()
+        );
+        assert!(a.normalized(&config).to_string().ends_with(":jpeg"));
+
+        // Disabled normalization leaves keys untouched
+        let off = KeyNormalization {
+            enabled: false,
+            ..config
+        };
+        assert_eq!(a.normalized(&off).to_string(), a.to_string());
+    }
 }
@@ -5,11 +5,33 @@ use serde::{Deserialize, Serialize};
 use sqlx::{postgres::PgPoolOptions, FromRow, PgPool};
 use uuid::Uuid;
 
+#[cfg(feature = "offline")]
+use crate::catalog_memory::MemoryCatalog;
+use crate::catalog_search::{
+    escape_like, level_type, CatalogSearchHit, CatalogSearchQuery, CatalogSearchResults,
+};
+use crate::level::canonicalize_level;
 use wms_common::{BoundingBox, LayerId, WmsError, WmsResult};
 
 /// Database connection pool and catalog operations.
 pub struct Catalog {
-    pool: PgPool,
+    backend: Backend,
+    /// Let latest-run queries use runs that are not yet published
+    include_unpublished_runs: bool,
+}
+
+/// Excludes datasets of runs still being ingested from latest-run queries
+/// (see [`Catalog::update_run_publication`]). Runs without a `model_runs`
+/// row, such as observations, are always included.
+const PUBLISHED_RUN_FILTER: &str = "AND NOT EXISTS (SELECT 1 FROM model_runs r \
+     WHERE r.model = datasets.model AND r.reference_time = datasets.reference_time \
+     AND r.published_at IS NULL)";
+
+enum Backend {
+    Postgres(PgPool),
+    /// Fixture datasets for offline mode (see [`Catalog::in_memory`])
+    #[cfg(feature = "offline")]
+    Memory(MemoryCatalog),
 }
 
 impl Catalog {
@@ -29,17 +51,64 @@ impl Catalog {
             .await
             .map_err(|e| WmsError::DatabaseError(format!("Connection failed: {}", e)))?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            backend: Backend::Postgres(pool),
+            include_unpublished_runs: false,
+        })
+    }
+
+    /// Let latest-run queries use runs whose forecast hours are not all
+    /// ingested yet, restoring the behaviour before runs were published.
+    pub fn with_unpublished_runs(mut self, include: bool) -> Self {
+        self.include_unpublished_runs = include;
+        self
+    }
+
+    /// SQL condition restricting latest-run queries to published runs.
+    fn published_run_filter(&self) -> &'static str {
+        if self.include_unpublished_runs {
+            ""
+        } else {
+            PUBLISHED_RUN_FILTER
+        }
+    }
+
+    /// Create an empty catalog held in memory, for offline mode and tests.
+    ///
+    /// Supports registering datasets and the lookups used to render and
+    /// advertise layers; other operations return a database error.
+    #[cfg(feature = "offline")]
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory(MemoryCatalog::default()),
+            include_unpublished_runs: false,
+        }
+    }
+
+    /// The connection pool, or an error for an in-memory catalog.
+    pub(crate) fn pool(&self) -> WmsResult<&PgPool> {
+        match &self.backend {
+            Backend::Postgres(pool) => Ok(pool),
+            #[cfg(feature = "offline")]
+            Backend::Memory(_) => Err(WmsError::DatabaseError(
+                "Not supported by the in-memory catalog".to_string(),
+            )),
+        }
     }
 
     /// Run database migrations.
     pub async fn migrate(&self) -> WmsResult<()> {
+        #[cfg(feature = "offline")]
+        if matches!(self.backend, Backend::Memory(_)) {
+            return Ok(());
+        }
+
         // Split SQL statements and execute them individually
         for statement in SCHEMA_SQL.split(';') {
             let trimmed = statement.trim();
             if !trimmed.is_empty() {
                 sqlx::query(trimmed)
-                    .execute(&self.pool)
+                    .execute(self.pool()?)
                     .await
                     .map_err(|e| WmsError::DatabaseError(format!("Migration failed: {}", e)))?;
             }
@@ -49,7 +118,20 @@ impl Catalog {
     }
 
     /// Register a new ingested dataset.
+    ///
+    /// The level is stored under its canonical name (see [`crate::level`]).
     pub async fn register_dataset(&self, entry: &CatalogEntry) -> WmsResult<Uuid> {
+        let entry = &CatalogEntry {
+            level: canonicalize_level(&entry.level),
+            ..entry.clone()
+        };
+
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            memory.insert(entry);
+            return Ok(Uuid::new_v4());
+        }
+
         let id = Uuid::new_v4();
 
         sqlx::query(
@@ -58,20 +140,23 @@ impl Catalog {
                 id, model, parameter, level,
                 reference_time, forecast_hour, valid_time,
                 bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y,
-                storage_path, file_size, ingested_at, status, zarr_metadata
+                storage_path, file_size, ingested_at, status, zarr_metadata,
+                ensemble_member, source_lineage
             ) VALUES (
                 $1, $2, $3, $4,
                 $5, $6, $7,
                 $8, $9, $10, $11,
-                $12, $13, $14, $15, $16
+                $12, $13, $14, $15, $16,
+                $17, $18
             )
-            ON CONFLICT (model, parameter, level, reference_time, forecast_hour)
+            ON CONFLICT (model, parameter, level, reference_time, forecast_hour, ensemble_member)
             DO UPDATE SET
                 storage_path = EXCLUDED.storage_path,
                 file_size = EXCLUDED.file_size,
                 ingested_at = EXCLUDED.ingested_at,
                 status = EXCLUDED.status,
-                zarr_metadata = EXCLUDED.zarr_metadata
+                zarr_metadata = EXCLUDED.zarr_metadata,
+                source_lineage = EXCLUDED.source_lineage
             "#,
         )
         .bind(id)
@@ -90,7 +175,14 @@ impl Catalog {
         .bind(Utc::now())
         .bind("available")
         .bind(&entry.zarr_metadata)
-        .execute(&self.pool)
+        .bind(entry.ensemble_member.as_deref().unwrap_or(""))
+        .bind(
+            entry
+                .lineage
+                .as_ref()
+                .and_then(|l| serde_json::to_value(l).ok()),
+        )
+        .execute(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Insert failed: {}", e)))?;
 
@@ -103,7 +195,7 @@ impl Catalog {
         let mut _sql = String::from(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets WHERE status = 'available'",
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets WHERE status = 'available'",
         );
 
         let mut _params: Vec<String> = Vec::new();
@@ -125,16 +217,57 @@ impl Catalog {
         let rows = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets WHERE status = 'available' \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets WHERE status = 'available' \
              ORDER BY valid_time DESC LIMIT 100",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(rows.into_iter().map(|r| r.into()).collect())
     }
 
+    /// Search available datasets, grouped by (model, parameter, level).
+    ///
+    /// The text matches model, parameter and level names with ILIKE, or any
+    /// of `query.matching_layers`. Filtering by model and level type, facets
+    /// and paging are done by [`CatalogSearchResults::build`].
+    pub async fn search(&self, query: &CatalogSearchQuery) -> WmsResult<CatalogSearchResults> {
+        let pattern = query
+            .text
+            .as_deref()
+            .map(|text| format!("%{}%", escape_like(text)));
+        let layer_keys: Vec<String> = query
+            .matching_layers
+            .iter()
+            .map(|(model, parameter)| format!("{}:{}", model, parameter))
+            .collect();
+        let rows = sqlx::query_as::<_, SearchRow>(
+            "SELECT model, parameter, level, \
+             COUNT(*) AS dataset_count, \
+             COUNT(DISTINCT reference_time) AS run_count, \
+             MIN(valid_time) AS earliest_valid_time, \
+             MAX(valid_time) AS latest_valid_time, \
+             MAX(reference_time) AS latest_run \
+             FROM datasets WHERE status = 'available' \
+             AND ($1::text IS NULL OR model ILIKE $1 OR parameter ILIKE $1 OR level ILIKE $1 \
+                  OR model || ':' || parameter = ANY($2)) \
+             AND ($3::timestamptz IS NULL OR valid_time >= $3) \
+             AND ($4::timestamptz IS NULL OR valid_time <= $4) \
+             GROUP BY model, parameter, level",
+        )
+        .bind(pattern)
+        .bind(&layer_keys)
+        .bind(query.start)
+        .bind(query.end)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        let series = rows.into_iter().map(|r| r.into()).collect();
+        Ok(CatalogSearchResults::build(series, query))
+    }
+
     /// Get aggregated statistics per model.
     /// Returns (model_id, dataset_count, parameter_count, last_ingest_time, parameters_list)
     pub async fn get_model_stats(&self) -> WmsResult<Vec<ModelStats>> {
@@ -153,7 +286,7 @@ impl Catalog {
              FROM datasets WHERE status = 'available' \
              GROUP BY model ORDER BY model",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -167,7 +300,7 @@ impl Catalog {
         let param_rows = sqlx::query_as::<_, ParamRow>(
             "SELECT DISTINCT model, parameter FROM datasets WHERE status = 'available' ORDER BY model, parameter",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -190,24 +323,31 @@ impl Catalog {
             .collect())
     }
 
-    /// Get the most recent dataset for a layer.
+    /// Get the most recent dataset for a layer, from published runs.
     pub async fn get_latest(
         &self,
         model: &str,
         parameter: &str,
     ) -> WmsResult<Option<CatalogEntry>> {
-        let row = sqlx::query_as::<_, DatasetRow>(
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_latest(model, parameter));
+        }
+
+        let sql = format!(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
-             WHERE model = $1 AND parameter = $2 AND status = 'available' \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND status = 'available' {} \
              ORDER BY valid_time DESC LIMIT 1",
-        )
-        .bind(model)
-        .bind(parameter)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+            self.published_run_filter()
+        );
+        let row = sqlx::query_as::<_, DatasetRow>(&sql)
+            .bind(model)
+            .bind(parameter)
+            .fetch_optional(self.pool()?)
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(row.map(|r| r.into()))
     }
@@ -219,17 +359,22 @@ impl Catalog {
         parameter: &str,
         valid_time: DateTime<Utc>,
     ) -> WmsResult<Option<CatalogEntry>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_by_time(model, parameter, valid_time, None));
+        }
+
         let row = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
              WHERE model = $1 AND parameter = $2 AND status = 'available' \
              ORDER BY ABS(EXTRACT(EPOCH FROM (valid_time - $3))) ASC LIMIT 1",
         )
         .bind(model)
         .bind(parameter)
         .bind(valid_time)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -244,10 +389,16 @@ impl Catalog {
         valid_time: DateTime<Utc>,
         level: &str,
     ) -> WmsResult<Option<CatalogEntry>> {
+        let level = &canonicalize_level(level);
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_by_time(model, parameter, valid_time, Some(level)));
+        }
+
         let row = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
              WHERE model = $1 AND parameter = $2 AND level = $4 AND status = 'available' \
              ORDER BY ABS(EXTRACT(EPOCH FROM (valid_time - $3))) ASC LIMIT 1",
         )
@@ -255,33 +406,40 @@ impl Catalog {
         .bind(parameter)
         .bind(valid_time)
         .bind(level)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(row.map(|r| r.into()))
     }
 
-    /// Find dataset by forecast hour.
+    /// Find dataset by forecast hour, from the latest published run.
     pub async fn find_by_forecast_hour(
         &self,
         model: &str,
         parameter: &str,
         forecast_hour: u32,
     ) -> WmsResult<Option<CatalogEntry>> {
-        let row = sqlx::query_as::<_, DatasetRow>(
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_by_forecast_hour(model, parameter, forecast_hour, None));
+        }
+
+        let sql = format!(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
-             WHERE model = $1 AND parameter = $2 AND forecast_hour = $3 AND status = 'available' \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND forecast_hour = $3 AND status = 'available' {} \
              ORDER BY reference_time DESC LIMIT 1",
-        )
-        .bind(model)
-        .bind(parameter)
-        .bind(forecast_hour as i32)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+            self.published_run_filter()
+        );
+        let row = sqlx::query_as::<_, DatasetRow>(&sql)
+            .bind(model)
+            .bind(parameter)
+            .bind(forecast_hour as i32)
+            .fetch_optional(self.pool()?)
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(row.map(|r| r.into()))
     }
@@ -292,6 +450,11 @@ impl Catalog {
         model: &str,
         parameter: &str,
     ) -> WmsResult<Vec<DateTime<Utc>>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_available_times(model, parameter));
+        }
+
         let rows = sqlx::query_scalar::<_, DateTime<Utc>>(
             "SELECT DISTINCT valid_time FROM datasets \
              WHERE model = $1 AND parameter = $2 AND status = 'available' \
@@ -299,7 +462,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -308,10 +471,15 @@ impl Catalog {
 
     /// Get list of available models.
     pub async fn list_models(&self) -> WmsResult<Vec<String>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.list_models());
+        }
+
         let rows = sqlx::query_scalar::<_, String>(
             "SELECT DISTINCT model FROM datasets WHERE status = 'available' ORDER BY model",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -320,11 +488,16 @@ impl Catalog {
 
     /// Get list of parameters for a model.
     pub async fn list_parameters(&self, model: &str) -> WmsResult<Vec<String>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.list_parameters(model));
+        }
+
         let rows = sqlx::query_scalar::<_, String>(
             "SELECT DISTINCT parameter FROM datasets WHERE model = $1 AND status = 'available' ORDER BY parameter"
         )
         .bind(model)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -337,12 +510,12 @@ impl Catalog {
         let rows = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
              WHERE ingested_at > $1 AND status = 'available' \
              ORDER BY ingested_at DESC LIMIT 50",
         )
         .bind(cutoff)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -355,7 +528,7 @@ impl Catalog {
             "UPDATE datasets SET status = 'expired' WHERE valid_time < $1 AND status = 'available'",
         )
         .bind(older_than)
-        .execute(&self.pool)
+        .execute(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Update failed: {}", e)))?;
 
@@ -374,7 +547,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(older_than)
-        .execute(&self.pool)
+        .execute(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Update failed: {}", e)))?;
 
@@ -420,7 +593,7 @@ impl Catalog {
         }
 
         let result = query_builder
-            .execute(&self.pool)
+            .execute(self.pool()?)
             .await
             .map_err(|e| WmsError::DatabaseError(format!("Update failed: {}", e)))?;
 
@@ -443,13 +616,51 @@ impl Catalog {
              ORDER BY reference_time DESC",
         )
         .bind(model)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(rows)
     }
 
+    /// Record how complete a forecast run is, publishing it once all
+    /// `expected_forecast_hours` have been ingested.
+    ///
+    /// Until published, latest-run queries keep serving the previous run so
+    /// animations never mix forecast hours from two runs. A published run
+    /// stays published. Returns whether the run is published.
+    pub async fn update_run_publication(
+        &self,
+        model: &str,
+        reference_time: DateTime<Utc>,
+        expected_forecast_hours: u32,
+    ) -> WmsResult<bool> {
+        #[cfg(feature = "offline")]
+        if matches!(self.backend, Backend::Memory(_)) {
+            return Ok(true);
+        }
+
+        let published = sqlx::query_scalar::<_, bool>(
+            "INSERT INTO model_runs (model, reference_time, expected_forecast_hours, published_at) \
+             SELECT $1, $2, $3, \
+                    CASE WHEN COUNT(DISTINCT forecast_hour) >= $3 THEN NOW() END \
+             FROM datasets \
+             WHERE model = $1 AND reference_time = $2 AND status = 'available' \
+             ON CONFLICT (model, reference_time) DO UPDATE SET \
+                 expected_forecast_hours = EXCLUDED.expected_forecast_hours, \
+                 published_at = COALESCE(model_runs.published_at, EXCLUDED.published_at) \
+             RETURNING published_at IS NOT NULL",
+        )
+        .bind(model)
+        .bind(reference_time)
+        .bind(expected_forecast_hours as i32)
+        .fetch_one(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(published)
+    }
+
     /// Get the count of distinct forecast hours for a specific run.
     ///
     /// This is used to determine if a run is "complete" by comparing against expected forecast hours.
@@ -465,7 +676,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(reference_time)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -477,7 +688,7 @@ impl Catalog {
         let paths = sqlx::query_scalar::<_, String>(
             "SELECT DISTINCT storage_path FROM datasets WHERE status = 'expired'",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -488,7 +699,7 @@ impl Catalog {
     /// Call this AFTER deleting files from object storage.
     pub async fn delete_expired(&self) -> WmsResult<u64> {
         let result = sqlx::query("DELETE FROM datasets WHERE status = 'expired'")
-            .execute(&self.pool)
+            .execute(self.pool()?)
             .await
             .map_err(|e| WmsError::DatabaseError(format!("Delete failed: {}", e)))?;
 
@@ -499,7 +710,7 @@ impl Catalog {
     pub async fn count_expired(&self) -> WmsResult<i64> {
         let count =
             sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM datasets WHERE status = 'expired'")
-                .fetch_one(&self.pool)
+                .fetch_one(self.pool()?)
                 .await
                 .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -519,7 +730,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(older_than)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -536,7 +747,7 @@ impl Catalog {
             "SELECT MIN(valid_time) FROM datasets WHERE model = $1 AND status = 'available'",
         )
         .bind(model)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -549,6 +760,11 @@ impl Catalog {
         model: &str,
         parameter: &str,
     ) -> WmsResult<Vec<DateTime<Utc>>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_available_runs(model, parameter));
+        }
+
         let rows = sqlx::query_scalar::<_, DateTime<Utc>>(
             "SELECT DISTINCT reference_time FROM datasets \
              WHERE model = $1 AND parameter = $2 AND status = 'available' \
@@ -556,19 +772,112 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(rows)
     }
 
+    /// Find datasets from every run that covers a valid time.
+    ///
+    /// Returns one entry per (run, level), newest run first. Pass `level` to
+    /// restrict to a single vertical level.
+    pub async fn find_runs_for_valid_time(
+        &self,
+        model: &str,
+        parameter: &str,
+        valid_time: DateTime<Utc>,
+        level: Option<&str>,
+    ) -> WmsResult<Vec<CatalogEntry>> {
+        let level = level.map(canonicalize_level);
+        let level = level.as_deref();
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_runs_for_valid_time(model, parameter, valid_time, level));
+        }
+
+        let rows = sqlx::query_as::<_, DatasetRow>(
+            "SELECT model, parameter, level, reference_time, forecast_hour, \
+             bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND valid_time = $3 \
+             AND ($4::text IS NULL OR level = $4) AND status = 'available' \
+             ORDER BY reference_time DESC, level ASC",
+        )
+        .bind(model)
+        .bind(parameter)
+        .bind(valid_time)
+        .bind(level)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Find datasets whose valid time falls in `(start, end]`, oldest first.
+    ///
+    /// Returns one entry per valid time; when several runs or levels share a
+    /// valid time, the newest run and first level win. Pass `reference_time`
+    /// to restrict to a single forecast run and `level` to a single vertical
+    /// level.
+    pub async fn find_in_valid_time_range(
+        &self,
+        model: &str,
+        parameter: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        reference_time: Option<DateTime<Utc>>,
+        level: Option<&str>,
+    ) -> WmsResult<Vec<CatalogEntry>> {
+        let level = level.map(canonicalize_level);
+        let level = level.as_deref();
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_in_valid_time_range(
+                model,
+                parameter,
+                start,
+                end,
+                reference_time,
+                level,
+            ));
+        }
+
+        let rows = sqlx::query_as::<_, DatasetRow>(
+            "SELECT DISTINCT ON (valid_time) model, parameter, level, reference_time, forecast_hour, \
+             bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND valid_time > $3 AND valid_time <= $4 \
+             AND ($5::timestamptz IS NULL OR reference_time = $5) \
+             AND ($6::text IS NULL OR level = $6) AND status = 'available' \
+             ORDER BY valid_time ASC, reference_time DESC, level ASC",
+        )
+        .bind(model)
+        .bind(parameter)
+        .bind(start)
+        .bind(end)
+        .bind(reference_time)
+        .bind(level)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
     /// Get available forecast hours for a model/parameter.
     pub async fn get_available_forecast_hours(
         &self,
         model: &str,
         parameter: &str,
     ) -> WmsResult<Vec<i32>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_forecast_hours(model, parameter, None, None));
+        }
+
         let rows = sqlx::query_scalar::<_, i32>(
             "SELECT DISTINCT forecast_hour FROM datasets \
              WHERE model = $1 AND parameter = $2 AND status = 'available' \
@@ -576,7 +885,41 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(rows)
+    }
+
+    /// Get the forecast hours of a single run for a model/parameter.
+    ///
+    /// Pass `level` to restrict to a single vertical level.
+    pub async fn get_run_forecast_hours(
+        &self,
+        model: &str,
+        parameter: &str,
+        reference_time: DateTime<Utc>,
+        level: Option<&str>,
+    ) -> WmsResult<Vec<i32>> {
+        let level = level.map(canonicalize_level);
+        let level = level.as_deref();
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_forecast_hours(model, parameter, Some(reference_time), level));
+        }
+
+        let rows = sqlx::query_scalar::<_, i32>(
+            "SELECT DISTINCT forecast_hour FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND reference_time = $3 \
+             AND ($4::text IS NULL OR level = $4) AND status = 'available' \
+             ORDER BY forecast_hour ASC",
+        )
+        .bind(model)
+        .bind(parameter)
+        .bind(reference_time)
+        .bind(level)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -589,6 +932,11 @@ impl Catalog {
         model: &str,
         parameter: &str,
     ) -> WmsResult<Vec<String>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_available_levels(model, parameter));
+        }
+
         let rows = sqlx::query_scalar::<_, String>(
             "SELECT DISTINCT level FROM datasets \
              WHERE model = $1 AND parameter = $2 AND status = 'available' \
@@ -596,14 +944,14 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(rows)
     }
 
-    /// Find dataset by forecast hour and level.
+    /// Find dataset by forecast hour and level, from the latest published run.
     pub async fn find_by_forecast_hour_and_level(
         &self,
         model: &str,
@@ -611,91 +959,242 @@ impl Catalog {
         forecast_hour: u32,
         level: &str,
     ) -> WmsResult<Option<CatalogEntry>> {
-        let row = sqlx::query_as::<_, DatasetRow>(
+        let level = &canonicalize_level(level);
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_by_forecast_hour(model, parameter, forecast_hour, Some(level)));
+        }
+
+        let sql = format!(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
-             WHERE model = $1 AND parameter = $2 AND forecast_hour = $3 AND level = $4 AND status = 'available' \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND forecast_hour = $3 AND level = $4 AND status = 'available' {} \
              ORDER BY reference_time DESC LIMIT 1",
+            self.published_run_filter()
+        );
+        let row = sqlx::query_as::<_, DatasetRow>(&sql)
+            .bind(model)
+            .bind(parameter)
+            .bind(forecast_hour as i32)
+            .bind(level)
+            .fetch_optional(self.pool()?)
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(row.map(|r| r.into()))
+    }
+
+    /// Find dataset by run and forecast hour, optionally at a specific level.
+    pub async fn find_by_run_and_forecast_hour(
+        &self,
+        model: &str,
+        parameter: &str,
+        reference_time: DateTime<Utc>,
+        forecast_hour: u32,
+        level: Option<&str>,
+    ) -> WmsResult<Option<CatalogEntry>> {
+        let level = level.map(canonicalize_level);
+        let level = level.as_deref();
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_by_run_and_forecast_hour(
+                model,
+                parameter,
+                reference_time,
+                forecast_hour,
+                level,
+            ));
+        }
+
+        let row = sqlx::query_as::<_, DatasetRow>(
+            "SELECT model, parameter, level, reference_time, forecast_hour, \
+             bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND reference_time = $3 AND forecast_hour = $4 \
+             AND ($5::text IS NULL OR level = $5) AND status = 'available' \
+             ORDER BY level ASC LIMIT 1",
         )
         .bind(model)
         .bind(parameter)
+        .bind(reference_time)
         .bind(forecast_hour as i32)
         .bind(level)
-        .fetch_optional(&self.pool)
+        .fetch_optional(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(row.map(|r| r.into()))
     }
 
-    /// Get the most recent dataset for a layer at a specific level.
-    pub async fn get_latest_at_level(
+    /// Find every ensemble member of a run at one forecast hour and level,
+    /// ordered by member. A deterministic dataset with the same keys (no
+    /// member) is included, first.
+    pub async fn find_ensemble_members(
         &self,
         model: &str,
         parameter: &str,
+        reference_time: DateTime<Utc>,
+        forecast_hour: u32,
         level: &str,
-    ) -> WmsResult<Option<CatalogEntry>> {
-        let row = sqlx::query_as::<_, DatasetRow>(
+    ) -> WmsResult<Vec<CatalogEntry>> {
+        let level = &canonicalize_level(level);
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_ensemble_members(
+                model,
+                parameter,
+                reference_time,
+                forecast_hour,
+                level,
+            ));
+        }
+
+        let rows = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
-             WHERE model = $1 AND parameter = $2 AND level = $3 AND status = 'available' \
-             ORDER BY valid_time DESC LIMIT 1",
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND reference_time = $3 AND forecast_hour = $4 \
+             AND level = $5 AND status = 'available' \
+             ORDER BY ensemble_member ASC",
         )
         .bind(model)
         .bind(parameter)
+        .bind(reference_time)
+        .bind(forecast_hour as i32)
         .bind(level)
-        .fetch_optional(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
-        Ok(row.map(|r| r.into()))
+        Ok(rows.into_iter().map(|r| r.into()).collect())
     }
 
-    /// Get dataset from latest run with earliest forecast hour.
-    /// This is the preferred default: most recent model run, but showing analysis/F00.
-    pub async fn get_latest_run_earliest_forecast(
+    /// Find every level of a parameter in one run at one forecast hour,
+    /// ordered by level name. Ensemble members are not included.
+    pub async fn find_run_levels(
         &self,
         model: &str,
         parameter: &str,
-    ) -> WmsResult<Option<CatalogEntry>> {
-        let row = sqlx::query_as::<_, DatasetRow>(
+        reference_time: DateTime<Utc>,
+        forecast_hour: u32,
+    ) -> WmsResult<Vec<CatalogEntry>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.find_run_levels(model, parameter, reference_time, forecast_hour));
+        }
+
+        let rows = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
-             WHERE model = $1 AND parameter = $2 AND status = 'available' \
-             ORDER BY reference_time DESC, forecast_hour ASC LIMIT 1",
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND reference_time = $3 AND forecast_hour = $4 \
+             AND ensemble_member = '' AND status = 'available' \
+             ORDER BY level ASC",
         )
         .bind(model)
         .bind(parameter)
-        .fetch_optional(&self.pool)
+        .bind(reference_time)
+        .bind(forecast_hour as i32)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get the most recent dataset for a layer at a specific level, from
+    /// published runs.
+    pub async fn get_latest_at_level(
+        &self,
+        model: &str,
+        parameter: &str,
+        level: &str,
+    ) -> WmsResult<Option<CatalogEntry>> {
+        let level = &canonicalize_level(level);
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_latest_at_level(model, parameter, level));
+        }
+
+        let sql = format!(
+            "SELECT model, parameter, level, reference_time, forecast_hour, \
+             bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND level = $3 AND status = 'available' {} \
+             ORDER BY valid_time DESC LIMIT 1",
+            self.published_run_filter()
+        );
+        let row = sqlx::query_as::<_, DatasetRow>(&sql)
+            .bind(model)
+            .bind(parameter)
+            .bind(level)
+            .fetch_optional(self.pool()?)
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
         Ok(row.map(|r| r.into()))
     }
 
-    /// Get dataset from latest run with earliest forecast hour at a specific level.
+    /// Get dataset from latest published run with earliest forecast hour.
+    /// This is the preferred default: most recent model run, but showing analysis/F00.
+    pub async fn get_latest_run_earliest_forecast(
+        &self,
+        model: &str,
+        parameter: &str,
+    ) -> WmsResult<Option<CatalogEntry>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_latest_run_earliest_forecast(model, parameter, None));
+        }
+
+        let sql = format!(
+            "SELECT model, parameter, level, reference_time, forecast_hour, \
+             bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND status = 'available' {} \
+             ORDER BY reference_time DESC, forecast_hour ASC LIMIT 1",
+            self.published_run_filter()
+        );
+        let row = sqlx::query_as::<_, DatasetRow>(&sql)
+            .bind(model)
+            .bind(parameter)
+            .fetch_optional(self.pool()?)
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(row.map(|r| r.into()))
+    }
+
+    /// Get dataset from latest published run with earliest forecast hour at a
+    /// specific level.
     pub async fn get_latest_run_earliest_forecast_at_level(
         &self,
         model: &str,
         parameter: &str,
         level: &str,
     ) -> WmsResult<Option<CatalogEntry>> {
-        let row = sqlx::query_as::<_, DatasetRow>(
+        let level = &canonicalize_level(level);
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_latest_run_earliest_forecast(model, parameter, Some(level)));
+        }
+
+        let sql = format!(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
-             WHERE model = $1 AND parameter = $2 AND level = $3 AND status = 'available' \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND parameter = $2 AND level = $3 AND status = 'available' {} \
              ORDER BY reference_time DESC, forecast_hour ASC LIMIT 1",
-        )
-        .bind(model)
-        .bind(parameter)
-        .bind(level)
-        .fetch_optional(&self.pool)
-        .await
-        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+            self.published_run_filter()
+        );
+        let row = sqlx::query_as::<_, DatasetRow>(&sql)
+            .bind(model)
+            .bind(parameter)
+            .bind(level)
+            .fetch_optional(self.pool()?)
+            .await
+            .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
         Ok(row.map(|r| r.into()))
     }
@@ -703,6 +1202,11 @@ impl Catalog {
     /// Get available runs and forecast hours for all layers of a model.
     /// Returns (runs, forecast_hours) where runs are ISO8601 strings and forecast_hours are integers.
     pub async fn get_model_dimensions(&self, model: &str) -> WmsResult<(Vec<String>, Vec<i32>)> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_model_dimensions(model));
+        }
+
         // Get distinct reference times, truncated to nearest minute to group similar ingestion times
         let runs = sqlx::query_scalar::<_, DateTime<Utc>>(
             "SELECT DISTINCT DATE_TRUNC('minute', reference_time) as ref_time FROM datasets \
@@ -710,7 +1214,7 @@ impl Catalog {
              ORDER BY ref_time DESC",
         )
         .bind(model)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -720,7 +1224,7 @@ impl Catalog {
              ORDER BY forecast_hour ASC",
         )
         .bind(model)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -736,6 +1240,13 @@ impl Catalog {
     /// Get the geographic bounding box for a model
     /// Returns the union of all dataset bounding boxes for the model
     pub async fn get_model_bbox(&self, model: &str) -> WmsResult<BoundingBox> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return memory.get_model_bbox(model).ok_or_else(|| {
+                WmsError::DatabaseError(format!("No datasets for model {}", model))
+            });
+        }
+
         let result = sqlx::query_as::<_, (f64, f64, f64, f64)>(
             "SELECT \
                 MIN(bbox_min_x) as min_x, \
@@ -746,7 +1257,7 @@ impl Catalog {
              WHERE model = $1 AND status = 'available'",
         )
         .bind(model)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -768,14 +1279,58 @@ impl Catalog {
         let rows = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
              bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-             storage_path, file_size, zarr_metadata FROM datasets \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
              WHERE model = $1 AND status = 'available' \
              ORDER BY reference_time DESC, parameter ASC \
              LIMIT $2",
         )
         .bind(model)
         .bind(limit as i64)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get every dataset of one model run (for predicting the next run's layout).
+    pub async fn get_run_entries(
+        &self,
+        model: &str,
+        reference_time: DateTime<Utc>,
+    ) -> WmsResult<Vec<CatalogEntry>> {
+        let rows = sqlx::query_as::<_, DatasetRow>(
+            "SELECT model, parameter, level, reference_time, forecast_hour, \
+             bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND reference_time = $2 AND status = 'available' \
+             ORDER BY forecast_hour ASC, parameter ASC, level ASC",
+        )
+        .bind(model)
+        .bind(reference_time)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    /// Get datasets of a model registered after `since`, oldest first.
+    pub async fn get_ingested_since(
+        &self,
+        model: &str,
+        since: DateTime<Utc>,
+    ) -> WmsResult<Vec<CatalogEntry>> {
+        let rows = sqlx::query_as::<_, DatasetRow>(
+            "SELECT model, parameter, level, reference_time, forecast_hour, \
+             bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
+             storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
+             WHERE model = $1 AND ingested_at > $2 AND status = 'available' \
+             ORDER BY ingested_at ASC",
+        )
+        .bind(model)
+        .bind(since)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -792,25 +1347,25 @@ impl Catalog {
             sqlx::query_as::<_, DatasetRow>(
                 "SELECT model, parameter, level, reference_time, forecast_hour, \
                  bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-                 storage_path, file_size, zarr_metadata FROM datasets \
+                 storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
                  WHERE model = $1 AND parameter = $2 AND status = 'available' \
                  ORDER BY reference_time DESC, forecast_hour ASC LIMIT 1",
             )
             .bind(model)
             .bind(param)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.pool()?)
             .await
             .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?
         } else {
             sqlx::query_as::<_, DatasetRow>(
                 "SELECT model, parameter, level, reference_time, forecast_hour, \
                  bbox_min_x, bbox_min_y, bbox_max_x, bbox_max_y, \
-                 storage_path, file_size, zarr_metadata FROM datasets \
+                 storage_path, file_size, zarr_metadata, ensemble_member, source_lineage FROM datasets \
                  WHERE model = $1 AND status = 'available' \
                  ORDER BY reference_time DESC, forecast_hour ASC LIMIT 1",
             )
             .bind(model)
-            .fetch_optional(&self.pool)
+            .fetch_optional(self.pool()?)
             .await
             .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?
         };
@@ -825,7 +1380,7 @@ impl Catalog {
         let paths = sqlx::query_scalar::<_, String>(
             "SELECT DISTINCT storage_path FROM datasets WHERE status = 'available'",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -858,7 +1413,7 @@ impl Catalog {
             }
 
             let result = query
-                .execute(&self.pool)
+                .execute(self.pool()?)
                 .await
                 .map_err(|e| WmsError::DatabaseError(format!("Delete failed: {}", e)))?;
 
@@ -873,7 +1428,7 @@ impl Catalog {
         let count = sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM datasets WHERE status = 'available'",
         )
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -883,7 +1438,7 @@ impl Catalog {
     /// Get total database record count (all statuses).
     pub async fn count_all(&self) -> WmsResult<i64> {
         let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM datasets")
-            .fetch_one(&self.pool)
+            .fetch_one(self.pool()?)
             .await
             .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -911,7 +1466,7 @@ impl Catalog {
              GROUP BY model, parameter \
              ORDER BY model, parameter",
         )
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -945,17 +1500,18 @@ impl Catalog {
             valid_time: chrono::DateTime<Utc>,
             storage_path: String,
             file_size: i64,
+            source_lineage: Option<serde_json::Value>,
         }
 
         let rows = sqlx::query_as::<_, DatasetRow>(
             "SELECT model, parameter, level, reference_time, forecast_hour, \
-             valid_time, storage_path, file_size \
+             valid_time, storage_path, file_size, source_lineage \
              FROM datasets WHERE model = $1 AND parameter = $2 AND status = 'available' \
              ORDER BY valid_time DESC",
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -970,6 +1526,7 @@ impl Catalog {
                 valid_time: r.valid_time,
                 storage_path: r.storage_path,
                 file_size: r.file_size as u64,
+                lineage: SourceLineage::from_column(r.source_lineage),
             })
             .collect())
     }
@@ -986,7 +1543,7 @@ impl Catalog {
              WHERE model = $1 AND status = 'available'",
         )
         .bind(model)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -996,6 +1553,26 @@ impl Catalog {
         }
     }
 
+    /// Get a version string for a model's available data.
+    /// Changes whenever datasets are ingested or expire, so it can key response caches.
+    pub async fn get_model_data_version(&self, model: &str) -> WmsResult<String> {
+        let (count, last_ingested) = sqlx::query_as::<_, (i64, Option<DateTime<Utc>>)>(
+            "SELECT COUNT(*), MAX(ingested_at) \
+             FROM datasets \
+             WHERE model = $1 AND status = 'available'",
+        )
+        .bind(model)
+        .fetch_one(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
+
+        Ok(format!(
+            "{}-{}",
+            last_ingested.map(|t| t.timestamp_millis()).unwrap_or(0),
+            count
+        ))
+    }
+
     /// Get all available valid times for a model (for populating temporal extent values).
     /// Returns unique valid times sorted ascending.
     pub async fn get_model_valid_times(&self, model: &str) -> WmsResult<Vec<DateTime<Utc>>> {
@@ -1005,7 +1582,7 @@ impl Catalog {
              ORDER BY valid_time ASC",
         )
         .bind(model)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -1027,7 +1604,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(reference_time)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -1045,6 +1622,11 @@ impl Catalog {
         model: &str,
         parameter: &str,
     ) -> WmsResult<Option<ParameterAvailability>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_parameter_availability(model, parameter));
+        }
+
         // First check if any data exists for this model/parameter
         let count = sqlx::query_scalar::<_, i64>(
             "SELECT COUNT(*) FROM datasets \
@@ -1052,7 +1634,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -1068,7 +1650,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -1080,7 +1662,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -1092,7 +1674,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_all(&self.pool)
+        .fetch_all(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -1108,7 +1690,7 @@ impl Catalog {
         )
         .bind(model)
         .bind(parameter)
-        .fetch_one(&self.pool)
+        .fetch_one(self.pool()?)
         .await
         .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))?;
 
@@ -1125,6 +1707,199 @@ impl Catalog {
             bbox: BoundingBox::new(bbox_result.0, bbox_result.1, bbox_result.2, bbox_result.3),
         }))
     }
+
+    // ========== Parameter Metadata ==========
+
+    /// Store the display information of a parameter, replacing what was
+    /// recorded for it before.
+    pub async fn upsert_parameter_metadata(&self, metadata: &ParameterMetadata) -> WmsResult<()> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            memory.upsert_parameter_metadata(metadata);
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO parameters \
+                (model, parameter, long_name, units, default_style, valid_min, valid_max) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) \
+             ON CONFLICT (model, parameter) DO UPDATE SET \
+                long_name = EXCLUDED.long_name, units = EXCLUDED.units, \
+                default_style = EXCLUDED.default_style, valid_min = EXCLUDED.valid_min, \
+                valid_max = EXCLUDED.valid_max, updated_at = NOW()",
+        )
+        .bind(&metadata.model)
+        .bind(&metadata.parameter)
+        .bind(&metadata.long_name)
+        .bind(&metadata.units)
+        .bind(&metadata.default_style)
+        .bind(metadata.valid_min)
+        .bind(metadata.valid_max)
+        .execute(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Insert failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Display information of a model's parameters, by parameter name.
+    pub async fn list_parameter_metadata(&self, model: &str) -> WmsResult<Vec<ParameterMetadata>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.list_parameter_metadata(model));
+        }
+
+        sqlx::query_as::<_, ParameterMetadata>(
+            "SELECT model, parameter, long_name, units, default_style, valid_min, valid_max \
+             FROM parameters WHERE model = $1 ORDER BY parameter",
+        )
+        .bind(model)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    /// Display information of one parameter, if it has been ingested.
+    pub async fn get_parameter_metadata(
+        &self,
+        model: &str,
+        parameter: &str,
+    ) -> WmsResult<Option<ParameterMetadata>> {
+        #[cfg(feature = "offline")]
+        if let Backend::Memory(memory) = &self.backend {
+            return Ok(memory.get_parameter_metadata(model, parameter));
+        }
+
+        sqlx::query_as::<_, ParameterMetadata>(
+            "SELECT model, parameter, long_name, units, default_style, valid_min, valid_max \
+             FROM parameters WHERE model = $1 AND parameter = $2",
+        )
+        .bind(model)
+        .bind(parameter)
+        .fetch_optional(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    // ========== Config Version History ==========
+
+    /// Store a new revision of a config file, numbered after the latest one.
+    ///
+    /// Concurrent writers can race for the same number; the loser fails on
+    /// the primary key rather than overwriting history.
+    pub async fn record_config_version(
+        &self,
+        kind: &str,
+        name: &str,
+        content: &str,
+        comment: Option<&str>,
+    ) -> WmsResult<ConfigVersion> {
+        sqlx::query_as::<_, ConfigVersion>(
+            "INSERT INTO config_versions (kind, name, version, content, comment) \
+             SELECT $1, $2, COALESCE(MAX(version), 0) + 1, $3, $4 \
+             FROM config_versions WHERE kind = $1 AND name = $2 \
+             RETURNING kind, name, version, content, comment, created_at",
+        )
+        .bind(kind)
+        .bind(name)
+        .bind(content)
+        .bind(comment)
+        .fetch_one(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Insert failed: {}", e)))
+    }
+
+    /// All revisions of a config file, newest first.
+    pub async fn list_config_versions(
+        &self,
+        kind: &str,
+        name: &str,
+    ) -> WmsResult<Vec<ConfigVersion>> {
+        sqlx::query_as::<_, ConfigVersion>(
+            "SELECT kind, name, version, content, comment, created_at \
+             FROM config_versions WHERE kind = $1 AND name = $2 \
+             ORDER BY version DESC",
+        )
+        .bind(kind)
+        .bind(name)
+        .fetch_all(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    /// A single revision of a config file.
+    pub async fn get_config_version(
+        &self,
+        kind: &str,
+        name: &str,
+        version: i32,
+    ) -> WmsResult<Option<ConfigVersion>> {
+        sqlx::query_as::<_, ConfigVersion>(
+            "SELECT kind, name, version, content, comment, created_at \
+             FROM config_versions WHERE kind = $1 AND name = $2 AND version = $3",
+        )
+        .bind(kind)
+        .bind(name)
+        .bind(version)
+        .fetch_optional(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+
+    /// The latest revision of a config file, if any has been recorded.
+    pub async fn latest_config_version(
+        &self,
+        kind: &str,
+        name: &str,
+    ) -> WmsResult<Option<ConfigVersion>> {
+        sqlx::query_as::<_, ConfigVersion>(
+            "SELECT kind, name, version, content, comment, created_at \
+             FROM config_versions WHERE kind = $1 AND name = $2 \
+             ORDER BY version DESC LIMIT 1",
+        )
+        .bind(kind)
+        .bind(name)
+        .fetch_optional(self.pool()?)
+        .await
+        .map_err(|e| WmsError::DatabaseError(format!("Query failed: {}", e)))
+    }
+}
+
+/// A stored revision of an admin-edited config file.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ConfigVersion {
+    /// Config family: "model", "layer" or "style"
+    pub kind: String,
+    /// Config name, i.e. the file stem (e.g. "gfs", "temperature")
+    pub name: String,
+    /// Revision number, starting at 1
+    pub version: i32,
+    /// File content at this revision
+    pub content: String,
+    /// Note supplied with the change (e.g. "rollback to 3")
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Display information of a parameter: names, units, default style and
+/// valid range. Recorded at ingestion from the model config and GRIB2
+/// parameter tables, so capabilities, EDR and legends label parameters the
+/// same way.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct ParameterMetadata {
+    pub model: String,
+    /// Short name, as in the datasets table (e.g. "TMP")
+    pub parameter: String,
+    /// Long name (e.g. "Temperature")
+    pub long_name: String,
+    /// Units of the stored values (e.g. "K")
+    pub units: String,
+    /// Style file the parameter's layers render with by default
+    pub default_style: Option<String>,
+    /// Smallest valid value; values outside the range are stored as NaN
+    pub valid_min: Option<f64>,
+    /// Largest valid value
+    pub valid_max: Option<f64>,
 }
 
 /// Full dataset information for tree views.
@@ -1138,6 +1913,8 @@ pub struct DatasetInfo {
     pub valid_time: DateTime<Utc>,
     pub storage_path: String,
     pub file_size: u64,
+    /// Source the dataset was ingested from, if recorded
+    pub lineage: Option<SourceLineage>,
 }
 
 /// Detailed statistics for a parameter.
@@ -1167,6 +1944,49 @@ pub struct CatalogEntry {
     /// None for legacy GRIB2/NetCDF format files.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zarr_metadata: Option<serde_json::Value>,
+    /// Ensemble member (e.g. "c00", "p01"), None for deterministic models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ensemble_member: Option<String>,
+    /// Source file the dataset was ingested from. None for datasets derived
+    /// from several files (composites) and for those ingested before
+    /// lineage was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<SourceLineage>,
+}
+
+/// Where a dataset came from: enough to trace a Zarr array back to the exact
+/// source file and byte range after the file itself has been cleaned up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLineage {
+    /// URL the source file was downloaded from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Path of the source file as ingested
+    pub file: String,
+    /// SHA-256 of the file contents (hex), after decompressing `.gz` files
+    pub sha256: String,
+    /// Byte offset of the GRIB2 message the dataset was decoded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_offset: Option<u64>,
+    /// Length of that GRIB2 message in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_length: Option<u64>,
+    /// Version of the ingestion code that wrote the dataset
+    pub ingestion_version: String,
+}
+
+impl SourceLineage {
+    /// Lineage of one GRIB2 message of this source file.
+    pub fn with_message(mut self, offset: u64, length: u64) -> Self {
+        self.message_offset = Some(offset);
+        self.message_length = Some(length);
+        self
+    }
+
+    /// Parse the `source_lineage` column; unreadable values count as absent.
+    fn from_column(value: Option<serde_json::Value>) -> Option<Self> {
+        value.and_then(|v| serde_json::from_value(v).ok())
+    }
 }
 
 impl CatalogEntry {
@@ -1177,6 +1997,23 @@ impl CatalogEntry {
     pub fn layer_id(&self) -> LayerId {
         LayerId::new(format!("{}:{}", self.model, self.parameter))
     }
+
+    /// Stable identifier of the dataset, built from the catalog's unique key
+    /// (model, parameter, level, run, forecast hour, ensemble member).
+    pub fn dataset_id(&self) -> String {
+        let id = format!(
+            "{}/{}/{}/{}/f{:03}",
+            self.model,
+            self.parameter,
+            self.level,
+            self.reference_time.format("%Y%m%dT%H%MZ"),
+            self.forecast_hour
+        );
+        match &self.ensemble_member {
+            Some(member) => format!("{}/{}", id, member),
+            None => id,
+        }
+    }
 }
 
 /// Query parameters for finding datasets.
@@ -1228,6 +2065,9 @@ struct DatasetRow {
     storage_path: String,
     file_size: i64,
     zarr_metadata: Option<serde_json::Value>,
+    /// Empty for deterministic models
+    ensemble_member: String,
+    source_lineage: Option<serde_json::Value>,
 }
 
 impl From<DatasetRow> for CatalogEntry {
@@ -1247,6 +2087,37 @@ impl From<DatasetRow> for CatalogEntry {
             storage_path: row.storage_path,
             file_size: row.file_size as u64,
             zarr_metadata: row.zarr_metadata,
+            ensemble_member: Some(row.ensemble_member).filter(|m| !m.is_empty()),
+            lineage: SourceLineage::from_column(row.source_lineage),
+        }
+    }
+}
+
+/// Internal row type for catalog search queries.
+#[derive(FromRow)]
+struct SearchRow {
+    model: String,
+    parameter: String,
+    level: String,
+    dataset_count: i64,
+    run_count: i64,
+    earliest_valid_time: DateTime<Utc>,
+    latest_valid_time: DateTime<Utc>,
+    latest_run: DateTime<Utc>,
+}
+
+impl From<SearchRow> for CatalogSearchHit {
+    fn from(row: SearchRow) -> Self {
+        CatalogSearchHit {
+            level_type: level_type(&row.level).to_string(),
+            model: row.model,
+            parameter: row.parameter,
+            level: row.level,
+            dataset_count: row.dataset_count as u64,
+            run_count: row.run_count as u64,
+            earliest_valid_time: row.earliest_valid_time,
+            latest_valid_time: row.latest_valid_time,
+            latest_run: row.latest_run,
         }
     }
 }
@@ -1285,14 +2156,32 @@ CREATE TABLE IF NOT EXISTS datasets (
     ingested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
     status VARCHAR(20) NOT NULL DEFAULT 'available',
     zarr_metadata JSONB,
-
-    UNIQUE(model, parameter, level, reference_time, forecast_hour)
+    ensemble_member VARCHAR(10) NOT NULL DEFAULT '',
+    source_lineage JSONB
 );
 
+ALTER TABLE datasets ADD COLUMN IF NOT EXISTS ensemble_member VARCHAR(10) NOT NULL DEFAULT '';
+ALTER TABLE datasets ADD COLUMN IF NOT EXISTS source_lineage JSONB;
+
+-- Ensemble members share model, parameter, level, run and forecast hour
+ALTER TABLE datasets DROP CONSTRAINT IF EXISTS datasets_model_parameter_level_reference_time_forecast_hour_key;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_datasets_key
+    ON datasets(model, parameter, level, reference_time, forecast_hour, ensemble_member);
+
 CREATE INDEX IF NOT EXISTS idx_datasets_model_param ON datasets(model, parameter);
 CREATE INDEX IF NOT EXISTS idx_datasets_valid_time ON datasets(valid_time DESC);
 CREATE INDEX IF NOT EXISTS idx_datasets_status ON datasets(status);
 
+-- Forecast runs being ingested; published once all expected hours exist
+CREATE TABLE IF NOT EXISTS model_runs (
+    model VARCHAR(50) NOT NULL,
+    reference_time TIMESTAMPTZ NOT NULL,
+    expected_forecast_hours INTEGER NOT NULL,
+    published_at TIMESTAMPTZ,
+
+    PRIMARY KEY(model, reference_time)
+);
+
 CREATE TABLE IF NOT EXISTS layer_styles (
     id UUID PRIMARY KEY,
     layer_id VARCHAR(200) NOT NULL,
@@ -1301,5 +2190,30 @@ CREATE TABLE IF NOT EXISTS layer_styles (
     created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
 
     UNIQUE(layer_id, style_name)
+);
+
+-- Display information per parameter, refreshed at ingestion
+CREATE TABLE IF NOT EXISTS parameters (
+    model VARCHAR(50) NOT NULL,
+    parameter VARCHAR(100) NOT NULL,
+    long_name TEXT NOT NULL,
+    units VARCHAR(50) NOT NULL,
+    default_style VARCHAR(100),
+    valid_min DOUBLE PRECISION,
+    valid_max DOUBLE PRECISION,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY(model, parameter)
+);
+
+CREATE TABLE IF NOT EXISTS config_versions (
+    kind VARCHAR(20) NOT NULL,
+    name VARCHAR(200) NOT NULL,
+    version INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    comment TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY(kind, name, version)
 )
 "#;
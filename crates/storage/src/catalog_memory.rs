@@ -0,0 +1,571 @@
+//! In-memory catalog backend for offline mode.
+//!
+//! Mirrors the Postgres queries that the WMS/WMTS render and capabilities
+//! paths use, so a service can run against fixture datasets without a
+//! database. Every entry counts as available; nothing expires.
+
+use chrono::{DateTime, DurationRound, Utc};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::RwLock;
+
+use crate::catalog::{CatalogEntry, ParameterAvailability, ParameterMetadata};
+use wms_common::BoundingBox;
+
+/// Datasets held in memory, queried with the same ordering as the SQL.
+#[derive(Default)]
+pub(crate) struct MemoryCatalog {
+    entries: RwLock<Vec<CatalogEntry>>,
+    /// Parameter metadata keyed by (model, parameter)
+    parameters: RwLock<BTreeMap<(String, String), ParameterMetadata>>,
+}
+
+impl MemoryCatalog {
+    /// Insert a dataset, replacing one with the same model, parameter, level,
+    /// run, forecast hour and ensemble member.
+    pub fn insert(&self, entry: &CatalogEntry) {
+        let mut entries = self.entries.write().unwrap();
+        entries.retain(|e| {
+            (
+                &e.model,
+                &e.parameter,
+                &e.level,
+                e.reference_time,
+                e.forecast_hour,
+                &e.ensemble_member,
+            ) != (
+                &entry.model,
+                &entry.parameter,
+                &entry.level,
+                entry.reference_time,
+                entry.forecast_hour,
+                &entry.ensemble_member,
+            )
+        });
+        entries.push(entry.clone());
+    }
+
+    /// Entries for a model/parameter that pass `filter`.
+    fn layer(
+        &self,
+        model: &str,
+        parameter: &str,
+        filter: impl Fn(&CatalogEntry) -> bool,
+    ) -> Vec<CatalogEntry> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|e| e.model == model && e.parameter == parameter && filter(e))
+            .cloned()
+            .collect()
+    }
+
+    fn model(&self, model: &str) -> Vec<CatalogEntry> {
+        self.entries
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|e| e.model == model)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_latest(&self, model: &str, parameter: &str) -> Option<CatalogEntry> {
+        self.layer(model, parameter, |_| true)
+            .into_iter()
+            .max_by_key(|e| e.valid_time())
+    }
+
+    pub fn get_latest_at_level(
+        &self,
+        model: &str,
+        parameter: &str,
+        level: &str,
+    ) -> Option<CatalogEntry> {
+        self.layer(model, parameter, |e| e.level == level)
+            .into_iter()
+            .max_by_key(|e| e.valid_time())
+    }
+
+    pub fn find_by_time(
+        &self,
+        model: &str,
+        parameter: &str,
+        valid_time: DateTime<Utc>,
+        level: Option<&str>,
+    ) -> Option<CatalogEntry> {
+        self.layer(model, parameter, |e| level.is_none_or(|l| e.level == l))
+            .into_iter()
+            .min_by_key(|e| (e.valid_time() - valid_time).num_seconds().abs())
+    }
+
+    pub fn find_by_forecast_hour(
+        &self,
+        model: &str,
+        parameter: &str,
+        forecast_hour: u32,
+        level: Option<&str>,
+    ) -> Option<CatalogEntry> {
+        self.layer(model, parameter, |e| {
+            e.forecast_hour == forecast_hour && level.is_none_or(|l| e.level == l)
+        })
+        .into_iter()
+        .max_by_key(|e| e.reference_time)
+    }
+
+    pub fn find_by_run_and_forecast_hour(
+        &self,
+        model: &str,
+        parameter: &str,
+        reference_time: DateTime<Utc>,
+        forecast_hour: u32,
+        level: Option<&str>,
+    ) -> Option<CatalogEntry> {
+        self.layer(model, parameter, |e| {
+            e.reference_time == reference_time
+                && e.forecast_hour == forecast_hour
+                && level.is_none_or(|l| e.level == l)
+        })
+        .into_iter()
+        .min_by(|a, b| a.level.cmp(&b.level))
+    }
+
+    pub fn find_run_levels(
+        &self,
+        model: &str,
+        parameter: &str,
+        reference_time: DateTime<Utc>,
+        forecast_hour: u32,
+    ) -> Vec<CatalogEntry> {
+        let mut entries = self.layer(model, parameter, |e| {
+            e.reference_time == reference_time
+                && e.forecast_hour == forecast_hour
+                && e.ensemble_member.is_none()
+        });
+        entries.sort_by(|a, b| a.level.cmp(&b.level));
+        entries
+    }
+
+    pub fn find_ensemble_members(
+        &self,
+        model: &str,
+        parameter: &str,
+        reference_time: DateTime<Utc>,
+        forecast_hour: u32,
+        level: &str,
+    ) -> Vec<CatalogEntry> {
+        let mut entries = self.layer(model, parameter, |e| {
+            e.reference_time == reference_time
+                && e.forecast_hour == forecast_hour
+                && e.level == level
+        });
+        entries.sort_by(|a, b| a.ensemble_member.cmp(&b.ensemble_member));
+        entries
+    }
+
+    pub fn get_latest_run_earliest_forecast(
+        &self,
+        model: &str,
+        parameter: &str,
+        level: Option<&str>,
+    ) -> Option<CatalogEntry> {
+        self.layer(model, parameter, |e| level.is_none_or(|l| e.level == l))
+            .into_iter()
+            .min_by_key(|e| (Reverse(e.reference_time), e.forecast_hour))
+    }
+
+    pub fn find_runs_for_valid_time(
+        &self,
+        model: &str,
+        parameter: &str,
+        valid_time: DateTime<Utc>,
+        level: Option<&str>,
+    ) -> Vec<CatalogEntry> {
+        let mut entries = self.layer(model, parameter, |e| {
+            e.valid_time() == valid_time && level.is_none_or(|l| e.level == l)
+        });
+        entries.sort_by(|a, b| {
+            b.reference_time
+                .cmp(&a.reference_time)
+                .then_with(|| a.level.cmp(&b.level))
+        });
+        entries
+    }
+
+    pub fn find_in_valid_time_range(
+        &self,
+        model: &str,
+        parameter: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        reference_time: Option<DateTime<Utc>>,
+        level: Option<&str>,
+    ) -> Vec<CatalogEntry> {
+        let mut entries = self.layer(model, parameter, |e| {
+            let valid = e.valid_time();
+            valid > start
+                && valid <= end
+                && reference_time.is_none_or(|r| e.reference_time == r)
+                && level.is_none_or(|l| e.level == l)
+        });
+        entries.sort_by(|a, b| {
+            a.valid_time()
+                .cmp(&b.valid_time())
+                .then_with(|| b.reference_time.cmp(&a.reference_time))
+                .then_with(|| a.level.cmp(&b.level))
+        });
+        entries.dedup_by_key(|e| e.valid_time());
+        entries
+    }
+
+    pub fn get_available_times(&self, model: &str, parameter: &str) -> Vec<DateTime<Utc>> {
+        let times: BTreeSet<_> = self
+            .layer(model, parameter, |_| true)
+            .iter()
+            .map(|e| e.valid_time())
+            .collect();
+        times.into_iter().rev().collect()
+    }
+
+    pub fn get_available_runs(&self, model: &str, parameter: &str) -> Vec<DateTime<Utc>> {
+        let runs: BTreeSet<_> = self
+            .layer(model, parameter, |_| true)
+            .iter()
+            .map(|e| e.reference_time)
+            .collect();
+        runs.into_iter().rev().collect()
+    }
+
+    pub fn get_forecast_hours(
+        &self,
+        model: &str,
+        parameter: &str,
+        reference_time: Option<DateTime<Utc>>,
+        level: Option<&str>,
+    ) -> Vec<i32> {
+        let hours: BTreeSet<_> = self
+            .layer(model, parameter, |e| {
+                reference_time.is_none_or(|r| e.reference_time == r)
+                    && level.is_none_or(|l| e.level == l)
+            })
+            .iter()
+            .map(|e| e.forecast_hour as i32)
+            .collect();
+        hours.into_iter().collect()
+    }
+
+    pub fn get_available_levels(&self, model: &str, parameter: &str) -> Vec<String> {
+        let levels: BTreeSet<_> = self
+            .layer(model, parameter, |_| true)
+            .into_iter()
+            .map(|e| e.level)
+            .collect();
+        levels.into_iter().collect()
+    }
+
+    pub fn list_models(&self) -> Vec<String> {
+        let models: BTreeSet<_> = self
+            .entries
+            .read()
+            .unwrap()
+            .iter()
+            .map(|e| e.model.clone())
+            .collect();
+        models.into_iter().collect()
+    }
+
+    pub fn list_parameters(&self, model: &str) -> Vec<String> {
+        let parameters: BTreeSet<_> = self.model(model).into_iter().map(|e| e.parameter).collect();
+        parameters.into_iter().collect()
+    }
+
+    pub fn get_model_dimensions(&self, model: &str) -> (Vec<String>, Vec<i32>) {
+        let entries = self.model(model);
+        let hours: BTreeSet<_> = entries.iter().map(|e| e.forecast_hour as i32).collect();
+        (run_strings(&entries), hours.into_iter().collect())
+    }
+
+    /// Union of the model's dataset bounding boxes, None if it has no data.
+    pub fn get_model_bbox(&self, model: &str) -> Option<BoundingBox> {
+        union_bbox(&self.model(model))
+    }
+
+    pub fn get_parameter_availability(
+        &self,
+        model: &str,
+        parameter: &str,
+    ) -> Option<ParameterAvailability> {
+        let entries = self.layer(model, parameter, |_| true);
+        let bbox = union_bbox(&entries)?;
+
+        Some(ParameterAvailability {
+            times: run_strings(&entries),
+            forecast_hours: self.get_forecast_hours(model, parameter, None, None),
+            levels: self.get_available_levels(model, parameter),
+            bbox,
+        })
+    }
+
+    pub fn upsert_parameter_metadata(&self, metadata: &ParameterMetadata) {
+        self.parameters.write().unwrap().insert(
+            (metadata.model.clone(), metadata.parameter.clone()),
+            metadata.clone(),
+        );
+    }
+
+    pub fn list_parameter_metadata(&self, model: &str) -> Vec<ParameterMetadata> {
+        self.parameters
+            .read()
+            .unwrap()
+            .values()
+            .filter(|m| m.model == model)
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_parameter_metadata(
+        &self,
+        model: &str,
+        parameter: &str,
+    ) -> Option<ParameterMetadata> {
+        self.parameters
+            .read()
+            .unwrap()
+            .get(&(model.to_string(), parameter.to_string()))
+            .cloned()
+    }
+}
+
+/// Distinct runs truncated to the minute, newest first, as ISO8601 strings
+/// (matches `DATE_TRUNC('minute', reference_time)`).
+fn run_strings(entries: &[CatalogEntry]) -> Vec<String> {
+    let runs: BTreeSet<_> = entries
+        .iter()
+        .map(|e| {
+            e.reference_time
+                .duration_trunc(chrono::Duration::minutes(1))
+                .unwrap_or(e.reference_time)
+        })
+        .collect();
+    runs.into_iter()
+        .rev()
+        .map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .collect()
+}
+
+fn union_bbox(entries: &[CatalogEntry]) -> Option<BoundingBox> {
+    entries.iter().map(|e| e.bbox).reduce(|a, b| {
+        BoundingBox::new(
+            a.min_x.min(b.min_x),
+            a.min_y.min(b.min_y),
+            a.max_x.max(b.max_x),
+            a.max_y.max(b.max_y),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(level: &str, run_hour: u32, forecast_hour: u32) -> CatalogEntry {
+        CatalogEntry {
+            model: "gfs".to_string(),
+            parameter: "TMP".to_string(),
+            level: level.to_string(),
+            reference_time: Utc.with_ymd_and_hms(2024, 12, 22, run_hour, 0, 0).unwrap(),
+            forecast_hour,
+            bbox: BoundingBox::new(0.0, -90.0, 360.0, 90.0),
+            storage_path: format!(
+                "grids/gfs/{}/{}/f{:03}.zarr",
+                level, run_hour, forecast_hour
+            ),
+            file_size: 1,
+            zarr_metadata: None,
+            ensemble_member: None,
+            lineage: None,
+        }
+    }
+
+    fn catalog() -> MemoryCatalog {
+        let catalog = MemoryCatalog::default();
+        for (level, run, fh) in [
+            ("2 m above ground", 0, 0),
+            ("2 m above ground", 0, 6),
+            ("2 m above ground", 6, 0),
+            ("2 m above ground", 6, 3),
+            ("500 mb", 6, 0),
+        ] {
+            catalog.insert(&entry(level, run, fh));
+        }
+        catalog
+    }
+
+    #[test]
+    fn test_insert_replaces_same_dataset() {
+        let catalog = catalog();
+        let mut replacement = entry("500 mb", 6, 0);
+        replacement.file_size = 42;
+        catalog.insert(&replacement);
+
+        let found = catalog
+            .find_by_forecast_hour("gfs", "TMP", 0, Some("500 mb"))
+            .unwrap();
+        assert_eq!(found.file_size, 42);
+        assert_eq!(catalog.get_available_levels("gfs", "TMP").len(), 2);
+    }
+
+    #[test]
+    fn test_insert_keeps_ensemble_members() {
+        let catalog = MemoryCatalog::default();
+        for member in ["c00", "p01"] {
+            let mut member_entry = entry("500 mb", 6, 0);
+            member_entry.ensemble_member = Some(member.to_string());
+            catalog.insert(&member_entry);
+        }
+
+        let entries = catalog.entries.read().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[1].dataset_id(),
+            "gfs/TMP/500 mb/20241222T0600Z/f000/p01"
+        );
+    }
+
+    #[test]
+    fn test_find_ensemble_members() {
+        let catalog = catalog();
+        for member in ["p02", "c00", "p01"] {
+            let mut member_entry = entry("500 mb", 6, 0);
+            member_entry.ensemble_member = Some(member.to_string());
+            catalog.insert(&member_entry);
+        }
+        let run = Utc.with_ymd_and_hms(2024, 12, 22, 6, 0, 0).unwrap();
+
+        let members = catalog.find_ensemble_members("gfs", "TMP", run, 0, "500 mb");
+        let names: Vec<_> = members
+            .iter()
+            .map(|e| e.ensemble_member.as_deref())
+            .collect();
+        // The deterministic dataset sorts first
+        assert_eq!(names, vec![None, Some("c00"), Some("p01"), Some("p02")]);
+        assert!(catalog
+            .find_ensemble_members("gfs", "TMP", run, 3, "500 mb")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_find_run_levels() {
+        let catalog = catalog();
+        let mut member_entry = entry("700 mb", 6, 0);
+        member_entry.ensemble_member = Some("p01".to_string());
+        catalog.insert(&member_entry);
+        let run = Utc.with_ymd_and_hms(2024, 12, 22, 6, 0, 0).unwrap();
+
+        let levels: Vec<_> = catalog
+            .find_run_levels("gfs", "TMP", run, 0)
+            .into_iter()
+            .map(|e| e.level)
+            .collect();
+        // The member's level isn't a level of the deterministic run
+        assert_eq!(levels, vec!["2 m above ground", "500 mb"]);
+        assert!(catalog.find_run_levels("gfs", "TMP", run, 6).is_empty());
+    }
+
+    #[test]
+    fn test_latest_run_earliest_forecast() {
+        let catalog = catalog();
+        let found = catalog
+            .get_latest_run_earliest_forecast("gfs", "TMP", Some("2 m above ground"))
+            .unwrap();
+        assert_eq!(found.reference_time.format("%H").to_string(), "06");
+        assert_eq!(found.forecast_hour, 0);
+
+        // Latest valid time is run 06 + 3h
+        let latest = catalog.get_latest("gfs", "TMP").unwrap();
+        assert_eq!(latest.forecast_hour, 3);
+    }
+
+    #[test]
+    fn test_valid_time_queries() {
+        let catalog = catalog();
+        let six = Utc.with_ymd_and_hms(2024, 12, 22, 6, 0, 0).unwrap();
+
+        // Run 00 f006 and run 06 f000 (both levels) share a valid time
+        let runs = catalog.find_runs_for_valid_time("gfs", "TMP", six, None);
+        let order: Vec<_> = runs
+            .iter()
+            .map(|e| (e.forecast_hour, e.level.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (0, "2 m above ground"),
+                (0, "500 mb"),
+                (6, "2 m above ground")
+            ]
+        );
+
+        // One entry per valid time, newest run and first level win
+        let start = Utc.with_ymd_and_hms(2024, 12, 22, 0, 0, 0).unwrap();
+        let range = catalog.find_in_valid_time_range("gfs", "TMP", start, six, None, None);
+        assert_eq!(range.len(), 1);
+        assert_eq!(range[0].reference_time, six);
+        assert_eq!(range[0].level, "2 m above ground");
+
+        let closest = catalog
+            .find_by_time("gfs", "TMP", six + chrono::Duration::hours(2), None)
+            .unwrap();
+        assert_eq!(closest.forecast_hour, 3);
+    }
+
+    #[test]
+    fn test_parameter_availability() {
+        let catalog = catalog();
+        assert!(catalog.get_parameter_availability("gfs", "UGRD").is_none());
+
+        let availability = catalog.get_parameter_availability("gfs", "TMP").unwrap();
+        assert_eq!(
+            availability.times,
+            vec!["2024-12-22T06:00:00Z", "2024-12-22T00:00:00Z"]
+        );
+        assert_eq!(availability.forecast_hours, vec![0, 3, 6]);
+        assert_eq!(availability.levels, vec!["2 m above ground", "500 mb"]);
+        assert_eq!(catalog.list_models(), vec!["gfs"]);
+        assert_eq!(catalog.list_parameters("gfs"), vec!["TMP"]);
+    }
+
+    #[test]
+    fn test_parameter_metadata() {
+        let catalog = catalog();
+        let metadata = |parameter: &str, long_name: &str| ParameterMetadata {
+            model: "gfs".to_string(),
+            parameter: parameter.to_string(),
+            long_name: long_name.to_string(),
+            units: "K".to_string(),
+            default_style: Some("temperature".to_string()),
+            valid_min: Some(150.0),
+            valid_max: Some(350.0),
+        };
+
+        catalog.upsert_parameter_metadata(&metadata("TMP", "Temp"));
+        catalog.upsert_parameter_metadata(&metadata("TMP", "Temperature"));
+        catalog.upsert_parameter_metadata(&metadata("DPT", "Dew Point Temperature"));
+
+        let listed = catalog.list_parameter_metadata("gfs");
+        assert_eq!(
+            listed
+                .iter()
+                .map(|m| m.parameter.as_str())
+                .collect::<Vec<_>>(),
+            vec!["DPT", "TMP"]
+        );
+        assert_eq!(
+            catalog.get_parameter_metadata("gfs", "TMP"),
+            Some(metadata("TMP", "Temperature"))
+        );
+        assert!(catalog.get_parameter_metadata("hrrr", "TMP").is_none());
+    }
+}
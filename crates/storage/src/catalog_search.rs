@@ -0,0 +1,352 @@
+//! Free-text and faceted search over the dataset catalog.
+//!
+//! Search results are grouped into series: one hit per (model, parameter,
+//! level) with counts and the valid time range of its datasets. Text and time
+//! filters run in SQL (see [`crate::Catalog::search`]); model and level type
+//! filters and the facet counts are applied here so each facet can be counted
+//! without its own filter, as discovery UIs expect.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// Catalog search parameters.
+#[derive(Debug, Clone, Default)]
+pub struct CatalogSearchQuery {
+    /// Case-insensitive substring of model, parameter or level names
+    pub text: Option<String>,
+    /// (model, parameter) pairs matched by other means (e.g. layer descriptions)
+    pub matching_layers: Vec<(String, String)>,
+    /// Only these models (empty = all)
+    pub models: Vec<String>,
+    /// Only these level types, see [`level_type`] (empty = all)
+    pub level_types: Vec<String>,
+    /// Only datasets valid at or after this time
+    pub start: Option<DateTime<Utc>>,
+    /// Only datasets valid at or before this time
+    pub end: Option<DateTime<Utc>>,
+    /// Maximum number of hits returned (None = all)
+    pub limit: Option<usize>,
+    /// Number of hits skipped
+    pub offset: usize,
+}
+
+/// One (model, parameter, level) series matching a search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogSearchHit {
+    pub model: String,
+    pub parameter: String,
+    pub level: String,
+    pub level_type: String,
+    pub dataset_count: u64,
+    pub run_count: u64,
+    pub earliest_valid_time: DateTime<Utc>,
+    pub latest_valid_time: DateTime<Utc>,
+    pub latest_run: DateTime<Utc>,
+}
+
+/// Number of matching series for one facet value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: u64,
+}
+
+/// Facets for refining a search.
+///
+/// Each facet is counted with every filter applied except its own, so a
+/// selected model still lists the other models it could switch to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CatalogSearchFacets {
+    pub models: Vec<FacetCount>,
+    pub level_types: Vec<FacetCount>,
+    /// Valid time range covered by the matching series
+    pub valid_time: Option<ValidTimeRange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidTimeRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Catalog search results.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CatalogSearchResults {
+    /// Number of matching series before `limit`/`offset`
+    pub total: usize,
+    /// Best matches first
+    pub hits: Vec<CatalogSearchHit>,
+    pub facets: CatalogSearchFacets,
+}
+
+impl CatalogSearchResults {
+    /// Filter, rank and page the series returned by the text/time query.
+    pub fn build(series: Vec<CatalogSearchHit>, query: &CatalogSearchQuery) -> Self {
+        let model_selected =
+            |hit: &CatalogSearchHit| query.models.is_empty() || query.models.contains(&hit.model);
+        let level_type_selected = |hit: &CatalogSearchHit| {
+            query.level_types.is_empty() || query.level_types.contains(&hit.level_type)
+        };
+
+        let facets = CatalogSearchFacets {
+            models: facet_counts(
+                series
+                    .iter()
+                    .filter(|h| level_type_selected(h))
+                    .map(|h| &h.model),
+            ),
+            level_types: facet_counts(
+                series
+                    .iter()
+                    .filter(|h| model_selected(h))
+                    .map(|h| &h.level_type),
+            ),
+            valid_time: None,
+        };
+
+        let mut hits: Vec<CatalogSearchHit> = series
+            .into_iter()
+            .filter(|h| model_selected(h) && level_type_selected(h))
+            .collect();
+
+        let text = query.text.as_deref().map(str::to_lowercase);
+        hits.sort_by_cached_key(|h| {
+            (
+                text.as_deref().map_or(0, |t| match_rank(h, t)),
+                h.model.clone(),
+                h.parameter.clone(),
+                h.level.clone(),
+            )
+        });
+
+        let valid_time = hits
+            .iter()
+            .map(|h| h.earliest_valid_time)
+            .min()
+            .zip(hits.iter().map(|h| h.latest_valid_time).max())
+            .map(|(start, end)| ValidTimeRange { start, end });
+
+        let total = hits.len();
+        let hits = hits
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect();
+
+        Self {
+            total,
+            hits,
+            facets: CatalogSearchFacets {
+                valid_time,
+                ..facets
+            },
+        }
+    }
+}
+
+/// Classify a level name into a coarse level type for faceting.
+///
+/// Returns one of `isobaric`, `height_above_ground`, `mean_sea_level`,
+/// `surface`, `entire_atmosphere`, `cloud` or `other`.
+pub fn level_type(level: &str) -> &'static str {
+    let level = level.trim().to_lowercase();
+
+    let is_pressure = level
+        .strip_suffix(" mb")
+        .or_else(|| level.strip_suffix(" hpa"))
+        .is_some_and(|value| value.parse::<f64>().is_ok());
+
+    if is_pressure {
+        "isobaric"
+    } else if level.contains("above ground") {
+        "height_above_ground"
+    } else if level.contains("above msl") || level == "mean sea level" {
+        "mean_sea_level"
+    } else if level == "surface" {
+        "surface"
+    } else if level.starts_with("entire atmosphere") {
+        "entire_atmosphere"
+    } else if level.contains("cloud") {
+        "cloud"
+    } else {
+        "other"
+    }
+}
+
+/// Escape `%`, `_` and `\` for use in a LIKE pattern.
+pub(crate) fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Lower is better: exact parameter, parameter prefix, any name, other match.
+fn match_rank(hit: &CatalogSearchHit, text: &str) -> u8 {
+    let parameter = hit.parameter.to_lowercase();
+    if parameter == text {
+        0
+    } else if parameter.starts_with(text) {
+        1
+    } else if parameter.contains(text)
+        || hit.model.to_lowercase().contains(text)
+        || hit.level.to_lowercase().contains(text)
+    {
+        2
+    } else {
+        3
+    }
+}
+
+/// Count values, most common first.
+fn facet_counts<'a>(values: impl Iterator<Item = &'a String>) -> Vec<FacetCount> {
+    let mut counts: BTreeMap<&str, u64> = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+
+    let mut facets: Vec<FacetCount> = counts
+        .into_iter()
+        .map(|(value, count)| FacetCount {
+            value: value.to_string(),
+            count,
+        })
+        .collect();
+    facets.sort_by_key(|f| Reverse(f.count));
+    facets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn hit(model: &str, parameter: &str, level: &str, day: u32) -> CatalogSearchHit {
+        let time = Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap();
+        CatalogSearchHit {
+            model: model.to_string(),
+            parameter: parameter.to_string(),
+            level: level.to_string(),
+            level_type: level_type(level).to_string(),
+            dataset_count: 10,
+            run_count: 2,
+            earliest_valid_time: time,
+            latest_valid_time: time + chrono::Duration::hours(48),
+            latest_run: time,
+        }
+    }
+
+    fn series() -> Vec<CatalogSearchHit> {
+        vec![
+            hit("hrrr", "TMP", "2 m above ground", 3),
+            hit("gfs", "TMP", "500 mb", 2),
+            hit("gfs", "TMP", "2 m above ground", 1),
+            hit("gfs", "TMAX", "2 m above ground", 1),
+            hit("gfs", "DPT", "2 m above ground", 1),
+            hit("gfs", "PRMSL", "mean sea level", 1),
+        ]
+    }
+
+    #[test]
+    fn test_level_type() {
+        assert_eq!(level_type("500 mb"), "isobaric");
+        assert_eq!(level_type("925 hPa"), "isobaric");
+        assert_eq!(level_type("2 m above ground"), "height_above_ground");
+        assert_eq!(level_type("0 m above MSL"), "mean_sea_level");
+        assert_eq!(level_type("mean sea level"), "mean_sea_level");
+        assert_eq!(level_type("surface"), "surface");
+        assert_eq!(
+            level_type("entire atmosphere (considered as a single layer)"),
+            "entire_atmosphere"
+        );
+        assert_eq!(level_type("low cloud top"), "cloud");
+        assert_eq!(level_type("ir_window"), "other");
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("WIND_BARBS"), "WIND\\_BARBS");
+        assert_eq!(escape_like("50%"), "50\\%");
+        assert_eq!(escape_like("a\\b"), "a\\\\b");
+    }
+
+    #[test]
+    fn test_ranks_exact_parameter_first() {
+        let query = CatalogSearchQuery {
+            text: Some("TMP".to_string()),
+            ..Default::default()
+        };
+        let mut series = series();
+        series.retain(|h| h.parameter.contains("TM"));
+
+        let results = CatalogSearchResults::build(series, &query);
+        let parameters: Vec<&str> = results.hits.iter().map(|h| h.parameter.as_str()).collect();
+        assert_eq!(parameters, vec!["TMP", "TMP", "TMP", "TMAX"]);
+        // Ties are ordered by model, then level
+        assert_eq!(results.hits[0].model, "gfs");
+        assert_eq!(results.hits[0].level, "2 m above ground");
+    }
+
+    #[test]
+    fn test_facets_ignore_own_filter() {
+        let query = CatalogSearchQuery {
+            models: vec!["gfs".to_string()],
+            level_types: vec!["height_above_ground".to_string()],
+            ..Default::default()
+        };
+
+        let results = CatalogSearchResults::build(series(), &query);
+        assert_eq!(results.total, 3);
+        assert!(results.hits.iter().all(|h| h.model == "gfs"));
+
+        // Model facet counts height_above_ground series of every model
+        assert_eq!(
+            results.facets.models,
+            vec![
+                FacetCount {
+                    value: "gfs".to_string(),
+                    count: 3
+                },
+                FacetCount {
+                    value: "hrrr".to_string(),
+                    count: 1
+                },
+            ]
+        );
+        // Level type facet counts every gfs series
+        assert_eq!(results.facets.level_types[0].value, "height_above_ground");
+        assert_eq!(results.facets.level_types[0].count, 3);
+        assert_eq!(results.facets.level_types.len(), 3);
+
+        let valid_time = results.facets.valid_time.unwrap();
+        assert_eq!(
+            valid_time.start,
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            valid_time.end,
+            Utc.with_ymd_and_hms(2024, 1, 3, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_paging() {
+        let query = CatalogSearchQuery {
+            limit: Some(2),
+            offset: 2,
+            ..Default::default()
+        };
+
+        let results = CatalogSearchResults::build(series(), &query);
+        assert_eq!(results.total, 6);
+        assert_eq!(results.hits.len(), 2);
+        assert_eq!(results.hits[0].parameter, "TMAX");
+        assert_eq!(results.hits[1].parameter, "TMP");
+    }
+}
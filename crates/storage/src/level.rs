@@ -0,0 +1,253 @@
+//! Canonical names for vertical levels.
+//!
+//! Producers spell the same level differently: GFS and HRRR files say
+//! "2 m above ground", other tools write "2 m above gnd", "2m AGL" or
+//! "2 m HAG"; isobaric levels show up as "500 mb", "500 hPa" or "50000 Pa".
+//! Levels are parsed into a kind, value and units and written back in the
+//! naming used by the model configs, so the catalog stores and looks up one
+//! name per level. Names that aren't recognized are kept as they are.
+
+use std::fmt;
+
+/// Kind of vertical level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LevelKind {
+    /// Ground or water surface
+    Surface,
+    /// Height above ground, in meters
+    HeightAboveGround,
+    /// Height above mean sea level, in meters
+    HeightAboveMsl,
+    /// Pressure level, in millibars
+    Isobaric,
+    /// Mean sea level
+    MeanSeaLevel,
+    /// Entire atmosphere considered as a single layer
+    EntireAtmosphere,
+    /// Anything else, kept by name
+    Other,
+}
+
+/// A level parsed into its kind and value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanonicalLevel {
+    pub kind: LevelKind,
+    /// Height in meters or pressure in millibars, for the kinds that have one
+    pub value: Option<f64>,
+    /// Original name, for levels of kind [`LevelKind::Other`]
+    pub name: Option<String>,
+}
+
+/// Spellings of the levels without a value.
+const NAMED_LEVELS: &[(&str, LevelKind)] = &[
+    ("surface", LevelKind::Surface),
+    ("sfc", LevelKind::Surface),
+    ("ground or water surface", LevelKind::Surface),
+    ("mean sea level", LevelKind::MeanSeaLevel),
+    ("msl", LevelKind::MeanSeaLevel),
+    ("entire atmosphere", LevelKind::EntireAtmosphere),
+    (
+        "entire atmosphere (considered as a single layer)",
+        LevelKind::EntireAtmosphere,
+    ),
+    ("entire atmos", LevelKind::EntireAtmosphere),
+    ("atmos col", LevelKind::EntireAtmosphere),
+    ("eatm", LevelKind::EntireAtmosphere),
+];
+
+/// Spellings of the reference surface after a height.
+const HEIGHT_REFERENCES: &[(&str, LevelKind)] = &[
+    ("above ground level", LevelKind::HeightAboveGround),
+    ("above ground", LevelKind::HeightAboveGround),
+    ("above gnd", LevelKind::HeightAboveGround),
+    ("agl", LevelKind::HeightAboveGround),
+    ("hag", LevelKind::HeightAboveGround),
+    ("above mean sea level", LevelKind::HeightAboveMsl),
+    ("above msl", LevelKind::HeightAboveMsl),
+    ("amsl", LevelKind::HeightAboveMsl),
+    ("msl", LevelKind::HeightAboveMsl),
+];
+
+/// Height units and their size in meters.
+const HEIGHT_UNITS: &[(&str, f64)] = &[("m", 1.0), ("meter", 1.0), ("meters", 1.0), ("km", 1000.0)];
+
+/// Pressure units and their size in millibars.
+const PRESSURE_UNITS: &[(&str, f64)] = &[
+    ("mb", 1.0),
+    ("mbar", 1.0),
+    ("millibar", 1.0),
+    ("millibars", 1.0),
+    ("hpa", 1.0),
+    ("pa", 0.01),
+];
+
+impl CanonicalLevel {
+    /// Parse a level name. Never fails: unrecognized names are kept as
+    /// [`LevelKind::Other`].
+    pub fn parse(level: &str) -> Self {
+        let trimmed = level.split_whitespace().collect::<Vec<_>>().join(" ");
+        let lower = trimmed.to_lowercase();
+
+        if let Some(&(_, kind)) = NAMED_LEVELS.iter().find(|(name, _)| *name == lower) {
+            return Self::new(kind, None);
+        }
+        if let Some(level) = Self::parse_with_value(&lower) {
+            return level;
+        }
+
+        Self {
+            kind: LevelKind::Other,
+            value: None,
+            name: Some(trimmed),
+        }
+    }
+
+    fn new(kind: LevelKind, value: Option<f64>) -> Self {
+        Self {
+            kind,
+            value,
+            name: None,
+        }
+    }
+
+    /// "<number>[ ]<units>[ <reference>]", e.g. "2m agl" or "500 hpa".
+    fn parse_with_value(lower: &str) -> Option<Self> {
+        let sign = usize::from(lower.starts_with('-'));
+        let number_end = lower[sign..]
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .map_or(lower.len(), |end| end + sign);
+        let value: f64 = lower[..number_end].parse().ok()?;
+        let rest = lower[number_end..].trim_start_matches(['-', ' ']);
+        let (units, reference) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        if let Some(&(_, scale)) = PRESSURE_UNITS.iter().find(|(u, _)| *u == units) {
+            return reference
+                .is_empty()
+                .then(|| Self::new(LevelKind::Isobaric, Some(value * scale)));
+        }
+
+        let &(_, scale) = HEIGHT_UNITS.iter().find(|(u, _)| *u == units)?;
+        let &(_, kind) = HEIGHT_REFERENCES.iter().find(|(r, _)| *r == reference)?;
+        Some(Self::new(kind, Some(value * scale)))
+    }
+
+    /// Whether two parsed levels are the same level.
+    pub fn matches(&self, other: &Self) -> bool {
+        match (&self.name, &other.name) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            _ => {
+                self.kind == other.kind
+                    && match (self.value, other.value) {
+                        (Some(a), Some(b)) => (a - b).abs() < 1e-6,
+                        (a, b) => a.is_none() && b.is_none(),
+                    }
+            }
+        }
+    }
+}
+
+impl fmt::Display for CanonicalLevel {
+    /// The name used in model configs, e.g. "2 m above ground", "500 mb".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.value.map(format_value).unwrap_or_default();
+        match self.kind {
+            LevelKind::Surface => write!(f, "surface"),
+            LevelKind::HeightAboveGround => write!(f, "{} m above ground", value),
+            LevelKind::HeightAboveMsl => write!(f, "{} m above MSL", value),
+            LevelKind::Isobaric => write!(f, "{} mb", value),
+            LevelKind::MeanSeaLevel => write!(f, "mean sea level"),
+            LevelKind::EntireAtmosphere => write!(f, "entire atmosphere"),
+            LevelKind::Other => write!(f, "{}", self.name.as_deref().unwrap_or_default()),
+        }
+    }
+}
+
+/// Format a level value without a trailing ".0".
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Canonical name of a level, e.g. "2 m AGL" -> "2 m above ground".
+pub fn canonicalize_level(level: &str) -> String {
+    CanonicalLevel::parse(level).to_string()
+}
+
+/// Whether two level names refer to the same level.
+pub fn levels_match(a: &str, b: &str) -> bool {
+    CanonicalLevel::parse(a).matches(&CanonicalLevel::parse(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gfs_hrrr_height_variants() {
+        for level in [
+            "2 m above ground",
+            "2 m above gnd",
+            "2m above ground",
+            "2 m AGL",
+            "2-m above ground level",
+            "2 m HAG",
+            "  2  m   above ground ",
+        ] {
+            assert_eq!(canonicalize_level(level), "2 m above ground", "{}", level);
+        }
+        assert_eq!(canonicalize_level("10 m above gnd"), "10 m above ground");
+        assert_eq!(canonicalize_level("1 km AGL"), "1000 m above ground");
+    }
+
+    #[test]
+    fn test_isobaric_variants() {
+        for level in ["500 mb", "500 hPa", "500mb", "500 millibars", "50000 Pa"] {
+            assert_eq!(canonicalize_level(level), "500 mb", "{}", level);
+        }
+        assert_eq!(canonicalize_level("92500 Pa"), "925 mb");
+    }
+
+    #[test]
+    fn test_mrms_levels() {
+        assert_eq!(canonicalize_level("0 m above MSL"), "0 m above MSL");
+        assert_eq!(
+            canonicalize_level("500 m above mean sea level"),
+            "500 m above MSL"
+        );
+        assert_eq!(canonicalize_level("500m AMSL"), "500 m above MSL");
+        assert_eq!(canonicalize_level("mean sea level"), "mean sea level");
+        assert_eq!(canonicalize_level("MSL"), "mean sea level");
+    }
+
+    #[test]
+    fn test_named_levels() {
+        assert_eq!(canonicalize_level("Surface"), "surface");
+        assert_eq!(canonicalize_level("sfc"), "surface");
+        assert_eq!(
+            canonicalize_level("entire atmosphere (considered as a single layer)"),
+            "entire atmosphere"
+        );
+        assert_eq!(canonicalize_level("atmos col"), "entire atmosphere");
+    }
+
+    #[test]
+    fn test_unrecognized_levels_are_kept() {
+        assert_eq!(canonicalize_level("low cloud layer"), "low cloud layer");
+        assert_eq!(canonicalize_level("clean_ir"), "clean_ir");
+        assert_eq!(canonicalize_level("2 m below ground"), "2 m below ground");
+        assert!(levels_match("Low Cloud Layer", "low cloud layer"));
+    }
+
+    #[test]
+    fn test_levels_match() {
+        assert!(levels_match("2 m above gnd", "2 m above ground"));
+        assert!(levels_match("850 hPa", "850 mb"));
+        assert!(!levels_match("850 mb", "500 mb"));
+        assert!(!levels_match("2 m above ground", "2 m above MSL"));
+        assert!(!levels_match("surface", "2 m above ground"));
+        assert!(levels_match("0.5 m above ground", "0.5 m AGL"));
+    }
+}
@@ -1,21 +1,49 @@
 //! Storage abstractions for weather-wms services.
 //!
 //! Provides unified interfaces for:
-//! - Object storage (MinIO/S3) for grid data
-//! - PostgreSQL for metadata catalog
-//! - Redis for caching
+//! - Object storage (MinIO/S3) for grid data, with retries and a circuit breaker
+//! - PostgreSQL for metadata catalog and benchmark history
+//! - Redis for caching (tiles and API responses)
+//! - Object storage tile archives for pre-rendered tile sets
+//!
+//! The `offline` feature adds an in-memory catalog, a local-directory object
+//! store and a disabled tile cache, so services can run without Postgres,
+//! MinIO or Redis (e.g. in integration tests).
 
+pub mod benchmarks;
 pub mod cache;
 pub mod catalog;
+#[cfg(feature = "offline")]
+mod catalog_memory;
+pub mod catalog_search;
+pub mod level;
 pub mod object_store;
+pub mod resilience;
+pub mod response_cache;
+pub mod tile_archive;
 pub mod tile_memory_cache;
 
 pub use self::object_store::{
     DetailedStorageStats, ObjectStorage, ObjectStorageConfig, StorageStats,
 };
-pub use cache::{CacheKey, TileCache};
+pub use ::object_store::{multipart::PartId, MultipartId};
+pub use benchmarks::{
+    config_hash, BenchmarkStore, CriterionResult, LoadTestQuery, LoadTestRun, NewCriterionResult,
+    NewLoadTestRun, RunTags,
+};
+pub use cache::{CacheKey, KeyNormalization, TileCache, CACHE_KEY_VERSION};
 pub use catalog::{
-    Catalog, CatalogEntry, DatasetInfo, DatasetQuery, ModelStats, ParameterAvailability,
-    ParameterStats, PurgePreview,
+    Catalog, CatalogEntry, ConfigVersion, DatasetInfo, DatasetQuery, ModelStats,
+    ParameterAvailability, ParameterMetadata, ParameterStats, PurgePreview, SourceLineage,
+};
+pub use catalog_search::{
+    CatalogSearchFacets, CatalogSearchHit, CatalogSearchQuery, CatalogSearchResults, FacetCount,
+    ValidTimeRange,
+};
+pub use level::{canonicalize_level, levels_match, CanonicalLevel, LevelKind};
+pub use resilience::{
+    BreakerState, CircuitBreaker, CircuitBreakerConfig, ObjectStoreMetrics, RetryPolicy,
 };
+pub use response_cache::{CachedResponse, ResponseCache};
+pub use tile_archive::{ArchiveKey, ArchiveManifest, TileArchive, TileArchiveWriter};
 pub use tile_memory_cache::{TileMemoryCache, TileMemoryCacheStats};
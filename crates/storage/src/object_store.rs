@@ -1,11 +1,24 @@
 //! Object storage interface for grid data (MinIO/S3 compatible).
 
 use bytes::Bytes;
-use object_store::{aws::AmazonS3Builder, path::Path, ObjectStore};
+use object_store::{
+    aws::AmazonS3Builder,
+    multipart::{MultiPartStore, PartId},
+    path::Path,
+    signer::Signer,
+    MultipartId, ObjectStore, RetryConfig,
+};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tracing::{debug, instrument};
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
 
+use crate::resilience::{
+    is_transient, CircuitBreaker, CircuitBreakerConfig, ObjectStoreMetrics, RetryPolicy,
+};
 use wms_common::{WmsError, WmsResult};
 
 /// Configuration for object storage connection.
@@ -23,6 +36,16 @@ pub struct ObjectStorageConfig {
     pub region: String,
     /// Allow HTTP (for local MinIO)
     pub allow_http: bool,
+    /// Endpoint clients reach the bucket at, used for signed URLs
+    /// (defaults to `endpoint`)
+    #[serde(default)]
+    pub public_endpoint: Option<String>,
+    /// Retries of failed GET, HEAD and PUT requests
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// When to stop sending requests to an unavailable endpoint
+    #[serde(default)]
+    pub circuit_breaker: CircuitBreakerConfig,
 }
 
 impl Default for ObjectStorageConfig {
@@ -34,6 +57,9 @@ impl Default for ObjectStorageConfig {
             secret_access_key: "minioadmin".to_string(),
             region: "us-east-1".to_string(),
             allow_http: true,
+            public_endpoint: None,
+            retry: RetryPolicy::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
         }
     }
 }
@@ -41,42 +67,171 @@ impl Default for ObjectStorageConfig {
 /// Object storage client for weather data.
 pub struct ObjectStorage {
     store: Arc<dyn ObjectStore>,
+    /// None for backends without multipart uploads (local directories)
+    multipart: Option<Arc<dyn MultiPartStore>>,
+    /// None for backends that cannot sign URLs (local directories)
+    signer: Option<Arc<dyn Signer>>,
     bucket: String,
+    /// Endpoint URL (or local directory), labels the metrics
+    endpoint: String,
+    retry: RetryPolicy,
+    breaker: CircuitBreaker,
+    retries: AtomicU64,
+    failures: AtomicU64,
 }
 
 impl ObjectStorage {
     /// Create a new object storage client from config.
     pub fn new(config: &ObjectStorageConfig) -> WmsResult<Self> {
-        let mut builder = AmazonS3Builder::new()
-            .with_endpoint(&config.endpoint)
-            .with_bucket_name(&config.bucket)
-            .with_access_key_id(&config.access_key_id)
-            .with_secret_access_key(&config.secret_access_key)
-            .with_region(&config.region);
-
-        if config.allow_http {
-            builder = builder.with_allow_http(true);
-        }
+        let build = |endpoint: &str| {
+            let mut builder = AmazonS3Builder::new()
+                .with_endpoint(endpoint)
+                .with_bucket_name(&config.bucket)
+                .with_access_key_id(&config.access_key_id)
+                .with_secret_access_key(&config.secret_access_key)
+                .with_region(&config.region)
+                // Retries are done by `request` so they follow `config.retry`
+                // and count towards the circuit breaker
+                .with_retry(RetryConfig {
+                    max_retries: 0,
+                    ..Default::default()
+                });
+
+            if config.allow_http {
+                builder = builder.with_allow_http(true);
+            }
+
+            builder
+                .build()
+                .map_err(|e| WmsError::StorageError(format!("Failed to create S3 client: {}", e)))
+        };
+
+        let store = Arc::new(build(&config.endpoint)?);
+        // Signatures cover the host, so URLs for clients outside the cluster
+        // are signed against the public endpoint
+        let signer: Arc<dyn Signer> = match &config.public_endpoint {
+            Some(endpoint) => Arc::new(build(endpoint)?),
+            None => store.clone(),
+        };
+        Ok(Self {
+            store: store.clone(),
+            multipart: Some(store),
+            signer: Some(signer),
+            bucket: config.bucket.clone(),
+            endpoint: config.endpoint.clone(),
+            retry: config.retry.clone(),
+            breaker: CircuitBreaker::new(config.circuit_breaker.clone()),
+            retries: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        })
+    }
 
-        let store = builder
-            .build()
-            .map_err(|e| WmsError::StorageError(format!("Failed to create S3 client: {}", e)))?;
+    /// Create a client that reads and writes objects under a local directory.
+    ///
+    /// Used by offline mode. Object paths map to files below `root`;
+    /// multipart uploads and signed URLs are not supported.
+    #[cfg(feature = "offline")]
+    pub fn local(root: impl AsRef<std::path::Path>) -> WmsResult<Self> {
+        let root = root.as_ref();
+        let store = object_store::local::LocalFileSystem::new_with_prefix(root).map_err(|e| {
+            WmsError::StorageError(format!("Failed to open {}: {}", root.display(), e))
+        })?;
 
         Ok(Self {
             store: Arc::new(store),
-            bucket: config.bucket.clone(),
+            multipart: None,
+            signer: None,
+            bucket: root.display().to_string(),
+            endpoint: root.display().to_string(),
+            retry: RetryPolicy::default(),
+            breaker: CircuitBreaker::new(CircuitBreakerConfig::default()),
+            retries: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
         })
     }
 
+    fn multipart(&self) -> WmsResult<&dyn MultiPartStore> {
+        self.multipart.as_deref().ok_or_else(|| {
+            WmsError::StorageError("Multipart uploads are not supported by this storage".into())
+        })
+    }
+
+    /// Send an idempotent request, retrying transient failures.
+    ///
+    /// The outer error means the circuit breaker is open and nothing was
+    /// sent; the inner result is the request's after its last attempt.
+    async fn request<T, F, Fut>(
+        &self,
+        path: &str,
+        mut send: F,
+    ) -> WmsResult<Result<T, object_store::Error>>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, object_store::Error>>,
+    {
+        if !self.breaker.allow() {
+            return Err(WmsError::ServiceUnavailable(format!(
+                "Object storage at {} is unavailable (circuit breaker open)",
+                self.endpoint
+            )));
+        }
+
+        let mut attempt = 0;
+        loop {
+            match send().await {
+                Err(e) if is_transient(&e) && attempt < self.retry.max_retries => {
+                    let delay = self.retry.backoff(attempt);
+                    attempt += 1;
+                    self.retries.fetch_add(1, Ordering::Relaxed);
+                    warn!(
+                        path = %path,
+                        attempt = attempt,
+                        max_retries = self.retry.max_retries,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %e,
+                        "Object storage request failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) if is_transient(&e) => {
+                    self.failures.fetch_add(1, Ordering::Relaxed);
+                    self.breaker.record_failure();
+                    return Ok(Err(e));
+                }
+                result => {
+                    self.breaker.record_success();
+                    return Ok(result);
+                }
+            }
+        }
+    }
+
+    /// Whether requests are being sent to the endpoint, i.e. the circuit
+    /// breaker isn't open.
+    pub fn is_available(&self) -> bool {
+        self.breaker.state() != crate::resilience::BreakerState::Open
+    }
+
+    /// Retry and circuit breaker counters.
+    pub fn resilience_metrics(&self) -> ObjectStoreMetrics {
+        ObjectStoreMetrics {
+            endpoint: self.endpoint.clone(),
+            retries: self.retries.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            breaker_state: self.breaker.state(),
+            breaker_trips: self.breaker.trips(),
+            breaker_rejected: self.breaker.rejected(),
+        }
+    }
+
     /// Write bytes to a path in the bucket.
     #[instrument(skip(self, data), fields(bucket = %self.bucket, path = %path))]
     pub async fn put(&self, path: &str, data: Bytes) -> WmsResult<()> {
         let location = Path::from(path);
         debug!(size = data.len(), "Writing object");
 
-        self.store
-            .put(&location, data)
-            .await
+        self.request(path, || self.store.put(&location, data.clone()))
+            .await?
             .map_err(|e| WmsError::StorageError(format!("Failed to write {}: {}", path, e)))?;
 
         Ok(())
@@ -87,30 +242,53 @@ impl ObjectStorage {
     pub async fn get(&self, path: &str) -> WmsResult<Bytes> {
         let location = Path::from(path);
 
-        let result = self
-            .store
-            .get(&location)
-            .await
+        // The body is read inside the retried request, a connection dropped
+        // mid-body fails like one dropped before the response
+        let bytes = self
+            .request(path, || async {
+                self.store.get(&location).await?.bytes().await
+            })
+            .await?
             .map_err(|e| WmsError::StorageError(format!("Failed to read {}: {}", path, e)))?;
 
-        let bytes = result
-            .bytes()
-            .await
-            .map_err(|e| WmsError::StorageError(format!("Failed to read bytes: {}", e)))?;
-
         debug!(size = bytes.len(), "Read object");
         Ok(bytes)
     }
 
+    /// Read bytes from a path, returning `None` if the object does not exist.
+    #[instrument(skip(self), fields(bucket = %self.bucket, path = %path))]
+    pub async fn get_if_exists(&self, path: &str) -> WmsResult<Option<Bytes>> {
+        let location = Path::from(path);
+
+        let result = self
+            .request(path, || async {
+                self.store.get(&location).await?.bytes().await
+            })
+            .await?;
+
+        let bytes = match result {
+            Ok(bytes) => bytes,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => {
+                return Err(WmsError::StorageError(format!(
+                    "Failed to read {}: {}",
+                    path, e
+                )))
+            }
+        };
+
+        debug!(size = bytes.len(), "Read object");
+        Ok(Some(bytes))
+    }
+
     /// Read a byte range from a path.
     #[instrument(skip(self), fields(bucket = %self.bucket, path = %path))]
     pub async fn get_range(&self, path: &str, start: usize, end: usize) -> WmsResult<Bytes> {
         let location = Path::from(path);
 
         let result = self
-            .store
-            .get_range(&location, start..end)
-            .await
+            .request(path, || self.store.get_range(&location, start..end))
+            .await?
             .map_err(|e| WmsError::StorageError(format!("Failed to read range {}: {}", path, e)))?;
 
         Ok(result)
@@ -120,7 +298,7 @@ impl ObjectStorage {
     pub async fn exists(&self, path: &str) -> WmsResult<bool> {
         let location = Path::from(path);
 
-        match self.store.head(&location).await {
+        match self.request(path, || self.store.head(&location)).await? {
             Ok(_) => Ok(true),
             Err(object_store::Error::NotFound { .. }) => Ok(false),
             Err(e) => Err(WmsError::StorageError(format!(
@@ -172,13 +350,107 @@ impl ObjectStorage {
     pub async fn head(&self, path: &str) -> WmsResult<u64> {
         let location = Path::from(path);
 
-        let meta = self.store.head(&location).await.map_err(|e| {
-            WmsError::StorageError(format!("Failed to get metadata for {}: {}", path, e))
-        })?;
+        let meta = self
+            .request(path, || self.store.head(&location))
+            .await?
+            .map_err(|e| {
+                WmsError::StorageError(format!("Failed to get metadata for {}: {}", path, e))
+            })?;
 
         Ok(meta.size as u64)
     }
 
+    /// Start a multipart upload, returning its upload ID.
+    #[instrument(skip(self), fields(bucket = %self.bucket, path = %path))]
+    pub async fn create_multipart(&self, path: &str) -> WmsResult<MultipartId> {
+        let location = Path::from(path);
+
+        self.multipart()?
+            .create_multipart(&location)
+            .await
+            .map_err(|e| {
+                WmsError::StorageError(format!("Failed to start multipart upload {}: {}", path, e))
+            })
+    }
+
+    /// Upload part `part_idx` (0-based) of a multipart upload.
+    ///
+    /// Parts may be uploaded concurrently and in any order. All parts but the
+    /// last must be at least 5 MiB.
+    #[instrument(skip(self, data), fields(bucket = %self.bucket, path = %path))]
+    pub async fn put_part(
+        &self,
+        path: &str,
+        upload_id: &MultipartId,
+        part_idx: usize,
+        data: Bytes,
+    ) -> WmsResult<PartId> {
+        let location = Path::from(path);
+        debug!(part = part_idx, size = data.len(), "Writing part");
+
+        self.multipart()?
+            .put_part(&location, upload_id, part_idx, data)
+            .await
+            .map_err(|e| {
+                WmsError::StorageError(format!(
+                    "Failed to write part {} of {}: {}",
+                    part_idx, path, e
+                ))
+            })
+    }
+
+    /// Complete a multipart upload. `parts` must be ordered by part index.
+    #[instrument(skip(self, parts), fields(bucket = %self.bucket, path = %path))]
+    pub async fn complete_multipart(
+        &self,
+        path: &str,
+        upload_id: &MultipartId,
+        parts: Vec<PartId>,
+    ) -> WmsResult<()> {
+        let location = Path::from(path);
+
+        self.multipart()?
+            .complete_multipart(&location, upload_id, parts)
+            .await
+            .map_err(|e| {
+                WmsError::StorageError(format!(
+                    "Failed to complete multipart upload {}: {}",
+                    path, e
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    /// Abort a multipart upload, discarding any parts already uploaded.
+    #[instrument(skip(self), fields(bucket = %self.bucket, path = %path))]
+    pub async fn abort_multipart(&self, path: &str, upload_id: &MultipartId) -> WmsResult<()> {
+        let location = Path::from(path);
+
+        self.multipart()?
+            .abort_multipart(&location, upload_id)
+            .await
+            .map_err(|e| {
+                WmsError::StorageError(format!("Failed to abort multipart upload {}: {}", path, e))
+            })
+    }
+
+    /// Presigned GET URL for an object, valid for `expires_in`.
+    ///
+    /// Lets clients without credentials download the object directly from
+    /// the bucket.
+    pub async fn signed_url(&self, path: &str, expires_in: Duration) -> WmsResult<String> {
+        let location = Path::from(path);
+        let signer = self.signer.as_ref().ok_or_else(|| {
+            WmsError::StorageError("Signed URLs are not supported by this storage".into())
+        })?;
+        let url = signer
+            .signed_url(Method::GET, &location, expires_in)
+            .await
+            .map_err(|e| WmsError::StorageError(format!("Failed to sign {}: {}", path, e)))?;
+        Ok(url.to_string())
+    }
+
     /// Delete an object.
     #[instrument(skip(self), fields(bucket = %self.bucket, path = %path))]
     pub async fn delete(&self, path: &str) -> WmsResult<()> {
@@ -353,4 +625,27 @@ mod tests {
             "grids/gfs/temperature_2m/20240115/12/006/42.bin"
         );
     }
+    #[cfg(feature = "offline")]
+    #[tokio::test]
+    async fn test_local_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = ObjectStorage::local(dir.path()).unwrap();
+
+        storage
+            .put("grids/gfs/a.bin", Bytes::from_static(b"abc"))
+            .await
+            .unwrap();
+        assert!(dir.path().join("grids/gfs/a.bin").exists());
+        assert_eq!(storage.get("grids/gfs/a.bin").await.unwrap(), "abc");
+        assert_eq!(
+            storage.list("grids").await.unwrap(),
+            vec!["grids/gfs/a.bin"]
+        );
+
+        assert!(storage.create_multipart("grids/gfs/b.bin").await.is_err());
+        assert!(storage
+            .signed_url("grids/gfs/a.bin", Duration::from_secs(60))
+            .await
+            .is_err());
+    }
 }
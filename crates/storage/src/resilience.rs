@@ -0,0 +1,408 @@
+//! Retries and circuit breaking for object storage requests.
+//!
+//! Idempotent requests (GET, HEAD, PUT) that fail with a transient error,
+//! such as a 503 from a MinIO node restarting, are retried with exponential
+//! backoff and full jitter. Errors that retrying can't fix (not found,
+//! precondition failed, ...) are returned straight away.
+//!
+//! Each [`crate::ObjectStorage`] has one [`CircuitBreaker`] for its endpoint.
+//! After `failure_threshold` consecutive requests fail even after retries the
+//! breaker opens: requests then fail fast with `ServiceUnavailable` instead
+//! of queueing behind timeouts, and services report themselves unready. Once
+//! `open_duration` has passed a single probe request is let through; its
+//! success closes the breaker, its failure keeps it open for another period.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How failed requests are retried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (0 disables retries)
+    pub max_retries: u32,
+    /// Backoff ceiling for the first retry, doubled on each further retry
+    pub initial_backoff: Duration,
+    /// Largest backoff ceiling
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Load the policy from `S3_MAX_RETRIES`, `S3_RETRY_BACKOFF_MS` and
+    /// `S3_RETRY_MAX_BACKOFF_MS`.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+
+        if let Some(n) = env_parse("S3_MAX_RETRIES") {
+            policy.max_retries = n;
+        }
+        if let Some(ms) = env_parse("S3_RETRY_BACKOFF_MS") {
+            policy.initial_backoff = Duration::from_millis(ms);
+        }
+        if let Some(ms) = env_parse("S3_RETRY_MAX_BACKOFF_MS") {
+            policy.max_backoff = Duration::from_millis(ms);
+        }
+
+        policy
+    }
+
+    /// Backoff ceiling before retry `attempt` (0-based).
+    pub fn backoff_ceiling(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+
+    /// Delay before retry `attempt`: uniformly random up to the ceiling, so
+    /// clients that failed together don't retry together.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff_ceiling(attempt)
+            .mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+/// When the circuit breaker opens and for how long.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failed requests that open the breaker (0 disables it)
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before probing the endpoint
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreakerConfig {
+    /// Load the config from `S3_BREAKER_FAILURES` and `S3_BREAKER_OPEN_SECS`.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Some(n) = env_parse("S3_BREAKER_FAILURES") {
+            config.failure_threshold = n;
+        }
+        if let Some(secs) = env_parse("S3_BREAKER_OPEN_SECS") {
+            config.open_duration = Duration::from_secs(secs);
+        }
+
+        config
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().and_then(|v| v.parse().ok())
+}
+
+/// State of a circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Requests go through
+    Closed,
+    /// Requests fail fast
+    Open,
+    /// A probe request decides whether to close or reopen
+    HalfOpen,
+}
+
+impl BreakerState {
+    /// Value of the `state` gauge: 0 closed, 1 open, 2 half-open.
+    pub fn as_gauge(self) -> u8 {
+        match self {
+            BreakerState::Closed => 0,
+            BreakerState::Open => 1,
+            BreakerState::HalfOpen => 2,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    /// When the breaker opened, or when the current probe was let through
+    since: Instant,
+}
+
+/// Per-endpoint circuit breaker.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Mutex<BreakerInner>,
+    trips: AtomicU64,
+    rejected: AtomicU64,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                since: Instant::now(),
+            }),
+            trips: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether a request may be sent now. Lets a single probe through once
+    /// the breaker has been open for `open_duration`; a probe whose result
+    /// never arrives (e.g. a cancelled request) is replaced after another
+    /// `open_duration`.
+    pub fn allow(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let allowed = match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::Open | BreakerState::HalfOpen => {
+                if inner.since.elapsed() >= self.config.open_duration {
+                    inner.state = BreakerState::HalfOpen;
+                    inner.since = Instant::now();
+                    true
+                } else {
+                    false
+                }
+            }
+        };
+        if !allowed {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    /// Record a request that reached the endpoint.
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.state = BreakerState::Closed;
+        inner.consecutive_failures = 0;
+    }
+
+    /// Record a request that failed after all its retries.
+    pub fn record_failure(&self) {
+        if self.config.failure_threshold == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        let trip = match inner.state {
+            BreakerState::Closed => inner.consecutive_failures >= self.config.failure_threshold,
+            BreakerState::HalfOpen => true,
+            BreakerState::Open => false,
+        };
+        if trip {
+            inner.state = BreakerState::Open;
+            inner.since = Instant::now();
+            self.trips.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Current state. An open breaker whose period has passed reads as
+    /// half-open until the next request probes the endpoint.
+    pub fn state(&self) -> BreakerState {
+        let inner = self.inner.lock().unwrap();
+        match inner.state {
+            BreakerState::Open if inner.since.elapsed() >= self.config.open_duration => {
+                BreakerState::HalfOpen
+            }
+            state => state,
+        }
+    }
+
+    /// Times the breaker has opened.
+    pub fn trips(&self) -> u64 {
+        self.trips.load(Ordering::Relaxed)
+    }
+
+    /// Requests failed fast while the breaker was open.
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+}
+
+/// Whether retrying a failed object store request may succeed.
+pub fn is_transient(error: &object_store::Error) -> bool {
+    !matches!(
+        error,
+        object_store::Error::NotFound { .. }
+            | object_store::Error::InvalidPath { .. }
+            | object_store::Error::NotSupported { .. }
+            | object_store::Error::AlreadyExists { .. }
+            | object_store::Error::Precondition { .. }
+            | object_store::Error::NotModified { .. }
+            | object_store::Error::NotImplemented
+            | object_store::Error::UnknownConfigurationKey { .. }
+    )
+}
+
+/// Retry and breaker counters of one object storage endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectStoreMetrics {
+    /// Endpoint URL (or local directory)
+    pub endpoint: String,
+    /// Requests retried after a transient failure
+    pub retries: u64,
+    /// Requests that still failed after their last retry
+    pub failures: u64,
+    /// Current breaker state
+    pub breaker_state: BreakerState,
+    /// Times the breaker has opened
+    pub breaker_trips: u64,
+    /// Requests failed fast by the open breaker
+    pub breaker_rejected: u64,
+}
+
+impl ObjectStoreMetrics {
+    /// Render as Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let endpoint = self.endpoint.replace('\\', "\\\\").replace('"', "\\\"");
+        format!(
+            "# HELP object_store_retries_total Object storage requests retried after a transient failure\n\
+             # TYPE object_store_retries_total counter\n\
+             object_store_retries_total{{endpoint=\"{endpoint}\"}} {}\n\
+             # HELP object_store_failures_total Object storage requests that failed after all retries\n\
+             # TYPE object_store_failures_total counter\n\
+             object_store_failures_total{{endpoint=\"{endpoint}\"}} {}\n\
+             # HELP object_store_breaker_state Circuit breaker state (0 closed, 1 open, 2 half-open)\n\
+             # TYPE object_store_breaker_state gauge\n\
+             object_store_breaker_state{{endpoint=\"{endpoint}\"}} {}\n\
+             # HELP object_store_breaker_trips_total Times the circuit breaker opened\n\
+             # TYPE object_store_breaker_trips_total counter\n\
+             object_store_breaker_trips_total{{endpoint=\"{endpoint}\"}} {}\n\
+             # HELP object_store_breaker_rejected_total Requests failed fast by the open circuit breaker\n\
+             # TYPE object_store_breaker_rejected_total counter\n\
+             object_store_breaker_rejected_total{{endpoint=\"{endpoint}\"}} {}\n",
+            self.retries,
+            self.failures,
+            self.breaker_state.as_gauge(),
+            self.breaker_trips,
+            self.breaker_rejected,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+        };
+        assert_eq!(policy.backoff_ceiling(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff_ceiling(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff_ceiling(3), Duration::from_millis(500));
+        assert_eq!(policy.backoff_ceiling(40), Duration::from_millis(500));
+
+        for attempt in 0..5 {
+            assert!(policy.backoff(attempt) <= policy.backoff_ceiling(attempt));
+        }
+    }
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            open_duration: Duration::from_secs(60),
+        });
+
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert!(breaker.allow());
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(!breaker.allow());
+        assert_eq!(breaker.trips(), 1);
+        assert_eq!(breaker.rejected(), 1);
+    }
+
+    #[test]
+    fn test_breaker_probe_closes_or_reopens() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 1,
+            open_duration: Duration::ZERO,
+        });
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        // A failed probe reopens the breaker straight away
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert_eq!(breaker.trips(), 2);
+
+        // A successful probe closes it
+        assert!(breaker.allow());
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_disabled_breaker_never_opens() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 0,
+            open_duration: Duration::from_secs(60),
+        });
+        for _ in 0..100 {
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        let not_found = object_store::Error::NotFound {
+            path: "a".into(),
+            source: "missing".into(),
+        };
+        let generic = object_store::Error::Generic {
+            store: "S3",
+            source: "503 Service Unavailable".into(),
+        };
+        assert!(!is_transient(&not_found));
+        assert!(is_transient(&generic));
+    }
+
+    #[test]
+    fn test_metrics_prometheus() {
+        let metrics = ObjectStoreMetrics {
+            endpoint: "http://minio:9000".to_string(),
+            retries: 4,
+            failures: 1,
+            breaker_state: BreakerState::Open,
+            breaker_trips: 1,
+            breaker_rejected: 7,
+        };
+        let text = metrics.to_prometheus();
+        let label = "{endpoint=\"http://minio:9000\"}";
+        assert!(text.contains(&format!("object_store_retries_total{} 4\n", label)));
+        assert!(text.contains(&format!("object_store_breaker_state{} 1\n", label)));
+        assert!(text.contains(&format!("object_store_breaker_rejected_total{} 7\n", label)));
+    }
+}
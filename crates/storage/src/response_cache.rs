@@ -0,0 +1,112 @@
+//! Redis cache for serialized API responses.
+//!
+//! Stores a response body with its content type under a caller-built key.
+//! Services share the tile cache's Redis instance, so keys are namespaced
+//! by the caller (e.g. `edr:v1:...`).
+
+use bytes::Bytes;
+use redis::{aio::MultiplexedConnection, AsyncCommands, Client};
+use std::time::Duration;
+
+use wms_common::{WmsError, WmsResult};
+
+/// Separates the content type from the body in a stored value.
+const SEPARATOR: u8 = b'\n';
+
+/// A cached response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedResponse {
+    pub content_type: String,
+    pub body: Bytes,
+}
+
+impl CachedResponse {
+    /// Encode as a single Redis value: the content type, a newline, the body.
+    fn encode(&self) -> Vec<u8> {
+        let mut value = Vec::with_capacity(self.content_type.len() + 1 + self.body.len());
+        value.extend_from_slice(self.content_type.as_bytes());
+        value.push(SEPARATOR);
+        value.extend_from_slice(&self.body);
+        value
+    }
+
+    fn decode(value: Vec<u8>) -> Option<Self> {
+        let split = value.iter().position(|&b| b == SEPARATOR)?;
+        let content_type = std::str::from_utf8(&value[..split]).ok()?.to_string();
+        let mut body = Bytes::from(value);
+        Some(Self {
+            content_type,
+            body: body.split_off(split + 1),
+        })
+    }
+}
+
+/// Redis response cache client.
+///
+/// The multiplexed connection is cloned per call, so the cache can be
+/// shared without a lock.
+#[derive(Clone)]
+pub struct ResponseCache {
+    conn: MultiplexedConnection,
+}
+
+impl ResponseCache {
+    /// Connect to Redis (e.g., "redis://redis:6379").
+    pub async fn connect(redis_url: &str) -> WmsResult<Self> {
+        let client = Client::open(redis_url)
+            .map_err(|e| WmsError::CacheError(format!("Redis connection failed: {}", e)))?;
+
+        let conn = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| WmsError::CacheError(format!("Redis connection failed: {}", e)))?;
+
+        Ok(Self { conn })
+    }
+
+    /// Get a cached response.
+    pub async fn get(&self, key: &str) -> WmsResult<Option<CachedResponse>> {
+        let value: Option<Vec<u8>> = self
+            .conn
+            .clone()
+            .get(key)
+            .await
+            .map_err(|e| WmsError::CacheError(format!("Cache get failed: {}", e)))?;
+
+        Ok(value.and_then(CachedResponse::decode))
+    }
+
+    /// Store a response for `ttl`.
+    pub async fn set(&self, key: &str, response: &CachedResponse, ttl: Duration) -> WmsResult<()> {
+        self.conn
+            .clone()
+            .set_ex::<_, _, ()>(key, response.encode(), ttl.as_secs().max(1))
+            .await
+            .map_err(|e| WmsError::CacheError(format!("Cache set failed: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_round_trip() {
+        let response = CachedResponse {
+            content_type: "application/vnd.cov+json".to_string(),
+            body: Bytes::from_static(b"{\n  \"type\": \"Coverage\"\n}"),
+        };
+        let decoded = CachedResponse::decode(response.encode()).unwrap();
+        assert_eq!(decoded, response);
+    }
+
+    #[test]
+    fn test_decode_rejects_values_without_content_type() {
+        assert!(CachedResponse::decode(b"no separator".to_vec()).is_none());
+
+        let empty_body = CachedResponse::decode(b"text/plain\n".to_vec()).unwrap();
+        assert!(empty_body.body.is_empty());
+    }
+}
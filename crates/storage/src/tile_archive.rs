@@ -0,0 +1,402 @@
+//! Persisted tile archives in object storage.
+//!
+//! Rendered tile sets are published per (layer, style, time) in a static
+//! z/x/y layout, so archived forecasts can be served without rendering, or
+//! straight from the bucket by a CDN:
+//!
+//! ```text
+//! {prefix}/{layer}/{style}/{time}/manifest.json
+//! {prefix}/{layer}/{style}/{time}/{tile_matrix_set}/{z}/{x}/{y}.png
+//! ```
+//!
+//! `x` is the tile column and `y` the tile row counted from the top, as in
+//! WMTS and XYZ URLs. The manifest is written after all tiles, so readers
+//! only see complete tile sets.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use crate::ObjectStorage;
+use wms_common::{TileCoord, WmsError, WmsResult};
+
+/// Identifies one archived tile set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ArchiveKey {
+    pub layer: String,
+    pub style: String,
+    /// Time key, e.g. `20240115T1200Z_f006` for a forecast hour of a run
+    pub time: String,
+}
+
+impl ArchiveKey {
+    /// Key for one forecast hour of a model run.
+    pub fn forecast(layer: &str, style: &str, run: DateTime<Utc>, forecast_hour: u32) -> Self {
+        Self {
+            layer: layer.to_string(),
+            style: style.to_string(),
+            time: format!("{}_f{:03}", run.format("%Y%m%dT%H%MZ"), forecast_hour),
+        }
+    }
+
+    /// Key for an observation time.
+    pub fn observation(layer: &str, style: &str, time: DateTime<Utc>) -> Self {
+        Self {
+            layer: layer.to_string(),
+            style: style.to_string(),
+            time: time.format("%Y%m%dT%H%M%SZ").to_string(),
+        }
+    }
+
+    /// Qualify the key with a vertical level (e.g. "500 mb" -> `_500_mb`).
+    pub fn with_elevation(mut self, elevation: Option<&str>) -> Self {
+        if let Some(elevation) = elevation {
+            let elevation: String = elevation
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            self.time = format!("{}_{}", self.time, elevation);
+        }
+        self
+    }
+}
+
+/// Metadata for a published tile set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub layer: String,
+    pub style: String,
+    pub time: String,
+    pub tile_matrix_set: String,
+    /// Model run the tiles were rendered from (None for observations)
+    pub reference_time: Option<DateTime<Utc>>,
+    pub min_zoom: u32,
+    pub max_zoom: u32,
+    pub tile_count: u64,
+    pub total_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ArchiveManifest {
+    /// Whether the archive holds tiles for a zoom level of a tile matrix set.
+    pub fn covers(&self, tile_matrix_set: &str, zoom: u32) -> bool {
+        self.tile_matrix_set == tile_matrix_set && (self.min_zoom..=self.max_zoom).contains(&zoom)
+    }
+}
+
+/// Read and publish tile archives.
+///
+/// Manifest lookups (including misses) are cached for `manifest_ttl`, so
+/// requests for times that were never archived don't hit object storage
+/// for every tile.
+pub struct TileArchive {
+    storage: Arc<ObjectStorage>,
+    prefix: String,
+    manifest_ttl: Duration,
+    manifests: RwLock<HashMap<ArchiveKey, CachedManifest>>,
+}
+
+struct CachedManifest {
+    manifest: Option<Arc<ArchiveManifest>>,
+    fetched_at: Instant,
+}
+
+impl TileArchive {
+    /// Create an archive rooted at `prefix` in the bucket.
+    pub fn new(storage: Arc<ObjectStorage>, prefix: &str, manifest_ttl: Duration) -> Self {
+        Self {
+            storage,
+            prefix: prefix.trim_matches('/').to_string(),
+            manifest_ttl,
+            manifests: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Path of the tile set directory.
+    /// Format: {prefix}/{layer}/{style}/{time}
+    pub fn archive_path(&self, key: &ArchiveKey) -> String {
+        format!("{}/{}/{}/{}", self.prefix, key.layer, key.style, key.time)
+    }
+
+    /// Path of the tile set manifest.
+    pub fn manifest_path(&self, key: &ArchiveKey) -> String {
+        format!("{}/manifest.json", self.archive_path(key))
+    }
+
+    /// Path of a single tile.
+    /// Format: {prefix}/{layer}/{style}/{time}/{tile_matrix_set}/{z}/{x}/{y}.png
+    pub fn tile_path(&self, key: &ArchiveKey, tile_matrix_set: &str, coord: &TileCoord) -> String {
+        format!(
+            "{}/{}/{}/{}/{}.png",
+            self.archive_path(key),
+            tile_matrix_set,
+            coord.z,
+            coord.x,
+            coord.y
+        )
+    }
+
+    /// Get the manifest of a published tile set.
+    pub async fn manifest(&self, key: &ArchiveKey) -> WmsResult<Option<Arc<ArchiveManifest>>> {
+        {
+            let manifests = self.manifests.read().await;
+            if let Some(cached) = manifests.get(key) {
+                if cached.fetched_at.elapsed() < self.manifest_ttl {
+                    return Ok(cached.manifest.clone());
+                }
+            }
+        }
+
+        let manifest = match self.storage.get_if_exists(&self.manifest_path(key)).await? {
+            Some(data) => Some(Arc::new(serde_json::from_slice(&data).map_err(|e| {
+                WmsError::StorageError(format!("Invalid archive manifest: {}", e))
+            })?)),
+            None => None,
+        };
+
+        self.manifests.write().await.insert(
+            key.clone(),
+            CachedManifest {
+                manifest: manifest.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+
+        Ok(manifest)
+    }
+
+    /// Read an archived tile.
+    ///
+    /// Returns `None` if the tile set is not published, does not cover the
+    /// tile's zoom level, or the tile itself was not archived.
+    pub async fn get_tile(
+        &self,
+        key: &ArchiveKey,
+        tile_matrix_set: &str,
+        coord: &TileCoord,
+    ) -> WmsResult<Option<Bytes>> {
+        match self.manifest(key).await? {
+            Some(manifest) if manifest.covers(tile_matrix_set, coord.z) => {
+                self.storage
+                    .get_if_exists(&self.tile_path(key, tile_matrix_set, coord))
+                    .await
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Start writing a tile set.
+    ///
+    /// Tiles become visible to readers once the writer is passed to
+    /// [`TileArchive::publish`].
+    pub fn writer(
+        &self,
+        key: ArchiveKey,
+        tile_matrix_set: &str,
+        reference_time: Option<DateTime<Utc>>,
+    ) -> TileArchiveWriter {
+        TileArchiveWriter {
+            storage: self.storage.clone(),
+            tile_root: format!("{}/{}", self.archive_path(&key), tile_matrix_set),
+            key,
+            tile_matrix_set: tile_matrix_set.to_string(),
+            reference_time,
+            tile_count: AtomicU64::new(0),
+            total_bytes: AtomicU64::new(0),
+            min_zoom: AtomicU32::new(u32::MAX),
+            max_zoom: AtomicU32::new(0),
+        }
+    }
+
+    /// Write the manifest for a finished tile set.
+    ///
+    /// Returns `None` without writing anything if no tiles were written.
+    pub async fn publish(&self, writer: &TileArchiveWriter) -> WmsResult<Option<ArchiveManifest>> {
+        let tile_count = writer.tile_count.load(Ordering::Relaxed);
+        if tile_count == 0 {
+            return Ok(None);
+        }
+
+        let manifest = ArchiveManifest {
+            layer: writer.key.layer.clone(),
+            style: writer.key.style.clone(),
+            time: writer.key.time.clone(),
+            tile_matrix_set: writer.tile_matrix_set.clone(),
+            reference_time: writer.reference_time,
+            min_zoom: writer.min_zoom.load(Ordering::Relaxed),
+            max_zoom: writer.max_zoom.load(Ordering::Relaxed),
+            tile_count,
+            total_bytes: writer.total_bytes.load(Ordering::Relaxed),
+            created_at: Utc::now(),
+        };
+
+        let json = serde_json::to_vec_pretty(&manifest).map_err(|e| {
+            WmsError::StorageError(format!("Failed to serialize archive manifest: {}", e))
+        })?;
+        self.storage
+            .put(&self.manifest_path(&writer.key), Bytes::from(json))
+            .await?;
+
+        self.manifests.write().await.insert(
+            writer.key.clone(),
+            CachedManifest {
+                manifest: Some(Arc::new(manifest.clone())),
+                fetched_at: Instant::now(),
+            },
+        );
+
+        info!(
+            layer = %manifest.layer,
+            style = %manifest.style,
+            time = %manifest.time,
+            tiles = manifest.tile_count,
+            bytes = manifest.total_bytes,
+            "Published tile archive"
+        );
+        Ok(Some(manifest))
+    }
+
+    /// Delete a tile set. Returns the number of objects deleted.
+    pub async fn delete(&self, key: &ArchiveKey) -> WmsResult<u64> {
+        // Remove the manifest first so readers stop using the tiles
+        self.storage.delete(&self.manifest_path(key)).await?;
+        self.manifests.write().await.remove(key);
+
+        let prefix = format!("{}/", self.archive_path(key));
+        Ok(self.storage.delete_prefix(&prefix).await? + 1)
+    }
+}
+
+/// Writes the tiles of one tile set.
+///
+/// Safe to share between concurrent render tasks.
+pub struct TileArchiveWriter {
+    storage: Arc<ObjectStorage>,
+    tile_root: String,
+    key: ArchiveKey,
+    tile_matrix_set: String,
+    reference_time: Option<DateTime<Utc>>,
+    tile_count: AtomicU64,
+    total_bytes: AtomicU64,
+    min_zoom: AtomicU32,
+    max_zoom: AtomicU32,
+}
+
+impl TileArchiveWriter {
+    /// Key of the tile set being written.
+    pub fn key(&self) -> &ArchiveKey {
+        &self.key
+    }
+
+    /// Number of tiles written so far.
+    pub fn tile_count(&self) -> u64 {
+        self.tile_count.load(Ordering::Relaxed)
+    }
+
+    /// Write one PNG tile.
+    pub async fn write_tile(&self, coord: &TileCoord, data: Bytes) -> WmsResult<()> {
+        let size = data.len() as u64;
+        let path = format!("{}/{}/{}/{}.png", self.tile_root, coord.z, coord.x, coord.y);
+        self.storage.put(&path, data).await?;
+
+        self.tile_count.fetch_add(1, Ordering::Relaxed);
+        self.total_bytes.fetch_add(size, Ordering::Relaxed);
+        self.min_zoom.fetch_min(coord.z, Ordering::Relaxed);
+        self.max_zoom.fetch_max(coord.z, Ordering::Relaxed);
+        debug!(path = %path, size = size, "Archived tile");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ObjectStorageConfig;
+    use chrono::TimeZone;
+
+    fn archive() -> TileArchive {
+        let storage = Arc::new(ObjectStorage::new(&ObjectStorageConfig::default()).unwrap());
+        TileArchive::new(storage, "/archives/", Duration::from_secs(60))
+    }
+
+    #[test]
+    fn test_archive_keys() {
+        let run = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let key = ArchiveKey::forecast("gfs_TMP", "temperature", run, 6);
+        assert_eq!(key.time, "20240115T1200Z_f006");
+
+        let key = key.with_elevation(Some("500 mb"));
+        assert_eq!(key.time, "20240115T1200Z_f006_500_mb");
+
+        let obs = Utc.with_ymd_and_hms(2024, 1, 15, 12, 5, 30).unwrap();
+        let key = ArchiveKey::observation("goes18_CMI_C13", "ir", obs).with_elevation(None);
+        assert_eq!(key.time, "20240115T120530Z");
+    }
+
+    #[test]
+    fn test_archive_paths() {
+        let archive = archive();
+        let run = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let key = ArchiveKey::forecast("gfs_TMP", "temperature", run, 6);
+
+        assert_eq!(
+            archive.manifest_path(&key),
+            "archives/gfs_TMP/temperature/20240115T1200Z_f006/manifest.json"
+        );
+        assert_eq!(
+            archive.tile_path(&key, "WebMercatorQuad", &TileCoord::new(4, 3, 5)),
+            "archives/gfs_TMP/temperature/20240115T1200Z_f006/WebMercatorQuad/4/3/5.png"
+        );
+
+        let writer = archive.writer(key.clone(), "WebMercatorQuad", Some(run));
+        assert_eq!(
+            writer.tile_root,
+            "archives/gfs_TMP/temperature/20240115T1200Z_f006/WebMercatorQuad"
+        );
+        assert_eq!(writer.tile_count(), 0);
+    }
+
+    #[test]
+    fn test_manifest_covers() {
+        let manifest = ArchiveManifest {
+            layer: "gfs_TMP".to_string(),
+            style: "temperature".to_string(),
+            time: "20240115T1200Z_f006".to_string(),
+            tile_matrix_set: "WebMercatorQuad".to_string(),
+            reference_time: None,
+            min_zoom: 0,
+            max_zoom: 4,
+            tile_count: 341,
+            total_bytes: 1024,
+            created_at: Utc::now(),
+        };
+
+        assert!(manifest.covers("WebMercatorQuad", 0));
+        assert!(manifest.covers("WebMercatorQuad", 4));
+        assert!(!manifest.covers("WebMercatorQuad", 5));
+        assert!(!manifest.covers("WorldCRS84Quad", 2));
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        let parsed: ArchiveManifest = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, manifest);
+    }
+
+    #[tokio::test]
+    async fn test_publish_empty_writer_is_noop() {
+        let archive = archive();
+        let run = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let writer = archive.writer(
+            ArchiveKey::forecast("gfs_TMP", "temperature", run, 0),
+            "WebMercatorQuad",
+            Some(run),
+        );
+
+        assert_eq!(archive.publish(&writer).await.unwrap(), None);
+    }
+}
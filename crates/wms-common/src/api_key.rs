@@ -0,0 +1,210 @@
+//! API key identification and per-key hourly usage budgets.
+//!
+//! Shared by the WMS and EDR services so clients are identified the same way
+//! everywhere. Requests without a key are grouped under a single anonymous key.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use thiserror::Error;
+
+/// HTTP header carrying the API key.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Query parameter carrying the API key (for clients that can't set headers).
+pub const API_KEY_QUERY_PARAM: &str = "api_key";
+
+/// Key used for requests that don't identify themselves.
+pub const ANONYMOUS_KEY: &str = "anonymous";
+
+/// Number of tracked keys above which stale hourly entries are pruned.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Client API key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    /// Key shared by all unidentified clients.
+    pub fn anonymous() -> Self {
+        Self(ANONYMOUS_KEY.to_string())
+    }
+
+    /// Resolve the key for a request from its header and query parameter values.
+    ///
+    /// The header takes precedence; blank values are ignored. Falls back to
+    /// [`ApiKey::anonymous`] if neither is present.
+    pub fn resolve(header: Option<&str>, query: Option<&str>) -> Self {
+        header
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .or_else(|| query.map(str::trim).filter(|k| !k.is_empty()))
+            .map(Self::new)
+            .unwrap_or_else(Self::anonymous)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_anonymous(&self) -> bool {
+        self.0 == ANONYMOUS_KEY
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Error returned when a request would exceed the key's hourly budget.
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error("Hourly budget exhausted for API key '{key}': request costs {cost}, {remaining} of {budget} remaining (resets in {resets_in_secs}s)")]
+pub struct BudgetExceeded {
+    pub key: String,
+    /// Cost of the rejected request
+    pub cost: u64,
+    /// Budget left in the current hour
+    pub remaining: u64,
+    /// Budget per hour
+    pub budget: u64,
+    /// Seconds until the budget resets
+    pub resets_in_secs: u64,
+}
+
+/// Usage of one key within one clock hour.
+#[derive(Debug, Clone, Copy)]
+struct HourlyUsage {
+    /// Hours since the Unix epoch
+    hour: i64,
+    consumed: u64,
+}
+
+/// Tracks consumed cost per API key, resetting at the top of every hour.
+///
+/// The budget itself is passed per call so different endpoints or collections
+/// can enforce different budgets against the same ledger.
+#[derive(Debug, Default)]
+pub struct HourlyBudgetTracker {
+    usage: Mutex<HashMap<ApiKey, HourlyUsage>>,
+}
+
+impl HourlyBudgetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Charge `cost` against `key` if it fits in the hourly `budget`.
+    ///
+    /// Returns the budget remaining after the charge. Rejected requests are not
+    /// charged.
+    pub fn try_consume(
+        &self,
+        key: &ApiKey,
+        cost: u64,
+        budget: u64,
+        now: DateTime<Utc>,
+    ) -> Result<u64, BudgetExceeded> {
+        let hour = hour_of(now);
+        let mut usage = self.usage.lock().unwrap();
+
+        if usage.len() > PRUNE_THRESHOLD {
+            usage.retain(|_, u| u.hour == hour);
+        }
+
+        let entry = usage
+            .entry(key.clone())
+            .or_insert(HourlyUsage { hour, consumed: 0 });
+        if entry.hour != hour {
+            *entry = HourlyUsage { hour, consumed: 0 };
+        }
+
+        let remaining = budget.saturating_sub(entry.consumed);
+        if cost > remaining {
+            return Err(BudgetExceeded {
+                key: key.to_string(),
+                cost,
+                remaining,
+                budget,
+                resets_in_secs: secs_until_next_hour(now),
+            });
+        }
+
+        entry.consumed += cost;
+        Ok(remaining - cost)
+    }
+
+    /// Cost consumed by `key` in the current hour.
+    pub fn consumed(&self, key: &ApiKey, now: DateTime<Utc>) -> u64 {
+        let hour = hour_of(now);
+        self.usage
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|u| u.hour == hour)
+            .map(|u| u.consumed)
+            .unwrap_or(0)
+    }
+}
+
+fn hour_of(now: DateTime<Utc>) -> i64 {
+    now.timestamp().div_euclid(3600)
+}
+
+fn secs_until_next_hour(now: DateTime<Utc>) -> u64 {
+    (3600 - now.timestamp().rem_euclid(3600)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_api_key_resolve() {
+        assert_eq!(ApiKey::resolve(Some("abc"), Some("xyz")).as_str(), "abc");
+        assert_eq!(ApiKey::resolve(Some("  "), Some("xyz")).as_str(), "xyz");
+        assert!(ApiKey::resolve(None, None).is_anonymous());
+        assert!(ApiKey::resolve(Some(""), Some("")).is_anonymous());
+    }
+
+    #[test]
+    fn test_budget_consume_and_reject() {
+        let tracker = HourlyBudgetTracker::new();
+        let key = ApiKey::new("client-a");
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 15, 0).unwrap();
+
+        assert_eq!(tracker.try_consume(&key, 60, 100, now), Ok(40));
+
+        let err = tracker.try_consume(&key, 50, 100, now).unwrap_err();
+        assert_eq!(err.remaining, 40);
+        assert_eq!(err.resets_in_secs, 45 * 60);
+
+        // Rejected requests are not charged
+        assert_eq!(tracker.consumed(&key, now), 60);
+
+        // Other keys have their own budget
+        let other = ApiKey::new("client-b");
+        assert_eq!(tracker.try_consume(&other, 100, 100, now), Ok(0));
+    }
+
+    #[test]
+    fn test_budget_resets_each_hour() {
+        let tracker = HourlyBudgetTracker::new();
+        let key = ApiKey::anonymous();
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 59, 59).unwrap();
+        let next_hour = Utc.with_ymd_and_hms(2024, 6, 1, 13, 0, 0).unwrap();
+
+        assert!(tracker.try_consume(&key, 100, 100, now).is_ok());
+        assert!(tracker.try_consume(&key, 1, 100, now).is_err());
+
+        assert_eq!(tracker.consumed(&key, next_hour), 0);
+        assert_eq!(tracker.try_consume(&key, 1, 100, next_hour), Ok(99));
+    }
+}
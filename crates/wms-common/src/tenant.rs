@@ -0,0 +1,381 @@
+//! Tenants: which layers an API key may see, and per-tenant usage.
+//!
+//! Tenants are defined in a YAML file (`config/tenants.yaml` by default):
+//!
+//! ```yaml
+//! default_tenant: public      # optional, for keys not listed below
+//! tenants:
+//!   - id: acme
+//!     api_keys: [acme-prod, acme-staging]
+//!     models: [hrrr]          # every layer of these models
+//!     layers: ["gfs_TMP*"]    # layer ids, `*` matches any run of characters
+//!   - id: public
+//!     models: [gfs]
+//! ```
+//!
+//! A key listed under a tenant sees only that tenant's layers. Other keys
+//! (and anonymous requests) get the default tenant if one is named, and are
+//! unrestricted otherwise, so services without a tenants file behave as
+//! before.
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::api_key::ApiKey;
+
+/// Tenants file name inside the config directory.
+pub const TENANTS_FILE: &str = "tenants.yaml";
+
+/// A customer and the layers its keys may see.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tenant {
+    pub id: String,
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// Models whose layers are all visible
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Visible layer id patterns (e.g. `gfs_TMP`, `goes18_*`)
+    #[serde(default)]
+    pub layers: Vec<String>,
+}
+
+impl Tenant {
+    /// Whether a layer (`{model}_{parameter}`) is visible to this tenant.
+    /// Layer ids are compared case-insensitively, as WMS layer names are.
+    pub fn allows_layer(&self, layer_id: &str) -> bool {
+        let model = layer_id.split('_').next().unwrap_or(layer_id);
+        let layer_id = layer_id.to_lowercase();
+        self.allows_model(model)
+            || self
+                .layers
+                .iter()
+                .any(|pattern| wildcard_match(&pattern.to_lowercase(), &layer_id))
+    }
+
+    /// Whether every layer of a model is visible to this tenant.
+    pub fn allows_model(&self, model: &str) -> bool {
+        self.models.iter().any(|m| m.eq_ignore_ascii_case(model))
+    }
+
+    /// Whether a model's parameter is visible, i.e. its layer id
+    /// `{model}_{parameter}` is.
+    pub fn allows_parameter(&self, model: &str, parameter: &str) -> bool {
+        self.allows_layer(&format!("{}_{}", model, parameter))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TenantsFile {
+    #[serde(default)]
+    default_tenant: Option<String>,
+    #[serde(default)]
+    tenants: Vec<Tenant>,
+}
+
+/// Maps API keys to tenants.
+#[derive(Debug, Clone, Default)]
+pub struct TenantRegistry {
+    tenants: Vec<Tenant>,
+    /// API key -> index into `tenants`
+    by_key: HashMap<String, usize>,
+    /// Tenant of keys not listed under any tenant
+    default: Option<usize>,
+}
+
+impl TenantRegistry {
+    /// Registry without tenants: every key is unrestricted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a tenants file.
+    pub fn from_yaml(contents: &str) -> Result<Self, String> {
+        let file: TenantsFile =
+            serde_yaml::from_str(contents).map_err(|e| format!("invalid tenants file: {}", e))?;
+
+        let mut by_key: HashMap<String, usize> = HashMap::new();
+        for (index, tenant) in file.tenants.iter().enumerate() {
+            if file.tenants[..index].iter().any(|t| t.id == tenant.id) {
+                return Err(format!("tenant '{}' is defined twice", tenant.id));
+            }
+            for key in &tenant.api_keys {
+                if let Some(&other) = by_key.get(key) {
+                    return Err(format!(
+                        "API key '{}' belongs to both '{}' and '{}'",
+                        key, file.tenants[other].id, tenant.id
+                    ));
+                }
+                by_key.insert(key.clone(), index);
+            }
+        }
+
+        let default = match &file.default_tenant {
+            Some(id) => Some(
+                file.tenants
+                    .iter()
+                    .position(|t| &t.id == id)
+                    .ok_or_else(|| format!("default tenant '{}' is not defined", id))?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            tenants: file.tenants,
+            by_key,
+            default,
+        })
+    }
+
+    /// Load a tenants file. A missing file gives an empty registry.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(contents) => {
+                Self::from_yaml(&contents).map_err(|e| format!("{}: {}", path.display(), e))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    /// Load the file named by `TENANTS_CONFIG`, or `tenants.yaml` in
+    /// `config_dir`.
+    pub fn from_env(config_dir: impl AsRef<Path>) -> Result<Self, String> {
+        match std::env::var("TENANTS_CONFIG") {
+            Ok(path) if !path.trim().is_empty() => Self::load(path.trim()),
+            _ => Self::load(config_dir.as_ref().join(TENANTS_FILE)),
+        }
+    }
+
+    /// Tenant of a key, or None if the key is unrestricted.
+    pub fn resolve(&self, key: &ApiKey) -> Option<&Tenant> {
+        self.by_key
+            .get(key.as_str())
+            .or(self.default.as_ref())
+            .map(|&index| &self.tenants[index])
+    }
+
+    /// Whether a layer is visible to a key.
+    pub fn allows_layer(&self, key: &ApiKey, layer_id: &str) -> bool {
+        self.resolve(key)
+            .is_none_or(|tenant| tenant.allows_layer(layer_id))
+    }
+
+    pub fn tenants(&self) -> &[Tenant] {
+        &self.tenants
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No wildcard: the whole text must match
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// Requests made and denied per tenant, service and request type.
+#[derive(Debug, Default)]
+pub struct TenantUsage {
+    /// (tenant, service, request) -> counts
+    counts: Mutex<BTreeMap<(String, &'static str, String), TenantCounts>>,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct TenantCounts {
+    requests: u64,
+    denied: u64,
+}
+
+impl TenantUsage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a request by `tenant` (e.g. `service` = "wms", `request` =
+    /// "GetMap").
+    pub fn record(&self, tenant: &Tenant, service: &'static str, request: &str) {
+        self.entry(tenant, service, request, |c| c.requests += 1);
+    }
+
+    /// Count a request rejected because it named a layer outside the tenant.
+    pub fn record_denied(&self, tenant: &Tenant, service: &'static str, request: &str) {
+        self.entry(tenant, service, request, |c| c.denied += 1);
+    }
+
+    fn entry(
+        &self,
+        tenant: &Tenant,
+        service: &'static str,
+        request: &str,
+        update: impl FnOnce(&mut TenantCounts),
+    ) {
+        let mut counts = self.counts.lock().unwrap();
+        update(
+            counts
+                .entry((tenant.id.clone(), service, request.to_string()))
+                .or_default(),
+        );
+    }
+
+    /// Requests made by a tenant.
+    pub fn requests(&self, tenant: &str, service: &'static str, request: &str) -> u64 {
+        self.get(tenant, service, request).requests
+    }
+
+    /// Requests by a tenant that were denied.
+    pub fn denied(&self, tenant: &str, service: &'static str, request: &str) -> u64 {
+        self.get(tenant, service, request).denied
+    }
+
+    fn get(&self, tenant: &str, service: &'static str, request: &str) -> TenantCounts {
+        self.counts
+            .lock()
+            .unwrap()
+            .get(&(tenant.to_string(), service, request.to_string()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Prometheus text exposition of the counters.
+    pub fn to_prometheus(&self) -> String {
+        let counts = self.counts.lock().unwrap();
+        let mut out = String::new();
+        out.push_str("# HELP tenant_requests_total Requests per tenant and request type\n");
+        out.push_str("# TYPE tenant_requests_total counter\n");
+        for ((tenant, service, request), c) in counts.iter() {
+            out.push_str(&format!(
+                "tenant_requests_total{{tenant=\"{}\",service=\"{}\",request=\"{}\"}} {}\n",
+                tenant, service, request, c.requests
+            ));
+        }
+        out.push_str(
+            "# HELP tenant_denied_total Requests denied for naming layers outside the tenant\n",
+        );
+        out.push_str("# TYPE tenant_denied_total counter\n");
+        for ((tenant, service, request), c) in counts.iter() {
+            out.push_str(&format!(
+                "tenant_denied_total{{tenant=\"{}\",service=\"{}\",request=\"{}\"}} {}\n",
+                tenant, service, request, c.denied
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TENANTS: &str = r#"
+default_tenant: public
+tenants:
+  - id: acme
+    api_keys: [acme-1, acme-2]
+    models: [hrrr]
+    layers: ["gfs_TMP*", "goes18_*_C13"]
+  - id: public
+    models: [gfs]
+"#;
+
+    #[test]
+    fn test_resolve_and_layer_visibility() {
+        let registry = TenantRegistry::from_yaml(TENANTS).unwrap();
+
+        let acme = registry.resolve(&ApiKey::new("acme-2")).unwrap();
+        assert_eq!(acme.id, "acme");
+        assert!(acme.allows_layer("hrrr_REFC"));
+        assert!(acme.allows_layer("gfs_TMP"));
+        assert!(acme.allows_layer("gfs_TMP_2M"));
+        assert!(acme.allows_layer("GFS_tmp"));
+        assert!(!acme.allows_layer("gfs_PRMSL"));
+        assert!(acme.allows_layer("goes18_CMI_C13"));
+        assert!(!acme.allows_layer("goes18_CMI_C02"));
+        assert!(acme.allows_parameter("gfs", "TMP"));
+
+        // Unlisted and anonymous keys get the default tenant
+        let public = registry.resolve(&ApiKey::anonymous()).unwrap();
+        assert_eq!(public.id, "public");
+        assert!(registry.allows_layer(&ApiKey::new("unknown"), "gfs_PRMSL"));
+        assert!(!registry.allows_layer(&ApiKey::new("unknown"), "hrrr_REFC"));
+    }
+
+    #[test]
+    fn test_no_tenants_is_unrestricted() {
+        let registry = TenantRegistry::from_yaml("tenants: []").unwrap();
+        assert!(registry.resolve(&ApiKey::new("anyone")).is_none());
+        assert!(registry.allows_layer(&ApiKey::anonymous(), "hrrr_REFC"));
+
+        let registry = TenantRegistry::load("/nonexistent/tenants.yaml").unwrap();
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_tenants() {
+        let shared_key = "tenants:\n  - id: a\n    api_keys: [k]\n  - id: b\n    api_keys: [k]\n";
+        assert!(TenantRegistry::from_yaml(shared_key)
+            .unwrap_err()
+            .contains("both 'a' and 'b'"));
+
+        let missing_default = "default_tenant: x\ntenants:\n  - id: a\n";
+        assert!(TenantRegistry::from_yaml(missing_default)
+            .unwrap_err()
+            .contains("'x' is not defined"));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("gfs_TMP", "gfs_TMP"));
+        assert!(!wildcard_match("gfs_TMP", "gfs_TMP_2M"));
+        assert!(wildcard_match("*", "anything"));
+        assert!(wildcard_match("*_C13", "goes18_CMI_C13"));
+        assert!(wildcard_match("a*b*c", "a-b-b-c"));
+        assert!(!wildcard_match("a*b*c", "a-c"));
+        assert!(!wildcard_match("ab*ba", "aba"));
+    }
+
+    #[test]
+    fn test_usage_prometheus() {
+        let registry = TenantRegistry::from_yaml(TENANTS).unwrap();
+        let acme = registry.resolve(&ApiKey::new("acme-1")).unwrap();
+        let usage = TenantUsage::new();
+
+        usage.record(acme, "wms", "GetMap");
+        usage.record(acme, "wms", "GetMap");
+        usage.record_denied(acme, "wms", "GetMap");
+        usage.record(acme, "edr", "position");
+        assert_eq!(usage.requests("acme", "wms", "GetMap"), 2);
+        assert_eq!(usage.denied("acme", "wms", "GetMap"), 1);
+        assert_eq!(usage.requests("acme", "edr", "position"), 1);
+        assert_eq!(usage.requests("public", "wms", "GetMap"), 0);
+
+        let text = usage.to_prometheus();
+        assert!(text.contains(
+            "tenant_requests_total{tenant=\"acme\",service=\"wms\",request=\"GetMap\"} 2\n"
+        ));
+        assert!(text.contains(
+            "tenant_denied_total{tenant=\"acme\",service=\"wms\",request=\"GetMap\"} 1\n"
+        ));
+    }
+}
@@ -157,6 +157,51 @@ impl TileMatrixSet {
         self.get_matrix_by_zoom(coord.z)
             .map(|m| m.tile_bbox(coord.x, coord.y))
     }
+
+    /// Find the tile a map request covers, if it lines up with the tile grid.
+    ///
+    /// The request must match the tile size in pixels, and each bbox edge must
+    /// be within `tolerance` pixels of the tile's edge, so bboxes that differ
+    /// only in float formatting resolve to the same tile.
+    pub fn snap_bbox(
+        &self,
+        bbox: &BoundingBox,
+        width: u32,
+        height: u32,
+        tolerance: f64,
+    ) -> Option<TileCoord> {
+        self.tile_matrices.iter().enumerate().find_map(|(z, m)| {
+            if width != m.tile_width || height != m.tile_height {
+                return None;
+            }
+            let res = m.resolution();
+            let max_error = tolerance * res;
+            let span_x = res * m.tile_width as f64;
+            let span_y = res * m.tile_height as f64;
+            if (bbox.width() - span_x).abs() > max_error
+                || (bbox.height() - span_y).abs() > max_error
+            {
+                return None;
+            }
+
+            let col = ((bbox.min_x - m.top_left_corner.0) / span_x).round();
+            let row = ((m.top_left_corner.1 - bbox.max_y) / span_y).round();
+            if col < 0.0
+                || row < 0.0
+                || col >= m.matrix_width as f64
+                || row >= m.matrix_height as f64
+            {
+                return None;
+            }
+
+            let tile = m.tile_bbox(col as u32, row as u32);
+            let aligned = (bbox.min_x - tile.min_x).abs() <= max_error
+                && (bbox.min_y - tile.min_y).abs() <= max_error
+                && (bbox.max_x - tile.max_x).abs() <= max_error
+                && (bbox.max_y - tile.max_y).abs() <= max_error;
+            aligned.then(|| TileCoord::new(z as u32, col as u32, row as u32))
+        })
+    }
 }
 
 /// Standard Web Mercator (Google/OSM) tile matrix set.
@@ -287,6 +332,98 @@ pub fn xyz_to_tms(coord: &TileCoord) -> (u32, u32, u32) {
     (coord.z, coord.x, n - 1 - coord.y)
 }
 
+/// Maximum latitude representable in Web Mercator.
+const WEB_MERCATOR_MAX_LAT: f64 = 85.051_128_779_806_59;
+
+/// Range of tile rows and columns intersecting a data extent at one zoom level
+/// (WMTS `TileMatrixLimits`). Bounds are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileMatrixLimits {
+    pub zoom: u32,
+    pub min_row: u32,
+    pub max_row: u32,
+    pub min_col: u32,
+    pub max_col: u32,
+}
+
+impl TileMatrixLimits {
+    /// Whether a tile at this zoom level lies within the limits.
+    pub fn contains(&self, coord: &TileCoord) -> bool {
+        coord.z == self.zoom
+            && (self.min_row..=self.max_row).contains(&coord.y)
+            && (self.min_col..=self.max_col).contains(&coord.x)
+    }
+}
+
+/// Tile limits for a lat/lon extent in the WebMercatorQuad tile matrix set.
+///
+/// `bbox` is in degrees with longitudes in -180..180. An extent with
+/// `min_x > max_x` crosses the antimeridian and keeps every column.
+/// Returns None if the extent lies entirely outside the Mercator latitude
+/// range.
+pub fn web_mercator_tile_limits(bbox: &BoundingBox, zoom: u32) -> Option<TileMatrixLimits> {
+    let n = 2u32.pow(zoom);
+    let north = bbox.max_y.min(WEB_MERCATOR_MAX_LAT);
+    let south = bbox.min_y.max(-WEB_MERCATOR_MAX_LAT);
+    if south > north {
+        return None;
+    }
+
+    let row = |lat: f64| {
+        let lat_rad = lat.to_radians();
+        let y = (1.0 - lat_rad.tan().asinh() / std::f64::consts::PI) / 2.0 * n as f64;
+        (y.floor().max(0.0) as u32).min(n - 1)
+    };
+    let (min_col, max_col) = column_limits(bbox, n);
+
+    Some(TileMatrixLimits {
+        zoom,
+        min_row: row(north),
+        max_row: row(south),
+        min_col,
+        max_col,
+    })
+}
+
+/// Tile limits for a lat/lon extent in the WorldCRS84Quad tile matrix set.
+///
+/// See [`web_mercator_tile_limits`] for the extent conventions.
+pub fn wgs84_tile_limits(bbox: &BoundingBox, zoom: u32) -> Option<TileMatrixLimits> {
+    let n_rows = 2u32.pow(zoom);
+    let north = bbox.max_y.min(90.0);
+    let south = bbox.min_y.max(-90.0);
+    if south > north {
+        return None;
+    }
+
+    let row = |lat: f64| {
+        let y = (90.0 - lat) / 180.0 * n_rows as f64;
+        (y.floor().max(0.0) as u32).min(n_rows - 1)
+    };
+    let (min_col, max_col) = column_limits(bbox, n_rows * 2);
+
+    Some(TileMatrixLimits {
+        zoom,
+        min_row: row(north),
+        max_row: row(south),
+        min_col,
+        max_col,
+    })
+}
+
+/// Column range for an extent in a matrix of `n_cols` columns spanning
+/// -180..180 degrees.
+fn column_limits(bbox: &BoundingBox, n_cols: u32) -> (u32, u32) {
+    if bbox.min_x > bbox.max_x || bbox.max_x - bbox.min_x >= 360.0 {
+        return (0, n_cols - 1);
+    }
+    let col = |lon: f64| {
+        let x = (lon.clamp(-180.0, 180.0) + 180.0) / 360.0 * n_cols as f64;
+        (x.floor() as u32).min(n_cols - 1)
+    };
+    (col(bbox.min_x), col(bbox.max_x))
+}
+
 /// Configuration for expanded tile rendering using full tile expansion.
 /// Used to render a larger area and crop to get seamless tile boundaries.
 ///
@@ -779,6 +916,105 @@ mod tests {
         assert!(children.contains(&tile));
     }
 
+    #[test]
+    fn test_web_mercator_tile_limits_conus() {
+        // Roughly the HRRR domain
+        let bbox = BoundingBox::new(-134.1, 21.1, -60.9, 52.6);
+
+        let z0 = web_mercator_tile_limits(&bbox, 0).unwrap();
+        assert_eq!(
+            (z0.min_row, z0.max_row, z0.min_col, z0.max_col),
+            (0, 0, 0, 0)
+        );
+
+        let z4 = web_mercator_tile_limits(&bbox, 4).unwrap();
+        assert_eq!((z4.min_col, z4.max_col), (2, 5));
+        assert_eq!((z4.min_row, z4.max_row), (5, 7));
+
+        // Every tile overlapping the extent is inside the limits
+        let nyc = latlon_to_tile(40.7128, -74.0060, 4);
+        assert!(z4.contains(&nyc));
+        let london = latlon_to_tile(51.5, -0.1, 4);
+        assert!(!z4.contains(&london));
+        assert!(!z4.contains(&TileCoord::new(5, nyc.x, nyc.y)));
+    }
+
+    #[test]
+    fn test_web_mercator_tile_limits_clamps_poles() {
+        let global = BoundingBox::new(-180.0, -90.0, 180.0, 90.0);
+        let z3 = web_mercator_tile_limits(&global, 3).unwrap();
+        assert_eq!(
+            (z3.min_row, z3.max_row, z3.min_col, z3.max_col),
+            (0, 7, 0, 7)
+        );
+
+        let polar = BoundingBox::new(-180.0, 86.0, 180.0, 90.0);
+        assert!(web_mercator_tile_limits(&polar, 3).is_none());
+    }
+
+    #[test]
+    fn test_wgs84_tile_limits() {
+        let bbox = BoundingBox::new(-134.1, 21.1, -60.9, 52.6);
+        let z1 = wgs84_tile_limits(&bbox, 1).unwrap();
+        // 4 columns x 2 rows of 90 degrees each
+        assert_eq!(
+            (z1.min_row, z1.max_row, z1.min_col, z1.max_col),
+            (0, 0, 0, 1)
+        );
+
+        let z3 = wgs84_tile_limits(&bbox, 3).unwrap();
+        for row in z3.min_row..=z3.max_row {
+            for col in z3.min_col..=z3.max_col {
+                let tile = wgs84_tile_to_latlon_bounds(&TileCoord::new(3, col, row));
+                assert!(tile.max_x > bbox.min_x && tile.min_x < bbox.max_x);
+                assert!(tile.max_y > bbox.min_y && tile.min_y < bbox.max_y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tile_limits_antimeridian_keeps_all_columns() {
+        // Normalized 0..360 grid: west > east
+        let bbox = BoundingBox::new(0.0, -90.0, -0.25, 90.0);
+        let z2 = web_mercator_tile_limits(&bbox, 2).unwrap();
+        assert_eq!((z2.min_col, z2.max_col), (0, 3));
+        let z2 = wgs84_tile_limits(&bbox, 2).unwrap();
+        assert_eq!((z2.min_col, z2.max_col), (0, 7));
+    }
+
+    #[test]
+    fn test_snap_bbox() {
+        let tms = web_mercator_tile_matrix_set();
+        let tile = tms.tile_bbox(&TileCoord::new(5, 7, 12)).unwrap();
+
+        assert_eq!(
+            tms.snap_bbox(&tile, 256, 256, 0.01),
+            Some(TileCoord::new(5, 7, 12))
+        );
+
+        // Rounded coordinates from a client still resolve to the tile
+        let rounded = BoundingBox::new(
+            (tile.min_x * 100.0).round() / 100.0,
+            (tile.min_y * 100.0).round() / 100.0,
+            (tile.max_x * 100.0).round() / 100.0,
+            (tile.max_y * 100.0).round() / 100.0,
+        );
+        assert_eq!(
+            tms.snap_bbox(&rounded, 256, 256, 0.01),
+            Some(TileCoord::new(5, 7, 12))
+        );
+
+        // Off the grid by half a tile, or not a tile-sized image
+        let shifted = BoundingBox::new(
+            tile.min_x + tile.width() / 2.0,
+            tile.min_y,
+            tile.max_x + tile.width() / 2.0,
+            tile.max_y,
+        );
+        assert_eq!(tms.snap_bbox(&shifted, 256, 256, 0.01), None);
+        assert_eq!(tms.snap_bbox(&tile, 512, 512, 0.01), None);
+    }
+
     #[test]
     fn test_tms_xyz_conversion() {
         let xyz = TileCoord { z: 3, x: 4, y: 2 };
@@ -5,6 +5,7 @@
 //!
 // TODO ask claude about if this is duplicate, consider this to be the place to handle custom getFeatureInfo rendering
 
+use quick_xml::escape::escape;
 use serde::{Deserialize, Serialize};
 
 /// GetFeatureInfo request parameters
@@ -96,9 +97,52 @@ pub struct FeatureInfo {
     /// Reference time (run time)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference_time: Option<String>,
+    /// Valid time (reference time plus forecast hour)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_time: Option<String>,
     /// Vertical level/elevation (e.g., "500 mb", "2 m above ground")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub level: Option<String>,
+    /// Dataset and chunks the value was read from (debugging/audit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<FeatureProvenance>,
+}
+
+/// Where a feature's value was read from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureProvenance {
+    /// Catalog id of the dataset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dataset_id: Option<String>,
+    /// Storage path of the array that was read
+    pub source: String,
+    /// Pyramid level read (0 = native resolution)
+    pub pyramid_level: u32,
+    /// Store keys of the chunks read
+    pub chunk_keys: Vec<String>,
+    /// Source file the dataset was ingested from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<FeatureLineage>,
+}
+
+/// Source file a feature's dataset was ingested from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureLineage {
+    /// URL the file was downloaded from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Path of the file as ingested
+    pub file: String,
+    /// SHA-256 of the file contents (hex)
+    pub sha256: String,
+    /// Byte offset of the GRIB2 message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_offset: Option<u64>,
+    /// Length of the GRIB2 message in bytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_length: Option<u64>,
+    /// Version of the ingestion code
+    pub ingestion_version: String,
 }
 
 /// Geographic location
@@ -108,14 +152,39 @@ pub struct Location {
     pub latitude: f64,
 }
 
+/// Outcome of querying one layer of a GetFeatureInfo request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueriedLayer {
+    /// Layer name as requested in QUERY_LAYERS
+    pub layer_name: String,
+    /// Number of features returned for the layer
+    pub feature_count: usize,
+    /// Whether features were dropped to honour FEATURE_COUNT
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// Why the layer could not be queried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// GetFeatureInfo response container
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeatureInfoResponse {
     /// Response type identifier
     #[serde(rename = "type")]
     pub response_type: String,
-    /// List of feature information
+    /// List of feature information, grouped by layer in QUERY_LAYERS order
     pub features: Vec<FeatureInfo>,
+    /// Per-layer summary of a multi-layer query
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<QueriedLayer>,
+}
+
+/// One layer's features (or failure) in a rendered response
+struct LayerBlock<'a> {
+    layer_name: &'a str,
+    features: Vec<&'a FeatureInfo>,
+    error: Option<&'a str>,
 }
 
 impl FeatureInfoResponse {
@@ -124,7 +193,81 @@ impl FeatureInfoResponse {
         Self {
             response_type: "FeatureInfoResponse".to_string(),
             features,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Combine the results of querying each layer.
+    ///
+    /// A feature is one level of a layer, so features sharing a level (e.g.
+    /// wind speed and direction) count once. `feature_count` limits the
+    /// features per layer, as FEATURE_COUNT does in WMS 1.3.0; `None` returns
+    /// all of them.
+    pub fn from_layers(
+        results: Vec<(String, Result<Vec<FeatureInfo>, String>)>,
+        feature_count: Option<u32>,
+    ) -> Self {
+        let mut features = Vec::new();
+        let mut layers = Vec::with_capacity(results.len());
+
+        for (layer_name, result) in results {
+            match result {
+                Ok(layer_features) => {
+                    let (kept, truncated) = limit_features(layer_features, feature_count);
+                    layers.push(QueriedLayer {
+                        layer_name,
+                        feature_count: kept.len(),
+                        truncated,
+                        error: None,
+                    });
+                    features.extend(kept);
+                }
+                Err(error) => layers.push(QueriedLayer {
+                    layer_name,
+                    feature_count: 0,
+                    truncated: false,
+                    error: Some(error),
+                }),
+            }
+        }
+
+        Self {
+            features,
+            layers,
+            ..Self::new(Vec::new())
+        }
+    }
+
+    /// Features grouped by layer, including layers that failed
+    fn layer_blocks(&self) -> Vec<LayerBlock<'_>> {
+        // Responses built by `new` have no layer summary; group by name
+        if self.layers.is_empty() {
+            let mut blocks: Vec<LayerBlock> = Vec::new();
+            for feature in &self.features {
+                match blocks.last_mut() {
+                    Some(block) if block.layer_name == feature.layer_name => {
+                        block.features.push(feature)
+                    }
+                    _ => blocks.push(LayerBlock {
+                        layer_name: &feature.layer_name,
+                        features: vec![feature],
+                        error: None,
+                    }),
+                }
+            }
+            return blocks;
         }
+
+        // Features are stored in layer order
+        let mut remaining = self.features.iter();
+        self.layers
+            .iter()
+            .map(|layer| LayerBlock {
+                layer_name: &layer.layer_name,
+                features: remaining.by_ref().take(layer.feature_count).collect(),
+                error: layer.error.as_deref(),
+            })
+            .collect()
     }
 
     /// Format as JSON
@@ -136,34 +279,45 @@ impl FeatureInfoResponse {
     pub fn to_html(&self) -> String {
         let mut html = String::from("<div class=\"feature-info\">\n");
 
-        for feature in &self.features {
-            html.push_str(&format!("  <h4>{}</h4>\n", feature.layer_name));
-            html.push_str("  <table>\n");
-            html.push_str(&format!(
-                "    <tr><td>Parameter:</td><td class=\"value\">{}</td></tr>\n",
-                feature.parameter
-            ));
-            html.push_str(&format!(
-                "    <tr><td>Value:</td><td class=\"value\">{:.2} {}</td></tr>\n",
-                feature.value, feature.unit
-            ));
-            if let Some(ref level) = feature.level {
+        for block in self.layer_blocks() {
+            html.push_str(&format!("  <h4>{}</h4>\n", block.layer_name));
+            if let Some(error) = block.error {
+                html.push_str(&format!("  <p class=\"error\">{}</p>\n", error));
+            }
+            for feature in block.features {
+                html.push_str("  <table>\n");
                 html.push_str(&format!(
-                    "    <tr><td>Level:</td><td class=\"value\">{}</td></tr>\n",
-                    level
+                    "    <tr><td>Parameter:</td><td class=\"value\">{}</td></tr>\n",
+                    feature.parameter
                 ));
-            }
-            html.push_str(&format!(
-                "    <tr><td>Location:</td><td class=\"value\">{:.3}°, {:.3}°</td></tr>\n",
-                feature.location.latitude, feature.location.longitude
-            ));
-            if let Some(hour) = feature.forecast_hour {
                 html.push_str(&format!(
-                    "    <tr><td>Forecast:</td><td class=\"value\">+{} hours</td></tr>\n",
-                    hour
+                    "    <tr><td>Value:</td><td class=\"value\">{:.2} {}</td></tr>\n",
+                    feature.value, feature.unit
+                ));
+                if let Some(ref level) = feature.level {
+                    html.push_str(&format!(
+                        "    <tr><td>Level:</td><td class=\"value\">{}</td></tr>\n",
+                        level
+                    ));
+                }
+                html.push_str(&format!(
+                    "    <tr><td>Location:</td><td class=\"value\">{:.3}°, {:.3}°</td></tr>\n",
+                    feature.location.latitude, feature.location.longitude
                 ));
+                if let Some(hour) = feature.forecast_hour {
+                    html.push_str(&format!(
+                        "    <tr><td>Forecast:</td><td class=\"value\">+{} hours</td></tr>\n",
+                        hour
+                    ));
+                }
+                if let Some(ref valid_time) = feature.valid_time {
+                    html.push_str(&format!(
+                        "    <tr><td>Valid:</td><td class=\"value\">{}</td></tr>\n",
+                        valid_time
+                    ));
+                }
+                html.push_str("  </table>\n");
             }
-            html.push_str("  </table>\n");
         }
 
         html.push_str("</div>");
@@ -174,22 +328,30 @@ impl FeatureInfoResponse {
     pub fn to_text(&self) -> String {
         let mut text = String::new();
 
-        for (i, feature) in self.features.iter().enumerate() {
+        for (i, block) in self.layer_blocks().into_iter().enumerate() {
             if i > 0 {
                 text.push_str("\n---\n");
             }
-            text.push_str(&format!("Layer: {}\n", feature.layer_name));
-            text.push_str(&format!("Parameter: {}\n", feature.parameter));
-            text.push_str(&format!("Value: {:.2} {}\n", feature.value, feature.unit));
-            if let Some(ref level) = feature.level {
-                text.push_str(&format!("Level: {}\n", level));
+            text.push_str(&format!("Layer: {}\n", block.layer_name));
+            if let Some(error) = block.error {
+                text.push_str(&format!("Error: {}\n", error));
             }
-            text.push_str(&format!(
-                "Location: {:.3}°N, {:.3}°E\n",
-                feature.location.latitude, feature.location.longitude
-            ));
-            if let Some(hour) = feature.forecast_hour {
-                text.push_str(&format!("Forecast: +{} hours\n", hour));
+            for feature in block.features {
+                text.push_str(&format!("Parameter: {}\n", feature.parameter));
+                text.push_str(&format!("Value: {:.2} {}\n", feature.value, feature.unit));
+                if let Some(ref level) = feature.level {
+                    text.push_str(&format!("Level: {}\n", level));
+                }
+                text.push_str(&format!(
+                    "Location: {:.3}°N, {:.3}°E\n",
+                    feature.location.latitude, feature.location.longitude
+                ));
+                if let Some(hour) = feature.forecast_hour {
+                    text.push_str(&format!("Forecast: +{} hours\n", hour));
+                }
+                if let Some(ref valid_time) = feature.valid_time {
+                    text.push_str(&format!("Valid: {}\n", valid_time));
+                }
             }
         }
 
@@ -201,29 +363,75 @@ impl FeatureInfoResponse {
         let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
         xml.push_str("<FeatureInfoResponse>\n");
 
-        for feature in &self.features {
-            xml.push_str("  <FeatureInfo>\n");
-            xml.push_str(&format!(
-                "    <LayerName>{}</LayerName>\n",
-                feature.layer_name
-            ));
-            xml.push_str(&format!(
-                "    <Parameter>{}</Parameter>\n",
-                feature.parameter
-            ));
-            xml.push_str(&format!("    <Value>{:.2}</Value>\n", feature.value));
-            xml.push_str(&format!("    <Unit>{}</Unit>\n", feature.unit));
-            if let Some(ref level) = feature.level {
-                xml.push_str(&format!("    <Level>{}</Level>\n", level));
+        for block in self.layer_blocks() {
+            xml.push_str(&format!("  <Layer name=\"{}\">\n", block.layer_name));
+            if let Some(error) = block.error {
+                xml.push_str(&format!("    <Error>{}</Error>\n", error));
             }
-            xml.push_str(&format!(
-                "    <Location longitude=\"{:.3}\" latitude=\"{:.3}\"/>\n",
-                feature.location.longitude, feature.location.latitude
-            ));
-            if let Some(hour) = feature.forecast_hour {
-                xml.push_str(&format!("    <ForecastHour>{}</ForecastHour>\n", hour));
+            for feature in block.features {
+                xml.push_str("    <FeatureInfo>\n");
+                xml.push_str(&format!(
+                    "      <LayerName>{}</LayerName>\n",
+                    feature.layer_name
+                ));
+                xml.push_str(&format!(
+                    "      <Parameter>{}</Parameter>\n",
+                    feature.parameter
+                ));
+                xml.push_str(&format!("      <Value>{:.2}</Value>\n", feature.value));
+                xml.push_str(&format!("      <Unit>{}</Unit>\n", feature.unit));
+                if let Some(ref level) = feature.level {
+                    xml.push_str(&format!("      <Level>{}</Level>\n", level));
+                }
+                xml.push_str(&format!(
+                    "      <Location longitude=\"{:.3}\" latitude=\"{:.3}\"/>\n",
+                    feature.location.longitude, feature.location.latitude
+                ));
+                if let Some(hour) = feature.forecast_hour {
+                    xml.push_str(&format!("      <ForecastHour>{}</ForecastHour>\n", hour));
+                }
+                if let Some(ref valid_time) = feature.valid_time {
+                    xml.push_str(&format!("      <ValidTime>{}</ValidTime>\n", valid_time));
+                }
+                if let Some(ref provenance) = feature.provenance {
+                    xml.push_str(&format!(
+                        "      <Provenance source=\"{}\" pyramidLevel=\"{}\">\n",
+                        provenance.source, provenance.pyramid_level
+                    ));
+                    if let Some(ref id) = provenance.dataset_id {
+                        xml.push_str(&format!("        <DatasetId>{}</DatasetId>\n", id));
+                    }
+                    for key in &provenance.chunk_keys {
+                        xml.push_str(&format!("        <ChunkKey>{}</ChunkKey>\n", key));
+                    }
+                    if let Some(ref lineage) = provenance.lineage {
+                        xml.push_str(&format!(
+                            "        <Lineage file=\"{}\" sha256=\"{}\" ingestionVersion=\"{}\"",
+                            escape(&lineage.file),
+                            lineage.sha256,
+                            escape(&lineage.ingestion_version)
+                        ));
+                        if let (Some(offset), Some(length)) =
+                            (lineage.message_offset, lineage.message_length)
+                        {
+                            xml.push_str(&format!(
+                                " messageOffset=\"{}\" messageLength=\"{}\"",
+                                offset, length
+                            ));
+                        }
+                        match lineage.url {
+                            Some(ref url) => {
+                                xml.push_str(&format!(">\n          <Url>{}</Url>\n", escape(url)));
+                                xml.push_str("        </Lineage>\n");
+                            }
+                            None => xml.push_str("/>\n"),
+                        }
+                    }
+                    xml.push_str("      </Provenance>\n");
+                }
+                xml.push_str("    </FeatureInfo>\n");
             }
-            xml.push_str("  </FeatureInfo>\n");
+            xml.push_str("  </Layer>\n");
         }
 
         xml.push_str("</FeatureInfoResponse>");
@@ -231,6 +439,37 @@ impl FeatureInfoResponse {
     }
 }
 
+/// Keep the features of the first `feature_count` levels.
+///
+/// Returns the kept features and whether any were dropped.
+fn limit_features(
+    features: Vec<FeatureInfo>,
+    feature_count: Option<u32>,
+) -> (Vec<FeatureInfo>, bool) {
+    let Some(limit) = feature_count else {
+        return (features, false);
+    };
+
+    let mut levels: Vec<Option<String>> = Vec::new();
+    let mut truncated = false;
+    let kept = features
+        .into_iter()
+        .filter(|feature| {
+            if levels.contains(&feature.level) {
+                return true;
+            }
+            if levels.len() < limit as usize {
+                levels.push(feature.level.clone());
+                true
+            } else {
+                truncated = true;
+                false
+            }
+        })
+        .collect();
+    (kept, truncated)
+}
+
 /// Convert pixel coordinates to geographic coordinates
 ///
 /// # Arguments
@@ -287,6 +526,77 @@ mod tests {
         assert_eq!(InfoFormat::from_mime("TEXT/HTML"), Some(InfoFormat::Html));
     }
 
+    fn feature(layer: &str, parameter: &str, level: &str) -> FeatureInfo {
+        FeatureInfo {
+            layer_name: layer.to_string(),
+            parameter: parameter.to_string(),
+            value: 1.0,
+            unit: "m/s".to_string(),
+            raw_value: 1.0,
+            raw_unit: "m/s".to_string(),
+            location: Location {
+                longitude: -95.0,
+                latitude: 40.0,
+            },
+            forecast_hour: Some(0),
+            reference_time: None,
+            valid_time: None,
+            level: Some(level.to_string()),
+            provenance: None,
+        }
+    }
+
+    #[test]
+    fn test_feature_info_from_layers() {
+        let results = vec![
+            (
+                "gfs_WIND_BARBS".to_string(),
+                Ok(vec![
+                    feature("gfs_WIND_BARBS", "Wind Speed", "500 mb"),
+                    feature("gfs_WIND_BARBS", "Wind Direction", "500 mb"),
+                    feature("gfs_WIND_BARBS", "Wind Speed", "850 mb"),
+                    feature("gfs_WIND_BARBS", "Wind Direction", "850 mb"),
+                ]),
+            ),
+            ("gfs_TMP".to_string(), Err("No data found".to_string())),
+            (
+                "hrrr_GUST".to_string(),
+                Ok(vec![feature("hrrr_GUST", "Gust", "surface")]),
+            ),
+        ];
+
+        // Speed and direction at one level are one feature
+        let response = FeatureInfoResponse::from_layers(results.clone(), Some(1));
+        assert_eq!(response.features.len(), 3);
+        assert_eq!(response.layers.len(), 3);
+        assert_eq!(response.layers[0].feature_count, 2);
+        assert!(response.layers[0].truncated);
+        assert_eq!(response.layers[1].error.as_deref(), Some("No data found"));
+        assert!(!response.layers[2].truncated);
+
+        let response = FeatureInfoResponse::from_layers(results, None);
+        assert_eq!(response.features.len(), 5);
+        assert!(response.layers.iter().all(|l| !l.truncated));
+
+        // Every layer gets a block, failed ones included
+        let text = response.to_text();
+        assert_eq!(text.matches("Layer: ").count(), 3);
+        assert!(text.contains("Layer: gfs_TMP\nError: No data found"));
+        assert!(text.contains("Layer: hrrr_GUST\nParameter: Gust"));
+
+        let html = response.to_html();
+        assert_eq!(html.matches("<h4>").count(), 3);
+        assert_eq!(html.matches("<table>").count(), 5);
+
+        let xml = response.to_xml();
+        assert_eq!(xml.matches("<Layer name=").count(), 3);
+        assert!(xml.contains("<Layer name=\"gfs_TMP\">\n    <Error>"));
+
+        let json: serde_json::Value = serde_json::from_str(&response.to_json().unwrap()).unwrap();
+        assert_eq!(json["features"].as_array().unwrap().len(), 5);
+        assert_eq!(json["layers"][1]["error"], "No data found");
+    }
+
     #[test]
     fn test_feature_info_response_json() {
         let response = FeatureInfoResponse::new(vec![FeatureInfo {
@@ -302,12 +612,54 @@ mod tests {
             },
             forecast_hour: Some(3),
             reference_time: Some("2025-11-26T12:00:00Z".to_string()),
+            valid_time: Some("2025-11-26T15:00:00Z".to_string()),
             level: Some("500 mb".to_string()),
+            provenance: None,
         }]);
 
         let json = response.to_json().unwrap();
         assert!(json.contains("FeatureInfoResponse"));
         assert!(json.contains("Temperature"));
         assert!(json.contains("500 mb"));
+        assert!(json.contains("\"valid_time\": \"2025-11-26T15:00:00Z\""));
+        assert!(!json.contains("provenance"));
+        assert!(response.to_text().contains("Valid: 2025-11-26T15:00:00Z\n"));
+    }
+
+    #[test]
+    fn test_feature_info_provenance() {
+        let mut feature = feature("gfs_TMP", "Temperature", "2 m above ground");
+        feature.provenance = Some(FeatureProvenance {
+            dataset_id: Some("gfs/TMP/2 m above ground/20251126T1200Z/f003".to_string()),
+            source: "/grids/gfs/20251126_12z/tmp_f003.zarr/0".to_string(),
+            pyramid_level: 0,
+            chunk_keys: vec!["grids/gfs/20251126_12z/tmp_f003.zarr/0/c/1/2".to_string()],
+            lineage: Some(FeatureLineage {
+                url: Some("https://example.com/gfs?file=f003&dir=12".to_string()),
+                file: "/data/gfs.t12z.pgrb2.0p25.f003".to_string(),
+                sha256: "ab".repeat(32),
+                message_offset: Some(4096),
+                message_length: Some(1024),
+                ingestion_version: "0.1.0".to_string(),
+            }),
+        });
+        let response = FeatureInfoResponse::new(vec![feature]);
+
+        let json: serde_json::Value = serde_json::from_str(&response.to_json().unwrap()).unwrap();
+        let provenance = &json["features"][0]["provenance"];
+        assert_eq!(
+            provenance["dataset_id"],
+            "gfs/TMP/2 m above ground/20251126T1200Z/f003"
+        );
+        assert_eq!(provenance["chunk_keys"].as_array().unwrap().len(), 1);
+        assert_eq!(provenance["lineage"]["message_offset"], 4096);
+
+        let xml = response.to_xml();
+        assert!(xml.contains(
+            "<Provenance source=\"/grids/gfs/20251126_12z/tmp_f003.zarr/0\" pyramidLevel=\"0\">"
+        ));
+        assert!(xml.contains("<ChunkKey>grids/gfs/20251126_12z/tmp_f003.zarr/0/c/1/2</ChunkKey>"));
+        assert!(xml.contains("messageOffset=\"4096\" messageLength=\"1024\">"));
+        assert!(xml.contains("<Url>https://example.com/gfs?file=f003&amp;dir=12</Url>"));
     }
 }
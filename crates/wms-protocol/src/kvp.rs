@@ -0,0 +1,392 @@
+//! WMS KVP request checking with strict and lenient parsing modes.
+//!
+//! Some clients send requests that don't follow WMS 1.3.0 to the letter:
+//! lowercase operation names (`REQUEST=getmap`), no `VERSION`, or 1.1.1
+//! parameters such as `SRS` in a 1.3.0 request. [`check_wms_kvp`] lists these
+//! deviations; in [`ParseMode::Lenient`] they are reported and the request is
+//! served, in [`ParseMode::Strict`] the request is rejected.
+//!
+//! Parameter *names* are case-insensitive (WMS 1.3.0 §6.8.1), so `layers=`
+//! is not a deviation, but parameter *values* are case-sensitive.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use wms_common::{WmsError, WmsResult};
+
+/// Operations and their spelling in WMS 1.3.0.
+const OPERATIONS: &[&str] = &["GetCapabilities", "GetMap", "GetFeatureInfo"];
+
+/// WMS 1.1.1 parameter names and their 1.3.0 replacements.
+const LEGACY_PARAMETERS: &[(&str, &str)] = &[("SRS", "CRS"), ("X", "I"), ("Y", "J")];
+
+/// How strictly requests are held to the WMS specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseMode {
+    /// Reject requests with any deviation.
+    Strict,
+    /// Serve requests with deviations and report them.
+    #[default]
+    Lenient,
+}
+
+impl ParseMode {
+    /// Name used in configuration.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParseMode::Strict => "strict",
+            ParseMode::Lenient => "lenient",
+        }
+    }
+}
+
+impl fmt::Display for ParseMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ParseMode {
+    type Err = WmsError;
+
+    fn from_str(s: &str) -> WmsResult<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "strict" => Ok(ParseMode::Strict),
+            "lenient" => Ok(ParseMode::Lenient),
+            other => Err(WmsError::InvalidParameter {
+                param: "mode".to_string(),
+                message: format!("unknown parse mode '{}', expected strict or lenient", other),
+            }),
+        }
+    }
+}
+
+/// Kind of deviation from the specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DeviationKind {
+    /// A case-sensitive value (`SERVICE`, `REQUEST`) has the wrong case.
+    ValueCase,
+    /// `VERSION` is missing from a GetMap or GetFeatureInfo request.
+    MissingVersion,
+    /// A WMS 1.1.1 parameter is used in a 1.3.0 request.
+    LegacyParameter,
+    /// A parameter is given more than once.
+    DuplicateParameter,
+}
+
+impl DeviationKind {
+    /// Short code used in response headers.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DeviationKind::ValueCase => "value-case",
+            DeviationKind::MissingVersion => "missing-version",
+            DeviationKind::LegacyParameter => "legacy-parameter",
+            DeviationKind::DuplicateParameter => "duplicate-parameter",
+        }
+    }
+}
+
+/// One deviation found in a request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Deviation {
+    pub kind: DeviationKind,
+    /// Parameter the deviation applies to (uppercase)
+    pub param: String,
+    pub message: String,
+}
+
+impl Deviation {
+    fn new(kind: DeviationKind, param: &str, message: String) -> Self {
+        Self {
+            kind,
+            param: param.to_string(),
+            message,
+        }
+    }
+}
+
+/// Result of checking a request in a given mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParseReport {
+    pub mode: ParseMode,
+    pub deviations: Vec<Deviation>,
+}
+
+impl ParseReport {
+    /// Whether the request follows the specification.
+    pub fn is_compliant(&self) -> bool {
+        self.deviations.is_empty()
+    }
+
+    /// Deviations as a response header value, e.g.
+    /// `value-case=REQUEST, missing-version=VERSION`.
+    ///
+    /// `None` for compliant requests.
+    pub fn header_value(&self) -> Option<String> {
+        if self.is_compliant() {
+            return None;
+        }
+        let codes: Vec<String> = self
+            .deviations
+            .iter()
+            .map(|d| format!("{}={}", d.kind.code(), d.param))
+            .collect();
+        Some(codes.join(", "))
+    }
+
+    /// The error a strict parser reports for this request, if any.
+    pub fn strict_error(&self) -> Option<WmsError> {
+        let first = self.deviations.first()?;
+        let message = self
+            .deviations
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Some(match first.kind {
+            DeviationKind::MissingVersion if self.deviations.len() == 1 => {
+                WmsError::MissingParameter(first.param.clone())
+            }
+            _ => WmsError::InvalidParameter {
+                param: first.param.clone(),
+                message,
+            },
+        })
+    }
+}
+
+/// Check WMS KVP parameters against WMS 1.3.0.
+///
+/// Returns the deviations in the order the parameters appear.
+pub fn check_wms_kvp<K: AsRef<str>, V: AsRef<str>>(pairs: &[(K, V)]) -> Vec<Deviation> {
+    let mut deviations = Vec::new();
+    let mut seen = HashSet::new();
+    let get = |name: &str| {
+        pairs
+            .iter()
+            .find(|(k, _)| k.as_ref().eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_ref())
+    };
+
+    for (key, _) in pairs {
+        let name = key.as_ref().to_uppercase();
+        if !seen.insert(name.clone()) {
+            deviations.push(Deviation::new(
+                DeviationKind::DuplicateParameter,
+                &name,
+                format!("{} is given more than once", name),
+            ));
+        }
+    }
+
+    if let Some(service) = get("SERVICE") {
+        if service != "WMS" && service.eq_ignore_ascii_case("WMS") {
+            deviations.push(Deviation::new(
+                DeviationKind::ValueCase,
+                "SERVICE",
+                format!("SERVICE must be 'WMS', got '{}'", service),
+            ));
+        }
+    }
+
+    let operation = get("REQUEST").and_then(|request| {
+        let operation = OPERATIONS
+            .iter()
+            .find(|op| op.eq_ignore_ascii_case(request))?;
+        if request != *operation {
+            deviations.push(Deviation::new(
+                DeviationKind::ValueCase,
+                "REQUEST",
+                format!("REQUEST must be '{}', got '{}'", operation, request),
+            ));
+        }
+        Some(*operation)
+    });
+
+    // Capabilities requests negotiate the version, other operations must give it
+    let version = get("VERSION");
+    if version.is_none() && matches!(operation, Some("GetMap" | "GetFeatureInfo")) {
+        deviations.push(Deviation::new(
+            DeviationKind::MissingVersion,
+            "VERSION",
+            format!("VERSION is required for {}", operation.unwrap_or_default()),
+        ));
+    }
+
+    if version != Some("1.1.1") {
+        for (legacy, replacement) in LEGACY_PARAMETERS {
+            if get(legacy).is_some() {
+                deviations.push(Deviation::new(
+                    DeviationKind::LegacyParameter,
+                    legacy,
+                    format!("{} is WMS 1.1.1; use {} in WMS 1.3.0", legacy, replacement),
+                ));
+            }
+        }
+    }
+
+    deviations
+}
+
+/// Check a request in the given mode.
+///
+/// In strict mode a request with deviations is an error; in lenient mode
+/// the deviations are returned in the report.
+pub fn parse_wms_kvp<K: AsRef<str>, V: AsRef<str>>(
+    pairs: &[(K, V)],
+    mode: ParseMode,
+) -> WmsResult<ParseReport> {
+    let report = ParseReport {
+        mode,
+        deviations: check_wms_kvp(pairs),
+    };
+    match (mode, report.strict_error()) {
+        (ParseMode::Strict, Some(err)) => Err(err),
+        _ => Ok(report),
+    }
+}
+
+/// Parse a BBOX value: four comma-separated finite numbers, in the axis
+/// order of the request's CRS.
+pub fn parse_bbox(value: &str) -> WmsResult<[f64; 4]> {
+    let invalid = || {
+        WmsError::InvalidBbox(format!(
+            "BBOX must be four comma-separated numbers, got '{}'",
+            value
+        ))
+    };
+
+    let mut parts = value.split(',');
+    let mut coords = [0.0; 4];
+    for coord in &mut coords {
+        let part = parts.next().ok_or_else(invalid)?;
+        *coord = part
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(invalid)?;
+    }
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(coords)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(pairs: &[(&str, &str)]) -> Vec<(DeviationKind, String)> {
+        check_wms_kvp(pairs)
+            .into_iter()
+            .map(|d| (d.kind, d.param))
+            .collect()
+    }
+
+    #[test]
+    fn test_compliant_request() {
+        let pairs = [
+            ("SERVICE", "WMS"),
+            ("VERSION", "1.3.0"),
+            ("REQUEST", "GetMap"),
+            ("layers", "gfs_TMP"),
+            ("CRS", "EPSG:4326"),
+        ];
+        assert!(check_wms_kvp(&pairs).is_empty());
+
+        // Capabilities requests may leave out VERSION
+        let pairs = [("service", "WMS"), ("request", "GetCapabilities")];
+        assert!(check_wms_kvp(&pairs).is_empty());
+
+        // SRS is correct in 1.1.1
+        let pairs = [
+            ("SERVICE", "WMS"),
+            ("VERSION", "1.1.1"),
+            ("REQUEST", "GetMap"),
+            ("SRS", "EPSG:4326"),
+        ];
+        assert!(check_wms_kvp(&pairs).is_empty());
+    }
+
+    #[test]
+    fn test_deviations() {
+        let pairs = [
+            ("service", "wms"),
+            ("request", "getmap"),
+            ("srs", "EPSG:4326"),
+            ("LAYERS", "a"),
+            ("layers", "b"),
+        ];
+        assert_eq!(
+            kinds(&pairs),
+            vec![
+                (DeviationKind::DuplicateParameter, "LAYERS".to_string()),
+                (DeviationKind::ValueCase, "SERVICE".to_string()),
+                (DeviationKind::ValueCase, "REQUEST".to_string()),
+                (DeviationKind::MissingVersion, "VERSION".to_string()),
+                (DeviationKind::LegacyParameter, "SRS".to_string()),
+            ]
+        );
+
+        // Unknown operations are left to the handler
+        assert!(kinds(&[("SERVICE", "WMS"), ("REQUEST", "GetLegend")]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_bbox() {
+        assert_eq!(
+            parse_bbox("30, -120,50.5,-80").unwrap(),
+            [30.0, -120.0, 50.5, -80.0]
+        );
+        for value in [
+            "",
+            "1,2,3",
+            "1,2,3,4,5",
+            "1,2,x,3,4",
+            "1,2,NaN,4",
+            "1,inf,3,4",
+        ] {
+            assert!(parse_bbox(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        let pairs = [("SERVICE", "WMS"), ("REQUEST", "getmap")];
+
+        let report = parse_wms_kvp(&pairs, ParseMode::Lenient).unwrap();
+        assert!(!report.is_compliant());
+        assert_eq!(
+            report.header_value().unwrap(),
+            "value-case=REQUEST, missing-version=VERSION"
+        );
+
+        let err = parse_wms_kvp(&pairs, ParseMode::Strict).unwrap_err();
+        assert_eq!(err.wms_exception_code(), "InvalidParameterValue");
+        assert!(err.to_string().contains("VERSION is required"));
+
+        let pairs = [("SERVICE", "WMS"), ("REQUEST", "GetMap")];
+        let err = parse_wms_kvp(&pairs, ParseMode::Strict).unwrap_err();
+        assert_eq!(err.wms_exception_code(), "MissingParameterValue");
+
+        let pairs = [("SERVICE", "WMS"), ("REQUEST", "GetCapabilities")];
+        let report = parse_wms_kvp(&pairs, ParseMode::Strict).unwrap();
+        assert_eq!(report.header_value(), None);
+    }
+
+    #[test]
+    fn test_parse_mode_from_str() {
+        assert_eq!("Strict".parse::<ParseMode>().unwrap(), ParseMode::Strict);
+        assert_eq!(
+            " lenient ".parse::<ParseMode>().unwrap(),
+            ParseMode::Lenient
+        );
+        assert!("loose".parse::<ParseMode>().is_err());
+        assert_eq!(ParseMode::default(), ParseMode::Lenient);
+    }
+}
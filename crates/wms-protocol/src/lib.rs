@@ -3,19 +3,31 @@
 //! Supports:
 //! - WMS 1.1.1 and WMS 1.3.0 specifications
 //! - WMTS 1.0.0 specification (KVP and RESTful bindings)
+//! - OGC API - Maps (collection and styled maps)
 
 pub mod exceptions;
 pub mod getfeatureinfo;
 pub mod getmap;
+pub mod kvp;
+pub mod ogc_maps;
 pub mod wmts;
 
 // Re-export GetFeatureInfo types
 pub use getfeatureinfo::{
-    mercator_to_wgs84, pixel_to_geographic, FeatureInfo, FeatureInfoResponse,
-    GetFeatureInfoRequest, InfoFormat, Location,
+    mercator_to_wgs84, pixel_to_geographic, FeatureInfo, FeatureInfoResponse, FeatureLineage,
+    FeatureProvenance, GetFeatureInfoRequest, InfoFormat, Location, QueriedLayer,
+};
+
+pub use ogc_maps::{
+    DocumentFormat, MapCrs, MapFormat, MapQueryParams, MapRequest, DEFAULT_MAP_WIDTH,
+};
+
+pub use kvp::{
+    check_wms_kvp, parse_bbox, parse_wms_kvp, Deviation, DeviationKind, ParseMode, ParseReport,
 };
 
 pub use wmts::{
-    wmts_exception, GetCapabilitiesRequest, GetTileRequest, WmtsCapabilitiesBuilder,
-    WmtsDimensionInfo, WmtsKvpParams, WmtsLayerInfo, WmtsRequest, WmtsRestPath, WmtsStyleInfo,
+    format_wmts_time, parse_wmts_time, wmts_exception, GetCapabilitiesRequest, GetTileRequest,
+    WmtsCapabilitiesBuilder, WmtsDimensionInfo, WmtsKvpParams, WmtsLayerInfo, WmtsRequest,
+    WmtsRestPath, WmtsStyleInfo, CURRENT_TIME, DEFAULT_MAX_TIME_VALUES,
 };
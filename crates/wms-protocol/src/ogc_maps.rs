@@ -0,0 +1,663 @@
+//! OGC API - Maps request parsing and JSON documents.
+//!
+//! A collection's map is requested from `/collections/{collectionId}/map`
+//! (or `/collections/{collectionId}/styles/{styleId}/map`) with the `bbox`,
+//! `bbox-crs`, `crs`, `width`, `height`, `datetime` and `f` query
+//! parameters. Unlike WMS 1.3.0 with EPSG:4326, CRS84 bounding boxes are
+//! always in longitude, latitude order.
+//!
+//! The landing page, conformance declaration and collection documents are
+//! served as JSON, or as simple HTML pages for browsers.
+
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use wms_common::{WmsError, WmsResult};
+
+use crate::getfeatureinfo::mercator_to_wgs84;
+
+/// Conformance class URIs declared at `/conformance`.
+pub mod conformance {
+    /// OGC API - Common core
+    pub const COMMON_CORE: &str = "http://www.opengis.net/spec/ogcapi-common-1/1.0/conf/core";
+    /// Landing page
+    pub const LANDING_PAGE: &str =
+        "http://www.opengis.net/spec/ogcapi-common-1/1.0/conf/landing-page";
+    /// JSON encoding of resources
+    pub const JSON: &str = "http://www.opengis.net/spec/ogcapi-common-1/1.0/conf/json";
+    /// HTML encoding of resources
+    pub const HTML: &str = "http://www.opengis.net/spec/ogcapi-common-1/1.0/conf/html";
+    /// Collections
+    pub const COLLECTIONS: &str =
+        "http://www.opengis.net/spec/ogcapi-common-2/1.0/conf/collections";
+    /// Maps core
+    pub const CORE: &str = "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/core";
+    /// Maps of a single collection
+    pub const COLLECTION_MAP: &str =
+        "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/collection-map";
+    /// Maps in a named style
+    pub const STYLED_MAP: &str = "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/styled-map";
+    /// `width` and `height`
+    pub const SCALING: &str = "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/scaling";
+    /// `bbox` and `bbox-crs`
+    pub const SPATIAL_SUBSETTING: &str =
+        "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/spatial-subsetting";
+    /// `datetime`
+    pub const DATETIME: &str = "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/datetime";
+    /// `crs` (output CRS)
+    pub const CRS: &str = "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/crs";
+    /// PNG maps
+    pub const PNG: &str = "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/png";
+    /// JPEG maps
+    pub const JPEG: &str = "http://www.opengis.net/spec/ogcapi-maps-1/1.0/conf/jpeg";
+
+    /// Every class this implementation declares.
+    pub const ALL: &[&str] = &[
+        COMMON_CORE,
+        LANDING_PAGE,
+        JSON,
+        HTML,
+        COLLECTIONS,
+        CORE,
+        COLLECTION_MAP,
+        STYLED_MAP,
+        SCALING,
+        SPATIAL_SUBSETTING,
+        DATETIME,
+        CRS,
+        PNG,
+        JPEG,
+    ];
+}
+
+/// Map width used when neither `width` nor `height` is given.
+pub const DEFAULT_MAP_WIDTH: u32 = 1024;
+
+/// Largest latitude representable in Web Mercator.
+const MERCATOR_MAX_LAT: f64 = 85.051_128_78;
+
+/// Half the Web Mercator extent in meters.
+const MERCATOR_HALF_EXTENT: f64 = 20_037_508.34;
+
+/// CRS of a map or bounding box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapCrs {
+    /// WGS 84 longitude, latitude
+    Crs84,
+    /// Web Mercator (EPSG:3857)
+    WebMercator,
+}
+
+impl MapCrs {
+    /// CRS URI as advertised in collection documents.
+    pub fn uri(&self) -> &'static str {
+        match self {
+            Self::Crs84 => "http://www.opengis.net/def/crs/OGC/1.3/CRS84",
+            Self::WebMercator => "http://www.opengis.net/def/crs/EPSG/0/3857",
+        }
+    }
+
+    /// Parse a CRS URI, safe CURIE (`[EPSG:3857]`) or short code.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+        let code = value.rsplit(['/', ':']).next()?;
+        match code.to_uppercase().as_str() {
+            "CRS84" | "4326" => Some(Self::Crs84),
+            "3857" | "900913" => Some(Self::WebMercator),
+            _ => None,
+        }
+    }
+
+    /// Transform a bounding box from this CRS to `to`.
+    ///
+    /// Latitudes are clamped to the Web Mercator range when projecting.
+    pub fn transform_bbox(&self, bbox: [f64; 4], to: MapCrs) -> [f64; 4] {
+        match (self, to) {
+            (Self::Crs84, Self::WebMercator) => {
+                let (min_x, min_y) = wgs84_to_mercator(bbox[0], bbox[1]);
+                let (max_x, max_y) = wgs84_to_mercator(bbox[2], bbox[3]);
+                [min_x, min_y, max_x, max_y]
+            }
+            (Self::WebMercator, Self::Crs84) => {
+                let (min_lon, min_lat) = mercator_to_wgs84(bbox[0], bbox[1]);
+                let (max_lon, max_lat) = mercator_to_wgs84(bbox[2], bbox[3]);
+                [min_lon, min_lat, max_lon, max_lat]
+            }
+            _ => bbox,
+        }
+    }
+}
+
+fn wgs84_to_mercator(lon: f64, lat: f64) -> (f64, f64) {
+    let lat = lat.clamp(-MERCATOR_MAX_LAT, MERCATOR_MAX_LAT).to_radians();
+    let x = lon / 180.0 * MERCATOR_HALF_EXTENT;
+    let y = (std::f64::consts::FRAC_PI_4 + lat / 2.0).tan().ln() / std::f64::consts::PI
+        * MERCATOR_HALF_EXTENT;
+    (x, y)
+}
+
+/// Encoding of a map image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl MapFormat {
+    /// Media type of the encoded image.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    /// Parse an `f` value (`png`, `jpeg`, or a media type).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "png" | "image/png" => Some(Self::Png),
+            "jpeg" | "jpg" | "image/jpeg" => Some(Self::Jpeg),
+            "webp" | "image/webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    /// Pick the format from `f`, else the first image type in the Accept
+    /// header that can be produced, else PNG.
+    pub fn negotiate(f: Option<&str>, accept: Option<&str>) -> WmsResult<Self> {
+        if let Some(f) = f {
+            return Self::parse(f).ok_or_else(|| WmsError::UnsupportedFormat(f.to_string()));
+        }
+        let accepted = accept
+            .into_iter()
+            .flat_map(|a| a.split(','))
+            .filter_map(|t| Self::parse(t.split(';').next().unwrap_or("")))
+            .next();
+        Ok(accepted.unwrap_or(Self::Png))
+    }
+}
+
+/// Encoding of a metadata document (landing page, collections).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Json,
+    Html,
+}
+
+impl DocumentFormat {
+    /// Pick the format from `f`, else HTML if the Accept header prefers it
+    /// (browsers), else JSON.
+    pub fn negotiate(f: Option<&str>, accept: Option<&str>) -> WmsResult<Self> {
+        match f.map(|f| f.trim().to_lowercase()) {
+            Some(f) if f == "json" || f == "application/json" => Ok(Self::Json),
+            Some(f) if f == "html" || f == "text/html" => Ok(Self::Html),
+            Some(f) => Err(WmsError::UnsupportedFormat(f)),
+            None => {
+                let first = accept
+                    .and_then(|a| a.split(',').next())
+                    .map(|t| t.split(';').next().unwrap_or("").trim());
+                Ok(match first {
+                    Some("text/html") => Self::Html,
+                    _ => Self::Json,
+                })
+            }
+        }
+    }
+}
+
+/// Query parameters of a map request.
+///
+/// Values are kept as strings so invalid ones are reported as OGC API
+/// errors rather than rejected by the query string parser.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MapQueryParams {
+    pub bbox: Option<String>,
+    #[serde(rename = "bbox-crs")]
+    pub bbox_crs: Option<String>,
+    pub crs: Option<String>,
+    pub width: Option<String>,
+    pub height: Option<String>,
+    pub datetime: Option<String>,
+    pub f: Option<String>,
+}
+
+/// A validated map request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapRequest {
+    /// Area to map in `crs` coordinates; the collection's extent if unset
+    pub bbox: Option<[f64; 4]>,
+    /// CRS of the map
+    pub crs: MapCrs,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Instant to map (ISO 8601); the latest data if unset
+    pub datetime: Option<String>,
+    pub format: MapFormat,
+}
+
+impl MapQueryParams {
+    /// Validate the parameters. `accept` is the request's Accept header,
+    /// used for the format when `f` is not given.
+    pub fn into_request(self, accept: Option<&str>) -> WmsResult<MapRequest> {
+        let crs = parse_crs("crs", self.crs.as_deref())?;
+        let bbox_crs = parse_crs("bbox-crs", self.bbox_crs.as_deref())?;
+
+        let bbox = self
+            .bbox
+            .as_deref()
+            .map(|b| parse_bbox(b, bbox_crs))
+            .transpose()?
+            .map(|b| bbox_crs.transform_bbox(b, crs));
+
+        let datetime = match self.datetime {
+            Some(dt) if dt.contains('/') => {
+                return Err(WmsError::InvalidParameter {
+                    param: "datetime".to_string(),
+                    message: "a map shows a single instant, not an interval".to_string(),
+                })
+            }
+            other => other,
+        };
+
+        Ok(MapRequest {
+            bbox,
+            crs,
+            width: parse_size("width", self.width.as_deref())?,
+            height: parse_size("height", self.height.as_deref())?,
+            datetime,
+            format: MapFormat::negotiate(self.f.as_deref(), accept)?,
+        })
+    }
+}
+
+impl MapRequest {
+    /// Map size in pixels for `bbox` (in the map's CRS).
+    ///
+    /// A missing `width` or `height` follows from the other and the aspect
+    /// ratio of the bounding box; without either the map is
+    /// [`DEFAULT_MAP_WIDTH`] pixels wide.
+    pub fn size(&self, bbox: [f64; 4]) -> (u32, u32) {
+        let aspect = (bbox[2] - bbox[0]) / (bbox[3] - bbox[1]);
+        let scaled = |v: f64| (v.round() as u32).max(1);
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, scaled(width as f64 / aspect)),
+            (None, Some(height)) => (scaled(height as f64 * aspect), height),
+            (None, None) => (DEFAULT_MAP_WIDTH, scaled(DEFAULT_MAP_WIDTH as f64 / aspect)),
+        }
+    }
+}
+
+fn parse_crs(param: &str, value: Option<&str>) -> WmsResult<MapCrs> {
+    match value {
+        None => Ok(MapCrs::Crs84),
+        Some(value) => MapCrs::parse(value).ok_or_else(|| WmsError::InvalidParameter {
+            param: param.to_string(),
+            message: format!("unsupported CRS '{}'", value),
+        }),
+    }
+}
+
+fn parse_bbox(value: &str, crs: MapCrs) -> WmsResult<[f64; 4]> {
+    let invalid = |message: &str| WmsError::InvalidParameter {
+        param: "bbox".to_string(),
+        message: format!("'{}': {}", value, message),
+    };
+
+    let coords = value
+        .split(',')
+        .map(|v| v.trim().parse::<f64>().ok().filter(|v| v.is_finite()))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| invalid("expected four numbers"))?;
+    let [min_x, min_y, max_x, max_y] = coords[..] else {
+        return Err(invalid("expected four numbers"));
+    };
+    if min_x >= max_x || min_y >= max_y {
+        return Err(invalid("minimum must be less than maximum"));
+    }
+    if crs == MapCrs::Crs84 && (min_y < -90.0 || max_y > 90.0) {
+        return Err(invalid("latitude out of range"));
+    }
+    Ok([min_x, min_y, max_x, max_y])
+}
+
+fn parse_size(param: &str, value: Option<&str>) -> WmsResult<Option<u32>> {
+    value
+        .map(|v| match v.trim().parse::<u32>() {
+            Ok(size) if size > 0 => Ok(size),
+            _ => Err(WmsError::InvalidParameter {
+                param: param.to_string(),
+                message: format!("'{}' is not a positive integer", v),
+            }),
+        })
+        .transpose()
+}
+
+/// A link in an OGC API document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub href: String,
+    pub rel: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl Link {
+    pub fn new(href: impl Into<String>, rel: impl Into<String>) -> Self {
+        Self {
+            href: href.into(),
+            rel: rel.into(),
+            media_type: None,
+            title: None,
+        }
+    }
+
+    pub fn with_type(mut self, media_type: impl Into<String>) -> Self {
+        self.media_type = Some(media_type.into());
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// Landing page (`/`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LandingPage {
+    pub title: String,
+    pub description: String,
+    pub links: Vec<Link>,
+}
+
+impl LandingPage {
+    /// HTML rendering for browsers.
+    pub fn to_html(&self) -> String {
+        html_page(&self.title, Some(&self.description), &self.links, "")
+    }
+}
+
+/// Conformance declaration (`/conformance`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConformanceDeclaration {
+    #[serde(rename = "conformsTo")]
+    pub conforms_to: Vec<String>,
+}
+
+impl ConformanceDeclaration {
+    /// HTML rendering for browsers.
+    pub fn to_html(&self) -> String {
+        let items: String = self
+            .conforms_to
+            .iter()
+            .map(|c| format!("<li>{}</li>", escape(c.as_str())))
+            .collect();
+        html_page("Conformance", None, &[], &format!("<ul>{}</ul>", items))
+    }
+}
+
+impl Default for ConformanceDeclaration {
+    /// Declares [`conformance::ALL`].
+    fn default() -> Self {
+        Self {
+            conforms_to: conformance::ALL.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+}
+
+/// Collection list (`/collections`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collections {
+    pub collections: Vec<Collection>,
+    pub links: Vec<Link>,
+}
+
+impl Collections {
+    /// HTML rendering for browsers.
+    pub fn to_html(&self) -> String {
+        let items: String = self
+            .collections
+            .iter()
+            .map(|c| {
+                format!(
+                    "<h2>{}</h2>{}",
+                    escape(c.title.as_str()),
+                    link_list(&c.links)
+                )
+            })
+            .collect();
+        html_page("Collections", None, &self.links, &items)
+    }
+}
+
+/// A collection (one map layer).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extent: Option<Extent>,
+    /// CRSs maps of the collection can be requested in
+    pub crs: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub styles: Vec<CollectionStyle>,
+    pub links: Vec<Link>,
+}
+
+impl Collection {
+    /// HTML rendering for browsers.
+    pub fn to_html(&self) -> String {
+        let styles: String = self
+            .styles
+            .iter()
+            .map(|s| {
+                let title = s.title.as_deref().unwrap_or(&s.id);
+                format!("<h2>Style: {}</h2>{}", escape(title), link_list(&s.links))
+            })
+            .collect();
+        html_page(
+            &self.title,
+            self.description.as_deref(),
+            &self.links,
+            &styles,
+        )
+    }
+}
+
+/// Spatial and temporal extent of a collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Extent {
+    pub spatial: SpatialExtent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temporal: Option<TemporalExtent>,
+}
+
+/// Bounding box in CRS84.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpatialExtent {
+    pub bbox: Vec<[f64; 4]>,
+    pub crs: String,
+}
+
+impl SpatialExtent {
+    pub fn crs84(bbox: [f64; 4]) -> Self {
+        Self {
+            bbox: vec![bbox],
+            crs: MapCrs::Crs84.uri().to_string(),
+        }
+    }
+}
+
+/// Time interval the collection has data for (ISO 8601, open ends null).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemporalExtent {
+    pub interval: Vec<[Option<String>; 2]>,
+    pub trs: String,
+}
+
+impl TemporalExtent {
+    pub fn gregorian(start: Option<String>, end: Option<String>) -> Self {
+        Self {
+            interval: vec![[start, end]],
+            trs: "http://www.opengis.net/def/uom/ISO-8601/0/Gregorian".to_string(),
+        }
+    }
+}
+
+/// A style a collection can be mapped in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionStyle {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub links: Vec<Link>,
+}
+
+/// OGC API exception document (`{"code": .., "description": ..}`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiException {
+    pub code: String,
+    pub description: String,
+}
+
+fn link_list(links: &[Link]) -> String {
+    let items: String = links
+        .iter()
+        .map(|l| {
+            format!(
+                r#"<li><a href="{}">{}</a> ({}{})</li>"#,
+                escape(l.href.as_str()),
+                escape(l.title.as_deref().unwrap_or(&l.href)),
+                escape(l.rel.as_str()),
+                l.media_type
+                    .as_deref()
+                    .map(|t| format!(", {}", escape(t)))
+                    .unwrap_or_default(),
+            )
+        })
+        .collect();
+    format!("<ul>{}</ul>", items)
+}
+
+fn html_page(title: &str, description: Option<&str>, links: &[Link], body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html><html lang="en"><head><meta charset="utf-8"><title>{title}</title></head><body><h1>{title}</h1>{description}{links}{body}</body></html>"#,
+        title = escape(title),
+        description = description
+            .map(|d| format!("<p>{}</p>", escape(d)))
+            .unwrap_or_default(),
+        links = link_list(links),
+        body = body,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> MapQueryParams {
+        let object = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), serde_json::Value::String(v.to_string())))
+            .collect();
+        serde_json::from_value(serde_json::Value::Object(object)).unwrap()
+    }
+
+    #[test]
+    fn test_map_request() {
+        let request = params(&[
+            ("bbox", "-130,20,-60,55"),
+            ("width", "700"),
+            ("datetime", "2024-01-01T12:00:00Z"),
+            ("f", "jpeg"),
+        ])
+        .into_request(None)
+        .unwrap();
+        assert_eq!(request.bbox, Some([-130.0, 20.0, -60.0, 55.0]));
+        assert_eq!(request.crs, MapCrs::Crs84);
+        assert_eq!(request.format, MapFormat::Jpeg);
+        // Height follows from the bbox aspect ratio
+        assert_eq!(request.size(request.bbox.unwrap()), (700, 350));
+
+        let defaults = MapQueryParams::default()
+            .into_request(Some("image/webp,*/*"))
+            .unwrap();
+        assert_eq!(defaults.format, MapFormat::Webp);
+        assert_eq!(defaults.size([0.0, 0.0, 4.0, 1.0]), (1024, 256));
+    }
+
+    #[test]
+    fn test_bbox_crs() {
+        // A CRS84 bbox is projected to the requested map CRS
+        let request = params(&[
+            ("bbox", "-180,-85.0511287798,180,85.0511287798"),
+            ("crs", "http://www.opengis.net/def/crs/EPSG/0/3857"),
+        ])
+        .into_request(None)
+        .unwrap();
+        assert_eq!(request.crs, MapCrs::WebMercator);
+        let bbox = request.bbox.unwrap();
+        assert!((bbox[0] + MERCATOR_HALF_EXTENT).abs() < 1.0);
+        assert!((bbox[3] - MERCATOR_HALF_EXTENT).abs() < 1.0);
+
+        let request = params(&[
+            ("bbox", "0,0,1113194.9,1118890.0"),
+            ("bbox-crs", "[EPSG:3857]"),
+        ])
+        .into_request(None)
+        .unwrap();
+        let bbox = request.bbox.unwrap();
+        assert!((bbox[2] - 10.0).abs() < 1e-3 && (bbox[3] - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        for pairs in [
+            vec![("bbox", "1,2,3")],
+            vec![("bbox", "10,0,0,10")],
+            vec![("bbox", "NaN,0,10,10")],
+            vec![("bbox", "-inf,0,inf,10")],
+            vec![("bbox", "0,-100,10,10")],
+            vec![("width", "0")],
+            vec![("height", "tall")],
+            vec![("crs", "EPSG:32633")],
+            vec![("datetime", "2024-01-01T00:00:00Z/2024-01-02T00:00:00Z")],
+            vec![("f", "tiff")],
+        ] {
+            assert!(params(&pairs).into_request(None).is_err(), "{:?}", pairs);
+        }
+    }
+
+    #[test]
+    fn test_documents() {
+        assert_eq!(
+            DocumentFormat::negotiate(None, Some("text/html,application/xhtml+xml")).unwrap(),
+            DocumentFormat::Html
+        );
+        assert_eq!(
+            DocumentFormat::negotiate(Some("json"), Some("text/html")).unwrap(),
+            DocumentFormat::Json
+        );
+
+        let conformance = serde_json::to_value(ConformanceDeclaration::default()).unwrap();
+        assert!(conformance["conformsTo"]
+            .as_array()
+            .unwrap()
+            .contains(&conformance::COLLECTION_MAP.into()));
+
+        let landing = LandingPage {
+            title: "Maps & more".to_string(),
+            description: "Weather maps".to_string(),
+            links: vec![Link::new("/ogc/conformance", "conformance").with_type("application/json")],
+        };
+        let html = landing.to_html();
+        assert!(html.contains("<h1>Maps &amp; more</h1>"));
+        assert!(html.contains(r#"<a href="/ogc/conformance">"#));
+    }
+}
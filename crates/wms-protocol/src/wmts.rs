@@ -2,6 +2,7 @@
 //!
 //! Supports WMTS 1.0.0 specification with both KVP and RESTful bindings.
 // TODO ask claude if this file is used
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::Deserialize;
 
 use wms_common::{BoundingBox, CrsCode, TileCoord, TileMatrixSet, WmsError, WmsResult};
@@ -195,7 +196,7 @@ impl WmtsKvpParams {
                     tile_matrix,
                     tile_row,
                     tile_col,
-                    time: self.time,
+                    time: normalize_time_param(self.time)?,
                     elevation: None,
                     dimensions: std::collections::HashMap::new(),
                 }))
@@ -326,7 +327,7 @@ impl WmtsRestPath {
             tile_row,
             tile_col,
             format: format.to_string(),
-            time,
+            time: normalize_time_param(time)?,
         })
     }
 
@@ -376,6 +377,7 @@ pub struct WmtsStyleInfo {
     pub is_default: bool,
 }
 
+/// A layer dimension advertised in capabilities, with its explicit values.
 #[derive(Debug, Clone)]
 pub struct WmtsDimensionInfo {
     pub identifier: String,
@@ -383,6 +385,104 @@ pub struct WmtsDimensionInfo {
     pub values: Vec<String>,
 }
 
+impl WmtsDimensionInfo {
+    /// TIME dimension of an observation layer: the `max_values` most recent
+    /// of `times` as ISO 8601 instants, newest first, defaulting to the
+    /// newest. A layer with no times advertises only `current`.
+    pub fn time(times: impl IntoIterator<Item = DateTime<Utc>>, max_values: usize) -> Self {
+        let mut times: Vec<DateTime<Utc>> = times.into_iter().collect();
+        times.sort_unstable_by(|a, b| b.cmp(a));
+        times.dedup();
+        times.truncate(max_values);
+
+        let values: Vec<String> = times.into_iter().map(format_wmts_time).collect();
+        let default = values
+            .first()
+            .cloned()
+            .unwrap_or_else(|| CURRENT_TIME.to_string());
+        Self {
+            identifier: "time".to_string(),
+            values: if values.is_empty() {
+                vec![CURRENT_TIME.to_string()]
+            } else {
+                values
+            },
+            default,
+        }
+    }
+
+    /// `<Dimension>` element for a layer in the capabilities document.
+    pub fn to_xml(&self) -> String {
+        let mut xml = format!(
+            r#"      <Dimension>
+        <ows:Identifier>{}</ows:Identifier>
+        <Default>{}</Default>
+"#,
+            self.identifier, self.default
+        );
+        for value in &self.values {
+            xml.push_str(&format!("        <Value>{}</Value>\n", value));
+        }
+        xml.push_str("      </Dimension>");
+        xml
+    }
+}
+
+/// Most recent observation times advertised per layer by default. GOES and
+/// MRMS layers accumulate thousands of times, which would bloat the
+/// capabilities document.
+pub const DEFAULT_MAX_TIME_VALUES: usize = 100;
+
+/// TIME value asking for the most recent data.
+pub const CURRENT_TIME: &str = "current";
+
+/// Format a TIME value the way capabilities advertise it.
+pub fn format_wmts_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// Parse a WMTS TIME value, which must be an ISO 8601 instant (e.g.
+/// `2024-01-15T12:00:00Z`, with or without seconds or fractional seconds,
+/// or with a UTC offset). `current` and `latest` ask for the most recent
+/// data and parse to None.
+pub fn parse_wmts_time(value: &str) -> WmsResult<Option<DateTime<Utc>>> {
+    let value = value.trim();
+    if value.is_empty()
+        || value.eq_ignore_ascii_case(CURRENT_TIME)
+        || value.eq_ignore_ascii_case("latest")
+    {
+        return Ok(None);
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(time.with_timezone(&Utc)));
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.fZ", "%Y-%m-%dT%H:%MZ"] {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(Some(time.and_utc()));
+        }
+    }
+
+    let hint = if value.parse::<u32>().is_ok() {
+        " (forecast hours go in the FORECAST dimension)"
+    } else {
+        ""
+    };
+    Err(WmsError::InvalidTime(format!(
+        "TIME '{}' is not an ISO 8601 time such as 2024-01-15T12:00:00Z{}",
+        value, hint
+    )))
+}
+
+/// Normalize an optional TIME parameter to the advertised format, dropping
+/// requests for the most recent data.
+fn normalize_time_param(time: Option<String>) -> WmsResult<Option<String>> {
+    Ok(match time {
+        Some(time) => parse_wmts_time(&time)?.map(format_wmts_time),
+        None => None,
+    })
+}
+
 impl WmtsCapabilitiesBuilder {
     pub fn build(&self) -> String {
         let mut xml = String::new();
@@ -513,17 +613,8 @@ impl WmtsCapabilitiesBuilder {
 
             // Dimensions (TIME, etc.)
             for dim in &layer.dimensions {
-                xml.push_str(&format!(
-                    r#"      <Dimension>
-        <ows:Identifier>{}</ows:Identifier>
-        <Default>{}</Default>
-        <Value>{}</Value>
-      </Dimension>
-"#,
-                    dim.identifier,
-                    dim.default,
-                    dim.values.join(",")
-                ));
+                xml.push_str(&dim.to_xml());
+                xml.push('\n');
             }
 
             // ResourceURL (RESTful)
@@ -634,6 +725,90 @@ mod tests {
 
         assert_eq!(parsed.layer, "gfs_temp");
         assert_eq!(parsed.time, Some("2024-01-15T12:00:00Z".to_string()));
+
+        // Times are normalized to the advertised format
+        let path = "/goes18_CMI_C13/default/2024-01-15T07:00:00-05:00/WebMercatorQuad/5/10/15.png";
+        let parsed = WmtsRestPath::parse(path).unwrap();
+        assert_eq!(parsed.time, Some("2024-01-15T12:00:00Z".to_string()));
+
+        let path = "/goes18_CMI_C13/default/current/WebMercatorQuad/5/10/15.png";
+        assert_eq!(WmtsRestPath::parse(path).unwrap().time, None);
+
+        let path = "/goes18_CMI_C13/default/6/WebMercatorQuad/5/10/15.png";
+        assert!(WmtsRestPath::parse(path).is_err());
+    }
+
+    #[test]
+    fn test_parse_wmts_time() {
+        let expected = "2024-01-15T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        for value in [
+            "2024-01-15T12:00:00Z",
+            "2024-01-15T12:00:00.000Z",
+            "2024-01-15T12:00Z",
+            "2024-01-15T13:00:00+01:00",
+        ] {
+            assert_eq!(parse_wmts_time(value).unwrap(), Some(expected), "{}", value);
+        }
+        assert_eq!(parse_wmts_time("current").unwrap(), None);
+        assert_eq!(parse_wmts_time("LATEST").unwrap(), None);
+
+        let err = parse_wmts_time("6").unwrap_err().to_string();
+        assert!(err.contains("FORECAST"), "{}", err);
+        assert!(parse_wmts_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_kvp_time_normalized() {
+        let params = |time: &str| WmtsKvpParams {
+            service: Some("WMTS".to_string()),
+            request: Some("GetTile".to_string()),
+            version: None,
+            layer: Some("goes18_CMI_C13".to_string()),
+            style: None,
+            format: None,
+            tile_matrix_set: Some("WebMercatorQuad".to_string()),
+            tile_matrix: Some("5".to_string()),
+            tile_row: Some(10),
+            tile_col: Some(15),
+            time: Some(time.to_string()),
+            i: None,
+            j: None,
+            info_format: None,
+        };
+
+        let Ok(WmtsRequest::GetTile(request)) = params("2024-01-15T12:00Z").into_request() else {
+            panic!("expected GetTile");
+        };
+        assert_eq!(request.time.as_deref(), Some("2024-01-15T12:00:00Z"));
+        assert!(params("f006").into_request().is_err());
+    }
+
+    #[test]
+    fn test_time_dimension_info() {
+        let t = |hour: u32| {
+            format!("2024-01-15T{:02}:00:00Z", hour)
+                .parse::<DateTime<Utc>>()
+                .unwrap()
+        };
+        let dim = WmtsDimensionInfo::time([t(10), t(12), t(11), t(12), t(9)], 3);
+        assert_eq!(dim.identifier, "time");
+        assert_eq!(dim.default, "2024-01-15T12:00:00Z");
+        assert_eq!(
+            dim.values,
+            [
+                "2024-01-15T12:00:00Z",
+                "2024-01-15T11:00:00Z",
+                "2024-01-15T10:00:00Z"
+            ]
+        );
+
+        let xml = dim.to_xml();
+        assert_eq!(xml.matches("<Value>").count(), 3);
+        assert!(xml.contains("<Default>2024-01-15T12:00:00Z</Default>"));
+
+        let empty = WmtsDimensionInfo::time([], DEFAULT_MAX_TIME_VALUES);
+        assert_eq!(empty.default, CURRENT_TIME);
+        assert_eq!(empty.values, [CURRENT_TIME]);
     }
 
     #[test]
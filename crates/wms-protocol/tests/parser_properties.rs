@@ -0,0 +1,281 @@
+//! Property tests for the WMS, WMTS and OGC API - Maps request parsers.
+//!
+//! Request parameters come straight from the query string or URL path, so
+//! every parser must return an error, never panic, whatever it is given.
+//! Each parser is fed arbitrary strings and, because random text rarely gets
+//! past the first check, strings built from the tokens real requests use.
+//! Well-formed requests must parse back to the values they were built from.
+//!
+//! The `fuzz/` crate at the repository root runs the same parsers under
+//! cargo-fuzz.
+
+use chrono::{DateTime, TimeZone, Utc};
+use proptest::prelude::*;
+use wms_protocol::{
+    check_wms_kvp, format_wmts_time, parse_bbox, parse_wms_kvp, parse_wmts_time, DocumentFormat,
+    MapFormat, MapQueryParams, ParseMode, WmtsKvpParams, WmtsRequest, WmtsRestPath,
+};
+
+// ============================================================================
+// Generators
+// ============================================================================
+
+/// Numbers as they appear in requests, including the awkward ones.
+fn number_text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<f64>().prop_map(|v| v.to_string()),
+        (-1000i64..1000).prop_map(|v| v.to_string()),
+        Just("NaN".to_string()),
+        Just("inf".to_string()),
+        Just("-0".to_string()),
+        Just("1e400".to_string()),
+        Just(String::new()),
+        "[0-9eE+.-]{1,12}",
+    ]
+}
+
+/// Comma-separated lists of number-like values.
+fn number_list() -> impl Strategy<Value = String> {
+    prop::collection::vec(number_text(), 0..7).prop_map(|parts| parts.join(","))
+}
+
+/// KVP keys, mostly the ones the parsers look for, in any case.
+fn kvp_key() -> impl Strategy<Value = String> {
+    prop_oneof![
+        prop::sample::select(vec![
+            "SERVICE", "service", "REQUEST", "request", "VERSION", "LAYERS", "STYLES", "CRS",
+            "SRS", "BBOX", "WIDTH", "HEIGHT", "FORMAT", "TIME", "I", "J", "X", "Y",
+        ])
+        .prop_map(str::to_string),
+        ".{0,8}",
+    ]
+}
+
+/// KVP values, mostly the ones the parsers compare against.
+fn kvp_value() -> impl Strategy<Value = String> {
+    prop_oneof![
+        prop::sample::select(vec![
+            "WMS",
+            "wms",
+            "GetMap",
+            "getmap",
+            "GetFeatureInfo",
+            "GETCAPABILITIES",
+            "1.3.0",
+            "1.1.1",
+            "EPSG:4326",
+            "",
+        ])
+        .prop_map(str::to_string),
+        number_list(),
+        ".{0,16}",
+    ]
+}
+
+/// URL path segments as a WMTS REST path has them.
+fn path_segment() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[a-zA-Z0-9_]{0,10}",
+        (0u64..=u64::from(u32::MAX) + 1).prop_map(|v| v.to_string()),
+        "[0-9]{0,6}\\.(png|jpg|webp|)",
+        Just("2024-01-15T12:00:00Z".to_string()),
+        Just(".".to_string()),
+        ".{0,6}",
+    ]
+}
+
+/// TIME values: real instants in several spellings, and junk.
+fn time_text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        valid_time().prop_map(format_wmts_time),
+        valid_time().prop_map(|t| t.to_rfc3339()),
+        Just("current".to_string()),
+        Just("latest".to_string()),
+        "[0-9T:Z+.-]{0,25}",
+        ".{0,25}",
+    ]
+}
+
+/// Whole-second instants between 1970 and 2100.
+fn valid_time() -> impl Strategy<Value = DateTime<Utc>> {
+    (0i64..4_102_444_800).prop_map(|secs| Utc.timestamp_opt(secs, 0).unwrap())
+}
+
+fn wmts_kvp_params() -> impl Strategy<Value = WmtsKvpParams> {
+    let text = || prop::option::of(".{0,12}");
+    let request = prop::option::of(prop::sample::select(vec![
+        "GetCapabilities".to_string(),
+        "GetTile".to_string(),
+        "GetFeatureInfo".to_string(),
+        "gettile".to_string(),
+    ]));
+    (
+        (
+            prop::option::of(prop_oneof![Just("WMTS".to_string()), ".{0,5}"]),
+            request,
+            text(),
+            text(),
+            text(),
+            text(),
+            text(),
+        ),
+        (
+            prop::option::of(prop_oneof!["[0-9]{1,3}", ".{0,5}"]),
+            prop::option::of(any::<u32>()),
+            prop::option::of(any::<u32>()),
+            prop::option::of(time_text()),
+            prop::option::of(any::<u32>()),
+            prop::option::of(any::<u32>()),
+            text(),
+        ),
+    )
+        .prop_map(
+            |(
+                (service, request, version, layer, style, format, tile_matrix_set),
+                (tile_matrix, tile_row, tile_col, time, i, j, info_format),
+            )| WmtsKvpParams {
+                service,
+                request,
+                version,
+                layer,
+                style,
+                format,
+                tile_matrix_set,
+                tile_matrix,
+                tile_row,
+                tile_col,
+                time,
+                i,
+                j,
+                info_format,
+            },
+        )
+}
+
+// ============================================================================
+// WMS KVP (GetMap, GetFeatureInfo)
+// ============================================================================
+
+proptest! {
+    #[test]
+    fn kvp_check_never_panics(pairs in prop::collection::vec((kvp_key(), kvp_value()), 0..10)) {
+        let deviations = check_wms_kvp(&pairs);
+        let lenient = parse_wms_kvp(&pairs, ParseMode::Lenient).unwrap();
+        prop_assert_eq!(&lenient.deviations, &deviations);
+
+        // Strict mode rejects exactly the requests with deviations
+        let strict = parse_wms_kvp(&pairs, ParseMode::Strict);
+        prop_assert_eq!(strict.is_ok(), deviations.is_empty());
+    }
+
+    #[test]
+    fn bbox_never_panics(value in prop_oneof![number_list(), ".{0,40}"]) {
+        if let Ok(bbox) = parse_bbox(&value) {
+            prop_assert!(bbox.iter().all(|v| v.is_finite()));
+        }
+    }
+
+    #[test]
+    fn bbox_round_trips(bbox in prop::array::uniform4(-1e7f64..1e7)) {
+        let value = bbox.map(|v| v.to_string()).join(",");
+        prop_assert_eq!(parse_bbox(&value).unwrap(), bbox);
+    }
+}
+
+// ============================================================================
+// WMTS KVP and REST (GetTile, GetFeatureInfo)
+// ============================================================================
+
+proptest! {
+    #[test]
+    fn wmts_kvp_never_panics(params in wmts_kvp_params()) {
+        if let Ok(WmtsRequest::GetTile(tile)) = params.into_request() {
+            let _ = tile.to_tile_coord();
+            let _ = tile.cache_key();
+        }
+    }
+
+    #[test]
+    fn rest_path_never_panics(
+        path in prop_oneof![
+            prop::collection::vec(path_segment(), 0..10).prop_map(|s| s.join("/")),
+            ".{0,60}",
+        ]
+    ) {
+        if let Ok(rest) = WmtsRestPath::parse(&path) {
+            let _ = rest.into_request().to_tile_coord();
+        }
+    }
+
+    #[test]
+    fn rest_path_round_trips(
+        layer in "[a-zA-Z][a-zA-Z0-9_]{0,15}",
+        z in 0u32..24,
+        row in any::<u32>(),
+        col in any::<u32>(),
+        time in prop::option::of(valid_time()),
+    ) {
+        let time_part = time.map(|t| format!("{}/", format_wmts_time(t))).unwrap_or_default();
+        let path = format!("/{}/default/{}WebMercatorQuad/{}/{}/{}.png", layer, time_part, z, row, col);
+        let request = WmtsRestPath::parse(&path).unwrap().into_request();
+        prop_assert_eq!(&request.layer, &layer);
+        prop_assert_eq!(&request.format, "image/png");
+        prop_assert_eq!(&request.time, &time.map(format_wmts_time));
+        let coord = request.to_tile_coord().unwrap();
+        prop_assert_eq!((coord.z, coord.x, coord.y), (z, col, row));
+    }
+
+    #[test]
+    fn wmts_time_never_panics(value in time_text()) {
+        let _ = parse_wmts_time(&value);
+    }
+
+    #[test]
+    fn wmts_time_round_trips(time in valid_time()) {
+        prop_assert_eq!(parse_wmts_time(&format_wmts_time(time)).unwrap(), Some(time));
+        prop_assert_eq!(parse_wmts_time(&time.to_rfc3339()).unwrap(), Some(time));
+    }
+}
+
+// ============================================================================
+// OGC API - Maps
+// ============================================================================
+
+proptest! {
+    #[test]
+    fn map_params_never_panic(
+        bbox in prop::option::of(prop_oneof![number_list(), ".{0,30}"]),
+        bbox_crs in prop::option::of(prop_oneof![
+            Just("http://www.opengis.net/def/crs/EPSG/0/3857".to_string()),
+            ".{0,12}",
+        ]),
+        width in prop::option::of(prop_oneof![number_text(), ".{0,6}"]),
+        height in prop::option::of(prop_oneof![number_text(), ".{0,6}"]),
+        datetime in prop::option::of(".{0,25}"),
+        f in prop::option::of(".{0,12}"),
+        accept in prop::option::of(".{0,40}"),
+    ) {
+        let params = MapQueryParams {
+            bbox,
+            bbox_crs,
+            crs: None,
+            width,
+            height,
+            datetime,
+            f,
+        };
+        if let Ok(request) = params.into_request(accept.as_deref()) {
+            if let Some(bbox) = request.bbox {
+                prop_assert!(bbox.iter().all(|v| v.is_finite()), "{:?}", bbox);
+                let (width, height) = request.size(bbox);
+                prop_assert!(width > 0 && height > 0);
+            }
+        }
+    }
+
+    #[test]
+    fn format_negotiation_never_panics(f in prop::option::of(".{0,12}"), accept in prop::option::of(".{0,40}")) {
+        let _ = MapFormat::negotiate(f.as_deref(), accept.as_deref());
+        let _ = DocumentFormat::negotiate(f.as_deref(), accept.as_deref());
+    }
+}
@@ -0,0 +1,24 @@
+//! EDR `coords` WKT and the other free-text query parameters.
+
+#![no_main]
+
+use edr_protocol::{
+    AreaQuery, BboxQuery, DateTimeQuery, DistanceUnit, PositionQuery, RadiusQuery, TrajectoryQuery,
+    VerticalUnit,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|value: &str| {
+    let _ = PositionQuery::parse_coords_multi(value);
+    let _ = PositionQuery::parse_coords(value);
+    let _ = PositionQuery::parse_z(value);
+    let _ = PositionQuery::parse_parameter_names(value);
+    let _ = AreaQuery::parse_polygon_multi(value);
+    let _ = AreaQuery::parse_polygon(value);
+    let _ = TrajectoryQuery::parse_coords(value);
+    let _ = BboxQuery::parse(value);
+    let _ = DateTimeQuery::parse(value);
+    let _ = RadiusQuery::parse_within(value);
+    let _ = DistanceUnit::parse(value);
+    let _ = VerticalUnit::parse(value);
+});
@@ -0,0 +1,77 @@
+//! WMS, WMTS and OGC API - Maps key-value query strings.
+//!
+//! The input is split into `key=value` pairs as a query string would be and
+//! run through the WMS GetMap/GetFeatureInfo checks, the WMTS GetTile and
+//! GetFeatureInfo request builder and the Maps parameter parser.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wms_protocol::{
+    parse_bbox, parse_wms_kvp, InfoFormat, MapQueryParams, ParseMode, WmtsKvpParams, WmtsRequest,
+};
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(query) = std::str::from_utf8(data) else {
+        return;
+    };
+    let pairs: Vec<(&str, &str)> = query
+        .split('&')
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .collect();
+    let get = |key: &str| {
+        pairs
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.to_string())
+    };
+
+    // WMS 1.1.1 / 1.3.0
+    let _ = parse_wms_kvp(&pairs, ParseMode::Lenient);
+    let _ = parse_wms_kvp(&pairs, ParseMode::Strict);
+    if let Some(bbox) = get("BBOX") {
+        let _ = parse_bbox(&bbox);
+    }
+    if let Some(format) = get("INFO_FORMAT") {
+        let _ = InfoFormat::from_mime(&format);
+    }
+
+    // WMTS KVP
+    let number = |key: &str| get(key).and_then(|v| v.parse().ok());
+    let wmts = WmtsKvpParams {
+        service: get("SERVICE"),
+        request: get("REQUEST"),
+        version: get("VERSION"),
+        layer: get("LAYER"),
+        style: get("STYLE"),
+        format: get("FORMAT"),
+        tile_matrix_set: get("TILEMATRIXSET"),
+        tile_matrix: get("TILEMATRIX"),
+        tile_row: number("TILEROW"),
+        tile_col: number("TILECOL"),
+        time: get("TIME"),
+        i: number("I"),
+        j: number("J"),
+        info_format: get("INFOFORMAT"),
+    };
+    if let Ok(WmtsRequest::GetTile(tile)) = wmts.into_request() {
+        let _ = tile.to_tile_coord();
+        let _ = tile.cache_key();
+    }
+
+    // OGC API - Maps
+    let maps = MapQueryParams {
+        bbox: get("bbox"),
+        bbox_crs: get("bbox-crs"),
+        crs: get("crs"),
+        width: get("width"),
+        height: get("height"),
+        datetime: get("datetime"),
+        f: get("f"),
+    };
+    if let Ok(request) = maps.into_request(get("accept").as_deref()) {
+        if let Some(bbox) = request.bbox {
+            let _ = request.size(bbox);
+        }
+    }
+});
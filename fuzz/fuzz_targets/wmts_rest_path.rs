@@ -0,0 +1,17 @@
+//! WMTS RESTful tile paths and TIME values.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wms_protocol::{parse_wmts_time, WmtsRestPath};
+
+fuzz_target!(|path: &str| {
+    if let Ok(rest) = WmtsRestPath::parse(path) {
+        if let Some(time) = &rest.time {
+            let _ = parse_wmts_time(time);
+        }
+        let request = rest.into_request();
+        let _ = request.to_tile_coord();
+        let _ = request.cache_key();
+    }
+});
@@ -11,6 +11,10 @@
 #
 # Note: Criterion 0.5 automatically compares with previous runs stored in target/criterion.
 # The 'save' and 'compare' commands provide manual baseline management.
+#
+# Set PUBLISH_URL (and ADMIN_TOKEN if the API requires it) to store the results,
+# tagged with the current git SHA, in the API's benchmark history:
+#   PUBLISH_URL=http://localhost:8080 ./scripts/run_benchmarks.sh
 
 set -e
 
@@ -169,6 +173,28 @@ case "$ACTION" in
         ;;
 esac
 
+# Store the latest estimates of every benchmark in the API's benchmark history
+if [ -n "$PUBLISH_URL" ] && [ "$ACTION" != "list" ] && [ -d "$CRITERION_DIR" ]; then
+    if ! command -v jq &> /dev/null; then
+        echo "WARNING: 'jq' is required to publish results; skipping."
+    else
+        echo ""
+        echo "Publishing results to $PUBLISH_URL..."
+        BODY=$(cd "$CRITERION_DIR" && find . -path '*/new/estimates.json' | sort | while read -r file; do
+            name=${file#./}
+            jq -c --arg name "${name%/new/estimates.json}" '{name: $name, estimates: .}' "$file"
+        done | jq -s -c '{benchmarks: .}')
+        GIT_SHA=$(git rev-parse HEAD 2>/dev/null || echo "")
+        GIT_BRANCH=$(git rev-parse --abbrev-ref HEAD 2>/dev/null || echo "")
+        curl -sS -f -X POST \
+            -H "Content-Type: application/json" \
+            ${ADMIN_TOKEN:+-H "Authorization: Bearer $ADMIN_TOKEN"} \
+            --data "$BODY" \
+            "$PUBLISH_URL/api/admin/benchmarks/criterion?git_sha=$GIT_SHA&git_branch=$GIT_BRANCH"
+        echo ""
+    fi
+fi
+
 echo ""
 echo "=== Benchmark Complete ==="
 echo "HTML report: file://$CRITERION_DIR/report/index.html"
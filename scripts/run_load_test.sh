@@ -46,6 +46,7 @@ OUTPUT_FORMAT="table"
 RESET_CACHE=false
 SAVE_RESULTS=false
 LOG_REQUESTS=false
+PUBLISH_URL="${PUBLISH_URL:-}"
 RESULTS_DIR="$PROJECT_ROOT/validation/load-test/results"
 
 # Show help
@@ -69,6 +70,8 @@ OPTIONS:
   -r, --reset-cache       Reset Redis cache before test
   -S, --save              Save results to results/ directory
   -l, --log-requests      Log all requests to JSONL for visualization
+  -p, --publish URL       Store results in the benchmark history of the API
+                          at URL (default: \$PUBLISH_URL; uses \$ADMIN_TOKEN)
   -h, --help              Show this help message
 
 EXAMPLES:
@@ -84,6 +87,9 @@ EXAMPLES:
   # Stress test with cache reset
   $0 stress --reset-cache
 
+  # Warm cache test recorded on the /loadtest dashboard
+  $0 warm_cache --log-requests --publish http://localhost:8080
+
 NOTES:
   - Ensure services are running: ./scripts/start.sh
   - Results are saved to: validation/load-test/results/
@@ -116,6 +122,10 @@ while [[ $# -gt 0 ]]; do
             LOG_REQUESTS=true
             shift
             ;;
+        -p|--publish)
+            PUBLISH_URL="$2"
+            shift 2
+            ;;
         -h|--help)
             show_help
             exit 0
@@ -161,6 +171,7 @@ log_info "Output format: $OUTPUT_FORMAT"
 log_info "Reset cache:   $RESET_CACHE"
 log_info "Save results:  $SAVE_RESULTS"
 log_info "Log requests:  $LOG_REQUESTS"
+log_info "Publish to:    ${PUBLISH_URL:-(not published)}"
 log_info "=========================================="
 echo ""
 
@@ -213,6 +224,12 @@ if [ "$LOG_REQUESTS" = true ]; then
     EXTRA_ARGS="$EXTRA_ARGS --log-requests"
 fi
 
+# Only the first run of a saved test is published
+PUBLISH_ARGS=""
+if [ -n "$PUBLISH_URL" ]; then
+    PUBLISH_ARGS="--publish $PUBLISH_URL"
+fi
+
 # Prepare output redirection based on format
 if [ "$SAVE_RESULTS" = true ]; then
     TIMESTAMP=$(date +%Y%m%d_%H%M%S)
@@ -224,7 +241,7 @@ if [ "$SAVE_RESULTS" = true ]; then
         json)
             OUTPUT_FILE="$RESULTS_DIR/${SCENARIO}_${TIMESTAMP}.json"
             log_info "Saving results to: $OUTPUT_FILE"
-            RESULT=$("$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output json $EXTRA_ARGS 2>&1)
+            RESULT=$("$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output json $EXTRA_ARGS $PUBLISH_ARGS 2>&1)
             echo "$RESULT" | tee "$OUTPUT_FILE"
             # Extract just the JSON and append to JSONL for dashboard (compact JSON, one line per record)
             # The JSON starts with { and we want everything from the first { to the last }
@@ -237,7 +254,7 @@ if [ "$SAVE_RESULTS" = true ]; then
                 echo "timestamp,config,duration,requests,rps,p50,p90,p99,cache_hit_rate" > "$OUTPUT_FILE"
             fi
             log_info "Appending results to: $OUTPUT_FILE"
-            RESULT=$("$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output json $EXTRA_ARGS 2>&1)
+            RESULT=$("$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output json $EXTRA_ARGS $PUBLISH_ARGS 2>&1)
             # Extract just the JSON part
             JSON_ONLY=$(echo "$RESULT" | sed -n '/^{/,/^}/p')
             # Save JSON to JSONL for dashboard (compact JSON, one line per record)
@@ -250,7 +267,7 @@ if [ "$SAVE_RESULTS" = true ]; then
         *)
             OUTPUT_FILE="$RESULTS_DIR/${SCENARIO}_${TIMESTAMP}.txt"
             log_info "Saving results to: $OUTPUT_FILE"
-            RESULT=$("$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output table $EXTRA_ARGS | tee "$OUTPUT_FILE")
+            RESULT=$("$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output table $EXTRA_ARGS $PUBLISH_ARGS | tee "$OUTPUT_FILE")
             # Also save JSON to JSONL for dashboard (compact JSON, one line per record)
             JSON_RESULT=$("$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output json $EXTRA_ARGS 2>&1)
             echo "$JSON_RESULT" | sed -n '/^{/,/^}/p' | jq -c '.' >> "$JSONL_FILE" 2>/dev/null || true
@@ -259,7 +276,7 @@ if [ "$SAVE_RESULTS" = true ]; then
     esac
 else
     # Just run without saving
-    "$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output "$OUTPUT_FORMAT" $EXTRA_ARGS
+    "$LOAD_TEST_BIN" run --scenario "$SCENARIO_FILE" --output "$OUTPUT_FORMAT" $EXTRA_ARGS $PUBLISH_ARGS
 fi
 
 echo ""
@@ -287,6 +304,9 @@ log_info "Next steps:"
 log_info "  - View detailed results: cat $OUTPUT_FILE"
 log_info "  - Run more tests: $0 --help"
 log_info "  - Reset cache: ./scripts/reset_test_state.sh"
+if [ -n "$PUBLISH_URL" ]; then
+    log_info "  - Compare with earlier runs: $PUBLISH_URL/loadtest"
+fi
 if [ "$LOG_REQUESTS" = true ]; then
     log_info "  - Visualize requests: open validation/load-test/visualize.html"
     log_info "    (load the JSONL file from results/ directory)"
@@ -50,6 +50,7 @@ fn default_enabled() -> bool {
 pub struct SourceConfig {
     #[serde(rename = "type")]
     pub source_type: String,
+    #[serde(default)]
     pub bucket: String,
     #[serde(default)]
     pub prefix_template: String,
@@ -238,6 +239,11 @@ impl ModelConfig {
         self.schedule.schedule_type == "observation"
     }
 
+    /// Check if the model is generated by the ingester rather than downloaded.
+    pub fn is_synthetic(&self) -> bool {
+        self.source.source_type == "synthetic"
+    }
+
     /// Get the lookback period in minutes for observation data.
     /// Uses retention.hours converted to minutes for observation models.
     /// Falls back to schedule.lookback_minutes if retention is not set or zero.
@@ -279,7 +285,9 @@ pub fn load_model_configs(config_dir: &Path) -> Result<Vec<ModelConfig>> {
         {
             match ModelConfig::load(&path) {
                 Ok(config) => {
-                    if config.model.enabled {
+                    if config.is_synthetic() {
+                        debug!(model = %config.model.id, "Skipping synthetic model (generated by the ingester)");
+                    } else if config.model.enabled {
                         info!(
                             model = %config.model.id,
                             name = %config.model.name,
@@ -465,4 +473,18 @@ retention:
         // When retention.hours is 0, should fallback to schedule.lookback_minutes
         assert_eq!(config.lookback_minutes(), 45);
     }
+
+    #[test]
+    fn test_synthetic_model_is_not_downloaded() {
+        let yaml = include_str!("../../../config/models/synthetic.yaml");
+
+        let config: ModelConfig = serde_yaml::from_str(yaml).unwrap();
+        assert!(config.is_synthetic());
+        assert!(config.source.bucket.is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("models")).unwrap();
+        std::fs::write(dir.path().join("models/synthetic.yaml"), yaml).unwrap();
+        assert!(load_model_configs(dir.path()).unwrap().is_empty());
+    }
 }
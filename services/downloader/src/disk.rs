@@ -0,0 +1,352 @@
+//! Disk space watchdog and download admission control.
+//!
+//! Downloads stream into the temp directory and are then moved to the output
+//! directory. Before a download starts, [`DiskWatchdog::admit`] checks that
+//! its remaining bytes fit with a reserve left free, counting the bytes still
+//! owed to downloads already admitted. When free space drops below the
+//! reserve, the scheduler pauses new downloads until it recovers instead of
+//! letting them fail halfway and retry.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use thiserror::Error;
+use tokio::fs;
+use tracing::{error, info, warn};
+
+/// Suffix of in-progress download files in the temp directory.
+pub const PARTIAL_SUFFIX: &str = ".partial";
+
+/// A download was refused (or stopped) because the disk is too full.
+#[derive(Debug, Error)]
+#[error(
+    "not enough disk space in {}: need {needed} bytes plus {reserve} reserved, {available} free",
+    path.display()
+)]
+pub struct InsufficientSpace {
+    pub path: PathBuf,
+    pub needed: u64,
+    pub reserve: u64,
+    pub available: u64,
+}
+
+/// Whether a download failed because the disk is full, either refused at
+/// admission or with "No space left on device" while writing.
+pub fn is_out_of_space(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause.is::<InsufficientSpace>()
+            || cause
+                .downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::StorageFull)
+    })
+}
+
+/// Free space on the filesystem holding `path`, as available to
+/// unprivileged writers.
+#[allow(clippy::unnecessary_cast)] // statvfs field widths vary by platform
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    // SAFETY: `c_path` is NUL-terminated and `stat` is a valid out pointer.
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok((stat.f_bavail as u64).saturating_mul(stat.f_frsize as u64))
+}
+
+/// Whether `needed` bytes fit in `available` with `reserve` left over.
+fn fits(available: u64, needed: u64, reserve: u64) -> bool {
+    needed.saturating_add(reserve) <= available
+}
+
+/// Disk usage snapshot for the status API.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskStatus {
+    /// Free bytes on the fullest download volume (None if it can't be read)
+    pub available_bytes: Option<u64>,
+    /// Bytes kept free; downloads pause below this
+    pub reserve_bytes: u64,
+    /// Bytes still owed to admitted downloads
+    pub reserved_bytes: u64,
+    /// Whether new downloads are paused for low space
+    pub paused: bool,
+}
+
+/// Watches free space on the temp and output volumes and admits downloads.
+#[derive(Debug)]
+pub struct DiskWatchdog {
+    temp_dir: PathBuf,
+    /// Output directory, if it's on a different filesystem than the temp
+    /// directory (files are then copied rather than renamed).
+    separate_output: Option<PathBuf>,
+    reserve_bytes: u64,
+    /// Remaining bytes of admitted downloads not yet finished.
+    reserved: Arc<Mutex<u64>>,
+    paused: AtomicBool,
+}
+
+impl DiskWatchdog {
+    /// Create a watchdog keeping `reserve_bytes` free on the volumes of
+    /// `temp_dir` and `output_dir` (which should already exist).
+    pub fn new(temp_dir: &Path, output_dir: &Path, reserve_bytes: u64) -> Self {
+        let separate_output = match (device_id(temp_dir), device_id(output_dir)) {
+            (Some(temp), Some(output)) if temp == output => None,
+            _ => Some(output_dir.to_path_buf()),
+        };
+        Self {
+            temp_dir: temp_dir.to_path_buf(),
+            separate_output,
+            reserve_bytes,
+            reserved: Arc::new(Mutex::new(0)),
+            paused: AtomicBool::new(false),
+        }
+    }
+
+    /// Free space on the fullest download volume, and its path.
+    fn available(&self) -> io::Result<(&Path, u64)> {
+        let mut fullest = (self.temp_dir.as_path(), available_space(&self.temp_dir)?);
+        if let Some(output) = &self.separate_output {
+            let available = available_space(output)?;
+            if available < fullest.1 {
+                fullest = (output.as_path(), available);
+            }
+        }
+        Ok(fullest)
+    }
+
+    /// Admit a download that still needs `needed` bytes (0 if its size is
+    /// unknown, which only requires the reserve to be free).
+    ///
+    /// The returned reservation holds the bytes until it's dropped, so
+    /// concurrent downloads can't all be admitted into the same free space.
+    pub fn admit(&self, needed: u64) -> Result<Reservation, InsufficientSpace> {
+        let mut reserved = self.reserved.lock().unwrap();
+        let (path, available) = match self.available() {
+            Ok(available) => available,
+            Err(e) => {
+                // Don't block downloads on a failing statvfs; writes will tell
+                warn!(error = %e, "Failed to read free disk space, admitting download");
+                *reserved += needed;
+                return Ok(self.reservation(needed));
+            }
+        };
+
+        let available = available.saturating_sub(*reserved);
+        if !fits(available, needed, self.reserve_bytes) {
+            return Err(InsufficientSpace {
+                path: path.to_path_buf(),
+                needed,
+                reserve: self.reserve_bytes,
+                available,
+            });
+        }
+        *reserved += needed;
+        Ok(self.reservation(needed))
+    }
+
+    fn reservation(&self, bytes: u64) -> Reservation {
+        Reservation {
+            reserved: self.reserved.clone(),
+            bytes,
+        }
+    }
+
+    /// Re-check free space, pausing downloads when it's below the reserve
+    /// and resuming once it's back. Returns whether downloads are paused.
+    ///
+    /// Entering the paused state is logged as an error, so log-based
+    /// alerting fires once per episode rather than once per download.
+    pub fn check(&self) -> bool {
+        let (path, available) = match self.available() {
+            Ok(available) => available,
+            Err(e) => {
+                warn!(error = %e, "Failed to read free disk space");
+                return self.is_paused();
+            }
+        };
+
+        let low = available < self.reserve_bytes;
+        let was_paused = self.paused.swap(low, Ordering::Relaxed);
+        if low && !was_paused {
+            error!(
+                path = %path.display(),
+                available_mb = available / (1024 * 1024),
+                reserve_mb = self.reserve_bytes / (1024 * 1024),
+                "Disk space low, pausing downloads"
+            );
+        } else if !low && was_paused {
+            info!(
+                path = %path.display(),
+                available_mb = available / (1024 * 1024),
+                "Disk space recovered, resuming downloads"
+            );
+        }
+        low
+    }
+
+    /// Whether downloads are paused, as of the last [`check`](Self::check).
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Current disk usage for the status API.
+    pub fn status(&self) -> DiskStatus {
+        DiskStatus {
+            available_bytes: self.available().ok().map(|(_, available)| available),
+            reserve_bytes: self.reserve_bytes,
+            reserved_bytes: *self.reserved.lock().unwrap(),
+            paused: self.is_paused(),
+        }
+    }
+}
+
+/// Bytes set aside for an admitted download, released when dropped.
+#[derive(Debug)]
+pub struct Reservation {
+    reserved: Arc<Mutex<u64>>,
+    bytes: u64,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let mut reserved = self.reserved.lock().unwrap();
+        *reserved = reserved.saturating_sub(self.bytes);
+    }
+}
+
+fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| m.dev())
+}
+
+/// Delete partial downloads in `temp_dir` that no queued download will
+/// resume, e.g. left behind by downloads that were since dropped from the
+/// state database. `active` holds the filenames of queued downloads.
+///
+/// Returns the number of files and bytes removed.
+pub async fn remove_orphaned_partials(
+    temp_dir: &Path,
+    active: &HashSet<String>,
+) -> io::Result<(usize, u64)> {
+    let mut removed = (0, 0);
+    let mut entries = match fs::read_dir(temp_dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(removed),
+        Err(e) => return Err(e),
+    };
+
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        let Some(filename) = name.to_str().and_then(|n| n.strip_suffix(PARTIAL_SUFFIX)) else {
+            continue;
+        };
+        if active.contains(filename) {
+            continue;
+        }
+
+        let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+        let path = entry.path();
+        match fs::remove_file(&path).await {
+            Ok(()) => {
+                info!(
+                    file = %path.display(),
+                    bytes = size,
+                    "Removed orphaned partial download"
+                );
+                removed.0 += 1;
+                removed.1 += size;
+            }
+            Err(e) => warn!(
+                file = %path.display(),
+                error = %e,
+                "Failed to remove orphaned partial download"
+            ),
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fits() {
+        assert!(fits(1000, 600, 400));
+        assert!(!fits(1000, 601, 400));
+        assert!(fits(1000, 0, 1000));
+        assert!(!fits(1000, u64::MAX, 0));
+    }
+
+    #[test]
+    fn test_admit_counts_reservations() {
+        let dir = tempfile::tempdir().unwrap();
+        let available = available_space(dir.path()).unwrap();
+        assert!(available > 0);
+
+        let watchdog = DiskWatchdog::new(dir.path(), dir.path(), 0);
+        let half = available / 2 + 1;
+        let first = watchdog.admit(half).unwrap();
+        assert_eq!(watchdog.status().reserved_bytes, half);
+        // The second half no longer fits while the first is in flight
+        let err = watchdog.admit(half).unwrap_err();
+        assert_eq!(err.needed, half);
+        drop(first);
+        assert_eq!(watchdog.status().reserved_bytes, 0);
+
+        let watchdog = DiskWatchdog::new(dir.path(), dir.path(), u64::MAX);
+        assert!(watchdog.admit(0).is_err());
+        assert!(is_out_of_space(&watchdog.admit(0).unwrap_err().into()));
+    }
+
+    #[test]
+    fn test_check_pauses_below_reserve() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let watchdog = DiskWatchdog::new(dir.path(), dir.path(), u64::MAX);
+        assert!(!watchdog.is_paused());
+        assert!(watchdog.check());
+        assert!(watchdog.status().paused);
+
+        let watchdog = DiskWatchdog::new(dir.path(), dir.path(), 0);
+        assert!(!watchdog.check());
+    }
+
+    #[test]
+    fn test_is_out_of_space() {
+        let full = io::Error::new(io::ErrorKind::StorageFull, "No space left on device");
+        let err = anyhow::Error::from(full).context("Error writing to file");
+        assert!(is_out_of_space(&err));
+        assert!(!is_out_of_space(&anyhow::anyhow!("HTTP error: 503")));
+    }
+
+    #[tokio::test]
+    async fn test_remove_orphaned_partials() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("gfs.f000.partial"), b"resume me").unwrap();
+        std::fs::write(dir.path().join("gfs.f003.partial"), b"orphan").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"not ours").unwrap();
+
+        let active = HashSet::from(["gfs.f000".to_string()]);
+        let removed = remove_orphaned_partials(dir.path(), &active).await.unwrap();
+        assert_eq!(removed, (1, 6));
+        assert!(dir.path().join("gfs.f000.partial").exists());
+        assert!(!dir.path().join("gfs.f003.partial").exists());
+        assert!(dir.path().join("notes.txt").exists());
+
+        let missing = dir.path().join("missing");
+        assert_eq!(
+            remove_orphaned_partials(&missing, &active).await.unwrap(),
+            (0, 0)
+        );
+    }
+}
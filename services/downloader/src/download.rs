@@ -5,10 +5,12 @@
 //! - Exponential backoff retry on failures
 //! - Progress tracking and persistence
 //! - File integrity verification via Content-Length
+//! - Disk space admission (see [`crate::disk`])
 //!
 //! TODO need to test retry logic?
 
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
@@ -20,6 +22,7 @@ use tokio::fs::{self, OpenOptions};
 use tokio::io::AsyncWriteExt;
 use tracing::{debug, info, instrument, warn};
 
+use crate::disk::{is_out_of_space, DiskWatchdog, PARTIAL_SUFFIX};
 use crate::state::{DownloadState, DownloadStatus};
 
 /// Configuration for the download manager.
@@ -40,6 +43,9 @@ pub struct DownloadConfig {
     pub temp_dir: PathBuf,
     /// Directory for completed downloads
     pub output_dir: PathBuf,
+    /// Free space to keep on the download volumes; downloads that would eat
+    /// into it wait until space frees up
+    pub disk_reserve_bytes: u64,
 }
 
 impl Default for DownloadConfig {
@@ -52,6 +58,7 @@ impl Default for DownloadConfig {
             chunk_size: 64 * 1024,                     // 64KB
             temp_dir: PathBuf::from("/tmp/weather-downloads"),
             output_dir: PathBuf::from("/data/downloads"),
+            disk_reserve_bytes: 1024 * 1024 * 1024, // 1GB
         }
     }
 }
@@ -87,10 +94,14 @@ impl DownloadProgress {
 pub struct DownloadManager {
     client: Client,
     config: DownloadConfig,
+    disk: Arc<DiskWatchdog>,
 }
 
 impl DownloadManager {
     /// Create a new download manager with the given configuration.
+    ///
+    /// The temp and output directories should already exist, so the disk
+    /// watchdog can tell whether they share a filesystem.
     pub fn new(config: DownloadConfig) -> Result<Self> {
         let client = Client::builder()
             .timeout(config.request_timeout)
@@ -100,7 +111,22 @@ impl DownloadManager {
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client, config })
+        let disk = Arc::new(DiskWatchdog::new(
+            &config.temp_dir,
+            &config.output_dir,
+            config.disk_reserve_bytes,
+        ));
+
+        Ok(Self {
+            client,
+            config,
+            disk,
+        })
+    }
+
+    /// Disk space watchdog for the download volumes.
+    pub fn disk(&self) -> Arc<DiskWatchdog> {
+        self.disk.clone()
     }
 
     /// Download a file with automatic retry and resumption.
@@ -117,7 +143,10 @@ impl DownloadManager {
         fs::create_dir_all(&self.config.temp_dir).await?;
         fs::create_dir_all(&self.config.output_dir).await?;
 
-        let temp_path = self.config.temp_dir.join(format!("{}.partial", filename));
+        let temp_path = self
+            .config
+            .temp_dir
+            .join(format!("{}{}", filename, PARTIAL_SUFFIX));
         let final_path = self.config.output_dir.join(filename);
 
         // Check if already completed
@@ -147,6 +176,23 @@ impl DownloadManager {
         progress.downloaded_bytes = resume_from;
         progress.started_at = Utc::now();
 
+        // Only start if the rest of the file fits on disk; otherwise leave it
+        // queued rather than failing halfway through
+        if progress.total_bytes.is_none() {
+            progress.total_bytes = self.get_content_length(url).await.unwrap_or(None);
+        }
+        let remaining = progress
+            .total_bytes
+            .map_or(0, |total| total.saturating_sub(resume_from));
+        let _reservation = match self.disk.admit(remaining) {
+            Ok(reservation) => reservation,
+            Err(e) => {
+                warn!(error = %e, "Deferring download until disk space frees up");
+                state.update_status(url, DownloadStatus::Pending).await?;
+                return Err(e.into());
+            }
+        };
+
         info!(
             url = %url,
             filename = %filename,
@@ -193,6 +239,14 @@ impl DownloadManager {
 
                     return Ok(final_path);
                 }
+                Err(e) if is_out_of_space(&e) => {
+                    // Retrying can't help; keep the partial file to resume
+                    // once space frees up
+                    warn!(error = %e, "Disk full, deferring download");
+                    state.update_progress(url, &progress).await?;
+                    state.update_status(url, DownloadStatus::Pending).await?;
+                    return Err(e);
+                }
                 Err(e) => {
                     retry_count += 1;
                     progress.retry_count = retry_count;
@@ -5,14 +5,17 @@
 //! - Automatic retry with exponential backoff
 //! - Progress persistence to survive restarts
 //! - Triggers ingestion after download completes
+//! - Pauses downloads when the disk runs low
 //! - HTTP status API for monitoring
 
 mod config;
+mod disk;
 mod download;
 mod scheduler;
 mod server;
 mod state;
 
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -20,7 +23,7 @@ use std::time::Duration;
 use anyhow::Result;
 use clap::Parser;
 use tokio::sync::broadcast;
-use tracing::{info, Level};
+use tracing::{info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
 use download::{DownloadConfig, DownloadManager};
@@ -60,6 +63,11 @@ struct Args {
     #[arg(long, default_value = "5")]
     max_retries: u32,
 
+    /// Free disk space (MB) to keep on the download volumes; downloads
+    /// pause below it
+    #[arg(long, env = "DISK_RESERVE_MB", default_value = "1024")]
+    disk_reserve_mb: u64,
+
     /// Ingester URL for triggering ingestion after download
     #[arg(long, env = "INGESTER_URL")]
     ingester_url: Option<String>,
@@ -127,6 +135,7 @@ async fn main() -> Result<()> {
         chunk_size: 64 * 1024,
         temp_dir: args.temp_dir.clone(),
         output_dir: args.output_dir.clone(),
+        disk_reserve_bytes: args.disk_reserve_mb * 1024 * 1024,
     };
     let download_manager = Arc::new(DownloadManager::new(download_config)?);
 
@@ -139,6 +148,15 @@ async fn main() -> Result<()> {
         );
     }
 
+    // Partial files nothing will resume only take up space
+    let active: HashSet<String> = in_progress.iter().map(|r| r.filename.clone()).collect();
+    match disk::remove_orphaned_partials(&args.temp_dir, &active).await {
+        Ok((0, _)) => {}
+        Ok((files, bytes)) => info!(files, bytes, "Removed orphaned partial downloads"),
+        Err(e) => warn!(error = %e, "Failed to clean up orphaned partial downloads"),
+    }
+    download_manager.disk().check();
+
     // Create scheduler
     let scheduler = Scheduler::new(
         download_manager.clone(),
@@ -156,6 +174,7 @@ async fn main() -> Result<()> {
     let server_state = Arc::new(ServerState {
         download_state: state.clone(),
         model_schedules,
+        disk: download_manager.disk(),
     });
 
     // Shutdown signal
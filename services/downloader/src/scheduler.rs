@@ -8,12 +8,13 @@ use std::time::Duration;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Datelike, Duration as ChronoDuration, Timelike, Utc};
 use futures::stream::{self, StreamExt};
-use reqwest::Client;
+use reqwest::{header, Client, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 use tracing::{debug, error, info, instrument, warn};
 
 use crate::config::{self, ModelConfig};
+use crate::disk::is_out_of_space;
 use crate::download::DownloadManager;
 use crate::state::DownloadState;
 
@@ -177,6 +178,12 @@ impl Scheduler {
             self.state.queue_download(url, filename, model_id).await?;
         }
 
+        // Leave the queue for a later cycle while the disk is low
+        if self.download_manager.disk().check() {
+            warn!(model = %model_id, "Disk space low, not starting downloads");
+            return Ok(());
+        }
+
         // Process download queue with concurrency limit
         // Each download triggers ingestion immediately upon completion
         let pending = self.state.get_in_progress().await?;
@@ -210,6 +217,14 @@ impl Scheduler {
                                         info!(file = %record.filename, "Ingestion triggered successfully");
                                         let _ = state.mark_ingested(&record.url).await;
                                     }
+                                    Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                                        // Left pending; trigger_ingestion retries it next round
+                                        info!(
+                                            file = %record.filename,
+                                            retry_after = ?retry_after(&response),
+                                            "Ingester queue full, deferring ingestion"
+                                        );
+                                    }
                                     Ok(response) => {
                                         warn!(
                                             file = %record.filename,
@@ -225,6 +240,10 @@ impl Scheduler {
 
                             Ok(path)
                         }
+                        Err(e) if is_out_of_space(&e) => {
+                            warn!(url = %record.url, error = %e, "Download deferred for disk space");
+                            Err(e)
+                        }
                         Err(e) => {
                             error!(url = %record.url, error = %e, "Download failed");
                             Err(e)
@@ -574,15 +593,11 @@ impl Scheduler {
     ) -> Result<Vec<(String, String)>> {
         let mut files = Vec::new();
 
-        // GOES-16 and GOES-18 satellite imagery
+        // GOES satellite imagery (goes16, goes18, goes19, ...)
         // Files are available every 5-10 minutes
         // Format: OR_ABI-L2-CMIPC-M{mode}C{band:02}_G{satellite}_s{start}_e{end}_c{created}.nc
 
-        let satellite_num = if model.model.id == "goes16" {
-            "16"
-        } else {
-            "18"
-        };
+        let satellite_num = model.model.id.trim_start_matches("goes");
 
         // Calculate hours to check - need to cover entire lookback period
         // Add 1 to ensure we include the current partial hour
@@ -912,6 +927,16 @@ impl Scheduler {
                     info!(file = %filename, "Ingestion triggered successfully");
                     self.state.mark_ingested(&url).await?;
                 }
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    // The ingester is saturated; the remaining files stay
+                    // pending until the next round
+                    info!(
+                        file = %filename,
+                        retry_after = ?retry_after(&response),
+                        "Ingester queue full, deferring remaining ingestion"
+                    );
+                    break;
+                }
                 Ok(response) => {
                     warn!(
                         file = %filename,
@@ -936,3 +961,14 @@ impl Scheduler {
         Vec::new()
     }
 }
+
+/// Seconds from a response's `Retry-After` header, if present.
+fn retry_after(response: &Response) -> Option<u64> {
+    response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
@@ -19,6 +19,7 @@ use serde::{Deserialize, Serialize};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 
+use crate::disk::{DiskStatus, DiskWatchdog};
 use crate::scheduler::ModelSchedule;
 use crate::state::{DownloadState, DownloadStatus};
 
@@ -34,6 +35,7 @@ pub struct StatusResponse {
     pub active_downloads: Vec<ActiveDownload>,
     pub recent_completed: Vec<CompletedDownloadResponse>,
     pub pending_ingestion: usize,
+    pub disk: DiskStatus,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -149,6 +151,7 @@ pub struct RetryQuery {
 pub struct ServerState {
     pub download_state: Arc<DownloadState>,
     pub model_schedules: Vec<ModelSchedule>,
+    pub disk: Arc<DiskWatchdog>,
 }
 
 // ============================================================================
@@ -248,9 +251,13 @@ async fn status_handler(Extension(state): Extension<Arc<ServerState>>) -> impl I
         .map(|v| v.len())
         .unwrap_or(0);
 
+    let disk = state.disk.status();
+
     let response = StatusResponse {
         service: "downloader".to_string(),
-        status: if stats.in_progress > 0 {
+        status: if disk.paused {
+            "paused_low_disk".to_string()
+        } else if stats.in_progress > 0 {
             "downloading".to_string()
         } else if stats.pending > 0 {
             "pending".to_string()
@@ -261,6 +268,7 @@ async fn status_handler(Extension(state): Extension<Arc<ServerState>>) -> impl I
         active_downloads: active,
         recent_completed: recent,
         pending_ingestion,
+        disk,
     };
 
     Json(response).into_response()
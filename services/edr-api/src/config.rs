@@ -84,6 +84,46 @@ impl EdrConfig {
             .collect()
     }
 
+    /// Copy of the configuration with only the parameters `visible`
+    /// accepts (given the model and parameter name), dropping collections
+    /// left without any.
+    pub fn scoped(&self, visible: impl Fn(&str, &str) -> bool) -> Self {
+        let models = self
+            .models
+            .iter()
+            .map(|(id, model)| {
+                let collections = model
+                    .collections
+                    .iter()
+                    .filter_map(|collection| {
+                        let parameters: Vec<ParameterDefinition> = collection
+                            .parameters
+                            .iter()
+                            .filter(|p| visible(&model.model, &p.name))
+                            .cloned()
+                            .collect();
+                        (!parameters.is_empty()).then(|| CollectionDefinition {
+                            parameters,
+                            ..collection.clone()
+                        })
+                    })
+                    .collect();
+                (
+                    id.clone(),
+                    ModelEdrConfig {
+                        collections,
+                        ..model.clone()
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            models,
+            locations: self.locations.clone(),
+        }
+    }
+
     /// Find a collection by ID.
     pub fn find_collection(&self, id: &str) -> Option<(&ModelEdrConfig, &CollectionDefinition)> {
         for model_config in self.models.values() {
@@ -139,6 +179,11 @@ pub struct CollectionDefinition {
     /// Run mode (instances or latest).
     #[serde(default)]
     pub run_mode: RunMode,
+
+    /// How often new data arrives, in seconds. Cached query responses
+    /// live this long (EDR_RESPONSE_CACHE_TTL_SECS if unset).
+    #[serde(default)]
+    pub update_interval_secs: Option<u64>,
 }
 
 /// Filter for selecting levels by type.
@@ -279,6 +324,14 @@ pub struct LimitsConfig {
     /// Maximum corridor length in km.
     #[serde(default = "default_max_corridor_length")]
     pub max_corridor_length_km: Option<f64>,
+
+    /// Maximum cost of a single query (points × parameters × times × levels).
+    #[serde(default)]
+    pub max_query_cost: Option<u64>,
+
+    /// Total query cost each API key may consume per hour.
+    #[serde(default)]
+    pub hourly_cost_budget: Option<u64>,
 }
 
 impl Default for LimitsConfig {
@@ -292,6 +345,8 @@ impl Default for LimitsConfig {
             max_radius_km: default_max_radius(),
             max_trajectory_points: default_max_trajectory_points(),
             max_corridor_length_km: default_max_corridor_length(),
+            max_query_cost: None,
+            hourly_cost_budget: None,
         }
     }
 }
@@ -460,4 +515,33 @@ limits:
         assert_eq!(config.limits.max_vertical_levels, 1);
         assert_eq!(config.limits.max_area_sq_degrees, Some(50.0));
     }
+
+    #[test]
+    fn test_scoped_config() {
+        let yaml = r#"
+model: goes18
+collections:
+  - id: goes18-infrared
+    parameters:
+      - name: CMI_C13
+      - name: CMI_C14
+  - id: goes18-visible
+    parameters:
+      - name: CMI_C02
+"#;
+        let model: ModelEdrConfig = serde_yaml::from_str(yaml).unwrap();
+        let config = EdrConfig {
+            models: HashMap::from([("goes18".to_string(), model)]),
+            locations: LocationsConfig::default(),
+        };
+
+        let scoped = config.scoped(|model, parameter| model == "goes18" && parameter == "CMI_C13");
+        let (_, infrared) = scoped.find_collection("goes18-infrared").unwrap();
+        assert_eq!(infrared.parameters.len(), 1);
+        assert_eq!(infrared.parameters[0].name, "CMI_C13");
+        assert!(scoped.find_collection("goes18-visible").is_none());
+
+        // The original is untouched
+        assert!(config.find_collection("goes18-visible").is_some());
+    }
 }
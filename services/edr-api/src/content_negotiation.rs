@@ -3,9 +3,11 @@
 //! Per OGC EDR spec and RFC 7231, the server should respect Accept headers
 //! and return 406 Not Acceptable if the requested format is not supported.
 
-use axum::http::{header, HeaderMap, StatusCode};
+use axum::http::{header, HeaderMap};
 use axum::response::Response;
-use edr_protocol::responses::ExceptionResponse;
+use edr_protocol::EdrError;
+
+use crate::problem::error_response;
 
 /// Supported media types for data queries (position, area, etc.)
 pub const DATA_QUERY_MEDIA_TYPES: &[&str] = &[
@@ -29,6 +31,9 @@ pub enum OutputFormat {
 }
 
 impl OutputFormat {
+    /// Every output format with an encoder in this build.
+    pub const ALL: [OutputFormat; 2] = [OutputFormat::CoverageJson, OutputFormat::GeoJson];
+
     /// Get the Content-Type header value for this format.
     pub fn content_type(&self) -> &'static str {
         match self {
@@ -69,6 +74,117 @@ impl OutputFormat {
     }
 }
 
+/// Raster output formats for grid (area and cube) queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RasterFormat {
+    /// Arc/Info ASCII Grid
+    AsciiGrid,
+    /// float32 BIL with `.hdr`/`.prj` sidecars, bundled in a ZIP archive
+    Bil,
+}
+
+impl RasterFormat {
+    /// Get the Content-Type header value for this format.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            RasterFormat::AsciiGrid => "application/x-ascii-grid",
+            RasterFormat::Bil => "application/zip",
+        }
+    }
+
+    /// File extension for the downloaded file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            RasterFormat::AsciiGrid => "asc",
+            RasterFormat::Bil => "zip",
+        }
+    }
+
+    /// Parse format from the `f` query parameter value.
+    pub fn from_query_param(f: &str) -> Option<Self> {
+        match f.to_lowercase().as_str() {
+            "asc" | "aaigrid" | "ascii-grid" | "application/x-ascii-grid" => {
+                Some(RasterFormat::AsciiGrid)
+            }
+            "bil" | "ehdr" => Some(RasterFormat::Bil),
+            _ => None,
+        }
+    }
+
+    /// Parse format from Accept header media type.
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type {
+            "application/x-ascii-grid" => Some(RasterFormat::AsciiGrid),
+            "application/zip" => Some(RasterFormat::Bil),
+            _ => None,
+        }
+    }
+}
+
+/// Media type of CF-compliant NetCDF grid downloads.
+pub const NETCDF_MEDIA_TYPE: &str = "application/x-netcdf";
+
+/// Whether an `f` parameter value asks for NetCDF.
+fn is_netcdf_param(f: &str) -> bool {
+    matches!(
+        f.to_lowercase().as_str(),
+        "netcdf" | "nc" | NETCDF_MEDIA_TYPE
+    )
+}
+
+/// Output format for grid query responses: JSON, a raster export or NetCDF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridOutputFormat {
+    /// CoverageJSON or GeoJSON
+    Json(OutputFormat),
+    /// Raster file for GIS tools
+    Raster(RasterFormat),
+    /// CF-compliant NetCDF with units, grid mapping, time and levels
+    NetCdf,
+}
+
+/// Negotiate the output format of a grid query.
+///
+/// Same rules as [`negotiate_format`], additionally accepting the raster
+/// formats and NetCDF. A file media type is only chosen from the Accept
+/// header when it ranks above every JSON type and wildcard, so `*/*` keeps
+/// returning CoverageJSON.
+pub fn negotiate_grid_format(
+    headers: &HeaderMap,
+    f_param: Option<&str>,
+) -> Result<GridOutputFormat, Response> {
+    match f_param.filter(|f| !f.is_empty()) {
+        Some(f) => {
+            if let Some(format) = RasterFormat::from_query_param(f) {
+                return Ok(GridOutputFormat::Raster(format));
+            }
+            if is_netcdf_param(f) {
+                return Ok(GridOutputFormat::NetCdf);
+            }
+            if OutputFormat::from_query_param(f).is_none() {
+                return Err(invalid_grid_format_response(f));
+            }
+        }
+        None => {
+            // Use a file format if one ranks above every JSON type
+            for (media_type, _) in accepted_media_types(headers) {
+                if let Some(format) = RasterFormat::from_media_type(media_type) {
+                    return Ok(GridOutputFormat::Raster(format));
+                }
+                if media_type == NETCDF_MEDIA_TYPE {
+                    return Ok(GridOutputFormat::NetCdf);
+                }
+                if media_type.ends_with("/*") || OutputFormat::from_media_type(media_type).is_some()
+                {
+                    break;
+                }
+            }
+        }
+    }
+
+    negotiate_format(headers, f_param).map(GridOutputFormat::Json)
+}
+
 /// Negotiate the output format based on the `f` query parameter and Accept header.
 ///
 /// Priority:
@@ -96,6 +212,34 @@ pub fn negotiate_format(
     }
 
     // Next, check Accept header
+    let accepted_types = accepted_media_types(headers);
+
+    // Find the first matching format
+    for (media_type, _) in &accepted_types {
+        // Handle wildcards - default to CoverageJSON
+        if *media_type == "*/*" || *media_type == "application/*" {
+            return Ok(OutputFormat::CoverageJson);
+        }
+
+        if let Some(format) = OutputFormat::from_media_type(media_type) {
+            return Ok(format);
+        }
+    }
+
+    // Check if any type is acceptable (no Accept header or only wildcards)
+    if accepted_types.is_empty() {
+        return Ok(OutputFormat::CoverageJson);
+    }
+
+    // No acceptable format found - return 406
+    let requested: Vec<&str> = accepted_types.iter().map(|(t, _)| *t).collect();
+    Err(not_acceptable_response(&requested, DATA_QUERY_MEDIA_TYPES))
+}
+
+/// Media types from the Accept header with their quality values, best first.
+///
+/// A missing Accept header is treated as `*/*`.
+fn accepted_media_types(headers: &HeaderMap) -> Vec<(&str, f32)> {
     let accept = headers
         .get(header::ACCEPT)
         .and_then(|v| v.to_str().ok())
@@ -130,27 +274,7 @@ pub fn negotiate_format(
 
     // Sort by quality (highest first)
     accepted_types.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-    // Find the first matching format
-    for (media_type, _) in &accepted_types {
-        // Handle wildcards - default to CoverageJSON
-        if *media_type == "*/*" || *media_type == "application/*" {
-            return Ok(OutputFormat::CoverageJson);
-        }
-
-        if let Some(format) = OutputFormat::from_media_type(media_type) {
-            return Ok(format);
-        }
-    }
-
-    // Check if any type is acceptable (no Accept header or only wildcards)
-    if accepted_types.is_empty() {
-        return Ok(OutputFormat::CoverageJson);
-    }
-
-    // No acceptable format found - return 406
-    let requested: Vec<&str> = accepted_types.iter().map(|(t, _)| *t).collect();
-    Err(not_acceptable_response(&requested, DATA_QUERY_MEDIA_TYPES))
+    accepted_types
 }
 
 /// Check if the Accept header is compatible with the supported media types.
@@ -202,45 +326,32 @@ pub fn check_accept_header(headers: &HeaderMap, supported_types: &[&str]) -> Res
 
 /// Create a 406 Not Acceptable response
 fn not_acceptable_response(requested: &[&str], supported: &[&str]) -> Response {
-    let exc = ExceptionResponse::new(
-        "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0/invalid-parameter-value",
-        406,
-        format!(
-            "Content negotiation failed. Requested format(s) '{}' not supported. Supported formats: {}",
-            requested.join(", "),
-            supported.iter().filter(|s| **s != "*/*").cloned().collect::<Vec<_>>().join(", ")
-        ),
-    )
-    .with_title("Not Acceptable");
-
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-
-    Response::builder()
-        .status(StatusCode::NOT_ACCEPTABLE)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
+    error_response(EdrError::NotAcceptable(format!(
+        "Requested format(s) '{}' not supported. Supported formats: {}",
+        requested.join(", "),
+        supported
+            .iter()
+            .filter(|s| **s != "*/*")
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    )))
 }
 
 /// Create a 400 Bad Request response for invalid format parameter
 fn invalid_format_response(format: &str) -> Response {
-    let exc = ExceptionResponse::new(
-        "http://www.opengis.net/def/exceptions/ogcapi-edr-1/1.0/invalid-parameter-value",
-        400,
-        format!(
-            "Invalid output format '{}'. Supported formats: CoverageJSON, GeoJSON",
-            format
-        ),
-    )
-    .with_title("Bad Request");
-
-    let json = serde_json::to_string(&exc).unwrap_or_default();
+    error_response(EdrError::UnsupportedFormat(format!(
+        "'{}'. Supported formats: CoverageJSON, GeoJSON",
+        format
+    )))
+}
 
-    Response::builder()
-        .status(StatusCode::BAD_REQUEST)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
+/// Create a 400 Bad Request response for an invalid grid query format
+fn invalid_grid_format_response(format: &str) -> Response {
+    error_response(EdrError::UnsupportedFormat(format!(
+        "'{}'. Supported formats: CoverageJSON, GeoJSON, ASC, BIL, NetCDF",
+        format
+    )))
 }
 
 /// Helper to check Accept header for data queries
@@ -491,4 +602,50 @@ mod tests {
         let headers = make_headers("text/html");
         assert!(negotiate_format(&headers, None).is_err());
     }
+
+    #[test]
+    fn test_negotiate_grid_format() {
+        let headers = HeaderMap::new();
+        assert_eq!(
+            negotiate_grid_format(&headers, Some("asc")).unwrap(),
+            GridOutputFormat::Raster(RasterFormat::AsciiGrid)
+        );
+        assert_eq!(
+            negotiate_grid_format(&headers, Some("BIL")).unwrap(),
+            GridOutputFormat::Raster(RasterFormat::Bil)
+        );
+        assert_eq!(
+            negotiate_grid_format(&headers, Some("geojson")).unwrap(),
+            GridOutputFormat::Json(OutputFormat::GeoJson)
+        );
+        assert_eq!(
+            negotiate_grid_format(&headers, Some("NetCDF")).unwrap(),
+            GridOutputFormat::NetCdf
+        );
+        assert!(negotiate_grid_format(&headers, Some("tiff")).is_err());
+
+        // Raster media types are honoured when preferred
+        let headers = make_headers("text/html, application/zip, application/json;q=0.5");
+        assert_eq!(
+            negotiate_grid_format(&headers, None).unwrap(),
+            GridOutputFormat::Raster(RasterFormat::Bil)
+        );
+
+        let headers = make_headers("application/x-netcdf, */*;q=0.1");
+        assert_eq!(
+            negotiate_grid_format(&headers, None).unwrap(),
+            GridOutputFormat::NetCdf
+        );
+
+        // Wildcards still mean CoverageJSON
+        let headers = make_headers("*/*");
+        assert_eq!(
+            negotiate_grid_format(&headers, None).unwrap(),
+            GridOutputFormat::Json(OutputFormat::CoverageJson)
+        );
+
+        // File formats aren't offered by the JSON-only negotiation
+        assert!(negotiate_format(&HeaderMap::new(), Some("asc")).is_err());
+        assert!(negotiate_format(&HeaderMap::new(), Some("netcdf")).is_err());
+    }
 }
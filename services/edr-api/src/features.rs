@@ -0,0 +1,365 @@
+//! Registry of the query types and output encoders this server provides.
+//!
+//! The query routes, the data queries advertised on collections and the
+//! `/edr/conformance` declaration are all derived from one
+//! [`FeatureRegistry`], so only classes that are actually served are
+//! declared to OGC compliance tests.
+
+use axum::{routing::get, Router};
+use edr_protocol::{conformance, ConformanceClasses, DataQueries};
+
+use crate::content_negotiation::OutputFormat;
+use crate::handlers;
+
+/// Query types mounted when EDR_QUERY_TYPES is not set.
+///
+/// Corridor and cube queries don't yet pass the OGC conformance tests for
+/// their classes, so they are opt-in.
+pub const DEFAULT_QUERY_TYPES: &[QueryType] = &[
+    QueryType::Position,
+    QueryType::Area,
+    QueryType::Radius,
+    QueryType::Trajectory,
+    QueryType::Locations,
+];
+
+/// An EDR data query type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryType {
+    Position,
+    Area,
+    Radius,
+    Trajectory,
+    Corridor,
+    Cube,
+    Locations,
+}
+
+impl QueryType {
+    /// All query types with a handler in this build.
+    pub const ALL: [QueryType; 7] = [
+        QueryType::Position,
+        QueryType::Area,
+        QueryType::Radius,
+        QueryType::Trajectory,
+        QueryType::Corridor,
+        QueryType::Cube,
+        QueryType::Locations,
+    ];
+
+    /// Name of the query, as used in its path and in EDR_QUERY_TYPES.
+    pub fn name(self) -> &'static str {
+        match self {
+            QueryType::Position => "position",
+            QueryType::Area => "area",
+            QueryType::Radius => "radius",
+            QueryType::Trajectory => "trajectory",
+            QueryType::Corridor => "corridor",
+            QueryType::Cube => "cube",
+            QueryType::Locations => "locations",
+        }
+    }
+
+    /// Parse a query type name (case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|q| q.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Conformance class URI of the query.
+    pub fn conformance_class(self) -> &'static str {
+        match self {
+            QueryType::Position => conformance::POSITION,
+            QueryType::Area => conformance::AREA,
+            QueryType::Radius => conformance::RADIUS,
+            QueryType::Trajectory => conformance::TRAJECTORY,
+            QueryType::Corridor => conformance::CORRIDOR,
+            QueryType::Cube => conformance::CUBE,
+            QueryType::Locations => conformance::LOCATIONS,
+        }
+    }
+
+    /// Add the collection and instance routes of this query to `router`.
+    fn mount(self, router: Router) -> Router {
+        let collection = format!("/edr/collections/:collection_id/{}", self.name());
+        let instance = format!(
+            "/edr/collections/:collection_id/instances/:instance_id/{}",
+            self.name()
+        );
+
+        match self {
+            QueryType::Position => router
+                .route(&collection, get(handlers::position::position_handler))
+                .route(
+                    &instance,
+                    get(handlers::position::instance_position_handler),
+                ),
+            QueryType::Area => router
+                .route(&collection, get(handlers::area::area_handler))
+                .route(&instance, get(handlers::area::instance_area_handler)),
+            QueryType::Radius => router
+                .route(&collection, get(handlers::radius::radius_handler))
+                .route(&instance, get(handlers::radius::instance_radius_handler)),
+            QueryType::Trajectory => router
+                .route(&collection, get(handlers::trajectory::trajectory_handler))
+                .route(
+                    &instance,
+                    get(handlers::trajectory::instance_trajectory_handler),
+                ),
+            QueryType::Corridor => router
+                .route(&collection, get(handlers::corridor::corridor_handler))
+                .route(
+                    &instance,
+                    get(handlers::corridor::instance_corridor_handler),
+                ),
+            QueryType::Cube => router
+                .route(&collection, get(handlers::cube::cube_handler))
+                .route(&instance, get(handlers::cube::instance_cube_handler)),
+            QueryType::Locations => router
+                .route(
+                    &collection,
+                    get(handlers::locations::locations_list_handler),
+                )
+                .route(
+                    &format!("{}/:location_id", collection),
+                    get(handlers::locations::location_query_handler),
+                )
+                .route(
+                    &instance,
+                    get(handlers::locations::instance_locations_list_handler),
+                )
+                .route(
+                    &format!("{}/:location_id", instance),
+                    get(handlers::locations::instance_location_query_handler),
+                ),
+        }
+    }
+}
+
+/// Conformance classes of an output encoder.
+fn format_conformance_classes(format: OutputFormat) -> &'static [&'static str] {
+    match format {
+        OutputFormat::CoverageJson => &[conformance::COVJSON],
+        // Plain GeoJSON for the locations list, EDR GeoJSON for data queries
+        OutputFormat::GeoJson => &[conformance::GEOJSON, conformance::EDR_GEOJSON],
+    }
+}
+
+/// The query types and output encoders this server provides.
+#[derive(Debug, Clone)]
+pub struct FeatureRegistry {
+    queries: Vec<QueryType>,
+    output_formats: Vec<OutputFormat>,
+}
+
+impl Default for FeatureRegistry {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUERY_TYPES)
+    }
+}
+
+impl FeatureRegistry {
+    /// Registry with the given query types and every compiled-in encoder.
+    pub fn new(queries: &[QueryType]) -> Self {
+        let mut registry = Self {
+            queries: Vec::new(),
+            output_formats: OutputFormat::ALL.to_vec(),
+        };
+        for &query in queries {
+            registry.register_query(query);
+        }
+        registry
+    }
+
+    /// Registry from EDR_QUERY_TYPES (comma-separated query names), or the
+    /// default query types if unset or empty. Unknown names are skipped with
+    /// a warning.
+    pub fn from_env() -> Self {
+        match std::env::var("EDR_QUERY_TYPES") {
+            Ok(names) if !names.trim().is_empty() => Self::from_names(&names),
+            _ => Self::default(),
+        }
+    }
+
+    fn from_names(names: &str) -> Self {
+        let queries: Vec<_> = names
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .filter_map(|name| {
+                let query = QueryType::from_name(name);
+                if query.is_none() {
+                    tracing::warn!(query = name.trim(), "Unknown query type in EDR_QUERY_TYPES");
+                }
+                query
+            })
+            .collect();
+        Self::new(&queries)
+    }
+
+    /// Enable a query type.
+    pub fn register_query(&mut self, query: QueryType) {
+        if !self.queries.contains(&query) {
+            self.queries.push(query);
+        }
+    }
+
+    /// Whether a query type is enabled.
+    pub fn has_query(&self, query: QueryType) -> bool {
+        self.queries.contains(&query)
+    }
+
+    /// Enabled query types, in registration order.
+    pub fn queries(&self) -> &[QueryType] {
+        &self.queries
+    }
+
+    /// Mount the routes of every enabled query type.
+    pub fn mount_queries(&self, router: Router) -> Router {
+        self.queries
+            .iter()
+            .fold(router, |router, query| query.mount(router))
+    }
+
+    /// Conformance declaration for the enabled features.
+    ///
+    /// Core, collections and instances are always served; each enabled
+    /// query and compiled-in encoder adds its own classes.
+    pub fn conformance(&self) -> ConformanceClasses {
+        let mut classes = ConformanceClasses::minimal()
+            .with_class(conformance::COLLECTIONS)
+            .with_class(conformance::INSTANCES);
+
+        for query in &self.queries {
+            classes = classes.with_class(query.conformance_class());
+        }
+        for &format in &self.output_formats {
+            for class in format_conformance_classes(format) {
+                classes = classes.with_class(class);
+            }
+        }
+
+        classes
+    }
+
+    /// Data queries to advertise for a collection or instance.
+    ///
+    /// Cube queries are only offered for collections with vertical levels.
+    pub fn data_queries(
+        &self,
+        base_url: &str,
+        collection_id: &str,
+        has_vertical_levels: bool,
+    ) -> DataQueries {
+        let mut queries = if self.has_query(QueryType::Position) {
+            DataQueries::with_position(base_url, collection_id)
+        } else {
+            DataQueries::default()
+        };
+
+        for query in &self.queries {
+            queries = match query {
+                QueryType::Position => queries,
+                QueryType::Area => queries.with_area(base_url, collection_id),
+                QueryType::Radius => queries.with_radius(base_url, collection_id),
+                QueryType::Trajectory => queries.with_trajectory(base_url, collection_id),
+                QueryType::Corridor => queries.with_corridor(base_url, collection_id),
+                QueryType::Cube if has_vertical_levels => {
+                    queries.with_cube(base_url, collection_id)
+                }
+                QueryType::Cube => queries,
+                QueryType::Locations => queries.with_locations(base_url, collection_id),
+            };
+        }
+
+        queries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::Service;
+
+    #[test]
+    fn test_default_conformance_omits_opt_in_queries() {
+        let conformance = FeatureRegistry::default().conformance();
+
+        for class in [
+            conformance::CORE,
+            conformance::COLLECTIONS,
+            conformance::INSTANCES,
+            conformance::POSITION,
+            conformance::LOCATIONS,
+            conformance::COVJSON,
+            conformance::GEOJSON,
+            conformance::EDR_GEOJSON,
+        ] {
+            assert!(conformance.contains(class), "missing {}", class);
+        }
+        assert!(!conformance.contains(conformance::CUBE));
+        assert!(!conformance.contains(conformance::CORRIDOR));
+    }
+
+    #[test]
+    fn test_from_names() {
+        let registry = FeatureRegistry::from_names(" position, CUBE ,bogus,,cube");
+
+        assert_eq!(registry.queries(), &[QueryType::Position, QueryType::Cube]);
+        let conformance = registry.conformance();
+        assert!(conformance.contains(conformance::CUBE));
+        assert!(!conformance.contains(conformance::AREA));
+    }
+
+    #[test]
+    fn test_data_queries_match_enabled_queries() {
+        let registry = FeatureRegistry::new(&[QueryType::Area, QueryType::Cube]);
+        let base = "http://localhost:8083/edr";
+
+        let queries = registry.data_queries(base, "hrrr-isobaric", true);
+        assert!(queries.area.is_some());
+        assert!(queries.cube.is_some());
+        assert!(queries.position.is_none());
+        assert!(queries.corridor.is_none());
+
+        // Cube needs vertical levels
+        let queries = registry.data_queries(base, "hrrr-surface", false);
+        assert!(queries.cube.is_none());
+
+        let queries = FeatureRegistry::default().data_queries(base, "hrrr-surface", false);
+        assert!(queries.position.is_some());
+        assert!(queries.locations.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_only_enabled_queries_are_mounted() {
+        let router = FeatureRegistry::new(&[QueryType::Position]).mount_queries(Router::new());
+
+        let status = |uri: &'static str| {
+            let mut router = router.clone();
+            async move {
+                router
+                    .call(Request::get(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+                    .status()
+            }
+        };
+
+        // Mounted handlers fail without app state, unmounted routes are 404
+        assert_ne!(
+            status("/edr/collections/hrrr/position").await,
+            StatusCode::NOT_FOUND
+        );
+        assert_ne!(
+            status("/edr/collections/hrrr/instances/2024-01-01T00:00:00Z/position").await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status("/edr/collections/hrrr/cube").await,
+            StatusCode::NOT_FOUND
+        );
+    }
+}
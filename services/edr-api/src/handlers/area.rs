@@ -3,20 +3,23 @@
 use axum::{
     extract::{Extension, Path, Query},
     http::{header, HeaderMap, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
 use edr_protocol::{
-    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery,
-    responses::ExceptionResponse, AreaQuery, CoverageJson, EdrFeatureCollection, ParsedPolygons,
+    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery, AreaQuery,
+    CoverageJson, EdrError, EdrFeatureCollection, RasterGrid,
 };
-use grid_processor::{BoundingBox, DatasetQuery};
+use grid_processor::{BoundingBox, CfAttributes, DatasetQuery, PyramidSelection};
 use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::config::LevelValue;
-use crate::content_negotiation::{negotiate_format, OutputFormat};
-use crate::limits::ResponseSizeEstimate;
+use crate::content_negotiation::{negotiate_grid_format, GridOutputFormat, OutputFormat};
+use crate::limits::{enforce_limits, ResponseSizeEstimate};
+use crate::netcdf::{netcdf_response, NetCdfVariables};
+use crate::problem::{error_response, grid_error};
+use crate::raster::{raster_basename, raster_response};
 use crate::state::AppState;
 
 /// Query parameters for area endpoint.
@@ -71,7 +74,7 @@ async fn area_query(
     headers: HeaderMap,
 ) -> Response {
     // Negotiate output format based on Accept header and f parameter
-    let output_format = match negotiate_format(&headers, params.f.as_deref()) {
+    let output_format = match negotiate_grid_format(&headers, params.f.as_deref()) {
         Ok(format) => format,
         Err(response) => {
             return response;
@@ -79,23 +82,20 @@ async fn area_query(
     };
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "area");
 
     // Find the collection
     let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // Check for required coords parameter
     let coords_str = match &params.coords {
         Some(c) if !c.trim().is_empty() => c.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: coords"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: coords".to_string(),
+            ));
         }
     };
 
@@ -103,54 +103,21 @@ async fn area_query(
     let parsed_polygons = match AreaQuery::parse_polygon_multi(coords_str) {
         Ok(p) => p,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid coordinates: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid coordinates: {}",
+                e
+            )));
         }
     };
 
-    // Extract polygons for processing
-    let polygons: Vec<Vec<(f64, f64)>> = match &parsed_polygons {
-        ParsedPolygons::Single(polygon) => vec![polygon.clone()],
-        ParsedPolygons::Multi(polygons) => polygons.clone(),
-    };
-
-    // Use first polygon for primary calculations (union for point-in-polygon checks)
-    let polygon = polygons.first().cloned().unwrap_or_default();
-
-    // Create AreaQuery for calculations
-    let area_query_struct = AreaQuery {
-        polygon: polygon.clone(),
-        z: None,
-        datetime: None,
-        parameter_names: None,
-        crs: None,
-    };
-
-    // For MULTIPOLYGON, we create additional AreaQuery structs for contains_point checks
-    let all_area_queries: Vec<AreaQuery> = polygons
-        .iter()
-        .map(|p| AreaQuery {
-            polygon: p.clone(),
-            z: None,
-            datetime: None,
-            parameter_names: None,
-            crs: None,
-        })
-        .collect();
-
     // Check area size limit
-    let area_sq_degrees = area_query_struct.area_sq_degrees();
+    let area_sq_degrees = parsed_polygons.bbox().area_sq_degrees();
     let max_area = model_config.limits.max_area_sq_degrees.unwrap_or(100.0);
     if area_sq_degrees > max_area {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(format!(
-                "Area too large: {:.2} sq degrees exceeds limit of {:.2}",
-                area_sq_degrees, max_area
-            )),
-        );
+        return error_response(EdrError::ResponseTooLarge(format!(
+            "Area too large: {:.2} sq degrees exceeds limit of {:.2}",
+            area_sq_degrees, max_area
+        )));
     }
 
     // Parse vertical levels
@@ -158,10 +125,10 @@ async fn area_query(
         match edr_protocol::PositionQuery::parse_z(z) {
             Ok(values) => Some(values),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid z parameter: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid z parameter: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -173,10 +140,10 @@ async fn area_query(
         match DateTimeQuery::parse(dt) {
             Ok(dt) => Some(dt),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid datetime: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid datetime: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -207,20 +174,17 @@ async fn area_query(
             .collect();
         for param in &requested_params {
             if !available.contains(&param.as_str()) {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!(
-                        "Parameter '{}' not available in collection. Available: {:?}",
-                        param, available
-                    )),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Parameter '{}' not available in collection. Available: {:?}",
+                    param, available
+                )));
             }
         }
         requested_params
     };
 
     // Get the bbox of the polygon for grid queries
-    let bbox = area_query_struct.bbox();
+    let bbox = parsed_polygons.bbox();
 
     // Get the list of times to query
     // For interval queries (especially open-ended ones), expand against available times
@@ -265,11 +229,13 @@ async fn area_query(
         resolution,
     );
 
-    if let Err(limit_err) = estimate.check_limits(&model_config.limits) {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(limit_err.to_string()),
-        );
+    if let Err(limit_err) = enforce_limits(
+        &estimate,
+        &model_config.limits,
+        &state.cost_budget,
+        &headers,
+    ) {
+        return limit_err.into_response();
     }
 
     // Parse instance_id if provided and validate it exists
@@ -284,13 +250,10 @@ async fn area_query(
                     Ok(runs) => {
                         let run_exists = runs.iter().any(|(rt, _)| *rt == ref_time);
                         if !run_exists {
-                            return error_response(
-                                StatusCode::NOT_FOUND,
-                                ExceptionResponse::not_found(format!(
-                                    "Instance not found: {} for collection {}",
-                                    id, collection_id
-                                )),
-                            );
+                            return error_response(EdrError::InstanceNotFound(format!(
+                                "{} for collection {}",
+                                id, collection_id
+                            )));
                         }
                     }
                     Err(e) => {
@@ -302,10 +265,10 @@ async fn area_query(
                 Some(ref_time)
             }
             Err(_) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid instance ID format: {}", id)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid instance ID format: {}",
+                    id
+                )));
             }
         }
     } else {
@@ -331,10 +294,9 @@ async fn area_query(
     let first_param = match params_to_query.first() {
         Some(p) => p,
         None => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("No parameters specified"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "No parameters specified".to_string(),
+            ));
         }
     };
 
@@ -347,8 +309,10 @@ async fn area_query(
     // Build the level string
     let level_str = build_level_string(&collection_def.level_filter, param_def, z_val);
 
-    // Build the DatasetQuery
-    let mut query = DatasetQuery::forecast(&model_config.model, first_param);
+    // Build the DatasetQuery. Area queries return every cell in the polygon,
+    // so they must never be served from a downsampled pyramid level.
+    let mut query = DatasetQuery::forecast(&model_config.model, first_param)
+        .with_pyramid(PyramidSelection::NATIVE);
 
     if let Some(level) = &level_str {
         query = query.at_level(level);
@@ -373,20 +337,13 @@ async fn area_query(
         Ok(r) => r,
         Err(e) => {
             tracing::error!("Failed to read region: {}", e);
-            return error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ExceptionResponse::internal_error(format!("Failed to read data: {}", e)),
-            );
+            return error_response(grid_error(e));
         }
     };
 
-    // Build x and y coordinate arrays from the grid metadata
-    let x_values: Vec<f64> = (0..region.width)
-        .map(|i| region.bbox.min_lon + (i as f64 + 0.5) * region.resolution.0)
-        .collect();
-    let y_values: Vec<f64> = (0..region.height)
-        .map(|j| region.bbox.max_lat - (j as f64 + 0.5) * region.resolution.1)
-        .collect();
+    // Build x and y coordinate arrays from the region's grid coordinates
+    let x_values = region.lon_values();
+    let y_values = region.lat_values();
 
     // Build the time axis
     let t_values = if !time_strings.is_empty() {
@@ -410,6 +367,10 @@ async fn area_query(
         ranges: Some(std::collections::HashMap::new()),
     };
 
+    // NetCDF exports carry the same masked values as the coverage
+    let want_netcdf = output_format == GridOutputFormat::NetCdf;
+    let mut netcdf = NetCdfVariables::new();
+
     // For each parameter, query the data and add to coverage
     for param_name in &params_to_query {
         // Find the parameter definition
@@ -422,7 +383,8 @@ async fn area_query(
         let level_str = build_level_string(&collection_def.level_filter, param_def, z_val);
 
         // Build the DatasetQuery
-        let mut query = DatasetQuery::forecast(&model_config.model, param_name);
+        let mut query = DatasetQuery::forecast(&model_config.model, param_name)
+            .with_pyramid(PyramidSelection::NATIVE);
 
         if let Some(level) = &level_str {
             query = query.at_level(level);
@@ -442,6 +404,8 @@ async fn area_query(
             .as_ref()
             .map(|m| m.units.clone())
             .unwrap_or_default();
+        netcdf.record_run(metadata.as_ref());
+        let cf = || CfAttributes::for_parameter(param_name, level_str.as_deref().unwrap_or(""));
 
         // Read the region for this parameter
         match state
@@ -452,22 +416,18 @@ async fn area_query(
             Ok(param_region) => {
                 // Apply polygon mask - set values outside polygon to null
                 let mut values: Vec<Option<f32>> = Vec::with_capacity(param_region.data.len());
+                let lons = param_region.lon_values();
+                let lats = param_region.lat_values();
 
                 for (idx, &value) in param_region.data.iter().enumerate() {
                     let row = idx / param_region.width;
                     let col = idx % param_region.width;
 
-                    // Calculate lon/lat for this grid cell
-                    let lon =
-                        param_region.bbox.min_lon + (col as f64 + 0.5) * param_region.resolution.0;
-                    let lat =
-                        param_region.bbox.max_lat - (row as f64 + 0.5) * param_region.resolution.1;
-
-                    // Check if point is inside any polygon (union of all polygons for MULTIPOLYGON)
-                    let inside_any = all_area_queries
-                        .iter()
-                        .any(|aq| aq.contains_point(lon, lat));
-                    if inside_any {
+                    // Look up lon/lat for this grid point
+                    let (lon, lat) = (lons[col], lats[row]);
+
+                    // Union of all polygons for MULTIPOLYGON, minus their holes
+                    if parsed_polygons.contains_point(lon, lat) {
                         if value.is_nan() {
                             values.push(None);
                         } else {
@@ -478,6 +438,10 @@ async fn area_query(
                     }
                 }
 
+                if want_netcdf {
+                    netcdf.push(param_name, &units_str, cf(), &values);
+                }
+
                 let unit = Unit::from_symbol(&units_str);
                 let cov_param = CovJsonParameter::new(param_name).with_unit(unit);
 
@@ -499,6 +463,9 @@ async fn area_query(
                 // Add parameter with null values
                 let cov_param = CovJsonParameter::new(param_name);
                 let null_values: Vec<Option<f32>> = vec![None; y_values.len() * x_values.len()];
+                if want_netcdf {
+                    netcdf.push(param_name, &units_str, cf(), &null_values);
+                }
                 let shape = vec![y_values.len(), x_values.len()];
                 let axis_names = vec!["y".to_string(), "x".to_string()];
 
@@ -513,6 +480,22 @@ async fn area_query(
         }
     }
 
+    // Raster and NetCDF exports skip JSON serialization entirely
+    let output_format = match output_format {
+        GridOutputFormat::Json(format) => format,
+        GridOutputFormat::Raster(format) => {
+            return raster_response(
+                RasterGrid::from_coverage(&coverage),
+                format,
+                &raster_basename(&collection_id, "area"),
+            );
+        }
+        GridOutputFormat::NetCdf => {
+            let coverage = netcdf.into_coverage(x_values, y_values, None, query_time);
+            return netcdf_response(coverage, &raster_basename(&collection_id, "area"));
+        }
+    };
+
     // Serialize response based on requested format
     let (json, content_type) = match output_format {
         OutputFormat::GeoJson => {
@@ -521,10 +504,9 @@ async fn area_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize GeoJSON: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             }
         }
@@ -532,10 +514,9 @@ async fn area_query(
             Ok(j) => (j, output_format.content_type()),
             Err(e) => {
                 tracing::error!("Failed to serialize CoverageJSON: {}", e);
-                return error_response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    ExceptionResponse::internal_error("Failed to serialize response"),
-                );
+                return error_response(EdrError::InternalError(
+                    "Failed to serialize response".to_string(),
+                ));
             }
         },
     };
@@ -603,15 +584,6 @@ fn build_level_string(
     }
 }
 
-fn error_response(status: StatusCode, exc: ExceptionResponse) -> Response {
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
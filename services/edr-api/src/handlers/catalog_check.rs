@@ -12,8 +12,10 @@ use axum::{
     response::Response,
 };
 use chrono::{DateTime, Utc};
+use edr_protocol::EdrError;
 use serde::Serialize;
 
+use crate::problem::error_response;
 use crate::state::AppState;
 
 /// Response from the catalog check endpoint.
@@ -77,10 +79,10 @@ pub async fn catalog_check_handler(Extension(state): Extension<Arc<AppState>>) -
     let models = match state.catalog.list_models().await {
         Ok(m) => m,
         Err(e) => {
-            return error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                &format!("Failed to list models: {}", e),
-            );
+            return error_response(EdrError::InternalError(format!(
+                "Failed to list models: {}",
+                e
+            )));
         }
     };
 
@@ -203,17 +205,6 @@ pub async fn catalog_check_handler(Extension(state): Extension<Arc<AppState>>) -
         .unwrap()
 }
 
-fn error_response(status: StatusCode, message: &str) -> Response {
-    let body = serde_json::json!({
-        "error": message
-    });
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(body.to_string().into())
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
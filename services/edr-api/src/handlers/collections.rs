@@ -6,16 +6,53 @@ use axum::{
     response::Response,
 };
 use edr_protocol::{
-    parameters::Parameter, responses::ExceptionResponse, Collection, CollectionList, DataQueries,
-    Extent, TemporalExtent, VerticalExtent,
+    parameters::Parameter, Collection, CollectionList, EdrError, Extent, TemporalExtent,
+    VerticalExtent,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::config::{CollectionDefinition, LevelValue, ModelEdrConfig};
 use crate::content_negotiation::check_metadata_accept;
+use crate::problem::error_response;
 use crate::state::AppState;
-use storage::Catalog;
+use storage::{Catalog, ParameterMetadata};
+
+/// EDR parameter labeled with its catalog metadata: the long name as label
+/// and description, and its units. Parameters not yet ingested keep their
+/// short name as label.
+fn catalog_parameter(name: &str, metadata: Option<&ParameterMetadata>) -> Parameter {
+    match metadata {
+        Some(metadata) => Parameter::new(name, &metadata.long_name)
+            .with_description(&metadata.long_name)
+            .with_unit_symbol(&metadata.units),
+        None => Parameter::new(name, name),
+    }
+}
+
+/// Build the parameters of a collection from the catalog metadata.
+async fn build_parameters(
+    catalog: &Catalog,
+    model_config: &ModelEdrConfig,
+    collection_def: &CollectionDefinition,
+) -> HashMap<String, Parameter> {
+    let metadata = catalog
+        .list_parameter_metadata(&model_config.model)
+        .await
+        .unwrap_or_default();
+
+    collection_def
+        .parameters
+        .iter()
+        .map(|param_def| {
+            let param_metadata = metadata.iter().find(|m| m.parameter == param_def.name);
+            (
+                param_def.name.clone(),
+                catalog_parameter(&param_def.name, param_metadata),
+            )
+        })
+        .collect()
+}
 
 /// Build extent from catalog data for a collection.
 async fn build_extent_from_catalog(
@@ -128,6 +165,7 @@ pub async fn list_collections_handler(
     }
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "collections");
 
     let mut collections = Vec::new();
 
@@ -139,23 +177,15 @@ pub async fn list_collections_handler(
         // Build links
         collection.build_links(&state.base_url);
 
-        // Add data queries (position, area, radius, trajectory, corridor, locations, and cube if vertical levels)
-        let mut queries = DataQueries::with_position(&state.base_url, &collection_def.id)
-            .with_area(&state.base_url, &collection_def.id)
-            .with_radius(&state.base_url, &collection_def.id)
-            .with_trajectory(&state.base_url, &collection_def.id)
-            .with_corridor(&state.base_url, &collection_def.id)
-            .with_locations(&state.base_url, &collection_def.id);
-
-        // Only add cube for collections with vertical levels
+        // Add data queries for the enabled query types (cube only if vertical levels)
         let has_vertical_levels = collection_def
             .parameters
             .iter()
             .any(|p| p.levels.iter().any(|l| matches!(l, LevelValue::Numeric(_))));
-
-        if has_vertical_levels {
-            queries = queries.with_cube(&state.base_url, &collection_def.id);
-        }
+        let queries =
+            state
+                .features
+                .data_queries(&state.base_url, &collection_def.id, has_vertical_levels);
 
         collection = collection.with_data_queries(queries);
 
@@ -165,11 +195,7 @@ pub async fn list_collections_handler(
             collection = collection.with_extent(extent);
 
             // Add parameters (required by OGC EDR tests)
-            let mut params = HashMap::new();
-            for param_def in &coll_def.parameters {
-                let param = Parameter::new(&param_def.name, &param_def.name);
-                params.insert(param_def.name.clone(), param);
-            }
+            let params = build_parameters(&state.catalog, model_config, coll_def).await;
             if !params.is_empty() {
                 collection = collection.with_parameters(params);
             }
@@ -211,16 +237,11 @@ pub async fn get_collection_handler(
     }
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "collection");
 
     // Find the collection
     let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        let exc = ExceptionResponse::not_found(format!("Collection not found: {}", collection_id));
-        let json = serde_json::to_string(&exc).unwrap_or_default();
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(json.into())
-            .unwrap();
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     let mut collection = Collection::new(&collection_def.id)
@@ -230,23 +251,15 @@ pub async fn get_collection_handler(
     // Build links
     collection.build_links(&state.base_url);
 
-    // Add data queries (position, area, radius, trajectory, corridor, locations, and cube if vertical levels)
-    let mut queries = DataQueries::with_position(&state.base_url, &collection_def.id)
-        .with_area(&state.base_url, &collection_def.id)
-        .with_radius(&state.base_url, &collection_def.id)
-        .with_trajectory(&state.base_url, &collection_def.id)
-        .with_corridor(&state.base_url, &collection_def.id)
-        .with_locations(&state.base_url, &collection_def.id);
-
-    // Only add cube for collections with vertical levels
+    // Add data queries for the enabled query types (cube only if vertical levels)
     let has_vertical_levels = collection_def
         .parameters
         .iter()
         .any(|p| p.levels.iter().any(|l| matches!(l, LevelValue::Numeric(_))));
-
-    if has_vertical_levels {
-        queries = queries.with_cube(&state.base_url, &collection_def.id);
-    }
+    let queries =
+        state
+            .features
+            .data_queries(&state.base_url, &collection_def.id, has_vertical_levels);
 
     collection = collection.with_data_queries(queries);
 
@@ -255,12 +268,8 @@ pub async fn get_collection_handler(
     collection = collection.with_extent(extent);
 
     // Add parameters
-    let mut params = HashMap::new();
-    for param_def in &collection_def.parameters {
-        let param = Parameter::new(&param_def.name, &param_def.name);
-        // TODO: Add unit and levels from catalog
-        params.insert(param_def.name.clone(), param);
-    }
+    // TODO: Add levels from catalog
+    let params = build_parameters(&state.catalog, model_config, collection_def).await;
     if !params.is_empty() {
         collection = collection.with_parameters(params);
     }
@@ -282,6 +291,8 @@ pub async fn get_collection_handler(
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use edr_protocol::parameters::Unit;
     use edr_protocol::{Collection, CollectionList, DataQueries};
 
     #[test]
@@ -317,4 +328,26 @@ mod tests {
         let pos = queries.position.unwrap();
         assert!(pos.link.href.contains("/position"));
     }
+
+    #[test]
+    fn test_catalog_parameter() {
+        let metadata = ParameterMetadata {
+            model: "gfs".to_string(),
+            parameter: "TMP".to_string(),
+            long_name: "Temperature".to_string(),
+            units: "K".to_string(),
+            default_style: None,
+            valid_min: None,
+            valid_max: None,
+        };
+
+        let param = catalog_parameter("TMP", Some(&metadata));
+        assert_eq!(param.id.as_deref(), Some("TMP"));
+        assert_eq!(param.label.as_deref(), Some("Temperature"));
+        assert_eq!(param.unit, Some(Unit::from_symbol("K")));
+
+        let param = catalog_parameter("DPT", None);
+        assert_eq!(param.label.as_deref(), Some("DPT"));
+        assert_eq!(param.unit, None);
+    }
 }
@@ -1,21 +1,29 @@
 //! Conformance endpoint handler.
 
 use axum::{
+    extract::Extension,
     http::{header, HeaderMap, StatusCode},
     response::Response,
 };
-use edr_protocol::ConformanceClasses;
+use std::sync::Arc;
 
 use crate::content_negotiation::check_metadata_accept;
+use crate::state::AppState;
 
 /// GET /edr/conformance - Conformance classes
-pub async fn conformance_handler(headers: HeaderMap) -> Response {
+///
+/// Declares only the classes of the mounted query types and compiled-in
+/// output encoders (see [`crate::features::FeatureRegistry`]).
+pub async fn conformance_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Response {
     // Check Accept header - return 406 if unsupported format requested
     if let Err(response) = check_metadata_accept(&headers) {
         return response;
     }
 
-    let conformance = ConformanceClasses::current();
+    let conformance = state.features.conformance();
 
     let json = serde_json::to_string_pretty(&conformance).unwrap_or_default();
 
@@ -29,11 +37,13 @@ pub async fn conformance_handler(headers: HeaderMap) -> Response {
 
 #[cfg(test)]
 mod tests {
-    use edr_protocol::{conformance, ConformanceClasses};
+    use edr_protocol::conformance;
+
+    use crate::features::FeatureRegistry;
 
     #[test]
     fn test_conformance_classes() {
-        let conf = ConformanceClasses::current();
+        let conf = FeatureRegistry::default().conformance();
 
         // Must include core, collections, position
         assert!(conf.contains(conformance::CORE));
@@ -43,7 +53,7 @@ mod tests {
 
     #[test]
     fn test_conformance_json() {
-        let conf = ConformanceClasses::current();
+        let conf = FeatureRegistry::default().conformance();
         let json = serde_json::to_string(&conf).unwrap();
 
         // Should contain conformsTo array
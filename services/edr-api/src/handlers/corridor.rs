@@ -30,15 +30,15 @@
 use axum::{
     extract::{Extension, Path, Query},
     http::{header, HeaderMap, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, TimeZone, Utc};
 use edr_protocol::{
     coverage_json::{CovJsonParameter, CoverageCollection},
     parameters::Unit,
     queries::DateTimeQuery,
-    responses::ExceptionResponse,
-    CoverageJson, DistanceUnit, EdrFeatureCollection, PositionQuery, TrajectoryQuery, VerticalUnit,
+    CoverageJson, DistanceUnit, EdrError, EdrFeatureCollection, PositionQuery, TrajectoryQuery,
+    VerticalUnit,
 };
 use grid_processor::DatasetQuery;
 use serde::Deserialize;
@@ -46,7 +46,8 @@ use std::sync::Arc;
 
 use crate::config::LevelValue;
 use crate::content_negotiation::{negotiate_format, OutputFormat};
-use crate::limits::ResponseSizeEstimate;
+use crate::limits::{enforce_limits, ResponseSizeEstimate};
+use crate::problem::error_response;
 use crate::state::AppState;
 
 /// Query parameters for corridor endpoint.
@@ -228,13 +229,11 @@ async fn corridor_query(
     };
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "corridor");
 
     // Find the collection
     let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // ===== Validate Required Parameters =====
@@ -243,10 +242,9 @@ async fn corridor_query(
     let coords_str = match &params.coords {
         Some(c) if !c.trim().is_empty() => c.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: coords"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: coords".to_string(),
+            ));
         }
     };
 
@@ -254,10 +252,9 @@ async fn corridor_query(
     let corridor_width_str = match &params.corridor_width {
         Some(w) if !w.trim().is_empty() => w.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: corridor-width"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: corridor-width".to_string(),
+            ));
         }
     };
 
@@ -265,10 +262,9 @@ async fn corridor_query(
     let width_units_str = match &params.width_units {
         Some(u) if !u.trim().is_empty() => u.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: width-units"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: width-units".to_string(),
+            ));
         }
     };
 
@@ -295,14 +291,11 @@ async fn corridor_query(
         .iter()
         .any(|u| u.eq_ignore_ascii_case(width_units_str))
     {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request(format!(
-                "Invalid width-units '{}'. Supported units: {}",
-                width_units_str,
-                SUPPORTED_WIDTH_UNITS.join(", ")
-            )),
-        );
+        return error_response(EdrError::InvalidParameter(format!(
+            "Invalid width-units '{}'. Supported units: {}",
+            width_units_str,
+            SUPPORTED_WIDTH_UNITS.join(", ")
+        )));
     }
 
     // Validate height-units is in supported list
@@ -310,24 +303,21 @@ async fn corridor_query(
         .iter()
         .any(|u| u.eq_ignore_ascii_case(height_units_str))
     {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request(format!(
-                "Invalid height-units '{}'. Supported units: {}",
-                height_units_str,
-                SUPPORTED_HEIGHT_UNITS.join(", ")
-            )),
-        );
+        return error_response(EdrError::InvalidParameter(format!(
+            "Invalid height-units '{}'. Supported units: {}",
+            height_units_str,
+            SUPPORTED_HEIGHT_UNITS.join(", ")
+        )));
     }
 
     // Parse width units
     let width_units = match DistanceUnit::parse(width_units_str) {
         Ok(u) => u,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid width-units: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid width-units: {}",
+                e
+            )));
         }
     };
 
@@ -335,10 +325,10 @@ async fn corridor_query(
     let _height_units = match VerticalUnit::parse(height_units_str) {
         Ok(u) => u,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid height-units: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid height-units: {}",
+                e
+            )));
         }
     };
 
@@ -347,38 +337,30 @@ async fn corridor_query(
     let corridor_width: f64 = match corridor_width_str.trim().parse() {
         Ok(v) if v > 0.0 => v,
         Ok(_) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("corridor-width must be a positive number"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "corridor-width must be a positive number".to_string(),
+            ));
         }
         Err(_) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!(
-                    "Invalid corridor-width '{}'. Expected a number.",
-                    corridor_width_str
-                )),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid corridor-width '{}'. Expected a number.",
+                corridor_width_str
+            )));
         }
     };
 
     let _corridor_height: f64 = match corridor_height_str.trim().parse() {
         Ok(v) if v >= 0.0 => v, // Allow 0 for 2D corridors (no vertical extent)
         Ok(_) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("corridor-height must be a non-negative number"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "corridor-height must be a non-negative number".to_string(),
+            ));
         }
         Err(_) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!(
-                    "Invalid corridor-height '{}'. Expected a number.",
-                    corridor_height_str
-                )),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid corridor-height '{}'. Expected a number.",
+                corridor_height_str
+            )));
         }
     };
 
@@ -388,13 +370,10 @@ async fn corridor_query(
     let parsed_trajectory = match TrajectoryQuery::parse_coords(coords_str) {
         Ok(t) => t,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!(
+            return error_response(EdrError::InvalidParameter(format!(
                     "Invalid coordinates: {}. Expected LINESTRING, LINESTRINGZ, LINESTRINGM, LINESTRINGZM, or MULTI* variant.",
                     e
-                )),
-            );
+                )));
         }
     };
 
@@ -402,34 +381,29 @@ async fn corridor_query(
     let waypoints = parsed_trajectory.waypoints;
 
     if waypoints.is_empty() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request("Corridor must contain at least one waypoint"),
-        );
+        return error_response(EdrError::InvalidParameter(
+            "Corridor must contain at least one waypoint".to_string(),
+        ));
     }
 
     // ===== Check for Coordinate/Parameter Conflicts =====
 
     // Check for conflicting z parameter when coords already has Z
     if line_type.has_z() && params.z.is_some() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request(
-                "Cannot specify 'z' parameter when coords contains Z coordinates (LINESTRINGZ/LINESTRINGZM). \
-                 Use either embedded Z coordinates or the z query parameter, not both."
-            ),
-        );
+        return error_response(EdrError::InvalidParameter(
+            "Cannot specify 'z' parameter when coords contains Z coordinates (LINESTRINGZ/LINESTRINGZM). \
+             Use either embedded Z coordinates or the z query parameter, not both."
+                .to_string(),
+        ));
     }
 
     // Check for conflicting datetime parameter when coords already has M
     if line_type.has_m() && params.datetime.is_some() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request(
-                "Cannot specify 'datetime' parameter when coords contains M coordinates (LINESTRINGM/LINESTRINGZM). \
-                 Use either embedded M coordinates or the datetime query parameter, not both."
-            ),
-        );
+        return error_response(EdrError::InvalidParameter(
+            "Cannot specify 'datetime' parameter when coords contains M coordinates (LINESTRINGM/LINESTRINGZM). \
+             Use either embedded M coordinates or the datetime query parameter, not both."
+                .to_string(),
+        ));
     }
 
     // ===== Parse Optional Parameters =====
@@ -442,10 +416,10 @@ async fn corridor_query(
         match PositionQuery::parse_z(z) {
             Ok(values) => Some(values),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid z parameter: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid z parameter: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -457,10 +431,10 @@ async fn corridor_query(
         match DateTimeQuery::parse(dt) {
             Ok(dt) => Some(dt),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid datetime: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid datetime: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -489,13 +463,10 @@ async fn corridor_query(
             .collect();
         for param in &requested_params {
             if !available.contains(&param.as_str()) {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!(
-                        "Parameter '{}' not available in collection. Available: {:?}",
-                        param, available
-                    )),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Parameter '{}' not available in collection. Available: {:?}",
+                    param, available
+                )));
             }
         }
         requested_params
@@ -541,11 +512,13 @@ async fn corridor_query(
         num_levels,
     );
 
-    if let Err(limit_err) = estimate.check_limits(&model_config.limits) {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(limit_err.to_string()),
-        );
+    if let Err(limit_err) = enforce_limits(
+        &estimate,
+        &model_config.limits,
+        &state.cost_budget,
+        &headers,
+    ) {
+        return limit_err.into_response();
     }
 
     // ===== Parse Instance ID =====
@@ -561,13 +534,10 @@ async fn corridor_query(
                     Ok(runs) => {
                         let run_exists = runs.iter().any(|(rt, _)| *rt == ref_time);
                         if !run_exists {
-                            return error_response(
-                                StatusCode::NOT_FOUND,
-                                ExceptionResponse::not_found(format!(
-                                    "Instance not found: {} for collection {}",
-                                    id, collection_id
-                                )),
-                            );
+                            return error_response(EdrError::InstanceNotFound(format!(
+                                "{} for collection {}",
+                                id, collection_id
+                            )));
                         }
                     }
                     Err(e) => {
@@ -578,10 +548,10 @@ async fn corridor_query(
                 Some(ref_time)
             }
             Err(_) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid instance ID format: {}", id)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid instance ID format: {}",
+                    id
+                )));
             }
         }
     } else {
@@ -849,10 +819,9 @@ async fn corridor_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize GeoJSON: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             }
         }
@@ -860,10 +829,9 @@ async fn corridor_query(
             Ok(j) => (j, output_format.content_type()),
             Err(e) => {
                 tracing::error!("Failed to serialize CoverageJSON: {}", e);
-                return error_response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    ExceptionResponse::internal_error("Failed to serialize response"),
-                );
+                return error_response(EdrError::InternalError(
+                    "Failed to serialize response".to_string(),
+                ));
             }
         },
     };
@@ -920,15 +888,6 @@ fn build_level_string(
     }
 }
 
-fn error_response(status: StatusCode, exc: ExceptionResponse) -> Response {
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
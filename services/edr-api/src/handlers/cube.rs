@@ -5,11 +5,13 @@
 //! - bbox is REQUIRED
 //! - z is REQUIRED
 //! - Returns CoverageCollection with one Coverage per z-level
+//!
+//! With `f=netcdf` the levels are instead stacked into one CF NetCDF file.
 
 use axum::{
     extract::{Extension, Path, Query},
     http::{header, HeaderMap, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
 use edr_protocol::{
@@ -19,17 +21,19 @@ use edr_protocol::{
     },
     parameters::Unit,
     queries::{BboxQuery, DateTimeQuery},
-    responses::ExceptionResponse,
-    EdrFeatureCollection,
+    EdrError, EdrFeatureCollection, RasterGrid,
 };
-use grid_processor::{BoundingBox, DatasetQuery};
+use grid_processor::{BoundingBox, CfAttributes, DatasetQuery, VerticalAxis};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::config::LevelValue;
-use crate::content_negotiation::{negotiate_format, OutputFormat};
-use crate::limits::ResponseSizeEstimate;
+use crate::content_negotiation::{negotiate_grid_format, GridOutputFormat, OutputFormat};
+use crate::limits::{enforce_limits, ResponseSizeEstimate};
+use crate::netcdf::{netcdf_response, regular_axis_values, NetCdfVariables};
+use crate::problem::error_response;
+use crate::raster::{raster_basename, raster_response};
 use crate::state::AppState;
 
 /// WKT representation for EPSG:4326
@@ -94,7 +98,7 @@ async fn cube_query(
     headers: HeaderMap,
 ) -> Response {
     // Negotiate output format based on Accept header and f parameter
-    let output_format = match negotiate_format(&headers, params.f.as_deref()) {
+    let output_format = match negotiate_grid_format(&headers, params.f.as_deref()) {
         Ok(format) => format,
         Err(response) => {
             return response;
@@ -102,13 +106,11 @@ async fn cube_query(
     };
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "cube");
 
     // Find the collection
     let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // Check if this collection supports cube queries (requires numeric vertical levels)
@@ -118,25 +120,20 @@ async fn cube_query(
         .any(|p| p.levels.iter().any(|l| matches!(l, LevelValue::Numeric(_))));
 
     if !has_vertical_levels {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request(format!(
+        return error_response(EdrError::InvalidParameter(format!(
                 "Collection '{}' does not support cube queries. Cube queries require collections with numeric vertical levels (e.g., pressure levels). Use the area query instead.",
                 collection_id
-            )),
-        );
+            )));
     }
 
     // Check for required bbox parameter (OGC EDR Requirement A.28.D/F)
     let bbox_str = match &params.bbox {
         Some(b) if !b.trim().is_empty() => b.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(
-                    "Missing required parameter: bbox. Cube queries require a bounding box.",
-                ),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: bbox. Cube queries require a bounding box."
+                    .to_string(),
+            ));
         }
     };
 
@@ -144,10 +141,10 @@ async fn cube_query(
     let bbox = match BboxQuery::parse(bbox_str) {
         Ok(b) => b,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid bbox parameter: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid bbox parameter: {}",
+                e
+            )));
         }
     };
 
@@ -155,12 +152,10 @@ async fn cube_query(
     let z_str = match &params.z {
         Some(z) if !z.trim().is_empty() => z.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(
-                    "Missing required parameter: z. Cube queries require vertical level(s).",
-                ),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: z. Cube queries require vertical level(s)."
+                    .to_string(),
+            ));
         }
     };
 
@@ -168,31 +163,27 @@ async fn cube_query(
     let z_values = match edr_protocol::PositionQuery::parse_z(z_str) {
         Ok(values) => values,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid z parameter: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid z parameter: {}",
+                e
+            )));
         }
     };
 
     if z_values.is_empty() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request("z parameter must contain at least one level"),
-        );
+        return error_response(EdrError::InvalidParameter(
+            "z parameter must contain at least one level".to_string(),
+        ));
     }
 
     // Check area size limit
     let area_sq_degrees = bbox.area_sq_degrees();
     let max_area = model_config.limits.max_area_sq_degrees.unwrap_or(100.0);
     if area_sq_degrees > max_area {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(format!(
-                "Area too large: {:.2} sq degrees exceeds limit of {:.2}",
-                area_sq_degrees, max_area
-            )),
-        );
+        return error_response(EdrError::ResponseTooLarge(format!(
+            "Area too large: {:.2} sq degrees exceeds limit of {:.2}",
+            area_sq_degrees, max_area
+        )));
     }
 
     // Parse datetime
@@ -200,10 +191,10 @@ async fn cube_query(
         match DateTimeQuery::parse(dt) {
             Ok(dt) => Some(dt),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid datetime: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid datetime: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -232,13 +223,10 @@ async fn cube_query(
             .collect();
         for param in &requested_params {
             if !available.contains(&param.as_str()) {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!(
-                        "Parameter '{}' not available in collection. Available: {:?}",
-                        param, available
-                    )),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Parameter '{}' not available in collection. Available: {:?}",
+                    param, available
+                )));
             }
         }
         requested_params
@@ -282,11 +270,13 @@ async fn cube_query(
         resolution,
     );
 
-    if let Err(limit_err) = estimate.check_limits(&model_config.limits) {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(limit_err.to_string()),
-        );
+    if let Err(limit_err) = enforce_limits(
+        &estimate,
+        &model_config.limits,
+        &state.cost_budget,
+        &headers,
+    ) {
+        return limit_err.into_response();
     }
 
     // Parse instance_id if provided
@@ -301,13 +291,10 @@ async fn cube_query(
                     Ok(runs) => {
                         let run_exists = runs.iter().any(|(rt, _)| *rt == ref_time);
                         if !run_exists {
-                            return error_response(
-                                StatusCode::NOT_FOUND,
-                                ExceptionResponse::not_found(format!(
-                                    "Instance not found: {} for collection {}",
-                                    id, collection_id
-                                )),
-                            );
+                            return error_response(EdrError::InstanceNotFound(format!(
+                                "{} for collection {}",
+                                id, collection_id
+                            )));
                         }
                     }
                     Err(e) => {
@@ -318,10 +305,10 @@ async fn cube_query(
                 Some(ref_time)
             }
             Err(_) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid instance ID format: {}", id)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid instance ID format: {}",
+                    id
+                )));
             }
         }
     } else {
@@ -372,6 +359,12 @@ async fn cube_query(
     // Build a Coverage for each z-level
     let mut coverages: Vec<CoverageJson> = Vec::new();
 
+    // NetCDF exports stack the levels that were read into one file
+    let want_netcdf = output_format == GridOutputFormat::NetCdf;
+    let mut netcdf = NetCdfVariables::new();
+    let mut netcdf_levels: Vec<f64> = Vec::new();
+    let mut netcdf_grid: Option<(usize, usize)> = None;
+
     for z_val in &z_values {
         // Get the first param to determine grid size
         let first_param = match params_to_query.first() {
@@ -465,6 +458,11 @@ async fn cube_query(
                 .as_ref()
                 .map(|m| m.units.clone())
                 .unwrap_or_default();
+            netcdf.record_run(metadata.as_ref());
+            let cf = || {
+                CfAttributes::for_parameter(param_name, level_str.as_deref().unwrap_or(""))
+                    .with_long_name(format!("{} on {} levels", param_name, level_type))
+            };
 
             // Update shared parameter with units if we have them
             if !units_str.is_empty() {
@@ -504,6 +502,10 @@ async fn cube_query(
                             )
                         };
 
+                    if want_netcdf {
+                        netcdf.push(param_name, &units_str, cf(), &values);
+                    }
+
                     // Shape: [t, y, x, z] per the IBL example
                     let shape = vec![1, out_height, out_width, 1];
                     let axis_names = vec![
@@ -529,6 +531,9 @@ async fn cube_query(
 
                     // Add null values
                     let null_values: Vec<Option<f32>> = vec![None; out_height * out_width];
+                    if want_netcdf {
+                        netcdf.push(param_name, &units_str, cf(), &null_values);
+                    }
                     let shape = vec![1, out_height, out_width, 1];
                     let axis_names = vec![
                         "t".to_string(),
@@ -554,6 +559,8 @@ async fn cube_query(
         };
 
         coverages.push(coverage);
+        netcdf_levels.push(*z_val);
+        netcdf_grid = Some((out_width, out_height));
     }
 
     // Build the CoverageCollection
@@ -568,6 +575,30 @@ async fn cube_query(
         final_collection = final_collection.with_coverage(cov);
     }
 
+    // Raster and NetCDF exports skip JSON serialization entirely
+    let output_format = match output_format {
+        GridOutputFormat::Json(format) => format,
+        GridOutputFormat::Raster(format) => {
+            return raster_response(
+                RasterGrid::from_collection(&final_collection),
+                format,
+                &raster_basename(&collection_id, "cube"),
+            );
+        }
+        GridOutputFormat::NetCdf => {
+            // The grid is the same at every level; rows run north to south
+            let (out_width, out_height) = netcdf_grid.unwrap_or((0, 0));
+            let vertical_units = if is_isobaric { "hPa" } else { "m" };
+            let coverage = netcdf.into_coverage(
+                regular_axis_values(bbox.west, bbox.east, out_width),
+                regular_axis_values(bbox.north, bbox.south, out_height),
+                Some(VerticalAxis::new(vertical_units, netcdf_levels)),
+                query_time,
+            );
+            return netcdf_response(coverage, &raster_basename(&collection_id, "cube"));
+        }
+    };
+
     // Serialize response based on requested format
     let (json, content_type) = match output_format {
         OutputFormat::GeoJson => {
@@ -576,10 +607,9 @@ async fn cube_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize GeoJSON: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             }
         }
@@ -587,10 +617,9 @@ async fn cube_query(
             Ok(j) => (j, output_format.content_type()),
             Err(e) => {
                 tracing::error!("Failed to serialize CoverageJSON: {}", e);
-                return error_response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    ExceptionResponse::internal_error("Failed to serialize response"),
-                );
+                return error_response(EdrError::InternalError(
+                    "Failed to serialize response".to_string(),
+                ));
             }
         },
     };
@@ -753,15 +782,6 @@ fn build_level_string(
     }
 }
 
-fn error_response(status: StatusCode, exc: ExceptionResponse) -> Response {
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
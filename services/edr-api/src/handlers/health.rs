@@ -64,9 +64,9 @@ pub async fn ready_handler(Extension(state): Extension<Arc<AppState>>) -> Respon
 }
 
 /// GET /metrics - Prometheus metrics
-pub async fn metrics_handler() -> Response {
+pub async fn metrics_handler(Extension(state): Extension<Arc<AppState>>) -> Response {
     // TODO: Integrate with metrics-exporter-prometheus
-    let metrics = r#"# HELP edr_requests_total Total EDR API requests
+    let mut metrics = r#"# HELP edr_requests_total Total EDR API requests
 # TYPE edr_requests_total counter
 edr_requests_total{endpoint="position"} 0
 edr_requests_total{endpoint="collections"} 0
@@ -76,7 +76,14 @@ edr_requests_total{endpoint="collections"} 0
 edr_request_duration_seconds_bucket{endpoint="position",le="0.01"} 0
 edr_request_duration_seconds_bucket{endpoint="position",le="0.1"} 0
 edr_request_duration_seconds_bucket{endpoint="position",le="1"} 0
-"#;
+"#
+    .to_string();
+
+    // Requests per tenant
+    if !state.tenants.is_empty() {
+        metrics.push('\n');
+        metrics.push_str(&state.tenant_usage.to_prometheus());
+    }
 
     Response::builder()
         .status(StatusCode::OK)
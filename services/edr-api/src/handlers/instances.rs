@@ -5,12 +5,11 @@ use axum::{
     http::{header, HeaderMap, StatusCode},
     response::Response,
 };
-use edr_protocol::{
-    responses::ExceptionResponse, DataQueries, Extent, Instance, InstanceList, TemporalExtent,
-};
+use edr_protocol::{EdrError, Extent, Instance, InstanceList, TemporalExtent};
 use std::sync::Arc;
 
 use crate::content_negotiation::check_metadata_accept;
+use crate::problem::error_response;
 use crate::state::AppState;
 
 /// GET /edr/collections/:collection_id/instances - List all instances
@@ -25,16 +24,11 @@ pub async fn list_instances_handler(
     }
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "instances");
 
     // Find the collection
     let Some((model_config, _collection_def)) = config.find_collection(&collection_id) else {
-        let exc = ExceptionResponse::not_found(format!("Collection not found: {}", collection_id));
-        let json = serde_json::to_string(&exc).unwrap_or_default();
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(json.into())
-            .unwrap();
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // Query catalog for available model runs
@@ -43,13 +37,9 @@ pub async fn list_instances_handler(
         Ok(runs) => runs,
         Err(e) => {
             tracing::error!("Failed to list model runs: {}", e);
-            let exc = ExceptionResponse::internal_error("Failed to list instances");
-            let json = serde_json::to_string(&exc).unwrap_or_default();
-            return Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(json.into())
-                .unwrap();
+            return error_response(EdrError::InternalError(
+                "Failed to list instances".to_string(),
+            ));
         }
     };
 
@@ -75,13 +65,10 @@ pub async fn list_instances_handler(
         // Build links
         instance.build_links(&state.base_url, &collection_id);
 
-        // Add data queries (position, area, radius, trajectory, corridor, and locations)
-        let queries = DataQueries::with_position(&state.base_url, &collection_id)
-            .with_area(&state.base_url, &collection_id)
-            .with_radius(&state.base_url, &collection_id)
-            .with_trajectory(&state.base_url, &collection_id)
-            .with_corridor(&state.base_url, &collection_id)
-            .with_locations(&state.base_url, &collection_id);
+        // Add data queries for the enabled query types (no cube on instances)
+        let queries = state
+            .features
+            .data_queries(&state.base_url, &collection_id, false);
         instance = instance.with_data_queries(queries);
 
         // Get actual temporal extent from forecast range
@@ -139,32 +126,21 @@ pub async fn get_instance_handler(
     }
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "instance");
 
     // Find the collection
     let Some((model_config, _collection_def)) = config.find_collection(&collection_id) else {
-        let exc = ExceptionResponse::not_found(format!("Collection not found: {}", collection_id));
-        let json = serde_json::to_string(&exc).unwrap_or_default();
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(json.into())
-            .unwrap();
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // Parse instance_id as datetime
     let reference_time = match chrono::DateTime::parse_from_rfc3339(&instance_id) {
         Ok(dt) => dt.with_timezone(&chrono::Utc),
         Err(_) => {
-            let exc = ExceptionResponse::bad_request(format!(
+            return error_response(EdrError::InvalidParameter(format!(
                 "Invalid instance ID format: {}. Expected ISO8601 datetime.",
                 instance_id
-            ));
-            let json = serde_json::to_string(&exc).unwrap_or_default();
-            return Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(json.into())
-                .unwrap();
+            )));
         }
     };
 
@@ -174,29 +150,19 @@ pub async fn get_instance_handler(
         Ok(runs) => runs,
         Err(e) => {
             tracing::error!("Failed to query model runs: {}", e);
-            let exc = ExceptionResponse::internal_error("Failed to get instance");
-            let json = serde_json::to_string(&exc).unwrap_or_default();
-            return Response::builder()
-                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                .header(header::CONTENT_TYPE, "application/json")
-                .body(json.into())
-                .unwrap();
+            return error_response(EdrError::InternalError(
+                "Failed to get instance".to_string(),
+            ));
         }
     };
 
     // Check if the requested run exists
     let run_exists = runs.iter().any(|(rt, _)| *rt == reference_time);
     if !run_exists {
-        let exc = ExceptionResponse::not_found(format!(
-            "Instance not found: {} for collection {}",
+        return error_response(EdrError::InstanceNotFound(format!(
+            "{} for collection {}",
             instance_id, collection_id
-        ));
-        let json = serde_json::to_string(&exc).unwrap_or_default();
-        return Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .header(header::CONTENT_TYPE, "application/json")
-            .body(json.into())
-            .unwrap();
+        )));
     }
 
     // Get spatial bbox from catalog
@@ -216,13 +182,10 @@ pub async fn get_instance_handler(
     // Build links
     instance.build_links(&state.base_url, &collection_id);
 
-    // Add data queries (position, area, radius, trajectory, corridor, and locations)
-    let queries = DataQueries::with_position(&state.base_url, &collection_id)
-        .with_area(&state.base_url, &collection_id)
-        .with_radius(&state.base_url, &collection_id)
-        .with_trajectory(&state.base_url, &collection_id)
-        .with_corridor(&state.base_url, &collection_id)
-        .with_locations(&state.base_url, &collection_id);
+    // Add data queries for the enabled query types (no cube on instances)
+    let queries = state
+        .features
+        .data_queries(&state.base_url, &collection_id, false);
     instance = instance.with_data_queries(queries);
 
     // Get actual temporal extent from forecast range
@@ -10,13 +10,13 @@
 use axum::{
     extract::{Extension, Path, Query},
     http::{header, HeaderMap, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use edr_protocol::{
-    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery,
-    responses::ExceptionResponse, CoverageJson, EdrFeatureCollection, LocationFeatureCollection,
+    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery, CoverageJson,
+    EdrError, EdrFeatureCollection, LocationFeatureCollection,
     PositionQuery as ParsedPositionQuery,
 };
 use grid_processor::DatasetQuery;
@@ -25,8 +25,9 @@ use std::sync::Arc;
 
 use crate::config::LevelValue;
 use crate::content_negotiation::{negotiate_format, OutputFormat};
-use crate::limits::ResponseSizeEstimate;
+use crate::limits::{enforce_limits, ResponseSizeEstimate};
 use crate::location_cache::LocationCacheKey;
+use crate::problem::error_response;
 use crate::state::AppState;
 
 /// Query parameters for locations list endpoint.
@@ -86,16 +87,14 @@ async fn locations_list(
     collection_id: String,
     _instance_id: Option<String>,
     params: LocationsListParams,
-    _headers: HeaderMap,
+    headers: HeaderMap,
 ) -> Response {
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "locations");
 
     // Validate the collection exists
     if config.find_collection(&collection_id).is_none() {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     }
 
     // Get all locations from config
@@ -131,10 +130,9 @@ async fn locations_list(
         Ok(j) => j,
         Err(e) => {
             tracing::error!("Failed to serialize locations: {}", e);
-            return error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ExceptionResponse::internal_error("Failed to serialize response"),
-            );
+            return error_response(EdrError::InternalError(
+                "Failed to serialize response".to_string(),
+            ));
         }
     };
 
@@ -192,6 +190,16 @@ async fn location_query(
         Err(response) => return response,
     };
 
+    // Resolve the collection as the caller's tenant sees it before checking
+    // the cache, so cached responses never reach tenants that can't see them
+    let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "location");
+
+    // Find the collection
+    let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
+    };
+
     // Build cache key early to check cache before expensive operations
     // Include format in cache key to ensure different formats are cached separately
     let cache_key = LocationCacheKey::new(
@@ -202,7 +210,8 @@ async fn location_query(
         params.parameter_name.clone(),
         params.z.clone(),
         params.f.clone(),
-    );
+    )
+    .with_tenant(state.request_tenant(&headers).map(|t| t.id.as_str()));
 
     // Check cache first
     if let Some((cached_data, cached_content_type)) = state.location_cache.get(&cache_key).await {
@@ -220,25 +229,12 @@ async fn location_query(
             .unwrap();
     }
 
-    let config = state.edr_config.read().await;
-
-    // Find the collection
-    let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
-    };
-
     // Find the location by ID
     let Some(location) = config.locations.find(&location_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!(
-                "Location not found: {}. Use GET /collections/{}/locations to list available locations.",
-                location_id, collection_id
-            )),
-        );
+        return error_response(EdrError::LocationNotFound(format!(
+            "{}. Use GET /collections/{}/locations to list available locations.",
+            location_id, collection_id
+        )));
     };
 
     let lon = location.lon();
@@ -257,10 +253,10 @@ async fn location_query(
         match ParsedPositionQuery::parse_z(z) {
             Ok(values) => Some(values),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid z parameter: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid z parameter: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -272,10 +268,10 @@ async fn location_query(
         match DateTimeQuery::parse(dt) {
             Ok(dt) => Some(dt),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid datetime: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid datetime: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -305,13 +301,10 @@ async fn location_query(
             .collect();
         for param in &requested_params {
             if !available.contains(&param.as_str()) {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!(
-                        "Parameter '{}' not available in collection. Available: {:?}",
-                        param, available
-                    )),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Parameter '{}' not available in collection. Available: {:?}",
+                    param, available
+                )));
             }
         }
         requested_params
@@ -348,11 +341,13 @@ async fn location_query(
     };
     let estimate = ResponseSizeEstimate::for_position(params_to_query.len(), num_times, num_levels);
 
-    if let Err(limit_err) = estimate.check_limits(&model_config.limits) {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(limit_err.to_string()),
-        );
+    if let Err(limit_err) = enforce_limits(
+        &estimate,
+        &model_config.limits,
+        &state.cost_budget,
+        &headers,
+    ) {
+        return limit_err.into_response();
     }
 
     // Parse and validate instance_id
@@ -367,13 +362,10 @@ async fn location_query(
                     Ok(runs) => {
                         let run_exists = runs.iter().any(|(rt, _)| *rt == ref_time);
                         if !run_exists {
-                            return error_response(
-                                StatusCode::NOT_FOUND,
-                                ExceptionResponse::not_found(format!(
-                                    "Instance not found: {} for collection {}",
-                                    id, collection_id
-                                )),
-                            );
+                            return error_response(EdrError::InstanceNotFound(format!(
+                                "{} for collection {}",
+                                id, collection_id
+                            )));
                         }
                     }
                     Err(e) => {
@@ -384,10 +376,10 @@ async fn location_query(
                 Some(ref_time)
             }
             Err(_) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid instance ID format: {}", id)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid instance ID format: {}",
+                    id
+                )));
             }
         }
     } else {
@@ -571,10 +563,9 @@ async fn location_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize GeoJSON: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             }
         }
@@ -582,10 +573,9 @@ async fn location_query(
             Ok(j) => (j, output_format.content_type()),
             Err(e) => {
                 tracing::error!("Failed to serialize CoverageJSON: {}", e);
-                return error_response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    ExceptionResponse::internal_error("Failed to serialize response"),
-                );
+                return error_response(EdrError::InternalError(
+                    "Failed to serialize response".to_string(),
+                ));
             }
         },
     };
@@ -653,15 +643,6 @@ fn build_level_string(
     }
 }
 
-fn error_response(status: StatusCode, exc: ExceptionResponse) -> Response {
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use edr_protocol::Location;
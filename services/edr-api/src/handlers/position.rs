@@ -2,22 +2,28 @@
 
 use axum::{
     extract::{Extension, Path, Query},
-    http::{header, HeaderMap, StatusCode},
-    response::Response,
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
 use edr_protocol::{
-    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery,
-    responses::ExceptionResponse, CoverageCollection, CoverageJson, EdrFeatureCollection,
-    ParsedCoords, PositionQuery as ParsedPositionQuery,
+    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery, CoverageCollection,
+    CoverageJson, EdrError, EdrFeatureCollection, ParsedCoords,
+    PositionQuery as ParsedPositionQuery,
 };
 use grid_processor::DatasetQuery;
 use serde::Deserialize;
 use std::sync::Arc;
+use wms_common::BoundingBox;
 
 use crate::config::LevelValue;
 use crate::content_negotiation::{negotiate_format, OutputFormat};
-use crate::limits::ResponseSizeEstimate;
+use crate::limits::{enforce_limits, ResponseSizeEstimate};
+use crate::problem::{error_response, grid_error};
+use crate::response_cache::{
+    cache_response, canonical_levels, canonical_names, canonical_points, CacheStatus,
+    ResponseCacheKey,
+};
 use crate::state::AppState;
 
 /// Query parameters for position endpoint.
@@ -88,23 +94,20 @@ async fn position_query(
     };
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "position");
 
     // Find the collection
     let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // Check for required coords parameter
     let coords_str = match &params.coords {
         Some(c) if !c.trim().is_empty() => c.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: coords"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: coords".to_string(),
+            ));
         }
     };
 
@@ -112,10 +115,10 @@ async fn position_query(
     let parsed_coords = match ParsedPositionQuery::parse_coords_multi(coords_str) {
         Ok(coords) => coords,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid coordinates: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid coordinates: {}",
+                e
+            )));
         }
     };
 
@@ -124,15 +127,27 @@ async fn position_query(
         ParsedCoords::Single(lon, lat) => vec![(*lon, *lat)],
         ParsedCoords::Multi(pts) => {
             if pts.is_empty() {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request("MULTIPOINT must contain at least one point"),
-                );
+                return error_response(EdrError::InvalidParameter(
+                    "MULTIPOINT must contain at least one point".to_string(),
+                ));
             }
             pts.clone()
         }
     };
 
+    // Reject points outside the collection's data (skipped if the extent is unknown)
+    if let Ok(extent) = state.catalog.get_model_bbox(&model_config.model).await {
+        if let Some((lon, lat)) = points
+            .iter()
+            .find(|(lon, lat)| !extent_contains(&extent, *lon, *lat))
+        {
+            return error_response(EdrError::OutOfExtent(format!(
+                "POINT({} {}) is outside the extent of collection {} ({}, {}, {}, {})",
+                lon, lat, collection_id, extent.min_x, extent.min_y, extent.max_x, extent.max_y
+            )));
+        }
+    }
+
     let is_multipoint = points.len() > 1;
     let (lon, lat) = points[0]; // Use first point for single-point calculations
 
@@ -141,10 +156,10 @@ async fn position_query(
         match ParsedPositionQuery::parse_z(z) {
             Ok(values) => Some(values),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid z parameter: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid z parameter: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -156,10 +171,10 @@ async fn position_query(
         match DateTimeQuery::parse(dt) {
             Ok(dt) => Some(dt),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid datetime: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid datetime: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -190,18 +205,52 @@ async fn position_query(
             .collect();
         for param in &requested_params {
             if !available.contains(&param.as_str()) {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!(
-                        "Parameter '{}' not available in collection. Available: {:?}",
-                        param, available
-                    )),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Parameter '{}' not available in collection. Available: {:?}",
+                    param, available
+                )));
             }
         }
         requested_params
     };
 
+    // Serve repeated identical queries from the shared response cache
+    let cache_ttl = state.response_cache.ttl_for(collection_def);
+    let cache_key = if state.response_cache.is_enabled() {
+        match state
+            .catalog
+            .get_model_data_version(&model_config.model)
+            .await
+        {
+            Ok(version) => Some(
+                ResponseCacheKey::new("position", &collection_id, version, instance_id.clone())
+                    .with("coords", Some(canonical_points(&points)))
+                    .with("z", z_values.as_deref().map(canonical_levels))
+                    .with(
+                        "datetime",
+                        params.datetime.as_ref().map(|d| d.trim().to_string()),
+                    )
+                    .with("parameter-name", Some(canonical_names(&params_to_query)))
+                    .with("f", Some(output_format.content_type().to_string())),
+            ),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to get data version, bypassing response cache: {}",
+                    e
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    if let Some(key) = &cache_key {
+        if let Some(cached) = state.response_cache.get(key).await {
+            tracing::debug!("Response cache hit for position query: {}", collection_id);
+            return cache_response(cached, CacheStatus::Hit, cache_ttl);
+        }
+    }
+
     // Get the list of times to query
     // For interval queries (especially open-ended ones), expand against available times
     let time_strings: Vec<String> = if let Some(ref dq) = datetime_query {
@@ -235,11 +284,13 @@ async fn position_query(
     };
     let estimate = ResponseSizeEstimate::for_position(params_to_query.len(), num_times, num_levels);
 
-    if let Err(limit_err) = estimate.check_limits(&model_config.limits) {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(limit_err.to_string()),
-        );
+    if let Err(limit_err) = enforce_limits(
+        &estimate,
+        &model_config.limits,
+        &state.cost_budget,
+        &headers,
+    ) {
+        return limit_err.into_response();
     }
 
     // Parse instance_id if provided and validate it exists
@@ -254,13 +305,10 @@ async fn position_query(
                     Ok(runs) => {
                         let run_exists = runs.iter().any(|(rt, _)| *rt == ref_time);
                         if !run_exists {
-                            return error_response(
-                                StatusCode::NOT_FOUND,
-                                ExceptionResponse::not_found(format!(
-                                    "Instance not found: {} for collection {}",
-                                    id, collection_id
-                                )),
-                            );
+                            return error_response(EdrError::InstanceNotFound(format!(
+                                "{} for collection {}",
+                                id, collection_id
+                            )));
                         }
                     }
                     Err(e) => {
@@ -272,10 +320,10 @@ async fn position_query(
                 Some(ref_time)
             }
             Err(_) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid instance ID format: {}", id)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid instance ID format: {}",
+                    id
+                )));
             }
         }
     } else {
@@ -366,10 +414,9 @@ async fn position_query(
                     Ok(j) => (j, output_format.content_type()),
                     Err(e) => {
                         tracing::error!("Failed to serialize GeoJSON: {}", e);
-                        return error_response(
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            ExceptionResponse::internal_error("Failed to serialize response"),
-                        );
+                        return error_response(EdrError::InternalError(
+                            "Failed to serialize response".to_string(),
+                        ));
                     }
                 }
             }
@@ -377,20 +424,17 @@ async fn position_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize CoverageCollection: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             },
         };
 
-        return Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, content_type)
-            .header(header::CACHE_CONTROL, "max-age=300")
-            .body(json.into())
-            .unwrap();
+        return state
+            .response_cache
+            .respond(cache_key.as_ref(), cache_ttl, content_type, json)
+            .await;
     }
 
     // Build CoverageJSON response - Point, PointSeries, or VerticalProfile
@@ -559,6 +603,19 @@ async fn position_query(
                         coverage = coverage.with_parameter_null(param_name, cov_param);
                     }
                 }
+                // Storage or catalog outages fail the request with a 502 so
+                // clients retry, rather than caching nulls that look like gaps
+                Err(e) if e.is_retryable() => {
+                    tracing::error!(
+                        "Failed to query {}/{} at ({}, {}): {}",
+                        model_config.model,
+                        param_name,
+                        lon,
+                        lat,
+                        e
+                    );
+                    return error_response(grid_error(e));
+                }
                 Err(e) => {
                     // Log the error but continue with other parameters
                     tracing::warn!(
@@ -585,10 +642,9 @@ async fn position_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize GeoJSON: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             }
         }
@@ -596,20 +652,17 @@ async fn position_query(
             Ok(j) => (j, output_format.content_type()),
             Err(e) => {
                 tracing::error!("Failed to serialize CoverageJSON: {}", e);
-                return error_response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    ExceptionResponse::internal_error("Failed to serialize response"),
-                );
+                return error_response(EdrError::InternalError(
+                    "Failed to serialize response".to_string(),
+                ));
             }
         },
     };
 
-    Response::builder()
-        .status(StatusCode::OK)
-        .header(header::CONTENT_TYPE, content_type)
-        .header(header::CACHE_CONTROL, "max-age=300")
-        .body(json.into())
-        .unwrap()
+    state
+        .response_cache
+        .respond(cache_key.as_ref(), cache_ttl, content_type, json)
+        .await
 }
 
 /// Build a catalog-compatible level string from EDR config.
@@ -673,13 +726,9 @@ fn build_level_string(
     }
 }
 
-fn error_response(status: StatusCode, exc: ExceptionResponse) -> Response {
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
+/// Whether a point lies within a collection extent, which may use 0..360 longitudes.
+fn extent_contains(extent: &BoundingBox, lon: f64, lat: f64) -> bool {
+    extent.contains_point(lon, lat) || extent.contains_point(lon + 360.0, lat)
 }
 
 #[cfg(test)]
@@ -687,6 +736,19 @@ mod tests {
     use super::*;
     use edr_protocol::PositionQuery;
 
+    #[test]
+    fn test_extent_contains() {
+        let conus = BoundingBox::new(-134.1, 21.1, -60.9, 52.6);
+        assert!(extent_contains(&conus, -97.5, 35.2));
+        assert!(!extent_contains(&conus, 10.0, 50.0));
+        assert!(!extent_contains(&conus, -97.5, 60.0));
+
+        // Global grids stored with 0..360 longitudes
+        let global = BoundingBox::new(0.0, -90.0, 359.75, 90.0);
+        assert!(extent_contains(&global, -97.5, 35.2));
+        assert!(extent_contains(&global, 120.0, -10.0));
+    }
+
     #[test]
     fn test_parse_wkt_point() {
         let (lon, lat) = PositionQuery::parse_coords("POINT(-97.5 35.2)").unwrap();
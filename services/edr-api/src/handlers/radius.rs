@@ -9,13 +9,12 @@
 use axum::{
     extract::{Extension, Path, Query},
     http::{header, HeaderMap, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
 use edr_protocol::{
-    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery,
-    responses::ExceptionResponse, CoverageJson, DistanceUnit, EdrFeatureCollection, ParsedCoords,
-    PositionQuery, RadiusQuery,
+    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery, CoverageJson,
+    DistanceUnit, EdrError, EdrFeatureCollection, ParsedCoords, PositionQuery, RadiusQuery,
 };
 use grid_processor::{BoundingBox, DatasetQuery};
 use serde::Deserialize;
@@ -23,7 +22,8 @@ use std::sync::Arc;
 
 use crate::config::LevelValue;
 use crate::content_negotiation::{negotiate_format, OutputFormat};
-use crate::limits::ResponseSizeEstimate;
+use crate::limits::{enforce_limits, ResponseSizeEstimate};
+use crate::problem::{error_response, grid_error};
 use crate::state::AppState;
 
 /// Query parameters for radius endpoint.
@@ -93,23 +93,20 @@ async fn radius_query(
     };
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "radius");
 
     // Find the collection
     let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // Check for required coords parameter
     let coords_str = match &params.coords {
         Some(c) if !c.trim().is_empty() => c.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: coords"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: coords".to_string(),
+            ));
         }
     };
 
@@ -117,10 +114,9 @@ async fn radius_query(
     let within_str = match &params.within {
         Some(w) if !w.trim().is_empty() => w.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: within"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: within".to_string(),
+            ));
         }
     };
 
@@ -128,10 +124,9 @@ async fn radius_query(
     let within_units_str = match &params.within_units {
         Some(u) if !u.trim().is_empty() => u.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: within-units"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: within-units".to_string(),
+            ));
         }
     };
 
@@ -139,10 +134,10 @@ async fn radius_query(
     let within_value = match RadiusQuery::parse_within(within_str) {
         Ok(v) => v,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid within parameter: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid within parameter: {}",
+                e
+            )));
         }
     };
 
@@ -150,10 +145,10 @@ async fn radius_query(
     let distance_unit = match DistanceUnit::parse(within_units_str) {
         Ok(u) => u,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!("Invalid within-units parameter: {}", e)),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid within-units parameter: {}",
+                e
+            )));
         }
     };
 
@@ -161,13 +156,10 @@ async fn radius_query(
     let parsed_coords = match PositionQuery::parse_coords_multi(coords_str) {
         Ok(c) => c,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!(
-                    "Invalid coordinates: {}. Expected POINT or MULTIPOINT.",
-                    e
-                )),
-            );
+            return error_response(EdrError::InvalidParameter(format!(
+                "Invalid coordinates: {}. Expected POINT or MULTIPOINT.",
+                e
+            )));
         }
     };
 
@@ -187,13 +179,10 @@ async fn radius_query(
     let radius_km = distance_unit.to_kilometers(within_value);
     let max_radius_km = model_config.limits.max_radius_km.unwrap_or(500.0);
     if radius_km > max_radius_km {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(format!(
-                "Radius too large: {:.2} km exceeds limit of {:.2} km",
-                radius_km, max_radius_km
-            )),
-        );
+        return error_response(EdrError::ResponseTooLarge(format!(
+            "Radius too large: {:.2} km exceeds limit of {:.2} km",
+            radius_km, max_radius_km
+        )));
     }
 
     // Parse vertical levels
@@ -201,10 +190,10 @@ async fn radius_query(
         match PositionQuery::parse_z(z) {
             Ok(values) => Some(values),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid z parameter: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid z parameter: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -216,10 +205,10 @@ async fn radius_query(
         match DateTimeQuery::parse(dt) {
             Ok(dt) => Some(dt),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid datetime: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid datetime: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -250,13 +239,10 @@ async fn radius_query(
             .collect();
         for param in &requested_params {
             if !available.contains(&param.as_str()) {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!(
-                        "Parameter '{}' not available in collection. Available: {:?}",
-                        param, available
-                    )),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Parameter '{}' not available in collection. Available: {:?}",
+                    param, available
+                )));
             }
         }
         requested_params
@@ -307,11 +293,13 @@ async fn radius_query(
         resolution,
     );
 
-    if let Err(limit_err) = estimate.check_limits(&model_config.limits) {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(limit_err.to_string()),
-        );
+    if let Err(limit_err) = enforce_limits(
+        &estimate,
+        &model_config.limits,
+        &state.cost_budget,
+        &headers,
+    ) {
+        return limit_err.into_response();
     }
 
     // Parse instance_id if provided and validate it exists
@@ -326,13 +314,10 @@ async fn radius_query(
                     Ok(runs) => {
                         let run_exists = runs.iter().any(|(rt, _)| *rt == ref_time);
                         if !run_exists {
-                            return error_response(
-                                StatusCode::NOT_FOUND,
-                                ExceptionResponse::not_found(format!(
-                                    "Instance not found: {} for collection {}",
-                                    id, collection_id
-                                )),
-                            );
+                            return error_response(EdrError::InstanceNotFound(format!(
+                                "{} for collection {}",
+                                id, collection_id
+                            )));
                         }
                     }
                     Err(e) => {
@@ -344,10 +329,10 @@ async fn radius_query(
                 Some(ref_time)
             }
             Err(_) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid instance ID format: {}", id)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid instance ID format: {}",
+                    id
+                )));
             }
         }
     } else {
@@ -372,10 +357,9 @@ async fn radius_query(
     let first_param = match params_to_query.first() {
         Some(p) => p,
         None => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("No parameters specified"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "No parameters specified".to_string(),
+            ));
         }
     };
 
@@ -419,20 +403,13 @@ async fn radius_query(
         Ok(r) => r,
         Err(e) => {
             tracing::error!("Failed to read region: {}", e);
-            return error_response(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                ExceptionResponse::internal_error(format!("Failed to read data: {}", e)),
-            );
+            return error_response(grid_error(e));
         }
     };
 
-    // Build x and y coordinate arrays from the grid metadata
-    let x_values: Vec<f64> = (0..region.width)
-        .map(|i| region.bbox.min_lon + (i as f64 + 0.5) * region.resolution.0)
-        .collect();
-    let y_values: Vec<f64> = (0..region.height)
-        .map(|j| region.bbox.max_lat - (j as f64 + 0.5) * region.resolution.1)
-        .collect();
+    // Build x and y coordinate arrays from the region's grid coordinates
+    let x_values = region.lon_values();
+    let y_values = region.lat_values();
 
     // Build the time axis
     let t_values = if !time_strings.is_empty() {
@@ -499,16 +476,15 @@ async fn radius_query(
                 // Apply radius mask - set values outside all circles to null
                 // Uses Haversine distance for accurate distance calculation
                 let mut values: Vec<Option<f32>> = Vec::with_capacity(param_region.data.len());
+                let lons = param_region.lon_values();
+                let lats = param_region.lat_values();
 
                 for (idx, &value) in param_region.data.iter().enumerate() {
                     let row = idx / param_region.width;
                     let col = idx % param_region.width;
 
-                    // Calculate lon/lat for this grid cell
-                    let lon =
-                        param_region.bbox.min_lon + (col as f64 + 0.5) * param_region.resolution.0;
-                    let lat =
-                        param_region.bbox.max_lat - (row as f64 + 0.5) * param_region.resolution.1;
+                    // Look up lon/lat for this grid point
+                    let (lon, lat) = (lons[col], lats[row]);
 
                     // Check if point is inside any of the radius circles (union)
                     let inside_any = radius_queries.iter().any(|rq| rq.contains_point(lon, lat));
@@ -566,10 +542,9 @@ async fn radius_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize GeoJSON: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             }
         }
@@ -577,10 +552,9 @@ async fn radius_query(
             Ok(j) => (j, output_format.content_type()),
             Err(e) => {
                 tracing::error!("Failed to serialize CoverageJSON: {}", e);
-                return error_response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    ExceptionResponse::internal_error("Failed to serialize response"),
-                );
+                return error_response(EdrError::InternalError(
+                    "Failed to serialize response".to_string(),
+                ));
             }
         },
     };
@@ -671,15 +645,6 @@ fn build_level_string(
     }
 }
 
-fn error_response(status: StatusCode, exc: ExceptionResponse) -> Response {
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
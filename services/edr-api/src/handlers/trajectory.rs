@@ -8,27 +8,32 @@
 //! The Z coordinate (in LINESTRINGZ/LINESTRINGZM) represents height.
 //! The M coordinate (in LINESTRINGM/LINESTRINGZM) represents Unix epoch time.
 //!
-//! If coords contains Z, the `z` query param MUST NOT be used.
-//! If coords contains M, the `datetime` query param provides additional filtering.
+//! If coords contains Z, the `z` query param MUST NOT be used, and each
+//! waypoint is sampled at its own level.
+//! If coords contains M, the `datetime` query param MUST NOT be used, and each
+//! waypoint is sampled at its own time: values are interpolated linearly
+//! between the forecast hours of the run (the instance, or the latest run)
+//! on either side of it. Waypoints outside the run's forecast range are null.
 
 use axum::{
     extract::{Extension, Path, Query},
     http::{header, HeaderMap, StatusCode},
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, TimeZone, Utc};
 use edr_protocol::{
-    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery,
-    responses::ExceptionResponse, CoverageJson, EdrFeatureCollection, PositionQuery,
-    TrajectoryQuery,
+    coverage_json::CovJsonParameter, parameters::Unit, queries::DateTimeQuery, CoverageJson,
+    EdrError, EdrFeatureCollection, PositionQuery, TrajectoryQuery, TrajectoryWaypoint,
 };
 use grid_processor::DatasetQuery;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::config::LevelValue;
 use crate::content_negotiation::{negotiate_format, OutputFormat};
-use crate::limits::ResponseSizeEstimate;
+use crate::limits::{enforce_limits, ResponseSizeEstimate};
+use crate::problem::error_response;
 use crate::state::AppState;
 
 /// Query parameters for trajectory endpoint.
@@ -91,23 +96,20 @@ async fn trajectory_query(
     };
 
     let config = state.edr_config.read().await;
+    let config = state.tenant_config(&config, &headers, "trajectory");
 
     // Find the collection
     let Some((model_config, collection_def)) = config.find_collection(&collection_id) else {
-        return error_response(
-            StatusCode::NOT_FOUND,
-            ExceptionResponse::not_found(format!("Collection not found: {}", collection_id)),
-        );
+        return error_response(EdrError::CollectionNotFound(collection_id.clone()));
     };
 
     // Check for required coords parameter
     let coords_str = match &params.coords {
         Some(c) if !c.trim().is_empty() => c.as_str(),
         _ => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request("Missing required parameter: coords"),
-            );
+            return error_response(EdrError::InvalidParameter(
+                "Missing required parameter: coords".to_string(),
+            ));
         }
     };
 
@@ -115,13 +117,10 @@ async fn trajectory_query(
     let parsed_trajectory = match TrajectoryQuery::parse_coords(coords_str) {
         Ok(t) => t,
         Err(e) => {
-            return error_response(
-                StatusCode::BAD_REQUEST,
-                ExceptionResponse::bad_request(format!(
+            return error_response(EdrError::InvalidParameter(format!(
                     "Invalid coordinates: {}. Expected LINESTRING, LINESTRINGZ, LINESTRINGM, LINESTRINGZM, or MULTI* variant.",
                     e
-                )),
-            );
+                )));
         }
     };
 
@@ -129,33 +128,28 @@ async fn trajectory_query(
     let waypoints = parsed_trajectory.waypoints;
 
     if waypoints.is_empty() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request("Trajectory must contain at least one waypoint"),
-        );
+        return error_response(EdrError::InvalidParameter(
+            "Trajectory must contain at least one waypoint".to_string(),
+        ));
     }
 
     // Check for conflicting z parameter when coords already has Z
     if line_type.has_z() && params.z.is_some() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request(
-                "Cannot specify 'z' parameter when coords contains Z coordinates (LINESTRINGZ/LINESTRINGZM). \
-                 Use either embedded Z coordinates or the z query parameter, not both."
-            ),
-        );
+        return error_response(EdrError::InvalidParameter(
+            "Cannot specify 'z' parameter when coords contains Z coordinates (LINESTRINGZ/LINESTRINGZM). \
+             Use either embedded Z coordinates or the z query parameter, not both."
+                .to_string(),
+        ));
     }
 
     // Check for conflicting datetime parameter when coords already has M
     // Per OGC EDR spec: An error SHALL be thrown if coords=LINESTRINGM and datetime is specified
     if line_type.has_m() && params.datetime.is_some() {
-        return error_response(
-            StatusCode::BAD_REQUEST,
-            ExceptionResponse::bad_request(
-                "Cannot specify 'datetime' parameter when coords contains M coordinates (LINESTRINGM/LINESTRINGZM). \
-                 Use either embedded M coordinates or the datetime query parameter, not both."
-            ),
-        );
+        return error_response(EdrError::InvalidParameter(
+            "Cannot specify 'datetime' parameter when coords contains M coordinates (LINESTRINGM/LINESTRINGZM). \
+             Use either embedded M coordinates or the datetime query parameter, not both."
+                .to_string(),
+        ));
     }
 
     // Parse vertical levels (only if not embedded in coords)
@@ -166,10 +160,10 @@ async fn trajectory_query(
         match PositionQuery::parse_z(z) {
             Ok(values) => Some(values),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid z parameter: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid z parameter: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -181,10 +175,10 @@ async fn trajectory_query(
         match DateTimeQuery::parse(dt) {
             Ok(dt) => Some(dt),
             Err(e) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid datetime: {}", e)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid datetime: {}",
+                    e
+                )));
             }
         }
     } else {
@@ -213,13 +207,10 @@ async fn trajectory_query(
             .collect();
         for param in &requested_params {
             if !available.contains(&param.as_str()) {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!(
-                        "Parameter '{}' not available in collection. Available: {:?}",
-                        param, available
-                    )),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Parameter '{}' not available in collection. Available: {:?}",
+                    param, available
+                )));
             }
         }
         requested_params
@@ -263,11 +254,13 @@ async fn trajectory_query(
         num_levels,
     );
 
-    if let Err(limit_err) = estimate.check_limits(&model_config.limits) {
-        return error_response(
-            StatusCode::PAYLOAD_TOO_LARGE,
-            ExceptionResponse::payload_too_large(limit_err.to_string()),
-        );
+    if let Err(limit_err) = enforce_limits(
+        &estimate,
+        &model_config.limits,
+        &state.cost_budget,
+        &headers,
+    ) {
+        return limit_err.into_response();
     }
 
     // Parse instance_id if provided
@@ -282,13 +275,10 @@ async fn trajectory_query(
                     Ok(runs) => {
                         let run_exists = runs.iter().any(|(rt, _)| *rt == ref_time);
                         if !run_exists {
-                            return error_response(
-                                StatusCode::NOT_FOUND,
-                                ExceptionResponse::not_found(format!(
-                                    "Instance not found: {} for collection {}",
-                                    id, collection_id
-                                )),
-                            );
+                            return error_response(EdrError::InstanceNotFound(format!(
+                                "{} for collection {}",
+                                id, collection_id
+                            )));
                         }
                     }
                     Err(e) => {
@@ -299,10 +289,10 @@ async fn trajectory_query(
                 Some(ref_time)
             }
             Err(_) => {
-                return error_response(
-                    StatusCode::BAD_REQUEST,
-                    ExceptionResponse::bad_request(format!("Invalid instance ID format: {}", id)),
-                );
+                return error_response(EdrError::InvalidParameter(format!(
+                    "Invalid instance ID format: {}",
+                    id
+                )));
             }
         }
     } else {
@@ -403,53 +393,78 @@ async fn trajectory_query(
             query = query.at_run(ref_time);
         }
 
+        // Run that time-parameterized waypoints are sampled from
+        let timed_run = if !line_type.has_m() {
+            None
+        } else if reference_time.is_some() {
+            reference_time
+        } else {
+            state
+                .catalog
+                .get_available_runs(&model_config.model, param_name)
+                .await
+                .ok()
+                .and_then(|runs| runs.first().copied())
+        };
+
+        // Forecast hours of the timed run, by level
+        let mut run_hours: HashMap<Option<String>, Vec<u32>> = HashMap::new();
+
         // Sample values at each waypoint
         let mut values: Vec<Option<f32>> = Vec::with_capacity(waypoints.len());
 
         for waypoint in &waypoints {
-            // For trajectory queries with embedded time (M coords), we might need
-            // to query at different valid times for each waypoint.
-            // For simplicity, we'll sample at the waypoint location using the first time.
-            // A more sophisticated implementation could query each waypoint at its embedded time.
-
-            let wp_query = if line_type.has_m() {
-                // Use the waypoint's embedded time
-                if let Some(epoch) = waypoint.m {
-                    if let Some(dt) = Utc.timestamp_opt(epoch, 0).single() {
-                        query.clone().at_valid_time(dt)
-                    } else {
-                        query.clone()
-                    }
-                } else {
-                    query.clone()
-                }
+            // Waypoints with embedded heights are sampled at their own level
+            let wp_level = if line_type.has_z() {
+                build_level_string(&collection_def.level_filter, param_def, waypoint.z)
             } else {
-                query.clone()
+                level_str.clone()
             };
 
-            match state
-                .grid_data_service
-                .read_point(&wp_query, waypoint.lon, waypoint.lat)
-                .await
-            {
-                Ok(point_value) => {
-                    // PointValue has an Option<f32> value field
-                    match point_value.value {
-                        Some(v) if !v.is_nan() => values.push(Some(v)),
-                        _ => values.push(None),
+            let value = if line_type.has_m() {
+                match timed_run {
+                    Some(run) => {
+                        if !run_hours.contains_key(&wp_level) {
+                            let hours = state
+                                .catalog
+                                .get_run_forecast_hours(
+                                    &model_config.model,
+                                    param_name,
+                                    run,
+                                    wp_level.as_deref(),
+                                )
+                                .await
+                                .map(|hours| hours.into_iter().map(|h| h as u32).collect())
+                                .unwrap_or_default();
+                            run_hours.insert(wp_level.clone(), hours);
+                        }
+
+                        let mut run_query =
+                            DatasetQuery::forecast(&model_config.model, param_name).at_run(run);
+                        if let Some(level) = &wp_level {
+                            run_query = run_query.at_level(level);
+                        }
+
+                        sample_at_waypoint_time(
+                            &state,
+                            &run_query,
+                            run,
+                            &run_hours[&wp_level],
+                            waypoint,
+                        )
+                        .await
                     }
+                    None => None,
                 }
-                Err(e) => {
-                    tracing::debug!(
-                        "Failed to sample {} at ({}, {}): {}",
-                        param_name,
-                        waypoint.lon,
-                        waypoint.lat,
-                        e
-                    );
-                    values.push(None);
+            } else {
+                let mut wp_query = query.clone();
+                if let Some(level) = &wp_level {
+                    wp_query = wp_query.at_level(level);
                 }
-            }
+                read_waypoint_value(&state, &wp_query, waypoint).await
+            };
+
+            values.push(value);
         }
 
         // Get metadata for units
@@ -478,10 +493,9 @@ async fn trajectory_query(
                 Ok(j) => (j, output_format.content_type()),
                 Err(e) => {
                     tracing::error!("Failed to serialize GeoJSON: {}", e);
-                    return error_response(
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        ExceptionResponse::internal_error("Failed to serialize response"),
-                    );
+                    return error_response(EdrError::InternalError(
+                        "Failed to serialize response".to_string(),
+                    ));
                 }
             }
         }
@@ -489,10 +503,9 @@ async fn trajectory_query(
             Ok(j) => (j, output_format.content_type()),
             Err(e) => {
                 tracing::error!("Failed to serialize CoverageJSON: {}", e);
-                return error_response(
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    ExceptionResponse::internal_error("Failed to serialize response"),
-                );
+                return error_response(EdrError::InternalError(
+                    "Failed to serialize response".to_string(),
+                ));
             }
         },
     };
@@ -505,6 +518,74 @@ async fn trajectory_query(
         .unwrap()
 }
 
+/// Sample a parameter at a waypoint's embedded time.
+///
+/// `query` selects the run (and level); the value is interpolated linearly
+/// between the run's forecast hours on either side of the waypoint time.
+async fn sample_at_waypoint_time(
+    state: &AppState,
+    query: &DatasetQuery,
+    run: DateTime<Utc>,
+    forecast_hours: &[u32],
+    waypoint: &TrajectoryWaypoint,
+) -> Option<f32> {
+    let time = Utc.timestamp_opt(waypoint.m?, 0).single()?;
+    let offset_hours = (time - run).num_seconds() as f64 / 3600.0;
+    let (lower, upper, weight) = bracket_forecast_hours(forecast_hours, offset_hours)?;
+
+    let lower_query = query.clone().at_forecast_hour(lower);
+    let lower_value = read_waypoint_value(state, &lower_query, waypoint).await?;
+    if lower == upper {
+        return Some(lower_value);
+    }
+
+    let upper_query = query.clone().at_forecast_hour(upper);
+    let upper_value = read_waypoint_value(state, &upper_query, waypoint).await?;
+    Some(lower_value + (upper_value - lower_value) * weight)
+}
+
+/// Forecast hours on either side of an offset from the run time (in hours),
+/// with the interpolation weight of the later one.
+///
+/// `forecast_hours` must be sorted ascending. Returns None if the offset is
+/// outside the available hours.
+fn bracket_forecast_hours(forecast_hours: &[u32], offset_hours: f64) -> Option<(u32, u32, f32)> {
+    let upper_idx = forecast_hours.partition_point(|&h| (h as f64) < offset_hours);
+    let upper = *forecast_hours.get(upper_idx)?;
+    if upper as f64 == offset_hours {
+        return Some((upper, upper, 0.0));
+    }
+
+    let lower = *forecast_hours.get(upper_idx.checked_sub(1)?)?;
+    let weight = (offset_hours - lower as f64) / (upper - lower) as f64;
+    Some((lower, upper, weight as f32))
+}
+
+/// Read a single value at a waypoint, treating errors and NaN as missing.
+async fn read_waypoint_value(
+    state: &AppState,
+    query: &DatasetQuery,
+    waypoint: &TrajectoryWaypoint,
+) -> Option<f32> {
+    match state
+        .grid_data_service
+        .read_point(query, waypoint.lon, waypoint.lat)
+        .await
+    {
+        Ok(point_value) => point_value.value.filter(|v| !v.is_nan()),
+        Err(e) => {
+            tracing::debug!(
+                "Failed to sample {} at ({}, {}): {}",
+                query.parameter,
+                waypoint.lon,
+                waypoint.lat,
+                e
+            );
+            None
+        }
+    }
+}
+
 /// Build a catalog-compatible level string from EDR config.
 fn build_level_string(
     level_filter: &crate::config::LevelFilter,
@@ -549,15 +630,6 @@ fn build_level_string(
     }
 }
 
-fn error_response(status: StatusCode, exc: ExceptionResponse) -> Response {
-    let json = serde_json::to_string(&exc).unwrap_or_default();
-    Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(json.into())
-        .unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -608,6 +680,26 @@ mod tests {
         assert!(parsed.line_type.has_m());
     }
 
+    #[test]
+    fn test_bracket_forecast_hours() {
+        let hours = [0, 1, 2, 3, 6, 9];
+
+        // Exact forecast hour
+        assert_eq!(bracket_forecast_hours(&hours, 3.0), Some((3, 3, 0.0)));
+        assert_eq!(bracket_forecast_hours(&hours, 0.0), Some((0, 0, 0.0)));
+
+        // Between forecast hours
+        assert_eq!(bracket_forecast_hours(&hours, 1.5), Some((1, 2, 0.5)));
+        let (lower, upper, weight) = bracket_forecast_hours(&hours, 7.0).unwrap();
+        assert_eq!((lower, upper), (6, 9));
+        assert!((weight - 1.0 / 3.0).abs() < 1e-6);
+
+        // Outside the run's forecast range
+        assert_eq!(bracket_forecast_hours(&hours, -0.5), None);
+        assert_eq!(bracket_forecast_hours(&hours, 9.5), None);
+        assert_eq!(bracket_forecast_hours(&[], 1.0), None);
+    }
+
     #[test]
     fn test_parse_invalid_linestring() {
         // POINT is not valid for trajectory
@@ -5,7 +5,12 @@
 
 pub mod config;
 pub mod content_negotiation;
+pub mod features;
 pub mod handlers;
 pub mod limits;
 pub mod location_cache;
+pub mod netcdf;
+pub mod problem;
+pub mod raster;
+pub mod response_cache;
 pub mod state;
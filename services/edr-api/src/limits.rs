@@ -1,4 +1,16 @@
-//! Response size limit calculation.
+//! Response size limit calculation and query cost budgets.
+//!
+//! Every data query is estimated before execution. The estimate is checked
+//! against the collection's per-query limits and, if configured, charged
+//! against the caller's hourly cost budget. The cost of a query is
+//! `points × parameters × times × levels`, i.e. the number of values returned.
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use edr_protocol::{responses::ExceptionResponse, PROBLEM_JSON_MEDIA_TYPE};
+use wms_common::api_key::{ApiKey, BudgetExceeded, HourlyBudgetTracker, API_KEY_HEADER};
 
 use crate::config::LimitsConfig;
 
@@ -135,6 +147,14 @@ impl ResponseSizeEstimate {
         }
     }
 
+    /// Cost of the query: the number of values it returns.
+    pub fn cost(&self) -> u64 {
+        (self.num_points as u64)
+            .saturating_mul(self.num_parameters as u64)
+            .saturating_mul(self.num_time_steps.max(1) as u64)
+            .saturating_mul(self.num_vertical_levels.max(1) as u64)
+    }
+
     /// Get estimated size in megabytes.
     pub fn estimated_mb(&self) -> f64 {
         self.estimated_bytes as f64 / (1024.0 * 1024.0)
@@ -163,6 +183,16 @@ impl ResponseSizeEstimate {
             });
         }
 
+        if let Some(max_cost) = limits.max_query_cost {
+            let cost = self.cost();
+            if cost > max_cost {
+                return Err(LimitExceeded::QueryTooExpensive {
+                    cost,
+                    limit: max_cost,
+                });
+            }
+        }
+
         let max_bytes = limits.max_response_size_mb * 1024 * 1024;
         if self.estimated_bytes > max_bytes {
             return Err(LimitExceeded::ResponseTooLarge {
@@ -182,6 +212,67 @@ pub enum LimitExceeded {
     TooManyTimeSteps { requested: usize, limit: usize },
     TooManyLevels { requested: usize, limit: usize },
     ResponseTooLarge { estimated_mb: f64, limit_mb: usize },
+    QueryTooExpensive { cost: u64, limit: u64 },
+    BudgetExhausted(BudgetExceeded),
+}
+
+impl LimitExceeded {
+    /// HTTP status for this error.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            LimitExceeded::BudgetExhausted(_) => StatusCode::TOO_MANY_REQUESTS,
+            _ => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+
+    /// Convert to an ExceptionResponse.
+    pub fn to_exception(&self) -> ExceptionResponse {
+        match self {
+            LimitExceeded::BudgetExhausted(_) => {
+                ExceptionResponse::too_many_requests(self.to_string())
+            }
+            _ => ExceptionResponse::payload_too_large(self.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for LimitExceeded {
+    fn into_response(self) -> Response {
+        let json = serde_json::to_string(&self.to_exception()).unwrap_or_default();
+        let mut builder = Response::builder()
+            .status(self.status_code())
+            .header(header::CONTENT_TYPE, PROBLEM_JSON_MEDIA_TYPE);
+        if let LimitExceeded::BudgetExhausted(e) = &self {
+            builder = builder.header(header::RETRY_AFTER, e.resets_in_secs);
+        }
+        builder.body(json.into()).unwrap()
+    }
+}
+
+/// Check a query estimate against the per-query limits and charge its cost to
+/// the caller's hourly budget.
+///
+/// The caller is identified by the `x-api-key` header (anonymous if absent).
+/// Queries rejected by the per-query limits are not charged.
+pub fn enforce_limits(
+    estimate: &ResponseSizeEstimate,
+    limits: &LimitsConfig,
+    budget: &HourlyBudgetTracker,
+    headers: &HeaderMap,
+) -> Result<(), LimitExceeded> {
+    estimate.check_limits(limits)?;
+
+    if let Some(hourly_budget) = limits.hourly_cost_budget {
+        let key = ApiKey::resolve(
+            headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()),
+            None,
+        );
+        budget
+            .try_consume(&key, estimate.cost(), hourly_budget, chrono::Utc::now())
+            .map_err(LimitExceeded::BudgetExhausted)?;
+    }
+
+    Ok(())
 }
 
 impl std::fmt::Display for LimitExceeded {
@@ -218,6 +309,14 @@ impl std::fmt::Display for LimitExceeded {
                     estimated_mb, limit_mb
                 )
             }
+            LimitExceeded::QueryTooExpensive { cost, limit } => {
+                write!(
+                    f,
+                    "Query too expensive: cost is {} values (points × parameters × times × levels), limit is {}",
+                    cost, limit
+                )
+            }
+            LimitExceeded::BudgetExhausted(e) => write!(f, "{}", e),
         }
     }
 }
@@ -298,6 +397,75 @@ mod tests {
         assert!(display.contains("10"));
     }
 
+    #[test]
+    fn test_query_cost() {
+        // 1 point × 3 params × 24 times × 6 levels
+        let estimate = ResponseSizeEstimate::for_position(3, 24, 6);
+        assert_eq!(estimate.cost(), 432);
+
+        let estimate = ResponseSizeEstimate::for_trajectory(2, 100, 1, 1);
+        assert_eq!(estimate.cost(), 200);
+    }
+
+    #[test]
+    fn test_check_limits_query_too_expensive() {
+        let estimate = ResponseSizeEstimate::for_position(3, 24, 6);
+        let limits = LimitsConfig {
+            max_query_cost: Some(100),
+            ..Default::default()
+        };
+
+        let result = estimate.check_limits(&limits);
+        assert!(matches!(
+            result,
+            Err(LimitExceeded::QueryTooExpensive {
+                cost: 432,
+                limit: 100
+            })
+        ));
+    }
+
+    #[test]
+    fn test_enforce_limits_hourly_budget() {
+        let estimate = ResponseSizeEstimate::for_position(3, 24, 6); // cost 432
+        let limits = LimitsConfig {
+            hourly_cost_budget: Some(1000),
+            ..Default::default()
+        };
+        let budget = HourlyBudgetTracker::new();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(API_KEY_HEADER, "client-a".parse().unwrap());
+
+        assert!(enforce_limits(&estimate, &limits, &budget, &headers).is_ok());
+        assert!(enforce_limits(&estimate, &limits, &budget, &headers).is_ok());
+
+        let err = enforce_limits(&estimate, &limits, &budget, &headers).unwrap_err();
+        assert!(matches!(err, LimitExceeded::BudgetExhausted(_)));
+        assert_eq!(err.status_code(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Anonymous callers have a separate budget
+        assert!(enforce_limits(&estimate, &limits, &budget, &HeaderMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_limit_exceeded_problem_json() {
+        let err = LimitExceeded::QueryTooExpensive {
+            cost: 500,
+            limit: 100,
+        };
+        let exc = err.to_exception();
+        assert_eq!(exc.status, Some(413));
+        assert!(exc.detail.unwrap().contains("500"));
+
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+    }
+
     #[test]
     fn test_radius_estimate() {
         // 100 km radius at 0.03 degree resolution
@@ -41,6 +41,8 @@ pub struct LocationCacheKey {
     pub z: Option<String>,
     /// Output format (e.g., "geojson", "covjson")
     pub format: Option<String>,
+    /// Tenant of the caller, whose collections may expose fewer parameters
+    pub tenant: Option<String>,
 }
 
 impl LocationCacheKey {
@@ -62,13 +64,20 @@ impl LocationCacheKey {
             parameters,
             z,
             format,
+            tenant: None,
         }
     }
 
+    /// Scope the key to a tenant.
+    pub fn with_tenant(mut self, tenant: Option<&str>) -> Self {
+        self.tenant = tenant.map(String::from);
+        self
+    }
+
     /// Convert to a string key for the LRU cache.
     fn to_string_key(&self) -> String {
         format!(
-            "{}:{}:{}:{}:{}:{}:{}",
+            "{}:{}:{}:{}:{}:{}:{}:{}",
             self.collection_id,
             self.location_id,
             self.instance_id.as_deref().unwrap_or("-"),
@@ -76,6 +85,7 @@ impl LocationCacheKey {
             self.parameters.as_deref().unwrap_or("-"),
             self.z.as_deref().unwrap_or("-"),
             self.format.as_deref().unwrap_or("-"),
+            self.tenant.as_deref().unwrap_or("-"),
         )
     }
 }
@@ -5,14 +5,14 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::{routing::get, Extension, Router};
+use axum::{middleware, routing::get, Extension, Router};
 use clap::Parser;
 use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
 use tracing::info;
 use tracing_subscriber::{fmt, EnvFilter};
 
-use edr_api::handlers;
 use edr_api::state::AppState;
+use edr_api::{handlers, problem};
 
 /// EDR API Server
 #[derive(Parser, Debug)]
@@ -117,78 +117,17 @@ async fn run_server(args: Args) {
         .route(
             "/edr/collections/:collection_id/instances/:instance_id",
             get(handlers::instances::get_instance_handler),
-        )
-        // Position query
-        .route(
-            "/edr/collections/:collection_id/position",
-            get(handlers::position::position_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/position",
-            get(handlers::position::instance_position_handler),
-        )
-        // Area query
-        .route(
-            "/edr/collections/:collection_id/area",
-            get(handlers::area::area_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/area",
-            get(handlers::area::instance_area_handler),
-        )
-        // Radius query
-        .route(
-            "/edr/collections/:collection_id/radius",
-            get(handlers::radius::radius_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/radius",
-            get(handlers::radius::instance_radius_handler),
-        )
-        // Trajectory query
-        .route(
-            "/edr/collections/:collection_id/trajectory",
-            get(handlers::trajectory::trajectory_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/trajectory",
-            get(handlers::trajectory::instance_trajectory_handler),
-        )
-        // Corridor query
-        .route(
-            "/edr/collections/:collection_id/corridor",
-            get(handlers::corridor::corridor_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/corridor",
-            get(handlers::corridor::instance_corridor_handler),
-        )
-        // Cube query
-        .route(
-            "/edr/collections/:collection_id/cube",
-            get(handlers::cube::cube_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/cube",
-            get(handlers::cube::instance_cube_handler),
-        )
-        // Locations query
-        .route(
-            "/edr/collections/:collection_id/locations",
-            get(handlers::locations::locations_list_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/locations/:location_id",
-            get(handlers::locations::location_query_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/locations",
-            get(handlers::locations::instance_locations_list_handler),
-        )
-        .route(
-            "/edr/collections/:collection_id/instances/:instance_id/locations/:location_id",
-            get(handlers::locations::instance_location_query_handler),
-        )
+        );
+
+    // Data queries: only the enabled query types are mounted, and
+    // /edr/conformance declares exactly those
+    info!(
+        queries = ?state.features.queries().iter().map(|q| q.name()).collect::<Vec<_>>(),
+        "Mounting EDR query types"
+    );
+    let app = state
+        .features
+        .mount_queries(app)
         // Health and metrics
         .route("/health", get(handlers::health::health_handler))
         .route("/ready", get(handlers::health::ready_handler))
@@ -198,7 +137,10 @@ async fn run_server(args: Args) {
             "/edr/catalog-check",
             get(handlers::catalog_check::catalog_check_handler),
         )
+        // Unknown routes get a problem+json 404
+        .fallback(problem::not_found_handler)
         // Middleware
+        .layer(middleware::from_fn(problem::problem_instance))
         .layer(Extension(state))
         .layer(TraceLayer::new_for_http())
         .layer(CompressionLayer::new())
@@ -0,0 +1,241 @@
+//! NetCDF downloads of grid query results.
+//!
+//! Area and cube queries can be answered with a CF-compliant NetCDF file
+//! (see [`grid_processor::NetCdfCoverage`]) instead of JSON. Handlers collect
+//! each parameter's values in a [`NetCdfVariables`] while they build the
+//! CoverageJSON ranges; cubes stack the levels along a vertical coordinate.
+
+use axum::{
+    http::{header, StatusCode},
+    response::Response,
+};
+use chrono::{DateTime, Duration, Utc};
+use edr_protocol::EdrError;
+use grid_processor::{CfAttributes, GridMetadata, NetCdfCoverage, VerticalAxis};
+
+use crate::content_negotiation::NETCDF_MEDIA_TYPE;
+use crate::problem::error_response;
+
+/// One parameter's values, level after level.
+struct Variable {
+    name: String,
+    units: String,
+    cf: CfAttributes,
+    values: Vec<f32>,
+}
+
+/// Parameter values collected while answering a grid query.
+#[derive(Default)]
+pub struct NetCdfVariables {
+    variables: Vec<Variable>,
+    run: Option<(DateTime<Utc>, u32)>,
+}
+
+impl NetCdfVariables {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one level of a parameter; missing values become NaN.
+    ///
+    /// The first call for a parameter sets its units and CF attributes.
+    pub fn push(&mut self, name: &str, units: &str, cf: CfAttributes, values: &[Option<f32>]) {
+        let values = values.iter().map(|v| v.unwrap_or(f32::NAN));
+        match self.variables.iter_mut().find(|v| v.name == name) {
+            Some(variable) => {
+                if variable.units.is_empty() {
+                    variable.units = units.to_string();
+                }
+                variable.values.extend(values);
+            }
+            None => self.variables.push(Variable {
+                name: name.to_string(),
+                units: units.to_string(),
+                cf,
+                values: values.collect(),
+            }),
+        }
+    }
+
+    /// Remember the run and forecast hour of the data that was read.
+    pub fn record_run(&mut self, metadata: Option<&GridMetadata>) {
+        if self.run.is_none() {
+            self.run = metadata.map(|m| (m.reference_time, m.forecast_hour));
+        }
+    }
+
+    /// Build the coverage on the given grid.
+    ///
+    /// The valid time comes from the data that was read, falling back to
+    /// the requested time. Nothing collected is a 404.
+    pub fn into_coverage(
+        self,
+        lon: Vec<f64>,
+        lat: Vec<f64>,
+        vertical: Option<VerticalAxis>,
+        requested_time: Option<DateTime<Utc>>,
+    ) -> Result<NetCdfCoverage, EdrError> {
+        let no_data = || EdrError::NoDataAvailable("no data found for the NetCDF export".into());
+        if self.variables.is_empty() {
+            return Err(no_data());
+        }
+        let valid_time = match self.run {
+            Some((run, hour)) => run + Duration::hours(hour as i64),
+            None => requested_time.ok_or_else(no_data)?,
+        };
+
+        let mut coverage = NetCdfCoverage::new(lon, lat, valid_time);
+        if let Some((run, _)) = self.run {
+            coverage = coverage.with_reference_time(run);
+        }
+        if let Some(vertical) = vertical {
+            coverage = coverage.with_vertical(vertical);
+        }
+        for variable in self.variables {
+            coverage
+                .add_variable(
+                    &variable.name,
+                    &variable.units,
+                    variable.cf,
+                    &variable.values,
+                )
+                .map_err(|e| EdrError::InternalError(e.to_string()))?;
+        }
+        Ok(coverage)
+    }
+}
+
+/// Longitudes or latitudes of a regular axis from `start` to `stop`
+/// inclusive, as in a CoverageJSON regular axis.
+pub fn regular_axis_values(start: f64, stop: f64, num: usize) -> Vec<f64> {
+    if num < 2 {
+        return vec![start; num];
+    }
+    let step = (stop - start) / (num - 1) as f64;
+    (0..num).map(|i| start + step * i as f64).collect()
+}
+
+/// Encode a coverage as a NetCDF file download named `<basename>.nc`.
+pub fn netcdf_response(coverage: Result<NetCdfCoverage, EdrError>, basename: &str) -> Response {
+    let body = coverage.and_then(|coverage| {
+        coverage
+            .to_netcdf()
+            .map_err(|e| EdrError::InternalError(format!("Failed to encode NetCDF: {}", e)))
+    });
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => return error_response(e),
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, NETCDF_MEDIA_TYPE)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.nc\"", basename),
+        )
+        .header(header::CACHE_CONTROL, "max-age=300")
+        .body(body.into())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_regular_axis_values() {
+        assert_eq!(regular_axis_values(40.0, 38.0, 3), vec![40.0, 39.0, 38.0]);
+        assert_eq!(regular_axis_values(-100.0, -90.0, 1), vec![-100.0]);
+    }
+
+    #[test]
+    fn test_variables_stack_levels() {
+        let run = Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap();
+        let mut variables = NetCdfVariables::new();
+        let cf = CfAttributes::for_parameter("TMP", "850 mb");
+        variables.push("TMP", "K", cf.clone(), &[Some(270.0), None]);
+        variables.push("TMP", "K", cf, &[Some(250.0), Some(251.0)]);
+        variables.run = Some((run, 6));
+
+        let coverage = variables
+            .into_coverage(
+                vec![-100.0, -99.0],
+                vec![40.0],
+                Some(VerticalAxis::new("hPa", vec![850.0, 500.0])),
+                None,
+            )
+            .unwrap();
+        assert_eq!(coverage.valid_time, run + Duration::hours(6));
+        assert_eq!(coverage.reference_time, Some(run));
+        assert_eq!(coverage.len(), 1);
+    }
+
+    #[test]
+    fn test_variables_need_data_and_time() {
+        assert!(NetCdfVariables::new()
+            .into_coverage(vec![0.0], vec![0.0], None, None)
+            .is_err());
+
+        let mut variables = NetCdfVariables::new();
+        variables.push(
+            "TMP",
+            "",
+            CfAttributes::for_parameter("TMP", "surface"),
+            &[None],
+        );
+        assert!(variables
+            .into_coverage(vec![0.0], vec![0.0], None, None)
+            .is_err());
+
+        // A level count that doesn't match the vertical axis is a bug
+        let mut variables = NetCdfVariables::new();
+        variables.push(
+            "TMP",
+            "K",
+            CfAttributes::for_parameter("TMP", "surface"),
+            &[None],
+        );
+        let requested = Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap();
+        assert!(variables
+            .into_coverage(
+                vec![0.0],
+                vec![0.0],
+                Some(VerticalAxis::new("hPa", vec![850.0, 500.0])),
+                Some(requested),
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_netcdf_response() {
+        let requested = Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap();
+        let mut variables = NetCdfVariables::new();
+        variables.push(
+            "TMP",
+            "K",
+            CfAttributes::for_parameter("TMP", "surface"),
+            &[Some(1.0)],
+        );
+        let coverage = variables.into_coverage(vec![0.0], vec![0.0], None, Some(requested));
+
+        let response = netcdf_response(coverage, "gfs_cube");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-netcdf"
+        );
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"gfs_cube.nc\""
+        );
+
+        let response = netcdf_response(
+            Err(EdrError::NoDataAvailable("none".to_string())),
+            "gfs_cube",
+        );
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
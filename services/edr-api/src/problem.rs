@@ -0,0 +1,199 @@
+//! RFC 7807 problem+json error responses.
+//!
+//! Handlers report failures with [`error_response`]; grid read failures go
+//! through [`grid_error`] first so their status says whether to retry. The
+//! [`problem_instance`]
+//! middleware fills in each problem's `instance` with the request URI, so
+//! handlers don't need to thread the URI through to every error path.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode, Uri},
+    middleware::Next,
+    response::Response,
+};
+use edr_protocol::{responses::ExceptionResponse, EdrError, PROBLEM_JSON_MEDIA_TYPE};
+use grid_processor::{ErrorKind, GridProcessorError};
+
+/// Largest problem body the middleware will rewrite.
+const MAX_PROBLEM_BODY_BYTES: usize = 64 * 1024;
+
+/// Build a problem+json response for an error.
+pub fn error_response(err: EdrError) -> Response {
+    problem_response(err.to_exception())
+}
+
+/// Map a failed grid read to the error reported to the client: missing data
+/// is 404, a storage or catalog failure is 502 (worth retrying), and corrupt
+/// data or a bug is 500.
+pub fn grid_error(err: GridProcessorError) -> EdrError {
+    match err.kind() {
+        ErrorKind::NotFound => EdrError::NoDataAvailable(err.to_string()),
+        ErrorKind::InvalidRequest => EdrError::InvalidParameter(err.to_string()),
+        ErrorKind::Upstream => EdrError::DataAccessError(err.to_string()),
+        ErrorKind::CorruptData | ErrorKind::Internal => {
+            EdrError::InternalError(format!("Failed to read data: {}", err))
+        }
+    }
+}
+
+/// Build a problem+json response from a problem detail.
+pub fn problem_response(exc: ExceptionResponse) -> Response {
+    let status = exc
+        .status
+        .and_then(|s| StatusCode::from_u16(s).ok())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let json = serde_json::to_string(&exc).unwrap_or_default();
+
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, PROBLEM_JSON_MEDIA_TYPE)
+        .body(json.into())
+        .unwrap()
+}
+
+/// Middleware that sets `instance` on problem responses to the request URI.
+pub async fn problem_instance(request: Request, next: Next) -> Response {
+    let instance = instance_uri(request.uri());
+    set_problem_instance(next.run(request).await, instance).await
+}
+
+/// Set `instance` on a problem response that doesn't have one yet.
+async fn set_problem_instance(response: Response, instance: String) -> Response {
+    let is_problem = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes() == PROBLEM_JSON_MEDIA_TYPE.as_bytes());
+    if !is_problem {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, MAX_PROBLEM_BODY_BYTES).await else {
+        return Response::from_parts(parts, Body::empty());
+    };
+
+    match serde_json::from_slice::<ExceptionResponse>(&bytes) {
+        Ok(exc) if exc.instance.is_none() => {
+            let json = serde_json::to_string(&exc.with_instance(instance)).unwrap_or_default();
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, json.into())
+        }
+        _ => Response::from_parts(parts, bytes.into()),
+    }
+}
+
+/// Fallback for requests that match no route.
+pub async fn not_found_handler(uri: Uri) -> Response {
+    problem_response(ExceptionResponse::not_found(format!(
+        "No resource at {}",
+        uri.path()
+    )))
+}
+
+/// Request path and query, used as the problem `instance`.
+fn instance_uri(uri: &Uri) -> String {
+    uri.path_and_query()
+        .map(|pq| pq.as_str().to_string())
+        .unwrap_or_else(|| uri.path().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use edr_protocol::queries::CoordinateParseError;
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let response = error_response(EdrError::CollectionNotFound("nope".to_string()));
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROBLEM_JSON_MEDIA_TYPE
+        );
+
+        let json = body_json(response).await;
+        assert!(json["type"]
+            .as_str()
+            .unwrap()
+            .ends_with("/unknown-collection"));
+        assert_eq!(json["status"], 404);
+        assert!(json.get("instance").is_none());
+
+        let err: EdrError = CoordinateParseError::OutOfRange("lat 95".to_string()).into();
+        assert_eq!(error_response(err).status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_set_problem_instance() {
+        let uri: Uri = "/edr/collections/gfs/position?f=CoverageJSON"
+            .parse()
+            .unwrap();
+        let response = error_response(EdrError::InvalidParameter(
+            "Missing required parameter: coords".to_string(),
+        ));
+
+        let response = set_problem_instance(response, instance_uri(&uri)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let json = body_json(response).await;
+        assert_eq!(
+            json["instance"],
+            "/edr/collections/gfs/position?f=CoverageJSON"
+        );
+        assert_eq!(json["detail"], "Missing required parameter: coords");
+
+        // An existing instance is kept
+        let exc = ExceptionResponse::not_found("gone").with_instance("/edr/elsewhere");
+        let response = set_problem_instance(problem_response(exc), "/edr".to_string()).await;
+        assert_eq!(body_json(response).await["instance"], "/edr/elsewhere");
+
+        // Other responses pass through untouched
+        let response = Response::new(Body::from("ok"));
+        let response = set_problem_instance(response, "/edr".to_string()).await;
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"ok");
+    }
+
+    #[test]
+    fn test_grid_error_status() {
+        let status = |err| error_response(grid_error(err)).status();
+        assert_eq!(
+            status(GridProcessorError::NotFound("gfs/TMP".to_string())),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(GridProcessorError::storage_error("connection reset")),
+            StatusCode::BAD_GATEWAY
+        );
+        assert_eq!(
+            status(GridProcessorError::Catalog("pool timed out".to_string())),
+            StatusCode::BAD_GATEWAY
+        );
+        assert_eq!(
+            status(GridProcessorError::ChecksumMismatch(
+                "chunk (1, 0)".to_string()
+            )),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn test_not_found_handler() {
+        let response = not_found_handler("/edr/nowhere".parse().unwrap()).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(body_json(response).await["detail"]
+            .as_str()
+            .unwrap()
+            .contains("/edr/nowhere"));
+    }
+}
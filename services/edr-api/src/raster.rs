@@ -0,0 +1,80 @@
+//! Raster downloads of grid query results.
+//!
+//! Area and cube queries can be answered with an Arc/Info ASCII Grid or a
+//! zipped float32 BIL (see [`edr_protocol::raster`]) instead of JSON.
+
+use axum::{
+    http::{header, StatusCode},
+    response::Response,
+};
+use edr_protocol::{EdrError, RasterExportError, RasterGrid};
+
+use crate::content_negotiation::RasterFormat;
+use crate::problem::error_response;
+
+/// Encode a grid as a raster file download.
+///
+/// `basename` names the downloaded file (and the files inside a BIL archive).
+/// Coverages that can't be written in the requested format are a 400, since
+/// the client can narrow the query (e.g. to a single parameter) or pick
+/// another format.
+pub fn raster_response(
+    grid: Result<RasterGrid, RasterExportError>,
+    format: RasterFormat,
+    basename: &str,
+) -> Response {
+    let body = grid.and_then(|grid| match format {
+        RasterFormat::AsciiGrid => grid.encode_ascii_grid().map(String::into_bytes),
+        RasterFormat::Bil => grid.encode_bil_zip(basename),
+    });
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => return error_response(EdrError::InvalidParameter(e.to_string())),
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}.{}\"",
+                basename,
+                format.extension()
+            ),
+        )
+        .header(header::CACHE_CONTROL, "max-age=300")
+        .body(body.into())
+        .unwrap()
+}
+
+/// File name for a raster download, safe for `Content-Disposition`.
+pub fn raster_basename(collection_id: &str, query: &str) -> String {
+    format!("{}_{}", collection_id, query)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raster_basename() {
+        assert_eq!(raster_basename("hrrr-surface", "area"), "hrrr-surface_area");
+        assert_eq!(raster_basename("a/b\"c", "cube"), "a_b_c_cube");
+    }
+
+    #[test]
+    fn test_raster_response_errors() {
+        let response = raster_response(Err(RasterExportError::Empty), RasterFormat::AsciiGrid, "x");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,322 @@
+//! Shared Redis cache for EDR query responses.
+//!
+//! Dashboards repeat the same query on every refresh. Responses are cached
+//! in the Redis instance wms-api uses for tiles, keyed by the collection,
+//! the model's data version and the canonicalized query. A new ingest
+//! changes the data version and so the key; superseded entries are never
+//! read again and expire with their TTL, which is the collection's
+//! update interval.
+//!
+//! ## Cache Key Structure
+//! `edr:v1:{collection}:{data_version}:{instance}:{query}:{name=value&...}`
+//!
+//! Query values are canonicalized so equivalent requests share an entry:
+//! coordinates are parsed and rounded, parameter names sorted, and the
+//! output format is the negotiated one rather than the raw `f`/Accept.
+
+use axum::{
+    http::{header, StatusCode},
+    response::Response,
+};
+use bytes::Bytes;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+use storage::CachedResponse;
+
+use crate::config::CollectionDefinition;
+
+/// Version of the key format, written as a `v{N}` prefix.
+pub const RESPONSE_CACHE_KEY_VERSION: u32 = 1;
+
+/// Cache key for an EDR query response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseCacheKey {
+    query: &'static str,
+    collection_id: String,
+    data_version: String,
+    instance_id: Option<String>,
+    params: BTreeMap<&'static str, String>,
+}
+
+impl ResponseCacheKey {
+    /// Create a key for a query type (e.g. "position") on a collection.
+    pub fn new(
+        query: &'static str,
+        collection_id: impl Into<String>,
+        data_version: impl Into<String>,
+        instance_id: Option<String>,
+    ) -> Self {
+        Self {
+            query,
+            collection_id: collection_id.into(),
+            data_version: data_version.into(),
+            instance_id,
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// Add a canonicalized query parameter; absent and empty values are skipped.
+    pub fn with(mut self, name: &'static str, value: Option<String>) -> Self {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            self.params.insert(name, value);
+        }
+        self
+    }
+}
+
+impl fmt::Display for ResponseCacheKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "edr:v{}:{}:{}:{}:{}:",
+            RESPONSE_CACHE_KEY_VERSION,
+            self.collection_id,
+            self.data_version,
+            self.instance_id.as_deref().unwrap_or("latest"),
+            self.query
+        )?;
+        for (i, (name, value)) in self.params.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Canonical form of query points: `lon lat` pairs rounded to 1e-6 degrees.
+pub fn canonical_points(points: &[(f64, f64)]) -> String {
+    points
+        .iter()
+        .map(|(lon, lat)| format!("{} {}", round_coord(*lon), round_coord(*lat)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn round_coord(value: f64) -> f64 {
+    // Adding 0.0 turns -0.0 into 0.0
+    (value * 1e6).round() / 1e6 + 0.0
+}
+
+/// Canonical form of a name list: sorted and deduplicated.
+pub fn canonical_names<S: AsRef<str>>(names: &[S]) -> String {
+    let mut names: Vec<&str> = names.iter().map(AsRef::as_ref).collect();
+    names.sort_unstable();
+    names.dedup();
+    names.join(",")
+}
+
+/// Canonical form of vertical levels, in request order.
+pub fn canonical_levels(levels: &[f64]) -> String {
+    levels
+        .iter()
+        .map(|z| z.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Whether a response was served from the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+    /// Caching is disabled or the data version is unknown
+    Bypass,
+}
+
+impl CacheStatus {
+    /// Value of the `X-Cache` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Miss => "MISS",
+            CacheStatus::Bypass => "BYPASS",
+        }
+    }
+}
+
+/// Response cache shared with wms-api through Redis.
+pub struct ResponseCache {
+    redis: Option<storage::ResponseCache>,
+    default_ttl: Duration,
+}
+
+impl ResponseCache {
+    /// Connect using `REDIS_URL`, unless `EDR_RESPONSE_CACHE_ENABLED=false`.
+    ///
+    /// An unreachable Redis disables caching rather than failing startup.
+    pub async fn from_env() -> Self {
+        let enabled = std::env::var("EDR_RESPONSE_CACHE_ENABLED")
+            .map(|v| v.to_lowercase() != "false" && v != "0")
+            .unwrap_or(true);
+        let default_ttl = Duration::from_secs(
+            std::env::var("EDR_RESPONSE_CACHE_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300),
+        );
+
+        if !enabled {
+            return Self::disabled(default_ttl);
+        }
+
+        let redis_url =
+            std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+        match storage::ResponseCache::connect(&redis_url).await {
+            Ok(redis) => {
+                tracing::info!(
+                    default_ttl_secs = default_ttl.as_secs(),
+                    "EDR response cache enabled"
+                );
+                Self {
+                    redis: Some(redis),
+                    default_ttl,
+                }
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Redis unavailable, EDR response cache disabled");
+                Self::disabled(default_ttl)
+            }
+        }
+    }
+
+    /// A cache that stores nothing.
+    pub fn disabled(default_ttl: Duration) -> Self {
+        Self {
+            redis: None,
+            default_ttl,
+        }
+    }
+
+    /// Whether responses are cached.
+    pub fn is_enabled(&self) -> bool {
+        self.redis.is_some()
+    }
+
+    /// How long responses for a collection stay cached.
+    pub fn ttl_for(&self, collection: &CollectionDefinition) -> Duration {
+        collection
+            .update_interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(self.default_ttl)
+    }
+
+    /// Look up a cached response. Redis errors count as misses.
+    pub async fn get(&self, key: &ResponseCacheKey) -> Option<CachedResponse> {
+        let redis = self.redis.as_ref()?;
+        match redis.get(&key.to_string()).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                tracing::warn!(error = %e, "EDR response cache read failed");
+                None
+            }
+        }
+    }
+
+    /// Build a response for freshly computed output, caching it under `key`.
+    ///
+    /// Without a key (caching disabled or data version unknown) the
+    /// response is marked as a bypass.
+    pub async fn respond(
+        &self,
+        key: Option<&ResponseCacheKey>,
+        ttl: Duration,
+        content_type: &str,
+        body: String,
+    ) -> Response {
+        let cached = CachedResponse {
+            content_type: content_type.to_string(),
+            body: Bytes::from(body),
+        };
+
+        let status = match (key, &self.redis) {
+            (Some(key), Some(redis)) => {
+                if let Err(e) = redis.set(&key.to_string(), &cached, ttl).await {
+                    tracing::warn!(error = %e, "EDR response cache write failed");
+                }
+                CacheStatus::Miss
+            }
+            _ => CacheStatus::Bypass,
+        };
+
+        cache_response(cached, status, ttl)
+    }
+}
+
+/// Build a 200 response with cache status headers.
+pub fn cache_response(cached: CachedResponse, status: CacheStatus, ttl: Duration) -> Response {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, cached.content_type)
+        .header(header::CACHE_CONTROL, format!("max-age={}", ttl.as_secs()))
+        .header("X-Cache", status.as_str())
+        .body(cached.body.into())
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_format() {
+        let key = ResponseCacheKey::new("position", "hrrr-height-agl", "1735473600000-412", None)
+            .with("parameter-name", Some("TMP,UGRD".to_string()))
+            .with("coords", Some("-97.5 35.2".to_string()))
+            .with("z", None)
+            .with("datetime", Some(String::new()));
+
+        assert_eq!(
+            key.to_string(),
+            "edr:v1:hrrr-height-agl:1735473600000-412:latest:position:\
+             coords=-97.5 35.2&parameter-name=TMP,UGRD"
+        );
+    }
+
+    #[test]
+    fn test_equivalent_queries_share_a_key() {
+        let key = |points: &[(f64, f64)], params: &[&str]| {
+            ResponseCacheKey::new(
+                "position",
+                "gfs",
+                "1-1",
+                Some("2024-12-29T12:00:00Z".into()),
+            )
+            .with("coords", Some(canonical_points(points)))
+            .with("parameter-name", Some(canonical_names(params)))
+            .to_string()
+        };
+
+        assert_eq!(
+            key(&[(-97.5, 35.2)], &["UGRD", "TMP"]),
+            key(&[(-97.500_000_01, 35.2)], &["TMP", "UGRD", "TMP"])
+        );
+        assert_ne!(
+            key(&[(-97.5, 35.2)], &["TMP"]),
+            key(&[(-97.5, 35.3)], &["TMP"])
+        );
+    }
+
+    #[test]
+    fn test_canonical_values() {
+        assert_eq!(
+            canonical_points(&[(-0.0, 1.5), (10.0, -20.25)]),
+            "0 1.5,10 -20.25"
+        );
+        assert_eq!(canonical_levels(&[850.0, 500.0, 2.5]), "850,500,2.5");
+    }
+
+    #[test]
+    fn test_ttl_follows_collection_update_interval() {
+        let cache = ResponseCache::disabled(Duration::from_secs(300));
+        let mut collection: CollectionDefinition =
+            serde_yaml::from_str("id: goes18-infrared").unwrap();
+        assert_eq!(cache.ttl_for(&collection), Duration::from_secs(300));
+
+        collection.update_interval_secs = Some(60);
+        assert_eq!(cache.ttl_for(&collection), Duration::from_secs(60));
+        assert!(!cache.is_enabled());
+    }
+}
@@ -1,14 +1,20 @@
 //! Application state for the EDR API.
 
 use anyhow::Result;
+use axum::http::HeaderMap;
+use std::borrow::Cow;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use grid_processor::{GridDataService, MinioConfig};
 use storage::Catalog;
+use wms_common::api_key::{ApiKey, API_KEY_HEADER};
+use wms_common::{HourlyBudgetTracker, Tenant, TenantRegistry, TenantUsage};
 
 use crate::config::EdrConfig;
+use crate::features::FeatureRegistry;
 use crate::location_cache::LocationCache;
+use crate::response_cache::ResponseCache;
 
 /// Shared application state.
 pub struct AppState {
@@ -26,6 +32,21 @@ pub struct AppState {
 
     /// Cache for location query responses.
     pub location_cache: Arc<LocationCache>,
+
+    /// Redis cache for query responses, shared with wms-api.
+    pub response_cache: ResponseCache,
+
+    /// Query cost consumed per API key in the current hour.
+    pub cost_budget: Arc<HourlyBudgetTracker>,
+
+    /// Enabled query types and output encoders.
+    pub features: FeatureRegistry,
+
+    /// API key -> tenant -> visible collections and parameters.
+    pub tenants: TenantRegistry,
+
+    /// Requests per tenant.
+    pub tenant_usage: TenantUsage,
 }
 
 impl AppState {
@@ -39,7 +60,6 @@ impl AppState {
         // Get S3/MinIO configuration
         let s3_endpoint =
             std::env::var("S3_ENDPOINT").unwrap_or_else(|_| "http://localhost:9000".to_string());
-        let s3_bucket = std::env::var("S3_BUCKET").unwrap_or_else(|_| "weather-data".to_string());
         let s3_access_key =
             std::env::var("S3_ACCESS_KEY").unwrap_or_else(|_| "minioadmin".to_string());
         let s3_secret_key =
@@ -52,14 +72,15 @@ impl AppState {
         // Create catalog
         let catalog = Arc::new(Catalog::connect(&database_url).await?);
 
-        // Create MinIO config
+        // Create MinIO config; the backend (S3, GCS or Azure) and bucket
+        // come from the environment
         let minio_config = MinioConfig {
             endpoint: s3_endpoint,
-            bucket: s3_bucket,
             access_key_id: s3_access_key,
             secret_access_key: s3_secret_key,
             region: "us-east-1".to_string(),
             allow_http: true,
+            ..MinioConfig::from_env()
         };
 
         // Get chunk cache size from environment
@@ -89,15 +110,57 @@ impl AppState {
 
         let location_cache = Arc::new(LocationCache::new(location_cache_mb, location_cache_ttl));
 
+        let response_cache = ResponseCache::from_env().await;
+
+        // Tenants are shared with wms-api (config/tenants.yaml)
+        let config_dir = std::env::var("CONFIG_DIR").unwrap_or_else(|_| "config".to_string());
+        let tenants = TenantRegistry::from_env(&config_dir).map_err(anyhow::Error::msg)?;
+        tracing::info!("Loaded {} tenants", tenants.tenants().len());
+
         Ok(Self {
             catalog,
             grid_data_service,
             edr_config: Arc::new(RwLock::new(edr_config)),
             base_url,
             location_cache,
+            response_cache,
+            cost_budget: Arc::new(HourlyBudgetTracker::new()),
+            features: FeatureRegistry::from_env(),
+            tenants,
+            tenant_usage: TenantUsage::new(),
         })
     }
 
+    /// Tenant of the caller identified by the `x-api-key` header, or None if
+    /// it is unrestricted.
+    pub fn request_tenant(&self, headers: &HeaderMap) -> Option<&Tenant> {
+        let key = ApiKey::resolve(
+            headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()),
+            None,
+        );
+        self.tenants.resolve(&key)
+    }
+
+    /// The EDR configuration as the caller sees it: only its tenant's
+    /// collections and parameters, if it has a tenant. Also counts the
+    /// request (e.g. `request` = "position") against the tenant.
+    pub fn tenant_config<'a>(
+        &self,
+        config: &'a EdrConfig,
+        headers: &HeaderMap,
+        request: &str,
+    ) -> Cow<'a, EdrConfig> {
+        match self.request_tenant(headers) {
+            Some(tenant) => {
+                self.tenant_usage.record(tenant, "edr", request);
+                Cow::Owned(
+                    config.scoped(|model, parameter| tenant.allows_parameter(model, parameter)),
+                )
+            }
+            None => Cow::Borrowed(config),
+        }
+    }
+
     /// Reload EDR configuration from disk.
     pub async fn reload_config(&self) -> Result<()> {
         let new_config = EdrConfig::load_from_dir("config/edr")?;
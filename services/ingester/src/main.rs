@@ -1,7 +1,8 @@
 //! Weather data ingester service.
 //!
 //! HTTP-triggered ingestion service that receives ingestion requests from the downloader
-//! and processes GRIB2/NetCDF files into Zarr format.
+//! and processes GRIB2/NetCDF files into Zarr format. Requests wait in a bounded priority
+//! queue drained by a fixed number of workers.
 //!
 //! # Usage
 //!
@@ -14,19 +15,29 @@
 //! ```bash
 //! ingester --test-file /path/to/data.grib2 --test-model gfs
 //! ```
+//!
+//! ## Synthetic data (demos, CI)
+//! ```bash
+//! ingester --synthetic
+//! ```
+//! Generates the latest run of the synthetic model (`config/models/synthetic.yaml`)
+//! at startup, then serves requests as usual.
 
+mod queue;
 mod server;
 
 use anyhow::Result;
+use chrono::Utc;
 use clap::Parser;
 use std::sync::Arc;
-use tracing::{info, Level};
+use tracing::{error, info, Level};
 use tracing_subscriber::FmtSubscriber;
 
-use ingestion::{IngestOptions, Ingester};
+use ingestion::{IngestOptions, Ingester, SyntheticModel, SYNTHETIC_MODEL};
 use std::env;
-use storage::{Catalog, ObjectStorage, ObjectStorageConfig};
+use storage::{Catalog, CircuitBreakerConfig, ObjectStorage, ObjectStorageConfig, RetryPolicy};
 
+use queue::IngestQueue;
 use server::{start_server, IngestionTracker, ServerState};
 
 #[derive(Parser, Debug)]
@@ -37,6 +48,14 @@ struct Args {
     #[arg(short, long, default_value = "8082")]
     port: u16,
 
+    /// Files ingested in parallel
+    #[arg(long, default_value = "2", env = "INGEST_WORKERS")]
+    workers: usize,
+
+    /// Most ingestion jobs that can wait; further submissions get 429
+    #[arg(long, default_value = "64", env = "INGEST_QUEUE_CAPACITY")]
+    queue_capacity: usize,
+
     /// Test with local file (bypasses HTTP server)
     #[arg(long)]
     test_file: Option<String>,
@@ -49,6 +68,14 @@ struct Args {
     #[arg(long)]
     forecast_hour: Option<u32>,
 
+    /// Generate the latest run of a synthetic model at startup
+    #[arg(long, env = "INGEST_SYNTHETIC")]
+    synthetic: bool,
+
+    /// Synthetic model to generate
+    #[arg(long, default_value = SYNTHETIC_MODEL, env = "INGEST_SYNTHETIC_MODEL")]
+    synthetic_model: String,
+
     /// Log level
     #[arg(long, default_value = "info")]
     log_level: String,
@@ -89,6 +116,9 @@ async fn main() -> Result<()> {
         allow_http: env::var("S3_ALLOW_HTTP")
             .map(|v| v == "true" || v == "1")
             .unwrap_or(true),
+        public_endpoint: None,
+        retry: RetryPolicy::from_env(),
+        circuit_breaker: CircuitBreakerConfig::from_env(),
     };
     let storage = Arc::new(ObjectStorage::new(&storage_config)?);
 
@@ -109,8 +139,13 @@ async fn main() -> Result<()> {
     let state = Arc::new(ServerState {
         ingester,
         tracker: IngestionTracker::new(),
+        queue: IngestQueue::new(args.queue_capacity, args.workers),
     });
 
+    if args.synthetic {
+        tokio::spawn(generate_synthetic(state.clone(), args.synthetic_model));
+    }
+
     // Start HTTP server
     info!(port = args.port, "Starting HTTP server");
     start_server(state, args.port).await?;
@@ -118,6 +153,32 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Generate the latest run of a synthetic model (demo/CI mode).
+async fn generate_synthetic(state: Arc<ServerState>, model: String) {
+    let reference_time = match SyntheticModel::load(&model) {
+        Ok(config) => config.latest_cycle(Utc::now()),
+        Err(e) => {
+            error!(model = %model, error = %e, "Cannot load synthetic model config");
+            return;
+        }
+    };
+
+    match state
+        .ingester
+        .ingest_synthetic(&model, reference_time)
+        .await
+    {
+        Ok(result) => info!(
+            model = %result.model,
+            reference_time = %result.reference_time,
+            datasets = result.datasets_registered,
+            parameters = ?result.parameters,
+            "Generated synthetic model run"
+        ),
+        Err(e) => error!(model = %model, error = %e, "Synthetic model generation failed"),
+    }
+}
+
 /// Run test file ingestion (development mode).
 async fn run_test_file(
     ingester: Ingester,
@@ -135,6 +196,7 @@ async fn run_test_file(
     let options = IngestOptions {
         model,
         forecast_hour,
+        ..Default::default()
     };
 
     let result = ingester.ingest_file(test_file, options).await?;
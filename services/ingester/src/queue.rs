@@ -0,0 +1,452 @@
+//! Bounded priority queue of ingestion jobs.
+//!
+//! Every request to `/ingest` or `/ingest/batch` becomes a job in this
+//! queue. A fixed pool of workers (see `server::spawn_workers`) takes the
+//! highest priority job first, oldest first within a priority. Submissions
+//! that don't fit are rejected as a whole, which the HTTP layer reports as
+//! `429 Too Many Requests` so callers can slow down.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{oneshot, Notify};
+use uuid::Uuid;
+
+use crate::server::{IngestRequest, IngestResponse};
+
+/// Finished jobs kept for `/jobs/:id` lookups.
+const MAX_FINISHED_JOBS: usize = 1000;
+
+/// Scheduling priority of a job.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// Backfill and re-ingestion
+    Low,
+    #[default]
+    Normal,
+    /// Latest observations, which are stale within minutes
+    High,
+}
+
+/// Lifecycle state of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// Status of a job, as returned by `GET /jobs/:id`.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub id: String,
+    pub file_path: String,
+    pub priority: Priority,
+    pub state: JobState,
+    /// Number of queued jobs that will run before this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    pub submitted_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Outcome of a finished job
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<IngestResponse>,
+}
+
+/// Queue occupancy, reported with every submission and by `GET /queue`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueueStats {
+    /// Jobs waiting for a worker
+    pub queued: usize,
+    /// Jobs being ingested
+    pub running: usize,
+    /// Most jobs that can wait at once
+    pub capacity: usize,
+    /// Number of workers ingesting in parallel
+    pub workers: usize,
+}
+
+/// A submission was rejected because the queue has no room for it.
+#[derive(Debug, Clone)]
+pub struct QueueFull {
+    /// Jobs the submission asked for
+    pub requested: usize,
+    pub stats: QueueStats,
+}
+
+impl fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Ingestion queue is full ({} of {} slots used, {} requested); retry later",
+            self.stats.queued, self.stats.capacity, self.requested
+        )
+    }
+}
+
+/// A job taken from the queue by a worker.
+pub struct Job {
+    pub id: String,
+    pub request: IngestRequest,
+    /// Notified when the job finishes (synchronous `/ingest` calls)
+    done: Option<oneshot::Sender<IngestResponse>>,
+}
+
+struct QueuedJob {
+    priority: Priority,
+    seq: u64,
+    job: Job,
+}
+
+impl QueuedJob {
+    /// Whether this job runs before `other`.
+    fn runs_before(&self, other: &QueuedJob) -> bool {
+        self.cmp(other) == Ordering::Greater
+    }
+}
+
+impl Ord for QueuedJob {
+    // BinaryHeap pops the greatest job: highest priority, then lowest seq
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedJob {}
+
+#[derive(Default)]
+struct QueueState {
+    pending: BinaryHeap<QueuedJob>,
+    jobs: HashMap<String, JobStatus>,
+    /// Finished job ids, oldest first
+    finished: VecDeque<String>,
+    running: usize,
+    next_seq: u64,
+}
+
+/// Bounded priority queue of ingestion jobs, shared by the HTTP handlers
+/// and the workers.
+pub struct IngestQueue {
+    state: Mutex<QueueState>,
+    available: Notify,
+    capacity: usize,
+    workers: usize,
+}
+
+impl IngestQueue {
+    /// Create a queue holding up to `capacity` waiting jobs, drained by
+    /// `workers` workers.
+    pub fn new(capacity: usize, workers: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState::default()),
+            available: Notify::new(),
+            capacity: capacity.max(1),
+            workers: workers.max(1),
+        }
+    }
+
+    /// Number of workers that should drain this queue.
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Queue a batch of requests, all or none. Returns the job ids in
+    /// request order.
+    pub fn submit(&self, requests: Vec<IngestRequest>) -> Result<Vec<String>, QueueFull> {
+        self.push(requests.into_iter().map(|r| (r, None)).collect())
+    }
+
+    /// Queue a single request and get notified when it finishes.
+    pub fn submit_and_wait(
+        &self,
+        request: IngestRequest,
+    ) -> Result<(String, oneshot::Receiver<IngestResponse>), QueueFull> {
+        let (tx, rx) = oneshot::channel();
+        let mut ids = self.push(vec![(request, Some(tx))])?;
+        Ok((ids.remove(0), rx))
+    }
+
+    fn push(
+        &self,
+        requests: Vec<(IngestRequest, Option<oneshot::Sender<IngestResponse>>)>,
+    ) -> Result<Vec<String>, QueueFull> {
+        let mut state = self.state.lock().unwrap();
+        if state.pending.len() + requests.len() > self.capacity {
+            return Err(QueueFull {
+                requested: requests.len(),
+                stats: self.stats_locked(&state),
+            });
+        }
+
+        let now = Utc::now();
+        let mut ids = Vec::with_capacity(requests.len());
+        for (request, done) in requests {
+            let id = Uuid::new_v4().to_string();
+            let priority = request.priority.unwrap_or_default();
+            state.jobs.insert(
+                id.clone(),
+                JobStatus {
+                    id: id.clone(),
+                    file_path: request.file_path.clone(),
+                    priority,
+                    state: JobState::Queued,
+                    queue_position: None,
+                    submitted_at: now,
+                    started_at: None,
+                    completed_at: None,
+                    result: None,
+                },
+            );
+
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.pending.push(QueuedJob {
+                priority,
+                seq,
+                job: Job {
+                    id: id.clone(),
+                    request,
+                    done,
+                },
+            });
+            ids.push(id);
+        }
+        drop(state);
+
+        for _ in 0..ids.len() {
+            self.available.notify_one();
+        }
+        Ok(ids)
+    }
+
+    /// Take the next job, waiting until one is queued.
+    pub async fn next(&self) -> Job {
+        loop {
+            if let Some(job) = self.try_next() {
+                return job;
+            }
+            self.available.notified().await;
+        }
+    }
+
+    fn try_next(&self) -> Option<Job> {
+        let mut state = self.state.lock().unwrap();
+        let queued = state.pending.pop()?;
+        state.running += 1;
+        if let Some(status) = state.jobs.get_mut(&queued.job.id) {
+            status.state = JobState::Running;
+            status.started_at = Some(Utc::now());
+        }
+        Some(queued.job)
+    }
+
+    /// Record the outcome of a job taken with [`next`](Self::next).
+    pub fn finish(&self, job: Job, response: IngestResponse) {
+        let mut state = self.state.lock().unwrap();
+        state.running = state.running.saturating_sub(1);
+        if let Some(status) = state.jobs.get_mut(&job.id) {
+            status.state = if response.success {
+                JobState::Succeeded
+            } else {
+                JobState::Failed
+            };
+            status.completed_at = Some(Utc::now());
+            status.result = Some(response.clone());
+        }
+
+        state.finished.push_back(job.id);
+        while state.finished.len() > MAX_FINISHED_JOBS {
+            if let Some(id) = state.finished.pop_front() {
+                state.jobs.remove(&id);
+            }
+        }
+        drop(state);
+
+        if let Some(done) = job.done {
+            // The caller may have disconnected; the status is still recorded
+            let _ = done.send(response);
+        }
+    }
+
+    /// Status of a job, if it is queued, running or recently finished.
+    pub fn status(&self, id: &str) -> Option<JobStatus> {
+        let state = self.state.lock().unwrap();
+        let mut status = state.jobs.get(id)?.clone();
+        if status.state == JobState::Queued {
+            let queued = state.pending.iter().find(|q| q.job.id == id)?;
+            status.queue_position = Some(
+                state
+                    .pending
+                    .iter()
+                    .filter(|q| q.runs_before(queued))
+                    .count(),
+            );
+        }
+        Some(status)
+    }
+
+    /// Current queue occupancy.
+    pub fn stats(&self) -> QueueStats {
+        self.stats_locked(&self.state.lock().unwrap())
+    }
+
+    fn stats_locked(&self, state: &QueueState) -> QueueStats {
+        QueueStats {
+            queued: state.pending.len(),
+            running: state.running,
+            capacity: self.capacity,
+            workers: self.workers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(file_path: &str, priority: Option<Priority>) -> IngestRequest {
+        IngestRequest {
+            file_path: file_path.to_string(),
+            source_url: None,
+            model: None,
+            forecast_hour: None,
+            priority,
+        }
+    }
+
+    fn response(success: bool) -> IngestResponse {
+        IngestResponse {
+            success,
+            message: String::new(),
+            datasets_registered: 0,
+            model: None,
+            reference_time: None,
+            parameters: vec![],
+            storage_paths: vec![],
+            job_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_priority_order() {
+        let queue = IngestQueue::new(10, 1);
+        queue
+            .submit(vec![
+                request("low.grib2", Some(Priority::Low)),
+                request("first.grib2", None),
+                request("high.grib2", Some(Priority::High)),
+                request("second.grib2", Some(Priority::Normal)),
+            ])
+            .unwrap();
+
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            let job = queue.next().await;
+            order.push(job.request.file_path.clone());
+            queue.finish(job, response(true));
+        }
+        assert_eq!(
+            order,
+            ["high.grib2", "first.grib2", "second.grib2", "low.grib2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_rejects_whole_batch() {
+        let queue = IngestQueue::new(3, 2);
+        queue.submit(vec![request("a.grib2", None)]).unwrap();
+
+        let full = queue
+            .submit(vec![
+                request("b.grib2", None),
+                request("c.grib2", None),
+                request("d.grib2", None),
+            ])
+            .unwrap_err();
+        assert_eq!(full.requested, 3);
+        assert_eq!(full.stats.queued, 1);
+        assert_eq!(queue.stats().queued, 1, "rejected batch must not be queued");
+
+        // Taking a job frees its slot
+        let job = queue.next().await;
+        assert_eq!(
+            queue.stats(),
+            QueueStats {
+                queued: 0,
+                running: 1,
+                capacity: 3,
+                workers: 2
+            }
+        );
+        queue.finish(job, response(true));
+        assert!(queue
+            .submit(vec![
+                request("b.grib2", None),
+                request("c.grib2", None),
+                request("d.grib2", None),
+            ])
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_job_status_lifecycle() {
+        let queue = IngestQueue::new(10, 1);
+        let ids = queue
+            .submit(vec![request("a.grib2", None), request("b.grib2", None)])
+            .unwrap();
+
+        let status = queue.status(&ids[1]).unwrap();
+        assert_eq!(status.state, JobState::Queued);
+        assert_eq!(status.queue_position, Some(1));
+
+        let job = queue.next().await;
+        assert_eq!(queue.status(&ids[0]).unwrap().state, JobState::Running);
+        assert_eq!(queue.status(&ids[1]).unwrap().queue_position, Some(0));
+
+        queue.finish(job, response(false));
+        let status = queue.status(&ids[0]).unwrap();
+        assert_eq!(status.state, JobState::Failed);
+        assert!(status.completed_at.is_some());
+        assert!(status.result.is_some());
+
+        assert!(queue.status("unknown").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_submit_and_wait() {
+        let queue = IngestQueue::new(10, 1);
+        let (id, done) = queue.submit_and_wait(request("a.grib2", None)).unwrap();
+
+        let job = queue.next().await;
+        assert_eq!(job.id, id);
+        queue.finish(job, response(true));
+
+        assert!(done.await.unwrap().success);
+    }
+}
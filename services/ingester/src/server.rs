@@ -1,15 +1,21 @@
 //! HTTP server for the ingester service.
 //!
 //! Provides endpoints for:
-//! - `POST /ingest` - Ingest a file (called by downloader)
+//! - `POST /ingest` - Ingest a file and wait for the result (called by downloader)
+//! - `POST /ingest/batch` - Queue several files without waiting
+//! - `GET /jobs/:id` - Status of a queued, running or finished job
+//! - `GET /queue` - Queue occupancy
 //! - `GET /status` - Get active/recent ingestions
 //! - `GET /health` - Health check
 //! - `GET /metrics` - Prometheus metrics
+//!
+//! All ingestions go through the bounded [`IngestQueue`]; when it is full,
+//! submissions get `429 Too Many Requests` with a `Retry-After` header.
 
 use axum::{
-    extract::{Extension, Json},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{Extension, Json, Path},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Router,
 };
@@ -19,10 +25,14 @@ use std::collections::VecDeque;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::{error, info};
-use uuid::Uuid;
+use tracing::{error, info, warn};
+
+use ingestion::{upload_metrics, IngestOptions, Ingester, IngestionResult};
 
-use ingestion::{IngestOptions, Ingester, IngestionResult};
+use crate::queue::{IngestQueue, Priority, QueueFull, QueueStats};
+
+/// Seconds a client should wait before retrying a rejected submission.
+const RETRY_AFTER_SECS: u64 = 30;
 
 /// Shared state for the HTTP server.
 pub struct ServerState {
@@ -30,6 +40,8 @@ pub struct ServerState {
     pub ingester: Ingester,
     /// Tracking for active/completed ingestions
     pub tracker: IngestionTracker,
+    /// Jobs waiting for a worker
+    pub queue: IngestQueue,
 }
 
 /// Request body for /ingest endpoint.
@@ -37,9 +49,8 @@ pub struct ServerState {
 pub struct IngestRequest {
     /// Path to the file to ingest
     pub file_path: String,
-    /// Source URL (for logging/tracking)
+    /// Source URL, recorded in the lineage of the ingested datasets
     #[serde(default)]
-    #[allow(dead_code)]
     pub source_url: Option<String>,
     /// Override model detection
     #[serde(default)]
@@ -47,10 +58,22 @@ pub struct IngestRequest {
     /// Override forecast hour detection
     #[serde(default)]
     pub forecast_hour: Option<u32>,
+    /// Scheduling priority (default: normal)
+    #[serde(default)]
+    pub priority: Option<Priority>,
+}
+
+/// Request body for /ingest/batch endpoint.
+#[derive(Debug, Deserialize)]
+pub struct BatchIngestRequest {
+    pub files: Vec<IngestRequest>,
+    /// Priority of files that don't set their own
+    #[serde(default)]
+    pub priority: Option<Priority>,
 }
 
 /// Response body for /ingest endpoint.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct IngestResponse {
     pub success: bool,
     pub message: String,
@@ -58,6 +81,43 @@ pub struct IngestResponse {
     pub model: Option<String>,
     pub reference_time: Option<String>,
     pub parameters: Vec<String>,
+    /// Zarr storage paths written by the ingestion (for cache invalidation)
+    pub storage_paths: Vec<String>,
+    /// Queue job that ran the ingestion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+}
+
+impl IngestResponse {
+    /// Response for an ingestion that did not produce any datasets.
+    fn failure(message: String) -> Self {
+        Self {
+            success: false,
+            message,
+            datasets_registered: 0,
+            model: None,
+            reference_time: None,
+            parameters: vec![],
+            storage_paths: vec![],
+            job_id: None,
+        }
+    }
+}
+
+/// Response body for /ingest/batch endpoint.
+#[derive(Debug, Serialize)]
+pub struct BatchIngestResponse {
+    /// Job ids, in request order
+    pub jobs: Vec<String>,
+    pub queue: QueueStats,
+}
+
+/// Response body when the queue rejects a submission.
+#[derive(Debug, Serialize)]
+pub struct QueueFullResponse {
+    pub success: bool,
+    pub message: String,
+    pub queue: QueueStats,
 }
 
 impl From<IngestionResult> for IngestResponse {
@@ -69,6 +129,8 @@ impl From<IngestionResult> for IngestResponse {
             model: Some(result.model),
             reference_time: Some(result.reference_time.to_rfc3339()),
             parameters: result.parameters,
+            storage_paths: result.storage_paths,
+            job_id: None,
         }
     }
 }
@@ -165,6 +227,7 @@ impl IngestionTracker {
             active: active.values().cloned().collect(),
             recent: completed.iter().take(20).cloned().collect(),
             total_completed: completed.len(),
+            queue: None,
         }
     }
 }
@@ -175,6 +238,9 @@ pub struct StatusResponse {
     pub active: Vec<ActiveIngestion>,
     pub recent: Vec<CompletedIngestion>,
     pub total_completed: usize,
+    /// Queue occupancy (filled in by the handler)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue: Option<QueueStats>,
 }
 
 /// Health check response.
@@ -186,30 +252,159 @@ pub struct HealthResponse {
 }
 
 /// POST /ingest - Ingest a file
+///
+/// Queues the file and waits for a worker to ingest it.
 async fn ingest_handler(
     Extension(state): Extension<Arc<ServerState>>,
     Json(request): Json<IngestRequest>,
-) -> impl IntoResponse {
-    let id = Uuid::new_v4().to_string();
+) -> Response {
+    info!(
+        file_path = %request.file_path,
+        model = ?request.model,
+        priority = ?request.priority,
+        "Received ingest request"
+    );
+
+    let (id, done) = match state.queue.submit_and_wait(request) {
+        Ok(submitted) => submitted,
+        Err(full) => return queue_full_response(full),
+    };
+
+    match done.await {
+        Ok(response) => {
+            let status = if response.success {
+                StatusCode::OK
+            } else {
+                StatusCode::INTERNAL_SERVER_ERROR
+            };
+            (status, Json(response)).into_response()
+        }
+        Err(_) => {
+            error!(id = %id, "Ingestion job was dropped before finishing");
+            let mut response = IngestResponse::failure("Ingestion job was dropped".to_string());
+            response.job_id = Some(id);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response()
+        }
+    }
+}
+
+/// POST /ingest/batch - Queue several files
+///
+/// Returns as soon as the files are queued; poll `/jobs/:id` for results.
+async fn batch_ingest_handler(
+    Extension(state): Extension<Arc<ServerState>>,
+    Json(batch): Json<BatchIngestRequest>,
+) -> Response {
+    if batch.files.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(IngestResponse::failure(
+                "Batch contains no files".to_string(),
+            )),
+        )
+            .into_response();
+    }
 
+    info!(
+        files = batch.files.len(),
+        priority = ?batch.priority,
+        "Received batch ingest request"
+    );
+
+    let files = batch
+        .files
+        .into_iter()
+        .map(|mut file| {
+            file.priority = file.priority.or(batch.priority);
+            file
+        })
+        .collect();
+
+    match state.queue.submit(files) {
+        Ok(jobs) => (
+            StatusCode::ACCEPTED,
+            Json(BatchIngestResponse {
+                jobs,
+                queue: state.queue.stats(),
+            }),
+        )
+            .into_response(),
+        Err(full) => queue_full_response(full),
+    }
+}
+
+/// 429 response for a submission the queue has no room for.
+fn queue_full_response(full: QueueFull) -> Response {
+    warn!(
+        requested = full.requested,
+        queued = full.stats.queued,
+        capacity = full.stats.capacity,
+        "Ingestion queue full, rejecting submission"
+    );
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+        Json(QueueFullResponse {
+            success: false,
+            message: full.to_string(),
+            queue: full.stats,
+        }),
+    )
+        .into_response()
+}
+
+/// GET /jobs/:id - Status of a job
+async fn job_status_handler(
+    Extension(state): Extension<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Response {
+    match state.queue.status(&id) {
+        Some(status) => Json(status).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("Unknown job '{}'", id)).into_response(),
+    }
+}
+
+/// GET /queue - Queue occupancy
+async fn queue_handler(Extension(state): Extension<Arc<ServerState>>) -> impl IntoResponse {
+    Json(state.queue.stats())
+}
+
+/// Start the workers that drain the ingestion queue.
+pub fn spawn_workers(state: Arc<ServerState>) {
+    for worker in 0..state.queue.workers() {
+        let state = state.clone();
+        tokio::spawn(async move {
+            loop {
+                let job = state.queue.next().await;
+                let response = run_job(&state, &job.id, &job.request).await;
+                state.queue.finish(job, response);
+            }
+        });
+        info!(worker = worker, "Started ingestion worker");
+    }
+}
+
+/// Ingest one queued file.
+async fn run_job(state: &ServerState, id: &str, request: &IngestRequest) -> IngestResponse {
     info!(
         id = %id,
         file_path = %request.file_path,
         model = ?request.model,
-        "Received ingest request"
+        "Starting ingestion job"
     );
 
     // Track start
-    state.tracker.start(&id, &request.file_path).await;
+    state.tracker.start(id, &request.file_path).await;
 
     // Build options
     let options = IngestOptions {
-        model: request.model,
+        model: request.model.clone(),
         forecast_hour: request.forecast_hour,
+        source_url: request.source_url.clone(),
     };
 
     // Perform ingestion
-    match state
+    let mut response = match state
         .ingester
         .ingest_file(&request.file_path, options)
         .await
@@ -225,7 +420,7 @@ async fn ingest_handler(
             state
                 .tracker
                 .complete(
-                    &id,
+                    id,
                     true,
                     result.datasets_registered,
                     result.parameters.clone(),
@@ -233,33 +428,27 @@ async fn ingest_handler(
                 )
                 .await;
 
-            (StatusCode::OK, Json(IngestResponse::from(result)))
+            IngestResponse::from(result)
         }
         Err(e) => {
             error!(id = %id, error = %e, "Ingestion failed");
 
             state
                 .tracker
-                .complete(&id, false, 0, vec![], Some(e.to_string()))
+                .complete(id, false, 0, vec![], Some(e.to_string()))
                 .await;
 
-            let response = IngestResponse {
-                success: false,
-                message: format!("Ingestion failed: {}", e),
-                datasets_registered: 0,
-                model: None,
-                reference_time: None,
-                parameters: vec![],
-            };
-
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(response))
+            IngestResponse::failure(format!("Ingestion failed: {}", e))
         }
-    }
+    };
+    response.job_id = Some(id.to_string());
+    response
 }
 
 /// GET /status - Get ingestion status
 async fn status_handler(Extension(state): Extension<Arc<ServerState>>) -> impl IntoResponse {
-    let status = state.tracker.get_status().await;
+    let mut status = state.tracker.get_status().await;
+    status.queue = Some(state.queue.stats());
     Json(status)
 }
 
@@ -273,18 +462,38 @@ async fn health_handler() -> impl IntoResponse {
 }
 
 /// GET /metrics - Prometheus metrics
-async fn metrics_handler() -> impl IntoResponse {
-    // For now, return a simple placeholder
-    // TODO: Integrate with actual metrics collection
-    "# HELP ingester_info Ingester service information\n\
-     # TYPE ingester_info gauge\n\
-     ingester_info{version=\"0.1.0\"} 1\n"
+async fn metrics_handler(Extension(state): Extension<Arc<ServerState>>) -> impl IntoResponse {
+    let queue = state.queue.stats();
+    format!(
+        "# HELP ingester_info Ingester service information\n\
+         # TYPE ingester_info gauge\n\
+         ingester_info{{version=\"0.1.0\"}} 1\n\
+         # HELP ingester_queue_jobs Ingestion jobs by state\n\
+         # TYPE ingester_queue_jobs gauge\n\
+         ingester_queue_jobs{{state=\"queued\"}} {}\n\
+         ingester_queue_jobs{{state=\"running\"}} {}\n\
+         # HELP ingester_queue_capacity Most jobs that can wait in the queue\n\
+         # TYPE ingester_queue_capacity gauge\n\
+         ingester_queue_capacity {}\n{}{}",
+        queue.queued,
+        queue.running,
+        queue.capacity,
+        upload_metrics().to_prometheus(),
+        state
+            .ingester
+            .storage()
+            .resilience_metrics()
+            .to_prometheus()
+    )
 }
 
 /// Build the HTTP router.
 pub fn build_router(state: Arc<ServerState>) -> Router {
     Router::new()
         .route("/ingest", post(ingest_handler))
+        .route("/ingest/batch", post(batch_ingest_handler))
+        .route("/jobs/:id", get(job_status_handler))
+        .route("/queue", get(queue_handler))
         .route("/status", get(status_handler))
         .route("/health", get(health_handler))
         .route("/metrics", get(metrics_handler))
@@ -293,6 +502,7 @@ pub fn build_router(state: Arc<ServerState>) -> Router {
 
 /// Start the HTTP server.
 pub async fn start_server(state: Arc<ServerState>, port: u16) -> anyhow::Result<()> {
+    spawn_workers(state.clone());
     let app = build_router(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
@@ -7,7 +7,7 @@
 
 use axum::{
     extract::{Extension, Path, Query},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Json},
 };
 use chrono::Utc;
@@ -15,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
+use crate::config_store::{self, ApplyError, ConfigKind};
 use crate::state::AppState;
 
 // ============================================================================
@@ -111,6 +112,9 @@ pub struct DatasetInfoResponse {
     pub valid_time: String,
     pub storage_path: String,
     pub file_size: u64,
+    /// Source file the dataset was ingested from, if recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lineage: Option<storage::SourceLineage>,
 }
 
 // Storage tree response types
@@ -270,7 +274,13 @@ pub struct ShredPreviewQuery {
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateConfigRequest {
+    /// File content: YAML for models and layers, JSON for styles
     pub yaml: String,
+    /// Validate without saving
+    #[serde(default)]
+    pub validate_only: bool,
+    /// Note stored with the new version
+    pub comment: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -278,6 +288,51 @@ pub struct UpdateConfigResponse {
     pub success: bool,
     pub message: String,
     pub validation_errors: Vec<String>,
+    /// Version recorded for the saved content
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigVersionSummary {
+    pub version: i32,
+    pub comment: Option<String>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub size_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigVersionsResponse {
+    pub kind: ConfigKind,
+    pub id: String,
+    pub versions: Vec<ConfigVersionSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigDiffQuery {
+    /// Version to diff from
+    pub from: i32,
+    /// Version to diff to (default: the current file)
+    pub to: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigDiffResponse {
+    pub kind: ConfigKind,
+    pub id: String,
+    pub from: i32,
+    /// Target version, or None for the current file
+    pub to: Option<i32>,
+    /// Unified diff
+    pub diff: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigRollbackRequest {
+    /// Version whose content becomes current again
+    pub version: i32,
+    /// Note stored with the new version (default: "Rollback to version N")
+    pub comment: Option<String>,
 }
 
 // ============================================================================
@@ -297,6 +352,9 @@ pub struct IngestRequest {
     /// Forecast hour override (if not auto-detected from filename)
     #[serde(default)]
     pub forecast_hour: Option<u32>,
+    /// Queue priority on the ingester ("low", "normal" or "high")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -307,6 +365,12 @@ pub struct IngestResponse {
     pub model: Option<String>,
     pub reference_time: Option<String>,
     pub parameters: Vec<String>,
+    /// Zarr storage paths written by the ingestion
+    #[serde(default)]
+    pub storage_paths: Vec<String>,
+    /// Ingester queue job that ran the ingestion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
 }
 
 // ============================================================================
@@ -488,6 +552,7 @@ pub async fn database_datasets_handler(
                     valid_time: d.valid_time.to_rfc3339(),
                     storage_path: d.storage_path,
                     file_size: d.file_size,
+                    lineage: d.lineage,
                 })
                 .collect();
             Json(response).into_response()
@@ -684,22 +749,27 @@ pub async fn list_models_handler(Extension(_state): Extension<Arc<AppState>>) ->
     }
 }
 
-/// GET /admin/config/models/:id - Get specific model configuration (returns raw YAML)
-pub async fn get_model_config_handler(
+/// GET /admin/config/:kind/:id - Get a model, layer or style config file (raw content)
+pub async fn get_config_handler(
     Extension(_state): Extension<Arc<AppState>>,
-    Path(model_id): Path<String>,
+    Path((kind, id)): Path<(String, String)>,
 ) -> impl IntoResponse {
-    info!("Admin: Getting config for model: {}", model_id);
+    info!("Admin: Getting {} config: {}", kind, id);
+
+    let kind = match parse_config_target(&kind, &id) {
+        Ok(kind) => kind,
+        Err(e) => return e.into_response(),
+    };
 
-    match load_model_yaml(&model_id).await {
-        Ok(Some(yaml)) => Json(ModelConfigYamlResponse { id: model_id, yaml }).into_response(),
+    match config_store::read_config(&config_store::config_dir(), kind, &id).await {
+        Ok(Some(yaml)) => Json(ModelConfigYamlResponse { id, yaml }).into_response(),
         Ok(None) => (
             StatusCode::NOT_FOUND,
-            format!("Model '{}' not found", model_id),
+            format!("{} config '{}' not found", kind.as_str(), id),
         )
             .into_response(),
         Err(e) => {
-            warn!("Failed to load model config: {}", e);
+            warn!("Failed to load config: {}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Failed to load config: {}", e),
@@ -828,45 +898,194 @@ pub async fn preview_shred_handler(
     }
 }
 
-/// PUT /admin/config/models/:id - Update model configuration
-pub async fn update_model_config_handler(
-    Extension(_state): Extension<Arc<AppState>>,
-    Path(model_id): Path<String>,
+/// PUT /admin/config/:kind/:id - Validate, save and reload a config file
+///
+/// Each save is recorded as a new version (see the `versions`, `diff` and
+/// `rollback` endpoints). With `validate_only` nothing is saved.
+pub async fn update_config_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((kind, id)): Path<(String, String)>,
     Json(payload): Json<UpdateConfigRequest>,
 ) -> impl IntoResponse {
-    info!("Admin: Updating config for model: {}", model_id);
-
-    // Validate YAML syntax
-    let validation_errors = validate_model_yaml(&payload.yaml);
-    if !validation_errors.is_empty() {
-        return Json(UpdateConfigResponse {
-            success: false,
-            message: "Validation failed".to_string(),
-            validation_errors,
-        })
-        .into_response();
+    info!("Admin: Updating {} config: {}", kind, id);
+
+    let kind = match parse_config_target(&kind, &id) {
+        Ok(kind) => kind,
+        Err(e) => return e.into_response(),
+    };
+
+    if payload.validate_only {
+        let validation_errors =
+            config_store::validate(&config_store::config_dir(), kind, &id, &payload.yaml);
+        let status = if validation_errors.is_empty() {
+            StatusCode::OK
+        } else {
+            StatusCode::UNPROCESSABLE_ENTITY
+        };
+        return (
+            status,
+            Json(UpdateConfigResponse {
+                success: validation_errors.is_empty(),
+                message: if validation_errors.is_empty() {
+                    "Configuration is valid".to_string()
+                } else {
+                    "Validation failed".to_string()
+                },
+                validation_errors,
+                version: None,
+            }),
+        )
+            .into_response();
     }
 
-    // Save the config
-    match save_model_yaml(&model_id, &payload.yaml).await {
-        Ok(()) => Json(UpdateConfigResponse {
-            success: true,
-            message: format!("Configuration for '{}' saved successfully", model_id),
-            validation_errors: vec![],
+    let result =
+        config_store::apply(&state, kind, &id, &payload.yaml, payload.comment.as_deref()).await;
+    config_apply_response(&id, result)
+}
+
+/// GET /admin/config/:kind/:id/versions - List recorded versions, newest first
+pub async fn config_versions_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((kind, id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let kind = match parse_config_target(&kind, &id) {
+        Ok(kind) => kind,
+        Err(e) => return e.into_response(),
+    };
+
+    match state.catalog.list_config_versions(kind.as_str(), &id).await {
+        Ok(versions) => Json(ConfigVersionsResponse {
+            kind,
+            id,
+            versions: versions
+                .into_iter()
+                .map(|v| ConfigVersionSummary {
+                    version: v.version,
+                    comment: v.comment,
+                    created_at: v.created_at,
+                    size_bytes: v.content.len(),
+                })
+                .collect(),
         })
         .into_response(),
         Err(e) => {
-            warn!("Failed to save model config: {}", e);
-            Json(UpdateConfigResponse {
-                success: false,
-                message: format!("Failed to save: {}", e),
-                validation_errors: vec![],
-            })
-            .into_response()
+            warn!("Failed to list config versions: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list versions: {}", e),
+            )
+                .into_response()
         }
     }
 }
 
+/// GET /admin/config/:kind/:id/versions/:version - Get a recorded version with its content
+pub async fn config_version_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((kind, id, version)): Path<(String, String, i32)>,
+) -> impl IntoResponse {
+    let kind = match parse_config_target(&kind, &id) {
+        Ok(kind) => kind,
+        Err(e) => return e.into_response(),
+    };
+
+    match state
+        .catalog
+        .get_config_version(kind.as_str(), &id, version)
+        .await
+    {
+        Ok(Some(version)) => Json(version).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            format!("Version {} of '{}' not found", version, id),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("Failed to load config version: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to load version: {}", e),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /admin/config/:kind/:id/diff?from=N[&to=M] - Unified diff between versions
+///
+/// Without `to`, diffs against the current file.
+pub async fn config_diff_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((kind, id)): Path<(String, String)>,
+    Query(query): Query<ConfigDiffQuery>,
+) -> impl IntoResponse {
+    let kind = match parse_config_target(&kind, &id) {
+        Ok(kind) => kind,
+        Err(e) => return e.into_response(),
+    };
+
+    let from = match load_config_version(&state, kind, &id, query.from).await {
+        Ok(content) => content,
+        Err(e) => return e.into_response(),
+    };
+    let (to, to_label) = match query.to {
+        Some(version) => match load_config_version(&state, kind, &id, version).await {
+            Ok(content) => (content, format!("{} v{}", id, version)),
+            Err(e) => return e.into_response(),
+        },
+        None => match config_store::read_config(&config_store::config_dir(), kind, &id).await {
+            Ok(content) => (content.unwrap_or_default(), format!("{} (current)", id)),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to load config: {}", e),
+                )
+                    .into_response()
+            }
+        },
+    };
+
+    Json(ConfigDiffResponse {
+        kind,
+        diff: config_store::diff(&from, &to, &format!("{} v{}", id, query.from), &to_label),
+        id,
+        from: query.from,
+        to: query.to,
+    })
+    .into_response()
+}
+
+/// POST /admin/config/:kind/:id/rollback - Make a recorded version current again
+///
+/// The old content is validated against the current schemas and saved as a
+/// new version, so the rollback itself can be undone.
+pub async fn config_rollback_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((kind, id)): Path<(String, String)>,
+    Json(payload): Json<ConfigRollbackRequest>,
+) -> impl IntoResponse {
+    info!(
+        "Admin: Rolling back {} config {} to version {}",
+        kind, id, payload.version
+    );
+
+    let kind = match parse_config_target(&kind, &id) {
+        Ok(kind) => kind,
+        Err(e) => return e.into_response(),
+    };
+
+    let content = match load_config_version(&state, kind, &id, payload.version).await {
+        Ok(content) => content,
+        Err(e) => return e.into_response(),
+    };
+    let comment = payload
+        .comment
+        .unwrap_or_else(|| format!("Rollback to version {}", payload.version));
+
+    let result = config_store::apply(&state, kind, &id, &content, Some(&comment)).await;
+    config_apply_response(&id, result)
+}
+
 /// POST /admin/ingest - Proxy ingestion request to ingester service
 ///
 /// This endpoint is called by the downloader service after successfully
@@ -896,6 +1115,27 @@ pub async fn ingest_handler(
         .send()
         .await
     {
+        Ok(response) if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            // Pass the ingester's backpressure through so the caller retries
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or("30")
+                .to_string();
+            let body = response
+                .json::<serde_json::Value>()
+                .await
+                .unwrap_or_else(|_| serde_json::json!({ "success": false }));
+            warn!(retry_after = %retry_after, "Ingester queue full");
+
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after)],
+                Json(body),
+            )
+                .into_response()
+        }
         Ok(response) => {
             let status = response.status();
             match response.json::<IngestResponse>().await {
@@ -906,6 +1146,17 @@ pub async fn ingest_handler(
                         tracing::debug!(
                             "Invalidated capabilities cache after successful ingestion"
                         );
+
+                        // Drop cached chunks of re-written Zarr arrays
+                        for path in &ingest_response.storage_paths {
+                            let removed =
+                                state.grid_processor_factory.invalidate_chunks(path).await;
+                            tracing::debug!(
+                                path = %path,
+                                removed = removed,
+                                "Invalidated cached chunks after ingestion"
+                            );
+                        }
                     }
                     if status.is_success() {
                         (StatusCode::OK, Json(ingest_response)).into_response()
@@ -929,6 +1180,8 @@ pub async fn ingest_handler(
                             model: None,
                             reference_time: None,
                             parameters: vec![],
+                            storage_paths: vec![],
+                            job_id: None,
                         }),
                     )
                         .into_response()
@@ -946,6 +1199,8 @@ pub async fn ingest_handler(
                     model: None,
                     reference_time: None,
                     parameters: vec![],
+                    storage_paths: vec![],
+                    job_id: None,
                 }),
             )
                 .into_response()
@@ -1035,101 +1290,91 @@ async fn load_model_summary(model_id: &str) -> anyhow::Result<Option<ModelSummar
     Ok(Some(summary))
 }
 
-/// Load raw YAML content for a model
-async fn load_model_yaml(model_id: &str) -> anyhow::Result<Option<String>> {
-    use std::fs;
-    use std::path::Path;
-
-    let config_path = Path::new("config/models").join(format!("{}.yaml", model_id));
-
-    if !config_path.exists() {
-        return Ok(None);
+/// Resolve the `:kind/:id` route parameters of the config endpoints.
+fn parse_config_target(kind: &str, id: &str) -> Result<ConfigKind, (StatusCode, String)> {
+    let Some(kind) = ConfigKind::from_segment(kind) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!(
+                "Unknown config type '{}' (expected models, layers or styles)",
+                kind
+            ),
+        ));
+    };
+    if !config_store::is_valid_name(id) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid config name '{}'", id),
+        ));
     }
-
-    let contents = fs::read_to_string(&config_path)?;
-    Ok(Some(contents))
-}
-
-/// Save YAML content for a model
-async fn save_model_yaml(model_id: &str, yaml_content: &str) -> anyhow::Result<()> {
-    use std::fs;
-    use std::path::Path;
-
-    let config_path = Path::new("config/models").join(format!("{}.yaml", model_id));
-
-    // Create backup of existing file
-    if config_path.exists() {
-        let backup_path = Path::new("config/models").join(format!("{}.yaml.bak", model_id));
-        fs::copy(&config_path, &backup_path)?;
+    Ok(kind)
+}
+
+/// Content of a recorded config version.
+async fn load_config_version(
+    state: &AppState,
+    kind: ConfigKind,
+    id: &str,
+    version: i32,
+) -> Result<String, (StatusCode, String)> {
+    match state
+        .catalog
+        .get_config_version(kind.as_str(), id, version)
+        .await
+    {
+        Ok(Some(version)) => Ok(version.content),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Version {} of '{}' not found", version, id),
+        )),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load version: {}", e),
+        )),
     }
-
-    fs::write(&config_path, yaml_content)?;
-    Ok(())
 }
 
-/// Validate YAML content for a model configuration
-fn validate_model_yaml(yaml_content: &str) -> Vec<String> {
-    let mut errors = Vec::new();
-
-    // Check YAML syntax
-    let yaml: serde_yaml::Value = match serde_yaml::from_str(yaml_content) {
-        Ok(v) => v,
-        Err(e) => {
-            errors.push(format!("YAML syntax error: {}", e));
-            return errors;
+/// Response for a save or rollback.
+fn config_apply_response(
+    id: &str,
+    result: Result<storage::ConfigVersion, ApplyError>,
+) -> axum::response::Response {
+    let (status, response) = match result {
+        Ok(version) => (
+            StatusCode::OK,
+            UpdateConfigResponse {
+                success: true,
+                message: format!(
+                    "Configuration for '{}' saved as version {}",
+                    id, version.version
+                ),
+                validation_errors: vec![],
+                version: Some(version.version),
+            },
+        ),
+        Err(ApplyError::Invalid(validation_errors)) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            UpdateConfigResponse {
+                success: false,
+                message: "Validation failed".to_string(),
+                validation_errors,
+                version: None,
+            },
+        ),
+        Err(ApplyError::Failed(e)) => {
+            warn!("Failed to save config: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                UpdateConfigResponse {
+                    success: false,
+                    message: format!("Failed to save: {}", e),
+                    validation_errors: vec![],
+                    version: None,
+                },
+            )
         }
     };
-
-    // Check required sections
-    if yaml.get("model").is_none() {
-        errors.push("Missing required section: 'model'".to_string());
-    } else {
-        let model = yaml.get("model").unwrap();
-        if model.get("id").is_none() {
-            errors.push("Missing required field: 'model.id'".to_string());
-        }
-        if model.get("name").is_none() {
-            errors.push("Missing required field: 'model.name'".to_string());
-        }
-    }
-
-    if yaml.get("source").is_none() {
-        errors.push("Missing required section: 'source'".to_string());
-    } else {
-        let source = yaml.get("source").unwrap();
-        if source.get("type").is_none() {
-            errors.push("Missing required field: 'source.type'".to_string());
-        }
-    }
-
-    if yaml.get("grid").is_none() {
-        errors.push("Missing required section: 'grid'".to_string());
-    } else {
-        let grid = yaml.get("grid").unwrap();
-        if grid.get("projection").is_none() {
-            errors.push("Missing required field: 'grid.projection'".to_string());
-        }
-    }
-
-    if yaml.get("schedule").is_none() {
-        errors.push("Missing required section: 'schedule'".to_string());
-    }
-
-    // Check parameters array
-    if let Some(params) = yaml.get("parameters") {
-        if let Some(params_seq) = params.as_sequence() {
-            for (i, param) in params_seq.iter().enumerate() {
-                if param.get("name").is_none() {
-                    errors.push(format!(
-                        "Parameter {} missing required field: 'name'",
-                        i + 1
-                    ));
-                }
-            }
-        }
-    }
-
-    errors
+    (status, Json(response)).into_response()
 }
 
 /// Build shredding preview from model configuration
@@ -1680,6 +1925,174 @@ pub async fn sync_run_handler(Extension(state): Extension<Arc<AppState>>) -> imp
     }
 }
 
+// ============================================================================
+// Chunk Checksum Verification
+// ============================================================================
+
+/// Query parameters for a checksum sweep
+#[derive(Debug, Deserialize)]
+pub struct ChecksumSweepQuery {
+    /// Only verify datasets of this model
+    pub model: Option<String>,
+}
+
+/// POST /api/admin/integrity/verify - Verify stored chunks against their checksums
+pub async fn checksum_sweep_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(query): Query<ChecksumSweepQuery>,
+) -> impl IntoResponse {
+    info!(model = ?query.model, "Admin: Running checksum sweep");
+
+    let sweep = crate::integrity::ChecksumSweepTask::new(state.clone());
+    match sweep.run(query.model.as_deref()).await {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => {
+            error!(error = %e, "Checksum sweep failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Checksum sweep failed: {}", e),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ============================================================================
+// Run Completeness
+// ============================================================================
+
+/// Query parameters for run completeness
+#[derive(Debug, Deserialize)]
+pub struct CompletenessQuery {
+    /// Only check runs of this model
+    pub model: Option<String>,
+    /// Newest runs checked per model (default 4)
+    pub runs: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompletenessResponse {
+    pub incomplete_runs: usize,
+    pub runs: Vec<crate::completeness::RunCompleteness>,
+}
+
+/// GET /api/admin/completeness - Expected vs. ingested datasets of recent runs
+pub async fn completeness_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(query): Query<CompletenessQuery>,
+) -> impl IntoResponse {
+    info!(model = ?query.model, runs = ?query.runs, "Admin: Checking run completeness");
+
+    if let Some(model) = &query.model {
+        if !state.completeness.models().contains(model) {
+            return (
+                StatusCode::NOT_FOUND,
+                format!("Model '{}' has no forecast hour schedule", model),
+            )
+                .into_response();
+        }
+    }
+
+    let runs = query.runs.unwrap_or(crate::completeness::DEFAULT_RUNS);
+    match state
+        .completeness
+        .check(&state.catalog, query.model.as_deref(), runs)
+        .await
+    {
+        Ok(runs) => Json(CompletenessResponse {
+            incomplete_runs: runs.iter().filter(|run| !run.complete).count(),
+            runs,
+        })
+        .into_response(),
+        Err(e) => {
+            error!(error = %e, "Run completeness check failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Run completeness check failed: {}", e),
+            )
+                .into_response()
+        }
+    }
+}
+
+// ============================================================================
+// Preview Matrix
+// ============================================================================
+
+/// GET /api/admin/preview - Thumbnail of every layer/style for the latest data
+pub async fn preview_matrix_handler(
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    Json(state.previews.manifest(&state).await)
+}
+
+/// GET /api/admin/preview/image/:layer/:style - Thumbnail from the current preview matrix
+pub async fn preview_image_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((layer, style)): Path<(String, String)>,
+) -> impl IntoResponse {
+    match state.previews.image(&layer, &style).await {
+        Some(png) => (
+            [
+                (header::CONTENT_TYPE, "image/png".to_string()),
+                (
+                    header::CACHE_CONTROL,
+                    format!("max-age={}", state.previews.config().cache_ttl.as_secs()),
+                ),
+            ],
+            png,
+        )
+            .into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            format!(
+                "No preview for {}/{}; reload /api/admin/preview",
+                layer, style
+            ),
+        )
+            .into_response(),
+    }
+}
+
+// ============================================================================
+// Bulk Export Handlers
+// ============================================================================
+
+/// POST /api/admin/exports - Queue a bulk export of a parameter across a run
+pub async fn export_create_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Json(request): Json<crate::export::ExportRequest>,
+) -> impl IntoResponse {
+    if let Err(message) = request.validate() {
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+
+    info!(
+        model = %request.model,
+        parameter = %request.parameter,
+        format = ?request.format,
+        "Admin: Export requested"
+    );
+    let job = crate::export::start_export(state.clone(), request).await;
+    (StatusCode::ACCEPTED, Json(job)).into_response()
+}
+
+/// GET /api/admin/exports - List recent export jobs
+pub async fn export_list_handler(Extension(state): Extension<Arc<AppState>>) -> impl IntoResponse {
+    Json(state.exports.list().await)
+}
+
+/// GET /api/admin/exports/:id - Get export progress and download URL
+pub async fn export_status_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.exports.get(&id).await {
+        Some(job) => Json(job).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("Export '{}' not found", id)).into_response(),
+    }
+}
+
 // ============================================================================
 // Full Configuration Endpoint (for dashboard widget)
 // ============================================================================
@@ -3,11 +3,18 @@
 //! Caches generated WMS and WMTS capabilities XML documents with a configurable TTL.
 //! This reduces database queries for frequently requested capabilities documents
 //! while ensuring data remains reasonably fresh.
+//!
+//! Documents are cached per tenant, since each tenant sees its own layers.
+//!
+//! Also holds the data extents of WMTS layers, recorded when capabilities are
+//! built, so GetTile can skip tiles outside a layer's coverage.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{debug, info};
+use wms_common::BoundingBox;
 
 /// Cached capabilities document with generation timestamp.
 struct CachedCapabilities {
@@ -17,15 +24,22 @@ struct CachedCapabilities {
 
 /// Cache for WMS and WMTS capabilities documents.
 ///
-/// Both capabilities documents are cached independently with a shared TTL.
+/// Both capabilities documents are cached independently with a shared TTL,
+/// one per tenant (`None` for unrestricted callers).
 /// Cache is invalidated on:
 /// - TTL expiration
 /// - Data ingestion
 /// - Data cleanup/expiration
 /// - Configuration reload
+///
+/// Layer and model extents are not subject to the TTL or invalidation: they
+/// are replaced whenever capabilities are rebuilt, and grid extents rarely
+/// change between runs.
 pub struct CapabilitiesCache {
-    wms_xml: RwLock<Option<CachedCapabilities>>,
-    wmts_xml: RwLock<Option<CachedCapabilities>>,
+    wms_xml: RwLock<HashMap<String, CachedCapabilities>>,
+    wmts_xml: RwLock<HashMap<String, CachedCapabilities>>,
+    layer_extents: RwLock<HashMap<String, BoundingBox>>,
+    model_extents: RwLock<HashMap<String, BoundingBox>>,
     ttl: Duration,
 }
 
@@ -34,16 +48,18 @@ impl CapabilitiesCache {
     pub fn new(ttl_secs: u64) -> Self {
         info!(ttl_secs = ttl_secs, "Initializing capabilities cache");
         Self {
-            wms_xml: RwLock::new(None),
-            wmts_xml: RwLock::new(None),
+            wms_xml: RwLock::new(HashMap::new()),
+            wmts_xml: RwLock::new(HashMap::new()),
+            layer_extents: RwLock::new(HashMap::new()),
+            model_extents: RwLock::new(HashMap::new()),
             ttl: Duration::from_secs(ttl_secs),
         }
     }
 
-    /// Get cached WMS capabilities if still valid.
-    pub async fn get_wms(&self) -> Option<String> {
+    /// Get cached WMS capabilities for a tenant if still valid.
+    pub async fn get_wms(&self, tenant: Option<&str>) -> Option<String> {
         let guard = self.wms_xml.read().await;
-        if let Some(cached) = guard.as_ref() {
+        if let Some(cached) = guard.get(tenant.unwrap_or_default()) {
             if cached.generated_at.elapsed() < self.ttl {
                 debug!("WMS capabilities cache hit");
                 return Some(cached.xml.clone());
@@ -53,20 +69,23 @@ impl CapabilitiesCache {
         None
     }
 
-    /// Store WMS capabilities in cache.
-    pub async fn set_wms(&self, xml: String) {
+    /// Store WMS capabilities for a tenant in cache.
+    pub async fn set_wms(&self, tenant: Option<&str>, xml: String) {
         let mut guard = self.wms_xml.write().await;
-        *guard = Some(CachedCapabilities {
-            xml,
-            generated_at: Instant::now(),
-        });
+        guard.insert(
+            tenant.unwrap_or_default().to_string(),
+            CachedCapabilities {
+                xml,
+                generated_at: Instant::now(),
+            },
+        );
         debug!("WMS capabilities cached");
     }
 
-    /// Get cached WMTS capabilities if still valid.
-    pub async fn get_wmts(&self) -> Option<String> {
+    /// Get cached WMTS capabilities for a tenant if still valid.
+    pub async fn get_wmts(&self, tenant: Option<&str>) -> Option<String> {
         let guard = self.wmts_xml.read().await;
-        if let Some(cached) = guard.as_ref() {
+        if let Some(cached) = guard.get(tenant.unwrap_or_default()) {
             if cached.generated_at.elapsed() < self.ttl {
                 debug!("WMTS capabilities cache hit");
                 return Some(cached.xml.clone());
@@ -76,23 +95,55 @@ impl CapabilitiesCache {
         None
     }
 
-    /// Store WMTS capabilities in cache.
-    pub async fn set_wmts(&self, xml: String) {
+    /// Store WMTS capabilities for a tenant in cache.
+    pub async fn set_wmts(&self, tenant: Option<&str>, xml: String) {
         let mut guard = self.wmts_xml.write().await;
-        *guard = Some(CachedCapabilities {
-            xml,
-            generated_at: Instant::now(),
-        });
+        guard.insert(
+            tenant.unwrap_or_default().to_string(),
+            CachedCapabilities {
+                xml,
+                generated_at: Instant::now(),
+            },
+        );
         debug!("WMTS capabilities cached");
     }
 
-    /// Invalidate both caches.
+    /// Replace the recorded layer extents (layer id -> lat/lon bbox).
+    pub async fn set_layer_extents(&self, extents: HashMap<String, BoundingBox>) {
+        *self.layer_extents.write().await = extents;
+    }
+
+    /// Add layer extents to the recorded ones, replacing those of the same
+    /// layers.
+    pub async fn merge_layer_extents(&self, extents: HashMap<String, BoundingBox>) {
+        self.layer_extents.write().await.extend(extents);
+    }
+
+    /// Get the recorded extent of a layer.
+    pub async fn get_layer_extent(&self, layer: &str) -> Option<BoundingBox> {
+        self.layer_extents.read().await.get(layer).copied()
+    }
+
+    /// Record the overall extent of a model.
+    pub async fn set_model_extent(&self, model: &str, extent: BoundingBox) {
+        self.model_extents
+            .write()
+            .await
+            .insert(model.to_string(), extent);
+    }
+
+    /// Get the recorded extent of a model.
+    pub async fn get_model_extent(&self, model: &str) -> Option<BoundingBox> {
+        self.model_extents.read().await.get(model).copied()
+    }
+
+    /// Invalidate both caches, for all tenants.
     /// Called when data changes (ingestion, cleanup, config reload).
     pub async fn invalidate(&self) {
         let mut wms_guard = self.wms_xml.write().await;
         let mut wmts_guard = self.wmts_xml.write().await;
-        *wms_guard = None;
-        *wmts_guard = None;
+        wms_guard.clear();
+        wmts_guard.clear();
         debug!("Capabilities cache invalidated");
     }
 
@@ -121,9 +172,9 @@ mod tests {
     #[tokio::test]
     async fn test_cache_hit_within_ttl() {
         let cache = CapabilitiesCache::new(60);
-        cache.set_wms("test xml".to_string()).await;
+        cache.set_wms(None, "test xml".to_string()).await;
 
-        let result = cache.get_wms().await;
+        let result = cache.get_wms(None).await;
         assert!(result.is_some());
         assert_eq!(result.unwrap(), "test xml");
     }
@@ -131,31 +182,31 @@ mod tests {
     #[tokio::test]
     async fn test_cache_miss_when_empty() {
         let cache = CapabilitiesCache::new(60);
-        let result = cache.get_wms().await;
+        let result = cache.get_wms(None).await;
         assert!(result.is_none());
     }
 
     #[tokio::test]
     async fn test_invalidate_clears_cache() {
         let cache = CapabilitiesCache::new(60);
-        cache.set_wms("wms xml".to_string()).await;
-        cache.set_wmts("wmts xml".to_string()).await;
+        cache.set_wms(None, "wms xml".to_string()).await;
+        cache.set_wmts(None, "wmts xml".to_string()).await;
 
         cache.invalidate().await;
 
-        assert!(cache.get_wms().await.is_none());
-        assert!(cache.get_wmts().await.is_none());
+        assert!(cache.get_wms(None).await.is_none());
+        assert!(cache.get_wmts(None).await.is_none());
     }
 
     #[tokio::test]
     async fn test_cache_expires_after_ttl() {
         let cache = CapabilitiesCache::new(0); // 0 second TTL = immediate expiration
-        cache.set_wms("test xml".to_string()).await;
+        cache.set_wms(None, "test xml".to_string()).await;
 
         // Even with 0 TTL, there's a tiny window, so we sleep a tiny bit
         tokio::time::sleep(Duration::from_millis(10)).await;
 
-        let result = cache.get_wms().await;
+        let result = cache.get_wms(None).await;
         assert!(result.is_none());
     }
 
@@ -164,29 +215,70 @@ mod tests {
         let cache = CapabilitiesCache::new(60);
 
         // Set only WMS
-        cache.set_wms("wms content".to_string()).await;
+        cache.set_wms(None, "wms content".to_string()).await;
 
         // WMTS should still be empty
-        assert!(cache.get_wms().await.is_some());
-        assert!(cache.get_wmts().await.is_none());
+        assert!(cache.get_wms(None).await.is_some());
+        assert!(cache.get_wmts(None).await.is_none());
 
         // Set WMTS
-        cache.set_wmts("wmts content".to_string()).await;
+        cache.set_wmts(None, "wmts content".to_string()).await;
 
         // Both should be present
-        assert_eq!(cache.get_wms().await.unwrap(), "wms content");
-        assert_eq!(cache.get_wmts().await.unwrap(), "wmts content");
+        assert_eq!(cache.get_wms(None).await.unwrap(), "wms content");
+        assert_eq!(cache.get_wmts(None).await.unwrap(), "wmts content");
+    }
+
+    #[tokio::test]
+    async fn test_tenants_are_cached_separately() {
+        let cache = CapabilitiesCache::new(60);
+
+        cache.set_wms(None, "all layers".to_string()).await;
+        assert!(cache.get_wms(Some("acme")).await.is_none());
+
+        cache.set_wms(Some("acme"), "acme layers".to_string()).await;
+        assert_eq!(cache.get_wms(Some("acme")).await.unwrap(), "acme layers");
+        assert_eq!(cache.get_wms(None).await.unwrap(), "all layers");
+
+        cache.invalidate().await;
+        assert!(cache.get_wms(Some("acme")).await.is_none());
     }
 
     #[tokio::test]
     async fn test_cache_overwrites_on_set() {
         let cache = CapabilitiesCache::new(60);
 
-        cache.set_wms("first".to_string()).await;
-        assert_eq!(cache.get_wms().await.unwrap(), "first");
+        cache.set_wms(None, "first".to_string()).await;
+        assert_eq!(cache.get_wms(None).await.unwrap(), "first");
+
+        cache.set_wms(None, "second".to_string()).await;
+        assert_eq!(cache.get_wms(None).await.unwrap(), "second");
+    }
+
+    #[tokio::test]
+    async fn test_extents_survive_invalidation() {
+        let cache = CapabilitiesCache::new(60);
+        let hrrr = BoundingBox::new(-134.1, 21.1, -60.9, 52.6);
+        cache
+            .set_layer_extents(HashMap::from([("hrrr_TMP".to_string(), hrrr)]))
+            .await;
+        cache.set_model_extent("hrrr", hrrr).await;
+
+        cache.invalidate().await;
+
+        assert_eq!(cache.get_layer_extent("hrrr_TMP").await, Some(hrrr));
+        assert_eq!(cache.get_model_extent("hrrr").await, Some(hrrr));
+        assert!(cache.get_layer_extent("gfs_TMP").await.is_none());
+
+        let gfs = BoundingBox::new(-180.0, -90.0, 180.0, 90.0);
+        cache
+            .merge_layer_extents(HashMap::from([("gfs_TMP".to_string(), gfs)]))
+            .await;
+        assert_eq!(cache.get_layer_extent("hrrr_TMP").await, Some(hrrr));
+        assert_eq!(cache.get_layer_extent("gfs_TMP").await, Some(gfs));
 
-        cache.set_wms("second".to_string()).await;
-        assert_eq!(cache.get_wms().await.unwrap(), "second");
+        cache.set_layer_extents(HashMap::new()).await;
+        assert!(cache.get_layer_extent("hrrr_TMP").await.is_none());
     }
 
     #[tokio::test]
@@ -211,9 +303,9 @@ mod tests {
             let cache_clone = cache.clone();
             let handle = tokio::spawn(async move {
                 if i % 2 == 0 {
-                    cache_clone.set_wms(format!("content_{}", i)).await;
+                    cache_clone.set_wms(None, format!("content_{}", i)).await;
                 } else {
-                    let _ = cache_clone.get_wms().await;
+                    let _ = cache_clone.get_wms(None).await;
                 }
             });
             handles.push(handle);
@@ -225,6 +317,6 @@ mod tests {
         }
 
         // Cache should have some content
-        assert!(cache.get_wms().await.is_some());
+        assert!(cache.get_wms(None).await.is_some());
     }
 }
@@ -9,6 +9,10 @@
 //! - Parameter filtering to focus on most-used data
 //! - Background polling for new data
 //! - On-ingest warming for immediate cache population
+//! - Arrival prediction: the next run is expected to have the same datasets as
+//!   the previous one, so the catalog is watched closely around its scheduled
+//!   arrival and each dataset is warmed (top zoom first, then the busiest
+//!   regions from the tile heatmap) as soon as it is registered
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -20,9 +24,11 @@ use std::time::Instant;
 use tokio::time::{interval, Duration};
 use tracing::{debug, info, warn};
 
+use crate::metrics::TileHeatmapCell;
 use crate::rendering::loaders::load_grid_data;
 use crate::state::AppState;
 use storage::CatalogEntry;
+use wms_common::BoundingBox;
 
 /// Precaching configuration from model YAML files.
 #[derive(Debug, Clone, Deserialize, Default)]
@@ -52,6 +58,23 @@ pub struct PrecacheConfig {
     /// which pyramid level is read, thus which chunks get cached.
     #[serde(default = "default_zoom_levels")]
     pub zoom_levels: Vec<u32>,
+
+    /// Watch for the next run around its predicted arrival and warm datasets
+    /// as soon as they are registered
+    #[serde(default)]
+    pub predict_arrivals: bool,
+
+    /// Catalog polling interval in seconds while an arrival is due
+    #[serde(default = "default_arrival_poll_interval")]
+    pub arrival_poll_secs: u64,
+
+    /// Minutes past the predicted arrival before the prediction is rebuilt
+    #[serde(default = "default_arrival_timeout")]
+    pub arrival_timeout_mins: u64,
+
+    /// Number of busiest tile heatmap regions to warm per arriving dataset
+    #[serde(default = "default_hot_regions")]
+    pub hot_regions: usize,
 }
 
 fn default_keep_recent() -> usize {
@@ -63,6 +86,18 @@ fn default_poll_interval() -> u64 {
 fn default_zoom_levels() -> Vec<u32> {
     vec![0, 2, 4]
 }
+fn default_arrival_poll_interval() -> u64 {
+    15
+}
+fn default_arrival_timeout() -> u64 {
+    60
+}
+fn default_hot_regions() -> usize {
+    8
+}
+
+/// Number of recent runs used to estimate observation cadence.
+const CADENCE_SAMPLE_RUNS: usize = 6;
 
 /// Structure matching model YAML files for precaching config.
 #[derive(Debug, Deserialize)]
@@ -70,6 +105,8 @@ struct ModelConfigFile {
     model: ModelConfigModel,
     #[serde(default)]
     precaching: Option<PrecacheConfig>,
+    #[serde(default)]
+    schedule: Option<ModelScheduleConfig>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -77,19 +114,147 @@ struct ModelConfigModel {
     id: String,
 }
 
+/// Schedule section of a model YAML file (only what arrival prediction needs).
+#[derive(Debug, Deserialize)]
+struct ModelScheduleConfig {
+    #[serde(default)]
+    cycles: Vec<u32>,
+    #[serde(default)]
+    delay_hours: f64,
+}
+
+/// When a model's runs become available.
+#[derive(Debug, Clone, Default)]
+pub struct ArrivalSchedule {
+    /// Forecast cycle hours (UTC); empty for observation models
+    pub cycles: Vec<u32>,
+    /// Typical lag between a run's reference time and its data arriving
+    pub delay: chrono::Duration,
+}
+
+/// Predicted next run of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrivalPrediction {
+    /// Reference time of the predicted run
+    pub reference_time: DateTime<Utc>,
+    /// When its first datasets are expected in the catalog
+    pub expected_at: DateTime<Utc>,
+}
+
+impl ArrivalSchedule {
+    /// Predict the run following `recent_runs` (reference times, newest first).
+    ///
+    /// Forecast models follow their cycle hours; observation models are
+    /// assumed to keep their recent cadence.
+    pub fn predict_next(&self, recent_runs: &[DateTime<Utc>]) -> Option<ArrivalPrediction> {
+        let latest = *recent_runs.first()?;
+        let reference_time = if self.cycles.is_empty() {
+            latest + observation_cadence(recent_runs)?
+        } else {
+            next_cycle(&self.cycles, latest)?
+        };
+
+        Some(ArrivalPrediction {
+            reference_time,
+            expected_at: reference_time + self.delay,
+        })
+    }
+}
+
+/// First cycle time strictly after `after`.
+fn next_cycle(cycles: &[u32], after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let mut hours: Vec<u32> = cycles.iter().copied().filter(|h| *h < 24).collect();
+    hours.sort_unstable();
+
+    let day = after.date_naive();
+    [day, day.succ_opt()?]
+        .into_iter()
+        .flat_map(|d| hours.iter().filter_map(move |&h| d.and_hms_opt(h, 0, 0)))
+        .map(|t| t.and_utc())
+        .find(|t| *t > after)
+}
+
+/// Median gap between recent observation times (newest first).
+fn observation_cadence(recent_runs: &[DateTime<Utc>]) -> Option<chrono::Duration> {
+    let mut gaps: Vec<chrono::Duration> = recent_runs
+        .windows(2)
+        .map(|w| w[0] - w[1])
+        .filter(|gap| *gap > chrono::Duration::zero())
+        .collect();
+    gaps.sort_unstable();
+    gaps.get(gaps.len() / 2).copied()
+}
+
+/// The `limit` most requested heatmap tiles overlapping `extent`, busiest first.
+///
+/// Heatmap cells are in -180..180 longitude; dataset extents may use 0..360.
+fn hottest_regions(cells: &[TileHeatmapCell], extent: &BoundingBox, limit: usize) -> Vec<[f32; 4]> {
+    let global = extent.max_x - extent.min_x >= 359.0;
+    let (west, east) = if extent.max_x > 180.0 {
+        (extent.min_x - 360.0, extent.max_x - 360.0)
+    } else {
+        (extent.min_x, extent.max_x)
+    };
+
+    let mut hot: Vec<&TileHeatmapCell> = cells
+        .iter()
+        .filter(|c| c.count > 0)
+        .filter(|c| global || ((c.min_lon as f64) < east && (c.max_lon as f64) > west))
+        .filter(|c| (c.min_lat as f64) < extent.max_y && (c.max_lat as f64) > extent.min_y)
+        .collect();
+    hot.sort_by_key(|c| std::cmp::Reverse(c.count));
+
+    hot.into_iter()
+        .take(limit)
+        .map(|c| [c.min_lon, c.min_lat, c.max_lon, c.max_lat])
+        .collect()
+}
+
+/// Identifies a dataset within a run: (parameter, level, forecast hour).
+type RunSlot = (String, String, u32);
+
+fn run_slot(entry: &CatalogEntry) -> RunSlot {
+    (
+        entry.parameter.clone(),
+        entry.level.clone(),
+        entry.forecast_hour,
+    )
+}
+
+/// Tracks the datasets of a predicted run as they arrive.
+struct ArrivalWatch {
+    prediction: ArrivalPrediction,
+    /// Datasets of the previous run, expected again in the predicted one
+    expected: HashSet<RunSlot>,
+    /// Datasets of the predicted run warmed so far
+    warmed: HashSet<RunSlot>,
+    /// Registrations up to this time have already been looked at
+    checked_until: DateTime<Utc>,
+}
+
+impl ArrivalWatch {
+    fn is_complete(&self) -> bool {
+        !self.expected.is_empty() && self.expected.is_subset(&self.warmed)
+    }
+}
+
 /// Chunk warmer for proactive cache population.
 pub struct ChunkWarmer {
     state: Arc<AppState>,
     /// Per-model precaching configuration
     configs: HashMap<String, PrecacheConfig>,
+    /// Per-model arrival schedules (for arrival prediction)
+    schedules: HashMap<String, ArrivalSchedule>,
     /// Track what we've already warmed to avoid redundant work
     warmed_keys: tokio::sync::RwLock<HashSet<String>>,
+    /// Predicted runs being watched, by model
+    watches: tokio::sync::Mutex<HashMap<String, ArrivalWatch>>,
 }
 
 impl ChunkWarmer {
     /// Create a new chunk warmer by loading configs from YAML files.
     pub fn new(state: Arc<AppState>, config_dir: &str) -> Self {
-        let configs = Self::load_precache_configs(config_dir);
+        let (configs, schedules) = Self::load_precache_configs(config_dir);
 
         info!(
             models_with_precaching = configs.iter().filter(|(_, c)| c.enabled).count(),
@@ -104,6 +269,7 @@ impl ChunkWarmer {
                     warm_on_ingest = config.warm_on_ingest,
                     poll_interval_secs = config.poll_interval_secs,
                     zoom_levels = ?config.zoom_levels,
+                    predict_arrivals = config.predict_arrivals,
                     "Chunk precaching enabled"
                 );
             }
@@ -112,18 +278,26 @@ impl ChunkWarmer {
         Self {
             state,
             configs,
+            schedules,
             warmed_keys: tokio::sync::RwLock::new(HashSet::new()),
+            watches: tokio::sync::Mutex::new(HashMap::new()),
         }
     }
 
-    /// Load precaching configs from model YAML files.
-    fn load_precache_configs(config_dir: &str) -> HashMap<String, PrecacheConfig> {
+    /// Load precaching configs and arrival schedules from model YAML files.
+    fn load_precache_configs(
+        config_dir: &str,
+    ) -> (
+        HashMap<String, PrecacheConfig>,
+        HashMap<String, ArrivalSchedule>,
+    ) {
         let mut configs = HashMap::new();
+        let mut schedules = HashMap::new();
         let models_dir = Path::new(config_dir).join("models");
 
         if !models_dir.exists() {
             warn!("Model config directory not found: {:?}", models_dir);
-            return configs;
+            return (configs, schedules);
         }
 
         if let Ok(entries) = std::fs::read_dir(&models_dir) {
@@ -133,6 +307,17 @@ impl ChunkWarmer {
                     if let Ok(contents) = std::fs::read_to_string(&path) {
                         match serde_yaml::from_str::<ModelConfigFile>(&contents) {
                             Ok(config) => {
+                                if let Some(schedule) = config.schedule {
+                                    schedules.insert(
+                                        config.model.id.clone(),
+                                        ArrivalSchedule {
+                                            cycles: schedule.cycles,
+                                            delay: chrono::Duration::seconds(
+                                                (schedule.delay_hours * 3600.0) as i64,
+                                            ),
+                                        },
+                                    );
+                                }
                                 if let Some(precache) = config.precaching {
                                     configs.insert(config.model.id.clone(), precache);
                                 }
@@ -150,7 +335,7 @@ impl ChunkWarmer {
             }
         }
 
-        configs
+        (configs, schedules)
     }
 
     /// Warm a specific dataset by reading at configured zoom levels.
@@ -464,6 +649,204 @@ impl ChunkWarmer {
         }
     }
 
+    /// Background loop that watches for predicted runs and warms their
+    /// datasets as soon as the catalog registers them.
+    pub async fn run_arrival_watch(self: Arc<Self>) {
+        let Some(poll_secs) = self
+            .configs
+            .values()
+            .filter(|c| c.enabled && c.predict_arrivals)
+            .map(|c| c.arrival_poll_secs)
+            .min()
+        else {
+            return;
+        };
+
+        info!(
+            poll_interval_secs = poll_secs,
+            "Starting chunk warming arrival watch"
+        );
+
+        let mut ticker = interval(Duration::from_secs(poll_secs.max(1)));
+
+        loop {
+            ticker.tick().await;
+
+            let heatmap = self.state.metrics.get_tile_heatmap().await;
+            for (model, config) in &self.configs {
+                if !config.enabled || !config.predict_arrivals {
+                    continue;
+                }
+
+                if let Err(e) = self.check_arrivals(model, config, &heatmap.cells).await {
+                    warn!(model = %model, error = %e, "Failed to check predicted arrivals");
+                }
+            }
+        }
+    }
+
+    /// Warm any newly registered datasets of the model's predicted run.
+    async fn check_arrivals(
+        &self,
+        model: &str,
+        config: &PrecacheConfig,
+        heatmap: &[TileHeatmapCell],
+    ) -> Result<()> {
+        let now = Utc::now();
+        let timeout = chrono::Duration::minutes(config.arrival_timeout_mins as i64);
+        let lead = chrono::Duration::seconds(config.arrival_poll_secs as i64);
+
+        let mut watches = self.watches.lock().await;
+        let stale = watches
+            .get(model)
+            .is_none_or(|w| w.is_complete() || now > w.prediction.expected_at + timeout);
+        if stale {
+            match self.predict_arrival(model, config).await? {
+                Some(watch) => {
+                    debug!(
+                        model = %model,
+                        reference_time = ?watch.prediction.reference_time,
+                        expected_at = ?watch.prediction.expected_at,
+                        expected_datasets = watch.expected.len(),
+                        "Predicted next arrival"
+                    );
+                    watches.insert(model.to_string(), watch);
+                }
+                None => {
+                    watches.remove(model);
+                    return Ok(());
+                }
+            }
+        }
+
+        let Some(watch) = watches.get_mut(model) else {
+            return Ok(());
+        };
+
+        // Only poll closely around the expected arrival
+        if now + lead < watch.prediction.expected_at || now > watch.prediction.expected_at + timeout
+        {
+            return Ok(());
+        }
+
+        let arrived: Vec<CatalogEntry> = self
+            .state
+            .catalog
+            .get_ingested_since(model, watch.checked_until)
+            .await?
+            .into_iter()
+            .filter(|e| e.reference_time >= watch.prediction.reference_time)
+            .filter(|e| config.parameters.is_empty() || config.parameters.contains(&e.parameter))
+            .filter(|e| e.zarr_metadata.is_some())
+            .collect();
+        watch.checked_until = now;
+
+        let Some(first) = arrived.first() else {
+            return Ok(());
+        };
+
+        let regions = hottest_regions(heatmap, &first.bbox, config.hot_regions);
+        let start = Instant::now();
+        for entry in &arrived {
+            self.warm_arrival(entry, config, &regions).await;
+            watch.warmed.insert(run_slot(entry));
+        }
+
+        info!(
+            model = %model,
+            reference_time = ?watch.prediction.reference_time,
+            datasets = arrived.len(),
+            hot_regions = regions.len(),
+            warmed = watch.warmed.len(),
+            expected = watch.expected.len(),
+            elapsed_ms = start.elapsed().as_millis(),
+            "Warmed arriving datasets"
+        );
+
+        Ok(())
+    }
+
+    /// Predict the model's next run and the datasets it will contain, taken
+    /// from the most recent run.
+    async fn predict_arrival(
+        &self,
+        model: &str,
+        config: &PrecacheConfig,
+    ) -> Result<Option<ArrivalWatch>> {
+        let recent_runs: Vec<DateTime<Utc>> = self
+            .state
+            .catalog
+            .get_model_runs_with_counts(model)
+            .await?
+            .into_iter()
+            .take(CADENCE_SAMPLE_RUNS)
+            .map(|(reference_time, _)| reference_time)
+            .collect();
+
+        let schedule = self.schedules.get(model).cloned().unwrap_or_default();
+        let Some(prediction) = schedule.predict_next(&recent_runs) else {
+            return Ok(None);
+        };
+
+        let expected = self
+            .state
+            .catalog
+            .get_run_entries(model, recent_runs[0])
+            .await?
+            .iter()
+            .filter(|e| config.parameters.is_empty() || config.parameters.contains(&e.parameter))
+            .map(run_slot)
+            .collect();
+
+        Ok(Some(ArrivalWatch {
+            prediction,
+            expected,
+            warmed: HashSet::new(),
+            // Datasets of the predicted run registered before the watch started
+            checked_until: prediction.reference_time.min(Utc::now()),
+        }))
+    }
+
+    /// Warm an arriving dataset: the top pyramid level first, so overview
+    /// tiles are ready, then the busiest regions at tile resolution.
+    async fn warm_arrival(
+        &self,
+        entry: &CatalogEntry,
+        config: &PrecacheConfig,
+        regions: &[[f32; 4]],
+    ) {
+        if let Some(&top_zoom) = config.zoom_levels.iter().min() {
+            if let Err(e) = self.warm_at_zoom(entry, top_zoom).await {
+                debug!(
+                    storage_path = %entry.storage_path,
+                    zoom = top_zoom,
+                    error = %e,
+                    "Failed to warm arriving dataset"
+                );
+            }
+        }
+
+        let requires_full_grid = self.state.model_dimensions.requires_full_grid(&entry.model);
+        for region in regions {
+            if let Err(e) = load_grid_data(
+                &self.state.grid_processor_factory,
+                entry,
+                Some(*region),
+                Some((256, 256)),
+                requires_full_grid,
+            )
+            .await
+            {
+                debug!(
+                    storage_path = %entry.storage_path,
+                    region = ?region,
+                    error = %e,
+                    "Failed to warm hot region"
+                );
+            }
+        }
+    }
+
     /// Clean up warmed_keys set by removing old entries.
     /// Since we track by storage_path+timestamp, old entries naturally become stale
     /// when the catalog no longer contains them.
@@ -493,3 +876,90 @@ impl ChunkWarmer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn cell(min_lon: f32, min_lat: f32, count: u64) -> TileHeatmapCell {
+        TileHeatmapCell {
+            min_lon,
+            min_lat,
+            max_lon: min_lon + 10.0,
+            max_lat: min_lat + 10.0,
+            count,
+            l1_hits: 0,
+            l2_hits: 0,
+            misses: 0,
+        }
+    }
+
+    #[test]
+    fn test_predict_next_forecast_cycle() {
+        let schedule = ArrivalSchedule {
+            cycles: vec![0, 6, 12, 18],
+            delay: chrono::Duration::hours(4),
+        };
+
+        let next = schedule
+            .predict_next(&[utc(2024, 1, 1, 12, 0), utc(2024, 1, 1, 6, 0)])
+            .unwrap();
+        assert_eq!(next.reference_time, utc(2024, 1, 1, 18, 0));
+        assert_eq!(next.expected_at, utc(2024, 1, 1, 22, 0));
+
+        // Wraps to the next day
+        let next = schedule.predict_next(&[utc(2024, 1, 1, 18, 0)]).unwrap();
+        assert_eq!(next.reference_time, utc(2024, 1, 2, 0, 0));
+
+        assert!(schedule.predict_next(&[]).is_none());
+    }
+
+    #[test]
+    fn test_predict_next_observation_cadence() {
+        let schedule = ArrivalSchedule::default();
+        let runs = [
+            utc(2024, 1, 1, 12, 20),
+            utc(2024, 1, 1, 12, 10),
+            utc(2024, 1, 1, 12, 5),
+            utc(2024, 1, 1, 11, 55),
+        ];
+
+        // Median gap is 10 minutes despite the irregular one
+        let next = schedule.predict_next(&runs).unwrap();
+        assert_eq!(next.reference_time, utc(2024, 1, 1, 12, 30));
+        assert_eq!(next.expected_at, next.reference_time);
+
+        // A single observation gives no cadence
+        assert!(schedule.predict_next(&runs[..1]).is_none());
+    }
+
+    #[test]
+    fn test_hottest_regions() {
+        let cells = [
+            cell(-100.0, 30.0, 5),
+            cell(-90.0, 30.0, 50),
+            cell(10.0, 40.0, 100), // Outside CONUS
+            cell(-80.0, 30.0, 0),
+        ];
+        let conus = BoundingBox::new(-125.0, 20.0, -60.0, 55.0);
+
+        let regions = hottest_regions(&cells, &conus, 8);
+        assert_eq!(
+            regions,
+            vec![[-90.0, 30.0, -80.0, 40.0], [-100.0, 30.0, -90.0, 40.0]]
+        );
+        assert_eq!(hottest_regions(&cells, &conus, 1).len(), 1);
+
+        // Global 0..360 grids take every requested tile
+        let global = BoundingBox::new(0.0, -90.0, 360.0, 90.0);
+        assert_eq!(
+            hottest_regions(&cells, &global, 8)[0],
+            [10.0, 40.0, 20.0, 50.0]
+        );
+    }
+}
@@ -0,0 +1,411 @@
+//! Expected-vs-actual dataset completeness of forecast runs.
+//!
+//! A forecast model is expected to deliver every configured parameter and
+//! level at every hour of its `schedule.forecast_hours` range. The
+//! [`CompletenessMonitor`] compares recent runs in the catalog against that
+//! for `/api/admin/completeness`, and keeps the missing counts of its last
+//! refresh for the `model_run_missing_datasets` gauge. Observation models
+//! have no forecast hours and are not monitored.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use storage::Catalog;
+use tracing::{debug, info, warn};
+use wms_common::WmsResult;
+
+use crate::state::AppState;
+
+/// Runs of each model checked by a refresh, newest first.
+pub const DEFAULT_RUNS: usize = 4;
+
+/// Datasets a run of one model is complete with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedDatasets {
+    pub model: String,
+    pub forecast_hours: Vec<u32>,
+    /// (parameter, level) pairs, with levels named as in the catalog
+    /// (e.g. "500 mb", "2 m above ground")
+    pub fields: BTreeSet<(String, String)>,
+}
+
+impl ExpectedDatasets {
+    /// Expected datasets of a model config, or `None` for models without a
+    /// forecast hour schedule.
+    pub fn from_yaml(yaml: &serde_yaml::Value) -> Option<Self> {
+        let model = yaml["model"]["id"].as_str()?.to_string();
+        let range = &yaml["schedule"]["forecast_hours"];
+        let start = range["start"].as_u64().unwrap_or(0) as u32;
+        let end = range["end"].as_u64()? as u32;
+        let step = range["step"].as_u64().unwrap_or(1) as usize;
+        if step == 0 || end < start {
+            return None;
+        }
+
+        let mut fields = BTreeSet::new();
+        for parameter in yaml["parameters"].as_sequence().into_iter().flatten() {
+            let Some(name) = parameter["name"].as_str() else {
+                continue;
+            };
+            for level in parameter["levels"].as_sequence().into_iter().flatten() {
+                for level_name in level_names(level) {
+                    fields.insert((name.to_string(), level_name));
+                }
+            }
+        }
+
+        Some(Self {
+            model,
+            forecast_hours: (start..=end).step_by(step).collect(),
+            fields,
+        })
+    }
+
+    /// Expected datasets of every forecast model in `config_dir/models`,
+    /// sorted by model.
+    pub fn load_from_directory(config_dir: impl AsRef<Path>) -> Vec<Self> {
+        let models_dir = config_dir.as_ref().join("models");
+        let entries = match fs::read_dir(&models_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!(error = %e, path = ?models_dir, "Failed to read models directory");
+                return Vec::new();
+            }
+        };
+
+        let mut expected: Vec<Self> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "yaml"))
+            .filter_map(|path| {
+                let contents = fs::read_to_string(&path).ok()?;
+                let yaml: serde_yaml::Value = serde_yaml::from_str(&contents).ok()?;
+                Self::from_yaml(&yaml)
+            })
+            .collect();
+        expected.sort_by(|a, b| a.model.cmp(&b.model));
+        expected
+    }
+
+    /// Number of datasets in a complete run.
+    pub fn total(&self) -> usize {
+        self.forecast_hours.len() * self.fields.len()
+    }
+
+    /// Compare the (parameter, level, forecast hour) of the datasets of a
+    /// run against the expected ones. Unexpected datasets are ignored, and
+    /// ensemble members of the same dataset count once.
+    pub fn compare<'a>(
+        &self,
+        reference_time: DateTime<Utc>,
+        datasets: impl IntoIterator<Item = (&'a str, &'a str, u32)>,
+    ) -> RunCompleteness {
+        let present: HashSet<(&str, &str, u32)> = datasets.into_iter().collect();
+        let has = |(parameter, level): &(String, String), hour: u32| {
+            present.contains(&(parameter.as_str(), level.as_str(), hour))
+        };
+
+        // Hours with nothing at all are reported once, not per field
+        let missing_forecast_hours: Vec<u32> = self
+            .forecast_hours
+            .iter()
+            .copied()
+            .filter(|&hour| !self.fields.iter().any(|field| has(field, hour)))
+            .collect();
+
+        let mut missing = missing_forecast_hours.len() * self.fields.len();
+        let mut missing_datasets = Vec::new();
+        for field in &self.fields {
+            let hours: Vec<u32> = self
+                .forecast_hours
+                .iter()
+                .copied()
+                .filter(|hour| !missing_forecast_hours.contains(hour) && !has(field, *hour))
+                .collect();
+            if !hours.is_empty() {
+                missing += hours.len();
+                missing_datasets.push(MissingDataset {
+                    parameter: field.0.clone(),
+                    level: field.1.clone(),
+                    forecast_hours: hours,
+                });
+            }
+        }
+
+        let expected = self.total();
+        RunCompleteness {
+            model: self.model.clone(),
+            reference_time,
+            expected,
+            available: expected - missing,
+            missing,
+            complete: missing == 0,
+            missing_forecast_hours,
+            missing_datasets,
+        }
+    }
+}
+
+/// Level names a config level entry is registered under: its `display`
+/// text, or `display_template` filled with each of its `values`.
+fn level_names(level: &serde_yaml::Value) -> Vec<String> {
+    if let Some(values) = level["values"].as_sequence() {
+        let template = level["display_template"].as_str().unwrap_or("{value}");
+        values
+            .iter()
+            .filter_map(|v| v.as_i64())
+            .map(|v| template.replace("{value}", &v.to_string()))
+            .collect()
+    } else {
+        level["display"]
+            .as_str()
+            .or_else(|| level["type"].as_str())
+            .map(|display| vec![display.to_string()])
+            .unwrap_or_default()
+    }
+}
+
+/// How complete one run of a model is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunCompleteness {
+    pub model: String,
+    pub reference_time: DateTime<Utc>,
+    pub expected: usize,
+    pub available: usize,
+    pub missing: usize,
+    pub complete: bool,
+    /// Forecast hours without any of the expected datasets
+    pub missing_forecast_hours: Vec<u32>,
+    /// Parameters and levels missing at some of the other forecast hours
+    pub missing_datasets: Vec<MissingDataset>,
+}
+
+/// A parameter and level missing at some forecast hours of a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingDataset {
+    pub parameter: String,
+    pub level: String,
+    pub forecast_hours: Vec<u32>,
+}
+
+/// Expected datasets of each model and the completeness of their recent
+/// runs as of the last refresh.
+#[derive(Debug, Default)]
+pub struct CompletenessMonitor {
+    expected: RwLock<Vec<ExpectedDatasets>>,
+    latest: RwLock<Vec<RunCompleteness>>,
+}
+
+impl CompletenessMonitor {
+    pub fn load_from_directory(config_dir: impl AsRef<Path>) -> Self {
+        Self {
+            expected: RwLock::new(ExpectedDatasets::load_from_directory(config_dir)),
+            latest: RwLock::default(),
+        }
+    }
+
+    /// Replace the expected datasets with those in a directory.
+    /// Returns the number of models monitored.
+    pub fn reload_from_directory(&self, config_dir: impl AsRef<Path>) -> usize {
+        let expected = ExpectedDatasets::load_from_directory(config_dir);
+        let count = expected.len();
+        *self.expected.write().unwrap() = expected;
+        count
+    }
+
+    /// Models monitored.
+    pub fn models(&self) -> Vec<String> {
+        let expected = self.expected.read().unwrap();
+        expected.iter().map(|e| e.model.clone()).collect()
+    }
+
+    /// Completeness of the `runs` newest runs of `model` (every monitored
+    /// model if None), newest first. Unmonitored models give no runs.
+    pub async fn check(
+        &self,
+        catalog: &Catalog,
+        model: Option<&str>,
+        runs: usize,
+    ) -> WmsResult<Vec<RunCompleteness>> {
+        let expected: Vec<ExpectedDatasets> = self
+            .expected
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|e| model.is_none_or(|m| e.model == m))
+            .cloned()
+            .collect();
+
+        let mut report = Vec::new();
+        for expected in &expected {
+            let model_runs = catalog.get_model_runs_with_counts(&expected.model).await?;
+            for (reference_time, _) in model_runs.into_iter().take(runs) {
+                let entries = catalog
+                    .get_run_entries(&expected.model, reference_time)
+                    .await?;
+                report.push(
+                    expected.compare(
+                        reference_time,
+                        entries
+                            .iter()
+                            .map(|e| (e.parameter.as_str(), e.level.as_str(), e.forecast_hour)),
+                    ),
+                );
+            }
+        }
+        Ok(report)
+    }
+
+    /// Check the [`DEFAULT_RUNS`] newest runs of every model and keep the
+    /// result for [`CompletenessMonitor::to_prometheus`]. Returns the
+    /// number of incomplete runs.
+    pub async fn refresh(&self, catalog: &Catalog) -> WmsResult<usize> {
+        let report = self.check(catalog, None, DEFAULT_RUNS).await?;
+        let incomplete = report.iter().filter(|run| !run.complete).count();
+        *self.latest.write().unwrap() = report;
+        Ok(incomplete)
+    }
+
+    /// Missing datasets of each run checked by the last refresh.
+    pub fn to_prometheus(&self) -> String {
+        let latest = self.latest.read().unwrap();
+        let mut out = String::from(
+            "# HELP model_run_missing_datasets Expected datasets not in the catalog per model run\n\
+             # TYPE model_run_missing_datasets gauge\n",
+        );
+        for run in latest.iter() {
+            out.push_str(&format!(
+                "model_run_missing_datasets{{model=\"{}\",run=\"{}\"}} {}\n",
+                run.model,
+                run.reference_time.format("%Y-%m-%dT%H:%M:%SZ"),
+                run.missing
+            ));
+        }
+        out
+    }
+}
+
+/// Refresh run completeness every `interval`, starting immediately.
+pub async fn run_refresh_loop(state: Arc<AppState>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        match state.completeness.refresh(&state.catalog).await {
+            Ok(incomplete) => info!(incomplete_runs = incomplete, "Refreshed run completeness"),
+            Err(e) => debug!(error = %e, "Cannot check run completeness"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const CONFIG: &str = r#"
+model:
+  id: gfs
+schedule:
+  forecast_hours:
+    start: 0
+    end: 6
+    step: 3
+parameters:
+  - name: TMP
+    levels:
+      - type: height_above_ground
+        value: 2
+        display: "2 m above ground"
+      - type: isobaric
+        values: [ 850, 500 ]
+        display_template: "{value} mb"
+  - name: PRMSL
+    levels:
+      - type: mean_sea_level
+"#;
+
+    fn expected() -> ExpectedDatasets {
+        ExpectedDatasets::from_yaml(&serde_yaml::from_str(CONFIG).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_expected_datasets_from_config() {
+        let expected = expected();
+        assert_eq!(expected.model, "gfs");
+        assert_eq!(expected.forecast_hours, vec![0, 3, 6]);
+        let fields: Vec<(&str, &str)> = expected
+            .fields
+            .iter()
+            .map(|(p, l)| (p.as_str(), l.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("PRMSL", "mean_sea_level"),
+                ("TMP", "2 m above ground"),
+                ("TMP", "500 mb"),
+                ("TMP", "850 mb"),
+            ]
+        );
+        assert_eq!(expected.total(), 12);
+
+        // Observations have no forecast hours to expect
+        let observation = "model:\n  id: mrms\nschedule:\n  type: observation\n";
+        assert!(ExpectedDatasets::from_yaml(&serde_yaml::from_str(observation).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_compare_run() {
+        let expected = expected();
+        let run = Utc.with_ymd_and_hms(2024, 12, 3, 6, 0, 0).unwrap();
+
+        let mut datasets = Vec::new();
+        for hour in [0, 3, 6] {
+            for (parameter, level) in &expected.fields {
+                datasets.push((parameter.as_str(), level.as_str(), hour));
+            }
+        }
+        let complete = expected.compare(run, datasets.iter().copied());
+        assert!(complete.complete);
+        assert_eq!((complete.available, complete.missing), (12, 0));
+
+        // f006 never arrived and TMP 500 mb is missing at f003
+        let partial: Vec<_> = datasets
+            .iter()
+            .copied()
+            .filter(|&(p, l, h)| h != 6 && !(p == "TMP" && l == "500 mb" && h == 3))
+            .chain([("TMP", "250 mb", 0)])
+            .collect();
+        let report = expected.compare(run, partial);
+        assert!(!report.complete);
+        assert_eq!(report.missing_forecast_hours, vec![6]);
+        assert_eq!(
+            report.missing_datasets,
+            vec![MissingDataset {
+                parameter: "TMP".to_string(),
+                level: "500 mb".to_string(),
+                forecast_hours: vec![3],
+            }]
+        );
+        assert_eq!(
+            (report.expected, report.available, report.missing),
+            (12, 7, 5)
+        );
+    }
+
+    #[test]
+    fn test_prometheus_gauge() {
+        let monitor = CompletenessMonitor::default();
+        let run = Utc.with_ymd_and_hms(2024, 12, 3, 6, 0, 0).unwrap();
+        *monitor.latest.write().unwrap() = vec![expected().compare(run, [])];
+
+        let text = monitor.to_prometheus();
+        assert!(text.contains("# TYPE model_run_missing_datasets gauge"));
+        assert!(text
+            .contains("model_run_missing_datasets{model=\"gfs\",run=\"2024-12-03T06:00:00Z\"} 12"));
+    }
+}
@@ -0,0 +1,440 @@
+//! Validated, versioned edits of model, layer and style config files.
+//!
+//! Admin edits are checked against the JSON schemas in `config/schemas/`
+//! (compiled into the binary) plus the checks the loaders would otherwise
+//! only log, e.g. a layer pointing at a missing style file. Accepted edits are
+//! written atomically, the affected registries and caches are reloaded, and
+//! the new content is recorded in the catalog's `config_versions` table so
+//! it can be diffed against and rolled back to later.
+//!
+//! The first edit of a file also records the content it replaces, so the
+//! state shipped with the deployment can always be restored.
+
+use jsonschema::Validator;
+use serde::Serialize;
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use storage::ConfigVersion;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::layer_config::LayerConfigRegistry;
+use crate::state::AppState;
+
+/// Serializes edits so the file, the registries and the history agree.
+static EDIT_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A family of config files that can be edited through the admin API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigKind {
+    Model,
+    Layer,
+    Style,
+}
+
+impl ConfigKind {
+    /// Parse the route segment ("models", "layers" or "styles").
+    pub fn from_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "models" => Some(ConfigKind::Model),
+            "layers" => Some(ConfigKind::Layer),
+            "styles" => Some(ConfigKind::Style),
+            _ => None,
+        }
+    }
+
+    /// Name stored in the `config_versions.kind` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigKind::Model => "model",
+            ConfigKind::Layer => "layer",
+            ConfigKind::Style => "style",
+        }
+    }
+
+    /// Subdirectory of the config directory holding these files.
+    pub fn dir(&self) -> &'static str {
+        match self {
+            ConfigKind::Model => "models",
+            ConfigKind::Layer => "layers",
+            ConfigKind::Style => "styles",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ConfigKind::Model | ConfigKind::Layer => "yaml",
+            ConfigKind::Style => "json",
+        }
+    }
+
+    fn validator(&self) -> &'static Validator {
+        static MODEL: OnceLock<Validator> = OnceLock::new();
+        static LAYER: OnceLock<Validator> = OnceLock::new();
+        static STYLE: OnceLock<Validator> = OnceLock::new();
+
+        let (cell, source) = match self {
+            ConfigKind::Model => (
+                &MODEL,
+                include_str!("../../../config/schemas/model.schema.json"),
+            ),
+            ConfigKind::Layer => (
+                &LAYER,
+                include_str!("../../../config/schemas/layer.schema.json"),
+            ),
+            ConfigKind::Style => (
+                &STYLE,
+                include_str!("../../../config/schemas/style.schema.json"),
+            ),
+        };
+        cell.get_or_init(|| {
+            let schema = serde_json::from_str(source).expect("bundled config schema is JSON");
+            jsonschema::validator_for(&schema).expect("bundled config schema is valid")
+        })
+    }
+}
+
+/// Root config directory (`CONFIG_DIR`, default `config`).
+pub fn config_dir() -> PathBuf {
+    PathBuf::from(std::env::var("CONFIG_DIR").unwrap_or_else(|_| "config".to_string()))
+}
+
+/// Whether `name` is usable as a config file stem (no path separators).
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 100
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Path of a config file, e.g. `config/models/gfs.yaml`.
+pub fn config_path(config_dir: &Path, kind: ConfigKind, name: &str) -> PathBuf {
+    config_dir
+        .join(kind.dir())
+        .join(format!("{}.{}", name, kind.extension()))
+}
+
+/// Read a config file; `None` if it does not exist.
+pub async fn read_config(
+    config_dir: &Path,
+    kind: ConfigKind,
+    name: &str,
+) -> std::io::Result<Option<String>> {
+    match tokio::fs::read_to_string(config_path(config_dir, kind, name)).await {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Validate config content. Returns the errors found (empty = valid).
+pub fn validate(config_dir: &Path, kind: ConfigKind, name: &str, content: &str) -> Vec<String> {
+    let value: serde_json::Value = match kind {
+        ConfigKind::Model | ConfigKind::Layer => match serde_yaml::from_str(content) {
+            Ok(value) => value,
+            Err(e) => return vec![format!("YAML syntax error: {}", e)],
+        },
+        ConfigKind::Style => match serde_json::from_str(content) {
+            Ok(value) => value,
+            Err(e) => return vec![format!("JSON syntax error: {}", e)],
+        },
+    };
+
+    let mut errors: Vec<String> = kind
+        .validator()
+        .iter_errors(&value)
+        .map(|e| format!("{}: {}", field_path(&e.instance_path.to_string()), e))
+        .collect();
+    if !errors.is_empty() {
+        return errors;
+    }
+
+    match kind {
+        ConfigKind::Model => {
+            let id = value["model"]["id"].as_str().unwrap_or_default();
+            if id != name {
+                errors.push(format!(
+                    "model.id: '{}' does not match the config name '{}'",
+                    id, name
+                ));
+            }
+        }
+        ConfigKind::Layer => {
+            if let Err(e) = LayerConfigRegistry::check_layer_yaml(content) {
+                errors.push(e);
+            }
+            let styles_dir = config_dir.join(ConfigKind::Style.dir());
+            for layer in value["layers"].as_array().into_iter().flatten() {
+                let style_file = layer["style_file"].as_str().unwrap_or_default();
+                if !styles_dir.join(style_file).exists() {
+                    errors.push(format!(
+                        "layers: layer '{}' uses style file '{}', which does not exist",
+                        layer["id"].as_str().unwrap_or_default(),
+                        style_file
+                    ));
+                }
+            }
+        }
+        ConfigKind::Style => {
+            if let Err(e) = renderer::style::StyleConfig::from_json(content) {
+                errors.push(format!("Invalid style: {}", e));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Render a JSON pointer (`/parameters/0/name`) as `parameters[0].name`.
+fn field_path(pointer: &str) -> String {
+    let mut path = String::new();
+    for segment in pointer.split('/').skip(1) {
+        if segment.parse::<usize>().is_ok() {
+            path.push_str(&format!("[{}]", segment));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(&segment.replace("~1", "/").replace("~0", "~"));
+        }
+    }
+    if path.is_empty() {
+        "(root)".to_string()
+    } else {
+        path
+    }
+}
+
+/// Unified diff between two revisions of a file.
+pub fn diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(old_label, new_label)
+        .to_string()
+}
+
+/// Why an edit was not applied.
+#[derive(Debug)]
+pub enum ApplyError {
+    /// The content failed validation
+    Invalid(Vec<String>),
+    /// Writing the file or recording the version failed; the previous
+    /// content was restored
+    Failed(String),
+}
+
+/// Validate, write and reload a config file, recording it as a new version.
+///
+/// If the version can't be recorded the previous file is restored, so the
+/// history never misses content that was live.
+pub async fn apply(
+    state: &AppState,
+    kind: ConfigKind,
+    name: &str,
+    content: &str,
+    comment: Option<&str>,
+) -> Result<ConfigVersion, ApplyError> {
+    let _guard = EDIT_LOCK.lock().await;
+    let config_dir = config_dir();
+
+    let errors = validate(&config_dir, kind, name, content);
+    if !errors.is_empty() {
+        return Err(ApplyError::Invalid(errors));
+    }
+
+    let path = config_path(&config_dir, kind, name);
+    let previous = read_config(&config_dir, kind, name)
+        .await
+        .map_err(|e| ApplyError::Failed(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    // Keep the content being replaced if it predates the history
+    if let Some(previous) = &previous {
+        let latest = state
+            .catalog
+            .latest_config_version(kind.as_str(), name)
+            .await
+            .map_err(|e| ApplyError::Failed(e.to_string()))?;
+        if latest.is_none() {
+            state
+                .catalog
+                .record_config_version(kind.as_str(), name, previous, Some("Initial version"))
+                .await
+                .map_err(|e| ApplyError::Failed(e.to_string()))?;
+        }
+    }
+
+    write_atomic(&path, content)
+        .await
+        .map_err(|e| ApplyError::Failed(format!("Failed to write {}: {}", path.display(), e)))?;
+    reload(state, kind, &config_dir).await;
+
+    match state
+        .catalog
+        .record_config_version(kind.as_str(), name, content, comment)
+        .await
+    {
+        Ok(version) => {
+            info!(
+                kind = kind.as_str(),
+                name = name,
+                version = version.version,
+                "Config updated"
+            );
+            Ok(version)
+        }
+        Err(e) => {
+            warn!(
+                error = %e,
+                kind = kind.as_str(),
+                name = name,
+                "Failed to record config version, restoring previous content"
+            );
+            let restored = match &previous {
+                Some(previous) => write_atomic(&path, previous).await,
+                None => tokio::fs::remove_file(&path).await,
+            };
+            if let Err(restore_error) = restored {
+                warn!(error = %restore_error, path = %path.display(), "Failed to restore config");
+            }
+            reload(state, kind, &config_dir).await;
+            Err(ApplyError::Failed(e.to_string()))
+        }
+    }
+}
+
+/// Replace a file via a temporary sibling, so readers never see a partial write.
+async fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    tokio::fs::write(&tmp_path, content).await?;
+    tokio::fs::rename(&tmp_path, path).await
+}
+
+/// Reload what depends on a kind of config file and drop rendered tiles.
+///
+/// Each registry is rebuilt before it replaces the old one, so requests see
+/// either the old or the new configuration.
+pub async fn reload(state: &AppState, kind: ConfigKind, config_dir: &Path) {
+    match kind {
+        ConfigKind::Model => {
+            let models = state.model_dimensions.reload_from_directory(config_dir);
+            state.completeness.reload_from_directory(config_dir);
+            info!(models = models, "Model dimension configurations reloaded");
+        }
+        ConfigKind::Layer => reload_layers(state, config_dir).await,
+        ConfigKind::Style => crate::rendering::clear_palette_cache(),
+    }
+
+    state.capabilities_cache.invalidate().await;
+    state.tile_memory_cache.clear().await;
+}
+
+/// Rebuild the layer registry from `config_dir` and swap it in.
+pub async fn reload_layers(state: &AppState, config_dir: &Path) {
+    let registry = LayerConfigRegistry::load_from_directory(config_dir);
+    info!(
+        models = registry.models().len(),
+        total_layers = registry.total_layers(),
+        "Layer configurations reloaded"
+    );
+    *state.layer_configs.write().await = registry;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_config_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config")
+    }
+
+    #[test]
+    fn test_bundled_configs_pass_validation() {
+        let config_dir = repo_config_dir();
+        for kind in [ConfigKind::Model, ConfigKind::Layer, ConfigKind::Style] {
+            for entry in std::fs::read_dir(config_dir.join(kind.dir())).unwrap() {
+                let path = entry.unwrap().path();
+                let name = path.file_stem().unwrap().to_str().unwrap();
+                if path.extension().and_then(|e| e.to_str()) != Some(kind.extension())
+                    || name.contains("schema")
+                {
+                    continue;
+                }
+
+                let content = std::fs::read_to_string(&path).unwrap();
+                let errors = validate(&config_dir, kind, name, &content);
+                assert!(errors.is_empty(), "{}: {:?}", path.display(), errors);
+            }
+        }
+    }
+
+    #[test]
+    fn test_validation_errors_name_the_field() {
+        let config_dir = repo_config_dir();
+        let content = std::fs::read_to_string(config_dir.join("models/gfs.yaml")).unwrap();
+
+        let broken = content.replacen("projection: geographic", "projection: polar", 1);
+        let errors = validate(&config_dir, ConfigKind::Model, "gfs", &broken);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with("grid.projection: "), "{}", errors[0]);
+
+        let errors = validate(&config_dir, ConfigKind::Model, "gfs2", &content);
+        assert!(errors[0].starts_with("model.id: "), "{:?}", errors);
+
+        let errors = validate(&config_dir, ConfigKind::Model, "gfs", "model: [unclosed");
+        assert!(errors[0].starts_with("YAML syntax error"), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_layer_style_file_must_exist() {
+        let config_dir = repo_config_dir();
+        let content = std::fs::read_to_string(config_dir.join("layers/gfs.yaml")).unwrap();
+        let broken = content.replacen("style_file: temperature.json", "style_file: nope.json", 1);
+
+        let errors = validate(&config_dir, ConfigKind::Layer, "gfs", &broken);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains("'nope.json'"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn test_style_schema_rejects_bad_colors() {
+        let style = r##"{"version": "1.0", "styles": {"default": {"type": "gradient",
+            "stops": [{"value": 0, "color": "red"}]}}}"##;
+        let errors = validate(&repo_config_dir(), ConfigKind::Style, "test", style);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(
+            errors[0].starts_with("styles.default.stops[0].color: "),
+            "{}",
+            errors[0]
+        );
+    }
+
+    #[test]
+    fn test_names_and_paths() {
+        assert!(is_valid_name("goes18"));
+        assert!(is_valid_name("wind_barbs"));
+        assert!(!is_valid_name("../models/gfs"));
+        assert!(!is_valid_name(""));
+
+        assert_eq!(
+            config_path(Path::new("config"), ConfigKind::Style, "wind"),
+            Path::new("config/styles/wind.json")
+        );
+        assert_eq!(ConfigKind::from_segment("layers"), Some(ConfigKind::Layer));
+        assert_eq!(ConfigKind::from_segment("layer"), None);
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = diff("a: 1\nb: 2\n", "a: 1\nb: 3\n", "v1", "v2");
+        assert_eq!(
+            diff,
+            "--- v1\n+++ v2\n@@ -1,2 +1,2 @@\n a: 1\n-b: 2\n+b: 3\n"
+        );
+        assert_eq!(field_path("/parameters/0/name"), "parameters[0].name");
+        assert_eq!(field_path(""), "(root)");
+    }
+}
@@ -0,0 +1,563 @@
+//! Bulk exports of a parameter across the forecast hours of a run.
+//!
+//! Researchers who want e.g. "all TMP 2 m from run X over CONUS" submit one
+//! export instead of scripting hundreds of GetMap requests. Each export runs
+//! as a background job: it reads every forecast hour of the run, stacks them
+//! into one NetCDF file or zipped Zarr store ([`grid_processor::GridSeries`]),
+//! uploads the bundle under `EXPORT_PREFIX` in the data bucket and hands out
+//! a presigned download URL. Jobs and their progress are kept in memory.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use grid_processor::{ExportFormat, GridCoordinates, GridSeries};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use storage::CatalogEntry;
+use tokio::sync::Mutex;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::common::parse_iso8601_timestamp;
+use crate::rendering::loaders::load_grid_data;
+use crate::state::AppState;
+
+/// Export settings.
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    /// Key prefix of exported bundles in the data bucket
+    pub prefix: String,
+    /// How long download URLs stay valid
+    pub url_ttl: Duration,
+    /// Largest export in grid values (time steps x grid points)
+    pub max_values: usize,
+    /// Finished jobs kept for status queries
+    pub max_retained_jobs: usize,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            prefix: "exports".to_string(),
+            url_ttl: Duration::from_secs(24 * 3600),
+            max_values: 100_000_000,
+            max_retained_jobs: 100,
+        }
+    }
+}
+
+impl ExportConfig {
+    /// Load configuration from environment variables.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(prefix) = env::var("EXPORT_PREFIX") {
+            config.prefix = prefix.trim_matches('/').to_string();
+        }
+
+        // S3 presigned URLs are valid for at most 7 days
+        if let Some(secs) = env::var("EXPORT_URL_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+        {
+            config.url_ttl = Duration::from_secs(secs.clamp(60, 7 * 24 * 3600));
+        }
+
+        if let Some(max) = env::var("EXPORT_MAX_VALUES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.max_values = max;
+        }
+
+        config
+    }
+}
+
+/// Request body for a new export.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportRequest {
+    pub model: String,
+    pub parameter: String,
+    /// Vertical level; defaults to the first level of the run
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Model run (ISO8601); defaults to the latest run
+    #[serde(default)]
+    pub run: Option<String>,
+    /// Subset as [min_lon, min_lat, max_lon, max_lat]; defaults to the full grid
+    #[serde(default)]
+    pub bbox: Option<[f64; 4]>,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+impl ExportRequest {
+    /// Check the request before a job is queued.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.is_empty() || self.parameter.is_empty() {
+            return Err("model and parameter are required".to_string());
+        }
+        if let Some(run) = &self.run {
+            if parse_iso8601_timestamp(run).is_none() {
+                return Err(format!("Invalid run '{}'", run));
+            }
+        }
+        if let Some([min_lon, min_lat, max_lon, max_lat]) = self.bbox {
+            if !(min_lon < max_lon && min_lat < max_lat) {
+                return Err("bbox must be [min_lon, min_lat, max_lon, max_lat]".to_string());
+            }
+            if !(-90.0..=90.0).contains(&min_lat) || !(-90.0..=90.0).contains(&max_lat) {
+                return Err("bbox latitudes must be within -90..90".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// State of an export job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Queued,
+    Running,
+    Complete,
+    Failed,
+}
+
+/// An export job as reported by the status endpoints.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportJob {
+    pub id: String,
+    pub model: String,
+    pub parameter: String,
+    pub level: Option<String>,
+    /// Model run, once resolved
+    pub reference_time: Option<DateTime<Utc>>,
+    pub format: ExportFormat,
+    pub status: ExportStatus,
+    /// Forecast hours read so far
+    pub steps_done: usize,
+    /// Forecast hours in the run
+    pub steps_total: usize,
+    /// Percentage of forecast hours read
+    pub progress: f64,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Object key of the bundle in the data bucket
+    pub storage_path: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub download_url: Option<String>,
+    pub url_expires_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+impl ExportJob {
+    fn new(request: &ExportRequest) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            model: request.model.clone(),
+            parameter: request.parameter.clone(),
+            level: request.level.clone(),
+            reference_time: None,
+            format: request.format,
+            status: ExportStatus::Queued,
+            steps_done: 0,
+            steps_total: 0,
+            progress: 0.0,
+            created_at: Utc::now(),
+            completed_at: None,
+            storage_path: None,
+            size_bytes: None,
+            download_url: None,
+            url_expires_at: None,
+            error: None,
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        matches!(self.status, ExportStatus::Complete | ExportStatus::Failed)
+    }
+}
+
+/// In-memory registry of export jobs, newest first.
+pub struct ExportJobs {
+    config: ExportConfig,
+    jobs: Mutex<VecDeque<ExportJob>>,
+}
+
+impl ExportJobs {
+    pub fn new(config: ExportConfig) -> Self {
+        Self {
+            config,
+            jobs: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ExportConfig {
+        &self.config
+    }
+
+    /// Register a queued job, dropping the oldest finished jobs over the limit.
+    async fn insert(&self, job: ExportJob) {
+        let mut jobs = self.jobs.lock().await;
+        jobs.push_front(job);
+        while jobs.len() > self.config.max_retained_jobs {
+            match jobs.iter().rposition(ExportJob::is_finished) {
+                Some(idx) => jobs.remove(idx),
+                None => break,
+            };
+        }
+    }
+
+    async fn update(&self, id: &str, f: impl FnOnce(&mut ExportJob)) {
+        if let Some(job) = self.jobs.lock().await.iter_mut().find(|j| j.id == id) {
+            f(job);
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Option<ExportJob> {
+        self.jobs.lock().await.iter().find(|j| j.id == id).cloned()
+    }
+
+    pub async fn list(&self) -> Vec<ExportJob> {
+        self.jobs.lock().await.iter().cloned().collect()
+    }
+}
+
+/// Queue an export and run it in the background.
+pub async fn start_export(state: Arc<AppState>, request: ExportRequest) -> ExportJob {
+    let job = ExportJob::new(&request);
+    state.exports.insert(job.clone()).await;
+
+    let id = job.id.clone();
+    tokio::spawn(async move {
+        state
+            .exports
+            .update(&id, |job| job.status = ExportStatus::Running)
+            .await;
+
+        match run_export(&state, &id, &request).await {
+            Ok(()) => info!(id = %id, "Export complete"),
+            Err(e) => {
+                error!(id = %id, error = %e, "Export failed");
+                state
+                    .exports
+                    .update(&id, |job| {
+                        job.status = ExportStatus::Failed;
+                        job.completed_at = Some(Utc::now());
+                        job.error = Some(e);
+                    })
+                    .await;
+            }
+        }
+    });
+
+    job
+}
+
+async fn run_export(
+    state: &Arc<AppState>,
+    id: &str,
+    request: &ExportRequest,
+) -> Result<(), String> {
+    let config = state.exports.config();
+    let (model, parameter) = (&request.model, &request.parameter);
+
+    let reference_time = match &request.run {
+        Some(run) => {
+            parse_iso8601_timestamp(run).ok_or_else(|| format!("Invalid run '{}'", run))?
+        }
+        None => state
+            .catalog
+            .get_available_runs(model, parameter)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| format!("No runs available for {}/{}", model, parameter))?,
+    };
+
+    let entries = state
+        .catalog
+        .get_run_entries(model, reference_time)
+        .await
+        .map_err(|e| e.to_string())?;
+    let entries = run_steps(entries, parameter, request.level.as_deref());
+    let Some(level) = entries.first().map(|e| e.level.clone()) else {
+        return Err(format!(
+            "No {}/{} data for run {}",
+            model,
+            parameter,
+            reference_time.to_rfc3339()
+        ));
+    };
+
+    // Projected grids can only be read whole
+    let requires_full_grid = state.model_dimensions.requires_full_grid(model);
+    if requires_full_grid && request.bbox.is_some() {
+        return Err(format!(
+            "{} uses a projected grid; bbox subsets are not supported",
+            model
+        ));
+    }
+    let bbox = request.bbox.map(|b| b.map(|v| v as f32));
+
+    let steps_total = entries.len();
+    state
+        .exports
+        .update(id, |job| {
+            job.level = Some(level.clone());
+            job.reference_time = Some(reference_time);
+            job.steps_total = steps_total;
+        })
+        .await;
+    info!(
+        id = %id,
+        model = %model,
+        parameter = %parameter,
+        level = %level,
+        reference_time = %reference_time,
+        steps = steps_total,
+        "Export started"
+    );
+
+    let mut series: Option<GridSeries> = None;
+    for (step, entry) in entries.iter().enumerate() {
+        let grid = load_grid_data(
+            &state.grid_processor_factory,
+            entry,
+            bbox,
+            None,
+            requires_full_grid,
+        )
+        .await?;
+
+        // The first forecast hour fixes the grid of the export
+        if series.is_none() {
+            let values = grid.width * grid.height * steps_total;
+            if values > config.max_values {
+                return Err(format!(
+                    "Export of {} values exceeds the limit of {}; use a smaller bbox",
+                    values, config.max_values
+                ));
+            }
+            let (lon, lat) = grid_axes(
+                grid.width,
+                grid.height,
+                grid.bbox,
+                grid.coordinates.as_ref(),
+            );
+            series = Some(GridSeries::new(
+                parameter,
+                &level,
+                &grid.native_units,
+                reference_time,
+                lon,
+                lat,
+            ));
+        }
+        if let Some(series) = &mut series {
+            series
+                .push(entry.forecast_hour, &grid.data)
+                .map_err(|e| e.to_string())?;
+        }
+
+        state
+            .exports
+            .update(id, |job| {
+                job.steps_done = step + 1;
+                job.progress = (step + 1) as f64 / steps_total as f64 * 100.0;
+            })
+            .await;
+    }
+
+    let series = series.ok_or("No data read")?;
+    let format = request.format;
+    let data = tokio::task::spawn_blocking(move || series.encode(format))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let size_bytes = data.len() as u64;
+
+    let storage_path = format!(
+        "{}/{}/{}_{}_{}.{}",
+        config.prefix,
+        id,
+        model,
+        parameter,
+        reference_time.format("%Y%m%dT%H%MZ"),
+        format.extension()
+    );
+    ingestion::upload_file(
+        &state.storage,
+        &storage_path,
+        Bytes::from(data),
+        &ingestion::UploadConfig::from_env(),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let download_url = state
+        .storage
+        .signed_url(&storage_path, config.url_ttl)
+        .await
+        .map_err(|e| e.to_string())?;
+    let now = Utc::now();
+    let url_expires_at = chrono::Duration::from_std(config.url_ttl)
+        .ok()
+        .map(|ttl| now + ttl);
+
+    state
+        .exports
+        .update(id, |job| {
+            job.status = ExportStatus::Complete;
+            job.completed_at = Some(now);
+            job.storage_path = Some(storage_path);
+            job.size_bytes = Some(size_bytes);
+            job.download_url = Some(download_url);
+            job.url_expires_at = url_expires_at;
+        })
+        .await;
+
+    Ok(())
+}
+
+/// Datasets of one parameter and level in a run, one per forecast hour.
+///
+/// Without a level, the first level of the run is used.
+fn run_steps(
+    mut entries: Vec<CatalogEntry>,
+    parameter: &str,
+    level: Option<&str>,
+) -> Vec<CatalogEntry> {
+    entries.retain(|e| e.parameter == parameter);
+    let level = level
+        .map(str::to_string)
+        .or_else(|| entries.first().map(|e| e.level.clone()));
+    entries.retain(|e| Some(&e.level) == level.as_ref());
+    entries.sort_by_key(|e| e.forecast_hour);
+    entries.dedup_by_key(|e| e.forecast_hour);
+    entries
+}
+
+/// Longitude and latitude of the columns and rows of a loaded grid.
+///
+/// Regular grids carry no coordinates; their bbox spans whole cells with
+/// points at its west/north edge (see [`GridCoordinates::regular`]).
+fn grid_axes(
+    width: usize,
+    height: usize,
+    bbox: Option<[f32; 4]>,
+    coordinates: Option<&GridCoordinates>,
+) -> (Vec<f64>, Vec<f64>) {
+    if let Some(coords) = coordinates {
+        return (coords.lon.values(), coords.lat.values());
+    }
+    let [min_lon, min_lat, max_lon, max_lat] = bbox.unwrap_or_default().map(f64::from);
+    let step_x = (max_lon - min_lon) / width.max(1) as f64;
+    let step_y = (max_lat - min_lat) / height.max(1) as f64;
+    let lon = (0..width).map(|i| min_lon + i as f64 * step_x).collect();
+    let lat = (0..height).map(|j| max_lat - j as f64 * step_y).collect();
+    (lon, lat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(parameter: &str, level: &str, forecast_hour: u32) -> CatalogEntry {
+        CatalogEntry {
+            model: "gfs".to_string(),
+            parameter: parameter.to_string(),
+            level: level.to_string(),
+            reference_time: Utc.with_ymd_and_hms(2024, 12, 17, 12, 0, 0).unwrap(),
+            forecast_hour,
+            bbox: wms_common::BoundingBox::new(0.0, -90.0, 360.0, 90.0),
+            storage_path: String::new(),
+            file_size: 0,
+            zarr_metadata: None,
+            ensemble_member: None,
+            lineage: None,
+        }
+    }
+
+    #[test]
+    fn test_run_steps_selects_one_level() {
+        let entries = vec![
+            entry("TMP", "2 m above ground", 6),
+            entry("TMP", "2 m above ground", 0),
+            entry("TMP", "500 mb", 0),
+            entry("UGRD", "2 m above ground", 3),
+            entry("TMP", "2 m above ground", 6),
+        ];
+
+        let steps = run_steps(entries.clone(), "TMP", None);
+        let hours: Vec<u32> = steps.iter().map(|e| e.forecast_hour).collect();
+        assert_eq!(hours, vec![0, 6]);
+        assert!(steps.iter().all(|e| e.level == "2 m above ground"));
+
+        let steps = run_steps(entries, "TMP", Some("500 mb"));
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].level, "500 mb");
+    }
+
+    #[test]
+    fn test_request_validation() {
+        let request: ExportRequest = serde_json::from_value(serde_json::json!({
+            "model": "gfs",
+            "parameter": "TMP",
+            "bbox": [-125.0, 24.0, -66.0, 50.0],
+            "format": "zarr"
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+        assert_eq!(request.format, ExportFormat::Zarr);
+
+        let bad_bbox = ExportRequest {
+            bbox: Some([-66.0, 24.0, -125.0, 50.0]),
+            ..request.clone()
+        };
+        assert!(bad_bbox.validate().is_err());
+
+        let bad_run = ExportRequest {
+            run: Some("yesterday".to_string()),
+            ..request
+        };
+        assert!(bad_run.validate().is_err());
+    }
+
+    #[test]
+    fn test_regular_grid_axes() {
+        let (lon, lat) = grid_axes(4, 2, Some([-100.0, 30.0, -96.0, 32.0]), None);
+        assert_eq!(lon, vec![-100.0, -99.0, -98.0, -97.0]);
+        assert_eq!(lat, vec![32.0, 31.0]);
+    }
+
+    #[tokio::test]
+    async fn test_finished_jobs_are_evicted_first() {
+        let jobs = ExportJobs::new(ExportConfig {
+            max_retained_jobs: 2,
+            ..ExportConfig::default()
+        });
+        let request: ExportRequest =
+            serde_json::from_value(serde_json::json!({"model": "gfs", "parameter": "TMP"}))
+                .unwrap();
+
+        let running = ExportJob::new(&request);
+        let running_id = running.id.clone();
+        jobs.insert(running).await;
+        let mut done = ExportJob::new(&request);
+        done.status = ExportStatus::Complete;
+        let done_id = done.id.clone();
+        jobs.insert(done).await;
+        jobs.insert(ExportJob::new(&request)).await;
+
+        assert_eq!(jobs.list().await.len(), 2);
+        assert!(jobs.get(&running_id).await.is_some());
+        assert!(jobs.get(&done_id).await.is_none());
+    }
+}
@@ -2,8 +2,9 @@
 //!
 //! Provides endpoints for:
 //! - Listing available forecast times
-//! - Listing available parameters
+//! - Listing available parameters and their display metadata
 //! - Listing recent ingestion events
+//! - Mapping fixed animation frames to observation times
 
 use axum::{
     extract::{Extension, Path, Query},
@@ -12,9 +13,20 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use storage::ParameterMetadata;
 use tracing::{info, instrument};
 
+use super::common::parse_iso8601_timestamp;
+use crate::layer_config::parse_iso8601_duration;
 use crate::state::AppState;
+use crate::time_match::{frame_times, match_frames, FrameMatch};
+use crate::update_cadence::format_iso8601_duration;
+
+/// Frames in an animation when the request doesn't say.
+const DEFAULT_ANIMATION_FRAMES: usize = 12;
+
+/// Most frames one animation request may ask for.
+const MAX_ANIMATION_FRAMES: usize = 200;
 
 // ============================================================================
 // Response Types
@@ -31,6 +43,17 @@ pub struct ForecastTimesResponse {
 pub struct ParametersResponse {
     pub model: String,
     pub parameters: Vec<String>,
+    /// Long name, units, default style and valid range of the parameters
+    /// ingested so far
+    pub metadata: Vec<ParameterMetadata>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnimationFramesResponse {
+    pub layer: String,
+    /// Frame spacing (ISO 8601 duration)
+    pub step: String,
+    pub frames: Vec<FrameMatch>,
 }
 
 #[derive(Debug, Serialize)]
@@ -56,6 +79,16 @@ pub struct IngestionEventsQuery {
     pub limit: Option<i32>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AnimationFramesQuery {
+    /// Last frame time (default: latest observation)
+    pub end: Option<String>,
+    /// Frame spacing as an ISO 8601 duration (default: the model's cadence)
+    pub step: Option<String>,
+    /// Number of frames
+    pub count: Option<usize>,
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
@@ -87,7 +120,11 @@ pub async fn forecast_times_handler(
     }))
 }
 
-/// GET /api/parameters/:model - Get available parameters
+/// GET /api/parameters/:model - Get available parameters and their metadata
+///
+/// Besides the parameters in the catalog, lists the virtual parameters of
+/// layers computed from them (temporal composites, ensemble products) once
+/// their source parameter has data.
 #[instrument(skip(state))]
 pub async fn parameters_handler(
     Extension(state): Extension<Arc<AppState>>,
@@ -95,12 +132,106 @@ pub async fn parameters_handler(
 ) -> Result<Json<ParametersResponse>, StatusCode> {
     info!(model = %model, "Parameters request");
 
-    let parameters = state.catalog.list_parameters(&model).await.map_err(|e| {
+    let mut parameters = state.catalog.list_parameters(&model).await.map_err(|e| {
         tracing::error!(error = %e, "Failed to list parameters");
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    Ok(Json(ParametersResponse { model, parameters }))
+    if let Some(model_config) = state.layer_configs.read().await.get_model(&model) {
+        let virtual_parameters: Vec<String> = model_config
+            .layers
+            .iter()
+            .filter(|l| l.is_reduced() && parameters.iter().any(|p| p == l.source_parameter()))
+            .map(|l| l.parameter.clone())
+            .filter(|p| !parameters.contains(p))
+            .collect();
+        parameters.extend(virtual_parameters);
+    }
+
+    let metadata = state
+        .catalog
+        .list_parameter_metadata(&model)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list parameter metadata");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ParametersResponse {
+        model,
+        parameters,
+        metadata,
+    }))
+}
+
+/// GET /api/animation-frames/:layer - Observation shown in each animation frame
+///
+/// Frames are evenly spaced and end at a round time; each is mapped to the
+/// nearest observation (see [`match_frames`]). Only observation layers
+/// have frames.
+#[instrument(skip(state))]
+pub async fn animation_frames_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(layer): Path<String>,
+    Query(query): Query<AnimationFramesQuery>,
+) -> Result<Json<AnimationFramesResponse>, StatusCode> {
+    info!(layer = %layer, "Animation frames request");
+
+    let parameter = {
+        let layer_configs = state.layer_configs.read().await;
+        let layer_config = layer_configs
+            .get_layer(&layer)
+            .ok_or(StatusCode::NOT_FOUND)?;
+        layer_config.source_parameter().to_string()
+    };
+    let model = layer.split('_').next().unwrap_or(&layer);
+    if !state.model_dimensions.is_observation(model) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let step = match query.step.as_deref() {
+        Some(step) => parse_iso8601_duration(step).ok_or(StatusCode::BAD_REQUEST)?,
+        None => {
+            let cadence = state.update_cadence.cadence(model, &state.model_dimensions);
+            chrono::Duration::from_std(cadence).map_err(|_| StatusCode::BAD_REQUEST)?
+        }
+    };
+    let step_std = step.to_std().map_err(|_| StatusCode::BAD_REQUEST)?;
+    if step_std.is_zero() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let count = query
+        .count
+        .unwrap_or(DEFAULT_ANIMATION_FRAMES)
+        .clamp(1, MAX_ANIMATION_FRAMES);
+
+    let available = state
+        .catalog
+        .get_available_times(model, &parameter)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to get available times");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let end = match query.end.as_deref() {
+        Some(end) => Some(parse_iso8601_timestamp(end).ok_or(StatusCode::BAD_REQUEST)?),
+        None => available.iter().max().copied(),
+    };
+    let frames = match end {
+        Some(end) => match_frames(
+            &frame_times(end, step, count),
+            &available,
+            state.time_tolerance(model),
+        ),
+        None => Vec::new(),
+    };
+
+    Ok(Json(AnimationFramesResponse {
+        layer,
+        step: format_iso8601_duration(step_std),
+        frames,
+    }))
 }
 
 /// GET /api/ingestion/events - Get recent ingestion events
@@ -161,11 +292,46 @@ mod tests {
         let response = ParametersResponse {
             model: "hrrr".to_string(),
             parameters: vec!["TMP".to_string(), "UGRD".to_string(), "VGRD".to_string()],
+            metadata: vec![ParameterMetadata {
+                model: "hrrr".to_string(),
+                parameter: "TMP".to_string(),
+                long_name: "Temperature".to_string(),
+                units: "K".to_string(),
+                default_style: Some("temperature".to_string()),
+                valid_min: Some(150.0),
+                valid_max: Some(350.0),
+            }],
         };
 
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("\"model\":\"hrrr\""));
         assert!(json.contains("TMP"));
+        assert!(json.contains("\"long_name\":\"Temperature\""));
+        assert!(json.contains("\"units\":\"K\""));
+    }
+
+    #[test]
+    fn test_animation_frames_response_serialization() {
+        use chrono::TimeZone;
+
+        let frame = chrono::Utc.with_ymd_and_hms(2024, 12, 3, 0, 5, 0).unwrap();
+        let response = AnimationFramesResponse {
+            layer: "goes16_CMI_C13".to_string(),
+            step: "PT5M".to_string(),
+            frames: vec![
+                FrameMatch {
+                    frame,
+                    time: Some(frame + chrono::Duration::seconds(17)),
+                },
+                FrameMatch { frame, time: None },
+            ],
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(
+            json.contains("{\"frame\":\"2024-12-03T00:05:00Z\",\"time\":\"2024-12-03T00:05:17Z\"}")
+        );
+        assert!(json.contains("\"time\":null"));
     }
 
     #[test]
@@ -1,98 +1,406 @@
 //! Load test and benchmark result handlers.
+//!
+//! Results are kept in Postgres (see [`storage::BenchmarkStore`]): the
+//! `load-test` tool and `scripts/run_benchmarks.sh` publish to the admin
+//! endpoints, and the dashboards read from the public ones.
 
 use axum::{
-    extract::Path,
+    extract::{Path, Query},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use storage::{
+    CriterionResult, LoadTestQuery, LoadTestRun, NewCriterionResult, NewLoadTestRun, RunTags,
+};
+use tracing::{info, warn};
+use uuid::Uuid;
+use wms_common::WmsError;
 
-/// GET /api/loadtest/results - Serve historical load test results
-pub async fn loadtest_results_handler() -> impl IntoResponse {
-    let results_dir = std::env::var("LOADTEST_RESULTS_DIR")
-        .unwrap_or_else(|_| "validation/load-test/results".to_string());
+use crate::state::AppState;
 
-    match std::fs::read_dir(&results_dir) {
-        Ok(entries) => {
-            let mut results = Vec::new();
-            for entry in entries.flatten() {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.ends_with(".jsonl") {
-                        results.push(name.to_string());
-                    }
-                }
-            }
-            results.sort();
-            results.reverse(); // Most recent first
-            Json(serde_json::json!({ "files": results })).into_response()
+/// Change of a benchmark's mean, in percent, reported as improved/regressed.
+const CRITERION_NOISE_PCT: f64 = 2.0;
+
+const BENCHMARK_STORE_UNAVAILABLE: &str = "Benchmark history requires the Postgres catalog";
+
+/// Response when the catalog isn't backed by Postgres.
+fn no_benchmark_store() -> Response {
+    (StatusCode::SERVICE_UNAVAILABLE, BENCHMARK_STORE_UNAVAILABLE).into_response()
+}
+
+/// Map a storage error to a response.
+fn store_error(context: &str, e: WmsError) -> Response {
+    let status = match e {
+        WmsError::InvalidParameter { .. } => StatusCode::BAD_REQUEST,
+        WmsError::DataNotAvailable(_) => StatusCode::NOT_FOUND,
+        _ => {
+            warn!(error = %e, "{}", context);
+            StatusCode::INTERNAL_SERVER_ERROR
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to read results: {}", e),
+    };
+    (status, format!("{}: {}", context, e)).into_response()
+}
+
+// ============================================================================
+// Load test runs
+// ============================================================================
+
+/// GET /api/loadtest/results - Stored load test runs, newest first
+///
+/// Filters: `scenario`, `git_sha` (or a prefix), `config_hash`, `limit`.
+pub async fn loadtest_results_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(query): Query<LoadTestQuery>,
+) -> Response {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return no_benchmark_store();
+    };
+    match store.list_load_tests(&query).await {
+        Ok(runs) => Json(serde_json::json!({ "runs": runs })).into_response(),
+        Err(e) => store_error("Failed to list load test runs", e),
+    }
+}
+
+/// POST /api/admin/benchmarks/loadtest - Store the JSON results of a load test run
+///
+/// Query parameters `git_sha`, `git_branch` and `config_hash` tag the run;
+/// missing tags are taken from the results' `git_info` and `system_config`.
+pub async fn loadtest_record_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(tags): Query<RunTags>,
+    Json(results): Json<serde_json::Value>,
+) -> Response {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return no_benchmark_store();
+    };
+    let run = match NewLoadTestRun::from_results(results, tags) {
+        Ok(run) => run,
+        Err(e) => return store_error("Invalid load test results", e),
+    };
+    match store.record_load_test(&run).await {
+        Ok(id) => {
+            info!(
+                run_id = %id,
+                scenario = %run.scenario,
+                git_sha = ?run.tags.git_sha,
+                "Recorded load test run"
+            );
+            (
+                StatusCode::CREATED,
+                Json(serde_json::json!({ "id": id, "tags": run.tags })),
+            )
+                .into_response()
+        }
+        Err(e) => store_error("Failed to record load test run", e),
+    }
+}
+
+/// POST /api/admin/benchmarks/loadtest/:id/requests - Append to a run's request log
+///
+/// The body is JSON Lines, one request per line, as written by
+/// `load-test run --log-requests`. Large logs can be sent in several parts.
+pub async fn loadtest_requests_record_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    body: String,
+) -> Response {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return no_benchmark_store();
+    };
+    let entries = match parse_json_lines(&body) {
+        Ok(entries) => entries,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
+    match store.append_load_test_requests(id, &entries).await {
+        Ok(stored) => Json(serde_json::json!({ "stored": stored })).into_response(),
+        Err(e) => store_error("Failed to store request log", e),
+    }
+}
+
+/// GET /api/loadtest/files - Runs with a stored request log
+///
+/// Listed as files for the tile visualizer: `path` is the URL of the log.
+pub async fn loadtest_files_handler(Extension(state): Extension<Arc<AppState>>) -> Response {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return no_benchmark_store();
+    };
+    match store.list_load_tests(&LoadTestQuery::default()).await {
+        Ok(runs) => Json(
+            runs.iter()
+                .filter(|run| run.logged_requests > 0)
+                .map(|run| {
+                    serde_json::json!({
+                        "name": request_log_name(run),
+                        "path": format!("/api/loadtest/file/{}", run.id),
+                        "size": format!("{} requests", run.logged_requests),
+                        "run_id": run.id,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => store_error("Failed to list request logs", e),
+    }
+}
+
+/// GET /api/loadtest/file/:id - A run's request log as JSON Lines
+pub async fn loadtest_file_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Response {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return no_benchmark_store();
+    };
+    match store.load_test_requests(id).await {
+        Ok(entries) if entries.is_empty() => (
+            StatusCode::NOT_FOUND,
+            format!("No request log for run {}", id),
         )
             .into_response(),
+        Ok(entries) => {
+            let mut content = String::new();
+            for entry in entries {
+                content.push_str(&entry.to_string());
+                content.push('\n');
+            }
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, "application/x-ndjson")
+                .body(content.into())
+                .unwrap()
+        }
+        Err(e) => store_error("Failed to read request log", e),
     }
 }
 
-/// GET /api/loadtest/files - List available JSONL result files
-pub async fn loadtest_files_handler() -> impl IntoResponse {
-    loadtest_results_handler().await
+/// Parse a JSON Lines body, skipping blank lines.
+fn parse_json_lines(body: &str) -> Result<Vec<serde_json::Value>, String> {
+    body.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("Invalid JSON on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+/// File name the `load-test` tool would have given a run's request log.
+fn request_log_name(run: &LoadTestRun) -> String {
+    format!(
+        "{}_{}.jsonl",
+        run.scenario.replace(' ', "_").to_lowercase(),
+        run.started_at.format("%Y%m%d_%H%M%S")
+    )
 }
 
-/// GET /api/loadtest/file/:filename - Serve specific load test file
-pub async fn loadtest_file_handler(Path(filename): Path<String>) -> Response {
-    // Sanitize filename to prevent path traversal
-    if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
-        return Response::builder()
-            .status(StatusCode::BAD_REQUEST)
-            .body("Invalid filename".into())
-            .unwrap();
+// ============================================================================
+// Criterion and trends
+// ============================================================================
+
+/// GET /api/criterion - Latest Criterion result of every benchmark
+///
+/// Grouped by benchmark group, with the change against each benchmark's
+/// previous result, for `web/benchmarks.html`.
+pub async fn criterion_benchmarks_handler(
+    Extension(state): Extension<Arc<AppState>>,
+) -> impl IntoResponse {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return Json(serde_json::json!({
+            "error": BENCHMARK_STORE_UNAVAILABLE
+        }));
+    };
+    match store.latest_criterion().await {
+        Ok(results) => Json(criterion_groups(&results)),
+        Err(e) => {
+            warn!(error = %e, "Failed to read Criterion results");
+            Json(serde_json::json!({ "error": e.to_string() }))
+        }
     }
+}
 
-    let results_dir = std::env::var("LOADTEST_RESULTS_DIR")
-        .unwrap_or_else(|_| "validation/load-test/results".to_string());
-    let filepath = format!("{}/{}", results_dir, filename);
-
-    match std::fs::read_to_string(&filepath) {
-        Ok(content) => Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "application/x-ndjson")
-            .body(content.into())
-            .unwrap(),
-        Err(_) => Response::builder()
-            .status(StatusCode::NOT_FOUND)
-            .body("File not found".into())
-            .unwrap(),
+/// Body of `POST /api/admin/benchmarks/criterion`.
+#[derive(Debug, Deserialize)]
+pub struct CriterionUpload {
+    pub benchmarks: Vec<CriterionUploadEntry>,
+}
+
+/// One benchmark's `estimates.json`, under its Criterion ID.
+#[derive(Debug, Deserialize)]
+pub struct CriterionUploadEntry {
+    pub name: String,
+    pub estimates: serde_json::Value,
+}
+
+/// POST /api/admin/benchmarks/criterion - Store the results of a Criterion run
+///
+/// Tagged like load test runs through `git_sha`, `git_branch` and
+/// `config_hash` query parameters.
+pub async fn criterion_record_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(tags): Query<RunTags>,
+    Json(upload): Json<CriterionUpload>,
+) -> Response {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return no_benchmark_store();
+    };
+
+    let mut results = Vec::with_capacity(upload.benchmarks.len());
+    for entry in upload.benchmarks {
+        match NewCriterionResult::from_estimates(&entry.name, entry.estimates) {
+            Some(result) => results.push(result),
+            None => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "Missing mean, median or std_dev estimate for '{}'",
+                        entry.name
+                    ),
+                )
+                    .into_response()
+            }
+        }
+    }
+
+    match store.record_criterion(&results, &tags).await {
+        Ok(stored) => {
+            info!(benchmarks = stored, git_sha = ?tags.git_sha, "Recorded Criterion results");
+            (
+                StatusCode::CREATED,
+                Json(serde_json::json!({ "stored": stored })),
+            )
+                .into_response()
+        }
+        Err(e) => store_error("Failed to record Criterion results", e),
     }
 }
 
-/// GET /api/benchmarks/criterion - Criterion microbenchmark results
-pub async fn criterion_benchmarks_handler() -> impl IntoResponse {
-    let target_dir = std::env::var("CARGO_TARGET_DIR").unwrap_or_else(|_| "target".to_string());
-    let criterion_dir = format!("{}/criterion", target_dir);
-
-    let mut benchmarks = Vec::new();
-
-    if let Ok(entries) = std::fs::read_dir(&criterion_dir) {
-        for entry in entries.flatten() {
-            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
-                if let Some(name) = entry.file_name().to_str() {
-                    // Try to read the estimates.json for this benchmark
-                    let estimates_path = format!("{}/{}/new/estimates.json", criterion_dir, name);
-                    if let Ok(content) = std::fs::read_to_string(&estimates_path) {
-                        if let Ok(estimates) = serde_json::from_str::<serde_json::Value>(&content) {
-                            benchmarks.push(serde_json::json!({
-                                "name": name,
-                                "estimates": estimates
-                            }));
-                        }
-                    }
+/// Query for `GET /api/benchmarks/trends`: one of `scenario` or `benchmark`.
+#[derive(Debug, Deserialize)]
+pub struct TrendQuery {
+    pub scenario: Option<String>,
+    pub benchmark: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// GET /api/benchmarks/trends - History of a scenario or benchmark, oldest first
+///
+/// Each point carries the run's git SHA and config hash, so charts can mark
+/// where the code or the configuration changed.
+pub async fn benchmark_trends_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(query): Query<TrendQuery>,
+) -> Response {
+    let Some(store) = state.benchmarks.as_ref() else {
+        return no_benchmark_store();
+    };
+    let limit = query.limit.unwrap_or(100);
+
+    match (query.scenario, query.benchmark) {
+        (Some(scenario), None) => {
+            let runs = store
+                .list_load_tests(&LoadTestQuery {
+                    scenario: Some(scenario.clone()),
+                    limit: Some(limit),
+                    ..Default::default()
+                })
+                .await;
+            match runs {
+                Ok(runs) => {
+                    let points: Vec<_> = runs
+                        .iter()
+                        .rev()
+                        .map(|run| {
+                            serde_json::json!({
+                                "run_id": run.id,
+                                "timestamp": run.started_at,
+                                "git_sha": run.tags.git_sha,
+                                "config_hash": run.tags.config_hash,
+                                "requests_per_second": run.requests_per_second,
+                                "latency_p50": run.latency_p50,
+                                "latency_p90": run.latency_p90,
+                                "latency_p99": run.latency_p99,
+                                "cache_hit_rate": run.cache_hit_rate,
+                            })
+                        })
+                        .collect();
+                    Json(serde_json::json!({ "scenario": scenario, "points": points }))
+                        .into_response()
                 }
+                Err(e) => store_error("Failed to read load test trend", e),
             }
         }
+        (None, Some(benchmark)) => match store.criterion_trend(&benchmark, limit).await {
+            Ok(results) => {
+                let points: Vec<_> = results
+                    .iter()
+                    .map(|result| {
+                        serde_json::json!({
+                            "timestamp": result.recorded_at,
+                            "git_sha": result.tags.git_sha,
+                            "config_hash": result.tags.config_hash,
+                            "mean_ns": result.mean_ns,
+                            "median_ns": result.median_ns,
+                            "std_dev_ns": result.std_dev_ns,
+                        })
+                    })
+                    .collect();
+                Json(serde_json::json!({ "benchmark": benchmark, "points": points }))
+                    .into_response()
+            }
+            Err(e) => store_error("Failed to read benchmark trend", e),
+        },
+        _ => (
+            StatusCode::BAD_REQUEST,
+            "Specify exactly one of 'scenario' or 'benchmark'",
+        )
+            .into_response(),
     }
+}
 
-    Json(serde_json::json!({ "benchmarks": benchmarks }))
+/// Group latest Criterion results as `web/benchmarks.html` renders them.
+fn criterion_groups(results: &[CriterionResult]) -> serde_json::Value {
+    let mut groups: BTreeMap<&str, Vec<serde_json::Value>> = BTreeMap::new();
+    for result in results {
+        let (group, function_id) = result
+            .benchmark
+            .split_once('/')
+            .unwrap_or((result.benchmark.as_str(), result.benchmark.as_str()));
+        let change_pct = result.change_pct();
+        let change_direction = match change_pct {
+            Some(pct) if pct <= -CRITERION_NOISE_PCT => "improved",
+            Some(pct) if pct >= CRITERION_NOISE_PCT => "regressed",
+            _ => "unchanged",
+        };
+        groups.entry(group).or_default().push(serde_json::json!({
+            "function_id": function_id,
+            "full_id": result.benchmark,
+            "mean_ns": result.mean_ns,
+            "mean_ms": result.mean_ns / 1_000_000.0,
+            "median_ns": result.median_ns,
+            "std_dev_ns": result.std_dev_ns,
+            "throughput": null,
+            "change_pct": change_pct,
+            "change_direction": change_direction,
+            "git_sha": result.tags.git_sha,
+            "config_hash": result.tags.config_hash,
+            "recorded_at": result.recorded_at,
+        }));
+    }
+
+    let total_benchmarks = results.len();
+    let groups: Vec<_> = groups
+        .into_iter()
+        .map(|(name, benchmarks)| serde_json::json!({ "name": name, "benchmarks": benchmarks }))
+        .collect();
+    serde_json::json!({
+        "total_groups": groups.len(),
+        "total_benchmarks": total_benchmarks,
+        "groups": groups,
+    })
 }
 
 /// GET /api/benchmarks - Benchmark results with git metadata
@@ -135,24 +443,41 @@ pub async fn loadtest_dashboard_handler() -> impl IntoResponse {
         th, td { border: 1px solid #ddd; padding: 8px; text-align: left; }
         th { background-color: #4CAF50; color: white; }
         tr:nth-child(even) { background-color: #f2f2f2; }
+        code { font-size: 0.9em; }
     </style>
 </head>
 <body>
     <h1>Load Test Results</h1>
     <div id="results">Loading...</div>
     <script>
+        const cell = v => '<td>' + (v === null || v === undefined ? '-' : v) + '</td>';
+        const short = v => v ? '<code>' + v.slice(0, 8) + '</code>' : null;
         fetch('/api/loadtest/results')
-            .then(r => r.json())
+            .then(r => {
+                if (!r.ok) return r.text().then(t => { throw new Error(t); });
+                return r.json();
+            })
             .then(data => {
-                if (data.files && data.files.length > 0) {
+                if (data.runs && data.runs.length > 0) {
                     document.getElementById('results').innerHTML =
-                        '<ul>' + data.files.map(f => '<li><a href="/api/loadtest/file/' + f + '">' + f + '</a></li>').join('') + '</ul>';
+                        '<table><tr><th>Started</th><th>Scenario</th><th>Git SHA</th><th>Config</th>' +
+                        '<th>Req/s</th><th>p50 (ms)</th><th>p99 (ms)</th><th>Cache hit %</th><th>Request log</th></tr>' +
+                        data.runs.map(run => '<tr>' +
+                            cell(run.started_at) + cell(run.scenario) + cell(short(run.git_sha)) +
+                            cell(short(run.config_hash)) + cell(run.requests_per_second.toFixed(1)) +
+                            cell(run.latency_p50.toFixed(2)) + cell(run.latency_p99.toFixed(2)) +
+                            cell(run.cache_hit_rate.toFixed(1)) +
+                            cell(run.logged_requests > 0
+                                ? '<a href="/api/loadtest/file/' + run.id + '">' + run.logged_requests + ' requests</a>'
+                                : null) +
+                            '</tr>').join('') +
+                        '</table>';
                 } else {
                     document.getElementById('results').innerHTML = '<p>No results found</p>';
                 }
             })
             .catch(e => {
-                document.getElementById('results').innerHTML = '<p>Error: ' + e + '</p>';
+                document.getElementById('results').innerHTML = '<p>Error: ' + e.message + '</p>';
             });
     </script>
 </body>
@@ -167,11 +492,82 @@ pub async fn loadtest_dashboard_handler() -> impl IntoResponse {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn criterion_result(benchmark: &str, mean_ns: f64, previous: Option<f64>) -> CriterionResult {
+        CriterionResult {
+            benchmark: benchmark.to_string(),
+            recorded_at: Utc::now(),
+            tags: RunTags {
+                git_sha: Some("0a9d2a3".to_string()),
+                ..Default::default()
+            },
+            mean_ns,
+            median_ns: mean_ns,
+            std_dev_ns: 1.0,
+            previous_mean_ns: previous,
+            estimates: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_parse_json_lines() {
+        let entries = parse_json_lines("{\"z\":3}\n\n{\"z\":4}\n").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1]["z"], 4);
+
+        let err = parse_json_lines("{\"z\":3}\nnot json\n").unwrap_err();
+        assert!(err.contains("line 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_request_log_name() {
+        let run = LoadTestRun {
+            id: Uuid::nil(),
+            scenario: "GOES Random Temporal".to_string(),
+            started_at: Utc.with_ymd_and_hms(2025, 12, 2, 19, 42, 58).unwrap(),
+            tags: RunTags::default(),
+            requests_per_second: 258.8,
+            latency_p50: 0.8,
+            latency_p90: 123.5,
+            latency_p99: 140.9,
+            cache_hit_rate: 51.3,
+            total_requests: 23299,
+            failed_requests: 0,
+            logged_requests: 23299,
+            recorded_at: Utc::now(),
+            results: serde_json::json!({}),
+        };
+        assert_eq!(
+            request_log_name(&run),
+            "goes_random_temporal_20251202_194258.jsonl"
+        );
+    }
+
     #[test]
-    fn test_filename_sanitization() {
-        // The handler should reject these
-        assert!("../etc/passwd".contains(".."));
-        assert!("/etc/passwd".contains('/'));
-        assert!("..\\windows".contains(".."));
+    fn test_criterion_groups() {
+        let results = vec![
+            criterion_result("render/png_256", 900.0, Some(1000.0)),
+            criterion_result("render/webp_256", 1010.0, Some(1000.0)),
+            criterion_result("grib2_parse", 2000.0, Some(1000.0)),
+            criterion_result("render/jpeg_256", 500.0, None),
+        ];
+        let groups = criterion_groups(&results);
+        assert_eq!(groups["total_groups"], 2);
+        assert_eq!(groups["total_benchmarks"], 4);
+
+        // Groups are sorted; ungrouped benchmarks form their own group
+        let ungrouped = &groups["groups"][0];
+        assert_eq!(ungrouped["name"], "grib2_parse");
+        assert_eq!(ungrouped["benchmarks"][0]["change_direction"], "regressed");
+
+        let render = &groups["groups"][1]["benchmarks"];
+        assert_eq!(render[0]["function_id"], "png_256");
+        assert_eq!(render[0]["full_id"], "render/png_256");
+        assert_eq!(render[0]["change_direction"], "improved");
+        assert_eq!(render[1]["change_direction"], "unchanged");
+        assert_eq!(render[2]["change_pct"], serde_json::Value::Null);
+        assert_eq!(render[2]["git_sha"], "0a9d2a3");
     }
 }
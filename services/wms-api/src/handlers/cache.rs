@@ -5,6 +5,7 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tracing::{info, instrument};
 
+use crate::config_store;
 use crate::state::AppState;
 
 /// POST /api/cache/clear - Clear all in-memory caches
@@ -51,13 +52,7 @@ pub async fn config_reload_layers_handler(
 ) -> impl IntoResponse {
     info!("Reloading layer configurations");
 
-    let config_dir = std::env::var("CONFIG_DIR").unwrap_or_else(|_| "config".to_string());
-    let layer_config_dir = format!("{}/layers", config_dir);
-
-    let new_registry =
-        crate::layer_config::LayerConfigRegistry::load_from_directory(&layer_config_dir);
-    let mut configs = state.layer_configs.write().await;
-    *configs = new_registry;
+    config_store::reload_layers(&state, &config_store::config_dir()).await;
 
     // Invalidate capabilities cache when layer configs change
     state.capabilities_cache.invalidate().await;
@@ -73,14 +68,12 @@ pub async fn config_reload_handler(
 ) -> impl IntoResponse {
     info!("Full configuration reload");
 
-    // Reload layer configs
-    let config_dir = std::env::var("CONFIG_DIR").unwrap_or_else(|_| "config".to_string());
-    let layer_config_dir = format!("{}/layers", config_dir);
-
-    let new_registry =
-        crate::layer_config::LayerConfigRegistry::load_from_directory(&layer_config_dir);
-    let mut configs = state.layer_configs.write().await;
-    *configs = new_registry;
+    // Reload model and layer configs
+    let config_dir = config_store::config_dir();
+    state.model_dimensions.reload_from_directory(&config_dir);
+    state.completeness.reload_from_directory(&config_dir);
+    config_store::reload_layers(&state, &config_dir).await;
+    crate::rendering::clear_palette_cache();
 
     // Clear caches
     state.tile_memory_cache.clear().await;
@@ -111,6 +104,11 @@ pub async fn config_handler(Extension(state): Extension<Arc<AppState>>) -> Json<
         },
         "chunk_cache": {
             "max_memory_mb": config.chunk_cache_size_mb
+        },
+        "cache_keys": {
+            "normalization": config.cache_key_normalization,
+            "bbox_tolerance_px": config.cache_key_bbox_tolerance,
+            "version": storage::CACHE_KEY_VERSION
         }
     }))
 }
@@ -0,0 +1,200 @@
+//! Catalog search API.
+//!
+//! Free-text and faceted search over ingested datasets for dataset discovery.
+//! Text matches model, parameter and level names in the catalog as well as
+//! layer titles and descriptions from the layer configs; results can be
+//! narrowed by model, level type and valid time range.
+
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+use super::common::parse_iso8601_timestamp;
+use crate::state::AppState;
+use storage::{CatalogSearchFacets, CatalogSearchHit, CatalogSearchQuery};
+
+/// Default number of hits when `limit` is not given.
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Upper bound on hits returned in a single request.
+const MAX_SEARCH_LIMIT: usize = 500;
+
+// ============================================================================
+// Response Types
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct CatalogSearchResponse {
+    /// Number of matching series before paging
+    pub total: usize,
+    pub limit: usize,
+    pub offset: usize,
+    /// Best matches first
+    pub hits: Vec<CatalogSearchResult>,
+    pub facets: CatalogSearchFacets,
+}
+
+/// A matching (model, parameter, level) series.
+#[derive(Debug, Serialize)]
+pub struct CatalogSearchResult {
+    #[serde(flatten)]
+    pub series: CatalogSearchHit,
+    /// WMS/WMTS layer name (e.g. "gfs_TMP")
+    pub layer: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+// ============================================================================
+// Query Parameters
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogSearchParams {
+    /// Free text (case-insensitive substring)
+    pub q: Option<String>,
+    /// Comma-separated models
+    pub model: Option<String>,
+    /// Comma-separated level types (e.g. "isobaric,surface")
+    pub level_type: Option<String>,
+    /// Earliest valid time (ISO8601)
+    pub start: Option<String>,
+    /// Latest valid time (ISO8601)
+    pub end: Option<String>,
+    /// Maximum number of hits, default 50
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// GET /api/catalog/search - Search datasets with facets
+#[instrument(skip(state))]
+pub async fn catalog_search_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(params): Query<CatalogSearchParams>,
+) -> Result<Json<CatalogSearchResponse>, (StatusCode, String)> {
+    let text = params
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(String::from);
+    let start = parse_time_param("start", params.start.as_deref())?;
+    let end = parse_time_param("end", params.end.as_deref())?;
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "start must not be after end".to_string(),
+            ));
+        }
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+
+    info!(q = ?text, model = ?params.model, level_type = ?params.level_type, "Catalog search request");
+
+    // Descriptions live in the layer configs rather than the catalog
+    let matching_layers = match &text {
+        Some(text) => state.layer_configs.read().await.search_layers(text),
+        None => Vec::new(),
+    };
+
+    let query = CatalogSearchQuery {
+        text,
+        matching_layers,
+        models: split_list(params.model.as_deref()),
+        level_types: split_list(params.level_type.as_deref()),
+        start,
+        end,
+        limit: Some(limit),
+        offset,
+    };
+
+    let results = state.catalog.search(&query).await.map_err(|e| {
+        error!(error = %e, "Catalog search failed");
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+
+    let hits = {
+        let configs = state.layer_configs.read().await;
+        results
+            .hits
+            .into_iter()
+            .map(|series| {
+                let layer_config = configs.get_layer_by_param(&series.model, &series.parameter);
+                CatalogSearchResult {
+                    layer: format!("{}_{}", series.model, series.parameter),
+                    title: layer_config.map(|l| l.title.clone()),
+                    description: layer_config.and_then(|l| l.abstract_text.clone()),
+                    series,
+                }
+            })
+            .collect()
+    };
+
+    Ok(Json(CatalogSearchResponse {
+        total: results.total,
+        limit,
+        offset,
+        hits,
+        facets: results.facets,
+    }))
+}
+
+fn parse_time_param(
+    name: &str,
+    value: Option<&str>,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, (StatusCode, String)> {
+    value
+        .map(|v| {
+            parse_iso8601_timestamp(v)
+                .ok_or_else(|| (StatusCode::BAD_REQUEST, format!("Invalid {} '{}'", name, v)))
+        })
+        .transpose()
+}
+
+/// Split a comma-separated parameter, dropping empty entries.
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_list() {
+        assert_eq!(split_list(Some("gfs, hrrr,,")), vec!["gfs", "hrrr"]);
+        assert!(split_list(Some("")).is_empty());
+        assert!(split_list(None).is_empty());
+    }
+
+    #[test]
+    fn test_parse_time_param() {
+        let time = parse_time_param("start", Some("2024-01-15T12:00:00Z")).unwrap();
+        assert_eq!(time.unwrap().to_rfc3339(), "2024-01-15T12:00:00+00:00");
+        assert!(parse_time_param("start", None).unwrap().is_none());
+
+        let (status, message) = parse_time_param("end", Some("yesterday")).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(message, "Invalid end 'yesterday'");
+    }
+}
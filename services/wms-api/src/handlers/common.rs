@@ -6,7 +6,12 @@ use axum::{
 };
 
 use serde::Deserialize;
+use std::collections::HashMap;
 
+use renderer::cvd::CVD_STYLE_SUFFIX;
+use storage::ParameterAvailability;
+
+use crate::layer_config::{LayerConfig, StylePolicy};
 use crate::model_config::ModelDimensionRegistry;
 
 // ============================================================================
@@ -70,6 +75,8 @@ pub struct DimensionParams {
     pub forecast: Option<String>,
     /// ELEVATION dimension - vertical level (e.g., "500 mb", "2 m above ground")
     pub elevation: Option<String>,
+    /// WINDOW dimension - temporal composite period (ISO8601 duration, e.g., "PT1H")
+    pub window: Option<String>,
 }
 
 impl DimensionParams {
@@ -106,6 +113,16 @@ impl DimensionParams {
             (forecast_hour, None, reference_time)
         }
     }
+
+    /// Whether the request names an exact run and forecast hour or an
+    /// exact observation time, so its response never changes.
+    pub fn is_pinned(&self, model: &str, registry: &ModelDimensionRegistry) -> bool {
+        match self.parse_for_layer(model, registry) {
+            (_, Some(_), _) => true,
+            (Some(_), None, Some(_)) => true,
+            _ => false,
+        }
+    }
 }
 
 /// Parse an ISO8601 timestamp string
@@ -123,117 +140,292 @@ pub fn parse_iso8601_timestamp(s: &str) -> Option<chrono::DateTime<chrono::Utc>>
     None
 }
 
+// ============================================================================
+// Composite Layer Availability
+// ============================================================================
+
+/// Availability of a multi-band composite layer (e.g., GOES true color).
+///
+/// Keeps only the times, forecast hours and levels shared by every band in
+/// `layer.requires`. Returns None if any band has no data or the bands have
+/// no time in common.
+pub fn band_composite_availability(
+    model_id: &str,
+    layer: &LayerConfig,
+    param_availability: &HashMap<String, ParameterAvailability>,
+) -> Option<ParameterAvailability> {
+    let bands: Vec<&ParameterAvailability> = layer
+        .requires
+        .iter()
+        .map(|band| param_availability.get(&format!("{}_{}", model_id, band)))
+        .collect::<Option<_>>()?;
+    let (first, rest) = bands.split_first()?;
+
+    let times: Vec<String> = first
+        .times
+        .iter()
+        .filter(|t| rest.iter().all(|b| b.times.contains(t)))
+        .cloned()
+        .collect();
+    if times.is_empty() {
+        return None;
+    }
+
+    Some(ParameterAvailability {
+        times,
+        forecast_hours: first
+            .forecast_hours
+            .iter()
+            .filter(|h| rest.iter().all(|b| b.forecast_hours.contains(h)))
+            .copied()
+            .collect(),
+        levels: first
+            .levels
+            .iter()
+            .filter(|l| rest.iter().all(|b| b.levels.contains(l)))
+            .cloned()
+            .collect(),
+        bbox: first.bbox,
+    })
+}
+
 // ============================================================================
 // Style File XML Helpers
 // ============================================================================
 
-/// Load styles from a JSON file and generate WMS-compatible XML for capabilities
-pub fn get_styles_xml_from_file(style_file: &str) -> String {
-    // Try to load and parse the style file
-    if let Ok(content) = std::fs::read_to_string(style_file) {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(styles) = json.get("styles").and_then(|s| s.as_object()) {
-                let mut xml_parts = Vec::new();
-
-                // Find the default style name (style with default: true, or first style)
-                let default_style_name = styles
-                    .iter()
-                    .find(|(_, def)| {
-                        def.get("default")
-                            .and_then(|d| d.as_bool())
-                            .unwrap_or(false)
-                    })
-                    .map(|(name, _)| name.clone());
-
-                // Output default style first (WMS convention)
-                if let Some(ref default_name) = default_style_name {
-                    if let Some(style_def) = styles.get(default_name) {
-                        let title = style_def
-                            .get("name")
-                            .and_then(|n| n.as_str())
-                            .unwrap_or(default_name);
-                        xml_parts.push(format!(
-                            "<Style><Name>{}</Name><Title>{}</Title></Style>",
-                            default_name, title
-                        ));
-                    }
-                }
+/// A style listed in capabilities.
+struct CapabilitiesStyle {
+    name: String,
+    title: String,
+}
 
-                // Then output remaining styles
-                for (style_key, style_def) in styles {
-                    // Skip if this was the default (already output)
-                    if Some(style_key) == default_style_name.as_ref() {
-                        continue;
-                    }
-
-                    let name = style_key;
-                    let title = style_def
-                        .get("name")
-                        .and_then(|n| n.as_str())
-                        .unwrap_or(style_key);
-
-                    xml_parts.push(format!(
-                        "<Style><Name>{}</Name><Title>{}</Title></Style>",
-                        name, title
-                    ));
-                }
+/// A deprecated style alias listed in capabilities.
+struct CapabilitiesAlias {
+    alias: String,
+    target: String,
+    title: String,
+}
 
-                if !xml_parts.is_empty() {
-                    return xml_parts.join("");
-                }
+/// Styles of a layer after applying its [`StylePolicy`].
+struct CapabilitiesStyles {
+    /// Styles to advertise, in style file order
+    styles: Vec<CapabilitiesStyle>,
+    /// Name of the default style, if one is configured or marked in the file
+    default_style: Option<String>,
+    /// Deprecated aliases whose target style exists
+    aliases: Vec<CapabilitiesAlias>,
+}
+
+/// Load the styles of a style file and apply the layer's [`StylePolicy`].
+/// Returns None if the file can't be read or contains no styles.
+fn load_capabilities_styles(style_file: &str, policy: &StylePolicy) -> Option<CapabilitiesStyles> {
+    let content = std::fs::read_to_string(style_file).ok()?;
+    let json = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    let styles = json.get("styles").and_then(|s| s.as_object())?;
+    if styles.is_empty() {
+        return None;
+    }
+
+    let mut entries: Vec<CapabilitiesStyle> = styles
+        .iter()
+        .map(|(key, def)| CapabilitiesStyle {
+            name: key.clone(),
+            title: def
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or(key)
+                .to_string(),
+        })
+        .collect();
+
+    // Colorblind-safe variants are generated for every gradient style
+    for (key, def) in styles {
+        let eligible = def
+            .get("type")
+            .and_then(|t| t.as_str())
+            .is_some_and(renderer::cvd::supports_cvd_variant);
+        let variant = renderer::cvd::cvd_style_name(key);
+        if eligible && !key.ends_with(CVD_STYLE_SUFFIX) && !styles.contains_key(&variant) {
+            let title = def.get("name").and_then(|n| n.as_str()).unwrap_or(key);
+            entries.push(CapabilitiesStyle {
+                name: variant,
+                title: format!("{} (colorblind-safe)", title),
+            });
+        }
+    }
+
+    // A forced style hides every other style from clients
+    if let Some(forced) = &policy.forced_style {
+        entries.retain(|e| &e.name == forced);
+        if entries.is_empty() {
+            entries.push(CapabilitiesStyle {
+                name: forced.clone(),
+                title: forced.clone(),
+            });
+        }
+        return Some(CapabilitiesStyles {
+            styles: entries,
+            default_style: Some(forced.clone()),
+            aliases: Vec::new(),
+        });
+    }
+
+    // Configured default style wins over the `default: true` flag in the style file
+    let default_style_name = policy
+        .default_style
+        .as_ref()
+        .filter(|name| styles.contains_key(name.as_str()))
+        .cloned()
+        .or_else(|| {
+            styles
+                .iter()
+                .find(|(_, def)| {
+                    def.get("default")
+                        .and_then(|d| d.as_bool())
+                        .unwrap_or(false)
+                })
+                .map(|(name, _)| name.clone())
+        });
+
+    let mut aliases: Vec<CapabilitiesAlias> = policy
+        .aliases
+        .iter()
+        .filter_map(|(alias, target)| {
+            entries
+                .iter()
+                .find(|e| &e.name == target)
+                .map(|e| CapabilitiesAlias {
+                    alias: alias.clone(),
+                    target: target.clone(),
+                    title: e.title.clone(),
+                })
+        })
+        .collect();
+    aliases.sort_by(|a, b| a.alias.cmp(&b.alias));
+
+    Some(CapabilitiesStyles {
+        styles: entries,
+        default_style: default_style_name,
+        aliases,
+    })
+}
+
+/// Names of the styles a layer advertises, default style first.
+///
+/// Deprecated aliases are left out. Returns `["default"]` if the style file
+/// can't be read, matching the capabilities fallback.
+pub fn style_names_from_file(style_file: &str, policy: &StylePolicy) -> Vec<String> {
+    let Some(CapabilitiesStyles {
+        styles,
+        default_style,
+        ..
+    }) = load_capabilities_styles(style_file, policy)
+    else {
+        return vec!["default".to_string()];
+    };
+
+    let mut names: Vec<String> = styles.into_iter().map(|e| e.name).collect();
+    if let Some(pos) = default_style
+        .as_ref()
+        .and_then(|d| names.iter().position(|n| n == d))
+    {
+        let default = names.remove(pos);
+        names.insert(0, default);
+    }
+    names
+}
+
+/// Load styles from a JSON file and generate WMS-compatible XML for capabilities.
+///
+/// The layer's [`StylePolicy`] decides which style is listed first (the WMS
+/// default), hides all but the forced style if one is set, and lists renamed
+/// styles under their old names with a deprecation notice.
+pub fn get_styles_xml_from_file(style_file: &str, policy: &StylePolicy) -> String {
+    if let Some(CapabilitiesStyles {
+        styles: entries,
+        default_style: default_style_name,
+        aliases,
+    }) = load_capabilities_styles(style_file, policy)
+    {
+        let mut xml_parts = Vec::new();
+
+        // Output default style first (WMS convention)
+        if let Some(ref default_name) = default_style_name {
+            if let Some(entry) = entries.iter().find(|e| &e.name == default_name) {
+                xml_parts.push(format!(
+                    "<Style><Name>{}</Name><Title>{}</Title></Style>",
+                    entry.name, entry.title
+                ));
+            }
+        }
+
+        // Then output remaining styles
+        for entry in &entries {
+            // Skip if this was the default (already output)
+            if Some(&entry.name) == default_style_name.as_ref() {
+                continue;
             }
+
+            xml_parts.push(format!(
+                "<Style><Name>{}</Name><Title>{}</Title></Style>",
+                entry.name, entry.title
+            ));
+        }
+
+        // Deprecated aliases still work but tell clients where the style moved
+        for a in &aliases {
+            xml_parts.push(format!(
+                "<Style><Name>{}</Name><Title>{} (deprecated)</Title><Abstract>Deprecated: style '{}' has been renamed to '{}'.</Abstract></Style>",
+                a.alias, a.title, a.alias, a.target
+            ));
         }
+
+        return xml_parts.join("");
     }
 
     // Fallback to just default style if file can't be read
     "<Style><Name>default</Name><Title>Default</Title></Style>".to_string()
 }
 
-/// Load styles from a JSON file and generate WMTS-compatible XML for capabilities
-pub fn get_wmts_styles_xml_from_file(style_file: &str) -> String {
-    // Try to load and parse the style file
-    if let Ok(content) = std::fs::read_to_string(style_file) {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-            if let Some(styles) = json.get("styles").and_then(|s| s.as_object()) {
-                let mut xml_parts = Vec::new();
-
-                // First, find the style marked as default (or use first if none marked)
-                let default_style_name = styles
-                    .iter()
-                    .find(|(_, def)| {
-                        def.get("default")
-                            .and_then(|d| d.as_bool())
-                            .unwrap_or(false)
-                    })
-                    .map(|(name, _)| name.as_str())
-                    .or_else(|| styles.keys().next().map(|s| s.as_str()));
-
-                for (style_key, style_def) in styles {
-                    let identifier = style_key;
-                    let title = style_def
-                        .get("name")
-                        .and_then(|n| n.as_str())
-                        .unwrap_or(style_key);
-
-                    // Check if this style is the default
-                    let is_default = Some(style_key.as_str()) == default_style_name;
-                    let default_attr = if is_default {
-                        " isDefault=\"true\""
-                    } else {
-                        ""
-                    };
-
-                    xml_parts.push(format!(
-                        r#"<Style{}><ows:Identifier>{}</ows:Identifier><ows:Title>{}</ows:Title></Style>"#,
-                        default_attr, identifier, title
-                    ));
-                }
+/// Load styles from a JSON file and generate WMTS-compatible XML for capabilities.
+///
+/// See [`get_styles_xml_from_file`] for how the layer's [`StylePolicy`] is applied.
+pub fn get_wmts_styles_xml_from_file(style_file: &str, policy: &StylePolicy) -> String {
+    if let Some(CapabilitiesStyles {
+        styles: entries,
+        default_style: default_style_name,
+        aliases,
+    }) = load_capabilities_styles(style_file, policy)
+    {
+        // Use the first style as default if none is marked
+        let default_style_name =
+            default_style_name.or_else(|| entries.first().map(|e| e.name.clone()));
+
+        let mut xml_parts = Vec::new();
+
+        for entry in &entries {
+            // Check if this style is the default
+            let is_default = Some(&entry.name) == default_style_name.as_ref();
+            let default_attr = if is_default {
+                " isDefault=\"true\""
+            } else {
+                ""
+            };
+
+            xml_parts.push(format!(
+                r#"<Style{}><ows:Identifier>{}</ows:Identifier><ows:Title>{}</ows:Title></Style>"#,
+                default_attr, entry.name, entry.title
+            ));
+        }
 
-                if !xml_parts.is_empty() {
-                    return xml_parts.join("");
-                }
-            }
+        for a in &aliases {
+            xml_parts.push(format!(
+                r#"<Style><ows:Identifier>{}</ows:Identifier><ows:Title>{} (deprecated)</ows:Title><ows:Abstract>Deprecated: style '{}' has been renamed to '{}'.</ows:Abstract></Style>"#,
+                a.alias, a.title, a.alias, a.target
+            ));
         }
+
+        return xml_parts.join("");
     }
 
     // Fallback to just default style if file can't be read
@@ -434,6 +626,12 @@ pub struct WmtsDimensionParams {
     /// ELEVATION dimension - vertical level (e.g., "500 mb", "2 m above ground")
     #[serde(rename = "elevation", alias = "ELEVATION")]
     pub elevation: Option<String>,
+    /// WINDOW dimension - temporal composite period (ISO8601 duration, e.g., "PT1H")
+    #[serde(rename = "window", alias = "WINDOW")]
+    pub window: Option<String>,
+    /// API key, for clients that can't set the `x-api-key` header
+    #[serde(rename = "api_key", alias = "API_KEY")]
+    pub api_key: Option<String>,
 }
 
 // ============================================================================
@@ -513,11 +711,151 @@ mod tests {
     #[test]
     fn test_get_styles_xml_fallback() {
         // Non-existent file should return fallback
-        let xml = get_styles_xml_from_file("/nonexistent/path.json");
+        let xml = get_styles_xml_from_file("/nonexistent/path.json", &StylePolicy::default());
         assert!(xml.contains("default"));
         assert!(xml.contains("Default"));
     }
 
+    fn write_style_file(dir: &tempfile::TempDir) -> String {
+        let path = dir.path().join("styles.json");
+        std::fs::write(
+            &path,
+            r#"{"styles": {
+                "default": {"name": "Default", "type": "gradient", "default": true},
+                "gradient": {"name": "Gradient", "type": "gradient"},
+                "isolines": {"name": "Isolines", "type": "contour"}
+            }}"#,
+        )
+        .unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn test_get_styles_xml_policy_default_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_style_file(&dir);
+        let policy = StylePolicy {
+            default_style: Some("gradient".to_string()),
+            ..Default::default()
+        };
+
+        let xml = get_styles_xml_from_file(&path, &policy);
+        assert!(xml.starts_with("<Style><Name>gradient</Name>"));
+        assert!(xml.contains("<Name>gradient_cvd</Name><Title>Gradient (colorblind-safe)</Title>"));
+
+        let wmts = get_wmts_styles_xml_from_file(&path, &policy);
+        assert!(
+            wmts.contains(r#"<Style isDefault="true"><ows:Identifier>gradient</ows:Identifier>"#)
+        );
+        assert!(
+            !wmts.contains(r#"<Style isDefault="true"><ows:Identifier>default</ows:Identifier>"#)
+        );
+    }
+
+    #[test]
+    fn test_get_styles_xml_policy_forced_and_aliases() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_style_file(&dir);
+
+        let forced = StylePolicy {
+            forced_style: Some("isolines".to_string()),
+            ..Default::default()
+        };
+        let xml = get_styles_xml_from_file(&path, &forced);
+        assert_eq!(
+            xml,
+            "<Style><Name>isolines</Name><Title>Isolines</Title></Style>"
+        );
+
+        let mut aliases = std::collections::HashMap::new();
+        aliases.insert("contours".to_string(), "isolines".to_string());
+        aliases.insert("missing_target".to_string(), "nope".to_string());
+        let aliased = StylePolicy {
+            aliases,
+            ..Default::default()
+        };
+        let xml = get_styles_xml_from_file(&path, &aliased);
+        assert!(xml.contains("<Name>contours</Name>"));
+        assert!(xml.contains("renamed to 'isolines'"));
+        assert!(!xml.contains("missing_target"));
+    }
+
+    #[test]
+    fn test_style_names_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_style_file(&dir);
+
+        let mut aliases = std::collections::HashMap::new();
+        aliases.insert("contours".to_string(), "isolines".to_string());
+        let policy = StylePolicy {
+            default_style: Some("gradient".to_string()),
+            aliases,
+            ..Default::default()
+        };
+
+        let names = style_names_from_file(&path, &policy);
+        assert_eq!(names[0], "gradient");
+        assert!(names.contains(&"gradient_cvd".to_string()));
+        assert!(names.contains(&"isolines".to_string()));
+        assert!(!names.contains(&"contours".to_string()));
+
+        assert_eq!(
+            style_names_from_file("/nonexistent/path.json", &policy),
+            vec!["default"]
+        );
+    }
+
+    #[test]
+    fn test_band_composite_availability() {
+        use crate::layer_config::UnitConfig;
+        use wms_common::BoundingBox;
+
+        let band = |times: &[&str], level: &str| ParameterAvailability {
+            times: times.iter().map(|t| t.to_string()).collect(),
+            forecast_hours: vec![],
+            levels: vec![level.to_string()],
+            bbox: BoundingBox::new(-143.0, 14.5, -53.0, 55.5),
+        };
+        let mut availability = HashMap::new();
+        availability.insert(
+            "goes16_CMI_C02".to_string(),
+            band(
+                &["2024-06-01T12:00:00Z", "2024-06-01T12:05:00Z"],
+                "visible_red",
+            ),
+        );
+        availability.insert(
+            "goes16_CMI_C13".to_string(),
+            band(&["2024-06-01T12:05:00Z"], "clean_ir"),
+        );
+
+        let mut layer = LayerConfig {
+            id: "goes16_SANDWICH".to_string(),
+            parameter: "SANDWICH".to_string(),
+            title: "Sandwich".to_string(),
+            abstract_text: None,
+            style_file: "goes_sandwich.json".to_string(),
+            units: UnitConfig::default(),
+            levels: vec![],
+            composite: true,
+            requires: vec!["CMI_C02".to_string(), "CMI_C13".to_string()],
+            accumulation: false,
+            style_policy: StylePolicy::default(),
+            temporal: None,
+            ensemble: None,
+            vertical: None,
+            limits: Default::default(),
+        };
+
+        let combined = band_composite_availability("goes16", &layer, &availability).unwrap();
+        assert_eq!(combined.times, vec!["2024-06-01T12:05:00Z"]);
+        assert!(combined.levels.is_empty());
+
+        // Missing band data hides the composite
+        layer.requires.push("CMI_C01".to_string());
+        assert!(band_composite_availability("goes16", &layer, &availability).is_none());
+    }
+
     #[test]
     fn test_wms_exception_format() {
         let resp = wms_exception("TestCode", "Test message", StatusCode::BAD_REQUEST);
@@ -21,8 +21,22 @@ pub async fn health_handler() -> impl IntoResponse {
     (StatusCode::OK, "OK")
 }
 
-/// GET /ready - Readiness check (verifies database connectivity)
+/// GET /ready - Readiness check (verifies database connectivity, that the
+/// object storage circuit breaker is closed and, if configured to gate
+/// readiness, that startup golden renders matched their references)
 pub async fn ready_handler(Extension(state): Extension<Arc<AppState>>) -> impl IntoResponse {
+    if !state.storage.is_available() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Not ready: object storage unavailable",
+        );
+    }
+    if !state.golden_renders_ok.load(Ordering::Relaxed) {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Not ready: golden renders differ from their references",
+        );
+    }
     match state.catalog.list_models().await {
         Ok(_) => (StatusCode::OK, "Ready"),
         Err(_) => (StatusCode::SERVICE_UNAVAILABLE, "Not ready"),
@@ -70,6 +84,18 @@ pub async fn metrics_handler(Extension(state): Extension<Arc<AppState>>) -> Resp
         "# HELP chunk_cache_misses Total chunk cache misses\n# TYPE chunk_cache_misses counter\nchunk_cache_misses {}\n",
         chunk_stats.misses
     ));
+    output.push_str(&format!(
+        "# HELP chunk_cache_prefetched Total chunks prefetched around requested tiles\n# TYPE chunk_cache_prefetched counter\nchunk_cache_prefetched {}\n",
+        chunk_stats.prefetched
+    ));
+    output.push_str(&format!(
+        "# HELP chunk_cache_prefetch_hits Total prefetched chunks later read\n# TYPE chunk_cache_prefetch_hits counter\nchunk_cache_prefetch_hits {}\n",
+        chunk_stats.prefetch_hits
+    ));
+    output.push_str(&format!(
+        "# HELP chunk_cache_prefetch_misses Total prefetched chunks evicted unread\n# TYPE chunk_cache_prefetch_misses counter\nchunk_cache_prefetch_misses {}\n",
+        chunk_stats.prefetch_misses
+    ));
 
     // L1 tile cache metrics
     output.push_str(&format!(
@@ -109,6 +135,17 @@ pub async fn metrics_handler(Extension(state): Extension<Arc<AppState>>) -> Resp
         l1_stats.bytes_evicted_total.load(Ordering::Relaxed)
     ));
 
+    // Object storage retries and circuit breaker
+    output.push_str(&state.storage.resilience_metrics().to_prometheus());
+
+    // Missing datasets of recent model runs
+    output.push_str(&state.completeness.to_prometheus());
+
+    // Requests per tenant
+    if !state.tenants.is_empty() {
+        output.push_str(&state.tenant_usage.to_prometheus());
+    }
+
     // Container memory metrics
     if let Some(mem_used) = container_stats
         .get("memory_used_bytes")
@@ -218,6 +255,11 @@ pub async fn api_metrics_handler(
             "cache_misses": snapshot.cache_misses,
             "cache_hit_rate": snapshot.cache_hit_rate,
 
+            // Cache key normalization (hits gained from canonical keys)
+            "cache_key_rewrites": snapshot.cache_key_rewrites,
+            "cache_key_rewrite_hits": snapshot.cache_key_rewrite_hits,
+            "cache_key_hit_rate_gain": snapshot.cache_key_hit_rate_gain,
+
             // Render stats
             "renders_total": snapshot.renders_total,
             "render_errors": snapshot.render_errors,
@@ -271,7 +313,11 @@ pub async fn api_metrics_handler(
             "hits": chunk_stats.hits,
             "misses": chunk_stats.misses,
             "hit_rate": chunk_hit_rate,
-            "evictions": chunk_stats.evictions
+            "evictions": chunk_stats.evictions,
+            "prefetched": chunk_stats.prefetched,
+            "prefetch_hits": chunk_stats.prefetch_hits,
+            "prefetch_misses": chunk_stats.prefetch_misses,
+            "prefetch_hit_rate": chunk_stats.prefetch_hit_rate() * 100.0
         },
 
         // System stats from container
@@ -405,7 +451,11 @@ pub async fn grid_processor_stats_handler(
             "hit_rate": hit_rate,
             "hit_rate_percent": hit_rate,
             "evictions": stats.evictions,
-            "total_requests": stats.hits + stats.misses
+            "total_requests": stats.hits + stats.misses,
+            "prefetched": stats.prefetched,
+            "prefetch_hits": stats.prefetch_hits,
+            "prefetch_misses": stats.prefetch_misses,
+            "prefetch_hit_rate_percent": stats.prefetch_hit_rate() * 100.0
         }
     }))
 }
@@ -3,7 +3,11 @@
 //! This module is organized into submodules:
 //! - `wms`: WMS GetCapabilities, GetMap, GetFeatureInfo handlers
 //! - `wmts`: WMTS GetCapabilities, GetTile handlers (KVP, REST, XYZ)
+//! - `ogc_maps`: OGC API - Maps landing page, collections and maps
 //! - `api`: REST API handlers (forecast times, parameters, ingestion events)
+//! - `run_comparison`: Same parameter across model runs at a point
+//! - `catalog_search`: Free-text and faceted dataset search
+//! - `styles`: Style and legend metadata as JSON
 //! - `metrics`: Health checks, Prometheus metrics, and monitoring
 //! - `validation`: WMS/WMTS validation handlers
 //! - `cache`: Cache management and config reload handlers
@@ -14,9 +18,13 @@
 pub mod api;
 pub mod benchmarks;
 pub mod cache;
+pub mod catalog_search;
 pub mod common;
 pub mod docs;
 pub mod metrics;
+pub mod ogc_maps;
+pub mod run_comparison;
+pub mod styles;
 pub mod validation;
 pub mod wms;
 pub mod wmts;
@@ -30,13 +38,24 @@ pub use common::{
 
 pub use wms::{wms_handler, WmsParams};
 
+pub use ogc_maps::{
+    ogc_collection_handler, ogc_collections_handler, ogc_conformance_handler, ogc_landing_handler,
+    ogc_map_handler, ogc_styled_map_handler,
+};
+
 pub use wmts::{wmts_kvp_handler, wmts_rest_handler, xyz_tile_handler, WmtsKvpParams};
 
 pub use api::{
-    forecast_times_handler, ingestion_events_handler, parameters_handler, ForecastTimesResponse,
-    IngestionEvent, ParametersResponse,
+    animation_frames_handler, forecast_times_handler, ingestion_events_handler, parameters_handler,
+    AnimationFramesResponse, ForecastTimesResponse, IngestionEvent, ParametersResponse,
 };
 
+pub use run_comparison::{run_comparison_handler, RunComparisonResponse};
+
+pub use catalog_search::{catalog_search_handler, CatalogSearchResponse};
+
+pub use styles::{layer_styles_handler, style_handler, LayerStylesResponse, StyleResponse};
+
 pub use metrics::{
     api_metrics_handler, container_stats_handler, grid_processor_stats_handler, health_handler,
     metrics_handler, ready_handler, storage_stats_handler, tile_heatmap_clear_handler,
@@ -44,7 +63,8 @@ pub use metrics::{
 };
 
 pub use validation::{
-    startup_validation_run_handler, validation_run_handler, validation_status_handler,
+    request_validation_handler, startup_validation_run_handler, validation_run_handler,
+    validation_status_handler,
 };
 
 pub use cache::{
@@ -53,8 +73,10 @@ pub use cache::{
 };
 
 pub use benchmarks::{
-    benchmarks_handler, criterion_benchmarks_handler, loadtest_dashboard_handler,
-    loadtest_file_handler, loadtest_files_handler, loadtest_results_handler,
+    benchmark_trends_handler, benchmarks_handler, criterion_benchmarks_handler,
+    criterion_record_handler, loadtest_dashboard_handler, loadtest_file_handler,
+    loadtest_files_handler, loadtest_record_handler, loadtest_requests_record_handler,
+    loadtest_results_handler,
 };
 
 pub use docs::{openapi_json_handler, openapi_yaml_handler, swagger_ui_handler};
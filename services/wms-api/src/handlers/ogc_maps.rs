@@ -0,0 +1,685 @@
+//! OGC API - Maps handlers.
+//!
+//! Every WMS layer is also a collection under `/ogc/collections`, with maps
+//! at `/ogc/collections/{layer}/map` and, per style,
+//! `/ogc/collections/{layer}/styles/{style}/map`. Maps go through the same
+//! rendering as WMS GetMap, and collections are listed from the same catalog
+//! availability as the WMS capabilities, so both offer the same layers,
+//! extents and styles.
+//!
+//! A forecast layer's `datetime` is a valid time, mapped with the newest
+//! run that covers it; an observation layer's is matched like WMS TIME.
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+};
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
+
+use super::common::{
+    band_composite_availability, convert_png_to_jpeg, convert_png_to_webp, parse_iso8601_timestamp,
+    style_names_from_file, DimensionParams,
+};
+use super::wms::{
+    check_limits, check_tenant_layers, collect_parameter_availability, match_layer_times,
+    normalize_bbox, render_weather_data, with_underlay, WmsError,
+};
+use crate::layer_config::{LayerConfig, LayerConfigRegistry};
+use crate::request_limits::MapRequest as LimitsRequest;
+use crate::state::AppState;
+use crate::time_match::is_default_keyword;
+use storage::ParameterAvailability;
+use wms_common::api_key::{ApiKey, API_KEY_HEADER, API_KEY_QUERY_PARAM};
+use wms_common::Tenant;
+use wms_protocol::ogc_maps::{
+    ApiException, Collection, CollectionStyle, Collections, ConformanceDeclaration, DocumentFormat,
+    Extent, LandingPage, Link, MapCrs, MapFormat, MapQueryParams, SpatialExtent, TemporalExtent,
+};
+
+/// Path the OGC API is mounted at.
+const OGC_API_PATH: &str = "/ogc";
+
+/// Link relation of a collection's map.
+const MAP_REL: &str = "http://www.opengis.net/def/rel/ogc/1.0/map";
+
+/// Service name in per-tenant usage metrics.
+const USAGE_SERVICE: &str = "ogcapi-maps";
+
+/// `f` parameter of metadata documents.
+#[derive(Debug, Default, Deserialize)]
+pub struct DocumentParams {
+    pub f: Option<String>,
+}
+
+// ============================================================================
+// Metadata documents
+// ============================================================================
+
+/// GET /ogc - Landing page
+pub async fn ogc_landing_handler(
+    headers: HeaderMap,
+    Query(params): Query<DocumentParams>,
+) -> Response {
+    let base = base_url(&headers);
+    let page = LandingPage {
+        title: "Weather WMS - OGC API Maps".to_string(),
+        description: "Weather model and observation maps, rendered on request".to_string(),
+        links: vec![
+            Link::new(&base, "self").with_type("application/json"),
+            Link::new(format!("{}?f=html", base), "alternate")
+                .with_type("text/html")
+                .with_title("This document as HTML"),
+            Link::new(
+                format!("{}/conformance", base),
+                "http://www.opengis.net/def/rel/ogc/1.0/conformance",
+            )
+            .with_type("application/json")
+            .with_title("Conformance classes"),
+            Link::new(
+                format!("{}/collections", base),
+                "http://www.opengis.net/def/rel/ogc/1.0/data",
+            )
+            .with_type("application/json")
+            .with_title("Collections"),
+            Link::new(
+                format!(
+                    "{}/wms?SERVICE=WMS&REQUEST=GetCapabilities",
+                    host_url(&headers)
+                ),
+                "related",
+            )
+            .with_type("application/xml")
+            .with_title("WMS 1.3.0 capabilities"),
+        ],
+    };
+    document(&headers, &params, &page, LandingPage::to_html)
+}
+
+/// GET /ogc/conformance - Conformance declaration
+pub async fn ogc_conformance_handler(
+    headers: HeaderMap,
+    Query(params): Query<DocumentParams>,
+) -> Response {
+    document(
+        &headers,
+        &params,
+        &ConformanceDeclaration::default(),
+        ConformanceDeclaration::to_html,
+    )
+}
+
+/// GET /ogc/collections - Every layer with data
+#[instrument(skip(state, headers, params, pairs))]
+pub async fn ogc_collections_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<DocumentParams>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Response {
+    let tenant = request_tenant(&state, &headers, &pairs);
+    let base = base_url(&headers);
+
+    let all_layer_configs = state.layer_configs.read().await;
+    let scoped_layer_configs;
+    let layer_configs = match &tenant {
+        Some(tenant) => {
+            scoped_layer_configs = all_layer_configs.scoped(|l| tenant.allows_layer(&l.id));
+            &scoped_layer_configs
+        }
+        None => &*all_layer_configs,
+    };
+    let param_availability = collect_parameter_availability(&state, layer_configs).await;
+
+    let mut collections: Vec<Collection> = layer_configs
+        .models()
+        .into_iter()
+        .filter_map(|model| layer_configs.get_model(model))
+        .flat_map(|model_config| {
+            model_config.layers.iter().filter_map(|layer| {
+                let availability =
+                    layer_availability(&model_config.model, layer, &param_availability)?;
+                Some(collection_document(
+                    &state,
+                    layer_configs,
+                    layer,
+                    &model_config.display_name,
+                    &availability,
+                    &base,
+                ))
+            })
+        })
+        .collect();
+    collections.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let list = Collections {
+        collections,
+        links: vec![
+            Link::new(format!("{}/collections", base), "self").with_type("application/json"),
+            Link::new(format!("{}/collections?f=html", base), "alternate").with_type("text/html"),
+        ],
+    };
+    document(&headers, &params, &list, Collections::to_html)
+}
+
+/// GET /ogc/collections/:collection - One layer
+#[instrument(skip(state, headers, params, pairs))]
+pub async fn ogc_collection_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(collection): Path<String>,
+    headers: HeaderMap,
+    Query(params): Query<DocumentParams>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Response {
+    let tenant = request_tenant(&state, &headers, &pairs);
+    if let Err(e) = check_tenant_layers(
+        &state,
+        tenant.as_ref(),
+        &[&collection],
+        USAGE_SERVICE,
+        "Collection",
+    ) {
+        return ogc_exception(&e);
+    }
+
+    let layer_configs = state.layer_configs.read().await;
+    let Some((model_config, layer)) = layer_configs.get_layer(&collection).and_then(|layer| {
+        let model = collection.split('_').next()?;
+        Some((layer_configs.get_model(model)?, layer))
+    }) else {
+        return ogc_exception(&unknown_collection(&collection));
+    };
+
+    // Same availability rules as the collection list
+    let mut param_availability = HashMap::new();
+    let parameters = if layer.is_band_composite() {
+        layer.requires.iter().map(String::as_str).collect()
+    } else {
+        vec![layer.source_parameter()]
+    };
+    for parameter in parameters {
+        if let Ok(Some(availability)) = state
+            .catalog
+            .get_parameter_availability(&model_config.model, parameter)
+            .await
+        {
+            param_availability.insert(
+                format!("{}_{}", model_config.model, parameter),
+                availability,
+            );
+        }
+    }
+    let Some(availability) = layer_availability(&model_config.model, layer, &param_availability)
+    else {
+        return ogc_exception(&unknown_collection(&collection));
+    };
+
+    let doc = collection_document(
+        &state,
+        &layer_configs,
+        layer,
+        &model_config.display_name,
+        &availability,
+        &base_url(&headers),
+    );
+    document(&headers, &params, &doc, Collection::to_html)
+}
+
+// ============================================================================
+// Maps
+// ============================================================================
+
+/// GET /ogc/collections/:collection/map - Map in the layer's default style
+pub async fn ogc_map_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(collection): Path<String>,
+    headers: HeaderMap,
+    Query(params): Query<MapQueryParams>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Response {
+    render_collection_map(state, &collection, None, &headers, params, &pairs).await
+}
+
+/// GET /ogc/collections/:collection/styles/:style/map - Map in a named style
+pub async fn ogc_styled_map_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((collection, style)): Path<(String, String)>,
+    headers: HeaderMap,
+    Query(params): Query<MapQueryParams>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Response {
+    render_collection_map(state, &collection, Some(&style), &headers, params, &pairs).await
+}
+
+#[instrument(skip(state, headers, params, pairs))]
+async fn render_collection_map(
+    state: Arc<AppState>,
+    collection: &str,
+    style: Option<&str>,
+    headers: &HeaderMap,
+    params: MapQueryParams,
+    pairs: &[(String, String)],
+) -> Response {
+    use crate::metrics::Timer;
+
+    let api_key = request_api_key(headers, pairs);
+    let tenant = state.tenants.resolve(&api_key).cloned();
+    if let Some(tenant) = &tenant {
+        state.tenant_usage.record(tenant, USAGE_SERVICE, "Map");
+    }
+    if let Err(e) =
+        check_tenant_layers(&state, tenant.as_ref(), &[collection], USAGE_SERVICE, "Map")
+    {
+        return ogc_exception(&e);
+    }
+
+    let (model, source_parameter, style_names) = {
+        let layer_configs = state.layer_configs.read().await;
+        let Some(layer) = layer_configs.get_layer(collection) else {
+            return ogc_exception(&unknown_collection(collection));
+        };
+        let style_names =
+            style_names_from_file(&layer_configs.get_style_path(layer), &layer.style_policy);
+        let model = collection
+            .split('_')
+            .next()
+            .unwrap_or(collection)
+            .to_string();
+        (model, layer.source_parameter().to_string(), style_names)
+    };
+    if let Some(style) = style {
+        if !style_names.iter().any(|s| s == style) {
+            return ogc_exception(&WmsError::StyleNotDefined(format!(
+                "Style '{}' is not defined for collection '{}'.",
+                style, collection
+            )));
+        }
+    }
+
+    let accept = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok());
+    let request = match params.into_request(accept) {
+        Ok(request) => request,
+        Err(e) => {
+            return ogc_response(
+                StatusCode::BAD_REQUEST,
+                "InvalidParameterValue",
+                &e.to_string(),
+            )
+        }
+    };
+
+    // Without a bbox the map covers the model's extent
+    let bbox = match request.bbox {
+        Some(bbox) => bbox,
+        None => match state.catalog.get_model_bbox(&model).await {
+            Ok(extent) => {
+                let (west, east, south, north) = normalize_bbox(&extent);
+                let lon_lat = if west < east {
+                    [west, south, east, north]
+                } else {
+                    [-180.0, south, 180.0, north]
+                };
+                MapCrs::Crs84.transform_bbox(lon_lat, request.crs)
+            }
+            Err(e) => return ogc_exception(&WmsError::MissingData(e.to_string())),
+        },
+    };
+    let (width, height) = request.size(bbox);
+    let lon_lat = request.crs.transform_bbox(bbox, MapCrs::Crs84);
+
+    if !state.request_limits.is_trusted(&api_key) {
+        let limits_request = LimitsRequest {
+            width,
+            height,
+            layers: 1,
+            extent: Some(lon_lat),
+        };
+        if let Err(e) = check_limits(&state, &[collection], &limits_request).await {
+            return ogc_exception(&e);
+        }
+    }
+
+    let (dimensions, warnings) = match map_dimensions(
+        &state,
+        collection,
+        &model,
+        &source_parameter,
+        request.datetime.as_deref(),
+    )
+    .await
+    {
+        Ok(resolved) => resolved,
+        Err(e) => return ogc_exception(&e),
+    };
+
+    // The WMS pipeline takes EPSG:4326 boxes in latitude, longitude order
+    let (wms_bbox, wms_crs) = match request.crs {
+        MapCrs::Crs84 => (
+            format!("{},{},{},{}", bbox[1], bbox[0], bbox[3], bbox[2]),
+            "EPSG:4326",
+        ),
+        MapCrs::WebMercator => (
+            format!("{},{},{},{}", bbox[0], bbox[1], bbox[2], bbox[3]),
+            "EPSG:3857",
+        ),
+    };
+
+    info!(collection = %collection, style = ?style, width = width, height = height,
+          bbox = %wms_bbox, crs = wms_crs, datetime = ?request.datetime, "OGC API map request");
+
+    let timer = Timer::start();
+    let rendered = render_weather_data(
+        &state,
+        collection,
+        style.unwrap_or(""),
+        width,
+        height,
+        Some(&wms_bbox),
+        Some(wms_crs),
+        &dimensions,
+    )
+    .await;
+    let png_data = match rendered {
+        Ok(png_data) if state.underlay.enabled_for_map(None) => {
+            with_underlay(
+                &state,
+                png_data,
+                width,
+                height,
+                Some(&wms_bbox),
+                Some(wms_crs),
+            )
+            .await
+        }
+        Ok(png_data) => png_data,
+        Err(e) => {
+            state.metrics.record_render(timer.elapsed_us(), false).await;
+            error!(collection = %collection, error = ?e, "OGC API map rendering failed");
+            return ogc_exception(&e);
+        }
+    };
+    state.metrics.record_render(timer.elapsed_us(), true).await;
+
+    let (body, format) = match request.format {
+        MapFormat::Png => (png_data, MapFormat::Png),
+        MapFormat::Jpeg => match convert_png_to_jpeg(&png_data) {
+            Ok(jpeg_data) => (jpeg_data, MapFormat::Jpeg),
+            Err(_) => (png_data, MapFormat::Png),
+        },
+        MapFormat::Webp => match convert_png_to_webp(&png_data) {
+            Ok(webp_data) => (webp_data, MapFormat::Webp),
+            Err(_) => (png_data, MapFormat::Png),
+        },
+    };
+
+    let pinned = dimensions.is_pinned(&model, &state.model_dimensions);
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, format.media_type())
+        .header(
+            header::CACHE_CONTROL,
+            state.cache_policy(&model, pinned).header_value(),
+        )
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header("Content-Crs", format!("<{}>", request.crs.uri()))
+        .header(
+            "Content-Bbox",
+            format!("{},{},{},{}", bbox[0], bbox[1], bbox[2], bbox[3]),
+        );
+    for warning in &warnings {
+        response = response.header(header::WARNING, warning);
+    }
+    response.body(body.into()).unwrap()
+}
+
+/// Dimensions for a map at `datetime`, with any time substitution warnings.
+async fn map_dimensions(
+    state: &AppState,
+    collection: &str,
+    model: &str,
+    source_parameter: &str,
+    datetime: Option<&str>,
+) -> Result<(DimensionParams, Vec<String>), WmsError> {
+    let datetime = datetime.filter(|dt| !is_default_keyword(dt));
+
+    if state.model_dimensions.is_observation(model) {
+        let dimensions = DimensionParams {
+            time: datetime.map(str::to_string),
+            ..Default::default()
+        };
+        let warnings = match_layer_times(state, &[collection], &dimensions).await?;
+        return Ok((dimensions, warnings));
+    }
+
+    let Some(datetime) = datetime else {
+        return Ok((DimensionParams::default(), Vec::new()));
+    };
+    let valid_time = parse_iso8601_timestamp(datetime).ok_or_else(|| {
+        WmsError::InvalidDimensionValue(format!(
+            "datetime '{}' is not an ISO 8601 timestamp",
+            datetime
+        ))
+    })?;
+
+    // Newest run first
+    let runs = state
+        .catalog
+        .find_runs_for_valid_time(model, source_parameter, valid_time, None)
+        .await
+        .map_err(|e| WmsError::RenderingError(e.to_string()))?;
+    let run = runs.first().ok_or_else(|| {
+        WmsError::MissingData(format!(
+            "No data available for collection '{}' at {}",
+            collection, datetime
+        ))
+    })?;
+
+    let dimensions = DimensionParams {
+        run: Some(
+            run.reference_time
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+        ),
+        forecast: Some(run.forecast_hour.to_string()),
+        ..Default::default()
+    };
+    Ok((dimensions, Vec::new()))
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Availability of a listed layer: its source parameter's, or the
+/// intersection of its bands' for multi-band composites. Other composite
+/// layers are not listed.
+fn layer_availability(
+    model: &str,
+    layer: &LayerConfig,
+    param_availability: &HashMap<String, ParameterAvailability>,
+) -> Option<ParameterAvailability> {
+    if layer.is_band_composite() {
+        band_composite_availability(model, layer, param_availability)
+    } else if layer.composite {
+        None
+    } else {
+        param_availability
+            .get(&format!("{}_{}", model, layer.source_parameter()))
+            .cloned()
+    }
+}
+
+fn collection_document(
+    state: &AppState,
+    layer_configs: &LayerConfigRegistry,
+    layer: &LayerConfig,
+    model_display_name: &str,
+    availability: &ParameterAvailability,
+    base: &str,
+) -> Collection {
+    let model = layer.id.split('_').next().unwrap_or(&layer.id);
+    let href = format!("{}/collections/{}", base, layer.id);
+    let (west, east, south, north) = normalize_bbox(&availability.bbox);
+
+    let styles = style_names_from_file(&layer_configs.get_style_path(layer), &layer.style_policy)
+        .into_iter()
+        .map(|id| CollectionStyle {
+            links: vec![Link::new(format!("{}/styles/{}/map", href, id), MAP_REL)
+                .with_type("image/png")
+                .with_title(format!("{} map in style {}", layer.title, id))],
+            id,
+            title: None,
+        })
+        .collect();
+
+    Collection {
+        id: layer.id.clone(),
+        title: format!("{} - {}", model_display_name, layer.title),
+        description: layer.abstract_text.clone(),
+        extent: Some(Extent {
+            spatial: SpatialExtent::crs84([west, south, east, north]),
+            temporal: temporal_extent(availability, state.model_dimensions.is_observation(model)),
+        }),
+        crs: [MapCrs::Crs84, MapCrs::WebMercator]
+            .iter()
+            .map(|crs| crs.uri().to_string())
+            .collect(),
+        styles,
+        links: vec![
+            Link::new(&href, "self").with_type("application/json"),
+            Link::new(format!("{}?f=html", href), "alternate").with_type("text/html"),
+            Link::new(format!("{}/map", href), MAP_REL)
+                .with_type("image/png")
+                .with_title(format!("{} map", layer.title)),
+        ],
+    }
+}
+
+/// Valid times the layer has data for: observation times, or each run plus
+/// its forecast hours.
+fn temporal_extent(
+    availability: &ParameterAvailability,
+    observational: bool,
+) -> Option<TemporalExtent> {
+    let times: Vec<_> = availability
+        .times
+        .iter()
+        .filter_map(|t| parse_iso8601_timestamp(t))
+        .collect();
+    let (mut start, mut end) = (*times.iter().min()?, *times.iter().max()?);
+    if !observational {
+        let hours = |h: Option<&i32>| chrono::Duration::hours(h.copied().unwrap_or(0) as i64);
+        start += hours(availability.forecast_hours.iter().min());
+        end += hours(availability.forecast_hours.iter().max());
+    }
+    let format =
+        |t: chrono::DateTime<chrono::Utc>| Some(t.to_rfc3339_opts(SecondsFormat::Secs, true));
+    Some(TemporalExtent::gregorian(format(start), format(end)))
+}
+
+/// Serialize a metadata document as JSON or HTML.
+fn document<T: Serialize>(
+    headers: &HeaderMap,
+    params: &DocumentParams,
+    doc: &T,
+    to_html: fn(&T) -> String,
+) -> Response {
+    let accept = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok());
+    let (body, content_type) = match DocumentFormat::negotiate(params.f.as_deref(), accept) {
+        Ok(DocumentFormat::Html) => (to_html(doc), "text/html; charset=utf-8"),
+        Ok(DocumentFormat::Json) => match serde_json::to_string_pretty(doc) {
+            Ok(json) => (json, "application/json"),
+            Err(e) => {
+                return ogc_response(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "NoApplicableCode",
+                    &e.to_string(),
+                )
+            }
+        },
+        Err(e) => {
+            return ogc_response(
+                StatusCode::BAD_REQUEST,
+                "InvalidParameterValue",
+                &e.to_string(),
+            )
+        }
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(body.into())
+        .unwrap()
+}
+
+fn unknown_collection(collection: &str) -> WmsError {
+    WmsError::LayerNotDefined(format!("Collection '{}' is not defined.", collection))
+}
+
+/// OGC API exception for a WMS error. Unknown collections and styles are
+/// missing resources (404) rather than bad requests as in WMS.
+fn ogc_exception(e: &WmsError) -> Response {
+    let status = match e {
+        WmsError::LayerNotDefined(_) | WmsError::StyleNotDefined(_) => StatusCode::NOT_FOUND,
+        _ => e.status_code(),
+    };
+    ogc_response(status, e.code(), &e.message())
+}
+
+fn ogc_response(status: StatusCode, code: &str, description: &str) -> Response {
+    let exception = ApiException {
+        code: code.to_string(),
+        description: description.to_string(),
+    };
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(serde_json::to_string(&exception).unwrap_or_default().into())
+        .unwrap()
+}
+
+fn request_api_key(headers: &HeaderMap, pairs: &[(String, String)]) -> ApiKey {
+    ApiKey::resolve(
+        headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()),
+        pairs
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(API_KEY_QUERY_PARAM))
+            .map(|(_, v)| v.as_str()),
+    )
+}
+
+fn request_tenant(
+    state: &AppState,
+    headers: &HeaderMap,
+    pairs: &[(String, String)],
+) -> Option<Tenant> {
+    state
+        .tenants
+        .resolve(&request_api_key(headers, pairs))
+        .cloned()
+}
+
+/// Scheme and host the request was made to, honoring a proxy's
+/// `X-Forwarded-Proto`.
+fn host_url(headers: &HeaderMap) -> String {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("localhost:8080");
+    let scheme = headers
+        .get("x-forwarded-proto")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("http");
+    format!("{}://{}", scheme, host)
+}
+
+/// Base URL of the OGC API, for links.
+fn base_url(headers: &HeaderMap) -> String {
+    format!("{}{}", host_url(headers), OGC_API_PATH)
+}
@@ -0,0 +1,253 @@
+//! Model run comparison API.
+//!
+//! Compares the same parameter across consecutive model runs at a single
+//! point and valid time ("run-to-run wobble"). Every run whose forecast
+//! covers the valid time contributes one value, so the response can be
+//! plotted directly as a plume or spaghetti chart.
+
+use axum::{
+    extract::{Extension, Path, Query},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::{info, instrument, warn};
+
+use super::common::parse_iso8601_timestamp;
+use crate::rendering::loaders::query_point_from_zarr;
+use crate::state::AppState;
+
+/// Default number of runs compared when `limit` is not given.
+const DEFAULT_RUN_LIMIT: usize = 20;
+
+/// Upper bound on runs read in a single request.
+const MAX_RUN_LIMIT: usize = 100;
+
+// ============================================================================
+// Response Types
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+pub struct RunComparisonResponse {
+    pub model: String,
+    pub parameter: String,
+    pub level: Option<String>,
+    pub units: Option<String>,
+    pub lon: f64,
+    pub lat: f64,
+    pub valid_time: String,
+    /// One value per run, oldest run first
+    pub runs: Vec<RunValue>,
+    pub summary: RunSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunValue {
+    pub reference_time: String,
+    pub forecast_hour: u32,
+    /// None if the point is outside the grid, missing, or the read failed
+    pub value: Option<f32>,
+}
+
+/// Spread statistics across runs (ignoring missing values).
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct RunSummary {
+    pub count: usize,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub mean: Option<f32>,
+    /// max - min
+    pub spread: Option<f32>,
+    /// Latest run value minus the previous run value
+    pub latest_change: Option<f32>,
+}
+
+// ============================================================================
+// Query Parameters
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct RunComparisonQuery {
+    pub lon: f64,
+    pub lat: f64,
+    pub valid_time: String,
+    /// Vertical level; defaults to the level of the latest run
+    pub level: Option<String>,
+    /// Maximum number of runs (newest first), default 20
+    pub limit: Option<usize>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// GET /api/run-comparison/:model/:parameter - Compare runs at a point
+#[instrument(skip(state))]
+pub async fn run_comparison_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path((model, parameter)): Path<(String, String)>,
+    Query(query): Query<RunComparisonQuery>,
+) -> Result<Json<RunComparisonResponse>, (StatusCode, String)> {
+    let valid_time = parse_iso8601_timestamp(&query.valid_time).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid valid_time '{}'", query.valid_time),
+        )
+    })?;
+    if !(-90.0..=90.0).contains(&query.lat) || !(-180.0..=360.0).contains(&query.lon) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Coordinates out of range: lon={}, lat={}",
+                query.lon, query.lat
+            ),
+        ));
+    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_RUN_LIMIT)
+        .clamp(1, MAX_RUN_LIMIT);
+
+    info!(
+        model = %model,
+        parameter = %parameter,
+        valid_time = %valid_time,
+        "Run comparison request"
+    );
+
+    let mut entries = state
+        .catalog
+        .find_runs_for_valid_time(&model, &parameter, valid_time, query.level.as_deref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to find runs for valid time");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        })?;
+
+    // Without an explicit level, follow the latest run's level
+    let level = query
+        .level
+        .clone()
+        .or_else(|| entries.first().map(|e| e.level.clone()));
+    if let Some(level) = &level {
+        entries.retain(|e| &e.level == level);
+    }
+    entries.truncate(limit);
+    entries.reverse();
+
+    let units = entries
+        .iter()
+        .find_map(|e| e.zarr_metadata.as_ref())
+        .and_then(|json| grid_processor::ZarrMetadata::from_json(json).ok())
+        .map(|meta| meta.units);
+
+    // Read all runs concurrently; each read touches a single chunk
+    let mut reads = JoinSet::new();
+    for (idx, entry) in entries.iter().cloned().enumerate() {
+        let state = state.clone();
+        let (lon, lat) = (query.lon, query.lat);
+        reads.spawn(async move {
+            let value = query_point_from_zarr(&state.grid_processor_factory, &entry, lon, lat)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(
+                        reference_time = %entry.reference_time,
+                        error = %e,
+                        "Run comparison point read failed"
+                    );
+                    None
+                });
+            (idx, value)
+        });
+    }
+
+    let mut values = vec![None; entries.len()];
+    while let Some(result) = reads.join_next().await {
+        if let Ok((idx, value)) = result {
+            values[idx] = value;
+        }
+    }
+
+    let summary = summarize(&values);
+    let runs = entries
+        .iter()
+        .zip(values)
+        .map(|(entry, value)| RunValue {
+            reference_time: entry.reference_time.to_rfc3339(),
+            forecast_hour: entry.forecast_hour,
+            value,
+        })
+        .collect();
+
+    Ok(Json(RunComparisonResponse {
+        model,
+        parameter,
+        level,
+        units,
+        lon: query.lon,
+        lat: query.lat,
+        valid_time: valid_time.to_rfc3339(),
+        runs,
+        summary,
+    }))
+}
+
+/// Summarize values ordered oldest run first.
+fn summarize(values: &[Option<f32>]) -> RunSummary {
+    let present: Vec<f32> = values.iter().flatten().copied().collect();
+    if present.is_empty() {
+        return RunSummary::default();
+    }
+
+    let min = present.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = present.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let mean = present.iter().sum::<f32>() / present.len() as f32;
+    let latest_change = match values {
+        [.., Some(previous), Some(latest)] => Some(latest - previous),
+        _ => None,
+    };
+
+    RunSummary {
+        count: present.len(),
+        min: Some(min),
+        max: Some(max),
+        mean: Some(mean),
+        spread: Some(max - min),
+        latest_change,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_ignores_missing_values() {
+        let summary = summarize(&[Some(280.0), None, Some(284.0), Some(283.0)]);
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.min, Some(280.0));
+        assert_eq!(summary.max, Some(284.0));
+        assert!((summary.mean.unwrap() - 282.333).abs() < 1e-3);
+        assert_eq!(summary.spread, Some(4.0));
+        assert_eq!(summary.latest_change, Some(-1.0));
+    }
+
+    #[test]
+    fn test_summarize_latest_change_requires_last_two_runs() {
+        let summary = summarize(&[Some(1.0), Some(2.0), None]);
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.latest_change, None);
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        assert_eq!(summarize(&[None, None]), RunSummary::default());
+        assert_eq!(summarize(&[]), RunSummary::default());
+    }
+}
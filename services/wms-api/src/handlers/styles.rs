@@ -0,0 +1,373 @@
+//! Style metadata API.
+//!
+//! Serves parsed style definitions as JSON so web clients can draw their own
+//! legends: the color stops, units and labels from the style file, the
+//! palette the renderer actually paints with, and the legend breaks. Layer
+//! responses also carry the parameter's catalog metadata (long name, units)
+//! for the legend title.
+//!
+//! Responses carry an `ETag` derived from the style file version and the
+//! response body; clients revalidate with `If-None-Match` and get
+//! `304 Not Modified` until the style changes.
+
+use axum::{
+    extract::{Extension, Path},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use renderer::style::{LegendEntry, StyleConfig, StyleDefinition};
+use serde::Serialize;
+use std::sync::Arc;
+use storage::ParameterMetadata;
+use tracing::{info, instrument, warn};
+
+use crate::state::AppState;
+
+// ============================================================================
+// Response Types
+// ============================================================================
+
+/// One style of a style file.
+#[derive(Debug, Serialize)]
+pub struct StyleMetadata {
+    /// Style ID accepted by `/api/styles/:style` (`file:name`)
+    pub id: String,
+    /// Style name as used in WMS `STYLES` / WMTS `STYLE`
+    pub name: String,
+    /// Style definition as parsed from the style file
+    pub definition: StyleDefinition,
+    /// Colors the renderer maps values to, for color ramp styles
+    pub palette: Option<PaletteSummary>,
+    /// Labeled color stops in ascending order
+    pub breaks: Vec<LegendEntry>,
+}
+
+/// Precomputed rendering palette of a style.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PaletteSummary {
+    pub min_value: f32,
+    pub max_value: f32,
+    /// Colors evenly spaced from `min_value` to `max_value`, as `#RRGGBB`
+    /// (or `#RRGGBBAA` when not opaque)
+    pub colors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StyleResponse {
+    /// Style file name without extension
+    pub file: String,
+    /// Style file version
+    pub version: String,
+    #[serde(flatten)]
+    pub style: StyleMetadata,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LayerStylesResponse {
+    pub layer: String,
+    pub file: String,
+    pub version: String,
+    /// Style rendered when the client does not name one
+    pub default_style: String,
+    /// Deprecated style names mapped to their replacements
+    pub aliases: std::collections::BTreeMap<String, String>,
+    /// All styles of the layer's style file, sorted by name
+    pub styles: Vec<StyleMetadata>,
+    /// Long name, units and valid range of the layer's parameter, once
+    /// ingested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter: Option<ParameterMetadata>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// GET /api/styles/:style - Get one style as JSON
+///
+/// `style` is `file:name` (e.g. `temperature:default`), or just `file` for
+/// the file's default style.
+#[instrument(skip(state, headers))]
+pub async fn style_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(style): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+    info!(style = %style, "Style metadata request");
+
+    let (file, name) = match style.split_once(':') {
+        Some((file, name)) => (file, Some(name)),
+        None => (style.as_str(), None),
+    };
+    let file = validate_file_name(file)?;
+
+    let path = {
+        let configs = state.layer_configs.read().await;
+        format!("{}/{}.json", configs.style_dir(), file)
+    };
+    let config = load_style_config(&path)?;
+
+    let (name, definition) = match name {
+        Some(name) => config
+            .styles
+            .get_key_value(name)
+            .ok_or_else(|| not_found(format!("Style '{}' not found in '{}'", name, file)))?,
+        None => config
+            .get_default_style()
+            .ok_or_else(|| not_found(format!("Style file '{}' has no styles", file)))?,
+    };
+
+    let response = StyleResponse {
+        file: file.to_string(),
+        version: config.version.clone(),
+        style: style_metadata(file, name, definition),
+    };
+    json_with_etag(&response, &config.version, &headers)
+}
+
+/// GET /api/layers/:layer/styles - Get all styles of a layer as JSON
+#[instrument(skip(state, headers))]
+pub async fn layer_styles_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Path(layer): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+    info!(layer = %layer, "Layer styles request");
+
+    let (path, file, policy, parameter) = {
+        let configs = state.layer_configs.read().await;
+        let layer_config = configs
+            .get_layer(&layer)
+            .ok_or_else(|| not_found(format!("Layer '{}' not found", layer)))?;
+        let file = layer_config
+            .style_file
+            .trim_end_matches(".json")
+            .to_string();
+        (
+            configs.get_style_path(layer_config),
+            file,
+            layer_config.style_policy.clone(),
+            layer_config.source_parameter().to_string(),
+        )
+    };
+    // Layer IDs are `{model}_{layer}`; get_layer has checked the format
+    let model = layer.split_once('_').map_or("", |(model, _)| model);
+    let parameter = state
+        .catalog
+        .get_parameter_metadata(model, &parameter)
+        .await
+        .unwrap_or_else(|e| {
+            warn!(layer = %layer, error = %e, "Failed to get parameter metadata");
+            None
+        });
+    let config = load_style_config(&path)?;
+
+    let mut names: Vec<&String> = config.styles.keys().collect();
+    names.sort();
+    let styles = names
+        .into_iter()
+        .map(|name| style_metadata(&file, name, &config.styles[name]))
+        .collect();
+
+    // Like rendering, fall back to the file's default style when the policy
+    // names a style the file does not have
+    let mut default_style = policy.resolve(None).name;
+    if !config.styles.contains_key(&default_style) {
+        if let Some(name) = config.default_style_name() {
+            default_style = name.to_string();
+        }
+    }
+
+    let response = LayerStylesResponse {
+        layer,
+        file,
+        version: config.version.clone(),
+        default_style,
+        aliases: policy.aliases.into_iter().collect(),
+        styles,
+        parameter,
+    };
+    json_with_etag(&response, &config.version, &headers)
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn not_found(message: String) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, message)
+}
+
+/// Reject style file names that could escape the styles directory.
+fn validate_file_name(file: &str) -> Result<&str, (StatusCode, String)> {
+    let file = file.trim_end_matches(".json");
+    let valid = !file.is_empty()
+        && file
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(file)
+    } else {
+        Err((
+            StatusCode::BAD_REQUEST,
+            format!("Invalid style file name '{}'", file),
+        ))
+    }
+}
+
+fn load_style_config(path: &str) -> Result<StyleConfig, (StatusCode, String)> {
+    if !std::path::Path::new(path).exists() {
+        return Err(not_found(format!("Style file '{}' not found", path)));
+    }
+    StyleConfig::from_file(path).map_err(|e| {
+        tracing::error!(path = %path, error = %e, "Failed to parse style file");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to parse style file: {}", e),
+        )
+    })
+}
+
+fn style_metadata(file: &str, name: &str, definition: &StyleDefinition) -> StyleMetadata {
+    let palette = definition.compute_palette().map(|palette| PaletteSummary {
+        min_value: palette.min_value,
+        max_value: palette.max_value,
+        // Index 0 is the transparent no-data color
+        colors: palette.colors[1..]
+            .iter()
+            .map(|&(r, g, b, a)| {
+                if a == 255 {
+                    format!("#{:02X}{:02X}{:02X}", r, g, b)
+                } else {
+                    format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+                }
+            })
+            .collect(),
+    });
+
+    StyleMetadata {
+        id: format!("{}:{}", file, name),
+        name: name.to_string(),
+        definition: definition.clone(),
+        palette,
+        breaks: definition.legend_entries(),
+    }
+}
+
+/// Strong ETag of a response body: the style file version plus a checksum,
+/// so edits that forget to bump the version still change it.
+fn etag(version: &str, body: &[u8]) -> String {
+    format!("\"{}-{:08x}\"", version, crc32fast::hash(body))
+}
+
+/// Whether an `If-None-Match` header value matches `etag`.
+fn if_none_match(header_value: &str, etag: &str) -> bool {
+    header_value.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+fn json_with_etag<T: Serialize>(
+    value: &T,
+    version: &str,
+    headers: &HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+    let body = serde_json::to_vec(value)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let etag = etag(version, &body);
+
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| if_none_match(v, &etag));
+    if not_modified {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, "no-cache".to_string()),
+            ],
+        )
+            .into_response());
+    }
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/json".to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, "no-cache".to_string()),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLES: &str = r##"{
+        "version": "1.2",
+        "styles": {
+            "default": {
+                "name": "Temperature",
+                "type": "gradient",
+                "default": true,
+                "units": "K",
+                "range": { "min": 250.0, "max": 300.0 },
+                "stops": [
+                    { "value": 300.0, "color": "#FF0000", "label": "Hot" },
+                    { "value": 250.0, "color": "#0000FF" }
+                ]
+            },
+            "isolines": {
+                "name": "Isolines",
+                "type": "contour",
+                "stops": []
+            }
+        }
+    }"##;
+
+    #[test]
+    fn test_style_metadata() {
+        let config = StyleConfig::from_json(STYLES).unwrap();
+        let metadata = style_metadata("temperature", "default", &config.styles["default"]);
+
+        assert_eq!(metadata.id, "temperature:default");
+        let palette = metadata.palette.unwrap();
+        assert_eq!((palette.min_value, palette.max_value), (250.0, 300.0));
+        assert_eq!(palette.colors.len(), 255);
+        assert_eq!(palette.colors.first().unwrap(), "#0000FF");
+        assert_eq!(palette.colors.last().unwrap(), "#FF0000");
+
+        // Breaks are sorted and labeled
+        let values: Vec<f32> = metadata.breaks.iter().map(|b| b.value).collect();
+        assert_eq!(values, vec![250.0, 300.0]);
+        assert_eq!(metadata.breaks[1].label, "Hot");
+
+        let contour = style_metadata("temperature", "isolines", &config.styles["isolines"]);
+        assert!(contour.palette.is_none());
+        assert!(contour.breaks.is_empty());
+    }
+
+    #[test]
+    fn test_validate_file_name() {
+        assert_eq!(validate_file_name("temperature").unwrap(), "temperature");
+        assert_eq!(validate_file_name("goes_ir.json").unwrap(), "goes_ir");
+        assert!(validate_file_name("../secrets").is_err());
+        assert!(validate_file_name("").is_err());
+    }
+
+    #[test]
+    fn test_etag_revalidation() {
+        let tag = etag("1.2", b"{}");
+        assert!(tag.starts_with("\"1.2-") && tag.ends_with('"'));
+        assert_ne!(tag, etag("1.2", b"{ }"));
+
+        assert!(if_none_match(&tag, &tag));
+        assert!(if_none_match(&format!("\"other\", W/{}", tag), &tag));
+        assert!(if_none_match("*", &tag));
+        assert!(!if_none_match("\"1.2-00000000\"", &tag));
+    }
+}
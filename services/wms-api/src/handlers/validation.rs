@@ -1,7 +1,11 @@
 //! Validation API handlers for WMS/WMTS testing.
 //! //TODO this can be removed once we have our validation pages locked in
 
-use axum::{extract::Extension, http::StatusCode, Json};
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    Json,
+};
 use std::sync::Arc;
 use tracing::{info, instrument};
 
@@ -54,10 +58,40 @@ pub async fn startup_validation_run_handler(
         "skipped": summary.skipped,
         "duration_ms": summary.duration_ms,
         "models_available": summary.models_available,
-        "models_missing": summary.models_missing
+        "models_missing": summary.models_missing,
+        "golden_mismatches": summary.golden_mismatches,
+        "golden_checks": summary.golden_checks
     })))
 }
 
+/// GET /api/validation/request - Check WMS request parameters against the spec
+///
+/// Takes the parameters of a WMS request as its own query string and lists
+/// their deviations, as well as whether the server would accept the request
+/// in its current parse mode.
+#[instrument(skip(state))]
+pub async fn request_validation_handler(
+    Extension(state): Extension<Arc<AppState>>,
+    Query(pairs): Query<Vec<(String, String)>>,
+) -> Json<serde_json::Value> {
+    let report = wms_protocol::ParseReport {
+        mode: state.wms_parse_mode,
+        deviations: wms_protocol::check_wms_kvp(&pairs),
+    };
+    let error = match state.wms_parse_mode {
+        wms_protocol::ParseMode::Strict => report.strict_error().map(|e| e.to_string()),
+        wms_protocol::ParseMode::Lenient => None,
+    };
+
+    Json(serde_json::json!({
+        "mode": report.mode,
+        "compliant": report.is_compliant(),
+        "accepted": error.is_none(),
+        "error": error,
+        "deviations": report.deviations,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -7,22 +7,29 @@
 
 use axum::{
     extract::{Extension, Query},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::Response,
 };
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info, instrument};
+use tracing::{error, info, instrument, warn};
 
 use super::common::{
-    convert_png_to_jpeg, convert_png_to_webp, get_styles_xml_from_file, mercator_to_wgs84,
-    wms_exception, DimensionParams,
+    band_composite_availability, convert_png_to_jpeg, convert_png_to_webp,
+    get_styles_xml_from_file, mercator_to_wgs84, parse_iso8601_timestamp, wms_exception,
+    DimensionParams,
 };
-use crate::layer_config::LayerConfigRegistry;
+use crate::layer_config::{LayerConfigRegistry, TemporalConfig, VerticalConfig};
 use crate::model_config::ModelDimensionRegistry;
+use crate::request_limits::{MapRequest, RequestLimits};
 use crate::state::AppState;
-use storage::ParameterAvailability;
+use crate::time_match::{is_default_keyword, match_time};
+use crate::update_cadence::{CachePolicy, DEFAULT_CADENCE};
+use grid_processor::LevelRange;
+use storage::{CanonicalLevel, ParameterAvailability, ParameterMetadata};
+use wms_common::api_key::{ApiKey, API_KEY_HEADER, API_KEY_QUERY_PARAM};
+use wms_common::Tenant;
 
 // ============================================================================
 // WMS Error Types (OGC Exception Codes)
@@ -49,8 +56,14 @@ pub enum WmsError {
     InvalidBBox(String),
     /// No data available for the requested layer/dimension combination (MissingDimensionValue)
     MissingData(String),
+    /// Dimension value is not offered by the layer (InvalidDimensionValue)
+    InvalidDimensionValue(String),
+    /// Request exceeds a size or complexity limit (InvalidParameterValue)
+    LimitExceeded(String),
     /// Internal rendering error (NoApplicableCode)
     RenderingError(String),
+    /// Storage or catalog failed while reading data; retryable (NoApplicableCode, 502)
+    UpstreamError(String),
 }
 
 impl WmsError {
@@ -63,7 +76,10 @@ impl WmsError {
             WmsError::InvalidFormat(_) => "InvalidFormat",
             WmsError::InvalidBBox(_) => "InvalidParameterValue",
             WmsError::MissingData(_) => "MissingDimensionValue",
+            WmsError::InvalidDimensionValue(_) => "InvalidDimensionValue",
+            WmsError::LimitExceeded(_) => "InvalidParameterValue",
             WmsError::RenderingError(_) => "NoApplicableCode",
+            WmsError::UpstreamError(_) => "NoApplicableCode",
         }
     }
 
@@ -76,7 +92,10 @@ impl WmsError {
             WmsError::InvalidFormat(msg) => msg.clone(),
             WmsError::InvalidBBox(msg) => msg.clone(),
             WmsError::MissingData(msg) => msg.clone(),
+            WmsError::InvalidDimensionValue(msg) => msg.clone(),
+            WmsError::LimitExceeded(msg) => msg.clone(),
             WmsError::RenderingError(msg) => format!("Rendering failed: {}", msg),
+            WmsError::UpstreamError(msg) => msg.clone(),
         }
     }
 
@@ -89,7 +108,10 @@ impl WmsError {
             WmsError::InvalidFormat(_) => StatusCode::BAD_REQUEST,
             WmsError::InvalidBBox(_) => StatusCode::BAD_REQUEST,
             WmsError::MissingData(_) => StatusCode::NOT_FOUND,
+            WmsError::InvalidDimensionValue(_) => StatusCode::BAD_REQUEST,
+            WmsError::LimitExceeded(_) => StatusCode::BAD_REQUEST,
             WmsError::RenderingError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            WmsError::UpstreamError(_) => StatusCode::BAD_GATEWAY,
         }
     }
 
@@ -107,6 +129,10 @@ impl WmsError {
         if err.contains("layer") && (err.contains("not found") || err.contains("not defined")) {
             return WmsError::LayerNotDefined(err);
         }
+        // Storage or catalog failures, tagged by the grid loaders
+        if err.contains(crate::rendering::loaders::UPSTREAM_UNAVAILABLE) {
+            return WmsError::UpstreamError(err);
+        }
         // Detect missing data errors (no data available for the requested dimensions)
         if err.contains("No data found")
             || err.contains("no data available")
@@ -208,6 +234,51 @@ fn validate_bbox(bbox: Option<&str>, crs: Option<&str>) -> Result<(), WmsError>
     Ok(())
 }
 
+/// Parse a request BBOX into a WGS84 extent [min_lon, min_lat, max_lon, max_lat].
+fn request_extent(bbox: Option<&str>, crs: Option<&str>) -> Option<[f64; 4]> {
+    let coords: Vec<f64> = bbox?
+        .split(',')
+        .filter_map(|s| s.trim().parse().ok())
+        .collect();
+    if coords.len() != 4 {
+        return None;
+    }
+
+    if crs.unwrap_or("EPSG:4326").contains("3857") {
+        let (min_lon, min_lat) = mercator_to_wgs84(coords[0], coords[1]);
+        let (max_lon, max_lat) = mercator_to_wgs84(coords[2], coords[3]);
+        Some([min_lon, min_lat, max_lon, max_lat])
+    } else {
+        // WMS 1.3.0 with EPSG:4326 uses axis order lat,lon
+        Some([coords[1], coords[0], coords[3], coords[2]])
+    }
+}
+
+/// Check a GetMap request against the service-wide limits and those of
+/// each requested layer.
+pub(crate) async fn check_limits(
+    state: &AppState,
+    layer_names: &[&str],
+    request: &MapRequest,
+) -> Result<(), WmsError> {
+    state
+        .request_limits
+        .check(request)
+        .map_err(WmsError::LimitExceeded)?;
+
+    let layer_configs = state.layer_configs.read().await;
+    for layer in layer_names
+        .iter()
+        .filter_map(|name| layer_configs.get_layer(name))
+    {
+        layer
+            .limits
+            .check(&layer.id, request)
+            .map_err(WmsError::LimitExceeded)?;
+    }
+    Ok(())
+}
+
 // ============================================================================
 // WMS Parameters
 // ============================================================================
@@ -247,6 +318,14 @@ pub struct WmsParams {
     pub forecast: Option<String>,
     #[serde(rename = "ELEVATION", alias = "elevation")]
     pub elevation: Option<String>,
+    // WINDOW: For temporal composite layers - ISO8601 duration (e.g., PT1H)
+    #[serde(
+        rename = "DIM_WINDOW",
+        alias = "dim_window",
+        alias = "WINDOW",
+        alias = "window"
+    )]
+    pub window: Option<String>,
     #[serde(rename = "TRANSPARENT", alias = "transparent")]
     pub transparent: Option<String>,
     // GetFeatureInfo parameters
@@ -260,17 +339,67 @@ pub struct WmsParams {
     pub j: Option<u32>,
     #[serde(rename = "FEATURE_COUNT", alias = "feature_count")]
     pub feature_count: Option<u32>,
+    // Vendor parameter: PROVENANCE=true adds the dataset and chunks each
+    // GetFeatureInfo value was read from
+    #[serde(rename = "PROVENANCE", alias = "provenance")]
+    pub provenance: Option<String>,
+    // Vendor parameter: UNDERLAY=false leaves out the configured basemap or
+    // land/sea underlay beneath GetMap images
+    #[serde(rename = "UNDERLAY", alias = "underlay")]
+    pub underlay: Option<String>,
 }
 
 // ============================================================================
 // WMS Handler Entry Point
 // ============================================================================
 
-#[instrument(skip(state))]
+/// Header listing a request's deviations from the WMS specification.
+pub const WMS_DEVIATIONS_HEADER: &str = "x-wms-deviations";
+
+#[instrument(skip(state, headers, pairs))]
 pub async fn wms_handler(
     Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
     Query(params): Query<WmsParams>,
+    Query(pairs): Query<Vec<(String, String)>>,
 ) -> Response {
+    // Strict mode rejects requests that deviate from the spec, lenient mode
+    // serves them and lists the deviations in a response header
+    let report = match wms_protocol::parse_wms_kvp(&pairs, state.wms_parse_mode) {
+        Ok(report) => report,
+        Err(e) => {
+            return wms_exception(
+                e.wms_exception_code(),
+                &e.to_string(),
+                StatusCode::BAD_REQUEST,
+            )
+        }
+    };
+    if !report.is_compliant() {
+        tracing::debug!(deviations = ?report.header_value(), "Non-compliant WMS request");
+    }
+
+    let api_key = ApiKey::resolve(
+        headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()),
+        pairs
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(API_KEY_QUERY_PARAM))
+            .map(|(_, v)| v.as_str()),
+    );
+
+    let mut response = wms_dispatch(state, params, api_key).await;
+    if let Some(value) = report
+        .header_value()
+        .and_then(|v| header::HeaderValue::from_str(&v).ok())
+    {
+        response.headers_mut().insert(WMS_DEVIATIONS_HEADER, value);
+    }
+    response
+}
+
+async fn wms_dispatch(state: Arc<AppState>, params: WmsParams, api_key: ApiKey) -> Response {
+    let tenant = state.tenants.resolve(&api_key).cloned();
+
     // Normalize service parameter to uppercase for comparison
     let service = params.service.as_deref().map(|s| s.to_uppercase());
     if service.as_deref() != Some("WMS") {
@@ -283,10 +412,13 @@ pub async fn wms_handler(
 
     // Normalize request parameter to match pattern
     let request = params.request.as_deref().map(|s| s.to_uppercase());
+    if let (Some(tenant), Some(name)) = (&tenant, request_name(request.as_deref())) {
+        state.tenant_usage.record(tenant, "wms", name);
+    }
     match request.as_deref() {
-        Some("GETCAPABILITIES") => wms_get_capabilities(state, params).await,
-        Some("GETMAP") => wms_get_map(state, params).await,
-        Some("GETFEATUREINFO") => wms_get_feature_info(state, params).await,
+        Some("GETCAPABILITIES") => wms_get_capabilities(state, params, tenant.as_ref()).await,
+        Some("GETMAP") => wms_get_map(state, params, api_key, tenant.as_ref()).await,
+        Some("GETFEATUREINFO") => wms_get_feature_info(state, params, tenant.as_ref()).await,
         Some(req) => wms_exception(
             "OperationNotSupported",
             &format!("Unknown request: {}", req),
@@ -300,15 +432,54 @@ pub async fn wms_handler(
     }
 }
 
+/// Request name used in per-tenant usage metrics.
+fn request_name(request: Option<&str>) -> Option<&'static str> {
+    match request? {
+        "GETCAPABILITIES" => Some("GetCapabilities"),
+        "GETMAP" => Some("GetMap"),
+        "GETFEATUREINFO" => Some("GetFeatureInfo"),
+        _ => None,
+    }
+}
+
+/// Reject layers outside the caller's tenant the same way as undefined
+/// layers, so a tenant can't discover what others are offered.
+pub(crate) fn check_tenant_layers(
+    state: &AppState,
+    tenant: Option<&Tenant>,
+    layers: &[&str],
+    service: &'static str,
+    request: &str,
+) -> Result<(), WmsError> {
+    let Some(tenant) = tenant else {
+        return Ok(());
+    };
+    match layers.iter().find(|layer| !tenant.allows_layer(layer)) {
+        Some(layer) => {
+            state.tenant_usage.record_denied(tenant, service, request);
+            Err(WmsError::LayerNotDefined(format!(
+                "Layer '{}' is not defined.",
+                layer
+            )))
+        }
+        None => Ok(()),
+    }
+}
+
 // ============================================================================
 // GetCapabilities
 // ============================================================================
 
-async fn wms_get_capabilities(state: Arc<AppState>, params: WmsParams) -> Response {
+async fn wms_get_capabilities(
+    state: Arc<AppState>,
+    params: WmsParams,
+    tenant: Option<&Tenant>,
+) -> Response {
     let version = params.version.as_deref().unwrap_or("1.3.0");
+    let tenant_id = tenant.map(|t| t.id.as_str());
 
     // Check cache first
-    if let Some(cached_xml) = state.capabilities_cache.get_wms().await {
+    if let Some(cached_xml) = state.capabilities_cache.get_wms(tenant_id).await {
         return Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, "application/xml")
@@ -318,42 +489,36 @@ async fn wms_get_capabilities(state: Arc<AppState>, params: WmsParams) -> Respon
     }
 
     // Build capabilities from layer configs (config-driven approach)
-    // Only include layers that have data in the catalog
-    let layer_configs = state.layer_configs.read().await;
+    // Only include layers that have data in the catalog, and that the
+    // caller's tenant may see
+    let all_layer_configs = state.layer_configs.read().await;
+    let scoped_layer_configs;
+    let layer_configs = match tenant {
+        Some(tenant) => {
+            scoped_layer_configs = all_layer_configs.scoped(|l| tenant.allows_layer(&l.id));
+            &scoped_layer_configs
+        }
+        None => &*all_layer_configs,
+    };
 
     // Collect availability data for each configured layer
-    let mut param_availability: HashMap<String, storage::ParameterAvailability> = HashMap::new();
-
-    for model_id in layer_configs.models() {
-        if let Some(model_config) = layer_configs.get_model(model_id) {
-            for layer in &model_config.layers {
-                // Skip composite layers - they're handled separately
-                if layer.composite {
-                    continue;
-                }
-
-                // Check if data exists for this layer
-                if let Ok(Some(availability)) = state
-                    .catalog
-                    .get_parameter_availability(model_id, &layer.parameter)
-                    .await
-                {
-                    let key = format!("{}_{}", model_id, layer.parameter);
-                    param_availability.insert(key, availability);
-                }
-            }
-        }
-    }
+    let param_availability = collect_parameter_availability(&state, layer_configs).await;
+    let param_metadata = collect_parameter_metadata(&state, layer_configs).await;
 
     let xml = build_wms_capabilities_xml_v2(
         version,
-        &layer_configs,
+        layer_configs,
         &param_availability,
+        &param_metadata,
         &state.model_dimensions,
+        &state.request_limits,
     );
 
     // Cache the result
-    state.capabilities_cache.set_wms(xml.clone()).await;
+    state
+        .capabilities_cache
+        .set_wms(tenant_id, xml.clone())
+        .await;
 
     Response::builder()
         .status(StatusCode::OK)
@@ -363,11 +528,99 @@ async fn wms_get_capabilities(state: Arc<AppState>, params: WmsParams) -> Respon
         .unwrap()
 }
 
+/// Catalog availability of the parameters behind the configured layers,
+/// keyed by `{model}_{parameter}`. Parameters without data are left out.
+pub(crate) async fn collect_parameter_availability(
+    state: &AppState,
+    layer_configs: &LayerConfigRegistry,
+) -> HashMap<String, ParameterAvailability> {
+    let mut param_availability: HashMap<String, ParameterAvailability> = HashMap::new();
+
+    for model_id in layer_configs.models() {
+        if let Some(model_config) = layer_configs.get_model(model_id) {
+            for layer in &model_config.layers {
+                // Skip composite layers - they're handled separately. Multi-band
+                // composites need the availability of each of their bands,
+                // temporal composites and ensemble products that of their
+                // source parameter.
+                let parameters: Vec<&str> = if layer.is_band_composite() {
+                    layer.requires.iter().map(String::as_str).collect()
+                } else if layer.composite {
+                    continue;
+                } else {
+                    vec![layer.source_parameter()]
+                };
+
+                for parameter in parameters {
+                    let key = format!("{}_{}", model_id, parameter);
+                    if param_availability.contains_key(&key) {
+                        continue;
+                    }
+
+                    // Check if data exists for this parameter
+                    if let Ok(Some(availability)) = state
+                        .catalog
+                        .get_parameter_availability(model_id, parameter)
+                        .await
+                    {
+                        param_availability.insert(key, availability);
+                    }
+                }
+            }
+        }
+    }
+
+    param_availability
+}
+
+/// Catalog metadata (long name, units) of the configured models' parameters,
+/// keyed by `{model}_{parameter}`.
+pub(crate) async fn collect_parameter_metadata(
+    state: &AppState,
+    layer_configs: &LayerConfigRegistry,
+) -> HashMap<String, ParameterMetadata> {
+    let mut param_metadata = HashMap::new();
+
+    for model_id in layer_configs.models() {
+        match state.catalog.list_parameter_metadata(model_id).await {
+            Ok(metadata) => param_metadata.extend(
+                metadata
+                    .into_iter()
+                    .map(|m| (format!("{}_{}", model_id, m.parameter), m)),
+            ),
+            Err(e) => warn!(model = %model_id, error = %e, "Failed to list parameter metadata"),
+        }
+    }
+
+    param_metadata
+}
+
+/// `<Abstract>` of a layer: the configured abstract, or else its parameter's
+/// catalog long name and units (e.g. "Temperature (K)"). Empty when neither
+/// is known.
+fn layer_abstract_xml(abstract_text: Option<&str>, metadata: Option<&ParameterMetadata>) -> String {
+    let text = match (abstract_text, metadata) {
+        (Some(text), _) => text.to_string(),
+        (None, Some(metadata)) => format!("{} ({})", metadata.long_name, metadata.units),
+        (None, None) => return String::new(),
+    };
+    let text = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!("<Abstract>{}</Abstract>", text)
+}
+
 // ============================================================================
 // GetMap
 // ============================================================================
 
-async fn wms_get_map(state: Arc<AppState>, params: WmsParams) -> Response {
+async fn wms_get_map(
+    state: Arc<AppState>,
+    params: WmsParams,
+    api_key: ApiKey,
+    tenant: Option<&Tenant>,
+) -> Response {
     use crate::metrics::Timer;
 
     // Record WMS request
@@ -386,7 +639,7 @@ async fn wms_get_map(state: Arc<AppState>, params: WmsParams) -> Response {
 
     let width = params.width.unwrap_or(256);
     let height = params.height.unwrap_or(256);
-    let styles_param = params.styles.as_deref().unwrap_or("default");
+    let styles_param = params.styles.as_deref().unwrap_or("");
     let bbox = params.bbox.as_deref();
     let crs = params.crs.as_deref();
     let format = params.format.as_deref();
@@ -410,49 +663,52 @@ async fn wms_get_map(state: Arc<AppState>, params: WmsParams) -> Response {
     let layer_names: Vec<&str> = layers_param.split(',').map(|s| s.trim()).collect();
     let style_names: Vec<&str> = styles_param.split(',').map(|s| s.trim()).collect();
 
+    if let Err(e) = check_tenant_layers(&state, tenant, &layer_names, "wms", "GetMap") {
+        return wms_exception(e.code(), &e.message(), e.status_code());
+    }
+
+    let extent = request_extent(bbox, crs);
+
+    // Enforce size and complexity limits, unless the caller is trusted
+    if !state.request_limits.is_trusted(&api_key) {
+        let request = MapRequest {
+            width,
+            height,
+            layers: layer_names.len(),
+            extent,
+        };
+        if let Err(e) = check_limits(&state, &layer_names, &request).await {
+            warn!(api_key = %api_key, error = %e.message(), "GetMap request over limits");
+            return wms_exception(e.code(), &e.message(), e.status_code());
+        }
+    }
+
     // Build dimension parameters from request
     let dimensions = DimensionParams {
         time: params.time.clone(),
         run: params.run.clone(),
         forecast: params.forecast.clone(),
         elevation: params.elevation.clone(),
+        window: params.window.clone(),
     };
 
     info!(layers = %layers_param, styles = %styles_param, num_layers = layer_names.len(),
           width = width, height = height, bbox = ?bbox, crs = ?crs,
           time = ?dimensions.time, run = ?dimensions.run, forecast = ?dimensions.forecast,
-          elevation = ?dimensions.elevation, "GetMap request");
-
-    // Record bbox for heatmap visualization (parse and convert to WGS84 if needed)
-    if let Some(bbox_str) = bbox {
-        let coords: Vec<f64> = bbox_str
-            .split(',')
-            .filter_map(|s| s.trim().parse().ok())
-            .collect();
-        if coords.len() == 4 {
-            let crs_str = crs.unwrap_or("EPSG:4326");
-            let bbox_array = if crs_str.contains("3857") {
-                let (min_lon, min_lat) = mercator_to_wgs84(coords[0], coords[1]);
-                let (max_lon, max_lat) = mercator_to_wgs84(coords[2], coords[3]);
-                [
-                    min_lon as f32,
-                    min_lat as f32,
-                    max_lon as f32,
-                    max_lat as f32,
-                ]
-            } else {
-                // WMS 1.3.0 with EPSG:4326 uses axis order lat,lon
-                [
-                    coords[1] as f32,
-                    coords[0] as f32,
-                    coords[3] as f32,
-                    coords[2] as f32,
-                ]
-            };
-            state
-                .metrics
-                .record_tile_request_location(&bbox_array, crate::metrics::TileCacheStatus::Miss);
-        }
+          elevation = ?dimensions.elevation, window = ?dimensions.window, "GetMap request");
+
+    // Resolve TIME against the available observations (WMS 1.3.0 Annex C)
+    let time_warnings = match match_layer_times(&state, &layer_names, &dimensions).await {
+        Ok(warnings) => warnings,
+        Err(e) => return wms_exception(e.code(), &e.message(), e.status_code()),
+    };
+
+    // Record bbox for heatmap visualization
+    if let Some(extent) = extent {
+        state.metrics.record_tile_request_location(
+            &extent.map(|v| v as f32),
+            crate::metrics::TileCacheStatus::Miss,
+        );
     }
 
     // Time the rendering
@@ -461,7 +717,7 @@ async fn wms_get_map(state: Arc<AppState>, params: WmsParams) -> Response {
     // Render layers (single or multiple)
     let render_result = if layer_names.len() == 1 {
         // Single layer - use existing function
-        let style = style_names.first().copied().unwrap_or("default");
+        let style = style_names.first().copied().unwrap_or("");
         render_weather_data(
             &state,
             layer_names[0],
@@ -489,10 +745,33 @@ async fn wms_get_map(state: Arc<AppState>, params: WmsParams) -> Response {
     };
 
     // Try to render actual data, return error on failure
+    // Draw the weather image over the basemap or land/sea underlay
+    let underlay_requested = params
+        .underlay
+        .as_deref()
+        .map(|v| !(v.eq_ignore_ascii_case("false") || v == "0"));
+    let render_result = match render_result {
+        Ok(png_data) if state.underlay.enabled_for_map(underlay_requested) => {
+            Ok(with_underlay(&state, png_data, width, height, bbox, crs).await)
+        }
+        other => other,
+    };
+
     match render_result {
         Ok(png_data) => {
             state.metrics.record_render(timer.elapsed_us(), true).await;
 
+            // Combined layers expire with the most frequently updated one
+            let cache_policy = layer_names
+                .iter()
+                .map(|layer| {
+                    let model = layer.split('_').next().unwrap_or(layer);
+                    let pinned = dimensions.is_pinned(model, &state.model_dimensions);
+                    state.cache_policy(model, pinned)
+                })
+                .reduce(CachePolicy::shorter)
+                .unwrap_or_else(|| CachePolicy::for_cadence(DEFAULT_CADENCE));
+
             // Convert to requested format
             let requested_format = format.unwrap_or("image/png").to_lowercase();
             let (output_data, content_type) = match requested_format.as_str() {
@@ -511,12 +790,15 @@ async fn wms_get_map(state: Arc<AppState>, params: WmsParams) -> Response {
                 _ => (png_data, "image/png"),
             };
 
-            Response::builder()
+            let mut response = Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, content_type)
-                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
-                .body(output_data.into())
-                .unwrap()
+                .header(header::CACHE_CONTROL, cache_policy.header_value())
+                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*");
+            for warning in &time_warnings {
+                response = response.header(header::WARNING, warning);
+            }
+            response.body(output_data.into()).unwrap()
         }
         Err(e) => {
             state.metrics.record_render(timer.elapsed_us(), false).await;
@@ -543,7 +825,11 @@ async fn wms_get_map(state: Arc<AppState>, params: WmsParams) -> Response {
 // GetFeatureInfo
 // ============================================================================
 
-async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Response {
+async fn wms_get_feature_info(
+    state: Arc<AppState>,
+    params: WmsParams,
+    tenant: Option<&Tenant>,
+) -> Response {
     use wms_protocol::{FeatureInfoResponse, InfoFormat};
 
     // Validate required parameters
@@ -642,10 +928,8 @@ async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Respon
     };
 
     // Parse BBOX
-    let bbox_coords: Result<Vec<f64>, _> = bbox.split(',').map(|s| s.trim().parse()).collect();
-
-    let bbox_array = match bbox_coords {
-        Ok(coords) if coords.len() == 4 => {
+    let bbox_array = match wms_protocol::parse_bbox(bbox) {
+        Ok(coords) => {
             if crs.contains("3857") {
                 [coords[0], coords[1], coords[2], coords[3]]
             } else {
@@ -653,7 +937,7 @@ async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Respon
                 [coords[1], coords[0], coords[3], coords[2]]
             }
         }
-        _ => {
+        Err(_) => {
             return wms_exception(
                 "InvalidParameterValue",
                 "BBOX must contain 4 coordinates",
@@ -687,8 +971,24 @@ async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Respon
             (None, None)
         };
 
-    // Parse ELEVATION parameter
+    // FEATURE_COUNT limits the features (levels) returned per layer
+    if params.feature_count == Some(0) {
+        return wms_exception(
+            "InvalidParameterValue",
+            "FEATURE_COUNT must be a positive integer",
+            StatusCode::BAD_REQUEST,
+        );
+    }
+
+    // Parse ELEVATION parameter; a comma-separated list queries several levels
     let elevation = params.elevation.clone();
+    let requested_levels: Option<Vec<String>> = elevation.as_deref().map(|e| {
+        e.split(',')
+            .map(|level| level.trim().to_string())
+            .filter(|level| !level.is_empty())
+            .collect::<Vec<_>>()
+    });
+    let requested_levels = requested_levels.filter(|levels| !levels.is_empty());
 
     info!(
         query_layers = %query_layers,
@@ -702,11 +1002,15 @@ async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Respon
         forecast_hour = ?forecast_hour,
         valid_time = ?valid_time,
         elevation = ?elevation,
+        feature_count = ?params.feature_count,
         "GetFeatureInfo request"
     );
 
     // Query each layer
     let layers: Vec<&str> = query_layers.split(',').map(|s| s.trim()).collect();
+    if let Err(e) = check_tenant_layers(&state, tenant, &layers, "wms", "GetFeatureInfo") {
+        return wms_exception(e.code(), &e.message(), e.status_code());
+    }
 
     // Validate all layer names before querying
     // Get list of valid models from catalog
@@ -739,15 +1043,20 @@ async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Respon
         }
     }
 
-    let mut all_features = Vec::new();
+    let include_provenance = params
+        .provenance
+        .as_deref()
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+    let mut results = Vec::with_capacity(layers.len());
 
     for layer in layers {
-        // Get effective elevation (use default if not specified)
-        let effective_elevation: Option<String> = match &elevation {
-            Some(elev) => Some(elev.clone()),
+        // Get effective elevations (use the layer default if not specified)
+        let effective_elevations: Vec<Option<String>> = match &requested_levels {
+            Some(levels) => levels.iter().cloned().map(Some).collect(),
             None => {
                 let parts: Vec<&str> = layer.split('_').collect();
-                if parts.len() >= 2 {
+                let default_level = if parts.len() >= 2 {
                     let model = parts[0];
                     let parameter = parts[1..].join("_").to_uppercase();
                     let configs = state.layer_configs.read().await;
@@ -757,41 +1066,58 @@ async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Respon
                         .map(|s| s.to_string())
                 } else {
                     None
-                }
+                };
+                vec![default_level]
             }
         };
 
         // Get layer configs for unit conversion
         let layer_configs = state.layer_configs.read().await;
 
-        match crate::rendering::query_point_value(
-            &state.catalog,
-            &state.metrics,
-            &state.grid_processor_factory,
-            &layer_configs,
-            layer,
-            bbox_array,
-            width,
-            height,
-            i,
-            j,
-            crs,
-            forecast_hour,
-            valid_time,
-            effective_elevation.as_deref(),
-        )
-        .await
-        {
-            Ok(mut features) => {
-                all_features.append(&mut features);
-            }
-            Err(e) => {
-                error!(layer = %layer, error = %e, "Failed to query layer");
+        // A layer succeeds if any of its levels has data
+        let mut layer_features = Vec::new();
+        let mut layer_errors = Vec::new();
+        for effective_elevation in &effective_elevations {
+            match crate::rendering::query_point_value(
+                &state.catalog,
+                &state.metrics,
+                &state.grid_processor_factory,
+                &layer_configs,
+                layer,
+                bbox_array,
+                width,
+                height,
+                i,
+                j,
+                crs,
+                forecast_hour,
+                valid_time,
+                effective_elevation.as_deref(),
+            )
+            .await
+            {
+                Ok(mut features) => {
+                    if !include_provenance {
+                        features.iter_mut().for_each(|f| f.provenance = None);
+                    }
+                    layer_features.append(&mut features);
+                }
+                Err(e) => {
+                    error!(layer = %layer, level = ?effective_elevation, error = %e, "Failed to query layer");
+                    layer_errors.push(e);
+                }
             }
         }
+
+        let result = if layer_features.is_empty() && !layer_errors.is_empty() {
+            Err(layer_errors.join("; "))
+        } else {
+            Ok(layer_features)
+        };
+        results.push((layer.to_string(), result));
     }
 
-    let response = FeatureInfoResponse::new(all_features);
+    let response = FeatureInfoResponse::from_layers(results, params.feature_count);
 
     // Format response based on INFO_FORMAT
     let (body, content_type) = match info_format {
@@ -822,7 +1148,9 @@ async fn wms_get_feature_info(state: Arc<AppState>, params: WmsParams) -> Respon
 // WMS Rendering
 // ============================================================================
 
-async fn render_weather_data(
+/// Render one layer as GetMap would, applying the layer's style policy and
+/// default level. Also used by the admin preview matrix.
+pub(crate) async fn render_weather_data(
     state: &Arc<AppState>,
     layer: &str,
     style: &str,
@@ -860,6 +1188,18 @@ async fn render_weather_data(
         }
     };
 
+    // Apply the layer's default/forced style and resolve renamed styles
+    let resolved_style =
+        state
+            .layer_configs
+            .read()
+            .await
+            .resolve_style(model, &parameter, Some(style));
+    if let Some(alias) = &resolved_style.deprecated_alias {
+        warn!(layer = %layer, alias = %alias, style = %resolved_style.name, "Deprecated style alias requested");
+    }
+    let style = resolved_style.name.as_str();
+
     // Check if this is a wind barbs composite layer
     if parameter == "WIND_BARBS" {
         let parsed_bbox = bbox.and_then(|b| parse_bbox(b, crs));
@@ -889,6 +1229,136 @@ async fn render_weather_data(
     // Parse BBOX parameter
     let parsed_bbox = bbox.and_then(|b| parse_bbox(b, crs));
 
+    // Check if this is a temporal composite layer (e.g., max over the last hour)
+    let temporal_layer = {
+        let configs = state.layer_configs.read().await;
+        configs
+            .get_layer_by_param(model, &parameter)
+            .and_then(|l| Some((l.temporal.clone()?, configs.get_style_path(l))))
+    };
+    if let Some((temporal, style_file)) = temporal_layer {
+        temporal
+            .resolve_window(dimensions.window.as_deref())
+            .map_err(WmsError::InvalidDimensionValue)?;
+
+        return crate::rendering::render_temporal_composite(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            &temporal,
+            dimensions.window.as_deref(),
+            forecast_hour,
+            observation_time,
+            level.as_deref(),
+            width,
+            height,
+            parsed_bbox,
+            &style_file,
+            Some(style),
+            crs.unwrap_or("EPSG:4326").contains("3857"),
+            state.model_dimensions.requires_full_grid(model),
+            state.model_dimensions.is_observation(model),
+        )
+        .await
+        .map_err(WmsError::from_rendering_error);
+    }
+
+    // Check if this is an ensemble layer (e.g., probability of freezing)
+    let ensemble_layer = {
+        let configs = state.layer_configs.read().await;
+        configs
+            .get_layer_by_param(model, &parameter)
+            .and_then(|l| Some((l.ensemble.clone()?, configs.get_style_path(l))))
+    };
+    if let Some((ensemble, style_file)) = ensemble_layer {
+        return crate::rendering::render_ensemble_product(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            &ensemble,
+            forecast_hour,
+            level.as_deref(),
+            width,
+            height,
+            parsed_bbox,
+            &style_file,
+            Some(style),
+            crs.unwrap_or("EPSG:4326").contains("3857"),
+            state.model_dimensions.requires_full_grid(model),
+        )
+        .await
+        .map_err(WmsError::from_rendering_error);
+    }
+
+    // Check if this is a column composite layer (e.g., max over 1000-500 mb)
+    let vertical_layer = {
+        let configs = state.layer_configs.read().await;
+        configs
+            .get_layer_by_param(model, &parameter)
+            .and_then(|l| Some((l.vertical.clone()?, configs.get_style_path(l))))
+    };
+    if let Some((vertical, style_file)) = vertical_layer {
+        let range = vertical
+            .resolve_range(dimensions.elevation.as_deref())
+            .map_err(WmsError::InvalidDimensionValue)?;
+
+        return crate::rendering::render_vertical_composite(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            &vertical,
+            &range,
+            forecast_hour,
+            width,
+            height,
+            parsed_bbox,
+            &style_file,
+            Some(style),
+            crs.unwrap_or("EPSG:4326").contains("3857"),
+            state.model_dimensions.requires_full_grid(model),
+        )
+        .await
+        .map_err(WmsError::from_rendering_error);
+    }
+
+    // Check if this is a multi-band composite layer (e.g., GOES true color)
+    let composite_style_file = {
+        let configs = state.layer_configs.read().await;
+        configs
+            .get_layer_by_param(model, &parameter)
+            .filter(|l| l.is_band_composite())
+            .map(|l| configs.get_style_path(l))
+    };
+    if let Some(style_file) = composite_style_file {
+        let output_bbox = parsed_bbox.ok_or_else(|| {
+            WmsError::InvalidBBox(format!("BBOX is required for layer '{}'", layer))
+        })?;
+
+        return crate::rendering::render_composite_layer(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            observation_time,
+            width,
+            height,
+            output_bbox,
+            &style_file,
+            Some(style),
+            crs.unwrap_or("EPSG:4326").contains("3857"),
+            state.model_dimensions.requires_full_grid(model),
+        )
+        .await
+        .map_err(WmsError::from_rendering_error);
+    }
+
     info!(forecast_hour = ?forecast_hour, observation_time = ?observation_time, level = ?level, bbox = ?parsed_bbox, style = style, "Parsed WMS parameters");
 
     // Check CRS for projection
@@ -983,9 +1453,8 @@ async fn render_multi_layer(
 
     // Render each layer and composite
     for (i, layer_name) in layer_names.iter().enumerate() {
-        // Get the style for this layer (use default if not enough styles provided)
-        let style = style_names.get(i).copied().unwrap_or("default");
-        let style = if style.is_empty() { "default" } else { style };
+        // Get the style for this layer (empty/missing styles resolve to the layer default)
+        let style = style_names.get(i).copied().unwrap_or("");
 
         info!(layer = %layer_name, style = %style, layer_index = i, "Rendering layer for multi-layer composite");
 
@@ -1032,8 +1501,96 @@ async fn render_multi_layer(
     Ok(png_bytes)
 }
 
+/// Match the TIME of each observation layer to its available times.
+///
+/// Returns the `Warning` header values for layers rendered at another time
+/// than requested (nearest value) or at the default time (TIME omitted).
+/// Fails if TIME isn't a timestamp or is further than the tolerance from
+/// every observation.
+pub(crate) async fn match_layer_times(
+    state: &AppState,
+    layer_names: &[&str],
+    dimensions: &DimensionParams,
+) -> Result<Vec<String>, WmsError> {
+    let requested = match dimensions.time.as_deref() {
+        Some(time) if !is_default_keyword(time) => {
+            Some(parse_iso8601_timestamp(time).ok_or_else(|| {
+                WmsError::InvalidDimensionValue(format!(
+                    "TIME '{}' is not an ISO 8601 timestamp",
+                    time
+                ))
+            })?)
+        }
+        _ => None,
+    };
+
+    let mut warnings = Vec::new();
+    for layer in layer_names {
+        let Some((model, parameter)) = layer.split_once('_') else {
+            continue;
+        };
+        if !state.model_dimensions.is_observation(model) {
+            continue;
+        }
+
+        let available = match state
+            .catalog
+            .get_available_times(model, &parameter.to_uppercase())
+            .await
+        {
+            Ok(times) => times,
+            Err(e) => {
+                warn!(layer = %layer, error = %e, "Cannot read available times");
+                continue;
+            }
+        };
+        let tolerance = state.time_tolerance(model);
+        let matched = match_time(requested, &available, tolerance)
+            .map_err(|e| WmsError::InvalidDimensionValue(format!("Layer '{}': {}", layer, e)))?;
+        if let Some(warning) = matched.and_then(|m| m.warning()) {
+            info!(layer = %layer, warning = %warning, "TIME substituted");
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+    }
+    Ok(warnings)
+}
+
+/// Composite a rendered GetMap image over the configured underlay.
+///
+/// The image is returned as is if the underlay can't be read.
+pub(crate) async fn with_underlay(
+    state: &AppState,
+    png_data: Vec<u8>,
+    width: u32,
+    height: u32,
+    bbox: Option<&str>,
+    crs: Option<&str>,
+) -> Vec<u8> {
+    let Some(extent) = bbox.and_then(|b| parse_bbox(b, crs)) else {
+        return png_data;
+    };
+    let web_mercator = crs.unwrap_or("EPSG:4326").contains("3857");
+    let Some(underlay) = state
+        .underlay
+        .for_extent(extent, width, height, web_mercator)
+        .await
+    else {
+        return png_data;
+    };
+
+    match crate::underlay::composite_over(&png_data, &underlay) {
+        Ok(composited) => composited,
+        Err(e) => {
+            warn!(error = %e, "Failed to composite underlay");
+            png_data
+        }
+    }
+}
+
 /// Alpha blend two RGBA pixels (src over dst)
-fn alpha_blend(dst: image::Rgba<u8>, src: image::Rgba<u8>) -> image::Rgba<u8> {
+pub(crate) fn alpha_blend(dst: image::Rgba<u8>, src: image::Rgba<u8>) -> image::Rgba<u8> {
     let src_a = src[3] as f32 / 255.0;
     let dst_a = dst[3] as f32 / 255.0;
 
@@ -1072,31 +1629,24 @@ fn alpha_blend(dst: image::Rgba<u8>, src: image::Rgba<u8>) -> image::Rgba<u8> {
 //TODO do we need to parse bbox for any arbitrary CRS?
 /// Parse a BBOX string into [min_lon, min_lat, max_lon, max_lat]
 fn parse_bbox(bbox_str: &str, crs: Option<&str>) -> Option<[f32; 4]> {
-    let coords: Vec<f64> = bbox_str
-        .split(',')
-        .filter_map(|s| s.trim().parse().ok())
-        .collect();
+    let coords = wms_protocol::parse_bbox(bbox_str).ok()?;
 
-    if coords.len() == 4 {
-        let crs_str = crs.unwrap_or("EPSG:4326");
-        let (min_lon, min_lat, max_lon, max_lat) = if crs_str.contains("3857") {
-            let (min_lon, min_lat) = mercator_to_wgs84(coords[0], coords[1]);
-            let (max_lon, max_lat) = mercator_to_wgs84(coords[2], coords[3]);
-            (min_lon, min_lat, max_lon, max_lat)
-        } else {
-            // WMS 1.3.0 with EPSG:4326 uses axis order lat,lon
-            (coords[1], coords[0], coords[3], coords[2])
-        };
-
-        Some([
-            min_lon as f32,
-            min_lat as f32,
-            max_lon as f32,
-            max_lat as f32,
-        ])
+    let crs_str = crs.unwrap_or("EPSG:4326");
+    let (min_lon, min_lat, max_lon, max_lat) = if crs_str.contains("3857") {
+        let (min_lon, min_lat) = mercator_to_wgs84(coords[0], coords[1]);
+        let (max_lon, max_lat) = mercator_to_wgs84(coords[2], coords[3]);
+        (min_lon, min_lat, max_lon, max_lat)
     } else {
-        None
-    }
+        // WMS 1.3.0 with EPSG:4326 uses axis order lat,lon
+        (coords[1], coords[0], coords[3], coords[2])
+    };
+
+    Some([
+        min_lon as f32,
+        min_lat as f32,
+        max_lon as f32,
+        max_lat as f32,
+    ])
 }
 
 // ============================================================================
@@ -1111,7 +1661,9 @@ fn build_wms_capabilities_xml_v2(
     version: &str,
     layer_configs: &LayerConfigRegistry,
     param_availability: &HashMap<String, ParameterAvailability>,
+    param_metadata: &HashMap<String, ParameterMetadata>,
     dimension_registry: &ModelDimensionRegistry,
+    limits: &RequestLimits,
 ) -> String {
     let mut model_layers: Vec<String> = Vec::new();
 
@@ -1128,40 +1680,85 @@ fn build_wms_capabilities_xml_v2(
         let mut vgrd_availability: Option<&ParameterAvailability> = None;
 
         for layer in &model_config.layers {
-            // Skip composite layers for now - handle them after regular layers
-            if layer.composite {
+            // Multi-band composites are listed when all of their bands have
+            // data; other composite layers are handled after regular layers
+            let composite_availability = if layer.is_band_composite() {
+                let Some(availability) =
+                    band_composite_availability(model_id, layer, param_availability)
+                else {
+                    continue;
+                };
+                Some(availability)
+            } else if layer.composite {
                 continue;
-            }
+            } else {
+                None
+            };
 
-            let key = format!("{}_{}", model_id, layer.parameter);
-            let Some(availability) = param_availability.get(&key) else {
-                // No data for this layer - skip it
-                continue;
+            let availability = match &composite_availability {
+                Some(availability) => availability,
+                None => {
+                    let key = format!("{}_{}", model_id, layer.source_parameter());
+                    let Some(availability) = param_availability.get(&key) else {
+                        // No data for this layer - skip it
+                        continue;
+                    };
+
+                    // Track UGRD/VGRD for wind barbs
+                    if layer.parameter == "UGRD" {
+                        ugrd_availability = Some(availability);
+                    } else if layer.parameter == "VGRD" {
+                        vgrd_availability = Some(availability);
+                    }
+                    availability
+                }
             };
 
-            // Track UGRD/VGRD for wind barbs
-            if layer.parameter == "UGRD" {
-                ugrd_availability = Some(availability);
-            } else if layer.parameter == "VGRD" {
-                vgrd_availability = Some(availability);
+            // Build dimensions for this specific layer; a column layer's
+            // ELEVATION is a range rather than one of its source's levels
+            let mut dimensions_xml = match &layer.vertical {
+                Some(vertical) => {
+                    let without_levels = ParameterAvailability {
+                        levels: Vec::new(),
+                        ..availability.clone()
+                    };
+                    let mut xml = build_layer_dimensions_xml(&without_levels, is_observational);
+                    xml.push_str(&build_vertical_dimension_xml(
+                        vertical,
+                        &availability.levels,
+                    ));
+                    xml
+                }
+                None => build_layer_dimensions_xml(availability, is_observational),
+            };
+            if let Some(temporal) = &layer.temporal {
+                dimensions_xml.push_str(&build_window_dimension_xml(temporal));
             }
-
-            // Build dimensions for this specific layer
-            let dimensions_xml = build_layer_dimensions_xml(availability, is_observational);
+            // Temporal, ensemble and column composites are rendered on the
+            // fly and can't be queried
+            let queryable = if layer.is_reduced() { 0 } else { 1 };
 
             // Get styles from style file
             let style_path = layer_configs.get_style_path(layer);
-            let styles_xml = get_styles_xml_from_file(&style_path);
+            let styles_xml = get_styles_xml_from_file(&style_path, &layer.style_policy);
 
             // Build bounding box (normalize longitude to -180/180)
             let (west, east, south, north) = normalize_bbox(&availability.bbox);
 
+            let metadata_key = format!("{}_{}", model_id, layer.source_parameter());
+            let abstract_xml = layer_abstract_xml(
+                layer.abstract_text.as_deref(),
+                param_metadata.get(&metadata_key),
+            );
+
             let layer_xml = format!(
-                r#"<Layer queryable="1"><Name>{}_{}</Name><Title>{} - {}</Title><CRS>EPSG:4326</CRS><CRS>EPSG:3857</CRS><EX_GeographicBoundingBox><westBoundLongitude>{}</westBoundLongitude><eastBoundLongitude>{}</eastBoundLongitude><southBoundLatitude>{}</southBoundLatitude><northBoundLatitude>{}</northBoundLatitude></EX_GeographicBoundingBox><BoundingBox CRS="EPSG:4326" minx="{}" miny="{}" maxx="{}" maxy="{}"/>{}{}</Layer>"#,
+                r#"<Layer queryable="{}"><Name>{}_{}</Name><Title>{} - {}</Title>{}<CRS>EPSG:4326</CRS><CRS>EPSG:3857</CRS><EX_GeographicBoundingBox><westBoundLongitude>{}</westBoundLongitude><eastBoundLongitude>{}</eastBoundLongitude><southBoundLatitude>{}</southBoundLatitude><northBoundLatitude>{}</northBoundLatitude></EX_GeographicBoundingBox><BoundingBox CRS="EPSG:4326" minx="{}" miny="{}" maxx="{}" maxy="{}"/>{}{}</Layer>"#,
+                queryable,
                 model_id,
                 layer.parameter,
                 model_config.display_name,
                 layer.title,
+                abstract_xml,
                 west,
                 east,
                 south,
@@ -1254,6 +1851,9 @@ fn build_wms_capabilities_xml_v2(
     <Title>Weather WMS Service</Title>
     <Abstract>Web Map Service for weather model data</Abstract>
     <OnlineResource xlink:href="http://localhost:8080/wms"/>
+    <LayerLimit>{}</LayerLimit>
+    <MaxWidth>{}</MaxWidth>
+    <MaxHeight>{}</MaxHeight>
   </Service>
   <Capability>
     <Request>
@@ -1285,6 +1885,9 @@ fn build_wms_capabilities_xml_v2(
   </Capability>
 </WMS_Capabilities>"#,
         version,
+        limits.max_layers,
+        limits.max_width,
+        limits.max_height,
         model_layers.join("")
     )
 }
@@ -1309,8 +1912,9 @@ fn build_layer_dimensions_xml(
             .first()
             .map(|s| s.as_str())
             .unwrap_or("latest");
+        // The default is the latest observation, so it is also "current"
         dimensions.push_str(&format!(
-            r#"<Dimension name="TIME" units="ISO8601" default="{}">{}</Dimension>"#,
+            r#"<Dimension name="TIME" units="ISO8601" default="{}" nearestValue="1" current="1">{}</Dimension>"#,
             time_default, time_values
         ));
     } else {
@@ -1364,8 +1968,47 @@ fn build_layer_dimensions_xml(
     dimensions
 }
 
+/// Build the WINDOW dimension XML for a temporal composite layer.
+fn build_window_dimension_xml(temporal: &TemporalConfig) -> String {
+    format!(
+        r#"<Dimension name="WINDOW" units="ISO8601" default="{}">{}</Dimension>"#,
+        temporal.default_window,
+        temporal.windows.join(",")
+    )
+}
+
+/// Build the ELEVATION dimension XML for a column composite layer.
+///
+/// The extent is the span of the source's levels of the default range's
+/// kind (e.g. "100 mb/1000 mb"), and the default is the configured range.
+fn build_vertical_dimension_xml(vertical: &VerticalConfig, levels: &[String]) -> String {
+    let default_range = vertical.default_range;
+    let values: Vec<f64> = levels
+        .iter()
+        .map(|l| CanonicalLevel::parse(l))
+        .filter(|l| l.kind == default_range.kind)
+        .filter_map(|l| l.value)
+        .collect();
+    let extent = match (
+        values.iter().copied().reduce(f64::min),
+        values.iter().copied().reduce(f64::max),
+    ) {
+        (Some(min), Some(max)) => LevelRange {
+            kind: default_range.kind,
+            min,
+            max,
+        },
+        _ => default_range,
+    };
+
+    format!(
+        r#"<Dimension name="ELEVATION" units="" default="{}">{}</Dimension>"#,
+        default_range, extent
+    )
+}
+
 /// Normalize bounding box longitude to -180/180 for WMS.
-fn normalize_bbox(bbox: &wms_common::BoundingBox) -> (f64, f64, f64, f64) {
+pub(crate) fn normalize_bbox(bbox: &wms_common::BoundingBox) -> (f64, f64, f64, f64) {
     let (west, east) = if bbox.min_x == 0.0 && bbox.max_x == 360.0 {
         (-180.0, 180.0)
     } else {
@@ -1428,6 +2071,80 @@ mod tests {
 
         let bbox = parse_bbox("1,2,3", None);
         assert!(bbox.is_none());
+
+        // A stray value is an error, not skipped
+        let bbox = parse_bbox("1,2,x,3,4", None);
+        assert!(bbox.is_none());
+    }
+
+    #[test]
+    fn test_grid_read_error_status() {
+        use crate::rendering::loaders::grid_read_error;
+        use grid_processor::GridProcessorError;
+
+        let status = |e: GridProcessorError| {
+            WmsError::from_rendering_error(grid_read_error("Failed to read Zarr region", &e))
+                .status_code()
+        };
+        assert_eq!(
+            status(GridProcessorError::NotFound("gfs/TMP".to_string())),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            status(GridProcessorError::storage_error("connection reset")),
+            StatusCode::BAD_GATEWAY
+        );
+        assert_eq!(
+            status(GridProcessorError::DecompressionError(
+                "bad block".to_string()
+            )),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn test_layer_abstract_xml() {
+        let metadata = ParameterMetadata {
+            model: "gfs".to_string(),
+            parameter: "APCP".to_string(),
+            long_name: "Total Precipitation <accumulated>".to_string(),
+            units: "kg m-2".to_string(),
+            default_style: None,
+            valid_min: None,
+            valid_max: None,
+        };
+
+        assert_eq!(
+            layer_abstract_xml(None, Some(&metadata)),
+            "<Abstract>Total Precipitation &lt;accumulated&gt; (kg m-2)</Abstract>"
+        );
+        // A configured abstract wins over the catalog metadata
+        assert_eq!(
+            layer_abstract_xml(Some("Rain & snow"), Some(&metadata)),
+            "<Abstract>Rain &amp; snow</Abstract>"
+        );
+        assert_eq!(layer_abstract_xml(None, None), "");
+    }
+
+    #[test]
+    fn test_vertical_dimension_xml() {
+        let vertical = VerticalConfig {
+            source: "TMP".to_string(),
+            reducer: grid_processor::VerticalReducer::Max,
+            default_range: "1000 mb/500 mb".parse().unwrap(),
+            max_levels: 50,
+        };
+        let levels = ["1000 mb", "850 mb", "250 mb", "2 m above ground"].map(String::from);
+
+        assert_eq!(
+            build_vertical_dimension_xml(&vertical, &levels),
+            r#"<Dimension name="ELEVATION" units="" default="500 mb/1000 mb">250 mb/1000 mb</Dimension>"#
+        );
+        // No isobaric levels yet: advertise the default range
+        assert_eq!(
+            build_vertical_dimension_xml(&vertical, &levels[3..]),
+            r#"<Dimension name="ELEVATION" units="" default="500 mb/1000 mb">500 mb/1000 mb</Dimension>"#
+        );
     }
 
     #[test]
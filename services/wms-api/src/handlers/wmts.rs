@@ -11,28 +11,37 @@
 
 use axum::{
     extract::{Extension, Path, Query},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     response::Response,
 };
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tracing::{debug, error, info, instrument};
+use std::sync::{Arc, OnceLock};
+use tracing::{debug, error, info, instrument, warn};
 
-use storage::CacheKey;
+use storage::ArchiveKey;
 use wms_common::{
-    tile::{web_mercator_tile_matrix_set, wgs84_tile_to_latlon_bounds},
-    BoundingBox, CrsCode, TileCoord,
+    api_key::{ApiKey, API_KEY_HEADER},
+    tile::{
+        web_mercator_tile_limits, web_mercator_tile_matrix_set, wgs84_tile_limits,
+        wgs84_tile_to_latlon_bounds, TileMatrixLimits,
+    },
+    BoundingBox, CrsCode, Tenant, TileCoord,
 };
 
 use super::common::{
-    convert_png_to_jpeg, convert_png_to_webp, get_wmts_styles_xml_from_file, wmts_exception,
-    DimensionParams, WmtsDimensionParams,
+    band_composite_availability, convert_png_to_jpeg, convert_png_to_webp,
+    get_wmts_styles_xml_from_file, wmts_exception, DimensionParams, WmtsDimensionParams,
 };
-use crate::layer_config::LayerConfigRegistry;
+use super::wms::check_tenant_layers;
+use crate::layer_config::{LayerConfigRegistry, TemporalConfig, VerticalConfig};
 use crate::model_config::ModelDimensionRegistry;
 use crate::state::AppState;
+use crate::update_cadence::{
+    format_iso8601_duration, CachePolicy, UpdateCadence, UPDATE_INTERVAL_ROLE,
+};
 use storage::ParameterAvailability;
+use wms_protocol::{format_wmts_time, parse_wmts_time, WmtsDimensionInfo, DEFAULT_MAX_TIME_VALUES};
 
 // ============================================================================
 // WMTS Parameters
@@ -67,16 +76,78 @@ pub struct WmtsKvpParams {
     pub forecast: Option<String>,
     #[serde(rename = "ELEVATION")]
     pub elevation: Option<String>,
+    #[serde(rename = "WINDOW")]
+    pub window: Option<String>,
+    #[serde(rename = "api_key", alias = "API_KEY")]
+    pub api_key: Option<String>,
 }
 
 // ============================================================================
 // WMTS Handler Entry Points
 // ============================================================================
 
+/// Tenant of the caller, identified by the `x-api-key` header or the
+/// `api_key` query parameter. None if the caller is unrestricted.
+fn request_tenant(
+    state: &AppState,
+    headers: &HeaderMap,
+    query_key: Option<&str>,
+) -> Option<Tenant> {
+    let api_key = ApiKey::resolve(
+        headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()),
+        query_key,
+    );
+    state.tenants.resolve(&api_key).cloned()
+}
+
+/// Count a GetTile request against the caller's tenant, rejecting layers
+/// outside it.
+fn check_tile_tenant(
+    state: &AppState,
+    tenant: Option<&Tenant>,
+    layer: &str,
+) -> Result<(), Response> {
+    let Some(tenant) = tenant else {
+        return Ok(());
+    };
+    state.tenant_usage.record(tenant, "wmts", "GetTile");
+    check_tenant_layers(state, Some(tenant), &[layer], "wmts", "GetTile")
+        .map_err(|e| wmts_exception(e.code(), &e.message(), e.status_code()))
+}
+
+/// Check the TIME of a tile request for an observation layer and rewrite it
+/// in the advertised format. Observation TIME must be an ISO 8601 instant
+/// (or `current`); anything else is rejected rather than silently served
+/// the latest data.
+fn normalize_observation_time(
+    dimensions: &mut DimensionParams,
+    model: &str,
+    registry: &ModelDimensionRegistry,
+) -> Result<(), Response> {
+    if !registry.is_observation(model) {
+        return Ok(());
+    }
+    let Some(time) = dimensions.time.as_deref() else {
+        return Ok(());
+    };
+    match parse_wmts_time(time) {
+        Ok(time) => {
+            dimensions.time = time.map(format_wmts_time);
+            Ok(())
+        }
+        Err(e) => Err(wmts_exception(
+            e.wms_exception_code(),
+            &e.to_string(),
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
 /// WMTS KVP (Key-Value Pair) handler
-#[instrument(skip(state))]
+#[instrument(skip(state, headers))]
 pub async fn wmts_kvp_handler(
     Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
     Query(params): Query<WmtsKvpParams>,
 ) -> Response {
     if params.service.as_deref() != Some("WMTS") {
@@ -87,8 +158,10 @@ pub async fn wmts_kvp_handler(
         );
     }
 
+    let tenant = request_tenant(&state, &headers, params.api_key.as_deref());
+
     match params.request.as_deref() {
-        Some("GetCapabilities") => wmts_get_capabilities(state).await,
+        Some("GetCapabilities") => wmts_get_capabilities(state, tenant.as_ref()).await,
         Some("GetTile") => {
             // Validate FORMAT parameter
             let format = params.format.as_deref().unwrap_or("image/png");
@@ -114,6 +187,9 @@ pub async fn wmts_kvp_handler(
             }
 
             let layer = params.layer.clone().unwrap_or_default();
+            if let Err(response) = check_tile_tenant(&state, tenant.as_ref(), &layer) {
+                return response;
+            }
             let style = params
                 .style
                 .clone()
@@ -166,15 +242,21 @@ pub async fn wmts_kvp_handler(
                 );
             }
 
-            let dimensions = DimensionParams {
+            let mut dimensions = DimensionParams {
                 time: params.time.clone(),
                 run: params.run.clone(),
                 forecast: params.forecast.clone(),
                 elevation: params.elevation.clone(),
+                window: params.window.clone(),
             };
 
             let model = layer.split('_').next().unwrap_or("");
-            let (forecast_hour, observation_time, _) =
+            if let Err(response) =
+                normalize_observation_time(&mut dimensions, model, &state.model_dimensions)
+            {
+                return response;
+            }
+            let (forecast_hour, observation_time, reference_time) =
                 dimensions.parse_for_layer(model, &state.model_dimensions);
 
             wmts_get_tile(
@@ -186,8 +268,10 @@ pub async fn wmts_kvp_handler(
                 tile_col,
                 tile_row,
                 forecast_hour,
+                reference_time,
                 observation_time,
                 dimensions.elevation.as_deref(),
+                dimensions.window.as_deref(),
                 format,
             )
             .await
@@ -201,9 +285,10 @@ pub async fn wmts_kvp_handler(
 }
 
 /// WMTS RESTful URL handler
-#[instrument(skip(state))]
+#[instrument(skip(state, headers))]
 pub async fn wmts_rest_handler(
     Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
     Path(path): Path<String>,
     Query(params): Query<WmtsDimensionParams>,
 ) -> Response {
@@ -216,13 +301,18 @@ pub async fn wmts_rest_handler(
         );
     }
 
-    // URL format: {layer}/{style}/{TileMatrixSet}/{z}/{x}/{y}.png
+    // URL format: {layer}/{style}[/{time}]/{TileMatrixSet}/{z}/{x}/{y}.png
     let layer = parts[0];
     let style = parts[1];
-    let tile_matrix_set = parts[2];
-    let z: u32 = parts[3].parse().unwrap_or(0);
-    let x: u32 = parts[4].parse().unwrap_or(0);
-    let last = parts[5];
+    let (path_time, parts) = if parts.len() >= 7 {
+        (Some(parts[2]), &parts[3..])
+    } else {
+        (None, &parts[2..])
+    };
+    let tile_matrix_set = parts[0];
+    let z: u32 = parts[1].parse().unwrap_or(0);
+    let x: u32 = parts[2].parse().unwrap_or(0);
+    let last = parts[3];
     let (y_str, _) = last.rsplit_once('.').unwrap_or((last, "png"));
     let y: u32 = y_str.parse().unwrap_or(0);
 
@@ -238,15 +328,29 @@ pub async fn wmts_rest_handler(
         );
     }
 
-    let dimensions = DimensionParams {
-        time: params.time.clone(),
+    let tenant = request_tenant(&state, &headers, params.api_key.as_deref());
+    if let Err(response) = check_tile_tenant(&state, tenant.as_ref(), layer) {
+        return response;
+    }
+
+    // A TIME path segment takes precedence over the query parameter
+    let mut dimensions = DimensionParams {
+        time: path_time
+            .map(str::to_string)
+            .or_else(|| params.time.clone()),
         run: params.run.clone(),
         forecast: params.forecast.clone(),
         elevation: params.elevation.clone(),
+        window: params.window.clone(),
     };
 
     let model = layer.split('_').next().unwrap_or("");
-    let (forecast_hour, observation_time, _) =
+    if let Err(response) =
+        normalize_observation_time(&mut dimensions, model, &state.model_dimensions)
+    {
+        return response;
+    }
+    let (forecast_hour, observation_time, reference_time) =
         dimensions.parse_for_layer(model, &state.model_dimensions);
 
     // REST always uses PNG (format determined by file extension)
@@ -259,32 +363,46 @@ pub async fn wmts_rest_handler(
         x,
         y,
         forecast_hour,
+        reference_time,
         observation_time,
         dimensions.elevation.as_deref(),
+        dimensions.window.as_deref(),
         "image/png",
     )
     .await
 }
 
 /// XYZ tile handler for Leaflet/OpenLayers
-#[instrument(skip(state))]
+#[instrument(skip(state, headers))]
 pub async fn xyz_tile_handler(
     Extension(state): Extension<Arc<AppState>>,
+    headers: HeaderMap,
     Path((layer, style, z, x, y)): Path<(String, String, u32, u32, String)>,
     Query(params): Query<WmtsDimensionParams>,
 ) -> Response {
+    let tenant = request_tenant(&state, &headers, params.api_key.as_deref());
+    if let Err(response) = check_tile_tenant(&state, tenant.as_ref(), &layer) {
+        return response;
+    }
+
     let (y_str, _) = y.rsplit_once('.').unwrap_or((&y, "png"));
     let y_val: u32 = y_str.parse().unwrap_or(0);
 
-    let dimensions = DimensionParams {
+    let mut dimensions = DimensionParams {
         time: params.time.clone(),
         run: params.run.clone(),
         forecast: params.forecast.clone(),
         elevation: params.elevation.clone(),
+        window: params.window.clone(),
     };
 
     let model = layer.split('_').next().unwrap_or("");
-    let (forecast_hour, observation_time, _) =
+    if let Err(response) =
+        normalize_observation_time(&mut dimensions, model, &state.model_dimensions)
+    {
+        return response;
+    }
+    let (forecast_hour, observation_time, reference_time) =
         dimensions.parse_for_layer(model, &state.model_dimensions);
 
     // XYZ always uses WebMercatorQuad and PNG
@@ -297,8 +415,10 @@ pub async fn xyz_tile_handler(
         x,
         y_val,
         forecast_hour,
+        reference_time,
         observation_time,
         dimensions.elevation.as_deref(),
+        dimensions.window.as_deref(),
         "image/png",
     )
     .await
@@ -308,9 +428,14 @@ pub async fn xyz_tile_handler(
 // GetCapabilities
 // ============================================================================
 
-async fn wmts_get_capabilities(state: Arc<AppState>) -> Response {
+async fn wmts_get_capabilities(state: Arc<AppState>, tenant: Option<&Tenant>) -> Response {
+    let tenant_id = tenant.map(|t| t.id.as_str());
+    if let Some(tenant) = tenant {
+        state.tenant_usage.record(tenant, "wmts", "GetCapabilities");
+    }
+
     // Check cache first
-    if let Some(cached_xml) = state.capabilities_cache.get_wmts().await {
+    if let Some(cached_xml) = state.capabilities_cache.get_wmts(tenant_id).await {
         return Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, "application/xml")
@@ -320,8 +445,17 @@ async fn wmts_get_capabilities(state: Arc<AppState>) -> Response {
     }
 
     // Build capabilities from layer configs (config-driven approach)
-    // Only include layers that have data in the catalog
-    let layer_configs = state.layer_configs.read().await;
+    // Only include layers that have data in the catalog, and that the
+    // caller's tenant may see
+    let all_layer_configs = state.layer_configs.read().await;
+    let scoped_layer_configs;
+    let layer_configs = match tenant {
+        Some(tenant) => {
+            scoped_layer_configs = all_layer_configs.scoped(|l| tenant.allows_layer(&l.id));
+            &scoped_layer_configs
+        }
+        None => &*all_layer_configs,
+    };
 
     // Collect availability data for each configured layer
     let mut param_availability: HashMap<String, ParameterAvailability> = HashMap::new();
@@ -329,32 +463,61 @@ async fn wmts_get_capabilities(state: Arc<AppState>) -> Response {
     for model_id in layer_configs.models() {
         if let Some(model_config) = layer_configs.get_model(model_id) {
             for layer in &model_config.layers {
-                // Skip composite layers - they're handled separately
-                if layer.composite {
+                // Skip composite layers - they're handled separately. Multi-band
+                // composites need the availability of each of their bands,
+                // temporal, ensemble and column composites that of their
+                // source parameter.
+                let parameters: Vec<&str> = if layer.is_band_composite() {
+                    layer.requires.iter().map(String::as_str).collect()
+                } else if layer.composite {
                     continue;
-                }
+                } else {
+                    vec![layer.source_parameter()]
+                };
+
+                for parameter in parameters {
+                    let key = format!("{}_{}", model_id, parameter);
+                    if param_availability.contains_key(&key) {
+                        continue;
+                    }
 
-                // Check if data exists for this layer
-                if let Ok(Some(availability)) = state
-                    .catalog
-                    .get_parameter_availability(model_id, &layer.parameter)
-                    .await
-                {
-                    let key = format!("{}_{}", model_id, layer.parameter);
-                    param_availability.insert(key, availability);
+                    // Check if data exists for this parameter
+                    if let Ok(Some(availability)) = state
+                        .catalog
+                        .get_parameter_availability(model_id, parameter)
+                        .await
+                    {
+                        param_availability.insert(key, availability);
+                    }
                 }
             }
         }
     }
 
-    let xml = build_wmts_capabilities_xml_v2(
-        &layer_configs,
+    let (xml, layer_extents) = build_wmts_capabilities_xml_v2(
+        layer_configs,
         &param_availability,
         &state.model_dimensions,
+        &state.update_cadence,
     );
 
-    // Cache the result
-    state.capabilities_cache.set_wmts(xml.clone()).await;
+    // Cache the result. A tenant's document only covers its layers, so its
+    // extents are added to the recorded ones rather than replacing them.
+    state
+        .capabilities_cache
+        .set_wmts(tenant_id, xml.clone())
+        .await;
+    if tenant.is_some() {
+        state
+            .capabilities_cache
+            .merge_layer_extents(layer_extents)
+            .await;
+    } else {
+        state
+            .capabilities_cache
+            .set_layer_extents(layer_extents)
+            .await;
+    }
 
     Response::builder()
         .status(StatusCode::OK)
@@ -377,8 +540,10 @@ async fn wmts_get_tile(
     x: u32,
     y: u32,
     forecast_hour: Option<u32>,
+    reference_time: Option<chrono::DateTime<chrono::Utc>>,
     observation_time: Option<chrono::DateTime<chrono::Utc>>,
     elevation: Option<&str>,
+    window: Option<&str>,
     format: &str,
 ) -> Response {
     use crate::metrics::Timer;
@@ -394,19 +559,87 @@ async fn wmts_get_tile(
         (layer, "".to_string())
     };
 
-    // Get effective elevation
+    // Get effective elevation (column layers default to their range instead)
     let effective_elevation: Option<String> = match elevation {
         Some(elev) => Some(elev.to_string()),
         None => {
             let configs = state.layer_configs.read().await;
             configs
                 .get_layer_by_param(model, &parameter)
+                .filter(|l| l.vertical.is_none())
                 .and_then(|l| l.default_level())
                 .map(|s| s.to_string())
         }
     };
     let elevation = effective_elevation.as_deref();
 
+    // Temporal composite layers resolve WINDOW up front so the default window
+    // and an explicit request for it share cached tiles
+    let temporal = state
+        .layer_configs
+        .read()
+        .await
+        .get_layer_by_param(model, &parameter)
+        .and_then(|l| l.temporal.clone());
+    let ensemble = state
+        .layer_configs
+        .read()
+        .await
+        .get_layer_by_param(model, &parameter)
+        .and_then(|l| l.ensemble.clone());
+    let vertical = state
+        .layer_configs
+        .read()
+        .await
+        .get_layer_by_param(model, &parameter)
+        .and_then(|l| l.vertical.clone());
+    let window = match &temporal {
+        Some(temporal) => match temporal.resolve_window(window) {
+            Ok((name, _)) => Some(name),
+            Err(e) => {
+                return wmts_exception("InvalidParameterValue", &e, StatusCode::BAD_REQUEST);
+            }
+        },
+        None => None,
+    };
+
+    // Column layers resolve ELEVATION to a level range up front. The default
+    // range is keyed like a request without ELEVATION, as prefetched tiles are.
+    let range = match &vertical {
+        Some(vertical) => match vertical.resolve_range(elevation) {
+            Ok(range) => Some(range),
+            Err(e) => {
+                return wmts_exception("InvalidParameterValue", &e, StatusCode::BAD_REQUEST);
+            }
+        },
+        None => None,
+    };
+    let range_elevation = vertical
+        .as_ref()
+        .zip(range)
+        .filter(|(vertical, range)| *range != vertical.default_range)
+        .map(|(_, range)| range.to_string());
+    let elevation = if vertical.is_some() {
+        range_elevation.as_deref()
+    } else {
+        elevation
+    };
+
+    // Apply the layer's default/forced style and resolve renamed styles.
+    // Cache keys resolve the requested style the same way, so aliases share
+    // cached tiles.
+    let requested_style = style;
+    let resolved_style =
+        state
+            .layer_configs
+            .read()
+            .await
+            .resolve_style(model, &parameter, Some(style));
+    if let Some(alias) = &resolved_style.deprecated_alias {
+        warn!(layer = %layer, alias = %alias, style = %resolved_style.name, "Deprecated style alias requested");
+    }
+    let style = resolved_style.name.as_str();
+
     info!(layer = %layer, style = %style, tile_matrix_set = %tile_matrix_set, z = z, x = x, y = y, forecast_hour = ?forecast_hour, elevation = ?elevation, "GetTile request");
 
     // Build cache key - include TileMatrixSet to avoid cache collisions
@@ -420,6 +653,11 @@ async fn wmts_get_tile(
         (None, Some(e)) => Some(e.clone()),
         (None, None) => None,
     };
+    let dimension_suffix = match (dimension_suffix, &window) {
+        (Some(d), Some(w)) => Some(format!("{}_w{}", d, w)),
+        (None, Some(w)) => Some(format!("w{}", w)),
+        (d, None) => d,
+    };
 
     // Use appropriate CRS code based on TileMatrixSet
     let crs_code = if tile_matrix_set == "WorldCRS84Quad" {
@@ -428,32 +666,21 @@ async fn wmts_get_tile(
         CrsCode::Epsg3857
     };
 
-    let cache_key = CacheKey::new(
-        layer,
-        style,
-        crs_code,
-        BoundingBox::new(x as f64, y as f64, z as f64, 0.0),
-        256,
-        256,
-        dimension_suffix.clone(),
-        "png",
-    );
-
-    // Cache key includes TileMatrixSet to avoid collisions between different projections
-    let cache_key_str = format!(
-        "{}:{}:{}:{}_{}_{}:{}",
-        tile_matrix_set,
-        layer,
-        style,
-        z,
-        x,
-        y,
-        dimension_suffix.as_deref().unwrap_or("current")
-    );
-
     // Get tile bounds based on TileMatrixSet
     let coord = TileCoord::new(z, x, y);
 
+    // L1 and L2 share the key; its CRS separates the two TileMatrixSets
+    let (cache_key, key_rewritten) = state
+        .tile_cache_key(
+            layer,
+            requested_style,
+            crs_code,
+            coord,
+            dimension_suffix.clone(),
+        )
+        .await;
+    let cache_key_str = cache_key.to_string();
+
     let latlon_bbox = if tile_matrix_set == "WorldCRS84Quad" {
         // WorldCRS84Quad uses linear lat/lon mapping
         wgs84_tile_to_latlon_bounds(&coord)
@@ -468,6 +695,16 @@ async fn wmts_get_tile(
         };
         wms_common::tile::tile_to_latlon_bounds(&coord)
     };
+
+    // Tiles outside the layer's data extent would render blank; skip the render
+    if let Some(extent) = layer_extent(&state, layer, model).await {
+        if !tile_in_extent(tile_matrix_set, &extent, &coord) {
+            debug!(layer = %layer, z = z, x = x, y = y, "Tile outside layer extent");
+            state.metrics.record_tile_out_of_extent();
+            return out_of_extent_tile(format);
+        }
+    }
+
     let bbox_array = [
         latlon_bbox.min_x as f32,
         latlon_bbox.min_y as f32,
@@ -475,10 +712,62 @@ async fn wmts_get_tile(
         latlon_bbox.max_y as f32,
     ];
 
+    // Tiles of the latest data expire with the model's update cadence
+    let pinned =
+        observation_time.is_some() || (reference_time.is_some() && forecast_hour.is_some());
+    let cache_control = state.cache_policy(model, pinned).header_value();
+
+    // Tiles pinned to a run or observation time may be published in the archive.
+    // Checked before L1/L2, whose keys don't include the run. Archive keys have
+    // no WINDOW, so temporal composites are never looked up there.
+    if let Some(key) = archive_key(
+        layer,
+        style,
+        forecast_hour,
+        reference_time,
+        observation_time,
+        elevation,
+    )
+    .filter(|_| temporal.is_none())
+    {
+        if let Some(tile_data) = archived_tile(&state, &key, tile_matrix_set, &coord).await {
+            state.metrics.record_tile_archive_hit();
+
+            // Convert to requested format
+            let (output_data, content_type) = match format {
+                "image/jpeg" => match convert_png_to_jpeg(&tile_data) {
+                    Ok(jpeg_data) => (jpeg_data, "image/jpeg"),
+                    Err(_) => (tile_data.to_vec(), "image/png"),
+                },
+                "image/webp" => match convert_png_to_webp(&tile_data) {
+                    Ok(webp_data) => (webp_data, "image/webp"),
+                    Err(_) => (tile_data.to_vec(), "image/png"),
+                },
+                _ => (tile_data.to_vec(), "image/png"),
+            };
+
+            // Archived tile sets never change
+            return Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, content_type)
+                .header(
+                    header::CACHE_CONTROL,
+                    CachePolicy::immutable().header_value(),
+                )
+                .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+                .header("X-Cache", "ARCHIVE-HIT")
+                .body(output_data.into())
+                .unwrap();
+        }
+    }
+
     // Check L1 cache
     if state.optimization_config.l1_cache_enabled {
         if let Some(tile_data) = state.tile_memory_cache.get(&cache_key_str).await {
             state.metrics.record_l1_cache_hit();
+            if key_rewritten {
+                state.metrics.record_cache_key_rewrite(true);
+            }
             state
                 .metrics
                 .record_tile_request_location(&bbox_array, crate::metrics::TileCacheStatus::L1Hit);
@@ -499,7 +788,7 @@ async fn wmts_get_tile(
             return Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, content_type)
-                .header(header::CACHE_CONTROL, "max-age=300")
+                .header(header::CACHE_CONTROL, &cache_control)
                 .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
                 .header("X-Cache", "L1-HIT")
                 .body(output_data.into())
@@ -513,6 +802,9 @@ async fn wmts_get_tile(
         let mut cache = state.cache.lock().await;
         if let Ok(Some(cached_data)) = cache.get(&cache_key).await {
             state.metrics.record_cache_hit().await;
+            if key_rewritten {
+                state.metrics.record_cache_key_rewrite(true);
+            }
             state
                 .metrics
                 .record_tile_request_location(&bbox_array, crate::metrics::TileCacheStatus::L2Hit);
@@ -541,7 +833,7 @@ async fn wmts_get_tile(
             return Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, content_type)
-                .header(header::CACHE_CONTROL, "max-age=3600")
+                .header(header::CACHE_CONTROL, &cache_control)
                 .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
                 .header("X-Cache", "L2-HIT")
                 .body(output_data.into())
@@ -549,6 +841,9 @@ async fn wmts_get_tile(
         }
         state.metrics.record_cache_miss().await;
     }
+    if key_rewritten {
+        state.metrics.record_cache_key_rewrite(false);
+    }
 
     state
         .metrics
@@ -563,9 +858,10 @@ async fn wmts_get_tile(
     }
 
     // Validate layer exists in configuration
-    {
+    let is_band_composite = {
         let configs = state.layer_configs.read().await;
-        if configs.get_layer_by_param(model, &parameter).is_none() {
+        let layer_config = configs.get_layer_by_param(model, &parameter);
+        if layer_config.is_none() {
             // Check if it's a wind barbs layer
             if parameter != "WIND_BARBS" {
                 return wmts_exception(
@@ -575,13 +871,111 @@ async fn wmts_get_tile(
                 );
             }
         }
-    }
+        layer_config.is_some_and(|l| l.is_band_composite())
+    };
 
     // Check if model requires full grid reads (non-geographic projection)
     let requires_full_grid = state.model_dimensions.requires_full_grid(model);
 
     // Render the tile
-    let result = if parameter == "WIND_BARBS" {
+    let result = if let Some(temporal) = &temporal {
+        let style_file = state
+            .layer_configs
+            .read()
+            .await
+            .get_style_file_for_parameter(model, &parameter);
+        crate::rendering::render_temporal_composite(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            temporal,
+            window.as_deref(),
+            forecast_hour,
+            observation_time,
+            elevation,
+            256,
+            256,
+            Some(bbox_array),
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+            state.model_dimensions.is_observation(model),
+        )
+        .await
+    } else if let Some(ensemble) = &ensemble {
+        let style_file = state
+            .layer_configs
+            .read()
+            .await
+            .get_style_file_for_parameter(model, &parameter);
+        crate::rendering::render_ensemble_product(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            ensemble,
+            forecast_hour,
+            elevation,
+            256,
+            256,
+            Some(bbox_array),
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+        )
+        .await
+    } else if let (Some(vertical), Some(range)) = (&vertical, &range) {
+        let style_file = state
+            .layer_configs
+            .read()
+            .await
+            .get_style_file_for_parameter(model, &parameter);
+        crate::rendering::render_vertical_composite(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            vertical,
+            range,
+            forecast_hour,
+            256,
+            256,
+            Some(bbox_array),
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+        )
+        .await
+    } else if is_band_composite {
+        let style_file = state
+            .layer_configs
+            .read()
+            .await
+            .get_style_file_for_parameter(model, &parameter);
+        crate::rendering::render_composite_layer(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            observation_time,
+            256,
+            256,
+            bbox_array,
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+        )
+        .await
+    } else if parameter == "WIND_BARBS" {
         // Get wind barbs style file
         let wind_style_file = state
             .layer_configs
@@ -657,6 +1051,27 @@ async fn wmts_get_tile(
         .await
     };
 
+    // Tiles only get an underlay when explicitly enabled; it is cached with them
+    let result = match result {
+        Ok(png_data) if state.underlay.enabled_for_tiles() => {
+            let web_mercator = tile_matrix_set != "WorldCRS84Quad";
+            match state
+                .underlay
+                .for_extent(bbox_array, 256, 256, web_mercator)
+                .await
+                .map(|underlay| crate::underlay::composite_over(&png_data, &underlay))
+            {
+                Some(Ok(composited)) => Ok(composited),
+                Some(Err(e)) => {
+                    warn!(error = %e, "Failed to composite tile underlay");
+                    Ok(png_data)
+                }
+                None => Ok(png_data),
+            }
+        }
+        other => other,
+    };
+
     match result {
         Ok(png_data) => {
             let layer_type = crate::metrics::LayerType::from_layer_and_style(layer, style);
@@ -722,7 +1137,7 @@ async fn wmts_get_tile(
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, content_type)
-                .header(header::CACHE_CONTROL, "max-age=3600")
+                .header(header::CACHE_CONTROL, &cache_control)
                 .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
                 .header("X-Cache", "MISS")
                 .body(output_data.into())
@@ -731,15 +1146,151 @@ async fn wmts_get_tile(
         Err(e) => {
             state.metrics.record_render(timer.elapsed_us(), false).await;
             error!(layer = %layer, error = %e, "WMTS tile rendering failed");
-            wmts_exception(
-                "NoApplicableCode",
-                &format!("Rendering failed: {}", e),
-                StatusCode::INTERNAL_SERVER_ERROR,
-            )
+            // Missing data is 404 and storage outages 502, so clients know to retry
+            let e = super::wms::WmsError::from_rendering_error(e);
+            wmts_exception(e.code(), &e.message(), e.status_code())
+        }
+    }
+}
+
+// ============================================================================
+// Tile Archive
+// ============================================================================
+
+/// Archive key for a GetTile request.
+///
+/// Only requests pinned to a model run (RUN + FORECAST) or an observation
+/// TIME have one; tiles for the latest run change with every ingested run.
+fn archive_key(
+    layer: &str,
+    style: &str,
+    forecast_hour: Option<u32>,
+    reference_time: Option<chrono::DateTime<chrono::Utc>>,
+    observation_time: Option<chrono::DateTime<chrono::Utc>>,
+    elevation: Option<&str>,
+) -> Option<ArchiveKey> {
+    let key = match (reference_time, forecast_hour, observation_time) {
+        (Some(run), Some(hour), _) => ArchiveKey::forecast(layer, style, run, hour),
+        (_, _, Some(time)) => ArchiveKey::observation(layer, style, time),
+        _ => return None,
+    };
+    Some(key.with_elevation(elevation))
+}
+
+/// Read a tile from the tile archive, keeping it in L1 under its archive key.
+async fn archived_tile(
+    state: &AppState,
+    key: &ArchiveKey,
+    tile_matrix_set: &str,
+    coord: &TileCoord,
+) -> Option<bytes::Bytes> {
+    let archive = state.tile_archive.as_ref()?;
+    let l1_enabled = state.optimization_config.l1_cache_enabled;
+    let l1_key = format!(
+        "archive:{}:{}:{}:{}:{}_{}_{}",
+        tile_matrix_set, key.layer, key.style, key.time, coord.z, coord.x, coord.y
+    );
+
+    if l1_enabled {
+        if let Some(tile_data) = state.tile_memory_cache.get(&l1_key).await {
+            return Some(tile_data);
+        }
+    }
+
+    match archive.get_tile(key, tile_matrix_set, coord).await {
+        Ok(Some(tile_data)) => {
+            if l1_enabled {
+                state
+                    .tile_memory_cache
+                    .set(&l1_key, tile_data.clone(), None)
+                    .await;
+            }
+            Some(tile_data)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!(error = %e, layer = %key.layer, time = %key.time, "Failed to read archived tile");
+            None
+        }
+    }
+}
+
+// ============================================================================
+// Layer Extents
+// ============================================================================
+
+/// Data extent of a layer in degrees, longitudes in -180..180.
+///
+/// Uses the per-layer extent recorded when WMTS capabilities were last built,
+/// falling back to the model's overall extent from the catalog.
+async fn layer_extent(state: &AppState, layer: &str, model: &str) -> Option<BoundingBox> {
+    let cache = &state.capabilities_cache;
+    if let Some(extent) = cache.get_layer_extent(layer).await {
+        return Some(extent);
+    }
+    if let Some(extent) = cache.get_model_extent(model).await {
+        return Some(extent);
+    }
+
+    match state.catalog.get_model_bbox(model).await {
+        Ok(bbox) => {
+            let extent = normalized_extent(&bbox);
+            cache.set_model_extent(model, extent).await;
+            Some(extent)
+        }
+        Err(e) => {
+            debug!(model = %model, error = %e, "No extent available for model");
+            None
         }
     }
 }
 
+/// Tile limits of an extent at one zoom level of a tile matrix set.
+fn tile_limits(tile_matrix_set: &str, extent: &BoundingBox, zoom: u32) -> Option<TileMatrixLimits> {
+    if tile_matrix_set == "WorldCRS84Quad" {
+        wgs84_tile_limits(extent, zoom)
+    } else {
+        web_mercator_tile_limits(extent, zoom)
+    }
+}
+
+/// Whether a tile intersects a layer's data extent.
+fn tile_in_extent(tile_matrix_set: &str, extent: &BoundingBox, coord: &TileCoord) -> bool {
+    tile_limits(tile_matrix_set, extent, coord.z).is_some_and(|limits| limits.contains(coord))
+}
+
+/// Response for tiles outside a layer's data extent.
+///
+/// A transparent tile, encoded once, so clients that ignore
+/// `TileMatrixSetLimits` still get a valid image without a render.
+fn out_of_extent_tile(format: &str) -> Response {
+    static BLANK_PNG: OnceLock<Vec<u8>> = OnceLock::new();
+    let png = BLANK_PNG.get_or_init(|| {
+        renderer::png::create_png(&vec![0u8; 256 * 256 * 4], 256, 256).expect("blank tile encodes")
+    });
+
+    let (output_data, content_type) = match format {
+        "image/jpeg" => match convert_png_to_jpeg(png) {
+            Ok(jpeg_data) => (jpeg_data, "image/jpeg"),
+            Err(_) => (png.clone(), "image/png"),
+        },
+        "image/webp" => match convert_png_to_webp(png) {
+            Ok(webp_data) => (webp_data, "image/webp"),
+            Err(_) => (png.clone(), "image/png"),
+        },
+        _ => (png.clone(), "image/png"),
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CACHE_CONTROL, "max-age=3600")
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header("X-Cache", "OUT-OF-EXTENT")
+        .body(output_data.into())
+        .unwrap()
+}
+
 // ============================================================================
 // Tile Prefetching
 // ============================================================================
@@ -819,16 +1370,44 @@ fn spawn_tile_prefetch(
 }
 
 async fn prefetch_single_tile(state: Arc<AppState>, layer: &str, style: &str, coord: TileCoord) {
-    let cache_key = CacheKey::new(
-        layer,
-        style,
-        CrsCode::Epsg3857,
-        BoundingBox::new(coord.x as f64, coord.y as f64, coord.z as f64, 0.0),
-        256,
-        256,
-        None,
-        "png",
-    );
+    let parts: Vec<&str> = layer.split('_').collect();
+    let (model, parameter) = if parts.len() >= 2 {
+        (parts[0], parts[1..].join("_").to_uppercase())
+    } else {
+        return;
+    };
+
+    // Temporal composites are prefetched for their default window, keyed as in GetTile
+    let temporal = state
+        .layer_configs
+        .read()
+        .await
+        .get_layer_by_param(model, &parameter)
+        .and_then(|l| l.temporal.clone());
+
+    let ensemble = state
+        .layer_configs
+        .read()
+        .await
+        .get_layer_by_param(model, &parameter)
+        .and_then(|l| l.ensemble.clone());
+
+    let vertical = state
+        .layer_configs
+        .read()
+        .await
+        .get_layer_by_param(model, &parameter)
+        .and_then(|l| l.vertical.clone());
+
+    let (cache_key, _) = state
+        .tile_cache_key(
+            layer,
+            style,
+            CrsCode::Epsg3857,
+            coord,
+            temporal.as_ref().map(|t| format!("w{}", t.default_window)),
+        )
+        .await;
 
     {
         let mut cache = state.cache.lock().await;
@@ -837,12 +1416,11 @@ async fn prefetch_single_tile(state: Arc<AppState>, layer: &str, style: &str, co
         }
     }
 
-    let parts: Vec<&str> = layer.split('_').collect();
-    let (model, parameter) = if parts.len() >= 2 {
-        (parts[0], parts[1..].join("_").to_uppercase())
-    } else {
-        return;
-    };
+    if let Some(extent) = layer_extent(&state, layer, model).await {
+        if !tile_in_extent("WebMercatorQuad", &extent, &coord) {
+            return;
+        }
+    }
 
     let latlon_bbox = wms_common::tile::tile_to_latlon_bounds(&coord);
     let bbox_array = [
@@ -861,7 +1439,91 @@ async fn prefetch_single_tile(state: Arc<AppState>, layer: &str, style: &str, co
     // Check if model requires full grid reads (non-geographic projection)
     let requires_full_grid = state.model_dimensions.requires_full_grid(model);
 
-    let result = if parameter == "WIND_BARBS" {
+    let is_band_composite = state
+        .layer_configs
+        .read()
+        .await
+        .get_layer_by_param(model, &parameter)
+        .is_some_and(|l| l.is_band_composite());
+
+    let result = if let Some(temporal) = &temporal {
+        crate::rendering::render_temporal_composite(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            temporal,
+            None,
+            None,
+            None,
+            None,
+            256,
+            256,
+            Some(bbox_array),
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+            state.model_dimensions.is_observation(model),
+        )
+        .await
+    } else if let Some(ensemble) = &ensemble {
+        crate::rendering::render_ensemble_product(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            ensemble,
+            None,
+            None,
+            256,
+            256,
+            Some(bbox_array),
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+        )
+        .await
+    } else if let Some(vertical) = &vertical {
+        crate::rendering::render_vertical_composite(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            vertical,
+            &vertical.default_range,
+            None,
+            256,
+            256,
+            Some(bbox_array),
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+        )
+        .await
+    } else if is_band_composite {
+        crate::rendering::render_composite_layer(
+            &state.catalog,
+            &state.metrics,
+            &state.grid_processor_factory,
+            model,
+            &parameter,
+            None,
+            256,
+            256,
+            bbox_array,
+            &style_file,
+            Some(style),
+            true,
+            requires_full_grid,
+        )
+        .await
+    } else if parameter == "WIND_BARBS" {
         crate::rendering::render_wind_barbs_tile_with_level(
             &state.catalog,
             &state.grid_processor_factory,
@@ -924,8 +1586,11 @@ async fn prefetch_single_tile(state: Arc<AppState>, layer: &str, style: &str, co
 // WMTS Capabilities XML Builder (Legacy - kept for reference)
 // ============================================================================
 
+/// Highest TileMatrix identifier in both tile matrix sets.
+const MAX_TILE_MATRIX: u32 = 18;
+
 fn build_tile_matrices() -> String {
-    (0..=18)
+    (0..=MAX_TILE_MATRIX)
         .map(|z| {
             let n = 2u32.pow(z);
             let scale = 559082264.0287178 / (n as f64);
@@ -950,7 +1615,7 @@ fn build_tile_matrices() -> String {
 /// Build TileMatrix elements for WorldCRS84Quad TileMatrixSet.
 /// WorldCRS84Quad uses 2:1 aspect ratio: matrix_width = 2^(z+1), matrix_height = 2^z
 fn build_wgs84_tile_matrices() -> String {
-    (0..=18)
+    (0..=MAX_TILE_MATRIX)
         .map(|z| {
             let n_rows = 2u32.pow(z);
             let n_cols = 2u32.pow(z + 1); // 2:1 aspect ratio
@@ -975,14 +1640,68 @@ fn build_wgs84_tile_matrices() -> String {
         .join("\n")
 }
 
+/// Build a TileMatrixSetLink element, with TileMatrixSetLimits covering the
+/// layer's data extent. Limits are omitted when the extent covers the whole
+/// tile matrix set.
+fn build_tile_matrix_set_link(tile_matrix_set: &str, extent: &BoundingBox) -> String {
+    let full_coverage = tile_limits(tile_matrix_set, extent, MAX_TILE_MATRIX).is_some_and(|l| {
+        let n_rows = 2u32.pow(MAX_TILE_MATRIX);
+        let n_cols = if tile_matrix_set == "WorldCRS84Quad" {
+            n_rows * 2
+        } else {
+            n_rows
+        };
+        l.min_row == 0 && l.max_row == n_rows - 1 && l.min_col == 0 && l.max_col == n_cols - 1
+    });
+    if full_coverage {
+        return format!(
+            r#"      <TileMatrixSetLink>
+        <TileMatrixSet>{}</TileMatrixSet>
+      </TileMatrixSetLink>"#,
+            tile_matrix_set
+        );
+    }
+
+    let limits = (0..=MAX_TILE_MATRIX)
+        .filter_map(|z| tile_limits(tile_matrix_set, extent, z))
+        .map(|l| {
+            format!(
+                r#"          <TileMatrixLimits>
+            <TileMatrix>{}</TileMatrix>
+            <MinTileRow>{}</MinTileRow>
+            <MaxTileRow>{}</MaxTileRow>
+            <MinTileCol>{}</MinTileCol>
+            <MaxTileCol>{}</MaxTileCol>
+          </TileMatrixLimits>"#,
+                l.zoom, l.min_row, l.max_row, l.min_col, l.max_col
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"      <TileMatrixSetLink>
+        <TileMatrixSet>{}</TileMatrixSet>
+        <TileMatrixSetLimits>
+{}
+        </TileMatrixSetLimits>
+      </TileMatrixSetLink>"#,
+        tile_matrix_set, limits
+    )
+}
+
 /// Build WMTS capabilities XML from layer configs (config-driven approach).
 /// Only includes layers that have data available in the catalog.
+///
+/// Also returns the data extent of each advertised layer, keyed by layer id.
 fn build_wmts_capabilities_xml_v2(
     layer_configs: &LayerConfigRegistry,
     param_availability: &HashMap<String, ParameterAvailability>,
     dimension_registry: &ModelDimensionRegistry,
-) -> String {
+    update_cadence: &UpdateCadence,
+) -> (String, HashMap<String, BoundingBox>) {
     let mut all_layers: Vec<String> = Vec::new();
+    let mut layer_extents: HashMap<String, BoundingBox> = HashMap::new();
 
     for model_id in layer_configs.models() {
         let Some(model_config) = layer_configs.get_model(model_id) else {
@@ -990,43 +1709,72 @@ fn build_wmts_capabilities_xml_v2(
         };
 
         let is_observational = dimension_registry.is_observation(model_id);
+        let update_metadata =
+            build_update_metadata_wmts(update_cadence.cadence(model_id, dimension_registry));
 
         // Track availability for composite layer validation (e.g., WIND_BARBS)
         let mut ugrd_availability: Option<&ParameterAvailability> = None;
         let mut vgrd_availability: Option<&ParameterAvailability> = None;
 
         for layer in &model_config.layers {
-            // Skip composite layers for now - handle them after regular layers
-            if layer.composite {
-                continue;
-            }
-
-            let key = format!("{}_{}", model_id, layer.parameter);
-            let Some(availability) = param_availability.get(&key) else {
-                // No data for this layer - skip it
+            // Multi-band composites are listed when all of their bands have
+            // data; other composite layers are handled after regular layers
+            let composite_availability = if layer.is_band_composite() {
+                let Some(availability) =
+                    band_composite_availability(model_id, layer, param_availability)
+                else {
+                    continue;
+                };
+                Some(availability)
+            } else if layer.composite {
                 continue;
+            } else {
+                None
             };
 
-            // Track UGRD/VGRD for wind barbs
-            if layer.parameter == "UGRD" {
-                ugrd_availability = Some(availability);
-            } else if layer.parameter == "VGRD" {
-                vgrd_availability = Some(availability);
-            }
+            let availability = match &composite_availability {
+                Some(availability) => availability,
+                None => {
+                    let key = format!("{}_{}", model_id, layer.source_parameter());
+                    let Some(availability) = param_availability.get(&key) else {
+                        // No data for this layer - skip it
+                        continue;
+                    };
+
+                    // Track UGRD/VGRD for wind barbs
+                    if layer.parameter == "UGRD" {
+                        ugrd_availability = Some(availability);
+                    } else if layer.parameter == "VGRD" {
+                        vgrd_availability = Some(availability);
+                    }
+                    availability
+                }
+            };
 
             let layer_id = format!("{}_{}", model_id, layer.parameter);
             let layer_title = format!("{} - {}", model_config.display_name, layer.title);
 
             // Build dimensions for this specific layer
             let time_dimensions = build_layer_time_dimensions_wmts(availability, is_observational);
-            let elevation_dim = build_layer_elevation_dimension_wmts(&availability.levels);
+            let resource_path = build_resource_path_wmts(&layer_id, is_observational);
+            let elevation_dim = match &layer.vertical {
+                Some(vertical) => build_layer_range_dimension_wmts(vertical),
+                None => build_layer_elevation_dimension_wmts(&availability.levels),
+            };
+            let window_dim = build_layer_window_dimension_wmts(layer.temporal.as_ref());
 
             // Get styles from style file
             let style_path = layer_configs.get_style_path(layer);
-            let styles = get_wmts_styles_xml_from_file(&style_path);
+            let styles = get_wmts_styles_xml_from_file(&style_path, &layer.style_policy);
 
             // Build bounding box
             let (west, east, south, north) = normalize_bbox_wmts(&availability.bbox);
+            let extent = BoundingBox::new(west, south, east, north);
+            let tile_matrix_set_links = format!(
+                "{}\n{}",
+                build_tile_matrix_set_link("WebMercatorQuad", &extent),
+                build_tile_matrix_set_link("WorldCRS84Quad", &extent)
+            );
 
             all_layers.push(format!(
                 r#"    <Layer>
@@ -1036,26 +1784,25 @@ fn build_wmts_capabilities_xml_v2(
         <ows:LowerCorner>{} {}</ows:LowerCorner>
         <ows:UpperCorner>{} {}</ows:UpperCorner>
       </ows:WGS84BoundingBox>
+{}
 {}
       <Format>image/png</Format>
       <Format>image/jpeg</Format>
       <Format>image/webp</Format>
-      <TileMatrixSetLink>
-        <TileMatrixSet>WebMercatorQuad</TileMatrixSet>
-      </TileMatrixSetLink>
-      <TileMatrixSetLink>
-        <TileMatrixSet>WorldCRS84Quad</TileMatrixSet>
-      </TileMatrixSetLink>
-{}{}
-      <ResourceURL format="image/png" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{Style}}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.png"/>
-      <ResourceURL format="image/webp" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{Style}}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.webp"/>
+{}
+{}{}{}
+      <ResourceURL format="image/png" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.png"/>
+      <ResourceURL format="image/webp" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.webp"/>
     </Layer>"#,
                 layer_title, layer_id,
                 west, south, east, north,
+                update_metadata,
                 styles,
-                time_dimensions, elevation_dim,
-                layer_id, layer_id
+                tile_matrix_set_links,
+                time_dimensions, elevation_dim, window_dim,
+                resource_path, resource_path
             ));
+            layer_extents.insert(layer_id, extent);
         }
 
         // Handle WIND_BARBS composite layer
@@ -1096,9 +1843,16 @@ fn build_wmts_capabilities_xml_v2(
                 let layer_id = format!("{}_WIND_BARBS", model_id);
                 let time_dimensions =
                     build_layer_time_dimensions_wmts(&wind_availability, is_observational);
+                let resource_path = build_resource_path_wmts(&layer_id, is_observational);
                 let elevation_dim = build_layer_elevation_dimension_wmts(&wind_availability.levels);
 
                 let (west, east, south, north) = normalize_bbox_wmts(&ugrd.bbox);
+                let extent = BoundingBox::new(west, south, east, north);
+                let tile_matrix_set_links = format!(
+                    "{}\n{}",
+                    build_tile_matrix_set_link("WebMercatorQuad", &extent),
+                    build_tile_matrix_set_link("WorldCRS84Quad", &extent)
+                );
 
                 all_layers.push(format!(
                     r#"    <Layer>
@@ -1108,21 +1862,24 @@ fn build_wmts_capabilities_xml_v2(
         <ows:LowerCorner>{} {}</ows:LowerCorner>
         <ows:UpperCorner>{} {}</ows:UpperCorner>
       </ows:WGS84BoundingBox>
+{}
       <Style isDefault="true"><ows:Identifier>default</ows:Identifier><ows:Title>Default</ows:Title></Style>
       <Format>image/png</Format>
       <Format>image/jpeg</Format>
       <Format>image/webp</Format>
-      <TileMatrixSetLink><TileMatrixSet>WebMercatorQuad</TileMatrixSet></TileMatrixSetLink>
-      <TileMatrixSetLink><TileMatrixSet>WorldCRS84Quad</TileMatrixSet></TileMatrixSetLink>
+{}
 {}{}
-      <ResourceURL format="image/png" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{Style}}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.png"/>
-      <ResourceURL format="image/webp" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{Style}}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.webp"/>
+      <ResourceURL format="image/png" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.png"/>
+      <ResourceURL format="image/webp" resourceType="tile" template="http://localhost:8080/wmts/rest/{}/{{TileMatrixSet}}/{{TileMatrix}}/{{TileRow}}/{{TileCol}}.webp"/>
     </Layer>"#,
                     model_config.display_name, layer_id,
                     west, south, east, north,
+                    update_metadata,
+                    tile_matrix_set_links,
                     time_dimensions, elevation_dim,
-                    layer_id, layer_id
+                    resource_path, resource_path
                 ));
+                layer_extents.insert(layer_id, extent);
             }
         }
     }
@@ -1131,7 +1888,7 @@ fn build_wmts_capabilities_xml_v2(
     let webmercator_tile_matrices = build_tile_matrices();
     let wgs84_tile_matrices = build_wgs84_tile_matrices();
 
-    format!(
+    let xml = format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <Capabilities xmlns="http://www.opengis.net/wmts/1.0"
     xmlns:ows="http://www.opengis.net/ows/1.1"
@@ -1165,7 +1922,9 @@ fn build_wmts_capabilities_xml_v2(
   </Contents>
 </Capabilities>"#,
         layers, webmercator_tile_matrices, wgs84_tile_matrices
-    )
+    );
+
+    (xml, layer_extents)
 }
 
 /// Build time dimension XML for a WMTS layer based on actual data availability.
@@ -1174,29 +1933,12 @@ fn build_layer_time_dimensions_wmts(
     is_observational: bool,
 ) -> String {
     if is_observational {
-        let time_values = if availability.times.is_empty() {
-            "        <Value>latest</Value>".to_string()
-        } else {
-            availability
-                .times
-                .iter()
-                .map(|v| format!("        <Value>{}</Value>", v))
-                .collect::<Vec<_>>()
-                .join("\n")
-        };
-        let default = availability
+        // Observation layers take ISO 8601 TIME values; list the most recent
+        let times = availability
             .times
-            .first()
-            .map(|s| s.as_str())
-            .unwrap_or("latest");
-        format!(
-            r#"      <Dimension>
-        <ows:Identifier>time</ows:Identifier>
-        <Default>{}</Default>
-{}
-      </Dimension>"#,
-            default, time_values
-        )
+            .iter()
+            .filter_map(|t| parse_wmts_time(t).ok().flatten());
+        WmtsDimensionInfo::time(times, DEFAULT_MAX_TIME_VALUES).to_xml()
     } else {
         let run_values = if availability.times.is_empty() {
             "        <Value>latest</Value>".to_string()
@@ -1242,7 +1984,28 @@ fn build_layer_time_dimensions_wmts(
     }
 }
 
+/// Build the layer part of a RESTful tile URL template. Observation layers
+/// take their TIME as a path segment, e.g.
+/// `goes18_CMI_C13/{Style}/{time}/WebMercatorQuad/...`.
+fn build_resource_path_wmts(layer_id: &str, is_observational: bool) -> String {
+    if is_observational {
+        format!("{}/{{Style}}/{{time}}", layer_id)
+    } else {
+        format!("{}/{{Style}}", layer_id)
+    }
+}
+
 /// Build elevation dimension XML for WMTS layer.
+/// Metadata link advertising how often a layer's data is updated, as an
+/// ISO 8601 duration. Clients can use it to schedule refreshes.
+fn build_update_metadata_wmts(cadence: std::time::Duration) -> String {
+    format!(
+        r#"      <ows:Metadata xlink:type="simple" xlink:role="{}" xlink:title="{}"/>"#,
+        UPDATE_INTERVAL_ROLE,
+        format_iso8601_duration(cadence)
+    )
+}
+
 fn build_layer_elevation_dimension_wmts(levels: &[String]) -> String {
     if levels.len() <= 1 {
         return String::new();
@@ -1273,6 +2036,46 @@ fn build_layer_elevation_dimension_wmts(levels: &[String]) -> String {
     )
 }
 
+/// Build the elevation dimension XML for a WMTS column composite layer.
+///
+/// Tiles can be requested for any range, but only the default range is
+/// listed: WMTS dimensions are discrete values.
+fn build_layer_range_dimension_wmts(vertical: &VerticalConfig) -> String {
+    format!(
+        r#"
+      <Dimension>
+        <ows:Identifier>elevation</ows:Identifier>
+        <Default>{0}</Default>
+        <Value>{0}</Value>
+      </Dimension>"#,
+        vertical.default_range
+    )
+}
+
+/// Build the window dimension XML for a WMTS temporal composite layer.
+fn build_layer_window_dimension_wmts(temporal: Option<&TemporalConfig>) -> String {
+    let Some(temporal) = temporal else {
+        return String::new();
+    };
+
+    let values = temporal
+        .windows
+        .iter()
+        .map(|v| format!("        <Value>{}</Value>", v))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"
+      <Dimension>
+        <ows:Identifier>window</ows:Identifier>
+        <Default>{}</Default>
+{}
+      </Dimension>"#,
+        temporal.default_window, values
+    )
+}
+
 /// Normalize bounding box longitude to -180/180 for WMTS.
 fn normalize_bbox_wmts(bbox: &wms_common::BoundingBox) -> (f64, f64, f64, f64) {
     let (west, east) = if bbox.min_x == 0.0 && bbox.max_x == 360.0 {
@@ -1293,6 +2096,12 @@ fn normalize_bbox_wmts(bbox: &wms_common::BoundingBox) -> (f64, f64, f64, f64) {
     (west, east, bbox.min_y, bbox.max_y)
 }
 
+/// Normalized lat/lon extent of a catalog bounding box.
+fn normalized_extent(bbox: &BoundingBox) -> BoundingBox {
+    let (west, east, south, north) = normalize_bbox_wmts(bbox);
+    BoundingBox::new(west, south, east, north)
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1316,6 +2125,29 @@ mod tests {
         assert_eq!(tiles.len(), 24);
     }
 
+    #[test]
+    fn test_archive_key_requires_pinned_time() {
+        use chrono::TimeZone;
+        let run = chrono::Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+
+        // Latest-run requests are never served from the archive
+        assert!(archive_key("gfs_TMP", "temperature", Some(6), None, None, None).is_none());
+        assert!(archive_key("gfs_TMP", "temperature", None, Some(run), None, None).is_none());
+
+        let key = archive_key(
+            "gfs_TMP",
+            "temperature",
+            Some(6),
+            Some(run),
+            None,
+            Some("2 m"),
+        );
+        assert_eq!(key.unwrap().time, "20240115T1200Z_f006_2_m");
+
+        let key = archive_key("goes18_CMI_C13", "ir", None, None, Some(run), None);
+        assert_eq!(key.unwrap().time, "20240115T120000Z");
+    }
+
     #[test]
     fn test_get_tiles_edge_handling() {
         // Tile at corner of world (z=2, x=0, y=0)
@@ -1336,4 +2168,79 @@ mod tests {
         assert!(matrices.contains("<ows:Identifier>18</ows:Identifier>"));
         assert!(matrices.contains("<TileWidth>256</TileWidth>"));
     }
+
+    #[test]
+    fn test_tile_in_extent() {
+        let hrrr = BoundingBox::new(-134.1, 21.1, -60.9, 52.6);
+        let mercator = |x, y| tile_in_extent("WebMercatorQuad", &hrrr, &TileCoord::new(4, x, y));
+        let wgs84 = |x, y| tile_in_extent("WorldCRS84Quad", &hrrr, &TileCoord::new(2, x, y));
+
+        // z=4 tile over the central US, and one over Europe
+        assert!(mercator(3, 6));
+        assert!(!mercator(8, 5));
+        // WorldCRS84Quad has twice as many columns
+        assert!(wgs84(1, 0));
+        assert!(!wgs84(5, 0));
+    }
+
+    #[test]
+    fn test_tile_matrix_set_link_limits() {
+        let hrrr = BoundingBox::new(-134.1, 21.1, -60.9, 52.6);
+        let link = build_tile_matrix_set_link("WebMercatorQuad", &hrrr);
+        assert!(link.contains("<TileMatrixSetLimits>"));
+        assert_eq!(link.matches("<TileMatrixLimits>").count(), 19);
+        assert!(link.contains(
+            "<TileMatrix>4</TileMatrix>\n            <MinTileRow>5</MinTileRow>\n            <MaxTileRow>7</MaxTileRow>\n            <MinTileCol>2</MinTileCol>\n            <MaxTileCol>5</MaxTileCol>"
+        ));
+    }
+
+    #[test]
+    fn test_update_metadata() {
+        let metadata = build_update_metadata_wmts(std::time::Duration::from_secs(120));
+        assert_eq!(
+            metadata.trim(),
+            r#"<ows:Metadata xlink:type="simple" xlink:role="urn:weather-wms:update-interval" xlink:title="PT2M"/>"#
+        );
+    }
+
+    #[test]
+    fn test_observation_time_dimension_is_bounded() {
+        let times: Vec<String> = (0..DEFAULT_MAX_TIME_VALUES as i64 + 20)
+            .map(|i| {
+                let time = chrono::DateTime::from_timestamp(1_705_320_000 - i * 300, 0).unwrap();
+                format_wmts_time(time)
+            })
+            .collect();
+        let availability = ParameterAvailability {
+            times,
+            forecast_hours: vec![],
+            levels: vec![],
+            bbox: BoundingBox::new(-130.0, 20.0, -60.0, 55.0),
+        };
+
+        let xml = build_layer_time_dimensions_wmts(&availability, true);
+        assert_eq!(xml.matches("<Value>").count(), DEFAULT_MAX_TIME_VALUES);
+        assert!(xml.contains("<Default>2024-01-15T12:00:00Z</Default>"));
+        assert!(xml.contains("<Value>2024-01-15T12:00:00Z</Value>"));
+
+        assert_eq!(
+            build_resource_path_wmts("goes18_CMI_C13", true),
+            "goes18_CMI_C13/{Style}/{time}"
+        );
+        assert_eq!(
+            build_resource_path_wmts("gfs_TMP", false),
+            "gfs_TMP/{Style}"
+        );
+    }
+
+    #[test]
+    fn test_tile_matrix_set_link_global_has_no_limits() {
+        // GFS 0-360 grid normalizes to a wrapped extent
+        let gfs = normalized_extent(&BoundingBox::new(0.0, -90.0, 359.75, 90.0));
+        for tms in ["WebMercatorQuad", "WorldCRS84Quad"] {
+            let link = build_tile_matrix_set_link(tms, &gfs);
+            assert!(!link.contains("TileMatrixSetLimits"));
+            assert!(link.contains(&format!("<TileMatrixSet>{}</TileMatrixSet>", tms)));
+        }
+    }
 }
@@ -0,0 +1,163 @@
+//! Verification sweep of stored Zarr grids against their chunk checksums.
+//!
+//! Grids are written with a CRC32C checksum per chunk (see
+//! [`grid_processor::ZarrMetadata::checksum`]). Reads already reject or flag
+//! corrupt chunks as they are requested; the sweep reads every chunk of every
+//! available dataset, bypassing the chunk cache, so corruption in object
+//! storage is found before a request hits it. Datasets written before
+//! checksums were introduced are skipped.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use grid_processor::{
+    create_minio_storage, ChunkVerification, GridMetadata, ZarrGridProcessor, ZarrMetadata,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use storage::CatalogEntry;
+use tracing::{error, info, warn};
+
+use crate::state::AppState;
+
+/// A dataset with chunks that failed their checksum.
+#[derive(Debug, Clone, Serialize)]
+pub struct CorruptDataset {
+    pub model: String,
+    pub parameter: String,
+    pub level: String,
+    pub reference_time: DateTime<Utc>,
+    pub forecast_hour: u32,
+    pub storage_path: String,
+    /// Chunks (x, y) whose checksum didn't match
+    pub corrupt_chunks: Vec<(usize, usize)>,
+}
+
+/// Statistics from a checksum sweep.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ChecksumSweepStats {
+    /// Number of datasets whose chunks were verified
+    pub datasets_checked: u64,
+    /// Number of datasets written without checksums
+    pub datasets_skipped: u64,
+    /// Number of chunks read
+    pub chunks_checked: u64,
+    /// Datasets with at least one corrupt chunk
+    pub corrupt_datasets: Vec<CorruptDataset>,
+    /// Datasets that couldn't be read at all
+    pub errors: Vec<String>,
+}
+
+/// Sweep that verifies the chunk checksums of stored grids.
+pub struct ChecksumSweepTask {
+    state: Arc<AppState>,
+}
+
+impl ChecksumSweepTask {
+    /// Create a new sweep.
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Verify every available dataset, or only those of `model`.
+    pub async fn run(&self, model: Option<&str>) -> Result<ChecksumSweepStats> {
+        let models = match model {
+            Some(model) => vec![model.to_string()],
+            None => self.state.catalog.list_models().await?,
+        };
+
+        info!(models = ?models, "Starting checksum sweep");
+        let mut stats = ChecksumSweepStats::default();
+
+        for model in &models {
+            let runs = self.state.catalog.get_model_runs_with_counts(model).await?;
+            for (reference_time, _) in runs {
+                let entries = self
+                    .state
+                    .catalog
+                    .get_run_entries(model, reference_time)
+                    .await?;
+                for entry in &entries {
+                    self.verify_entry(entry, &mut stats);
+                }
+            }
+        }
+
+        info!(
+            datasets_checked = stats.datasets_checked,
+            datasets_skipped = stats.datasets_skipped,
+            chunks_checked = stats.chunks_checked,
+            corrupt_datasets = stats.corrupt_datasets.len(),
+            errors = stats.errors.len(),
+            "Checksum sweep complete"
+        );
+        Ok(stats)
+    }
+
+    /// Verify one dataset and add the outcome to `stats`.
+    fn verify_entry(&self, entry: &CatalogEntry, stats: &mut ChecksumSweepStats) {
+        let zarr_meta = match entry.zarr_metadata.as_ref().map(ZarrMetadata::from_json) {
+            Some(Ok(meta)) if meta.checksum.is_some() => meta,
+            Some(Err(e)) => {
+                stats.errors.push(format!(
+                    "{}: invalid zarr_metadata: {}",
+                    entry.storage_path, e
+                ));
+                return;
+            }
+            _ => {
+                stats.datasets_skipped += 1;
+                return;
+            }
+        };
+
+        match self.verify_chunks(&entry.storage_path, zarr_meta.into()) {
+            Ok(verification) => {
+                stats.datasets_checked += 1;
+                stats.chunks_checked += verification.chunks_checked as u64;
+                if !verification.is_intact() {
+                    error!(
+                        storage_path = %entry.storage_path,
+                        corrupt_chunks = ?verification.corrupt_chunks,
+                        "Dataset has chunks that fail their checksum"
+                    );
+                    stats.corrupt_datasets.push(CorruptDataset {
+                        model: entry.model.clone(),
+                        parameter: entry.parameter.clone(),
+                        level: entry.level.clone(),
+                        reference_time: entry.reference_time,
+                        forecast_hour: entry.forecast_hour,
+                        storage_path: entry.storage_path.clone(),
+                        corrupt_chunks: verification.corrupt_chunks,
+                    });
+                }
+            }
+            Err(e) => {
+                warn!(storage_path = %entry.storage_path, error = %e, "Failed to verify dataset");
+                stats.errors.push(format!("{}: {}", entry.storage_path, e));
+            }
+        }
+    }
+
+    fn verify_chunks(
+        &self,
+        storage_path: &str,
+        metadata: GridMetadata,
+    ) -> Result<ChunkVerification> {
+        let factory = &self.state.grid_processor_factory;
+        let zarr_path = if storage_path.starts_with('/') {
+            storage_path.to_string()
+        } else {
+            format!("/{}", storage_path)
+        };
+
+        let store = create_minio_storage(factory.minio_config())?;
+        let processor = ZarrGridProcessor::with_metadata(
+            store,
+            &zarr_path,
+            metadata,
+            factory.chunk_cache(),
+            factory.config().clone(),
+        )?;
+        Ok(processor.verify_chunks()?)
+    }
+}
@@ -4,6 +4,9 @@
 //! This provides a single source of truth for which layers are exposed via WMS/WMTS,
 //! including their style file mappings, units, and level definitions.
 
+use grid_processor::{
+    EnsembleStatistic, LevelRange, TemporalReducer, ThresholdComparison, VerticalReducer,
+};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
@@ -181,6 +184,247 @@ pub struct LevelConfig {
     pub default: bool,
 }
 
+/// Style selection policy for a layer.
+///
+/// Controls which style is used when a client omits `STYLES`/`STYLE` (or sends
+/// it empty), optionally pins the layer to a single style, and maps renamed
+/// style names to their replacements so saved client URLs keep working.
+#[derive(Debug, Clone, Default)]
+pub struct StylePolicy {
+    /// Style used when the request does not name one (falls back to "default")
+    pub default_style: Option<String>,
+    /// Style always used regardless of what the client requests
+    pub forced_style: Option<String>,
+    /// Deprecated style names mapped to the style that replaced them
+    pub aliases: HashMap<String, String>,
+}
+
+/// Result of resolving a requested style name against a layer's [`StylePolicy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedStyle {
+    /// Style name to render with
+    pub name: String,
+    /// Deprecated alias the client used, if the name was rewritten from one
+    pub deprecated_alias: Option<String>,
+}
+
+impl StylePolicy {
+    /// Resolve a requested style name to the style that should be rendered.
+    ///
+    /// Resolution order:
+    /// 1. `forced_style`, if configured
+    /// 2. `default_style` (or "default") when nothing/"default" is requested
+    /// 3. Alias lookup for renamed styles
+    /// 4. The requested name unchanged
+    pub fn resolve(&self, requested: Option<&str>) -> ResolvedStyle {
+        if let Some(forced) = &self.forced_style {
+            return ResolvedStyle {
+                name: forced.clone(),
+                deprecated_alias: None,
+            };
+        }
+
+        let requested = requested.map(str::trim).unwrap_or("");
+        if requested.is_empty() || requested == "default" {
+            return ResolvedStyle {
+                name: self
+                    .default_style
+                    .clone()
+                    .unwrap_or_else(|| "default".to_string()),
+                deprecated_alias: None,
+            };
+        }
+
+        if let Some(target) = self.aliases.get(requested) {
+            return ResolvedStyle {
+                name: target.clone(),
+                deprecated_alias: Some(requested.to_string()),
+            };
+        }
+
+        ResolvedStyle {
+            name: requested.to_string(),
+            deprecated_alias: None,
+        }
+    }
+}
+
+/// Maximum datasets read for one temporal composite, unless configured.
+pub const DEFAULT_TEMPORAL_MAX_DATASETS: usize = 180;
+
+/// Temporal composite layer: a source parameter reduced over a time window
+/// (e.g., max reflectivity over the last hour, 24 h precipitation total).
+///
+/// The window is exposed as the `WINDOW` dimension and ends at the requested
+/// time (TIME for observations, RUN + FORECAST for forecast models).
+#[derive(Debug, Clone)]
+pub struct TemporalConfig {
+    /// Parameter whose datasets are combined (e.g., "REFL")
+    pub source: String,
+    /// How values in the window are combined
+    pub reducer: TemporalReducer,
+    /// Window lengths offered in the WINDOW dimension (ISO 8601 durations)
+    pub windows: Vec<String>,
+    /// Window used when the request doesn't name one
+    pub default_window: String,
+    /// Most datasets read for a single composite
+    pub max_datasets: usize,
+}
+
+impl TemporalConfig {
+    /// Resolve a requested WINDOW value to one of the configured windows.
+    ///
+    /// Returns the window name and its duration. Empty requests use the
+    /// default window; unknown windows are an error.
+    pub fn resolve_window(
+        &self,
+        requested: Option<&str>,
+    ) -> Result<(String, chrono::Duration), String> {
+        let name = match requested.map(str::trim).filter(|w| !w.is_empty()) {
+            Some(window) => self
+                .windows
+                .iter()
+                .find(|w| w.eq_ignore_ascii_case(window))
+                .ok_or_else(|| {
+                    format!(
+                        "WINDOW '{}' is not available. Available windows: {}",
+                        window,
+                        self.windows.join(", ")
+                    )
+                })?,
+            None => &self.default_window,
+        };
+
+        let duration = parse_iso8601_duration(name)
+            .ok_or_else(|| format!("Invalid WINDOW duration '{}'", name))?;
+        Ok((name.clone(), duration))
+    }
+}
+
+/// Maximum members read for one ensemble product, unless configured.
+pub const DEFAULT_ENSEMBLE_MAX_MEMBERS: usize = 64;
+
+/// Ensemble layer: a statistic over the members of a source parameter at
+/// one run and forecast hour (e.g., ensemble mean, spread, or the probability
+/// of 2 m temperature below freezing).
+#[derive(Debug, Clone)]
+pub struct EnsembleConfig {
+    /// Parameter whose members are combined (e.g., "TMP")
+    pub source: String,
+    /// Statistic computed over the members
+    pub statistic: EnsembleStatistic,
+    /// Most members read for a single product
+    pub max_members: usize,
+}
+
+/// Maximum levels read for one column composite, unless configured.
+pub const DEFAULT_VERTICAL_MAX_LEVELS: usize = 50;
+
+/// Column composite layer: a source parameter reduced over a range of levels
+/// at one run and forecast hour (e.g., max temperature between 1000 and
+/// 500 mb).
+///
+/// The range is requested as `ELEVATION=low/high`; every level of the source
+/// inside it is read.
+#[derive(Debug, Clone)]
+pub struct VerticalConfig {
+    /// Parameter whose levels are combined (e.g., "TMP")
+    pub source: String,
+    /// How the levels in the range are combined
+    pub reducer: VerticalReducer,
+    /// Range used when the request doesn't give one
+    pub default_range: LevelRange,
+    /// Most levels read for a single composite
+    pub max_levels: usize,
+}
+
+impl VerticalConfig {
+    /// Resolve a requested ELEVATION value to a level range.
+    ///
+    /// Empty requests use the default range. A single level, or a range of a
+    /// different kind of level than the default (heights for an isobaric
+    /// layer), is an error.
+    pub fn resolve_range(&self, requested: Option<&str>) -> Result<LevelRange, String> {
+        let Some(elevation) = requested.map(str::trim).filter(|e| !e.is_empty()) else {
+            return Ok(self.default_range);
+        };
+        if !LevelRange::is_range(elevation) {
+            return Err(format!(
+                "ELEVATION '{}' is not a range. Column layers take ELEVATION=low/high (default {})",
+                elevation, self.default_range
+            ));
+        }
+
+        let range = elevation.parse::<LevelRange>().map_err(|e| e.to_string())?;
+        if range.kind != self.default_range.kind {
+            return Err(format!(
+                "ELEVATION '{}' is not the same kind of level as {}",
+                elevation, self.default_range
+            ));
+        }
+        Ok(range)
+    }
+}
+
+/// Parse an ISO 8601 duration with day and time parts (e.g., "PT1H",
+/// "PT30M", "P1D", "P1DT12H"). Years, months and weeks are not supported.
+pub fn parse_iso8601_duration(s: &str) -> Option<chrono::Duration> {
+    let rest = s.trim().to_ascii_uppercase();
+    let rest = rest.strip_prefix('P')?;
+    let (date, time) = match rest.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+
+    let mut seconds: i64 = 0;
+    let mut parse_part = |part: &str, units: &[(char, i64)]| -> Option<()> {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+            } else {
+                let (_, scale) = units.iter().find(|(unit, _)| *unit == c)?;
+                seconds += number.parse::<i64>().ok()? * scale;
+                number.clear();
+            }
+        }
+        number.is_empty().then_some(())
+    };
+
+    parse_part(date, &[('D', 86400)])?;
+    if let Some(time) = time {
+        if time.is_empty() {
+            return None;
+        }
+        parse_part(time, &[('H', 3600), ('M', 60), ('S', 1)])?;
+    }
+
+    (seconds > 0).then(|| chrono::Duration::seconds(seconds))
+}
+
+/// GetMap limits for a layer, applied on top of the service-wide
+/// [`RequestLimits`](crate::request_limits::RequestLimits).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LayerLimits {
+    /// Most pixels (WIDTH × HEIGHT) in one image
+    #[serde(default)]
+    pub max_pixels: Option<u64>,
+    /// Coarsest zoom level served (see [`MapRequest::zoom`](crate::request_limits::MapRequest::zoom))
+    #[serde(default)]
+    pub min_zoom: Option<f64>,
+    /// Largest extents allowed when zoomed out
+    #[serde(default)]
+    pub extent_rules: Vec<ExtentRule>,
+}
+
+/// Requests coarser than `below_zoom` may span at most `max_span_degrees`
+/// of longitude or latitude.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtentRule {
+    pub below_zoom: f64,
+    pub max_span_degrees: f64,
+}
+
 /// Layer configuration loaded from YAML
 #[derive(Debug, Clone)]
 pub struct LayerConfig {
@@ -204,6 +448,16 @@ pub struct LayerConfig {
     pub requires: Vec<String>,
     /// Whether this is an accumulation parameter
     pub accumulation: bool,
+    /// Default/forced style and style aliases
+    pub style_policy: StylePolicy,
+    /// Temporal composite settings, for layers reducing a parameter over a time window
+    pub temporal: Option<TemporalConfig>,
+    /// Ensemble settings, for layers reducing the members of a parameter
+    pub ensemble: Option<EnsembleConfig>,
+    /// Column settings, for layers reducing a parameter over a level range
+    pub vertical: Option<VerticalConfig>,
+    /// GetMap size and extent limits
+    pub limits: LayerLimits,
 }
 
 impl LayerConfig {
@@ -220,6 +474,50 @@ impl LayerConfig {
     pub fn level_values(&self) -> Vec<String> {
         self.levels.iter().map(|l| l.value.clone()).collect()
     }
+
+    /// Check if this is a multi-band image composite (e.g., GOES true color)
+    /// rendered from its `requires` bands through an `rgb_composite` style.
+    pub fn is_band_composite(&self) -> bool {
+        self.composite
+            && !self.requires.is_empty()
+            && self.parameter != "WIND_BARBS"
+            && self.vector_components().is_none()
+    }
+
+    /// U and V component parameters of a vector composite, e.g.
+    /// `("UGRD", "VGRD")` for wind barbs.
+    pub fn vector_components(&self) -> Option<(&str, &str)> {
+        match self.requires.as_slice() {
+            [u, v]
+                if self.composite
+                    && u.starts_with('U')
+                    && v.starts_with('V')
+                    && u[1..] == v[1..] =>
+            {
+                Some((u.as_str(), v.as_str()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parameter whose catalog datasets back this layer: the temporal
+    /// composite's, ensemble product's or column composite's source, or the
+    /// layer's own parameter.
+    pub fn source_parameter(&self) -> &str {
+        self.temporal
+            .as_ref()
+            .map(|t| t.source.as_str())
+            .or_else(|| self.ensemble.as_ref().map(|e| e.source.as_str()))
+            .or_else(|| self.vertical.as_ref().map(|v| v.source.as_str()))
+            .unwrap_or(&self.parameter)
+    }
+
+    /// Whether the layer is reduced from several datasets when rendered
+    /// (temporal composites, ensemble products and column composites), and
+    /// so can't be queried with GetFeatureInfo.
+    pub fn is_reduced(&self) -> bool {
+        self.temporal.is_some() || self.ensemble.is_some() || self.vertical.is_some()
+    }
 }
 
 /// Model layer configuration - contains all layers for a weather model
@@ -308,6 +606,138 @@ struct YamlLayer {
     requires: Vec<String>,
     #[serde(default)]
     accumulation: bool,
+    #[serde(default)]
+    default_style: Option<String>,
+    #[serde(default)]
+    forced_style: Option<String>,
+    #[serde(default)]
+    style_aliases: HashMap<String, String>,
+    #[serde(default)]
+    temporal: Option<YamlTemporal>,
+    #[serde(default)]
+    ensemble: Option<YamlEnsemble>,
+    #[serde(default)]
+    vertical: Option<YamlVertical>,
+    #[serde(default)]
+    limits: LayerLimits,
+}
+
+#[derive(Debug, Deserialize)]
+struct YamlTemporal {
+    source: String,
+    reducer: TemporalReducer,
+    windows: Vec<String>,
+    #[serde(default)]
+    default_window: Option<String>,
+    #[serde(default)]
+    max_datasets: Option<usize>,
+}
+
+impl YamlTemporal {
+    /// Validate windows and fill in defaults.
+    fn into_config(self) -> Result<TemporalConfig, String> {
+        if let Some(invalid) = self
+            .windows
+            .iter()
+            .find(|w| parse_iso8601_duration(w).is_none())
+        {
+            return Err(format!("invalid window duration '{}'", invalid));
+        }
+
+        let default_window = match self.default_window {
+            Some(window) if self.windows.contains(&window) => window,
+            Some(window) => {
+                return Err(format!("default_window '{}' is not in windows", window));
+            }
+            None => self
+                .windows
+                .first()
+                .cloned()
+                .ok_or_else(|| "at least one window is required".to_string())?,
+        };
+
+        Ok(TemporalConfig {
+            source: self.source,
+            reducer: self.reducer,
+            windows: self.windows,
+            default_window,
+            max_datasets: self.max_datasets.unwrap_or(DEFAULT_TEMPORAL_MAX_DATASETS),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum YamlEnsembleStatistic {
+    Mean,
+    Spread,
+    Probability,
+}
+
+#[derive(Debug, Deserialize)]
+struct YamlEnsemble {
+    source: String,
+    statistic: YamlEnsembleStatistic,
+    #[serde(default)]
+    threshold: Option<f32>,
+    #[serde(default)]
+    comparison: Option<ThresholdComparison>,
+    #[serde(default)]
+    max_members: Option<usize>,
+}
+
+impl YamlEnsemble {
+    /// Check the threshold settings and fill in defaults.
+    fn into_config(self) -> Result<EnsembleConfig, String> {
+        let statistic = match (self.statistic, self.threshold) {
+            (YamlEnsembleStatistic::Probability, Some(threshold)) => {
+                EnsembleStatistic::Probability {
+                    threshold,
+                    comparison: self.comparison.unwrap_or(ThresholdComparison::Above),
+                }
+            }
+            (YamlEnsembleStatistic::Probability, None) => {
+                return Err("probability needs a threshold".to_string());
+            }
+            (_, Some(_)) => {
+                return Err("threshold only applies to probability".to_string());
+            }
+            (YamlEnsembleStatistic::Mean, None) => EnsembleStatistic::Mean,
+            (YamlEnsembleStatistic::Spread, None) => EnsembleStatistic::Spread,
+        };
+
+        Ok(EnsembleConfig {
+            source: self.source,
+            statistic,
+            max_members: self.max_members.unwrap_or(DEFAULT_ENSEMBLE_MAX_MEMBERS),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct YamlVertical {
+    source: String,
+    reducer: VerticalReducer,
+    default_range: String,
+    #[serde(default)]
+    max_levels: Option<usize>,
+}
+
+impl YamlVertical {
+    /// Parse the default range and fill in defaults.
+    fn into_config(self) -> Result<VerticalConfig, String> {
+        let default_range = self
+            .default_range
+            .parse::<LevelRange>()
+            .map_err(|e| e.to_string())?;
+
+        Ok(VerticalConfig {
+            source: self.source,
+            reducer: self.reducer,
+            default_range,
+            max_levels: self.max_levels.unwrap_or(DEFAULT_VERTICAL_MAX_LEVELS),
+        })
+    }
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -408,6 +838,13 @@ impl LayerConfigRegistry {
     }
 
     /// Load a single layer config file
+    /// Check that layer config YAML parses, without loading it.
+    pub fn check_layer_yaml(contents: &str) -> Result<(), String> {
+        serde_yaml::from_str::<YamlLayerFile>(contents)
+            .map(|_| ())
+            .map_err(|e| format!("Invalid layer config: {}", e))
+    }
+
     fn load_layer_file<P: AsRef<Path>>(path: P) -> Option<ModelLayerConfig> {
         let contents = match fs::read_to_string(path.as_ref()) {
             Ok(c) => c,
@@ -428,34 +865,81 @@ impl LayerConfigRegistry {
         let layers = yaml
             .layers
             .into_iter()
-            .map(|l| LayerConfig {
-                id: l.id,
-                parameter: l.parameter,
-                title: l.title,
-                abstract_text: l.abstract_text,
-                style_file: l.style_file,
-                units: l
-                    .units
-                    .map(|u| UnitConfig {
-                        native: u.native.unwrap_or_default(),
-                        display: u.display.unwrap_or_default(),
-                        conversion: u
-                            .conversion
-                            .map(|c| UnitConversion::from_str(&c))
-                            .unwrap_or(UnitConversion::None),
-                    })
-                    .unwrap_or_default(),
-                levels: l
-                    .levels
-                    .into_iter()
-                    .map(|lv| LevelConfig {
-                        value: lv.value,
-                        default: lv.default,
-                    })
-                    .collect(),
-                composite: l.composite,
-                requires: l.requires,
-                accumulation: l.accumulation,
+            .filter_map(|l| {
+                let temporal = match l.temporal.map(YamlTemporal::into_config).transpose() {
+                    Ok(temporal) => temporal,
+                    Err(e) => {
+                        warn!(
+                            layer = %l.id,
+                            error = %e,
+                            path = ?path.as_ref(),
+                            "Skipping layer with invalid temporal config"
+                        );
+                        return None;
+                    }
+                };
+                let ensemble = match l.ensemble.map(YamlEnsemble::into_config).transpose() {
+                    Ok(ensemble) => ensemble,
+                    Err(e) => {
+                        warn!(
+                            layer = %l.id,
+                            error = %e,
+                            path = ?path.as_ref(),
+                            "Skipping layer with invalid ensemble config"
+                        );
+                        return None;
+                    }
+                };
+                let vertical = match l.vertical.map(YamlVertical::into_config).transpose() {
+                    Ok(vertical) => vertical,
+                    Err(e) => {
+                        warn!(
+                            layer = %l.id,
+                            error = %e,
+                            path = ?path.as_ref(),
+                            "Skipping layer with invalid vertical config"
+                        );
+                        return None;
+                    }
+                };
+                Some(LayerConfig {
+                    id: l.id,
+                    parameter: l.parameter,
+                    title: l.title,
+                    abstract_text: l.abstract_text,
+                    style_file: l.style_file,
+                    units: l
+                        .units
+                        .map(|u| UnitConfig {
+                            native: u.native.unwrap_or_default(),
+                            display: u.display.unwrap_or_default(),
+                            conversion: u
+                                .conversion
+                                .map(|c| UnitConversion::from_str(&c))
+                                .unwrap_or(UnitConversion::None),
+                        })
+                        .unwrap_or_default(),
+                    levels: l
+                        .levels
+                        .into_iter()
+                        .map(|lv| LevelConfig {
+                            value: lv.value,
+                            default: lv.default,
+                        })
+                        .collect(),
+                    composite: l.composite,
+                    requires: l.requires,
+                    accumulation: l.accumulation,
+                    style_policy: StylePolicy {
+                        default_style: l.default_style,
+                        forced_style: l.forced_style,
+                        aliases: l.style_aliases,
+                    },
+                    temporal,
+                    ensemble,
+                    vertical,
+                    limits: l.limits,
+                })
             })
             .collect();
 
@@ -508,11 +992,32 @@ impl LayerConfigRegistry {
             .and_then(|m| m.get_layer_by_parameter(parameter))
     }
 
+    /// Style config directory path
+    pub fn style_dir(&self) -> &str {
+        &self.style_dir
+    }
+
     /// Get the full path to a style file for a layer
     pub fn get_style_path(&self, layer: &LayerConfig) -> String {
         format!("{}/{}", self.style_dir, layer.style_file)
     }
 
+    /// Resolve the style to render for a model/parameter combination.
+    ///
+    /// Applies the layer's [`StylePolicy`]; layers without a config resolve
+    /// with an empty policy (empty/"default" requests map to "default").
+    pub fn resolve_style(
+        &self,
+        model: &str,
+        parameter: &str,
+        requested: Option<&str>,
+    ) -> ResolvedStyle {
+        match self.get_layer_by_param(model, parameter) {
+            Some(layer) => layer.style_policy.resolve(requested),
+            None => StylePolicy::default().resolve(requested),
+        }
+    }
+
     /// Get style file path for a model/parameter combination.
     /// Returns None if no layer config is found.
     pub fn try_get_style_file(&self, model: &str, parameter: &str) -> Option<String> {
@@ -586,6 +1091,59 @@ impl LayerConfigRegistry {
             })
     }
 
+    /// Find layers whose title or description contains `text` (case-insensitive).
+    /// Returns (model, parameter) pairs, parameters uppercased as in the catalog.
+    pub fn search_layers(&self, text: &str) -> Vec<(String, String)> {
+        let text = text.to_lowercase();
+        let mut matches = Vec::new();
+
+        for (model, config) in &self.configs {
+            for layer in &config.layers {
+                let described = layer.title.to_lowercase().contains(&text)
+                    || layer
+                        .abstract_text
+                        .as_deref()
+                        .is_some_and(|a| a.to_lowercase().contains(&text));
+                if described {
+                    matches.push((model.clone(), layer.parameter.to_uppercase()));
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Copy of the registry keeping only the layers `visible` accepts, and
+    /// only models with at least one of them (e.g. a tenant's layers).
+    pub fn scoped(&self, visible: impl Fn(&LayerConfig) -> bool) -> Self {
+        let configs = self
+            .configs
+            .iter()
+            .filter_map(|(model, config)| {
+                let layers: Vec<LayerConfig> = config
+                    .layers
+                    .iter()
+                    .filter(|l| visible(l))
+                    .cloned()
+                    .collect();
+                (!layers.is_empty()).then(|| {
+                    (
+                        model.clone(),
+                        ModelLayerConfig {
+                            layers,
+                            ..config.clone()
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        Self {
+            configs,
+            style_dir: self.style_dir.clone(),
+        }
+    }
+
     /// Validate that all parameters in the catalog have layer configs.
     /// Returns a list of (model, parameter) tuples that are missing configs.
     pub fn find_missing_configs(
@@ -705,11 +1263,336 @@ mod tests {
             composite: false,
             requires: vec![],
             accumulation: false,
+            style_policy: StylePolicy::default(),
+            temporal: None,
+            ensemble: None,
+            vertical: None,
+            limits: LayerLimits::default(),
         };
 
         assert_eq!(layer.default_level(), Some("2 m above ground"));
     }
 
+    #[test]
+    fn test_vector_components() {
+        let composite = |parameter: &str, requires: &[&str]| LayerConfig {
+            id: format!("gfs_{}", parameter),
+            parameter: parameter.to_string(),
+            title: parameter.to_string(),
+            abstract_text: None,
+            style_file: String::new(),
+            units: UnitConfig::default(),
+            levels: vec![],
+            composite: true,
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+            accumulation: false,
+            style_policy: StylePolicy::default(),
+            temporal: None,
+            ensemble: None,
+            vertical: None,
+            limits: LayerLimits::default(),
+        };
+
+        let barbs = composite("WIND_BARBS", &["UGRD", "VGRD"]);
+        assert_eq!(barbs.vector_components(), Some(("UGRD", "VGRD")));
+        assert!(!barbs.is_band_composite());
+
+        let currents = composite("CURRENTS", &["UOGRD", "VOGRD"]);
+        assert_eq!(currents.vector_components(), Some(("UOGRD", "VOGRD")));
+
+        let true_color = composite("TRUE_COLOR", &["CMI_C01", "CMI_C02", "CMI_C03"]);
+        assert_eq!(true_color.vector_components(), None);
+        assert!(true_color.is_band_composite());
+        assert_eq!(composite("X", &["UGRD", "VOGRD"]).vector_components(), None);
+    }
+
+    #[test]
+    fn test_style_policy_default_style() {
+        let policy = StylePolicy {
+            default_style: Some("gradient".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(policy.resolve(None).name, "gradient");
+        assert_eq!(policy.resolve(Some("")).name, "gradient");
+        assert_eq!(policy.resolve(Some("default")).name, "gradient");
+        assert_eq!(policy.resolve(Some("isolines")).name, "isolines");
+
+        // Without a configured default, the hard-coded fallback is kept
+        assert_eq!(StylePolicy::default().resolve(Some("")).name, "default");
+    }
+
+    #[test]
+    fn test_style_policy_forced_style() {
+        let policy = StylePolicy {
+            default_style: Some("gradient".to_string()),
+            forced_style: Some("enhanced".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(policy.resolve(None).name, "enhanced");
+        assert_eq!(policy.resolve(Some("isolines")).name, "enhanced");
+    }
+
+    #[test]
+    fn test_style_policy_aliases() {
+        let mut aliases = HashMap::new();
+        aliases.insert("temp_classic".to_string(), "gradient".to_string());
+        let policy = StylePolicy {
+            aliases,
+            ..Default::default()
+        };
+
+        let resolved = policy.resolve(Some("temp_classic"));
+        assert_eq!(resolved.name, "gradient");
+        assert_eq!(resolved.deprecated_alias.as_deref(), Some("temp_classic"));
+
+        let resolved = policy.resolve(Some("gradient"));
+        assert_eq!(resolved.name, "gradient");
+        assert!(resolved.deprecated_alias.is_none());
+    }
+
+    #[test]
+    fn test_yaml_style_policy_parsing() {
+        let yaml = r#"
+model: gfs
+display_name: "GFS"
+layers:
+  - id: gfs_TMP
+    parameter: TMP
+    title: "Temperature"
+    style_file: temperature.json
+    default_style: gradient
+    style_aliases:
+      temp_classic: gradient
+"#;
+        let parsed: YamlLayerFile = serde_yaml::from_str(yaml).unwrap();
+        let layer = &parsed.layers[0];
+        assert_eq!(layer.default_style.as_deref(), Some("gradient"));
+        assert!(layer.forced_style.is_none());
+        assert_eq!(
+            layer.style_aliases.get("temp_classic").map(String::as_str),
+            Some("gradient")
+        );
+    }
+
+    #[test]
+    fn test_parse_iso8601_duration() {
+        let hours = |h| Some(chrono::Duration::hours(h));
+        assert_eq!(parse_iso8601_duration("PT1H"), hours(1));
+        assert_eq!(parse_iso8601_duration("P1D"), hours(24));
+        assert_eq!(parse_iso8601_duration("p1dt12h"), hours(36));
+        assert_eq!(
+            parse_iso8601_duration("PT1H30M"),
+            Some(chrono::Duration::minutes(90))
+        );
+        assert!(parse_iso8601_duration("1H").is_none());
+        assert!(parse_iso8601_duration("PT").is_none());
+        assert!(parse_iso8601_duration("PT0H").is_none());
+        assert!(parse_iso8601_duration("P1M").is_none());
+    }
+
+    #[test]
+    fn test_yaml_limits_parsing() {
+        let yaml = r#"
+model: mrms
+display_name: "MRMS"
+layers:
+  - id: mrms_REFL
+    parameter: REFL
+    title: "Reflectivity"
+    style_file: reflectivity.json
+    limits:
+      min_zoom: 3
+      extent_rules:
+        - below_zoom: 6
+          max_span_degrees: 40
+  - id: mrms_RAIN
+    parameter: RAIN
+    title: "Rain"
+    style_file: precipitation.json
+"#;
+        let parsed: YamlLayerFile = serde_yaml::from_str(yaml).unwrap();
+        let limits = &parsed.layers[0].limits;
+        assert_eq!(limits.min_zoom, Some(3.0));
+        assert!(limits.max_pixels.is_none());
+        assert_eq!(limits.extent_rules.len(), 1);
+        assert_eq!(limits.extent_rules[0].max_span_degrees, 40.0);
+
+        let unlimited = &parsed.layers[1].limits;
+        assert!(unlimited.min_zoom.is_none() && unlimited.extent_rules.is_empty());
+    }
+
+    #[test]
+    fn test_yaml_temporal_parsing() {
+        let yaml = r#"
+model: mrms
+display_name: "MRMS"
+layers:
+  - id: mrms_REFL_MAX
+    parameter: REFL_MAX
+    title: "Max Reflectivity"
+    style_file: reflectivity.json
+    temporal:
+      source: REFL
+      reducer: max
+      windows: [PT30M, PT1H, PT3H]
+      default_window: PT1H
+"#;
+        let parsed: YamlLayerFile = serde_yaml::from_str(yaml).unwrap();
+        let layer = parsed.layers.into_iter().next().unwrap();
+        let temporal = layer.temporal.unwrap().into_config().unwrap();
+        assert_eq!(temporal.source, "REFL");
+        assert_eq!(temporal.reducer, TemporalReducer::Max);
+        assert_eq!(temporal.max_datasets, DEFAULT_TEMPORAL_MAX_DATASETS);
+
+        let (name, duration) = temporal.resolve_window(None).unwrap();
+        assert_eq!(name, "PT1H");
+        assert_eq!(duration, chrono::Duration::hours(1));
+        let (name, _) = temporal.resolve_window(Some("pt3h")).unwrap();
+        assert_eq!(name, "PT3H");
+        assert!(temporal.resolve_window(Some("P1D")).is_err());
+
+        let invalid = YamlTemporal {
+            source: "REFL".to_string(),
+            reducer: TemporalReducer::Max,
+            windows: vec!["PT1H".to_string()],
+            default_window: Some("PT3H".to_string()),
+            max_datasets: None,
+        };
+        assert!(invalid.into_config().is_err());
+    }
+
+    #[test]
+    fn test_yaml_ensemble_parsing() {
+        let yaml = r#"
+model: gefs
+display_name: "GEFS"
+layers:
+  - id: gefs_TMP_PROB_FREEZE
+    parameter: TMP_PROB_FREEZE
+    title: "Probability of Freezing"
+    style_file: probability.json
+    ensemble:
+      source: TMP
+      statistic: probability
+      threshold: 273.15
+      comparison: below
+  - id: gefs_HGT_SPREAD
+    parameter: HGT_SPREAD
+    title: "500 mb Height Spread"
+    style_file: geopotential.json
+    ensemble:
+      source: HGT
+      statistic: spread
+      max_members: 21
+"#;
+        let parsed: YamlLayerFile = serde_yaml::from_str(yaml).unwrap();
+        let mut layers = parsed.layers.into_iter();
+
+        let probability = layers
+            .next()
+            .unwrap()
+            .ensemble
+            .unwrap()
+            .into_config()
+            .unwrap();
+        assert_eq!(probability.source, "TMP");
+        assert_eq!(
+            probability.statistic,
+            EnsembleStatistic::Probability {
+                threshold: 273.15,
+                comparison: ThresholdComparison::Below,
+            }
+        );
+        assert_eq!(probability.max_members, DEFAULT_ENSEMBLE_MAX_MEMBERS);
+
+        let spread = layers
+            .next()
+            .unwrap()
+            .ensemble
+            .unwrap()
+            .into_config()
+            .unwrap();
+        assert_eq!(spread.statistic, EnsembleStatistic::Spread);
+        assert_eq!(spread.max_members, 21);
+
+        let ensemble = |statistic, threshold| YamlEnsemble {
+            source: "TMP".to_string(),
+            statistic,
+            threshold,
+            comparison: None,
+            max_members: None,
+        };
+        assert!(ensemble(YamlEnsembleStatistic::Probability, None)
+            .into_config()
+            .is_err());
+        assert!(ensemble(YamlEnsembleStatistic::Mean, Some(0.0))
+            .into_config()
+            .is_err());
+        // Exceeding means above unless configured otherwise
+        let above = ensemble(YamlEnsembleStatistic::Probability, Some(0.0))
+            .into_config()
+            .unwrap();
+        assert!(matches!(
+            above.statistic,
+            EnsembleStatistic::Probability {
+                comparison: ThresholdComparison::Above,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_yaml_vertical_parsing() {
+        let yaml = r#"
+model: gfs
+display_name: "GFS"
+layers:
+  - id: gfs_TMP_COLUMN_MAX
+    parameter: TMP_COLUMN_MAX
+    title: "Column Max Temperature"
+    style_file: temperature.json
+    vertical:
+      source: TMP
+      reducer: max
+      default_range: 1000 mb/500 mb
+"#;
+        let parsed: YamlLayerFile = serde_yaml::from_str(yaml).unwrap();
+        let vertical = parsed
+            .layers
+            .into_iter()
+            .next()
+            .unwrap()
+            .vertical
+            .unwrap()
+            .into_config()
+            .unwrap();
+        assert_eq!(vertical.source, "TMP");
+        assert_eq!(vertical.reducer, VerticalReducer::Max);
+        assert_eq!(vertical.max_levels, DEFAULT_VERTICAL_MAX_LEVELS);
+
+        assert_eq!(
+            vertical.resolve_range(None).unwrap(),
+            vertical.default_range
+        );
+        let range = vertical.resolve_range(Some("850/700 mb")).unwrap();
+        assert_eq!((range.min, range.max), (700.0, 850.0));
+        // A single level, or heights for an isobaric layer, aren't columns
+        assert!(vertical.resolve_range(Some("500 mb")).is_err());
+        assert!(vertical
+            .resolve_range(Some("0 m above ground/1000 m above ground"))
+            .is_err());
+
+        let invalid = YamlVertical {
+            source: "TMP".to_string(),
+            reducer: VerticalReducer::Min,
+            default_range: "500 mb".to_string(),
+            max_levels: None,
+        };
+        assert!(invalid.into_config().is_err());
+    }
+
     #[test]
     fn test_empty_registry() {
         let registry = LayerConfigRegistry::new();
@@ -717,6 +1600,99 @@ mod tests {
         assert!(registry.get_model("gfs").is_none());
     }
 
+    #[test]
+    fn test_search_layers_by_description() {
+        let layer = |parameter: &str, title: &str, abstract_text: Option<&str>| LayerConfig {
+            id: format!("gfs_{}", parameter),
+            parameter: parameter.to_string(),
+            title: title.to_string(),
+            abstract_text: abstract_text.map(String::from),
+            style_file: "default.json".to_string(),
+            units: UnitConfig::default(),
+            levels: vec![],
+            composite: false,
+            requires: vec![],
+            accumulation: false,
+            style_policy: StylePolicy::default(),
+            temporal: None,
+            ensemble: None,
+            vertical: None,
+            limits: LayerLimits::default(),
+        };
+
+        let mut registry = LayerConfigRegistry::new();
+        registry.configs.insert(
+            "gfs".to_string(),
+            ModelLayerConfig {
+                model: "gfs".to_string(),
+                display_name: "GFS".to_string(),
+                default_bbox: None,
+                layers: vec![
+                    layer("TMP", "Temperature", None),
+                    layer("DPT", "Dew Point", Some("Dew point temperature")),
+                    layer("Prmsl", "Pressure", Some("Mean sea level pressure")),
+                ],
+            },
+        );
+
+        let mut matches = registry.search_layers("TEMPERATURE");
+        matches.sort();
+        assert_eq!(
+            matches,
+            vec![
+                ("gfs".to_string(), "DPT".to_string()),
+                ("gfs".to_string(), "TMP".to_string()),
+            ]
+        );
+        assert_eq!(
+            registry.search_layers("sea level"),
+            vec![("gfs".to_string(), "PRMSL".to_string())]
+        );
+        assert!(registry.search_layers("humidity").is_empty());
+    }
+
+    #[test]
+    fn test_scoped_registry() {
+        let layer = |model: &str, parameter: &str| LayerConfig {
+            id: format!("{}_{}", model, parameter),
+            parameter: parameter.to_string(),
+            title: parameter.to_string(),
+            abstract_text: None,
+            style_file: "default.json".to_string(),
+            units: UnitConfig::default(),
+            levels: vec![],
+            composite: false,
+            requires: vec![],
+            accumulation: false,
+            style_policy: StylePolicy::default(),
+            temporal: None,
+            ensemble: None,
+            vertical: None,
+            limits: LayerLimits::default(),
+        };
+        let model = |model: &str, parameters: &[&str]| ModelLayerConfig {
+            model: model.to_string(),
+            display_name: model.to_uppercase(),
+            default_bbox: None,
+            layers: parameters.iter().map(|p| layer(model, p)).collect(),
+        };
+
+        let mut registry = LayerConfigRegistry::new();
+        registry
+            .configs
+            .insert("gfs".to_string(), model("gfs", &["TMP", "PRMSL"]));
+        registry
+            .configs
+            .insert("hrrr".to_string(), model("hrrr", &["REFC"]));
+
+        let scoped = registry.scoped(|l| l.id == "gfs_TMP");
+        assert_eq!(scoped.total_layers(), 1);
+        assert!(scoped.get_layer("gfs_TMP").is_some());
+        assert!(scoped.get_layer("gfs_PRMSL").is_none());
+        assert!(!scoped.has_model("hrrr"));
+        assert_eq!(registry.total_layers(), 3);
+    }
+
     #[test]
     fn test_unit_conversion_infer() {
         // Temperature conversions
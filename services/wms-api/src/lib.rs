@@ -6,14 +6,25 @@ pub mod admin;
 pub mod capabilities_cache;
 pub mod chunk_warming;
 pub mod cleanup;
+pub mod completeness;
+pub mod config_store;
+pub mod export;
 pub mod handlers;
+pub mod integrity;
 pub mod layer_config;
 pub mod memory_pressure;
 pub mod metrics;
 pub mod model_config;
+pub mod preview;
 pub mod rendering;
+pub mod request_limits;
+pub mod routes;
+pub mod security;
 pub mod startup_validation;
 pub mod state;
+pub mod time_match;
+pub mod underlay;
+pub mod update_cadence;
 pub mod validation;
 pub mod warming;
 
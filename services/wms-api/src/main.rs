@@ -3,21 +3,19 @@
 //! HTTP server implementing OGC WMS 1.1.1/1.3.0 and WMTS 1.0.0 specifications.
 
 use wms_api::{
-    admin, chunk_warming, cleanup, handlers, memory_pressure, startup_validation, state, warming,
+    chunk_warming, cleanup, completeness, memory_pressure, routes, security, startup_validation,
+    state, update_cadence, warming,
 };
 
 use anyhow::Result;
-use axum::{
-    extract::Extension,
-    routing::{get, post},
-    Router,
-};
+use axum::{extract::Extension, middleware, Router};
 use clap::Parser;
 use std::{env, net::SocketAddr, sync::Arc};
-use tower_http::{compression::CompressionLayer, cors::CorsLayer, trace::TraceLayer};
-use tracing::{info, Level};
+use tower_http::{compression::CompressionLayer, trace::TraceLayer};
+use tracing::{error, info, warn, Level};
 use tracing_subscriber::FmtSubscriber;
 
+use security::SecurityConfig;
 use state::AppState;
 
 #[derive(Parser, Debug)]
@@ -103,6 +101,9 @@ async fn async_main(args: Args) -> Result<()> {
     // Run database migrations
     info!("Running database migrations...");
     state.catalog.migrate().await?;
+    if let Some(benchmarks) = &state.benchmarks {
+        benchmarks.migrate().await?;
+    }
     info!("Database migrations completed successfully");
 
     // Validate layer configs against catalog
@@ -188,6 +189,9 @@ async fn async_main(args: Args) -> Result<()> {
                 .ok()
                 .and_then(|v| v.parse::<usize>().ok())
                 .unwrap_or(10),
+            publish_archive: env::var("CACHE_WARMING_PUBLISH_ARCHIVE")
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(false),
         };
 
         if warming_config.enabled {
@@ -275,6 +279,32 @@ async fn async_main(args: Args) -> Result<()> {
         }
     }
 
+    // Measure model update cadences from the catalog for cache lifetimes
+    {
+        let interval_secs = env::var("CADENCE_REFRESH_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(900);
+        let state = state.clone();
+        tokio::spawn(async move {
+            update_cadence::run_refresh_loop(state, std::time::Duration::from_secs(interval_secs))
+                .await;
+        });
+    }
+
+    // Compare recent runs against the expected datasets for the missing-datasets gauge
+    {
+        let interval_secs = env::var("COMPLETENESS_REFRESH_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(300);
+        let state = state.clone();
+        tokio::spawn(async move {
+            completeness::run_refresh_loop(state, std::time::Duration::from_secs(interval_secs))
+                .await;
+        });
+    }
+
     // Start chunk warming background task (proactive cache warming for GOES/observation data)
     {
         let config_dir = env::var("CONFIG_DIR").unwrap_or_else(|_| "/app/config".to_string());
@@ -292,6 +322,12 @@ async fn async_main(args: Args) -> Result<()> {
             warmer_clone.run_forever().await;
         });
 
+        // Watch for predicted model arrivals (no-op unless a model enables it)
+        let warmer_clone = chunk_warmer.clone();
+        tokio::spawn(async move {
+            warmer_clone.run_arrival_watch().await;
+        });
+
         info!("Chunk warming background task started");
     }
 
@@ -306,158 +342,58 @@ async fn async_main(args: Args) -> Result<()> {
         info!("Memory pressure monitoring disabled (set ENABLE_MEMORY_PRESSURE=true to enable)");
     }
 
+    let security_config = SecurityConfig::from_env();
+
     // Build router
-    let app = Router::new()
-        // WMS endpoints
-        .route("/wms", get(handlers::wms_handler))
-        .route("/wms/", get(handlers::wms_handler))
-        // WMTS endpoints (KVP)
-        .route("/wmts", get(handlers::wmts_kvp_handler))
-        .route("/wmts/", get(handlers::wmts_kvp_handler))
-        // WMTS RESTful endpoints
-        .route("/wmts/rest/*path", get(handlers::wmts_rest_handler))
-        // Simple tile endpoints (XYZ/TMS style for easy integration)
-        .route(
-            "/tiles/:layer/:style/:z/:x/:y",
-            get(handlers::xyz_tile_handler),
-        )
-        // Health check
-        .route("/health", get(handlers::health_handler))
-        .route("/ready", get(handlers::ready_handler))
-        // Metrics
-        .route("/metrics", get(handlers::metrics_handler))
-        // API endpoints
-        .route(
-            "/api/forecast-times/:model/:parameter",
-            get(handlers::forecast_times_handler),
-        )
-        .route("/api/parameters/:model", get(handlers::parameters_handler))
-        // Ingestion events API
-        .route(
-            "/api/ingestion/events",
-            get(handlers::ingestion_events_handler),
-        )
-        // Validation API
-        .route(
-            "/api/validation/status",
-            get(handlers::validation_status_handler),
-        )
-        .route("/api/validation/run", get(handlers::validation_run_handler))
-        // Startup validation API (test renders + cache warming)
-        .route(
-            "/api/validation/startup",
-            get(handlers::startup_validation_run_handler),
-        )
-        // Storage stats API
-        .route("/api/storage/stats", get(handlers::storage_stats_handler))
-        // Container/pod resource stats API
-        .route(
-            "/api/container/stats",
-            get(handlers::container_stats_handler),
-        )
-        // Grid processor stats API (Zarr chunk cache and processing)
-        .route(
-            "/api/grid-processor/stats",
-            get(handlers::grid_processor_stats_handler),
-        )
-        // Tile request heatmap API (for geographic visualization)
-        .route("/api/tile-heatmap", get(handlers::tile_heatmap_handler))
-        .route(
-            "/api/tile-heatmap/clear",
-            post(handlers::tile_heatmap_clear_handler),
-        )
-        // Application metrics API
-        .route("/api/metrics", get(handlers::api_metrics_handler))
-        // Configuration API - shows optimization settings
-        .route("/api/config", get(handlers::config_handler))
-        // Cache API endpoints
-        .route("/api/cache/list", get(handlers::cache_list_handler))
-        .route("/api/cache/clear", post(handlers::cache_clear_handler))
-        // Config reload endpoints (hot reload)
-        .route("/api/config/reload", post(handlers::config_reload_handler))
-        .route(
-            "/api/config/reload/layers",
-            post(handlers::config_reload_layers_handler),
-        )
-        // API Documentation (Swagger UI)
-        .route("/api/docs", get(handlers::swagger_ui_handler))
-        .route(
-            "/api/docs/openapi.yaml",
-            get(handlers::openapi_yaml_handler),
-        )
-        .route(
-            "/api/docs/openapi.json",
-            get(handlers::openapi_json_handler),
-        )
-        // Load test dashboard
-        .route("/loadtest", get(handlers::loadtest_dashboard_handler))
-        .route(
-            "/api/loadtest/results",
-            get(handlers::loadtest_results_handler),
-        )
-        // Benchmark comparison API (for web/benchmarks.html - load test comparisons)
-        .route("/api/benchmarks", get(handlers::benchmarks_handler))
-        // Criterion microbenchmark results API
-        .route(
-            "/api/criterion",
-            get(handlers::criterion_benchmarks_handler),
-        )
-        .route("/api/loadtest/files", get(handlers::loadtest_files_handler))
-        .route(
-            "/api/loadtest/file/:filename",
-            get(handlers::loadtest_file_handler),
-        )
-        // Admin dashboard
-        .route(
-            "/api/admin/ingestion/status",
-            get(admin::ingestion_status_handler),
-        )
-        .route(
-            "/api/admin/database/details",
-            get(admin::database_details_handler),
-        )
-        .route(
-            "/api/admin/database/datasets/:model/:parameter",
-            get(admin::database_datasets_handler),
-        )
-        .route("/api/admin/storage/tree", get(admin::storage_tree_handler))
-        .route(
-            "/api/admin/ingestion/log",
-            get(admin::ingestion_log_handler),
-        )
-        .route(
-            "/api/admin/preview-shred",
-            get(admin::preview_shred_handler),
-        )
-        .route("/api/admin/config/models", get(admin::list_models_handler))
-        .route(
-            "/api/admin/config/models/:id",
-            get(admin::get_model_config_handler).put(admin::update_model_config_handler),
-        )
-        .route("/api/admin/config/full", get(admin::full_config_handler))
-        // Ingest endpoint (called by downloader service)
-        .route("/admin/ingest", post(admin::ingest_handler))
-        // Cleanup/retention endpoints
-        .route(
-            "/api/admin/cleanup/status",
-            get(admin::cleanup_status_handler),
-        )
-        .route("/api/admin/cleanup/run", post(admin::cleanup_run_handler))
-        // Database/storage sync endpoints
-        .route("/api/admin/sync/status", get(admin::sync_status_handler))
-        .route("/api/admin/sync/preview", get(admin::sync_preview_handler))
-        .route("/api/admin/sync/run", post(admin::sync_run_handler))
-        // Ingestion tracking endpoint
-        .route(
-            "/api/admin/ingestion/active",
-            get(admin::ingestion_active_handler),
-        )
-        // Layer extensions
-        .layer(Extension(state))
-        .layer(Extension(prometheus_handle))
-        .layer(TraceLayer::new_for_http())
-        .layer(CompressionLayer::new())
-        .layer(CorsLayer::permissive());
+    let public_routes = routes::public_routes();
+
+    // Admin and cache-management endpoints
+    let mut admin_routes = routes::admin_routes();
+
+    if let Some(token) = &security_config.admin_token {
+        admin_routes = admin_routes.route_layer(middleware::from_fn_with_state(
+            Arc::<str>::from(token.as_str()),
+            security::require_admin_token,
+        ));
+    } else if security_config.admin_listen.is_none() {
+        warn!("Admin endpoints are public (set ADMIN_LISTEN or ADMIN_TOKEN to restrict them)");
+    }
+
+    if security_config.allows_any_origin() {
+        warn!("CORS allows any origin (set CORS_ALLOWED_ORIGINS to restrict it)");
+    }
+
+    // Layer extensions
+    let security_headers = Arc::new(security_config.security_headers());
+    let with_layers = |router: Router| {
+        router
+            .layer(Extension(state.clone()))
+            .layer(Extension(prometheus_handle.clone()))
+            .layer(middleware::from_fn_with_state(
+                security_headers.clone(),
+                security::add_security_headers,
+            ))
+            .layer(TraceLayer::new_for_http())
+            .layer(CompressionLayer::new())
+            .layer(security_config.cors_layer())
+    };
+
+    // Serve admin endpoints on their own listener if configured
+    let app = match &security_config.admin_listen {
+        Some(admin_listen) => {
+            let admin_addr: SocketAddr = admin_listen.parse()?;
+            let admin_listener = tokio::net::TcpListener::bind(admin_addr).await?;
+            let admin_app = with_layers(admin_routes);
+            info!(address = %admin_addr, "Admin endpoints listening");
+            tokio::spawn(async move {
+                if let Err(e) = axum::serve(admin_listener, admin_app).await {
+                    error!(error = %e, "Admin server failed");
+                }
+            });
+            with_layers(public_routes)
+        }
+        None => with_layers(public_routes.merge(admin_routes)),
+    };
 
     // Parse listen address
     let addr: SocketAddr = args.listen.parse()?;
@@ -141,8 +141,9 @@ impl MemoryPressureMonitor {
             // If we need to free more than the chunk cache has, evict all of it
             let evict_ratio = (bytes_to_free as f64 / chunk_stats.memory_bytes as f64).min(0.5);
             if evict_ratio > 0.1 {
-                // Clear chunk cache completely (no partial eviction API)
-                let (evicted, _bytes) = self.state.grid_processor_factory.clear_chunk_cache().await;
+                // Empty the chunk cache; with a disk tier the chunks are
+                // spilled there rather than dropped
+                let (evicted, _bytes) = self.state.grid_processor_factory.evict_chunk_cache().await;
                 total_evicted += evicted;
                 info!(
                     evicted_entries = evicted,
@@ -17,6 +17,10 @@ pub struct MetricsCollector {
     pub wmts_requests: AtomicU64,
     pub cache_hits: AtomicU64,
     pub cache_misses: AtomicU64,
+    /// Tile lookups whose cache key was rewritten by normalization, and
+    /// how many of them hit (L1 or L2)
+    pub cache_key_rewrites: AtomicU64,
+    pub cache_key_rewrite_hits: AtomicU64,
     pub minio_reads: AtomicU64,
     pub minio_read_bytes: AtomicU64,
 
@@ -137,7 +141,7 @@ impl DataSourceType {
             "gfs" => DataSourceType::Grib2Gfs,
             "hrrr" => DataSourceType::Grib2Hrrr,
             "mrms" => DataSourceType::Grib2Mrms,
-            "goes16" | "goes18" | "goes" => DataSourceType::NetcdfGoes,
+            m if m.starts_with("goes") => DataSourceType::NetcdfGoes,
             other => DataSourceType::Other(other.to_string()),
         }
     }
@@ -482,6 +486,8 @@ impl MetricsCollector {
             wmts_requests: AtomicU64::new(0),
             cache_hits: AtomicU64::new(0),
             cache_misses: AtomicU64::new(0),
+            cache_key_rewrites: AtomicU64::new(0),
+            cache_key_rewrite_hits: AtomicU64::new(0),
             minio_reads: AtomicU64::new(0),
             minio_read_bytes: AtomicU64::new(0),
             renders_total: AtomicU64::new(0),
@@ -535,6 +541,19 @@ impl MetricsCollector {
         counter!("cache_misses_total").increment(1);
     }
 
+    /// Record a tile lookup under a key that normalization rewrote.
+    ///
+    /// Hits here would have been misses with the key as requested, so they
+    /// measure the hit rate gained by normalization.
+    pub fn record_cache_key_rewrite(&self, hit: bool) {
+        self.cache_key_rewrites.fetch_add(1, Ordering::Relaxed);
+        counter!("cache_key_rewrites_total").increment(1);
+        if hit {
+            self.cache_key_rewrite_hits.fetch_add(1, Ordering::Relaxed);
+            counter!("cache_key_rewrite_hits_total").increment(1);
+        }
+    }
+
     /// Record L1 cache hit
     pub fn record_l1_cache_hit(&self) {
         counter!("tile_memory_cache_hits_total").increment(1);
@@ -545,6 +564,16 @@ impl MetricsCollector {
         counter!("tile_memory_cache_misses_total").increment(1);
     }
 
+    /// Record a WMTS tile request outside the layer's data extent
+    pub fn record_tile_out_of_extent(&self) {
+        counter!("wmts_tiles_out_of_extent_total").increment(1);
+    }
+
+    /// Record a WMTS tile served from the tile archive
+    pub fn record_tile_archive_hit(&self) {
+        counter!("wmts_tile_archive_hits_total").increment(1);
+    }
+
     /// Record a tile request location for heatmap visualization
     /// bbox format: [min_lon, min_lat, max_lon, max_lat]
     pub fn record_tile_request_location(&self, bbox: &[f32; 4], cache_status: TileCacheStatus) {
@@ -995,6 +1024,13 @@ impl MetricsCollector {
         gauge!("chunk_cache_memory_bytes").set(stats.memory_bytes as f64);
         gauge!("chunk_cache_memory_mb").set(memory_mb);
         gauge!("chunk_cache_hit_rate_percent").set(hit_rate);
+        gauge!("chunk_cache_disk_hits_total").set(stats.disk_hits as f64);
+        gauge!("chunk_cache_disk_entries").set(stats.disk_entries as f64);
+        gauge!("chunk_cache_disk_bytes").set(stats.disk_bytes as f64);
+        gauge!("chunk_cache_prefetched_total").set(stats.prefetched as f64);
+        gauge!("chunk_cache_prefetch_hits_total").set(stats.prefetch_hits as f64);
+        gauge!("chunk_cache_prefetch_misses_total").set(stats.prefetch_misses as f64);
+        gauge!("chunk_cache_prefetch_hit_rate_percent").set(stats.prefetch_hit_rate() * 100.0);
     }
 
     /// Get current metrics snapshot
@@ -1022,6 +1058,14 @@ impl MetricsCollector {
             0.0
         };
 
+        let wmts_requests = self.wmts_requests.load(Ordering::Relaxed);
+        let cache_key_rewrite_hits = self.cache_key_rewrite_hits.load(Ordering::Relaxed);
+        let cache_key_hit_rate_gain = if wmts_requests > 0 {
+            (cache_key_rewrite_hits as f64 / wmts_requests as f64) * 100.0
+        } else {
+            0.0
+        };
+
         // Build per-layer-type stats
         let mut layer_type_stats = HashMap::new();
         for (layer_type, stats) in layer_times.iter() {
@@ -1061,7 +1105,7 @@ impl MetricsCollector {
             uptime_secs: self.start_time.elapsed().as_secs(),
 
             wms_requests: self.wms_requests.load(Ordering::Relaxed),
-            wmts_requests: self.wmts_requests.load(Ordering::Relaxed),
+            wmts_requests,
 
             // Request rates
             wms_rate_1m: wms_rate.rate_1m(),
@@ -1076,6 +1120,9 @@ impl MetricsCollector {
             cache_hits,
             cache_misses,
             cache_hit_rate,
+            cache_key_rewrites: self.cache_key_rewrites.load(Ordering::Relaxed),
+            cache_key_rewrite_hits,
+            cache_key_hit_rate_gain,
 
             minio_reads: self.minio_reads.load(Ordering::Relaxed),
             minio_read_bytes: self.minio_read_bytes.load(Ordering::Relaxed),
@@ -1125,6 +1172,8 @@ impl MetricsCollector {
         self.wmts_requests.store(0, Ordering::Relaxed);
         self.cache_hits.store(0, Ordering::Relaxed);
         self.cache_misses.store(0, Ordering::Relaxed);
+        self.cache_key_rewrites.store(0, Ordering::Relaxed);
+        self.cache_key_rewrite_hits.store(0, Ordering::Relaxed);
         self.minio_reads.store(0, Ordering::Relaxed);
         self.minio_read_bytes.store(0, Ordering::Relaxed);
         self.renders_total.store(0, Ordering::Relaxed);
@@ -1171,6 +1220,12 @@ pub struct MetricsSnapshot {
     pub cache_hits: u64,
     pub cache_misses: u64,
     pub cache_hit_rate: f64,
+    /// Tile lookups under keys rewritten by normalization
+    pub cache_key_rewrites: u64,
+    /// Of those, lookups that hit
+    pub cache_key_rewrite_hits: u64,
+    /// Percentage of tile requests served from cache only because of normalization
+    pub cache_key_hit_rate_gain: f64,
 
     // MinIO stats
     pub minio_reads: u64,
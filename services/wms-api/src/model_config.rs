@@ -7,6 +7,8 @@ use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::sync::RwLock;
+use std::time::Duration;
 use tracing::{debug, warn};
 
 /// Dimension type for a model - determines which WMS dimensions are exposed.
@@ -50,6 +52,9 @@ pub struct ModelDimensionConfig {
     /// where the relationship between grid indices and geographic coordinates is non-linear.
     /// For these models, partial bbox reads would produce incorrect results.
     pub requires_full_grid: bool,
+    /// How often new data arrives, from `schedule` (the poll interval of
+    /// observations, the shortest gap between forecast cycles)
+    pub update_interval: Option<Duration>,
 }
 
 impl Default for ModelDimensionConfig {
@@ -62,6 +67,7 @@ impl Default for ModelDimensionConfig {
             has_time: false,
             has_elevation: true,
             requires_full_grid: false, // Most models support partial reads
+            update_interval: None,
         }
     }
 }
@@ -113,37 +119,82 @@ struct YamlModelMetadata {
 struct YamlScheduleConfig {
     #[serde(rename = "type")]
     schedule_type: Option<String>,
+    /// Forecast cycle hours (UTC)
+    #[serde(default)]
+    cycles: Option<Vec<u32>>,
+    #[serde(default)]
+    poll_interval_secs: Option<u64>,
+}
+
+impl YamlScheduleConfig {
+    /// Expected time between new datasets.
+    fn update_interval(&self) -> Option<Duration> {
+        if self.schedule_type.as_deref() == Some("observation") {
+            return self.poll_interval_secs.map(Duration::from_secs);
+        }
+
+        let mut cycles: Vec<u32> = self.cycles.as_ref()?.iter().map(|h| h % 24).collect();
+        cycles.sort_unstable();
+        cycles.dedup();
+        let first = *cycles.first()?;
+        let last = *cycles.last()?;
+        // Gap from the last cycle of the day to the first of the next
+        let wrap = 24 - last + first;
+        let shortest = cycles
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .chain(std::iter::once(wrap))
+            .min()?;
+        Some(Duration::from_secs(u64::from(shortest) * 3600))
+    }
 }
 
 /// Registry of model dimension configurations.
-#[derive(Debug, Clone, Default)]
+///
+/// Configurations can be replaced in place (see
+/// [`ModelDimensionRegistry::reload_from_directory`]) when model files are
+/// edited through the admin API.
+#[derive(Debug, Default)]
 pub struct ModelDimensionRegistry {
-    configs: HashMap<String, ModelDimensionConfig>,
+    configs: RwLock<HashMap<String, ModelDimensionConfig>>,
 }
 
 impl ModelDimensionRegistry {
     /// Create a new empty registry.
     pub fn new() -> Self {
-        Self {
-            configs: HashMap::new(),
-        }
+        Self::default()
     }
 
     /// Load dimension configurations from all model YAML files in a directory.
     pub fn load_from_directory<P: AsRef<Path>>(config_dir: P) -> Self {
-        let mut registry = Self::new();
+        Self {
+            configs: RwLock::new(Self::load_configs(config_dir)),
+        }
+    }
+
+    /// Replace all configurations with those in a directory.
+    /// Returns the number of models loaded.
+    pub fn reload_from_directory<P: AsRef<Path>>(&self, config_dir: P) -> usize {
+        let configs = Self::load_configs(config_dir);
+        let count = configs.len();
+        *self.configs.write().unwrap() = configs;
+        count
+    }
+
+    fn load_configs<P: AsRef<Path>>(config_dir: P) -> HashMap<String, ModelDimensionConfig> {
+        let mut configs = HashMap::new();
         let models_dir = config_dir.as_ref().join("models");
 
         if !models_dir.exists() {
             warn!(path = ?models_dir, "Models config directory not found");
-            return registry;
+            return configs;
         }
 
         let entries = match fs::read_dir(&models_dir) {
             Ok(entries) => entries,
             Err(e) => {
                 warn!(error = %e, path = ?models_dir, "Failed to read models directory");
-                return registry;
+                return configs;
             }
         };
 
@@ -156,18 +207,18 @@ impl ModelDimensionRegistry {
                         dimension_type = ?config.1.dimension_type,
                         "Loaded dimension config"
                     );
-                    registry.configs.insert(config.0, config.1);
+                    configs.insert(config.0, config.1);
                 }
             }
         }
 
         debug!(
-            count = registry.configs.len(),
-            models = ?registry.configs.keys().collect::<Vec<_>>(),
+            count = configs.len(),
+            models = ?configs.keys().collect::<Vec<_>>(),
             "Loaded model dimension configs"
         );
 
-        registry
+        configs
     }
 
     /// Load dimension config from a single YAML file.
@@ -196,6 +247,11 @@ impl ModelDimensionRegistry {
             false
         };
 
+        let update_interval = yaml
+            .schedule
+            .as_ref()
+            .and_then(YamlScheduleConfig::update_interval);
+
         // Determine dimension type from explicit config or infer from schedule
         let config = if let Some(dims) = yaml.dimensions {
             // Explicit dimensions config
@@ -215,8 +271,9 @@ impl ModelDimensionRegistry {
                 has_time: dims.time.unwrap_or(dimension_type.is_observation()),
                 has_elevation: dims.elevation.unwrap_or(true),
                 requires_full_grid,
+                update_interval,
             }
-        } else if let Some(schedule) = yaml.schedule {
+        } else if let Some(schedule) = &yaml.schedule {
             // Infer from schedule.type if no explicit dimensions config
             let dimension_type = match schedule.schedule_type.as_deref() {
                 Some("observation") => DimensionType::Observation,
@@ -230,6 +287,7 @@ impl ModelDimensionRegistry {
                 has_time: dimension_type.is_observation(),
                 has_elevation: true,
                 requires_full_grid,
+                update_interval,
             }
         } else {
             // Default to forecast
@@ -244,7 +302,12 @@ impl ModelDimensionRegistry {
     /// Get dimension config for a model.
     /// Returns default (forecast) config if model not found.
     pub fn get(&self, model: &str) -> ModelDimensionConfig {
-        self.configs.get(model).cloned().unwrap_or_default()
+        self.configs
+            .read()
+            .unwrap()
+            .get(model)
+            .cloned()
+            .unwrap_or_default()
     }
 
     /// Get dimension type for a model.
@@ -270,9 +333,15 @@ impl ModelDimensionRegistry {
         self.get(model).requires_full_grid
     }
 
+    /// Configured interval between new datasets of a model, if its schedule
+    /// defines one.
+    pub fn update_interval(&self, model: &str) -> Option<Duration> {
+        self.get(model).update_interval
+    }
+
     /// Get all registered model IDs.
-    pub fn models(&self) -> Vec<&str> {
-        self.configs.keys().map(|s| s.as_str()).collect()
+    pub fn models(&self) -> Vec<String> {
+        self.configs.read().unwrap().keys().cloned().collect()
     }
 }
 
@@ -296,5 +365,43 @@ mod tests {
         // Unknown models should return default (forecast) config
         let config = registry.get("unknown_model");
         assert!(config.dimension_type.is_forecast());
+        assert_eq!(registry.update_interval("unknown_model"), None);
+    }
+
+    #[test]
+    fn test_update_interval_from_schedule() {
+        let dir = tempfile::tempdir().unwrap();
+        let models_dir = dir.path().join("models");
+        fs::create_dir(&models_dir).unwrap();
+        fs::write(
+            models_dir.join("gfs.yaml"),
+            "model:\n  id: gfs\nschedule:\n  cycles: [0, 6, 12, 18]\n  poll_interval_secs: 3600\n",
+        )
+        .unwrap();
+        fs::write(
+            models_dir.join("nam.yaml"),
+            "model:\n  id: nam\nschedule:\n  cycles: [18, 0, 12]\n",
+        )
+        .unwrap();
+        fs::write(
+            models_dir.join("mrms.yaml"),
+            "model:\n  id: mrms\nschedule:\n  type: observation\n  poll_interval_secs: 120\n",
+        )
+        .unwrap();
+
+        let registry = ModelDimensionRegistry::load_from_directory(dir.path());
+        assert_eq!(
+            registry.update_interval("gfs"),
+            Some(Duration::from_secs(6 * 3600))
+        );
+        // Uneven cycles use the shortest gap, including 18z -> 00z
+        assert_eq!(
+            registry.update_interval("nam"),
+            Some(Duration::from_secs(6 * 3600))
+        );
+        assert_eq!(
+            registry.update_interval("mrms"),
+            Some(Duration::from_secs(120))
+        );
     }
 }
@@ -0,0 +1,363 @@
+//! Layer/style preview matrix for the admin dashboard.
+//!
+//! Renders one small thumbnail per (layer, style) of the latest data so a
+//! broken style or missing dataset is spotted at a glance after a deploy.
+//! Thumbnails are rendered through the same path as WMS GetMap, a few at a
+//! time, and each one is abandoned once it exceeds the per-tile budget. The
+//! manifest and its images are kept for a short time so a dashboard refresh
+//! doesn't re-render the whole matrix.
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+use crate::handlers::common::{style_names_from_file, DimensionParams};
+use crate::handlers::wms::{render_weather_data, WmsError};
+use crate::layer_config::BoundingBoxConfig;
+use crate::state::AppState;
+
+/// Preview matrix settings.
+#[derive(Debug, Clone)]
+pub struct PreviewConfig {
+    /// Thumbnail width and height in pixels
+    pub size: u32,
+    /// Longest a single thumbnail may take to render
+    pub tile_budget: Duration,
+    /// Thumbnails rendered at once
+    pub concurrency: usize,
+    /// How long a rendered matrix is served before it is rebuilt
+    pub cache_ttl: Duration,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            size: 128,
+            tile_budget: Duration::from_millis(2000),
+            concurrency: 8,
+            cache_ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+impl PreviewConfig {
+    /// Load configuration from environment variables.
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+            env::var(key).ok().and_then(|v| v.parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            size: parse::<u32>("PREVIEW_SIZE")
+                .map(|s| s.clamp(32, 512))
+                .unwrap_or(defaults.size),
+            tile_budget: parse::<u64>("PREVIEW_TILE_BUDGET_MS")
+                .map(|ms| Duration::from_millis(ms.max(100)))
+                .unwrap_or(defaults.tile_budget),
+            concurrency: parse::<usize>("PREVIEW_CONCURRENCY")
+                .map(|n| n.max(1))
+                .unwrap_or(defaults.concurrency),
+            cache_ttl: parse::<u64>("PREVIEW_CACHE_TTL_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.cache_ttl),
+        }
+    }
+}
+
+/// Outcome of rendering one thumbnail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreviewStatus {
+    /// Rendered; the image is available at `image_url`
+    Ok,
+    /// The layer has no data to render
+    NoData,
+    /// Rendering failed
+    Failed,
+    /// Rendering took longer than the per-tile budget
+    TimedOut,
+}
+
+/// One cell of the preview matrix.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewTile {
+    pub layer: String,
+    pub style: String,
+    pub status: PreviewStatus,
+    pub render_ms: u64,
+    /// URL of the thumbnail, if it rendered
+    pub image_url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// The preview matrix returned by `/api/admin/preview`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewManifest {
+    pub generated_at: DateTime<Utc>,
+    /// Thumbnail width and height in pixels
+    pub size: u32,
+    pub tile_budget_ms: u64,
+    /// Seconds until the matrix is rebuilt
+    pub cache_ttl_secs: u64,
+    pub tiles: Vec<PreviewTile>,
+}
+
+struct CachedPreview {
+    built_at: Instant,
+    manifest: PreviewManifest,
+    images: HashMap<(String, String), Bytes>,
+}
+
+/// Builds the preview matrix and keeps the latest one.
+pub struct PreviewMatrix {
+    config: PreviewConfig,
+    cached: Mutex<Option<CachedPreview>>,
+}
+
+impl PreviewMatrix {
+    pub fn new(config: PreviewConfig) -> Self {
+        Self {
+            config,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn config(&self) -> &PreviewConfig {
+        &self.config
+    }
+
+    /// The current matrix, rendering it if the cached one is missing or
+    /// older than the cache TTL.
+    ///
+    /// Concurrent callers wait for a single rebuild instead of each
+    /// rendering the matrix.
+    pub async fn manifest(&self, state: &Arc<AppState>) -> PreviewManifest {
+        let mut cached = self.cached.lock().await;
+        if let Some(preview) = cached.as_ref() {
+            if preview.built_at.elapsed() < self.config.cache_ttl {
+                return preview.manifest.clone();
+            }
+        }
+
+        let preview = self.build(state).await;
+        let manifest = preview.manifest.clone();
+        *cached = Some(preview);
+        manifest
+    }
+
+    /// Thumbnail of the current matrix. `None` once the matrix has expired or
+    /// if the thumbnail didn't render.
+    pub async fn image(&self, layer: &str, style: &str) -> Option<Bytes> {
+        let cached = self.cached.lock().await;
+        cached
+            .as_ref()
+            .filter(|p| p.built_at.elapsed() < self.config.cache_ttl)
+            .and_then(|p| p.images.get(&(layer.to_string(), style.to_string())))
+            .cloned()
+    }
+
+    async fn build(&self, state: &Arc<AppState>) -> CachedPreview {
+        let started = Instant::now();
+        let cells = preview_cells(state).await;
+        info!(
+            tiles = cells.len(),
+            concurrency = self.config.concurrency,
+            "Rendering preview matrix"
+        );
+
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency));
+        let mut tasks = JoinSet::new();
+        for (index, (layer, style, bbox)) in cells.iter().cloned().enumerate() {
+            let state = state.clone();
+            let semaphore = semaphore.clone();
+            let size = self.config.size;
+            let budget = self.config.tile_budget;
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok();
+                let start = Instant::now();
+                let result = tokio::time::timeout(
+                    budget,
+                    render_thumbnail(&state, &layer, &style, &bbox, size),
+                )
+                .await;
+                (index, result, start.elapsed())
+            });
+        }
+
+        let mut results: Vec<_> = (0..cells.len()).map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((index, result, elapsed)) => results[index] = Some((result, elapsed)),
+                Err(e) => warn!(error = %e, "Preview render task failed"),
+            }
+        }
+
+        let mut tiles = Vec::with_capacity(cells.len());
+        let mut images = HashMap::new();
+        for ((layer, style, _), result) in cells.into_iter().zip(results) {
+            let (status, error, render_ms) = match result {
+                Some((Ok(Ok(png)), elapsed)) => {
+                    images.insert((layer.clone(), style.clone()), Bytes::from(png));
+                    (PreviewStatus::Ok, None, elapsed.as_millis() as u64)
+                }
+                Some((Ok(Err(e @ WmsError::MissingData(_))), elapsed)) => (
+                    PreviewStatus::NoData,
+                    Some(e.message()),
+                    elapsed.as_millis() as u64,
+                ),
+                Some((Ok(Err(e)), elapsed)) => (
+                    PreviewStatus::Failed,
+                    Some(e.message()),
+                    elapsed.as_millis() as u64,
+                ),
+                Some((Err(_), elapsed)) => (
+                    PreviewStatus::TimedOut,
+                    Some(format!(
+                        "Exceeded {} ms budget",
+                        self.config.tile_budget.as_millis()
+                    )),
+                    elapsed.as_millis() as u64,
+                ),
+                // The render task panicked
+                None => (
+                    PreviewStatus::Failed,
+                    Some("Render task panicked".to_string()),
+                    0,
+                ),
+            };
+
+            tiles.push(PreviewTile {
+                image_url: (status == PreviewStatus::Ok).then(|| image_url(&layer, &style)),
+                layer,
+                style,
+                status,
+                render_ms,
+                error,
+            });
+        }
+
+        let failed = tiles
+            .iter()
+            .filter(|t| matches!(t.status, PreviewStatus::Failed | PreviewStatus::TimedOut))
+            .count();
+        info!(
+            tiles = tiles.len(),
+            failed = failed,
+            duration_ms = started.elapsed().as_millis() as u64,
+            "Preview matrix rendered"
+        );
+
+        CachedPreview {
+            built_at: Instant::now(),
+            manifest: PreviewManifest {
+                generated_at: Utc::now(),
+                size: self.config.size,
+                tile_budget_ms: self.config.tile_budget.as_millis() as u64,
+                cache_ttl_secs: self.config.cache_ttl.as_secs(),
+                tiles,
+            },
+            images,
+        }
+    }
+}
+
+/// Every (layer, style) of the configured layers with the extent to render,
+/// ordered by model and then as configured.
+async fn preview_cells(state: &AppState) -> Vec<(String, String, BoundingBoxConfig)> {
+    let configs = state.layer_configs.read().await;
+    let mut models = configs.models();
+    models.sort_unstable();
+
+    let mut cells = Vec::new();
+    for model in models {
+        let Some(model_config) = configs.get_model(model) else {
+            continue;
+        };
+        let bbox = model_config.default_bbox.clone().unwrap_or_default();
+        for layer in &model_config.layers {
+            let style_file = configs.get_style_path(layer);
+            for style in style_names_from_file(&style_file, &layer.style_policy) {
+                cells.push((layer.id.clone(), style, bbox.clone()));
+            }
+        }
+    }
+    cells
+}
+
+async fn render_thumbnail(
+    state: &Arc<AppState>,
+    layer: &str,
+    style: &str,
+    bbox: &BoundingBoxConfig,
+    size: u32,
+) -> Result<Vec<u8>, WmsError> {
+    render_weather_data(
+        state,
+        layer,
+        style,
+        size,
+        size,
+        Some(&bbox_param(bbox)),
+        Some("EPSG:4326"),
+        &DimensionParams::default(),
+    )
+    .await
+}
+
+/// WMS 1.3.0 BBOX value for EPSG:4326, which orders axes lat,lon.
+fn bbox_param(bbox: &BoundingBoxConfig) -> String {
+    format!("{},{},{},{}", bbox.south, bbox.west, bbox.north, bbox.east)
+}
+
+fn image_url(layer: &str, style: &str) -> String {
+    format!("/api/admin/preview/image/{}/{}", layer, style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bbox_param_is_lat_lon() {
+        let bbox = BoundingBoxConfig {
+            west: -134.0,
+            south: 21.0,
+            east: -60.0,
+            north: 53.0,
+        };
+        assert_eq!(bbox_param(&bbox), "21,-134,53,-60");
+    }
+
+    #[test]
+    fn test_manifest_serialization() {
+        let manifest = PreviewManifest {
+            generated_at: Utc::now(),
+            size: 128,
+            tile_budget_ms: 2000,
+            cache_ttl_secs: 60,
+            tiles: vec![PreviewTile {
+                layer: "gfs_TMP".to_string(),
+                style: "temperature".to_string(),
+                status: PreviewStatus::TimedOut,
+                render_ms: 2001,
+                image_url: None,
+                error: Some("Exceeded 2000 ms budget".to_string()),
+            }],
+        };
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert_eq!(json["tiles"][0]["status"], "timed_out");
+        assert_eq!(
+            image_url("gfs_TMP", "temperature"),
+            "/api/admin/preview/image/gfs_TMP/temperature"
+        );
+    }
+}
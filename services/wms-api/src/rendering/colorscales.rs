@@ -20,10 +20,16 @@
 //! If a style file cannot be loaded or doesn't contain the requested style, an error
 //! is returned. **There is no fallback** - all layers must have properly configured
 //! styles. This ensures consistent colors across all tiles.
+//!
+//! ## Colorblind-safe variants
+//!
+//! Every gradient style automatically gets a `<style>_cvd` variant generated by
+//! `renderer::cvd` (unless the style file already defines one).
 
 use once_cell::sync::Lazy;
+use renderer::mask::MaskInput;
 use renderer::style::{
-    apply_style_gradient, apply_style_gradient_indexed, PrecomputedPalette, StyleConfig,
+    apply_style_gradient, apply_style_gradient_indexed_masked, PrecomputedPalette, StyleConfig,
 };
 use std::collections::HashMap;
 use std::sync::RwLock;
@@ -37,6 +43,11 @@ use std::sync::RwLock;
 static PALETTE_CACHE: Lazy<RwLock<HashMap<(String, String), PrecomputedPalette>>> =
     Lazy::new(|| RwLock::new(HashMap::new()));
 
+/// Drop all cached palettes, e.g. after a style file was edited.
+pub(crate) fn clear_palette_cache() {
+    PALETTE_CACHE.write().unwrap().clear();
+}
+
 /// Get or compute a palette for the given style.
 fn get_or_compute_palette(
     style_file_path: &str,
@@ -145,6 +156,7 @@ pub struct IndexedRenderResult {
 ///
 /// # Arguments
 /// * `data` - Grid data values to render
+/// * `mask_data` - Values of the style's mask field on the same grid, if it has one
 /// * `style_file_path` - Full path to the style JSON file
 /// * `style_name` - Optional specific style name; if None, uses the default style
 /// * `width` - Output image width in pixels
@@ -156,18 +168,20 @@ pub struct IndexedRenderResult {
 ///
 /// # Example
 /// ```ignore
-/// let result = render_with_style_file_indexed(&data, "config/styles/temperature.json", None, 256, 256)?;
+/// let result = render_with_style_file_indexed(&data, None, "config/styles/temperature.json", None, 256, 256)?;
 /// let png = renderer::png::create_png_from_precomputed(&result.indices, 256, 256, &result.palette)?;
 /// ```
 pub fn render_with_style_file_indexed(
     data: &[f32],
+    mask_data: Option<&[f32]>,
     style_file_path: &str,
     style_name: Option<&str>,
     width: usize,
     height: usize,
 ) -> Result<IndexedRenderResult, String> {
     let config = StyleConfig::from_file(style_file_path)
-        .map_err(|e| format!("Failed to load style file '{}': {}", style_file_path, e))?;
+        .map_err(|e| format!("Failed to load style file '{}': {}", style_file_path, e))?
+        .with_cvd_variants();
 
     let effective_style_name = style_name.unwrap_or("default");
 
@@ -190,8 +204,11 @@ pub fn render_with_style_file_indexed(
         ));
     }
 
-    // Render to indices
-    let indices = apply_style_gradient_indexed(data, width, height, &palette, style);
+    // Render to indices, hiding pixels rejected by the style's mask
+    let mask = mask_data
+        .zip(style.mask.as_ref())
+        .map(|(values, mask)| MaskInput::new(values, mask));
+    let indices = apply_style_gradient_indexed_masked(data, width, height, &palette, style, mask);
 
     Ok(IndexedRenderResult { indices, palette })
 }
@@ -226,7 +243,8 @@ pub fn render_with_style_file(
     height: usize,
 ) -> Result<Vec<u8>, String> {
     let config = StyleConfig::from_file(style_file_path)
-        .map_err(|e| format!("Failed to load style file '{}': {}", style_file_path, e))?;
+        .map_err(|e| format!("Failed to load style file '{}': {}", style_file_path, e))?
+        .with_cvd_variants();
 
     // Get requested style or default style
     // When style_name is "default" or None, use the default style from the config
@@ -0,0 +1,239 @@
+//! Multi-band composite rendering (e.g., GOES true color, Sandwich).
+//!
+//! Composite layers read their recipe from an `rgb_composite` style, load every
+//! band the recipe needs for the same observation, resample each band onto the
+//! output grid and combine them with [`renderer::composite::CompositeRecipe`].
+//! Packed RGB bands (composited at ingestion) are split into their channels
+//! first, since interpolating packed colors would mix them.
+
+use chrono::{DateTime, Utc};
+use grid_processor::GridProcessorFactory;
+use netcdf_parser::composite::unpack_rgb;
+use renderer::composite::{packed_channel, CompositeRecipe, COMPOSITE_STYLE_TYPE};
+use renderer::style::StyleConfig;
+use std::collections::HashMap;
+use std::time::Instant;
+use storage::{Catalog, CatalogEntry};
+use tracing::{debug, info};
+
+use super::loaders::load_grid_data;
+use super::png_options;
+use super::resampling::resample_grid_for_bbox_with_proj;
+use crate::metrics::MetricsCollector;
+
+/// Load the composite recipe from a style file.
+///
+/// Uses the named style, or the file's default style if `style_name` is None,
+/// empty or "default".
+fn load_composite_recipe(
+    style_file: &str,
+    style_name: Option<&str>,
+) -> Result<CompositeRecipe, String> {
+    let config = StyleConfig::from_file(style_file)
+        .map_err(|e| format!("Failed to load style file {}: {}", style_file, e))?;
+
+    let style = match style_name.filter(|s| !s.is_empty() && *s != "default") {
+        Some(name) => config
+            .get_style(name)
+            .ok_or_else(|| format!("Style '{}' not found in {}", name, style_file))?,
+        None => config
+            .get_default_style()
+            .map(|(_, s)| s)
+            .ok_or_else(|| format!("No styles defined in {}", style_file))?,
+    };
+
+    if style.style_type != COMPOSITE_STYLE_TYPE {
+        return Err(format!(
+            "Style '{}' is not an {} style",
+            style.name, COMPOSITE_STYLE_TYPE
+        ));
+    }
+
+    style
+        .composite
+        .clone()
+        .ok_or_else(|| format!("Style '{}' has no composite recipe", style.name))
+}
+
+/// Render a multi-band composite layer to a PNG image.
+///
+/// # Arguments
+/// - `catalog`: Catalog for finding datasets
+/// - `metrics`: Metrics collector
+/// - `grid_processor_factory`: Factory for Zarr-based grid access
+/// - `model`: Model name (e.g., "goes18")
+/// - `layer_parameter`: Composite parameter name (e.g., "TRUE_COLOR"), for logging
+/// - `observation_time`: Observation time; if None, uses the latest scan
+/// - `width`: Output image width
+/// - `height`: Output image height
+/// - `bbox`: Output bounding box [min_lon, min_lat, max_lon, max_lat]
+/// - `style_file`: Path to the composite style JSON file
+/// - `style_name`: Optional style name within the file
+/// - `use_mercator`: Use Web Mercator projection for resampling
+/// - `requires_full_grid`: Force full grid read (for non-geographic projections)
+pub async fn render_composite_layer(
+    catalog: &Catalog,
+    metrics: &MetricsCollector,
+    grid_processor_factory: &GridProcessorFactory,
+    model: &str,
+    layer_parameter: &str,
+    observation_time: Option<DateTime<Utc>>,
+    width: u32,
+    height: u32,
+    bbox: [f32; 4],
+    style_file: &str,
+    style_name: Option<&str>,
+    use_mercator: bool,
+    requires_full_grid: bool,
+) -> Result<Vec<u8>, String> {
+    let render_start = Instant::now();
+    let recipe = load_composite_recipe(style_file, style_name)?;
+    let bands = recipe.required_bands();
+
+    let entries = find_band_entries(catalog, model, &bands, observation_time).await?;
+    info!(
+        model = model,
+        parameter = layer_parameter,
+        bands = ?bands,
+        reference_time = ?entries[0].reference_time,
+        "Rendering composite layer"
+    );
+
+    let start = Instant::now();
+    let output_size = Some((width as usize, height as usize));
+    let mut grids = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        grids.push(
+            load_grid_data(
+                grid_processor_factory,
+                entry,
+                Some(bbox),
+                output_size,
+                requires_full_grid,
+            )
+            .await?,
+        );
+    }
+    metrics
+        .record_grib_load(start.elapsed().as_micros() as u64)
+        .await;
+
+    let start = Instant::now();
+    let mut band_data: HashMap<String, Vec<f32>> = HashMap::with_capacity(bands.len());
+    for ((band, entry), grid) in bands.iter().zip(&entries).zip(grids) {
+        let data_bounds = grid.bbox.unwrap_or([
+            entry.bbox.min_x as f32,
+            entry.bbox.min_y as f32,
+            entry.bbox.max_x as f32,
+            entry.bbox.max_y as f32,
+        ]);
+        let channels = if recipe.packed_band() == Some(band.as_str()) {
+            unpack_channels(&grid.data)
+                .into_iter()
+                .enumerate()
+                .map(|(index, data)| (packed_channel(band, index), data))
+                .collect()
+        } else {
+            vec![(band.clone(), grid.data)]
+        };
+        for (name, data) in channels {
+            let resampled = resample_grid_for_bbox_with_proj(
+                &data,
+                grid.width,
+                grid.height,
+                width as usize,
+                height as usize,
+                bbox,
+                data_bounds,
+                use_mercator,
+                model,
+                grid.goes_projection.as_ref(),
+                grid.grid_uses_360,
+                grid.coordinates.as_ref(),
+            );
+            band_data.insert(name, resampled);
+        }
+    }
+    metrics
+        .record_resample(start.elapsed().as_micros() as u64)
+        .await;
+
+    let start = Instant::now();
+    let pixels = recipe.render(&band_data, width as usize, height as usize)?;
+    let png = renderer::png::create_png_with_options(
+        &pixels,
+        width as usize,
+        height as usize,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    metrics
+        .record_png_encode(start.elapsed().as_micros() as u64)
+        .await;
+
+    debug!(
+        parameter = layer_parameter,
+        elapsed_ms = render_start.elapsed().as_millis() as u64,
+        "Composite render complete"
+    );
+
+    Ok(png)
+}
+
+/// Split a packed RGB grid into red, green and blue grids in [0, 1], NaN
+/// where the packed grid has no color.
+fn unpack_channels(packed: &[f32]) -> [Vec<f32>; 3] {
+    let mut channels = [
+        Vec::with_capacity(packed.len()),
+        Vec::with_capacity(packed.len()),
+        Vec::with_capacity(packed.len()),
+    ];
+    for &value in packed {
+        let rgb = unpack_rgb(value).unwrap_or([f32::NAN; 3]);
+        for (channel, v) in channels.iter_mut().zip(rgb) {
+            channel.push(v);
+        }
+    }
+    channels
+}
+
+/// Find catalog entries for every band from the same scan.
+///
+/// The first band picks the scan (closest to `observation_time`, or the
+/// latest); all other bands must have data at that exact reference time.
+async fn find_band_entries(
+    catalog: &Catalog,
+    model: &str,
+    bands: &[String],
+    observation_time: Option<DateTime<Utc>>,
+) -> Result<Vec<CatalogEntry>, String> {
+    let (first, rest) = bands
+        .split_first()
+        .ok_or_else(|| "Composite recipe has no bands".to_string())?;
+
+    let first_entry = match observation_time {
+        Some(time) => catalog.find_by_time(model, first, time).await,
+        None => catalog.get_latest_run_earliest_forecast(model, first).await,
+    }
+    .map_err(|e| format!("Catalog query failed: {}", e))?
+    .ok_or_else(|| format!("No data found for {}/{}", model, first))?;
+
+    let scan_time = first_entry.reference_time;
+    let mut entries = vec![first_entry];
+    for band in rest {
+        let entry = catalog
+            .find_by_time(model, band, scan_time)
+            .await
+            .map_err(|e| format!("Catalog query failed: {}", e))?
+            .filter(|e| e.reference_time == scan_time)
+            .ok_or_else(|| {
+                format!(
+                    "No data found for {}/{} at scan time {}",
+                    model, band, scan_time
+                )
+            })?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
@@ -0,0 +1,260 @@
+//! Ensemble product rendering (e.g., probability of 2 m temperature below
+//! freezing).
+//!
+//! An ensemble layer reads every member of its source parameter for the
+//! requested run, forecast hour and level, resamples each onto the output
+//! grid and reduces them cell by cell with
+//! [`grid_processor::EnsembleAccumulator`]. Products are cached by the exact
+//! set of member datasets, so a late member arriving produces a new one.
+
+use grid_processor::{EnsembleAccumulator, GridProcessorFactory, TemporalCompositeCache};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use storage::{Catalog, CatalogEntry};
+use tracing::{debug, info};
+
+use super::colorscales::render_with_style_file_indexed;
+use super::loaders::load_grid_data;
+use super::png_options;
+use super::resampling::resample_grid_for_output;
+use super::temporal::find_requested_entry;
+use crate::layer_config::EnsembleConfig;
+use crate::metrics::MetricsCollector;
+
+/// Cache of reduced ensemble products shared by all requests.
+///
+/// Sized by `ENSEMBLE_CACHE_SIZE_MB` (default 128 MB).
+fn product_cache() -> &'static Mutex<TemporalCompositeCache> {
+    static CACHE: OnceLock<Mutex<TemporalCompositeCache>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        let size_mb = std::env::var("ENSEMBLE_CACHE_SIZE_MB")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(128);
+        Mutex::new(TemporalCompositeCache::new(size_mb * 1024 * 1024))
+    })
+}
+
+/// Render an ensemble layer to a PNG image.
+///
+/// The members are those of the dataset selected by the usual dimensions
+/// (`forecast_hour` and `level`, or the latest run's earliest forecast).
+///
+/// # Arguments
+/// - `catalog`: Catalog for finding datasets
+/// - `metrics`: Metrics collector
+/// - `grid_processor_factory`: Factory for Zarr-based grid access
+/// - `model`: Weather model name
+/// - `layer_parameter`: Layer parameter (for logging)
+/// - `ensemble`: Ensemble settings from the layer config
+/// - `forecast_hour`: Optional forecast hour
+/// - `level`: Optional vertical level
+/// - `width`: Output image width
+/// - `height`: Output image height
+/// - `bbox`: Optional bounding box
+/// - `style_file`: Path to style JSON file (from layer config)
+/// - `style_name`: Optional style name within the file
+/// - `use_mercator`: Use Web Mercator projection for resampling
+/// - `requires_full_grid`: Force full grid read (for non-geographic projections)
+#[allow(clippy::too_many_arguments)]
+pub async fn render_ensemble_product(
+    catalog: &Catalog,
+    metrics: &MetricsCollector,
+    grid_processor_factory: &GridProcessorFactory,
+    model: &str,
+    layer_parameter: &str,
+    ensemble: &EnsembleConfig,
+    forecast_hour: Option<u32>,
+    level: Option<&str>,
+    width: u32,
+    height: u32,
+    bbox: Option<[f32; 4]>,
+    style_file: &str,
+    style_name: Option<&str>,
+    use_mercator: bool,
+    requires_full_grid: bool,
+) -> Result<Vec<u8>, String> {
+    let render_start = Instant::now();
+    let source = ensemble.source.as_str();
+
+    let anchor =
+        find_requested_entry(catalog, model, source, forecast_hour, None, level, false).await?;
+    let mut members = catalog
+        .find_ensemble_members(
+            model,
+            source,
+            anchor.reference_time,
+            anchor.forecast_hour,
+            &anchor.level,
+        )
+        .await
+        .map_err(|e| format!("Catalog query failed: {}", e))?;
+    // A deterministic dataset is not a member
+    members.retain(|e| e.ensemble_member.is_some());
+    if members.is_empty() {
+        return Err(format!(
+            "No ensemble members found for {}/{} at {} f{:03}",
+            model, source, anchor.reference_time, anchor.forecast_hour
+        ));
+    }
+    members.truncate(ensemble.max_members);
+
+    info!(
+        model = model,
+        parameter = layer_parameter,
+        source = source,
+        statistic = %ensemble.statistic,
+        run = %anchor.reference_time,
+        forecast_hour = anchor.forecast_hour,
+        members = members.len(),
+        "Rendering ensemble product"
+    );
+
+    let rendered_width = width as usize;
+    let rendered_height = height as usize;
+    let cache_key = product_cache_key(
+        model,
+        ensemble,
+        &members,
+        bbox,
+        rendered_width,
+        rendered_height,
+        use_mercator,
+    );
+
+    let cached = product_cache()
+        .lock()
+        .map_err(|_| "Ensemble product cache poisoned".to_string())?
+        .get(&cache_key);
+    let product = match cached {
+        Some(product) => {
+            debug!(parameter = layer_parameter, "Ensemble product cache hit");
+            product
+        }
+        None => {
+            let product = Arc::new(
+                reduce_members(
+                    grid_processor_factory,
+                    metrics,
+                    ensemble,
+                    &members,
+                    bbox,
+                    rendered_width,
+                    rendered_height,
+                    use_mercator,
+                    requires_full_grid,
+                )
+                .await?,
+            );
+            product_cache()
+                .lock()
+                .map_err(|_| "Ensemble product cache poisoned".to_string())?
+                .insert(cache_key, product.clone());
+            product
+        }
+    };
+
+    let start = Instant::now();
+    let render_result = render_with_style_file_indexed(
+        &product,
+        None,
+        style_file,
+        style_name,
+        rendered_width,
+        rendered_height,
+    )?;
+    let png = renderer::png::create_png_from_precomputed_with_options(
+        &render_result.indices,
+        rendered_width,
+        rendered_height,
+        &render_result.palette,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    metrics
+        .record_png_encode(start.elapsed().as_micros() as u64)
+        .await;
+
+    debug!(
+        parameter = layer_parameter,
+        elapsed_ms = render_start.elapsed().as_millis() as u64,
+        "Ensemble product render complete"
+    );
+
+    Ok(png)
+}
+
+/// Read, resample and reduce every member.
+#[allow(clippy::too_many_arguments)]
+async fn reduce_members(
+    grid_processor_factory: &GridProcessorFactory,
+    metrics: &MetricsCollector,
+    ensemble: &EnsembleConfig,
+    members: &[CatalogEntry],
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+    requires_full_grid: bool,
+) -> Result<Vec<f32>, String> {
+    let mut accumulator = EnsembleAccumulator::new(ensemble.statistic, width * height);
+
+    for member in members {
+        let start = Instant::now();
+        let grid = load_grid_data(
+            grid_processor_factory,
+            member,
+            bbox,
+            Some((width, height)),
+            requires_full_grid,
+        )
+        .await?;
+        metrics
+            .record_grib_load(start.elapsed().as_micros() as u64)
+            .await;
+
+        let start = Instant::now();
+        let resampled = resample_grid_for_output(&grid, member, bbox, width, height, use_mercator);
+        metrics
+            .record_resample(start.elapsed().as_micros() as u64)
+            .await;
+
+        accumulator.add(&resampled).map_err(|e| e.to_string())?;
+    }
+
+    Ok(accumulator.finish())
+}
+
+/// Cache key identifying everything a product depends on.
+fn product_cache_key(
+    model: &str,
+    ensemble: &EnsembleConfig,
+    members: &[CatalogEntry],
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+) -> String {
+    let mut paths: Vec<&str> = members.iter().map(|e| e.storage_path.as_str()).collect();
+    paths.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    paths.hash(&mut hasher);
+
+    let bbox_key = bbox
+        .map(|b| format!("{},{},{},{}", b[0], b[1], b[2], b[3]))
+        .unwrap_or_else(|| "full".to_string());
+
+    format!(
+        "{}:{}:{}:{:016x}:{}:{}x{}:{}",
+        model,
+        ensemble.source,
+        ensemble.statistic,
+        hasher.finish(),
+        bbox_key,
+        width,
+        height,
+        if use_mercator { "3857" } else { "4326" }
+    )
+}
@@ -9,16 +9,27 @@
 //! - Automatic level generation or explicit level specification
 //! - Unit transformation (e.g., Pa to hPa, K to C)
 //! - Special level highlighting (e.g., 1013 hPa, 0C freezing level)
+//! - Contours traced on HRRR's native Lambert grid when zoomed in past the
+//!   grid resolution, instead of on the resampled output
 
+use projection::LambertConformal;
 use renderer::contour;
-use renderer::style::ContourStyle;
+use renderer::style::{ContourStyle, NativeGridMode};
 use storage::Catalog;
 use tracing::info;
 
 use super::loaders::load_grid_data;
-use super::resampling::resample_grid_for_bbox;
+use super::png_options;
+use super::resampling::{lat_to_mercator_y, resample_grid_for_bbox};
 use grid_processor::GridProcessorFactory;
 
+/// With `native_grid: auto`, contours are traced on the native grid when
+/// there are at most this many grid cells per output pixel.
+const NATIVE_CONTOUR_MAX_CELLS_PER_PIXEL: f64 = 1.0;
+
+/// Points sampled along each bbox edge to find the grid cells it covers.
+const WINDOW_EDGE_SAMPLES: usize = 16;
+
 /// Render isolines (contour lines) for a single tile with optional level.
 pub async fn render_isolines_tile_with_level(
     catalog: &Catalog,
@@ -137,56 +148,12 @@ pub async fn render_isolines_tile_with_level(
         "Rendering isolines"
     );
 
-    // Resample data to the render bbox
-    // Use Mercator projection when rendering for Web Mercator display
-    let resampled_data_raw = resample_grid_for_bbox(
-        &grid_data,
-        grid_width,
-        grid_height,
-        render_width,
-        render_height,
-        render_bbox,
-        data_bounds,
-        use_mercator,
-        model,
-        grid_uses_360,
-    );
-
-    // Apply transform to convert data to display units (e.g., Pa -> hPa, K -> C)
-    // This ensures contour levels (defined in display units) match the data
-    let resampled_data: Vec<f32> = resampled_data_raw
-        .iter()
-        .map(|&v| renderer::style::apply_transform(v, style_config.transform.as_ref()))
-        .collect();
-
     // Also transform min/max for level generation
     let transformed_min =
         renderer::style::apply_transform(min_val, style_config.transform.as_ref());
     let transformed_max =
         renderer::style::apply_transform(max_val, style_config.transform.as_ref());
 
-    // Log resampled data stats for debugging (now in display units)
-    let resampled_valid: Vec<f32> = resampled_data
-        .iter()
-        .filter(|v| !v.is_nan())
-        .copied()
-        .collect();
-    let resampled_min = resampled_valid.iter().fold(f32::INFINITY, |a, &b| a.min(b));
-    let resampled_max = resampled_valid
-        .iter()
-        .fold(f32::NEG_INFINITY, |a, &b| a.max(b));
-    let nan_count = resampled_data.iter().filter(|v| v.is_nan()).count();
-
-    info!(
-        resampled_min = resampled_min,
-        resampled_max = resampled_max,
-        valid_count = resampled_valid.len(),
-        nan_count = nan_count,
-        total = resampled_data.len(),
-        transform = ?style_config.transform,
-        "Resampled data stats for isolines (in display units)"
-    );
-
     // Generate contour levels from style config (using transformed min/max)
     let levels = style_config.generate_levels(transformed_min, transformed_max);
 
@@ -234,13 +201,89 @@ pub async fn render_isolines_tile_with_level(
         special_levels,
     };
 
-    // Render contours
-    let contour_pixels = contour::render_contours(
-        &resampled_data,
-        render_width,
-        render_height,
-        &contour_config,
-    );
+    // Trace contours on HRRR's native grid when the output resolves it
+    let native_window = match style_config.contour.native_grid {
+        NativeGridMode::Never => None,
+        _ if model != "hrrr" => None,
+        mode => LambertWindow::covering(render_bbox, grid_width, grid_height).filter(|w| {
+            mode == NativeGridMode::Always
+                || w.cells_per_pixel(render_width, render_height)
+                    <= NATIVE_CONTOUR_MAX_CELLS_PER_PIXEL
+        }),
+    };
+
+    let contour_pixels = if let Some(window) = native_window {
+        info!(
+            window_width = window.width,
+            window_height = window.height,
+            "Tracing isolines on the native grid"
+        );
+        let window_data: Vec<f32> = window
+            .extract(&grid_data, grid_width)
+            .into_iter()
+            .map(|v| renderer::style::apply_transform(v, style_config.transform.as_ref()))
+            .collect();
+        contour::render_contours_reprojected(
+            &window_data,
+            window.width,
+            window.height,
+            render_width,
+            render_height,
+            &contour_config,
+            |p| window.to_pixel(p, render_bbox, render_width, render_height, use_mercator),
+        )
+    } else {
+        // Resample data to the render bbox
+        // Use Mercator projection when rendering for Web Mercator display
+        let resampled_data_raw = resample_grid_for_bbox(
+            &grid_data,
+            grid_width,
+            grid_height,
+            render_width,
+            render_height,
+            render_bbox,
+            data_bounds,
+            use_mercator,
+            model,
+            grid_uses_360,
+        );
+
+        // Apply transform to convert data to display units (e.g., Pa -> hPa, K -> C)
+        // This ensures contour levels (defined in display units) match the data
+        let resampled_data: Vec<f32> = resampled_data_raw
+            .iter()
+            .map(|&v| renderer::style::apply_transform(v, style_config.transform.as_ref()))
+            .collect();
+
+        // Log resampled data stats for debugging (now in display units)
+        let resampled_valid: Vec<f32> = resampled_data
+            .iter()
+            .filter(|v| !v.is_nan())
+            .copied()
+            .collect();
+        let resampled_min = resampled_valid.iter().fold(f32::INFINITY, |a, &b| a.min(b));
+        let resampled_max = resampled_valid
+            .iter()
+            .fold(f32::NEG_INFINITY, |a, &b| a.max(b));
+        let nan_count = resampled_data.iter().filter(|v| v.is_nan()).count();
+
+        info!(
+            resampled_min = resampled_min,
+            resampled_max = resampled_max,
+            valid_count = resampled_valid.len(),
+            nan_count = nan_count,
+            total = resampled_data.len(),
+            transform = ?style_config.transform,
+            "Resampled data stats for isolines (in display units)"
+        );
+
+        contour::render_contours(
+            &resampled_data,
+            render_width,
+            render_height,
+            &contour_config,
+        )
+    };
 
     // Crop to center tile if we used expanded rendering
     let final_pixels = if let Some((coord, tile_config)) = needs_crop {
@@ -250,6 +293,156 @@ pub async fn render_isolines_tile_with_level(
     };
 
     // Encode as PNG
-    renderer::png::create_png(&final_pixels, width as usize, height as usize)
-        .map_err(|e| format!("PNG encoding failed: {}", e))
+    renderer::png::create_png_with_options(
+        &final_pixels,
+        width as usize,
+        height as usize,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))
+}
+
+/// Block of HRRR Lambert grid cells covering an output bbox.
+#[derive(Debug)]
+struct LambertWindow {
+    proj: LambertConformal,
+    i0: usize,
+    j0: usize,
+    width: usize,
+    height: usize,
+}
+
+impl LambertWindow {
+    /// Cells covering `bbox` (degrees), with a one-cell margin so contours
+    /// run past the edges. None if the bbox misses the grid.
+    fn covering(bbox: [f32; 4], grid_width: usize, grid_height: usize) -> Option<Self> {
+        let proj = LambertConformal::hrrr();
+        let [min_lon, min_lat, max_lon, max_lat] = bbox.map(|v| v as f64);
+
+        let (mut min_i, mut min_j) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_i, mut max_j) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for k in 0..=WINDOW_EDGE_SAMPLES {
+            let t = k as f64 / WINDOW_EDGE_SAMPLES as f64;
+            let lon = min_lon + t * (max_lon - min_lon);
+            let lat = min_lat + t * (max_lat - min_lat);
+            for (lat, lon) in [
+                (min_lat, lon),
+                (max_lat, lon),
+                (lat, min_lon),
+                (lat, max_lon),
+            ] {
+                let (i, j) = proj.geo_to_grid(lat, lon);
+                min_i = min_i.min(i);
+                max_i = max_i.max(i);
+                min_j = min_j.min(j);
+                max_j = max_j.max(j);
+            }
+        }
+
+        let last_i = grid_width.checked_sub(1)? as f64;
+        let last_j = grid_height.checked_sub(1)? as f64;
+        if !(max_i >= 0.0 && max_j >= 0.0 && min_i <= last_i && min_j <= last_j) {
+            return None;
+        }
+        let i0 = (min_i.floor() - 1.0).clamp(0.0, last_i) as usize;
+        let j0 = (min_j.floor() - 1.0).clamp(0.0, last_j) as usize;
+        let i1 = (max_i.ceil() + 1.0).clamp(0.0, last_i) as usize;
+        let j1 = (max_j.ceil() + 1.0).clamp(0.0, last_j) as usize;
+
+        Some(Self {
+            proj,
+            i0,
+            j0,
+            width: i1 - i0 + 1,
+            height: j1 - j0 + 1,
+        })
+    }
+
+    /// Grid cells per output pixel (the larger of the two axes).
+    fn cells_per_pixel(&self, width: usize, height: usize) -> f64 {
+        let x = self.width as f64 / width.max(1) as f64;
+        let y = self.height as f64 / height.max(1) as f64;
+        x.max(y)
+    }
+
+    /// Copy the window out of the full grid.
+    fn extract(&self, data: &[f32], grid_width: usize) -> Vec<f32> {
+        (self.j0..self.j0 + self.height)
+            .flat_map(|j| {
+                let row = j * grid_width + self.i0;
+                data[row..row + self.width].iter().copied()
+            })
+            .collect()
+    }
+
+    /// Output pixel of a point in window grid indices.
+    fn to_pixel(
+        &self,
+        point: contour::Point,
+        bbox: [f32; 4],
+        width: usize,
+        height: usize,
+        use_mercator: bool,
+    ) -> Option<contour::Point> {
+        let (lat, mut lon) = self.proj.grid_to_geo(
+            self.i0 as f64 + point.x as f64,
+            self.j0 as f64 + point.y as f64,
+        );
+        if lon > 180.0 {
+            lon -= 360.0;
+        }
+        if !lat.is_finite() || !lon.is_finite() {
+            return None;
+        }
+
+        let [min_lon, min_lat, max_lon, max_lat] = bbox.map(|v| v as f64);
+        let x_ratio = (lon - min_lon) / (max_lon - min_lon);
+        let y_ratio = if use_mercator {
+            let top = lat_to_mercator_y(max_lat);
+            (top - lat_to_mercator_y(lat)) / (top - lat_to_mercator_y(min_lat))
+        } else {
+            (max_lat - lat) / (max_lat - min_lat)
+        };
+
+        // Output pixel centers sit at +0.5, where the resampled path samples
+        Some(contour::Point::new(
+            (x_ratio * width as f64 - 0.5) as f32,
+            (y_ratio * height as f64 - 0.5) as f32,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lambert_window() {
+        let proj = LambertConformal::hrrr();
+        let (width, height) = proj.dimensions();
+
+        // A small box over Kansas covers a few cells, with margins
+        let bbox = [-98.0, 38.0, -97.5, 38.5];
+        let window = LambertWindow::covering(bbox, width, height).unwrap();
+        assert!(window.width > 2 && window.width < 40, "{:?}", window);
+        assert!(window.height > 2 && window.height < 40, "{:?}", window);
+        assert!(window.cells_per_pixel(256, 256) < NATIVE_CONTOUR_MAX_CELLS_PER_PIXEL);
+        assert!(window.cells_per_pixel(4, 4) > NATIVE_CONTOUR_MAX_CELLS_PER_PIXEL);
+
+        // Grid points map back inside the output
+        let (i, j) = proj.geo_to_grid(38.25, -97.75);
+        let point =
+            contour::Point::new((i - window.i0 as f64) as f32, (j - window.j0 as f64) as f32);
+        let pixel = window.to_pixel(point, bbox, 256, 256, false).unwrap();
+        assert!((pixel.x - 127.5).abs() < 0.5, "{:?}", pixel);
+        assert!((pixel.y - 127.5).abs() < 0.5, "{:?}", pixel);
+
+        // Outside CONUS the grid has no cells
+        assert!(LambertWindow::covering([10.0, 40.0, 11.0, 41.0], width, height).is_none());
+
+        let data: Vec<f32> = (0..width * height).map(|v| v as f32).collect();
+        let extracted = window.extract(&data, width);
+        assert_eq!(extracted.len(), window.width * window.height);
+        assert_eq!(extracted[0], (window.j0 * width + window.i0) as f32);
+    }
 }
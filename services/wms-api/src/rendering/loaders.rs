@@ -13,7 +13,23 @@ use storage::CatalogEntry;
 use tracing::{debug, error, info, instrument};
 
 use super::types::GridData;
-use grid_processor::GridProcessorFactory;
+use grid_processor::{ErrorKind, GridProcessorError, GridProcessorFactory, Provenance};
+
+/// Prefix of messages for grid reads that failed upstream (storage or
+/// catalog), which `WmsError::from_rendering_error` reports as 502 so
+/// clients retry.
+pub const UPSTREAM_UNAVAILABLE: &str = "Upstream unavailable";
+
+/// Describe a failed grid read, keeping its kind recoverable from the
+/// message: a missing dataset reads as missing data and an upstream failure
+/// gets the [`UPSTREAM_UNAVAILABLE`] prefix.
+pub fn grid_read_error(context: &str, e: &GridProcessorError) -> String {
+    match e.kind() {
+        ErrorKind::NotFound => format!("Data not available: {}: {}", context, e),
+        ErrorKind::Upstream => format!("{}: {}: {}", UPSTREAM_UNAVAILABLE, context, e),
+        _ => format!("{}: {}", context, e),
+    }
+}
 
 // ============================================================================
 // ============================================================================
@@ -183,7 +199,7 @@ pub async fn load_grid_data_from_zarr(
                         output_size = ?out_size,
                         "Failed to read multiscale Zarr region"
                     );
-                    format!("Failed to read multiscale Zarr region: {}", e)
+                    grid_read_error("Failed to read multiscale Zarr region", &e)
                 })?;
             let read_duration = start.elapsed();
 
@@ -197,6 +213,11 @@ pub async fn load_grid_data_from_zarr(
                 level, out_size
             );
 
+            // Partial reads are map tiles; warm the tiles around this one
+            if read_bbox != zarr_meta.bbox {
+                ms_factory.spawn_prefetch(level, read_bbox);
+            }
+
             (region, Some(level))
         } else {
             // Only native level available, use standard loading
@@ -262,6 +283,10 @@ pub async fn load_grid_data_from_zarr(
         zarr_meta.units.clone()
     };
 
+    // Regular grids resample from the bbox alone; only carry explicit axes
+    let coordinates = (!region.coordinates.is_regular()).then_some(region.coordinates);
+    let provenance = region.provenance.map(|p| p.with_dataset(entry));
+
     Ok(GridData {
         data: region.data,
         width: region.width,
@@ -270,11 +295,16 @@ pub async fn load_grid_data_from_zarr(
         goes_projection,
         grid_uses_360,
         native_units,
+        coordinates,
+        provenance,
     })
 }
 
 /// Helper to load a region from the native (level 0) Zarr array.
 /// Used when multiscale is not available or not needed.
+///
+/// After a partial read, chunks of the tiles around `read_bbox` are fetched
+/// into the chunk cache in the background.
 async fn load_region_from_native<S>(
     store: S,
     zarr_path: &str,
@@ -300,6 +330,9 @@ where
         chunk_shape: zarr_meta.chunk_shape,
         num_chunks: zarr_meta.num_chunks,
         fill_value: zarr_meta.fill_value,
+        coordinates: zarr_meta.coordinates.clone(),
+        time: zarr_meta.time.clone(),
+        vertical: zarr_meta.vertical.clone(),
     };
 
     // For native loading, we need to append /0 to get level 0
@@ -326,7 +359,7 @@ where
             chunk_shape = ?grid_metadata.chunk_shape,
             "Failed to open Zarr array"
         );
-        format!("Failed to open Zarr: {}", e)
+        grid_read_error("Failed to open Zarr", &e)
     })?;
 
     // Read the region
@@ -338,7 +371,7 @@ where
             bbox = ?read_bbox,
             "Failed to read Zarr region"
         );
-        format!("Failed to read Zarr region: {}", e)
+        grid_read_error("Failed to read Zarr region", &e)
     })?;
     let read_duration = start.elapsed();
 
@@ -350,6 +383,10 @@ where
         "Loaded native Zarr region"
     );
 
+    if *read_bbox != zarr_meta.bbox {
+        processor.spawn_prefetch(*read_bbox);
+    }
+
     Ok(region)
 }
 
@@ -369,18 +406,34 @@ where
 /// * `Ok(Some(value))` - The data value at the point
 /// * `Ok(None)` - Point is outside grid bounds or contains fill/NaN value
 /// * `Err(...)` - Failed to read data
+pub async fn query_point_from_zarr(
+    factory: &GridProcessorFactory,
+    entry: &CatalogEntry,
+    lon: f64,
+    lat: f64,
+) -> Result<Option<f32>, String> {
+    query_point_with_provenance(factory, entry, lon, lat)
+        .await
+        .map(|(value, _)| value)
+}
+
+/// Query a single point value from Zarr storage, along with the dataset and
+/// chunks it was read from.
+///
+/// Same as [`query_point_from_zarr`], for callers that report provenance
+/// (e.g. GetFeatureInfo).
 #[instrument(skip(factory, entry), fields(
     model = %entry.model,
     parameter = %entry.parameter,
     level = %entry.level,
     storage_path = %entry.storage_path,
 ))]
-pub async fn query_point_from_zarr(
+pub async fn query_point_with_provenance(
     factory: &GridProcessorFactory,
     entry: &CatalogEntry,
     lon: f64,
     lat: f64,
-) -> Result<Option<f32>, String> {
+) -> Result<(Option<f32>, Provenance), String> {
     use grid_processor::{create_minio_storage, GridProcessor, ZarrGridProcessor, ZarrMetadata};
 
     // Parse zarr_metadata from catalog entry
@@ -400,13 +453,7 @@ pub async fn query_point_from_zarr(
     let grid_uses_360 = zarr_meta.bbox.min_lon >= 0.0 && zarr_meta.bbox.max_lon > 180.0;
 
     // Normalize longitude to match grid coordinate system
-    let query_lon = if grid_uses_360 && lon < 0.0 {
-        lon + 360.0
-    } else if !grid_uses_360 && lon > 180.0 {
-        lon - 360.0
-    } else {
-        lon
-    };
+    let query_lon = grid_processor::normalize_longitude(lon, grid_uses_360);
 
     debug!(
         lon = lon,
@@ -444,6 +491,9 @@ pub async fn query_point_from_zarr(
         chunk_shape: zarr_meta.chunk_shape,
         num_chunks: zarr_meta.num_chunks,
         fill_value: zarr_meta.fill_value,
+        coordinates: zarr_meta.coordinates.clone(),
+        time: zarr_meta.time.clone(),
+        vertical: zarr_meta.vertical.clone(),
     };
 
     // Create processor with metadata from catalog
@@ -456,6 +506,10 @@ pub async fn query_point_from_zarr(
     )
     .map_err(|e| {
         error!(error = %e, zarr_path = %zarr_path, "Failed to open Zarr array");
+        grid_read_error("Failed to open Zarr", &e)
+    })?;
+    processor.verify_shape().map_err(|e| {
+        error!(error = %e, zarr_path = %zarr_path, "Point query not at native resolution");
         format!("Failed to open Zarr: {}", e)
     })?;
 
@@ -463,7 +517,7 @@ pub async fn query_point_from_zarr(
     let start = Instant::now();
     let value = processor.read_point(query_lon, lat).await.map_err(|e| {
         error!(error = %e, lon = query_lon, lat = lat, "Failed to read point from Zarr");
-        format!("Failed to read point: {}", e)
+        grid_read_error("Failed to read point", &e)
     })?;
     let read_duration = start.elapsed();
 
@@ -475,5 +529,8 @@ pub async fn query_point_from_zarr(
         "Zarr point query complete"
     );
 
-    Ok(value)
+    let provenance = processor
+        .point_provenance(query_lon, lat)
+        .with_dataset(entry);
+    Ok((value, provenance))
 }
@@ -0,0 +1,96 @@
+//! Masking a layer by a secondary field (e.g., precipitation type by rate).
+//!
+//! Styles with a `mask` (see [`renderer::mask`]) hide pixels where another
+//! parameter of the same model fails a threshold. The mask field is loaded
+//! from the same run and valid time as the rendered dataset and resampled
+//! onto the same output grid, so pixels line up one to one.
+
+use grid_processor::GridProcessorFactory;
+use renderer::mask::DataMask;
+use renderer::style::StyleConfig;
+use storage::{Catalog, CatalogEntry};
+use tracing::debug;
+
+use super::loaders::load_grid_data;
+use super::resampling::resample_grid_for_output;
+
+/// Mask configured on a style, if any.
+///
+/// Uses the named style, or the file's default style if `style_name` is None
+/// or "default". Unknown styles are reported by the renderer, not here.
+pub(crate) fn load_style_mask(
+    style_file: &str,
+    style_name: Option<&str>,
+) -> Result<Option<DataMask>, String> {
+    let config = StyleConfig::from_file(style_file)
+        .map_err(|e| format!("Failed to load style file '{}': {}", style_file, e))?
+        .with_cvd_variants();
+
+    let style = match style_name.filter(|s| *s != "default") {
+        Some(name) => config.get_style(name),
+        None => config.get_default_style().map(|(_, s)| s),
+    };
+
+    Ok(style.and_then(|s| s.mask.clone()))
+}
+
+/// Load the mask field for `entry` and resample it onto the output grid.
+///
+/// The mask dataset must come from the same run and valid time as `entry`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn load_mask_data(
+    catalog: &Catalog,
+    grid_processor_factory: &GridProcessorFactory,
+    entry: &CatalogEntry,
+    mask: &DataMask,
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+    requires_full_grid: bool,
+) -> Result<Vec<f32>, String> {
+    let valid_time = entry.reference_time + chrono::Duration::hours(entry.forecast_hour as i64);
+    let mask_entry = catalog
+        .find_runs_for_valid_time(
+            &entry.model,
+            &mask.parameter,
+            valid_time,
+            mask.level.as_deref(),
+        )
+        .await
+        .map_err(|e| format!("Catalog query failed: {}", e))?
+        .into_iter()
+        .find(|e| e.reference_time == entry.reference_time)
+        .ok_or_else(|| {
+            format!(
+                "No mask data found for {}/{} at run {} valid {}",
+                entry.model, mask.parameter, entry.reference_time, valid_time
+            )
+        })?;
+
+    debug!(
+        model = %entry.model,
+        parameter = %mask.parameter,
+        level = %mask_entry.level,
+        storage_path = %mask_entry.storage_path,
+        "Loading mask grid"
+    );
+
+    let grid = load_grid_data(
+        grid_processor_factory,
+        &mask_entry,
+        bbox,
+        Some((width, height)),
+        requires_full_grid,
+    )
+    .await?;
+
+    Ok(resample_grid_for_output(
+        &grid,
+        &mask_entry,
+        bbox,
+        width,
+        height,
+        use_mercator,
+    ))
+}
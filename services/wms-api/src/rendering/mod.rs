@@ -1,11 +1,16 @@
 //! Shared weather data rendering logic.
 
 mod colorscales;
+mod composite;
+mod ensemble;
 mod isolines;
 pub(crate) mod loaders;
+mod mask;
 mod resampling;
 mod sampling;
+mod temporal;
 mod types;
+mod vertical;
 mod wind;
 
 #[cfg(test)]
@@ -14,21 +19,44 @@ mod tests;
 use crate::metrics::{DataSourceType, MetricsCollector};
 use grid_processor::GridProcessorFactory;
 use loaders::load_grid_data;
-use resampling::resample_grid_for_bbox_with_proj;
+use mask::{load_mask_data, load_style_mask};
+use renderer::png::{PngOptions, PngProfile};
+use resampling::{load_style_minify, minify_grid_for_output, resample_grid_for_output};
+use std::sync::OnceLock;
 use std::time::Instant;
 use storage::Catalog;
 use tracing::info;
 
 // Re-export functions for internal use
-pub(crate) use colorscales::render_with_style_file_indexed;
+pub(crate) use colorscales::{clear_palette_cache, render_with_style_file_indexed};
 
 // Re-export public functions from submodules
+pub use composite::render_composite_layer;
+pub use ensemble::render_ensemble_product;
 pub use isolines::render_isolines_tile_with_level;
 pub use sampling::query_point_value;
+pub use temporal::render_temporal_composite;
+pub use vertical::render_vertical_composite;
 pub use wind::{
     render_wind_barbs_layer, render_wind_barbs_tile, render_wind_barbs_tile_with_level,
 };
 
+/// PNG encoder options for images rendered on request (tiles, GetMap).
+///
+/// Loaded once from the `PNG_*` environment variables.
+pub(crate) fn png_options() -> &'static PngOptions {
+    static OPTIONS: OnceLock<PngOptions> = OnceLock::new();
+    OPTIONS.get_or_init(|| PngOptions::from_env(PngProfile::Interactive))
+}
+
+/// PNG encoder options for tiles rendered ahead of time (cache warming).
+///
+/// Loaded once from the `PNG_SEED_*` environment variables.
+pub(crate) fn seeding_png_options() -> &'static PngOptions {
+    static OPTIONS: OnceLock<PngOptions> = OnceLock::new();
+    OPTIONS.get_or_init(|| PngOptions::from_env(PngProfile::Seeding))
+}
+
 /// Render weather data with optional style configuration and level.
 ///
 /// This is a convenience wrapper for callers that don't need observation time.
@@ -243,17 +271,12 @@ pub async fn render_weather_data(
         }
     }
 
-    let grid_data = grid_result.data;
-    let grid_width = grid_result.width;
-    let grid_height = grid_result.height;
-    let goes_projection = grid_result.goes_projection;
-
-    if grid_data.len() != grid_width * grid_height {
+    if grid_result.data.len() != grid_result.width * grid_result.height {
         return Err(format!(
             "Grid data size mismatch: {} vs {}x{}",
-            grid_data.len(),
-            grid_width,
-            grid_height
+            grid_result.data.len(),
+            grid_result.width,
+            grid_result.height
         ));
     }
 
@@ -262,49 +285,27 @@ pub async fn render_weather_data(
     let rendered_width = width as usize;
     let rendered_height = height as usize;
 
-    // Use actual bbox from grid data if available (for Zarr partial reads),
-    // otherwise fall back to entry.bbox (for GRIB2/NetCDF full grid reads)
-    let data_bounds = grid_result.bbox.unwrap_or_else(|| {
-        [
-            entry.bbox.min_x as f32,
-            entry.bbox.min_y as f32,
-            entry.bbox.max_x as f32,
-            entry.bbox.max_y as f32,
-        ]
-    });
-
     let start = Instant::now();
-    let resampled_data = {
-        if let Some(output_bbox) = bbox {
-            // Resample grid data to output bbox using projection-aware resampling
-            resample_grid_for_bbox_with_proj(
-                &grid_data,
-                grid_width,
-                grid_height,
-                rendered_width,
-                rendered_height,
-                output_bbox,
-                data_bounds,
-                use_mercator,
-                model,
-                goes_projection.as_ref(),
-                grid_result.grid_uses_360,
-            )
-        } else {
-            // No bbox - resample entire data grid
-            if grid_width != rendered_width || grid_height != rendered_height {
-                renderer::gradient::resample_grid(
-                    &grid_data,
-                    grid_width,
-                    grid_height,
-                    rendered_width,
-                    rendered_height,
-                )
-            } else {
-                grid_data.clone()
-            }
-        }
-    };
+    // Average noisy fields over each pixel when many grid cells fall in it
+    let mut grid_result = grid_result;
+    if let Some(minify) = load_style_minify(style_file, style_name)? {
+        minify_grid_for_output(
+            &mut grid_result,
+            &entry,
+            bbox,
+            rendered_width,
+            rendered_height,
+            &minify,
+        );
+    }
+    let resampled_data = resample_grid_for_output(
+        &grid_result,
+        &entry,
+        bbox,
+        rendered_width,
+        rendered_height,
+        use_mercator,
+    );
     let resample_duration = start.elapsed();
     let resample_us = resample_duration.as_micros() as u64;
     metrics.record_resample(resample_us).await;
@@ -314,12 +315,32 @@ pub async fn render_weather_data(
         metrics.record_model_resample(wm, resample_us);
     }
 
+    // Secondary field hiding parts of this layer (e.g. precip type where rate is zero)
+    let mask_data = match load_style_mask(style_file, style_name)? {
+        Some(mask) => Some(
+            load_mask_data(
+                catalog,
+                grid_processor_factory,
+                &entry,
+                &mask,
+                bbox,
+                rendered_width,
+                rendered_height,
+                use_mercator,
+                requires_full_grid,
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
     // Apply color rendering using indexed path for optimal performance
     // This uses pre-computed palettes and outputs palette indices directly
     let start = Instant::now();
     let png = {
         let render_result = render_with_style_file_indexed(
             &resampled_data,
+            mask_data.as_deref(),
             style_file,
             style_name,
             rendered_width,
@@ -327,11 +348,12 @@ pub async fn render_weather_data(
         )?;
 
         // Encode to indexed PNG using pre-computed palette
-        renderer::png::create_png_from_precomputed(
+        renderer::png::create_png_from_precomputed_with_options(
             &render_result.indices,
             rendered_width,
             rendered_height,
             &render_result.palette,
+            png_options(),
         )
         .map_err(|e| format!("PNG encoding failed: {}", e))?
     };
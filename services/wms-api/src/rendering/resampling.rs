@@ -7,13 +7,23 @@
 //! - Geographic to Web Mercator (EPSG:3857)
 //! - Lambert Conformal (HRRR) to geographic/Mercator
 //! - Geostationary (GOES) to geographic/Mercator
+//! - Rectilinear grids with explicit (non-uniform) axes to geographic/Mercator
 //!
-//! All resampling uses bilinear interpolation for smooth results.
-
+//! All resampling uses bilinear interpolation for smooth results. Styles can
+//! ask for oversampled requests to be box filtered first (see
+//! [`minify_grid_for_output`]) so noisy fields don't alias.
+
+use grid_processor::{
+    box_filter, normalize_longitude, AxisCoordinates, GridCoordinates, LongitudeAxis, MinifyFilter,
+    MinifyOptions,
+};
+use netcdf_parser::GoesProjection;
 use projection::{Geostationary, LambertConformal};
+use renderer::style::StyleConfig;
+use storage::CatalogEntry;
 use tracing::debug;
 
-use super::types::GoesProjectionParams;
+use super::types::{GoesProjectionParams, GridData};
 
 // ============================================================================
 // Web Mercator coordinate conversions
@@ -108,123 +118,20 @@ pub fn resample_from_geographic(
     data_bounds: [f32; 4],
     grid_uses_360: bool,
 ) -> Vec<f32> {
-    let [out_min_lon, out_min_lat, out_max_lon, out_max_lat] = output_bbox;
-    let [data_min_lon, data_min_lat, data_max_lon, data_max_lat] = data_bounds;
-
-    // Use the explicit grid_uses_360 flag rather than inferring from data_bounds.
-    // This is important because partial reads may return data with bbox that doesn't
-    // span > 180, but the underlying grid still uses 0-360 convention.
-    let data_uses_360 = grid_uses_360;
-
-    // Data grid resolution
-    let data_lon_range = data_max_lon - data_min_lon;
-    let data_lat_range = data_max_lat - data_min_lat;
-
-    let mut output = vec![f32::NAN; output_width * output_height];
-
-    // For each output pixel, calculate its geographic position and sample from data grid
-    for out_y in 0..output_height {
-        for out_x in 0..output_width {
-            // Calculate geographic coordinates of this output pixel (pixel center)
-            let x_ratio = (out_x as f32 + 0.5) / output_width as f32;
-            let y_ratio = (out_y as f32 + 0.5) / output_height as f32;
-
-            let lon = out_min_lon + x_ratio * (out_max_lon - out_min_lon);
-            let lat = out_max_lat - y_ratio * (out_max_lat - out_min_lat); // Y is inverted
-
-            // Check if this is a global grid (covers nearly 360 degrees)
-            let is_global_grid = data_uses_360 && (data_max_lon - data_min_lon) > 359.0;
-
-            // Normalize longitude for data grids that use 0-360 convention
-            // For tiles that span negative longitudes, we need to add 360 to map them
-            // to the 0-360 grid.
-            let norm_lon = if data_uses_360 && lon < 0.0 {
-                lon + 360.0
-            } else if data_uses_360 && lon >= 0.0 && lon < 1.0 && data_min_lon > 180.0 {
-                // Special case: data is from the "wrapped" region (e.g., 343-360)
-                // and we're at lon near 0, which should map to near 360
-                lon + 360.0
-            } else {
-                lon
-            };
-
-            // For global grids, handle the gap between grid end (e.g., 359.75°) and 360°
-            // by treating longitudes in this gap as valid and wrapping interpolation
-            let in_wrap_gap = is_global_grid && norm_lon > data_max_lon && norm_lon < 360.0;
-
-            // Check if this pixel is within data bounds (with special handling for wrap gap)
-            if !in_wrap_gap {
-                if norm_lon < data_min_lon
-                    || norm_lon > data_max_lon
-                    || lat < data_min_lat
-                    || lat > data_max_lat
-                {
-                    // Outside data coverage - leave as NaN for transparent rendering
-                    continue;
-                }
-            } else {
-                // In wrap gap - only check latitude bounds
-                if lat < data_min_lat || lat > data_max_lat {
-                    continue;
-                }
-            }
-
-            // Convert to data grid coordinates (continuous, not indices)
-            // For the wrap gap, calculate position relative to the last grid cell
-            let grid_x = if in_wrap_gap {
-                // In the gap between last grid point and 360°
-                // Map to position past the last column, interpolation will wrap to column 0
-                let gap_start = data_max_lon;
-                let gap_size = 360.0 - data_max_lon + data_min_lon; // Gap wraps around
-                let pos_in_gap = norm_lon - gap_start;
-                (data_width as f32 - 1.0) + (pos_in_gap / gap_size) as f32
-            } else {
-                (norm_lon - data_min_lon) / data_lon_range * data_width as f32
-            };
-            let grid_y = (data_max_lat - lat) / data_lat_range * data_height as f32;
-
-            // Bilinear interpolation from data grid
-            let x1 = grid_x.floor() as usize;
-            let y1 = grid_y.floor() as usize;
-
-            // For global grids (0-360), wrap x2 around instead of clamping
-            // This ensures smooth interpolation across the prime meridian
-            let x2 = if is_global_grid && x1 + 1 >= data_width {
-                0 // Wrap to column 0
-            } else {
-                (x1 + 1).min(data_width - 1)
-            };
-            let y2 = (y1 + 1).min(data_height - 1);
-
-            // Bounds check
-            if x1 >= data_width || y1 >= data_height {
-                continue;
-            }
-
-            let dx = grid_x - x1 as f32;
-            let dy = grid_y - y1 as f32;
-
-            // Sample four surrounding grid points
-            let v11 = data.get(y1 * data_width + x1).copied().unwrap_or(f32::NAN);
-            let v21 = data.get(y1 * data_width + x2).copied().unwrap_or(f32::NAN);
-            let v12 = data.get(y2 * data_width + x1).copied().unwrap_or(f32::NAN);
-            let v22 = data.get(y2 * data_width + x2).copied().unwrap_or(f32::NAN);
-
-            // Skip interpolation if any corner is NaN
-            if v11.is_nan() || v21.is_nan() || v12.is_nan() || v22.is_nan() {
-                continue;
-            }
-
-            // Bilinear interpolation
-            let v1 = v11 * (1.0 - dx) + v21 * dx;
-            let v2 = v12 * (1.0 - dx) + v22 * dx;
-            let value = v1 * (1.0 - dy) + v2 * dy;
-
-            output[out_y * output_width + out_x] = value;
-        }
-    }
+    let [_, out_min_lat, _, out_max_lat] = output_bbox;
 
-    output
+    resample_lat_lon_grid(
+        data,
+        data_width,
+        data_height,
+        output_width,
+        output_height,
+        output_bbox,
+        data_bounds,
+        grid_uses_360,
+        // Y is inverted
+        |y_ratio| out_max_lat - y_ratio * (out_max_lat - out_min_lat),
+    )
 }
 
 /// Resample from geographic grid for Web Mercator (EPSG:3857) output
@@ -244,109 +151,173 @@ pub fn resample_for_mercator(
     data_bounds: [f32; 4],
     grid_uses_360: bool,
 ) -> Vec<f32> {
-    let [out_min_lon, out_min_lat, out_max_lon, out_max_lat] = output_bbox;
-    let [data_min_lon, data_min_lat, data_max_lon, data_max_lat] = data_bounds;
-
-    // Use the explicit grid_uses_360 flag rather than inferring from data_bounds.
-    // This is important because partial reads may return data with bbox that doesn't
-    // span > 180, but the underlying grid still uses 0-360 convention.
-    let data_uses_360 = grid_uses_360;
+    let [_, out_min_lat, _, out_max_lat] = output_bbox;
 
     // Convert lat bounds to Mercator Y coordinates
     let min_merc_y = lat_to_mercator_y(out_min_lat as f64);
     let max_merc_y = lat_to_mercator_y(out_max_lat as f64);
 
-    // Data grid resolution
-    let data_lon_range = data_max_lon - data_min_lon;
+    resample_lat_lon_grid(
+        data,
+        data_width,
+        data_height,
+        output_width,
+        output_height,
+        output_bbox,
+        data_bounds,
+        grid_uses_360,
+        // y_ratio 0 = top = max_merc_y, y_ratio 1 = bottom = min_merc_y
+        |y_ratio| mercator_y_to_lat(max_merc_y - y_ratio as f64 * (max_merc_y - min_merc_y)) as f32,
+    )
+}
+
+/// Sample a regular lat/lon grid at the center of every output pixel.
+///
+/// Longitude is linear across the output bbox; `row_lat` maps the vertical
+/// position of a pixel center (0 at the top, 1 at the bottom) to latitude.
+///
+/// Longitudes are normalized to the grid's convention using the explicit
+/// `grid_uses_360` flag rather than inferring it from data_bounds: partial
+/// reads may return data with a bbox that doesn't span > 180, but the
+/// underlying grid still uses 0-360. Global grids wrap interpolation between
+/// their last and first column (see [`LongitudeAxis`]), so tiles on either
+/// side of the seam sample the same cells.
+#[allow(clippy::too_many_arguments)]
+fn resample_lat_lon_grid(
+    data: &[f32],
+    data_width: usize,
+    data_height: usize,
+    output_width: usize,
+    output_height: usize,
+    output_bbox: [f32; 4],
+    data_bounds: [f32; 4],
+    grid_uses_360: bool,
+    row_lat: impl Fn(f32) -> f32,
+) -> Vec<f32> {
+    let [out_min_lon, _, out_max_lon, _] = output_bbox;
+    let [data_min_lon, data_min_lat, data_max_lon, data_max_lat] = data_bounds;
+
+    let lon_axis = LongitudeAxis::new(data_min_lon as f64, data_max_lon as f64, data_width);
     let data_lat_range = data_max_lat - data_min_lat;
 
     let mut output = vec![f32::NAN; output_width * output_height];
 
+    // Column positions only depend on the output column
+    let grid_xs: Vec<Option<f64>> = (0..output_width)
+        .map(|out_x| {
+            let x_ratio = (out_x as f64 + 0.5) / output_width as f64;
+            let lon = out_min_lon as f64 + x_ratio * (out_max_lon - out_min_lon) as f64;
+            lon_axis.column(normalize_longitude(lon, grid_uses_360))
+        })
+        .collect();
+
     for out_y in 0..output_height {
-        for out_x in 0..output_width {
-            // Calculate position in output image (pixel center)
-            let x_ratio = (out_x as f32 + 0.5) / output_width as f32;
-            let y_ratio = (out_y as f32 + 0.5) / output_height as f32;
+        let y_ratio = (out_y as f32 + 0.5) / output_height as f32;
+        let lat = row_lat(y_ratio);
+        if lat < data_min_lat || lat > data_max_lat {
+            // Outside data coverage - leave as NaN for transparent rendering
+            continue;
+        }
+        let grid_y = ((data_max_lat - lat) / data_lat_range * data_height as f32) as f64;
 
-            // Longitude is linear
-            let lon = out_min_lon + x_ratio * (out_max_lon - out_min_lon);
+        for (out_x, grid_x) in grid_xs.iter().enumerate() {
+            let Some(grid_x) = *grid_x else { continue };
+            output[out_y * output_width + out_x] =
+                lon_axis.bilinear(data, data_height, grid_x, grid_y);
+        }
+    }
 
-            // Y position is in Mercator space, need to convert to latitude
-            // y_ratio 0 = top = max_merc_y, y_ratio 1 = bottom = min_merc_y
-            let merc_y = max_merc_y - y_ratio as f64 * (max_merc_y - min_merc_y);
-            let lat = mercator_y_to_lat(merc_y) as f32;
-
-            // Check if this is a global grid (covers nearly 360 degrees)
-            let is_global_grid = data_uses_360 && (data_max_lon - data_min_lon) > 359.0;
-
-            // Normalize longitude for data grids that use 0-360 convention
-            // For tiles that span negative longitudes, we need to add 360 to map them
-            // to the 0-360 grid.
-            let norm_lon = if data_uses_360 && lon < 0.0 {
-                lon + 360.0
-            } else if data_uses_360 && lon >= 0.0 && lon < 1.0 && data_min_lon > 180.0 {
-                // Special case: data is from the "wrapped" region (e.g., 343-360)
-                // and we're at lon near 0, which should map to near 360
-                lon + 360.0
-            } else {
-                lon
-            };
+    output
+}
 
-            // For global grids, handle the gap between grid end (e.g., 359.75°) and 360°
-            // by treating longitudes in this gap as valid and wrapping interpolation
-            let in_wrap_gap = is_global_grid && norm_lon > data_max_lon && norm_lon < 360.0;
-
-            // Check if this pixel is within data bounds (with special handling for wrap gap)
-            if !in_wrap_gap {
-                if norm_lon < data_min_lon
-                    || norm_lon > data_max_lon
-                    || lat < data_min_lat
-                    || lat > data_max_lat
-                {
-                    // Outside data coverage - leave as NaN for transparent rendering
-                    continue;
-                }
-            } else {
-                // In wrap gap - only check latitude bounds
-                if lat < data_min_lat || lat > data_max_lat {
-                    continue;
-                }
-            }
+/// Resample from a rectilinear grid with explicit per-axis coordinates
+///
+/// Used for grids whose points are not uniformly spaced (e.g., Gaussian
+/// latitudes), where the position of each row/column cannot be derived from
+/// the data bounds. Each output pixel is located on the source grid by
+/// searching the coordinate axes, then bilinearly interpolated.
+/// Pixels outside the axes are set to NaN for transparent rendering.
+pub fn resample_from_coordinates(
+    data: &[f32],
+    data_width: usize,
+    data_height: usize,
+    output_width: usize,
+    output_height: usize,
+    output_bbox: [f32; 4],
+    coordinates: &GridCoordinates,
+    use_mercator: bool,
+    grid_uses_360: bool,
+) -> Vec<f32> {
+    let [out_min_lon, out_min_lat, out_max_lon, out_max_lat] = output_bbox;
+    let (out_min_lon, out_max_lon) = (out_min_lon as f64, out_max_lon as f64);
+    let (out_min_lat, out_max_lat) = (out_min_lat as f64, out_max_lat as f64);
 
-            // Convert to data grid coordinates
-            // For the wrap gap, calculate position relative to the last grid cell
-            let grid_x = if in_wrap_gap {
-                // In the gap between last grid point and 360°
-                // Map to position past the last column, interpolation will wrap to column 0
-                let gap_start = data_max_lon;
-                let gap_size = 360.0 - data_max_lon + data_min_lon; // Gap wraps around
-                let pos_in_gap = norm_lon - gap_start;
-                (data_width as f32 - 1.0) + (pos_in_gap / gap_size) as f32
-            } else {
-                (norm_lon - data_min_lon) / data_lon_range * data_width as f32
-            };
-            let grid_y = (data_max_lat - lat) / data_lat_range * data_height as f32;
+    let min_merc_y = lat_to_mercator_y(out_min_lat);
+    let max_merc_y = lat_to_mercator_y(out_max_lat);
 
-            // Bilinear interpolation
-            let x1 = grid_x.floor() as usize;
-            let y1 = grid_y.floor() as usize;
+    let mut output = vec![f32::NAN; output_width * output_height];
+    if coordinates.lon.len() != data_width || coordinates.lat.len() != data_height {
+        debug!(
+            data_width = data_width,
+            data_height = data_height,
+            lon_points = coordinates.lon.len(),
+            lat_points = coordinates.lat.len(),
+            "Grid coordinates do not match data dimensions"
+        );
+        return output;
+    }
 
-            // For global grids (0-360), wrap x2 around instead of clamping
-            // This ensures smooth interpolation across the prime meridian
-            let x2 = if is_global_grid && x1 + 1 >= data_width {
-                0 // Wrap to column 0
+    // Row positions only depend on the output row
+    let grid_ys: Vec<Option<f64>> = (0..output_height)
+        .map(|out_y| {
+            let y_ratio = (out_y as f64 + 0.5) / output_height as f64;
+            let lat = if use_mercator {
+                mercator_y_to_lat(max_merc_y - y_ratio * (max_merc_y - min_merc_y))
             } else {
-                (x1 + 1).min(data_width - 1)
+                out_max_lat - y_ratio * (out_max_lat - out_min_lat)
             };
-            let y2 = (y1 + 1).min(data_height - 1);
+            coordinates.lat.fractional_index(lat)
+        })
+        .collect();
+
+    // Regularly spaced longitudes of a global grid (e.g. a Gaussian grid)
+    // wrap around the seam like any other global grid
+    let wrap_axis = match &coordinates.lon {
+        AxisCoordinates::Regular { start, step, count } => {
+            Some(LongitudeAxis::regular(*start, *step, *count)).filter(LongitudeAxis::is_global)
+        }
+        AxisCoordinates::Explicit { .. } => None,
+    };
 
-            // Bounds check
-            if x1 >= data_width || y1 >= data_height {
-                continue;
+    let grid_xs: Vec<Option<f64>> = (0..output_width)
+        .map(|out_x| {
+            let x_ratio = (out_x as f64 + 0.5) / output_width as f64;
+            let lon = out_min_lon + x_ratio * (out_max_lon - out_min_lon);
+            let norm_lon = normalize_longitude(lon, grid_uses_360);
+            match wrap_axis {
+                Some(axis) => axis.column(norm_lon),
+                None => coordinates.lon.fractional_index(norm_lon),
             }
+        })
+        .collect();
+
+    for (out_y, grid_y) in grid_ys.iter().enumerate() {
+        let Some(grid_y) = *grid_y else { continue };
+        for (out_x, grid_x) in grid_xs.iter().enumerate() {
+            let Some(grid_x) = *grid_x else { continue };
+
+            let (x1, x2, dx) = match wrap_axis {
+                Some(axis) => axis.neighbours(grid_x),
+                None => {
+                    let x1 = grid_x.floor() as usize;
+                    (x1, (x1 + 1).min(data_width - 1), grid_x - x1 as f64)
+                }
+            };
+            let y1 = grid_y.floor() as usize;
+            let y2 = (y1 + 1).min(data_height - 1);
 
-            let dx = grid_x - x1 as f32;
-            let dy = grid_y - y1 as f32;
+            let dx = dx as f32;
+            let dy = (grid_y - y1 as f64) as f32;
 
             let v11 = data.get(y1 * data_width + x1).copied().unwrap_or(f32::NAN);
             let v21 = data.get(y1 * data_width + x2).copied().unwrap_or(f32::NAN);
@@ -360,9 +331,7 @@ pub fn resample_for_mercator(
 
             let v1 = v11 * (1.0 - dx) + v21 * dx;
             let v2 = v12 * (1.0 - dx) + v22 * dx;
-            let value = v1 * (1.0 - dy) + v2 * dy;
-
-            output[out_y * output_width + out_x] = value;
+            output[out_y * output_width + out_x] = v1 * (1.0 - dy) + v2 * dy;
         }
     }
 
@@ -398,10 +367,15 @@ pub fn resample_grid_for_bbox(
         model,
         None,
         grid_uses_360,
+        None,
     )
 }
 
 /// Resample grid data for a given bbox, with optional GOES projection parameters
+///
+/// `coordinates` carries explicit axes for non-uniformly spaced geographic
+/// grids; when present (and not regular) it takes precedence over
+/// `data_bounds` for locating grid points.
 pub fn resample_grid_for_bbox_with_proj(
     data: &[f32],
     data_width: usize,
@@ -414,7 +388,30 @@ pub fn resample_grid_for_bbox_with_proj(
     model: &str,
     goes_projection: Option<&GoesProjectionParams>,
     grid_uses_360: bool,
+    coordinates: Option<&GridCoordinates>,
 ) -> Vec<f32> {
+    if let Some(coords) = coordinates.filter(|c| !c.is_regular()) {
+        if model != "hrrr" && goes_projection.is_none() {
+            debug!(
+                model = model,
+                data_width = data_width,
+                data_height = data_height,
+                "Using explicit grid coordinates for resampling"
+            );
+            return resample_from_coordinates(
+                data,
+                data_width,
+                data_height,
+                output_width,
+                output_height,
+                output_bbox,
+                coords,
+                use_mercator,
+                grid_uses_360,
+            );
+        }
+    }
+
     // Use Lambert Conformal resampling for HRRR (native projection)
     if model == "hrrr" {
         debug!(
@@ -446,7 +443,7 @@ pub fn resample_grid_for_bbox_with_proj(
                 output_bbox,
             )
         }
-    } else if model == "goes16" || model == "goes18" || model == "goes" {
+    } else if model.starts_with("goes") {
         // GOES satellite data handling
         // If goes_projection is present, data is in native geostationary projection (raw NetCDF)
         // If goes_projection is None, data has been pre-projected to geographic (Zarr)
@@ -546,6 +543,48 @@ pub fn resample_grid_for_bbox_with_proj(
     }
 }
 
+/// Resample loaded grid data onto the output image grid.
+///
+/// With an output bbox this uses projection-aware resampling from the grid's
+/// actual bounds (partial Zarr reads) or the catalog entry's bbox; without
+/// one the entire grid is stretched to the output size.
+pub fn resample_grid_for_output(
+    grid: &GridData,
+    entry: &CatalogEntry,
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+) -> Vec<f32> {
+    let data_bounds = grid.bbox.unwrap_or([
+        entry.bbox.min_x as f32,
+        entry.bbox.min_y as f32,
+        entry.bbox.max_x as f32,
+        entry.bbox.max_y as f32,
+    ]);
+
+    match bbox {
+        Some(output_bbox) => resample_grid_for_bbox_with_proj(
+            &grid.data,
+            grid.width,
+            grid.height,
+            width,
+            height,
+            output_bbox,
+            data_bounds,
+            use_mercator,
+            &entry.model,
+            grid.goes_projection.as_ref(),
+            grid.grid_uses_360,
+            grid.coordinates.as_ref(),
+        ),
+        None if grid.width != width || grid.height != height => {
+            renderer::gradient::resample_grid(&grid.data, grid.width, grid.height, width, height)
+        }
+        None => grid.data.clone(),
+    }
+}
+
 /// Model-aware resampling for geographic output (used for wind barbs and other non-Mercator rendering)
 ///
 /// This wraps the projection-specific resampling functions to handle different grid types.
@@ -569,8 +608,11 @@ pub fn resample_for_model_geographic(
             output_height,
             output_bbox,
         )
-    } else if model == "goes16" || model == "goes18" || model == "goes" {
-        let satellite_lon = if model == "goes18" { -137.2 } else { -75.0 };
+    } else if model.starts_with("goes") {
+        let satellite_lon = ingestion::build_satellite_registry()
+            .get(model)
+            .map(|s| s.longitude)
+            .unwrap_or_else(|| GoesProjection::default().longitude_origin);
         resample_geostationary_to_geographic(
             data,
             data_width,
@@ -594,6 +636,89 @@ pub fn resample_for_model_geographic(
     }
 }
 
+// ============================================================================
+// Minification (anti-aliasing of oversampled requests)
+// ============================================================================
+
+/// Minification filter configured on a style, if any.
+///
+/// Uses the named style, or the file's default style if `style_name` is None
+/// or "default".
+pub fn load_style_minify(
+    style_file: &str,
+    style_name: Option<&str>,
+) -> Result<Option<MinifyOptions>, String> {
+    let config = StyleConfig::from_file(style_file)
+        .map_err(|e| format!("Failed to load style file '{}': {}", style_file, e))?
+        .with_cvd_variants();
+
+    let style = match style_name.filter(|s| *s != "default") {
+        Some(name) => config.get_style(name),
+        None => config.get_default_style().map(|(_, s)| s),
+    };
+
+    Ok(style
+        .and_then(|s| s.minify.as_ref())
+        .map(|m| MinifyOptions {
+            filter: if m.is_box() {
+                MinifyFilter::Box
+            } else {
+                MinifyFilter::Bilinear
+            },
+            threshold: m.threshold,
+        }))
+}
+
+/// Box filter grid data that will be sampled at many cells per pixel.
+///
+/// The source-to-output ratio compares the grid spacing (from its bounds)
+/// with the output pixel size, so for projected grids (Lambert, GOES) it is
+/// an approximation; that is close enough to size a smoothing window.
+/// Returns the window used, if any.
+pub fn minify_grid_for_output(
+    grid: &mut GridData,
+    entry: &CatalogEntry,
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    options: &MinifyOptions,
+) -> Option<(usize, usize)> {
+    if grid.width == 0 || grid.height == 0 || width == 0 || height == 0 {
+        return None;
+    }
+
+    let ratio = match bbox {
+        Some([min_x, min_y, max_x, max_y]) => {
+            let [data_min_x, data_min_y, data_max_x, data_max_y] = grid.bbox.unwrap_or([
+                entry.bbox.min_x as f32,
+                entry.bbox.min_y as f32,
+                entry.bbox.max_x as f32,
+                entry.bbox.max_y as f32,
+            ]);
+            let cell_x = (data_max_x - data_min_x).abs() as f64 / grid.width as f64;
+            let cell_y = (data_max_y - data_min_y).abs() as f64 / grid.height as f64;
+            (
+                (max_x - min_x).abs() as f64 / width as f64 / cell_x,
+                (max_y - min_y).abs() as f64 / height as f64 / cell_y,
+            )
+        }
+        // The whole grid is stretched onto the output
+        None => (
+            grid.width as f64 / width as f64,
+            grid.height as f64 / height as f64,
+        ),
+    };
+
+    let window = options.window(ratio)?;
+    grid.data = box_filter(&grid.data, grid.width, grid.height, window);
+    debug!(
+        ratio = ?ratio,
+        window = ?window,
+        "Box filtered grid for minified output"
+    );
+    Some(window)
+}
+
 // ============================================================================
 // Lambert Conformal projection resampling (HRRR)
 // ============================================================================
@@ -779,12 +904,8 @@ fn resample_geostationary_to_geographic(
     output_bbox: [f32; 4],
     satellite_lon: f64,
 ) -> Vec<f32> {
-    // Create GOES projection based on satellite position (fallback if no dynamic projection)
-    let proj = if satellite_lon < -100.0 {
-        Geostationary::goes18_conus()
-    } else {
-        Geostationary::goes16_conus()
-    };
+    // CONUS sector of the satellite (fallback if no dynamic projection)
+    let proj = Geostationary::goes_conus(satellite_lon);
     resample_geostationary_to_geographic_with_proj(
         data,
         data_width,
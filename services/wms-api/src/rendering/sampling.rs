@@ -12,12 +12,12 @@ use projection::{Geostationary, LambertConformal};
 use storage::Catalog;
 use tracing::info;
 
-use super::loaders::{load_grid_data, query_point_from_zarr};
+use super::loaders::{load_grid_data, query_point_from_zarr, query_point_with_provenance};
 use super::resampling::bilinear_interpolate;
 use super::types::GoesProjectionParams;
 use crate::layer_config::LayerConfigRegistry;
 use crate::metrics::MetricsCollector;
-use grid_processor::GridProcessorFactory;
+use grid_processor::{wrap_longitude, GridProcessorFactory, Provenance};
 
 // ============================================================================
 // Public query functions
@@ -95,15 +95,24 @@ pub async fn query_point_value(
         "GetFeatureInfo query"
     );
 
-    // Handle special composite layers
-    if parameter == "WIND_BARBS" {
-        return query_wind_barbs_value(
+    // Vector layers (e.g. wind barbs) report speed and direction computed
+    // from both components rather than either component alone
+    let layer_config = layer_configs.get_layer_by_param(model, &parameter);
+    let components = layer_config
+        .and_then(|lc| lc.vector_components())
+        .or_else(|| (parameter == "WIND_BARBS").then_some(("UGRD", "VGRD")));
+    if let Some(components) = components {
+        return query_vector_value(
             catalog,
             grid_processor_factory,
+            layer,
             model,
+            components,
+            layer_config.map(|lc| &lc.units),
             lon,
             lat,
             forecast_hour,
+            valid_time,
             level,
         )
         .await;
@@ -173,6 +182,7 @@ pub async fn query_point_value(
 
     // Load and sample grid data from Zarr
     // For point sampling, we always load full grid (efficient query happens in query_point_from_zarr)
+    let provenance;
     let value = if is_netcdf {
         // Handle NetCDF (GOES satellite) data via Zarr path
         let grid_result = load_grid_data(grid_processor_factory, &entry, None, None, true).await?;
@@ -181,6 +191,7 @@ pub async fn query_point_value(
         let grid_height = grid_result.height;
         let goes_projection = grid_result.goes_projection;
         let grid_bbox = grid_result.bbox;
+        provenance = grid_result.provenance;
 
         // Sample value at the point using projection-aware sampling
         sample_grid_value_with_projection(
@@ -195,7 +206,10 @@ pub async fn query_point_value(
         )?
     } else {
         // Use Zarr for efficient point query (reads only one chunk)
-        match query_point_from_zarr(grid_processor_factory, &entry, lon, lat).await? {
+        let (value, point_provenance) =
+            query_point_with_provenance(grid_processor_factory, &entry, lon, lat).await?;
+        provenance = Some(point_provenance);
+        match value {
             Some(v) => v,
             None => {
                 // Point outside grid or fill value - return no-data response
@@ -212,7 +226,9 @@ pub async fn query_point_value(
                     },
                     forecast_hour: Some(entry.forecast_hour),
                     reference_time: Some(entry.reference_time.to_rfc3339()),
+                    valid_time: Some(entry.valid_time().to_rfc3339()),
                     level: Some(entry.level.clone()),
+                    provenance: provenance.map(feature_provenance),
                 }]);
             }
         }
@@ -236,7 +252,9 @@ pub async fn query_point_value(
             },
             forecast_hour: Some(entry.forecast_hour),
             reference_time: Some(entry.reference_time.to_rfc3339()),
+            valid_time: Some(entry.valid_time().to_rfc3339()),
             level: Some(entry.level.clone()),
+            provenance: provenance.map(feature_provenance),
         }]);
     }
 
@@ -247,9 +265,6 @@ pub async fn query_point_value(
         .and_then(|m| m.get("units").and_then(|v| v.as_str()))
         .unwrap_or("");
 
-    // Get layer config for display unit preferences
-    let layer_config = layer_configs.get_layer_by_param(model, &parameter);
-
     // Create unit config using native units from Zarr + display from layer config
     let unit_config =
         UnitConfig::from_zarr_and_config(native_units, layer_config.map(|lc| &lc.units));
@@ -271,134 +286,217 @@ pub async fn query_point_value(
         },
         forecast_hour: Some(entry.forecast_hour),
         reference_time: Some(entry.reference_time.to_rfc3339()),
+        valid_time: Some(entry.valid_time().to_rfc3339()),
         level: Some(entry.level.clone()),
+        provenance: provenance.map(feature_provenance),
     }])
 }
 
-/// Query wind barbs value (combines UGRD and VGRD) using Zarr data
-pub async fn query_wind_barbs_value(
+/// Query a vector layer (e.g. wind barbs from UGRD and VGRD) at a point.
+///
+/// Both components are read from the same run, forecast hour and level, and
+/// returned as separate speed and direction features. Speed is converted to
+/// the layer's display units; direction is where the flow comes from, in
+/// degrees clockwise from north.
+#[allow(clippy::too_many_arguments)]
+pub async fn query_vector_value(
     catalog: &Catalog,
     grid_processor_factory: &GridProcessorFactory,
+    layer: &str,
     model: &str,
+    (u_parameter, v_parameter): (&str, &str),
+    units: Option<&UnitConfig>,
     lon: f64,
     lat: f64,
     forecast_hour: Option<u32>,
+    valid_time: Option<chrono::DateTime<chrono::Utc>>,
     level: Option<&str>,
 ) -> Result<Vec<wms_protocol::FeatureInfo>, String> {
     use wms_protocol::{FeatureInfo, Location};
 
-    // Load U component, optionally at a specific level
-    let u_entry = match (forecast_hour, level) {
-        (Some(hour), Some(lev)) => catalog
-            .find_by_forecast_hour_and_level(model, "UGRD", hour, lev)
-            .await
-            .map_err(|e| format!("Failed to get UGRD: {}", e))?
-            .ok_or_else(|| format!("No UGRD data available for hour {} level {}", hour, lev))?,
-        (Some(hour), None) => catalog
-            .find_by_forecast_hour(model, "UGRD", hour)
-            .await
-            .map_err(|e| format!("Failed to get UGRD: {}", e))?
-            .ok_or_else(|| format!("No UGRD data available for hour {}", hour))?,
-        (None, Some(lev)) => catalog
-            .get_latest_run_earliest_forecast_at_level(model, "UGRD", lev)
-            .await
-            .map_err(|e| format!("Failed to get UGRD: {}", e))?
-            .ok_or_else(|| format!("No UGRD data available at level {}", lev))?,
-        (None, None) => catalog
-            .get_latest_run_earliest_forecast(model, "UGRD")
-            .await
-            .map_err(|e| format!("Failed to get UGRD: {}", e))?
-            .ok_or_else(|| "No UGRD data available".to_string())?,
-    };
-
-    // Load V component, optionally at a specific level
-    let v_entry = match (forecast_hour, level) {
-        (Some(hour), Some(lev)) => catalog
-            .find_by_forecast_hour_and_level(model, "VGRD", hour, lev)
-            .await
-            .map_err(|e| format!("Failed to get VGRD: {}", e))?
-            .ok_or_else(|| format!("No VGRD data available for hour {} level {}", hour, lev))?,
-        (Some(hour), None) => catalog
-            .find_by_forecast_hour(model, "VGRD", hour)
-            .await
-            .map_err(|e| format!("Failed to get VGRD: {}", e))?
-            .ok_or_else(|| format!("No VGRD data available for hour {}", hour))?,
-        (None, Some(lev)) => catalog
-            .get_latest_run_earliest_forecast_at_level(model, "VGRD", lev)
-            .await
-            .map_err(|e| format!("Failed to get VGRD: {}", e))?
-            .ok_or_else(|| format!("No VGRD data available at level {}", lev))?,
-        (None, None) => catalog
-            .get_latest_run_earliest_forecast(model, "VGRD")
-            .await
-            .map_err(|e| format!("Failed to get VGRD: {}", e))?
-            .ok_or_else(|| "No VGRD data available".to_string())?,
-    };
+    let u_entry = find_component_entry(
+        catalog,
+        model,
+        u_parameter,
+        forecast_hour,
+        valid_time,
+        level,
+    )
+    .await?;
+    let v_entry = catalog
+        .find_by_run_and_forecast_hour(
+            model,
+            v_parameter,
+            u_entry.reference_time,
+            u_entry.forecast_hour,
+            Some(&u_entry.level),
+        )
+        .await
+        .map_err(|e| format!("Failed to get {}: {}", v_parameter, e))?
+        .ok_or_else(|| {
+            format!(
+                "No {} data matching {} for run {} hour {} level {}",
+                v_parameter,
+                u_parameter,
+                u_entry.reference_time.to_rfc3339(),
+                u_entry.forecast_hour,
+                u_entry.level
+            )
+        })?;
 
     // Ensure both components have Zarr metadata
-    if u_entry.zarr_metadata.is_none() {
-        return Err(
-            "UGRD data is not available - missing Zarr metadata (ingestion may be incomplete)"
-                .to_string(),
-        );
-    }
-    if v_entry.zarr_metadata.is_none() {
-        return Err(
-            "VGRD data is not available - missing Zarr metadata (ingestion may be incomplete)"
-                .to_string(),
-        );
+    for entry in [&u_entry, &v_entry] {
+        if entry.zarr_metadata.is_none() {
+            return Err(format!(
+                "{} data is not available - missing Zarr metadata (ingestion may be incomplete)",
+                entry.parameter
+            ));
+        }
     }
 
     // Query U and V values from Zarr (efficient single-chunk reads)
     let u = query_point_from_zarr(grid_processor_factory, &u_entry, lon, lat)
         .await?
-        .ok_or_else(|| format!("No UGRD data at point ({}, {})", lon, lat))?;
-
+        .ok_or_else(|| format!("No {} data at point ({}, {})", u_parameter, lon, lat))?;
     let v = query_point_from_zarr(grid_processor_factory, &v_entry, lon, lat)
         .await?
-        .ok_or_else(|| format!("No VGRD data at point ({}, {})", lon, lat))?;
+        .ok_or_else(|| format!("No {} data at point ({}, {})", v_parameter, lon, lat))?;
 
-    // Calculate speed and direction
-    let speed = (u * u + v * v).sqrt();
-    let direction_rad = v.atan2(u);
-    let direction_deg = direction_rad.to_degrees();
-    // Convert from mathematical angle to meteorological (from north, clockwise)
-    let wind_direction = (270.0 - direction_deg).rem_euclid(360.0);
+    let (speed, direction) = vector_speed_and_direction(u, v);
 
-    Ok(vec![
-        FeatureInfo {
-            layer_name: format!("{}_WIND_BARBS", model),
-            parameter: "Wind Speed".to_string(),
-            value: speed as f64,
-            unit: "m/s".to_string(),
-            raw_value: speed as f64,
-            raw_unit: "m/s".to_string(),
-            location: Location {
-                longitude: lon,
-                latitude: lat,
-            },
-            forecast_hour: Some(u_entry.forecast_hour),
-            reference_time: Some(u_entry.reference_time.to_rfc3339()),
-            level: Some(u_entry.level.clone()),
-        },
+    let native_units = u_entry
+        .zarr_metadata
+        .as_ref()
+        .and_then(|m| m.get("units").and_then(|v| v.as_str()))
+        .unwrap_or("m/s");
+    let unit_config = UnitConfig::from_zarr_and_config(native_units, units);
+
+    // "U Wind Component" -> "Wind"
+    let u_name = get_parameter_display_name(u_parameter);
+    let name = u_name
+        .strip_prefix("U ")
+        .and_then(|n| n.strip_suffix(" Component"))
+        .unwrap_or("Vector");
+
+    let feature = |quantity: &str, value: f64, unit: &str, raw_value: f64, raw_unit: &str| {
         FeatureInfo {
-            layer_name: format!("{}_WIND_BARBS", model),
-            parameter: "Wind Direction".to_string(),
-            value: wind_direction as f64,
-            unit: "degrees".to_string(),
-            raw_value: wind_direction as f64,
-            raw_unit: "degrees".to_string(),
+            layer_name: layer.to_string(),
+            parameter: format!("{} {}", name, quantity),
+            value,
+            unit: unit.to_string(),
+            raw_value,
+            raw_unit: raw_unit.to_string(),
             location: Location {
                 longitude: lon,
                 latitude: lat,
             },
             forecast_hour: Some(u_entry.forecast_hour),
             reference_time: Some(u_entry.reference_time.to_rfc3339()),
+            valid_time: Some(u_entry.valid_time().to_rfc3339()),
             level: Some(u_entry.level.clone()),
-        },
+            // Derived from two datasets
+            provenance: None,
+        }
+    };
+
+    Ok(vec![
+        feature(
+            "Speed",
+            unit_config.conversion.apply(speed as f64),
+            unit_config.effective_display(),
+            speed as f64,
+            &unit_config.native,
+        ),
+        feature(
+            "Direction",
+            direction as f64,
+            "degrees",
+            direction as f64,
+            "degrees",
+        ),
     ])
 }
 
+/// Dataset of one vector component. Observation times take priority over
+/// forecast hours; without either the latest run's earliest forecast is used.
+async fn find_component_entry(
+    catalog: &Catalog,
+    model: &str,
+    parameter: &str,
+    forecast_hour: Option<u32>,
+    valid_time: Option<chrono::DateTime<chrono::Utc>>,
+    level: Option<&str>,
+) -> Result<storage::CatalogEntry, String> {
+    let entry = match (valid_time, forecast_hour, level) {
+        (Some(time), _, Some(lev)) => {
+            catalog
+                .find_by_time_and_level(model, parameter, time, lev)
+                .await
+        }
+        (Some(time), _, None) => catalog.find_by_time(model, parameter, time).await,
+        (None, Some(hour), Some(lev)) => {
+            catalog
+                .find_by_forecast_hour_and_level(model, parameter, hour, lev)
+                .await
+        }
+        (None, Some(hour), None) => catalog.find_by_forecast_hour(model, parameter, hour).await,
+        (None, None, Some(lev)) => {
+            catalog
+                .get_latest_run_earliest_forecast_at_level(model, parameter, lev)
+                .await
+        }
+        (None, None, None) => {
+            catalog
+                .get_latest_run_earliest_forecast(model, parameter)
+                .await
+        }
+    };
+
+    entry
+        .map_err(|e| format!("Failed to get {}: {}", parameter, e))?
+        .ok_or_else(|| {
+            let mut message = format!("No {} data available", parameter);
+            if let Some(time) = valid_time {
+                message.push_str(&format!(" at time {}", time.to_rfc3339()));
+            } else if let Some(hour) = forecast_hour {
+                message.push_str(&format!(" for hour {}", hour));
+            }
+            if let Some(lev) = level {
+                message.push_str(&format!(" at level {}", lev));
+            }
+            message
+        })
+}
+
+/// Speed and meteorological direction (degrees clockwise from north that
+/// the flow comes from) of a U/V vector.
+pub fn vector_speed_and_direction(u: f32, v: f32) -> (f32, f32) {
+    let speed = (u * u + v * v).sqrt();
+    // Mathematical angle of the vector, turned to point where it comes from
+    let direction = (270.0 - v.atan2(u).to_degrees()).rem_euclid(360.0);
+    (speed, direction)
+}
+
+/// GetFeatureInfo form of a read's provenance.
+fn feature_provenance(provenance: Provenance) -> wms_protocol::FeatureProvenance {
+    wms_protocol::FeatureProvenance {
+        dataset_id: provenance.dataset_id,
+        source: provenance.source,
+        pyramid_level: provenance.pyramid_level,
+        chunk_keys: provenance.chunk_keys,
+        lineage: provenance
+            .lineage
+            .map(|lineage| wms_protocol::FeatureLineage {
+                url: lineage.url,
+                file: lineage.file,
+                sha256: lineage.sha256,
+                message_offset: lineage.message_offset,
+                message_length: lineage.message_length,
+                ingestion_version: lineage.ingestion_version,
+            }),
+    }
+}
+
 // ============================================================================
 // Grid sampling functions
 // ============================================================================
@@ -426,8 +524,8 @@ pub fn sample_grid_value(
     let lon_step = 360.0 / grid_width as f64;
     let lat_step = 180.0 / grid_height as f64;
 
-    // Normalize longitude to 0-360
-    let norm_lon = if lon < 0.0 { lon + 360.0 } else { lon };
+    // Normalize longitude to [0, 360); 360 itself is column 0 again
+    let norm_lon = wrap_longitude(lon, 0.0);
 
     // Convert to grid coordinates
     let grid_x = norm_lon / lon_step;
@@ -537,7 +635,7 @@ pub fn sample_grid_value_with_projection(
     // Handle GOES geostationary projection
     // Only use geostationary projection if we have projection params
     // If goes_projection is None, the data is already reprojected to geographic coordinates
-    if model == "goes16" || model == "goes18" || model == "goes" {
+    if model.starts_with("goes") {
         if let Some(params) = goes_projection {
             let proj = Geostationary::from_goes(
                 params.perspective_point_height,
@@ -769,3 +867,31 @@ pub fn convert_parameter_value(parameter: &str, value: f32) -> (f64, String, Str
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_speed_and_direction() {
+        // Meteorological convention: direction the wind blows from
+        let cases = [
+            ((0.0, -5.0), (5.0, 0.0)),  // from the north
+            ((-5.0, 0.0), (5.0, 90.0)), // from the east
+            ((0.0, 5.0), (5.0, 180.0)), // from the south
+            ((5.0, 0.0), (5.0, 270.0)), // from the west
+            ((3.0, 4.0), (5.0, 216.87)),
+        ];
+        for ((u, v), (speed, direction)) in cases {
+            let (s, d) = vector_speed_and_direction(u, v);
+            assert!((s - speed).abs() < 1e-4, "speed of ({}, {}) = {}", u, v, s);
+            assert!(
+                (d - direction).abs() < 0.01,
+                "direction of ({}, {}) = {}",
+                u,
+                v,
+                d
+            );
+        }
+    }
+}
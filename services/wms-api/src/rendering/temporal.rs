@@ -0,0 +1,323 @@
+//! Temporal composite rendering (e.g., max reflectivity over the last hour).
+//!
+//! A temporal layer reads every dataset of its source parameter whose valid
+//! time falls in the requested window, resamples each onto the output grid and
+//! reduces them cell by cell with [`grid_processor::TemporalAccumulator`].
+//! Reduced grids are cached by the exact set of datasets they were built from,
+//! so a new scan arriving naturally produces a new composite.
+
+use chrono::{DateTime, Utc};
+use grid_processor::{GridProcessorFactory, TemporalAccumulator, TemporalCompositeCache};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use storage::{Catalog, CatalogEntry};
+use tracing::{debug, info};
+
+use super::colorscales::render_with_style_file_indexed;
+use super::loaders::load_grid_data;
+use super::png_options;
+use super::resampling::resample_grid_for_output;
+use crate::layer_config::TemporalConfig;
+use crate::metrics::MetricsCollector;
+
+/// Cache of reduced composites shared by all requests.
+///
+/// Sized by `TEMPORAL_CACHE_SIZE_MB` (default 256 MB).
+fn composite_cache() -> &'static Mutex<TemporalCompositeCache> {
+    static CACHE: OnceLock<Mutex<TemporalCompositeCache>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        let size_mb = std::env::var("TEMPORAL_CACHE_SIZE_MB")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(256);
+        Mutex::new(TemporalCompositeCache::new(size_mb * 1024 * 1024))
+    })
+}
+
+/// Render a temporal composite layer to a PNG image.
+///
+/// The window ends at the dataset selected by the usual dimensions
+/// (`observation_time` for observations, `forecast_hour` for forecast models,
+/// latest data otherwise) and reaches back by the requested `window`, or the
+/// layer's default window.
+///
+/// # Arguments
+/// - `catalog`: Catalog for finding datasets
+/// - `metrics`: Metrics collector
+/// - `grid_processor_factory`: Factory for Zarr-based grid access
+/// - `model`: Weather model name
+/// - `layer_parameter`: Layer parameter (for logging)
+/// - `temporal`: Temporal composite settings from the layer config
+/// - `window`: Requested WINDOW dimension value
+/// - `forecast_hour`: Optional forecast hour ending the window
+/// - `observation_time`: Optional observation time ending the window
+/// - `level`: Optional vertical level
+/// - `width`: Output image width
+/// - `height`: Output image height
+/// - `bbox`: Optional bounding box
+/// - `style_file`: Path to style JSON file (from layer config)
+/// - `style_name`: Optional style name within the file
+/// - `use_mercator`: Use Web Mercator projection for resampling
+/// - `requires_full_grid`: Force full grid read (for non-geographic projections)
+/// - `is_observational`: Observation model (windows span scans rather than
+///   forecast hours of one run)
+#[allow(clippy::too_many_arguments)]
+pub async fn render_temporal_composite(
+    catalog: &Catalog,
+    metrics: &MetricsCollector,
+    grid_processor_factory: &GridProcessorFactory,
+    model: &str,
+    layer_parameter: &str,
+    temporal: &TemporalConfig,
+    window: Option<&str>,
+    forecast_hour: Option<u32>,
+    observation_time: Option<DateTime<Utc>>,
+    level: Option<&str>,
+    width: u32,
+    height: u32,
+    bbox: Option<[f32; 4]>,
+    style_file: &str,
+    style_name: Option<&str>,
+    use_mercator: bool,
+    requires_full_grid: bool,
+    is_observational: bool,
+) -> Result<Vec<u8>, String> {
+    let render_start = Instant::now();
+    let (window_name, window_duration) = temporal.resolve_window(window)?;
+    let source = temporal.source.as_str();
+
+    let anchor = find_requested_entry(
+        catalog,
+        model,
+        source,
+        forecast_hour,
+        observation_time,
+        level,
+        is_observational,
+    )
+    .await?;
+    let end = anchor.valid_time();
+    let start = end - window_duration;
+
+    // Forecast windows stay within the anchor's run; observations span scans
+    let reference_time = (!is_observational).then_some(anchor.reference_time);
+    let mut entries = catalog
+        .find_in_valid_time_range(
+            model,
+            source,
+            start,
+            end,
+            reference_time,
+            Some(&anchor.level),
+        )
+        .await
+        .map_err(|e| format!("Catalog query failed: {}", e))?;
+    if entries.is_empty() {
+        entries.push(anchor);
+    }
+    if entries.len() > temporal.max_datasets {
+        // Keep the most recent datasets
+        entries.drain(..entries.len() - temporal.max_datasets);
+    }
+
+    info!(
+        model = model,
+        parameter = layer_parameter,
+        source = source,
+        reducer = %temporal.reducer,
+        window = %window_name,
+        window_end = ?end,
+        datasets = entries.len(),
+        "Rendering temporal composite"
+    );
+
+    let rendered_width = width as usize;
+    let rendered_height = height as usize;
+    let cache_key = composite_cache_key(
+        model,
+        temporal,
+        &window_name,
+        &entries,
+        bbox,
+        rendered_width,
+        rendered_height,
+        use_mercator,
+    );
+
+    let cached = composite_cache()
+        .lock()
+        .map_err(|_| "Temporal composite cache poisoned".to_string())?
+        .get(&cache_key);
+    let composite = match cached {
+        Some(composite) => {
+            debug!(parameter = layer_parameter, "Temporal composite cache hit");
+            composite
+        }
+        None => {
+            let composite = Arc::new(
+                reduce_entries(
+                    grid_processor_factory,
+                    metrics,
+                    temporal,
+                    &entries,
+                    bbox,
+                    rendered_width,
+                    rendered_height,
+                    use_mercator,
+                    requires_full_grid,
+                )
+                .await?,
+            );
+            composite_cache()
+                .lock()
+                .map_err(|_| "Temporal composite cache poisoned".to_string())?
+                .insert(cache_key, composite.clone());
+            composite
+        }
+    };
+
+    let start = Instant::now();
+    let render_result = render_with_style_file_indexed(
+        &composite,
+        None,
+        style_file,
+        style_name,
+        rendered_width,
+        rendered_height,
+    )?;
+    let png = renderer::png::create_png_from_precomputed_with_options(
+        &render_result.indices,
+        rendered_width,
+        rendered_height,
+        &render_result.palette,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    metrics
+        .record_png_encode(start.elapsed().as_micros() as u64)
+        .await;
+
+    debug!(
+        parameter = layer_parameter,
+        elapsed_ms = render_start.elapsed().as_millis() as u64,
+        "Temporal composite render complete"
+    );
+
+    Ok(png)
+}
+
+/// Find the dataset selected by the request's dimensions: the end of a
+/// temporal window, or the run and forecast hour of an ensemble product.
+pub(super) async fn find_requested_entry(
+    catalog: &Catalog,
+    model: &str,
+    source: &str,
+    forecast_hour: Option<u32>,
+    observation_time: Option<DateTime<Utc>>,
+    level: Option<&str>,
+    is_observational: bool,
+) -> Result<CatalogEntry, String> {
+    let entry = match (observation_time, forecast_hour, level) {
+        (Some(time), _, _) => catalog.find_by_time(model, source, time).await,
+        (None, Some(hour), Some(lev)) => {
+            catalog
+                .find_by_forecast_hour_and_level(model, source, hour, lev)
+                .await
+        }
+        (None, Some(hour), None) => catalog.find_by_forecast_hour(model, source, hour).await,
+        (None, None, _) if is_observational => catalog.get_latest(model, source).await,
+        (None, None, Some(lev)) => {
+            catalog
+                .get_latest_run_earliest_forecast_at_level(model, source, lev)
+                .await
+        }
+        (None, None, None) => {
+            catalog
+                .get_latest_run_earliest_forecast(model, source)
+                .await
+        }
+    }
+    .map_err(|e| format!("Catalog query failed: {}", e))?;
+
+    entry.ok_or_else(|| format!("No data found for {}/{}", model, source))
+}
+
+/// Read, resample and reduce every dataset in the window.
+#[allow(clippy::too_many_arguments)]
+async fn reduce_entries(
+    grid_processor_factory: &GridProcessorFactory,
+    metrics: &MetricsCollector,
+    temporal: &TemporalConfig,
+    entries: &[CatalogEntry],
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+    requires_full_grid: bool,
+) -> Result<Vec<f32>, String> {
+    let mut accumulator = TemporalAccumulator::new(temporal.reducer, width * height);
+
+    for entry in entries {
+        let start = Instant::now();
+        let grid = load_grid_data(
+            grid_processor_factory,
+            entry,
+            bbox,
+            Some((width, height)),
+            requires_full_grid,
+        )
+        .await?;
+        metrics
+            .record_grib_load(start.elapsed().as_micros() as u64)
+            .await;
+
+        let start = Instant::now();
+        let resampled = resample_grid_for_output(&grid, entry, bbox, width, height, use_mercator);
+        metrics
+            .record_resample(start.elapsed().as_micros() as u64)
+            .await;
+
+        accumulator.add(&resampled).map_err(|e| e.to_string())?;
+    }
+
+    Ok(accumulator.finish())
+}
+
+/// Cache key identifying everything a composite depends on.
+///
+/// Dataset paths are hashed rather than listed; a window can hold hundreds.
+#[allow(clippy::too_many_arguments)]
+fn composite_cache_key(
+    model: &str,
+    temporal: &TemporalConfig,
+    window: &str,
+    entries: &[CatalogEntry],
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+) -> String {
+    let mut paths: Vec<&str> = entries.iter().map(|e| e.storage_path.as_str()).collect();
+    paths.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    paths.hash(&mut hasher);
+
+    let bbox_key = bbox
+        .map(|b| format!("{},{},{},{}", b[0], b[1], b[2], b[3]))
+        .unwrap_or_else(|| "full".to_string());
+
+    format!(
+        "{}:{}:{}:{}:{:016x}:{}:{}x{}:{}",
+        model,
+        temporal.source,
+        temporal.reducer,
+        window,
+        hasher.finish(),
+        bbox_key,
+        width,
+        height,
+        if use_mercator { "3857" } else { "4326" }
+    )
+}
@@ -1,9 +1,10 @@
 //! Tests for resampling functions.
 
 use crate::rendering::resampling::{
-    bilinear_interpolate, lat_to_mercator_y, mercator_y_to_lat, resample_for_mercator,
-    resample_from_geographic,
+    bilinear_interpolate, lat_to_mercator_y, load_style_minify, mercator_y_to_lat,
+    resample_for_mercator, resample_from_coordinates, resample_from_geographic,
 };
+use grid_processor::{AxisCoordinates, GridCoordinates, MinifyFilter};
 
 // ============================================================================
 // Web Mercator conversion tests
@@ -221,3 +222,205 @@ fn test_resample_for_mercator_high_latitude() {
         "Should have valid values at high latitudes"
     );
 }
+
+// ============================================================================
+// Antimeridian regression tiles
+// ============================================================================
+
+/// Field of the dateline regression grids: smooth and periodic in longitude,
+/// so any seam or misplaced column shows up as a wrong value.
+fn dateline_field(lon: f64) -> f32 {
+    (280.0 + 10.0 * lon.to_radians().cos() + 3.0 * (3.0 * lon).to_radians().sin()) as f32
+}
+
+/// 1° global grid in either longitude convention, with bounds stopping at
+/// the last column as decoded from GRIB2 (0 to 359, or -180 to 179).
+fn dateline_grid(uses_360: bool) -> (Vec<f32>, [f32; 4]) {
+    let min_lon = if uses_360 { 0.0 } else { -180.0 };
+    let data = (0..181)
+        .flat_map(|_| (0..360).map(move |col| dateline_field(min_lon + col as f64)))
+        .collect();
+    (data, [min_lon as f32, -90.0, min_lon as f32 + 359.0, 90.0])
+}
+
+/// WGS84 bounds of a Web Mercator tile.
+fn tile_bounds(z: u32, x: u32, y: u32) -> [f32; 4] {
+    let n = 2f64.powi(z as i32);
+    let lat = |y: f64| {
+        (std::f64::consts::PI * (1.0 - 2.0 * y / n))
+            .sinh()
+            .atan()
+            .to_degrees()
+    };
+    [
+        (x as f64 / n * 360.0 - 180.0) as f32,
+        lat(y as f64 + 1.0) as f32,
+        ((x + 1) as f64 / n * 360.0 - 180.0) as f32,
+        lat(y as f64) as f32,
+    ]
+}
+
+/// Check every pixel of a rendered tile against the analytic field.
+fn assert_matches_field(tile: &[f32], width: usize, bbox: [f32; 4], context: &str) {
+    for (i, value) in tile.iter().enumerate() {
+        let x_ratio = ((i % width) as f64 + 0.5) / width as f64;
+        let lon = bbox[0] as f64 + x_ratio * (bbox[2] - bbox[0]) as f64;
+        let expected = dateline_field(lon);
+        assert!(
+            (value - expected).abs() < 0.01,
+            "{}: pixel {} at lon {:.4} is {}, expected {}",
+            context,
+            i,
+            lon,
+            value,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_tiles_seamless_across_antimeridian_and_prime_meridian() {
+    const SIZE: usize = 256;
+    for uses_360 in [true, false] {
+        let (data, bounds) = dateline_grid(uses_360);
+        for z in 1..=6 {
+            let n = 1u32 << z;
+            let y = n / 2 - 1;
+            // Both sides of 180° and of 0°
+            for x in [n - 1, 0, n / 2 - 1, n / 2] {
+                let bbox = tile_bounds(z, x, y);
+                let tile =
+                    resample_for_mercator(&data, 360, 181, SIZE, SIZE, bbox, bounds, uses_360);
+                assert_matches_field(
+                    &tile,
+                    SIZE,
+                    bbox,
+                    &format!("uses_360={} tile {}/{}/{}", uses_360, z, x, y),
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_geographic_bbox_straddling_antimeridian() {
+    for uses_360 in [true, false] {
+        let (data, bounds) = dateline_grid(uses_360);
+        // Clients wrapping the map ask for longitudes past ±180
+        for bbox in [
+            [170.0f32, -10.0, 190.0, 10.0],
+            [-190.0, -10.0, -170.0, 10.0],
+            [179.5, -1.0, 180.5, 1.0],
+        ] {
+            let result = resample_from_geographic(&data, 360, 181, 64, 16, bbox, bounds, uses_360);
+            assert_matches_field(
+                &result,
+                64,
+                bbox,
+                &format!("uses_360={} bbox {:?}", uses_360, bbox),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_partial_region_does_not_wrap() {
+    // Region read from a 0.25° GFS grid: columns 1000..1100 (250° to 275°)
+    let data = vec![1.0f32; 100 * 20];
+    let bounds = [250.0f32, 30.0, 275.0, 35.0];
+    let output_bbox = [-115.0f32, 30.0, -80.0, 35.0];
+
+    let result = resample_from_geographic(&data, 100, 20, 35, 5, output_bbox, bounds, true);
+
+    // Only the 25 columns of output between -110 and -85 are covered
+    for row in result.chunks(35) {
+        let covered: Vec<bool> = row.iter().map(|v| !v.is_nan()).collect();
+        assert_eq!(covered.iter().filter(|c| **c).count(), 25);
+        assert!(!covered[0] && !covered[34]);
+    }
+}
+
+// ============================================================================
+// Explicit coordinate resampling tests
+// ============================================================================
+
+#[test]
+fn test_resample_from_coordinates_non_uniform_latitudes() {
+    // 2x3 grid with rows at 10°, 8° and 0° latitude (non-uniform spacing)
+    let data = vec![0.0f32, 0.0, 10.0, 10.0, 20.0, 20.0];
+    let coords = GridCoordinates {
+        lon: AxisCoordinates::regular(0.0, 1.0, 2),
+        lat: AxisCoordinates::explicit(vec![10.0, 8.0, 0.0]),
+    };
+    let output_bbox = [0.0f32, 0.0, 1.0, 10.0];
+
+    // Output pixel centers at 9°, 7°, 5°, 3°, 1° latitude
+    let result = resample_from_coordinates(&data, 2, 3, 1, 5, output_bbox, &coords, false, false);
+
+    let expected = [5.0, 11.25, 13.75, 16.25, 18.75];
+    for (value, expected) in result.iter().zip(expected) {
+        assert!(
+            (value - expected).abs() < 0.001,
+            "Expected {}, got {}",
+            expected,
+            value
+        );
+    }
+}
+
+#[test]
+fn test_resample_from_coordinates_outside_axes() {
+    let data = vec![1.0f32; 4];
+    let coords = GridCoordinates {
+        lon: AxisCoordinates::explicit(vec![0.0, 2.0]),
+        lat: AxisCoordinates::explicit(vec![2.0, 0.0]),
+    };
+    let output_bbox = [10.0f32, 10.0, 20.0, 20.0];
+
+    let result = resample_from_coordinates(&data, 2, 2, 2, 2, output_bbox, &coords, false, false);
+
+    assert!(result.iter().all(|v| v.is_nan()));
+}
+
+// ============================================================================
+// Minification tests
+// ============================================================================
+
+#[test]
+fn test_load_style_minify() {
+    let style_path = std::env::temp_dir().join("test_style_minify.json");
+    let style_content = r##"{
+        "version": "1.0",
+        "styles": {
+            "speckle": {
+                "name": "Reflectivity",
+                "type": "gradient",
+                "default": true,
+                "stops": [
+                    { "value": 0.0, "color": "#000000" },
+                    { "value": 70.0, "color": "#FFFFFF" }
+                ],
+                "minify": { "filter": "box", "threshold": 3 }
+            },
+            "plain": {
+                "name": "Plain",
+                "type": "gradient",
+                "stops": [
+                    { "value": 0.0, "color": "#000000" },
+                    { "value": 70.0, "color": "#FFFFFF" }
+                ]
+            }
+        }
+    }"##;
+    std::fs::write(&style_path, style_content).unwrap();
+    let path = style_path.to_str().unwrap();
+
+    let default = load_style_minify(path, None);
+    let plain = load_style_minify(path, Some("plain"));
+    let _ = std::fs::remove_file(&style_path);
+
+    let minify = default.unwrap().unwrap();
+    assert_eq!(minify.filter, MinifyFilter::Box);
+    assert_eq!(minify.threshold, 3.0);
+    assert!(plain.unwrap().is_none());
+}
@@ -15,6 +15,12 @@ pub struct GridData {
     /// Native units from the data source (e.g., "K", "Pa", "%").
     /// Read from Zarr metadata as the authoritative source of truth.
     pub native_units: String,
+    /// Explicit coordinates for grids that are not uniformly spaced
+    /// (e.g., Gaussian latitudes). None for regular grids, whose points
+    /// follow from `bbox` and the dimensions.
+    pub coordinates: Option<grid_processor::GridCoordinates>,
+    /// Dataset, file and chunks the data was read from
+    pub provenance: Option<grid_processor::Provenance>,
 }
 
 /// Dynamic GOES projection parameters extracted from NetCDF file
@@ -0,0 +1,257 @@
+//! Column composite rendering (e.g., max temperature between 1000 and
+//! 500 mb).
+//!
+//! A column layer reads every level of its source parameter inside the
+//! requested range, for one run and forecast hour, resamples each onto the
+//! output grid and reduces them cell by cell with
+//! [`grid_processor::VerticalAccumulator`]. Composites are cached by the
+//! exact set of level datasets, so they are only recomputed when the range,
+//! output grid or underlying data changes.
+
+use grid_processor::{
+    GridProcessorFactory, LevelRange, TemporalCompositeCache, VerticalAccumulator,
+};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use storage::{Catalog, CatalogEntry};
+use tracing::{debug, info};
+
+use super::colorscales::render_with_style_file_indexed;
+use super::loaders::load_grid_data;
+use super::png_options;
+use super::resampling::resample_grid_for_output;
+use super::temporal::find_requested_entry;
+use crate::layer_config::VerticalConfig;
+use crate::metrics::MetricsCollector;
+
+/// Cache of reduced column composites shared by all requests.
+///
+/// Sized by `VERTICAL_CACHE_SIZE_MB` (default 128 MB).
+fn composite_cache() -> &'static Mutex<TemporalCompositeCache> {
+    static CACHE: OnceLock<Mutex<TemporalCompositeCache>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        let size_mb = std::env::var("VERTICAL_CACHE_SIZE_MB")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(128);
+        Mutex::new(TemporalCompositeCache::new(size_mb * 1024 * 1024))
+    })
+}
+
+/// Render a column composite layer to a PNG image.
+///
+/// The levels are those of the run selected by `forecast_hour` (or the
+/// latest run's earliest forecast) that fall inside `range`.
+///
+/// # Arguments
+/// - `catalog`: Catalog for finding datasets
+/// - `metrics`: Metrics collector
+/// - `grid_processor_factory`: Factory for Zarr-based grid access
+/// - `model`: Weather model name
+/// - `layer_parameter`: Layer parameter (for logging)
+/// - `vertical`: Column settings from the layer config
+/// - `range`: Level range, resolved from ELEVATION
+/// - `forecast_hour`: Optional forecast hour
+/// - `width`: Output image width
+/// - `height`: Output image height
+/// - `bbox`: Optional bounding box
+/// - `style_file`: Path to style JSON file (from layer config)
+/// - `style_name`: Optional style name within the file
+/// - `use_mercator`: Use Web Mercator projection for resampling
+/// - `requires_full_grid`: Force full grid read (for non-geographic projections)
+#[allow(clippy::too_many_arguments)]
+pub async fn render_vertical_composite(
+    catalog: &Catalog,
+    metrics: &MetricsCollector,
+    grid_processor_factory: &GridProcessorFactory,
+    model: &str,
+    layer_parameter: &str,
+    vertical: &VerticalConfig,
+    range: &LevelRange,
+    forecast_hour: Option<u32>,
+    width: u32,
+    height: u32,
+    bbox: Option<[f32; 4]>,
+    style_file: &str,
+    style_name: Option<&str>,
+    use_mercator: bool,
+    requires_full_grid: bool,
+) -> Result<Vec<u8>, String> {
+    let render_start = Instant::now();
+    let source = vertical.source.as_str();
+
+    let anchor =
+        find_requested_entry(catalog, model, source, forecast_hour, None, None, false).await?;
+    let mut levels = catalog
+        .find_run_levels(model, source, anchor.reference_time, anchor.forecast_hour)
+        .await
+        .map_err(|e| format!("Catalog query failed: {}", e))?;
+    levels.retain(|e| range.contains(&e.level));
+    if levels.is_empty() {
+        return Err(format!(
+            "No levels of {}/{} in {} at {} f{:03}",
+            model, source, range, anchor.reference_time, anchor.forecast_hour
+        ));
+    }
+    levels.truncate(vertical.max_levels);
+
+    info!(
+        model = model,
+        parameter = layer_parameter,
+        source = source,
+        reducer = %vertical.reducer,
+        range = %range,
+        run = %anchor.reference_time,
+        forecast_hour = anchor.forecast_hour,
+        levels = levels.len(),
+        "Rendering column composite"
+    );
+
+    let rendered_width = width as usize;
+    let rendered_height = height as usize;
+    let cache_key = composite_cache_key(
+        model,
+        vertical,
+        &levels,
+        bbox,
+        rendered_width,
+        rendered_height,
+        use_mercator,
+    );
+
+    let cached = composite_cache()
+        .lock()
+        .map_err(|_| "Column composite cache poisoned".to_string())?
+        .get(&cache_key);
+    let composite = match cached {
+        Some(composite) => {
+            debug!(parameter = layer_parameter, "Column composite cache hit");
+            composite
+        }
+        None => {
+            let composite = Arc::new(
+                reduce_levels(
+                    grid_processor_factory,
+                    metrics,
+                    vertical,
+                    &levels,
+                    bbox,
+                    rendered_width,
+                    rendered_height,
+                    use_mercator,
+                    requires_full_grid,
+                )
+                .await?,
+            );
+            composite_cache()
+                .lock()
+                .map_err(|_| "Column composite cache poisoned".to_string())?
+                .insert(cache_key, composite.clone());
+            composite
+        }
+    };
+
+    let start = Instant::now();
+    let render_result = render_with_style_file_indexed(
+        &composite,
+        None,
+        style_file,
+        style_name,
+        rendered_width,
+        rendered_height,
+    )?;
+    let png = renderer::png::create_png_from_precomputed_with_options(
+        &render_result.indices,
+        rendered_width,
+        rendered_height,
+        &render_result.palette,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))?;
+    metrics
+        .record_png_encode(start.elapsed().as_micros() as u64)
+        .await;
+
+    debug!(
+        parameter = layer_parameter,
+        elapsed_ms = render_start.elapsed().as_millis() as u64,
+        "Column composite render complete"
+    );
+
+    Ok(png)
+}
+
+/// Read, resample and reduce every level in the range.
+#[allow(clippy::too_many_arguments)]
+async fn reduce_levels(
+    grid_processor_factory: &GridProcessorFactory,
+    metrics: &MetricsCollector,
+    vertical: &VerticalConfig,
+    levels: &[CatalogEntry],
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+    requires_full_grid: bool,
+) -> Result<Vec<f32>, String> {
+    let mut accumulator = VerticalAccumulator::new(vertical.reducer, width * height);
+
+    for level in levels {
+        let start = Instant::now();
+        let grid = load_grid_data(
+            grid_processor_factory,
+            level,
+            bbox,
+            Some((width, height)),
+            requires_full_grid,
+        )
+        .await?;
+        metrics
+            .record_grib_load(start.elapsed().as_micros() as u64)
+            .await;
+
+        let start = Instant::now();
+        let resampled = resample_grid_for_output(&grid, level, bbox, width, height, use_mercator);
+        metrics
+            .record_resample(start.elapsed().as_micros() as u64)
+            .await;
+
+        accumulator.add(&resampled).map_err(|e| e.to_string())?;
+    }
+
+    Ok(accumulator.finish())
+}
+
+/// Cache key identifying everything a composite depends on.
+fn composite_cache_key(
+    model: &str,
+    vertical: &VerticalConfig,
+    levels: &[CatalogEntry],
+    bbox: Option<[f32; 4]>,
+    width: usize,
+    height: usize,
+    use_mercator: bool,
+) -> String {
+    let mut paths: Vec<&str> = levels.iter().map(|e| e.storage_path.as_str()).collect();
+    paths.sort_unstable();
+    let mut hasher = DefaultHasher::new();
+    paths.hash(&mut hasher);
+
+    let bbox_key = bbox
+        .map(|b| format!("{},{},{},{}", b[0], b[1], b[2], b[3]))
+        .unwrap_or_else(|| "full".to_string());
+
+    format!(
+        "{}:{}:{}:{:016x}:{}:{}x{}:{}",
+        model,
+        vertical.source,
+        vertical.reducer,
+        hasher.finish(),
+        bbox_key,
+        width,
+        height,
+        if use_mercator { "3857" } else { "4326" }
+    )
+}
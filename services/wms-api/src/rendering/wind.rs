@@ -17,6 +17,7 @@ use std::time::Instant;
 use storage::{Catalog, CatalogEntry};
 use tracing::{debug, info, warn};
 
+use super::png_options;
 use super::resampling::resample_for_model_geographic;
 use grid_processor::GridProcessorFactory;
 
@@ -204,8 +205,13 @@ pub async fn render_wind_barbs_tile(
     };
 
     // Encode as PNG
-    renderer::png::create_png(&final_pixels, width as usize, height as usize)
-        .map_err(|e| format!("PNG encoding failed: {}", e))
+    renderer::png::create_png_with_options(
+        &final_pixels,
+        width as usize,
+        height as usize,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))
 }
 
 /// Render wind barbs combining U and V component data with optional level/elevation.
@@ -346,8 +352,13 @@ pub async fn render_wind_barbs_tile_with_level(
     };
 
     // Encode as PNG
-    renderer::png::create_png(&final_pixels, width as usize, height as usize)
-        .map_err(|e| format!("PNG encoding failed: {}", e))
+    renderer::png::create_png_with_options(
+        &final_pixels,
+        width as usize,
+        height as usize,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))
 }
 
 /// Render wind barbs combining U and V component data
@@ -623,8 +634,13 @@ pub async fn render_wind_barbs_layer(
     );
 
     // Encode as PNG
-    renderer::png::create_png(&barb_pixels, width as usize, height as usize)
-        .map_err(|e| format!("PNG encoding failed: {}", e))
+    renderer::png::create_png_with_options(
+        &barb_pixels,
+        width as usize,
+        height as usize,
+        png_options(),
+    )
+    .map_err(|e| format!("PNG encoding failed: {}", e))
 }
 
 // ============================================================================
@@ -693,8 +709,8 @@ pub(crate) async fn load_wind_components_from_zarr(
     bbox: Option<[f32; 4]>,
 ) -> Result<(Vec<f32>, Vec<f32>, usize, usize, [f32; 4], bool), String> {
     use grid_processor::{
-        create_minio_storage, BoundingBox as GpBoundingBox, GridProcessor, MinioConfig,
-        ZarrGridProcessor, ZarrMetadata,
+        create_minio_storage, BoundingBox as GpBoundingBox, GridProcessor, ZarrGridProcessor,
+        ZarrMetadata,
     };
 
     // Parse zarr_metadata for U component
@@ -731,8 +747,7 @@ pub(crate) async fn load_wind_components_from_zarr(
     );
 
     // Create MinIO storage
-    let minio_config = MinioConfig::from_env();
-    let store = create_minio_storage(&minio_config)
+    let store = create_minio_storage(factory.minio_config())
         .map_err(|e| format!("Failed to create MinIO storage: {}", e))?;
 
     // Build storage paths
@@ -760,6 +775,9 @@ pub(crate) async fn load_wind_components_from_zarr(
         chunk_shape: u_zarr_meta.chunk_shape,
         num_chunks: u_zarr_meta.num_chunks,
         fill_value: u_zarr_meta.fill_value,
+        coordinates: u_zarr_meta.coordinates.clone(),
+        time: u_zarr_meta.time.clone(),
+        vertical: u_zarr_meta.vertical.clone(),
     };
 
     // Create U processor
@@ -785,6 +803,9 @@ pub(crate) async fn load_wind_components_from_zarr(
         chunk_shape: v_zarr_meta.chunk_shape,
         num_chunks: v_zarr_meta.num_chunks,
         fill_value: v_zarr_meta.fill_value,
+        coordinates: v_zarr_meta.coordinates.clone(),
+        time: v_zarr_meta.time.clone(),
+        vertical: v_zarr_meta.vertical.clone(),
     };
 
     // Create V processor
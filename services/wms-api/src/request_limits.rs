@@ -0,0 +1,289 @@
+//! Size and complexity limits for WMS GetMap requests.
+//!
+//! Service-wide limits (image size, pixel count, layer count) come from
+//! environment variables (see [`RequestLimits::from_env`]). Layers can add
+//! stricter limits in their YAML config (see [`LayerLimits`]), e.g. to keep
+//! high-resolution radar from being rendered over a whole continent.
+//! Requests made with a trusted API key skip all limits.
+
+use std::collections::HashSet;
+use std::env;
+
+use wms_common::api_key::ApiKey;
+
+use crate::layer_config::LayerLimits;
+
+/// Tile size the effective zoom level of a GetMap request is measured in.
+const TILE_SIZE: f64 = 256.0;
+
+/// Service-wide GetMap limits.
+#[derive(Clone, Debug)]
+pub struct RequestLimits {
+    /// Largest WIDTH (advertised as `MaxWidth`)
+    pub max_width: u32,
+    /// Largest HEIGHT (advertised as `MaxHeight`)
+    pub max_height: u32,
+    /// Most pixels (WIDTH × HEIGHT) in one image
+    pub max_pixels: u64,
+    /// Most layers in one request (advertised as `LayerLimit`)
+    pub max_layers: usize,
+    /// API keys exempt from all limits
+    pub trusted_api_keys: HashSet<String>,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_width: 4096,
+            max_height: 4096,
+            max_pixels: 4096 * 2048,
+            max_layers: 8,
+            trusted_api_keys: HashSet::new(),
+        }
+    }
+}
+
+impl RequestLimits {
+    /// Parse limits from environment variables, keeping defaults for unset
+    /// or invalid values.
+    ///
+    /// `WMS_TRUSTED_API_KEYS` is comma-separated.
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+            env::var(key).ok().and_then(|v| v.trim().parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            max_width: parse("WMS_MAX_WIDTH").unwrap_or(defaults.max_width),
+            max_height: parse("WMS_MAX_HEIGHT").unwrap_or(defaults.max_height),
+            max_pixels: parse("WMS_MAX_PIXELS").unwrap_or(defaults.max_pixels),
+            max_layers: parse("WMS_LAYER_LIMIT").unwrap_or(defaults.max_layers),
+            trusted_api_keys: env::var("WMS_TRUSTED_API_KEYS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|k| k.trim().to_string())
+                        .filter(|k| !k.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Whether requests with this key skip all limits.
+    pub fn is_trusted(&self, key: &ApiKey) -> bool {
+        !key.is_anonymous() && self.trusted_api_keys.contains(key.as_str())
+    }
+
+    /// Check a request against the service-wide limits.
+    pub fn check(&self, request: &MapRequest) -> Result<(), String> {
+        if request.width > self.max_width {
+            return Err(format!(
+                "WIDTH {} exceeds the maximum of {}",
+                request.width, self.max_width
+            ));
+        }
+        if request.height > self.max_height {
+            return Err(format!(
+                "HEIGHT {} exceeds the maximum of {}",
+                request.height, self.max_height
+            ));
+        }
+        check_pixels(request, self.max_pixels)?;
+        if request.layers > self.max_layers {
+            return Err(format!(
+                "{} layers requested, at most {} are allowed per request",
+                request.layers, self.max_layers
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl LayerLimits {
+    /// Check a request for `layer_id` against this layer's limits.
+    pub fn check(&self, layer_id: &str, request: &MapRequest) -> Result<(), String> {
+        if let Some(max_pixels) = self.max_pixels {
+            check_pixels(request, max_pixels)
+                .map_err(|e| format!("{} for layer {}", e, layer_id))?;
+        }
+
+        // Zoom and extent limits need a bbox; requests without one are
+        // rejected by the renderer anyway
+        let (Some(zoom), Some(span)) = (request.zoom(), request.span_degrees()) else {
+            return Ok(());
+        };
+
+        if let Some(min_zoom) = self.min_zoom {
+            if zoom < min_zoom {
+                return Err(format!(
+                    "Layer {} is not available below zoom level {} (requested extent is zoom {:.1}); \
+                     request a smaller BBOX or a larger image",
+                    layer_id, min_zoom, zoom
+                ));
+            }
+        }
+
+        if let Some(rule) = self
+            .extent_rules
+            .iter()
+            .find(|r| zoom < r.below_zoom && span > r.max_span_degrees)
+        {
+            return Err(format!(
+                "BBOX spans {:.1} degrees; layer {} allows at most {} degrees below zoom level {} \
+                 (requested extent is zoom {:.1})",
+                span, layer_id, rule.max_span_degrees, rule.below_zoom, zoom
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn check_pixels(request: &MapRequest, max_pixels: u64) -> Result<(), String> {
+    let pixels = request.width as u64 * request.height as u64;
+    if pixels > max_pixels {
+        return Err(format!(
+            "Image of {}x{} ({} pixels) exceeds the maximum of {} pixels",
+            request.width, request.height, pixels, max_pixels
+        ));
+    }
+    Ok(())
+}
+
+/// The parts of a GetMap request that limits apply to.
+#[derive(Clone, Debug)]
+pub struct MapRequest {
+    pub width: u32,
+    pub height: u32,
+    /// Number of layers requested
+    pub layers: usize,
+    /// Requested extent in WGS84 as [min_lon, min_lat, max_lon, max_lat]
+    pub extent: Option<[f64; 4]>,
+}
+
+impl MapRequest {
+    /// Zoom level of a Web Mercator tile pyramid with the same resolution
+    /// (degrees of longitude per pixel) as the request.
+    pub fn zoom(&self) -> Option<f64> {
+        let [min_lon, _, max_lon, _] = self.extent?;
+        let degrees_per_pixel = (max_lon - min_lon) / self.width.max(1) as f64;
+        (degrees_per_pixel > 0.0).then(|| (360.0 / (TILE_SIZE * degrees_per_pixel)).log2())
+    }
+
+    /// Larger of the request's longitude and latitude spans, in degrees.
+    pub fn span_degrees(&self) -> Option<f64> {
+        let [min_lon, min_lat, max_lon, max_lat] = self.extent?;
+        Some((max_lon - min_lon).max(max_lat - min_lat))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer_config::ExtentRule;
+
+    fn request(width: u32, height: u32, extent: [f64; 4]) -> MapRequest {
+        MapRequest {
+            width,
+            height,
+            layers: 1,
+            extent: Some(extent),
+        }
+    }
+
+    #[test]
+    fn test_service_limits() {
+        let limits = RequestLimits::default();
+        let globe = [-180.0, -90.0, 180.0, 90.0];
+
+        assert!(limits.check(&request(1024, 512, globe)).is_ok());
+        assert!(limits.check(&request(10000, 256, globe)).is_err());
+        assert!(limits.check(&request(256, 10000, globe)).is_err());
+
+        // Within both dimensions but over the pixel budget
+        let err = limits.check(&request(4096, 4096, globe)).unwrap_err();
+        assert!(err.contains("pixels"), "{}", err);
+
+        let mut many = request(256, 256, globe);
+        many.layers = 9;
+        assert!(limits.check(&many).is_err());
+    }
+
+    #[test]
+    fn test_trusted_keys() {
+        let limits = RequestLimits {
+            trusted_api_keys: ["partner".to_string()].into_iter().collect(),
+            ..RequestLimits::default()
+        };
+
+        assert!(limits.is_trusted(&ApiKey::new("partner")));
+        assert!(!limits.is_trusted(&ApiKey::new("someone-else")));
+        assert!(!limits.is_trusted(&ApiKey::anonymous()));
+    }
+
+    #[test]
+    fn test_request_zoom() {
+        // A 256 px wide image of the whole globe is zoom 0
+        let zoom = request(256, 256, [-180.0, -90.0, 180.0, 90.0])
+            .zoom()
+            .unwrap();
+        assert!(zoom.abs() < 1e-9);
+
+        let zoom = request(512, 256, [0.0, 0.0, 45.0, 45.0]).zoom().unwrap();
+        assert!((zoom - 4.0).abs() < 1e-9);
+
+        let span = request(256, 256, [0.0, 10.0, 45.0, 70.0]).span_degrees();
+        assert_eq!(span, Some(60.0));
+    }
+
+    #[test]
+    fn test_layer_limits() {
+        let limits = LayerLimits {
+            max_pixels: Some(512 * 512),
+            min_zoom: Some(2.0),
+            extent_rules: vec![ExtentRule {
+                below_zoom: 5.0,
+                max_span_degrees: 30.0,
+            }],
+        };
+
+        // Zoom 4, 22.5 degrees wide
+        assert!(limits
+            .check("mrms_REFL", &request(256, 256, [0.0, 0.0, 22.5, 22.5]))
+            .is_ok());
+
+        // Zoom 3, 45 degrees wide: too large below zoom 5
+        let err = limits
+            .check("mrms_REFL", &request(256, 256, [0.0, 0.0, 45.0, 45.0]))
+            .unwrap_err();
+        assert!(err.contains("at most 30 degrees"), "{}", err);
+
+        // The same extent at zoom 5 is allowed
+        assert!(limits
+            .check("mrms_REFL", &request(1024, 256, [0.0, 0.0, 45.0, 10.0]))
+            .is_ok());
+
+        // Zoom 0 is below the minimum zoom
+        let err = limits
+            .check(
+                "mrms_REFL",
+                &request(256, 256, [-180.0, -90.0, 180.0, 90.0]),
+            )
+            .unwrap_err();
+        assert!(err.contains("below zoom level 2"), "{}", err);
+
+        assert!(limits
+            .check("mrms_REFL", &request(1024, 1024, [0.0, 0.0, 1.0, 1.0]))
+            .is_err());
+
+        // No limits configured
+        let none = LayerLimits::default();
+        assert!(none
+            .check(
+                "gfs_TMP",
+                &request(4096, 2048, [-180.0, -90.0, 180.0, 90.0])
+            )
+            .is_ok());
+    }
+}
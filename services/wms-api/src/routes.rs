@@ -0,0 +1,253 @@
+//! HTTP route tables for the WMS/WMTS API.
+//!
+//! Handlers read the shared [`AppState`](crate::state::AppState) from an
+//! `Extension`, so callers add it as a layer together with any middleware.
+//! The server binary serves both tables; integration tests can drive them
+//! directly (see `AppState::offline`).
+
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::{admin, handlers};
+
+/// OGC service, tile, health and read-only API endpoints.
+pub fn public_routes() -> Router {
+    Router::new()
+        // WMS endpoints
+        .route("/wms", get(handlers::wms_handler))
+        .route("/wms/", get(handlers::wms_handler))
+        // WMTS endpoints (KVP)
+        .route("/wmts", get(handlers::wmts_kvp_handler))
+        .route("/wmts/", get(handlers::wmts_kvp_handler))
+        // WMTS RESTful endpoints
+        .route("/wmts/rest/*path", get(handlers::wmts_rest_handler))
+        // OGC API - Maps
+        .route("/ogc", get(handlers::ogc_landing_handler))
+        .route("/ogc/", get(handlers::ogc_landing_handler))
+        .route("/ogc/conformance", get(handlers::ogc_conformance_handler))
+        .route("/ogc/collections", get(handlers::ogc_collections_handler))
+        .route(
+            "/ogc/collections/:collection",
+            get(handlers::ogc_collection_handler),
+        )
+        .route(
+            "/ogc/collections/:collection/map",
+            get(handlers::ogc_map_handler),
+        )
+        .route(
+            "/ogc/collections/:collection/styles/:style/map",
+            get(handlers::ogc_styled_map_handler),
+        )
+        // Simple tile endpoints (XYZ/TMS style for easy integration)
+        .route(
+            "/tiles/:layer/:style/:z/:x/:y",
+            get(handlers::xyz_tile_handler),
+        )
+        // Health check
+        .route("/health", get(handlers::health_handler))
+        .route("/ready", get(handlers::ready_handler))
+        // Metrics
+        .route("/metrics", get(handlers::metrics_handler))
+        // API endpoints
+        .route(
+            "/api/forecast-times/:model/:parameter",
+            get(handlers::forecast_times_handler),
+        )
+        .route("/api/parameters/:model", get(handlers::parameters_handler))
+        .route(
+            "/api/animation-frames/:layer",
+            get(handlers::animation_frames_handler),
+        )
+        .route(
+            "/api/run-comparison/:model/:parameter",
+            get(handlers::run_comparison_handler),
+        )
+        .route("/api/catalog/search", get(handlers::catalog_search_handler))
+        .route("/api/styles/:style", get(handlers::style_handler))
+        .route(
+            "/api/layers/:layer/styles",
+            get(handlers::layer_styles_handler),
+        )
+        // Ingestion events API
+        .route(
+            "/api/ingestion/events",
+            get(handlers::ingestion_events_handler),
+        )
+        // Validation API
+        .route(
+            "/api/validation/status",
+            get(handlers::validation_status_handler),
+        )
+        .route("/api/validation/run", get(handlers::validation_run_handler))
+        .route(
+            "/api/validation/request",
+            get(handlers::request_validation_handler),
+        )
+        // Storage stats API
+        .route("/api/storage/stats", get(handlers::storage_stats_handler))
+        // Container/pod resource stats API
+        .route(
+            "/api/container/stats",
+            get(handlers::container_stats_handler),
+        )
+        // Grid processor stats API (Zarr chunk cache and processing)
+        .route(
+            "/api/grid-processor/stats",
+            get(handlers::grid_processor_stats_handler),
+        )
+        // Tile request heatmap API (for geographic visualization)
+        .route("/api/tile-heatmap", get(handlers::tile_heatmap_handler))
+        // Application metrics API
+        .route("/api/metrics", get(handlers::api_metrics_handler))
+        // Configuration API - shows optimization settings
+        .route("/api/config", get(handlers::config_handler))
+        // API Documentation (Swagger UI)
+        .route("/api/docs", get(handlers::swagger_ui_handler))
+        .route(
+            "/api/docs/openapi.yaml",
+            get(handlers::openapi_yaml_handler),
+        )
+        .route(
+            "/api/docs/openapi.json",
+            get(handlers::openapi_json_handler),
+        )
+        // Load test dashboard
+        .route("/loadtest", get(handlers::loadtest_dashboard_handler))
+        .route(
+            "/api/loadtest/results",
+            get(handlers::loadtest_results_handler),
+        )
+        // Benchmark comparison API (for web/benchmarks.html - load test comparisons)
+        .route("/api/benchmarks", get(handlers::benchmarks_handler))
+        // Criterion microbenchmark results API
+        .route(
+            "/api/criterion",
+            get(handlers::criterion_benchmarks_handler),
+        )
+        .route("/api/loadtest/files", get(handlers::loadtest_files_handler))
+        .route(
+            "/api/loadtest/file/:id",
+            get(handlers::loadtest_file_handler),
+        )
+        .route(
+            "/api/benchmarks/trends",
+            get(handlers::benchmark_trends_handler),
+        )
+}
+
+/// Admin and cache-management endpoints.
+pub fn admin_routes() -> Router {
+    Router::new()
+        // Startup validation API (test renders + cache warming)
+        .route(
+            "/api/validation/startup",
+            get(handlers::startup_validation_run_handler),
+        )
+        .route(
+            "/api/tile-heatmap/clear",
+            post(handlers::tile_heatmap_clear_handler),
+        )
+        // Cache API endpoints
+        // Benchmark history uploads (load-test --publish, run_benchmarks.sh)
+        .route(
+            "/api/admin/benchmarks/loadtest",
+            post(handlers::loadtest_record_handler),
+        )
+        .route(
+            "/api/admin/benchmarks/loadtest/:id/requests",
+            post(handlers::loadtest_requests_record_handler),
+        )
+        .route(
+            "/api/admin/benchmarks/criterion",
+            post(handlers::criterion_record_handler),
+        )
+        .route("/api/cache/list", get(handlers::cache_list_handler))
+        .route("/api/cache/clear", post(handlers::cache_clear_handler))
+        // Config reload endpoints (hot reload)
+        .route("/api/config/reload", post(handlers::config_reload_handler))
+        .route(
+            "/api/config/reload/layers",
+            post(handlers::config_reload_layers_handler),
+        )
+        // Admin dashboard
+        .route(
+            "/api/admin/ingestion/status",
+            get(admin::ingestion_status_handler),
+        )
+        .route(
+            "/api/admin/database/details",
+            get(admin::database_details_handler),
+        )
+        .route(
+            "/api/admin/database/datasets/:model/:parameter",
+            get(admin::database_datasets_handler),
+        )
+        .route("/api/admin/storage/tree", get(admin::storage_tree_handler))
+        .route(
+            "/api/admin/ingestion/log",
+            get(admin::ingestion_log_handler),
+        )
+        .route(
+            "/api/admin/preview-shred",
+            get(admin::preview_shred_handler),
+        )
+        .route("/api/admin/preview", get(admin::preview_matrix_handler))
+        .route(
+            "/api/admin/preview/image/:layer/:style",
+            get(admin::preview_image_handler),
+        )
+        .route("/api/admin/config/models", get(admin::list_models_handler))
+        .route("/api/admin/config/full", get(admin::full_config_handler))
+        // Model/layer/style config editing with version history
+        .route(
+            "/api/admin/config/:kind/:id",
+            get(admin::get_config_handler).put(admin::update_config_handler),
+        )
+        .route(
+            "/api/admin/config/:kind/:id/versions",
+            get(admin::config_versions_handler),
+        )
+        .route(
+            "/api/admin/config/:kind/:id/versions/:version",
+            get(admin::config_version_handler),
+        )
+        .route(
+            "/api/admin/config/:kind/:id/diff",
+            get(admin::config_diff_handler),
+        )
+        .route(
+            "/api/admin/config/:kind/:id/rollback",
+            post(admin::config_rollback_handler),
+        )
+        // Ingest endpoint (called by downloader service)
+        .route("/admin/ingest", post(admin::ingest_handler))
+        // Cleanup/retention endpoints
+        .route(
+            "/api/admin/cleanup/status",
+            get(admin::cleanup_status_handler),
+        )
+        .route("/api/admin/cleanup/run", post(admin::cleanup_run_handler))
+        // Database/storage sync endpoints
+        .route("/api/admin/sync/status", get(admin::sync_status_handler))
+        .route("/api/admin/sync/preview", get(admin::sync_preview_handler))
+        .route("/api/admin/sync/run", post(admin::sync_run_handler))
+        .route(
+            "/api/admin/integrity/verify",
+            post(admin::checksum_sweep_handler),
+        )
+        // Bulk export endpoints (background jobs with signed download URLs)
+        .route(
+            "/api/admin/exports",
+            get(admin::export_list_handler).post(admin::export_create_handler),
+        )
+        .route("/api/admin/exports/:id", get(admin::export_status_handler))
+        // Expected vs. ingested datasets of recent runs
+        .route("/api/admin/completeness", get(admin::completeness_handler))
+        // Ingestion tracking endpoint
+        .route(
+            "/api/admin/ingestion/active",
+            get(admin::ingestion_active_handler),
+        )
+}
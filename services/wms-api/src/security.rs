@@ -0,0 +1,336 @@
+//! HTTP security configuration: CORS, security headers, and admin access.
+//!
+//! All settings come from environment variables (see [`SecurityConfig::from_env`]).
+//! Defaults keep local development working (any origin, admin endpoints on the
+//! main listener without auth); production deployments should set
+//! `CORS_ALLOWED_ORIGINS` and either `ADMIN_LISTEN` or `ADMIN_TOKEN`.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+use tracing::warn;
+
+/// Header accepted as an alternative to `Authorization: Bearer <token>`.
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+
+/// CORS, security header and admin access settings.
+#[derive(Clone, Debug)]
+pub struct SecurityConfig {
+    /// Origins allowed to make cross-origin requests ("*" = any)
+    pub cors_allowed_origins: Vec<String>,
+    /// Methods allowed for cross-origin requests ("*" = any)
+    pub cors_allowed_methods: Vec<String>,
+    /// Request headers allowed for cross-origin requests ("*" = any)
+    pub cors_allowed_headers: Vec<String>,
+    /// How long browsers may cache preflight responses
+    pub cors_max_age_secs: u64,
+
+    /// Add standard security headers to every response
+    pub security_headers_enabled: bool,
+    /// `Strict-Transport-Security` max-age (0 = header not sent)
+    pub hsts_max_age_secs: u64,
+    /// `Content-Security-Policy` value, if any
+    pub content_security_policy: Option<String>,
+
+    /// Separate listen address for admin and cache-management endpoints
+    pub admin_listen: Option<String>,
+    /// Token required on admin and cache-management endpoints
+    pub admin_token: Option<String>,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            cors_allowed_origins: vec!["*".to_string()],
+            cors_allowed_methods: ["GET", "HEAD", "POST", "PUT", "OPTIONS"]
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+            cors_allowed_headers: ["content-type", "authorization", "x-api-key"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            cors_max_age_secs: 3600,
+            security_headers_enabled: true,
+            hsts_max_age_secs: 0,
+            content_security_policy: None,
+            admin_listen: None,
+            admin_token: None,
+        }
+    }
+}
+
+impl SecurityConfig {
+    /// Parse security configuration from environment variables.
+    ///
+    /// List values are comma-separated.
+    pub fn from_env() -> Self {
+        fn parse_list(key: &str) -> Option<Vec<String>> {
+            env::var(key).ok().map(|v| {
+                v.split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            })
+        }
+
+        fn parse_string(key: &str) -> Option<String> {
+            env::var(key)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        }
+
+        let defaults = Self::default();
+        Self {
+            cors_allowed_origins: parse_list("CORS_ALLOWED_ORIGINS")
+                .unwrap_or(defaults.cors_allowed_origins),
+            cors_allowed_methods: parse_list("CORS_ALLOWED_METHODS")
+                .unwrap_or(defaults.cors_allowed_methods),
+            cors_allowed_headers: parse_list("CORS_ALLOWED_HEADERS")
+                .unwrap_or(defaults.cors_allowed_headers),
+            cors_max_age_secs: env::var("CORS_MAX_AGE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.cors_max_age_secs),
+            security_headers_enabled: env::var("ENABLE_SECURITY_HEADERS")
+                .ok()
+                .map(|v| v.to_lowercase() == "true" || v == "1")
+                .unwrap_or(defaults.security_headers_enabled),
+            hsts_max_age_secs: env::var("HSTS_MAX_AGE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.hsts_max_age_secs),
+            content_security_policy: parse_string("CONTENT_SECURITY_POLICY"),
+            admin_listen: parse_string("ADMIN_LISTEN"),
+            admin_token: parse_string("ADMIN_TOKEN"),
+        }
+    }
+
+    /// Whether any origin may make cross-origin requests.
+    pub fn allows_any_origin(&self) -> bool {
+        is_wildcard(&self.cors_allowed_origins)
+    }
+
+    /// Build the CORS layer. Invalid origins and methods are skipped with a warning.
+    pub fn cors_layer(&self) -> CorsLayer {
+        let origins = if self.allows_any_origin() {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(
+                self.cors_allowed_origins
+                    .iter()
+                    .filter_map(|o| parse_or_warn(o, "CORS origin", |o| o.parse().ok())),
+            )
+        };
+
+        let methods = if is_wildcard(&self.cors_allowed_methods) {
+            AllowMethods::any()
+        } else {
+            AllowMethods::list(self.cors_allowed_methods.iter().filter_map(|m| {
+                parse_or_warn(m, "CORS method", |m| {
+                    Method::from_bytes(m.to_uppercase().as_bytes()).ok()
+                })
+            }))
+        };
+
+        let headers = if is_wildcard(&self.cors_allowed_headers) {
+            AllowHeaders::any()
+        } else {
+            AllowHeaders::list(
+                self.cors_allowed_headers
+                    .iter()
+                    .filter_map(|h| parse_or_warn(h, "CORS header", |h| h.parse().ok())),
+            )
+        };
+
+        CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .max_age(Duration::from_secs(self.cors_max_age_secs))
+    }
+
+    /// Security headers added to every response.
+    pub fn security_headers(&self) -> Vec<(HeaderName, HeaderValue)> {
+        if !self.security_headers_enabled {
+            return Vec::new();
+        }
+
+        let mut headers = vec![
+            (
+                header::X_CONTENT_TYPE_OPTIONS,
+                HeaderValue::from_static("nosniff"),
+            ),
+            (
+                header::X_FRAME_OPTIONS,
+                HeaderValue::from_static("SAMEORIGIN"),
+            ),
+            (
+                header::REFERRER_POLICY,
+                HeaderValue::from_static("strict-origin-when-cross-origin"),
+            ),
+        ];
+
+        if self.hsts_max_age_secs > 0 {
+            headers.push((
+                header::STRICT_TRANSPORT_SECURITY,
+                HeaderValue::from_str(&format!("max-age={}", self.hsts_max_age_secs))
+                    .expect("numeric header value"),
+            ));
+        }
+
+        if let Some(csp) = &self.content_security_policy {
+            match HeaderValue::from_str(csp) {
+                Ok(value) => headers.push((header::CONTENT_SECURITY_POLICY, value)),
+                Err(_) => warn!("Ignoring invalid CONTENT_SECURITY_POLICY"),
+            }
+        }
+
+        headers
+    }
+}
+
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|v| v == "*")
+}
+
+fn parse_or_warn<T>(value: &str, what: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    let parsed = parse(value);
+    if parsed.is_none() {
+        warn!(value = %value, "Ignoring invalid {}", what);
+    }
+    parsed
+}
+
+/// Middleware adding the configured security headers to every response.
+///
+/// Headers already set by a handler are left unchanged.
+pub async fn add_security_headers(
+    State(headers): State<Arc<Vec<(HeaderName, HeaderValue)>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    let response_headers = response.headers_mut();
+    for (name, value) in headers.iter() {
+        if !response_headers.contains_key(name) {
+            response_headers.insert(name.clone(), value.clone());
+        }
+    }
+    response
+}
+
+/// Middleware rejecting requests without the admin token.
+pub async fn require_admin_token(
+    State(token): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if has_admin_token(request.headers(), &token) {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(serde_json::json!({ "error": "Admin token required" })),
+    )
+        .into_response()
+}
+
+/// Whether the request carries the admin token, as `Authorization: Bearer <token>`
+/// or in the `X-Admin-Token` header.
+fn has_admin_token(headers: &HeaderMap, token: &str) -> bool {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let header_token = headers
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok());
+
+    bearer
+        .into_iter()
+        .chain(header_token)
+        .any(|provided| constant_time_eq(provided.trim().as_bytes(), token.as_bytes()))
+}
+
+/// Compare without returning early on the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_security_headers() {
+        let headers = SecurityConfig::default().security_headers();
+        let names: Vec<_> = headers.iter().map(|(name, _)| name.clone()).collect();
+
+        assert!(names.contains(&header::X_CONTENT_TYPE_OPTIONS));
+        assert!(names.contains(&header::X_FRAME_OPTIONS));
+        assert!(names.contains(&header::REFERRER_POLICY));
+        // HSTS is opt-in since local deployments use plain HTTP
+        assert!(!names.contains(&header::STRICT_TRANSPORT_SECURITY));
+    }
+
+    #[test]
+    fn test_optional_security_headers() {
+        let config = SecurityConfig {
+            hsts_max_age_secs: 31536000,
+            content_security_policy: Some("default-src 'self'".to_string()),
+            ..SecurityConfig::default()
+        };
+        let headers = config.security_headers();
+
+        let hsts = headers
+            .iter()
+            .find(|(name, _)| name == header::STRICT_TRANSPORT_SECURITY)
+            .unwrap();
+        assert_eq!(hsts.1, "max-age=31536000");
+        assert!(headers
+            .iter()
+            .any(|(name, value)| name == header::CONTENT_SECURITY_POLICY
+                && value == "default-src 'self'"));
+
+        let disabled = SecurityConfig {
+            security_headers_enabled: false,
+            ..config
+        };
+        assert!(disabled.security_headers().is_empty());
+    }
+
+    #[test]
+    fn test_admin_token() {
+        let mut headers = HeaderMap::new();
+        assert!(!has_admin_token(&headers, "secret"));
+
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert!(!has_admin_token(&headers, "secret"));
+
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert!(has_admin_token(&headers, "secret"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ADMIN_TOKEN_HEADER, "secret".parse().unwrap());
+        assert!(has_admin_token(&headers, "secret"));
+        assert!(!has_admin_token(&headers, "secret2"));
+    }
+
+    #[test]
+    fn test_wildcard() {
+        assert!(is_wildcard(&["*".to_string()]));
+        assert!(!is_wildcard(&["https://maps.example.com".to_string()]));
+    }
+}
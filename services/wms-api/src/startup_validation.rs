@@ -9,8 +9,18 @@
 //! Unlike cache warming which renders many tiles at low zoom levels,
 //! startup validation focuses on rendering a small set of representative
 //! tiles at various zoom levels to validate the full pipeline.
+//!
+//! Live tiles change with every run, so they are only checked for being
+//! valid PNGs with some visible pixels. Styling is checked separately by
+//! golden renders: a synthetic ramp across each style's range is rendered
+//! and compared, within a tolerance, against a reference image in
+//! `STARTUP_VALIDATION_GOLDEN_DIR`.
 
+use renderer::style::{StyleConfig, StyleDefinition};
 use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, error, info, warn};
@@ -19,6 +29,13 @@ use wms_common::TileCoord;
 use crate::rendering;
 use crate::state::AppState;
 
+/// Width and height of golden renders.
+const GOLDEN_SIZE: usize = 64;
+
+/// Rows at the bottom of the golden ramp left as no-data, so a style that
+/// stops rendering NaN as transparent shows up as a diff.
+const GOLDEN_NODATA_ROWS: usize = 8;
+
 /// Configuration for startup validation.
 #[derive(Clone, Debug)]
 pub struct StartupValidationConfig {
@@ -32,6 +49,16 @@ pub struct StartupValidationConfig {
     pub test_zoom_levels: Vec<u32>,
     /// Skip validation for specific models
     pub skip_models: Vec<String>,
+    /// Directory of golden reference images (None disables golden renders)
+    pub golden_dir: Option<PathBuf>,
+    /// Largest per-channel difference at which two pixels still match
+    pub golden_tolerance: u8,
+    /// Fraction of pixels that may differ before a golden render fails
+    pub golden_max_diff_fraction: f64,
+    /// Write missing reference images instead of reporting them
+    pub golden_update: bool,
+    /// Report not ready on /ready while a golden render mismatches
+    pub golden_fail_readiness: bool,
 }
 
 impl Default for StartupValidationConfig {
@@ -42,6 +69,11 @@ impl Default for StartupValidationConfig {
             fail_on_error: false,
             test_zoom_levels: vec![2, 4, 6],
             skip_models: Vec::new(),
+            golden_dir: None,
+            golden_tolerance: 2,
+            golden_max_diff_fraction: 0.001,
+            golden_update: false,
+            golden_fail_readiness: false,
         }
     }
 }
@@ -77,6 +109,21 @@ impl StartupValidationConfig {
                 .ok()
                 .map(|v| v.split(',').map(|s| s.trim().to_lowercase()).collect())
                 .unwrap_or_default(),
+            golden_dir: env::var("STARTUP_VALIDATION_GOLDEN_DIR")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from),
+            golden_tolerance: env::var("STARTUP_VALIDATION_GOLDEN_TOLERANCE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2),
+            golden_max_diff_fraction: env::var("STARTUP_VALIDATION_GOLDEN_MAX_DIFF")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(|f: f64| f.clamp(0.0, 1.0))
+                .unwrap_or(0.001),
+            golden_update: parse_bool("STARTUP_VALIDATION_GOLDEN_UPDATE", false),
+            golden_fail_readiness: parse_bool("STARTUP_VALIDATION_GOLDEN_FAIL_READINESS", false),
         }
     }
 }
@@ -106,6 +153,47 @@ pub struct ValidationSummary {
     pub models_available: Vec<String>,
     pub models_missing: Vec<String>,
     pub results: Vec<ValidationTestResult>,
+    pub golden_mismatches: usize,
+    pub golden_checks: Vec<GoldenCheckResult>,
+}
+
+/// Outcome of comparing a golden render with its reference image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoldenStatus {
+    /// Within tolerance of the reference
+    Match,
+    /// More pixels differ than allowed, or the sizes differ
+    Mismatch,
+    /// No reference image yet
+    Missing,
+    /// No reference image yet; the render was saved as one
+    Written,
+    /// The style couldn't be rendered or the reference couldn't be read
+    Error,
+}
+
+/// Result of one golden render.
+#[derive(Debug, Clone, Serialize)]
+pub struct GoldenCheckResult {
+    pub layer: String,
+    pub style: String,
+    pub status: GoldenStatus,
+    /// Path of the reference image
+    pub reference: String,
+    /// Pixels differing by more than the tolerance in any channel
+    pub differing_pixels: usize,
+    pub total_pixels: usize,
+    /// Largest per-channel difference over all pixels
+    pub max_channel_diff: u8,
+    pub error: Option<String>,
+}
+
+/// Pixel comparison of two RGBA images of the same size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ImageDiff {
+    differing_pixels: usize,
+    max_channel_diff: u8,
 }
 
 /// Model/layer configuration for validation.
@@ -119,6 +207,9 @@ struct ValidationTarget {
     test_bbox: [f32; 4],
     /// Description for logging
     description: String,
+    /// Whether an empty (fully transparent) tile is a failure. False for
+    /// fields that are legitimately blank in fair weather.
+    expect_coverage: bool,
 }
 
 /// Startup validator.
@@ -146,6 +237,8 @@ impl StartupValidator {
                 models_available: Vec::new(),
                 models_missing: Vec::new(),
                 results: Vec::new(),
+                golden_mismatches: 0,
+                golden_checks: Vec::new(),
             };
         }
 
@@ -168,6 +261,16 @@ impl StartupValidator {
         // Define validation targets for each model
         let targets = self.build_validation_targets(&available_models);
 
+        // Golden renders of each layer's style
+        let golden_checks = match &self.config.golden_dir {
+            Some(dir) => self.run_golden_checks(&targets, dir).await,
+            None => Vec::new(),
+        };
+        let golden_mismatches = golden_checks
+            .iter()
+            .filter(|c| matches!(c.status, GoldenStatus::Mismatch | GoldenStatus::Error))
+            .count();
+
         // Run validation tests
         let results = self.run_validation_tests(targets).await;
 
@@ -200,14 +303,45 @@ impl StartupValidator {
             models_available: available_models,
             models_missing,
             results,
+            golden_mismatches,
+            golden_checks,
         };
 
+        for check in &summary.golden_checks {
+            match check.status {
+                GoldenStatus::Mismatch | GoldenStatus::Error => warn!(
+                    layer = %check.layer,
+                    style = %check.style,
+                    status = ?check.status,
+                    differing_pixels = check.differing_pixels,
+                    total_pixels = check.total_pixels,
+                    max_channel_diff = check.max_channel_diff,
+                    reference = %check.reference,
+                    error = ?check.error,
+                    "Golden render differs from reference"
+                ),
+                GoldenStatus::Missing => info!(
+                    layer = %check.layer,
+                    style = %check.style,
+                    reference = %check.reference,
+                    "No golden reference (set STARTUP_VALIDATION_GOLDEN_UPDATE=true to write it)"
+                ),
+                GoldenStatus::Match | GoldenStatus::Written => {}
+            }
+        }
+        if self.config.golden_fail_readiness {
+            self.state
+                .golden_renders_ok
+                .store(golden_mismatches == 0, Ordering::Relaxed);
+        }
+
         // Log summary
-        if failed > 0 {
+        if failed > 0 || golden_mismatches > 0 {
             warn!(
                 total = summary.total_tests,
                 passed = summary.passed,
                 failed = summary.failed,
+                golden_mismatches = summary.golden_mismatches,
                 duration_ms = summary.duration_ms,
                 "Startup validation completed with failures"
             );
@@ -277,6 +411,7 @@ impl StartupValidator {
                         style: "temperature".to_string(),
                         test_bbox: [-130.0, 20.0, -60.0, 55.0], // CONUS
                         description: "GFS Temperature".to_string(),
+                        expect_coverage: true,
                     });
                     targets.push(ValidationTarget {
                         model: "gfs".to_string(),
@@ -284,6 +419,7 @@ impl StartupValidator {
                         style: "default".to_string(),
                         test_bbox: [-130.0, 20.0, -60.0, 55.0],
                         description: "GFS Wind Barbs".to_string(),
+                        expect_coverage: true,
                     });
                     targets.push(ValidationTarget {
                         model: "gfs".to_string(),
@@ -291,6 +427,7 @@ impl StartupValidator {
                         style: "atmospheric".to_string(),
                         test_bbox: [-180.0, -60.0, 180.0, 60.0], // Global
                         description: "GFS Pressure".to_string(),
+                        expect_coverage: true,
                     });
                 }
                 "hrrr" => {
@@ -301,6 +438,7 @@ impl StartupValidator {
                         style: "temperature".to_string(),
                         test_bbox: [-125.0, 21.0, -60.0, 50.0], // CONUS
                         description: "HRRR Temperature".to_string(),
+                        expect_coverage: true,
                     });
                     targets.push(ValidationTarget {
                         model: "hrrr".to_string(),
@@ -308,9 +446,10 @@ impl StartupValidator {
                         style: "default".to_string(),
                         test_bbox: [-125.0, 21.0, -60.0, 50.0],
                         description: "HRRR Wind Barbs".to_string(),
+                        expect_coverage: true,
                     });
                 }
-                "goes16" | "goes18" => {
+                m if m.starts_with("goes") => {
                     // GOES: Satellite imagery
                     let goes_model = model.clone();
                     targets.push(ValidationTarget {
@@ -319,6 +458,7 @@ impl StartupValidator {
                         style: "goes_visible".to_string(),
                         test_bbox: [-140.0, 15.0, -55.0, 55.0], // CONUS Full Disk view
                         description: format!("{} Visible", model.to_uppercase()),
+                        expect_coverage: true,
                     });
                     targets.push(ValidationTarget {
                         model: goes_model,
@@ -326,6 +466,7 @@ impl StartupValidator {
                         style: "goes_ir".to_string(),
                         test_bbox: [-140.0, 15.0, -55.0, 55.0],
                         description: format!("{} Infrared", model.to_uppercase()),
+                        expect_coverage: true,
                     });
                 }
                 "mrms" => {
@@ -336,6 +477,7 @@ impl StartupValidator {
                         style: "reflectivity".to_string(),
                         test_bbox: [-130.0, 20.0, -60.0, 55.0], // CONUS
                         description: "MRMS Reflectivity".to_string(),
+                        expect_coverage: false,
                     });
                     targets.push(ValidationTarget {
                         model: "mrms".to_string(),
@@ -343,6 +485,7 @@ impl StartupValidator {
                         style: "precip_rate".to_string(),
                         test_bbox: [-130.0, 20.0, -60.0, 55.0],
                         description: "MRMS Precip Rate".to_string(),
+                        expect_coverage: false,
                     });
                 }
                 _ => {
@@ -390,6 +533,215 @@ impl StartupValidator {
 
         results
     }
+
+    /// Render each distinct layer/style of the targets as a golden image
+    /// and compare it with its reference in `dir`.
+    async fn run_golden_checks(
+        &self,
+        targets: &[ValidationTarget],
+        dir: &Path,
+    ) -> Vec<GoldenCheckResult> {
+        let mut seen = HashSet::new();
+        let mut checks = Vec::new();
+        for target in targets {
+            // Wind barbs are drawn glyphs, not a color-mapped field
+            if target.parameter == "WIND_BARBS"
+                || !seen.insert((
+                    target.model.clone(),
+                    target.parameter.clone(),
+                    target.style.clone(),
+                ))
+            {
+                continue;
+            }
+            let style_file = self
+                .state
+                .layer_configs
+                .read()
+                .await
+                .get_style_file_for_parameter(&target.model, &target.parameter);
+            let layer = format!("{}_{}", target.model, target.parameter);
+            checks.push(self.golden_check(&layer, &style_file, &target.style, dir));
+        }
+        checks
+    }
+
+    /// Render one golden image and compare it with its reference.
+    fn golden_check(
+        &self,
+        layer: &str,
+        style_file: &str,
+        style: &str,
+        dir: &Path,
+    ) -> GoldenCheckResult {
+        let reference = dir.join(format!("{}_{}.png", layer, style));
+        let mut result = GoldenCheckResult {
+            layer: layer.to_string(),
+            style: style.to_string(),
+            status: GoldenStatus::Error,
+            reference: reference.display().to_string(),
+            differing_pixels: 0,
+            total_pixels: GOLDEN_SIZE * GOLDEN_SIZE,
+            max_channel_diff: 0,
+            error: None,
+        };
+
+        let png = match render_golden(style_file, style) {
+            Ok(png) => png,
+            Err(e) => {
+                result.error = Some(e);
+                return result;
+            }
+        };
+
+        let reference_png = match std::fs::read(&reference) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                result.status = GoldenStatus::Missing;
+                if self.config.golden_update {
+                    match std::fs::create_dir_all(dir)
+                        .and_then(|_| std::fs::write(&reference, &png))
+                    {
+                        Ok(()) => result.status = GoldenStatus::Written,
+                        Err(e) => result.error = Some(format!("Failed to write reference: {}", e)),
+                    }
+                }
+                return result;
+            }
+            Err(e) => {
+                result.error = Some(format!("Failed to read reference: {}", e));
+                return result;
+            }
+        };
+
+        let (actual, expected) = match (decode_rgba(&png), decode_rgba(&reference_png)) {
+            (Ok(actual), Ok(expected)) => (actual, expected),
+            (Err(e), _) | (_, Err(e)) => {
+                result.error = Some(e);
+                return result;
+            }
+        };
+        if actual.dimensions() != expected.dimensions() {
+            result.status = GoldenStatus::Mismatch;
+            result.error = Some(format!(
+                "Render is {:?}, reference is {:?}",
+                actual.dimensions(),
+                expected.dimensions()
+            ));
+            return result;
+        }
+
+        let diff = diff_rgba(
+            actual.as_raw(),
+            expected.as_raw(),
+            self.config.golden_tolerance,
+        );
+        result.differing_pixels = diff.differing_pixels;
+        result.max_channel_diff = diff.max_channel_diff;
+        let allowed = (result.total_pixels as f64 * self.config.golden_max_diff_fraction) as usize;
+        result.status = if diff.differing_pixels > allowed {
+            GoldenStatus::Mismatch
+        } else {
+            GoldenStatus::Match
+        };
+        result
+    }
+}
+
+/// Render the golden ramp for a style as a PNG.
+fn render_golden(style_file: &str, style_name: &str) -> Result<Vec<u8>, String> {
+    let config = StyleConfig::from_file(style_file)
+        .map_err(|e| format!("Failed to load style file '{}': {}", style_file, e))?;
+    let style = if style_name == "default" {
+        config.get_default_style().map(|(_, s)| s)
+    } else {
+        config.get_style(style_name)
+    }
+    .ok_or_else(|| format!("Style '{}' not found in '{}'", style_name, style_file))?;
+
+    let data = golden_field(style, GOLDEN_SIZE, GOLDEN_SIZE);
+    let rendered = rendering::render_with_style_file_indexed(
+        &data,
+        None,
+        style_file,
+        Some(style_name),
+        GOLDEN_SIZE,
+        GOLDEN_SIZE,
+    )?;
+    renderer::png::create_png_from_precomputed(
+        &rendered.indices,
+        GOLDEN_SIZE,
+        GOLDEN_SIZE,
+        &rendered.palette,
+    )
+}
+
+/// Synthetic field for a golden render: a left-to-right ramp across the
+/// style's range (in data units), with no-data rows at the bottom.
+fn golden_field(style: &StyleDefinition, width: usize, height: usize) -> Vec<f32> {
+    let (min, max) = match &style.range {
+        Some(range) => (range.min, range.max),
+        None => style
+            .stops
+            .iter()
+            .map(|s| s.value)
+            .fold(None, |acc: Option<(f32, f32)>, v| {
+                Some(acc.map_or((v, v), |(lo, hi)| (lo.min(v), hi.max(v))))
+            })
+            .unwrap_or((0.0, 1.0)),
+    };
+
+    let mut data = Vec::with_capacity(width * height);
+    for row in 0..height {
+        for col in 0..width {
+            if row + GOLDEN_NODATA_ROWS >= height {
+                data.push(f32::NAN);
+                continue;
+            }
+            let t = col as f32 / (width.max(2) - 1) as f32;
+            let value = min + t * (max - min);
+            // Stops are in transformed units; feed the renderer raw values
+            let raw = style
+                .transform
+                .as_ref()
+                .and_then(|transform| transform.invert(value))
+                .unwrap_or(value);
+            data.push(raw);
+        }
+    }
+    data
+}
+
+fn decode_rgba(png: &[u8]) -> Result<image::RgbaImage, String> {
+    image::load_from_memory_with_format(png, image::ImageFormat::Png)
+        .map(|img| img.to_rgba8())
+        .map_err(|e| format!("Failed to decode PNG: {}", e))
+}
+
+/// Whether a PNG decodes to an image with no visible pixels.
+fn is_fully_transparent(png: &[u8]) -> bool {
+    decode_rgba(png).is_ok_and(|img| img.pixels().all(|p| p[3] == 0))
+}
+
+/// Compare two RGBA buffers of equal length pixel by pixel.
+fn diff_rgba(actual: &[u8], reference: &[u8], tolerance: u8) -> ImageDiff {
+    let mut diff = ImageDiff {
+        differing_pixels: 0,
+        max_channel_diff: 0,
+    };
+    for (a, b) in actual.chunks_exact(4).zip(reference.chunks_exact(4)) {
+        let pixel_max = a
+            .iter()
+            .zip(b)
+            .map(|(x, y)| x.abs_diff(*y))
+            .max()
+            .unwrap_or(0);
+        diff.max_channel_diff = diff.max_channel_diff.max(pixel_max);
+        if pixel_max > tolerance {
+            diff.differing_pixels += 1;
+        }
+    }
+    diff
 }
 
 /// Run a single validation test.
@@ -489,8 +841,17 @@ async fn run_single_validation(
             // Validate PNG format
             let is_valid_png = tile_data.len() >= 8
                 && tile_data[0..8] == [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+            // A render that "succeeds" with nothing visible is as broken as
+            // one that errors
+            let problem = if !is_valid_png {
+                Some("Invalid PNG format")
+            } else if target.expect_coverage && is_fully_transparent(&tile_data) {
+                Some("Tile is fully transparent")
+            } else {
+                None
+            };
 
-            if is_valid_png {
+            if problem.is_none() {
                 // Store in cache for warming benefit
                 cache_tile(state, &layer, &target.style, &coord, &tile_data).await;
 
@@ -517,7 +878,8 @@ async fn run_single_validation(
                 warn!(
                     layer = %layer,
                     zoom = zoom,
-                    "Validation failed: invalid PNG format"
+                    problem = problem.unwrap_or_default(),
+                    "Validation failed"
                 );
 
                 ValidationTestResult {
@@ -529,7 +891,7 @@ async fn run_single_validation(
                     success: false,
                     render_time_ms: render_time.as_millis() as u64,
                     tile_size_bytes: tile_data.len(),
-                    error: Some("Invalid PNG format".to_string()),
+                    error: problem.map(str::to_string),
                 }
             }
         }
@@ -578,34 +940,21 @@ async fn cache_tile(
     coord: &TileCoord,
     data: &[u8],
 ) {
-    use storage::CacheKey;
-    use wms_common::{BoundingBox, CrsCode};
+    use wms_common::CrsCode;
 
     // Build cache key
-    let cache_key_str = format!(
-        "{}:{}:EPSG:3857:{}_{}_{}:current",
-        layer, style, coord.z, coord.x, coord.y
-    );
+    let (cache_key, _) = state
+        .tile_cache_key(layer, style, CrsCode::Epsg3857, *coord, None)
+        .await;
 
     // Store in L1 cache
     let data_bytes = bytes::Bytes::from(data.to_vec());
     state
         .tile_memory_cache
-        .set(&cache_key_str, data_bytes, None)
+        .set(&cache_key.to_string(), data_bytes, None)
         .await;
 
     // Store in L2 cache
-    let cache_key = CacheKey::new(
-        layer,
-        style,
-        CrsCode::Epsg3857,
-        BoundingBox::new(coord.x as f64, coord.y as f64, coord.z as f64, 0.0),
-        256,
-        256,
-        None,
-        "png",
-    );
-
     let mut cache = state.cache.lock().await;
     if let Err(e) = cache.set(&cache_key, data, None).await {
         debug!(error = %e, "Failed to store validation tile in L2 cache");
@@ -620,3 +969,73 @@ fn lat_lon_to_tile(lat: f64, lon: f64, zoom: u32) -> TileCoord {
     let y = ((1.0 - lat_rad.tan().asinh() / std::f64::consts::PI) / 2.0 * n).floor() as u32;
     TileCoord::new(zoom, x.min((n as u32) - 1), y.min((n as u32) - 1))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temperature_style_file() -> String {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../config/styles/temperature.json")
+            .display()
+            .to_string()
+    }
+
+    #[test]
+    fn test_diff_rgba_tolerance() {
+        let reference = [10, 20, 30, 255, 0, 0, 0, 0];
+        let actual = [12, 20, 30, 255, 0, 9, 0, 0];
+
+        let diff = diff_rgba(&actual, &reference, 2);
+        assert_eq!(diff.differing_pixels, 1);
+        assert_eq!(diff.max_channel_diff, 9);
+
+        assert_eq!(diff_rgba(&actual, &reference, 9).differing_pixels, 0);
+        assert_eq!(diff_rgba(&reference, &reference, 0).differing_pixels, 0);
+    }
+
+    #[test]
+    fn test_golden_field_ramp_and_nodata() {
+        let config = StyleConfig::from_file(&temperature_style_file()).unwrap();
+        let (_, style) = config.get_default_style().unwrap();
+        let data = golden_field(style, 16, 16);
+
+        assert_eq!(data.len(), 256);
+        let first_row = &data[..16];
+        assert!(first_row.windows(2).all(|w| w[1] > w[0]));
+        assert!(data[16 * (16 - GOLDEN_NODATA_ROWS)..]
+            .iter()
+            .all(|v| v.is_nan()));
+    }
+
+    #[test]
+    fn test_golden_render_is_deterministic() {
+        let style_file = temperature_style_file();
+        let first = decode_rgba(&render_golden(&style_file, "default").unwrap()).unwrap();
+        let second = decode_rgba(&render_golden(&style_file, "default").unwrap()).unwrap();
+
+        assert_eq!(first.dimensions(), (GOLDEN_SIZE as u32, GOLDEN_SIZE as u32));
+        assert_eq!(
+            diff_rgba(first.as_raw(), second.as_raw(), 0).differing_pixels,
+            0
+        );
+        // Ramp rows are colored, no-data rows are transparent
+        assert_eq!(first.get_pixel(GOLDEN_SIZE as u32 / 2, 0)[3], 255);
+        assert_eq!(first.get_pixel(0, GOLDEN_SIZE as u32 - 1)[3], 0);
+
+        assert!(render_golden(&style_file, "no_such_style").is_err());
+    }
+
+    #[test]
+    fn test_is_fully_transparent() {
+        let blank = renderer::png::create_png(&[0u8; 4 * 4 * 4], 4, 4).unwrap();
+        assert!(is_fully_transparent(&blank));
+
+        let mut pixels = [0u8; 4 * 4 * 4];
+        pixels[..4].copy_from_slice(&[255, 0, 0, 255]);
+        let one_pixel = renderer::png::create_png(&pixels, 4, 4).unwrap();
+        assert!(!is_fully_transparent(&one_pixel));
+
+        assert!(!is_fully_transparent(b"not a png"));
+    }
+}
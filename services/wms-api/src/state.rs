@@ -7,11 +7,22 @@ use tokio::sync::Mutex;
 use tracing::info;
 
 use crate::capabilities_cache::CapabilitiesCache;
+use crate::completeness::CompletenessMonitor;
+use crate::export::{ExportConfig, ExportJobs};
 use crate::layer_config::LayerConfigRegistry;
 use crate::metrics::MetricsCollector;
 use crate::model_config::ModelDimensionRegistry;
+use crate::preview::{PreviewConfig, PreviewMatrix};
+use crate::request_limits::RequestLimits;
+use crate::underlay::{Underlay, UnderlayConfig};
+use crate::update_cadence::{CachePolicy, UpdateCadence};
 use grid_processor::{GridProcessorFactory, MinioConfig};
-use storage::{Catalog, ObjectStorage, ObjectStorageConfig, TileCache, TileMemoryCache};
+use storage::{
+    BenchmarkStore, CacheKey, Catalog, CircuitBreakerConfig, KeyNormalization, ObjectStorage,
+    ObjectStorageConfig, RetryPolicy, TileArchive, TileCache, TileMemoryCache,
+};
+use wms_common::{CrsCode, TenantRegistry, TenantUsage, TileCoord};
+use wms_protocol::ParseMode;
 
 /// Configuration for performance optimizations.
 /// Each optimization can be toggled on/off via environment variables.
@@ -35,6 +46,13 @@ pub struct OptimizationConfig {
     // Cache Warming
     pub cache_warming_enabled: bool,
 
+    // Tile Archive (pre-rendered tile sets in object storage)
+    pub tile_archive_enabled: bool,
+
+    // Cache key normalization (canonical keys for equivalent requests)
+    pub cache_key_normalization: bool,
+    pub cache_key_bbox_tolerance: f64, // Bbox edge tolerance in pixels (default 0.01)
+
     // Memory Pressure Management
     pub memory_pressure_enabled: bool,
     pub memory_limit_mb: usize, // Hard limit for total memory (0 = auto-detect from cgroup)
@@ -94,6 +112,17 @@ impl OptimizationConfig {
             // Cache Warming
             cache_warming_enabled: parse_bool("ENABLE_CACHE_WARMING", true),
 
+            // Tile Archive
+            tile_archive_enabled: parse_bool("ENABLE_TILE_ARCHIVE", false),
+
+            // Cache Key Normalization
+            cache_key_normalization: parse_bool("ENABLE_CACHE_KEY_NORMALIZATION", true),
+            cache_key_bbox_tolerance: env::var("CACHE_KEY_BBOX_TOLERANCE")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| *v >= 0.0)
+                .unwrap_or(0.01),
+
             // Memory Pressure Management
             memory_pressure_enabled: parse_bool("ENABLE_MEMORY_PRESSURE", true),
             memory_limit_mb: parse_usize("MEMORY_LIMIT_MB", 0), // 0 = auto-detect
@@ -114,6 +143,14 @@ impl OptimizationConfig {
             memory_check_interval_secs: parse_u64("MEMORY_CHECK_INTERVAL_SECS", 30),
         }
     }
+
+    /// Cache key normalization settings.
+    pub fn key_normalization(&self) -> KeyNormalization {
+        KeyNormalization {
+            enabled: self.cache_key_normalization,
+            bbox_tolerance: self.cache_key_bbox_tolerance,
+        }
+    }
 }
 
 // GridProcessorFactory is now imported from grid_processor crate.
@@ -127,6 +164,7 @@ impl OptimizationConfig {
 /// Shared application state.
 pub struct AppState {
     pub catalog: Catalog,
+    pub benchmarks: Option<BenchmarkStore>, // Load test and Criterion history (None = in-memory catalog)
     pub cache: Mutex<TileCache>,
     pub tile_memory_cache: TileMemoryCache, // L1 cache for rendered tiles
     pub storage: Arc<ObjectStorage>,
@@ -139,6 +177,18 @@ pub struct AppState {
     pub model_dimensions: ModelDimensionRegistry, // Model dimension configurations (from YAML)
     pub layer_configs: tokio::sync::RwLock<LayerConfigRegistry>, // Layer configurations (from YAML) - styles, units, levels
     pub capabilities_cache: CapabilitiesCache, // Cache for WMS/WMTS capabilities documents
+    pub tile_archive: Option<TileArchive>, // Published tile sets in object storage (None = disabled)
+    pub wms_parse_mode: ParseMode,         // Strict rejects WMS requests that deviate from the spec
+    pub exports: ExportJobs,               // Bulk export jobs (NetCDF/Zarr bundles)
+    pub request_limits: RequestLimits,     // GetMap size/complexity limits
+    pub previews: PreviewMatrix,           // Admin layer/style thumbnail matrix
+    pub update_cadence: UpdateCadence,     // Per-model update cadence for cache lifetimes
+    pub completeness: CompletenessMonitor, // Expected vs. ingested datasets of recent runs
+    pub underlay: Underlay,                // Basemap/land-sea imagery beneath GetMap images
+    pub time_tolerance: Option<std::time::Duration>, // Nearest-TIME match tolerance (None = model cadence)
+    pub tenants: TenantRegistry,                     // API key -> tenant -> visible layers
+    pub tenant_usage: TenantUsage,                   // Requests per tenant
+    pub golden_renders_ok: std::sync::atomic::AtomicBool, // False while a startup golden render mismatches
 }
 
 impl AppState {
@@ -177,9 +227,18 @@ impl AppState {
             allow_http: env::var("S3_ALLOW_HTTP")
                 .map(|v| v.to_lowercase() == "true" || v == "1")
                 .unwrap_or(true),
+            public_endpoint: env::var("S3_PUBLIC_ENDPOINT").ok(),
+            retry: RetryPolicy::from_env(),
+            circuit_breaker: CircuitBreakerConfig::from_env(),
         };
 
-        let catalog = Catalog::connect_with_pool_size(&database_url, db_pool_size).await?;
+        // Latest-run queries skip runs still being ingested unless overridden
+        let serve_unpublished_runs = env::var("SERVE_UNPUBLISHED_RUNS")
+            .map(|v| v.to_lowercase() == "true" || v == "1")
+            .unwrap_or(false);
+        let catalog = Catalog::connect_with_pool_size(&database_url, db_pool_size)
+            .await?
+            .with_unpublished_runs(serve_unpublished_runs);
         let cache = TileCache::connect(&redis_url, redis_tile_ttl_secs).await?;
         let storage = Arc::new(ObjectStorage::new(&storage_config)?);
         let metrics = Arc::new(MetricsCollector::new());
@@ -236,7 +295,46 @@ impl AppState {
             .unwrap_or(120);
         let capabilities_cache = CapabilitiesCache::new(capabilities_cache_ttl);
 
+        // Tile archive shares the grid data bucket
+        let tile_archive = if optimization_config.tile_archive_enabled {
+            let prefix = env::var("TILE_ARCHIVE_PREFIX").unwrap_or_else(|_| "archives".to_string());
+            let manifest_ttl_secs = env::var("TILE_ARCHIVE_MANIFEST_TTL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60);
+            info!(prefix = %prefix, manifest_ttl_secs = manifest_ttl_secs, "Tile archive enabled");
+            Some(TileArchive::new(
+                storage.clone(),
+                &prefix,
+                std::time::Duration::from_secs(manifest_ttl_secs),
+            ))
+        } else {
+            None
+        };
+
+        let wms_parse_mode = env::var("WMS_PARSE_MODE")
+            .ok()
+            .and_then(|v| v.parse::<ParseMode>().ok())
+            .unwrap_or_default();
+        info!(mode = %wms_parse_mode, "WMS request parse mode");
+
+        let exports = ExportJobs::new(ExportConfig::from_env());
+
+        let request_limits = RequestLimits::from_env();
+        info!(
+            max_width = request_limits.max_width,
+            max_height = request_limits.max_height,
+            max_pixels = request_limits.max_pixels,
+            max_layers = request_limits.max_layers,
+            trusted_keys = request_limits.trusted_api_keys.len(),
+            "WMS GetMap limits"
+        );
+
+        let tenants = TenantRegistry::from_env(&config_dir).map_err(anyhow::Error::msg)?;
+        info!(tenants = tenants.tenants().len(), "Loaded tenants");
+
         Ok(Self {
+            benchmarks: BenchmarkStore::from_catalog(&catalog),
             catalog,
             cache: Mutex::new(cache),
             tile_memory_cache,
@@ -249,6 +347,135 @@ impl AppState {
             model_dimensions,
             layer_configs,
             capabilities_cache,
+            tile_archive,
+            wms_parse_mode,
+            exports,
+            request_limits,
+            previews: PreviewMatrix::new(PreviewConfig::from_env()),
+            update_cadence: UpdateCadence::new(),
+            completeness: CompletenessMonitor::load_from_directory(&config_dir),
+            underlay: Underlay::new(UnderlayConfig::from_env()),
+            time_tolerance: crate::time_match::tolerance_from_env(),
+            tenants,
+            tenant_usage: TenantUsage::new(),
+            golden_renders_ok: std::sync::atomic::AtomicBool::new(true),
         })
     }
+
+    /// State for running without Postgres, MinIO or Redis.
+    ///
+    /// The catalog starts empty and lives in memory (register fixtures with
+    /// `catalog.register_dataset`), objects and Zarr grids are read from
+    /// `data_dir` using their storage paths, and the Redis tile cache is
+    /// disabled. Background features (prefetch, warming, tile archive,
+    /// memory pressure) are off so requests are served deterministically.
+    #[cfg(feature = "offline")]
+    pub fn offline(
+        data_dir: impl AsRef<std::path::Path>,
+        config_dir: impl AsRef<std::path::Path>,
+    ) -> Result<Self> {
+        let data_dir = data_dir.as_ref();
+        let config_dir = config_dir.as_ref();
+
+        let optimization_config = OptimizationConfig {
+            chunk_cache_size_mb: 64,
+            prefetch_enabled: false,
+            cache_warming_enabled: false,
+            tile_archive_enabled: false,
+            memory_pressure_enabled: false,
+            ..OptimizationConfig::from_env()
+        };
+
+        let grid_processor_factory = GridProcessorFactory::new(
+            MinioConfig::local(data_dir),
+            optimization_config.chunk_cache_size_mb,
+        );
+        info!(data_dir = %data_dir.display(), "Offline mode: serving grids from local directory");
+
+        Ok(Self {
+            catalog: Catalog::in_memory(),
+            benchmarks: None,
+            cache: Mutex::new(TileCache::disabled()),
+            tile_memory_cache: TileMemoryCache::new(
+                optimization_config.l1_cache_size_mb,
+                optimization_config.l1_cache_ttl_secs,
+            ),
+            storage: Arc::new(ObjectStorage::local(data_dir)?),
+            grid_processor_factory,
+            metrics: Arc::new(MetricsCollector::new()),
+            prefetch_rings: 0,
+            chunk_warmer: tokio::sync::RwLock::new(None),
+            model_dimensions: ModelDimensionRegistry::load_from_directory(config_dir),
+            layer_configs: tokio::sync::RwLock::new(LayerConfigRegistry::load_from_directory(
+                config_dir,
+            )),
+            capabilities_cache: CapabilitiesCache::new(0),
+            tile_archive: None,
+            wms_parse_mode: ParseMode::default(),
+            exports: ExportJobs::new(ExportConfig::default()),
+            request_limits: RequestLimits::default(),
+            previews: PreviewMatrix::new(PreviewConfig::default()),
+            update_cadence: UpdateCadence::new(),
+            completeness: CompletenessMonitor::load_from_directory(config_dir),
+            underlay: Underlay::new(UnderlayConfig::default()),
+            time_tolerance: None,
+            tenants: TenantRegistry::load(config_dir.join(wms_common::tenant::TENANTS_FILE))
+                .map_err(anyhow::Error::msg)?,
+            tenant_usage: TenantUsage::new(),
+            golden_renders_ok: std::sync::atomic::AtomicBool::new(true),
+            optimization_config,
+        })
+    }
+
+    /// Cache policy for responses of a layer of `model`. `pinned` responses
+    /// name an exact run and forecast hour or observation time.
+    pub fn cache_policy(&self, model: &str, pinned: bool) -> CachePolicy {
+        self.update_cadence
+            .cache_policy(model, pinned, &self.model_dimensions)
+    }
+
+    /// Furthest a requested TIME may be from the observation used for it.
+    pub fn time_tolerance(&self, model: &str) -> std::time::Duration {
+        self.time_tolerance
+            .unwrap_or_else(|| self.update_cadence.cadence(model, &self.model_dimensions))
+    }
+
+    /// L1/L2 cache key for a 256x256 tile.
+    ///
+    /// The style is resolved through the layer's style policy so aliases and
+    /// the default style share entries, and the key is normalized (see
+    /// [`CacheKey::normalized`]). Also returns whether the canonical key
+    /// differs from the key as requested, for hit-rate metrics.
+    pub async fn tile_cache_key(
+        &self,
+        layer: &str,
+        style: &str,
+        crs: CrsCode,
+        coord: TileCoord,
+        time: Option<String>,
+    ) -> (CacheKey, bool) {
+        let requested = CacheKey::for_tile(layer, style, crs, coord, time, "png");
+        let normalization = self.optimization_config.key_normalization();
+        if !normalization.enabled {
+            return (requested, false);
+        }
+
+        let mut canonical = requested.clone();
+        if let Some((model, parameter)) = layer.split_once('_') {
+            canonical.style = self
+                .layer_configs
+                .read()
+                .await
+                .resolve_style(model, &parameter.to_uppercase(), Some(style))
+                .name;
+        }
+        let canonical = canonical.normalized(&normalization);
+
+        let as_requested = CacheKey {
+            version: canonical.version,
+            ..requested
+        };
+        let rewritten = as_requested.to_string() != canonical.to_string();
+        (canonical, rewritten)
+    }
 }
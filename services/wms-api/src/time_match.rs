@@ -0,0 +1,283 @@
+//! TIME dimension defaults and nearest-value matching (WMS 1.3.0 Annex C).
+//!
+//! Observation layers advertise their latest time as the TIME default with
+//! `nearestValue="1"`. A GetMap request without TIME uses that default; one
+//! whose TIME falls between observations uses the nearest available time if
+//! it is within the tolerance, and fails with `InvalidDimensionValue`
+//! otherwise. Either substitution is reported in a `Warning` header naming
+//! the time actually used.
+//!
+//! The tolerance is `TIME_TOLERANCE_SECS` if set, else the model's update
+//! cadence (see [`crate::update_cadence`]), so a request never matches an
+//! observation further away than the gap between two of them.
+//!
+//! Animations of observation layers use fixed frame times instead of the
+//! scans' irregular ones: [`frame_times`] spaces frames evenly and
+//! [`match_frames`] picks the scan shown in each.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::env;
+use std::time::Duration;
+
+/// Keywords for the default time, accepted in place of a timestamp.
+const DEFAULT_TIME_KEYWORDS: &[&str] = &["current", "latest", "now"];
+
+/// How a layer's TIME was matched to the available times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeMatch {
+    /// The requested time is available
+    Exact(DateTime<Utc>),
+    /// The requested time isn't available; the nearest one within the
+    /// tolerance is used
+    Nearest {
+        requested: DateTime<Utc>,
+        used: DateTime<Utc>,
+    },
+    /// TIME was omitted; the default (latest) time is used
+    Default(DateTime<Utc>),
+}
+
+impl TimeMatch {
+    /// The time rendered.
+    pub fn used(&self) -> DateTime<Utc> {
+        match *self {
+            TimeMatch::Exact(time) | TimeMatch::Default(time) => time,
+            TimeMatch::Nearest { used, .. } => used,
+        }
+    }
+
+    /// Value of the `Warning` header reporting a substituted time.
+    pub fn warning(&self) -> Option<String> {
+        let kind = match self {
+            TimeMatch::Exact(_) => return None,
+            TimeMatch::Nearest { .. } => "Nearest",
+            TimeMatch::Default(_) => "Default",
+        };
+        Some(format!(
+            "99 {} value used: time={} ISO8601",
+            kind,
+            format_time(self.used())
+        ))
+    }
+}
+
+/// `TIME_TOLERANCE_SECS`, if set.
+pub fn tolerance_from_env() -> Option<Duration> {
+    env::var("TIME_TOLERANCE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+}
+
+/// Whether a TIME value asks for the default time.
+pub fn is_default_keyword(time: &str) -> bool {
+    DEFAULT_TIME_KEYWORDS
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(time.trim()))
+}
+
+/// Match a requested time (None = omitted) against the available times.
+///
+/// Returns `Ok(None)` if the layer has no times to match against.
+pub fn match_time(
+    requested: Option<DateTime<Utc>>,
+    available: &[DateTime<Utc>],
+    tolerance: Duration,
+) -> Result<Option<TimeMatch>, String> {
+    let Some(requested) = requested else {
+        return Ok(available.iter().max().copied().map(TimeMatch::Default));
+    };
+    let Some(&nearest) = available
+        .iter()
+        .min_by_key(|t| (**t - requested).num_milliseconds().abs())
+    else {
+        return Ok(None);
+    };
+
+    let offset = (nearest - requested)
+        .abs()
+        .to_std()
+        .unwrap_or(Duration::MAX);
+    if offset.is_zero() {
+        Ok(Some(TimeMatch::Exact(nearest)))
+    } else if offset <= tolerance {
+        Ok(Some(TimeMatch::Nearest {
+            requested,
+            used: nearest,
+        }))
+    } else {
+        Err(format!(
+            "TIME {} is not available; the nearest time {} is {} s away (tolerance {} s)",
+            format_time(requested),
+            format_time(nearest),
+            offset.as_secs(),
+            tolerance.as_secs()
+        ))
+    }
+}
+
+/// An animation frame and the observation shown in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FrameMatch {
+    /// Nominal frame time
+    pub frame: DateTime<Utc>,
+    /// Observation time shown, or None to skip the frame
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// `count` frame times `step` apart, oldest first, the last at `end`
+/// rounded to a multiple of `step` (so frames fall on round times).
+pub fn frame_times(end: DateTime<Utc>, step: chrono::Duration, count: usize) -> Vec<DateTime<Utc>> {
+    let step_ms = step.num_milliseconds();
+    if step_ms <= 0 || count == 0 {
+        return Vec::new();
+    }
+
+    let end_ms = end.timestamp_millis();
+    let last_ms = (end_ms + step_ms / 2).div_euclid(step_ms) * step_ms;
+    (0..count as i64)
+        .rev()
+        .filter_map(|i| DateTime::from_timestamp_millis(last_ms - i * step_ms))
+        .collect()
+}
+
+/// Match ascending frame times to the available observations.
+///
+/// Each frame shows the nearest observation within the tolerance that is
+/// later than the previous frame's, so an observation is never shown twice
+/// in a row and the animation never steps backwards. Frames with no such
+/// observation are left empty.
+pub fn match_frames(
+    frames: &[DateTime<Utc>],
+    available: &[DateTime<Utc>],
+    tolerance: Duration,
+) -> Vec<FrameMatch> {
+    let tolerance_ms = i64::try_from(tolerance.as_millis()).unwrap_or(i64::MAX);
+    let mut previous: Option<DateTime<Utc>> = None;
+
+    frames
+        .iter()
+        .map(|&frame| {
+            let time = available
+                .iter()
+                .copied()
+                .filter(|t| previous.is_none_or(|p| *t > p))
+                .map(|t| ((t - frame).num_milliseconds().abs(), t))
+                .filter(|(offset, _)| *offset <= tolerance_ms)
+                .min()
+                .map(|(_, t)| t);
+            if time.is_some() {
+                previous = time;
+            }
+            FrameMatch { frame, time }
+        })
+        .collect()
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 12, 3, 0, minute, second)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_exact_and_nearest_match() {
+        let available = [at(10, 0), at(8, 0), at(6, 0)];
+        let tolerance = Duration::from_secs(120);
+
+        assert_eq!(
+            match_time(Some(at(8, 0)), &available, tolerance),
+            Ok(Some(TimeMatch::Exact(at(8, 0))))
+        );
+
+        let nearest = match_time(Some(at(8, 50)), &available, tolerance)
+            .unwrap()
+            .unwrap();
+        assert_eq!(nearest.used(), at(8, 0));
+        assert_eq!(
+            nearest.warning().as_deref(),
+            Some("99 Nearest value used: time=2024-12-03T00:08:00Z ISO8601")
+        );
+        assert_eq!(TimeMatch::Exact(at(8, 0)).warning(), None);
+    }
+
+    #[test]
+    fn test_outside_tolerance_is_rejected() {
+        let available = [at(10, 0)];
+        let err = match_time(Some(at(0, 0)), &available, Duration::from_secs(120)).unwrap_err();
+        assert!(err.contains("600 s away"), "{}", err);
+    }
+
+    #[test]
+    fn test_omitted_time_uses_latest() {
+        let available = [at(6, 0), at(10, 0), at(8, 0)];
+        let default = match_time(None, &available, Duration::ZERO)
+            .unwrap()
+            .unwrap();
+        assert_eq!(default, TimeMatch::Default(at(10, 0)));
+        assert_eq!(
+            default.warning().as_deref(),
+            Some("99 Default value used: time=2024-12-03T00:10:00Z ISO8601")
+        );
+
+        assert_eq!(match_time(None, &[], Duration::ZERO), Ok(None));
+        assert_eq!(match_time(Some(at(0, 0)), &[], Duration::ZERO), Ok(None));
+    }
+
+    #[test]
+    fn test_frame_times() {
+        let step = chrono::Duration::minutes(5);
+        assert_eq!(
+            frame_times(at(9, 50), step, 3),
+            vec![at(0, 0), at(5, 0), at(10, 0)]
+        );
+        assert_eq!(frame_times(at(6, 0), step, 1), vec![at(5, 0)]);
+        assert!(frame_times(at(6, 0), chrono::Duration::zero(), 3).is_empty());
+    }
+
+    #[test]
+    fn test_match_frames() {
+        let frames = [at(0, 0), at(1, 0), at(2, 0), at(3, 0), at(4, 0)];
+        // Irregular scans, with one missing around 00:03
+        let available = [at(0, 10), at(0, 50), at(2, 20), at(4, 40)];
+        let tolerance = Duration::from_secs(45);
+
+        let used: Vec<Option<DateTime<Utc>>> = match_frames(&frames, &available, tolerance)
+            .iter()
+            .map(|m| m.time)
+            .collect();
+        assert_eq!(
+            used,
+            vec![
+                Some(at(0, 10)),
+                // Nearest is 00:50, already after the previous frame's scan
+                Some(at(0, 50)),
+                Some(at(2, 20)),
+                // 02:20 was just shown; nothing else within the tolerance
+                None,
+                Some(at(4, 40)),
+            ]
+        );
+
+        // A scan nearest to two frames is shown only in the first
+        let matched = match_frames(&[at(0, 0), at(0, 30)], &[at(0, 20), at(0, 50)], tolerance);
+        assert_eq!(matched[0].time, Some(at(0, 20)));
+        assert_eq!(matched[1].time, Some(at(0, 50)));
+    }
+
+    #[test]
+    fn test_default_keywords() {
+        assert!(is_default_keyword("current"));
+        assert!(is_default_keyword("Latest"));
+        assert!(!is_default_keyword("2024-12-03T00:00:00Z"));
+    }
+}
@@ -0,0 +1,496 @@
+//! Basemap and land/sea underlays beneath rendered weather images.
+//!
+//! Weather rasters are transparent wherever a layer has no data, which reads
+//! poorly on a white page. When an underlay source is configured, GetMap
+//! draws the weather image over either:
+//!
+//! - **basemap**: tiles from an XYZ tile server, fetched on demand and kept in
+//!   memory
+//! - **landsea**: a static land/sea mask shipped as one global Web Mercator
+//!   PNG per zoom level (`<dir>/<z>.png`, 256·2^z pixels square)
+//!
+//! Both are read as 256 px Web Mercator tiles and resampled onto the request
+//! extent. GetMap uses the underlay unless the request passes
+//! `UNDERLAY=false`; WMTS and XYZ tiles only get it with `UNDERLAY_TILES=true`,
+//! since map clients normally draw their own basemap below tiles.
+
+use futures::future::join_all;
+use image::{Rgba, RgbaImage};
+use std::collections::{HashMap, VecDeque};
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::config_store::config_dir;
+
+/// Size of a Web Mercator tile in pixels.
+const TILE_SIZE: f64 = 256.0;
+
+/// Latitude limit of the Web Mercator projection.
+const MAX_MERCATOR_LAT: f64 = 85.051_128_78;
+
+/// Where underlay imagery comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnderlaySource {
+    /// XYZ tile server; the URL has `{z}`, `{x}` and `{y}` placeholders
+    Basemap { url_template: String },
+    /// Directory of global land/sea mask rasters, one `<z>.png` per zoom
+    LandSeaMask { dir: PathBuf },
+}
+
+/// Underlay settings.
+#[derive(Debug, Clone)]
+pub struct UnderlayConfig {
+    /// Underlay imagery (None = disabled)
+    pub source: Option<UnderlaySource>,
+    /// Highest zoom read from the source; deeper requests are upsampled
+    pub max_zoom: u32,
+    /// Also draw the underlay beneath WMTS/XYZ tiles
+    pub tiles: bool,
+    /// Source tiles kept in memory
+    pub cache_tiles: usize,
+    /// Longest a basemap tile fetch may take
+    pub fetch_timeout: Duration,
+}
+
+impl Default for UnderlayConfig {
+    fn default() -> Self {
+        Self {
+            source: None,
+            max_zoom: 12,
+            tiles: false,
+            cache_tiles: 512,
+            fetch_timeout: Duration::from_millis(3000),
+        }
+    }
+}
+
+impl UnderlayConfig {
+    /// Load configuration from environment variables.
+    pub fn from_env() -> Self {
+        fn parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+            env::var(key).ok().and_then(|v| v.parse().ok())
+        }
+
+        let source = match env::var("UNDERLAY_SOURCE")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "basemap" => match env::var("UNDERLAY_TILE_URL") {
+                Ok(url_template) if !url_template.is_empty() => {
+                    Some(UnderlaySource::Basemap { url_template })
+                }
+                _ => {
+                    warn!("UNDERLAY_SOURCE=basemap requires UNDERLAY_TILE_URL; underlay disabled");
+                    None
+                }
+            },
+            "landsea" => Some(UnderlaySource::LandSeaMask {
+                dir: env::var("UNDERLAY_MASK_DIR")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| config_dir().join("underlay")),
+            }),
+            "" | "none" | "off" => None,
+            other => {
+                warn!(source = %other, "Unknown UNDERLAY_SOURCE; underlay disabled");
+                None
+            }
+        };
+
+        // Global mask rasters grow 4x per zoom, so they stop much earlier
+        let default_max_zoom = match source {
+            Some(UnderlaySource::LandSeaMask { .. }) => 4,
+            _ => Self::default().max_zoom,
+        };
+
+        let defaults = Self::default();
+        Self {
+            source,
+            max_zoom: parse::<u32>("UNDERLAY_MAX_ZOOM")
+                .map(|z| z.min(22))
+                .unwrap_or(default_max_zoom),
+            tiles: env::var("UNDERLAY_TILES")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(defaults.tiles),
+            cache_tiles: parse::<usize>("UNDERLAY_CACHE_TILES")
+                .map(|n| n.max(1))
+                .unwrap_or(defaults.cache_tiles),
+            fetch_timeout: parse::<u64>("UNDERLAY_FETCH_TIMEOUT_MS")
+                .map(Duration::from_millis)
+                .unwrap_or(defaults.fetch_timeout),
+        }
+    }
+}
+
+type TileKey = (u32, u32, u32);
+
+/// Source tiles in memory, evicted oldest first.
+#[derive(Default)]
+struct TileCache {
+    tiles: HashMap<TileKey, Arc<RgbaImage>>,
+    order: VecDeque<TileKey>,
+}
+
+/// Reads underlay imagery and resamples it onto requested extents.
+pub struct Underlay {
+    config: UnderlayConfig,
+    client: reqwest::Client,
+    tiles: Mutex<TileCache>,
+    /// Decoded land/sea masks by zoom; None when the zoom's file is missing
+    masks: Mutex<HashMap<u32, Option<Arc<RgbaImage>>>>,
+}
+
+impl Underlay {
+    pub fn new(config: UnderlayConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.fetch_timeout)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            client,
+            tiles: Mutex::new(TileCache::default()),
+            masks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &UnderlayConfig {
+        &self.config
+    }
+
+    /// Whether GetMap draws an underlay, given the request's `UNDERLAY` value.
+    pub fn enabled_for_map(&self, requested: Option<bool>) -> bool {
+        self.config.source.is_some() && requested.unwrap_or(true)
+    }
+
+    /// Whether WMTS/XYZ tiles get an underlay.
+    pub fn enabled_for_tiles(&self) -> bool {
+        self.config.source.is_some() && self.config.tiles
+    }
+
+    /// Underlay for an output image covering `extent`
+    /// (`[min_lon, min_lat, max_lon, max_lat]`). Rows are spaced evenly in
+    /// Web Mercator `y` when `web_mercator` is set, else in latitude.
+    ///
+    /// Returns None when no source tile could be read.
+    pub async fn for_extent(
+        &self,
+        extent: [f32; 4],
+        width: u32,
+        height: u32,
+        web_mercator: bool,
+    ) -> Option<RgbaImage> {
+        if self.config.source.is_none() || width == 0 || height == 0 {
+            return None;
+        }
+
+        let [min_lon, min_lat, max_lon, max_lat] = extent.map(f64::from);
+        let zoom = zoom_for_extent(max_lon - min_lon, width, self.config.max_zoom);
+        let world = TILE_SIZE * 2f64.powi(zoom as i32);
+
+        let xs: Vec<f64> = (0..width)
+            .map(|i| {
+                let lon = min_lon + (i as f64 + 0.5) / width as f64 * (max_lon - min_lon);
+                lon_to_pixel(lon, world)
+            })
+            .collect();
+        let (top, bottom) = (lat_to_pixel(max_lat, world), lat_to_pixel(min_lat, world));
+        let ys: Vec<f64> = (0..height)
+            .map(|j| {
+                let t = (j as f64 + 0.5) / height as f64;
+                if web_mercator {
+                    top + t * (bottom - top)
+                } else {
+                    lat_to_pixel(max_lat - t * (max_lat - min_lat), world)
+                }
+            })
+            .collect();
+
+        let tile_index = |p: f64| (p / TILE_SIZE) as u32;
+        let mut cols: Vec<u32> = xs.iter().map(|&x| tile_index(x)).collect();
+        let mut rows: Vec<u32> = ys.iter().map(|&y| tile_index(y)).collect();
+        cols.sort_unstable();
+        cols.dedup();
+        rows.dedup();
+
+        let keys: Vec<TileKey> = rows
+            .iter()
+            .flat_map(|&y| cols.iter().map(move |&x| (zoom, x, y)))
+            .collect();
+        let fetched = join_all(keys.iter().map(|&(z, x, y)| self.tile(z, x, y))).await;
+        let tiles: HashMap<TileKey, Arc<RgbaImage>> = keys
+            .into_iter()
+            .zip(fetched)
+            .filter_map(|(key, tile)| Some((key, tile?)))
+            .collect();
+        if tiles.is_empty() {
+            return None;
+        }
+
+        let mut image = RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0]));
+        for (j, &py) in ys.iter().enumerate() {
+            for (i, &px) in xs.iter().enumerate() {
+                let key = (zoom, tile_index(px), tile_index(py));
+                if let Some(tile) = tiles.get(&key) {
+                    let tx = ((px / TILE_SIZE).fract() * tile.width() as f64) as u32;
+                    let ty = ((py / TILE_SIZE).fract() * tile.height() as f64) as u32;
+                    let pixel =
+                        *tile.get_pixel(tx.min(tile.width() - 1), ty.min(tile.height() - 1));
+                    image.put_pixel(i as u32, j as u32, pixel);
+                }
+            }
+        }
+        Some(image)
+    }
+
+    /// One 256 px Web Mercator tile of the underlay, from memory if cached.
+    pub async fn tile(&self, z: u32, x: u32, y: u32) -> Option<Arc<RgbaImage>> {
+        if let Some(tile) = self.tiles.lock().unwrap().tiles.get(&(z, x, y)) {
+            return Some(tile.clone());
+        }
+
+        let tile = match self.config.source.as_ref()? {
+            UnderlaySource::Basemap { url_template } => {
+                self.fetch_tile(url_template, z, x, y).await?
+            }
+            UnderlaySource::LandSeaMask { dir } => {
+                let mask = self.mask(dir, z)?;
+                let size = TILE_SIZE as u32;
+                if (x + 1) * size > mask.width() || (y + 1) * size > mask.height() {
+                    return None;
+                }
+                image::imageops::crop_imm(mask.as_ref(), x * size, y * size, size, size).to_image()
+            }
+        };
+
+        let tile = Arc::new(tile);
+        let mut cache = self.tiles.lock().unwrap();
+        if cache.tiles.insert((z, x, y), tile.clone()).is_none() {
+            cache.order.push_back((z, x, y));
+        }
+        while cache.order.len() > self.config.cache_tiles {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.tiles.remove(&oldest);
+            }
+        }
+        Some(tile)
+    }
+
+    async fn fetch_tile(&self, url_template: &str, z: u32, x: u32, y: u32) -> Option<RgbaImage> {
+        let url = tile_url(url_template, z, x, y);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        let bytes = match response {
+            Ok(response) => response.bytes().await.ok()?,
+            Err(e) => {
+                warn!(url = %url, error = %e, "Failed to fetch underlay tile");
+                return None;
+            }
+        };
+        match image::load_from_memory(&bytes) {
+            Ok(tile) => Some(tile.to_rgba8()),
+            Err(e) => {
+                warn!(url = %url, error = %e, "Failed to decode underlay tile");
+                None
+            }
+        }
+    }
+
+    /// The land/sea mask of a zoom level, decoded on first use.
+    fn mask(&self, dir: &std::path::Path, z: u32) -> Option<Arc<RgbaImage>> {
+        let mut masks = self.masks.lock().unwrap();
+        masks
+            .entry(z)
+            .or_insert_with(|| {
+                let path = dir.join(format!("{}.png", z));
+                match image::open(&path) {
+                    Ok(mask) => {
+                        debug!(path = %path.display(), "Loaded land/sea mask");
+                        Some(Arc::new(mask.to_rgba8()))
+                    }
+                    Err(e) => {
+                        warn!(path = %path.display(), error = %e, "Cannot read land/sea mask");
+                        None
+                    }
+                }
+            })
+            .clone()
+    }
+}
+
+/// Draw a rendered PNG over an underlay of the same size and re-encode it.
+pub fn composite_over(png: &[u8], underlay: &RgbaImage) -> Result<Vec<u8>, String> {
+    let weather = image::load_from_memory(png)
+        .map_err(|e| format!("Failed to decode PNG: {}", e))?
+        .to_rgba8();
+    if weather.dimensions() != underlay.dimensions() {
+        return Err(format!(
+            "Underlay is {:?}, image is {:?}",
+            underlay.dimensions(),
+            weather.dimensions()
+        ));
+    }
+
+    let mut pixels = Vec::with_capacity(weather.as_raw().len());
+    for (base, pixel) in underlay.pixels().zip(weather.pixels()) {
+        pixels.extend_from_slice(&crate::handlers::wms::alpha_blend(*base, *pixel).0);
+    }
+    let (width, height) = weather.dimensions();
+    renderer::png::create_png_auto(&pixels, width as usize, height as usize)
+}
+
+/// Basemap zoom whose resolution best matches `width` pixels across
+/// `lon_span` degrees.
+fn zoom_for_extent(lon_span: f64, width: u32, max_zoom: u32) -> u32 {
+    if lon_span <= 0.0 {
+        return max_zoom;
+    }
+    let world_pixels = width as f64 * 360.0 / lon_span;
+    let zoom = (world_pixels / TILE_SIZE).log2().ceil().max(0.0) as u32;
+    zoom.min(max_zoom)
+}
+
+/// Global pixel column of a longitude, wrapped onto the world.
+fn lon_to_pixel(lon: f64, world: f64) -> f64 {
+    ((lon + 180.0) / 360.0 * world).rem_euclid(world)
+}
+
+/// Global pixel row of a latitude, clamped to the Web Mercator limits.
+fn lat_to_pixel(lat: f64, world: f64) -> f64 {
+    let lat = lat.clamp(-MAX_MERCATOR_LAT, MAX_MERCATOR_LAT).to_radians();
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / std::f64::consts::PI) / 2.0;
+    (y * world).clamp(0.0, world - 1e-9)
+}
+
+fn tile_url(template: &str, z: u32, x: u32, y: u32) -> String {
+    template
+        .replace("{z}", &z.to_string())
+        .replace("{x}", &x.to_string())
+        .replace("{y}", &y.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_mask(dir: &std::path::Path, z: u32) {
+        // West half land (green), east half sea (blue)
+        let size = 256 << z;
+        let mask = RgbaImage::from_fn(size, size, |x, _| {
+            if x < size / 2 {
+                Rgba([0, 128, 0, 255])
+            } else {
+                Rgba([0, 0, 255, 255])
+            }
+        });
+        mask.save(dir.join(format!("{}.png", z))).unwrap();
+    }
+
+    fn landsea(dir: &std::path::Path, max_zoom: u32) -> Underlay {
+        Underlay::new(UnderlayConfig {
+            source: Some(UnderlaySource::LandSeaMask {
+                dir: dir.to_path_buf(),
+            }),
+            max_zoom,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_zoom_for_extent() {
+        assert_eq!(zoom_for_extent(360.0, 256, 12), 0);
+        assert_eq!(zoom_for_extent(360.0, 512, 12), 1);
+        assert_eq!(zoom_for_extent(45.0, 256, 12), 3);
+        assert_eq!(zoom_for_extent(0.001, 256, 12), 12);
+    }
+
+    #[test]
+    fn test_mercator_pixels() {
+        let world = 512.0;
+        assert_eq!(lon_to_pixel(-180.0, world), 0.0);
+        assert_eq!(lon_to_pixel(0.0, world), 256.0);
+        assert!((lon_to_pixel(190.0, world) - lon_to_pixel(-170.0, world)).abs() < 1e-9);
+        assert!((lat_to_pixel(0.0, world) - 256.0).abs() < 1e-9);
+        assert!(lat_to_pixel(89.0, world) < 1e-6);
+        assert!(lat_to_pixel(-89.0, world) > 511.0);
+    }
+
+    #[test]
+    fn test_tile_url() {
+        assert_eq!(
+            tile_url("https://tiles.example.com/{z}/{x}/{y}.png", 3, 2, 5),
+            "https://tiles.example.com/3/2/5.png"
+        );
+    }
+
+    #[test]
+    fn test_enabled_defaults() {
+        let disabled = Underlay::new(UnderlayConfig::default());
+        assert!(!disabled.enabled_for_map(None));
+
+        let underlay = landsea(std::path::Path::new("/nonexistent"), 4);
+        assert!(underlay.enabled_for_map(None));
+        assert!(!underlay.enabled_for_map(Some(false)));
+        assert!(!underlay.enabled_for_tiles());
+    }
+
+    #[tokio::test]
+    async fn test_landsea_underlay_for_extent() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mask(dir.path(), 0);
+        write_mask(dir.path(), 1);
+        let underlay = landsea(dir.path(), 1);
+
+        // A box straddling the prime meridian: land to the west, sea to the east
+        let image = underlay
+            .for_extent([-20.0, -10.0, 20.0, 10.0], 64, 32, false)
+            .await
+            .unwrap();
+        assert_eq!(image.dimensions(), (64, 32));
+        assert_eq!(*image.get_pixel(0, 16), Rgba([0, 128, 0, 255]));
+        assert_eq!(*image.get_pixel(63, 16), Rgba([0, 0, 255, 255]));
+
+        // Tiles of the zoom 1 mask are cached after the first read
+        assert!(underlay
+            .tiles
+            .lock()
+            .unwrap()
+            .tiles
+            .contains_key(&(1, 0, 0)));
+        assert!(underlay.tile(1, 2, 0).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_missing_mask_gives_no_underlay() {
+        let dir = tempfile::tempdir().unwrap();
+        let underlay = landsea(dir.path(), 2);
+        assert!(underlay
+            .for_extent([-10.0, -10.0, 10.0, 10.0], 16, 16, false)
+            .await
+            .is_none());
+    }
+
+    #[test]
+    fn test_composite_over() {
+        let mut weather = vec![0u8; 2 * 4];
+        weather[..4].copy_from_slice(&[255, 0, 0, 255]);
+        let png = renderer::png::create_png(&weather, 2, 1).unwrap();
+        let underlay = RgbaImage::from_pixel(2, 1, Rgba([0, 0, 255, 255]));
+
+        let out = composite_over(&png, &underlay).unwrap();
+        let out = image::load_from_memory(&out).unwrap().to_rgba8();
+        // Opaque weather wins, transparent weather shows the underlay
+        assert_eq!(*out.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+        assert_eq!(*out.get_pixel(1, 0), Rgba([0, 0, 255, 255]));
+
+        let wrong_size = RgbaImage::new(4, 4);
+        assert!(composite_over(&png, &wrong_size).is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! Per-model update cadence and the HTTP cache policy derived from it.
+//!
+//! Forecast layers change once per model cycle, observation layers every few
+//! minutes. The cadence of a model is the median gap between its most recent
+//! datasets in the catalog, falling back to the interval implied by its
+//! `schedule` config (see [`ModelDimensionRegistry::update_interval`]).
+//! Responses for the latest data are cached for a fraction of the cadence and
+//! may be served stale while revalidating for up to one cadence; responses
+//! pinned to a run and forecast hour or to an observation time never change.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use storage::Catalog;
+use tracing::{debug, info};
+
+use crate::model_config::ModelDimensionRegistry;
+use crate::state::AppState;
+
+/// Cadence assumed for models without a schedule or catalog history.
+pub const DEFAULT_CADENCE: Duration = Duration::from_secs(3600);
+
+/// `xlink:role` of the WMTS layer metadata advertising the cadence.
+pub const UPDATE_INTERVAL_ROLE: &str = "urn:weather-wms:update-interval";
+
+/// Recent datasets looked at when measuring a model's cadence.
+const HISTORY_RUNS: usize = 12;
+
+/// `Cache-Control` lifetimes for a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachePolicy {
+    pub max_age: Duration,
+    /// Zero for immutable responses
+    pub stale_while_revalidate: Duration,
+}
+
+impl CachePolicy {
+    /// Policy for responses that never change.
+    pub fn immutable() -> Self {
+        Self {
+            max_age: Duration::from_secs(86400),
+            stale_while_revalidate: Duration::ZERO,
+        }
+    }
+
+    /// Policy for the latest data of a layer updated every `cadence`.
+    pub fn for_cadence(cadence: Duration) -> Self {
+        Self {
+            max_age: (cadence / 2).clamp(Duration::from_secs(30), Duration::from_secs(3600)),
+            stale_while_revalidate: cadence
+                .clamp(Duration::from_secs(60), Duration::from_secs(6 * 3600)),
+        }
+    }
+
+    /// The policy of two that expires first, for responses combining layers.
+    pub fn shorter(self, other: Self) -> Self {
+        if other.max_age < self.max_age {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Value of the `Cache-Control` header.
+    pub fn header_value(&self) -> String {
+        if self.stale_while_revalidate.is_zero() {
+            format!("public, max-age={}, immutable", self.max_age.as_secs())
+        } else {
+            format!(
+                "public, max-age={}, stale-while-revalidate={}",
+                self.max_age.as_secs(),
+                self.stale_while_revalidate.as_secs()
+            )
+        }
+    }
+}
+
+/// Update cadence of each model, measured from catalog history.
+#[derive(Debug, Default)]
+pub struct UpdateCadence {
+    observed: RwLock<HashMap<String, Duration>>,
+}
+
+impl UpdateCadence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cadence of a model: measured if known, else configured, else
+    /// [`DEFAULT_CADENCE`].
+    pub fn cadence(&self, model: &str, dimensions: &ModelDimensionRegistry) -> Duration {
+        self.observed
+            .read()
+            .unwrap()
+            .get(model)
+            .copied()
+            .or_else(|| dimensions.update_interval(model))
+            .unwrap_or(DEFAULT_CADENCE)
+    }
+
+    /// Cache policy for a layer of `model`; `pinned` responses name an exact
+    /// run and forecast hour or observation time.
+    pub fn cache_policy(
+        &self,
+        model: &str,
+        pinned: bool,
+        dimensions: &ModelDimensionRegistry,
+    ) -> CachePolicy {
+        if pinned {
+            CachePolicy::immutable()
+        } else {
+            CachePolicy::for_cadence(self.cadence(model, dimensions))
+        }
+    }
+
+    /// Re-measure the cadence of `models` from their recent catalog history.
+    /// Models with too little history keep their configured cadence.
+    /// Returns the number of models measured.
+    pub async fn refresh(&self, catalog: &Catalog, models: &[String]) -> usize {
+        let mut observed = HashMap::new();
+        for model in models {
+            let runs = match catalog.get_model_runs_with_counts(model).await {
+                Ok(runs) => runs,
+                Err(e) => {
+                    debug!(model = %model, error = %e, "Cannot read catalog history for cadence");
+                    continue;
+                }
+            };
+            let times: Vec<DateTime<Utc>> = runs.into_iter().map(|(time, _)| time).collect();
+            if let Some(cadence) = cadence_from_history(&times) {
+                debug!(model = %model, cadence_secs = cadence.as_secs(), "Measured update cadence");
+                observed.insert(model.clone(), cadence);
+            }
+        }
+
+        let count = observed.len();
+        *self.observed.write().unwrap() = observed;
+        count
+    }
+}
+
+/// Re-measure the cadence of every configured model every `interval`,
+/// starting immediately.
+pub async fn run_refresh_loop(state: Arc<AppState>, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let models = state.model_dimensions.models();
+        let measured = state.update_cadence.refresh(&state.catalog, &models).await;
+        info!(
+            models = models.len(),
+            measured = measured,
+            "Refreshed model update cadences"
+        );
+    }
+}
+
+/// Median gap between the most recent reference times (newest first), or
+/// `None` with fewer than three datasets.
+fn cadence_from_history(times: &[DateTime<Utc>]) -> Option<Duration> {
+    let mut gaps: Vec<Duration> = times
+        .iter()
+        .take(HISTORY_RUNS + 1)
+        .zip(times.iter().skip(1))
+        .filter_map(|(newer, older)| (*newer - *older).to_std().ok())
+        .filter(|gap| !gap.is_zero())
+        .collect();
+    if gaps.len() < 2 {
+        return None;
+    }
+
+    gaps.sort_unstable();
+    Some(gaps[gaps.len() / 2])
+}
+
+/// ISO 8601 duration of a cadence (e.g. "PT2M", "PT6H", "P1D").
+pub fn format_iso8601_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs == 0 {
+        return "PT0S".to_string();
+    }
+
+    let (days, rem) = (secs / 86400, secs % 86400);
+    let (hours, rem) = (rem / 3600, rem % 3600);
+    let (minutes, seconds) = (rem / 60, rem % 60);
+
+    let mut out = String::from("P");
+    if days > 0 {
+        out.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 {
+        out.push('T');
+        for (value, unit) in [(hours, 'H'), (minutes, 'M'), (seconds, 'S')] {
+            if value > 0 {
+                out.push_str(&format!("{}{}", value, unit));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn minutes_ago(minutes: &[i64]) -> Vec<DateTime<Utc>> {
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+        minutes
+            .iter()
+            .map(|m| now - chrono::Duration::minutes(*m))
+            .collect()
+    }
+
+    #[test]
+    fn test_cadence_from_history() {
+        // A missed observation doesn't skew the median
+        let times = minutes_ago(&[0, 2, 4, 8, 10, 12]);
+        assert_eq!(cadence_from_history(&times), Some(Duration::from_secs(120)));
+
+        let runs = minutes_ago(&[0, 360, 720, 1080]);
+        assert_eq!(
+            cadence_from_history(&runs),
+            Some(Duration::from_secs(6 * 3600))
+        );
+
+        assert_eq!(cadence_from_history(&minutes_ago(&[0, 360])), None);
+    }
+
+    #[test]
+    fn test_cache_policy_for_cadence() {
+        let observation = CachePolicy::for_cadence(Duration::from_secs(120));
+        assert_eq!(
+            observation.header_value(),
+            "public, max-age=60, stale-while-revalidate=120"
+        );
+
+        let forecast = CachePolicy::for_cadence(Duration::from_secs(6 * 3600));
+        assert_eq!(
+            forecast.header_value(),
+            "public, max-age=3600, stale-while-revalidate=21600"
+        );
+
+        assert_eq!(observation.shorter(forecast), observation);
+        assert_eq!(
+            CachePolicy::immutable().header_value(),
+            "public, max-age=86400, immutable"
+        );
+    }
+
+    #[test]
+    fn test_cadence_falls_back_to_config() {
+        let cadence = UpdateCadence::new();
+        let dimensions = ModelDimensionRegistry::new();
+        assert_eq!(cadence.cadence("gfs", &dimensions), DEFAULT_CADENCE);
+
+        cadence
+            .observed
+            .write()
+            .unwrap()
+            .insert("mrms".to_string(), Duration::from_secs(120));
+        assert_eq!(
+            cadence.cadence("mrms", &dimensions),
+            Duration::from_secs(120)
+        );
+        assert_eq!(
+            cadence.cache_policy("mrms", true, &dimensions),
+            CachePolicy::immutable()
+        );
+    }
+
+    #[test]
+    fn test_format_iso8601_duration() {
+        assert_eq!(format_iso8601_duration(Duration::from_secs(120)), "PT2M");
+        assert_eq!(
+            format_iso8601_duration(Duration::from_secs(6 * 3600)),
+            "PT6H"
+        );
+        assert_eq!(
+            format_iso8601_duration(Duration::from_secs(5430)),
+            "PT1H30M30S"
+        );
+        assert_eq!(format_iso8601_duration(Duration::from_secs(86400)), "P1D");
+    }
+}
@@ -3,9 +3,14 @@
 //! This module warms the L1 and L2 caches by pre-rendering tiles for zoom levels 0-4
 //! across all configured layers and forecast hours. This dramatically improves cold
 //! start performance by ensuring frequently accessed tiles are already cached.
+//!
+//! With `publish_archive` set, the warmed tiles are also published to the tile
+//! archive, one tile set per layer, style and forecast hour of the latest run.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
+use storage::{ArchiveKey, TileArchiveWriter};
 use tracing::{debug, info, warn};
 use wms_common::TileCoord;
 
@@ -25,6 +30,8 @@ pub struct WarmingConfig {
     pub layers: Vec<WarmingLayer>,
     /// Number of concurrent warming tasks
     pub concurrency: usize,
+    /// Publish warmed tiles to the tile archive
+    pub publish_archive: bool,
 }
 
 /// Layer configuration for cache warming.
@@ -83,6 +90,12 @@ impl CacheWarmer {
 
         info!(total_tiles = total_tiles, "Generated warming tile list");
 
+        let archive_writers = if self.config.publish_archive {
+            self.archive_writers().await
+        } else {
+            HashMap::new()
+        };
+
         // Process with limited concurrency using semaphore
         let semaphore = Arc::new(tokio::sync::Semaphore::new(self.config.concurrency));
         let mut handles = Vec::new();
@@ -90,9 +103,13 @@ impl CacheWarmer {
         for (layer, style, coord, hour) in tiles {
             let permit = semaphore.clone().acquire_owned().await.unwrap();
             let state = self.state.clone();
+            let archive_writer = archive_writers
+                .get(&(layer.clone(), style.clone(), hour))
+                .cloned();
 
             let handle = tokio::spawn(async move {
-                let result = warm_single_tile(&state, &layer, &style, coord, hour).await;
+                let result =
+                    warm_single_tile(&state, &layer, &style, coord, hour, archive_writer).await;
                 drop(permit); // Release permit
                 result
             });
@@ -142,6 +159,87 @@ impl CacheWarmer {
             tiles_per_sec = format!("{:.1}", tiles_per_sec),
             "Cache warming complete"
         );
+
+        // Publish manifests last so readers only see complete tile sets
+        if let Some(archive) = &self.state.tile_archive {
+            for writer in archive_writers.values() {
+                if let Err(e) = archive.publish(writer).await {
+                    warn!(error = %e, time = %writer.key().time, "Failed to publish tile archive");
+                }
+            }
+        }
+    }
+
+    /// Create an archive writer per (layer, style, forecast hour) to warm.
+    ///
+    /// Tile sets are keyed by the latest run, which is what warming renders.
+    async fn archive_writers(&self) -> HashMap<(String, String, u32), Arc<TileArchiveWriter>> {
+        let mut writers = HashMap::new();
+
+        let Some(archive) = &self.state.tile_archive else {
+            warn!("Archive publishing requested but tile archive is disabled (set ENABLE_TILE_ARCHIVE=true)");
+            return writers;
+        };
+
+        for layer in &self.config.layers {
+            let parts: Vec<&str> = layer.name.split('_').collect();
+            if parts.len() < 2 {
+                continue;
+            }
+            let model = parts[0];
+            let parameter = parts[1..].join("_").to_uppercase();
+
+            if self.state.model_dimensions.is_observation(model) {
+                warn!(layer = %layer.name, "Archive publishing not supported for observation layers");
+                continue;
+            }
+
+            let run = match self
+                .state
+                .catalog
+                .get_latest_run_earliest_forecast(model, &parameter)
+                .await
+            {
+                Ok(Some(entry)) => entry.reference_time,
+                Ok(None) => {
+                    warn!(layer = %layer.name, "No data to archive");
+                    continue;
+                }
+                Err(e) => {
+                    warn!(error = %e, layer = %layer.name, "Failed to find latest run to archive");
+                    continue;
+                }
+            };
+
+            // Key tile sets the way GetTile looks them up: resolved style, default level
+            let (style, default_level) = {
+                let configs = self.state.layer_configs.read().await;
+                let style = configs
+                    .resolve_style(model, &parameter, Some(&layer.style))
+                    .name;
+                let default_level = configs
+                    .get_layer_by_param(model, &parameter)
+                    .and_then(|l| l.default_level())
+                    .map(|s| s.to_string());
+                (style, default_level)
+            };
+
+            for hour in &self.config.forecast_hours {
+                let key = ArchiveKey::forecast(&layer.name, &style, run, *hour)
+                    .with_elevation(default_level.as_deref());
+                let writer = archive.writer(key, "WebMercatorQuad", Some(run));
+                writers.insert(
+                    (layer.name.clone(), layer.style.clone(), *hour),
+                    Arc::new(writer),
+                );
+            }
+        }
+
+        info!(
+            tile_sets = writers.len(),
+            "Publishing warmed tiles to tile archive"
+        );
+        writers
     }
 
     /// Generate list of all tiles to warm.
@@ -177,48 +275,38 @@ async fn warm_single_tile(
     style: &str,
     coord: TileCoord,
     forecast_hour: u32,
+    archive_writer: Option<Arc<TileArchiveWriter>>,
 ) -> WarmResult {
-    use storage::CacheKey;
-    use wms_common::{BoundingBox, CrsCode};
-
-    // Build cache key with time dimension
-    let dimension_suffix = Some(format!("t{}", forecast_hour));
-
-    let cache_key = CacheKey::new(
-        layer,
-        style,
-        CrsCode::Epsg3857,
-        BoundingBox::new(coord.x as f64, coord.y as f64, coord.z as f64, 0.0),
-        256,
-        256,
-        dimension_suffix.clone(),
-        "png",
-    );
-
-    // Build string key for L1 cache
-    let cache_key_str = format!(
-        "{}:{}:EPSG:3857:{}_{}_{}:{}",
-        layer,
-        style,
-        coord.z,
-        coord.x,
-        coord.y,
-        dimension_suffix.as_deref().unwrap_or("current")
-    );
+    use wms_common::CrsCode;
+
+    // Key the tile as GetTile looks it up for this forecast hour
+    let (cache_key, _) = state
+        .tile_cache_key(
+            layer,
+            style,
+            CrsCode::Epsg3857,
+            coord,
+            Some(format!("t{}", forecast_hour)),
+        )
+        .await;
+    let cache_key_str = cache_key.to_string();
 
     // Check if already in L1 cache
-    if state.tile_memory_cache.get(&cache_key_str).await.is_some() {
+    if let Some(tile_data) = state.tile_memory_cache.get(&cache_key_str).await {
         debug!(layer = %layer, z = coord.z, x = coord.x, y = coord.y, "Already in L1 cache");
+        archive_tile(archive_writer.as_deref(), &coord, tile_data).await;
         return WarmResult::AlreadyCached;
     }
 
     // Check if already in L2 cache
-    {
+    let l2_data = {
         let mut cache = state.cache.lock().await;
-        if let Ok(Some(_)) = cache.get(&cache_key).await {
-            debug!(layer = %layer, z = coord.z, x = coord.x, y = coord.y, "Already in L2 cache");
-            return WarmResult::AlreadyCached;
-        }
+        cache.get(&cache_key).await
+    };
+    if let Ok(Some(tile_data)) = l2_data {
+        debug!(layer = %layer, z = coord.z, x = coord.x, y = coord.y, "Already in L2 cache");
+        archive_tile(archive_writer.as_deref(), &coord, tile_data).await;
+        return WarmResult::AlreadyCached;
     }
 
     // Not cached - render the tile
@@ -325,13 +413,21 @@ async fn warm_single_tile(
 
     match result {
         Ok(tile_data) => {
+            // Warmed tiles stay cached; trade encode time for smaller tiles
+            let tile_data = match recompress_for_seeding(tile_data).await {
+                Ok(tile_data) => tile_data,
+                Err(e) => return WarmResult::Failed(e),
+            };
+
             // Store in L1 cache
             let data_bytes = bytes::Bytes::from(tile_data.clone());
             state
                 .tile_memory_cache
-                .set(&cache_key_str, data_bytes, None)
+                .set(&cache_key_str, data_bytes.clone(), None)
                 .await;
 
+            archive_tile(archive_writer.as_deref(), &coord, data_bytes).await;
+
             // Store in L2 cache
             {
                 let mut cache = state.cache.lock().await;
@@ -350,6 +446,39 @@ async fn warm_single_tile(
     }
 }
 
+/// Write a warmed tile to its archive tile set, if publishing.
+async fn archive_tile(writer: Option<&TileArchiveWriter>, coord: &TileCoord, data: bytes::Bytes) {
+    if let Some(writer) = writer {
+        if let Err(e) = writer.write_tile(coord, data).await {
+            warn!(error = %e, z = coord.z, x = coord.x, y = coord.y, "Failed to archive tile");
+        }
+    }
+}
+
+/// Re-encode a rendered tile with the seeding PNG options.
+///
+/// Keeps the original encoding if the options match the interactive ones,
+/// re-encoding fails, or the result is not smaller.
+async fn recompress_for_seeding(png: Vec<u8>) -> Result<Vec<u8>, String> {
+    let options = *rendering::seeding_png_options();
+    if options == *rendering::png_options() {
+        return Ok(png);
+    }
+
+    tokio::task::spawn_blocking(
+        move || match renderer::png::recompress_png(&png, &options) {
+            Ok(smaller) if smaller.len() < png.len() => smaller,
+            Ok(_) => png,
+            Err(e) => {
+                debug!(error = %e, "Failed to recompress warmed tile");
+                png
+            }
+        },
+    )
+    .await
+    .map_err(|e| format!("Tile recompression task failed: {}", e))
+}
+
 impl Default for WarmingConfig {
     fn default() -> Self {
         Self {
@@ -358,6 +487,7 @@ impl Default for WarmingConfig {
             forecast_hours: vec![0],
             layers: Vec::new(),
             concurrency: 10,
+            publish_archive: false,
         }
     }
 }
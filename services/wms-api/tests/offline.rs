@@ -0,0 +1,457 @@
+//! Full-router tests against an offline AppState.
+//!
+//! Each test writes a small GFS temperature grid into a temp directory,
+//! registers it in the in-memory catalog and drives the public routes, so
+//! the WMS/WMTS/XYZ paths run end to end without Postgres, MinIO or Redis.
+//!
+//! Run with: cargo test -p wms-api --features offline --test offline
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{header, Request, StatusCode};
+use axum::{Extension, Router};
+use chrono::{TimeZone, Utc};
+use grid_processor::testdata::create_temperature_grid;
+use grid_processor::{BoundingBox, GridProcessorConfig, ZarrWriter};
+use storage::CatalogEntry;
+use tempfile::TempDir;
+use tower::ServiceExt;
+use wms_api::routes;
+use wms_api::state::AppState;
+use wms_common::TenantRegistry;
+use zarrs_filesystem::FilesystemStore;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Router over an offline state with one gfs TMP dataset at 2 m.
+struct Fixture {
+    router: Router,
+    state: Arc<AppState>,
+    data_dir: TempDir,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        Self::with_state(|_| {}).await
+    }
+
+    /// Fixture whose state is adjusted by `configure` before serving.
+    async fn with_state(configure: impl FnOnce(&mut AppState)) -> Self {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut state = AppState::offline(data_dir.path(), config_dir()).unwrap();
+        configure(&mut state);
+
+        let entry = write_grid(data_dir.path(), "gfs", "TMP", "2 m above ground");
+        state.catalog.register_dataset(&entry).await.unwrap();
+
+        let state = Arc::new(state);
+        Self {
+            router: routes::public_routes().layer(Extension(state.clone())),
+            state,
+            data_dir,
+        }
+    }
+
+    /// Register another gfs dataset at 2 m with every point set to `value`.
+    async fn add_constant_grid(&self, parameter: &str, value: f32, units: &str) {
+        let entry = write_grid_values(
+            self.data_dir.path(),
+            "gfs",
+            parameter,
+            "2 m above ground",
+            &vec![value; 72 * 37],
+            units,
+        );
+        self.state.catalog.register_dataset(&entry).await.unwrap();
+    }
+
+    async fn get(&self, uri: &str) -> (StatusCode, String, Vec<u8>) {
+        self.send(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+    }
+
+    async fn send(&self, request: Request<Body>) -> (StatusCode, String, Vec<u8>) {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, body.to_vec())
+    }
+
+    async fn get_png(&self, uri: &str) -> Vec<u8> {
+        let (status, content_type, body) = self.get(uri).await;
+        assert_eq!(
+            status,
+            StatusCode::OK,
+            "{}: {}",
+            uri,
+            String::from_utf8_lossy(&body)
+        );
+        assert_eq!(content_type, "image/png", "{}", uri);
+        assert!(body.starts_with(PNG_SIGNATURE), "{}: not a PNG", uri);
+        body
+    }
+}
+
+fn config_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config")
+}
+
+/// Write a 5-degree global temperature grid (GFS layout, 0-360 longitude)
+/// as Zarr under `data_dir` and return its catalog entry.
+fn write_grid(data_dir: &Path, model: &str, parameter: &str, level: &str) -> CatalogEntry {
+    let values = create_temperature_grid(72, 37);
+    write_grid_values(data_dir, model, parameter, level, &values, "K")
+}
+
+/// Write 72x37 `values` as a 5-degree global grid and return its catalog entry.
+fn write_grid_values(
+    data_dir: &Path,
+    model: &str,
+    parameter: &str,
+    level: &str,
+    values: &[f32],
+    units: &str,
+) -> CatalogEntry {
+    let (width, height) = (72, 37);
+    let bbox = BoundingBox::new(0.0, -90.0, 355.0, 90.0);
+    let reference_time = Utc.with_ymd_and_hms(2024, 12, 22, 0, 0, 0).unwrap();
+    let storage_path = format!(
+        "grids/{}/20241222_00z/{}_f000.zarr",
+        model,
+        parameter.to_lowercase()
+    );
+
+    let zarr_dir = data_dir.join(&storage_path);
+    std::fs::create_dir_all(&zarr_dir).unwrap();
+    let store = FilesystemStore::new(&zarr_dir).unwrap();
+    let result = ZarrWriter::new(GridProcessorConfig::default())
+        .write(
+            store,
+            "/",
+            values,
+            width,
+            height,
+            &bbox,
+            model,
+            parameter,
+            level,
+            units,
+            reference_time,
+            0,
+        )
+        .unwrap();
+
+    CatalogEntry {
+        model: model.to_string(),
+        parameter: parameter.to_string(),
+        level: level.to_string(),
+        reference_time,
+        forecast_hour: 0,
+        bbox: wms_common::BoundingBox::new(bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat),
+        storage_path,
+        file_size: result.bytes_written,
+        zarr_metadata: Some(result.metadata.to_json()),
+        ensemble_member: None,
+        lineage: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_health_and_ready() {
+    let fixture = Fixture::new().await;
+
+    let (status, _, _) = fixture.get("/health").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Readiness lists models through the in-memory catalog
+    let (status, _, _) = fixture.get("/ready").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wms_capabilities_lists_only_layers_with_data() {
+    let fixture = Fixture::new().await;
+
+    let (status, _, body) = fixture
+        .get("/wms?SERVICE=WMS&REQUEST=GetCapabilities&VERSION=1.3.0")
+        .await;
+    let xml = String::from_utf8(body).unwrap();
+
+    assert_eq!(status, StatusCode::OK, "{}", xml);
+    assert!(xml.contains("WMS_Capabilities"));
+    assert!(xml.contains("gfs_TMP"));
+    assert!(!xml.contains("gfs_DPT"), "layer without data advertised");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wms_get_map() {
+    let fixture = Fixture::new().await;
+
+    fixture
+        .get_png(
+            "/wms?SERVICE=WMS&REQUEST=GetMap&VERSION=1.1.1&LAYERS=gfs_TMP&STYLES=\
+             &SRS=EPSG:4326&BBOX=0,-45,90,45&WIDTH=256&HEIGHT=256&FORMAT=image/png",
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wmts_get_tile() {
+    let fixture = Fixture::new().await;
+
+    fixture
+        .get_png(
+            "/wmts?SERVICE=WMTS&REQUEST=GetTile&VERSION=1.0.0&LAYER=gfs_TMP&STYLE=default\
+             &FORMAT=image/png&TILEMATRIXSET=WebMercatorQuad&TILEMATRIX=2&TILEROW=1&TILECOL=2",
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_xyz_tile_is_stable_across_requests() {
+    let fixture = Fixture::new().await;
+
+    // The second request is served from the L1 cache (Redis is disabled)
+    let first = fixture.get_png("/tiles/gfs_TMP/default/2/2/1.png").await;
+    let second = fixture.get_png("/tiles/gfs_TMP/default/2/2/1.png").await;
+    assert_eq!(first, second);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wms_get_map_without_data_is_an_exception() {
+    let fixture = Fixture::new().await;
+
+    let (status, content_type, _) = fixture
+        .get(
+            "/wms?SERVICE=WMS&REQUEST=GetMap&VERSION=1.1.1&LAYERS=gfs_DPT&STYLES=\
+             &SRS=EPSG:4326&BBOX=0,-45,90,45&WIDTH=256&HEIGHT=256&FORMAT=image/png",
+        )
+        .await;
+    assert_ne!(status, StatusCode::OK);
+    assert!(content_type.contains("xml"), "{}", content_type);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wms_get_map_size_limits() {
+    let fixture = Fixture::with_state(|state| {
+        state
+            .request_limits
+            .trusted_api_keys
+            .insert("partner".to_string());
+    })
+    .await;
+    let oversized = "/wms?SERVICE=WMS&REQUEST=GetMap&VERSION=1.1.1&LAYERS=gfs_TMP&STYLES=\
+                     &SRS=EPSG:4326&BBOX=0,-45,90,45&WIDTH=10000&HEIGHT=256&FORMAT=image/png";
+
+    let (status, content_type, body) = fixture.get(oversized).await;
+    let xml = String::from_utf8(body).unwrap();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(content_type.contains("xml"), "{}", content_type);
+    assert!(xml.contains("InvalidParameterValue"), "{}", xml);
+    assert!(xml.contains("WIDTH 10000"), "{}", xml);
+
+    // Trusted keys skip the limits
+    let (status, content_type, _) = fixture
+        .send(
+            Request::get(oversized)
+                .header("x-api-key", "partner")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/png");
+
+    // The limits are advertised in the capabilities
+    let (_, _, body) = fixture
+        .get("/wms?SERVICE=WMS&REQUEST=GetCapabilities&VERSION=1.3.0")
+        .await;
+    let xml = String::from_utf8(body).unwrap();
+    assert!(xml.contains("<MaxWidth>4096</MaxWidth>"), "{}", xml);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tenant_layer_visibility() {
+    let fixture = Fixture::with_state(|state| {
+        state.tenants = TenantRegistry::from_yaml(
+            "tenants:\n  - id: acme\n    api_keys: [acme-key]\n    layers: [gfs_DPT]\n",
+        )
+        .unwrap();
+    })
+    .await;
+    fixture.add_constant_grid("DPT", 280.0, "K").await;
+    let as_acme = |uri: &str| {
+        Request::get(uri)
+            .header("x-api-key", "acme-key")
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // The tenant's capabilities only list its layers
+    let capabilities = "/wms?SERVICE=WMS&REQUEST=GetCapabilities&VERSION=1.3.0";
+    let (_, _, body) = fixture.send(as_acme(capabilities)).await;
+    let xml = String::from_utf8(body).unwrap();
+    assert!(xml.contains("<Name>gfs_DPT</Name>"), "{}", xml);
+    assert!(!xml.contains("<Name>gfs_TMP</Name>"), "{}", xml);
+
+    // Keys without a tenant are unrestricted
+    let (_, _, body) = fixture.get(capabilities).await;
+    let xml = String::from_utf8(body).unwrap();
+    assert!(xml.contains("<Name>gfs_TMP</Name>"), "{}", xml);
+
+    // Layers outside the tenant are undefined for it
+    let get_map = "/wms?SERVICE=WMS&REQUEST=GetMap&VERSION=1.1.1&LAYERS=gfs_TMP&STYLES=\
+                   &SRS=EPSG:4326&BBOX=0,-45,90,45&WIDTH=256&HEIGHT=256&FORMAT=image/png";
+    let (status, _, body) = fixture.send(as_acme(get_map)).await;
+    let xml = String::from_utf8(body).unwrap();
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(xml.contains("LayerNotDefined"), "{}", xml);
+
+    let (status, _, _) = fixture
+        .send(as_acme("/tiles/gfs_TMP/default/2/2/1.png"))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, content_type, _) = fixture
+        .send(as_acme(&get_map.replace("gfs_TMP", "gfs_DPT")))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/png");
+
+    let usage = &fixture.state.tenant_usage;
+    assert_eq!(usage.requests("acme", "wms", "GetMap"), 2);
+    assert_eq!(usage.denied("acme", "wms", "GetMap"), 1);
+    assert_eq!(usage.denied("acme", "wmts", "GetTile"), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wms_get_feature_info_provenance() {
+    let fixture = Fixture::new().await;
+    let query = "/wms?SERVICE=WMS&REQUEST=GetFeatureInfo&VERSION=1.1.1&LAYERS=gfs_TMP\
+                 &QUERY_LAYERS=gfs_TMP&STYLES=&SRS=EPSG:4326&BBOX=0,-45,90,45&WIDTH=256\
+                 &HEIGHT=256&X=128&Y=128&INFO_FORMAT=application/json";
+
+    let (status, _, body) = fixture.get(query).await;
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status, StatusCode::OK, "{}", json);
+    assert!(json["features"][0].get("provenance").is_none(), "{}", json);
+
+    // The vendor parameter adds the dataset and chunks the value came from
+    let (status, _, body) = fixture.get(&format!("{}&PROVENANCE=true", query)).await;
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status, StatusCode::OK, "{}", json);
+
+    let provenance = &json["features"][0]["provenance"];
+    assert_eq!(
+        provenance["dataset_id"],
+        "gfs/TMP/2 m above ground/20241222T0000Z/f000"
+    );
+    assert_eq!(
+        provenance["source"],
+        "/grids/gfs/20241222_00z/tmp_f000.zarr"
+    );
+    assert_eq!(provenance["pyramid_level"], 0);
+    assert!(
+        !provenance["chunk_keys"].as_array().unwrap().is_empty(),
+        "{}",
+        provenance
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_wms_get_feature_info_wind_barbs() {
+    let fixture = Fixture::new().await;
+    // Wind blowing towards the north-east, i.e. from 217 degrees
+    fixture.add_constant_grid("UGRD", 3.0, "m/s").await;
+    fixture.add_constant_grid("VGRD", 4.0, "m/s").await;
+
+    let query = "/wms?SERVICE=WMS&REQUEST=GetFeatureInfo&VERSION=1.1.1&LAYERS=gfs_WIND_BARBS\
+                 &QUERY_LAYERS=gfs_WIND_BARBS&STYLES=&SRS=EPSG:4326&BBOX=0,-45,90,45\
+                 &WIDTH=256&HEIGHT=256&X=128&Y=128&INFO_FORMAT=application/json";
+    let (status, _, body) = fixture.get(query).await;
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status, StatusCode::OK, "{}", json);
+
+    let features = json["features"].as_array().unwrap();
+    assert_eq!(features.len(), 2, "{}", json);
+    let speed = &features[0];
+    assert_eq!(speed["parameter"], "Wind Speed");
+    assert!(
+        (speed["value"].as_f64().unwrap() - 5.0).abs() < 1e-3,
+        "{}",
+        speed
+    );
+    assert_eq!(speed["unit"], "m/s");
+    assert_eq!(speed["level"], "2 m above ground");
+    assert_eq!(speed["valid_time"], "2024-12-22T00:00:00+00:00");
+
+    let direction = &features[1];
+    assert_eq!(direction["parameter"], "Wind Direction");
+    assert!(
+        (direction["value"].as_f64().unwrap() - 216.87).abs() < 0.01,
+        "{}",
+        direction
+    );
+    assert_eq!(direction["unit"], "degrees");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ogc_api_maps() {
+    let fixture = Fixture::new().await;
+
+    let (status, _, body) = fixture.get("/ogc/conformance").await;
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status, StatusCode::OK);
+    let conforms_to = json["conformsTo"].as_array().unwrap();
+    assert!(conforms_to
+        .iter()
+        .any(|c| c.as_str().unwrap().ends_with("/conf/collection-map")));
+
+    // Only layers with data are collections
+    let (status, _, body) = fixture.get("/ogc/collections").await;
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status, StatusCode::OK, "{}", json);
+    let ids: Vec<&str> = json["collections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["id"].as_str().unwrap())
+        .collect();
+    assert!(ids.contains(&"gfs_TMP"), "{:?}", ids);
+    assert!(!ids.contains(&"gfs_DPT"), "{:?}", ids);
+
+    let (status, content_type, _) = fixture.get("/ogc/collections/gfs_TMP?f=html").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(content_type.starts_with("text/html"), "{}", content_type);
+
+    fixture
+        .get_png("/ogc/collections/gfs_TMP/map?bbox=0,-45,90,45&width=256")
+        .await;
+    fixture
+        .get_png("/ogc/collections/gfs_TMP/styles/gradient/map?bbox=0,-45,90,45&width=256")
+        .await;
+
+    let (status, _, body) = fixture.get("/ogc/collections/gfs_NOPE/map").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(json["description"].as_str().unwrap().contains("gfs_NOPE"));
+
+    let (status, _, _) = fixture
+        .get("/ogc/collections/gfs_TMP/styles/nope/map")
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _, _) = fixture
+        .get("/ogc/collections/gfs_TMP/map?bbox=0,45,90")
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,271 @@
+//! Cache behavior analysis for two-phase runs.
+//!
+//! The seed phase requests every tile of a deterministic set once against a
+//! cold cache. The measured phase replays the same set, so every request
+//! whose tile was cacheable during seeding should be a hit. Comparing the
+//! achieved hit rate with that expectation, overall and per zoom level,
+//! separates cache regressions from a test that simply asked for new tiles.
+
+use crate::config::CacheThresholds;
+use crate::runner::RequestResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// `X-Cache` value for tiles outside the layer extent, which are never cached.
+const OUT_OF_EXTENT: &str = "OUT-OF-EXTENT";
+
+/// Whether a seed-phase response should leave the tile in the cache.
+pub fn is_cacheable(seed: &RequestResult) -> bool {
+    seed.error.is_none() && seed.status == 200 && seed.cache_status != OUT_OF_EXTENT
+}
+
+/// One measured-phase request.
+#[derive(Debug, Clone)]
+pub struct Observation {
+    pub zoom: u32,
+    /// Raw `X-Cache` value ("L1-HIT", "MISS", ...), empty if absent
+    pub cache_status: String,
+    pub cache_hit: bool,
+    /// The tile was cacheable when seeded
+    pub expected_hit: bool,
+}
+
+/// Hit statistics for one zoom level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoomCacheStats {
+    pub zoom: u32,
+    pub requests: u64,
+    pub hits: u64,
+    pub hit_rate: f64,
+    pub expected_hit_rate: f64,
+}
+
+/// Results of a two-phase cache analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheAnalysis {
+    /// Distinct tile requests in the set
+    pub tile_count: usize,
+    pub passes: u32,
+    /// Seeded tiles that were not cacheable (errors, non-200, out of extent)
+    pub seed_uncacheable: usize,
+    pub seed_duration_secs: f64,
+    pub measured_requests: u64,
+    pub hit_rate: f64,
+    pub expected_hit_rate: f64,
+    pub by_zoom: Vec<ZoomCacheStats>,
+    /// Measured-phase responses by `X-Cache` value
+    pub by_cache_status: BTreeMap<String, u64>,
+    /// Explanations for a gap between expected and achieved hit rates
+    pub notes: Vec<String>,
+    /// Thresholds that were not met; empty if the run passed
+    pub threshold_failures: Vec<String>,
+}
+
+impl CacheAnalysis {
+    /// Aggregate measured-phase observations and check them against `thresholds`.
+    pub fn from_observations(
+        tile_count: usize,
+        passes: u32,
+        seed_uncacheable: usize,
+        seed_duration_secs: f64,
+        observations: &[Observation],
+        thresholds: &CacheThresholds,
+    ) -> Self {
+        #[derive(Default)]
+        struct Counts {
+            requests: u64,
+            hits: u64,
+            expected: u64,
+        }
+
+        let mut total = Counts::default();
+        let mut zooms: BTreeMap<u32, Counts> = BTreeMap::new();
+        let mut by_cache_status = BTreeMap::new();
+
+        for obs in observations {
+            for counts in [&mut total, zooms.entry(obs.zoom).or_default()] {
+                counts.requests += 1;
+                counts.hits += obs.cache_hit as u64;
+                counts.expected += obs.expected_hit as u64;
+            }
+            let status = if obs.cache_status.is_empty() {
+                "(none)".to_string()
+            } else {
+                obs.cache_status.clone()
+            };
+            *by_cache_status.entry(status).or_insert(0) += 1;
+        }
+
+        let by_zoom = zooms
+            .into_iter()
+            .map(|(zoom, c)| ZoomCacheStats {
+                zoom,
+                requests: c.requests,
+                hits: c.hits,
+                hit_rate: percent(c.hits, c.requests),
+                expected_hit_rate: percent(c.expected, c.requests),
+            })
+            .collect();
+
+        let mut analysis = Self {
+            tile_count,
+            passes,
+            seed_uncacheable,
+            seed_duration_secs,
+            measured_requests: total.requests,
+            hit_rate: percent(total.hits, total.requests),
+            expected_hit_rate: percent(total.expected, total.requests),
+            by_zoom,
+            by_cache_status,
+            notes: Vec::new(),
+            threshold_failures: Vec::new(),
+        };
+        analysis.threshold_failures = analysis.check(thresholds);
+        analysis
+    }
+
+    /// Whether every threshold was met.
+    pub fn passed(&self) -> bool {
+        self.threshold_failures.is_empty()
+    }
+
+    fn check(&self, thresholds: &CacheThresholds) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        if let Some(min) = thresholds.min_hit_rate {
+            if self.hit_rate < min {
+                failures.push(format!(
+                    "hit rate {:.1}% is below the minimum of {:.1}%",
+                    self.hit_rate, min
+                ));
+            }
+        }
+        if let Some(max) = thresholds.max_hit_rate_shortfall {
+            let shortfall = self.expected_hit_rate - self.hit_rate;
+            if shortfall > max {
+                failures.push(format!(
+                    "hit rate {:.1}% is {:.1} points below the expected {:.1}% (max {:.1})",
+                    self.hit_rate, shortfall, self.expected_hit_rate, max
+                ));
+            }
+        }
+        if let Some(min) = thresholds.min_zoom_hit_rate {
+            for zoom in self.by_zoom.iter().filter(|z| z.hit_rate < min) {
+                failures.push(format!(
+                    "zoom {} hit rate {:.1}% is below the minimum of {:.1}%",
+                    zoom.zoom, zoom.hit_rate, min
+                ));
+            }
+        }
+
+        failures
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn obs(zoom: u32, cache_status: &str, expected_hit: bool) -> Observation {
+        Observation {
+            zoom,
+            cache_status: cache_status.to_string(),
+            cache_hit: cache_status.contains("HIT"),
+            expected_hit,
+        }
+    }
+
+    #[test]
+    fn test_is_cacheable() {
+        let seed = |status: u16, cache_status: &str, error: Option<&str>| RequestResult {
+            url: String::new(),
+            status,
+            latency_us: 0,
+            bytes: 0,
+            cache_hit: false,
+            cache_status: cache_status.to_string(),
+            timestamp: Instant::now(),
+            error: error.map(String::from),
+        };
+
+        assert!(is_cacheable(&seed(200, "MISS", None)));
+        assert!(is_cacheable(&seed(200, "L1-HIT", None)));
+        assert!(!is_cacheable(&seed(200, "OUT-OF-EXTENT", None)));
+        assert!(!is_cacheable(&seed(500, "", None)));
+        assert!(!is_cacheable(&seed(0, "", Some("timeout"))));
+    }
+
+    #[test]
+    fn test_hit_rates_by_zoom() {
+        let observations = vec![
+            obs(4, "L1-HIT", true),
+            obs(4, "L2-HIT", true),
+            obs(8, "L1-HIT", true),
+            obs(8, "MISS", true),
+            obs(8, "MISS", false),
+            obs(8, "", false),
+        ];
+
+        let analysis = CacheAnalysis::from_observations(
+            5,
+            1,
+            2,
+            1.0,
+            &observations,
+            &CacheThresholds::default(),
+        );
+
+        assert_eq!(analysis.measured_requests, 6);
+        assert!((analysis.hit_rate - 50.0).abs() < 1e-9);
+        assert!((analysis.expected_hit_rate - 400.0 / 6.0).abs() < 1e-9);
+
+        let z4 = &analysis.by_zoom[0];
+        assert_eq!((z4.zoom, z4.requests, z4.hits), (4, 2, 2));
+        let z8 = &analysis.by_zoom[1];
+        assert_eq!((z8.zoom, z8.requests, z8.hits), (8, 4, 1));
+        assert!((z8.hit_rate - 25.0).abs() < 1e-9);
+        assert!((z8.expected_hit_rate - 50.0).abs() < 1e-9);
+
+        assert_eq!(analysis.by_cache_status["MISS"], 2);
+        assert_eq!(analysis.by_cache_status["(none)"], 1);
+        assert!(analysis.passed());
+    }
+
+    #[test]
+    fn test_thresholds() {
+        let observations = vec![
+            obs(4, "L1-HIT", true),
+            obs(4, "L1-HIT", true),
+            obs(10, "L1-HIT", true),
+            obs(10, "MISS", true),
+        ];
+        let analyze = |thresholds: CacheThresholds| {
+            CacheAnalysis::from_observations(4, 1, 0, 1.0, &observations, &thresholds)
+        };
+
+        // 75% achieved vs 100% expected; zoom 10 at 50%
+        let lenient = analyze(CacheThresholds {
+            min_hit_rate: Some(70.0),
+            max_hit_rate_shortfall: Some(30.0),
+            min_zoom_hit_rate: Some(50.0),
+        });
+        assert!(lenient.passed(), "{:?}", lenient.threshold_failures);
+
+        let strict = analyze(CacheThresholds {
+            min_hit_rate: Some(80.0),
+            max_hit_rate_shortfall: Some(10.0),
+            min_zoom_hit_rate: Some(60.0),
+        });
+        assert_eq!(strict.threshold_failures.len(), 3);
+        assert!(strict.threshold_failures[2].starts_with("zoom 10 "));
+    }
+}
@@ -9,7 +9,8 @@ pub struct TestConfig {
     pub name: String,
     pub description: String,
     pub base_url: String,
-    pub duration_secs: u64,
+    #[serde(default)]
+    pub duration_secs: u64, // Ignored in cache analysis mode
     pub concurrency: u32,
     #[serde(default)]
     pub requests_per_second: Option<f64>,
@@ -23,6 +24,8 @@ pub struct TestConfig {
     pub time_selection: Option<TimeSelection>,
     #[serde(default)]
     pub log_requests: bool, // Log all requests to file for debugging
+    #[serde(default)]
+    pub cache_analysis: Option<CacheAnalysisConfig>, // Two-phase cache behavior run
 }
 
 /// Layer configuration for testing.
@@ -99,6 +102,107 @@ pub enum TimeSelection {
     None,
 }
 
+/// Two-phase cache analysis: a cold seed phase requests a deterministic tile
+/// set once, then a measured phase replays the same set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheAnalysisConfig {
+    /// Number of distinct tile requests in the set
+    pub tile_count: usize,
+    /// How many times the measured phase replays the set
+    #[serde(default = "default_passes")]
+    pub passes: u32,
+    /// Regression thresholds checked against the measured phase
+    #[serde(default)]
+    pub thresholds: CacheThresholds,
+}
+
+fn default_passes() -> u32 {
+    1
+}
+
+/// Regression thresholds for cache analysis. Hit rates are percentages.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheThresholds {
+    /// Minimum overall hit rate
+    #[serde(default)]
+    pub min_hit_rate: Option<f64>,
+    /// Maximum shortfall of the achieved hit rate below the expected one,
+    /// in percentage points
+    #[serde(default)]
+    pub max_hit_rate_shortfall: Option<f64>,
+    /// Minimum hit rate at every zoom level
+    #[serde(default)]
+    pub min_zoom_hit_rate: Option<f64>,
+}
+
+/// EDR load test scenario (e.g. `scenarios/hrrr-edr.yaml`).
+///
+/// Position, area and cube queries are generated; other sections of the
+/// scenario file are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdrScenario {
+    pub name: String,
+    pub description: String,
+    pub base_url: String, // EDR API root, e.g. http://localhost:8083/edr
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub position_queries: Vec<EdrQueryConfig>,
+    #[serde(default)]
+    pub area_queries: Vec<EdrQueryConfig>,
+    #[serde(default)]
+    pub cube_queries: Vec<EdrQueryConfig>,
+    /// Position queries over many times
+    #[serde(default)]
+    pub time_series_queries: Vec<EdrQueryConfig>,
+    /// Position queries over many levels
+    #[serde(default)]
+    pub vertical_profile_queries: Vec<EdrQueryConfig>,
+}
+
+/// One weighted EDR query of a scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdrQueryConfig {
+    pub name: String,
+    pub collection: String,
+    /// WKT geometry (POINT/MULTIPOINT for position, POLYGON for area)
+    #[serde(default)]
+    pub coords: Option<String>,
+    /// Cube bounding box as west,south,east,north
+    #[serde(default)]
+    pub bbox: Option<String>,
+    /// Pick the location at random within this box instead
+    #[serde(default)]
+    pub random_bbox: Option<BBox>,
+    /// Side of random area polygons, in degrees
+    #[serde(default)]
+    pub polygon_size: Option<f64>,
+    /// Side of random cube bounding boxes, in degrees
+    #[serde(default)]
+    pub bbox_size: Option<f64>,
+    #[serde(default)]
+    pub parameters: Vec<String>,
+    #[serde(default)]
+    pub z: Option<String>,
+    #[serde(default)]
+    pub datetime: Option<String>,
+    #[serde(default)]
+    pub resolution_x: Option<u32>,
+    #[serde(default)]
+    pub resolution_y: Option<u32>,
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+impl EdrScenario {
+    /// Load an EDR scenario from a YAML file.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let scenario: EdrScenario = serde_yaml::from_str(&content)?;
+        Ok(scenario)
+    }
+}
+
 /// Order for selecting times from WMS GetCapabilities
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -118,8 +222,20 @@ impl TestConfig {
 
     /// Validate configuration.
     pub fn validate(&self) -> anyhow::Result<()> {
-        if self.duration_secs == 0 {
-            anyhow::bail!("duration_secs must be > 0");
+        match &self.cache_analysis {
+            Some(analysis) => {
+                if analysis.tile_count == 0 {
+                    anyhow::bail!("cache_analysis.tile_count must be > 0");
+                }
+                if analysis.passes == 0 {
+                    anyhow::bail!("cache_analysis.passes must be > 0");
+                }
+            }
+            None => {
+                if self.duration_secs == 0 {
+                    anyhow::bail!("duration_secs must be > 0");
+                }
+            }
         }
         if self.concurrency == 0 {
             anyhow::bail!("concurrency must be > 0");
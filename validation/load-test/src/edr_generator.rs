@@ -0,0 +1,337 @@
+//! EDR query URL generation.
+//!
+//! URLs are built with the `edr-protocol` query builders, so every generated
+//! request is one the EDR API's own parsers accept.
+
+use crate::config::{BBox, EdrQueryConfig, EdrScenario};
+use edr_protocol::queries::{AreaQuery, BboxQuery, ParsedCoords, PositionQuery};
+use edr_protocol::{AreaQueryBuilder, CubeQueryBuilder, PositionQueryBuilder};
+use rand::prelude::*;
+
+/// An EDR request URL with the name of the scenario query it came from.
+pub type EdrRequest = (String, String);
+
+/// Location of a query: fixed from the scenario, or random within a box.
+#[derive(Debug, Clone)]
+enum Geometry {
+    Points(Vec<(f64, f64)>),
+    Polygon(Vec<(f64, f64)>),
+    Bbox([f64; 4]),
+    /// Random point, or square of the given side (degrees)
+    Random(BBox, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum QueryType {
+    Position,
+    Area,
+    Cube,
+}
+
+#[derive(Debug, Clone)]
+struct QueryTemplate {
+    query_type: QueryType,
+    config: EdrQueryConfig,
+    geometry: Geometry,
+    /// Vertical levels, or a `from/to` range
+    z: Option<Vec<f64>>,
+    z_range: bool,
+}
+
+/// Generates EDR position, area and cube query URLs.
+pub struct EdrGenerator {
+    base_url: String,
+    templates: Vec<QueryTemplate>,
+    cumulative: Vec<f64>,
+    rng: StdRng,
+}
+
+impl EdrGenerator {
+    /// Create a generator for a scenario.
+    ///
+    /// Every query is built once up front, so an invalid scenario fails here
+    /// instead of producing requests the server rejects.
+    pub fn new(scenario: &EdrScenario) -> anyhow::Result<Self> {
+        let position_queries = scenario
+            .position_queries
+            .iter()
+            .chain(&scenario.time_series_queries)
+            .chain(&scenario.vertical_profile_queries)
+            .map(|q| (QueryType::Position, q));
+        let area_queries = scenario.area_queries.iter().map(|q| (QueryType::Area, q));
+        let cube_queries = scenario.cube_queries.iter().map(|q| (QueryType::Cube, q));
+
+        let mut templates = Vec::new();
+        for (query_type, config) in position_queries.chain(area_queries).chain(cube_queries) {
+            let template = QueryTemplate::new(query_type, config)
+                .map_err(|e| anyhow::anyhow!("query '{}': {}", config.name, e))?;
+            template
+                .build(&scenario.base_url, &mut StdRng::seed_from_u64(0))
+                .map_err(|e| anyhow::anyhow!("query '{}': {}", config.name, e))?;
+            templates.push(template);
+        }
+        if templates.is_empty() {
+            anyhow::bail!(
+                "scenario '{}' has no position, area or cube queries",
+                scenario.name
+            );
+        }
+
+        // Cumulative distribution for weighted query selection
+        let total: f64 = templates.iter().map(|t| t.config.weight).sum();
+        if total <= 0.0 {
+            anyhow::bail!("query weights must add up to more than 0");
+        }
+        let mut cumulative = Vec::with_capacity(templates.len());
+        let mut sum = 0.0;
+        for template in &templates {
+            sum += template.config.weight / total;
+            cumulative.push(sum);
+        }
+
+        let rng = match scenario.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        Ok(Self {
+            base_url: scenario.base_url.clone(),
+            templates,
+            cumulative,
+            rng,
+        })
+    }
+
+    /// Generate the next request.
+    pub fn next_request(&mut self) -> EdrRequest {
+        let r: f64 = self.rng.gen();
+        let index = self
+            .cumulative
+            .iter()
+            .position(|&cum| r <= cum)
+            .unwrap_or(self.templates.len() - 1);
+        let template = &self.templates[index];
+
+        // Templates were validated in new(); random locations stay inside
+        // the valid box they were validated with
+        let url = template
+            .build(&self.base_url, &mut self.rng)
+            .expect("validated EDR query");
+        (template.config.name.clone(), url)
+    }
+}
+
+impl QueryTemplate {
+    fn new(query_type: QueryType, config: &EdrQueryConfig) -> anyhow::Result<Self> {
+        let geometry = match (&config.random_bbox, query_type) {
+            (Some(bbox), QueryType::Position) => Geometry::Random(bbox.clone(), 0.0),
+            (Some(bbox), QueryType::Area) => {
+                Geometry::Random(bbox.clone(), config.polygon_size.unwrap_or(1.0))
+            }
+            (Some(bbox), QueryType::Cube) => {
+                Geometry::Random(bbox.clone(), config.bbox_size.unwrap_or(1.0))
+            }
+            (None, QueryType::Position) => {
+                let coords = config
+                    .coords
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("missing coords"))?;
+                let points = match PositionQuery::parse_coords_multi(coords)? {
+                    ParsedCoords::Single(lon, lat) => vec![(lon, lat)],
+                    ParsedCoords::Multi(points) => points,
+                };
+                Geometry::Points(points)
+            }
+            (None, QueryType::Area) => {
+                let coords = config
+                    .coords
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("missing coords"))?;
+                Geometry::Polygon(AreaQuery::parse_polygon(coords)?)
+            }
+            (None, QueryType::Cube) => {
+                let bbox = config
+                    .bbox
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("missing bbox"))?;
+                let bbox = BboxQuery::parse(bbox)?;
+                Geometry::Bbox([bbox.west, bbox.south, bbox.east, bbox.north])
+            }
+        };
+
+        let z = config
+            .z
+            .as_deref()
+            .map(PositionQuery::parse_z)
+            .transpose()?;
+        let z_range = config.z.as_deref().is_some_and(|z| z.contains('/'));
+
+        Ok(Self {
+            query_type,
+            config: config.clone(),
+            geometry,
+            z,
+            z_range,
+        })
+    }
+
+    /// Build the query URL, picking a location for random geometries.
+    fn build(
+        &self,
+        base_url: &str,
+        rng: &mut StdRng,
+    ) -> Result<String, edr_protocol::QueryBuildError> {
+        let geometry = match &self.geometry {
+            Geometry::Random(bbox, size) => random_geometry(self.query_type, bbox, *size, rng),
+            geometry => geometry.clone(),
+        };
+        let config = &self.config;
+
+        macro_rules! with_options {
+            ($builder:expr) => {{
+                let mut builder = $builder.parameters(config.parameters.iter().cloned());
+                match (&self.z, self.z_range) {
+                    (Some(z), true) => builder = builder.z_range(z[0], z[1]),
+                    (Some(z), false) => builder = builder.z(z),
+                    (None, _) => {}
+                }
+                if let Some(datetime) = &config.datetime {
+                    builder = builder.datetime(datetime.clone());
+                }
+                builder
+            }};
+        }
+
+        match geometry {
+            Geometry::Points(points) => {
+                let builder = points.iter().fold(
+                    PositionQueryBuilder::new(&config.collection),
+                    |b, &(lon, lat)| b.point(lon, lat),
+                );
+                with_options!(builder).build(base_url)
+            }
+            Geometry::Polygon(ring) => {
+                with_options!(AreaQueryBuilder::new(&config.collection).polygon(ring))
+                    .build(base_url)
+            }
+            Geometry::Bbox([west, south, east, north]) => {
+                let mut builder = with_options!(
+                    CubeQueryBuilder::new(&config.collection).bbox(west, south, east, north)
+                );
+                if let (Some(x), Some(y)) = (config.resolution_x, config.resolution_y) {
+                    builder = builder.resolution(x, y);
+                }
+                builder.build(base_url)
+            }
+            Geometry::Random(..) => unreachable!("random geometry is resolved above"),
+        }
+    }
+}
+
+/// Random point (position) or square of side `size` (area, cube) inside
+/// `bbox`, rounded to 0.01° so URLs stay short.
+fn random_geometry(query_type: QueryType, bbox: &BBox, size: f64, rng: &mut StdRng) -> Geometry {
+    let size = size
+        .min(bbox.max_lon - bbox.min_lon)
+        .min(bbox.max_lat - bbox.min_lat);
+    let lon = round(rng.gen_range(bbox.min_lon..=bbox.max_lon - size));
+    let lat = round(rng.gen_range(bbox.min_lat..=bbox.max_lat - size));
+    let (east, north) = (round(lon + size), round(lat + size));
+
+    match query_type {
+        QueryType::Position => Geometry::Points(vec![(lon, lat)]),
+        QueryType::Area => Geometry::Polygon(vec![
+            (lon, lat),
+            (east, lat),
+            (east, north),
+            (lon, north),
+            (lon, lat),
+        ]),
+        QueryType::Cube => Geometry::Bbox([lon, lat, east, north]),
+    }
+}
+
+fn round(degrees: f64) -> f64 {
+    (degrees * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+name: edr
+description: EDR queries
+base_url: http://localhost:8083/edr
+seed: 3
+position_queries:
+  - name: point
+    collection: hrrr-isobaric
+    coords: "POINT(-100 40)"
+    parameters: ["TMP", "RH"]
+    z: "850,700"
+area_queries:
+  - name: random_area
+    collection: hrrr-surface
+    random_bbox: { min_lon: -120, max_lon: -80, min_lat: 30, max_lat: 48 }
+    polygon_size: 1.0
+    parameters: ["TMP"]
+    datetime: "../.."
+cube_queries:
+  - name: cube
+    collection: hrrr-isobaric
+    bbox: "-101,39,-100,40"
+    z: "850"
+    resolution_x: 10
+    resolution_y: 10
+    weight: 0
+corridor_queries:
+  - name: ignored
+    collection: hrrr-surface
+"#;
+
+    #[test]
+    fn test_generates_valid_queries() {
+        let scenario: EdrScenario = serde_yaml::from_str(SCENARIO).unwrap();
+        let mut generator = EdrGenerator::new(&scenario).unwrap();
+
+        let requests: Vec<EdrRequest> = (0..50).map(|_| generator.next_request()).collect();
+        assert!(requests.iter().all(|(name, _)| name != "cube")); // zero weight
+        assert!(requests.contains(&(
+            "point".to_string(),
+            "http://localhost:8083/edr/collections/hrrr-isobaric/position\
+             ?coords=POINT(-100%2040)&z=850,700&parameter-name=TMP,RH"
+                .to_string()
+        )));
+
+        // Random areas differ but stay valid polygons
+        let areas: Vec<&String> = requests
+            .iter()
+            .filter(|(name, _)| name == "random_area")
+            .map(|(_, url)| url)
+            .collect();
+        assert!(areas.len() > 1);
+        assert!(areas.iter().any(|url| *url != areas[0]));
+        assert!(areas
+            .iter()
+            .all(|url| url.contains("/area?coords=POLYGON((")));
+
+        // Seeded scenarios replay the same requests
+        let mut replay = EdrGenerator::new(&scenario).unwrap();
+        let replayed: Vec<EdrRequest> = (0..50).map(|_| replay.next_request()).collect();
+        assert_eq!(requests, replayed);
+    }
+
+    #[test]
+    fn test_rejects_invalid_queries() {
+        let mut scenario: EdrScenario = serde_yaml::from_str(SCENARIO).unwrap();
+        scenario.cube_queries[0].z = None;
+        let error = EdrGenerator::new(&scenario).err().unwrap();
+        assert!(error.to_string().contains("query 'cube'"));
+
+        scenario.position_queries.clear();
+        scenario.area_queries.clear();
+        scenario.cube_queries.clear();
+        assert!(EdrGenerator::new(&scenario).is_err());
+    }
+}
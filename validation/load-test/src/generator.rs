@@ -3,8 +3,12 @@
 use crate::config::{BBox, TestConfig, TileSelection, TimeOrder, TimeSelection};
 use crate::wms_client;
 use rand::prelude::*;
+use std::collections::HashSet;
 use std::f64::consts::PI;
 
+/// A tile request URL with its (z, x, y, layer_name).
+pub type TileRequest = (String, (u32, u32, u32, String));
+
 /// Generates WMTS tile request URLs.
 pub struct TileGenerator {
     config: TestConfig,
@@ -158,6 +162,26 @@ impl TileGenerator {
         (url, (z, x, y, layer_name))
     }
 
+    /// Generate up to `count` distinct tile requests, in generation order.
+    /// Stops early if the tile space has fewer distinct requests.
+    pub fn unique_requests(&mut self, count: usize) -> Vec<TileRequest> {
+        let mut seen = HashSet::with_capacity(count);
+        let mut requests = Vec::with_capacity(count);
+        let max_attempts = count.saturating_mul(100);
+
+        for _ in 0..max_attempts {
+            if requests.len() == count {
+                break;
+            }
+            let (url, tile_info) = self.next_url_with_info();
+            if seen.insert(url.clone()) {
+                requests.push((url, tile_info));
+            }
+        }
+
+        requests
+    }
+
     /// Select a layer index based on configured weights.
     fn select_layer_index(&mut self) -> usize {
         let r: f64 = self.rng.gen();
@@ -380,4 +404,34 @@ mod tests {
         assert_eq!(tiles.len(), 1);
         assert_eq!(tiles[0], (0, 0));
     }
+
+    #[test]
+    fn test_unique_requests_are_deterministic() {
+        let config: TestConfig = serde_yaml::from_str(
+            r#"
+name: cache
+description: cache analysis
+base_url: http://localhost:8080
+concurrency: 1
+seed: 7
+layers:
+  - name: gfs_TMP
+tile_selection:
+  type: random
+  zoom_range: [2, 3]
+"#,
+        )
+        .unwrap();
+
+        let first = TileGenerator::new(config.clone()).unique_requests(50);
+        let second = TileGenerator::new(config.clone()).unique_requests(50);
+        assert_eq!(first.len(), 50);
+        assert_eq!(first, second);
+
+        let urls: HashSet<_> = first.iter().map(|(url, _)| url).collect();
+        assert_eq!(urls.len(), 50);
+
+        // Zoom 2-3 has only 80 tiles
+        assert_eq!(TileGenerator::new(config).unique_requests(500).len(), 80);
+    }
 }
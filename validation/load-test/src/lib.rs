@@ -2,19 +2,31 @@
 //!
 //! This crate provides tools to:
 //! - Generate realistic WMTS tile request patterns
+//! - Generate EDR position, area and cube queries from EDR scenarios
 //! - Execute load tests with controlled concurrency
 //! - Collect detailed performance metrics
+//! - Analyze cache behavior over a seeded, replayed tile set
 //! - Output results in multiple formats (console, JSON, CSV)
+//! - Publish results to the API's benchmark history
 
+pub mod cache_analysis;
 pub mod config;
+pub mod edr_generator;
 pub mod generator;
 pub mod metrics;
+pub mod publish;
 pub mod report;
 pub mod runner;
 pub mod wms_client;
 
-pub use config::{BBox, LayerConfig, TestConfig, TileSelection};
+pub use cache_analysis::CacheAnalysis;
+pub use config::{
+    BBox, CacheAnalysisConfig, CacheThresholds, EdrQueryConfig, EdrScenario, LayerConfig,
+    TestConfig, TileSelection,
+};
+pub use edr_generator::EdrGenerator;
 pub use generator::TileGenerator;
 pub use metrics::{MetricsCollector, TestResults};
+pub use publish::Publisher;
 pub use report::ResultsReport;
 pub use runner::{LoadRunner, RequestResult};
@@ -1,7 +1,6 @@
 //! Load test CLI for Weather WMS/WMTS service.
 
 use clap::{Parser, Subcommand};
-use std::collections::HashSet;
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -14,17 +13,6 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Compare two request log files to analyze cache behavior
-    Compare {
-        /// First request log file (e.g., from cold cache run)
-        #[arg(short = '1', long)]
-        first: PathBuf,
-
-        /// Second request log file (e.g., from warm cache run)
-        #[arg(short = '2', long)]
-        second: PathBuf,
-    },
-
     /// Run a load test from a scenario file
     Run {
         /// Path to scenario YAML file
@@ -46,6 +34,11 @@ enum Commands {
         /// Log all requests to a JSONL file for analysis/visualization
         #[arg(long)]
         log_requests: bool,
+
+        /// Store results (and the request log) in the benchmark history of
+        /// the API at this URL; uses ADMIN_TOKEN if set
+        #[arg(long)]
+        publish: Option<String>,
     },
 
     /// Run a quick smoke test
@@ -63,6 +56,17 @@ enum Commands {
         url: String,
     },
 
+    /// Print EDR query URLs generated from an EDR scenario
+    EdrUrls {
+        /// Path to EDR scenario YAML file
+        #[arg(short, long)]
+        scenario: PathBuf,
+
+        /// Number of URLs to print
+        #[arg(short, long, default_value = "100")]
+        count: usize,
+    },
+
     /// List available scenarios
     List {
         /// Scenarios directory
@@ -76,121 +80,13 @@ async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Compare { first, second } => {
-            println!("Comparing request logs:");
-            println!("  First:  {}", first.display());
-            println!("  Second: {}", second.display());
-            println!();
-
-            // Parse first file
-            let first_content = std::fs::read_to_string(&first)?;
-            let first_requests: Vec<serde_json::Value> = first_content
-                .lines()
-                .filter_map(|line| serde_json::from_str(line).ok())
-                .collect();
-
-            // Parse second file
-            let second_content = std::fs::read_to_string(&second)?;
-            let second_requests: Vec<serde_json::Value> = second_content
-                .lines()
-                .filter_map(|line| serde_json::from_str(line).ok())
-                .collect();
-
-            // Extract unique tiles from each
-            let first_tiles: HashSet<String> = first_requests
-                .iter()
-                .map(|r| format!("{}/{}/{}", r["z"], r["x"], r["y"]))
-                .collect();
-
-            let second_tiles: HashSet<String> = second_requests
-                .iter()
-                .map(|r| format!("{}/{}/{}", r["z"], r["x"], r["y"]))
-                .collect();
-
-            // Analyze
-            let common: HashSet<_> = first_tiles.intersection(&second_tiles).collect();
-            let only_first: HashSet<_> = first_tiles.difference(&second_tiles).collect();
-            let only_second: HashSet<_> = second_tiles.difference(&first_tiles).collect();
-
-            // Cache stats for second run
-            let second_hits = second_requests
-                .iter()
-                .filter(|r| r["cache_status"].as_str() == Some("HIT"))
-                .count();
-            let second_misses = second_requests.len() - second_hits;
-
-            println!("Request Counts:");
-            println!("  First run:  {} requests", first_requests.len());
-            println!("  Second run: {} requests", second_requests.len());
-            println!();
-
-            println!("Unique Tiles:");
-            println!("  First run:  {} unique tiles", first_tiles.len());
-            println!("  Second run: {} unique tiles", second_tiles.len());
-            println!();
-
-            println!("Tile Overlap Analysis:");
-            println!(
-                "  Common tiles:        {} ({:.1}% of first run)",
-                common.len(),
-                (common.len() as f64 / first_tiles.len() as f64) * 100.0
-            );
-            println!("  Only in first run:   {}", only_first.len());
-            println!(
-                "  Only in second run:  {} (NEW tiles causing cache misses)",
-                only_second.len()
-            );
-            println!();
-
-            println!("Second Run Cache Performance:");
-            println!(
-                "  Cache hits:   {} ({:.1}%)",
-                second_hits,
-                (second_hits as f64 / second_requests.len() as f64) * 100.0
-            );
-            println!(
-                "  Cache misses: {} ({:.1}%)",
-                second_misses,
-                (second_misses as f64 / second_requests.len() as f64) * 100.0
-            );
-            println!();
-
-            // Expected vs actual
-            let expected_hits = common.len();
-            let expected_misses = only_second.len();
-            println!("Expected (if all common tiles were cached):");
-            println!("  Expected hits from common tiles: ~{}", expected_hits);
-            println!("  Expected misses from new tiles:  ~{}", expected_misses);
-            println!();
-
-            if !only_second.is_empty() {
-                println!("Why aren't you seeing 100% cache hits?");
-                println!(
-                    "  The second run made {} more requests than the first run,",
-                    second_requests.len() as i64 - first_requests.len() as i64
-                );
-                println!(
-                    "  accessing {} tiles that were never requested in the first run.",
-                    only_second.len()
-                );
-                println!();
-                println!("Suggestions:");
-                println!("  1. Use a fixed number of requests instead of duration-based testing");
-                println!("  2. Constrain the tile space with a bbox in your scenario");
-                println!("  3. Use a smaller zoom range to reduce the tile space");
-                println!(
-                    "  4. Use TileSelection::Fixed with specific tiles for exact reproducibility"
-                );
-            }
-
-            Ok(())
-        }
         Commands::Run {
             scenario,
             concurrency,
             duration,
             output,
             log_requests,
+            publish,
         } => {
             println!("Loading scenario: {}", scenario.display());
 
@@ -213,7 +109,13 @@ async fn main() -> anyhow::Result<()> {
             println!("✓ Configuration loaded successfully");
             println!("  Name: {}", config.name);
             println!("  Description: {}", config.description);
-            println!("  Duration: {}s", config.duration_secs);
+            match &config.cache_analysis {
+                Some(analysis) => println!(
+                    "  Cache analysis: {} tiles, {} measured pass(es)",
+                    analysis.tile_count, analysis.passes
+                ),
+                None => println!("  Duration: {}s", config.duration_secs),
+            }
             println!("  Concurrency: {}", config.concurrency);
             println!("  Layers: {}", config.layers.len());
             println!();
@@ -236,6 +138,25 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
 
+            if let Some(url) = publish {
+                let publisher = load_test::Publisher::new(&url, std::env::var("ADMIN_TOKEN").ok());
+                let id = publisher
+                    .publish(&results, runner.request_log_path())
+                    .await?;
+                eprintln!("✓ Published run {} to {}", id, url);
+            }
+
+            // Fail the run (non-zero exit) on cache regressions
+            if let Some(analysis) = &results.cache_analysis {
+                if !analysis.passed() {
+                    anyhow::bail!(
+                        "cache analysis failed {} threshold(s): {}",
+                        analysis.threshold_failures.len(),
+                        analysis.threshold_failures.join("; ")
+                    );
+                }
+            }
+
             Ok(())
         }
         Commands::Quick {
@@ -273,6 +194,7 @@ async fn main() -> anyhow::Result<()> {
                 seed: None,
                 time_selection: None,
                 log_requests: false,
+                cache_analysis: None,
             };
 
             // Run the load test
@@ -284,6 +206,17 @@ async fn main() -> anyhow::Result<()> {
 
             Ok(())
         }
+        Commands::EdrUrls { scenario, count } => {
+            let scenario = load_test::EdrScenario::from_file(&scenario)?;
+            let mut generator = load_test::EdrGenerator::new(&scenario)?;
+
+            for _ in 0..count {
+                let (_, url) = generator.next_request();
+                println!("{}", url);
+            }
+
+            Ok(())
+        }
         Commands::List { dir } => {
             println!("Available scenarios in {}:", dir.display());
             println!();
@@ -1,5 +1,6 @@
 //! Metrics collection and statistics.
 
+use crate::cache_analysis::CacheAnalysis;
 use hdrhistogram::Histogram;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
@@ -120,6 +121,7 @@ impl MetricsCollector {
             concurrency,
             system_config,
             git_info: GitInfo::capture(),
+            cache_analysis: None,
         }
     }
 }
@@ -170,6 +172,10 @@ pub struct TestResults {
     // Git metadata for tracking code changes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git_info: Option<GitInfo>,
+
+    // Two-phase cache analysis (cache_analysis scenarios only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_analysis: Option<CacheAnalysis>,
 }
 
 /// Git repository information captured at test time
@@ -0,0 +1,91 @@
+//! Publish load test results to the WMS API's benchmark history.
+//!
+//! Runs are stored through the admin endpoints under
+//! `/api/admin/benchmarks/`, tagged with the git SHA and config hash from the
+//! results, and show up on the `/loadtest` dashboard and trend API.
+
+use crate::metrics::TestResults;
+use anyhow::Context;
+use std::path::Path;
+use std::time::Duration;
+
+/// Request log lines sent per upload request.
+const REQUEST_LOG_BATCH_LINES: usize = 5000;
+
+/// Client for the benchmark history endpoints.
+pub struct Publisher {
+    client: reqwest::Client,
+    base_url: String,
+    admin_token: Option<String>,
+}
+
+impl Publisher {
+    /// Create a publisher for the API at `base_url`.
+    ///
+    /// `admin_token` is sent as a bearer token when the API sets `ADMIN_TOKEN`.
+    pub fn new(base_url: &str, admin_token: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            admin_token,
+        }
+    }
+
+    /// Store the results of a run and, if given, its JSONL request log.
+    ///
+    /// Returns the ID of the stored run.
+    pub async fn publish(
+        &self,
+        results: &TestResults,
+        request_log: Option<&Path>,
+    ) -> anyhow::Result<String> {
+        let url = format!("{}/api/admin/benchmarks/loadtest", self.base_url);
+        let response: serde_json::Value = self
+            .post(&url)
+            .json(results)
+            .send()
+            .await?
+            .error_for_status()
+            .context("Failed to store load test results")?
+            .json()
+            .await?;
+        let id = response["id"]
+            .as_str()
+            .context("Response has no run ID")?
+            .to_string();
+
+        if let Some(path) = request_log {
+            let log = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let url = format!(
+                "{}/api/admin/benchmarks/loadtest/{}/requests",
+                self.base_url, id
+            );
+            let lines: Vec<&str> = log.lines().collect();
+            for batch in lines.chunks(REQUEST_LOG_BATCH_LINES) {
+                self.post(&url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/x-ndjson")
+                    .body(batch.join("\n"))
+                    .send()
+                    .await?
+                    .error_for_status()
+                    .context("Failed to store request log")?;
+            }
+        }
+
+        Ok(id)
+    }
+
+    fn post(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.post(url);
+        match &self.admin_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
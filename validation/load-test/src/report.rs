@@ -1,5 +1,6 @@
 //! Results reporting and formatting.
 
+use crate::cache_analysis::CacheAnalysis;
 use crate::metrics::TestResults;
 use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Table};
 
@@ -56,7 +57,73 @@ impl ResultsReport {
             &format!("{:.1} MB/s", results.bytes_per_second / 1_000_000.0),
         ]);
 
-        table.to_string()
+        let mut output = table.to_string();
+        if let Some(analysis) = &results.cache_analysis {
+            output.push('\n');
+            output.push_str(&Self::format_cache_analysis(analysis));
+        }
+        output
+    }
+
+    /// Format a two-phase cache analysis as console tables.
+    pub fn format_cache_analysis(analysis: &CacheAnalysis) -> String {
+        let mut summary = Table::new();
+        summary
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec!["Cache Analysis".to_string()]);
+
+        summary.add_row(vec![
+            "Tile Set:",
+            &format!(
+                "{} tiles x {} pass(es)",
+                analysis.tile_count, analysis.passes
+            ),
+        ]);
+        summary.add_row(vec![
+            "Seed Phase:",
+            &format!(
+                "{:.1}s, {} uncacheable",
+                analysis.seed_duration_secs, analysis.seed_uncacheable
+            ),
+        ]);
+        summary.add_row(vec![
+            "Hit Rate:",
+            &format!(
+                "{:.1}% achieved / {:.1}% expected",
+                analysis.hit_rate, analysis.expected_hit_rate
+            ),
+        ]);
+        for (status, count) in &analysis.by_cache_status {
+            summary.add_row(vec![format!("  {}", status), count.to_string()]);
+        }
+        for note in &analysis.notes {
+            summary.add_row(vec!["Note:", note]);
+        }
+        summary.add_row(vec![
+            "Thresholds:",
+            if analysis.passed() { "PASS" } else { "FAIL" },
+        ]);
+        for failure in &analysis.threshold_failures {
+            summary.add_row(vec!["", failure]);
+        }
+
+        let mut zooms = Table::new();
+        zooms
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_header(vec!["Zoom", "Requests", "Hits", "Hit Rate", "Expected"]);
+        for zoom in &analysis.by_zoom {
+            zooms.add_row(vec![
+                zoom.zoom.to_string(),
+                zoom.requests.to_string(),
+                zoom.hits.to_string(),
+                format!("{:.1}%", zoom.hit_rate),
+                format!("{:.1}%", zoom.expected_hit_rate),
+            ]);
+        }
+
+        format!("{}\n{}", summary, zooms)
     }
 
     /// Format results as JSON.
@@ -1,15 +1,18 @@
 //! HTTP request execution and load test orchestration.
 
-use crate::config::TestConfig;
-use crate::generator::TileGenerator;
+use crate::cache_analysis::{self, CacheAnalysis, Observation};
+use crate::config::{CacheAnalysisConfig, TestConfig};
+use crate::generator::{TileGenerator, TileRequest};
 use crate::metrics::{MetricsCollector, SystemConfig, TestResults};
 use indicatif::{ProgressBar, ProgressStyle};
 use serde::Serialize;
 use std::fs::File;
 use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
 use tokio::time::sleep;
 
 /// A single logged request for debugging and visualization.
@@ -24,12 +27,21 @@ pub struct RequestLog {
     pub latency_ms: f64,
     pub cache_status: String,
     pub status: u16,
+    /// "seed" or "measured" in cache analysis runs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<&'static str>,
 }
 
+type RequestLogWriter = Arc<Mutex<BufWriter<File>>>;
+
+/// Seed used for the tile set when a cache analysis scenario sets none.
+const CACHE_ANALYSIS_DEFAULT_SEED: u64 = 42;
+
 /// Executes load tests with controlled concurrency.
 pub struct LoadRunner {
     client: reqwest::Client,
     config: TestConfig,
+    request_log_path: Option<PathBuf>,
 }
 
 /// Result of a single HTTP request.
@@ -40,6 +52,8 @@ pub struct RequestResult {
     pub latency_us: u64,
     pub bytes: usize,
     pub cache_hit: bool,
+    /// Raw `X-Cache` header value, empty if absent
+    pub cache_status: String,
     pub timestamp: Instant,
     pub error: Option<String>,
 }
@@ -53,11 +67,24 @@ impl LoadRunner {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { client, config }
+        Self {
+            client,
+            config,
+            request_log_path: None,
+        }
+    }
+
+    /// JSONL request log written by the last run, if `log_requests` is set.
+    pub fn request_log_path(&self) -> Option<&Path> {
+        self.request_log_path.as_deref()
     }
 
     /// Run the load test.
     pub async fn run(&mut self) -> anyhow::Result<TestResults> {
+        if let Some(analysis) = self.config.cache_analysis.clone() {
+            return self.run_cache_analysis(analysis).await;
+        }
+
         // Fetch system configuration from API
         let system_config = self.fetch_system_config().await.ok();
 
@@ -79,46 +106,7 @@ impl LoadRunner {
 
         // Display system configuration if available
         if let Some(ref config) = system_config {
-            println!();
-            println!("System Configuration:");
-            println!(
-                "  L1 Cache: {} (size: {}, ttl: {}s)",
-                if config.l1_cache_enabled {
-                    "enabled"
-                } else {
-                    "disabled"
-                },
-                config.l1_cache_size,
-                config.l1_cache_ttl_secs
-            );
-            println!(
-                "  Chunk Cache: {} (size: {} MB)",
-                if config.chunk_cache_enabled {
-                    "enabled"
-                } else {
-                    "disabled"
-                },
-                config.chunk_cache_size_mb
-            );
-            println!(
-                "  Prefetch: {} (rings: {}, zoom: {}-{})",
-                if config.prefetch_enabled {
-                    "enabled"
-                } else {
-                    "disabled"
-                },
-                config.prefetch_rings,
-                config.prefetch_min_zoom,
-                config.prefetch_max_zoom
-            );
-            println!(
-                "  Cache Warming: {}",
-                if config.cache_warming_enabled {
-                    "enabled"
-                } else {
-                    "disabled"
-                }
-            );
+            Self::print_system_config(config);
         }
 
         println!();
@@ -138,31 +126,7 @@ impl LoadRunner {
         let semaphore = Arc::new(Semaphore::new(self.config.concurrency as usize));
 
         // Request logging setup
-        let request_log: Option<Arc<Mutex<BufWriter<File>>>> = if self.config.log_requests {
-            // Ensure results directory exists - use validation/load-test/results if it exists,
-            // otherwise create results/ in current directory
-            let results_dir = if std::path::Path::new("validation/load-test/results").exists()
-                || std::path::Path::new("validation/load-test").exists()
-            {
-                "validation/load-test/results"
-            } else {
-                "results"
-            };
-            std::fs::create_dir_all(results_dir)?;
-            // Include scenario name in filename for easier identification
-            let scenario_name = self.config.name.replace(' ', "_").to_lowercase();
-            let log_path = format!(
-                "{}/{}_{}.jsonl",
-                results_dir,
-                scenario_name,
-                chrono::Utc::now().format("%Y%m%d_%H%M%S")
-            );
-            println!("  Logging requests to: {}", log_path);
-            let file = File::create(&log_path)?;
-            Some(Arc::new(Mutex::new(BufWriter::new(file))))
-        } else {
-            None
-        };
+        let request_log = self.open_request_log()?;
 
         let start_time = Instant::now();
         let mut _requests_sent = 0u64;
@@ -215,16 +179,7 @@ impl LoadRunner {
 
                 // Record metrics (skip during warmup)
                 if !in_warmup {
-                    let mut m = metrics_clone.lock().await;
-                    if let Some(ref err) = result.error {
-                        m.record_failure();
-                        eprintln!("Request failed: {} - {}", url, err);
-                    } else if result.status == 200 {
-                        m.record_success(result.latency_us, result.bytes, result.cache_hit);
-                    } else {
-                        m.record_failure();
-                        eprintln!("Request returned {}: {}", result.status, url);
-                    }
+                    Self::record_result(&mut *metrics_clone.lock().await, &result);
 
                     // Log request if enabled
                     if let Some(ref log) = request_log_clone {
@@ -239,6 +194,7 @@ impl LoadRunner {
                             latency_ms: result.latency_us as f64 / 1000.0,
                             cache_status: cache_status.to_string(),
                             status: result.status,
+                            phase: None,
                         };
                         if let Ok(json) = serde_json::to_string(&log_entry) {
                             let mut writer = log.lock().await;
@@ -277,6 +233,10 @@ impl LoadRunner {
         pb.finish_with_message("Complete!");
         println!();
 
+        if let Some(ref log) = request_log {
+            log.lock().await.flush()?;
+        }
+
         // Generate results
         let m = metrics.lock().await;
 
@@ -292,6 +252,326 @@ impl LoadRunner {
         ))
     }
 
+    /// Run a two-phase cache analysis: seed the cache with a deterministic
+    /// tile set, then replay the set and compare hits with expectations.
+    async fn run_cache_analysis(
+        &mut self,
+        analysis: CacheAnalysisConfig,
+    ) -> anyhow::Result<TestResults> {
+        let system_config = self.fetch_system_config().await.ok();
+
+        // The tile set must be the same in both phases and across runs
+        let mut generator_config = self.config.clone();
+        generator_config
+            .seed
+            .get_or_insert(CACHE_ANALYSIS_DEFAULT_SEED);
+        println!("Initializing tile generator...");
+        let mut generator = TileGenerator::new_async(generator_config).await?;
+        let tiles = generator.unique_requests(analysis.tile_count);
+        if tiles.len() < analysis.tile_count {
+            eprintln!(
+                "Warning: Only {} distinct tile requests available, but {} requested. Using all of them.",
+                tiles.len(),
+                analysis.tile_count
+            );
+        }
+        let urls: Vec<String> = tiles.iter().map(|(url, _)| url.clone()).collect();
+
+        println!("Starting cache analysis: {}", self.config.name);
+        println!("  Tile set: {} requests", tiles.len());
+        println!("  Measured passes: {}", analysis.passes);
+        println!("  Concurrency: {}", self.config.concurrency);
+        if let Some(rps) = self.config.requests_per_second {
+            println!("  Rate limit: {:.1} req/s", rps);
+        }
+        if let Some(ref config) = system_config {
+            Self::print_system_config(config);
+        }
+        println!();
+
+        let request_log = self.open_request_log()?;
+
+        // Phase 1: request every tile once against the cold cache
+        let pb = Self::phase_progress_bar(urls.len() as u64, "Seed phase");
+        let seed_start = Instant::now();
+        let seed_results = self.execute_batch(&urls, None, &pb).await?;
+        let seed_duration_secs = seed_start.elapsed().as_secs_f64();
+        pb.finish_with_message("Seed phase complete");
+        Self::log_batch(&request_log, "seed", seed_start, &tiles, &seed_results).await;
+
+        let cacheable: Vec<bool> = seed_results
+            .iter()
+            .map(cache_analysis::is_cacheable)
+            .collect();
+        let seed_uncacheable = cacheable.iter().filter(|c| !**c).count();
+
+        // Phase 2: replay the same tiles, measuring
+        let metrics = Arc::new(Mutex::new(MetricsCollector::new()));
+        let mut observations = Vec::with_capacity(urls.len() * analysis.passes as usize);
+        let pb =
+            Self::phase_progress_bar(urls.len() as u64 * analysis.passes as u64, "Measured phase");
+        let measured_start = Instant::now();
+        for _ in 0..analysis.passes {
+            let results = self.execute_batch(&urls, Some(&metrics), &pb).await?;
+            Self::log_batch(&request_log, "measured", measured_start, &tiles, &results).await;
+
+            for ((result, (_, tile_info)), &expected_hit) in
+                results.iter().zip(&tiles).zip(&cacheable)
+            {
+                observations.push(Observation {
+                    zoom: tile_info.0,
+                    cache_status: result.cache_status.clone(),
+                    cache_hit: result.cache_hit,
+                    expected_hit,
+                });
+            }
+        }
+        pb.finish_with_message("Complete!");
+        println!();
+
+        if let Some(ref log) = request_log {
+            log.lock().await.flush()?;
+        }
+
+        let mut cache_analysis = CacheAnalysis::from_observations(
+            tiles.len(),
+            analysis.passes,
+            seed_uncacheable,
+            seed_duration_secs,
+            &observations,
+            &analysis.thresholds,
+        );
+        cache_analysis.notes =
+            Self::cache_analysis_notes(tiles.len(), seed_uncacheable, system_config.as_ref());
+
+        let layers = self.config.layers.iter().map(|l| l.name.clone()).collect();
+        let mut results = metrics.lock().await.results(
+            self.config.name.clone(),
+            self.config.name.clone(),
+            layers,
+            self.config.concurrency,
+            system_config,
+        );
+        results.cache_analysis = Some(cache_analysis);
+        Ok(results)
+    }
+
+    /// Reasons the achieved hit rate may legitimately fall short of 100%.
+    fn cache_analysis_notes(
+        tile_count: usize,
+        seed_uncacheable: usize,
+        system_config: Option<&SystemConfig>,
+    ) -> Vec<String> {
+        let mut notes = Vec::new();
+
+        if seed_uncacheable > 0 {
+            notes.push(format!(
+                "{} of {} seeded tiles failed or were out of extent and are expected misses",
+                seed_uncacheable, tile_count
+            ));
+        }
+
+        if let Some(config) = system_config {
+            let cached = tile_count - seed_uncacheable;
+            if !config.l1_cache_enabled && !config.l2_cache_enabled {
+                notes.push("L1 and L2 tile caches are both disabled".to_string());
+            } else if !config.l2_cache_enabled && config.l1_cache_size < cached {
+                notes.push(format!(
+                    "L1 cache holds {} tiles but {} were seeded and L2 is disabled; evictions lower the hit rate",
+                    config.l1_cache_size, cached
+                ));
+            }
+        }
+
+        notes
+    }
+
+    /// Execute requests with the configured concurrency and rate limit,
+    /// recording them in `metrics` if given. Results are in request order.
+    async fn execute_batch(
+        &self,
+        urls: &[String],
+        metrics: Option<&Arc<Mutex<MetricsCollector>>>,
+        pb: &ProgressBar,
+    ) -> anyhow::Result<Vec<RequestResult>> {
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency as usize));
+        let request_interval = self
+            .config
+            .requests_per_second
+            .map(|rps| Duration::from_secs_f64(1.0 / rps));
+        let mut last_request_time = Instant::now();
+        let mut tasks = JoinSet::new();
+
+        for (i, url) in urls.iter().enumerate() {
+            if let Some(interval) = request_interval {
+                let time_since_last = last_request_time.elapsed();
+                if time_since_last < interval {
+                    sleep(interval - time_since_last).await;
+                }
+                last_request_time = Instant::now();
+            }
+
+            let permit = semaphore.clone().acquire_owned().await?;
+            let client = self.client.clone();
+            let url = url.clone();
+            let metrics = metrics.cloned();
+            let pb = pb.clone();
+
+            tasks.spawn(async move {
+                let result = Self::execute_request_static(&client, &url).await;
+                if let Some(metrics) = metrics {
+                    Self::record_result(&mut *metrics.lock().await, &result);
+                } else if let Some(ref err) = result.error {
+                    eprintln!("Request failed: {} - {}", url, err);
+                }
+                pb.inc(1);
+                drop(permit);
+                (i, result)
+            });
+        }
+
+        let mut results: Vec<Option<RequestResult>> = Vec::new();
+        results.resize_with(urls.len(), || None);
+        while let Some(joined) = tasks.join_next().await {
+            let (i, result) = joined?;
+            results[i] = Some(result);
+        }
+
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Record a finished request, reporting failures.
+    fn record_result(metrics: &mut MetricsCollector, result: &RequestResult) {
+        if let Some(ref err) = result.error {
+            metrics.record_failure();
+            eprintln!("Request failed: {} - {}", result.url, err);
+        } else if result.status == 200 {
+            metrics.record_success(result.latency_us, result.bytes, result.cache_hit);
+        } else {
+            metrics.record_failure();
+            eprintln!("Request returned {}: {}", result.status, result.url);
+        }
+    }
+
+    /// Write a batch of results to the request log, if enabled.
+    async fn log_batch(
+        request_log: &Option<RequestLogWriter>,
+        phase: &'static str,
+        phase_start: Instant,
+        tiles: &[TileRequest],
+        results: &[RequestResult],
+    ) {
+        let Some(log) = request_log else {
+            return;
+        };
+        let mut writer = log.lock().await;
+        for ((url, tile_info), result) in tiles.iter().zip(results) {
+            let log_entry = RequestLog {
+                timestamp_ms: result
+                    .timestamp
+                    .saturating_duration_since(phase_start)
+                    .as_millis() as u64,
+                url: url.clone(),
+                z: tile_info.0,
+                x: tile_info.1,
+                y: tile_info.2,
+                layer: tile_info.3.clone(),
+                latency_ms: result.latency_us as f64 / 1000.0,
+                cache_status: if result.cache_hit { "HIT" } else { "MISS" }.to_string(),
+                status: result.status,
+                phase: Some(phase),
+            };
+            if let Ok(json) = serde_json::to_string(&log_entry) {
+                let _ = writeln!(writer, "{}", json);
+            }
+        }
+    }
+
+    /// Open the JSONL request log if `log_requests` is set.
+    fn open_request_log(&mut self) -> anyhow::Result<Option<RequestLogWriter>> {
+        if !self.config.log_requests {
+            return Ok(None);
+        }
+
+        // Ensure results directory exists - use validation/load-test/results if it exists,
+        // otherwise create results/ in current directory
+        let results_dir = if std::path::Path::new("validation/load-test/results").exists()
+            || std::path::Path::new("validation/load-test").exists()
+        {
+            "validation/load-test/results"
+        } else {
+            "results"
+        };
+        std::fs::create_dir_all(results_dir)?;
+        // Include scenario name in filename for easier identification
+        let scenario_name = self.config.name.replace(' ', "_").to_lowercase();
+        let log_path = format!(
+            "{}/{}_{}.jsonl",
+            results_dir,
+            scenario_name,
+            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        );
+        println!("  Logging requests to: {}", log_path);
+        let file = File::create(&log_path)?;
+        self.request_log_path = Some(PathBuf::from(log_path));
+        Ok(Some(Arc::new(Mutex::new(BufWriter::new(file)))))
+    }
+
+    fn phase_progress_bar(len: u64, message: &'static str) -> ProgressBar {
+        let pb = ProgressBar::new(len);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
+                .expect("Invalid progress bar template")
+                .progress_chars("##-"),
+        );
+        pb.set_message(message);
+        pb
+    }
+
+    fn print_system_config(config: &SystemConfig) {
+        println!();
+        println!("System Configuration:");
+        println!(
+            "  L1 Cache: {} (size: {}, ttl: {}s)",
+            if config.l1_cache_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            config.l1_cache_size,
+            config.l1_cache_ttl_secs
+        );
+        println!(
+            "  Chunk Cache: {} (size: {} MB)",
+            if config.chunk_cache_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            config.chunk_cache_size_mb
+        );
+        println!(
+            "  Prefetch: {} (rings: {}, zoom: {}-{})",
+            if config.prefetch_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            },
+            config.prefetch_rings,
+            config.prefetch_min_zoom,
+            config.prefetch_max_zoom
+        );
+        println!(
+            "  Cache Warming: {}",
+            if config.cache_warming_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+    }
+
     /// Fetch system configuration from WMS API
     async fn fetch_system_config(&self) -> anyhow::Result<SystemConfig> {
         let config_url = format!("{}/api/config", self.config.base_url);
@@ -336,12 +616,13 @@ impl LoadRunner {
                 let status = response.status().as_u16();
 
                 // Check for cache hit from X-Cache header
-                let cache_hit = response
+                let cache_status = response
                     .headers()
                     .get("x-cache")
                     .and_then(|v| v.to_str().ok())
-                    .map(|v| v.to_uppercase().contains("HIT"))
-                    .unwrap_or(false);
+                    .map(|v| v.to_uppercase())
+                    .unwrap_or_default();
+                let cache_hit = cache_status.contains("HIT");
 
                 // Read response body
                 let bytes = match response.bytes().await {
@@ -355,6 +636,7 @@ impl LoadRunner {
                     latency_us: start.elapsed().as_micros() as u64,
                     bytes,
                     cache_hit,
+                    cache_status,
                     timestamp: start,
                     error: None,
                 }
@@ -365,6 +647,7 @@ impl LoadRunner {
                 latency_us: start.elapsed().as_micros() as u64,
                 bytes: 0,
                 cache_hit: false,
+                cache_status: String::new(),
                 timestamp: start,
                 error: Some(e.to_string()),
             },
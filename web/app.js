@@ -32,6 +32,7 @@ let availableRuns = [];
 let availableForecastHours = [0, 3, 6, 12, 24];
 let availableElevations = []; // Available levels for current layer
 let availableObservationTimes = []; // For TIME-only layers (GOES, MRMS)
+const MAX_ANIMATION_FRAMES = 48; // Frames requested for observation animations
 let currentObservationTime = null; // Selected observation time
 let layerTimeMode = 'forecast'; // 'forecast' (RUN+FORECAST) or 'observation' (TIME only)
 let playbackInterval = null;
@@ -1418,7 +1419,9 @@ function formatLayerName(layerName) {
         'CMI_C01': 'Visible Blue - Band 1',
         'CMI_C02': 'Visible Red - Band 2',
         'CMI_C08': 'Upper-Level Water Vapor - Band 8',
-        'CMI_C13': 'Clean Longwave IR - Band 13'
+        'CMI_C13': 'Clean Longwave IR - Band 13',
+        'TRUE_COLOR': 'True Color',
+        'SANDWICH': 'Sandwich (Visible/IR)'
     };
     
     // Extract parameter from layer name (e.g., "gfs_PRMSL" -> "PRMSL")
@@ -2953,6 +2956,11 @@ async function fetchAvailableTimes() {
             }
         }
         
+        // Step observation layers through evenly spaced frames
+        if (layerTimeMode === 'observation') {
+            await applyAnimationFrames();
+        }
+        
         // Populate and show the time slider
         populateTimeSlider();
         
@@ -2988,6 +2996,35 @@ async function fetchAvailableTimes() {
 
 
 
+// Replace the observation times with the scans shown in fixed animation
+// frames, so the slider and playback step evenly through time instead of
+// following irregular scan times. Keeps the capabilities times on failure.
+async function applyAnimationFrames() {
+    if (availableObservationTimes.length < 2) return;
+    
+    const count = Math.min(availableObservationTimes.length, MAX_ANIMATION_FRAMES);
+    try {
+        const response = await fetch(
+            `${API_BASE}/api/animation-frames/${encodeURIComponent(selectedLayer)}?count=${count}`
+        );
+        if (!response.ok) return;
+        const data = await response.json();
+        
+        // Frames are oldest first; the slider wants newest first
+        const times = data.frames
+            .filter(f => f.time)
+            .map(f => f.time)
+            .reverse();
+        if (times.length === 0) return;
+        
+        availableObservationTimes = times;
+        currentObservationTime = times[0];
+        console.log(`Animation frames (${data.step}):`, times.length, 'of', count);
+    } catch (error) {
+        console.warn('Failed to fetch animation frames:', error);
+    }
+}
+
 // Note: stopPlayback() is defined earlier in this file (around line 289)
 // with full implementation including button state updates
 